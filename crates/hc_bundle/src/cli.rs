@@ -3,13 +3,13 @@
 
 use holochain_types::prelude::{AppManifest, DnaManifest, ValidatedDnaManifest};
 use holochain_types::web_app::WebAppManifest;
-use holochain_util::ffs;
 use mr_bundle::Manifest;
 use std::path::Path;
 use std::path::PathBuf;
 use structopt::StructOpt;
 
 use crate::error::HcBundleResult;
+use crate::manifest::{read_manifest, read_validated_app_manifest};
 
 /// The file extension to use for DNA bundles
 pub const DNA_BUNDLE_EXT: &str = "dna";
@@ -99,6 +99,9 @@ pub enum HcAppBundle {
     ///
     /// creates a file `./some/directory/foo/[name].happ`, based on
     /// `./some/directory/foo/happ.yaml`
+    ///
+    /// The manifest is validated first, so that errors which would otherwise
+    /// only show up when installing the app are reported with the line at fault.
     Pack {
         /// The path to the working directory containing a `happ.yaml` manifest
         path: std::path::PathBuf,
@@ -290,23 +293,20 @@ impl HcWebAppBundle {
 async fn get_dna_name(manifest_path: &Path) -> HcBundleResult<String> {
     let manifest_path = manifest_path.to_path_buf();
     let manifest_path = manifest_path.join(&ValidatedDnaManifest::path());
-    let manifest_yaml = ffs::read_to_string(&manifest_path).await?;
-    let manifest: DnaManifest = serde_yaml::from_str(&manifest_yaml)?;
+    let manifest: DnaManifest = read_manifest(&manifest_path).await?;
     Ok(manifest.name())
 }
 
 async fn get_app_name(manifest_path: &Path) -> HcBundleResult<String> {
     let manifest_path = manifest_path.to_path_buf();
     let manifest_path = manifest_path.join(&AppManifest::path());
-    let manifest_yaml = ffs::read_to_string(&manifest_path).await?;
-    let manifest: AppManifest = serde_yaml::from_str(&manifest_yaml)?;
+    let manifest = read_validated_app_manifest(&manifest_path).await?;
     Ok(manifest.app_name().to_string())
 }
 
 async fn get_web_app_name(manifest_path: &Path) -> HcBundleResult<String> {
     let manifest_path = manifest_path.to_path_buf();
     let manifest_path = manifest_path.join(&WebAppManifest::path());
-    let manifest_yaml = ffs::read_to_string(&manifest_path).await?;
-    let manifest: WebAppManifest = serde_yaml::from_str(&manifest_yaml)?;
+    let manifest: WebAppManifest = read_manifest(&manifest_path).await?;
     Ok(manifest.app_name().to_string())
 }
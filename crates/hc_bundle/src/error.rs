@@ -35,6 +35,44 @@ pub enum HcBundleError {
 
     #[error("This file should have a '.{0}' extension: {1}")]
     FileExtensionMissing(&'static str, PathBuf),
+
+    /// A manifest which can't be parsed or is invalid
+    #[error("{0}")]
+    InvalidManifest(ManifestError),
+}
+
+/// Why a manifest can't be parsed or is invalid,
+/// with the line at fault if it is known.
+#[derive(Debug)]
+pub struct ManifestError {
+    /// Path to the manifest.
+    pub path: PathBuf,
+    /// The number and text of the line at fault.
+    pub line: Option<(usize, String)>,
+    /// What is wrong with the manifest.
+    pub reason: String,
+}
+
+impl std::fmt::Display for ManifestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.line {
+            Some((number, text)) => write!(
+                f,
+                "Invalid manifest {}:{}: {}\n{:>5} | {}",
+                self.path.display(),
+                number,
+                self.reason,
+                number,
+                text
+            ),
+            None => write!(
+                f,
+                "Invalid manifest {}: {}",
+                self.path.display(),
+                self.reason
+            ),
+        }
+    }
 }
 
 /// HcBundle Result type.
@@ -1,6 +1,7 @@
 mod cli;
 mod error;
 mod init;
+mod manifest;
 mod packing;
 
 pub use cli::{HcAppBundle, HcDnaBundle, HcWebAppBundle};
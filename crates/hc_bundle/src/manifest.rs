@@ -0,0 +1,108 @@
+//! Reading and validating manifests before they are packed, so that a bad
+//! manifest is reported with the line of the manifest at fault.
+
+use crate::error::{HcBundleError, HcBundleResult, ManifestError};
+use holochain_types::prelude::{AppManifest, AppRoleId};
+use holochain_util::ffs;
+use serde::de::DeserializeOwned;
+use std::path::Path;
+
+/// Read and parse the manifest at this path.
+pub(crate) async fn read_manifest<M: DeserializeOwned>(path: &Path) -> HcBundleResult<M> {
+    let yaml = ffs::read_to_string(path).await?;
+    parse_manifest(path, &yaml)
+}
+
+fn parse_manifest<M: DeserializeOwned>(path: &Path, yaml: &str) -> HcBundleResult<M> {
+    serde_yaml::from_str(yaml).map_err(|e| {
+        // serde_yaml already says where in the file the error is.
+        let reason = e.to_string();
+        let line = e.location().map(|location| location.line());
+        manifest_error(path, yaml, line, reason)
+    })
+}
+
+/// Read the app manifest at this path and check that it is valid, which is
+/// otherwise only found out when the app is installed.
+pub(crate) async fn read_validated_app_manifest(path: &Path) -> HcBundleResult<AppManifest> {
+    let yaml = ffs::read_to_string(path).await?;
+    validate_app_manifest(path, &yaml)
+}
+
+fn validate_app_manifest(path: &Path, yaml: &str) -> HcBundleResult<AppManifest> {
+    let manifest: AppManifest = parse_manifest(path, yaml)?;
+    if let Err(e) = manifest.clone().validate() {
+        let line = role_line(yaml, e.role_id());
+        return Err(manifest_error(path, yaml, line, e.to_string()));
+    }
+    Ok(manifest)
+}
+
+fn manifest_error(path: &Path, yaml: &str, line: Option<usize>, reason: String) -> HcBundleError {
+    let line = line.and_then(|line| {
+        let text = yaml.lines().nth(line.checked_sub(1)?)?;
+        Some((line, text.to_string()))
+    });
+    HcBundleError::InvalidManifest(ManifestError {
+        path: path.to_owned(),
+        line,
+        reason,
+    })
+}
+
+/// The line number of the `id` of this role in the manifest.
+fn role_line(yaml: &str, role_id: &AppRoleId) -> Option<usize> {
+    yaml.lines()
+        .position(|line| {
+            let line = line.trim_start().trim_start_matches("- ").trim();
+            match line.strip_prefix("id:") {
+                Some(id) => id.trim().trim_matches(|c| c == '"' || c == '\'') == role_id.as_str(),
+                None => false,
+            }
+        })
+        .map(|index| index + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const APP: &str = r#"---
+manifest_version: "1"
+name: test-app
+roles:
+  - id: role-1
+    dna:
+      bundled: dna1.dna
+  - id: "role-2"
+    provisioning:
+      strategy: use_existing
+    dna:
+      bundled: dna2.dna
+"#;
+
+    fn invalid(result: HcBundleResult<impl std::fmt::Debug>) -> ManifestError {
+        match result {
+            Err(HcBundleError::InvalidManifest(e)) => e,
+            r => panic!("Expected an invalid manifest but got {:?}", r),
+        }
+    }
+
+    #[test]
+    fn invalid_manifests_are_reported_with_their_line() {
+        let path = Path::new("happ.yaml");
+
+        // Role 2 uses an existing cell without saying which version.
+        let e = invalid(validate_app_manifest(path, APP));
+        assert_eq!(e.line, Some((8, r#"  - id: "role-2""#.to_string())));
+        assert!(e.reason.contains("role-2"));
+        assert!(e.to_string().starts_with("Invalid manifest happ.yaml:8: "));
+
+        let fixed = APP.replace("strategy: use_existing", "strategy: create");
+        validate_app_manifest(path, &fixed).unwrap();
+
+        let broken = APP.replace("    dna:\n      bundled: dna1.dna\n", "    dna: [\n");
+        let e = invalid(parse_manifest::<AppManifest>(path, &broken));
+        assert!(e.line.is_some());
+    }
+}
@@ -185,6 +185,7 @@ async fn test_multi_integrity() {
                 ZomeDef::Wasm(WasmZome {
                     wasm_hash: wasm_hash.clone(),
                     dependencies: vec![],
+                    unrestricted_functions: Default::default(),
                 })
                 .into(),
             ),
@@ -193,6 +194,7 @@ async fn test_multi_integrity() {
                 ZomeDef::Wasm(WasmZome {
                     wasm_hash: wasm_hash.clone(),
                     dependencies: vec![],
+                    unrestricted_functions: Default::default(),
                 })
                 .into(),
             ),
@@ -203,6 +205,7 @@ async fn test_multi_integrity() {
                 ZomeDef::Wasm(WasmZome {
                     wasm_hash: wasm_hash2.clone(),
                     dependencies: vec!["zome1".into()],
+                    unrestricted_functions: Default::default(),
                 })
                 .into(),
             ),
@@ -211,10 +214,13 @@ async fn test_multi_integrity() {
                 ZomeDef::Wasm(WasmZome {
                     wasm_hash: wasm_hash2.clone(),
                     dependencies: vec!["zome1".into(), "zome2".into()],
+                    unrestricted_functions: Default::default(),
                 })
                 .into(),
             ),
         ],
+        runtime_properties: Default::default(),
+        ephemeral_ttl_secs: None,
     };
     assert_eq!(
         dna.dna_def().integrity_zomes[0]
@@ -0,0 +1,347 @@
+//! Benchmarking the zome calls of the apps installed in sandboxes.
+//!
+//! A mix of zome calls is made against a cell, with a number of calls in
+//! flight at once, and the latency of each call is measured. While the calls
+//! are made the op counts of the conductor are sampled, to show how far the
+//! validation and integration workflows fall behind the authored ops under
+//! load and how long they take to catch up afterwards.
+//! Running the same benchmark against each release makes performance
+//! regressions measurable.
+use std::str::FromStr;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+
+use anyhow::anyhow;
+use holochain_conductor_api::ZomeCall as ZomeCallRequest;
+use holochain_types::prelude::AppRoleId;
+use holochain_types::prelude::CellId;
+use holochain_types::prelude::ExternIO;
+use holochain_types::prelude::InstalledAppId;
+use structopt::StructOpt;
+
+use crate::calls::request_op_counts;
+use crate::cmds::Existing;
+use crate::ports::get_app_api;
+use crate::zome_call::admin_ports;
+use crate::zome_call::app_port;
+use crate::zome_call::call_with;
+use crate::zome_call::connect;
+use crate::zome_call::find_cell;
+use crate::CmdRunner;
+
+/// How often the op counts are sampled while the calls are made.
+const SAMPLE_INTERVAL: Duration = Duration::from_millis(250);
+
+#[doc(hidden)]
+#[derive(Debug, StructOpt)]
+pub struct Bench {
+    #[structopt(short, long, conflicts_with_all = &["existing_paths", "indices"], value_delimiter = ",")]
+    /// Ports to running conductor admin interfaces.
+    /// If this is empty existing sandboxes will be used,
+    /// which must already be running.
+    pub running: Vec<u16>,
+    #[structopt(flatten)]
+    pub existing: Existing,
+    #[structopt(long, default_value = crate::cli::DEFAULT_APP_ID)]
+    /// ID of the installed app to call.
+    pub app_id: InstalledAppId,
+    #[structopt(long)]
+    /// Role of the cell to call.
+    /// Only needed if the app has more than one cell.
+    pub role_id: Option<AppRoleId>,
+    #[structopt(short, long = "call", required = true, number_of_values = 1)]
+    /// A zome call in the mix, as `zome/function` optionally followed by
+    /// `=` and a JSON payload, and optionally preceded by a weight and `*`.
+    /// For example `-c='3*posts/get_posts' -c='posts/create_post="hello"'`
+    /// makes three reads for every write.
+    pub calls: Vec<BenchCall>,
+    #[structopt(short = "n", long, default_value = "1000")]
+    /// Total number of zome calls to make.
+    pub total: usize,
+    #[structopt(long, default_value = "10")]
+    /// Number of zome calls in flight at once.
+    pub concurrency: usize,
+    #[structopt(long, default_value = "60")]
+    /// Seconds to wait after the last call for the
+    /// authored ops to be integrated.
+    pub drain_timeout: u64,
+}
+
+/// A zome call in the mix of a benchmark.
+#[derive(Debug, Clone)]
+pub struct BenchCall {
+    /// How many times this call is made for each
+    /// time a call with a weight of one is made.
+    pub weight: usize,
+    /// The zome to call.
+    pub zome: String,
+    /// The function to call.
+    pub function: String,
+    /// The payload for the function.
+    pub payload: ExternIO,
+}
+
+impl BenchCall {
+    fn name(&self) -> String {
+        format!("{}/{}", self.zome, self.function)
+    }
+}
+
+impl FromStr for BenchCall {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (call, payload) = s.split_once('=').unwrap_or((s, "null"));
+        let (weight, call) = match call.split_once('*') {
+            Some((weight, call)) => (weight.trim().parse()?, call),
+            None => (1, call),
+        };
+        let (zome, function) = call
+            .split_once('/')
+            .ok_or_else(|| anyhow!("Expected a call like zome/function but got {}", call))?;
+        let payload: serde_json::Value = serde_json::from_str(payload)
+            .map_err(|e| anyhow!("The payload of {} is not valid JSON: {}", call, e))?;
+        Ok(Self {
+            weight,
+            zome: zome.trim().to_string(),
+            function: function.trim().to_string(),
+            payload: ExternIO::encode(payload)?,
+        })
+    }
+}
+
+/// The outcome of one zome call.
+struct Sample {
+    call: usize,
+    latency: Duration,
+    ok: bool,
+}
+
+/// How the ops authored during the benchmark were processed.
+#[derive(Debug, Default)]
+struct QueueReport {
+    /// Ops published by the cell during the benchmark.
+    published: usize,
+    /// The most authored ops which were not yet integrated at any sample.
+    max_backlog: usize,
+    /// How long after the last call the backlog took to clear,
+    /// if it did within the timeout.
+    drained_after: Option<Duration>,
+}
+
+#[doc(hidden)]
+pub async fn bench(req: Bench) -> anyhow::Result<()> {
+    let Bench {
+        running,
+        existing,
+        app_id,
+        role_id,
+        calls,
+        total,
+        concurrency,
+        drain_timeout,
+    } = req;
+    // Calls are made in a fixed order so runs are comparable.
+    let schedule: Vec<usize> = calls
+        .iter()
+        .enumerate()
+        .flat_map(|(i, call)| std::iter::repeat(i).take(call.weight))
+        .collect();
+    if schedule.is_empty() {
+        anyhow::bail!("All the calls have a weight of zero");
+    }
+    for port in admin_ports(running, existing).await? {
+        let mut cmd = connect(port).await?;
+        let cell_id = find_cell(&mut cmd, &app_id, role_id.as_ref()).await?;
+        let app_port = app_port(&mut cmd).await?;
+        msg!(
+            "Benchmarking {} zome calls, {} at a time, on the conductor on admin port {}",
+            total,
+            concurrency,
+            port
+        );
+
+        let start_counts = op_counts(&mut cmd, &cell_id).await?;
+        let next = Arc::new(AtomicUsize::new(0));
+        let started = Instant::now();
+        let mut workers = Vec::with_capacity(concurrency.max(1));
+        for _ in 0..concurrency.max(1) {
+            let mut client = get_app_api(app_port).await?;
+            let next = next.clone();
+            let schedule = schedule.clone();
+            let calls = calls.clone();
+            let cell_id = cell_id.clone();
+            workers.push(tokio::task::spawn(async move {
+                let mut samples = Vec::new();
+                let mut first_error = None;
+                loop {
+                    let i = next.fetch_add(1, Ordering::Relaxed);
+                    if i >= total {
+                        break;
+                    }
+                    let call = schedule[i % schedule.len()];
+                    let request = ZomeCallRequest {
+                        provenance: cell_id.agent_pubkey().clone(),
+                        cell_id: cell_id.clone(),
+                        zome_name: calls[call].zome.clone().into(),
+                        fn_name: calls[call].function.clone().into(),
+                        payload: calls[call].payload.clone(),
+                        cap_secret: None,
+                    };
+                    let call_started = Instant::now();
+                    let result = call_with(&mut client, request).await;
+                    samples.push(Sample {
+                        call,
+                        latency: call_started.elapsed(),
+                        ok: result.is_ok(),
+                    });
+                    if let Err(e) = result {
+                        first_error.get_or_insert(e);
+                    }
+                }
+                (samples, first_error)
+            }));
+        }
+
+        // Sample the op counts until all the calls are made.
+        let mut queue = QueueReport::default();
+        let workers = futures::future::join_all(workers);
+        tokio::pin!(workers);
+        let results = loop {
+            tokio::select! {
+                results = &mut workers => break results,
+                _ = tokio::time::sleep(SAMPLE_INTERVAL) => {
+                    queue.sample(start_counts, op_counts(&mut cmd, &cell_id).await?);
+                }
+            }
+        };
+        let elapsed = started.elapsed();
+
+        let mut samples = Vec::with_capacity(total);
+        for result in results {
+            let (worker_samples, first_error) = result?;
+            samples.extend(worker_samples);
+            if let Some(e) = first_error {
+                msg!("A zome call failed: {:?}", e);
+            }
+        }
+
+        // Wait for the ops authored by the calls to be integrated.
+        let finished = Instant::now();
+        let drain_timeout = Duration::from_secs(drain_timeout);
+        loop {
+            let backlog = queue.sample(start_counts, op_counts(&mut cmd, &cell_id).await?);
+            if backlog == 0 {
+                queue.drained_after = Some(finished.elapsed());
+                break;
+            }
+            if finished.elapsed() >= drain_timeout {
+                break;
+            }
+            tokio::time::sleep(SAMPLE_INTERVAL).await;
+        }
+
+        report(&calls, samples, elapsed, &queue, drain_timeout);
+    }
+    Ok(())
+}
+
+impl QueueReport {
+    /// Add a sample of the op counts and return the backlog at that point.
+    fn sample(&mut self, start: (usize, usize), (published, integrated): (usize, usize)) -> usize {
+        self.published = published.saturating_sub(start.0);
+        let backlog = self
+            .published
+            .saturating_sub(integrated.saturating_sub(start.1));
+        self.max_backlog = self.max_backlog.max(backlog);
+        backlog
+    }
+}
+
+/// The published and integrated op counts of this cell.
+async fn op_counts(cmd: &mut CmdRunner, cell_id: &CellId) -> anyhow::Result<(usize, usize)> {
+    request_op_counts(cmd)
+        .await?
+        .into_iter()
+        .find(|counts| counts.cell_id == *cell_id)
+        .map(|counts| (counts.published, counts.integrated))
+        .ok_or_else(|| anyhow!("The conductor has no op counts for cell {:?}", cell_id))
+}
+
+fn report(
+    calls: &[BenchCall],
+    samples: Vec<Sample>,
+    elapsed: Duration,
+    queue: &QueueReport,
+    drain_timeout: Duration,
+) {
+    msg!(
+        "{} calls in {:.2}s, {:.1} calls/s",
+        samples.len(),
+        elapsed.as_secs_f64(),
+        samples.len() as f64 / elapsed.as_secs_f64()
+    );
+    msg!(
+        "{:<32} | {:>6} | {:>6} | {:>9} | {:>9} | {:>9}",
+        "call",
+        "calls",
+        "errors",
+        "p50 ms",
+        "p95 ms",
+        "p99 ms"
+    );
+    let mut rows: Vec<_> = calls
+        .iter()
+        .enumerate()
+        .map(|(i, call)| (call.name(), Some(i)))
+        .collect();
+    if calls.len() > 1 {
+        rows.push(("all".to_string(), None));
+    }
+    for (name, call) in rows {
+        let samples: Vec<_> = samples
+            .iter()
+            .filter(|sample| call.map_or(true, |call| sample.call == call))
+            .collect();
+        let errors = samples.iter().filter(|sample| !sample.ok).count();
+        let mut latencies: Vec<_> = samples.iter().map(|sample| sample.latency).collect();
+        latencies.sort();
+        msg!(
+            "{:<32} | {:>6} | {:>6} | {:>9.2} | {:>9.2} | {:>9.2}",
+            name,
+            samples.len(),
+            errors,
+            millis(percentile(&latencies, 50.0)),
+            millis(percentile(&latencies, 95.0)),
+            millis(percentile(&latencies, 99.0))
+        );
+    }
+    msg!(
+        "{} ops published, at most {} waiting to be integrated",
+        queue.published,
+        queue.max_backlog
+    );
+    match queue.drained_after {
+        Some(drained_after) => msg!(
+            "All ops integrated {:.2}s after the last call",
+            drained_after.as_secs_f64()
+        ),
+        None => msg!(
+            "Ops were still waiting to be integrated {}s after the last call",
+            drain_timeout.as_secs()
+        ),
+    }
+}
+
+/// The latency which this percent of the sorted latencies are at or below.
+fn percentile(sorted: &[Duration], percent: f64) -> Option<Duration> {
+    let rank = (percent / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted.get(rank.max(1) - 1).copied()
+}
+
+fn millis(latency: Option<Duration>) -> f64 {
+    latency.map_or(f64::NAN, |latency| latency.as_secs_f64() * 1000.0)
+}
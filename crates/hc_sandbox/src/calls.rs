@@ -12,8 +12,13 @@ use anyhow::bail;
 use anyhow::ensure;
 use holochain_conductor_api::AdminRequest;
 use holochain_conductor_api::AdminResponse;
+use holochain_conductor_api::AppInterfaceInfo;
 use holochain_conductor_api::AppStatusFilter;
+use holochain_conductor_api::CellOpCounts;
+use holochain_conductor_api::FullStateDump;
 use holochain_conductor_api::InterfaceDriver;
+use holochain_conductor_api::Passphrase;
+use holochain_conductor_api::UninstalledAppData;
 use holochain_conductor_api::{AdminInterfaceConfig, InstalledAppInfo};
 use holochain_p2p::kitsune_p2p::agent_store::AgentInfoSigned;
 use holochain_types::prelude::DnaHash;
@@ -55,6 +60,7 @@ pub struct Call {
 pub enum AdminRequestCli {
     AddAdminWs(AddAdminWs),
     AddAppWs(AddAppWs),
+    RemoveAppWs(RemoveAppWs),
     RegisterDna(RegisterDna),
     InstallApp(InstallApp),
     InstallAppBundle(InstallAppBundle),
@@ -62,6 +68,8 @@ pub enum AdminRequestCli {
     UninstallApp(UninstallApp),
     /// Calls AdminRequest::ListAppInterfaces.
     ListAppWs,
+    /// Calls AdminRequest::ListAppInterfaceSettings.
+    ListAppWsSettings,
     /// Calls AdminRequest::ListDnas.
     ListDnas,
     /// Calls AdminRequest::GenerateAgentPubKey.
@@ -75,10 +83,14 @@ pub enum AdminRequestCli {
     EnableApp(EnableApp),
     DisableApp(DisableApp),
     DumpState(DumpState),
+    DumpFullState(DumpFullState),
     /// Calls AdminRequest::AddAgentInfo.
     /// _Unimplemented_.
     AddAgents,
     ListAgents(ListAgents),
+    /// Calls AdminRequest::UnlockConductor with the keystore passphrase,
+    /// read like any other passphrase.
+    UnlockConductor,
 }
 #[derive(Debug, StructOpt, Clone)]
 /// Calls AdminRequest::AddAdminInterfaces
@@ -96,6 +108,18 @@ pub struct AddAppWs {
     /// Optional port number.
     /// Defaults to assigned by OS.
     pub port: Option<u16>,
+    #[structopt(long)]
+    /// Address of the network interface to listen on.
+    /// Defaults to 127.0.0.1.
+    pub bind_address: Option<std::net::IpAddr>,
+    #[structopt(long = "allowed-origin")]
+    /// An origin to accept connections from, which can be repeated.
+    /// Defaults to accepting any origin.
+    pub allowed_origins: Vec<String>,
+    #[structopt(long)]
+    /// Refuse zome calls which aren't signed by the agent of the cell
+    /// they call.
+    pub require_signed_zome_calls: bool,
 }
 
 #[derive(Debug, StructOpt, Clone)]
@@ -163,12 +187,28 @@ pub struct InstallAppBundle {
     pub network_seed: Option<NetworkSeed>,
 }
 
+#[derive(Debug, StructOpt, Clone)]
+/// Calls AdminRequest::DetachAppInterface
+/// and closes an app interface.
+pub struct RemoveAppWs {
+    /// The port of the app interface to close.
+    pub port: u16,
+}
+
 #[derive(Debug, StructOpt, Clone)]
 /// Calls AdminRequest::UninstallApp
 /// and uninstalls the specified app.
 pub struct UninstallApp {
     /// The InstalledAppId to uninstall.
     pub app_id: String,
+    #[structopt(long)]
+    /// Delete the source chains of the app's cells,
+    /// unless another app still uses them.
+    pub delete_data: bool,
+    #[structopt(long)]
+    /// Write the source chains of the app's cells
+    /// to this JSON file before uninstalling it.
+    pub export: Option<PathBuf>,
 }
 
 #[derive(Debug, StructOpt, Clone)]
@@ -201,6 +241,22 @@ pub struct DumpState {
     pub agent_key: AgentPubKey,
 }
 
+#[derive(Debug, StructOpt, Clone)]
+/// Calls AdminRequest::DumpFullState
+/// and prints the cell's source chain, the ops in
+/// validation and integration and its op counts.
+pub struct DumpFullState {
+    #[structopt(parse(try_from_str = parse_dna_hash))]
+    /// The dna hash half of the cell id to dump.
+    pub dna: DnaHash,
+    #[structopt(parse(try_from_str = parse_agent_key))]
+    /// The agent half of the cell id to dump.
+    pub agent_key: AgentPubKey,
+    #[structopt(long)]
+    /// Print the whole dump as JSON instead of as tables.
+    pub json: bool,
+}
+
 #[derive(Debug, StructOpt, Clone)]
 /// Calls AdminRequest::RequestAgentInfo
 /// and pretty prints the agent info on
@@ -222,6 +278,9 @@ pub struct ListApps {
     #[structopt(short, long, parse(try_from_str = parse_status_filter))]
     /// Optionally request agent info for a particular cell id.
     pub status: Option<AppStatusFilter>,
+    #[structopt(short, long, parse(try_from_str = parse_agent_key))]
+    /// Optionally only list the apps installed for this agent.
+    pub agent_key: Option<AgentPubKey>,
 }
 
 #[doc(hidden)]
@@ -284,10 +343,18 @@ async fn call_inner(cmd: &mut CmdRunner, call: AdminRequestCli) -> anyhow::Resul
             let port = attach_app_interface(cmd, args).await?;
             msg!("Added App port {}", port);
         }
+        AdminRequestCli::RemoveAppWs(args) => {
+            detach_app_interface(cmd, args.port).await?;
+            msg!("Removed App port {}", args.port);
+        }
         AdminRequestCli::ListAppWs => {
             let ports = list_app_ws(cmd).await?;
             msg!("Attached App Interfaces {:?}", ports);
         }
+        AdminRequestCli::ListAppWsSettings => {
+            let interfaces = list_app_ws_settings(cmd).await?;
+            msg!("Attached App Interfaces {:#?}", interfaces);
+        }
         AdminRequestCli::RegisterDna(args) => {
             let dnas = register_dna(cmd, args).await?;
             msg!("Registered Dna: {:?}", dnas);
@@ -340,6 +407,21 @@ async fn call_inner(cmd: &mut CmdRunner, call: AdminRequestCli) -> anyhow::Resul
             let state = dump_state(cmd, args).await?;
             msg!("DUMP STATE \n{}", state);
         }
+        AdminRequestCli::DumpFullState(args) => {
+            let json = args.json;
+            let cell_id = CellId::new(args.dna, args.agent_key);
+            let dump = dump_full_state(cmd, cell_id.clone()).await?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&dump)?);
+            } else {
+                let counts = request_op_counts(cmd).await?;
+                let counts = counts.iter().find(|c| c.cell_id == cell_id);
+                msg!(
+                    "FULL STATE DUMP\n{}",
+                    crate::state_dump::render(&dump, counts)
+                );
+            }
+        }
         AdminRequestCli::AddAgents => todo!("Adding agent info via cli is coming soon"),
         AdminRequestCli::ListAgents(args) => {
             use std::fmt::Write;
@@ -390,6 +472,10 @@ async fn call_inner(cmd: &mut CmdRunner, call: AdminRequestCli) -> anyhow::Resul
                 msg!("{}\n", out);
             }
         }
+        AdminRequestCli::UnlockConductor => {
+            unlock_conductor(cmd).await?;
+            msg!("Unlocked conductor");
+        }
     }
     Ok(())
 }
@@ -400,7 +486,7 @@ pub async fn add_admin_interface(cmd: &mut CmdRunner, args: AddAdminWs) -> anyho
     let resp = cmd
         .command(AdminRequest::AddAdminInterfaces(vec![
             AdminInterfaceConfig {
-                driver: InterfaceDriver::Websocket { port },
+                driver: InterfaceDriver::websocket(port),
             },
         ]))
         .await?;
@@ -532,6 +618,12 @@ pub async fn uninstall_app(cmd: &mut CmdRunner, args: UninstallApp) -> anyhow::R
     let resp = cmd
         .command(AdminRequest::UninstallApp {
             installed_app_id: args.app_id,
+            data: if args.delete_data {
+                UninstalledAppData::Delete
+            } else {
+                UninstalledAppData::Retain
+            },
+            export_path: args.export,
         })
         .await?;
 
@@ -548,6 +640,13 @@ pub async fn list_app_ws(cmd: &mut CmdRunner) -> anyhow::Result<Vec<u16>> {
     Ok(expect_match!(resp => AdminResponse::AppInterfacesListed, "Failed to list app interfaces"))
 }
 
+/// Calls [`AdminRequest::ListAppInterfaceSettings`].
+pub async fn list_app_ws_settings(cmd: &mut CmdRunner) -> anyhow::Result<Vec<AppInterfaceInfo>> {
+    let resp = cmd.command(AdminRequest::ListAppInterfaceSettings).await?;
+    let error_msg = "Failed to list app interface settings";
+    Ok(expect_match!(resp => AdminResponse::AppInterfaceSettingsListed, error_msg))
+}
+
 /// Calls [`AdminRequest::ListCellIds`].
 pub async fn list_dnas(cmd: &mut CmdRunner) -> anyhow::Result<Vec<DnaHash>> {
     let resp = cmd.command(AdminRequest::ListDnas).await?;
@@ -582,6 +681,7 @@ pub async fn list_apps(
     let resp = cmd
         .command(AdminRequest::ListApps {
             status_filter: args.status,
+            agent_pub_key: args.agent_key,
         })
         .await?;
     Ok(expect_match!(resp => AdminResponse::AppsListed, "Failed to list apps"))
@@ -616,7 +716,12 @@ pub async fn disable_app(cmd: &mut CmdRunner, args: DisableApp) -> anyhow::Resul
 /// Calls [`AdminRequest::AttachAppInterface`] and adds another app interface.
 pub async fn attach_app_interface(cmd: &mut CmdRunner, args: AddAppWs) -> anyhow::Result<u16> {
     let resp = cmd
-        .command(AdminRequest::AttachAppInterface { port: args.port })
+        .command(AdminRequest::AttachAppInterface {
+            port: args.port,
+            bind_address: args.bind_address,
+            allowed_origins: Some(args.allowed_origins).filter(|o| !o.is_empty()),
+            require_signed_zome_calls: args.require_signed_zome_calls,
+        })
         .await?;
     tracing::debug!(?resp);
     match resp {
@@ -629,6 +734,22 @@ pub async fn attach_app_interface(cmd: &mut CmdRunner, args: AddAppWs) -> anyhow
     }
 }
 
+/// Calls [`AdminRequest::DetachAppInterface`] and closes the app interface on a port.
+pub async fn detach_app_interface(cmd: &mut CmdRunner, port: u16) -> anyhow::Result<()> {
+    let resp = cmd
+        .command(AdminRequest::DetachAppInterface { port })
+        .await?;
+    tracing::debug!(?resp);
+    match resp {
+        AdminResponse::AppInterfaceDetached => Ok(()),
+        _ => Err(anyhow!(
+            "Failed to detach app interface {}, got: {:?}",
+            port,
+            resp
+        )),
+    }
+}
+
 /// Calls [`AdminRequest::DumpState`] and dumps the current cell's state.
 // TODO: Add pretty print.
 // TODO: Default to dumping all cell state.
@@ -641,6 +762,43 @@ pub async fn dump_state(cmd: &mut CmdRunner, args: DumpState) -> anyhow::Result<
     Ok(expect_match!(resp => AdminResponse::StateDumped, "Failed to dump state"))
 }
 
+/// Calls [`AdminRequest::DumpFullState`] and returns the cell's full state.
+pub async fn dump_full_state(
+    cmd: &mut CmdRunner,
+    cell_id: CellId,
+) -> anyhow::Result<FullStateDump> {
+    let resp = cmd
+        .command(AdminRequest::DumpFullState {
+            cell_id: Box::new(cell_id),
+            dht_ops_cursor: None,
+        })
+        .await?;
+    Ok(expect_match!(resp => AdminResponse::FullStateDumped, "Failed to dump full state"))
+}
+
+/// Calls [`AdminRequest::RequestOpCounts`].
+pub async fn request_op_counts(cmd: &mut CmdRunner) -> anyhow::Result<Vec<CellOpCounts>> {
+    let resp = cmd.command(AdminRequest::RequestOpCounts).await?;
+    Ok(expect_match!(resp => AdminResponse::OpCountsRequested, "Failed to request op counts"))
+}
+
+/// Calls [`AdminRequest::UnlockConductor`] with the keystore passphrase.
+pub async fn unlock_conductor(cmd: &mut CmdRunner) -> anyhow::Result<()> {
+    let passphrase = holochain_util::pw::pw_get()?;
+    let passphrase = String::from_utf8(passphrase.read_lock().to_vec())?;
+    let resp = cmd
+        .command(AdminRequest::UnlockConductor {
+            passphrase: Passphrase(passphrase),
+        })
+        .await?;
+    ensure!(
+        matches!(resp, AdminResponse::ConductorUnlocked),
+        "Failed to unlock conductor, got: {:?}",
+        resp
+    );
+    Ok(())
+}
+
 /// Calls [`AdminRequest::AddAgentInfo`] with and adds the list of agent info.
 pub async fn add_agent_info(cmd: &mut CmdRunner, args: Vec<AgentInfoSigned>) -> anyhow::Result<()> {
     let resp = cmd
@@ -6,7 +6,7 @@ use std::path::Path;
 use std::path::PathBuf;
 use structopt::StructOpt;
 
-const DEFAULT_APP_ID: &str = "test-app";
+pub(crate) const DEFAULT_APP_ID: &str = "test-app";
 
 #[derive(Debug, StructOpt)]
 /// Helper for generating, running, and interacting with Holochain Conductor "sandboxes".
@@ -72,6 +72,15 @@ pub enum HcSandboxSubcommand {
     /// Make a call to a conductor's admin interface.
     Call(crate::calls::Call),
 
+    /// Call a zome function of an app on each conductor,
+    /// as the agent of the app's cell.
+    ZomeCall(crate::zome_call::ZomeCall),
+
+    /// Benchmark a mix of zome calls of an app on each conductor,
+    /// reporting latency percentiles, throughput and how long
+    /// the authored ops take to be integrated.
+    Bench(crate::bench::Bench),
+
     /// List sandboxes found in `$(pwd)/.hc`.
     List {
         /// Show more verbose information.
@@ -154,6 +163,8 @@ impl HcSandbox {
             HcSandboxSubcommand::Call(call) => {
                 crate::calls::call(&self.holochain_path, call).await?
             }
+            HcSandboxSubcommand::ZomeCall(call) => crate::zome_call::zome_call(call).await?,
+            HcSandboxSubcommand::Bench(bench) => crate::bench::bench(bench).await?,
             // HcSandboxSubcommand::Task => todo!("Running custom tasks is coming soon"),
             HcSandboxSubcommand::List { verbose } => {
                 crate::save::list(std::env::current_dir()?, verbose)?
@@ -73,6 +73,28 @@
 //! ```shell
 //! hc sandbox call list-cells
 //! ```
+//! #### Zome Call
+//! Calls a zome function of an app on each of the sandboxes,
+//! which must already be running.
+//! The payload is given as JSON.
+//!
+//! ```shell
+//! hc sandbox zome-call --zome posts --function create_post -p='"hello"'
+//! ```
+//! To see the state of a cell, for example when debugging why its ops
+//! aren't being integrated, dump it as tables or with `--json`:
+//!
+//! ```shell
+//! hc sandbox call dump-full-state <dna-hash> <agent-key>
+//! ```
+//! #### Bench
+//! Benchmarks a mix of zome calls on each of the running sandboxes and
+//! reports the p50, p95 and p99 latency of each call, the throughput, and
+//! how far integration of the authored ops fell behind.
+//!
+//! ```shell
+//! hc sandbox bench -n 5000 --concurrency 20 -c='3*posts/get_posts' -c='posts/create_post="hello"'
+//! ```
 //! #### List and Clean
 //! These commands allow you to list the persisted sandboxes
 //! in the current directory (from the`.hcXXX`) file.
@@ -130,6 +152,7 @@ macro_rules! msg {
     })
 }
 
+pub mod bench;
 pub mod bundles;
 pub mod calls;
 pub mod cli;
@@ -140,6 +163,8 @@ pub mod generate;
 pub mod run;
 pub mod sandbox;
 pub mod save;
+pub mod state_dump;
+pub mod zome_call;
 pub use cli::HcSandbox;
 
 mod ports;
@@ -45,6 +45,9 @@ pub async fn run(
             &mut cmd,
             AddAppWs {
                 port: Some(app_port),
+                bind_address: None,
+                allowed_origins: vec![],
+                require_signed_zome_calls: false,
             },
         )
         .await?;
@@ -0,0 +1,124 @@
+//! Readable rendering of a cell's full state dump, for support and debugging.
+//!
+//! Hashes are shortened to keep the tables narrow.
+//! Use `--json` on the command to get the complete dump instead.
+use std::fmt::Write;
+
+use holochain_conductor_api::CellOpCounts;
+use holochain_conductor_api::FullStateDump;
+use holochain_types::dht_op::DhtOp;
+use holochain_types::prelude::*;
+
+/// Render the source chain, the ops in each stage of validation and the op
+/// counts of a cell as tables.
+pub fn render(dump: &FullStateDump, counts: Option<&CellOpCounts>) -> String {
+    let mut out = String::new();
+    let chain = &dump.source_chain_dump;
+    let _ = writeln!(
+        out,
+        "Source chain: {} actions, {} ops published",
+        chain.records.len(),
+        chain.published_ops_count
+    );
+    let _ = writeln!(
+        out,
+        "{:>5} | {:<18} | {:<16} | {:<23} | entry",
+        "seq", "action", "hash", "time"
+    );
+    for record in &chain.records {
+        let action = &record.action;
+        let _ = writeln!(
+            out,
+            "{:>5} | {:<18} | {:<16} | {:<23} | {}",
+            action.action_seq(),
+            action.action_type().to_string(),
+            short(&record.action_address),
+            time(action.timestamp()),
+            action.entry_type().map(entry_type).unwrap_or_default()
+        );
+    }
+    let _ = writeln!(out, "Init: {:?}", dump.init_status);
+
+    let integration = &dump.integration_dump;
+    render_ops(&mut out, "Validation limbo", &integration.validation_limbo);
+    render_ops(
+        &mut out,
+        "Integration limbo",
+        &integration.integration_limbo,
+    );
+    let _ = writeln!(out, "Integrated: {} ops", integration.integrated.len());
+    let mut by_type = std::collections::BTreeMap::new();
+    for op in &integration.integrated {
+        *by_type.entry(op.get_type().to_string()).or_insert(0) += 1;
+    }
+    for (op_type, count) in by_type {
+        let _ = writeln!(out, "  {:<26} {}", op_type, count);
+    }
+    if let Some(counts) = counts {
+        let _ = writeln!(
+            out,
+            "Op counts: {} published by this cell, {} integrated for its DNA",
+            counts.published, counts.integrated
+        );
+    }
+    let _ = writeln!(out, "Peers: {} known", dump.peer_dump.peers.len());
+    out
+}
+
+fn render_ops(out: &mut String, stage: &str, ops: &[DhtOp]) {
+    let _ = writeln!(out, "{}: {} ops", stage, ops.len());
+    if ops.is_empty() {
+        return;
+    }
+    let _ = writeln!(
+        out,
+        "  {:<26} | {:<16} | {:<16} | {:>5} | action",
+        "op", "basis", "author", "seq"
+    );
+    for op in ops {
+        let action = op.action();
+        let _ = writeln!(
+            out,
+            "  {:<26} | {:<16} | {:<16} | {:>5} | {}",
+            op.get_type().to_string(),
+            short(&op.dht_basis()),
+            short(action.author()),
+            action.action_seq(),
+            action.action_type()
+        );
+    }
+}
+
+/// The start and end of a hash, which is enough to tell hashes apart by eye.
+fn short<T: HashType>(hash: &HoloHash<T>) -> String {
+    let hash = hash.to_string();
+    match (hash.get(..8), hash.get(hash.len().saturating_sub(6)..)) {
+        (Some(start), Some(end)) if hash.len() > 16 => format!("{}..{}", start, end),
+        _ => hash,
+    }
+}
+
+fn time(timestamp: Timestamp) -> String {
+    let micros = timestamp.as_micros();
+    chrono::NaiveDateTime::from_timestamp_opt(
+        micros.div_euclid(1_000_000),
+        (micros.rem_euclid(1_000_000) * 1000) as u32,
+    )
+    .map(|time| time.format("%Y-%m-%d %H:%M:%S%.3f").to_string())
+    .unwrap_or_else(|| micros.to_string())
+}
+
+fn entry_type(entry_type: &EntryType) -> String {
+    match entry_type {
+        EntryType::AgentPubKey => "agent key".to_string(),
+        EntryType::App(app) => format!(
+            "app entry {} of zome {} ({:?})",
+            app.id().0,
+            app.zome_id().0,
+            app.visibility()
+        ),
+        EntryType::CapClaim => "cap claim".to_string(),
+        EntryType::CapGrant => "cap grant".to_string(),
+        EntryType::Chunk => "blob chunk".to_string(),
+    }
+}
@@ -0,0 +1,183 @@
+//! Helpers for calling zome functions of the apps installed in sandboxes.
+//!
+//! Calls are made over an app interface of each conductor, which is attached
+//! if the conductor doesn't have one yet. The payload is given as JSON and
+//! the result is printed as MessagePack data.
+use anyhow::anyhow;
+use anyhow::bail;
+use holochain_conductor_api::AppRequest;
+use holochain_conductor_api::AppResponse;
+use holochain_conductor_api::ZomeCall as ZomeCallRequest;
+use holochain_types::prelude::AppRoleId;
+use holochain_types::prelude::CellId;
+use holochain_types::prelude::ExternIO;
+use holochain_types::prelude::InstalledAppId;
+use holochain_websocket::WebsocketSender;
+use structopt::StructOpt;
+
+use crate::calls::attach_app_interface;
+use crate::calls::list_app_ws;
+use crate::calls::list_apps;
+use crate::calls::AddAppWs;
+use crate::calls::ListApps;
+use crate::cmds::Existing;
+use crate::ports::get_admin_ports;
+use crate::ports::get_app_api;
+use crate::CmdRunner;
+
+#[doc(hidden)]
+#[derive(Debug, StructOpt)]
+pub struct ZomeCall {
+    #[structopt(short, long, conflicts_with_all = &["existing_paths", "indices"], value_delimiter = ",")]
+    /// Ports to running conductor admin interfaces.
+    /// If this is empty existing sandboxes will be used,
+    /// which must already be running.
+    pub running: Vec<u16>,
+    #[structopt(flatten)]
+    pub existing: Existing,
+    #[structopt(long, default_value = crate::cli::DEFAULT_APP_ID)]
+    /// ID of the installed app to call.
+    pub app_id: InstalledAppId,
+    #[structopt(long)]
+    /// Role of the cell to call.
+    /// Only needed if the app has more than one cell.
+    pub role_id: Option<AppRoleId>,
+    #[structopt(short, long)]
+    /// Name of the zome to call.
+    pub zome: String,
+    #[structopt(short, long)]
+    /// Name of the zome function to call.
+    pub function: String,
+    #[structopt(short, long, default_value = "null")]
+    /// Payload for the zome function as JSON.
+    /// For example `-p='{"content": "hello"}'`.
+    pub payload: String,
+}
+
+#[doc(hidden)]
+pub async fn zome_call(req: ZomeCall) -> anyhow::Result<()> {
+    let ZomeCall {
+        running,
+        existing,
+        app_id,
+        role_id,
+        zome,
+        function,
+        payload,
+    } = req;
+    let payload: serde_json::Value = serde_json::from_str(&payload)
+        .map_err(|e| anyhow!("The payload is not valid JSON: {}", e))?;
+    let payload = ExternIO::encode(payload)?;
+    for port in admin_ports(running, existing).await? {
+        let mut cmd = connect(port).await?;
+        let cell_id = find_cell(&mut cmd, &app_id, role_id.as_ref()).await?;
+        let app_port = app_port(&mut cmd).await?;
+        let request = ZomeCallRequest {
+            provenance: cell_id.agent_pubkey().clone(),
+            cell_id,
+            zome_name: zome.clone().into(),
+            fn_name: function.clone().into(),
+            payload: payload.clone(),
+            cap_secret: None,
+        };
+        let output = call(app_port, request).await?;
+        let output: rmpv::Value = output.decode()?;
+        msg!("Conductor on admin port {} returned: {}", port, output);
+    }
+    Ok(())
+}
+
+/// The admin ports of the running conductors to call, which are the
+/// sandboxes in the current directory if none are given.
+pub(crate) async fn admin_ports(running: Vec<u16>, existing: Existing) -> anyhow::Result<Vec<u16>> {
+    if !running.is_empty() {
+        return Ok(running);
+    }
+    let paths = if existing.is_empty() {
+        crate::save::load(std::env::current_dir()?)?
+    } else {
+        existing.load()?
+    };
+    get_admin_ports(paths).await
+}
+
+/// Connect to the admin interface of a running conductor.
+pub(crate) async fn connect(port: u16) -> anyhow::Result<CmdRunner> {
+    CmdRunner::try_new(port).await.map_err(|e| {
+        anyhow!(
+            "Failed to connect to the conductor on admin port {}, is it running? {:?}",
+            port,
+            e
+        )
+    })
+}
+
+/// Make a zome call over the app interface on this port.
+pub async fn call(app_port: u16, request: ZomeCallRequest) -> anyhow::Result<ExternIO> {
+    let mut client = get_app_api(app_port).await?;
+    call_with(&mut client, request).await
+}
+
+/// Make a zome call with a client which is already connected to an app interface.
+pub(crate) async fn call_with(
+    client: &mut WebsocketSender,
+    request: ZomeCallRequest,
+) -> anyhow::Result<ExternIO> {
+    let response: AppResponse = client
+        .request(AppRequest::ZomeCall(Box::new(request)))
+        .await?;
+    match response {
+        AppResponse::ZomeCall(output) => Ok(*output),
+        AppResponse::Error(e) => bail!("The zome call failed: {:?}", e),
+        r => bail!("Unexpected response to the zome call: {:?}", r),
+    }
+}
+
+/// Find the cell of the app with this role, or its only cell.
+pub(crate) async fn find_cell(
+    cmd: &mut CmdRunner,
+    app_id: &InstalledAppId,
+    role_id: Option<&AppRoleId>,
+) -> anyhow::Result<CellId> {
+    let list = ListApps {
+        status: None,
+        agent_key: None,
+    };
+    let app = list_apps(cmd, list)
+        .await?
+        .into_iter()
+        .find(|app| app.installed_app_id == *app_id)
+        .ok_or_else(|| anyhow!("No app with ID {} is installed", app_id))?;
+    let mut cells = app
+        .cell_data
+        .into_iter()
+        .filter(|cell| role_id.map_or(true, |role_id| cell.as_role_id() == role_id));
+    match (cells.next(), cells.next()) {
+        (Some(cell), None) => Ok(cell.into_id()),
+        (None, _) => bail!("App {} has no cell with role {:?}", app_id, role_id),
+        (Some(_), Some(_)) => bail!(
+            "App {} has more than one cell, choose one with --role-id",
+            app_id
+        ),
+    }
+}
+
+/// The port of an app interface of this conductor,
+/// attaching a new one if there are none.
+pub(crate) async fn app_port(cmd: &mut CmdRunner) -> anyhow::Result<u16> {
+    match list_app_ws(cmd).await?.first() {
+        Some(port) => Ok(*port),
+        None => {
+            attach_app_interface(
+                cmd,
+                AddAppWs {
+                    port: None,
+                    bind_address: None,
+                    allowed_origins: Vec::new(),
+                    require_signed_zome_calls: false,
+                },
+            )
+            .await
+        }
+    }
+}
@@ -67,6 +67,7 @@ enum ActivityEntry<Unit> {
     Agent(AgentPubKey),
     CapClaim(EntryHash),
     CapGrant(EntryHash),
+    Chunk(EntryHash),
 }
 
 impl OpHelper for Op {
@@ -351,6 +352,11 @@ impl OpHelper for Op {
                             original_action_hash: original_action_address.clone(),
                             original_entry_hash: original_entry_address.clone(),
                         },
+                        ActivityEntry::Chunk(entry_hash) => OpActivity::UpdateChunk {
+                            entry_hash,
+                            original_action_hash: original_action_address.clone(),
+                            original_entry_hash: original_entry_address.clone(),
+                        },
                     },
                     Action::Delete(Delete {
                         deletes_address,
@@ -411,6 +417,13 @@ impl OpHelper for Op {
                     deletes_entry_address: original_entry_hash,
                     ..
                 } = &delete.hashed.content;
+                // Chunks have no app defined type to map to.
+                if let EntryType::Chunk = original_action.entry_type() {
+                    return Ok(OpType::RegisterDelete(OpDelete::Chunk {
+                        original_action_hash: original_action_hash.clone(),
+                        original_entry_hash: original_entry_hash.clone(),
+                    }));
+                }
                 let r = match map_entry::<ET>(
                     original_action.entry_type(),
                     original_entry_hash,
@@ -514,6 +527,7 @@ where
                 }
                 Ok(InScopeEntry::Agent(entry_hash.clone().into()))
             }
+            EntryType::Chunk => Err(host_validated_chunk()),
             _ => Err(wasm_error!(WasmErrorInner::Guest(
                 "Entry type is a capability and should be private but there is an entry present"
                     .to_string()
@@ -539,6 +553,7 @@ where
             EntryType::AgentPubKey => Err(wasm_error!(WasmErrorInner::Guest(
                 "Entry type AgentPubKey is missing entry.".to_string()
             ))),
+            EntryType::Chunk => Err(host_validated_chunk()),
         },
         RecordEntryRef::NotApplicable => Err(wasm_error!(WasmErrorInner::Guest(
             "Has Entry type but entry is marked not applicable".to_string()
@@ -586,6 +601,7 @@ where
         EntryType::AgentPubKey => Ok(ActivityEntry::Agent(entry_hash.clone().into())),
         EntryType::CapClaim => Ok(ActivityEntry::CapClaim(entry_hash.clone())),
         EntryType::CapGrant => Ok(ActivityEntry::CapGrant(entry_hash.clone())),
+        EntryType::Chunk => Ok(ActivityEntry::Chunk(entry_hash.clone())),
     }
 }
 
@@ -657,6 +673,16 @@ fn deny_other_zome() -> WasmError {
     ))
 }
 
+/// Produce an error because chunks of blobs
+/// are only validated by the host, so the ops
+/// storing them are never passed to a zome.
+fn host_validated_chunk() -> WasmError {
+    wasm_error!(WasmErrorInner::Host(
+        "Op called for a chunk, which is only validated by the host. This is a Holochain bug"
+            .to_string()
+    ))
+}
+
 impl<ET> InScopeEntry<ET>
 where
     ET: UnitEnum,
@@ -713,6 +739,7 @@ impl<Unit, LT> From<ActivityEntry<Unit>> for OpActivity<Unit, LT> {
             ActivityEntry::Agent(key) => OpActivity::CreateAgent(key),
             ActivityEntry::CapClaim(hash) => OpActivity::CreateCapClaim(hash),
             ActivityEntry::CapGrant(hash) => OpActivity::CreateCapGrant(hash),
+            ActivityEntry::Chunk(hash) => OpActivity::CreateChunk(hash),
         }
     }
 }
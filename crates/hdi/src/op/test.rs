@@ -71,6 +71,7 @@ fn test_get_unit_entry_type(
 #[test_case(EntryType::AgentPubKey => matches Ok(ActivityEntry::Agent(_)); "agent")]
 #[test_case(EntryType::CapClaim => matches Ok(ActivityEntry::CapClaim(_)); "cap claim")]
 #[test_case(EntryType::CapGrant => matches Ok(ActivityEntry::CapGrant(_)); "cap grant")]
+#[test_case(EntryType::Chunk => matches Ok(ActivityEntry::Chunk(_)); "chunk")]
 #[test_case(EntryType::App(public_aet(0, 3)) => matches Err(WasmErrorInner::Guest(_)) ; "entry type out of range")]
 #[test_case(EntryType::App(private_aet(0, 3)) => matches Err(WasmErrorInner::Guest(_)) ; "private entry type out of range")]
 #[test_case(
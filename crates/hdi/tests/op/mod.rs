@@ -138,6 +138,7 @@ fn op_errors(op: Op) -> WasmErrorInner {
 #[test_case(OpType::RegisterAgentActivity(OpActivity::CreateEntry { entry_hash: eh(0), entry_type: None }))]
 #[test_case(OpType::RegisterAgentActivity(OpActivity::CreateCapClaim(eh(0))))]
 #[test_case(OpType::RegisterAgentActivity(OpActivity::CreateCapGrant(eh(0))))]
+#[test_case(OpType::RegisterAgentActivity(OpActivity::CreateChunk(eh(0))))]
 #[test_case(OpType::RegisterAgentActivity(OpActivity::CreatePrivateEntry {entry_hash: eh(0), entry_type: Some(UnitEntryTypes::A) }))]
 #[test_case(OpType::RegisterAgentActivity(OpActivity::CreatePrivateEntry {entry_hash: eh(0), entry_type: None }))]
 #[test_case(OpType::RegisterAgentActivity(OpActivity::CreateAgent(ak(4))))]
@@ -148,6 +149,7 @@ fn op_errors(op: Op) -> WasmErrorInner {
 #[test_case(OpType::RegisterAgentActivity(OpActivity::UpdateAgent { new_key: ak(2), original_action_hash: ah(1), original_key: ak(1) }))]
 #[test_case(OpType::RegisterAgentActivity(OpActivity::UpdateCapClaim { entry_hash: eh(0), original_action_hash: ah(1), original_entry_hash: eh(1) }))]
 #[test_case(OpType::RegisterAgentActivity(OpActivity::UpdateCapGrant { entry_hash: eh(0), original_action_hash: ah(1), original_entry_hash: eh(1) }))]
+#[test_case(OpType::RegisterAgentActivity(OpActivity::UpdateChunk { entry_hash: eh(0), original_action_hash: ah(1), original_entry_hash: eh(1) }))]
 #[test_case(OpType::RegisterAgentActivity(OpActivity::DeleteEntry { original_action_hash: ah(1), original_entry_hash: eh(1) }))]
 #[test_case(OpType::RegisterAgentActivity(OpActivity::CreateLink {base_address: lh(0), target_address: lh(2), tag: ().into(), link_type: Some(LinkTypes::A) }))]
 #[test_case(OpType::RegisterAgentActivity(OpActivity::CreateLink {base_address: lh(0), target_address: lh(2), tag: ().into(), link_type: None }))]
@@ -205,6 +207,7 @@ fn op_errors(op: Op) -> WasmErrorInner {
 #[test_case(OpType::RegisterDelete(OpDelete::Agent { original_key: ak(4), original_action_hash: ah(2) }))]
 #[test_case(OpType::RegisterDelete(OpDelete::CapClaim { original_action_hash: ah(1), original_entry_hash: eh(1) }))]
 #[test_case(OpType::RegisterDelete(OpDelete::CapGrant { original_action_hash: ah(1), original_entry_hash: eh(1) }))]
+#[test_case(OpType::RegisterDelete(OpDelete::Chunk { original_action_hash: ah(1), original_entry_hash: eh(1) }))]
 // Register Create Link
 #[test_case(OpType::RegisterCreateLink {base_address: lh(0), target_address: lh(2), tag: ().into(), link_type: LinkTypes::A })]
 #[test_case(OpType::RegisterCreateLink {base_address: lh(0), target_address: lh(2), tag: ().into(), link_type: LinkTypes::B })]
@@ -755,6 +758,26 @@ fn op_to_type(op: OpType<EntryTypes, LinkTypes>) {
                 original_entry: None,
             })
         }
+        OpType::RegisterDelete(OpDelete::Chunk {
+            original_action_hash,
+            original_entry_hash,
+        }) => {
+            let mut d = Delete::arbitrary(&mut ud).unwrap();
+            d.deletes_address = original_action_hash;
+            d.deletes_entry_address = original_entry_hash.clone();
+            let mut c = Create::arbitrary(&mut ud).unwrap();
+            c.entry_type = EntryType::Chunk;
+            c.entry_hash = original_entry_hash;
+            let original_action = EntryCreationAction::Create(c);
+            Op::RegisterDelete(RegisterDelete {
+                delete: SignedHashed {
+                    hashed: HoloHashed::from_content_sync(d),
+                    signature: Signature::arbitrary(&mut ud).unwrap(),
+                },
+                original_action,
+                original_entry: None,
+            })
+        }
         OpType::RegisterAgentActivity(activity) => {
             let r = match activity {
                 OpActivity::CreateEntry {
@@ -876,6 +899,12 @@ fn op_to_type(op: OpType<EntryTypes, LinkTypes>) {
                     c.entry_type = EntryType::CapGrant;
                     Action::Create(c)
                 }
+                OpActivity::CreateChunk(entry_hash) => {
+                    let mut c = Create::arbitrary(&mut ud).unwrap();
+                    c.entry_hash = entry_hash;
+                    c.entry_type = EntryType::Chunk;
+                    Action::Create(c)
+                }
                 OpActivity::UpdateCapClaim {
                     entry_hash,
                     original_action_hash,
@@ -900,6 +929,18 @@ fn op_to_type(op: OpType<EntryTypes, LinkTypes>) {
                     u.original_entry_address = original_entry_hash;
                     Action::Update(u)
                 }
+                OpActivity::UpdateChunk {
+                    entry_hash,
+                    original_action_hash,
+                    original_entry_hash,
+                } => {
+                    let mut u = Update::arbitrary(&mut ud).unwrap();
+                    u.entry_hash = entry_hash;
+                    u.entry_type = EntryType::Chunk;
+                    u.original_action_address = original_action_hash;
+                    u.original_entry_address = original_entry_hash;
+                    Action::Update(u)
+                }
                 OpActivity::Dna(dna_hash) => {
                     let mut d = Dna::arbitrary(&mut ud).unwrap();
                     d.hash = dna_hash;
@@ -18,6 +18,32 @@ pub fn get_agent_activity(
     })
 }
 
+/// Get a page of the activity at up to `limit` positions of a remote agent's
+/// chain, like [ `get_agent_activity` ]. Pages of hash bounded ranges hold up to
+/// `limit` valid actions instead, along with the rejected actions among them.
+///
+/// Pass the `next` cursor of each page back in to get the page after it, starting
+/// with `None`. Only the activity in the page is fetched from the agent's
+/// neighbourhood. The status of the chain is always that of the whole chain.
+pub fn get_agent_activity_page(
+    agent: AgentPubKey,
+    query: ChainQueryFilter,
+    request: ActivityRequest,
+    cursor: Option<ChainCursor>,
+    limit: u32,
+) -> ExternResult<AgentActivityPage> {
+    HDK.with(|h| {
+        h.borrow()
+            .get_agent_activity_page(GetAgentActivityPageInput {
+                agent_pubkey: agent,
+                chain_query_filter: query,
+                activity_request: request,
+                cursor,
+                limit,
+            })
+    })
+}
+
 /// Walks the source chain in ascending order (oldest to latest) filtering by action and/or entry type
 ///
 /// Given an action and entry type, returns an [ `Vec<Record>` ]
@@ -31,3 +57,55 @@ pub fn get_agent_activity(
 pub fn query(filter: ChainQueryFilter) -> ExternResult<Vec<Record>> {
     HDK.with(|h| h.borrow().query(filter))
 }
+
+/// Query a page of up to `limit` records of the source chain, like [ `query` ].
+///
+/// Pass the `next` cursor of each page back in to get the page after it, starting
+/// with `None`. The host carries on from the cursor, so the records of earlier
+/// pages aren't read again. The last page has no `next` cursor.
+///
+/// ```ignore
+/// let mut cursor = None;
+/// loop {
+///     let page = query_page(ChainQueryFilter::new(), cursor, 20)?;
+///     // ...
+///     match page.next {
+///         Some(next) => cursor = Some(next),
+///         None => break,
+///     }
+/// }
+/// ```
+pub fn query_page(
+    filter: ChainQueryFilter,
+    cursor: Option<ChainCursor>,
+    limit: u32,
+) -> ExternResult<RecordsPage> {
+    HDK.with(|h| {
+        h.borrow().query_page(ChainQueryPageInput {
+            filter,
+            cursor,
+            limit,
+        })
+    })
+}
+
+/// Check the records committed so far in this zome call against the same sys validation
+/// (entry and link tag sizes, entry types, previous actions and timestamps) that they must pass
+/// when the call's records are committed at the end of the call.
+///
+/// Instead of the whole call failing with an invalid commit, this returns a
+/// [ `SysValidationIssue` ] for each record that would be rejected, so the zome can report
+/// precisely what is wrong, and return an error to discard the call's records.
+///
+/// ```ignore
+/// create_entry(EntryTypes::Post(post))?;
+/// let issues = dry_run_sys_validation()?;
+/// if !issues.is_empty() {
+///     return Err(wasm_error!(WasmErrorInner::Guest(format!("{:?}", issues))));
+/// }
+/// ```
+///
+/// App validation is not run, so records may still be rejected by the app's validation callbacks.
+pub fn dry_run_sys_validation() -> ExternResult<Vec<SysValidationIssue>> {
+    HDK.with(|h| h.borrow().dry_run_sys_validation(()))
+}
@@ -229,6 +229,35 @@ where
         .unwrap())
 }
 
+/// Get only the action at the action hash passed in, without its entry.
+/// Returns [`None`] if the action does not exist.
+///
+/// Authorities only send the action, so this is cheaper than [`get`] when the entry isn't
+/// needed, e.g. to read who authored a record and when. Rejected actions are not returned.
+pub fn get_action(hash: ActionHash) -> ExternResult<Option<SignedActionHashed>> {
+    Ok(HDK
+        .with(|h| h.borrow().get_action(vec![hash]))?
+        .into_iter()
+        .next()
+        .unwrap())
+}
+
+/// Check whether there is any data at the entry or action hash passed in, without fetching it.
+///
+/// Authorities only send the actions at the hash, so this is cheaper than [`get`] when the data
+/// itself isn't needed. Data which has been rejected doesn't exist. Data which has been deleted
+/// still exists, use [`get`] to check whether it's live.
+pub fn exists<H>(hash: H) -> ExternResult<bool>
+where
+    AnyDhtHash: From<H>,
+{
+    Ok(HDK
+        .with(|h| h.borrow().exists(vec![AnyDhtHash::from(hash)]))?
+        .into_iter()
+        .next()
+        .unwrap())
+}
+
 /// Get a record and its details for the entry or action hash passed in.
 /// Returns [`None`] if the entry/action does not exist.
 /// The details returned are a contextual mix of records and action hashes.
@@ -254,11 +283,16 @@ where
 ///
 /// [`Details`] for an action hash return:
 /// - the record for this action hash if it exists
+/// - the validation status of that record
 /// - all update and delete _records_ that reference that specified action
 ///
 /// [`Details`] for an entry hash return:
 /// - all creates, updates and delete _records_ that reference that entry hash
 /// - all update and delete _records_ that reference the records that reference the entry hash
+/// - the creates and updates of the entry that were rejected by validation
+/// - whether the entry is live or dead as far as this agent can see
+///
+/// This is everything needed to render the edit history of an entry with a single call.
 ///
 /// Note: Entries are just values, so can be referenced by many CRUD actions by many authors.
 ///       e.g. the number 1 or string "foo" can be referenced by anyone publishing CRUD actions at
@@ -287,6 +321,34 @@ pub fn get_details<H: Into<AnyDhtHash>>(
         .unwrap())
 }
 
+/// Commit a blob which is too large for a single entry, such as a file.
+///
+/// The blob is split into chunks of at most [`CHUNK_SIZE_LIMIT`] bytes and each chunk is created
+/// as its own [`Entry::Chunk`], so the chunks are published to different authorities.
+/// Chunks are addressed by their content, so identical chunks are only stored once.
+///
+/// The returned [`BlobManifest`] is all that's needed to read the blob back with [`read_blob`],
+/// and is small enough to store in an app entry.
+///
+/// ```ignore
+/// let manifest = write_blob(file_bytes)?;
+/// create_entry(EntryTypes::File(File { name, manifest }))?;
+/// ```
+///
+/// Chunks are only validated by the host, which checks their size, so the ops storing chunks
+/// are never passed to the validation callbacks of the app. The agent activity and deletes of
+/// chunks are still validated by the app, as [`OpActivity::CreateChunk`] and [`OpDelete::Chunk`].
+pub fn write_blob<B: Into<Vec<u8>>>(blob: B) -> ExternResult<BlobManifest> {
+    HDK.with(|h| h.borrow().write_blob(Bytes::from(blob.into())))
+}
+
+/// Read a blob written by [`write_blob`] back by getting all of its chunks.
+///
+/// Returns [`None`] if any of the chunks can't be found locally or on the network.
+pub fn read_blob(manifest: BlobManifest) -> ExternResult<Option<Bytes>> {
+    HDK.with(|h| h.borrow().read_blob(manifest))
+}
+
 /// Implements a whole lot of sane defaults for a struct or enum that should behave as an entry.
 /// All the entry def fields are available as dedicated methods on the type and matching From impls
 /// are provided for each. This allows for both Foo::entry_def() and EntryDef::from(Foo::new())
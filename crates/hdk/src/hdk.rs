@@ -26,7 +26,16 @@ pub trait HdkT: HdiT {
         &self,
         get_agent_activity_input: GetAgentActivityInput,
     ) -> ExternResult<AgentActivity>;
+    fn get_agent_activity_page(
+        &self,
+        get_agent_activity_page_input: GetAgentActivityPageInput,
+    ) -> ExternResult<AgentActivityPage>;
     fn query(&self, filter: ChainQueryFilter) -> ExternResult<Vec<Record>>;
+    fn query_page(&self, query_page_input: ChainQueryPageInput) -> ExternResult<RecordsPage>;
+    fn dry_run_sys_validation(
+        &self,
+        dry_run_sys_validation_input: (),
+    ) -> ExternResult<Vec<SysValidationIssue>>;
     // Ed25519
     fn sign(&self, sign: Sign) -> ExternResult<Signature>;
     fn sign_ephemeral(&self, sign_ephemeral: SignEphemeral) -> ExternResult<EphemeralSignatures>;
@@ -35,7 +44,11 @@ pub trait HdkT: HdiT {
     fn update(&self, update_input: UpdateInput) -> ExternResult<ActionHash>;
     fn delete(&self, delete_input: DeleteInput) -> ExternResult<ActionHash>;
     fn get(&self, get_input: Vec<GetInput>) -> ExternResult<Vec<Option<Record>>>;
+    fn get_action(&self, hashes: Vec<ActionHash>) -> ExternResult<Vec<Option<SignedActionHashed>>>;
+    fn exists(&self, hashes: Vec<AnyDhtHash>) -> ExternResult<Vec<bool>>;
     fn get_details(&self, get_input: Vec<GetInput>) -> ExternResult<Vec<Option<Details>>>;
+    fn write_blob(&self, blob: Bytes) -> ExternResult<BlobManifest>;
+    fn read_blob(&self, manifest: BlobManifest) -> ExternResult<Option<Bytes>>;
     // CounterSigning
     fn accept_countersigning_preflight_request(
         &self,
@@ -44,6 +57,7 @@ pub trait HdkT: HdiT {
     // Info
     fn agent_info(&self, agent_info_input: ()) -> ExternResult<AgentInfo>;
     fn call_info(&self, call_info_input: ()) -> ExternResult<CallInfo>;
+    fn runtime_properties(&self, runtime_properties_input: ()) -> ExternResult<SerializedBytes>;
     // Link
     fn create_link(&self, create_link_input: CreateLinkInput) -> ExternResult<ActionHash>;
     fn delete_link(&self, delete_link_input: DeleteLinkInput) -> ExternResult<ActionHash>;
@@ -52,10 +66,18 @@ pub trait HdkT: HdiT {
         &self,
         get_links_input: Vec<GetLinksInput>,
     ) -> ExternResult<Vec<LinkDetails>>;
+    fn aggregate_links(
+        &self,
+        aggregate_links_input: Vec<AggregateLinksInput>,
+    ) -> ExternResult<Vec<LinkAggregate>>;
     // P2P
     fn call(&self, call: Vec<Call>) -> ExternResult<Vec<ZomeCallResponse>>;
     fn emit_signal(&self, app_signal: AppSignal) -> ExternResult<()>;
     fn remote_signal(&self, remote_signal: RemoteSignal) -> ExternResult<()>;
+    fn remote_signal_acknowledged(
+        &self,
+        remote_signal: RemoteSignal,
+    ) -> ExternResult<Vec<RemoteSignalReceipt>>;
     // Random
     fn random_bytes(&self, number_of_bytes: u32) -> ExternResult<Bytes>;
     // Time
@@ -96,7 +118,16 @@ mockall::mock! {
             &self,
             get_agent_activity_input: GetAgentActivityInput,
         ) -> ExternResult<AgentActivity>;
+        fn get_agent_activity_page(
+            &self,
+            get_agent_activity_page_input: GetAgentActivityPageInput,
+        ) -> ExternResult<AgentActivityPage>;
         fn query(&self, filter: ChainQueryFilter) -> ExternResult<Vec<Record>>;
+        fn query_page(&self, query_page_input: ChainQueryPageInput) -> ExternResult<RecordsPage>;
+        fn dry_run_sys_validation(
+            &self,
+            dry_run_sys_validation_input: (),
+        ) -> ExternResult<Vec<SysValidationIssue>>;
         // Ed25519
         fn sign(&self, sign: Sign) -> ExternResult<Signature>;
         fn sign_ephemeral(&self, sign_ephemeral: SignEphemeral) -> ExternResult<EphemeralSignatures>;
@@ -105,7 +136,14 @@ mockall::mock! {
         fn update(&self, update_input: UpdateInput) -> ExternResult<ActionHash>;
         fn delete(&self, delete_input: DeleteInput) -> ExternResult<ActionHash>;
         fn get(&self, get_input: Vec<GetInput>) -> ExternResult<Vec<Option<Record>>>;
+        fn get_action(
+            &self,
+            hashes: Vec<ActionHash>,
+        ) -> ExternResult<Vec<Option<SignedActionHashed>>>;
+        fn exists(&self, hashes: Vec<AnyDhtHash>) -> ExternResult<Vec<bool>>;
         fn get_details(&self, get_input: Vec<GetInput>) -> ExternResult<Vec<Option<Details>>>;
+        fn write_blob(&self, blob: Bytes) -> ExternResult<BlobManifest>;
+        fn read_blob(&self, manifest: BlobManifest) -> ExternResult<Option<Bytes>>;
         // CounterSigning
         fn accept_countersigning_preflight_request(
             &self,
@@ -114,6 +152,10 @@ mockall::mock! {
         // Info
         fn agent_info(&self, agent_info_input: ()) -> ExternResult<AgentInfo>;
         fn call_info(&self, call_info_input: ()) -> ExternResult<CallInfo>;
+        fn runtime_properties(
+            &self,
+            runtime_properties_input: (),
+        ) -> ExternResult<SerializedBytes>;
         // Link
         fn create_link(&self, create_link_input: CreateLinkInput) -> ExternResult<ActionHash>;
         fn delete_link(&self, delete_link_input: DeleteLinkInput) -> ExternResult<ActionHash>;
@@ -122,10 +164,18 @@ mockall::mock! {
             &self,
             get_links_input: Vec<GetLinksInput>,
         ) -> ExternResult<Vec<LinkDetails>>;
+        fn aggregate_links(
+            &self,
+            aggregate_links_input: Vec<AggregateLinksInput>,
+        ) -> ExternResult<Vec<LinkAggregate>>;
         // P2P
         fn call(&self, call: Vec<Call>) -> ExternResult<Vec<ZomeCallResponse>>;
         fn emit_signal(&self, app_signal: AppSignal) -> ExternResult<()>;
         fn remote_signal(&self, remote_signal: RemoteSignal) -> ExternResult<()>;
+        fn remote_signal_acknowledged(
+            &self,
+            remote_signal: RemoteSignal,
+        ) -> ExternResult<Vec<RemoteSignalReceipt>>;
         // Random
         fn random_bytes(&self, number_of_bytes: u32) -> ExternResult<Bytes>;
         // Time
@@ -273,9 +323,21 @@ impl HdkT for ErrHdk {
     fn get_agent_activity(&self, _: GetAgentActivityInput) -> ExternResult<AgentActivity> {
         Self::err()
     }
+    fn get_agent_activity_page(
+        &self,
+        _: GetAgentActivityPageInput,
+    ) -> ExternResult<AgentActivityPage> {
+        Self::err()
+    }
     fn query(&self, _: ChainQueryFilter) -> ExternResult<Vec<Record>> {
         Self::err()
     }
+    fn query_page(&self, _: ChainQueryPageInput) -> ExternResult<RecordsPage> {
+        Self::err()
+    }
+    fn dry_run_sys_validation(&self, _: ()) -> ExternResult<Vec<SysValidationIssue>> {
+        Self::err()
+    }
     fn sign(&self, _: Sign) -> ExternResult<Signature> {
         Self::err()
     }
@@ -294,9 +356,21 @@ impl HdkT for ErrHdk {
     fn get(&self, _: Vec<GetInput>) -> ExternResult<Vec<Option<Record>>> {
         Self::err()
     }
+    fn get_action(&self, _: Vec<ActionHash>) -> ExternResult<Vec<Option<SignedActionHashed>>> {
+        Self::err()
+    }
+    fn exists(&self, _: Vec<AnyDhtHash>) -> ExternResult<Vec<bool>> {
+        Self::err()
+    }
     fn get_details(&self, _: Vec<GetInput>) -> ExternResult<Vec<Option<Details>>> {
         Self::err()
     }
+    fn write_blob(&self, _: Bytes) -> ExternResult<BlobManifest> {
+        Self::err()
+    }
+    fn read_blob(&self, _: BlobManifest) -> ExternResult<Option<Bytes>> {
+        Self::err()
+    }
     // CounterSigning
     fn accept_countersigning_preflight_request(
         &self,
@@ -310,6 +384,9 @@ impl HdkT for ErrHdk {
     fn call_info(&self, _: ()) -> ExternResult<CallInfo> {
         Self::err()
     }
+    fn runtime_properties(&self, _: ()) -> ExternResult<SerializedBytes> {
+        Self::err()
+    }
     // Link
     fn create_link(&self, _: CreateLinkInput) -> ExternResult<ActionHash> {
         Self::err()
@@ -323,6 +400,9 @@ impl HdkT for ErrHdk {
     fn get_link_details(&self, _: Vec<GetLinksInput>) -> ExternResult<Vec<LinkDetails>> {
         Self::err()
     }
+    fn aggregate_links(&self, _: Vec<AggregateLinksInput>) -> ExternResult<Vec<LinkAggregate>> {
+        Self::err()
+    }
     // P2P
     fn call(&self, _: Vec<Call>) -> ExternResult<Vec<ZomeCallResponse>> {
         Self::err()
@@ -333,6 +413,12 @@ impl HdkT for ErrHdk {
     fn remote_signal(&self, _: RemoteSignal) -> ExternResult<()> {
         Self::err()
     }
+    fn remote_signal_acknowledged(
+        &self,
+        _: RemoteSignal,
+    ) -> ExternResult<Vec<RemoteSignalReceipt>> {
+        Self::err()
+    }
     // Random
     fn random_bytes(&self, _: u32) -> ExternResult<Bytes> {
         Self::err()
@@ -464,9 +550,24 @@ impl HdkT for HostHdk {
             get_agent_activity_input,
         )
     }
+    fn get_agent_activity_page(
+        &self,
+        get_agent_activity_page_input: GetAgentActivityPageInput,
+    ) -> ExternResult<AgentActivityPage> {
+        host_call::<GetAgentActivityPageInput, AgentActivityPage>(
+            __get_agent_activity_page,
+            get_agent_activity_page_input,
+        )
+    }
     fn query(&self, filter: ChainQueryFilter) -> ExternResult<Vec<Record>> {
         host_call::<ChainQueryFilter, Vec<Record>>(__query, filter)
     }
+    fn query_page(&self, query_page_input: ChainQueryPageInput) -> ExternResult<RecordsPage> {
+        host_call::<ChainQueryPageInput, RecordsPage>(__query_page, query_page_input)
+    }
+    fn dry_run_sys_validation(&self, _: ()) -> ExternResult<Vec<SysValidationIssue>> {
+        host_call::<(), Vec<SysValidationIssue>>(__dry_run_sys_validation, ())
+    }
     fn sign(&self, sign: Sign) -> ExternResult<Signature> {
         host_call::<Sign, Signature>(__sign, sign)
     }
@@ -485,9 +586,21 @@ impl HdkT for HostHdk {
     fn get(&self, get_inputs: Vec<GetInput>) -> ExternResult<Vec<Option<Record>>> {
         host_call::<Vec<GetInput>, Vec<Option<Record>>>(__get, get_inputs)
     }
+    fn get_action(&self, hashes: Vec<ActionHash>) -> ExternResult<Vec<Option<SignedActionHashed>>> {
+        host_call::<Vec<ActionHash>, Vec<Option<SignedActionHashed>>>(__get_action, hashes)
+    }
+    fn exists(&self, hashes: Vec<AnyDhtHash>) -> ExternResult<Vec<bool>> {
+        host_call::<Vec<AnyDhtHash>, Vec<bool>>(__exists, hashes)
+    }
     fn get_details(&self, get_inputs: Vec<GetInput>) -> ExternResult<Vec<Option<Details>>> {
         host_call::<Vec<GetInput>, Vec<Option<Details>>>(__get_details, get_inputs)
     }
+    fn write_blob(&self, blob: Bytes) -> ExternResult<BlobManifest> {
+        host_call::<Bytes, BlobManifest>(__write_blob, blob)
+    }
+    fn read_blob(&self, manifest: BlobManifest) -> ExternResult<Option<Bytes>> {
+        host_call::<BlobManifest, Option<Bytes>>(__read_blob, manifest)
+    }
     // CounterSigning
     fn accept_countersigning_preflight_request(
         &self,
@@ -504,6 +617,9 @@ impl HdkT for HostHdk {
     fn call_info(&self, _: ()) -> ExternResult<CallInfo> {
         host_call::<(), CallInfo>(__call_info, ())
     }
+    fn runtime_properties(&self, _: ()) -> ExternResult<SerializedBytes> {
+        host_call::<(), SerializedBytes>(__runtime_properties, ())
+    }
     fn create_link(&self, create_link_input: CreateLinkInput) -> ExternResult<ActionHash> {
         host_call::<CreateLinkInput, ActionHash>(__create_link, create_link_input)
     }
@@ -519,6 +635,15 @@ impl HdkT for HostHdk {
     ) -> ExternResult<Vec<LinkDetails>> {
         host_call::<Vec<GetLinksInput>, Vec<LinkDetails>>(__get_link_details, get_links_input)
     }
+    fn aggregate_links(
+        &self,
+        aggregate_links_input: Vec<AggregateLinksInput>,
+    ) -> ExternResult<Vec<LinkAggregate>> {
+        host_call::<Vec<AggregateLinksInput>, Vec<LinkAggregate>>(
+            __aggregate_links,
+            aggregate_links_input,
+        )
+    }
     fn call(&self, call: Vec<Call>) -> ExternResult<Vec<ZomeCallResponse>> {
         host_call::<Vec<Call>, Vec<ZomeCallResponse>>(__call, call)
     }
@@ -528,6 +653,15 @@ impl HdkT for HostHdk {
     fn remote_signal(&self, remote_signal: RemoteSignal) -> ExternResult<()> {
         host_call::<RemoteSignal, ()>(__remote_signal, remote_signal)
     }
+    fn remote_signal_acknowledged(
+        &self,
+        remote_signal: RemoteSignal,
+    ) -> ExternResult<Vec<RemoteSignalReceipt>> {
+        host_call::<RemoteSignal, Vec<RemoteSignalReceipt>>(
+            __remote_signal_acknowledged,
+            remote_signal,
+        )
+    }
     fn random_bytes(&self, number_of_bytes: u32) -> ExternResult<Bytes> {
         host_call::<u32, Bytes>(__random_bytes, number_of_bytes)
     }
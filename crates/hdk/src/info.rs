@@ -18,3 +18,19 @@ pub fn agent_info() -> ExternResult<AgentInfo> {
 pub fn call_info() -> ExternResult<CallInfo> {
     HDK.with(|h| h.borrow().call_info(()))
 }
+
+/// The current runtime properties of the DNA, from the `runtime_properties`
+/// of its manifest or the last update over the admin API.
+///
+/// Unlike the properties in [ `DnaInfo` ], these don't affect the DNA hash,
+/// so they can change while the app runs, between one call and the next.
+/// Deserialize them into the app's own type:
+///
+/// ```ignore
+/// let settings = Settings::try_from(runtime_properties()?).map_err(|e| wasm_error!(e.into()))?;
+/// ```
+///
+/// As they can change, they aren't available to validation.
+pub fn runtime_properties() -> ExternResult<SerializedBytes> {
+    HDK.with(|h| h.borrow().runtime_properties(()))
+}
@@ -195,3 +195,77 @@ pub fn get_link_details(
         .next()
         .unwrap())
 }
+
+/// Count the links that [ `get_links` ] would return, without returning them.
+///
+/// The host counts the links, so they don't have to be passed into the zome and deserialized as
+/// they are for `get_links(..)?.len()`. The host still fetches every link from the authorities of
+/// the base to count them, so this doesn't save any network traffic.
+/// Links are filtered by type and tag prefix exactly as [ `get_links` ] filters them.
+pub fn count_links(
+    base: impl Into<AnyLinkableHash>,
+    link_type: impl LinkTypeFilterExt,
+    link_tag: Option<LinkTag>,
+) -> ExternResult<usize> {
+    match aggregate_links(base, link_type, link_tag, LinkAggregation::Count)? {
+        LinkAggregate::Count(count) => Ok(count),
+        aggregate => Err(unexpected_aggregate(aggregate)),
+    }
+}
+
+/// Check whether [ `get_links` ] would return any links, without returning them.
+///
+/// Links are filtered by type and tag prefix exactly as [ `get_links` ] filters them.
+pub fn links_exist(
+    base: impl Into<AnyLinkableHash>,
+    link_type: impl LinkTypeFilterExt,
+    link_tag: Option<LinkTag>,
+) -> ExternResult<bool> {
+    match aggregate_links(base, link_type, link_tag, LinkAggregation::Exists)? {
+        LinkAggregate::Exists(exists) => Ok(exists),
+        aggregate => Err(unexpected_aggregate(aggregate)),
+    }
+}
+
+/// Get the distinct tags of the links that [ `get_links` ] would return, in ascending order.
+///
+/// This suits links used as an index, such as tags or categories on a base, where the zome only
+/// needs the set of tags and not every link that carries them.
+pub fn get_link_tags(
+    base: impl Into<AnyLinkableHash>,
+    link_type: impl LinkTypeFilterExt,
+    link_tag: Option<LinkTag>,
+) -> ExternResult<Vec<LinkTag>> {
+    match aggregate_links(base, link_type, link_tag, LinkAggregation::DistinctTags)? {
+        LinkAggregate::DistinctTags(tags) => Ok(tags),
+        aggregate => Err(unexpected_aggregate(aggregate)),
+    }
+}
+
+fn aggregate_links(
+    base: impl Into<AnyLinkableHash>,
+    link_type: impl LinkTypeFilterExt,
+    link_tag: Option<LinkTag>,
+    aggregation: LinkAggregation,
+) -> ExternResult<LinkAggregate> {
+    let link_type = link_type.try_into_filter()?;
+    Ok(HDK
+        .with(|h| {
+            h.borrow().aggregate_links(vec![AggregateLinksInput::new(
+                base.into(),
+                link_type,
+                link_tag,
+                aggregation,
+            )])
+        })?
+        .into_iter()
+        .next()
+        .unwrap())
+}
+
+fn unexpected_aggregate(aggregate: LinkAggregate) -> WasmError {
+    wasm_error!(WasmErrorInner::Guest(format!(
+        "The host returned an aggregate of the wrong kind: {:?}",
+        aggregate
+    )))
+}
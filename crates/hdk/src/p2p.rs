@@ -141,6 +141,55 @@ where
         h.borrow().remote_signal(RemoteSignal {
             signal: ExternIO::encode(input).map_err(|e| wasm_error!(e.into()))?,
             agents,
+            delivery: RemoteSignalDelivery::BestEffort,
+        })
+    })
+}
+
+/// ## Batched Remote Signal
+/// Send a signal to a list of other agents, like [ `remote_signal` ], but
+/// handed to the network in one request which is never dropped for a busy
+/// network.
+///
+/// This blocks until the network has taken the signal for every agent,
+/// and returns an error if it can't. It still doesn't wait for the agents
+/// to receive it, see [ `remote_signal_acknowledged` ] for that.
+pub fn remote_signal_batched<I>(input: I, agents: Vec<AgentPubKey>) -> ExternResult<()>
+where
+    I: serde::Serialize + std::fmt::Debug,
+{
+    HDK.with(|h| {
+        h.borrow().remote_signal(RemoteSignal {
+            signal: ExternIO::encode(input).map_err(|e| wasm_error!(e.into()))?,
+            agents,
+            delivery: RemoteSignalDelivery::Batched,
+        })
+    })
+}
+
+/// ## Acknowledged Remote Signal
+/// Send a signal to a list of other agents, like [ `remote_signal` ], and
+/// wait to hear what became of it for each of them.
+///
+/// A [ `RemoteSignalReceipt` ] is returned for every agent, in the order
+/// they were given, saying whether their `recv_remote_signal` received the
+/// signal, they haven't granted the capability to call it, or the signal
+/// failed to reach them.
+///
+/// This blocks until every agent has answered or the call times out, so
+/// prefer [ `remote_signal` ] unless the zome needs to know.
+pub fn remote_signal_acknowledged<I>(
+    input: I,
+    agents: Vec<AgentPubKey>,
+) -> ExternResult<Vec<RemoteSignalReceipt>>
+where
+    I: serde::Serialize + std::fmt::Debug,
+{
+    HDK.with(|h| {
+        h.borrow().remote_signal_acknowledged(RemoteSignal {
+            signal: ExternIO::encode(input).map_err(|e| wasm_error!(e.into()))?,
+            agents,
+            delivery: RemoteSignalDelivery::BestEffort,
         })
     })
 }
@@ -3,9 +3,12 @@ pub use crate::capability::create_cap_grant;
 pub use crate::capability::delete_cap_grant;
 pub use crate::capability::generate_cap_secret;
 pub use crate::capability::update_cap_grant;
+pub use crate::chain::dry_run_sys_validation;
 pub use crate::chain::get_agent_activity;
+pub use crate::chain::get_agent_activity_page;
 pub use crate::chain::must_get_agent_activity;
 pub use crate::chain::query;
+pub use crate::chain::query_page;
 pub use crate::countersigning::accept_countersigning_preflight_request;
 pub use crate::countersigning::session_times_from_millis;
 pub use crate::ed25519::sign;
@@ -18,13 +21,17 @@ pub use crate::entry::create;
 pub use crate::entry::create_entry;
 pub use crate::entry::delete;
 pub use crate::entry::delete_entry;
+pub use crate::entry::exists;
 pub use crate::entry::get;
+pub use crate::entry::get_action;
 pub use crate::entry::get_details;
 pub use crate::entry::must_get_action;
 pub use crate::entry::must_get_entry;
 pub use crate::entry::must_get_valid_record;
+pub use crate::entry::read_blob;
 pub use crate::entry::update;
 pub use crate::entry::update_entry;
+pub use crate::entry::write_blob;
 pub use crate::entry_def;
 pub use crate::entry_defs;
 pub use crate::hash::*;
@@ -39,11 +46,15 @@ pub use crate::hdk::*;
 pub use crate::info::agent_info;
 pub use crate::info::call_info;
 pub use crate::info::dna_info;
+pub use crate::info::runtime_properties;
 pub use crate::info::zome_info;
+pub use crate::link::count_links;
 pub use crate::link::create_link;
 pub use crate::link::delete_link;
 pub use crate::link::get_link_details;
+pub use crate::link::get_link_tags;
 pub use crate::link::get_links;
+pub use crate::link::links_exist;
 pub use crate::link::LinkTypeFilterExt;
 pub use crate::map_extern;
 pub use crate::map_extern::ExternResult;
@@ -51,6 +62,8 @@ pub use crate::p2p::call;
 pub use crate::p2p::call_remote;
 pub use crate::p2p::emit_signal;
 pub use crate::p2p::remote_signal;
+pub use crate::p2p::remote_signal_acknowledged;
+pub use crate::p2p::remote_signal_batched;
 pub use crate::random::*;
 pub use crate::time::schedule;
 pub use crate::time::sleep;
@@ -118,6 +131,7 @@ macro_rules! holochain_externs {
             __sign_ephemeral,
             __zome_info,
             __call_info,
+            __runtime_properties,
             __dna_info,
             __random_bytes,
             __sys_time,
@@ -126,20 +140,29 @@ macro_rules! holochain_externs {
             __capability_grants,
             __capability_info,
             __get,
+            __get_action,
+            __exists,
             __get_details,
             __get_links,
             __get_link_details,
+            __aggregate_links,
             __get_agent_activity,
+            __get_agent_activity_page,
             __must_get_entry,
             __must_get_valid_record,
             __must_get_action,
+            __read_blob,
+            __write_blob,
             __accept_countersigning_preflight_request,
             __query,
+            __query_page,
+            __dry_run_sys_validation,
             __call_remote,
             __call,
             __create,
             __emit_signal,
             __remote_signal,
+            __remote_signal_acknowledged,
             __create_link,
             __delete_link,
             __update,
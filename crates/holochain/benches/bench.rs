@@ -13,6 +13,7 @@ use holochain_wasm_test_utils::TestZomes;
 use once_cell::sync::Lazy;
 use std::sync::Mutex;
 
+mod entry_hashes;
 mod websocket;
 
 static TOKIO_RUNTIME: Lazy<Mutex<tokio::runtime::Runtime>> = Lazy::new(|| {
@@ -98,4 +99,4 @@ pub fn wasm_call_n(c: &mut Criterion) {
 
 criterion_group!(wasm, wasm_call_n);
 
-criterion_main!(wasm, websocket::websocket);
+criterion_main!(wasm, entry_hashes::entry_hashes, websocket::websocket);
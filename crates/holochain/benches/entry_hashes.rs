@@ -0,0 +1,77 @@
+use ::fixt::prelude::*;
+use criterion::criterion_group;
+use criterion::BatchSize;
+use criterion::BenchmarkId;
+use criterion::Criterion;
+use criterion::Throughput;
+use holochain::core::check_op_entry_hashes;
+use holochain_types::prelude::*;
+use holochain_zome_types::fixt::CreateFixturator;
+use holochain_zome_types::fixt::SignatureFixturator;
+
+/// How many ops are checked in each batch.
+const BATCH: usize = 8;
+
+/// A batch of store entry ops, each with an entry of `size` bytes.
+fn ops(size: usize) -> Vec<(DhtOpHash, DhtOp)> {
+    (0..BATCH)
+        .map(|i| {
+            let entry = Entry::App(AppEntryBytes(SerializedBytes::from(UnsafeBytes::from(
+                vec![i as u8; size],
+            ))));
+            let mut create = fixt!(Create);
+            create.entry_hash = EntryHash::with_data_sync(&entry);
+            let op = DhtOp::StoreEntry(
+                fixt!(Signature),
+                NewEntryAction::Create(create),
+                Box::new(entry),
+            );
+            (DhtOpHash::with_data_sync(&op), op)
+        })
+        .collect()
+}
+
+/// Compare hashing the entries of a batch one at a time with hashing them
+/// in parallel, as sys validation and incoming ops now do.
+pub fn check_entry_hashes(c: &mut Criterion) {
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let mut group = c.benchmark_group("check_entry_hashes");
+    group.sample_size(10);
+
+    for size in [
+        1_000_000,  // 1 mb
+        4_000_000,  // 4 mb
+        16_000_000, // the largest entry
+    ] {
+        let ops = ops(size);
+        group.throughput(Throughput::Bytes((size * BATCH) as _));
+
+        group.bench_function(BenchmarkId::new("serial", size), |b| {
+            b.iter(|| {
+                for (_, op) in &ops {
+                    let action = op.action();
+                    let (entry_hash, _) = action.entry_data().unwrap();
+                    assert_eq!(*entry_hash, EntryHash::with_data_sync(op.entry().unwrap()));
+                }
+            });
+        });
+
+        group.bench_function(BenchmarkId::new("batch", size), |b| {
+            b.iter_batched(
+                || ops.clone(),
+                |ops| {
+                    let (_, wrong) = runtime.block_on(check_op_entry_hashes(ops)).unwrap();
+                    assert!(wrong.is_empty());
+                },
+                BatchSize::LargeInput,
+            );
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(entry_hashes, check_entry_hashes);
@@ -1,11 +1,16 @@
+use holochain::conductor::conductor::SHUTDOWN_DRAIN_TIMEOUT;
 use holochain::conductor::config::ConductorConfig;
 use holochain::conductor::interactive;
+use holochain::conductor::interface::socket_activation;
+use holochain::conductor::locked;
+use holochain::conductor::log_filter;
 use holochain::conductor::manager::handle_shutdown;
 use holochain::conductor::paths::ConfigFilePath;
 use holochain::conductor::Conductor;
 use holochain::conductor::ConductorHandle;
 use holochain_conductor_api::conductor::ConductorConfigError;
 use holochain_conductor_api::config::conductor::KeystoreConfig;
+use holochain_util::pw::PassphraseSource;
 use holochain_util::tokio_helper;
 use observability::Output;
 #[cfg(unix)]
@@ -14,11 +19,32 @@ use std::path::PathBuf;
 use structopt::StructOpt;
 use tracing::*;
 
-const ERROR_CODE: i32 = 42;
+mod telemetry;
+
+/// The exit code when the config or options are wrong, following
+/// `EX_CONFIG` from `sysexits.h`. Restarting won't help, so service managers
+/// can be told not to, e.g. with systemd's `RestartPreventExitStatus=78`.
+const EXIT_CONFIG: i32 = 78;
+/// The exit code when something the conductor needs can't be had, such as
+/// the keystore passphrase or the database directory, following
+/// `EX_UNAVAILABLE`.
+const EXIT_UNAVAILABLE: i32 = 69;
+/// The exit code when the conductor fails to start, or stops with an error,
+/// following `EX_SOFTWARE`.
+const EXIT_SOFTWARE: i32 = 70;
+const EXIT_CODES_HELP: &str = "EXIT CODES:
+    0     The conductor shut down cleanly
+    69    The keystore passphrase or database directory couldn't be had
+    70    The conductor failed to start, or stopped with an error
+    78    The config or options are wrong";
 const MAGIC_CONDUCTOR_READY_STRING: &str = "Conductor ready.";
 
 #[derive(Debug, StructOpt)]
-#[structopt(name = "holochain", about = "The Holochain Conductor.")]
+#[structopt(
+    name = "holochain",
+    about = "The Holochain Conductor.",
+    after_help = EXIT_CODES_HELP
+)]
 struct Opt {
     #[structopt(
         long,
@@ -47,6 +73,39 @@ struct Opt {
     #[structopt(short = "p", long)]
     pub piped: bool,
 
+    /// Read the passphrase from this environment variable, which is
+    /// removed once read so it isn't passed on to child processes.
+    #[structopt(
+        long,
+        value_name = "VAR",
+        conflicts_with_all = &["piped", "passphrase_fd", "passphrase_command", "locked"]
+    )]
+    pub passphrase_env: Option<String>,
+
+    /// Read the passphrase from this file descriptor, such as one passed
+    /// in by a service manager.
+    #[structopt(
+        long,
+        value_name = "FD",
+        conflicts_with_all = &["piped", "passphrase_command", "locked"]
+    )]
+    pub passphrase_fd: Option<i32>,
+
+    /// Run this shell command and read the passphrase from the first line
+    /// of its output, e.g. `systemd-ask-password` or a secrets manager.
+    #[structopt(
+        long,
+        value_name = "COMMAND",
+        conflicts_with_all = &["piped", "locked"]
+    )]
+    pub passphrase_command: Option<String>,
+
+    /// Start without the passphrase. The admin interfaces are opened, but
+    /// only accept an `unlock_conductor` request with the passphrase, and
+    /// no cells are started until then.
+    #[structopt(long, conflicts_with = "piped")]
+    pub locked: bool,
+
     #[structopt(
         short = "i",
         long,
@@ -80,12 +139,29 @@ async fn async_main() {
         return;
     }
 
-    observability::init_fmt(opt.structured.clone()).expect("Failed to start contextual logging");
+    // The config is loaded first, as it says where to export spans to.
+    let (config, config_path) = load_config_from_opt(&opt);
+
+    match &config.open_telemetry {
+        Some(open_telemetry) => telemetry::init(
+            opt.structured.clone(),
+            open_telemetry,
+            config.log_filter.as_deref(),
+        )
+        .expect("Failed to start OpenTelemetry export"),
+        None => log_filter::init_fmt(opt.structured.clone(), config.log_filter.as_deref())
+            .expect("Failed to start contextual logging"),
+    }
     debug!("observability initialized");
+    debug!("config_path: {}", config_path);
+
+    // Take any sockets passed in by the service manager before the
+    // interfaces are bound, so they can use them instead.
+    socket_activation::receive_inherited_listeners();
 
     kitsune_p2p_types::metrics::init_sys_info_poll();
 
-    let conductor = conductor_handle_from_config_path(&opt).await;
+    let conductor = conductor_handle_from_config(&opt, config, config_path).await;
 
     info!("Conductor successfully initialized.");
 
@@ -100,6 +176,11 @@ async fn async_main() {
     #[cfg(unix)]
     let _ = notify(true, &[NotifyState::Ready]);
 
+    #[cfg(unix)]
+    spawn_reload_config_on_sighup(conductor.clone());
+
+    spawn_graceful_shutdown_on_signal(conductor.clone());
+
     // Await on the main JoinHandle, keeping the process alive until all
     // Conductor activity has ceased
     let result = conductor
@@ -107,40 +188,86 @@ async fn async_main() {
         .expect("The shutdown handle has already been taken.")
         .await;
 
+    // However the managed tasks came to an end, make sure everything which
+    // was committed is flushed to disk and the network is closed cleanly.
+    if let Err(e) = conductor.close_storage_and_network().await {
+        error!(?e, "Failed to cleanly close databases and network");
+    }
+    telemetry::shutdown();
+
+    if let Ok(Err(e)) = &result {
+        error!(?e, "Conductor stopped with an error");
+        std::process::exit(EXIT_SOFTWARE);
+    }
     handle_shutdown(result);
+}
 
-    // TODO: on SIGINT/SIGKILL, kill the conductor:
-    // conductor.kill().await
+/// Start a graceful shutdown when the process receives SIGTERM or SIGINT.
+fn spawn_graceful_shutdown_on_signal(conductor: ConductorHandle) {
+    tokio::spawn(async move {
+        #[cfg(unix)]
+        {
+            use tokio::signal::unix::{signal, SignalKind};
+            let mut terminate = match signal(SignalKind::terminate()) {
+                Ok(terminate) => terminate,
+                Err(e) => {
+                    error!(?e, "Could not listen for SIGTERM");
+                    return;
+                }
+            };
+            tokio::select! {
+                _ = terminate.recv() => info!("Received SIGTERM, shutting down"),
+                _ = tokio::signal::ctrl_c() => info!("Received SIGINT, shutting down"),
+            }
+        }
+        #[cfg(not(unix))]
+        if tokio::signal::ctrl_c().await.is_err() {
+            error!("Could not listen for ctrl-c");
+            return;
+        }
+        conductor.graceful_shutdown(SHUTDOWN_DRAIN_TIMEOUT).await;
+    });
 }
 
-async fn conductor_handle_from_config_path(opt: &Opt) -> ConductorHandle {
+fn load_config_from_opt(opt: &Opt) -> (ConductorConfig, ConfigFilePath) {
     let config_path = opt.config_path.clone();
     let config_path_default = config_path.is_none();
     let config_path: ConfigFilePath = config_path.map(Into::into).unwrap_or_default();
-    debug!("config_path: {}", config_path);
 
     let config: ConductorConfig = if opt.interactive {
         // Load config, offer to create default config if missing
-        interactive::load_config_or_prompt_for_default(config_path)
+        interactive::load_config_or_prompt_for_default(config_path.clone())
             .expect("Could not load conductor config")
             .unwrap_or_else(|| {
                 println!("Cannot continue without configuration");
-                std::process::exit(ERROR_CODE);
+                std::process::exit(EXIT_CONFIG);
             })
     } else {
         load_config(&config_path, config_path_default)
     };
+    (config, config_path)
+}
 
+async fn conductor_handle_from_config(
+    opt: &Opt,
+    config: ConductorConfig,
+    config_path: ConfigFilePath,
+) -> ConductorHandle {
     // read the passphrase to prepare for usage
     let passphrase = match &config.keystore {
         KeystoreConfig::DangerTestKeystoreLegacyDeprecated => None,
         KeystoreConfig::LairServerLegacyDeprecated { .. } => None,
+        // A locked conductor is given the passphrase once it's running.
+        _ if opt.locked => None,
         _ => {
-            if opt.piped {
-                holochain_util::pw::pw_set_piped(true);
+            holochain_util::pw::pw_set_source(passphrase_source(opt));
+            match holochain_util::pw::pw_get() {
+                Ok(passphrase) => Some(passphrase),
+                Err(e) => {
+                    println!("Couldn't read the keystore passphrase: {}", e);
+                    std::process::exit(EXIT_UNAVAILABLE);
+                }
             }
-
-            Some(holochain_util::pw::pw_get().unwrap())
         }
     };
 
@@ -157,18 +284,78 @@ async fn conductor_handle_from_config_path(opt: &Opt) -> ConductorHandle {
             Ok(()) => println!("Created database at {}.", env_path.display()),
             Err(e) => {
                 println!("Couldn't create database: {}", e);
-                std::process::exit(ERROR_CODE);
+                std::process::exit(EXIT_UNAVAILABLE);
+            }
+        }
+    }
+
+    let mut builder = Conductor::builder();
+    if opt.locked {
+        println!("Conductor locked. Unlock it over an admin interface to start it.");
+        match locked::wait_for_unlock(&config).await {
+            Ok(keystore) => builder = builder.with_keystore(keystore),
+            Err(e) => {
+                println!("Couldn't start the conductor locked: {}", e);
+                std::process::exit(EXIT_CONFIG);
             }
         }
     }
 
     // Initialize the Conductor
-    Conductor::builder()
+    match builder
         .config(config)
+        .config_path(Some(config_path.into()))
         .passphrase(passphrase)
         .build()
         .await
-        .expect("Could not initialize Conductor from configuration")
+    {
+        Ok(conductor) => conductor,
+        Err(e) => {
+            error!(?e, "Could not initialize Conductor from configuration");
+            println!("Couldn't start the conductor: {}", e);
+            std::process::exit(EXIT_SOFTWARE);
+        }
+    }
+}
+
+/// Where the options say to read the keystore passphrase from.
+fn passphrase_source(opt: &Opt) -> PassphraseSource {
+    if let Some(var) = &opt.passphrase_env {
+        PassphraseSource::Env(var.clone())
+    } else if let Some(fd) = opt.passphrase_fd {
+        PassphraseSource::Fd(fd)
+    } else if let Some(command) = &opt.passphrase_command {
+        PassphraseSource::Command(command.clone())
+    } else if opt.piped {
+        PassphraseSource::Piped
+    } else {
+        PassphraseSource::Interactive
+    }
+}
+
+/// Re-read the config file whenever the process receives SIGHUP,
+/// applying whatever settings can be changed without a restart.
+#[cfg(unix)]
+fn spawn_reload_config_on_sighup(conductor: ConductorHandle) {
+    use tokio::signal::unix::{signal, SignalKind};
+    tokio::spawn(async move {
+        let mut hangups = match signal(SignalKind::hangup()) {
+            Ok(hangups) => hangups,
+            Err(e) => {
+                error!(
+                    ?e,
+                    "Could not listen for SIGHUP, config reloading is disabled"
+                );
+                return;
+            }
+        };
+        while hangups.recv().await.is_some() {
+            match conductor.clone().reload_config(None).await {
+                Ok(report) => info!(?report, "Reloaded conductor config on SIGHUP"),
+                Err(e) => error!(?e, "Failed to reload conductor config"),
+            }
+        }
+    });
 }
 
 /// Load config, throw friendly error on failure
@@ -176,11 +363,11 @@ fn load_config(config_path: &ConfigFilePath, config_path_default: bool) -> Condu
     match ConductorConfig::load_yaml(config_path.as_ref()) {
         Err(ConductorConfigError::ConfigMissing(_)) => {
             display_friendly_missing_config_message(config_path, config_path_default);
-            std::process::exit(ERROR_CODE);
+            std::process::exit(EXIT_CONFIG);
         }
         Err(ConductorConfigError::SerializationError(err)) => {
             display_friendly_malformed_config_message(config_path, err);
-            std::process::exit(ERROR_CODE);
+            std::process::exit(EXIT_CONFIG);
         }
         result => result.expect("Could not load conductor config"),
     }
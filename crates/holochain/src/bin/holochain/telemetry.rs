@@ -0,0 +1,58 @@
+//! Logging which also exports spans to an OpenTelemetry collector.
+
+use holochain::conductor::log_filter;
+use holochain_conductor_api::config::conductor::OpenTelemetryConfig;
+use observability::Output;
+use opentelemetry::sdk::trace::Sampler;
+use opentelemetry::sdk::Resource;
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::WithExportConfig;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+
+/// Start logging as [`observability::init_fmt`] does, filtered by
+/// `log_filter` or else `RUST_LOG`, and export the recorded spans to the
+/// collector.
+pub fn init(
+    output: Output,
+    config: &OpenTelemetryConfig,
+    directives: Option<&str>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Follow the sampling decision of remote parents, so a trace started on
+    // another node is recorded on every node or none.
+    let sampler = Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(config.sample_ratio())));
+    let tracer = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(
+            opentelemetry_otlp::new_exporter()
+                .tonic()
+                .with_endpoint(config.endpoint.clone()),
+        )
+        .with_trace_config(
+            opentelemetry::sdk::trace::config()
+                .with_sampler(sampler)
+                .with_resource(Resource::new(vec![KeyValue::new(
+                    "service.name",
+                    config.service_name().to_string(),
+                )])),
+        )
+        .install_batch(opentelemetry::runtime::Tokio)?;
+
+    let log = match output {
+        Output::None | Output::Json => None,
+        _ => Some(tracing_subscriber::fmt::layer()),
+    };
+    let json = matches!(output, Output::Json).then(|| tracing_subscriber::fmt::layer().json());
+    tracing_subscriber::registry()
+        .with(log_filter::reloadable(log_filter::env_filter(directives)?))
+        .with(log)
+        .with(json)
+        .with(tracing_opentelemetry::layer().with_tracer(tracer))
+        .try_init()?;
+    Ok(())
+}
+
+/// Export the spans which haven't been sent yet.
+pub fn shutdown() {
+    opentelemetry::global::shutdown_tracer_provider();
+}
@@ -14,12 +14,15 @@
 // TODO: clean up allow(missing_docs) once parent is fully documented
 
 pub mod api;
+mod audit_log;
 mod cell;
 #[allow(clippy::module_inception)]
 #[allow(missing_docs)]
 pub mod conductor;
 #[allow(missing_docs)]
 pub mod config;
+mod db_encryption;
+mod dht_shard;
 pub mod entry_def_store;
 #[allow(missing_docs)]
 pub mod error;
@@ -27,17 +30,23 @@ pub mod handle;
 pub mod interactive;
 pub mod interface;
 pub mod kitsune_host_impl;
+pub mod locked;
+pub mod log_filter;
 pub mod manager;
 pub mod p2p_agent_store;
 pub mod paths;
+mod quota;
+mod relay;
 #[allow(missing_docs)]
 pub mod ribosome_store;
+mod signed_zome_call;
 pub mod space;
 pub mod state;
+mod zome_fn_access;
 
 pub use cell::error::CellError;
 pub use cell::Cell;
 pub use conductor::Conductor;
 pub use conductor::ConductorBuilder;
-pub use conductor::{full_integration_dump, integration_dump};
+pub use conductor::{cell_op_counts, full_integration_dump, integration_dump};
 pub use handle::ConductorHandle;
@@ -9,11 +9,16 @@ use crate::conductor::interface::SignalBroadcaster;
 use crate::conductor::ConductorHandle;
 use crate::core::ribosome::guest_callback::post_commit::PostCommitArgs;
 use crate::core::ribosome::real_ribosome::RealRibosome;
+use crate::core::ribosome::zome_call_limits::ZomeCallCancel;
+use crate::core::SysValidationOutcome;
+use crate::core::workflow::sys_validation_workflow::sys_validate_record;
 use crate::core::workflow::ZomeCallResult;
 use async_trait::async_trait;
 use holo_hash::DnaHash;
 use holochain_conductor_api::ZomeCall;
 use holochain_keystore::MetaLairClient;
+use holochain_p2p::HolochainP2pDna;
+use holochain_state::host_fn_workspace::HostFnWorkspace;
 use holochain_state::host_fn_workspace::SourceChainWorkspace;
 use holochain_types::prelude::*;
 use tokio::sync::mpsc::error::SendError;
@@ -112,6 +117,17 @@ impl CellConductorApiT for CellConductorApi {
         Arc::new(self)
     }
 
+    async fn check_zome_fn_access(
+        &self,
+        call: &ZomeCall,
+        origin: ZomeCallOrigin,
+    ) -> ConductorApiResult<()> {
+        Ok(self
+            .conductor_handle
+            .check_zome_fn_access(call, origin)
+            .await?)
+    }
+
     async fn post_commit_permit(&self) -> Result<OwnedPermit<PostCommitArgs>, SendError<()>> {
         self.conductor_handle.post_commit_permit().await
     }
@@ -161,6 +177,14 @@ pub trait CellConductorApiT: Send + Sync + Sized {
     /// Turn this into a call zome handle
     fn into_call_zome_handle(self) -> CellConductorReadHandle;
 
+    /// Refuse a zome call from an origin which the app manifests of the
+    /// called cell don't allow it from.
+    async fn check_zome_fn_access(
+        &self,
+        call: &ZomeCall,
+        origin: ZomeCallOrigin,
+    ) -> ConductorApiResult<()>;
+
     /// Get an OwnedPermit to the post commit task.
     async fn post_commit_permit(&self) -> Result<OwnedPermit<PostCommitArgs>, SendError<()>>;
 }
@@ -172,11 +196,12 @@ pub trait CellConductorReadHandleT: Send + Sync {
     /// Get this cell id
     fn cell_id(&self) -> &CellId;
 
-    /// Invoke a zome function on a Cell
+    /// Invoke a zome function on a Cell, which is cancelled with `cancel`
     async fn call_zome(
         &self,
         call: ZomeCall,
         workspace_lock: SourceChainWorkspace,
+        cancel: ZomeCallCancel,
     ) -> ConductorApiResult<ZomeCallResult>;
 
     /// Get a zome from this cell's Dna
@@ -192,6 +217,15 @@ pub trait CellConductorReadHandleT: Send + Sync {
         cell_id: &CellId,
         role_id: &AppRoleId,
     ) -> ConductorResult<Option<CellId>>;
+
+    /// Sys validate a record authored in a zome call, as it will be
+    /// when the call's records are committed.
+    async fn sys_validate_record(
+        &self,
+        record: &Record,
+        workspace: &HostFnWorkspace,
+        network: HolochainP2pDna,
+    ) -> SysValidationOutcome<()>;
 }
 
 #[async_trait]
@@ -204,13 +238,19 @@ impl CellConductorReadHandleT for CellConductorApi {
         &self,
         call: ZomeCall,
         workspace_lock: SourceChainWorkspace,
+        cancel: ZomeCallCancel,
     ) -> ConductorApiResult<ZomeCallResult> {
         if self.cell_id == call.cell_id {
             self.conductor_handle
-                .call_zome_with_workspace(call, workspace_lock)
+                .call_zome_with_workspace(call, workspace_lock, cancel)
                 .await
         } else {
-            self.conductor_handle.call_zome(call).await
+            self.conductor_handle
+                .check_zome_fn_access(&call, ZomeCallOrigin::Cell(self.cell_id.clone()))
+                .await?;
+            self.conductor_handle
+                .call_zome_cancellable(call, cancel)
+                .await
         }
     }
 
@@ -231,4 +271,13 @@ impl CellConductorReadHandleT for CellConductorApi {
             .find_cell_with_role_alongside_cell(cell_id, role_id)
             .await
     }
+
+    async fn sys_validate_record(
+        &self,
+        record: &Record,
+        workspace: &HostFnWorkspace,
+        network: HolochainP2pDna,
+    ) -> SysValidationOutcome<()> {
+        sys_validate_record(record, workspace, network, self.conductor_handle.as_ref()).await
+    }
 }
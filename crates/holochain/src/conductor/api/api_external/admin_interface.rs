@@ -5,10 +5,13 @@ use crate::conductor::api::error::ConductorApiError;
 use crate::conductor::api::error::ConductorApiResult;
 use crate::conductor::api::error::SerializationError;
 use crate::conductor::conductor::CellStatus;
+use crate::conductor::conductor::SHUTDOWN_DRAIN_TIMEOUT;
 use crate::conductor::error::ConductorError;
 use crate::conductor::interface::error::InterfaceError;
 use crate::conductor::interface::error::InterfaceResult;
+use crate::conductor::interface::InterfaceDriver;
 use crate::conductor::ConductorHandle;
+use holochain_conductor_api::audit_log::AuditOutcome;
 use holochain_serialized_bytes::prelude::*;
 use holochain_types::dna::DnaBundle;
 use holochain_types::prelude::*;
@@ -130,6 +133,15 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                 }
                 Ok(AdminResponse::DnaRegistered(hash))
             }
+            FetchDna {
+                dna_hash,
+                agent_key,
+            } => {
+                self.conductor_handle
+                    .fetch_dna(dna_hash.clone(), agent_key)
+                    .await?;
+                Ok(AdminResponse::DnaRegistered(dna_hash))
+            }
             UpdateCoordinators(payload) => {
                 let UpdateCoordinatorsPayload { dna_hash, source } = *payload;
                 let (coordinator_zomes, wasms) = match source {
@@ -147,6 +159,16 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
 
                 Ok(AdminResponse::CoordinatorsUpdated)
             }
+            UpdateRuntimeProperties(payload) => {
+                let UpdateRuntimePropertiesPayload {
+                    dna_hash,
+                    properties,
+                } = *payload;
+                self.conductor_handle
+                    .update_runtime_properties(&dna_hash, properties)
+                    .await?;
+                Ok(AdminResponse::RuntimePropertiesUpdated)
+            }
             CreateCloneCell(payload) => {
                 let cell_id = payload.cell_id();
                 self.conductor_handle
@@ -217,10 +239,25 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                     InstalledAppInfo::from_installed_app(&app),
                 ))
             }
-            UninstallApp { installed_app_id } => {
+            InstallAppBundleForAgents(payload) => {
+                let apps = self
+                    .conductor_handle
+                    .clone()
+                    .install_app_bundle_for_agents(*payload)
+                    .await?
+                    .into_iter()
+                    .map(|app| InstalledAppInfo::from_installed_app(&app.into()))
+                    .collect();
+                Ok(AdminResponse::AppBundleInstalledForAgents(apps))
+            }
+            UninstallApp {
+                installed_app_id,
+                data,
+                export_path,
+            } => {
                 self.conductor_handle
                     .clone()
-                    .uninstall_app(&installed_app_id)
+                    .uninstall_app(&installed_app_id, data, export_path)
                     .await?;
                 Ok(AdminResponse::AppUninstalled)
             }
@@ -251,8 +288,21 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                 let app_ids = self.conductor_handle.list_running_apps().await?;
                 Ok(AdminResponse::EnabledAppsListed(app_ids))
             }
-            ListApps { status_filter } => {
-                let apps = self.conductor_handle.list_apps(status_filter).await?;
+            ListApps {
+                status_filter,
+                agent_pub_key,
+            } => {
+                let apps = self
+                    .conductor_handle
+                    .list_apps(status_filter)
+                    .await?
+                    .into_iter()
+                    .filter(|app| {
+                        agent_pub_key
+                            .as_ref()
+                            .map_or(true, |agent| app.agent_pub_key == *agent)
+                    })
+                    .collect();
                 Ok(AdminResponse::AppsListed(apps))
             }
             EnableApp { installed_app_id } => {
@@ -299,19 +349,55 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                     .await?;
                 Ok(AdminResponse::AppStarted(app.status().is_running()))
             }
-            AttachAppInterface { port } => {
-                let port = port.unwrap_or(0);
+            SetAppQuota {
+                installed_app_id,
+                quota,
+            } => {
+                self.conductor_handle
+                    .set_app_quota(installed_app_id, quota)
+                    .await?;
+                Ok(AdminResponse::AppQuotaSet)
+            }
+            AttachAppInterface {
+                port,
+                bind_address,
+                allowed_origins,
+                require_signed_zome_calls,
+            } => {
+                let driver = InterfaceDriver::Websocket {
+                    port: port.unwrap_or(0),
+                    bind_address,
+                    allowed_origins,
+                    heartbeat: None,
+                    require_signed_zome_calls,
+                };
                 let port = self
                     .conductor_handle
                     .clone()
-                    .add_app_interface(port)
+                    .add_app_interface(driver)
                     .await?;
                 Ok(AdminResponse::AppInterfaceAttached { port })
             }
+            AttachAppLocalSocket { path, mode } => {
+                let driver = InterfaceDriver::LocalSocket { path, mode };
+                self.conductor_handle
+                    .clone()
+                    .add_app_interface(driver)
+                    .await?;
+                Ok(AdminResponse::AppLocalSocketAttached)
+            }
             ListAppInterfaces => {
                 let interfaces = self.conductor_handle.list_app_interfaces().await?;
                 Ok(AdminResponse::AppInterfacesListed(interfaces))
             }
+            ListAppInterfaceSettings => {
+                let interfaces = self.conductor_handle.list_app_interface_settings().await?;
+                Ok(AdminResponse::AppInterfaceSettingsListed(interfaces))
+            }
+            DetachAppInterface { port } => {
+                self.conductor_handle.remove_app_interface(port).await?;
+                Ok(AdminResponse::AppInterfaceDetached)
+            }
             DumpState { cell_id } => {
                 let state = self.conductor_handle.dump_cell_state(&cell_id).await?;
                 Ok(AdminResponse::StateDumped(state))
@@ -330,6 +416,20 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                 let dump = self.conductor_handle.dump_network_metrics(dna_hash).await?;
                 Ok(AdminResponse::NetworkMetricsDumped(dump))
             }
+            DumpNetworkStats => {
+                let dump = self.conductor_handle.dump_network_stats().await?;
+                Ok(AdminResponse::NetworkStatsDumped(dump))
+            }
+            DumpRelayStats => {
+                let dump = self.conductor_handle.dump_relay_stats()?;
+                Ok(AdminResponse::RelayStatsDumped(dump))
+            }
+            DumpInterfaceStats => Ok(AdminResponse::InterfaceStatsDumped(
+                self.conductor_handle.interface_stats(),
+            )),
+            DumpTasks => Ok(AdminResponse::TasksDumped(
+                self.conductor_handle.task_info(),
+            )),
             AddAgentInfo { agent_infos } => {
                 self.conductor_handle.add_agent_infos(agent_infos).await?;
                 Ok(AdminResponse::AgentInfoAdded)
@@ -366,8 +466,120 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                     .await?;
                 Ok(AdminResponse::RecordsAdded)
             }
+            ReloadConfig { config_path } => {
+                let report = self
+                    .conductor_handle
+                    .clone()
+                    .reload_config(config_path)
+                    .await?;
+                Ok(AdminResponse::ConfigReloaded(report))
+            }
+            Shutdown => {
+                // Run the shutdown in the background so that this response
+                // can be sent before the interface closes.
+                let conductor_handle = self.conductor_handle.clone();
+                tokio::spawn(async move {
+                    conductor_handle
+                        .graceful_shutdown(SHUTDOWN_DRAIN_TIMEOUT)
+                        .await
+                });
+                Ok(AdminResponse::ShuttingDown)
+            }
+            SetDnaSchedulingWeight { dna_hash, weight } => {
+                self.conductor_handle
+                    .set_dna_scheduling_weight(dna_hash, weight);
+                Ok(AdminResponse::DnaSchedulingWeightSet)
+            }
+            BackupCell { cell_id, path } => {
+                self.conductor_handle.backup_cell(&cell_id, &path).await?;
+                Ok(AdminResponse::CellBackedUp)
+            }
+            RestoreCell { cell_id, path } => {
+                self.conductor_handle.restore_cell(&cell_id, &path).await?;
+                Ok(AdminResponse::CellRestored)
+            }
+            BackupConductor { path } => {
+                self.conductor_handle.backup_conductor(&path).await?;
+                Ok(AdminResponse::ConductorBackedUp)
+            }
+            ExportDhtOps { cell_id, path } => {
+                let count = self
+                    .conductor_handle
+                    .export_dht_ops(&cell_id, &path)
+                    .await?;
+                Ok(AdminResponse::DhtOpsExported(count))
+            }
+            ImportDhtOps { cell_id, path } => {
+                let count = self
+                    .conductor_handle
+                    .import_dht_ops(&cell_id, &path)
+                    .await?;
+                Ok(AdminResponse::DhtOpsImported(count))
+            }
+            CheckDbIntegrity { cell_id, repair } => {
+                let report = self
+                    .conductor_handle
+                    .check_db_integrity(&cell_id, repair)
+                    .await?;
+                Ok(AdminResponse::DbIntegrityChecked(report))
+            }
+            RollbackUncommitted { cell_id } => {
+                let action_hash = self.conductor_handle.rollback_uncommitted(&cell_id).await?;
+                Ok(AdminResponse::UncommittedRolledBack(action_hash))
+            }
+            RequestBootstrapHealth => {
+                let health = self.conductor_handle.bootstrap_health().await?;
+                Ok(AdminResponse::BootstrapHealthRequested(health))
+            }
+            SetNetworkEnabled { enabled } => {
+                self.conductor_handle.set_network_enabled(enabled).await?;
+                Ok(AdminResponse::NetworkEnabledSet)
+            }
+            QueryAuditLog {
+                since,
+                until,
+                limit,
+            } => {
+                let entries = self
+                    .conductor_handle
+                    .query_audit_log(since, until, limit)
+                    .await?;
+                Ok(AdminResponse::AuditLogQueried(entries))
+            }
+            GetAgreedChainHead { cell_id } => {
+                let head = self.conductor_handle.agreed_chain_head(&cell_id)?;
+                Ok(AdminResponse::AgreedChainHeadRequested(head))
+            }
+            RequestOpCounts => {
+                let counts = self.conductor_handle.op_counts().await?;
+                Ok(AdminResponse::OpCountsRequested(counts))
+            }
+            // The conductor is already unlocked if it's handling requests.
+            UnlockConductor { .. } => Ok(AdminResponse::ConductorUnlocked),
         }
     }
+
+    /// Deal with error cases produced by `handle_admin_request_inner`,
+    /// recording the call in the audit log.
+    async fn handle_admin_request(&self, request: AdminRequest) -> AdminResponse {
+        debug!("admin request: {:?}", request);
+
+        let request_type: &'static str = (&request).into();
+        let res = match self.handle_admin_request_inner(request).await {
+            Ok(response) => response,
+            Err(e) => AdminResponse::Error(e.into()),
+        };
+        let outcome = match &res {
+            AdminResponse::Error(e) => AuditOutcome::Error {
+                message: format!("{:?}", e),
+            },
+            _ => AuditOutcome::Success,
+        };
+        self.conductor_handle
+            .audit_admin_call(request_type, outcome);
+        debug!("admin response: {:?}", res);
+        res
+    }
 }
 
 /// Return the proper phenotype for a Dna, given a manifest and some optional
@@ -682,4 +894,56 @@ mod test {
             .await
             .ok();
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn admin_calls_are_audited() {
+        use crate::sweettest::*;
+        use holochain_conductor_api::audit_log::AuditEvent;
+        use holochain_conductor_api::config::conductor::AuditLogConfig;
+
+        let audit_dir = tempfile::tempdir().unwrap();
+        let mut config = standard_config();
+        config.audit_log = Some(AuditLogConfig {
+            path: Some(audit_dir.path().to_path_buf()),
+            include_zome_calls: false,
+            max_file_size_bytes: None,
+            max_rotated_files: None,
+        });
+        let conductor = SweetConductor::from_config(config).await;
+        let admin_api = RealAdminInterfaceApi::new(conductor.handle());
+
+        admin_api.handle_admin_request(AdminRequest::ListDnas).await;
+        admin_api
+            .handle_admin_request(AdminRequest::EnableApp {
+                installed_app_id: "missing".to_string(),
+            })
+            .await;
+
+        let res = admin_api
+            .handle_admin_request(AdminRequest::QueryAuditLog {
+                since: None,
+                until: None,
+                limit: None,
+            })
+            .await;
+        let entries = match res {
+            AdminResponse::AuditLogQueried(entries) => entries,
+            other => panic!("unexpected response {:?}", other),
+        };
+        assert_eq!(entries.len(), 2);
+        assert_eq!(
+            entries[0].event,
+            AuditEvent::AdminCall {
+                request: "list_dnas".to_string()
+            }
+        );
+        assert_eq!(entries[0].outcome, AuditOutcome::Success);
+        assert_eq!(
+            entries[1].event,
+            AuditEvent::AdminCall {
+                request: "enable_app".to_string()
+            }
+        );
+        assert_matches!(entries[1].outcome, AuditOutcome::Error { .. });
+    }
 }
@@ -1,10 +1,15 @@
 use super::InterfaceApi;
+use crate::conductor::api::error::ConductorApiError;
 use crate::conductor::api::error::ConductorApiResult;
 use crate::conductor::api::error::ExternalApiWireError;
 use crate::conductor::api::error::SerializationError;
+use crate::conductor::error::ConductorError;
 use crate::conductor::interface::error::InterfaceError;
 use crate::conductor::interface::error::InterfaceResult;
+use crate::conductor::interface::signal_subscriptions::SignalSubscriptions;
+use crate::conductor::interface::started_zome_calls::StartedZomeCalls;
 use crate::conductor::ConductorHandle;
+use crate::core::ribosome::zome_call_limits::ZomeCallCancel;
 
 use holochain_serialized_bytes::prelude::*;
 
@@ -12,6 +17,13 @@ use holochain_types::prelude::*;
 
 pub use holochain_conductor_api::*;
 
+/// How many records [`AppRequest::RecordsIntegratedSince`] lists when it
+/// isn't given a limit.
+const DEFAULT_INTEGRATED_RECORDS: usize = 100;
+
+/// The most records [`AppRequest::RecordsIntegratedSince`] lists at a time.
+const MAX_INTEGRATED_RECORDS: usize = 1000;
+
 /// The interface that a Conductor exposes to the outside world.
 #[async_trait::async_trait]
 pub trait AppInterfaceApi: 'static + Send + Sync + Clone {
@@ -41,12 +53,45 @@ pub trait AppInterfaceApi: 'static + Send + Sync + Clone {
 #[derive(Clone)]
 pub struct RealAppInterfaceApi {
     conductor_handle: ConductorHandle,
+
+    /// The signal subscriptions of the connection this api serves
+    signal_subscriptions: SignalSubscriptions,
+
+    /// The zome calls started on the connection this api serves
+    started_zome_calls: StartedZomeCalls,
+
+    /// Whether unsigned zome calls are refused
+    require_signed_zome_calls: bool,
 }
 
 impl RealAppInterfaceApi {
     /// Create a new instance from a shared Conductor reference
     pub fn new(conductor_handle: ConductorHandle) -> Self {
-        Self { conductor_handle }
+        Self {
+            conductor_handle,
+            signal_subscriptions: SignalSubscriptions::default(),
+            started_zome_calls: StartedZomeCalls::default(),
+            require_signed_zome_calls: false,
+        }
+    }
+
+    /// Refuse zome calls which aren't signed by the agent of the cell they
+    /// call, as unsigned calls may be made as any agent.
+    pub fn require_signed_zome_calls(mut self, require: bool) -> Self {
+        self.require_signed_zome_calls = require;
+        self
+    }
+
+    /// A copy of this api for a new connection, with its own signal
+    /// subscriptions.
+    pub(crate) fn for_connection(&self) -> Self {
+        Self::new(self.conductor_handle.clone())
+            .require_signed_zome_calls(self.require_signed_zome_calls)
+    }
+
+    /// The signal subscriptions of the connection this api serves
+    pub(crate) fn signal_subscriptions(&self) -> &SignalSubscriptions {
+        &self.signal_subscriptions
     }
 }
 
@@ -57,12 +102,31 @@ impl AppInterfaceApi for RealAppInterfaceApi {
         &self,
         request: AppRequest,
     ) -> ConductorApiResult<AppResponse> {
+        if self.require_signed_zome_calls
+            && matches!(
+                request,
+                AppRequest::ZomeCall(_)
+                    | AppRequest::ZomeCallInvocation(_)
+                    | AppRequest::StartZomeCall(_)
+            )
+        {
+            return Ok(AppResponse::Error(
+                ExternalApiWireError::ZomeCallUnauthorized(
+                    "This interface only accepts signed zome calls".to_string(),
+                ),
+            ));
+        }
         match request {
             AppRequest::AppInfo { installed_app_id } => Ok(AppResponse::AppInfo(
                 self.conductor_handle
                     .get_app_info(&installed_app_id)
                     .await?,
             )),
+            AppRequest::FullAppInfo { installed_app_id } => Ok(AppResponse::FullAppInfo(
+                self.conductor_handle
+                    .get_full_app_info(&installed_app_id)
+                    .await?,
+            )),
             AppRequest::ZomeCallInvocation(call) => {
                 tracing::warn!(
                     "AppRequest::ZomeCallInvocation is deprecated, use AppRequest::ZomeCall (TODO: update conductor-api)"
@@ -78,34 +142,131 @@ impl AppInterfaceApi for RealAppInterfaceApi {
                         }
                     })
             }
+            AppRequest::SignedZomeCall(call) => {
+                let call = self.conductor_handle.verify_signed_zome_call(*call).await?;
+                zome_call(&self.conductor_handle, call, ZomeCallCancel::default()).await
+            }
             AppRequest::ZomeCall(call) => {
-                match self.conductor_handle.call_zome(*call.clone()).await? {
-                    Ok(ZomeCallResponse::Ok(output)) => Ok(AppResponse::ZomeCall(Box::new(output))),
-                    Ok(ZomeCallResponse::Unauthorized(_, _, _, _)) => Ok(AppResponse::Error(
-                        ExternalApiWireError::ZomeCallUnauthorized(format!(
-                            "No capabilities grant has been committed that allows the CapSecret {:?} to call the function {} in zome {}",
-                            call.cap_secret, call.fn_name, call.zome_name
-                        )),
-                    )),
-                    Ok(ZomeCallResponse::NetworkError(e)) => unreachable!(
-                        "Interface zome calls should never be routed to the network. This is a bug. Got {}",
-                        e
-                    ),
-                    Ok(ZomeCallResponse::CountersigningSession(e)) => Ok(AppResponse::Error(
-                        ExternalApiWireError::CountersigningSessionError(format!(
-                            "A countersigning session has failed to start on this zome call because: {}",
-                            e
-                        )),
-                    )),
-                    Err(e) => Ok(AppResponse::Error(e.into())),
+                zome_call(&self.conductor_handle, *call, ZomeCallCancel::default()).await
+            }
+            AppRequest::StartZomeCall(call) => {
+                let conductor_handle = self.conductor_handle.clone();
+                let call_id = self.started_zome_calls.start(|cancel| async move {
+                    zome_call(&conductor_handle, *call, cancel)
+                        .await
+                        .unwrap_or_else(|e| AppResponse::Error(e.into()))
+                });
+                Ok(AppResponse::ZomeCallStarted(call_id))
+            }
+            AppRequest::AwaitZomeCall { call_id } => self
+                .started_zome_calls
+                .wait(call_id)
+                .await
+                .ok_or_else(|| unknown_zome_call(call_id)),
+            AppRequest::CancelZomeCall { call_id } => {
+                if self.started_zome_calls.cancel(call_id) {
+                    Ok(AppResponse::ZomeCallCancelled)
+                } else {
+                    Err(unknown_zome_call(call_id))
                 }
             }
-            AppRequest::SignalSubscription(_) => Ok(AppResponse::Unimplemented(request)),
+            AppRequest::ProvisionDeferredCell {
+                installed_app_id,
+                role_id,
+            } => Ok(AppResponse::DeferredCellProvisioned(
+                self.conductor_handle
+                    .clone()
+                    .provision_deferred_cell(installed_app_id, role_id)
+                    .await?,
+            )),
+            AppRequest::SignalSubscription(subscription) => {
+                let app = self
+                    .conductor_handle
+                    .get_app_info(&subscription.installed_app_id)
+                    .await?
+                    .ok_or_else(|| {
+                        ConductorError::AppNotInstalled(subscription.installed_app_id.clone())
+                    })?;
+                let cells = app
+                    .cell_data
+                    .into_iter()
+                    .chain(app.deferred_cell_data)
+                    .map(|cell| cell.into_id())
+                    .collect();
+                self.signal_subscriptions.set(
+                    subscription.installed_app_id,
+                    cells,
+                    subscription.filters,
+                );
+                Ok(AppResponse::SignalSubscriptionUpdated)
+            }
+            AppRequest::RecordsIntegratedSince {
+                cell_id,
+                since,
+                limit,
+            } => {
+                let limit = limit
+                    .map_or(DEFAULT_INTEGRATED_RECORDS, |limit| limit as usize)
+                    .min(MAX_INTEGRATED_RECORDS);
+                Ok(AppResponse::RecordsIntegratedSince(
+                    self.conductor_handle
+                        .records_integrated_since(&cell_id, since, limit)
+                        .await?,
+                ))
+            }
             AppRequest::Crypto(_) => Ok(AppResponse::Unimplemented(request)),
         }
     }
 }
 
+/// Make a zome call from an app interface.
+async fn zome_call(
+    conductor_handle: &ConductorHandle,
+    call: ZomeCall,
+    cancel: ZomeCallCancel,
+) -> ConductorApiResult<AppResponse> {
+    // Deferred cells are created when they are first called.
+    if !conductor_handle.list_cell_ids(None).contains(&call.cell_id) {
+        conductor_handle
+            .clone()
+            .provision_deferred_cell_by_id(&call.cell_id)
+            .await?;
+    }
+    conductor_handle
+        .check_zome_fn_access(&call, ZomeCallOrigin::AppInterface)
+        .await?;
+    match conductor_handle
+        .call_zome_cancellable(call.clone(), cancel)
+        .await?
+    {
+        Ok(ZomeCallResponse::Ok(output)) => Ok(AppResponse::ZomeCall(Box::new(output))),
+        Ok(ZomeCallResponse::Unauthorized(_, _, _, _)) => Ok(AppResponse::Error(
+            ExternalApiWireError::ZomeCallUnauthorized(format!(
+                "No capabilities grant has been committed that allows the CapSecret {:?} to call the function {} in zome {}",
+                call.cap_secret, call.fn_name, call.zome_name
+            )),
+        )),
+        Ok(ZomeCallResponse::NetworkError(e)) => unreachable!(
+            "Interface zome calls should never be routed to the network. This is a bug. Got {}",
+            e
+        ),
+        Ok(ZomeCallResponse::CountersigningSession(e)) => Ok(AppResponse::Error(
+            ExternalApiWireError::CountersigningSessionError(format!(
+                "A countersigning session has failed to start on this zome call because: {}",
+                e
+            )),
+        )),
+        Err(e) => Ok(AppResponse::Error(e.into())),
+    }
+}
+
+fn unknown_zome_call(call_id: ZomeCallId) -> ConductorApiError {
+    ConductorApiError::other(format!(
+        "No zome call with ID {} was started on this connection, or its result was already read",
+        call_id
+    ))
+}
+
 #[async_trait::async_trait]
 impl InterfaceApi for RealAppInterfaceApi {
     type ApiRequest = AppRequest;
@@ -5,6 +5,8 @@ use crate::conductor::CellError;
 use crate::core::ribosome::error::RibosomeError;
 use crate::core::workflow::error::WorkflowError;
 use holo_hash::DnaHash;
+use holochain_conductor_api::CellGenesisFailure;
+use holochain_conductor_api::GenesisFailureReason;
 use holochain_sqlite::error::DatabaseError;
 use holochain_state::source_chain::SourceChainError;
 use holochain_state::workspace::WorkspaceError;
@@ -151,6 +153,30 @@ impl From<ConductorApiError> for ExternalApiWireError {
     fn from(err: ConductorApiError) -> Self {
         match err {
             ConductorApiError::DnaReadError(e) => ExternalApiWireError::DnaReadError(e),
+            ConductorApiError::ConductorError(e) => match *e {
+                ConductorError::GenesisFailed { errors } => ExternalApiWireError::GenesisFailed(
+                    errors
+                        .into_iter()
+                        .map(|(cell_id, e)| CellGenesisFailure {
+                            cell_id,
+                            reason: match e.genesis_self_check_rejection() {
+                                Some(reason) => {
+                                    GenesisFailureReason::SelfCheckRejected(reason.to_string())
+                                }
+                                None => GenesisFailureReason::Error(e.to_string()),
+                            },
+                        })
+                        .collect(),
+                ),
+                ConductorError::QuotaExceeded(exceeded) => {
+                    ExternalApiWireError::QuotaExceeded(exceeded)
+                }
+                e @ ConductorError::InvalidSignedZomeCall(_)
+                | e @ ConductorError::ZomeFnAccessDenied { .. } => {
+                    ExternalApiWireError::ZomeCallUnauthorized(e.to_string())
+                }
+                e => ExternalApiWireError::internal(ConductorApiError::ConductorError(Box::new(e))),
+            },
             e => ExternalApiWireError::internal(e),
         }
     }
@@ -164,6 +190,11 @@ impl From<SerializationError> for ExternalApiWireError {
 
 impl From<RibosomeError> for ExternalApiWireError {
     fn from(e: RibosomeError) -> Self {
-        ExternalApiWireError::RibosomeError(e.to_string())
+        match e {
+            RibosomeError::ZomeCallCancelled(_, _) => {
+                ExternalApiWireError::ZomeCallCancelled(e.to_string())
+            }
+            e => ExternalApiWireError::RibosomeError(e.to_string()),
+        }
     }
 }
@@ -0,0 +1,224 @@
+//! The conductor's append-only audit log, configured with [`AuditLogConfig`].
+//!
+//! Entries are written as lines of JSON to `audit.log` in the log directory.
+//! Once that file is too large it is renamed to `audit.<micros>.log`, after
+//! the time of the rotation, so the rotated files sort oldest first.
+
+use holochain_conductor_api::audit_log::AuditEntry;
+use holochain_conductor_api::audit_log::AuditEvent;
+use holochain_conductor_api::audit_log::AuditOutcome;
+use holochain_conductor_api::config::conductor::AuditLogConfig;
+use holochain_types::prelude::Timestamp;
+use std::io::BufRead;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// The file entries are currently appended to.
+const CURRENT_FILE: &str = "audit.log";
+
+/// An append-only log of the calls made to the conductor.
+pub(crate) struct AuditLog {
+    config: AuditLogConfig,
+    dir: PathBuf,
+    current: parking_lot::Mutex<CurrentFile>,
+}
+
+struct CurrentFile {
+    file: std::fs::File,
+    size: u64,
+}
+
+impl AuditLog {
+    /// Open the log, continuing the current file if there is one.
+    pub(crate) fn open(config: AuditLogConfig, environment_path: &Path) -> std::io::Result<Self> {
+        let dir = config
+            .path
+            .clone()
+            .unwrap_or_else(|| environment_path.join("audit"));
+        std::fs::create_dir_all(&dir)?;
+        let current = CurrentFile::open(&dir)?;
+        Ok(Self {
+            config,
+            dir,
+            current: parking_lot::Mutex::new(current),
+        })
+    }
+
+    /// Should zome calls be recorded as well as admin calls.
+    pub(crate) fn include_zome_calls(&self) -> bool {
+        self.config.include_zome_calls
+    }
+
+    /// Record a call. Failing to write the entry is logged, but doesn't
+    /// fail the call.
+    pub(crate) fn record(&self, event: AuditEvent, outcome: AuditOutcome) {
+        let entry = AuditEntry {
+            timestamp: Timestamp::now(),
+            event,
+            outcome,
+        };
+        if let Err(e) = self.append(&entry) {
+            tracing::error!(?e, ?entry, "Failed to write to the audit log");
+        }
+    }
+
+    fn append(&self, entry: &AuditEntry) -> std::io::Result<()> {
+        let mut line = serde_json::to_vec(entry)?;
+        line.push(b'\n');
+        let mut current = self.current.lock();
+        if current.size > 0 && current.size + line.len() as u64 > self.config.max_file_size_bytes()
+        {
+            self.rotate(&mut current)?;
+        }
+        current.file.write_all(&line)?;
+        current.size += line.len() as u64;
+        Ok(())
+    }
+
+    fn rotate(&self, current: &mut CurrentFile) -> std::io::Result<()> {
+        current.file.sync_all()?;
+        let rotated = format!("audit.{:020}.log", Timestamp::now().as_micros());
+        std::fs::rename(self.dir.join(CURRENT_FILE), self.dir.join(rotated))?;
+        *current = CurrentFile::open(&self.dir)?;
+
+        if let Some(max_rotated_files) = self.config.max_rotated_files {
+            let rotated = self.rotated_files()?;
+            let excess = rotated.len().saturating_sub(max_rotated_files);
+            for path in &rotated[..excess] {
+                std::fs::remove_file(path)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The rotated files, oldest first.
+    fn rotated_files(&self) -> std::io::Result<Vec<PathBuf>> {
+        let mut rotated = Vec::new();
+        for dir_entry in std::fs::read_dir(&self.dir)? {
+            let path = dir_entry?.path();
+            let is_rotated = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .map_or(false, |n| {
+                    n != CURRENT_FILE && n.starts_with("audit.") && n.ends_with(".log")
+                });
+            if is_rotated {
+                rotated.push(path);
+            }
+        }
+        rotated.sort();
+        Ok(rotated)
+    }
+
+    /// Read the entries recorded after `since` and before `until`,
+    /// oldest first, up to `limit` of them.
+    pub(crate) fn query(
+        &self,
+        since: Option<Timestamp>,
+        until: Option<Timestamp>,
+        limit: Option<usize>,
+    ) -> std::io::Result<Vec<AuditEntry>> {
+        // Hold the lock while listing so a rotation can't move the current
+        // file between the listing and reading it.
+        let files = {
+            let _current = self.current.lock();
+            let mut files = self.rotated_files()?;
+            files.push(self.dir.join(CURRENT_FILE));
+            files
+                .into_iter()
+                .map(std::fs::File::open)
+                .collect::<std::io::Result<Vec<_>>>()?
+        };
+        let limit = limit.unwrap_or(usize::MAX);
+        let mut entries = Vec::new();
+        for file in files {
+            for line in std::io::BufReader::new(file).lines() {
+                if entries.len() >= limit {
+                    return Ok(entries);
+                }
+                // A line being appended right now may not be complete yet.
+                let entry: AuditEntry = match serde_json::from_str(&line?) {
+                    Ok(entry) => entry,
+                    Err(_) => continue,
+                };
+                if since.map_or(true, |since| entry.timestamp > since)
+                    && until.map_or(true, |until| entry.timestamp < until)
+                {
+                    entries.push(entry);
+                }
+            }
+        }
+        Ok(entries)
+    }
+}
+
+impl CurrentFile {
+    fn open(dir: &Path) -> std::io::Result<Self> {
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(dir.join(CURRENT_FILE))?;
+        let size = file.metadata()?.len();
+        Ok(Self { file, size })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn admin_call(request: &str) -> AuditEvent {
+        AuditEvent::AdminCall {
+            request: request.to_string(),
+        }
+    }
+
+    #[test]
+    fn rotates_and_queries_across_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = AuditLogConfig {
+            path: Some(dir.path().to_path_buf()),
+            include_zome_calls: false,
+            max_file_size_bytes: Some(1),
+            max_rotated_files: Some(2),
+        };
+        let log = AuditLog::open(config.clone(), dir.path()).unwrap();
+        for request in ["list_dnas", "list_apps", "install_app", "enable_app"] {
+            log.record(admin_call(request), AuditOutcome::Success);
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+        log.record(
+            admin_call("uninstall_app"),
+            AuditOutcome::Error {
+                message: "no such app".to_string(),
+            },
+        );
+
+        // Every entry gets its own file, and only two rotated files are kept.
+        assert_eq!(log.rotated_files().unwrap().len(), 2);
+        let entries = log.query(None, None, None).unwrap();
+        let requests: Vec<_> = entries
+            .iter()
+            .map(|e| match &e.event {
+                AuditEvent::AdminCall { request } => request.as_str(),
+                _ => unreachable!(),
+            })
+            .collect();
+        assert_eq!(requests, ["install_app", "enable_app", "uninstall_app"]);
+        assert_eq!(
+            entries[2].outcome,
+            AuditOutcome::Error {
+                message: "no such app".to_string()
+            }
+        );
+
+        // Reopening continues the log, and queries can page through it.
+        drop(log);
+        let log = AuditLog::open(config, dir.path()).unwrap();
+        let page = log.query(None, None, Some(1)).unwrap();
+        assert_eq!(page, entries[..1]);
+        let page = log.query(Some(page[0].timestamp), None, Some(1)).unwrap();
+        assert_eq!(page, entries[1..2]);
+    }
+}
@@ -17,6 +17,7 @@ use crate::core::queue_consumer::InitialQueueTriggers;
 use crate::core::queue_consumer::QueueTriggers;
 use crate::core::ribosome::guest_callback::init::InitResult;
 use crate::core::ribosome::real_ribosome::RealRibosome;
+use crate::core::ribosome::zome_call_limits::ZomeCallCancel;
 use crate::core::ribosome::ZomeCallInvocation;
 use crate::core::workflow::call_zome_workflow;
 use crate::core::workflow::countersigning_workflow::countersigning_success;
@@ -24,6 +25,7 @@ use crate::core::workflow::countersigning_workflow::incoming_countersigning;
 use crate::core::workflow::genesis_workflow::genesis_workflow;
 use crate::core::workflow::initialize_zomes_workflow;
 use crate::core::workflow::CallZomeWorkflowArgs;
+use crate::core::workflow::ChainHeadCoordinator;
 use crate::core::workflow::GenesisWorkflowArgs;
 use crate::core::workflow::GenesisWorkspace;
 use crate::core::workflow::InitializeZomesWorkflowArgs;
@@ -35,6 +37,7 @@ use hash_type::AnyDht;
 use holo_hash::*;
 use holochain_cascade::authority;
 use holochain_cascade::Cascade;
+use holochain_conductor_api::CellInitStatus;
 use holochain_p2p::event::CountersigningSessionNegotiationMessage;
 use holochain_serialized_bytes::SerializedBytes;
 use holochain_sqlite::prelude::*;
@@ -55,6 +58,14 @@ use tracing_futures::Instrument;
 
 pub const INIT_MUTEX_TIMEOUT_SECS: u64 = 30;
 
+/// The most times init is run while it has unresolved dependencies.
+pub const INIT_RETRY_ATTEMPTS: u32 = 5;
+
+/// How long to wait before running init again after it had unresolved
+/// dependencies. Doubles after each attempt, which keeps all the attempts
+/// well within [`INIT_MUTEX_TIMEOUT_SECS`].
+pub const INIT_RETRY_BACKOFF_MS: u64 = 500;
+
 mod validation_package;
 
 #[allow(missing_docs)]
@@ -107,6 +118,8 @@ where
     holochain_p2p_cell: P2pCell,
     queue_triggers: QueueTriggers,
     init_mutex: tokio::sync::Mutex<()>,
+    init_status: parking_lot::Mutex<CellInitStatus>,
+    chain_head_coordinator: Arc<ChainHeadCoordinator>,
 }
 
 impl Cell {
@@ -156,6 +169,8 @@ impl Cell {
                     holochain_p2p_cell,
                     queue_triggers,
                     init_mutex: Default::default(),
+                    init_status: Default::default(),
+                    chain_head_coordinator: Default::default(),
                 },
                 initial_queue_triggers,
             ))
@@ -293,7 +308,7 @@ impl Cell {
                         provenance: self.id.agent_pubkey().clone(),
                         fn_name: scheduled_fn.fn_name().clone(),
                     };
-                    tasks.push(self.call_zome(invocation, None));
+                    tasks.push(self.call_zome(invocation, None, ZomeCallCancel::default()));
                 }
                 let results: Vec<CellResult<ZomeCallResult>> =
                     futures::future::join_all(tasks).await;
@@ -356,7 +371,10 @@ impl Cell {
             | QueryAgentInfoSignedNearBasis { .. }
             | QueryPeerDensity { .. }
             | Publish { .. }
-            | FetchOpData { .. } => {
+            | FetchOpData { .. }
+            | FetchDna { .. }
+            | QueueRemoteSignal { .. }
+            | TakeQueuedRemoteSignals { .. } => {
                 // These events are aggregated over a set of cells, so need to be handled at the conductor level.
                 unreachable!()
             }
@@ -662,14 +680,17 @@ impl Cell {
             .map_err(Into::into)
     }
 
-    #[instrument(skip(self, _dht_hash, _options))]
+    #[instrument(skip(self, options))]
     /// a remote node is asking us for metadata
     async fn handle_get_meta(
         &self,
-        _dht_hash: holo_hash::AnyDhtHash,
-        _options: holochain_p2p::event::GetMetaOptions,
+        dht_hash: holo_hash::AnyDhtHash,
+        options: holochain_p2p::event::GetMetaOptions,
     ) -> CellResult<MetadataSet> {
-        unimplemented!()
+        let db = self.space.dht_db.clone();
+        authority::handle_get_meta(db.into(), dht_hash, options)
+            .await
+            .map_err(Into::into)
     }
 
     #[instrument(skip(self, options))]
@@ -767,6 +788,17 @@ impl Cell {
             crate::core::workflow::publish_dht_ops_workflow::DEFAULT_RECEIPT_BUNDLE_SIZE,
         );
 
+        // Let the author know if the op was rejected.
+        let rejection =
+            (receipt.receipt.validation_status == ValidationStatus::Rejected).then(|| {
+                SystemSignal::OpRejected {
+                    cell_id: self.id.clone(),
+                    op_hash: receipt.receipt.dht_op_hash.clone(),
+                    validators: receipt.receipt.validators.clone(),
+                    reason: receipt.receipt.rejected_reason.clone(),
+                }
+            });
+
         self.space
             .dht_db
             .async_commit(move |txn| {
@@ -789,6 +821,12 @@ impl Cell {
             })
             .await?;
 
+        if let Some(signal) = rejection {
+            if let Err(e) = self.signal_broadcaster().await.send(signal.into()) {
+                tracing::warn!(?e, "Failed to broadcast OpRejected signal");
+            }
+        }
+
         Ok(())
     }
 
@@ -816,18 +854,40 @@ impl Cell {
             provenance: from_agent,
             fn_name,
         };
+        if let Err(e) = self
+            .conductor_api
+            .check_zome_fn_access(&invocation, ZomeCallOrigin::Remote)
+            .await
+        {
+            tracing::info!(?e, "Refused a remote call");
+            let ZomeCall {
+                cell_id,
+                zome_name,
+                fn_name,
+                provenance,
+                ..
+            } = invocation;
+            return Ok(
+                ZomeCallResponse::Unauthorized(cell_id, zome_name, fn_name, provenance)
+                    .try_into()?,
+            );
+        }
         // double ? because
         // - ConductorApiResult
         // - ZomeCallResult
-        Ok(self.call_zome(invocation, None).await??.try_into()?)
+        Ok(self
+            .call_zome(invocation, None, ZomeCallCancel::default())
+            .await??
+            .try_into()?)
     }
 
     /// Function called by the Conductor
-    #[instrument(skip(self, call, workspace_lock))]
+    #[instrument(skip(self, call, workspace_lock, cancel))]
     pub async fn call_zome(
         &self,
         call: ZomeCall,
         workspace_lock: Option<SourceChainWorkspace>,
+        cancel: ZomeCallCancel,
     ) -> CellResult<ZomeCallResult> {
         // Only check if init has run if this call is not coming from
         // an already running init call.
@@ -874,6 +934,8 @@ impl Cell {
             signal_tx,
             conductor_handle,
             is_root_zome_call,
+            chain_head_coordinator: self.chain_head_coordinator.clone(),
+            cancel,
         };
         Ok(call_zome_workflow(
             workspace_lock,
@@ -888,6 +950,10 @@ impl Cell {
     }
 
     /// Check if each Zome's init callback has been run, and if not, run it.
+    ///
+    /// Init is run again, with a backoff, while it has unresolved
+    /// dependencies, up to [`INIT_RETRY_ATTEMPTS`] times.
+    /// Other calls to the cell wait for this to finish.
     #[tracing::instrument(skip(self))]
     async fn check_or_run_zome_init(&self) -> CellResult<()> {
         // Ensure that only one init check is run at a time
@@ -898,7 +964,56 @@ impl Cell {
         .await
         .map_err(|_| CellError::InitTimeout)?;
 
-        // If not run it
+        if *self.init_status.lock() == CellInitStatus::Initialized {
+            return Ok(());
+        }
+
+        let mut backoff = std::time::Duration::from_millis(INIT_RETRY_BACKOFF_MS);
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            let init_result = match self.run_zome_init().await {
+                Ok(Some(init_result)) => init_result,
+                Ok(None) => {
+                    *self.init_status.lock() = CellInitStatus::Initialized;
+                    return Ok(());
+                }
+                Err(e) => {
+                    *self.init_status.lock() = CellInitStatus::NotRun;
+                    return Err(e);
+                }
+            };
+            trace!(?init_result, attempts);
+            let status = match &init_result {
+                InitResult::Pass => CellInitStatus::Initialized,
+                InitResult::Fail(zome_name, reason) => CellInitStatus::Failed {
+                    zome_name: zome_name.clone(),
+                    reason: reason.clone(),
+                },
+                InitResult::UnresolvedDependencies(zome_name, _) => {
+                    if attempts < INIT_RETRY_ATTEMPTS {
+                        debug!(%zome_name, attempts, "Retrying init with unresolved dependencies");
+                        tokio::time::sleep(backoff).await;
+                        backoff *= 2;
+                        continue;
+                    }
+                    CellInitStatus::UnresolvedDependencies {
+                        zome_name: zome_name.clone(),
+                        attempts,
+                    }
+                }
+            };
+            *self.init_status.lock() = status;
+            return match init_result {
+                InitResult::Pass => Ok(()),
+                r => Err(CellError::InitFailed(r)),
+            };
+        }
+    }
+
+    /// Run each Zome's init callback, unless the source chain shows
+    /// that they have already passed, in which case return `None`.
+    async fn run_zome_init(&self) -> CellResult<Option<InitResult>> {
         let keystore = self.conductor_api.keystore().clone();
         let id = self.id.clone();
         let conductor_handle = self.conductor_handle.clone();
@@ -922,9 +1037,10 @@ impl Cell {
 
         // Check if initialization has run
         if workspace.source_chain().has_initialized()? {
-            return Ok(());
+            return Ok(None);
         }
         trace!("running init");
+        *self.init_status.lock() = CellInitStatus::Running;
 
         let signal_tx = self.signal_broadcaster().await;
 
@@ -939,12 +1055,23 @@ impl Cell {
             initialize_zomes_workflow(workspace, self.holochain_p2p_cell.clone(), keystore, args)
                 .await
                 .map_err(Box::new)?;
-        trace!(?init_result);
-        match init_result {
-            InitResult::Pass => {}
-            r => return Err(CellError::InitFailed(r)),
-        }
-        Ok(())
+        Ok(Some(init_result))
+    }
+
+    /// Whether each Zome's init callback has run, as far as this cell has
+    /// seen since it was created.
+    /// Cells whose init ran before they were created report
+    /// [`CellInitStatus::NotRun`] until their first zome call.
+    pub fn init_status(&self) -> CellInitStatus {
+        self.init_status.lock().clone()
+    }
+
+    /// The chain head of the last write committed after the agent activity
+    /// authorities agreed that no other device sharing this cell's agent key
+    /// had written on top of the head it built on.
+    /// This is only tracked while chain head coordination is enabled.
+    pub fn agreed_chain_head(&self) -> Option<ChainHead> {
+        self.chain_head_coordinator.agreed_chain_head()
     }
 
     /// Clean up long-running managed tasks.
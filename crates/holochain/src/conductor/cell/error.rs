@@ -77,4 +77,22 @@ pub enum CellError {
     StateMutationError(#[from] holochain_state::mutations::StateMutationError),
 }
 
+impl CellError {
+    /// The reason the DNA's `genesis_self_check` callback rejected the cell,
+    /// if that is why genesis failed.
+    pub fn genesis_self_check_rejection(&self) -> Option<&str> {
+        let api_error = match self {
+            CellError::Genesis(e) | CellError::ConductorApiError(e) => e,
+            _ => return None,
+        };
+        match api_error.as_ref() {
+            ConductorApiError::WorkflowError(e) => match e.as_ref() {
+                WorkflowError::GenesisFailure(reason) => Some(reason),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
 pub type CellResult<T> = Result<T, CellError>;
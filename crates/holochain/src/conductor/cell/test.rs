@@ -1,4 +1,5 @@
 use crate::conductor::manager::spawn_task_manager;
+use crate::conductor::manager::TaskTable;
 use crate::conductor::space::TestSpaces;
 use crate::core::ribosome::guest_callback::genesis_self_check::GenesisSelfCheckResult;
 use crate::core::ribosome::MockRibosomeT;
@@ -41,6 +42,9 @@ async fn test_cell_handle_publish() {
     mock_handle
         .expect_get_queue_consumer_workflows()
         .return_const(spaces.queue_consumer_map.clone());
+    mock_handle
+        .expect_get_workflow_scheduler()
+        .return_const(spaces.spaces.workflow_scheduler.clone());
     mock_handle.expect_keystore().return_const(keystore.clone());
 
     let mock_handle: crate::conductor::handle::ConductorHandle = Arc::new(mock_handle);
@@ -63,7 +67,7 @@ async fn test_cell_handle_publish() {
     .await
     .unwrap();
 
-    let (add_task_sender, shutdown) = spawn_task_manager(mock_handle.clone());
+    let (add_task_sender, shutdown) = spawn_task_manager(mock_handle.clone(), TaskTable::default());
     let (stop_tx, _) = sync::broadcast::channel(1);
 
     let (_cell, _) = super::Cell::create(
@@ -11,27 +11,39 @@
 
 pub use self::share::RwShare;
 use super::api::RealAppInterfaceApi;
+use super::audit_log::AuditLog;
 use super::config::AdminInterfaceConfig;
 use super::config::InterfaceDriver;
 use super::entry_def_store::get_entry_defs;
 use super::error::ConductorError;
 use super::handle::ConductorHandleImpl;
+use super::interface::connections::ConnectionCounter;
+use super::interface::connections::InterfaceConnections;
 use super::interface::error::InterfaceResult;
+use super::interface::health::spawn_health_endpoint_task;
+use super::interface::http::spawn_http_gateway_task;
 use super::interface::websocket::spawn_admin_interface_task;
 use super::interface::websocket::spawn_app_interface_task;
 use super::interface::websocket::spawn_websocket_listener;
 use super::interface::websocket::SIGNAL_BUFFER_SIZE;
 use super::interface::SignalBroadcaster;
+use super::log_filter;
 use super::manager::keep_alive_task;
 use super::manager::ManagedTaskAdd;
 use super::manager::ManagedTaskHandle;
 use super::manager::TaskManagerRunHandle;
+use super::manager::TaskTable;
 use super::paths::DatabaseRootPath;
+use super::quota::QuotaTracker;
+use super::relay::spawn_relay;
+use super::relay::Relay;
 use super::ribosome_store::RibosomeStore;
+use super::signed_zome_call::NonceTracker;
 use super::space::Space;
 use super::space::Spaces;
 use super::state::AppInterfaceId;
 use super::state::ConductorState;
+use super::zome_fn_access::ZomeFnAccessTracker;
 use super::CellError;
 use super::{api::CellConductorApi, state::AppInterfaceConfig};
 use super::{api::CellConductorApiT, interface::AppInterfaceRuntime};
@@ -46,6 +58,8 @@ use crate::core::ribosome::guest_callback::post_commit::PostCommitArgs;
 use crate::core::ribosome::guest_callback::post_commit::POST_COMMIT_CHANNEL_BOUND;
 use crate::core::ribosome::guest_callback::post_commit::POST_COMMIT_CONCURRENT_LIMIT;
 use crate::core::ribosome::RibosomeT;
+use crate::core::workflow::countersigning_workflow::countersigning_session_failed;
+use crate::core::workflow::error::WorkflowError;
 use crate::{
     conductor::api::error::ConductorApiResult, core::ribosome::real_ribosome::RealRibosome,
 };
@@ -54,21 +68,34 @@ use futures::future;
 use futures::future::TryFutureExt;
 use futures::stream::StreamExt;
 use holo_hash::DnaHash;
+use holochain_conductor_api::conductor::ConfigReloadReport;
+use holochain_conductor_api::conductor::HealthEndpointConfig;
+use holochain_conductor_api::conductor::HttpGatewayConfig;
 use holochain_conductor_api::conductor::KeystoreConfig;
+use holochain_conductor_api::AppInterfaceInfo;
 use holochain_conductor_api::AppStatusFilter;
+use holochain_conductor_api::CellOpCounts;
 use holochain_conductor_api::FullIntegrationStateDump;
 use holochain_conductor_api::InstalledAppInfo;
 use holochain_conductor_api::IntegrationStateDump;
+use holochain_conductor_api::InterfaceStats;
+use holochain_conductor_api::SignedZomeCall;
+use holochain_conductor_api::ZomeCall;
 use holochain_keystore::lair_keystore::spawn_lair_keystore;
 use holochain_keystore::test_keystore::spawn_test_keystore;
 use holochain_keystore::MetaLairClient;
+use holochain_p2p::kitsune_p2p::dependencies::kitsune_p2p_types::config::LIVE_TUNING_PARAMS;
 use holochain_sqlite::prelude::*;
 use holochain_sqlite::sql::sql_cell::state_dump;
+use holochain_state::integrity::DbIntegrityReport;
 use holochain_state::prelude::from_blob;
 use holochain_state::prelude::StateMutationResult;
 use holochain_state::prelude::StateQueryResult;
+use holochain_state::snapshot::DbSnapshot;
+use holochain_state::snapshot::STATE_DUMP_TIMEOUT;
 use holochain_types::prelude::*;
 pub use holochain_types::share;
+use rusqlite::named_params;
 use rusqlite::Transaction;
 use std::collections::{HashMap, HashSet};
 use std::sync::atomic::AtomicBool;
@@ -123,6 +150,10 @@ where
 pub(crate) type StopBroadcaster = tokio::sync::broadcast::Sender<()>;
 pub(crate) type StopReceiver = tokio::sync::broadcast::Receiver<()>;
 
+/// How long a graceful shutdown waits for in-flight zome calls to finish
+/// before stopping the conductor's tasks anyway.
+pub const SHUTDOWN_DRAIN_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
 /// A Conductor is a group of [Cell]s
 #[derive(Clone)]
 pub struct Conductor<CA = CellConductorApi>
@@ -132,8 +163,12 @@ where
     /// The collection of cells associated with this Conductor
     cells: RwShare<HashMap<CellId, CellItem<CA>>>,
 
-    /// The config used to create this Conductor
-    pub config: ConductorConfig,
+    /// The config used to create this Conductor, updated in place by
+    /// [`ConductorHandleT::reload_config`](super::handle::ConductorHandleT::reload_config)
+    config: RwShare<ConductorConfig>,
+
+    /// The path the config was loaded from, if any, used when reloading it.
+    config_path: Option<std::path::PathBuf>,
 
     /// The map of dna hash spaces.
     pub(super) spaces: Spaces,
@@ -142,11 +177,36 @@ where
     /// tasks can check on the shutdown status
     shutting_down: Arc<AtomicBool>,
 
+    /// Set to true when the conductor has opened its interfaces and started
+    /// its apps, so the health endpoint can report it ready.
+    initialized: Arc<AtomicBool>,
+
+    /// Every zome call made through the conductor holds a read lock on this
+    /// for its duration, so that a graceful shutdown can wait on the write
+    /// lock until all in-flight calls have finished.
+    zome_call_gate: Arc<tokio::sync::RwLock<()>>,
+
+    /// The quotas of the installed apps and what they have used so far.
+    pub(super) quotas: Arc<QuotaTracker>,
+
+    /// Where the zome functions of the cells of installed apps may be
+    /// called from.
+    pub(super) zome_fn_access: Arc<ZomeFnAccessTracker>,
+
+    /// The nonces of the signed zome calls which haven't expired yet.
+    zome_call_nonces: Arc<NonceTracker>,
+
     /// The admin websocket ports this conductor has open.
     /// This exists so that we can run tests and bind to port 0, and find out
     /// the dynamically allocated port later.
     admin_websocket_ports: RwShare<Vec<u16>>,
 
+    /// The number of clients connected to each admin and app interface.
+    interface_connections: Arc<InterfaceConnections>,
+
+    /// The tasks the task manager is running.
+    pub(super) task_table: TaskTable,
+
     /// Collection app interface data, keyed by id
     app_interfaces: RwShare<HashMap<AppInterfaceId, AppInterfaceRuntime>>,
 
@@ -164,6 +224,12 @@ where
     holochain_p2p: holochain_p2p::HolochainP2pRef,
 
     post_commit: tokio::sync::mpsc::Sender<PostCommitArgs>,
+
+    /// The audit log of calls made to the conductor, if enabled.
+    pub(super) audit_log: Option<Arc<AuditLog>>,
+
+    /// The relay the conductor runs for other peers, if enabled.
+    relay: Option<Arc<Relay>>,
 }
 
 impl Conductor {
@@ -246,10 +312,104 @@ impl Conductor {
         }
     }
 
+    /// Mark the conductor as initialized, once its interfaces are open and
+    /// its apps have started.
+    pub(super) fn set_initialized(&self) {
+        self.initialized
+            .store(true, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    /// Is the conductor initialized and not shutting down.
+    pub(super) fn is_ready(&self) -> bool {
+        self.initialized.load(std::sync::atomic::Ordering::Relaxed) && self.check_running().is_ok()
+    }
+
     pub(super) fn ribosome_store(&self) -> &RwShare<RibosomeStore> {
         &self.ribosome_store
     }
 
+    /// Get a copy of the current config.
+    pub(super) fn config(&self) -> ConductorConfig {
+        self.config.share_ref(|c| c.clone())
+    }
+
+    /// The path the config was loaded from, if any.
+    pub(super) fn config_path(&self) -> Option<&std::path::PathBuf> {
+        self.config_path.as_ref()
+    }
+
+    /// Swap the runtime-safe settings listed in the report into the
+    /// current config, leaving everything else as it was at startup.
+    /// Changed network settings are pushed into the running network.
+    pub(super) async fn apply_reloaded_config(
+        &self,
+        new_config: ConductorConfig,
+        report: &ConfigReloadReport,
+    ) -> ConductorResult<()> {
+        let applied = |name: &str| report.applied.iter().any(|s| s == name);
+        if applied("log_filter") {
+            log_filter::set_log_filter(new_config.log_filter.as_deref())
+                .map_err(ConductorError::ConfigError)?;
+        }
+        let network = self.config.share_mut(|config| {
+            if applied("log_filter") {
+                config.log_filter = new_config.log_filter.clone();
+            }
+            if applied("admin_interfaces") {
+                config.admin_interfaces = new_config.admin_interfaces.clone();
+            }
+            if applied("dht_pruning") {
+                config.dht_pruning = new_config.dht_pruning.clone();
+            }
+            if applied("zome_call_limits") {
+                config.zome_call_limits = new_config.zome_call_limits.clone();
+                crate::core::ribosome::zome_call_limits::set_max_memory_bytes(
+                    config
+                        .zome_call_limits
+                        .as_ref()
+                        .and_then(|limits| limits.max_memory_bytes),
+                );
+            }
+            if applied("chain_head_coordination") {
+                config.chain_head_coordination = new_config.chain_head_coordination.clone();
+            }
+            let network_applied = [
+                "network.tuning_params",
+                "network.bootstrap_service",
+                "network.bootstrap_services",
+            ]
+            .iter()
+            .any(|name| applied(name));
+            if !network_applied {
+                return None;
+            }
+            let mut network = config.network.clone().unwrap_or_default();
+            let new_network = new_config.network.clone().unwrap_or_default();
+            network.tuning_params = Arc::new(
+                network
+                    .tuning_params
+                    .with_params_from(&new_network.tuning_params, LIVE_TUNING_PARAMS),
+            );
+            network.bootstrap_service = new_network.bootstrap_service;
+            network.bootstrap_services = new_network.bootstrap_services;
+            config.network = Some(network.clone());
+            Some(network)
+        });
+        for setting in &report.requires_restart {
+            tracing::warn!(
+                "Conductor config setting `{}` changed but requires a restart to take effect",
+                setting
+            );
+        }
+        if let Some(network) = network {
+            use holochain_p2p::HolochainP2pSender;
+            self.holochain_p2p
+                .update_network_config(network.tuning_params.clone(), network.bootstrap_urls())
+                .await?;
+        }
+        Ok(())
+    }
+
     /// Broadcasts the shutdown signal to all managed tasks.
     /// To actually wait for these tasks to complete, be sure to
     /// `take_shutdown_handle` to await for completion.
@@ -273,6 +433,249 @@ impl Conductor {
         });
     }
 
+    /// Begin a graceful shutdown.
+    ///
+    /// New zome calls and interface requests are refused straight away.
+    /// Zome calls which are already running are given up to `drain_timeout`
+    /// to finish, after which the stop signal is broadcast to all managed
+    /// tasks. Queue consumers only check for the stop signal between runs,
+    /// so any workflow which is mid-way through a batch will commit it
+    /// before exiting.
+    ///
+    /// Once the task manager has finished, call
+    /// [`Conductor::close_storage_and_network`] to complete the shutdown.
+    pub(super) async fn graceful_shutdown(&self, drain_timeout: std::time::Duration) {
+        self.shutting_down
+            .store(true, std::sync::atomic::Ordering::Relaxed);
+        tracing::info!("Graceful shutdown started, waiting for in-flight zome calls.");
+        if tokio::time::timeout(drain_timeout, self.zome_call_gate.write())
+            .await
+            .is_err()
+        {
+            tracing::warn!(
+                "Zome calls were still running after {:?}, shutting down anyway.",
+                drain_timeout
+            );
+        }
+        self.shutdown();
+    }
+
+    /// The final step of a graceful shutdown, once all managed tasks have
+    /// stopped: flush every database's write-ahead log to disk and close the
+    /// network connections.
+    pub(super) async fn close_storage_and_network(&self) -> ConductorResult<()> {
+        use ghost_actor::GhostControlSender;
+        self.spaces.checkpoint_all().await?;
+        if let Err(e) = self.holochain_p2p.ghost_actor_shutdown().await {
+            tracing::warn!(?e, "Network actor was already shut down");
+        }
+        if let Some(relay) = &self.relay {
+            relay.close().await;
+        }
+        Ok(())
+    }
+
+    /// Check a zome call signed by a client, returning the call to make.
+    /// See [`NonceTracker::verify`].
+    pub(super) async fn verify_signed_zome_call(
+        &self,
+        call: SignedZomeCall,
+    ) -> ConductorResult<ZomeCall> {
+        self.zome_call_nonces.verify(call).await
+    }
+
+    /// The relay's address, and what it has forwarded for each peer, as JSON.
+    pub(super) fn relay_stats(&self) -> ConductorResult<String> {
+        let relay = self
+            .relay
+            .as_ref()
+            .ok_or_else(|| ConductorError::other("This conductor isn't running a relay"))?;
+        serde_json::to_string_pretty(&relay.stats()).map_err(ConductorError::other)
+    }
+
+    /// Back up the source chains and integrated ops of a cell's DNA.
+    /// See [`Spaces::backup_space`].
+    pub(super) async fn backup_cell(
+        &self,
+        cell_id: &CellId,
+        path: &std::path::Path,
+    ) -> ConductorResult<()> {
+        self.spaces.backup_space(cell_id.dna_hash(), path).await
+    }
+
+    /// Restore a cell's DNA from a backup taken with [`Conductor::backup_cell`].
+    ///
+    /// No cells of the DNA may be running, as merging in an older copy of a
+    /// live source chain could fork it. If this is done before the app is
+    /// installed, genesis finds the restored chain and is skipped.
+    pub(super) async fn restore_cell(
+        &self,
+        cell_id: &CellId,
+        path: &std::path::Path,
+    ) -> ConductorResult<()> {
+        let dna_hash = cell_id.dna_hash();
+        if self
+            .cells
+            .share_ref(|c| c.keys().any(|id| id.dna_hash() == dna_hash))
+        {
+            return Err(ConductorError::RestoreWhileRunning(dna_hash.clone()));
+        }
+        self.spaces.restore_space(dna_hash, path).await
+    }
+
+    /// Export the integrated ops held for a cell's DNA to a shard file.
+    pub(super) async fn export_dht_ops(
+        &self,
+        cell_id: &CellId,
+        path: &std::path::Path,
+    ) -> ConductorResult<usize> {
+        self.spaces.export_dht_ops(cell_id.dna_hash(), path).await
+    }
+
+    /// Write the source chains of these cells to a new JSON file.
+    pub(super) async fn export_source_chains(
+        &self,
+        cell_ids: &[CellId],
+        path: &std::path::Path,
+    ) -> ConductorResult<()> {
+        #[derive(serde::Serialize)]
+        struct SourceChainExport {
+            cell_id: CellId,
+            source_chain: holochain_state::source_chain::SourceChainJsonDump,
+        }
+
+        let mut export = Vec::with_capacity(cell_ids.len());
+        for cell_id in cell_ids {
+            let authored_db = self.spaces.authored_db(cell_id.dna_hash())?;
+            let author = cell_id.agent_pubkey().clone();
+            let source_chain =
+                holochain_state::source_chain::dump_state(authored_db.into(), author)
+                    .await
+                    .map_err(ConductorError::other)?;
+            export.push(SourceChainExport {
+                cell_id: cell_id.clone(),
+                source_chain,
+            });
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(path)?;
+        serde_json::to_writer_pretty(std::io::BufWriter::new(file), &export)
+            .map_err(std::io::Error::from)?;
+        Ok(())
+    }
+
+    /// Delete the source chains of these cells, which must no longer be
+    /// running, from the authored databases of their DNAs.
+    pub(super) async fn delete_source_chains(&self, cell_ids: Vec<CellId>) -> ConductorResult<()> {
+        for cell_id in cell_ids {
+            let author = cell_id.agent_pubkey().clone();
+            let deleted = self
+                .spaces
+                .authored_db(cell_id.dna_hash())?
+                .async_commit(move |txn| {
+                    holochain_state::mutations::delete_source_chain(txn, &author)
+                })
+                .await?;
+            tracing::info!(
+                ?cell_id,
+                deleted,
+                "Deleted the source chain of an uninstalled cell"
+            );
+        }
+        Ok(())
+    }
+
+    /// Import a shard file into a cell's DNA. The cell must be running so
+    /// that the imported ops are validated.
+    pub(super) async fn import_dht_ops(
+        &self,
+        cell_id: &CellId,
+        path: &std::path::Path,
+    ) -> ConductorResult<usize> {
+        self.cell_by_id(cell_id)?;
+        self.spaces.import_dht_ops(cell_id.dna_hash(), path).await
+    }
+
+    /// Check the databases of a cell's DNA for corruption, optionally
+    /// repairing what can be. See [`Spaces::check_integrity`].
+    pub(super) async fn check_db_integrity(
+        &self,
+        cell_id: &CellId,
+        repair: bool,
+    ) -> ConductorResult<DbIntegrityReport> {
+        let report = self
+            .spaces
+            .check_integrity(cell_id.dna_hash(), repair)
+            .await?;
+        if !report.is_healthy() {
+            tracing::warn!(?cell_id, ?report, "Database integrity check found problems");
+        }
+        Ok(report)
+    }
+
+    /// Roll back a countersigned action left at the head of a cell's chain
+    /// by a session which failed, once the agent activity authorities of
+    /// the session's signers confirm that it did.
+    /// See [`holochain_state::source_chain::rollback_uncommitted`].
+    pub(super) async fn rollback_uncommitted(
+        &self,
+        cell_id: &CellId,
+    ) -> ConductorResult<Option<ActionHash>> {
+        let author = Arc::new(cell_id.agent_pubkey().clone());
+        let authored_db = self.spaces.authored_db(cell_id.dna_hash())?;
+        let session = authored_db
+            .async_reader({
+                let author = author.clone();
+                move |txn| {
+                    holochain_state::source_chain::uncommitted_countersigning_session(&txn, author)
+                }
+            })
+            .await
+            .map_err(WorkflowError::from)?;
+        let (action_hash, entry_hash, session_data) = match session {
+            Some(session) => session,
+            None => return Ok(None),
+        };
+        let network = self.cell_by_id(cell_id)?.holochain_p2p_dna().clone();
+        if !countersigning_session_failed(&network, entry_hash, &session_data).await? {
+            return Err(ConductorError::CountersigningSessionNotFailed(action_hash));
+        }
+        let rolled_back = authored_db
+            .async_commit({
+                let action_hash = action_hash.clone();
+                move |txn| {
+                    holochain_state::source_chain::rollback_uncommitted(txn, author, &action_hash)
+                }
+            })
+            .await
+            .map_err(WorkflowError::from)?;
+        if !rolled_back {
+            return Ok(None);
+        }
+        tracing::warn!(?cell_id, ?action_hash, "Rolled back an uncommitted action");
+        Ok(Some(action_hash))
+    }
+
+    /// Back up the whole conductor. See [`Spaces::backup_all`].
+    pub(super) async fn backup_conductor(&self, path: &std::path::Path) -> ConductorResult<()> {
+        self.spaces.backup_all(path).await
+    }
+
+    /// Wait for permission to run a zome call. The returned guard must be
+    /// held until the call completes.
+    /// Fails if the conductor is shutting down.
+    pub(super) async fn zome_call_permit(
+        &self,
+    ) -> ConductorResult<tokio::sync::OwnedRwLockReadGuard<()>> {
+        self.check_running()?;
+        Ok(self.zome_call_gate.clone().read_owned().await)
+    }
+
     /// Return the handle which waits for the task manager task to complete
     pub(super) fn take_shutdown_handle(&self) -> Option<TaskManagerRunHandle> {
         self.task_manager
@@ -299,19 +702,28 @@ impl Conductor {
             let admin_api = admin_api.clone();
             let stop_tx = stop_tx.clone();
             async move {
-                match driver {
-                    InterfaceDriver::Websocket { port } => {
-                        let (listener_handle, listener) = spawn_websocket_listener(port).await?;
-                        let port = listener_handle.local_addr().port().unwrap_or(port);
-                        let handle: ManagedTaskHandle = spawn_admin_interface_task(
-                            listener_handle,
-                            listener,
-                            admin_api.clone(),
-                            stop_tx.subscribe(),
-                        )?;
-                        InterfaceResult::Ok((port, handle))
+                let (listener_handle, listener) = spawn_websocket_listener(&driver).await?;
+                // Local sockets have no port.
+                let (port, name) = match &driver {
+                    InterfaceDriver::Websocket { port, .. } => {
+                        let port = listener_handle.local_addr().port().unwrap_or(*port);
+                        (Some(port), format!("admin interface, port {}", port))
                     }
-                }
+                    InterfaceDriver::LocalSocket { path, .. } => (
+                        None,
+                        format!("admin interface, local socket {}", path.display()),
+                    ),
+                };
+                let connections = ConnectionCounter::default();
+                let handle: ManagedTaskHandle = spawn_admin_interface_task(
+                    listener_handle,
+                    listener,
+                    admin_api.clone(),
+                    connections.clone(),
+                    stop_tx.subscribe(),
+                )?;
+                let driver = driver.with_port(port.unwrap_or_default());
+                InterfaceResult::Ok((port, name, handle, driver, connections))
             }
         };
 
@@ -337,13 +749,11 @@ impl Conductor {
             .await?;
 
             // Now that tasks are spawned, register them with the TaskManager
-            for (port, handle) in handles {
-                ports.push(port);
-                self.manage_task(ManagedTaskAdd::ignore(
-                    handle,
-                    &format!("admin interface, port {}", port),
-                ))
-                .await?
+            for (port, name, handle, driver, connections) in handles {
+                ports.extend(port);
+                self.interface_connections.add(driver, true, connections);
+                self.manage_task(ManagedTaskAdd::ignore(handle, &name))
+                    .await?
             }
             for p in ports {
                 self.add_admin_port(p);
@@ -354,35 +764,60 @@ impl Conductor {
 
     pub(super) async fn add_app_interface_via_handle(
         &self,
-        port: either::Either<u16, AppInterfaceId>,
+        interface: either::Either<InterfaceDriver, (AppInterfaceId, InterfaceDriver)>,
         handle: ConductorHandle,
     ) -> ConductorResult<u16> {
-        let interface_id = match port {
-            either::Either::Left(port) => AppInterfaceId::new(port),
-            either::Either::Right(id) => id,
+        let (interface_id, driver) = match interface {
+            either::Either::Left(driver) => (AppInterfaceId::new(driver.port()), driver),
+            either::Either::Right((id, driver)) => {
+                let driver = driver.with_port(id.port());
+                (id, driver)
+            }
         };
-        let port = interface_id.port();
-        tracing::debug!("Attaching interface {}", port);
-        let app_api = RealAppInterfaceApi::new(handle);
+        tracing::debug!("Attaching interface {:?}", driver);
+        let app_api = RealAppInterfaceApi::new(handle)
+            .require_signed_zome_calls(driver.require_signed_zome_calls());
         // This receiver is thrown away because we can produce infinite new
         // receivers from the Sender
         let (signal_tx, _r) = tokio::sync::broadcast::channel(SIGNAL_BUFFER_SIZE);
-        let stop_rx = self.task_manager.share_ref(|tm| {
+        // The interface is stopped either when it's detached or when the
+        // conductor shuts down.
+        let (stop_tx, stop_rx) = tokio::sync::broadcast::channel(1);
+        let mut shutdown_rx = self.task_manager.share_ref(|tm| {
             tm.as_ref()
                 .expect("Task manager not initialized")
                 .task_stop_broadcaster()
                 .subscribe()
         });
-        let (port, task) = spawn_app_interface_task(port, app_api, signal_tx.clone(), stop_rx)
-            .await
-            .map_err(Box::new)?;
+        tokio::task::spawn({
+            let stop_tx = stop_tx.clone();
+            async move {
+                let _ = shutdown_rx.recv().await;
+                let _ = stop_tx.send(());
+            }
+        });
+        let connections = ConnectionCounter::default();
+        let (port, task) = spawn_app_interface_task(
+            &driver,
+            app_api,
+            signal_tx.clone(),
+            connections.clone(),
+            stop_rx,
+        )
+        .await
+        .map_err(Box::new)?;
+        let name = match &driver {
+            InterfaceDriver::Websocket { .. } => format!("app interface, port {}", port),
+            InterfaceDriver::LocalSocket { path, .. } => {
+                format!("app interface, local socket {}", path.display())
+            }
+        };
         // TODO: RELIABILITY: Handle this task by restarting it if it fails and log the error
-        self.manage_task(ManagedTaskAdd::ignore(
-            task,
-            &format!("app interface, port {}", port),
-        ))
-        .await?;
-        let interface = AppInterfaceRuntime::Websocket { signal_tx };
+        self.manage_task(ManagedTaskAdd::ignore(task, &name))
+            .await?;
+        self.interface_connections
+            .add(driver.with_port(port), false, connections);
+        let interface = AppInterfaceRuntime::Websocket { signal_tx, stop_tx };
 
         self.app_interfaces.share_mut(|app_interfaces| {
             if app_interfaces.contains_key(&interface_id) {
@@ -394,22 +829,125 @@ impl Conductor {
             app_interfaces.insert(interface_id.clone(), interface);
             Ok(())
         })?;
-        let config = AppInterfaceConfig::websocket(port);
+        let config = AppInterfaceConfig {
+            signal_subscriptions: HashMap::new(),
+            driver: driver.with_port(port),
+        };
         self.update_state(|mut state| {
             state.app_interfaces.insert(interface_id, config);
             Ok(state)
         })
         .await?;
-        tracing::debug!("App interface added at port: {}", port);
+        tracing::debug!("App interface added: {}", name);
+        Ok(port)
+    }
+
+    /// Start the HTTP gateway for making zome calls.
+    pub(super) async fn start_http_gateway_via_handle(
+        &self,
+        config: HttpGatewayConfig,
+        handle: ConductorHandle,
+    ) -> ConductorResult<u16> {
+        let stop_rx = self.task_manager.share_ref(|tm| {
+            tm.as_ref()
+                .expect("Task manager not initialized")
+                .task_stop_broadcaster()
+                .subscribe()
+        });
+        let (port, task) = spawn_http_gateway_task(config, handle, stop_rx)
+            .await
+            .map_err(Box::new)?;
+        self.manage_task(ManagedTaskAdd::ignore(
+            task,
+            &format!("http gateway, port {}", port),
+        ))
+        .await?;
         Ok(port)
     }
 
+    /// Start the health endpoint for service managers and load balancers.
+    pub(super) async fn start_health_endpoint_via_handle(
+        &self,
+        config: HealthEndpointConfig,
+        handle: ConductorHandle,
+    ) -> ConductorResult<u16> {
+        let stop_rx = self.task_manager.share_ref(|tm| {
+            tm.as_ref()
+                .expect("Task manager not initialized")
+                .task_stop_broadcaster()
+                .subscribe()
+        });
+        let (port, task) = spawn_health_endpoint_task(config, handle, stop_rx)
+            .await
+            .map_err(Box::new)?;
+        self.manage_task(ManagedTaskAdd::ignore(
+            task,
+            &format!("health endpoint, port {}", port),
+        ))
+        .await?;
+        Ok(port)
+    }
+
+    /// The number of clients connected to each interface.
+    pub(super) fn interface_stats(&self) -> Vec<InterfaceStats> {
+        self.interface_connections.stats()
+    }
+
+    /// Close the app interface on a port and its connections, and remove it
+    /// from the state so it isn't started again.
+    pub(super) async fn remove_app_interface(&self, port: u16) -> ConductorResult<()> {
+        let (_, removed) = self
+            .update_state_prime(move |mut state| {
+                let id = state
+                    .app_interfaces
+                    .iter()
+                    .find(|(_, config)| {
+                        matches!(config.driver, InterfaceDriver::Websocket { .. })
+                            && config.driver.port() == port
+                    })
+                    .map(|(id, _)| id.clone())
+                    .ok_or(ConductorError::AppInterfaceNotFound(port))?;
+                let config = state.app_interfaces.remove(&id);
+                Ok((state, (id, config)))
+            })
+            .await?;
+        let (id, config) = removed;
+        if let Some(interface) = self
+            .app_interfaces
+            .share_mut(|app_interfaces| app_interfaces.remove(&id))
+        {
+            interface.stop();
+        }
+        if let Some(config) = config {
+            self.interface_connections.remove(&config.driver);
+        }
+        tracing::debug!("App interface on port {} removed", port);
+        Ok(())
+    }
+
+    /// The settings of the app interfaces, including those on local sockets.
+    pub(super) async fn app_interface_settings(&self) -> ConductorResult<Vec<AppInterfaceInfo>> {
+        let mut interfaces: Vec<_> = self
+            .get_state()
+            .await?
+            .app_interfaces
+            .into_values()
+            .map(|config| AppInterfaceInfo {
+                driver: config.driver,
+                signal_subscriptions: config.signal_subscriptions,
+            })
+            .collect();
+        interfaces.sort_by_key(|interface| interface.driver.port());
+        Ok(interfaces)
+    }
+
     pub(super) async fn list_app_interfaces(&self) -> ConductorResult<Vec<u16>> {
         Ok(self
             .get_state()
             .await?
             .app_interfaces
             .values()
+            .filter(|config| matches!(config.driver, InterfaceDriver::Websocket { .. }))
             .map(|config| config.driver.port())
             .collect())
     }
@@ -451,10 +989,10 @@ impl Conductor {
         &self,
         handle: ConductorHandle,
     ) -> ConductorResult<()> {
-        for id in self.get_state().await?.app_interfaces.keys().cloned() {
+        for (id, config) in self.get_state().await?.app_interfaces {
             tracing::debug!("Starting up app interface: {:?}", id);
             let _ = self
-                .add_app_interface_via_handle(either::Right(id), handle.clone())
+                .add_app_interface_via_handle(either::Right((id, config.driver)), handle.clone())
                 .await?;
         }
         Ok(())
@@ -742,6 +1280,91 @@ impl Conductor {
         }
     }
 
+    /// Find the app and role whose deferred cell has this ID.
+    pub(super) async fn find_deferred_cell(
+        &self,
+        cell_id: &CellId,
+    ) -> ConductorResult<Option<(InstalledAppId, AppRoleId)>> {
+        Ok(self
+            .get_state()
+            .await?
+            .installed_apps()
+            .iter()
+            .find_map(|(app_id, app)| {
+                app.deferred_cells()
+                    .find(|(_, id)| *id == cell_id)
+                    .map(|(role_id, _)| (app_id.clone(), role_id.clone()))
+            }))
+    }
+
+    /// Record that the deferred cell of an app's role has been created.
+    pub(super) async fn provision_deferred_cell_in_app(
+        &self,
+        app_id: InstalledAppId,
+        role_id: AppRoleId,
+    ) -> ConductorResult<CellId> {
+        let (_, cell_id) = self
+            .update_state_prime(move |mut state| {
+                let app = state
+                    .installed_apps_mut()
+                    .get_mut(&app_id)
+                    .ok_or_else(|| ConductorError::AppNotInstalled(app_id.clone()))?;
+                let cell_id = app.provision_deferred_cell(&role_id)?;
+                Ok((state, cell_id))
+            })
+            .await?;
+        Ok(cell_id)
+    }
+
+    /// Replace the quota of an installed app.
+    pub(super) async fn set_app_quota(
+        &self,
+        app_id: InstalledAppId,
+        quota: AppQuota,
+    ) -> ConductorResult<()> {
+        self.update_state(move |mut state| {
+            state
+                .installed_apps_mut()
+                .get_mut(&app_id)
+                .ok_or_else(|| ConductorError::AppNotInstalled(app_id.clone()))?
+                .set_quota(quota);
+            Ok(state)
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Check that an app may have another clone cell.
+    pub(super) async fn check_clone_cell_quota(
+        &self,
+        app_id: &InstalledAppId,
+    ) -> ConductorResult<()> {
+        let state = self.get_state().await?;
+        let app = state.get_app(app_id)?;
+        match app.quota().max_clone_cells {
+            Some(max) if app.cloned_cells().count() >= max as usize => {
+                Err(ConductorError::QuotaExceeded(AppQuotaExceeded {
+                    installed_app_id: app_id.clone(),
+                    exceeded: QuotaExceeded::CloneCells { max },
+                }))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// The bytes the databases of these cells take up on disk. Cells of the
+    /// same DNA share their databases, so each DNA's are only counted once.
+    pub(super) async fn disk_bytes(&self, cell_ids: &[CellId]) -> ConductorResult<u64> {
+        let dna_hashes: HashSet<_> = cell_ids.iter().map(CellId::dna_hash).collect();
+        let mut bytes = 0;
+        for dna_hash in dna_hashes {
+            bytes += self.spaces.authored_db(dna_hash)?.size_bytes().await?;
+            bytes += self.spaces.dht_db(dna_hash)?.size_bytes().await?;
+            bytes += self.spaces.cache(dna_hash)?.size_bytes().await?;
+        }
+        Ok(bytes)
+    }
+
     /// Associate a Cell with an existing App
     pub(super) async fn add_clone_cell_to_app(
         &self,
@@ -791,6 +1414,26 @@ impl Conductor {
         Ok(cell_id)
     }
 
+    /// Add a clone cell of a DNA which already exists, e.g. one which another
+    /// agent created and invited this one to, to an app's role.
+    pub(super) async fn add_existing_clone_cell_to_app(
+        &self,
+        app_id: InstalledAppId,
+        role_id: AppRoleId,
+        cell_id: CellId,
+    ) -> ConductorResult<()> {
+        self.update_state_prime(move |mut state| {
+            if let Some(app) = state.installed_apps_mut().get_mut(&app_id) {
+                app.add_clone(&role_id, cell_id)?;
+                Ok((state, ()))
+            } else {
+                Err(ConductorError::AppNotRunning(app_id.clone()))
+            }
+        })
+        .await?;
+        Ok(())
+    }
+
     pub(super) async fn load_wasms_into_dna_files(
         &self,
     ) -> ConductorResult<(
@@ -959,6 +1602,16 @@ impl Conductor {
         Ok(zome_defs)
     }
 
+    /// Store a changed [`DnaDef`] whose hash is the same, so the change
+    /// outlives a restart.
+    pub(super) async fn update_dna_def(&self, dna: DnaDefHashed) -> ConductorResult<()> {
+        self.spaces
+            .wasm_db
+            .async_commit(move |txn| holochain_state::dna_def::update(txn, &dna))
+            .await?;
+        Ok(())
+    }
+
     pub(super) fn list_cell_ids(&self, filter: Option<CellStatusFilter>) -> Vec<CellId> {
         self.cells.share_ref(|cells| {
             cells
@@ -1112,6 +1765,7 @@ pub(super) async fn genesis_cells(
         let space = conductor
             .get_or_create_space(cell_id.dna_hash())
             .map_err(|e| CellError::FailedToCreateDnaSpace(e.into()));
+        let failed_cell_id = cell_id.clone();
         async {
             let space = space?;
             let authored_db = space.authored_db;
@@ -1138,6 +1792,7 @@ pub(super) async fn genesis_cells(
             .and_then(|result| async move { result.map(|_| cell_id) })
             .await
         }
+        .map_err(move |e| (failed_cell_id, e))
     });
     let (success, errors): (Vec<_>, Vec<_>) = futures::future::join_all(cells_tasks)
         .await
@@ -1201,39 +1856,76 @@ pub async fn integration_dump(
         .await
 }
 
+/// Count the ops a cell has published and the ops integrated for its DNA.
+pub async fn cell_op_counts(
+    cell_id: CellId,
+    authored_db: &DbRead<DbKindAuthored>,
+    dht_db: &DbRead<DbKindDht>,
+) -> ConductorApiResult<CellOpCounts> {
+    let author = cell_id.agent_pubkey().clone();
+    let published = authored_db
+        .async_reader(move |txn| {
+            let published = txn.query_row(
+                "
+                SELECT count(DhtOp.hash) FROM DhtOp
+                JOIN Action ON DhtOp.action_hash = Action.hash
+                WHERE Action.author = :author
+                AND (DhtOp.type != :store_entry OR Action.private_entry = 0)
+                ",
+                named_params! {
+                    ":author": author,
+                    ":store_entry": DhtOpType::StoreEntry,
+                },
+                |row| row.get(0),
+            )?;
+            ConductorApiResult::Ok(published)
+        })
+        .await?;
+    let integrated = dht_db
+        .async_reader(move |txn| {
+            let integrated = txn.query_row(
+                "SELECT count(hash) FROM DhtOp WHERE when_integrated IS NOT NULL",
+                [],
+                |row| row.get(0),
+            )?;
+            ConductorApiResult::Ok(integrated)
+        })
+        .await?;
+    Ok(CellOpCounts {
+        cell_id,
+        published,
+        integrated,
+    })
+}
+
 /// Dump the full integration json state.
 /// Careful! This will return a lot of data.
+///
+/// Each part is read separately from one [`DbSnapshot`], so the parts and
+/// the cursor agree without the whole dump being read in one go.
 pub async fn full_integration_dump(
     vault: &DbRead<DbKindDht>,
     dht_ops_cursor: Option<u64>,
 ) -> ConductorApiResult<FullIntegrationStateDump> {
-    vault
-        .async_reader(move |txn| {
-            let integrated =
-                query_dht_ops_from_statement(&txn, state_dump::DHT_OPS_INTEGRATED, dht_ops_cursor)?;
-
-            let validation_limbo = query_dht_ops_from_statement(
-                &txn,
-                state_dump::DHT_OPS_IN_VALIDATION_LIMBO,
-                dht_ops_cursor,
-            )?;
-
-            let integration_limbo = query_dht_ops_from_statement(
-                &txn,
-                state_dump::DHT_OPS_IN_INTEGRATION_LIMBO,
-                dht_ops_cursor,
-            )?;
-
-            let dht_ops_cursor = txn.query_row(state_dump::DHT_OPS_ROW_ID, [], |row| row.get(0))?;
-
-            ConductorApiResult::Ok(FullIntegrationStateDump {
-                validation_limbo,
-                integration_limbo,
-                integrated,
-                dht_ops_cursor,
-            })
+    let snapshot = DbSnapshot::open(vault, STATE_DUMP_TIMEOUT).await?;
+    let read_ops = |stmt_str: &'static str| {
+        snapshot.read(move |txn| query_dht_ops_from_statement(txn, stmt_str, dht_ops_cursor))
+    };
+    let integrated = read_ops(state_dump::DHT_OPS_INTEGRATED).await?;
+    let validation_limbo = read_ops(state_dump::DHT_OPS_IN_VALIDATION_LIMBO).await?;
+    let integration_limbo = read_ops(state_dump::DHT_OPS_IN_INTEGRATION_LIMBO).await?;
+    let dht_ops_cursor = snapshot
+        .read(|txn| {
+            ConductorApiResult::Ok(txn.query_row(state_dump::DHT_OPS_ROW_ID, [], |row| row.get(0))?)
         })
-        .await
+        .await?;
+
+    Ok(FullIntegrationStateDump {
+        validation_limbo,
+        integration_limbo,
+        integrated,
+        dht_ops_cursor,
+    })
 }
 
 fn query_dht_ops_from_statement(
@@ -1276,17 +1968,56 @@ impl Conductor {
     #[allow(clippy::too_many_arguments)]
     async fn new(
         config: ConductorConfig,
+        config_path: Option<std::path::PathBuf>,
         ribosome_store: RwShare<RibosomeStore>,
         keystore: MetaLairClient,
         holochain_p2p: holochain_p2p::HolochainP2pRef,
         spaces: Spaces,
         post_commit: tokio::sync::mpsc::Sender<PostCommitArgs>,
     ) -> ConductorResult<Self> {
+        crate::core::ribosome::module_cache::set_cache_dir(
+            &std::path::PathBuf::from(config.environment_path.clone()).join("wasm-cache"),
+        );
+        if let Some(instance_pool) = config.instance_pool.clone() {
+            crate::core::ribosome::instance_pool::configure(instance_pool);
+        }
+        crate::core::ribosome::zome_call_limits::set_max_memory_bytes(
+            config
+                .zome_call_limits
+                .as_ref()
+                .and_then(|limits| limits.max_memory_bytes),
+        );
+        if let Some(entry_compression) = &config.entry_compression {
+            holochain_state::compression::configure(entry_compression.threshold_bytes);
+        }
+        let audit_log = config
+            .audit_log
+            .clone()
+            .map(|audit_log| {
+                AuditLog::open(
+                    audit_log,
+                    &std::path::PathBuf::from(config.environment_path.clone()),
+                )
+            })
+            .transpose()?
+            .map(Arc::new);
+        let relay = match &config.relay {
+            Some(relay_config) => Some(Arc::new(spawn_relay(relay_config, &config).await?)),
+            None => None,
+        };
         Ok(Self {
             spaces,
             cells: RwShare::new(HashMap::new()),
-            config,
+            config: RwShare::new(config),
+            config_path,
             shutting_down: Arc::new(AtomicBool::new(false)),
+            initialized: Arc::new(AtomicBool::new(false)),
+            zome_call_gate: Arc::new(tokio::sync::RwLock::new(())),
+            quotas: Arc::new(QuotaTracker::default()),
+            zome_fn_access: Arc::new(ZomeFnAccessTracker::default()),
+            zome_call_nonces: Arc::new(NonceTracker::default()),
+            interface_connections: Arc::new(InterfaceConnections::default()),
+            task_table: TaskTable::default(),
             app_interfaces: RwShare::new(HashMap::new()),
             task_manager: RwShare::new(None),
             admin_websocket_ports: RwShare::new(Vec::new()),
@@ -1294,6 +2025,8 @@ impl Conductor {
             keystore,
             holochain_p2p,
             post_commit,
+            audit_log,
+            relay,
         })
     }
 
@@ -1328,7 +2061,10 @@ impl Conductor {
     where
         F: FnOnce(ConductorState) -> ConductorResult<ConductorState> + 'static,
     {
-        self.spaces.update_state(f).await
+        let state = self.spaces.update_state(f).await?;
+        self.quotas.refresh(&state);
+        self.zome_fn_access.refresh(&state);
+        Ok(state)
     }
 
     /// Update the internal state with a pure function mapping old state to new,
@@ -1340,7 +2076,10 @@ impl Conductor {
         O: Send + 'static,
     {
         self.check_running()?;
-        self.spaces.update_state_prime(f).await
+        let (state, output) = self.spaces.update_state_prime(f).await?;
+        self.quotas.refresh(&state);
+        self.zome_fn_access.refresh(&state);
+        Ok((state, output))
     }
 
     fn add_admin_port(&self, port: u16) {
@@ -1363,7 +2102,12 @@ impl Conductor {
 }
 
 mod builder {
+    use holochain_conductor_api::conductor::DnaNetworkBackendConfig;
+    use holochain_conductor_api::conductor::NetworkBackendConfig;
     use holochain_p2p::dht::ArqStrat;
+    use holochain_p2p::event::HolochainP2pEventReceiver;
+    use holochain_p2p::HolochainP2pRef;
+    use holochain_p2p::InMemoryNetwork;
 
     use super::*;
     use crate::conductor::handle::DevSettings;
@@ -1376,12 +2120,17 @@ mod builder {
     pub struct ConductorBuilder {
         /// The configuration
         pub config: ConductorConfig,
+        /// The path the configuration was loaded from, if any
+        pub config_path: Option<std::path::PathBuf>,
         /// The RibosomeStore (mockable)
         pub ribosome_store: RibosomeStore,
         /// For new lair, passphrase is required
         pub passphrase: Option<sodoken::BufRead>,
         /// Optional keystore override
         pub keystore: Option<MetaLairClient>,
+        /// Optional network backend override, used instead of the one
+        /// chosen in the config
+        pub network: Option<(HolochainP2pRef, HolochainP2pEventReceiver)>,
         #[cfg(any(test, feature = "test_utils"))]
         /// Optional state override (for testing)
         pub state: Option<ConductorState>,
@@ -1404,12 +2153,25 @@ mod builder {
             self
         }
 
+        /// Set the path the config was loaded from, so that it can be reloaded
+        pub fn config_path(mut self, config_path: Option<std::path::PathBuf>) -> Self {
+            self.config_path = config_path;
+            self
+        }
+
         /// Set the passphrase for use in keystore initialization
         pub fn passphrase(mut self, passphrase: Option<sodoken::BufRead>) -> Self {
             self.passphrase = passphrase;
             self
         }
 
+        /// Use an already spawned network backend instead of the one chosen
+        /// in the config, for backends the config doesn't know about
+        pub fn network(mut self, network: (HolochainP2pRef, HolochainP2pEventReceiver)) -> Self {
+            self.network = Some(network);
+            self
+        }
+
         /// Initialize a "production" Conductor
         pub async fn build(self) -> ConductorResult<ConductorHandle> {
             cfg_if::cfg_if! {
@@ -1448,41 +2210,35 @@ mod builder {
             let Self {
                 ribosome_store,
                 config,
+                config_path,
+                network,
                 ..
             } = self;
 
             let ribosome_store = RwShare::new(ribosome_store);
 
-            let spaces = Spaces::new(&config)?;
-            let tag = spaces.get_state().await?.tag().clone();
-
-            let network_config = config.network.clone().unwrap_or_default();
-            let (cert_digest, cert, cert_priv_key) =
-                keystore.get_or_create_tls_cert_by_tag(tag.0).await?;
-            let tls_config =
-                holochain_p2p::kitsune_p2p::dependencies::kitsune_p2p_types::tls::TlsConfig {
-                    cert,
-                    cert_priv_key,
-                    cert_digest,
-                };
-            let strat =
-                ArqStrat::from_params(network_config.tuning_params.gossip_redundancy_target);
+            if let Some(db_encryption) = &config.db_encryption {
+                crate::conductor::db_encryption::unlock_databases(
+                    &keystore,
+                    config.environment_path.as_ref(),
+                    db_encryption,
+                )
+                .await?;
+            }
 
-            let host = KitsuneHostImpl::new(
-                spaces.clone(),
-                ribosome_store.clone(),
-                network_config.tuning_params.clone(),
-                strat,
-            );
+            let spaces = Spaces::new(&config)?;
+            spaces.migrate_state().await?;
 
             let (holochain_p2p, p2p_evt) =
-                holochain_p2p::spawn_holochain_p2p(network_config, tls_config, host).await?;
+                Self::spawn_network(network, &config, &spaces, &ribosome_store, Some(&keystore))
+                    .await?;
 
             let (post_commit_sender, post_commit_receiver) =
                 tokio::sync::mpsc::channel(POST_COMMIT_CHANNEL_BOUND);
 
             let conductor = Conductor::new(
                 config.clone(),
+                config_path,
                 ribosome_store,
                 keystore,
                 holochain_p2p,
@@ -1519,6 +2275,125 @@ mod builder {
             Self::finish(handle, config, p2p_evt, post_commit_receiver).await
         }
 
+        /// Spawn the network backend chosen in the config, unless one was given.
+        /// Kitsune uses the operator's own TLS cert if there is one, otherwise
+        /// one from the keystore, or an ephemeral one if there is no keystore.
+        async fn spawn_network(
+            network: Option<(HolochainP2pRef, HolochainP2pEventReceiver)>,
+            config: &ConductorConfig,
+            spaces: &Spaces,
+            ribosome_store: &RwShare<RibosomeStore>,
+            keystore: Option<&MetaLairClient>,
+        ) -> ConductorResult<(HolochainP2pRef, HolochainP2pEventReceiver)> {
+            if let Some(network) = network {
+                return Ok(network);
+            }
+            let default_backend = config.network_backend.clone().unwrap_or_default();
+            let default = Self::spawn_network_backend(
+                &default_backend,
+                config,
+                spaces,
+                ribosome_store,
+                keystore,
+            )
+            .await?;
+
+            // The DNAs which use each of the other backends.
+            let mut others: Vec<(&NetworkBackendConfig, Vec<DnaHash>)> = Vec::new();
+            for DnaNetworkBackendConfig { dna_hash, backend } in &config.dna_network_backends {
+                if *backend == default_backend {
+                    continue;
+                }
+                match others.iter_mut().find(|(b, _)| *b == backend) {
+                    Some((_, dna_hashes)) => dna_hashes.push(dna_hash.clone()),
+                    None => others.push((backend, vec![dna_hash.clone()])),
+                }
+            }
+            if others.is_empty() {
+                return Ok(default);
+            }
+            let mut other_networks = Vec::with_capacity(others.len());
+            for (backend, dna_hashes) in others {
+                let network =
+                    Self::spawn_network_backend(backend, config, spaces, ribosome_store, keystore)
+                        .await?;
+                other_networks.push((dna_hashes, network));
+            }
+            Ok(holochain_p2p::spawn_per_dna_holochain_p2p(default, other_networks).await?)
+        }
+
+        async fn spawn_network_backend(
+            backend: &NetworkBackendConfig,
+            config: &ConductorConfig,
+            spaces: &Spaces,
+            ribosome_store: &RwShare<RibosomeStore>,
+            keystore: Option<&MetaLairClient>,
+        ) -> ConductorResult<(HolochainP2pRef, HolochainP2pEventReceiver)> {
+            use holochain_p2p::kitsune_p2p::dependencies::kitsune_p2p_types::tls::TlsConfig;
+
+            match backend {
+                NetworkBackendConfig::Kitsune => (),
+                NetworkBackendConfig::InMemory { name } => {
+                    return Ok(holochain_p2p::spawn_in_memory_holochain_p2p(
+                        InMemoryNetwork::named(name),
+                    )
+                    .await?);
+                }
+                #[cfg(feature = "libp2p")]
+                NetworkBackendConfig::Libp2p {
+                    listen_addresses,
+                    bootstrap_peers,
+                } => {
+                    return Ok(holochain_p2p::spawn_libp2p_holochain_p2p(
+                        holochain_p2p::Libp2pConfig {
+                            listen_addresses: listen_addresses.clone(),
+                            bootstrap_peers: bootstrap_peers.clone(),
+                        },
+                    )
+                    .await?);
+                }
+                #[cfg(not(feature = "libp2p"))]
+                NetworkBackendConfig::Libp2p { .. } => {
+                    return Err(ConductorError::other(
+                        "the libp2p network backend needs holochain to be built with the \
+                        `libp2p` feature",
+                    ));
+                }
+            }
+
+            let network_config = config.network.clone().unwrap_or_default();
+            let tls_config = match (&network_config.tls_cert_path, keystore) {
+                // use the operator's own cert
+                (Some(path), _) => TlsConfig::load_file(path)
+                    .await
+                    .map_err(ConductorError::other)?,
+                (None, Some(keystore)) => {
+                    let tag = spaces.get_state().await?.tag().clone();
+                    let (cert_digest, cert, cert_priv_key) =
+                        keystore.get_or_create_tls_cert_by_tag(tag.0).await?;
+                    TlsConfig {
+                        cert,
+                        cert_priv_key,
+                        cert_digest,
+                    }
+                }
+                (None, None) => TlsConfig::new_ephemeral()
+                    .await
+                    .map_err(ConductorError::other)?,
+            };
+            let strat =
+                ArqStrat::from_params(network_config.tuning_params.gossip_redundancy_target);
+
+            let host = KitsuneHostImpl::new(
+                spaces.clone(),
+                ribosome_store.clone(),
+                network_config.tuning_params.clone(),
+                strat,
+            );
+
+            Ok(holochain_p2p::spawn_holochain_p2p(network_config, tls_config, host).await?)
+        }
+
         fn spawn_post_commit(
             conductor_handle: ConductorHandle,
             receiver: tokio::sync::mpsc::Receiver<PostCommitArgs>,
@@ -1631,17 +2506,12 @@ mod builder {
             self.config.environment_path = env_path.to_path_buf().into();
 
             let spaces = Spaces::new(&self.config)?;
-
-            let network_config = self.config.network.clone().unwrap_or_default();
-            let tuning_params = network_config.tuning_params.clone();
-            let strat = ArqStrat::from_params(tuning_params.gossip_redundancy_target);
+            spaces.migrate_state().await?;
 
             let ribosome_store = RwShare::new(self.ribosome_store);
-            let host =
-                KitsuneHostImpl::new(spaces.clone(), ribosome_store.clone(), tuning_params, strat);
 
             let (holochain_p2p, p2p_evt) =
-                holochain_p2p::spawn_holochain_p2p(network_config, holochain_p2p::kitsune_p2p::dependencies::kitsune_p2p_types::tls::TlsConfig::new_ephemeral().await.unwrap(), host)
+                Self::spawn_network(self.network, &self.config, &spaces, &ribosome_store, None)
                     .await?;
 
             let (post_commit_sender, post_commit_receiver) =
@@ -1649,6 +2519,7 @@ mod builder {
 
             let conductor = Conductor::new(
                 self.config.clone(),
+                self.config_path.clone(),
                 ribosome_store,
                 keystore,
                 holochain_p2p,
@@ -12,7 +12,11 @@ use crate::{
     assert_eq_retry_10s, core::ribosome::guest_callback::genesis_self_check::GenesisSelfCheckResult,
 };
 use ::fixt::prelude::*;
+use holochain_conductor_api::CellInitStatus;
+use holochain_conductor_api::ExternalApiWireError;
+use holochain_conductor_api::GenesisFailureReason;
 use holochain_conductor_api::InstalledAppInfoStatus;
+use holochain_conductor_api::UninstalledAppData;
 use holochain_conductor_api::{AdminRequest, AdminResponse, AppRequest, AppResponse, ZomeCall};
 use holochain_keystore::crude_mock_keystore::*;
 use holochain_state::prelude::{test_keystore, *};
@@ -39,6 +43,7 @@ async fn can_update_state() {
     .unwrap();
     let conductor = Conductor::new(
         Default::default(),
+        None,
         ribosome_store,
         keystore,
         holochain_p2p,
@@ -94,6 +99,7 @@ async fn can_add_clone_cell_to_app() {
 
     let conductor = Conductor::new(
         Default::default(),
+        None,
         ribosome_store,
         keystore,
         holochain_p2p,
@@ -172,6 +178,7 @@ async fn app_ids_are_unique() {
     .unwrap();
     let conductor = Conductor::new(
         Default::default(),
+        None,
         ribosome_store,
         test_keystore(),
         holochain_p2p,
@@ -348,7 +355,7 @@ async fn test_uninstall_app() {
 
     conductor
         .inner_handle()
-        .uninstall_app(&"app".to_string())
+        .uninstall_app(&"app".to_string(), UninstalledAppData::Retain, None)
         .await
         .unwrap();
 
@@ -362,6 +369,74 @@ async fn test_uninstall_app() {
     );
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn uninstall_app_can_export_and_delete_source_chains() {
+    observability::test_run().ok();
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let (dna, _, _) = mk_dna(simple_create_entry_zome()).await.unwrap();
+    let (kept,) = conductor
+        .setup_app("kept", &[dna.clone()])
+        .await
+        .unwrap()
+        .into_tuple();
+    let (deleted,) = conductor
+        .setup_app("deleted", &[dna])
+        .await
+        .unwrap()
+        .into_tuple();
+    for cell in [&kept, &deleted] {
+        let _: ActionHash = conductor
+            .call(&cell.zome("create_entry"), "create", ())
+            .await;
+    }
+    let chain_len = |cell: &SweetCell| {
+        let db = cell.authored_db().clone();
+        let author = cell.agent_pubkey().clone();
+        async move {
+            holochain_state::source_chain::dump_state(db.into(), author)
+                .await
+                .unwrap()
+                .records
+                .len()
+        }
+    };
+    let len = chain_len(&deleted).await;
+    assert!(len > 3);
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("chains.json");
+    conductor
+        .inner_handle()
+        .uninstall_app(
+            &"deleted".to_string(),
+            UninstalledAppData::Delete,
+            Some(path.clone()),
+        )
+        .await
+        .unwrap();
+
+    // The export has the chain as it was before it was deleted.
+    let export: serde_json::Value = serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+    assert_eq!(
+        export[0]["source_chain"]["records"]
+            .as_array()
+            .unwrap()
+            .len(),
+        len
+    );
+    assert_eq!(chain_len(&deleted).await, 0);
+    // The other agent's chain in the same DNA is untouched.
+    assert_eq!(chain_len(&kept).await, len);
+
+    // Exporting never overwrites a file.
+    let result = conductor
+        .inner_handle()
+        .uninstall_app(&"kept".to_string(), UninstalledAppData::Delete, Some(path))
+        .await;
+    assert_matches!(result, Err(ConductorError::IoError(_)));
+    assert_eq!(conductor.list_apps(None).await.unwrap().len(), 1);
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_reconciliation_idempotency() {
     observability::test_run().ok();
@@ -484,7 +559,11 @@ async fn test_signing_error_during_genesis_doesnt_bork_interfaces() {
     let (cell1,) = app1.into_tuple();
     let (cell2,) = app2.into_tuple();
 
-    let app_port = conductor.inner_handle().add_app_interface(0).await.unwrap();
+    let app_port = conductor
+        .inner_handle()
+        .add_app_interface(InterfaceDriver::websocket(0))
+        .await
+        .unwrap();
     let (mut app_client, _) = websocket_client_by_port(app_port).await.unwrap();
     let (mut admin_client, _) = conductor.admin_ws_client().await;
 
@@ -635,11 +714,25 @@ async fn test_installation_fails_if_genesis_self_check_is_invalid() {
         panic!("this should have been an error")
     };
 
-    if let ConductorApiError::ConductorError(inner) = err {
-        assert_matches!(*inner, ConductorError::GenesisFailed { errors } if errors.len() == 1);
+    if let ConductorApiError::ConductorError(inner) = &err {
+        assert_matches!(inner.as_ref(), ConductorError::GenesisFailed { errors } if errors.len() == 1);
     } else {
         panic!("this should have been an error too");
     }
+
+    // The rejection is reported to the client with the reason given.
+    match ExternalApiWireError::from(err) {
+        ExternalApiWireError::GenesisFailed(failures) => {
+            assert_eq!(failures.len(), 1);
+            assert_eq!(
+                failures[0].reason,
+                GenesisFailureReason::SelfCheckRejected(
+                    "intentional invalid result for testing".to_string()
+                )
+            );
+        }
+        other => panic!("unexpected wire error {:?}", other),
+    }
 }
 
 #[tokio::test(flavor = "multi_thread")]
@@ -693,6 +786,44 @@ async fn test_bad_entry_validation_after_genesis_returns_zome_call_error() {
     );
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn create_must_match_entry_def_visibility() {
+    observability::test_run().ok();
+    let private_entry_def = EntryDef {
+        visibility: EntryVisibility::Private,
+        ..EntryDef::default_with_id("secret")
+    };
+    let zome = InlineZomeSet::new_unique_single("integrity", "custom", vec![private_entry_def], 0)
+        .callback(
+            "custom",
+            "create",
+            move |api, visibility: EntryVisibility| {
+                let entry = Entry::app(().try_into().unwrap()).unwrap();
+                let hash = api.create(CreateInput::new(
+                    InlineZomeSet::get_entry_location(&api, EntryDefIndex(0)),
+                    visibility,
+                    entry,
+                    ChainTopOrdering::default(),
+                ))?;
+                Ok(hash)
+            },
+        );
+    let dnas = [mk_dna(zome).await.unwrap().0];
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let app = conductor.setup_app("app", &dnas).await.unwrap();
+    let (cell,) = app.into_tuple();
+
+    // A private entry can't be created as public.
+    let result: ConductorApiResult<ActionHash> = conductor
+        .call_fallible(&cell.zome("custom"), "create", EntryVisibility::Public)
+        .await;
+    assert_matches!(result, Err(_));
+
+    let _: ActionHash = conductor
+        .call(&cell.zome("custom"), "create", EntryVisibility::Private)
+        .await;
+}
+
 // NB: currently the pre-genesis and post-genesis handling of panics is the same.
 //   If we implement [ B-04188 ], then this test will be made more possible.
 //   Otherwise, we have to devise a way to discover whether a panic happened
@@ -1007,3 +1138,563 @@ async fn test_init_concurrency() {
     assert_eq!(num_calls_clone.fetch_add(0, Ordering::SeqCst), 100);
     assert_eq!(num_inits_clone.fetch_add(0, Ordering::SeqCst), 1);
 }
+
+/// Check that init is run again while it has unresolved dependencies,
+/// and that the cell's state dump shows when it has passed.
+#[tokio::test(flavor = "multi_thread")]
+async fn init_is_retried_on_unresolved_dependencies() {
+    observability::test_run().ok();
+    let num_inits = Arc::new(AtomicU32::new(0));
+    let num_inits_clone = num_inits.clone();
+
+    let zome = InlineZomeSet::new_unique_single("integrity", "zome", vec![], 0)
+        .callback("zome", "init", move |_, ()| {
+            if num_inits.fetch_add(1, Ordering::SeqCst) < 2 {
+                Ok(InitCallbackResult::UnresolvedDependencies(
+                    UnresolvedDependencies::Hashes(vec![]),
+                ))
+            } else {
+                Ok(InitCallbackResult::Pass)
+            }
+        })
+        .callback("zome", "zomefunc", |_, ()| Ok(()));
+    let dnas = [mk_dna(zome).await.unwrap().0];
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let app = conductor.setup_app("app", &dnas).await.unwrap();
+    let (cell,) = app.into_tuple();
+
+    let dump = conductor
+        .dump_full_cell_state(cell.cell_id(), None)
+        .await
+        .unwrap();
+    assert_eq!(dump.init_status, CellInitStatus::NotRun);
+
+    let _: () = conductor.call(&cell.zome("zome"), "zomefunc", ()).await;
+    assert_eq!(num_inits_clone.load(Ordering::SeqCst), 3);
+
+    let dump = conductor
+        .dump_full_cell_state(cell.cell_id(), None)
+        .await
+        .unwrap();
+    assert_eq!(dump.init_status, CellInitStatus::Initialized);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_reload_config() {
+    use holochain_p2p::kitsune_p2p::KitsuneP2pConfig;
+    use holochain_types::db::DbSyncStrategy;
+    use kitsune_p2p_types::config::tuning_params_struct::KitsuneP2pTuningParams;
+
+    observability::test_run().ok();
+    let db_dir = test_db_dir();
+    let config_dir = tempfile::tempdir().unwrap();
+    let config_path = config_dir.path().join("conductor-config.yml");
+    let config = ConductorConfig {
+        environment_path: db_dir.path().to_path_buf().into(),
+        ..Default::default()
+    };
+    std::fs::write(&config_path, serde_yaml::to_string(&config).unwrap()).unwrap();
+
+    let conductor = Conductor::builder()
+        .config(config.clone())
+        .config_path(Some(config_path.clone()))
+        .test(db_dir.path(), &[])
+        .await
+        .unwrap();
+
+    let mut tuning_params = KitsuneP2pTuningParams::default();
+    tuning_params.gossip_strategy = "none".to_string();
+    let mut network = KitsuneP2pConfig::default();
+    network.tuning_params = Arc::new(tuning_params);
+    let new_config = ConductorConfig {
+        network: Some(network),
+        db_sync_strategy: DbSyncStrategy::Resilient,
+        dht_pruning: Some(Default::default()),
+        ..config
+    };
+    std::fs::write(&config_path, serde_yaml::to_string(&new_config).unwrap()).unwrap();
+
+    let report = conductor.clone().reload_config(None).await.unwrap();
+    assert_eq!(report.applied, vec!["dht_pruning".to_string()]);
+    assert_eq!(
+        report.requires_restart,
+        vec![
+            "db_sync_strategy".to_string(),
+            "network.tuning_params.gossip_strategy".to_string()
+        ]
+    );
+
+    // Only the runtime-safe setting was swapped in.
+    let running = conductor.get_config();
+    assert!(running.dht_pruning.is_some());
+    assert!(running
+        .network
+        .map_or(true, |n| n.tuning_params.gossip_strategy
+            == "sharded-gossip"));
+    assert_eq!(running.db_sync_strategy, DbSyncStrategy::Fast);
+
+    conductor.shutdown();
+}
+
+/// Tuning params, bootstrap services and the log filter are pushed into the
+/// running network and logger by a config reload.
+#[tokio::test(flavor = "multi_thread")]
+async fn test_reload_config_applies_network_and_log_settings() {
+    use super::log_filter;
+
+    observability::test_run().ok();
+    // Stands in for the reloadable filter the holochain binary logs with.
+    let _filter = log_filter::reloadable::<tracing_subscriber::Registry>(
+        log_filter::env_filter(Some("info")).unwrap(),
+    );
+
+    let zome = InlineZomeSet::new_unique_single("integrity", "zome", vec![], 0);
+    let dnas = [mk_dna(zome).await.unwrap().0];
+    let mut conductor = SweetConductor::from_standard_config().await;
+    conductor.setup_app("app", &dnas).await.unwrap();
+    let handle = conductor.inner_handle();
+
+    let bootstrap_url = url2::url2!("http://127.0.0.1:1");
+    let mut new_config = handle.get_config();
+    let mut network = new_config.network.clone().unwrap_or_default();
+    let mut tuning_params = (*network.tuning_params).clone();
+    tuning_params.gossip_max_batch_size = 1_000;
+    network.tuning_params = Arc::new(tuning_params);
+    network.bootstrap_service = Some(bootstrap_url.clone());
+    new_config.network = Some(network);
+    new_config.log_filter = Some("warn".to_string());
+    let config_dir = tempfile::tempdir().unwrap();
+    let config_path = config_dir.path().join("conductor-config.yml");
+    std::fs::write(&config_path, serde_yaml::to_string(&new_config).unwrap()).unwrap();
+
+    let report = handle
+        .clone()
+        .reload_config(Some(config_path))
+        .await
+        .unwrap();
+    assert_eq!(
+        report.applied,
+        vec![
+            "network.tuning_params".to_string(),
+            "network.bootstrap_service".to_string(),
+            "log_filter".to_string(),
+        ]
+    );
+    assert!(report.requires_restart.is_empty());
+
+    // The space the app joined gossips with the new params.
+    let stats: serde_json::Value =
+        serde_json::from_str(&handle.dump_network_stats().await.unwrap()).unwrap();
+    assert_eq!(
+        stats["spaces"][0]["tuning_params"]["gossip_max_batch_size"],
+        "1000"
+    );
+    // The network uses the new bootstrap service.
+    let health = handle.bootstrap_health().await.unwrap();
+    assert_eq!(
+        health.into_iter().map(|h| h.url).collect::<Vec<_>>(),
+        vec![bootstrap_url]
+    );
+    assert_eq!(log_filter::current_log_filter(), Some("warn".to_string()));
+}
+
+/// A graceful shutdown lets in-flight zome calls finish, refuses new ones,
+/// and then stops all managed tasks.
+#[tokio::test(flavor = "multi_thread")]
+async fn test_graceful_shutdown_drains_zome_calls() {
+    observability::test_run().ok();
+    let started = Arc::new(tokio::sync::Notify::new());
+    let started_clone = started.clone();
+
+    let zome = InlineZomeSet::new_unique_single("integrity", "zome", vec![], 0).callback(
+        "zome",
+        "slow",
+        move |_, ()| {
+            started_clone.notify_one();
+            std::thread::sleep(std::time::Duration::from_millis(500));
+            Ok(())
+        },
+    );
+    let dnas = [mk_dna(zome).await.unwrap().0];
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let app = conductor.setup_app("app", &dnas).await.unwrap();
+    let (cell,) = app.into_tuple();
+    let handle = conductor.inner_handle();
+    let shutdown_handle = handle.take_shutdown_handle().unwrap();
+
+    let in_flight = {
+        let handle = SweetConductorHandle::from(handle.clone());
+        let zome = cell.zome("zome");
+        tokio::spawn(async move { handle.call_fallible::<_, (), _>(&zome, "slow", ()).await })
+    };
+    started.notified().await;
+
+    handle
+        .graceful_shutdown(std::time::Duration::from_secs(10))
+        .await;
+
+    // The call which was already running was allowed to complete.
+    in_flight.await.unwrap().unwrap();
+
+    // New calls are refused.
+    let result: ConductorApiResult<()> = conductor
+        .call_fallible(&cell.zome("zome"), "slow", ())
+        .await;
+    assert_matches!(
+        result,
+        Err(ConductorApiError::ConductorError(e)) if matches!(*e, ConductorError::ShuttingDown)
+    );
+
+    tokio::time::timeout(std::time::Duration::from_secs(30), shutdown_handle)
+        .await
+        .expect("Managed tasks did not stop")
+        .unwrap()
+        .unwrap();
+    handle.close_storage_and_network().await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn deferred_cells_are_created_on_first_call() {
+    observability::test_run().ok();
+    let conductor = SweetConductor::from_standard_config().await;
+    let agent = SweetAgents::one(conductor.keystore()).await;
+    let (dna, _, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Foo])
+        .await
+        .unwrap();
+    let path = std::path::PathBuf::from(format!("{}", dna.dna_hash()));
+    let role = AppRoleManifest {
+        id: "deferred".into(),
+        provisioning: Some(CellProvisioning::Create { deferred: true }),
+        dna: AppRoleDnaManifest {
+            location: Some(DnaLocation::Bundled(path.clone())),
+            ..AppRoleDnaManifest::sample()
+        },
+        zome_fn_access: ZomeFnAccessRules::default(),
+    };
+    let manifest: AppManifest = AppManifestCurrentBuilder::default()
+        .name("app".into())
+        .description(None)
+        .roles(vec![role])
+        .build()
+        .unwrap()
+        .into();
+    let resources = vec![(path, DnaBundle::from_dna_file(dna).await.unwrap())];
+    let bundle = AppBundle::new(manifest, resources, std::path::PathBuf::from("."))
+        .await
+        .unwrap();
+
+    let handle = conductor.inner_handle();
+    handle
+        .clone()
+        .install_app_bundle(InstallAppBundlePayload {
+            source: AppBundleSource::Bundle(bundle),
+            agent_key: agent,
+            installed_app_id: Some("app".into()),
+            membrane_proofs: Default::default(),
+            network_seed: None,
+        })
+        .await
+        .unwrap();
+    handle.clone().enable_app("app".into()).await.unwrap();
+
+    // The app runs without its deferred cell.
+    let info = handle.get_app_info(&"app".into()).await.unwrap().unwrap();
+    assert_eq!(info.status, InstalledAppInfoStatus::Running);
+    assert!(info.cell_data.is_empty());
+    let cell_id = info.deferred_cell_data[0].as_id().clone();
+    assert!(!handle.list_cell_ids(None).contains(&cell_id));
+
+    // The first call to the cell creates it.
+    let app_port = handle
+        .clone()
+        .add_app_interface(InterfaceDriver::websocket(0))
+        .await
+        .unwrap();
+    let (mut app_client, _) = websocket_client_by_port(app_port).await.unwrap();
+    let response: AppResponse = app_client
+        .request(AppRequest::ZomeCall(Box::new(ZomeCall {
+            cell_id: cell_id.clone(),
+            zome_name: TestWasm::Foo.into(),
+            fn_name: "foo".into(),
+            payload: ExternIO::encode(()).unwrap(),
+            cap_secret: None,
+            provenance: cell_id.agent_pubkey().clone(),
+        })))
+        .await
+        .unwrap();
+    assert_matches!(response, AppResponse::ZomeCall(_));
+    assert!(handle.list_cell_ids(None).contains(&cell_id));
+    let info = handle.get_app_info(&"app".into()).await.unwrap().unwrap();
+    assert_eq!(info.cell_data[0].as_id(), &cell_id);
+    assert!(info.deferred_cell_data.is_empty());
+
+    // Provisioning it again just returns it.
+    let response: AppResponse = app_client
+        .request(AppRequest::ProvisionDeferredCell {
+            installed_app_id: "app".into(),
+            role_id: "deferred".into(),
+        })
+        .await
+        .unwrap();
+    assert_matches!(response, AppResponse::DeferredCellProvisioned(id) if id == cell_id);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn full_app_info_describes_roles_and_cells() {
+    observability::test_run().ok();
+    let (dna, _, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Foo])
+        .await
+        .unwrap();
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let app = conductor.setup_app("app", &[dna.clone()]).await.unwrap();
+    let (cell,) = app.into_tuple();
+
+    let info = conductor
+        .get_full_app_info(&"app".into())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(info.status, InstalledAppInfoStatus::Running);
+    assert_eq!(&info.agent_pub_key, cell.agent_pubkey());
+    assert_eq!(info.roles.len(), 1);
+    let role = &info.roles[0];
+    assert!(role.is_provisioned);
+    assert!(role.clone_cells.is_empty());
+    assert_eq!(&role.base_cell.cell_id, cell.cell_id());
+    assert!(role.base_cell.enabled);
+    let modifiers = role.base_cell.dna_modifiers.as_ref().unwrap();
+    assert_eq!(modifiers.network_seed, dna.dna_def().network_seed);
+
+    conductor
+        .disable_app("app".to_string(), DisabledAppReason::User)
+        .await
+        .unwrap();
+    let info = conductor
+        .get_full_app_info(&"app".into())
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(!info.roles[0].base_cell.enabled);
+
+    assert!(conductor
+        .get_full_app_info(&"no such app".into())
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn app_quotas_refuse_requests_and_are_signalled() {
+    use futures::StreamExt;
+    observability::test_run().ok();
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let (dna, _, _) = mk_dna(simple_create_entry_zome()).await.unwrap();
+    let (cell,) = conductor
+        .setup_app("app", &[dna])
+        .await
+        .unwrap()
+        .into_tuple();
+    let mut signals = conductor.signals();
+    let handle = conductor.inner_handle();
+    handle
+        .set_app_quota(
+            "app".into(),
+            AppQuota {
+                max_clone_cells: Some(0),
+                max_zome_calls_per_second: Some(1),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    let info = handle.get_app_info(&"app".into()).await.unwrap().unwrap();
+    assert_eq!(info.quota.max_zome_calls_per_second, Some(1));
+
+    // Of two calls made at once, only one fits in the quota.
+    let call = || ZomeCall {
+        cell_id: cell.cell_id().clone(),
+        zome_name: "create_entry".into(),
+        fn_name: "create".into(),
+        payload: ExternIO::encode(()).unwrap(),
+        cap_secret: None,
+        provenance: cell.agent_pubkey().clone(),
+    };
+    let (a, b) = futures::future::join(handle.call_zome(call()), handle.call_zome(call())).await;
+    let mut errors: Vec<_> = [a, b]
+        .into_iter()
+        .filter_map(|result| result.err())
+        .map(ExternalApiWireError::from)
+        .collect();
+    assert_eq!(errors.len(), 1);
+    let exceeded = AppQuotaExceeded {
+        installed_app_id: "app".into(),
+        exceeded: QuotaExceeded::ZomeCallRate { max: 1 },
+    };
+    assert_matches!(
+        errors.pop(),
+        Some(ExternalApiWireError::QuotaExceeded(e)) if e == exceeded
+    );
+    let signal = tokio::time::timeout(std::time::Duration::from_secs(10), signals.next())
+        .await
+        .unwrap();
+    assert_eq!(
+        signal,
+        Some(Signal::System(SystemSignal::QuotaExceeded(exceeded)))
+    );
+
+    let result = handle
+        .clone()
+        .create_clone_cell(CreateCloneCellPayload {
+            properties: None,
+            dna_hash: cell.cell_id().dna_hash().clone(),
+            agent_key: cell.agent_pubkey().clone(),
+            installed_app_id: "app".into(),
+            role_id: info.cell_data[0].as_role_id().clone(),
+            membrane_proof: None,
+        })
+        .await;
+    assert_matches!(
+        result,
+        Err(ConductorError::QuotaExceeded(AppQuotaExceeded {
+            exceeded: QuotaExceeded::CloneCells { max: 0 },
+            ..
+        }))
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn one_app_can_be_installed_for_many_agents() {
+    observability::test_run().ok();
+    let conductor = SweetConductor::from_standard_config().await;
+    let agents = SweetAgents::get(conductor.keystore(), 2).await;
+    let (dna, _, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Foo])
+        .await
+        .unwrap();
+    let path = std::path::PathBuf::from(format!("{}", dna.dna_hash()));
+    let role = AppRoleManifest {
+        id: "role".into(),
+        provisioning: Some(CellProvisioning::Create { deferred: false }),
+        dna: AppRoleDnaManifest {
+            location: Some(DnaLocation::Bundled(path.clone())),
+            ..AppRoleDnaManifest::sample()
+        },
+        zome_fn_access: ZomeFnAccessRules::default(),
+    };
+    let manifest: AppManifest = AppManifestCurrentBuilder::default()
+        .name("app".into())
+        .description(None)
+        .roles(vec![role])
+        .build()
+        .unwrap()
+        .into();
+    let resources = vec![(path, DnaBundle::from_dna_file(dna).await.unwrap())];
+    let bundle = AppBundle::new(manifest, resources, std::path::PathBuf::from("."))
+        .await
+        .unwrap();
+
+    let (mut admin_client, _) = conductor.admin_ws_client().await;
+    let response: AdminResponse = admin_client
+        .request(AdminRequest::InstallAppBundleForAgents(Box::new(
+            InstallAppBundleForAgentsPayload {
+                source: AppBundleSource::Bundle(bundle),
+                agent_keys: agents.clone(),
+                installed_app_id: None,
+                membrane_proofs: Default::default(),
+                network_seed: None,
+            },
+        )))
+        .await
+        .unwrap();
+    let apps = match response {
+        AdminResponse::AppBundleInstalledForAgents(apps) => apps,
+        r => panic!("Unexpected response {:?}", r),
+    };
+
+    // Each agent has their own app and cell, in the same DNA.
+    assert_eq!(apps.len(), 2);
+    for (app, agent) in apps.iter().zip(&agents) {
+        assert_eq!(app.installed_app_id, agent_app_id("app", agent));
+        assert_eq!(&app.agent_pub_key, agent);
+        assert_eq!(app.cell_data[0].as_id().agent_pubkey(), agent);
+    }
+    assert_eq!(
+        apps[0].cell_data[0].as_id().dna_hash(),
+        apps[1].cell_data[0].as_id().dna_hash()
+    );
+
+    let response: AdminResponse = admin_client
+        .request(AdminRequest::ListApps {
+            status_filter: None,
+            agent_pub_key: Some(agents[1].clone()),
+        })
+        .await
+        .unwrap();
+    assert_matches!(
+        response,
+        AdminResponse::AppsListed(apps) if apps.len() == 1
+            && apps[0].installed_app_id == agent_app_id("app", &agents[1])
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn conductors_can_share_an_in_memory_network() {
+    use holochain_conductor_api::conductor::NetworkBackendConfig;
+    use holochain_test_wasm_common::AnchorInput;
+
+    observability::test_run().ok();
+    let mut config = standard_config();
+    config.network_backend = Some(NetworkBackendConfig::InMemory {
+        name: "conductors_can_share_an_in_memory_network".into(),
+    });
+    let mut conductors = SweetConductorBatch::from_config(2, config).await;
+    let (dna, _, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Anchor])
+        .await
+        .unwrap();
+    let apps = conductors.setup_app("app", &[dna]).await.unwrap();
+    let ((alice,), (bob,)) = apps.into_tuples();
+
+    let anchor = AnchorInput("alice".to_string(), "0".to_string());
+    let _: EntryHash = conductors[0]
+        .call(&alice.zome(TestWasm::Anchor), "anchor", anchor)
+        .await;
+
+    crate::test_utils::consistency_10s(&[&alice, &bob]).await;
+
+    let hashes: EntryHashes = conductors[1]
+        .call(
+            &bob.zome(TestWasm::Anchor),
+            "list_anchor_addresses",
+            "alice",
+        )
+        .await;
+    assert_eq!(hashes.0.len(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn conductor_runs_a_relay_when_configured() {
+    use holochain_conductor_api::conductor::RelayConfig;
+
+    observability::test_run().ok();
+    let conductor = SweetConductor::from_standard_config().await;
+    assert!(conductor.dump_relay_stats().is_err());
+
+    let mut config = standard_config();
+    config.relay = Some(RelayConfig {
+        bind_to: Some(url2::url2!("kitsune-quic://127.0.0.1:0")),
+        tls_cert_path: None,
+        allowed_clients: Some(vec![
+            "qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqo".to_string()
+        ]),
+        max_bytes_per_second_per_client: Some(1024),
+    });
+    let conductor = SweetConductor::from_config(config).await;
+    let stats: serde_json::Value =
+        serde_json::from_str(&conductor.dump_relay_stats().unwrap()).unwrap();
+    assert!(stats["addr"]
+        .as_str()
+        .unwrap()
+        .starts_with("kitsune-proxy://"));
+    assert_eq!(stats["relay"]["allowed_client_count"], 1);
+    assert_eq!(stats["relay"]["max_bytes_per_second"], 1024);
+    assert_eq!(stats["relay"]["forwarded_messages"], 0);
+}
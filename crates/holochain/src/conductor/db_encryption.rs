@@ -0,0 +1,95 @@
+//! Unlocking of the conductor's encrypted databases.
+//!
+//! The databases are encrypted with a random [`DbKey`]. That key is kept in a
+//! file in the environment directory, boxed with a shared secret held in the
+//! keystore, so it can only be recovered while the keystore is unlocked.
+
+use super::error::ConductorError;
+use super::error::ConductorResult;
+use holochain_conductor_api::config::conductor::DbEncryptionConfig;
+use holochain_keystore::MetaLairClient;
+use holochain_sqlite::encryption;
+use holochain_sqlite::encryption::DbKey;
+use std::path::Path;
+use std::sync::Arc;
+
+/// The tag of the keystore secret the database key is encrypted with.
+const DB_KEY_SECRET_TAG: &str = "holochain-db-encryption";
+
+/// The file in the environment directory holding the encrypted database key.
+const DB_KEY_FILE: &str = "db.key";
+
+/// The file a new key is written to while the databases are being re-keyed.
+/// If the conductor dies between the databases being re-keyed and this being
+/// moved over [`DB_KEY_FILE`], moving it over by hand recovers the databases.
+const NEW_DB_KEY_FILE: &str = "db.key.new";
+
+/// Set the key all of the conductor's databases are opened with, creating it
+/// on first run, and re-key the databases if configured to.
+///
+/// This must be called before any databases are opened. The key is shared by
+/// every database in the process, so only one conductor per process can use
+/// encrypted databases.
+pub(crate) async fn unlock_databases(
+    keystore: &MetaLairClient,
+    environment_path: &Path,
+    config: &DbEncryptionConfig,
+) -> ConductorResult<()> {
+    std::fs::create_dir_all(environment_path)?;
+    let key_path = environment_path.join(DB_KEY_FILE);
+    let key = if key_path.exists() {
+        read_key(keystore, &key_path).await?
+    } else {
+        tracing::info!("Creating a new database encryption key");
+        keystore.new_shared_secret(DB_KEY_SECRET_TAG.into()).await?;
+        let key = DbKey::generate();
+        write_key(keystore, &key_path, &key).await?;
+        key
+    };
+    encryption::set_db_key(key)?;
+
+    if config.rekey_on_startup {
+        let new_key = DbKey::generate();
+        let new_key_path = environment_path.join(NEW_DB_KEY_FILE);
+        write_key(keystore, &new_key_path, &new_key).await?;
+        let root = environment_path.to_owned();
+        let count =
+            tokio::task::spawn_blocking(move || encryption::rekey_databases(&root, new_key))
+                .await??;
+        std::fs::rename(&new_key_path, &key_path)?;
+        tracing::info!("Re-keyed {} databases", count);
+    }
+    Ok(())
+}
+
+async fn read_key(keystore: &MetaLairClient, path: &Path) -> ConductorResult<DbKey> {
+    let file = std::fs::read(path)?;
+    if file.len() <= 24 {
+        return Err(ConductorError::other(format!(
+            "The database key file at {} is truncated",
+            path.display()
+        )));
+    }
+    let (nonce, cipher) = file.split_at(24);
+    let nonce: [u8; 24] = nonce.try_into().expect("Split at the nonce length");
+    let bytes = keystore
+        .shared_secret_decrypt(DB_KEY_SECRET_TAG.into(), nonce, Arc::from(cipher))
+        .await?;
+    let bytes: [u8; 32] = bytes[..].try_into().map_err(|_| {
+        ConductorError::other(format!(
+            "The database key file at {} does not hold a valid key",
+            path.display()
+        ))
+    })?;
+    Ok(DbKey::from_bytes(bytes))
+}
+
+async fn write_key(keystore: &MetaLairClient, path: &Path, key: &DbKey) -> ConductorResult<()> {
+    let (nonce, cipher) = keystore
+        .shared_secret_encrypt(DB_KEY_SECRET_TAG.into(), Arc::from(&key.as_bytes()[..]))
+        .await?;
+    let mut file = nonce.to_vec();
+    file.extend_from_slice(&cipher);
+    std::fs::write(path, file)?;
+    Ok(())
+}
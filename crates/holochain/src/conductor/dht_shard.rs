@@ -0,0 +1,208 @@
+//! Export and import of the integrated DHT ops a node holds for a DNA.
+//!
+//! A shard file lets a new node seed its DHT database from a file rather than
+//! gossiping everything in. The ops in it are not trusted: importing feeds
+//! them through the incoming ops workflow, just like ops received by publish,
+//! so they are counterfeit checked and then sys and app validated as normal.
+//!
+//! The file is [`MAGIC`] followed by a length-prefixed [`ShardHeader`] naming
+//! the DNA, then one length-prefixed, msgpack encoded [`DhtOp`] per op.
+
+use super::error::ConductorError;
+use super::error::ConductorResult;
+use holo_hash::DhtOpHash;
+use holo_hash::DnaHash;
+use holochain_state::query::map_sql_dht_op_common;
+use holochain_types::dht_op::DhtOp;
+use holochain_types::dht_op::DhtOpHashed;
+use rusqlite::Transaction;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde::Serialize;
+use std::io::BufReader;
+use std::io::BufWriter;
+use std::io::Read;
+use std::io::Write;
+use std::path::Path;
+
+/// The start of every shard file, including the format version.
+const MAGIC: &[u8; 8] = b"HCSHARD1";
+
+/// How many ops are fed to the incoming ops workflow at a time on import.
+pub(crate) const IMPORT_BATCH_SIZE: usize = 1000;
+
+/// Frames larger than this are taken as a sign of a corrupt file
+/// rather than being allocated.
+const MAX_FRAME_LEN: u32 = 64 * 1024 * 1024;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ShardHeader {
+    dna_hash: DnaHash,
+}
+
+/// Write every integrated op in a DHT database to a new shard file at `path`.
+/// Returns the number of ops written.
+pub(crate) fn write_shard(
+    txn: &Transaction,
+    dna_hash: &DnaHash,
+    path: &Path,
+) -> ConductorResult<usize> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = BufWriter::new(
+        std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(path)?,
+    );
+    file.write_all(MAGIC)?;
+    write_frame(
+        &mut file,
+        &ShardHeader {
+            dna_hash: dna_hash.clone(),
+        },
+    )?;
+
+    let mut stmt = txn.prepare(holochain_sqlite::sql::sql_cell::FETCH_INTEGRATED_OPS)?;
+    let mut rows = stmt.query([])?;
+    let mut count = 0;
+    while let Some(row) = rows.next()? {
+        // Private entries and pruned data are skipped.
+        if let Some(op) = map_sql_dht_op_common(row)? {
+            write_frame(&mut file, &op)?;
+            count += 1;
+        }
+    }
+    file.flush()?;
+    Ok(count)
+}
+
+/// Reads the ops out of a shard file in batches.
+pub(crate) struct ShardReader {
+    file: BufReader<std::fs::File>,
+}
+
+impl ShardReader {
+    /// Open a shard file, checking it was exported from the expected DNA.
+    pub(crate) fn open(path: &Path, dna_hash: &DnaHash) -> ConductorResult<Self> {
+        let mut file = BufReader::new(std::fs::File::open(path)?);
+        let mut magic = [0; 8];
+        file.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(ConductorError::InvalidDhtShard(
+                "not a DHT shard file, or an unsupported version".to_string(),
+            ));
+        }
+        let header: ShardHeader = read_frame(&mut file)?
+            .ok_or_else(|| ConductorError::InvalidDhtShard("missing header".to_string()))?;
+        if header.dna_hash != *dna_hash {
+            return Err(ConductorError::InvalidDhtShard(format!(
+                "exported from DNA {} rather than {}",
+                header.dna_hash, dna_hash
+            )));
+        }
+        Ok(Self { file })
+    }
+
+    /// Read up to `max` more ops, hashed as they are read rather than
+    /// trusting the exporter. Returns an empty batch at the end of the file.
+    pub(crate) fn next_batch(&mut self, max: usize) -> ConductorResult<Vec<(DhtOpHash, DhtOp)>> {
+        let mut batch = Vec::with_capacity(max);
+        while batch.len() < max {
+            match read_frame::<DhtOp>(&mut self.file)? {
+                Some(op) => {
+                    let (op, hash) = DhtOpHashed::from_content_sync(op).into_inner();
+                    batch.push((hash, op));
+                }
+                None => break,
+            }
+        }
+        Ok(batch)
+    }
+}
+
+fn write_frame<T: Serialize + std::fmt::Debug>(
+    file: &mut impl Write,
+    value: &T,
+) -> ConductorResult<()> {
+    let bytes = holochain_serialized_bytes::encode(value)?;
+    let len = u32::try_from(bytes.len())
+        .map_err(|_| ConductorError::InvalidDhtShard("op is too large".to_string()))?;
+    file.write_all(&len.to_be_bytes())?;
+    file.write_all(&bytes)?;
+    Ok(())
+}
+
+/// Read the next frame, or `None` if the file ends cleanly before it.
+fn read_frame<T: DeserializeOwned + std::fmt::Debug>(
+    file: &mut impl Read,
+) -> ConductorResult<Option<T>> {
+    let mut len = [0; 4];
+    match file.read_exact(&mut len) {
+        Ok(()) => (),
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e.into()),
+    }
+    let len = u32::from_be_bytes(len);
+    if len > MAX_FRAME_LEN {
+        return Err(ConductorError::InvalidDhtShard(format!(
+            "frame of {} bytes is too large",
+            len
+        )));
+    }
+    let mut bytes = vec![0; len as usize];
+    file.read_exact(&mut bytes)
+        .map_err(|_| ConductorError::InvalidDhtShard("truncated".to_string()))?;
+    Ok(Some(holochain_serialized_bytes::decode(&bytes)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::fixt::prelude::*;
+    use holo_hash::fixt::DnaHashFixturator;
+    use holo_hash::HasHash;
+    use holochain_state::mutations;
+    use holochain_state::test_utils::test_dht_db;
+    use holochain_types::prelude::*;
+
+    #[test]
+    fn shard_round_trips_integrated_ops() {
+        let db = test_dht_db();
+        let dna_hash = fixt!(DnaHash);
+        let integrated = DhtOpHashed::from_content_sync(DhtOp::RegisterAgentActivity(
+            fixt!(Signature),
+            Action::Dna(fixt!(Dna)),
+        ));
+        let in_limbo = DhtOpHashed::from_content_sync(DhtOp::RegisterAgentActivity(
+            fixt!(Signature),
+            Action::Dna(fixt!(Dna)),
+        ));
+        db.test_commit(|txn| {
+            mutations::insert_op(txn, &integrated).unwrap();
+            mutations::set_when_integrated(txn, integrated.as_hash(), Timestamp::now()).unwrap();
+            mutations::insert_op(txn, &in_limbo).unwrap();
+        });
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("shard");
+        let count = db
+            .test_commit(|txn| write_shard(txn, &dna_hash, &path))
+            .unwrap();
+        assert_eq!(count, 1);
+
+        // Only the integrated op is exported.
+        let mut reader = ShardReader::open(&path, &dna_hash).unwrap();
+        let batch = reader.next_batch(10).unwrap();
+        let (op, hash) = integrated.into_inner();
+        assert_eq!(batch, vec![(hash, op)]);
+        assert!(reader.next_batch(10).unwrap().is_empty());
+
+        // A shard can't be imported into another DNA.
+        assert!(matches!(
+            ShardReader::open(&path, &fixt!(DnaHash)),
+            Err(ConductorError::InvalidDhtShard(_))
+        ));
+    }
+}
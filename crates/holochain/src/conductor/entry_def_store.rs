@@ -1,8 +1,6 @@
 //! # Entry Defs Store
 //! Stores all the entry definitions across zomes
-use crate::core::ribosome::guest_callback::entry_defs::EntryDefsHostAccess;
-use crate::core::ribosome::guest_callback::entry_defs::EntryDefsInvocation;
-use crate::core::ribosome::guest_callback::entry_defs::EntryDefsResult;
+use crate::core::ribosome::error::RibosomeError;
 use crate::core::ribosome::real_ribosome::RealRibosome;
 use crate::core::ribosome::RibosomeT;
 
@@ -10,14 +8,13 @@ use error::EntryDefStoreError;
 use error::EntryDefStoreResult;
 use holo_hash::*;
 use holochain_types::prelude::*;
-use std::collections::HashMap;
 
 use super::handle::ConductorHandleT;
 
 pub mod error;
 
 /// Get an [EntryDef] from the entry def store
-/// or fallback to running the zome
+/// or fallback to the entry defs of the ribosome
 pub(crate) async fn get_entry_def(
     entry_def_index: EntryDefIndex,
     zome: IntegrityZomeDef,
@@ -31,20 +28,13 @@ pub(crate) async fn get_entry_def(
         .get_ribosome(dna_hash)
         .map_err(|_| EntryDefStoreError::DnaFileMissing(dna_hash.clone()))?;
 
-    // If it's not found run the ribosome and get the entry defs
+    // If it's not found get it from the entry defs the ribosome collected
     match &entry_def {
         Some(_) => Ok(entry_def),
         None => Ok(get_entry_defs(ribosome)
             .await?
             .into_iter()
-            .find(
-                |(
-                    EntryDefBufferKey {
-                        entry_def_position, ..
-                    },
-                    _,
-                )| *entry_def_position == entry_def_index,
-            )
+            .find(|(k, _)| *k == key)
             .map(|(_, v)| v)),
     }
 }
@@ -54,55 +44,42 @@ pub(crate) async fn get_entry_def(
 pub(crate) async fn get_entry_defs(
     ribosome: RealRibosome,
 ) -> EntryDefStoreResult<Vec<(EntryDefBufferKey, EntryDef)>> {
-    let invocation = EntryDefsInvocation;
-
-    // Get the zomes hashes
-    let zomes = ribosome
-        .dna_def()
-        .integrity_zomes
-        .iter()
-        .cloned()
-        .enumerate()
-        .map(|(i, (zome_name, zome))| (zome_name, (ZomeId(i as u8), zome)))
-        .collect::<HashMap<_, _>>();
-
-    let result = tokio::task::spawn_blocking(move || {
-        ribosome.run_entry_defs(EntryDefsHostAccess, invocation)
-    })
-    .await?;
-
-    match result? {
-        EntryDefsResult::Defs(map) => {
-            // Turn the defs map into a vec of keys and entry defs
-            map.into_iter()
-                // Skip zomes without entry defs
-                .filter_map(|(zome_name, entry_defs)| {
-                    zomes.get(&zome_name).map(|zome| (zome.clone(), entry_defs))
-                })
-                // Get each entry def and pair with a key
-                .flat_map(|((_id, zome), entry_defs)| {
-                    entry_defs
-                        .into_iter()
-                        .enumerate()
-                        .map(move |(local_index, entry_def)| {
-                            let entry_def_position = u8::try_from(local_index)
-                                .map_err(|_| EntryDefStoreError::EntryTypeMissing)?;
-
-                            Ok((
-                                EntryDefBufferKey {
-                                    zome: zome.clone(),
-                                    entry_def_position: entry_def_position.into(),
-                                },
-                                entry_def,
-                            ))
-                        })
+    let zomes = ribosome.dna_def().integrity_zomes.clone();
+
+    // The entry defs callbacks are only run once per ribosome.
+    let entry_defs =
+        tokio::task::spawn_blocking(move || ribosome.entry_defs().map(<[EntryDefs]>::to_vec))
+            .await?
+            .map_err(|e| match e {
+                RibosomeError::EntryDefs(zome_name, msg) => {
+                    EntryDefStoreError::CallbackFailed(zome_name, msg)
+                }
+                e => e.into(),
+            })?;
+
+    // Pair each entry def with a key of its zome and position
+    zomes
+        .into_iter()
+        .zip(entry_defs)
+        .flat_map(|((_zome_name, zome), entry_defs)| {
+            entry_defs
+                .0
+                .into_iter()
+                .enumerate()
+                .map(move |(local_index, entry_def)| {
+                    let entry_def_position = u8::try_from(local_index)
+                        .map_err(|_| EntryDefStoreError::TooManyEntryDefs)?;
+
+                    Ok((
+                        EntryDefBufferKey {
+                            zome: zome.clone(),
+                            entry_def_position: entry_def_position.into(),
+                        },
+                        entry_def,
+                    ))
                 })
-                .collect()
-        }
-        EntryDefsResult::Err(zome_name, msg) => {
-            Err(EntryDefStoreError::CallbackFailed(zome_name, msg))
-        }
-    }
+        })
+        .collect()
 }
 
 #[cfg(test)]
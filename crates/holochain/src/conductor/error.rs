@@ -49,6 +49,34 @@ pub enum ConductorError {
     #[error("Attempted to call into the conductor while it is shutting down")]
     ShuttingDown,
 
+    #[error("The {workflow} failed {failures} times in a row. Last error: {last_error}")]
+    RepeatedWorkflowFailure {
+        workflow: String,
+        failures: u32,
+        last_error: String,
+    },
+
+    #[error("Can't restore DNA {0} from a backup while any of its cells are running")]
+    RestoreWhileRunning(DnaHash),
+
+    #[error("Can't back up DNA {0} as this conductor holds no data for it")]
+    SpaceMissing(DnaHash),
+
+    #[error("The DHT shard file is invalid: {0}")]
+    InvalidDhtShard(String),
+
+    #[error(
+        "The conductor state was persisted with schema version {found}, \
+        but this conductor only supports up to version {supported}"
+    )]
+    StateSchemaTooNew { found: u32, supported: u32 },
+
+    #[error("The audit log is not enabled in the conductor config")]
+    AuditLogDisabled,
+
+    #[error("The countersigning session of action {0} can't be confirmed to have failed, so the action can't be rolled back")]
+    CountersigningSessionNotFailed(ActionHash),
+
     #[error("Error while performing IO for the Conductor: {0}")]
     IoError(#[from] std::io::Error),
 
@@ -67,12 +95,15 @@ pub enum ConductorError {
     #[error("Attempted to add two app interfaces with the same id: {0:?}")]
     AppInterfaceIdCollision(AppInterfaceId),
 
+    #[error("No app interface is listening on port {0}")]
+    AppInterfaceNotFound(u16),
+
     // Box is to avoid cycle in error definition
     #[error(transparent)]
     InterfaceError(#[from] Box<InterfaceError>),
 
     #[error("Failed to run genesis on the following cells in the app: {errors:?}")]
-    GenesisFailed { errors: Vec<CellError> },
+    GenesisFailed { errors: Vec<(CellId, CellError)> },
 
     #[error(transparent)]
     SerializedBytesError(#[from] holochain_serialized_bytes::SerializedBytesError),
@@ -89,6 +120,19 @@ pub enum ConductorError {
     #[error("Tried to perform an operation on an app that was not running: {0}")]
     AppNotRunning(InstalledAppId),
 
+    #[error("{0}")]
+    QuotaExceeded(AppQuotaExceeded),
+
+    #[error("The function {fn_name} in zome {zome_name} may not be called from {origin}")]
+    ZomeFnAccessDenied {
+        zome_name: ZomeName,
+        fn_name: FunctionName,
+        origin: ZomeCallOrigin,
+    },
+
+    #[error("Invalid signed zome call: {0}")]
+    InvalidSignedZomeCall(String),
+
     #[error(transparent)]
     HolochainP2pError(#[from] holochain_p2p::HolochainP2pError),
 
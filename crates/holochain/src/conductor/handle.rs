@@ -39,7 +39,9 @@ use super::api::error::ConductorApiResult;
 use super::api::ZomeCall;
 use super::conductor::CellStatus;
 use super::config::AdminInterfaceConfig;
+use super::error::ConductorError;
 use super::error::ConductorResult;
+use super::interface::InterfaceDriver;
 use super::interface::SignalBroadcaster;
 use super::manager::spawn_task_manager;
 use super::manager::TaskManagerClient;
@@ -53,23 +55,39 @@ use super::space::Spaces;
 use super::Cell;
 use super::CellError;
 use super::Conductor;
-use super::{full_integration_dump, integration_dump};
+use super::{cell_op_counts, full_integration_dump, integration_dump};
 use crate::conductor::p2p_agent_store::get_single_agent_info;
 use crate::conductor::p2p_agent_store::query_peer_density;
 use crate::conductor::p2p_agent_store::P2pBatch;
 use crate::core::queue_consumer::QueueConsumerMap;
+use crate::core::queue_consumer::WorkflowScheduler;
+use crate::core::ribosome::error::RibosomeResult;
 use crate::core::ribosome::guest_callback::post_commit::PostCommitArgs;
 use crate::core::ribosome::real_ribosome::RealRibosome;
+use crate::core::ribosome::zome_call_limits::ZomeCallCancel;
 use crate::core::ribosome::RibosomeT;
 use crate::core::workflow::ZomeCallResult;
 use derive_more::From;
 use futures::future::FutureExt;
 use futures::StreamExt;
+use holochain_conductor_api::audit_log::AuditEntry;
+use holochain_conductor_api::audit_log::AuditEvent;
+use holochain_conductor_api::audit_log::AuditOutcome;
 use holochain_conductor_api::conductor::ConductorConfig;
+use holochain_conductor_api::conductor::ConfigReloadReport;
+use holochain_conductor_api::AppInterfaceInfo;
 use holochain_conductor_api::AppStatusFilter;
+use holochain_conductor_api::CellInitStatus;
+use holochain_conductor_api::CellOpCounts;
+use holochain_conductor_api::FullAppInfo;
 use holochain_conductor_api::FullStateDump;
 use holochain_conductor_api::InstalledAppInfo;
+use holochain_conductor_api::IntegratedRecord;
+use holochain_conductor_api::InterfaceStats;
 use holochain_conductor_api::JsonDump;
+use holochain_conductor_api::SignedZomeCall;
+use holochain_conductor_api::TaskInfo;
+use holochain_conductor_api::UninstalledAppData;
 use holochain_keystore::MetaLairClient;
 use holochain_p2p::actor::HolochainP2pRefToDna;
 use holochain_p2p::event::HolochainP2pEvent;
@@ -77,6 +95,7 @@ use holochain_p2p::event::HolochainP2pEvent::*;
 use holochain_p2p::DnaHashExt;
 use holochain_p2p::HolochainP2pDnaT;
 use holochain_state::host_fn_workspace::SourceChainWorkspace;
+use holochain_state::integrity::DbIntegrityReport;
 use holochain_state::prelude::SourceChainError;
 use holochain_state::prelude::SourceChainResult;
 use holochain_state::prelude::StateMutationError;
@@ -84,6 +103,7 @@ use holochain_state::prelude::StateMutationResult;
 use holochain_state::source_chain;
 use holochain_types::prelude::*;
 use kitsune_p2p::agent_store::AgentInfoSigned;
+use kitsune_p2p_types::bootstrap::BootstrapServerHealth;
 use kitsune_p2p_types::config::JOIN_NETWORK_TIMEOUT;
 use std::collections::HashMap;
 use std::{collections::HashSet, sync::Arc};
@@ -95,6 +115,8 @@ use tracing::*;
 use super::state::ConductorState;
 #[cfg(any(test, feature = "test_utils"))]
 use crate::core::queue_consumer::QueueTriggers;
+#[cfg(any(test, feature = "test_utils"))]
+use crate::core::workflow::fault_injection::FaultInjector;
 
 /// A handle to the Conductor that can easily be passed around and cheaply cloned
 pub type ConductorHandle = Arc<dyn ConductorHandleT>;
@@ -109,6 +131,10 @@ pub trait ConductorHandleT: Send + Sync {
     /// Returns error if conductor is shutting down
     fn check_running(&self) -> ConductorResult<()>;
 
+    /// Is the conductor initialized and not shutting down, i.e. ready for
+    /// its interfaces to be used.
+    fn is_ready(&self) -> bool;
+
     /// Initialize the task manager, add admin interfaces from config,
     /// start up app interfaces from db, and register all tasks.
     ///
@@ -129,14 +155,26 @@ pub trait ConductorHandleT: Send + Sync {
     ) -> ConductorResult<()>;
 
     /// Add an app interface
-    async fn add_app_interface(self: Arc<Self>, port: u16) -> ConductorResult<u16>;
+    async fn add_app_interface(self: Arc<Self>, driver: InterfaceDriver) -> ConductorResult<u16>;
 
     /// List the app interfaces currently installed.
     async fn list_app_interfaces(&self) -> ConductorResult<Vec<u16>>;
 
+    /// List the settings of the app interfaces currently installed.
+    async fn list_app_interface_settings(&self) -> ConductorResult<Vec<AppInterfaceInfo>>;
+
+    /// Close the app interface on a port, and stop it from being started
+    /// again on restart.
+    async fn remove_app_interface(&self, port: u16) -> ConductorResult<()>;
+
     /// Install a [`DnaFile`](holochain_types::dna::DnaFile) in this Conductor
     async fn register_dna(&self, dna: DnaFile) -> ConductorResult<()>;
 
+    /// Fetch a DNA which this Conductor doesn't have from the peers of that
+    /// DNA, and register it once it's verified against its hash. The agent
+    /// joins the DNA's network, without holding any of its data, to ask them.
+    async fn fetch_dna(&self, dna_hash: DnaHash, agent: AgentPubKey) -> ConductorResult<()>;
+
     /// Update coordinator zomes on an existing dna.
     async fn update_coordinators(
         &self,
@@ -145,6 +183,13 @@ pub trait ConductorHandleT: Send + Sync {
         wasms: Vec<wasm::DnaWasm>,
     ) -> ConductorResult<()>;
 
+    /// Replace the runtime properties of an existing dna.
+    async fn update_runtime_properties(
+        &self,
+        hash: &DnaHash,
+        properties: YamlProperties,
+    ) -> ConductorResult<()>;
+
     /// Get the list of hashes of installed Dnas in this Conductor
     fn list_dnas(&self) -> Vec<DnaHash>;
 
@@ -173,11 +218,32 @@ pub trait ConductorHandleT: Send + Sync {
     /// Invoke a zome function on a Cell
     async fn call_zome(&self, invocation: ZomeCall) -> ConductorApiResult<ZomeCallResult>;
 
+    /// Invoke a zome function on a Cell, which is aborted and rolled back
+    /// if the token is cancelled before the call is committed.
+    async fn call_zome_cancellable(
+        &self,
+        invocation: ZomeCall,
+        cancel: ZomeCallCancel,
+    ) -> ConductorApiResult<ZomeCallResult>;
+
+    /// Refuse a zome call from an origin which the app manifests of the
+    /// called cell don't allow it from.
+    async fn check_zome_fn_access(
+        &self,
+        call: &ZomeCall,
+        origin: ZomeCallOrigin,
+    ) -> ConductorResult<()>;
+
+    /// Check a zome call signed by the agent of the cell it calls,
+    /// returning the call to make
+    async fn verify_signed_zome_call(&self, call: SignedZomeCall) -> ConductorApiResult<ZomeCall>;
+
     /// Invoke a zome function on a Cell with a workspace
     async fn call_zome_with_workspace(
         &self,
         invocation: ZomeCall,
         workspace_lock: SourceChainWorkspace,
+        cancel: ZomeCallCancel,
     ) -> ConductorApiResult<ZomeCallResult>;
 
     /// Get a Websocket port which will
@@ -186,8 +252,22 @@ pub trait ConductorHandleT: Send + Sync {
     /// Get the running queue consumer workflows per [`DnaHash`] map.
     fn get_queue_consumer_workflows(&self) -> QueueConsumerMap;
 
-    /// Get the conductor config
-    fn get_config(&self) -> &ConductorConfig;
+    /// Get the scheduler which shares validation work fairly between DNAs.
+    fn get_workflow_scheduler(&self) -> WorkflowScheduler;
+
+    /// Set how large a share of the conductor's validation concurrency a
+    /// DNA gets while other DNAs are also busy.
+    fn set_dna_scheduling_weight(&self, dna_hash: DnaHash, weight: u32);
+
+    /// Get the current conductor config
+    fn get_config(&self) -> ConductorConfig;
+
+    /// Re-read the conductor config from disk and apply the settings
+    /// which can safely change while running.
+    async fn reload_config(
+        self: Arc<Self>,
+        config_path: Option<std::path::PathBuf>,
+    ) -> ConductorResult<ConfigReloadReport>;
 
     /// Return the JoinHandle for all managed tasks, which when resolved will
     /// signal that the Conductor has completely shut down.
@@ -199,6 +279,64 @@ pub trait ConductorHandleT: Send + Sync {
     /// Send a signal to all managed tasks asking them to end ASAP.
     fn shutdown(&self);
 
+    /// Stop accepting new work, wait up to `drain_timeout` for in-flight
+    /// zome calls to finish, then signal all managed tasks to stop once
+    /// their current batch is committed.
+    ///
+    /// Await the [`TaskManagerRunHandle`] and then call
+    /// [`ConductorHandleT::close_storage_and_network`] to complete the shutdown.
+    async fn graceful_shutdown(&self, drain_timeout: std::time::Duration);
+
+    /// Flush all databases to disk and close network connections.
+    /// This should be the last thing done to a conductor before it is dropped.
+    async fn close_storage_and_network(&self) -> ConductorResult<()>;
+
+    /// Write a consistent snapshot of the source chains and integrated ops of
+    /// a cell's DNA to a new directory. This covers every cell of that DNA.
+    async fn backup_cell(&self, cell_id: &CellId, path: &std::path::Path) -> ConductorResult<()>;
+
+    /// Merge a backup taken with [`ConductorHandleT::backup_cell`] into the
+    /// cell's DNA. Fails if any cells of the DNA are running.
+    async fn restore_cell(&self, cell_id: &CellId, path: &std::path::Path) -> ConductorResult<()>;
+
+    /// Write a consistent snapshot of every database needed to bring this
+    /// conductor back up to a new directory, which a new conductor can use as
+    /// its environment path.
+    async fn backup_conductor(&self, path: &std::path::Path) -> ConductorResult<()>;
+
+    /// Write every integrated op held for a cell's DNA to a new shard file,
+    /// returning the number of ops written.
+    async fn export_dht_ops(
+        &self,
+        cell_id: &CellId,
+        path: &std::path::Path,
+    ) -> ConductorResult<usize>;
+
+    /// Feed the ops in a shard file written by [`ConductorHandleT::export_dht_ops`]
+    /// through validation and into a running cell's DNA, returning the number
+    /// of ops read.
+    async fn import_dht_ops(
+        &self,
+        cell_id: &CellId,
+        path: &std::path::Path,
+    ) -> ConductorResult<usize>;
+
+    /// Check the databases of a cell's DNA for corruption. If `repair` is
+    /// set, corrupt data which can be fetched again from the network is removed.
+    async fn check_db_integrity(
+        &self,
+        cell_id: &CellId,
+        repair: bool,
+    ) -> ConductorResult<DbIntegrityReport>;
+
+    /// Roll back a countersigned action left at the head of a cell's chain
+    /// by a session which can no longer complete, returning its hash.
+    async fn rollback_uncommitted(&self, cell_id: &CellId) -> ConductorResult<Option<ActionHash>>;
+
+    /// The last chain head of a cell which was agreed on with the other
+    /// devices sharing its agent key.
+    fn agreed_chain_head(&self, cell_id: &CellId) -> ConductorResult<Option<ChainHead>>;
+
     /// Request access to this conductor's keystore
     fn keystore(&self) -> &MetaLairClient;
 
@@ -217,6 +355,22 @@ pub trait ConductorHandleT: Send + Sync {
     /// Destroy a cloned Cell
     async fn destroy_clone_cell(self: Arc<Self>, cell_id: CellId) -> ConductorResult<()>;
 
+    /// Create the deferred cell of an app's role, running genesis, and start
+    /// it if the app is running. Returns the ID of the cell, which may have
+    /// been provisioned already.
+    async fn provision_deferred_cell(
+        self: Arc<Self>,
+        installed_app_id: InstalledAppId,
+        role_id: AppRoleId,
+    ) -> ConductorResult<CellId>;
+
+    /// Create the deferred cell with this ID if there is one which hasn't
+    /// been created yet. Returns whether a cell was created.
+    async fn provision_deferred_cell_by_id(
+        self: Arc<Self>,
+        cell_id: &CellId,
+    ) -> ConductorResult<bool>;
+
     /// Install Cells into ConductorState based on installation info, and run
     /// genesis on all new source chains
     async fn install_app(
@@ -231,8 +385,22 @@ pub trait ConductorHandleT: Send + Sync {
         payload: InstallAppBundlePayload,
     ) -> ConductorResult<StoppedApp>;
 
+    /// Install an AppBundle once for each of several agents, each under the
+    /// app ID given by [`agent_app_id`].
+    async fn install_app_bundle_for_agents(
+        self: Arc<Self>,
+        payload: InstallAppBundleForAgentsPayload,
+    ) -> ConductorResult<Vec<StoppedApp>>;
+
     /// Uninstall an app from the state DB and remove all running Cells
-    async fn uninstall_app(self: Arc<Self>, app: &InstalledAppId) -> ConductorResult<()>;
+    /// Optionally the source chains of the app's cells are exported to a file
+    /// first, and the source chains of the cells which are removed deleted.
+    async fn uninstall_app(
+        self: Arc<Self>,
+        app: &InstalledAppId,
+        data: UninstalledAppData,
+        export_path: Option<std::path::PathBuf>,
+    ) -> ConductorResult<()>;
 
     /// Adjust app statuses (via state transitions) to match the current
     /// reality of which Cells are present in the conductor.
@@ -265,6 +433,9 @@ pub trait ConductorHandleT: Send + Sync {
     /// Start an enabled but stopped (paused) app
     async fn start_app(self: Arc<Self>, app_id: InstalledAppId) -> ConductorResult<InstalledApp>;
 
+    /// Replace the quota of an installed app
+    async fn set_app_quota(&self, app_id: InstalledAppId, quota: AppQuota) -> ConductorResult<()>;
+
     /// Start the scheduler. All ephemeral tasks are deleted.
     async fn start_scheduler(self: Arc<Self>, interval_period: std::time::Duration);
 
@@ -316,6 +487,15 @@ pub trait ConductorHandleT: Send + Sync {
     /// Dump the cells state
     async fn dump_cell_state(&self, cell_id: &CellId) -> ConductorApiResult<String>;
 
+    /// List up to `limit` records integrated into the DHT database of a
+    /// cell's DNA at or after `since`, oldest first.
+    async fn records_integrated_since(
+        &self,
+        cell_id: &CellId,
+        since: Timestamp,
+        limit: usize,
+    ) -> ConductorApiResult<Vec<IntegratedRecord>>;
+
     /// Dump the full cells state
     async fn dump_full_cell_state(
         &self,
@@ -326,6 +506,39 @@ pub trait ConductorHandleT: Send + Sync {
     /// Dump the network metrics
     async fn dump_network_metrics(&self, dna_hash: Option<DnaHash>) -> ConductorApiResult<String>;
 
+    /// Dump a summary of the network stats
+    async fn dump_network_stats(&self) -> ConductorApiResult<String>;
+
+    /// Dump the stats of the relay this conductor runs, if any
+    fn dump_relay_stats(&self) -> ConductorApiResult<String>;
+
+    /// The number of clients connected to each admin and app interface
+    fn interface_stats(&self) -> Vec<InterfaceStats>;
+
+    /// The tasks the task manager is running
+    fn task_info(&self) -> Vec<TaskInfo>;
+
+    /// Count the ops published by each running cell and integrated for its DNA
+    async fn op_counts(&self) -> ConductorApiResult<Vec<CellOpCounts>>;
+
+    /// Report how each configured bootstrap server has been responding
+    async fn bootstrap_health(&self) -> ConductorApiResult<Vec<BootstrapServerHealth>>;
+
+    /// Take the network offline or bring it back online
+    async fn set_network_enabled(&self, enabled: bool) -> ConductorApiResult<()>;
+
+    /// Record an admin call in the audit log, if it is enabled.
+    fn audit_admin_call(&self, request: &'static str, outcome: AuditOutcome);
+
+    /// Read the entries recorded in the audit log after `since` and
+    /// before `until`, oldest first, up to `limit` of them.
+    async fn query_audit_log(
+        &self,
+        since: Option<Timestamp>,
+        until: Option<Timestamp>,
+        limit: Option<usize>,
+    ) -> ConductorResult<Vec<AuditEntry>>;
+
     /// Access the broadcast Sender which will send a Signal across every
     /// attached app interface
     async fn signal_broadcaster(&self) -> SignalBroadcaster;
@@ -336,6 +549,13 @@ pub trait ConductorHandleT: Send + Sync {
         installed_app_id: &InstalledAppId,
     ) -> ConductorResult<Option<InstalledAppInfo>>;
 
+    /// Get everything about an installed App, including which of its cells
+    /// are running and the modifiers of their DNAs
+    async fn get_full_app_info(
+        &self,
+        installed_app_id: &InstalledAppId,
+    ) -> ConductorResult<Option<FullAppInfo>>;
+
     /// Add signed agent info to the conductor
     async fn add_agent_infos(&self, agent_infos: Vec<AgentInfoSigned>) -> ConductorApiResult<()>;
 
@@ -392,6 +612,10 @@ pub trait ConductorHandleT: Send + Sync {
     #[cfg(any(test, feature = "test_utils"))]
     fn get_spaces(&self) -> Spaces;
 
+    /// Retrieve the faults injected into this conductor's workflows. FOR TESTING ONLY.
+    #[cfg(any(test, feature = "test_utils"))]
+    fn fault_injector(&self) -> FaultInjector;
+
     /// Retrieve Senders for triggering workflows. FOR TESTING ONLY.
     #[cfg(any(test, feature = "test_utils"))]
     fn get_cell_triggers(&self, cell_id: &CellId) -> ConductorApiResult<QueueTriggers>;
@@ -516,6 +740,10 @@ impl ConductorHandleT for ConductorHandleImpl {
         self.conductor.check_running()
     }
 
+    fn is_ready(&self) -> bool {
+        self.conductor.is_ready()
+    }
+
     async fn add_admin_interfaces(
         self: Arc<Self>,
         configs: Vec<AdminInterfaceConfig>,
@@ -532,7 +760,8 @@ impl ConductorHandleT for ConductorHandleImpl {
         self.load_dnas().await?;
 
         // Start the task manager
-        let (task_add_sender, run_handle) = spawn_task_manager(self.clone());
+        let (task_add_sender, run_handle) =
+            spawn_task_manager(self.clone(), self.conductor.task_table.clone());
         let (task_stop_broadcaster, _) = tokio::sync::broadcast::channel::<()>(1);
         self.conductor.task_manager.share_mut(|tm| {
             if tm.is_some() {
@@ -553,16 +782,33 @@ impl ConductorHandleT for ConductorHandleImpl {
             .startup_app_interfaces_via_handle(self.clone())
             .await?;
 
+        if let Some(config) = self.conductor.config().http_gateway {
+            self.conductor
+                .start_http_gateway_via_handle(config, self.clone())
+                .await?;
+        }
+
+        if let Some(config) = self.conductor.config().health_endpoint {
+            self.conductor
+                .start_health_endpoint_via_handle(config, self.clone())
+                .await?;
+        }
+
         // We don't care what fx are returned here, since all cells need to
         // be spun up
         let _ = self.conductor.start_paused_apps().await?;
 
-        self.process_app_status_fx(AppStatusFx::SpinUp, None).await
+        let errors = self
+            .clone()
+            .process_app_status_fx(AppStatusFx::SpinUp, None)
+            .await?;
+        self.conductor.set_initialized();
+        Ok(errors)
     }
 
-    async fn add_app_interface(self: Arc<Self>, port: u16) -> ConductorResult<u16> {
+    async fn add_app_interface(self: Arc<Self>, driver: InterfaceDriver) -> ConductorResult<u16> {
         self.conductor
-            .add_app_interface_via_handle(either::Left(port), self.clone())
+            .add_app_interface_via_handle(either::Left(driver), self.clone())
             .await
     }
 
@@ -570,13 +816,54 @@ impl ConductorHandleT for ConductorHandleImpl {
         self.conductor.list_app_interfaces().await
     }
 
+    async fn list_app_interface_settings(&self) -> ConductorResult<Vec<AppInterfaceInfo>> {
+        self.conductor.app_interface_settings().await
+    }
+
+    async fn remove_app_interface(&self, port: u16) -> ConductorResult<()> {
+        self.conductor.remove_app_interface(port).await
+    }
+
     async fn register_dna(&self, dna: DnaFile) -> ConductorResult<()> {
-        let ribosome = RealRibosome::new(dna)?;
+        // Compile every zome now, so the compiled wasm is cached on disk
+        // before the first call and for later conductor starts.
+        let ribosome = tokio::task::spawn_blocking(move || {
+            let ribosome = RealRibosome::new(dna)?;
+            ribosome.precompile()?;
+            ribosome.fill_instance_pool()?;
+            RibosomeResult::Ok(ribosome)
+        })
+        .await??;
         self.register_genotype(ribosome.clone()).await?;
         self.conductor.register_phenotype(ribosome);
         Ok(())
     }
 
+    async fn fetch_dna(&self, dna_hash: DnaHash, agent: AgentPubKey) -> ConductorResult<()> {
+        use holochain_p2p::HolochainP2pSender;
+        if self.get_dna_file(&dna_hash).is_some() {
+            return Ok(());
+        }
+        // Peers only serve a DNA on its own network, so the agent joins it
+        // with an empty arc for as long as it takes to ask them.
+        let network = self.holochain_p2p();
+        let empty_arc = holochain_p2p::dht_arc::DhtArc::empty(agent.get_loc());
+        network
+            .join(dna_hash.clone(), agent.clone(), Some(empty_arc))
+            .await?;
+        let responses = network.fetch_dna(dna_hash.clone(), dna_hash.clone()).await;
+        if let Err(e) = network.leave(dna_hash.clone(), agent).await {
+            tracing::warn!(?e, %dna_hash, "Failed to leave the network of a fetched DNA");
+        }
+        for dna_file in responses?.into_iter().filter_map(|response| response.0) {
+            match dna_file.verify(&dna_hash).await {
+                Ok(()) => return self.register_dna(dna_file).await,
+                Err(e) => tracing::warn!(?e, %dna_hash, "A peer sent an invalid DNA"),
+            }
+        }
+        Err(DnaError::DnaMissing(dna_hash).into())
+    }
+
     async fn update_coordinators(
         &self,
         hash: &DnaHash,
@@ -592,10 +879,17 @@ impl ConductorHandleT for ConductorHandleImpl {
                     Some(dna) => Ok(dna),
                     None => Err(DnaError::DnaMissing(hash.to_owned())),
                 })?;
-        let _old_wasms = ribosome
+        let old_wasms = ribosome
             .dna_file
             .update_coordinators(coordinator_zomes.clone(), wasms.clone())
             .await?;
+        let ribosome = tokio::task::spawn_blocking(move || {
+            ribosome.drop_instances(&old_wasms);
+            ribosome.precompile()?;
+            ribosome.fill_instance_pool()?;
+            RibosomeResult::Ok(ribosome)
+        })
+        .await??;
 
         // Add new wasm code to db.
         self.conductor
@@ -616,8 +910,53 @@ impl ConductorHandleT for ConductorHandleImpl {
         Ok(())
     }
 
+    async fn update_runtime_properties(
+        &self,
+        hash: &DnaHash,
+        properties: YamlProperties,
+    ) -> ConductorResult<()> {
+        // Like updating coordinators, this isn't concurrent safe.
+        let mut ribosome =
+            self.conductor
+                .ribosome_store()
+                .share_ref(|d| match d.get_ribosome(hash) {
+                    Some(dna) => Ok(dna),
+                    None => Err(DnaError::DnaMissing(hash.to_owned())),
+                })?;
+        ribosome
+            .dna_file
+            .update_runtime_properties(SerializedBytes::try_from(properties)?);
+        self.conductor
+            .update_dna_def(ribosome.dna_def().clone())
+            .await?;
+
+        // Idle instances hold the ribosome they were made with, so they are
+        // replaced for new calls to read the new properties.
+        let ribosome = tokio::task::spawn_blocking(move || {
+            let wasm_hashes: Vec<_> = ribosome.dna_file.code().keys().cloned().collect();
+            ribosome.drop_instances(&wasm_hashes);
+            ribosome.fill_instance_pool()?;
+            RibosomeResult::Ok(ribosome)
+        })
+        .await??;
+
+        self.conductor
+            .ribosome_store()
+            .share_mut(|d| d.add_ribosome(ribosome));
+
+        Ok(())
+    }
+
     async fn load_dnas(&self) -> ConductorResult<()> {
         let (ribosomes, entry_defs) = self.conductor.load_wasms_into_dna_files().await?;
+        let ribosomes = tokio::task::spawn_blocking(move || {
+            let ribosomes: Vec<_> = ribosomes.into_iter().collect();
+            for (_, ribosome) in &ribosomes {
+                ribosome.fill_instance_pool()?;
+            }
+            RibosomeResult::Ok(ribosomes)
+        })
+        .await??;
         self.conductor.ribosome_store().share_mut(|ds| {
             ds.add_ribosomes(ribosomes);
             ds.add_entry_defs(entry_defs);
@@ -651,8 +990,49 @@ impl ConductorHandleT for ConductorHandleImpl {
             .share_ref(|ds| ds.get_entry_def(key))
     }
 
-    fn get_config(&self) -> &ConductorConfig {
-        &self.conductor.config
+    fn get_config(&self) -> ConductorConfig {
+        self.conductor.config()
+    }
+
+    async fn reload_config(
+        self: Arc<Self>,
+        config_path: Option<std::path::PathBuf>,
+    ) -> ConductorResult<ConfigReloadReport> {
+        let config_path = config_path
+            .or_else(|| self.conductor.config_path().cloned())
+            .ok_or_else(|| {
+                ConductorError::ConfigError(
+                    "The conductor was not started from a config file, so a path must be given"
+                        .to_string(),
+                )
+            })?;
+        let new_config = ConductorConfig::load_yaml(&config_path)?;
+        let old_config = self.get_config();
+        let mut report = old_config.reload_report(&new_config);
+        if !super::log_filter::is_reloadable() {
+            if let Some(i) = report.applied.iter().position(|s| s == "log_filter") {
+                let setting = report.applied.remove(i);
+                report.requires_restart.push(setting);
+            }
+        }
+
+        if report.applied.iter().any(|s| s == "admin_interfaces") {
+            let existing = old_config.admin_interfaces.unwrap_or_default();
+            let added = new_config
+                .admin_interfaces
+                .clone()
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|c| !existing.contains(c))
+                .collect();
+            self.clone().add_admin_interfaces(added).await?;
+        }
+
+        self.conductor
+            .apply_reloaded_config(new_config, &report)
+            .await?;
+        tracing::info!(?report, "Reloaded conductor config");
+        Ok(report)
     }
 
     #[instrument(skip(self))]
@@ -769,6 +1149,47 @@ impl ConductorHandleT for ConductorHandleImpl {
                 let signature = to_agent.sign_raw(self.keystore(), data.into()).await?;
                 respond.respond(Ok(async move { Ok(signature) }.boxed().into()));
             }
+            FetchDna {
+                dna_hash,
+                requested,
+                respond,
+                ..
+            } => {
+                // A DNA is only served to the peers on its own network.
+                let dna_file = if requested == dna_hash {
+                    self.get_dna_file(&requested)
+                } else {
+                    None
+                };
+                let response = holochain_p2p::event::FetchDnaResponse(dna_file);
+                respond.respond(Ok(async move { Ok(response) }.boxed().into()));
+            }
+            QueueRemoteSignal {
+                dna_hash,
+                to_agent_list,
+                payload,
+                respond,
+                ..
+            } => {
+                let res = self
+                    .conductor
+                    .spaces
+                    .queue_remote_signal(dna_hash, to_agent_list, payload)
+                    .await
+                    .map_err(holochain_p2p::HolochainP2pError::other);
+                respond.respond(Ok(async move { res }.boxed().into()));
+            }
+            TakeQueuedRemoteSignals {
+                dna_hash, respond, ..
+            } => {
+                let res = self
+                    .conductor
+                    .spaces
+                    .take_queued_remote_signals(dna_hash)
+                    .await
+                    .map_err(holochain_p2p::HolochainP2pError::other);
+                respond.respond(Ok(async move { res }.boxed().into()));
+            }
             HolochainP2pEvent::CallRemote { .. }
             | CountersigningSessionNegotiation { .. }
             | GetValidationPackage { .. }
@@ -849,18 +1270,84 @@ impl ConductorHandleT for ConductorHandleImpl {
     }
 
     async fn call_zome(&self, call: ZomeCall) -> ConductorApiResult<ZomeCallResult> {
-        let cell = self.cell_by_id(&call.cell_id)?;
-        Ok(cell.call_zome(call, None).await?)
+        self.call_zome_cancellable(call, ZomeCallCancel::default())
+            .await
+    }
+
+    async fn call_zome_cancellable(
+        &self,
+        call: ZomeCall,
+        cancel: ZomeCallCancel,
+    ) -> ConductorApiResult<ZomeCallResult> {
+        let audit_log = self
+            .conductor
+            .audit_log
+            .as_ref()
+            .filter(|audit_log| audit_log.include_zome_calls());
+        let event = audit_log.map(|_| AuditEvent::ZomeCall {
+            cell_id: call.cell_id.clone(),
+            zome_name: call.zome_name.clone(),
+            fn_name: call.fn_name.clone(),
+            provenance: call.provenance.clone(),
+        });
+        let result = async {
+            self.check_zome_call_quotas(&call.cell_id).await?;
+            let _permit = self.conductor.zome_call_permit().await?;
+            let cell = self.cell_by_id(&call.cell_id)?;
+            ConductorApiResult::Ok(cell.call_zome(call, None, cancel).await?)
+        }
+        .await;
+        if let (Some(audit_log), Some(event)) = (audit_log, event) {
+            let outcome = match &result {
+                Ok(Ok(ZomeCallResponse::Ok(_))) => AuditOutcome::Success,
+                Ok(Ok(response)) => AuditOutcome::Error {
+                    message: format!("{:?}", response),
+                },
+                Ok(Err(e)) => AuditOutcome::Error {
+                    message: e.to_string(),
+                },
+                Err(e) => AuditOutcome::Error {
+                    message: e.to_string(),
+                },
+            };
+            audit_log.record(event, outcome);
+        }
+        result
+    }
+
+    async fn check_zome_fn_access(
+        &self,
+        call: &ZomeCall,
+        origin: ZomeCallOrigin,
+    ) -> ConductorResult<()> {
+        let access = &self.conductor.zome_fn_access;
+        if !access.is_loaded() {
+            access.refresh(&self.conductor.get_state().await?);
+        }
+        if access.allows(call, &origin) {
+            Ok(())
+        } else {
+            Err(ConductorError::ZomeFnAccessDenied {
+                zome_name: call.zome_name.clone(),
+                fn_name: call.fn_name.clone(),
+                origin,
+            })
+        }
+    }
+
+    async fn verify_signed_zome_call(&self, call: SignedZomeCall) -> ConductorApiResult<ZomeCall> {
+        Ok(self.conductor.verify_signed_zome_call(call).await?)
     }
 
     async fn call_zome_with_workspace(
         &self,
         call: ZomeCall,
         workspace_lock: SourceChainWorkspace,
+        cancel: ZomeCallCancel,
     ) -> ConductorApiResult<ZomeCallResult> {
         debug!(cell_id = ?call.cell_id);
         let cell = self.cell_by_id(&call.cell_id)?;
-        Ok(cell.call_zome(call, Some(workspace_lock)).await?)
+        Ok(cell.call_zome(call, Some(workspace_lock), cancel).await?)
     }
 
     fn take_shutdown_handle(&self) -> Option<TaskManagerRunHandle> {
@@ -875,10 +1362,73 @@ impl ConductorHandleT for ConductorHandleImpl {
         self.conductor.get_queue_consumer_workflows()
     }
 
+    fn get_workflow_scheduler(&self) -> WorkflowScheduler {
+        self.conductor.spaces.workflow_scheduler.clone()
+    }
+
+    fn set_dna_scheduling_weight(&self, dna_hash: DnaHash, weight: u32) {
+        self.conductor
+            .spaces
+            .workflow_scheduler
+            .set_weight(Arc::new(dna_hash), weight)
+    }
+
     fn shutdown(&self) {
         self.conductor.shutdown()
     }
 
+    async fn graceful_shutdown(&self, drain_timeout: std::time::Duration) {
+        self.conductor.graceful_shutdown(drain_timeout).await
+    }
+
+    async fn close_storage_and_network(&self) -> ConductorResult<()> {
+        self.conductor.close_storage_and_network().await
+    }
+
+    async fn backup_cell(&self, cell_id: &CellId, path: &std::path::Path) -> ConductorResult<()> {
+        self.conductor.backup_cell(cell_id, path).await
+    }
+
+    async fn restore_cell(&self, cell_id: &CellId, path: &std::path::Path) -> ConductorResult<()> {
+        self.conductor.restore_cell(cell_id, path).await
+    }
+
+    async fn backup_conductor(&self, path: &std::path::Path) -> ConductorResult<()> {
+        self.conductor.backup_conductor(path).await
+    }
+
+    async fn export_dht_ops(
+        &self,
+        cell_id: &CellId,
+        path: &std::path::Path,
+    ) -> ConductorResult<usize> {
+        self.conductor.export_dht_ops(cell_id, path).await
+    }
+
+    async fn import_dht_ops(
+        &self,
+        cell_id: &CellId,
+        path: &std::path::Path,
+    ) -> ConductorResult<usize> {
+        self.conductor.import_dht_ops(cell_id, path).await
+    }
+
+    async fn check_db_integrity(
+        &self,
+        cell_id: &CellId,
+        repair: bool,
+    ) -> ConductorResult<DbIntegrityReport> {
+        self.conductor.check_db_integrity(cell_id, repair).await
+    }
+
+    async fn rollback_uncommitted(&self, cell_id: &CellId) -> ConductorResult<Option<ActionHash>> {
+        self.conductor.rollback_uncommitted(cell_id).await
+    }
+
+    fn agreed_chain_head(&self, cell_id: &CellId) -> ConductorResult<Option<ChainHead>> {
+        Ok(self.conductor.cell_by_id(cell_id)?.agreed_chain_head())
+    }
+
     fn keystore(&self) -> &MetaLairClient {
         self.conductor.keystore()
     }
@@ -903,12 +1453,43 @@ impl ConductorHandleT for ConductorHandleImpl {
             role_id,
             membrane_proof,
         } = payload;
+        if let Err(e) = self
+            .conductor
+            .check_clone_cell_quota(&installed_app_id)
+            .await
+        {
+            if let ConductorError::QuotaExceeded(exceeded) = &e {
+                self.signal_quota_exceeded(exceeded.clone()).await;
+            }
+            return Err(e);
+        }
+        let base_dna_hash = self
+            .conductor
+            .get_state()
+            .await?
+            .get_app(&installed_app_id)?
+            .role(&role_id)?
+            .dna_hash()
+            .clone();
+        // An invite to a clone which another agent created names the DNA of
+        // the clone, which is fetched from its peers if this conductor
+        // doesn't have it.
+        let invited = dna_hash != base_dna_hash;
+        if invited {
+            self.fetch_dna(dna_hash.clone(), agent_key.clone()).await?;
+        }
         let cell_id = CellId::new(dna_hash, agent_key);
         let cells = vec![(cell_id.clone(), membrane_proof)];
 
         // Run genesis on cells.
         crate::conductor::conductor::genesis_cells(&self.conductor, cells, self.clone()).await?;
 
+        if invited {
+            self.conductor
+                .add_existing_clone_cell_to_app(installed_app_id, role_id, cell_id.clone())
+                .await?;
+            return Ok(cell_id);
+        }
         let properties = properties.unwrap_or_else(|| ().into());
         let cell_id = self
             .conductor
@@ -921,6 +1502,53 @@ impl ConductorHandleT for ConductorHandleImpl {
         todo!()
     }
 
+    async fn provision_deferred_cell(
+        self: Arc<Self>,
+        installed_app_id: InstalledAppId,
+        role_id: AppRoleId,
+    ) -> ConductorResult<CellId> {
+        let (cell_id, membrane_proof) = {
+            let state = self.conductor.get_state().await?;
+            let role = state.get_app(&installed_app_id)?.role(&role_id)?;
+            if let Some(cell_id) = role.provisioned_cell() {
+                return Ok(cell_id.clone());
+            }
+            (role.cell_id().clone(), role.membrane_proof().cloned())
+        };
+
+        // Genesis does nothing if it has already run, so it's fine if this
+        // races with another call provisioning the same cell.
+        let cells = vec![(cell_id.clone(), membrane_proof)];
+        crate::conductor::conductor::genesis_cells(&self.conductor, cells, self.clone()).await?;
+        self.conductor
+            .provision_deferred_cell_in_app(installed_app_id.clone(), role_id)
+            .await?;
+
+        // Join the new cell to the network if the app is running.
+        self.clone()
+            .process_app_status_fx(
+                AppStatusFx::SpinUp,
+                Some(vec![installed_app_id].into_iter().collect()),
+            )
+            .await?;
+        Ok(cell_id)
+    }
+
+    async fn provision_deferred_cell_by_id(
+        self: Arc<Self>,
+        cell_id: &CellId,
+    ) -> ConductorResult<bool> {
+        let deferred = self.conductor.find_deferred_cell(cell_id).await?;
+        match deferred {
+            Some((installed_app_id, role_id)) => {
+                self.provision_deferred_cell(installed_app_id, role_id)
+                    .await?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
     async fn install_app(
         self: Arc<Self>,
         installed_app_id: InstalledAppId,
@@ -970,8 +1598,44 @@ impl ConductorHandleT for ConductorHandleImpl {
 
         let installed_app_id =
             installed_app_id.unwrap_or_else(|| bundle.manifest().app_name().to_owned());
+        let gamut = DnaGamut::new(
+            self.conductor
+                .get_state()
+                .await?
+                .installed_apps()
+                .values()
+                .flat_map(|app| app.all_cells().cloned()),
+        );
+        let mut available_dnas = HashMap::new();
+        for (version, required) in bundle.existing_dna_versions()? {
+            if let CellResolution::Match(..) = gamut.resolve_cell(version.clone(), &agent_key) {
+                continue;
+            }
+            let hashes: Vec<DnaHash> = version
+                .dna_hashes()
+                .into_iter()
+                .map(|hash| hash.clone().into())
+                .collect();
+            // A role can name a DNA which this conductor doesn't have, e.g.
+            // from an invite, in which case it's fetched from its peers.
+            if required && !hashes.iter().any(|hash| self.get_dna_file(hash).is_some()) {
+                for hash in &hashes {
+                    match self.fetch_dna(hash.clone(), agent_key.clone()).await {
+                        Ok(()) => break,
+                        Err(e) => {
+                            tracing::warn!(?e, %hash, "Failed to fetch the DNA of an app role")
+                        }
+                    }
+                }
+            }
+            available_dnas.extend(
+                hashes
+                    .into_iter()
+                    .filter_map(|hash| Some((hash.clone(), self.get_dna_file(&hash)?))),
+            );
+        }
         let ops = bundle
-            .resolve_cells(agent_key.clone(), DnaGamut::placeholder(), membrane_proofs)
+            .resolve_cells(agent_key.clone(), gamut, available_dnas, membrane_proofs)
             .await?;
 
         let cells_to_create = ops.cells_to_create();
@@ -992,6 +1656,53 @@ impl ConductorHandleT for ConductorHandleImpl {
         Ok(stopped_app)
     }
 
+    async fn install_app_bundle_for_agents(
+        self: Arc<Self>,
+        payload: InstallAppBundleForAgentsPayload,
+    ) -> ConductorResult<Vec<StoppedApp>> {
+        let InstallAppBundleForAgentsPayload {
+            source,
+            agent_keys,
+            installed_app_id,
+            mut membrane_proofs,
+            network_seed,
+        } = payload;
+
+        // The bundle is read once, and decoded again for each agent.
+        let bundle = source.resolve().await?;
+        let installed_app_id =
+            installed_app_id.unwrap_or_else(|| bundle.manifest().app_name().to_owned());
+        let bytes = bundle.encode()?;
+
+        let mut apps = Vec::with_capacity(agent_keys.len());
+        for agent_key in agent_keys {
+            let payload = InstallAppBundlePayload {
+                source: AppBundleSource::Bundle(AppBundle::decode(&bytes)?),
+                installed_app_id: Some(agent_app_id(&installed_app_id, &agent_key)),
+                membrane_proofs: membrane_proofs.remove(&agent_key).unwrap_or_default(),
+                network_seed: network_seed.clone(),
+                agent_key,
+            };
+            match self.clone().install_app_bundle(payload).await {
+                Ok(app) => apps.push(app),
+                Err(e) => {
+                    // Leave none of the agents with the app installed.
+                    for app in apps {
+                        if let Err(e) = self
+                            .clone()
+                            .uninstall_app(app.id(), UninstalledAppData::Retain, None)
+                            .await
+                        {
+                            tracing::error!(?e, app_id = %app.id(), "Failed to uninstall app");
+                        }
+                    }
+                    return Err(e);
+                }
+            }
+        }
+        Ok(apps)
+    }
+
     /// Start the scheduler. None is not an option.
     /// Calling this will:
     /// - Delete/unschedule all ephemeral scheduled functions GLOBALLY
@@ -1108,6 +1819,10 @@ impl ConductorHandleT for ConductorHandleImpl {
         Ok(app)
     }
 
+    async fn set_app_quota(&self, app_id: InstalledAppId, quota: AppQuota) -> ConductorResult<()> {
+        self.conductor.set_app_quota(app_id, quota).await
+    }
+
     #[tracing::instrument(skip(self))]
     #[cfg(any(test, feature = "test_utils"))]
     async fn pause_app(
@@ -1128,7 +1843,21 @@ impl ConductorHandleT for ConductorHandleImpl {
     async fn uninstall_app(
         self: Arc<Self>,
         installed_app_id: &InstalledAppId,
+        data: UninstalledAppData,
+        export_path: Option<std::path::PathBuf>,
     ) -> ConductorResult<()> {
+        if let Some(path) = export_path {
+            let state = self.conductor.get_state().await?;
+            let cell_ids: Vec<_> = state
+                .get_app(installed_app_id)?
+                .all_cells()
+                .cloned()
+                .collect();
+            self.conductor
+                .export_source_chains(&cell_ids, &path)
+                .await?;
+        }
+
         let self_clone = self.clone();
         let app = self.conductor.remove_app_from_db(installed_app_id).await?;
         tracing::debug!(msg = "Removed app from db.", app = ?app);
@@ -1137,6 +1866,22 @@ impl ConductorHandleT for ConductorHandleImpl {
         self_clone
             .process_app_status_fx(AppStatusFx::SpinDown, None)
             .await?;
+
+        if data == UninstalledAppData::Delete {
+            // Cells which are still used by other apps keep their data.
+            let state = self.conductor.get_state().await?;
+            let in_use: HashSet<&CellId> = state
+                .installed_apps()
+                .iter()
+                .flat_map(|(_, app)| app.all_cells())
+                .collect();
+            let removed = app
+                .all_cells()
+                .filter(|cell_id| !in_use.contains(cell_id))
+                .cloned()
+                .collect();
+            self.conductor.delete_source_chains(removed).await?;
+        }
         Ok(())
     }
 
@@ -1194,10 +1939,13 @@ impl ConductorHandleT for ConductorHandleImpl {
             source_chain::dump_state(authored_db.clone().into(), cell_id.agent_pubkey().clone())
                 .await?;
 
+        let init_status = self.cell_init_status(cell_id, &source_chain_dump);
+
         let out = JsonDump {
             peer_dump,
             source_chain_dump,
             integration_dump: integration_dump(&dht_db.clone().into()).await?,
+            init_status,
         };
         // Add summary
         let summary = out.to_string();
@@ -1205,6 +1953,29 @@ impl ConductorHandleT for ConductorHandleImpl {
         Ok(serde_json::to_string_pretty(&out)?)
     }
 
+    async fn records_integrated_since(
+        &self,
+        cell_id: &CellId,
+        since: Timestamp,
+        limit: usize,
+    ) -> ConductorApiResult<Vec<IntegratedRecord>> {
+        let dht_db = self.conductor.cell_by_id(cell_id)?.dht_db().clone();
+        let records = dht_db
+            .async_reader(move |txn| {
+                holochain_state::query::integrated_since::records_integrated_since(
+                    &txn, since, limit,
+                )
+            })
+            .await?;
+        Ok(records
+            .into_iter()
+            .map(|(when_integrated, record)| IntegratedRecord {
+                when_integrated,
+                record,
+            })
+            .collect())
+    }
+
     async fn dump_full_cell_state(
         &self,
         cell_id: &CellId,
@@ -1222,10 +1993,13 @@ impl ConductorHandleT for ConductorHandleImpl {
         let source_chain_dump =
             source_chain::dump_state(authored_db.into(), cell_id.agent_pubkey().clone()).await?;
 
+        let init_status = self.cell_init_status(cell_id, &source_chain_dump);
+
         let out = FullStateDump {
             peer_dump,
             source_chain_dump,
             integration_dump: full_integration_dump(&dht_db, dht_ops_cursor).await?,
+            init_status,
         };
         Ok(out)
     }
@@ -1238,6 +2012,74 @@ impl ConductorHandleT for ConductorHandleImpl {
             .map_err(super::api::error::ConductorApiError::other)
     }
 
+    async fn dump_network_stats(&self) -> ConductorApiResult<String> {
+        use holochain_p2p::HolochainP2pSender;
+        self.holochain_p2p()
+            .dump_network_stats()
+            .await
+            .map_err(super::api::error::ConductorApiError::other)
+    }
+
+    fn dump_relay_stats(&self) -> ConductorApiResult<String> {
+        Ok(self.conductor.relay_stats()?)
+    }
+
+    fn interface_stats(&self) -> Vec<InterfaceStats> {
+        self.conductor.interface_stats()
+    }
+
+    fn task_info(&self) -> Vec<TaskInfo> {
+        self.conductor.task_table.info()
+    }
+
+    async fn op_counts(&self) -> ConductorApiResult<Vec<CellOpCounts>> {
+        let mut counts = Vec::new();
+        for cell_id in self.list_cell_ids(Some(CellStatus::Joined)) {
+            let dna_hash = cell_id.dna_hash();
+            let authored_db = self.conductor.get_or_create_authored_db(dna_hash)?;
+            let dht_db = self.conductor.get_or_create_dht_db(dna_hash)?;
+            counts.push(cell_op_counts(cell_id, &authored_db.into(), &dht_db.into()).await?);
+        }
+        Ok(counts)
+    }
+
+    async fn bootstrap_health(&self) -> ConductorApiResult<Vec<BootstrapServerHealth>> {
+        use holochain_p2p::HolochainP2pSender;
+        self.holochain_p2p()
+            .bootstrap_health()
+            .await
+            .map_err(super::api::error::ConductorApiError::other)
+    }
+
+    async fn set_network_enabled(&self, enabled: bool) -> ConductorApiResult<()> {
+        use holochain_p2p::HolochainP2pSender;
+        self.holochain_p2p()
+            .set_network_enabled(enabled)
+            .await
+            .map_err(super::api::error::ConductorApiError::other)
+    }
+
+    fn audit_admin_call(&self, request: &'static str, outcome: AuditOutcome) {
+        if let Some(audit_log) = &self.conductor.audit_log {
+            let request = request.to_string();
+            audit_log.record(AuditEvent::AdminCall { request }, outcome);
+        }
+    }
+
+    async fn query_audit_log(
+        &self,
+        since: Option<Timestamp>,
+        until: Option<Timestamp>,
+        limit: Option<usize>,
+    ) -> ConductorResult<Vec<AuditEntry>> {
+        let audit_log = self
+            .conductor
+            .audit_log
+            .clone()
+            .ok_or(ConductorError::AuditLogDisabled)?;
+        Ok(tokio::task::spawn_blocking(move || audit_log.query(since, until, limit)).await??)
+    }
+
     async fn signal_broadcaster(&self) -> SignalBroadcaster {
         self.conductor.signal_broadcaster()
     }
@@ -1253,6 +2095,23 @@ impl ConductorHandleT for ConductorHandleImpl {
             .get_app_info(installed_app_id))
     }
 
+    async fn get_full_app_info(
+        &self,
+        installed_app_id: &InstalledAppId,
+    ) -> ConductorResult<Option<FullAppInfo>> {
+        let state = self.conductor.get_state().await?;
+        let app = match state.installed_apps().get(installed_app_id) {
+            Some(app) => app,
+            None => return Ok(None),
+        };
+        let running = self.conductor.running_cell_ids();
+        Ok(Some(FullAppInfo::from_installed_app(
+            app,
+            |cell_id| running.contains(cell_id),
+            |dna_hash| self.get_dna_def(dna_hash),
+        )))
+    }
+
     async fn add_agent_infos(&self, agent_infos: Vec<AgentInfoSigned>) -> ConductorApiResult<()> {
         let mut space_map = HashMap::new();
         for agent_info_signed in agent_infos {
@@ -1508,6 +2367,11 @@ impl ConductorHandleT for ConductorHandleImpl {
         self.conductor.spaces.clone()
     }
 
+    #[cfg(any(test, feature = "test_utils"))]
+    fn fault_injector(&self) -> FaultInjector {
+        self.conductor.spaces.faults.clone()
+    }
+
     #[cfg(any(test, feature = "test_utils"))]
     fn get_cell_triggers(&self, cell_id: &CellId) -> ConductorApiResult<QueueTriggers> {
         let cell = self.cell_by_id(cell_id)?;
@@ -1559,6 +2423,37 @@ impl ConductorHandleImpl {
         Ok(self.conductor.cell_by_id(cell_id)?)
     }
 
+    /// Count a zome call to this cell against the quotas of the apps it
+    /// belongs to, failing if it would exceed one of them.
+    async fn check_zome_call_quotas(&self, cell_id: &CellId) -> ConductorResult<()> {
+        let quotas = &self.conductor.quotas;
+        if !quotas.is_loaded() {
+            quotas.refresh(&self.conductor.get_state().await?);
+        }
+        let now = std::time::Instant::now();
+        for (app_id, cell_ids) in quotas.unmeasured_apps(cell_id, now) {
+            let bytes = self.conductor.disk_bytes(&cell_ids).await?;
+            quotas.record_disk_usage(&app_id, bytes, now);
+        }
+        match quotas.count_zome_call(cell_id, now) {
+            Ok(()) => Ok(()),
+            Err(refusal) => {
+                if refusal.first {
+                    self.signal_quota_exceeded(refusal.exceeded.clone()).await;
+                }
+                Err(ConductorError::QuotaExceeded(refusal.exceeded))
+            }
+        }
+    }
+
+    async fn signal_quota_exceeded(&self, exceeded: AppQuotaExceeded) {
+        tracing::warn!("{}", exceeded);
+        let signal = SystemSignal::QuotaExceeded(exceeded);
+        if let Err(e) = self.signal_broadcaster().await.send(signal.into()) {
+            tracing::warn!(?e, "Failed to broadcast QuotaExceeded signal");
+        }
+    }
+
     /// Install just the "code parts" (the wasm and entry defs) of a dna
     async fn register_genotype(&self, ribosome: RealRibosome) -> ConductorResult<()> {
         let entry_defs = self.conductor.register_dna_wasm(ribosome).await?;
@@ -1712,6 +2607,29 @@ impl ConductorHandleImpl {
         cell_ids
     }
 
+    /// The init status of the cell, falling back to the source chain when the
+    /// cell isn't running or hasn't been called since it was created.
+    fn cell_init_status(
+        &self,
+        cell_id: &CellId,
+        source_chain_dump: &source_chain::SourceChainJsonDump,
+    ) -> CellInitStatus {
+        let status = self
+            .conductor
+            .cell_by_id(cell_id)
+            .map(|cell| cell.init_status())
+            .unwrap_or(CellInitStatus::NotRun);
+        let initialized = source_chain_dump
+            .records
+            .iter()
+            .any(|r| matches!(r.action, Action::InitZomesComplete(_)));
+        if status == CellInitStatus::NotRun && initialized {
+            CellInitStatus::Initialized
+        } else {
+            status
+        }
+    }
+
     pub(super) fn p2p_agents_db(&self, hash: &DnaHash) -> DbWrite<DbKindP2pAgents> {
         self.conductor
             .spaces
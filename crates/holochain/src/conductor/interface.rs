@@ -4,6 +4,8 @@
 //!
 //! Currently the only InterfaceDriver is a Websocket-based one, whose
 //! implementation can be found in the `websocket` module here.
+//! The `http` module has an optional HTTP gateway for making zome calls,
+//! and the `health` module an optional health endpoint.
 
 use crate::conductor::api::*;
 use error::InterfaceError;
@@ -12,8 +14,15 @@ use holochain_types::signal::Signal;
 use std::convert::TryInto;
 use tokio::sync::broadcast;
 
+pub mod connections;
 #[allow(missing_docs)]
 pub mod error;
+pub mod health;
+pub mod http;
+pub(crate) mod signal_subscriptions;
+pub mod socket_activation;
+pub(crate) mod started_zome_calls;
+mod transcode;
 pub mod websocket;
 
 /// Represents runtime data about an existing App interface.
@@ -24,6 +33,8 @@ pub enum AppInterfaceRuntime {
     Websocket {
         /// The channel for this interface to send Signals across
         signal_tx: broadcast::Sender<Signal>,
+        /// Closes this interface and its connections when it's detached
+        stop_tx: broadcast::Sender<()>,
     },
 
     #[cfg(any(test, feature = "test_utils"))]
@@ -43,6 +54,13 @@ impl AppInterfaceRuntime {
             Self::Test { signal_tx, .. } => signal_tx,
         }
     }
+
+    /// Close the interface and its connections.
+    pub fn stop(&self) {
+        if let Self::Websocket { stop_tx, .. } = self {
+            let _ = stop_tx.send(());
+        }
+    }
 }
 
 /// A collection of Senders to be used for emitting Signals from a Cell.
@@ -0,0 +1,110 @@
+//! Counts of the clients connected to each of the conductor's interfaces,
+//! for [`AdminRequest::DumpInterfaceStats`].
+//!
+//! [`AdminRequest::DumpInterfaceStats`]: holochain_conductor_api::AdminRequest::DumpInterfaceStats
+
+use holochain_conductor_api::InterfaceDriver;
+use holochain_conductor_api::InterfaceStats;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+/// Counts the open connections of one interface.
+#[derive(Clone, Default)]
+pub struct ConnectionCounter(Arc<AtomicUsize>);
+
+impl ConnectionCounter {
+    /// Count a connection as open until the returned guard is dropped.
+    pub fn open(&self) -> OpenConnection {
+        self.0.fetch_add(1, Ordering::Relaxed);
+        OpenConnection(self.0.clone())
+    }
+
+    /// The number of open connections.
+    pub fn count(&self) -> usize {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// A connection counted by a [`ConnectionCounter`], for as long as this is
+/// held.
+pub struct OpenConnection(Arc<AtomicUsize>);
+
+impl Drop for OpenConnection {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// An interface and the count of its connections.
+struct CountedInterface {
+    driver: InterfaceDriver,
+    admin: bool,
+    connections: ConnectionCounter,
+}
+
+/// The connection counts of all the interfaces a conductor has open.
+#[derive(Default)]
+pub(crate) struct InterfaceConnections(parking_lot::Mutex<Vec<CountedInterface>>);
+
+impl InterfaceConnections {
+    /// Report the connections counted for an interface.
+    pub(crate) fn add(&self, driver: InterfaceDriver, admin: bool, connections: ConnectionCounter) {
+        self.0.lock().push(CountedInterface {
+            driver,
+            admin,
+            connections,
+        });
+    }
+
+    /// Stop counting the connections of an interface which has been closed.
+    pub(crate) fn remove(&self, driver: &InterfaceDriver) {
+        self.0
+            .lock()
+            .retain(|interface| &interface.driver != driver);
+    }
+
+    /// The stats of each interface, admin interfaces first.
+    pub(crate) fn stats(&self) -> Vec<InterfaceStats> {
+        let mut stats: Vec<_> = self
+            .0
+            .lock()
+            .iter()
+            .map(|interface| InterfaceStats {
+                driver: interface.driver.clone(),
+                admin: interface.admin,
+                active_connections: interface.connections.count(),
+            })
+            .collect();
+        stats.sort_by_key(|stats| !stats.admin);
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn connections_are_counted_while_open() {
+        let interfaces = InterfaceConnections::default();
+        let counter = ConnectionCounter::default();
+        interfaces.add(InterfaceDriver::websocket(1234), false, counter.clone());
+        interfaces.add(
+            InterfaceDriver::websocket(5678),
+            true,
+            ConnectionCounter::default(),
+        );
+
+        let first = counter.open();
+        let second = counter.open();
+        let stats = interfaces.stats();
+        assert_eq!(stats[0].driver.port(), 5678);
+        assert_eq!(stats[0].active_connections, 0);
+        assert_eq!(stats[1].active_connections, 2);
+
+        drop(first);
+        drop(second);
+        assert_eq!(interfaces.stats()[1].active_connections, 0);
+    }
+}
@@ -0,0 +1,155 @@
+//! A health endpoint, configured with [`HealthEndpointConfig`], which
+//! service managers and container orchestrators can probe to tell whether
+//! the conductor is alive, and whether it's ready for its interfaces to be
+//! used.
+//!
+//! `GET /livez` answers `200 OK` while the conductor is running. `GET
+//! /readyz` answers `200 OK` once the conductor has opened its interfaces and
+//! started its apps, and `503 Service Unavailable` while it's still starting
+//! or is shutting down. Both answer with the state of the conductor:
+//!
+//! ```json
+//! { "status": "ready" }
+//! ```
+//!
+//! where the status is one of `starting`, `ready` or `shutting_down`.
+
+use super::error::InterfaceResult;
+use super::http::bind_http;
+use crate::conductor::conductor::StopReceiver;
+use crate::conductor::manager::ManagedTaskHandle;
+use crate::conductor::manager::ManagedTaskResult;
+use crate::conductor::ConductorHandle;
+use holochain_conductor_api::conductor::HealthEndpointConfig;
+use hyper::header;
+use hyper::service::make_service_fn;
+use hyper::service::service_fn;
+use hyper::Body;
+use hyper::Method;
+use hyper::Request;
+use hyper::Response;
+use hyper::StatusCode;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+
+/// The state of the conductor, as reported by the health endpoint.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+enum HealthStatus {
+    Starting,
+    Ready,
+    ShuttingDown,
+}
+
+impl HealthStatus {
+    fn of(conductor_handle: &ConductorHandle) -> Self {
+        if conductor_handle.check_running().is_err() {
+            Self::ShuttingDown
+        } else if conductor_handle.is_ready() {
+            Self::Ready
+        } else {
+            Self::Starting
+        }
+    }
+}
+
+/// Spawn the health endpoint, returning the port it listens on.
+pub async fn spawn_health_endpoint_task(
+    config: HealthEndpointConfig,
+    conductor_handle: ConductorHandle,
+    mut stop_rx: StopReceiver,
+) -> InterfaceResult<(u16, ManagedTaskHandle)> {
+    let addr = SocketAddr::new(config.bind_address(), config.port);
+    let make_service = make_service_fn(move |_| {
+        let conductor_handle = conductor_handle.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request| {
+                let response = handle(request, HealthStatus::of(&conductor_handle));
+                async move { Ok::<_, Infallible>(response) }
+            }))
+        }
+    });
+    let server = bind_http(addr)?.serve(make_service);
+    let port = server.local_addr().port();
+    tracing::info!("Health endpoint listening at: {}", server.local_addr());
+    let server = server.with_graceful_shutdown(async move {
+        let _ = stop_rx.recv().await;
+    });
+    let task = tokio::task::spawn(async move {
+        server
+            .await
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+        ManagedTaskResult::Ok(())
+    });
+    Ok((port, task))
+}
+
+fn handle(request: Request<Body>, status: HealthStatus) -> Response<Body> {
+    let code = match (request.method(), request.uri().path()) {
+        (&Method::GET, "/livez") => StatusCode::OK,
+        (&Method::GET, "/readyz") if status == HealthStatus::Ready => StatusCode::OK,
+        (&Method::GET, "/readyz") => StatusCode::SERVICE_UNAVAILABLE,
+        (_, "/livez" | "/readyz") => StatusCode::METHOD_NOT_ALLOWED,
+        _ => StatusCode::NOT_FOUND,
+    };
+    Response::builder()
+        .status(code)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(
+            serde_json::json!({ "status": status }).to_string(),
+        ))
+        .expect("response headers are valid")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sweettest::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn health_is_reported_over_http() {
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let mut config = standard_config();
+        config.health_endpoint = Some(HealthEndpointConfig {
+            port,
+            bind_address: None,
+        });
+        let _conductor = SweetConductor::from_config(config).await;
+
+        let get = |path: &str| reqwest::get(format!("http://127.0.0.1:{}{}", port, path));
+        let response = get("/livez").await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = get("/readyz").await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: serde_json::Value =
+            serde_json::from_slice(&response.bytes().await.unwrap()).unwrap();
+        assert_eq!(body, serde_json::json!({ "status": "ready" }));
+        let response = get("/nothing").await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn readiness_follows_the_conductor() {
+        let request = || {
+            Request::get("/readyz")
+                .body(Body::empty())
+                .expect("request is valid")
+        };
+        assert_eq!(
+            handle(request(), HealthStatus::Starting).status(),
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+        assert_eq!(
+            handle(request(), HealthStatus::ShuttingDown).status(),
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+        assert_eq!(
+            handle(request(), HealthStatus::Ready).status(),
+            StatusCode::OK
+        );
+    }
+}
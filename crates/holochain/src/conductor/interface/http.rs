@@ -0,0 +1,438 @@
+//! An HTTP gateway, configured with [`HttpGatewayConfig`], which makes zome
+//! calls for POSTed requests, so services and scripts can call apps without
+//! speaking the websocket app interface's wire protocol.
+//!
+//! Requests are POSTed to `/zome_call` with a bearer token in the
+//! `Authorization` header. The body names the cell to call by app and role:
+//!
+//! ```json
+//! {
+//!   "installed_app_id": "chat",
+//!   "role_id": "chat",
+//!   "zome_name": "messages",
+//!   "fn_name": "post_message",
+//!   "payload": { "content": "hello" }
+//! }
+//! ```
+//!
+//! The JSON payload is transcoded to msgpack for the zome, and the zome's
+//! output is transcoded back to JSON, with hashes written as base64 strings.
+//! Clients which can encode msgpack may instead send and receive it as
+//! `application/msgpack`, in which case the payload is the msgpack encoded
+//! input and the response is the zome's output as is.
+//!
+//! A token with an `agent_key` calls the app installed for that agent with
+//! `InstallAppBundleForAgents` when it names the app, so each agent of a
+//! hosted app can be given a token which only reaches their own cells.
+//!
+//! A W3C `traceparent` header makes the call part of the client's trace,
+//! if spans are exported to OpenTelemetry.
+
+use super::error::InterfaceError;
+use super::error::InterfaceResult;
+use super::socket_activation;
+use super::transcode;
+use crate::conductor::api::AppInterfaceApi;
+use crate::conductor::api::AppRequest;
+use crate::conductor::api::AppResponse;
+use crate::conductor::api::ExternalApiWireError;
+use crate::conductor::api::RealAppInterfaceApi;
+use crate::conductor::api::ZomeCall;
+use crate::conductor::conductor::StopReceiver;
+use crate::conductor::manager::ManagedTaskHandle;
+use crate::conductor::manager::ManagedTaskResult;
+use crate::conductor::ConductorHandle;
+use holochain_conductor_api::conductor::HttpGatewayConfig;
+use holochain_types::prelude::*;
+use hyper::body::HttpBody;
+use hyper::header;
+use hyper::server::conn::AddrIncoming;
+use hyper::service::make_service_fn;
+use hyper::service::service_fn;
+use hyper::Body;
+use hyper::Method;
+use hyper::Request;
+use hyper::Response;
+use hyper::StatusCode;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tracing::Instrument;
+
+/// The content type of msgpack requests and responses.
+const MSGPACK: &str = "application/msgpack";
+
+/// A zome call POSTed to the gateway.
+#[derive(Debug, serde::Deserialize)]
+struct HttpZomeCall<P> {
+    installed_app_id: InstalledAppId,
+    role_id: AppRoleId,
+    zome_name: ZomeName,
+    fn_name: FunctionName,
+    payload: P,
+    #[serde(default)]
+    cap_secret: Option<CapSecret>,
+}
+
+/// Spawn the HTTP gateway, returning the port it listens on.
+pub async fn spawn_http_gateway_task(
+    config: HttpGatewayConfig,
+    conductor_handle: ConductorHandle,
+    mut stop_rx: StopReceiver,
+) -> InterfaceResult<(u16, ManagedTaskHandle)> {
+    let addr = SocketAddr::new(config.bind_address(), config.port);
+    let gateway = Arc::new(HttpGateway {
+        config,
+        api: RealAppInterfaceApi::new(conductor_handle.clone()),
+        conductor_handle,
+    });
+    let make_service = make_service_fn(move |_| {
+        let gateway = gateway.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request| {
+                let gateway = gateway.clone();
+                async move { Ok::<_, Infallible>(gateway.handle(request).await) }
+            }))
+        }
+    });
+    let server = bind_http(addr)?.serve(make_service);
+    let port = server.local_addr().port();
+    tracing::info!("HTTP gateway listening at: {}", server.local_addr());
+    let server = server.with_graceful_shutdown(async move {
+        let _ = stop_rx.recv().await;
+    });
+    let task = tokio::task::spawn(async move {
+        server
+            .await
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+        ManagedTaskResult::Ok(())
+    });
+    Ok((port, task))
+}
+
+/// Bind an HTTP server to this address, or take the socket bound to it
+/// which was inherited by socket activation.
+pub(super) fn bind_http(addr: SocketAddr) -> InterfaceResult<hyper::server::Builder<AddrIncoming>> {
+    match socket_activation::take_tcp_listener(addr) {
+        Some(listener) => hyper::Server::from_tcp(listener),
+        None => hyper::Server::try_bind(&addr),
+    }
+    .map_err(|e| InterfaceError::Other(e.to_string()))
+}
+
+type HttpError = (StatusCode, ExternalApiWireError);
+
+fn too_large(max_bytes: usize) -> HttpError {
+    (
+        StatusCode::PAYLOAD_TOO_LARGE,
+        ExternalApiWireError::internal(format!(
+            "Request bodies may be at most {} bytes",
+            max_bytes
+        )),
+    )
+}
+
+/// Read a request body, refusing it as soon as it grows larger than
+/// `max_bytes`, as the `Content-Length` header can be left out.
+async fn read_body(mut body: Body, max_bytes: usize) -> Result<Vec<u8>, HttpError> {
+    let mut bytes = Vec::new();
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk.map_err(|e| {
+            (
+                StatusCode::BAD_REQUEST,
+                ExternalApiWireError::Deserialization(e.to_string()),
+            )
+        })?;
+        if bytes.len() + chunk.len() > max_bytes {
+            return Err(too_large(max_bytes));
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    Ok(bytes)
+}
+
+struct HttpGateway {
+    config: HttpGatewayConfig,
+    api: RealAppInterfaceApi,
+    conductor_handle: ConductorHandle,
+}
+
+impl HttpGateway {
+    async fn handle(&self, request: Request<Body>) -> Response<Body> {
+        let msgpack = request
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .map_or(false, |content_type| content_type == MSGPACK);
+        // Join the trace of the client, if it sent its trace context.
+        let span = tracing::info_span!("http_zome_call");
+        holochain_p2p::trace_context::set_parent(
+            &span,
+            request
+                .headers()
+                .get("traceparent")
+                .and_then(|traceparent| traceparent.to_str().ok()),
+        );
+        let result = self
+            .zome_call(request, msgpack)
+            .instrument(span)
+            .await
+            .and_then(|output| {
+                if msgpack {
+                    Ok(output.into_vec())
+                } else {
+                    transcode::msgpack_to_json(output.as_bytes()).map_err(|e| {
+                        (
+                            StatusCode::INTERNAL_SERVER_ERROR,
+                            ExternalApiWireError::internal(e),
+                        )
+                    })
+                }
+            });
+        let (status, body) = match result {
+            Ok(body) => (StatusCode::OK, body),
+            Err((status, error)) => {
+                let body = if msgpack {
+                    holochain_serialized_bytes::encode(&error).unwrap_or_default()
+                } else {
+                    serde_json::to_vec(&error).unwrap_or_default()
+                };
+                (status, body)
+            }
+        };
+        Response::builder()
+            .status(status)
+            .header(
+                header::CONTENT_TYPE,
+                if msgpack { MSGPACK } else { "application/json" },
+            )
+            .body(Body::from(body))
+            .expect("response headers are valid")
+    }
+
+    async fn zome_call(
+        &self,
+        request: Request<Body>,
+        msgpack: bool,
+    ) -> Result<ExternIO, HttpError> {
+        let not_found =
+            |what: String| (StatusCode::NOT_FOUND, ExternalApiWireError::internal(what));
+        let unauthorized = |status, why: &str| {
+            (
+                status,
+                ExternalApiWireError::ZomeCallUnauthorized(why.to_string()),
+            )
+        };
+        let bad_request = |e: String| {
+            (
+                StatusCode::BAD_REQUEST,
+                ExternalApiWireError::Deserialization(e),
+            )
+        };
+
+        if request.uri().path() != "/zome_call" {
+            return Err(not_found(format!("No such path {}", request.uri().path())));
+        }
+        if request.method() != Method::POST {
+            return Err((
+                StatusCode::METHOD_NOT_ALLOWED,
+                ExternalApiWireError::internal("Zome calls must be POSTed"),
+            ));
+        }
+        let token = request
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|auth| auth.to_str().ok())
+            .and_then(|auth| auth.strip_prefix("Bearer "))
+            .and_then(|token| self.config.authorize(token.trim()))
+            .ok_or_else(|| unauthorized(StatusCode::UNAUTHORIZED, "Missing or unknown token"))?;
+
+        let max_bytes = self.config.max_request_bytes;
+        let declared_bytes = request
+            .headers()
+            .get(header::CONTENT_LENGTH)
+            .and_then(|length| length.to_str().ok())
+            .and_then(|length| length.parse::<u64>().ok());
+        if declared_bytes.map_or(false, |length| length > max_bytes as u64) {
+            return Err(too_large(max_bytes));
+        }
+        let body = read_body(request.into_body(), max_bytes).await?;
+        let call: HttpZomeCall<ExternIO> = if msgpack {
+            holochain_serialized_bytes::decode(&body[..]).map_err(|e| bad_request(e.to_string()))?
+        } else {
+            let call: HttpZomeCall<serde_json::Value> =
+                serde_json::from_slice(&body).map_err(|e| bad_request(e.to_string()))?;
+            HttpZomeCall {
+                payload: ExternIO::encode(&call.payload).map_err(|e| bad_request(e.to_string()))?,
+                installed_app_id: call.installed_app_id,
+                role_id: call.role_id,
+                zome_name: call.zome_name,
+                fn_name: call.fn_name,
+                cap_secret: call.cap_secret,
+            }
+        };
+        if !token.can_call(&call.installed_app_id) {
+            return Err(unauthorized(
+                StatusCode::FORBIDDEN,
+                "This token may not call this app",
+            ));
+        }
+
+        self.conductor_handle.check_running().map_err(|e| {
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                ExternalApiWireError::internal(e),
+            )
+        })?;
+        let installed_app_id = token.route(&call.installed_app_id);
+        let cell_id = self
+            .conductor_handle
+            .get_app_info(&installed_app_id)
+            .await
+            .map_err(|e| {
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    ExternalApiWireError::internal(e),
+                )
+            })?
+            .and_then(|app| {
+                app.cell_data
+                    .into_iter()
+                    .find(|cell| cell.as_role_id() == &call.role_id)
+            })
+            .ok_or_else(|| {
+                not_found(format!(
+                    "No cell with role {} in app {}",
+                    call.role_id, installed_app_id
+                ))
+            })?
+            .into_id();
+
+        let request = AppRequest::ZomeCall(Box::new(ZomeCall {
+            provenance: cell_id.agent_pubkey().clone(),
+            cell_id,
+            zome_name: call.zome_name,
+            fn_name: call.fn_name,
+            payload: call.payload,
+            cap_secret: call.cap_secret,
+        }));
+        match self.api.handle_app_request(request).await {
+            AppResponse::ZomeCall(output) => Ok(*output),
+            AppResponse::Error(error) => {
+                let status = match error {
+                    ExternalApiWireError::ZomeCallUnauthorized(_) => StatusCode::FORBIDDEN,
+                    ExternalApiWireError::Deserialization(_) => StatusCode::BAD_REQUEST,
+                    ExternalApiWireError::QuotaExceeded(_) => StatusCode::TOO_MANY_REQUESTS,
+                    _ => StatusCode::INTERNAL_SERVER_ERROR,
+                };
+                Err((status, error))
+            }
+            other => Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                ExternalApiWireError::internal(format!("Unexpected response {:?}", other)),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sweettest::*;
+    use holochain_conductor_api::conductor::HttpGatewayToken;
+    use holochain_conductor_api::conductor::DEFAULT_HTTP_GATEWAY_MAX_REQUEST_BYTES;
+    use holochain_wasm_test_utils::TestWasm;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn zome_calls_over_http() {
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let mut config = standard_config();
+        config.http_gateway = Some(HttpGatewayConfig {
+            port,
+            bind_address: None,
+            tokens: vec![
+                HttpGatewayToken {
+                    token: "secret".to_string(),
+                    installed_app_ids: None,
+                    agent_key: None,
+                },
+                HttpGatewayToken {
+                    token: "other-app-secret".to_string(),
+                    installed_app_ids: Some(vec!["other".to_string()]),
+                    agent_key: None,
+                },
+            ],
+            max_request_bytes: 1024,
+        });
+        let mut conductor = SweetConductor::from_config(config).await;
+        let (dna, _, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Foo])
+            .await
+            .unwrap();
+        conductor
+            .setup_app("app", &[("role".to_string(), dna)])
+            .await
+            .unwrap();
+
+        let call_with = |token: &str, payload: serde_json::Value| {
+            reqwest::Client::new()
+                .post(format!("http://127.0.0.1:{}/zome_call", port))
+                .bearer_auth(token)
+                .body(
+                    serde_json::json!({
+                        "installed_app_id": "app",
+                        "role_id": "role",
+                        "zome_name": TestWasm::Foo.coordinator_zome_name(),
+                        "fn_name": "foo",
+                        "payload": payload,
+                    })
+                    .to_string(),
+                )
+                .send()
+        };
+        let call = |token: &str| call_with(token, serde_json::Value::Null);
+
+        let response = call("secret").await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let output: serde_json::Value =
+            serde_json::from_slice(&response.bytes().await.unwrap()).unwrap();
+        assert_eq!(output, serde_json::json!("foo"));
+
+        let response = call("wrong").await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = call("other-app-secret").await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let response = call_with("secret", serde_json::json!("x".repeat(2048)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn bodies_without_a_length_are_limited() {
+        let (mut sender, body) = Body::channel();
+        tokio::spawn(async move {
+            for _ in 0..4 {
+                if sender.send_data(vec![0; 512].into()).await.is_err() {
+                    break;
+                }
+            }
+        });
+        let (status, _) = read_body(body, 1024).await.unwrap_err();
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+
+        let (mut sender, body) = Body::channel();
+        tokio::spawn(async move { sender.send_data(vec![0; 512].into()).await });
+        assert_eq!(
+            read_body(body, DEFAULT_HTTP_GATEWAY_MAX_REQUEST_BYTES)
+                .await
+                .unwrap()
+                .len(),
+            512
+        );
+    }
+}
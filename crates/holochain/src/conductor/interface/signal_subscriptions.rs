@@ -0,0 +1,97 @@
+//! The signal subscriptions of an app interface connection.
+//!
+//! Each connection keeps the [`SignalFilterSet`] its client chose for each
+//! app, along with the app's cells at the time, so signals are filtered
+//! before they are sent rather than by every client.
+
+use holochain_conductor_api::signal_subscription::SignalFilterSet;
+use holochain_types::prelude::*;
+use holochain_types::signal::Signal;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// An app's cells and the filters for their signals.
+struct AppSubscription {
+    cells: HashSet<CellId>,
+    filters: SignalFilterSet,
+}
+
+/// The subscriptions of one connection, shared between its request
+/// handler and its signal sender.
+#[derive(Clone, Default)]
+pub(crate) struct SignalSubscriptions(
+    Arc<parking_lot::Mutex<HashMap<InstalledAppId, AppSubscription>>>,
+);
+
+impl SignalSubscriptions {
+    /// Replace the filters for an app's signals.
+    pub(crate) fn set(
+        &self,
+        installed_app_id: InstalledAppId,
+        cells: HashSet<CellId>,
+        filters: SignalFilterSet,
+    ) {
+        self.0
+            .lock()
+            .insert(installed_app_id, AppSubscription { cells, filters });
+    }
+
+    /// Whether to send a signal on this connection. System signals, and
+    /// signals from cells of apps without a subscription, are always sent.
+    pub(crate) fn allows(&self, signal: &Signal) -> bool {
+        match signal {
+            Signal::App {
+                cell_id,
+                zome_name,
+                signal,
+            } => {
+                let subscriptions = self.0.lock();
+                let mut apps = subscriptions
+                    .values()
+                    .filter(|app| app.cells.contains(cell_id))
+                    .peekable();
+                apps.peek().is_none()
+                    || apps.any(|app| app.filters.allows(cell_id, zome_name, signal))
+            }
+            Signal::System(_) => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::fixt::prelude::*;
+    use holo_hash::fixt::AgentPubKeyFixturator;
+    use holo_hash::fixt::DnaHashFixturator;
+
+    #[test]
+    fn only_subscribed_apps_are_filtered() {
+        let cell_id = CellId::new(fixt!(DnaHash), fixt!(AgentPubKey));
+        let other_cell_id = CellId::new(fixt!(DnaHash), fixt!(AgentPubKey));
+        let signal = |cell_id: &CellId| Signal::App {
+            cell_id: cell_id.clone(),
+            zome_name: "zome".into(),
+            signal: AppSignal::new(ExternIO::encode(()).unwrap()),
+        };
+        let subscriptions = SignalSubscriptions::default();
+        assert!(subscriptions.allows(&signal(&cell_id)));
+
+        subscriptions.set(
+            "app".into(),
+            [cell_id.clone()].into(),
+            SignalFilterSet::block_all(),
+        );
+        assert!(!subscriptions.allows(&signal(&cell_id)));
+        assert!(subscriptions.allows(&signal(&other_cell_id)));
+        assert!(subscriptions.allows(&holochain_types::signal::test_signal("test")));
+
+        subscriptions.set(
+            "app".into(),
+            [cell_id.clone()].into(),
+            SignalFilterSet::allow_all(),
+        );
+        assert!(subscriptions.allows(&signal(&cell_id)));
+    }
+}
@@ -0,0 +1,143 @@
+//! Listening sockets inherited from a service manager with the systemd
+//! socket activation protocol, so the service manager can open the
+//! conductor's ports before the conductor starts, and hold on to waiting
+//! connections while it restarts.
+//!
+//! An interface uses an inherited socket bound to its configured address, or
+//! to its local socket path, instead of binding its own. Configured ports of
+//! 0 never match, and neither does a socket bound to a different address on
+//! the same port, e.g. `0.0.0.0` for an interface on `127.0.0.1`.
+
+use once_cell::sync::Lazy;
+use std::net::SocketAddr;
+use std::net::TcpListener;
+#[cfg(unix)]
+use std::os::unix::net::UnixListener;
+#[cfg(unix)]
+use std::path::Path;
+
+/// A listening socket passed to the conductor.
+enum Inherited {
+    Tcp(TcpListener),
+    #[cfg(unix)]
+    Unix(UnixListener),
+}
+
+/// The inherited sockets which no interface has taken yet.
+static INHERITED: Lazy<parking_lot::Mutex<Vec<Inherited>>> =
+    Lazy::new(|| parking_lot::Mutex::new(Vec::new()));
+
+/// The first file descriptor passed by socket activation.
+#[cfg(unix)]
+const SD_LISTEN_FDS_START: i32 = 3;
+
+/// Take the listening sockets passed to this process by socket activation,
+/// returning how many there are. This should be called once, at startup.
+///
+/// Sockets are only taken if `LISTEN_PID` is this process, so a conductor
+/// started by a socket activated process doesn't take its parent's sockets.
+#[cfg(unix)]
+pub fn receive_inherited_listeners() -> usize {
+    use std::os::unix::io::FromRawFd;
+    use std::os::unix::io::IntoRawFd;
+
+    let for_this_process = std::env::var("LISTEN_PID")
+        .ok()
+        .and_then(|pid| pid.parse::<u32>().ok())
+        == Some(std::process::id());
+    let count = std::env::var("LISTEN_FDS")
+        .ok()
+        .and_then(|count| count.parse::<i32>().ok())
+        .unwrap_or(0);
+    if !for_this_process || count <= 0 {
+        return 0;
+    }
+    let mut inherited = INHERITED.lock();
+    for fd in SD_LISTEN_FDS_START..SD_LISTEN_FDS_START + count {
+        // The file descriptors are passed to this process to listen on,
+        // so they're ours to own.
+        let listener = unsafe { TcpListener::from_raw_fd(fd) };
+        if listener.local_addr().is_ok() {
+            inherited.push(Inherited::Tcp(listener));
+            continue;
+        }
+        let listener = unsafe { UnixListener::from_raw_fd(listener.into_raw_fd()) };
+        if listener
+            .local_addr()
+            .map_or(false, |addr| addr.as_pathname().is_some())
+        {
+            inherited.push(Inherited::Unix(listener));
+        } else {
+            // Leave sockets of other kinds open, as they were passed.
+            tracing::warn!(fd, "Ignoring an inherited socket which isn't a listener");
+            let _ = listener.into_raw_fd();
+        }
+    }
+    tracing::info!(
+        count = inherited.len(),
+        "Received sockets by socket activation"
+    );
+    inherited.len()
+}
+
+/// Take the listening sockets passed to this process by socket activation,
+/// which isn't supported on this platform, so there are none.
+#[cfg(not(unix))]
+pub fn receive_inherited_listeners() -> usize {
+    0
+}
+
+/// Take the inherited TCP socket bound to this address, if there is one.
+pub(crate) fn take_tcp_listener(addr: SocketAddr) -> Option<TcpListener> {
+    if addr.port() == 0 {
+        return None;
+    }
+    let mut inherited = INHERITED.lock();
+    let i = inherited.iter().position(|listener| match listener {
+        Inherited::Tcp(listener) => listener.local_addr().ok() == Some(addr),
+        #[cfg(unix)]
+        Inherited::Unix(_) => false,
+    })?;
+    match inherited.remove(i) {
+        Inherited::Tcp(listener) => Some(listener),
+        #[cfg(unix)]
+        Inherited::Unix(_) => unreachable!("only a TCP socket was found"),
+    }
+}
+
+/// Take the inherited Unix domain socket at this path, if there is one.
+#[cfg(unix)]
+pub(crate) fn take_unix_listener(path: &Path) -> Option<UnixListener> {
+    let mut inherited = INHERITED.lock();
+    let i = inherited.iter().position(|listener| match listener {
+        Inherited::Unix(listener) => listener
+            .local_addr()
+            .ok()
+            .and_then(|addr| addr.as_pathname().map(|p| p == path))
+            .unwrap_or(false),
+        Inherited::Tcp(_) => false,
+    })?;
+    match inherited.remove(i) {
+        Inherited::Unix(listener) => Some(listener),
+        Inherited::Tcp(_) => unreachable!("only a Unix domain socket was found"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn listeners_are_taken_by_address() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        INHERITED.lock().push(Inherited::Tcp(listener));
+
+        let other_ip = SocketAddr::new([0, 0, 0, 0].into(), addr.port());
+        assert!(take_tcp_listener(other_ip).is_none());
+        assert!(take_tcp_listener(SocketAddr::new(addr.ip(), 0)).is_none());
+        assert_eq!(take_tcp_listener(addr).unwrap().local_addr().unwrap(), addr);
+        // It can only be taken once.
+        assert!(take_tcp_listener(addr).is_none());
+    }
+}
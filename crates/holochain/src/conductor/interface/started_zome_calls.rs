@@ -0,0 +1,123 @@
+//! The zome calls an app interface connection started with
+//! [`AppRequest::StartZomeCall`], which it can await or cancel by their id.
+//!
+//! Calls are cancelled when the connection which started them closes, as
+//! there is no one left to read their results.
+//!
+//! [`AppRequest::StartZomeCall`]: holochain_conductor_api::AppRequest::StartZomeCall
+
+use crate::core::ribosome::zome_call_limits::ZomeCallCancel;
+use holochain_conductor_api::AppResponse;
+use holochain_conductor_api::ExternalApiWireError;
+use holochain_conductor_api::ZomeCallId;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+
+/// A started call, and its task until the call is awaited.
+struct StartedCall {
+    cancel: ZomeCallCancel,
+    task: Option<JoinHandle<AppResponse>>,
+}
+
+#[derive(Default)]
+struct Calls {
+    next_id: AtomicU64,
+    calls: parking_lot::Mutex<HashMap<ZomeCallId, StartedCall>>,
+}
+
+impl Drop for Calls {
+    fn drop(&mut self) {
+        for call in self.calls.get_mut().values() {
+            call.cancel.cancel();
+        }
+    }
+}
+
+/// The calls started on one connection.
+#[derive(Clone, Default)]
+pub(crate) struct StartedZomeCalls(Arc<Calls>);
+
+impl StartedZomeCalls {
+    /// Spawn a call, which is given the token that cancels it.
+    pub(crate) fn start<F, Fut>(&self, call: F) -> ZomeCallId
+    where
+        F: FnOnce(ZomeCallCancel) -> Fut,
+        Fut: Future<Output = AppResponse> + Send + 'static,
+    {
+        let cancel = ZomeCallCancel::cancellable();
+        let task = tokio::task::spawn(call(cancel.clone()));
+        let call_id = self.0.next_id.fetch_add(1, Ordering::Relaxed);
+        self.0.calls.lock().insert(
+            call_id,
+            StartedCall {
+                cancel,
+                task: Some(task),
+            },
+        );
+        call_id
+    }
+
+    /// Wait for a call to finish, returning its response, or `None` if
+    /// there is no such call or it's already being awaited.
+    pub(crate) async fn wait(&self, call_id: ZomeCallId) -> Option<AppResponse> {
+        let task = self.0.calls.lock().get_mut(&call_id)?.task.take()?;
+        let response = task
+            .await
+            .unwrap_or_else(|e| AppResponse::Error(ExternalApiWireError::internal(e)));
+        self.0.calls.lock().remove(&call_id);
+        Some(response)
+    }
+
+    /// Cancel a call, returning whether there is such a call. A call which
+    /// has already finished is left as it was.
+    pub(crate) fn cancel(&self, call_id: ZomeCallId) -> bool {
+        match self.0.calls.lock().get(&call_id) {
+            Some(call) => {
+                call.cancel.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn calls_are_cancelled_and_awaited_once() {
+        let calls = StartedZomeCalls::default();
+        let call_id = calls.start(|cancel| async move {
+            while !cancel.is_cancelled() {
+                tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+            }
+            AppResponse::ZomeCallCancelled
+        });
+        assert!(calls.cancel(call_id));
+        assert!(matches!(
+            calls.wait(call_id).await,
+            Some(AppResponse::ZomeCallCancelled)
+        ));
+        assert!(calls.wait(call_id).await.is_none());
+        assert!(!calls.cancel(call_id));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn calls_are_cancelled_when_the_connection_closes() {
+        let calls = StartedZomeCalls::default();
+        let (cancel_tx, cancel_rx) = tokio::sync::oneshot::channel();
+        calls.start(|cancel| {
+            let _ = cancel_tx.send(cancel);
+            async { AppResponse::SignalSubscriptionUpdated }
+        });
+        let cancel = cancel_rx.await.unwrap();
+        assert!(!cancel.is_cancelled());
+        drop(calls);
+        assert!(cancel.is_cancelled());
+    }
+}
@@ -0,0 +1,147 @@
+//! Transcoding between msgpack and JSON, for clients which use JSON.
+//!
+//! JSON has no binary type, so binary data is written as an array of bytes,
+//! except for 39 byte binaries, which are hashes or agent keys, and are
+//! written as the base64 strings they can also be deserialized from.
+
+use serde::de::Deserializer;
+use serde::de::MapAccess;
+use serde::de::SeqAccess;
+use serde::de::Visitor;
+use serde_json::Value;
+
+/// The length of a hash with its prefix and location bytes.
+const HOLO_HASH_LEN: usize = 39;
+
+/// Transcode msgpack to JSON.
+pub(super) fn msgpack_to_json(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    let value: JsonValue = holochain_serialized_bytes::decode(bytes).map_err(|e| e.to_string())?;
+    serde_json::to_vec(&value.0).map_err(|e| e.to_string())
+}
+
+/// Transcode JSON to msgpack.
+pub(super) fn json_to_msgpack(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    let value: Value = serde_json::from_slice(bytes).map_err(|e| e.to_string())?;
+    holochain_serialized_bytes::encode(&value).map_err(|e| e.to_string())
+}
+
+/// A JSON value deserialized from any self-describing format.
+#[derive(Debug)]
+struct JsonValue(Value);
+
+impl<'de> serde::Deserialize<'de> for JsonValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer
+            .deserialize_any(JsonValueVisitor)
+            .map(JsonValue)
+    }
+}
+
+struct JsonValueVisitor;
+
+impl<'de> Visitor<'de> for JsonValueVisitor {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("any value")
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Value, E> {
+        Ok(Value::Bool(v))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Value, E> {
+        Ok(Value::from(v))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Value, E> {
+        Ok(Value::from(v))
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Value, E> {
+        Ok(Value::from(v))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Value, E> {
+        Ok(Value::String(v.to_string()))
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Value, E> {
+        if v.len() == HOLO_HASH_LEN {
+            Ok(Value::String(holo_hash::holo_hash_encode(v)))
+        } else {
+            Ok(Value::Array(v.iter().map(|b| Value::from(*b)).collect()))
+        }
+    }
+
+    fn visit_unit<E>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_none<E>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Value, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+        let mut values = Vec::new();
+        while let Some(JsonValue(value)) = seq.next_element()? {
+            values.push(value);
+        }
+        Ok(Value::Array(values))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Value, A::Error> {
+        let mut object = serde_json::Map::new();
+        while let Some((JsonValue(key), JsonValue(value))) = map.next_entry()? {
+            let key = match key {
+                Value::String(key) => key,
+                key => key.to_string(),
+            };
+            object.insert(key, value);
+        }
+        Ok(Value::Object(object))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::fixt::prelude::*;
+    use holo_hash::fixt::ActionHashFixturator;
+    use holo_hash::ActionHash;
+    use holochain_types::prelude::ExternIO;
+
+    #[derive(serde::Serialize, Debug)]
+    struct Output {
+        hash: ActionHash,
+        bytes: ExternIO,
+        count: u32,
+        label: Option<String>,
+    }
+
+    #[test]
+    fn transcodes_hashes_to_base64() {
+        let hash = fixt!(ActionHash);
+        let output = Output {
+            hash: hash.clone(),
+            bytes: ExternIO(vec![1, 2]),
+            count: 3,
+            label: None,
+        };
+        let msgpack = holochain_serialized_bytes::encode(&output).unwrap();
+        let json: Value = serde_json::from_slice(&msgpack_to_json(&msgpack).unwrap()).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "hash": holo_hash::holo_hash_encode(hash.get_raw_39()),
+                "bytes": [1, 2],
+                "count": 3,
+                "label": null,
+            })
+        );
+    }
+}
@@ -1,25 +1,33 @@
 //! Module for establishing Websocket-based Interfaces,
-//! i.e. those configured with `InterfaceDriver::Websocket`
+//! i.e. those configured with `InterfaceDriver::Websocket`,
+//! or `InterfaceDriver::LocalSocket` for websockets over a local socket
 
+use super::connections::ConnectionCounter;
+use super::connections::OpenConnection;
 use super::error::InterfaceError;
 use super::error::InterfaceResult;
+use super::socket_activation;
+use super::transcode;
 use crate::conductor::conductor::StopReceiver;
 use crate::conductor::interface::*;
 use crate::conductor::manager::ManagedTaskHandle;
 use crate::conductor::manager::ManagedTaskResult;
+use holochain_conductor_api::config::DEFAULT_LOCAL_SOCKET_MODE;
 use holochain_serialized_bytes::SerializedBytes;
+use holochain_serialized_bytes::SerializedBytesError;
+use holochain_serialized_bytes::UnsafeBytes;
 use holochain_types::signal::Signal;
 use holochain_websocket::ListenerHandle;
 use holochain_websocket::ListenerItem;
+use holochain_websocket::ListenerStream;
 use holochain_websocket::WebsocketConfig;
 use holochain_websocket::WebsocketListener;
 use holochain_websocket::WebsocketMessage;
 use holochain_websocket::WebsocketReceiver;
 use holochain_websocket::WebsocketSender;
+use holochain_websocket::WireFormat;
 use std::convert::TryFrom;
 
-use std::sync::atomic::AtomicIsize;
-use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use tokio::sync::broadcast;
 use tokio_stream::StreamExt;
@@ -30,31 +38,76 @@ use url2::url2;
 /// Number of signals in buffer before applying
 /// back pressure.
 pub(crate) const SIGNAL_BUFFER_SIZE: usize = 50;
-const MAX_CONNECTIONS: isize = 400;
+const MAX_CONNECTIONS: usize = 400;
 
 /// Create a WebsocketListener to be used in interfaces
 pub async fn spawn_websocket_listener(
-    port: u16,
+    driver: &InterfaceDriver,
 ) -> InterfaceResult<(
     ListenerHandle,
     impl futures::stream::Stream<Item = ListenerItem>,
 )> {
     trace!("Initializing Admin interface");
-    let listener = WebsocketListener::bind_with_handle(
-        url2!("ws://127.0.0.1:{}", port),
-        Arc::new(WebsocketConfig::default()),
-    )
-    .await?;
+    let listener = bind_websocket_listener(driver).await?;
     trace!("LISTENING AT: {}", listener.0.local_addr());
     Ok(listener)
 }
 
+/// Bind a WebsocketListener to the address and port of the driver,
+/// only accepting connections from its allowed origins,
+/// or to the local socket of the driver.
+async fn bind_websocket_listener(
+    driver: &InterfaceDriver,
+) -> InterfaceResult<(ListenerHandle, ListenerStream)> {
+    let heartbeat = driver.heartbeat();
+    let config = WebsocketConfig::default()
+        .allowed_origins(driver.allowed_origins().cloned())
+        .heartbeat_interval_s(heartbeat.interval_s as usize)
+        .heartbeat_timeout_s(heartbeat.timeout_s as usize);
+    Ok(match driver {
+        InterfaceDriver::Websocket { .. } => {
+            let addr = std::net::SocketAddr::new(driver.bind_address(), driver.port());
+            match socket_activation::take_tcp_listener(addr) {
+                Some(listener) => {
+                    let (handle, listener) =
+                        WebsocketListener::from_std_with_handle(listener, Arc::new(config))?;
+                    (handle, futures::StreamExt::boxed(listener))
+                }
+                None => {
+                    let (handle, listener) = WebsocketListener::bind_with_handle(
+                        url2!("ws://{}", addr),
+                        Arc::new(config),
+                    )
+                    .await?;
+                    (handle, futures::StreamExt::boxed(listener))
+                }
+            }
+        }
+        InterfaceDriver::LocalSocket { path, mode } => {
+            #[cfg(unix)]
+            if let Some(listener) = socket_activation::take_unix_listener(path) {
+                let (handle, listener) =
+                    WebsocketListener::from_std_local_with_handle(listener, Arc::new(config))?;
+                return Ok((handle, futures::StreamExt::boxed(listener)));
+            }
+            let (handle, listener) = WebsocketListener::bind_local_with_handle(
+                path,
+                mode.unwrap_or(DEFAULT_LOCAL_SOCKET_MODE),
+                Arc::new(config),
+            )
+            .await?;
+            (handle, futures::StreamExt::boxed(listener))
+        }
+    })
+}
+
 /// Create an Admin Interface, which only receives AdminRequest messages
-/// from the external client
+/// from the external client, counting its open connections
 pub fn spawn_admin_interface_task<A: InterfaceApi>(
     handle: ListenerHandle,
     listener: impl futures::stream::Stream<Item = ListenerItem> + Send + 'static,
     api: A,
+    connections: ConnectionCounter,
     mut stop_rx: StopReceiver,
 ) -> InterfaceResult<ManagedTaskHandle> {
     Ok(tokio::task::spawn(async move {
@@ -63,13 +116,12 @@ pub fn spawn_admin_interface_task<A: InterfaceApi>(
             handle.close_on(async move { stop_rx.recv().await.map(|_| true).unwrap_or(true) }),
         );
 
-        let num_connections = Arc::new(AtomicIsize::new(0));
         futures::pin_mut!(listener);
         // establish a new connection to a client
         while let Some(connection) = listener.next().await {
             match connection {
                 Ok((_, rx_from_iface)) => {
-                    if num_connections.fetch_add(1, Ordering::Relaxed) > MAX_CONNECTIONS {
+                    if connections.count() >= MAX_CONNECTIONS {
                         // Max connections so drop this connection
                         // which will close it.
                         continue;
@@ -77,7 +129,7 @@ pub fn spawn_admin_interface_task<A: InterfaceApi>(
                     tokio::task::spawn(recv_incoming_admin_msgs(
                         api.clone(),
                         rx_from_iface,
-                        num_connections.clone(),
+                        connections.open(),
                     ));
                 }
                 Err(err) => {
@@ -90,24 +142,24 @@ pub fn spawn_admin_interface_task<A: InterfaceApi>(
 }
 
 /// Create an App Interface, which includes the ability to receive signals
-/// from Cells via a broadcast channel
-pub async fn spawn_app_interface_task<A: InterfaceApi>(
-    port: u16,
-    api: A,
+/// from Cells via a broadcast channel, counting its open connections
+pub async fn spawn_app_interface_task(
+    driver: &InterfaceDriver,
+    api: RealAppInterfaceApi,
     signal_broadcaster: broadcast::Sender<Signal>,
+    connections: ConnectionCounter,
     mut stop_rx: StopReceiver,
 ) -> InterfaceResult<(u16, ManagedTaskHandle)> {
     trace!("Initializing App interface");
-    let (handle, mut listener) = WebsocketListener::bind_with_handle(
-        url2!("ws://127.0.0.1:{}", port),
-        Arc::new(WebsocketConfig::default()),
-    )
-    .await?;
+    let (handle, mut listener) = bind_websocket_listener(driver).await?;
     trace!("LISTENING AT: {}", handle.local_addr());
-    let port = handle
-        .local_addr()
-        .port()
-        .ok_or(InterfaceError::PortError)?;
+    let port = match driver {
+        InterfaceDriver::Websocket { .. } => handle
+            .local_addr()
+            .port()
+            .ok_or(InterfaceError::PortError)?,
+        InterfaceDriver::LocalSocket { .. } => 0,
+    };
     // Task that will kill the listener and all child connections.
     tokio::task::spawn(
         handle.close_on(async move { stop_rx.recv().await.map(|_| true).unwrap_or(true) }),
@@ -119,10 +171,11 @@ pub async fn spawn_app_interface_task<A: InterfaceApi>(
                 Ok((tx_to_iface, rx_from_iface)) => {
                     let rx_from_cell = signal_broadcaster.subscribe();
                     spawn_recv_incoming_msgs_and_outgoing_signals(
-                        api.clone(),
+                        api.for_connection(),
                         rx_from_iface,
                         rx_from_cell,
                         tx_to_iface,
+                        connections.open(),
                     );
                 }
                 Err(err) => {
@@ -141,35 +194,42 @@ pub async fn spawn_app_interface_task<A: InterfaceApi>(
 async fn recv_incoming_admin_msgs<A: InterfaceApi>(
     api: A,
     rx_from_iface: WebsocketReceiver,
-    num_connections: Arc<AtomicIsize>,
+    open_connection: OpenConnection,
 ) {
     use futures::stream::StreamExt;
 
+    let wire_format = rx_from_iface.wire_format();
     rx_from_iface
         .for_each_concurrent(4096, move |msg| {
             let api = api.clone();
             async move {
-                if let Err(e) = handle_incoming_message(msg, api.clone()).await {
+                if let Err(e) = handle_incoming_message(msg, wire_format, api.clone()).await {
                     error!(error = &e as &dyn std::error::Error)
                 }
             }
         })
         .await;
-    num_connections.fetch_sub(1, Ordering::SeqCst);
+    drop(open_connection);
 }
 
 /// Polls for messages coming in from the external client while simultaneously
 /// polling for signals being broadcast from the Cells associated with this
-/// App interface.
-fn spawn_recv_incoming_msgs_and_outgoing_signals<A: InterfaceApi>(
-    api: A,
+/// App interface, which are sent if the connection is subscribed to them.
+///
+/// Once the connection closes, including when the client stops answering
+/// heartbeat pings, signals are no longer sent, and its subscriptions and
+/// place in the signal broadcast are dropped.
+fn spawn_recv_incoming_msgs_and_outgoing_signals(
+    api: RealAppInterfaceApi,
     rx_from_iface: WebsocketReceiver,
     rx_from_cell: broadcast::Receiver<Signal>,
     tx_to_iface: WebsocketSender,
+    open_connection: OpenConnection,
 ) {
     use futures::stream::StreamExt;
 
     trace!("CONNECTION: {}", rx_from_iface.remote_addr());
+    let wire_format = rx_from_iface.wire_format();
 
     let rx_from_cell = futures::stream::unfold(rx_from_cell, |mut rx_from_cell| async move {
         if let Ok(item) = rx_from_cell.recv().await {
@@ -179,12 +239,17 @@ fn spawn_recv_incoming_msgs_and_outgoing_signals<A: InterfaceApi>(
         }
     });
 
-    tokio::task::spawn(rx_from_cell.for_each_concurrent(4096, move |signal| {
+    let signal_subscriptions = api.signal_subscriptions().clone();
+    let signals = tokio::task::spawn(rx_from_cell.for_each_concurrent(4096, move |signal| {
         let mut tx_to_iface = tx_to_iface.clone();
+        let subscribed = signal_subscriptions.allows(&signal);
         async move {
+            if !subscribed {
+                return;
+            }
             trace!(msg = "Sending signal!", ?signal);
             if let Err(err) = async move {
-                let bytes = SerializedBytes::try_from(signal)?;
+                let bytes = encode_message(SerializedBytes::try_from(signal)?, wire_format)?;
                 tx_to_iface.signal(bytes).await?;
                 InterfaceResult::Ok(())
             }
@@ -195,27 +260,67 @@ fn spawn_recv_incoming_msgs_and_outgoing_signals<A: InterfaceApi>(
         }
     }));
 
-    tokio::task::spawn(rx_from_iface.for_each_concurrent(4096, move |msg| {
-        let api = api.clone();
-        async move {
-            if let Err(err) = handle_incoming_message(msg, api).await {
-                error!(?err, "error handling websocket message");
-            }
-        }
-    }));
+    let remote_addr = rx_from_iface.remote_addr().clone();
+    tokio::task::spawn(async move {
+        rx_from_iface
+            .for_each_concurrent(4096, move |msg| {
+                let api = api.clone();
+                async move {
+                    if let Err(err) = handle_incoming_message(msg, wire_format, api).await {
+                        error!(?err, "error handling websocket message");
+                    }
+                }
+            })
+            .await;
+        signals.abort();
+        drop(open_connection);
+        trace!("CONNECTION CLOSED: {}", remote_addr);
+    });
 }
 
 /// Handles messages on all interfaces
-async fn handle_incoming_message<A>(ws_msg: WebsocketMessage, api: A) -> InterfaceResult<()>
+async fn handle_incoming_message<A>(
+    ws_msg: WebsocketMessage,
+    wire_format: WireFormat,
+    api: A,
+) -> InterfaceResult<()>
 where
     A: InterfaceApi,
 {
     let (bytes, respond) = ws_msg;
+    let request = decode_message(bytes, wire_format).and_then(TryInto::try_into);
+    let response: SerializedBytes = api.handle_request(request).await?.try_into()?;
     Ok(respond
-        .respond(api.handle_request(bytes.try_into()).await?.try_into()?)
+        .respond(encode_message(response, wire_format)?)
         .await?)
 }
 
+/// Transcode a message received in the connection's format to msgpack.
+fn decode_message(
+    bytes: SerializedBytes,
+    wire_format: WireFormat,
+) -> Result<SerializedBytes, SerializedBytesError> {
+    match wire_format {
+        WireFormat::Msgpack => Ok(bytes),
+        WireFormat::Json => transcode::json_to_msgpack(bytes.bytes())
+            .map(|bytes| UnsafeBytes::from(bytes).into())
+            .map_err(SerializedBytesError::Deserialize),
+    }
+}
+
+/// Transcode a msgpack message to send in the connection's format.
+fn encode_message(
+    bytes: SerializedBytes,
+    wire_format: WireFormat,
+) -> Result<SerializedBytes, SerializedBytesError> {
+    match wire_format {
+        WireFormat::Msgpack => Ok(bytes),
+        WireFormat::Json => transcode::msgpack_to_json(bytes.bytes())
+            .map(|bytes| UnsafeBytes::from(bytes).into())
+            .map_err(SerializedBytesError::Serialize),
+    }
+}
+
 /// Test items needed by other crates
 #[cfg(any(test, feature = "test_utils"))]
 pub use crate::test_utils::setup_app;
@@ -335,7 +440,30 @@ pub mod test {
         };
         let respond = Respond::Request(Box::new(respond));
         let msg = (msg, respond);
-        handle_incoming_message(msg, admin_api).await.unwrap();
+        handle_incoming_message(msg, WireFormat::Msgpack, admin_api)
+            .await
+            .unwrap();
+        conductor_handle.shutdown();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn json_wire_format() {
+        let (_tmpdir, conductor_handle) = setup_admin().await;
+        let admin_api = RealAdminInterfaceApi::new(conductor_handle.clone());
+        let msg = UnsafeBytes::from(br#"{"type": "list_dnas"}"#.to_vec()).into();
+        let respond = |bytes: SerializedBytes| {
+            let response: serde_json::Value = serde_json::from_slice(bytes.bytes()).unwrap();
+            assert_eq!(
+                response,
+                serde_json::json!({ "type": "dnas_listed", "data": [] })
+            );
+            async { Ok(()) }.boxed().into()
+        };
+        let respond = Respond::Request(Box::new(respond));
+        let msg = (msg, respond);
+        handle_incoming_message(msg, WireFormat::Json, admin_api)
+            .await
+            .unwrap();
         conductor_handle.shutdown();
     }
 
@@ -363,7 +491,9 @@ pub mod test {
         };
         let respond = Respond::Request(Box::new(respond));
         let msg = (msg, respond);
-        handle_incoming_message(msg, admin_api).await.unwrap();
+        handle_incoming_message(msg, WireFormat::Msgpack, admin_api)
+            .await
+            .unwrap();
         conductor_handle.shutdown();
     }
 
@@ -406,7 +536,9 @@ pub mod test {
         };
         let respond = Respond::Request(Box::new(respond));
         let msg = (msg, respond);
-        handle_incoming_message(msg, app_api).await.unwrap();
+        handle_incoming_message(msg, WireFormat::Msgpack, app_api)
+            .await
+            .unwrap();
         // the time here should be almost the same (about +0.1ms) vs. the raw real_ribosome call
         // the overhead of a websocket request locally is small
         let shutdown = handle.take_shutdown_handle().unwrap();
@@ -457,9 +589,13 @@ pub mod test {
         let respond = Respond::Request(Box::new(respond));
         let msg = (msg, respond);
 
-        handle_incoming_message(msg, RealAdminInterfaceApi::new(conductor_handle.clone()))
-            .await
-            .unwrap();
+        handle_incoming_message(
+            msg,
+            WireFormat::Msgpack,
+            RealAdminInterfaceApi::new(conductor_handle.clone()),
+        )
+        .await
+        .unwrap();
 
         // Get the state
         let state: ConductorState = conductor_handle.get_state_from_handle().await.unwrap();
@@ -506,9 +642,13 @@ pub mod test {
         let respond = Respond::Request(Box::new(respond));
         let msg = (msg, respond);
 
-        handle_incoming_message(msg, RealAdminInterfaceApi::new(conductor_handle.clone()))
-            .await
-            .unwrap();
+        handle_incoming_message(
+            msg,
+            WireFormat::Msgpack,
+            RealAdminInterfaceApi::new(conductor_handle.clone()),
+        )
+        .await
+        .unwrap();
 
         // Get the state
         let state = conductor_handle.get_state_from_handle().await.unwrap();
@@ -546,7 +686,12 @@ pub mod test {
         let (_tmpdir, conductor_handle) = setup_admin().await;
         let shutdown = conductor_handle.take_shutdown_handle().unwrap();
         let admin_api = RealAdminInterfaceApi::new(conductor_handle.clone());
-        let msg = AdminRequest::AttachAppInterface { port: None };
+        let msg = AdminRequest::AttachAppInterface {
+            port: None,
+            bind_address: None,
+            allowed_origins: None,
+            require_signed_zome_calls: false,
+        };
         let msg = msg.try_into().unwrap();
         let respond = |bytes: SerializedBytes| {
             let response: AdminResponse = bytes.try_into().unwrap();
@@ -555,7 +700,199 @@ pub mod test {
         };
         let respond = Respond::Request(Box::new(respond));
         let msg = (msg, respond);
-        handle_incoming_message(msg, admin_api).await.unwrap();
+        handle_incoming_message(msg, WireFormat::Msgpack, admin_api)
+            .await
+            .unwrap();
+        conductor_handle.shutdown();
+        shutdown.await.unwrap().unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn attach_app_local_socket() {
+        observability::test_run().ok();
+        let (tmpdir, conductor_handle) = setup_admin().await;
+        let shutdown = conductor_handle.take_shutdown_handle().unwrap();
+        let admin_api = RealAdminInterfaceApi::new(conductor_handle.clone());
+        let path = tmpdir.path().join("app.sock");
+        let msg = AdminRequest::AttachAppLocalSocket {
+            path: path.clone(),
+            mode: None,
+        };
+        let response = admin_api.handle_admin_request(msg).await;
+        assert_matches!(response, AdminResponse::AppLocalSocketAttached);
+        // Local sockets aren't listed with the websocket ports.
+        assert!(conductor_handle
+            .list_app_interfaces()
+            .await
+            .unwrap()
+            .is_empty());
+
+        let (mut sender, _receiver) =
+            holochain_websocket::connect_local(&path, Arc::new(WebsocketConfig::default()))
+                .await
+                .unwrap();
+        let response: AppResponse = sender
+            .request(AppRequest::AppInfo {
+                installed_app_id: "no such app".to_string(),
+            })
+            .await
+            .unwrap();
+        assert_matches!(response, AppResponse::AppInfo(None));
+
+        conductor_handle.shutdown();
+        shutdown.await.unwrap().unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn detached_app_interfaces_are_closed_and_forgotten() {
+        observability::test_run().ok();
+        let (_tmpdir, conductor_handle) = setup_admin().await;
+        let shutdown = conductor_handle.take_shutdown_handle().unwrap();
+        let admin_api = RealAdminInterfaceApi::new(conductor_handle.clone());
+        let response = admin_api
+            .handle_admin_request(AdminRequest::AttachAppInterface {
+                port: None,
+                bind_address: None,
+                allowed_origins: None,
+                require_signed_zome_calls: false,
+            })
+            .await;
+        let port = match response {
+            AdminResponse::AppInterfaceAttached { port } => port,
+            other => panic!("unexpected response {:?}", other),
+        };
+
+        let response = admin_api
+            .handle_admin_request(AdminRequest::ListAppInterfaceSettings)
+            .await;
+        match response {
+            AdminResponse::AppInterfaceSettingsListed(interfaces) => {
+                assert_eq!(interfaces.len(), 1);
+                assert_eq!(interfaces[0].driver.port(), port);
+            }
+            other => panic!("unexpected response {:?}", other),
+        }
+
+        let response = admin_api
+            .handle_admin_request(AdminRequest::DetachAppInterface { port })
+            .await;
+        assert_matches!(response, AdminResponse::AppInterfaceDetached);
+        assert!(conductor_handle
+            .list_app_interfaces()
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(conductor_handle
+            .interface_stats()
+            .iter()
+            .all(|stats| stats.admin));
+
+        let response = admin_api
+            .handle_admin_request(AdminRequest::DetachAppInterface { port })
+            .await;
+        assert_matches!(response, AdminResponse::Error(_));
+
+        conductor_handle.shutdown();
+        shutdown.await.unwrap().unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn interface_stats_count_open_connections() {
+        observability::test_run().ok();
+        let (_tmpdir, conductor_handle) = setup_admin().await;
+        let shutdown = conductor_handle.take_shutdown_handle().unwrap();
+        let admin_api = RealAdminInterfaceApi::new(conductor_handle.clone());
+        let response = admin_api
+            .handle_admin_request(AdminRequest::AttachAppInterface {
+                port: None,
+                bind_address: None,
+                allowed_origins: None,
+                require_signed_zome_calls: false,
+            })
+            .await;
+        let port = match response {
+            AdminResponse::AppInterfaceAttached { port } => port,
+            other => panic!("unexpected response {:?}", other),
+        };
+
+        async fn wait_for_connections(admin_api: &RealAdminInterfaceApi, expected: usize) {
+            for _ in 0..50 {
+                let response = admin_api
+                    .handle_admin_request(AdminRequest::DumpInterfaceStats)
+                    .await;
+                let stats = match response {
+                    AdminResponse::InterfaceStatsDumped(stats) => stats,
+                    other => panic!("unexpected response {:?}", other),
+                };
+                let app = stats.into_iter().find(|stats| !stats.admin).unwrap();
+                if app.active_connections == expected {
+                    return;
+                }
+                tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+            }
+            panic!("the app interface never had {} connections", expected);
+        }
+
+        let client = holochain_websocket::connect(
+            url2!("ws://127.0.0.1:{}", port),
+            Arc::new(WebsocketConfig::default()),
+        )
+        .await
+        .unwrap();
+        wait_for_connections(&admin_api, 1).await;
+        // Closing the connection stops it being counted.
+        drop(client);
+        wait_for_connections(&admin_api, 0).await;
+
+        conductor_handle.shutdown();
+        shutdown.await.unwrap().unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn signed_only_app_interfaces_refuse_unsigned_zome_calls() {
+        observability::test_run().ok();
+        let (_tmpdir, conductor_handle) = setup_admin().await;
+        let shutdown = conductor_handle.take_shutdown_handle().unwrap();
+        let admin_api = RealAdminInterfaceApi::new(conductor_handle.clone());
+        let response = admin_api
+            .handle_admin_request(AdminRequest::AttachAppInterface {
+                port: None,
+                bind_address: None,
+                allowed_origins: None,
+                require_signed_zome_calls: true,
+            })
+            .await;
+        let port = match response {
+            AdminResponse::AppInterfaceAttached { port } => port,
+            other => panic!("unexpected response {:?}", other),
+        };
+
+        let (mut sender, _receiver) = holochain_websocket::connect(
+            url2!("ws://127.0.0.1:{}", port),
+            Arc::new(WebsocketConfig::default()),
+        )
+        .await
+        .unwrap();
+        let call = ZomeCall {
+            cell_id: CellId::new(fake_dna_hash(1), fake_agent_pubkey_1()),
+            zome_name: "zome".into(),
+            fn_name: "fn".into(),
+            payload: ExternIO::encode(()).unwrap(),
+            cap_secret: None,
+            provenance: fake_agent_pubkey_2(),
+        };
+        for request in [
+            AppRequest::ZomeCall(Box::new(call.clone())),
+            AppRequest::StartZomeCall(Box::new(call)),
+        ] {
+            let response: AppResponse = sender.request(request).await.unwrap();
+            assert_matches!(
+                response,
+                AppResponse::Error(ExternalApiWireError::ZomeCallUnauthorized(_))
+            );
+        }
+
         conductor_handle.shutdown();
         shutdown.await.unwrap().unwrap();
     }
@@ -592,7 +929,9 @@ pub mod test {
         };
         let respond = Respond::Request(Box::new(respond));
         let msg = (msg, respond);
-        handle_incoming_message(msg, admin_api).await.unwrap();
+        handle_incoming_message(msg, WireFormat::Msgpack, admin_api)
+            .await
+            .unwrap();
         conductor_handle.shutdown();
         shutdown.await.unwrap().unwrap();
     }
@@ -616,6 +955,8 @@ pub mod test {
                     .map(TestZomes::from)
                     .map(|z| z.coordinator.into_inner())
                     .collect(),
+                runtime_properties: Default::default(),
+                ephemeral_ttl_secs: None,
             },
             zomes.into_iter().flat_map(|t| Vec::<DnaWasm>::from(t)),
         )
@@ -746,7 +1087,9 @@ pub mod test {
         let respond = Respond::Request(Box::new(respond));
         let msg = (msg, respond);
 
-        handle_incoming_message(msg, admin_api).await.unwrap();
+        handle_incoming_message(msg, WireFormat::Msgpack, admin_api)
+            .await
+            .unwrap();
         rx
     }
 
@@ -3,6 +3,7 @@
 mod query_region_set;
 mod query_size_limited_regions;
 
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use super::{ribosome_store::RibosomeStore, space::Spaces};
@@ -85,6 +86,39 @@ impl KitsuneHost for KitsuneHostImpl {
         .into()
     }
 
+    fn query_latest_metrics(
+        &self,
+        space: std::sync::Arc<kitsune_p2p::KitsuneSpace>,
+    ) -> KitsuneHostResult<Vec<kitsune_p2p::event::MetricRecord>> {
+        async move {
+            let db = self.spaces.p2p_metrics_db(&DnaHash::from_kitsune(&space))?;
+            use holochain_sqlite::db::AsP2pMetricStoreConExt;
+            let permit = db.conn_permit().await;
+            let task = tokio::task::spawn_blocking(move || {
+                let mut conn = db.with_permit(permit)?;
+                conn.p2p_latest_metrics()
+            })
+            .await;
+            Ok(task??)
+        }
+        .boxed()
+        .into()
+    }
+
+    fn space_tuning_param_overrides(
+        &self,
+        space: Arc<kitsune_p2p::KitsuneSpace>,
+    ) -> KitsuneHostResult<HashMap<String, String>> {
+        async move {
+            Ok(self
+                .spaces
+                .dna_tuning_param_overrides(&DnaHash::from_kitsune(&space))
+                .await?)
+        }
+        .boxed()
+        .into()
+    }
+
     fn get_agent_info_signed(
         &self,
         GetAgentInfoSignedEvt { space, agent }: GetAgentInfoSignedEvt,
@@ -132,6 +166,16 @@ impl KitsuneHost for KitsuneHostImpl {
         .into()
     }
 
+    fn dht_storage_bytes(&self, space: Arc<kitsune_p2p::KitsuneSpace>) -> KitsuneHostResult<u64> {
+        let dna_hash = DnaHash::from_kitsune(&space);
+        async move {
+            let db = self.spaces.dht_db(&dna_hash)?;
+            Ok(db.size_bytes().await?)
+        }
+        .boxed()
+        .into()
+    }
+
     fn get_topology(&self, space: Arc<kitsune_p2p::KitsuneSpace>) -> KitsuneHostResult<Topology> {
         let dna_hash = DnaHash::from_kitsune(&space);
         let dna_def = self
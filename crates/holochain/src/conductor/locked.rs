@@ -0,0 +1,174 @@
+//! Serving the admin interfaces of a conductor which was started locked,
+//! until it is unlocked with the passphrase of its keystore.
+//!
+//! A locked conductor has no keystore, so none of its cells can run. Its
+//! admin interfaces answer every request but
+//! [`AdminRequest::UnlockConductor`] with
+//! [`ExternalApiWireError::ConductorLocked`], so clients and service managers
+//! can tell a locked conductor from one which is down.
+
+use super::api::error::SerializationError;
+use super::api::AdminRequest;
+use super::api::AdminResponse;
+use super::api::ExternalApiWireError;
+use super::api::InterfaceApi;
+use super::api::Passphrase;
+use super::config::AdminInterfaceConfig;
+use super::config::ConductorConfig;
+use super::error::ConductorError;
+use super::error::ConductorResult;
+use super::interface::connections::ConnectionCounter;
+use super::interface::error::InterfaceResult;
+use super::interface::websocket::spawn_admin_interface_task;
+use super::interface::websocket::spawn_websocket_listener;
+use holochain_conductor_api::conductor::KeystoreConfig;
+use holochain_keystore::lair_keystore::spawn_lair_keystore;
+use holochain_keystore::MetaLairClient;
+use holochain_serialized_bytes::SerializedBytesError;
+use std::sync::Arc;
+use tokio::sync::oneshot;
+
+/// How long the response to an unlock is given to reach the client before
+/// the interfaces are closed for the conductor to start.
+const UNLOCK_RESPONSE_GRACE: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// The admin API of a locked conductor.
+#[derive(Clone)]
+struct LockedAdminInterfaceApi {
+    connection_url: url2::Url2,
+    /// Taken by the first successful unlock.
+    unlocked: Arc<tokio::sync::Mutex<Option<oneshot::Sender<MetaLairClient>>>>,
+}
+
+impl LockedAdminInterfaceApi {
+    async fn unlock(&self, passphrase: Passphrase) -> AdminResponse {
+        // Held while the keystore is unlocked, so concurrent unlocks wait
+        // for the first rather than each spawning a keystore client.
+        let mut unlocked = self.unlocked.lock().await;
+        if unlocked.is_none() {
+            return AdminResponse::ConductorUnlocked;
+        }
+        let passphrase = match holochain_util::pw::pw_from_bytes(passphrase.0.into_bytes()) {
+            Ok(passphrase) => passphrase,
+            Err(e) => return AdminResponse::Error(ExternalApiWireError::internal(e)),
+        };
+        match spawn_lair_keystore(self.connection_url.clone(), passphrase).await {
+            Ok(keystore) => {
+                if let Some(unlocked) = unlocked.take() {
+                    let _ = unlocked.send(keystore);
+                }
+                AdminResponse::ConductorUnlocked
+            }
+            Err(e) => {
+                tracing::warn!(?e, "Failed to unlock the keystore");
+                AdminResponse::Error(ExternalApiWireError::internal(e))
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl InterfaceApi for LockedAdminInterfaceApi {
+    type ApiRequest = AdminRequest;
+    type ApiResponse = AdminResponse;
+
+    async fn handle_request(
+        &self,
+        request: Result<Self::ApiRequest, SerializedBytesError>,
+    ) -> InterfaceResult<Self::ApiResponse> {
+        Ok(match request {
+            Ok(AdminRequest::UnlockConductor { passphrase }) => self.unlock(passphrase).await,
+            Ok(_) => AdminResponse::Error(ExternalApiWireError::ConductorLocked),
+            Err(e) => AdminResponse::Error(SerializationError::from(e).into()),
+        })
+    }
+}
+
+/// Serve the admin interfaces of the config until the conductor is unlocked,
+/// returning its keystore.
+///
+/// The interfaces are closed before this returns, so the conductor can open
+/// them again when it's built with the keystore.
+pub async fn wait_for_unlock(config: &ConductorConfig) -> ConductorResult<MetaLairClient> {
+    let connection_url = match &config.keystore {
+        KeystoreConfig::LairServer { connection_url } => connection_url.clone(),
+        oth => {
+            return Err(ConductorError::other(format!(
+                "only a lair server keystore can be unlocked, not {:?}",
+                oth
+            )))
+        }
+    };
+    let interfaces = config.admin_interfaces.clone().unwrap_or_default();
+    if interfaces.is_empty() {
+        return Err(ConductorError::other(
+            "a locked conductor needs an admin interface to be unlocked through",
+        ));
+    }
+
+    let (unlocked_tx, unlocked_rx) = oneshot::channel();
+    let api = LockedAdminInterfaceApi {
+        connection_url,
+        unlocked: Arc::new(tokio::sync::Mutex::new(Some(unlocked_tx))),
+    };
+    let (stop_tx, _) = tokio::sync::broadcast::channel(1);
+    let mut tasks = Vec::with_capacity(interfaces.len());
+    for AdminInterfaceConfig { driver, .. } in interfaces {
+        let (listener_handle, listener) =
+            spawn_websocket_listener(&driver).await.map_err(Box::new)?;
+        tasks.push(
+            spawn_admin_interface_task(
+                listener_handle,
+                listener,
+                api.clone(),
+                ConnectionCounter::default(),
+                stop_tx.subscribe(),
+            )
+            .map_err(Box::new)?,
+        );
+    }
+    tracing::info!("Conductor locked, waiting to be unlocked over an admin interface");
+
+    let keystore = unlocked_rx
+        .await
+        .map_err(|_| ConductorError::other("the locked admin interfaces closed"))?;
+    tracing::info!("Conductor unlocked");
+
+    tokio::time::sleep(UNLOCK_RESPONSE_GRACE).await;
+    let _ = stop_tx.send(());
+    // The listeners are dropped when their tasks end, freeing their
+    // addresses for the conductor.
+    futures::future::join_all(tasks).await;
+    Ok(keystore)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn locked_interface_only_accepts_unlock() {
+        let (unlocked_tx, _unlocked_rx) = oneshot::channel();
+        let api = LockedAdminInterfaceApi {
+            connection_url: url2::url2!("unix:///nonexistent/socket"),
+            unlocked: Arc::new(tokio::sync::Mutex::new(Some(unlocked_tx))),
+        };
+        let response = api
+            .handle_request(Ok(AdminRequest::ListDnas))
+            .await
+            .unwrap();
+        assert!(matches!(
+            response,
+            AdminResponse::Error(ExternalApiWireError::ConductorLocked)
+        ));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn only_lair_keystores_are_unlocked() {
+        let config = ConductorConfig {
+            keystore: KeystoreConfig::DangerTestKeystore,
+            ..Default::default()
+        };
+        assert!(wait_for_unlock(&config).await.is_err());
+    }
+}
@@ -0,0 +1,100 @@
+//! The filter on which logs are written, kept behind a reload handle so that
+//! `log_filter` can be changed with a conductor config reload.
+//!
+//! Logging is set up once per process, so the handle is kept in a global,
+//! the same way as the wasm module cache directory.
+
+use observability::Output;
+use once_cell::sync::OnceCell;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::reload;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::EnvFilter;
+
+struct ReloadHandle {
+    reload: Box<dyn Fn(EnvFilter) -> Result<(), reload::Error> + Send + Sync>,
+    current: Box<dyn Fn() -> Option<String> + Send + Sync>,
+}
+
+static HANDLE: OnceCell<ReloadHandle> = OnceCell::new();
+
+/// The filter for the given directives, in the format of `RUST_LOG`.
+/// Without any, `RUST_LOG` is used as [`observability::init_fmt`] reads it.
+pub fn env_filter(directives: Option<&str>) -> Result<EnvFilter, String> {
+    match directives {
+        Some(directives) => EnvFilter::try_new(directives).map_err(|e| e.to_string()),
+        None => match std::env::var("RUST_LOG") {
+            Ok(_) => Ok(EnvFilter::from_default_env()),
+            Err(_) => Ok(EnvFilter::from_default_env()
+                .add_directive("[wasm_debug]=debug".parse().map_err(|e| format!("{}", e))?)),
+        },
+    }
+}
+
+/// Wrap a filter in a layer which can be reloaded, and keep its handle for
+/// [`set_log_filter`]. Only the first reloadable filter in a process is kept.
+pub fn reloadable<S>(filter: EnvFilter) -> reload::Layer<EnvFilter, S>
+where
+    S: tracing::Subscriber + 'static,
+{
+    let (layer, handle) = reload::Layer::new(filter);
+    let current = handle.clone();
+    let _ = HANDLE.set(ReloadHandle {
+        reload: Box::new(move |filter| handle.reload(filter)),
+        current: Box::new(move || current.with_current(|f| f.to_string()).ok()),
+    });
+    layer
+}
+
+/// Start logging to stderr as [`observability::init_fmt`] does, with a filter
+/// which can be reloaded. The flamegraph, json and OpenTelemetry outputs have
+/// their own formatting, so they are left to `observability`, and their
+/// filter can't be changed without a restart.
+pub fn init_fmt(output: Output, directives: Option<&str>) -> Result<(), String> {
+    let filter = env_filter(directives)?;
+    let fmt = || {
+        tracing_subscriber::fmt::layer()
+            .with_writer(std::io::stderr)
+            .with_target(true)
+    };
+    let registry = tracing_subscriber::registry();
+    match output {
+        Output::Log => registry.with(reloadable(filter)).with(fmt()).try_init(),
+        Output::LogTimed => registry
+            .with(reloadable(filter))
+            .with(fmt().with_span_events(FmtSpan::CLOSE))
+            .try_init(),
+        Output::Compact => registry
+            .with(reloadable(filter))
+            .with(fmt().compact())
+            .try_init(),
+        Output::None => Ok(()),
+        output => {
+            // `observability` reads its filter from the environment.
+            if let Some(directives) = directives {
+                std::env::set_var("RUST_LOG", directives);
+            }
+            return observability::init_fmt(output).map_err(|e| e.to_string());
+        }
+    }
+    .map_err(|e| e.to_string())
+}
+
+/// Can the log filter be changed while running.
+pub(crate) fn is_reloadable() -> bool {
+    HANDLE.get().is_some()
+}
+
+/// Swap in the filter for the given directives, see [`env_filter`].
+pub(crate) fn set_log_filter(directives: Option<&str>) -> Result<(), String> {
+    let handle = HANDLE
+        .get()
+        .ok_or_else(|| "logging was not started with a reloadable filter".to_string())?;
+    (handle.reload)(env_filter(directives)?).map_err(|e| e.to_string())
+}
+
+/// The filter in effect, if it can be reloaded.
+pub fn current_log_filter() -> Option<String> {
+    HANDLE.get().and_then(|handle| (handle.current)())
+}
@@ -42,6 +42,9 @@ pub enum ManagedTaskError {
 
     #[error(transparent)]
     Recv(#[from] tokio::sync::broadcast::error::RecvError),
+
+    #[error("The task was aborted after working for {0:?} without progress")]
+    Stalled(std::time::Duration),
 }
 
 pub type ManagedTaskResult = Result<(), ManagedTaskError>;
@@ -53,11 +56,28 @@ impl ManagedTaskError {
         #[allow(clippy::match_like_matches_macro)]
         match self {
             Io(_) | Join(_) | Recv(_) => false,
+            // The task can be started again along with its cell.
+            Stalled(_) => true,
             Conductor(err) => match **err {
                 C::ShuttingDown => true,
+                // The cell can run again once the operator has fixed the cause.
+                C::RepeatedWorkflowFailure { .. } => true,
                 // TODO: identify all recoverable cases
                 _ => false,
             },
         }
     }
+
+    /// Whether a cell's workflow gave up after failing too many times in a row.
+    pub fn is_repeated_workflow_failure(&self) -> bool {
+        matches!(
+            self,
+            ManagedTaskError::Conductor(err)
+                if matches!(**err, ConductorError::RepeatedWorkflowFailure { .. })
+        )
+    }
+    /// Whether the task was aborted for making no progress.
+    pub fn is_stalled(&self) -> bool {
+        matches!(self, ManagedTaskError::Stalled(_))
+    }
 }
@@ -4,10 +4,16 @@
 //! then a reaction can be set.
 //! An example would be a websocket closes with an error
 //! and you want to restart it.
+//!
+//! The tasks which are running are kept in a [`TaskTable`], which is also
+//! used to find tasks which have stalled and abort them.
 
 mod error;
 pub use error::*;
 
+mod task_table;
+pub use task_table::*;
+
 use futures::stream::FuturesUnordered;
 use holochain_types::prelude::*;
 use std::future::Future;
@@ -56,6 +62,9 @@ pub struct ManagedTaskAdd {
     handle: ManagedTaskHandle,
     kind: TaskKind,
     name: String,
+    progress: TaskProgress,
+    /// Set once the task manager has added the task to its table.
+    registration: Option<TaskRegistration>,
 }
 
 impl ManagedTaskAdd {
@@ -64,9 +73,25 @@ impl ManagedTaskAdd {
             handle,
             kind,
             name: name.to_string(),
+            progress: TaskProgress::default(),
+            registration: None,
         }
     }
 
+    /// Watch the progress the task reports, so it's aborted if it stalls.
+    pub fn with_progress(mut self, progress: TaskProgress) -> Self {
+        self.progress = progress;
+        self
+    }
+
+    fn register(&mut self, table: &TaskTable) {
+        let cell_id = match &self.kind {
+            TaskKind::CellCritical(cell_id) => Some(cell_id.clone()),
+            _ => None,
+        };
+        self.registration = Some(table.register(&self.name, cell_id, self.progress.clone()));
+    }
+
     /// You just want the task in the task manager but don't want
     /// to react to an error
     pub fn ignore(handle: ManagedTaskHandle, name: &str) -> Self {
@@ -98,11 +123,24 @@ impl Future for ManagedTaskAdd {
     type Output = TaskOutcome;
 
     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let stalled = self
+            .registration
+            .as_ref()
+            .map_or(false, |registration| registration.poll_stalled(cx));
+        if stalled {
+            self.handle.abort();
+        }
         let p = std::pin::Pin::new(&mut self.handle);
         match JoinHandle::poll(p, cx) {
             Poll::Ready(r) => Poll::Ready(handle_completed_task(
                 &self.kind,
-                r.unwrap_or_else(|e| Err(e.into())),
+                r.unwrap_or_else(|e| {
+                    if stalled && e.is_cancelled() {
+                        Err(ManagedTaskError::Stalled(STALLED_TASK_TIMEOUT))
+                    } else {
+                        Err(e.into())
+                    }
+                }),
                 self.name.clone(),
             )),
             Poll::Pending => Poll::Pending,
@@ -136,20 +174,27 @@ pub enum TaskOutcome {
 
 struct TaskManager {
     stream: FuturesUnordered<ManagedTaskAdd>,
+    table: TaskTable,
 }
 
 impl TaskManager {
-    fn new() -> Self {
+    fn new(table: TaskTable) -> Self {
         let stream = FuturesUnordered::new();
-        TaskManager { stream }
+        TaskManager { stream, table }
+    }
+
+    fn push(&mut self, mut task: ManagedTaskAdd) {
+        task.register(&self.table);
+        self.stream.push(task);
     }
 }
 
 pub(crate) fn spawn_task_manager(
     handle: ConductorHandle,
+    table: TaskTable,
 ) -> (mpsc::Sender<ManagedTaskAdd>, TaskManagerRunHandle) {
     let (send, recv) = mpsc::channel(CHANNEL_SIZE);
-    (send, tokio::spawn(run(handle, recv)))
+    (send, tokio::spawn(run(handle, table, recv)))
 }
 
 /// A super pessimistic task that is just waiting to die
@@ -162,26 +207,33 @@ pub(crate) async fn keep_alive_task(mut die: broadcast::Receiver<()>) -> Managed
 
 async fn run(
     conductor: ConductorHandle,
+    table: TaskTable,
     mut new_task_channel: mpsc::Receiver<ManagedTaskAdd>,
 ) -> TaskManagerResult {
-    let mut task_manager = TaskManager::new();
+    let mut task_manager = TaskManager::new(table);
     // Need to have at least one item in the stream or it will exit early
     if let Some(new_task) = new_task_channel.recv().await {
-        task_manager.stream.push(new_task);
+        task_manager.push(new_task);
     } else {
         error!("All senders to task manager were dropped before starting");
         return Err(TaskManagerError::TaskManagerFailedToStart);
     }
+    let mut stall_check = tokio::time::interval(STALL_CHECK_INTERVAL);
     loop {
         tokio::select! {
             Some(new_task) = new_task_channel.recv() => {
-                task_manager.stream.push(new_task);
+                task_manager.push(new_task);
                 tracing::debug!("Task added. Total tasks: {}", task_manager.stream.len());
             }
+            _ = stall_check.tick() => {
+                for name in task_manager.table.stall_tasks(Timestamp::now(), STALLED_TASK_TIMEOUT) {
+                    warn!(task = %name, "Aborting a task which has stalled");
+                }
+            }
             result = task_manager.stream.next() => {
                 tracing::debug!("Task completed. Total tasks: {}", task_manager.stream.len());
                 match result {
-                Some(TaskOutcome::NewTask(new_task)) => task_manager.stream.push(new_task),
+                Some(TaskOutcome::NewTask(new_task)) => task_manager.push(new_task),
                 Some(TaskOutcome::LogInfo(context)) => {
                     debug!("Managed task completed: {}", context)
                 }
@@ -208,6 +260,18 @@ async fn run(
                     tracing::error!("About to automatically stop apps");
                     let app_ids = conductor.list_running_apps_for_required_cell_id(&cell_id).await.map_err(TaskManagerError::internal)?;
                     if error.is_recoverable() {
+                        if error.is_repeated_workflow_failure() || error.is_stalled() {
+                            // Pause with the actual error as the reason, so the operator can see
+                            // why the apps stopped without having to read the logs.
+                            let reason = error.to_string();
+                            for app_id in app_ids.iter() {
+                                conductor.clone().pause_app(app_id.to_string(), PausedAppReason::Error(reason.clone())).await.map_err(TaskManagerError::internal)?;
+                            }
+                            let signal = SystemSignal::CellPaused(cell_id.clone(), reason);
+                            if let Err(e) = conductor.signal_broadcaster().await.send(signal.into()) {
+                                tracing::warn!(?e, "Failed to broadcast CellPaused signal");
+                            }
+                        }
                         conductor.remove_cells(&[cell_id]).await;
 
                         // The following message assumes that only the app_ids calculated will be paused, but other apps
@@ -393,7 +457,8 @@ mod test {
     async fn spawn_and_handle_dying_task() -> Result<()> {
         observability::test_run().ok();
         let mock_handle = MockConductorHandleT::new();
-        let (send_task_handle, main_task) = spawn_task_manager(Arc::new(mock_handle));
+        let (send_task_handle, main_task) =
+            spawn_task_manager(Arc::new(mock_handle), TaskTable::default());
         let handle = tokio::spawn(async {
             Err(Box::new(ConductorError::Other(
                 anyhow::anyhow!("This task gotta die").into(),
@@ -431,7 +496,8 @@ mod test {
         observability::test_run().ok();
         let (_tx, rx) = tokio::sync::broadcast::channel(1);
         let mock_handle = MockConductorHandleT::new();
-        let (send_task_handle, main_task) = spawn_task_manager(Arc::new(mock_handle));
+        let (send_task_handle, main_task) =
+            spawn_task_manager(Arc::new(mock_handle), TaskTable::default());
         send_task_handle
             .send(ManagedTaskAdd::ignore(
                 tokio::spawn(keep_alive_task(rx)),
@@ -466,7 +532,8 @@ mod test {
         observability::test_run().ok();
         let (_tx, rx) = tokio::sync::broadcast::channel(1);
         let mock_handle = MockConductorHandleT::new();
-        let (send_task_handle, main_task) = spawn_task_manager(Arc::new(mock_handle));
+        let (send_task_handle, main_task) =
+            spawn_task_manager(Arc::new(mock_handle), TaskTable::default());
         send_task_handle
             .send(ManagedTaskAdd::ignore(
                 tokio::spawn(keep_alive_task(rx)),
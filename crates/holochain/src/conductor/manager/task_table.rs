@@ -0,0 +1,231 @@
+//! The table of the tasks the task manager is running, for
+//! [`AdminRequest::DumpTasks`], and for finding the ones which have stalled.
+//!
+//! A task can report its progress with a [`TaskProgress`], marking when it
+//! starts and finishes each unit of work, e.g. each run of a queue
+//! consumer's workflow. A task which has been on one unit of work for longer
+//! than [`STALLED_TASK_TIMEOUT`] is stalled. It is aborted, and its end is
+//! handled according to its [`TaskKind`](super::TaskKind) with
+//! [`ManagedTaskError::Stalled`](super::ManagedTaskError::Stalled).
+//! Tasks which don't report their progress are never stalled.
+//!
+//! [`AdminRequest::DumpTasks`]: holochain_conductor_api::AdminRequest::DumpTasks
+
+use futures::task::AtomicWaker;
+use holochain_conductor_api::TaskInfo;
+use holochain_types::prelude::*;
+use std::collections::HashMap;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::task::Context;
+use std::time::Duration;
+
+/// How long a task may spend on one unit of work before it's stalled.
+pub const STALLED_TASK_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+/// How often the task manager looks for stalled tasks.
+pub(super) const STALL_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// The progress a task reports, shared with the task table.
+#[derive(Clone, Default)]
+pub struct TaskProgress(Arc<parking_lot::Mutex<Progress>>);
+
+#[derive(Default)]
+struct Progress {
+    last_progress: Option<Timestamp>,
+    working_since: Option<Timestamp>,
+}
+
+impl TaskProgress {
+    /// Mark the start of a unit of work, finishing the last one if it
+    /// hasn't been.
+    pub fn start_work(&self) {
+        let now = Timestamp::now();
+        let mut progress = self.0.lock();
+        if progress.working_since.is_some() {
+            progress.last_progress = Some(now);
+        }
+        progress.working_since = Some(now);
+    }
+
+    /// Mark the end of the current unit of work, if there is one.
+    pub fn finish_work(&self) {
+        let mut progress = self.0.lock();
+        if progress.working_since.take().is_some() {
+            progress.last_progress = Some(Timestamp::now());
+        }
+    }
+}
+
+/// Set when a task has stalled, waking the task manager's future for it so
+/// the task is aborted.
+#[derive(Default)]
+struct StallSignal {
+    stalled: AtomicBool,
+    waker: AtomicWaker,
+}
+
+struct Row {
+    name: String,
+    cell_id: Option<CellId>,
+    started_at: Timestamp,
+    progress: TaskProgress,
+    stall: Arc<StallSignal>,
+}
+
+#[derive(Default)]
+struct Tasks {
+    next_id: u64,
+    rows: HashMap<u64, Row>,
+}
+
+/// The tasks the task manager is running.
+#[derive(Clone, Default)]
+pub struct TaskTable(Arc<parking_lot::Mutex<Tasks>>);
+
+impl TaskTable {
+    /// Add a task to the table, for as long as the returned registration is
+    /// held.
+    pub(super) fn register(
+        &self,
+        name: &str,
+        cell_id: Option<CellId>,
+        progress: TaskProgress,
+    ) -> TaskRegistration {
+        let stall = Arc::new(StallSignal::default());
+        let mut tasks = self.0.lock();
+        let id = tasks.next_id;
+        tasks.next_id += 1;
+        tasks.rows.insert(
+            id,
+            Row {
+                name: name.to_string(),
+                cell_id,
+                started_at: Timestamp::now(),
+                progress,
+                stall: stall.clone(),
+            },
+        );
+        TaskRegistration {
+            table: self.clone(),
+            id,
+            stall,
+        }
+    }
+
+    /// Stall the tasks which have been on a unit of work since before
+    /// `now - timeout`, returning the names of the newly stalled ones.
+    pub(super) fn stall_tasks(&self, now: Timestamp, timeout: Duration) -> Vec<String> {
+        let cutoff = (now - timeout).unwrap_or(Timestamp::MIN);
+        self.0
+            .lock()
+            .rows
+            .values()
+            .filter(|row| {
+                row.progress
+                    .0
+                    .lock()
+                    .working_since
+                    .map_or(false, |since| since < cutoff)
+            })
+            .filter(|row| !row.stall.stalled.swap(true, Ordering::AcqRel))
+            .map(|row| {
+                row.stall.waker.wake();
+                row.name.clone()
+            })
+            .collect()
+    }
+
+    /// The tasks in the table, oldest first.
+    pub(crate) fn info(&self) -> Vec<TaskInfo> {
+        let mut info: Vec<_> = self
+            .0
+            .lock()
+            .rows
+            .values()
+            .map(|row| {
+                let progress = row.progress.0.lock();
+                TaskInfo {
+                    name: row.name.clone(),
+                    cell_id: row.cell_id.clone(),
+                    started_at: row.started_at,
+                    last_progress: progress.last_progress,
+                    working_since: progress.working_since,
+                    stalled: row.stall.stalled.load(Ordering::Acquire),
+                }
+            })
+            .collect();
+        info.sort_by_key(|task| task.started_at);
+        info
+    }
+}
+
+/// A task's row in a [`TaskTable`], which is removed when this is dropped.
+pub(super) struct TaskRegistration {
+    table: TaskTable,
+    id: u64,
+    stall: Arc<StallSignal>,
+}
+
+impl TaskRegistration {
+    /// Whether the task has stalled, waking the current task when it does.
+    pub(super) fn poll_stalled(&self, cx: &mut Context<'_>) -> bool {
+        self.stall.waker.register(cx.waker());
+        self.stall.stalled.load(Ordering::Acquire)
+    }
+}
+
+impl Drop for TaskRegistration {
+    fn drop(&mut self) {
+        self.table.0.lock().rows.remove(&self.id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn tasks_stuck_on_work_are_aborted() {
+        let table = TaskTable::default();
+        let progress = TaskProgress::default();
+        let handle = tokio::spawn(futures::future::pending::<ManagedTaskResult>());
+        let mut stuck = ManagedTaskAdd::ignore(handle, "stuck").with_progress(progress.clone());
+        stuck.register(&table);
+        let handle = tokio::spawn(futures::future::pending::<ManagedTaskResult>());
+        let mut idle = ManagedTaskAdd::ignore(handle, "idle");
+        idle.register(&table);
+
+        progress.start_work();
+        let later = (Timestamp::now() + Duration::from_secs(1)).unwrap();
+        assert!(table.stall_tasks(later, STALLED_TASK_TIMEOUT).is_empty());
+        assert_eq!(table.stall_tasks(later, Duration::ZERO), vec!["stuck"]);
+        assert!(table.stall_tasks(later, Duration::ZERO).is_empty());
+
+        let info = table.info();
+        assert_eq!(info.len(), 2);
+        assert!(info.iter().any(|task| task.name == "stuck" && task.stalled));
+        assert!(info.iter().any(|task| task.name == "idle" && !task.stalled));
+
+        assert!(matches!(
+            stuck.await,
+            TaskOutcome::MinorError(e, _) if matches!(*e, ManagedTaskError::Stalled(_))
+        ));
+        assert_eq!(table.info().len(), 1);
+    }
+
+    #[test]
+    fn finished_work_is_progress() {
+        let progress = TaskProgress::default();
+        progress.finish_work();
+        assert!(progress.0.lock().last_progress.is_none());
+        progress.start_work();
+        assert!(progress.0.lock().working_since.is_some());
+        progress.finish_work();
+        let progress = progress.0.lock();
+        assert!(progress.working_since.is_none());
+        assert!(progress.last_progress.is_some());
+    }
+}
@@ -46,9 +46,12 @@ pub async fn inject_agent_infos<'iter, I: IntoIterator<Item = &'iter AgentInfoSi
 }
 
 /// Inject multiple agent info entries into the peer store in batches.
+/// If pinning peer certs, agent infos advertising different certs to
+/// the unexpired info already held for the agent are dropped.
 pub async fn p2p_put_all_batch(
     env: DbWrite<DbKindP2pAgents>,
     rx: tokio::sync::mpsc::Receiver<P2pBatch>,
+    pin_peer_certs: bool,
 ) {
     let stream = tokio_stream::wrappers::ReceiverStream::new(rx);
     let mut stream = stream.ready_chunks(100);
@@ -63,6 +66,22 @@ pub async fn p2p_put_all_batch(
                 } in batch
                 {
                     for info in batch {
+                        if pin_peer_certs {
+                            match p2p_cert_pin_matches(txn, &info, now()) {
+                                Ok(true) => (),
+                                Ok(false) => {
+                                    tracing::warn!(
+                                        agent = ?info.agent,
+                                        "dropping agent info with a changed tls cert"
+                                    );
+                                    continue;
+                                }
+                                Err(e) => {
+                                    responses.push((Err(e), response));
+                                    continue 'batch;
+                                }
+                            }
+                        }
                         match p2p_put_single(txn, &info) {
                             Ok(_) => (),
                             Err(e) => {
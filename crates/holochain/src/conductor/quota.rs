@@ -0,0 +1,253 @@
+//! Enforcement of the [`AppQuota`]s of installed apps.
+//!
+//! The quotas are kept in memory along with the cells of each app, so that
+//! zome calls to apps without a quota cost no more than a map lookup.
+//! The disk space an app uses is measured at most once every
+//! [`DISK_USAGE_TTL`], since it takes a query of each of its databases.
+
+use holochain_types::prelude::*;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::time::Duration;
+use std::time::Instant;
+
+use super::state::ConductorState;
+
+/// How long a measurement of the disk space used by an app is trusted for.
+pub(crate) const DISK_USAGE_TTL: Duration = Duration::from_secs(10);
+
+/// A request refused because of an app's quota.
+#[derive(Debug)]
+pub(crate) struct Refusal {
+    pub exceeded: AppQuotaExceeded,
+    /// Whether this is the first refusal since the app was last within its
+    /// quota, which is when a signal is emitted.
+    pub first: bool,
+}
+
+/// The quotas of the installed apps which have one, and their usage so far.
+#[derive(Default)]
+pub(crate) struct QuotaTracker(parking_lot::Mutex<Option<HashMap<InstalledAppId, AppUsage>>>);
+
+struct AppUsage {
+    quota: AppQuota,
+    cells: HashSet<CellId>,
+    calls: CallWindow,
+    disk: Option<DiskUsage>,
+    disk_refused: bool,
+}
+
+/// The zome calls made in the current second.
+struct CallWindow {
+    start: Instant,
+    count: u32,
+    refused: bool,
+}
+
+struct DiskUsage {
+    measured_at: Instant,
+    bytes: u64,
+}
+
+impl QuotaTracker {
+    /// Have the quotas been read from the conductor state yet.
+    pub(crate) fn is_loaded(&self) -> bool {
+        self.0.lock().is_some()
+    }
+
+    /// Take the quotas and cells of the apps from the conductor state,
+    /// keeping the usage of the apps which were already tracked.
+    pub(crate) fn refresh(&self, state: &ConductorState) {
+        let mut apps = self.0.lock();
+        let mut old = apps.take().unwrap_or_default();
+        let now = Instant::now();
+        let new = state
+            .installed_apps()
+            .iter()
+            .filter(|(_, app)| !app.quota().is_unlimited())
+            .map(|(app_id, app)| {
+                let cells = app.all_cells().cloned().collect();
+                let usage = match old.remove(app_id) {
+                    Some(usage) => AppUsage {
+                        quota: app.quota().clone(),
+                        cells,
+                        ..usage
+                    },
+                    None => AppUsage {
+                        quota: app.quota().clone(),
+                        cells,
+                        calls: CallWindow {
+                            start: now,
+                            count: 0,
+                            refused: false,
+                        },
+                        disk: None,
+                        disk_refused: false,
+                    },
+                };
+                (app_id.clone(), usage)
+            })
+            .collect();
+        *apps = Some(new);
+    }
+
+    /// The apps with a disk quota which this cell belongs to and whose disk
+    /// usage needs measuring, along with their cells.
+    pub(crate) fn unmeasured_apps(
+        &self,
+        cell_id: &CellId,
+        now: Instant,
+    ) -> Vec<(InstalledAppId, Vec<CellId>)> {
+        let apps = self.0.lock();
+        apps.iter()
+            .flatten()
+            .filter(|(_, usage)| usage.quota.max_disk_bytes.is_some())
+            .filter(|(_, usage)| usage.cells.contains(cell_id))
+            .filter(|(_, usage)| {
+                usage.disk.as_ref().map_or(true, |disk| {
+                    now.duration_since(disk.measured_at) >= DISK_USAGE_TTL
+                })
+            })
+            .map(|(app_id, usage)| (app_id.clone(), usage.cells.iter().cloned().collect()))
+            .collect()
+    }
+
+    /// Record how many bytes the databases of an app take up.
+    pub(crate) fn record_disk_usage(&self, app_id: &InstalledAppId, bytes: u64, now: Instant) {
+        if let Some(usage) = self.0.lock().as_mut().and_then(|apps| apps.get_mut(app_id)) {
+            usage.disk = Some(DiskUsage {
+                measured_at: now,
+                bytes,
+            });
+        }
+    }
+
+    /// Count a zome call to this cell against the quotas of its apps,
+    /// unless it would exceed one of them.
+    pub(crate) fn count_zome_call(&self, cell_id: &CellId, now: Instant) -> Result<(), Refusal> {
+        let mut apps = self.0.lock();
+        let apps = match apps.as_mut() {
+            Some(apps) => apps,
+            None => return Ok(()),
+        };
+        let mut counted = Vec::new();
+        for (app_id, usage) in apps.iter_mut() {
+            if !usage.cells.contains(cell_id) {
+                continue;
+            }
+            if let Some(exceeded) = usage.disk_exceeded() {
+                let first = !std::mem::replace(&mut usage.disk_refused, true);
+                return Err(refusal(app_id, exceeded, first));
+            }
+            usage.disk_refused = false;
+            if let Some(max) = usage.quota.max_zome_calls_per_second {
+                let calls = &mut usage.calls;
+                if now.duration_since(calls.start) >= Duration::from_secs(1) {
+                    calls.start = now;
+                    calls.count = 0;
+                    calls.refused = false;
+                }
+                if calls.count >= max {
+                    let first = !std::mem::replace(&mut calls.refused, true);
+                    return Err(refusal(app_id, QuotaExceeded::ZomeCallRate { max }, first));
+                }
+                counted.push(app_id.clone());
+            }
+        }
+        // Only count the call once it is allowed by all of its apps.
+        for app_id in counted {
+            if let Some(usage) = apps.get_mut(&app_id) {
+                usage.calls.count += 1;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl AppUsage {
+    fn disk_exceeded(&self) -> Option<QuotaExceeded> {
+        let max = self.quota.max_disk_bytes?;
+        let used = self.disk.as_ref()?.bytes;
+        (used >= max).then(|| QuotaExceeded::DiskBytes { used, max })
+    }
+}
+
+fn refusal(app_id: &InstalledAppId, exceeded: QuotaExceeded, first: bool) -> Refusal {
+    Refusal {
+        exceeded: AppQuotaExceeded {
+            installed_app_id: app_id.clone(),
+            exceeded,
+        },
+        first,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::fixt::prelude::*;
+    use holo_hash::fixt::AgentPubKeyFixturator;
+    use holo_hash::fixt::DnaHashFixturator;
+
+    fn tracker(quota: AppQuota) -> (QuotaTracker, CellId) {
+        let cell_id = CellId::new(fixt!(DnaHash), fixt!(AgentPubKey));
+        let role = AppRoleAssignment::new(cell_id.clone(), true, 0);
+        let mut app = InstalledAppCommon::new("app", fixt!(AgentPubKey), [("role".into(), role)]);
+        app.set_quota(quota);
+        let mut state = ConductorState::default();
+        state.add_app(app).unwrap();
+        let tracker = QuotaTracker::default();
+        tracker.refresh(&state);
+        (tracker, cell_id)
+    }
+
+    #[test]
+    fn zome_calls_are_limited_per_second() {
+        let (tracker, cell_id) = tracker(AppQuota {
+            max_zome_calls_per_second: Some(2),
+            ..Default::default()
+        });
+        let start = Instant::now();
+        tracker.count_zome_call(&cell_id, start).unwrap();
+        tracker.count_zome_call(&cell_id, start).unwrap();
+        let refusal = tracker.count_zome_call(&cell_id, start).unwrap_err();
+        assert_eq!(
+            refusal.exceeded.exceeded,
+            QuotaExceeded::ZomeCallRate { max: 2 }
+        );
+        assert!(refusal.first);
+        assert!(!tracker.count_zome_call(&cell_id, start).unwrap_err().first);
+
+        // Calls to other cells don't count.
+        let other = CellId::new(fixt!(DnaHash), fixt!(AgentPubKey));
+        tracker.count_zome_call(&other, start).unwrap();
+
+        let later = start + Duration::from_secs(1);
+        tracker.count_zome_call(&cell_id, later).unwrap();
+    }
+
+    #[test]
+    fn disk_usage_is_measured_again_after_the_ttl() {
+        let (tracker, cell_id) = tracker(AppQuota {
+            max_disk_bytes: Some(100),
+            ..Default::default()
+        });
+        let start = Instant::now();
+        assert_eq!(tracker.unmeasured_apps(&cell_id, start).len(), 1);
+        tracker.record_disk_usage(&"app".to_string(), 100, start);
+        assert!(tracker.unmeasured_apps(&cell_id, start).is_empty());
+        let refusal = tracker.count_zome_call(&cell_id, start).unwrap_err();
+        assert_eq!(
+            refusal.exceeded.exceeded,
+            QuotaExceeded::DiskBytes {
+                used: 100,
+                max: 100
+            }
+        );
+
+        let later = start + DISK_USAGE_TTL;
+        assert_eq!(tracker.unmeasured_apps(&cell_id, later).len(), 1);
+        tracker.record_disk_usage(&"app".to_string(), 50, later);
+        tracker.count_zome_call(&cell_id, later).unwrap();
+    }
+}
@@ -0,0 +1,68 @@
+//! The relay the conductor runs for other peers, configured with
+//! [`RelayConfig`].
+
+use super::error::ConductorError;
+use super::error::ConductorResult;
+use holochain_conductor_api::config::conductor::ConductorConfig;
+use holochain_conductor_api::config::conductor::RelayConfig;
+use holochain_p2p::kitsune_p2p::dependencies::kitsune_p2p_proxy::relay;
+use holochain_p2p::kitsune_p2p::dependencies::kitsune_p2p_proxy::tx2::RelayLimits;
+use kitsune_p2p_types::tls::TlsConfig;
+
+pub(crate) use relay::Relay;
+
+/// Bind the relay, loading or creating its TLS certificate.
+/// It forwards with the conductor's network tuning params.
+pub(crate) async fn spawn_relay(
+    relay_config: &RelayConfig,
+    config: &ConductorConfig,
+) -> ConductorResult<Relay> {
+    let tls = match &relay_config.tls_cert_path {
+        Some(path) if path.exists() => TlsConfig::load_file(path)
+            .await
+            .map_err(ConductorError::other)?,
+        Some(path) => {
+            let tls = TlsConfig::new_ephemeral()
+                .await
+                .map_err(ConductorError::other)?;
+            tls.save_file(path).await.map_err(ConductorError::other)?;
+            tls
+        }
+        None => TlsConfig::new_ephemeral()
+            .await
+            .map_err(ConductorError::other)?,
+    };
+    let allowed_clients = relay_config
+        .allowed_clients
+        .as_ref()
+        .map(|clients| {
+            clients
+                .iter()
+                .map(|digest| relay::parse_cert_digest(digest))
+                .collect::<Result<_, _>>()
+        })
+        .transpose()
+        .map_err(ConductorError::other)?;
+    let relay = relay::spawn_relay(relay::RelayConfig {
+        bind_to: relay_config
+            .bind_to
+            .as_ref()
+            .map(|url| url.as_str())
+            .unwrap_or("kitsune-quic://0.0.0.0:0")
+            .into(),
+        tls,
+        tuning_params: config
+            .network
+            .as_ref()
+            .map(|network| network.tuning_params.clone())
+            .unwrap_or_default(),
+        limits: RelayLimits {
+            allowed_clients,
+            max_bytes_per_second: relay_config.max_bytes_per_second_per_client,
+        },
+    })
+    .await
+    .map_err(ConductorError::other)?;
+    tracing::info!(addr = ?relay.local_addr(), "Relay started");
+    Ok(relay)
+}
@@ -0,0 +1,145 @@
+//! Verification of [`SignedZomeCall`]s, which clients holding their own
+//! keys make to cells hosted by this conductor.
+//!
+//! A valid signature by the agent of the cell proves the call comes from
+//! that agent, without the conductor needing the agent's private key.
+//! Each nonce is remembered until its call expires so a call can't be
+//! replayed, and calls can expire at most [`MAX_SIGNED_ZOME_CALL_LIFETIME`]
+//! ahead, which bounds how many nonces are kept.
+
+use super::error::ConductorError;
+use super::error::ConductorResult;
+use holochain_conductor_api::SignedZomeCall;
+use holochain_conductor_api::ZomeCall;
+use holochain_keystore::AgentPubKeyExt;
+use holochain_types::prelude::*;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// How far ahead a signed call may expire.
+pub(crate) const MAX_SIGNED_ZOME_CALL_LIFETIME: Duration = Duration::from_secs(60 * 5);
+
+/// The nonces of the signed calls which haven't expired yet.
+#[derive(Default)]
+pub(crate) struct NonceTracker(parking_lot::Mutex<HashMap<(AgentPubKey, [u8; 32]), Timestamp>>);
+
+impl NonceTracker {
+    /// Check a signed call, returning the call to make if it is valid.
+    pub(crate) async fn verify(&self, signed: SignedZomeCall) -> ConductorResult<ZomeCall> {
+        let SignedZomeCall {
+            call,
+            nonce,
+            expires_at,
+            signature,
+        } = signed;
+        if &call.provenance != call.cell_id.agent_pubkey() {
+            return Err(invalid("the provenance is not the agent of the cell"));
+        }
+        let now = Timestamp::now();
+        if expires_at <= now {
+            return Err(invalid("the call has expired"));
+        }
+        if expires_at > now.saturating_add(&MAX_SIGNED_ZOME_CALL_LIFETIME) {
+            return Err(invalid("the call expires too far in the future"));
+        }
+        let data = SignedZomeCall::data_to_sign(&call, &nonce, &expires_at)?;
+        if !call
+            .provenance
+            .verify_signature_raw(&signature, data.into())
+            .await
+        {
+            return Err(invalid("the signature is not the provenance's"));
+        }
+
+        let mut nonces = self.0.lock();
+        nonces.retain(|_, expires_at| *expires_at > now);
+        match nonces.entry((call.provenance.clone(), nonce)) {
+            Entry::Occupied(_) => Err(invalid("the nonce has already been used")),
+            Entry::Vacant(entry) => {
+                entry.insert(expires_at);
+                Ok(call)
+            }
+        }
+    }
+}
+
+fn invalid(reason: &str) -> ConductorError {
+    ConductorError::InvalidSignedZomeCall(reason.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::fixt::prelude::*;
+    use holo_hash::fixt::DnaHashFixturator;
+    use holochain_keystore::test_keystore::spawn_test_keystore;
+
+    async fn sign(
+        keystore: &holochain_keystore::MetaLairClient,
+        call: ZomeCall,
+        nonce: [u8; 32],
+        expires_at: Timestamp,
+    ) -> SignedZomeCall {
+        let data = SignedZomeCall::data_to_sign(&call, &nonce, &expires_at).unwrap();
+        let signature = call
+            .provenance
+            .sign_raw(keystore, data.into())
+            .await
+            .unwrap();
+        SignedZomeCall {
+            call,
+            nonce,
+            expires_at,
+            signature,
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn signed_calls_are_verified_once() {
+        let keystore = spawn_test_keystore().await.unwrap();
+        let agent = keystore.new_sign_keypair_random().await.unwrap();
+        let call = ZomeCall {
+            cell_id: CellId::new(fixt!(DnaHash), agent.clone()),
+            zome_name: "zome".into(),
+            fn_name: "fn".into(),
+            payload: ExternIO::encode(()).unwrap(),
+            cap_secret: None,
+            provenance: agent,
+        };
+        let expires_at = Timestamp::now().saturating_add(&Duration::from_secs(60));
+        let tracker = NonceTracker::default();
+
+        let signed = sign(&keystore, call.clone(), [1; 32], expires_at).await;
+        tracker.verify(signed.clone()).await.unwrap();
+        // The same call can't be made twice.
+        assert!(matches!(
+            tracker.verify(signed).await,
+            Err(ConductorError::InvalidSignedZomeCall(_))
+        ));
+
+        // Nor can it be changed after it was signed.
+        let mut signed = sign(&keystore, call.clone(), [2; 32], expires_at).await;
+        signed.call.fn_name = "other_fn".into();
+        assert!(tracker.verify(signed).await.is_err());
+
+        // Nor made as another agent.
+        let mut other = call.clone();
+        other.provenance = keystore.new_sign_keypair_random().await.unwrap();
+        assert!(tracker
+            .verify(sign(&keystore, other, [3; 32], expires_at).await)
+            .await
+            .is_err());
+
+        let expired = Timestamp::now().saturating_sub(&Duration::from_secs(1));
+        assert!(tracker
+            .verify(sign(&keystore, call.clone(), [4; 32], expired).await)
+            .await
+            .is_err());
+        let too_late = Timestamp::now().saturating_add(&Duration::from_secs(60 * 60));
+        assert!(tracker
+            .verify(sign(&keystore, call, [5; 32], too_late).await)
+            .await
+            .is_err());
+    }
+}
@@ -1,10 +1,12 @@
 //! This module contains data and functions for running operations
 //! at the level of a [`DnaHash`] space.
 //! Multiple [`Cell`](crate::conductor::Cell)'s could share the same space.
-use std::{collections::HashMap, sync::Arc, time::Duration};
+use std::{collections::HashMap, path::Path, sync::Arc, time::Duration};
 
-use holo_hash::{DhtOpHash, DnaHash};
-use holochain_conductor_api::conductor::{ConductorConfig, DatabaseRootPath};
+use holo_hash::{AgentPubKey, DhtOpHash, DnaHash};
+use holochain_conductor_api::conductor::{
+    ConductorConfig, DatabaseRootPath, DnaTuningParamsConfig,
+};
 use holochain_p2p::{
     dht::{
         arq::{power_and_count_from_length, ArqBoundsSet},
@@ -16,17 +18,18 @@ use holochain_p2p::{
         ArqBounds, ArqStrat,
     },
     dht_arc::{DhtArcRange, DhtArcSet},
-    event::FetchOpDataQuery,
+    event::{FetchOpDataQuery, QueuedRemoteSignal},
 };
 use holochain_sqlite::{
     conn::{DbSyncLevel, DbSyncStrategy},
     db::{
         DbKindAuthored, DbKindCache, DbKindConductor, DbKindDht, DbKindP2pAgents, DbKindP2pMetrics,
-        DbKindWasm, DbWrite, ReadAccess,
+        DbKindT, DbKindWasm, DbRead, DbWrite, ReadAccess,
     },
     prelude::{DatabaseError, DatabaseResult},
 };
 use holochain_state::{
+    integrity::{self, DbIntegrityIssue, DbIntegrityReport},
     mutations,
     prelude::{from_blob, StateQueryResult},
     query::{map_sql_dht_op_common, StateQueryError},
@@ -45,17 +48,19 @@ use tracing::instrument;
 
 use crate::conductor::{error::ConductorError, state::ConductorState};
 use crate::core::{
-    queue_consumer::QueueConsumerMap,
+    queue_consumer::{QueueConsumerMap, WorkflowScheduler},
     workflow::{
         countersigning_workflow::{incoming_countersigning, CountersigningWorkspace},
+        fault_injection::FaultInjector,
         incoming_dht_ops_workflow::{
-            incoming_dht_ops_workflow, IncomingOpHashes, IncomingOpsBatch,
+            incoming_dht_ops_workflow, IncomingOpHashes, IncomingOpsBatch, SeenOps,
         },
     },
 };
 
 use super::{
     conductor::RwShare,
+    dht_shard,
     error::ConductorResult,
     p2p_agent_store::{self, P2pBatch},
 };
@@ -74,9 +79,14 @@ pub struct Spaces {
     pub(crate) db_sync_strategy: DbSyncStrategy,
     /// The map of running queue consumer workflows.
     pub(crate) queue_consumer_map: QueueConsumerMap,
+    /// Shares validation concurrency fairly between all spaces.
+    pub(crate) workflow_scheduler: WorkflowScheduler,
+    /// The faults injected into the workflows of every space.
+    pub(crate) faults: FaultInjector,
     pub(crate) conductor_db: DbWrite<DbKindConductor>,
     pub(crate) wasm_db: DbWrite<DbKindWasm>,
     network_config: KitsuneP2pConfig,
+    dna_tuning_params: Arc<Vec<DnaTuningParamsConfig>>,
 }
 
 #[derive(Clone)]
@@ -120,6 +130,12 @@ pub struct Space {
 
     /// Incoming ops batch for this space.
     pub incoming_ops_batch: IncomingOpsBatch,
+
+    /// Ops which may already be stored in this space.
+    pub seen_ops: SeenOps,
+
+    /// The faults injected into this space's workflows by tests.
+    pub faults: FaultInjector,
 }
 
 #[cfg(test)]
@@ -152,12 +168,41 @@ impl Spaces {
             db_dir: Arc::new(root_db_dir),
             db_sync_strategy,
             queue_consumer_map: QueueConsumerMap::new(),
+            workflow_scheduler: WorkflowScheduler::default(),
+            faults: FaultInjector::default(),
             conductor_db,
             wasm_db,
             network_config: config.network.clone().unwrap_or_default(),
+            dna_tuning_params: Arc::new(config.dna_tuning_params.clone()),
         })
     }
 
+    /// The network tuning params configured for this DNA, by its hash or the
+    /// roles it's installed under, to override the conductor's global ones.
+    pub async fn dna_tuning_param_overrides(
+        &self,
+        dna_hash: &DnaHash,
+    ) -> ConductorResult<HashMap<String, String>> {
+        let mut overrides = HashMap::new();
+        if self.dna_tuning_params.is_empty() {
+            return Ok(overrides);
+        }
+        let state = self.get_state().await?;
+        let role_ids: Vec<_> = state
+            .installed_apps()
+            .iter()
+            .flat_map(|(_, app)| app.roles().iter())
+            .filter(|(_, role)| role.dna_hash() == dna_hash)
+            .map(|(role_id, _)| role_id)
+            .collect();
+        for config in self.dna_tuning_params.iter() {
+            if config.applies_to(dna_hash, role_ids.iter().copied()) {
+                overrides.extend(config.tuning_params.clone());
+            }
+        }
+        Ok(overrides)
+    }
+
     /// Get the holochain conductor state
     pub async fn get_state(&self) -> ConductorResult<ConductorState> {
         let state = self
@@ -169,7 +214,9 @@ impl Spaces {
                     })
                     .optional()?;
                 match state {
-                    Some(state) => ConductorResult::Ok(Some(from_blob(state)?)),
+                    Some(state) => {
+                        ConductorResult::Ok(Some(ConductorState::from_persisted(state)?.0))
+                    }
                     None => ConductorResult::Ok(None),
                 }
             })
@@ -184,6 +231,31 @@ impl Spaces {
         }
     }
 
+    /// Migrate the persisted conductor state to the current schema version, if
+    /// it was persisted by an older conductor.
+    ///
+    /// The migrated state replaces the old one in the transaction it's read
+    /// in, so a conductor which crashes part way through still has the old
+    /// state to migrate when it's next started.
+    pub async fn migrate_state(&self) -> ConductorResult<()> {
+        self.conductor_db
+            .async_commit(|txn| {
+                let state = txn
+                    .query_row("SELECT blob FROM ConductorState WHERE id = 1", [], |row| {
+                        row.get("blob")
+                    })
+                    .optional()?;
+                if let Some(state) = state {
+                    let (state, migrated) = ConductorState::from_persisted(state)?;
+                    if migrated {
+                        mutations::insert_conductor_state(txn, (&state).try_into()?)?;
+                    }
+                }
+                ConductorResult::Ok(())
+            })
+            .await
+    }
+
     /// Update the internal state with a pure function mapping old state to new
     pub async fn update_state<F: Send>(&self, f: F) -> ConductorResult<ConductorState>
     where
@@ -210,7 +282,7 @@ impl Spaces {
                     })
                     .optional()?;
                 let state = match state {
-                    Some(state) => from_blob(state)?,
+                    Some(state) => ConductorState::from_persisted(state)?.0,
                     None => ConductorState::default(),
                 };
                 let (new_state, output) = f(state)?;
@@ -221,6 +293,123 @@ impl Spaces {
         Ok(output)
     }
 
+    /// Flush the write-ahead log of every database owned by these spaces,
+    /// including the conductor and wasm databases.
+    /// Used as the last step of a graceful shutdown.
+    pub async fn checkpoint_all(&self) -> ConductorResult<()> {
+        self.conductor_db.checkpoint().await?;
+        self.wasm_db.checkpoint().await?;
+        let spaces = self.get_from_spaces(|s| s.clone());
+        for space in spaces {
+            space.authored_db.checkpoint().await?;
+            space.dht_db.checkpoint().await?;
+            space.cache_db.checkpoint().await?;
+            space.p2p_agents_db.checkpoint().await?;
+            space.p2p_metrics_db.checkpoint().await?;
+        }
+        Ok(())
+    }
+
+    /// Back up the source chains and integrated ops of a DNA space into `dir`.
+    /// This covers every cell of the DNA on this conductor.
+    /// Each database is a consistent snapshot, laid out in `dir` the same way
+    /// as in the environment directory.
+    ///
+    /// Fails if the conductor has no space for the DNA, rather than creating
+    /// empty databases to back up.
+    pub async fn backup_space(&self, dna_hash: &DnaHash, dir: &Path) -> ConductorResult<()> {
+        let space = self
+            .map
+            .share_ref(|spaces| spaces.get(dna_hash).cloned())
+            .ok_or_else(|| ConductorError::SpaceMissing(dna_hash.clone()))?;
+        backup_db(&space.authored_db, dir).await?;
+        backup_db(&space.dht_db, dir).await?;
+        Ok(())
+    }
+
+    /// Merge a backup taken with [`Spaces::backup_space`] into a DNA space.
+    pub async fn restore_space(&self, dna_hash: &DnaHash, dir: &Path) -> ConductorResult<()> {
+        let space = self.get_or_create_space(dna_hash)?;
+        restore_db(&space.authored_db, dir).await?;
+        restore_db(&space.dht_db, dir).await?;
+        Ok(())
+    }
+
+    /// Export every integrated op held for a DNA to a new shard file,
+    /// returning the number of ops exported.
+    pub async fn export_dht_ops(&self, dna_hash: &DnaHash, path: &Path) -> ConductorResult<usize> {
+        let space = self.get_or_create_space(dna_hash)?;
+        let dna_hash = dna_hash.clone();
+        let path = path.to_owned();
+        space
+            .dht_db
+            .async_reader(move |txn| dht_shard::write_shard(&txn, &dna_hash, &path))
+            .await
+    }
+
+    /// Feed the ops in a shard file into a DNA space's incoming ops workflow,
+    /// a batch at a time, returning the number of ops read. They are then
+    /// validated and integrated like any other ops.
+    pub async fn import_dht_ops(&self, dna_hash: &DnaHash, path: &Path) -> ConductorResult<usize> {
+        let space = self.get_or_create_space(dna_hash)?;
+        let trigger = self
+            .queue_consumer_map
+            .sys_validation_trigger(space.dna_hash.clone())
+            // The queue consumers are only spawned once a cell is running.
+            .ok_or(ConductorError::CellNotInitialized)?;
+        let mut reader = dht_shard::ShardReader::open(path, dna_hash)?;
+        let mut count = 0;
+        loop {
+            let batch = reader.next_batch(dht_shard::IMPORT_BATCH_SIZE)?;
+            if batch.is_empty() {
+                break;
+            }
+            count += batch.len();
+            incoming_dht_ops_workflow(&space, trigger.clone(), batch, false).await?;
+        }
+        Ok(count)
+    }
+
+    /// Check the databases of a DNA space for corruption. If `repair` is set,
+    /// corrupt rows are removed from the DHT and cache databases so they can
+    /// be fetched again. The authored database is only ever checked.
+    pub async fn check_integrity(
+        &self,
+        dna_hash: &DnaHash,
+        repair: bool,
+    ) -> ConductorResult<DbIntegrityReport> {
+        let space = self.get_or_create_space(dna_hash)?;
+        let authored = space
+            .authored_db
+            .async_reader(|txn| {
+                let mut issues = integrity::check_db(&txn)?;
+                issues.extend(integrity::check_source_chains(&txn)?);
+                StateQueryResult::Ok(issues)
+            })
+            .await?;
+        let (dht, dht_removed) = check_and_repair_db(&space.dht_db, repair).await?;
+        let (cache, cache_removed) = check_and_repair_db(&space.cache_db, repair).await?;
+        Ok(DbIntegrityReport {
+            authored,
+            dht,
+            cache,
+            rows_removed: dht_removed + cache_removed,
+        })
+    }
+
+    /// Back up the conductor state, wasm and every DNA space into `dir`.
+    /// The backup has the same layout as the environment directory, so a
+    /// new conductor can be started from it by using it as its environment path.
+    pub async fn backup_all(&self, dir: &Path) -> ConductorResult<()> {
+        backup_db(&self.conductor_db, dir).await?;
+        backup_db(&self.wasm_db, dir).await?;
+        let dna_hashes = self.get_from_spaces(|s| s.dna_hash.clone());
+        for dna_hash in dna_hashes {
+            self.backup_space(&dna_hash, dir).await?;
+        }
+        Ok(())
+    }
+
     /// Get something from every space
     pub fn get_from_spaces<R, F: FnMut(&Space) -> R>(&self, f: F) -> Vec<R> {
         self.map
@@ -247,6 +436,8 @@ impl Spaces {
                             Arc::new(dna_hash.clone()),
                             &self.db_dir,
                             self.db_sync_strategy,
+                            self.network_config.tuning_params.tls_pin_peer_certs,
+                            self.faults.clone(),
                         )?;
 
                         let r = f(&space);
@@ -582,6 +773,63 @@ impl Spaces {
         Ok(results)
     }
 
+    /// Store a remote signal which is waiting for the network to be enabled.
+    pub async fn queue_remote_signal(
+        &self,
+        dna_hash: DnaHash,
+        to_agent_list: Vec<AgentPubKey>,
+        payload: Vec<u8>,
+    ) -> ConductorResult<()> {
+        let to_agents = holochain_serialized_bytes::encode(&to_agent_list)?;
+        self.conductor_db
+            .async_commit(move |txn| {
+                txn.execute(
+                    "INSERT INTO QueuedRemoteSignal (dna_hash, to_agents, payload)
+                    VALUES (:dna_hash, :to_agents, :payload)",
+                    named_params! {
+                        ":dna_hash": dna_hash,
+                        ":to_agents": to_agents,
+                        ":payload": payload,
+                    },
+                )?;
+                ConductorResult::Ok(())
+            })
+            .await
+    }
+
+    /// Remove and return the remote signals queued for a DNA, oldest first.
+    pub async fn take_queued_remote_signals(
+        &self,
+        dna_hash: DnaHash,
+    ) -> ConductorResult<Vec<QueuedRemoteSignal>> {
+        self.conductor_db
+            .async_commit(move |txn| {
+                let queued = txn
+                    .prepare(
+                        "SELECT to_agents, payload FROM QueuedRemoteSignal
+                        WHERE dna_hash = :dna_hash ORDER BY id",
+                    )?
+                    .query_map(named_params! { ":dna_hash": dna_hash }, |row| {
+                        Ok((row.get::<_, Vec<u8>>("to_agents")?, row.get("payload")?))
+                    })?
+                    .collect::<Result<Vec<_>, _>>()?;
+                txn.execute(
+                    "DELETE FROM QueuedRemoteSignal WHERE dna_hash = :dna_hash",
+                    named_params! { ":dna_hash": dna_hash },
+                )?;
+                queued
+                    .into_iter()
+                    .map(|(to_agents, payload)| {
+                        Ok(QueuedRemoteSignal {
+                            to_agent_list: holochain_serialized_bytes::decode(&to_agents)?,
+                            payload,
+                        })
+                    })
+                    .collect()
+            })
+            .await
+    }
+
     #[instrument(skip(self, request_validation_receipt, ops))]
     /// we are receiving a "publish" event from the network
     pub async fn handle_publish(
@@ -640,11 +888,44 @@ impl Spaces {
     }
 }
 
+/// Snapshot a database into its place under a backup directory.
+async fn backup_db<Kind: DbKindT>(db: &DbRead<Kind>, dir: &Path) -> DatabaseResult<()> {
+    db.backup_to(&dir.join(db.kind().filename())).await
+}
+
+/// Merge a database's snapshot from a backup directory back into it.
+async fn restore_db<Kind: DbKindT + Send + Sync + 'static>(
+    db: &DbWrite<Kind>,
+    dir: &Path,
+) -> DatabaseResult<()> {
+    db.restore_from(&dir.join(db.kind().filename())).await
+}
+
+/// Check a database whose data can be fetched again from the network,
+/// removing any corrupt rows if `repair` is set.
+async fn check_and_repair_db<Kind: DbKindT + Send + Sync + 'static>(
+    db: &DbWrite<Kind>,
+    repair: bool,
+) -> mutations::StateMutationResult<(Vec<DbIntegrityIssue>, usize)> {
+    db.async_commit(move |txn| {
+        let issues = integrity::check_db(txn)?;
+        let removed = if repair {
+            integrity::remove_corrupt_rows(txn, &issues)?
+        } else {
+            0
+        };
+        Ok((issues, removed))
+    })
+    .await
+}
+
 impl Space {
     fn new(
         dna_hash: Arc<DnaHash>,
         root_db_dir: &DatabaseRootPath,
         db_sync_strategy: DbSyncStrategy,
+        pin_peer_certs: bool,
+        faults: FaultInjector,
     ) -> ConductorResult<Self> {
         use holochain_p2p::DnaHashExt;
         let space = dna_hash.to_kitsune();
@@ -682,12 +963,14 @@ impl Space {
         tokio::spawn(p2p_agent_store::p2p_put_all_batch(
             p2p_agents_db.clone(),
             rx,
+            pin_peer_certs,
         ));
         let p2p_batch_sender = tx;
 
         let countersigning_workspace = CountersigningWorkspace::new();
         let incoming_op_hashes = IncomingOpHashes::default();
         let incoming_ops_batch = IncomingOpsBatch::default();
+        let seen_ops = SeenOps::default();
         let dht_query_cache = DhtDbQueryCache::new(dht_db.clone().into());
         let r = Self {
             dna_hash,
@@ -700,7 +983,9 @@ impl Space {
             countersigning_workspace,
             incoming_op_hashes,
             incoming_ops_batch,
+            seen_ops,
             dht_query_cache,
+            faults,
         };
         Ok(r)
     }
@@ -758,6 +1043,8 @@ impl TestSpace {
                 Arc::new(dna_hash),
                 &temp_dir.path().to_path_buf().into(),
                 Default::default(),
+                false,
+                Default::default(),
             )
             .unwrap(),
             _temp_dir: temp_dir,
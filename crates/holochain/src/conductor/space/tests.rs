@@ -1,10 +1,13 @@
 use std::time::Duration;
 
+use ::fixt::prelude::*;
 use arbitrary::*;
 use contrafact::Fact;
+use holo_hash::fixt::*;
 use holo_hash::HasHash;
 use holochain_cascade::test_utils::fill_db;
 use holochain_conductor_api::conductor::ConductorConfig;
+use holochain_conductor_api::conductor::DnaTuningParamsConfig;
 use holochain_p2p::dht::hash::RegionHash;
 use holochain_p2p::dht::prelude::Dimension;
 use holochain_p2p::dht::region::RegionData;
@@ -16,6 +19,7 @@ use holochain_zome_types::{DnaDef, DnaDefHashed, NOISE};
 use rand::Rng;
 
 use super::Spaces;
+use crate::conductor::error::ConductorError;
 
 /// Test that `fetch_op_regions` returns regions which correctly describe
 /// the set of ops in the database, and that `fetch_ops_by_region` returns the
@@ -138,3 +142,101 @@ async fn test_region_queries() {
     assert_eq!(fetched_ops.len(), NUM_OPS);
     assert_eq!(inserted_ops, fetched_ops);
 }
+
+/// DNAs get the tuning params configured for their hash
+/// or for a role they're installed under, with later entries winning.
+#[tokio::test(flavor = "multi_thread")]
+async fn test_dna_tuning_param_overrides() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let dna_by_hash = fixt!(DnaHash);
+    let dna_by_role = fixt!(DnaHash);
+    let other_dna = fixt!(DnaHash);
+    let params = |delay: &str| {
+        [(
+            "gossip_loop_iteration_delay_ms".to_string(),
+            delay.to_string(),
+        )]
+        .into_iter()
+        .collect::<std::collections::HashMap<_, _>>()
+    };
+    let spaces = Spaces::new(&ConductorConfig {
+        environment_path: temp_dir.path().to_path_buf().into(),
+        dna_tuning_params: vec![
+            DnaTuningParamsConfig {
+                dna_hash: None,
+                role_id: Some("archive".to_string()),
+                tuning_params: params("60000"),
+            },
+            DnaTuningParamsConfig {
+                dna_hash: Some(dna_by_hash.clone()),
+                role_id: None,
+                tuning_params: params("100"),
+            },
+            DnaTuningParamsConfig {
+                dna_hash: Some(dna_by_role.clone()),
+                role_id: None,
+                tuning_params: params("30000"),
+            },
+        ],
+        ..Default::default()
+    })
+    .unwrap();
+
+    let agent = fixt!(AgentPubKey);
+    let app = InstalledAppCommon::new(
+        "app",
+        agent.clone(),
+        vec![(
+            "archive".to_string(),
+            AppRoleAssignment::new(CellId::new(dna_by_role.clone(), agent), true, 0),
+        )],
+    );
+    spaces
+        .update_state(move |mut state| {
+            state.add_app(app)?;
+            Ok(state)
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(
+        spaces
+            .dna_tuning_param_overrides(&dna_by_hash)
+            .await
+            .unwrap(),
+        params("100")
+    );
+    assert_eq!(
+        spaces
+            .dna_tuning_param_overrides(&dna_by_role)
+            .await
+            .unwrap(),
+        params("30000")
+    );
+    assert!(spaces
+        .dna_tuning_param_overrides(&other_dna)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+/// Backing up a DNA the conductor holds no data for fails, without creating
+/// a space for it.
+#[tokio::test(flavor = "multi_thread")]
+async fn test_backup_missing_space() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let spaces = Spaces::new(&ConductorConfig {
+        environment_path: temp_dir.path().to_path_buf().into(),
+        ..Default::default()
+    })
+    .unwrap();
+    let dna_hash = fixt!(DnaHash);
+    let backup_dir = temp_dir.path().join("backup");
+
+    let err = spaces
+        .backup_space(&dna_hash, &backup_dir)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ConductorError::SpaceMissing(hash) if hash == dna_hash));
+    assert!(spaces.get_from_spaces(|_| ()).is_empty());
+}
@@ -1,8 +1,17 @@
 //! Structs which allow the Conductor's state to be persisted across
 //! startups and shutdowns
+//!
+//! The persisted state carries the version of its schema. State persisted by
+//! an older conductor is migrated to the current version with
+//! [`ConductorState::from_persisted`] before it's decoded, so a change to how
+//! the state is persisted never silently drops what an older conductor wrote,
+//! e.g. its installed apps. Any such change bumps
+//! [`CONDUCTOR_STATE_SCHEMA_VERSION`] with a migration from the version
+//! before.
 
 use holochain_conductor_api::signal_subscription::SignalSubscription;
 use holochain_conductor_api::{config::InterfaceDriver, InstalledAppInfo};
+use holochain_state::prelude::from_blob;
 use holochain_types::prelude::*;
 use serde::Deserialize;
 use serde::Serialize;
@@ -23,14 +32,38 @@ impl Default for ConductorStateTag {
     }
 }
 
+/// The version of the schema of the persisted [`ConductorState`].
+pub const CONDUCTOR_STATE_SCHEMA_VERSION: u32 = STATE_MIGRATIONS.len() as u32;
+
+/// The key of the schema version in the persisted state, which state
+/// persisted before versioning doesn't have.
+const SCHEMA_VERSION_KEY: &str = "schema_version";
+
+/// The fields of the persisted state, as they were encoded.
+type PersistedFields = Vec<(rmpv::Value, rmpv::Value)>;
+
+/// A migration of the persisted state from the version before it.
+type StateMigration = fn(&mut PersistedFields) -> ConductorResult<()>;
+
+/// The migrations of the persisted state, where the migration at index `i`
+/// migrates it from version `i` to version `i + 1`.
+const STATE_MIGRATIONS: &[StateMigration] = &[add_schema_version];
+
+/// Version 1 only adds the schema version itself.
+fn add_schema_version(_: &mut PersistedFields) -> ConductorResult<()> {
+    Ok(())
+}
+
 /// Mutable conductor state, stored in a DB and writable only via Admin interface.
 ///
 /// References between structs (cell configs pointing to
 /// the agent and DNA to be instantiated) are implemented
 /// via string IDs.
-#[derive(Clone, Deserialize, Serialize, Default, Debug, SerializedBytes)]
+#[derive(Clone, Deserialize, Serialize, Debug, SerializedBytes)]
 #[cfg_attr(test, derive(PartialEq))]
 pub struct ConductorState {
+    /// The version of the schema this state was persisted with.
+    schema_version: u32,
     /// Unique conductor tag / identifier.
     #[serde(default)]
     tag: ConductorStateTag,
@@ -74,7 +107,67 @@ impl AppInterfaceId {
     }
 }
 
+impl Default for ConductorState {
+    fn default() -> Self {
+        Self {
+            schema_version: CONDUCTOR_STATE_SCHEMA_VERSION,
+            tag: Default::default(),
+            installed_apps: Default::default(),
+            app_interfaces: Default::default(),
+        }
+    }
+}
+
 impl ConductorState {
+    /// Decode the state as it was persisted, first migrating it to the
+    /// current schema version if it was persisted by an older conductor.
+    /// Also returns whether it was migrated, so the migrated state can be
+    /// persisted in its place.
+    ///
+    /// State persisted by a newer conductor is an error, rather than being
+    /// decoded without whatever this conductor doesn't know about.
+    pub fn from_persisted(blob: Vec<u8>) -> ConductorResult<(Self, bool)> {
+        let mut state: rmpv::Value = from_blob(blob)?;
+        let fields = match &mut state {
+            rmpv::Value::Map(fields) => fields,
+            _ => {
+                return Err(ConductorError::other(
+                    "the persisted conductor state is not a map",
+                ))
+            }
+        };
+        let version = persisted_schema_version(fields)?;
+        if version > CONDUCTOR_STATE_SCHEMA_VERSION {
+            return Err(ConductorError::StateSchemaTooNew {
+                found: version,
+                supported: CONDUCTOR_STATE_SCHEMA_VERSION,
+            });
+        }
+        let migrated = version < CONDUCTOR_STATE_SCHEMA_VERSION;
+        if migrated {
+            for migration in &STATE_MIGRATIONS[version as usize..] {
+                migration(fields)?;
+            }
+            fields.retain(|(key, _)| key.as_str() != Some(SCHEMA_VERSION_KEY));
+            fields.push((
+                SCHEMA_VERSION_KEY.into(),
+                CONDUCTOR_STATE_SCHEMA_VERSION.into(),
+            ));
+            tracing::info!(
+                from = version,
+                to = CONDUCTOR_STATE_SCHEMA_VERSION,
+                "Migrated the persisted conductor state"
+            );
+        }
+        let state = holochain_serialized_bytes::encode(&state)?;
+        Ok((holochain_serialized_bytes::decode(&state)?, migrated))
+    }
+
+    /// The version of the schema this state was persisted with.
+    pub fn schema_version(&self) -> u32 {
+        self.schema_version
+    }
+
     /// A unique identifier for this conductor
     pub fn tag(&self) -> &ConductorStateTag {
         &self.tag
@@ -200,9 +293,7 @@ impl ConductorState {
 /// GUIs, browser based web UIs, local native UIs, other local applications and scripts.
 /// We currently have:
 /// * websockets
-///
-/// We will also soon develop
-/// * Unix domain sockets
+/// * websockets over local sockets, i.e. Unix domain sockets or Windows named pipes
 ///
 /// The cells (referenced by ID) that are to be made available via that interface should be listed.
 #[derive(Clone, Deserialize, Serialize, Debug)]
@@ -220,12 +311,77 @@ impl AppInterfaceConfig {
     pub fn websocket(port: u16) -> Self {
         Self {
             signal_subscriptions: HashMap::new(),
-            driver: InterfaceDriver::Websocket { port },
+            driver: InterfaceDriver::websocket(port),
         }
     }
 }
 
+/// The schema version of the persisted state, which is 0 for state
+/// persisted before it was versioned.
+fn persisted_schema_version(fields: &PersistedFields) -> ConductorResult<u32> {
+    match fields
+        .iter()
+        .find(|(key, _)| key.as_str() == Some(SCHEMA_VERSION_KEY))
+    {
+        Some((_, version)) => version
+            .as_u64()
+            .and_then(|version| u32::try_from(version).ok())
+            .ok_or_else(|| {
+                ConductorError::other(format!(
+                    "the persisted conductor state has an invalid schema version: {}",
+                    version
+                ))
+            }),
+        None => Ok(0),
+    }
+}
+
 // TODO: Tons of consistency check tests were ripped out in the great legacy code cleanup
 // We should add these back in when we've landed the new Dna format
 // See https://github.com/holochain/holochain/blob/7750a0291e549be006529e4153b3b6cf0d686462/crates/holochain/src/conductor/state/tests.rs#L1
 // for all old tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn persist(state: &ConductorState) -> Vec<u8> {
+        holochain_serialized_bytes::encode(state).unwrap()
+    }
+
+    #[test]
+    fn unversioned_state_is_migrated() {
+        let mut state = ConductorState::default();
+        state.app_interfaces.insert(
+            AppInterfaceId::new(1234),
+            AppInterfaceConfig::websocket(1234),
+        );
+        let mut unversioned: rmpv::Value =
+            holochain_serialized_bytes::decode(&persist(&state)).unwrap();
+        if let rmpv::Value::Map(fields) = &mut unversioned {
+            fields.retain(|(key, _)| key.as_str() != Some(SCHEMA_VERSION_KEY));
+        }
+        let unversioned = holochain_serialized_bytes::encode(&unversioned).unwrap();
+
+        let (migrated, was_migrated) = ConductorState::from_persisted(unversioned).unwrap();
+        assert!(was_migrated);
+        assert_eq!(migrated, state);
+        assert_eq!(migrated.schema_version(), CONDUCTOR_STATE_SCHEMA_VERSION);
+
+        let (current, was_migrated) = ConductorState::from_persisted(persist(&state)).unwrap();
+        assert!(!was_migrated);
+        assert_eq!(current, state);
+    }
+
+    #[test]
+    fn state_from_a_newer_conductor_is_an_error() {
+        let state = ConductorState {
+            schema_version: CONDUCTOR_STATE_SCHEMA_VERSION + 1,
+            ..Default::default()
+        };
+        assert!(matches!(
+            ConductorState::from_persisted(persist(&state)),
+            Err(ConductorError::StateSchemaTooNew { .. })
+        ));
+    }
+}
@@ -0,0 +1,130 @@
+//! Enforcement of the [`ZomeFnAccessRules`] set in app manifests.
+//!
+//! The rules are kept in memory by cell, along with the cells of the app
+//! which set them, so calls to cells without rules cost no more than a
+//! map lookup.
+
+use holochain_conductor_api::ZomeCall;
+use holochain_types::prelude::*;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use super::state::ConductorState;
+
+/// The rules an app sets for one of its cells.
+struct CellRules {
+    app_cells: Arc<HashSet<CellId>>,
+    rules: ZomeFnAccessRules,
+}
+
+/// The zome function access rules of the cells of apps which set any.
+#[derive(Default)]
+pub(crate) struct ZomeFnAccessTracker(parking_lot::RwLock<Option<HashMap<CellId, Vec<CellRules>>>>);
+
+impl ZomeFnAccessTracker {
+    /// Have the rules been read from the conductor state yet.
+    pub(crate) fn is_loaded(&self) -> bool {
+        self.0.read().is_some()
+    }
+
+    /// Take the rules and cells of the apps from the conductor state.
+    pub(crate) fn refresh(&self, state: &ConductorState) {
+        let mut cells: HashMap<CellId, Vec<CellRules>> = HashMap::new();
+        for app in state.installed_apps().values() {
+            let roles: Vec<_> = app
+                .roles()
+                .values()
+                .filter(|role| !role.zome_fn_access().is_empty())
+                .collect();
+            if roles.is_empty() {
+                continue;
+            }
+            let app_cells: Arc<HashSet<_>> = Arc::new(
+                app.roles()
+                    .values()
+                    .flat_map(|role| role.cells())
+                    .cloned()
+                    .collect(),
+            );
+            for role in roles {
+                for cell_id in role.cells() {
+                    cells.entry(cell_id.clone()).or_default().push(CellRules {
+                        app_cells: app_cells.clone(),
+                        rules: role.zome_fn_access().clone(),
+                    });
+                }
+            }
+        }
+        *self.0.write() = Some(cells);
+    }
+
+    /// Whether a call from this origin is allowed by every app the called
+    /// cell belongs to.
+    pub(crate) fn allows(&self, call: &ZomeCall, origin: &ZomeCallOrigin) -> bool {
+        let cells = self.0.read();
+        match cells.as_ref().and_then(|cells| cells.get(&call.cell_id)) {
+            Some(apps) => apps.iter().all(|app| {
+                app.rules
+                    .access(&call.zome_name, &call.fn_name)
+                    .allows(origin, &app.app_cells)
+            }),
+            None => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::fixt::prelude::*;
+    use holo_hash::fixt::AgentPubKeyFixturator;
+    use holo_hash::fixt::DnaHashFixturator;
+
+    #[test]
+    fn calls_are_allowed_by_the_rules_of_the_cells_app() {
+        let agent = fixt!(AgentPubKey);
+        let admin_cell = CellId::new(fixt!(DnaHash), agent.clone());
+        let other_cell = CellId::new(fixt!(DnaHash), agent.clone());
+        let mut rules = ZomeFnAccessRules::default();
+        rules.set(&"admin".into(), &"reset".into(), ZomeFnAccess::Internal);
+        let app = InstalledAppCommon::new(
+            "app",
+            agent.clone(),
+            [
+                (
+                    "admin".into(),
+                    AppRoleAssignment::new(admin_cell.clone(), true, 0).with_zome_fn_access(rules),
+                ),
+                (
+                    "other".into(),
+                    AppRoleAssignment::new(other_cell.clone(), true, 0),
+                ),
+            ],
+        );
+        let mut state = ConductorState::default();
+        state.add_app(app).unwrap();
+        let tracker = ZomeFnAccessTracker::default();
+        tracker.refresh(&state);
+        assert!(tracker.is_loaded());
+
+        let call = |cell_id: &CellId, fn_name: &str| ZomeCall {
+            cell_id: cell_id.clone(),
+            zome_name: "admin".into(),
+            fn_name: fn_name.into(),
+            cap_secret: None,
+            payload: ExternIO::encode(()).unwrap(),
+            provenance: agent.clone(),
+        };
+        let reset = call(&admin_cell, "reset");
+        assert!(!tracker.allows(&reset, &ZomeCallOrigin::AppInterface));
+        assert!(!tracker.allows(&reset, &ZomeCallOrigin::Remote));
+        assert!(tracker.allows(&reset, &ZomeCallOrigin::Cell(other_cell.clone())));
+        let stranger = CellId::new(fixt!(DnaHash), fixt!(AgentPubKey));
+        assert!(!tracker.allows(&reset, &ZomeCallOrigin::Cell(stranger)));
+
+        // Other functions, and the cells of roles without rules, are public.
+        assert!(tracker.allows(&call(&admin_cell, "get"), &ZomeCallOrigin::AppInterface));
+        assert!(tracker.allows(&call(&other_cell, "reset"), &ZomeCallOrigin::AppInterface));
+    }
+}
@@ -50,6 +50,7 @@ use tokio::task::JoinHandle;
 use validation_receipt_consumer::*;
 mod validation_receipt_consumer;
 use crate::conductor::conductor::RwShare;
+use crate::conductor::manager::TaskProgress;
 use crate::conductor::space::Space;
 use crate::conductor::{error::ConductorError, manager::ManagedTaskResult};
 use crate::conductor::{manager::ManagedTaskAdd, ConductorHandle};
@@ -60,6 +61,13 @@ use publish_dht_ops_consumer::*;
 mod countersigning_consumer;
 use countersigning_consumer::*;
 
+mod prune_dht_consumer;
+use prune_dht_consumer::*;
+
+mod scheduler;
+pub use scheduler::WorkflowPermit;
+pub use scheduler::WorkflowScheduler;
+
 #[cfg(test)]
 mod tests;
 
@@ -102,11 +110,10 @@ pub async fn spawn_queue_consumer_tasks(
         Box::new(network.clone()),
     );
     task_sender
-        .send(ManagedTaskAdd::cell_critical(
-            handle,
-            cell_id.clone(),
-            "publish_dht_ops_consumer",
-        ))
+        .send(
+            ManagedTaskAdd::cell_critical(handle, cell_id.clone(), "publish_dht_ops_consumer")
+                .with_progress(tx_publish.progress()),
+        )
         .await
         .expect("Failed to manage workflow handle");
 
@@ -120,16 +127,20 @@ pub async fn spawn_queue_consumer_tasks(
                 conductor_handle.clone(),
                 stop.subscribe(),
                 network.clone(),
+                space.faults.clone(),
             )
         });
 
     if let Some(handle) = handle {
         task_sender
-            .send(ManagedTaskAdd::cell_critical(
-                handle,
-                cell_id.clone(),
-                "validation_receipt_consumer",
-            ))
+            .send(
+                ManagedTaskAdd::cell_critical(
+                    handle,
+                    cell_id.clone(),
+                    "validation_receipt_consumer",
+                )
+                .with_progress(tx_receipt.progress()),
+            )
             .await
             .expect("Failed to manage workflow handle");
     }
@@ -145,16 +156,20 @@ pub async fn spawn_queue_consumer_tasks(
                 stop.subscribe(),
                 tx_receipt.clone(),
                 network.clone(),
+                space.faults.clone(),
             )
         });
 
     if let Some(handle) = handle {
         task_sender
-            .send(ManagedTaskAdd::cell_critical(
-                handle,
-                cell_id.clone(),
-                "integrate_dht_ops_consumer",
-            ))
+            .send(
+                ManagedTaskAdd::cell_critical(
+                    handle,
+                    cell_id.clone(),
+                    "integrate_dht_ops_consumer",
+                )
+                .with_progress(tx_integration.progress()),
+            )
             .await
             .expect("Failed to manage workflow handle");
     }
@@ -185,11 +200,10 @@ pub async fn spawn_queue_consumer_tasks(
     });
     if let Some(handle) = handle {
         task_sender
-            .send(ManagedTaskAdd::cell_critical(
-                handle,
-                cell_id.clone(),
-                "app_validation_consumer",
-            ))
+            .send(
+                ManagedTaskAdd::cell_critical(handle, cell_id.clone(), "app_validation_consumer")
+                    .with_progress(tx_app.progress()),
+            )
             .await
             .expect("Failed to manage workflow handle");
     }
@@ -219,11 +233,10 @@ pub async fn spawn_queue_consumer_tasks(
 
     if let Some(handle) = handle {
         task_sender
-            .send(ManagedTaskAdd::cell_critical(
-                handle,
-                cell_id.clone(),
-                "sys_validation_consumer",
-            ))
+            .send(
+                ManagedTaskAdd::cell_critical(handle, cell_id.clone(), "sys_validation_consumer")
+                    .with_progress(tx_sys.progress()),
+            )
             .await
             .expect("Failed to manage workflow handle");
     }
@@ -238,11 +251,32 @@ pub async fn spawn_queue_consumer_tasks(
     });
     if let Some(handle) = handle {
         task_sender
-            .send(ManagedTaskAdd::cell_critical(
-                handle,
-                cell_id.clone(),
-                "countersigning_consumer",
-            ))
+            .send(
+                ManagedTaskAdd::cell_critical(handle, cell_id.clone(), "countersigning_consumer")
+                    .with_progress(tx_cs.progress()),
+            )
+            .await
+            .expect("Failed to manage workflow handle");
+    }
+
+    // Pruning
+    // One per space.
+    let (tx_prune, handle) = queue_consumer_map.spawn_once_pruning(dna_hash.clone(), || {
+        spawn_prune_dht_consumer(
+            dna_hash.clone(),
+            dht_db.clone(),
+            cache.clone(),
+            authored_db.clone(),
+            conductor_handle.clone(),
+            stop.subscribe(),
+        )
+    });
+    if let Some(handle) = handle {
+        task_sender
+            .send(
+                ManagedTaskAdd::cell_critical(handle, cell_id.clone(), "prune_dht_consumer")
+                    .with_progress(tx_prune.progress()),
+            )
             .await
             .expect("Failed to manage workflow handle");
     }
@@ -333,6 +367,17 @@ impl QueueConsumerMap {
         self.spawn_once(QueueEntry(dna_hash, QueueType::Countersigning), spawn)
     }
 
+    fn spawn_once_pruning<S>(
+        &self,
+        dna_hash: Arc<DnaHash>,
+        spawn: S,
+    ) -> (TriggerSender, Option<JoinHandle<ManagedTaskResult>>)
+    where
+        S: FnOnce() -> (TriggerSender, JoinHandle<ManagedTaskResult>),
+    {
+        self.spawn_once(QueueEntry(dna_hash, QueueType::Pruning), spawn)
+    }
+
     /// Get the validation receipt trigger for this dna hash.
     pub fn validation_receipt_trigger(&self, dna_hash: Arc<DnaHash>) -> Option<TriggerSender> {
         self.get_trigger(&QueueEntry(dna_hash, QueueType::Receipt))
@@ -370,11 +415,14 @@ impl QueueConsumerMap {
     where
         S: FnOnce() -> (TriggerSender, JoinHandle<ManagedTaskResult>),
     {
-        self.map.share_mut(|map| match map.entry(key) {
-            std::collections::hash_map::Entry::Occupied(o) => (o.get().clone(), None),
-            std::collections::hash_map::Entry::Vacant(v) => {
+        self.map.share_mut(|map| match map.get(&key) {
+            // A consumer which has ended, e.g. after it stalled, is spawned
+            // again when a cell of its DNA is next created.
+            Some(ts) if !ts.is_closed() => (ts.clone(), None),
+            _ => {
                 let (ts, handle) = spawn();
-                (v.insert(ts).clone(), Some(handle))
+                map.insert(key, ts.clone());
+                (ts, Some(handle))
             }
         })
     }
@@ -390,6 +438,7 @@ enum QueueType {
     AppValidation,
     SysValidation,
     Countersigning,
+    Pruning,
 }
 
 /// The entry points for kicking off a chain reaction of queue activity
@@ -453,6 +502,10 @@ pub struct TriggerSender {
     reset_back_off: Option<Arc<AtomicBool>>,
     /// Pause / resume the back off loop if there is one.
     pause_back_off: Option<Arc<AtomicBool>>,
+    /// The spans which triggered the consumer since it last ran.
+    causes: TriggerCauses,
+    /// The progress of the consumer's workflow runs.
+    progress: TaskProgress,
 }
 
 /// The receiving end of a queue trigger channel
@@ -464,8 +517,20 @@ pub struct TriggerReceiver {
     reset_on_trigger: bool,
     /// The optional back off loop.
     back_off: Option<BackOff>,
+    /// The spans which triggered the consumer since it last ran.
+    causes: TriggerCauses,
+    /// The progress of the consumer's workflow runs.
+    progress: TaskProgress,
 }
 
+/// The spans which triggered a consumer, so the spans of the workflow's
+/// next run can be linked to them and followed from e.g. a zome call
+/// to the publishing of its ops.
+type TriggerCauses = Arc<parking_lot::Mutex<Vec<tracing::Span>>>;
+
+/// The most causes recorded between runs of a workflow.
+const MAX_TRIGGER_CAUSES: usize = 16;
+
 /// A loop that can optionally back off, pause and resume.
 struct BackOff {
     /// The starting duration for the back off.
@@ -483,16 +548,22 @@ impl TriggerSender {
     /// Create a new channel for waking a consumer
     pub fn new() -> (TriggerSender, TriggerReceiver) {
         let (tx, rx) = broadcast::channel(1);
+        let causes = TriggerCauses::default();
+        let progress = TaskProgress::default();
         (
             TriggerSender {
                 trigger: tx,
                 reset_back_off: None,
                 pause_back_off: None,
+                causes: causes.clone(),
+                progress: progress.clone(),
             },
             TriggerReceiver {
                 rx,
                 back_off: None,
                 reset_on_trigger: false,
+                causes,
+                progress,
             },
         )
     }
@@ -512,23 +583,47 @@ impl TriggerSender {
         let (tx, rx) = broadcast::channel(1);
         let reset_back_off = Arc::new(AtomicBool::new(false));
         let pause_back_off = Arc::new(AtomicBool::new(false));
+        let causes = TriggerCauses::default();
+        let progress = TaskProgress::default();
         (
             TriggerSender {
                 trigger: tx,
                 reset_back_off: Some(reset_back_off.clone()),
                 pause_back_off: Some(pause_back_off.clone()),
+                causes: causes.clone(),
+                progress: progress.clone(),
             },
             TriggerReceiver {
                 rx,
                 reset_on_trigger,
                 back_off: Some(BackOff::new(range, reset_back_off, pause_back_off)),
+                causes,
+                progress,
             },
         )
     }
 
+    /// The progress of the consumer's workflow runs, which are from when
+    /// it's woken by a trigger until it waits for the next one.
+    pub fn progress(&self) -> TaskProgress {
+        self.progress.clone()
+    }
+
+    /// Whether the consumer task has ended, so triggering it does nothing.
+    pub fn is_closed(&self) -> bool {
+        self.trigger.receiver_count() == 0
+    }
+
     /// Lazily nudge the consumer task, ignoring the case where the consumer
     /// already has a pending trigger signal
     pub fn trigger(&self, context: &'static &'static str) {
+        let cause = tracing::Span::current();
+        if !cause.is_none() {
+            let mut causes = self.causes.lock();
+            if causes.len() < MAX_TRIGGER_CAUSES {
+                causes.push(cause);
+            }
+        }
         if self.trigger.send(context).is_err() {
             tracing::warn!(
                 "Queue consumer trigger was sent while Cell is shutting down: ignoring."
@@ -592,6 +687,7 @@ impl TriggerReceiver {
             back_off,
             rx,
             reset_on_trigger,
+            ..
         } = self;
 
         let mut was_trigger = true;
@@ -652,6 +748,16 @@ impl TriggerReceiver {
         }
         Ok(())
     }
+
+    /// A span for the next run of the workflow, which follows from
+    /// the spans which triggered it.
+    pub fn run_span(&self, workflow: &'static str) -> tracing::Span {
+        let span = tracing::info_span!("workflow_run", workflow);
+        for cause in self.causes.lock().drain(..) {
+            span.follows_from(&cause);
+        }
+        span
+    }
 }
 
 /// Create a future that will be ok with either a recv or a lagged.
@@ -721,6 +827,95 @@ pub enum WorkComplete {
     Incomplete,
 }
 
+/// Bounds how much of its queue a workflow loads into memory in one pass.
+///
+/// Once either limit is reached the workflow leaves the rest of the queue
+/// for later and returns [`WorkComplete::Incomplete`], so its consumer
+/// re-triggers it and a large backlog is worked through in bounded chunks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WorkBudget {
+    /// The maximum number of items to take on in one pass.
+    pub max_items: usize,
+    /// The maximum total size in bytes of the items taken on in one pass.
+    pub max_bytes: usize,
+}
+
+impl WorkBudget {
+    /// The default maximum number of items per pass.
+    pub const DEFAULT_MAX_ITEMS: usize = 10_000;
+    /// The default maximum number of bytes per pass.
+    pub const DEFAULT_MAX_BYTES: usize = 64 * 1024 * 1024;
+
+    /// A budget with the given limits.
+    pub fn new(max_items: usize, max_bytes: usize) -> Self {
+        Self {
+            max_items,
+            max_bytes,
+        }
+    }
+
+    /// Start spending this budget for a single pass.
+    pub fn start(&self) -> WorkBudgetSpend {
+        WorkBudgetSpend {
+            budget: *self,
+            items: 0,
+            bytes: 0,
+            exhausted: false,
+        }
+    }
+}
+
+impl Default for WorkBudget {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_MAX_ITEMS, Self::DEFAULT_MAX_BYTES)
+    }
+}
+
+/// Tracks what has been spent from a [`WorkBudget`] during one pass.
+#[derive(Clone, Debug)]
+pub struct WorkBudgetSpend {
+    budget: WorkBudget,
+    items: usize,
+    bytes: usize,
+    exhausted: bool,
+}
+
+impl WorkBudgetSpend {
+    /// Try to take on one more item of the given size.
+    ///
+    /// Returns false if this would go over budget, in which case the item
+    /// should be left for the next pass. The first item is always accepted,
+    /// so a single oversized item can't stall the queue forever.
+    pub fn try_spend(&mut self, bytes: usize) -> bool {
+        if self.items > 0
+            && (self.items >= self.budget.max_items
+                || self.bytes.saturating_add(bytes) > self.budget.max_bytes)
+        {
+            self.exhausted = true;
+            return false;
+        }
+        self.items += 1;
+        self.bytes = self.bytes.saturating_add(bytes);
+        true
+    }
+
+    /// True if an item has been turned away, meaning there is more work
+    /// left on the queue than fit in this pass.
+    pub fn is_exhausted(&self) -> bool {
+        self.exhausted
+    }
+
+    /// The SQL `LIMIT` to use when loading items for this budget.
+    /// This is one more than the budget allows, so that a left over row
+    /// marks the budget as exhausted.
+    pub fn query_limit(&self) -> i64 {
+        self.budget
+            .max_items
+            .saturating_add(1)
+            .min(i64::MAX as usize) as i64
+    }
+}
+
 /// The only error possible when attempting to trigger: the channel is closed
 #[derive(Debug, Display, thiserror::Error)]
 pub struct QueueTriggerClosedError;
@@ -736,32 +931,82 @@ async fn next_job_or_exit(
     rx: &mut TriggerReceiver,
     stop: &mut sync::broadcast::Receiver<()>,
 ) -> Job {
+    rx.progress.finish_work();
     if stop.try_recv().is_ok() {
         return Job::Shutdown;
     }
     // Check for shutdown or next job
-    let next_job = rx.listen();
-    let kill = stop.recv();
-    tokio::pin!(next_job);
-    tokio::pin!(kill);
-
-    if let Either::Left((Err(_), _)) | Either::Right((_, _)) =
-        futures::future::select(next_job, kill).await
-    {
+    let shutdown = {
+        let next_job = rx.listen();
+        let kill = stop.recv();
+        tokio::pin!(next_job);
+        tokio::pin!(kill);
+
+        matches!(
+            futures::future::select(next_job, kill).await,
+            Either::Left((Err(_), _)) | Either::Right((_, _))
+        )
+    };
+    if shutdown {
         Job::Shutdown
     } else {
+        rx.progress.start_work();
         Job::Run
     }
 }
 
-/// Does nothing.
-/// Does extra nothing and logs about it if the error shouldn't bail the
-/// workflow.
-fn handle_workflow_error(err: WorkflowError) -> ManagedTaskResult {
-    if err.workflow_should_bail() {
-        Err(Box::new(ConductorError::from(err)).into())
-    } else {
-        tracing::error!(?err);
-        Ok(())
+/// The number of times in a row a queue consumer's workflow may fail
+/// before the consumer gives up and its cell is paused.
+pub const MAX_CONSECUTIVE_WORKFLOW_FAILURES: u32 = 10;
+
+/// Tracks the consecutive failures of a single queue consumer's workflow.
+///
+/// Errors which don't bail the workflow are just logged, but if they keep
+/// happening (e.g. a corrupted store) the workflow can never make progress.
+/// Once the workflow has failed too many times in a row the consumer exits
+/// with [`ConductorError::RepeatedWorkflowFailure`], which causes the apps
+/// using its cell to be paused until they are started again by an operator.
+struct WorkflowFailures {
+    workflow: &'static str,
+    limit: u32,
+    consecutive: u32,
+}
+
+impl WorkflowFailures {
+    fn new(workflow: &'static str) -> Self {
+        Self::with_limit(workflow, MAX_CONSECUTIVE_WORKFLOW_FAILURES)
+    }
+
+    fn with_limit(workflow: &'static str, limit: u32) -> Self {
+        Self {
+            workflow,
+            limit: limit.max(1),
+            consecutive: 0,
+        }
+    }
+
+    /// The workflow ran without error.
+    fn success(&mut self) {
+        self.consecutive = 0;
+    }
+
+    /// Bails if the error should bail the workflow or if the workflow has
+    /// now failed too many times in a row, otherwise logs the error.
+    fn handle_error(&mut self, err: WorkflowError) -> ManagedTaskResult {
+        if err.workflow_should_bail() {
+            return Err(Box::new(ConductorError::from(err)).into());
+        }
+        self.consecutive += 1;
+        tracing::error!(?err, workflow = self.workflow, failures = self.consecutive);
+        if self.consecutive >= self.limit {
+            Err(Box::new(ConductorError::RepeatedWorkflowFailure {
+                workflow: self.workflow.to_string(),
+                failures: self.consecutive,
+                last_error: err.to_string(),
+            })
+            .into())
+        } else {
+            Ok(())
+        }
     }
 }
@@ -31,6 +31,7 @@ pub fn spawn_app_validation_consumer(
     let trigger_self = tx.clone();
     let workspace = Arc::new(workspace);
     let handle = tokio::spawn(async move {
+        let mut failures = WorkflowFailures::new("app_validation_workflow");
         loop {
             // Wait for next job
             if let Job::Shutdown = next_job_or_exit(&mut rx, &mut stop).await {
@@ -48,15 +49,18 @@ pub fn spawn_app_validation_consumer(
                 conductor_handle.clone(),
                 network.clone(),
                 dht_query_cache.clone(),
+                WorkBudget::default(),
             )
+            .instrument(rx.run_span("app_validation_workflow"))
             .await;
             match result {
                 Ok(WorkComplete::Incomplete) => {
+                    failures.success();
                     tracing::debug!("Work incomplete, retriggering workflow");
                     trigger_self.trigger(&"retrigger")
                 }
-                Err(err) => handle_workflow_error(err)?,
-                _ => (),
+                Ok(WorkComplete::Complete) => failures.success(),
+                Err(err) => failures.handle_error(err)?,
             };
         }
         Ok(())
@@ -17,6 +17,7 @@ pub(crate) fn spawn_countersigning_consumer(
     let (tx, mut rx) = TriggerSender::new();
     let trigger_self = tx.clone();
     let handle = tokio::spawn(async move {
+        let mut failures = WorkflowFailures::new("countersigning_workflow");
         loop {
             // Wait for next job
             if let Job::Shutdown = next_job_or_exit(&mut rx, &mut stop).await {
@@ -27,13 +28,17 @@ pub(crate) fn spawn_countersigning_consumer(
             }
 
             // Run the workflow
-            match countersigning_workflow(&space, &dna_network, &trigger_sys).await {
+            match countersigning_workflow(&space, &dna_network, &trigger_sys)
+                .instrument(rx.run_span("countersigning_workflow"))
+                .await
+            {
                 Ok(WorkComplete::Incomplete) => {
+                    failures.success();
                     tracing::debug!("Work incomplete, retriggering workflow");
                     trigger_self.trigger(&"retrigger")
                 }
-                Err(err) => handle_workflow_error(err)?,
-                _ => (),
+                Ok(WorkComplete::Complete) => failures.success(),
+                Err(err) => failures.handle_error(err)?,
             };
         }
         Ok(())
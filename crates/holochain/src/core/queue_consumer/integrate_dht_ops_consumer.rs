@@ -2,13 +2,14 @@
 
 use super::*;
 use crate::conductor::manager::ManagedTaskResult;
+use crate::core::workflow::fault_injection::FaultInjector;
 use crate::core::workflow::integrate_dht_ops_workflow::integrate_dht_ops_workflow;
 use holochain_types::db_cache::DhtDbQueryCache;
 use tokio::task::JoinHandle;
 use tracing::*;
 
 /// Spawn the QueueConsumer for DhtOpIntegration workflow
-#[instrument(skip(env, stop, trigger_receipt, network, dht_query_cache))]
+#[instrument(skip(env, stop, trigger_receipt, network, dht_query_cache, faults))]
 pub fn spawn_integrate_dht_ops_consumer(
     dna_hash: Arc<DnaHash>,
     env: DbWrite<DbKindDht>,
@@ -16,10 +17,12 @@ pub fn spawn_integrate_dht_ops_consumer(
     mut stop: sync::broadcast::Receiver<()>,
     trigger_receipt: TriggerSender,
     network: HolochainP2pDna,
+    faults: FaultInjector,
 ) -> (TriggerSender, JoinHandle<ManagedTaskResult>) {
     let (tx, mut rx) = TriggerSender::new();
     let trigger_self = tx.clone();
     let handle = tokio::spawn(async move {
+        let mut failures = WorkflowFailures::new("integrate_dht_ops_workflow");
         loop {
             // Wait for next job
             if let Job::Shutdown = next_job_or_exit(&mut rx, &mut stop).await {
@@ -35,15 +38,18 @@ pub fn spawn_integrate_dht_ops_consumer(
                 &dht_query_cache,
                 trigger_receipt.clone(),
                 network.clone(),
+                &faults,
             )
+            .instrument(rx.run_span("integrate_dht_ops_workflow"))
             .await
             {
                 Ok(WorkComplete::Incomplete) => {
+                    failures.success();
                     tracing::debug!("Work incomplete, retriggering workflow");
                     trigger_self.trigger(&"retrigger")
                 }
-                Err(err) => handle_workflow_error(err)?,
-                _ => (),
+                Ok(WorkComplete::Complete) => failures.success(),
+                Err(err) => failures.handle_error(err)?,
             };
         }
         Ok(())
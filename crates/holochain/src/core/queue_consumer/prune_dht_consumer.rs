@@ -0,0 +1,82 @@
+//! The queue consumer for pruning deleted data and expired ephemeral data
+
+use super::*;
+
+use crate::conductor::manager::ManagedTaskResult;
+use crate::core::workflow::prune_dht_workflow::expire_ephemeral_ops_workflow;
+use crate::core::workflow::prune_dht_workflow::prune_dht_workflow;
+use tokio::task::JoinHandle;
+use tracing::*;
+
+/// How often the dht and cache are checked for data to prune.
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// The most often the data of an ephemeral DNA is checked for expired ops.
+const MIN_EXPIRY_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Spawn the QueueConsumer for the dht pruning workflow
+#[instrument(skip(dht_db, cache_db, authored_db, conductor_handle, stop))]
+pub fn spawn_prune_dht_consumer(
+    dna_hash: Arc<DnaHash>,
+    dht_db: DbWrite<DbKindDht>,
+    cache_db: DbWrite<DbKindCache>,
+    authored_db: DbWrite<DbKindAuthored>,
+    conductor_handle: ConductorHandle,
+    mut stop: sync::broadcast::Receiver<()>,
+) -> (TriggerSender, JoinHandle<ManagedTaskResult>) {
+    let ephemeral_ttl = conductor_handle
+        .get_dna_def(&dna_hash)
+        .and_then(|dna_def| dna_def.ephemeral_ttl());
+    // Expired ops are checked for often enough that they don't outlive
+    // their TTL by much.
+    let interval = ephemeral_ttl.map_or(PRUNE_INTERVAL, |ttl| {
+        (ttl / 10).clamp(MIN_EXPIRY_INTERVAL, PRUNE_INTERVAL)
+    });
+    let (tx, mut rx) = TriggerSender::new_with_loop(interval..interval, false);
+    let handle = tokio::spawn(async move {
+        let mut failures = WorkflowFailures::new("prune_dht_workflow");
+        loop {
+            // Wait for next job
+            if let Job::Shutdown = next_job_or_exit(&mut rx, &mut stop).await {
+                tracing::warn!(
+                    "Cell is shutting down: stopping prune_dht_workflow queue consumer."
+                );
+                break;
+            }
+
+            // Ops of an ephemeral DNA expire whether or not pruning is on.
+            if let Some(ttl) = ephemeral_ttl {
+                let result = expire_ephemeral_ops_workflow(
+                    dht_db.clone(),
+                    cache_db.clone(),
+                    authored_db.clone(),
+                    ttl,
+                )
+                .instrument(rx.run_span("expire_ephemeral_ops_workflow"))
+                .await;
+                match result {
+                    Ok(_) => failures.success(),
+                    Err(err) => failures.handle_error(err)?,
+                };
+            }
+
+            // Pruning can be turned on and off while running,
+            // so check the config on every pass.
+            let retention_period = match conductor_handle.get_config().dht_pruning.as_ref() {
+                Some(config) => config.retention_period(),
+                None => continue,
+            };
+
+            // Run the workflow
+            match prune_dht_workflow(dht_db.clone(), cache_db.clone(), retention_period)
+                .instrument(rx.run_span("prune_dht_workflow"))
+                .await
+            {
+                Ok(_) => failures.success(),
+                Err(err) => failures.handle_error(err)?,
+            };
+        }
+        Ok(())
+    });
+    (tx, handle)
+}
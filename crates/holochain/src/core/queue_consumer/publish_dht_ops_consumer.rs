@@ -25,6 +25,7 @@ pub fn spawn_publish_dht_ops_consumer(
     let trigger_self = tx.clone();
     let handle = tokio::spawn(async move {
         let network = network;
+        let mut failures = WorkflowFailures::new("publish_dht_ops_workflow");
         loop {
             // Wait for next job
             if let Job::Shutdown = next_job_or_exit(&mut rx, &mut stop).await {
@@ -57,15 +58,18 @@ pub fn spawn_publish_dht_ops_consumer(
                 network.as_ref(),
                 &trigger_self,
                 agent.clone(),
+                WorkBudget::default(),
             )
+            .instrument(rx.run_span("publish_dht_ops_workflow"))
             .await
             {
                 Ok(WorkComplete::Incomplete) => {
+                    failures.success();
                     tracing::debug!("Work incomplete, retriggering workflow");
                     trigger_self.trigger(&"retrigger")
                 }
-                Err(err) => handle_workflow_error(err)?,
-                _ => (),
+                Ok(WorkComplete::Complete) => failures.success(),
+                Err(err) => failures.handle_error(err)?,
             };
         }
         Ok(())
@@ -0,0 +1,280 @@
+//! Fair sharing of workflow concurrency between DNA spaces.
+//!
+//! Each DNA space runs its own validation consumers, but they all compete for
+//! the same conductor resources. Without any coordination, a single DNA with a
+//! large backlog can keep the runtime busy validating its ops while every other
+//! DNA's ops sit waiting.
+//!
+//! The [`WorkflowScheduler`] hands out a fixed number of slots conductor-wide.
+//! While only one DNA has work, it may use every slot. Once several DNAs are
+//! competing, each is limited to its weighted share of the slots, so a hot DNA
+//! can only slow the others down so much.
+
+use holo_hash::DnaHash;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::watch;
+
+/// Conductor-wide scheduler which divides workflow concurrency fairly
+/// between DNA spaces, according to their weights.
+#[derive(Clone)]
+pub struct WorkflowScheduler {
+    state: Arc<Mutex<SchedulerState>>,
+    released: Arc<watch::Sender<()>>,
+    /// Kept so that sends on the channel never fail.
+    _released_rx: watch::Receiver<()>,
+}
+
+struct SchedulerState {
+    total_slots: usize,
+    groups: HashMap<Arc<DnaHash>, TaskGroup>,
+}
+
+/// The scheduling state of a single DNA space.
+struct TaskGroup {
+    weight: u32,
+    running: usize,
+    waiting: usize,
+}
+
+impl Default for TaskGroup {
+    fn default() -> Self {
+        Self {
+            weight: WorkflowScheduler::DEFAULT_WEIGHT,
+            running: 0,
+            waiting: 0,
+        }
+    }
+}
+
+impl TaskGroup {
+    fn is_active(&self) -> bool {
+        self.running > 0 || self.waiting > 0
+    }
+}
+
+/// A slot handed out by the [`WorkflowScheduler`].
+/// The slot is given back when this is dropped.
+pub struct WorkflowPermit {
+    dna_hash: Arc<DnaHash>,
+    scheduler: WorkflowScheduler,
+}
+
+impl WorkflowScheduler {
+    /// The default number of slots shared between all DNA spaces.
+    pub const DEFAULT_TOTAL_SLOTS: usize = 100;
+    /// The weight of a DNA space which hasn't been given one.
+    pub const DEFAULT_WEIGHT: u32 = 1;
+
+    /// Create a scheduler with the given number of slots to share out.
+    pub fn new(total_slots: usize) -> Self {
+        let (tx, rx) = watch::channel(());
+        Self {
+            state: Arc::new(Mutex::new(SchedulerState {
+                total_slots: total_slots.max(1),
+                groups: HashMap::new(),
+            })),
+            released: Arc::new(tx),
+            _released_rx: rx,
+        }
+    }
+
+    /// Set the weight of a DNA space. A DNA with twice the weight of
+    /// another gets twice as many slots when both are busy.
+    /// A weight of zero is treated as one.
+    pub fn set_weight(&self, dna_hash: Arc<DnaHash>, weight: u32) {
+        self.state.lock().groups.entry(dna_hash).or_default().weight = weight.max(1);
+        // Shares have changed so let any waiters re-check.
+        let _ = self.released.send(());
+    }
+
+    /// The weight of a DNA space.
+    pub fn weight(&self, dna_hash: &DnaHash) -> u32 {
+        self.state
+            .lock()
+            .groups
+            .get(dna_hash)
+            .map(|g| g.weight)
+            .unwrap_or(Self::DEFAULT_WEIGHT)
+    }
+
+    /// Wait for a slot to run some work for this DNA space.
+    pub async fn acquire(&self, dna_hash: Arc<DnaHash>) -> WorkflowPermit {
+        let mut released = self.released.subscribe();
+        let waiting = Waiting::new(self.clone(), dna_hash);
+        loop {
+            if self.state.lock().try_start(waiting.dna_hash()) {
+                return waiting.into_permit();
+            }
+            // The sender lives as long as this scheduler so this can't fail.
+            let _ = released.changed().await;
+        }
+    }
+
+    fn release(&self, dna_hash: &DnaHash) {
+        {
+            let mut state = self.state.lock();
+            if let Some(group) = state.groups.get_mut(dna_hash) {
+                group.running = group.running.saturating_sub(1);
+            }
+        }
+        let _ = self.released.send(());
+    }
+}
+
+impl Default for WorkflowScheduler {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_TOTAL_SLOTS)
+    }
+}
+
+impl SchedulerState {
+    /// Move one waiter in this group to running, if the group is under
+    /// its share of slots and there are slots free.
+    fn try_start(&mut self, dna_hash: &DnaHash) -> bool {
+        let running: usize = self.groups.values().map(|g| g.running).sum();
+        if running >= self.total_slots {
+            return false;
+        }
+        let active_weight: u64 = self
+            .groups
+            .values()
+            .filter(|g| g.is_active())
+            .map(|g| g.weight as u64)
+            .sum();
+        let total_slots = self.total_slots as u64;
+        let group = match self.groups.get_mut(dna_hash) {
+            Some(group) => group,
+            None => return false,
+        };
+        let share = (total_slots * group.weight as u64 / active_weight.max(1)).max(1);
+        if group.running as u64 >= share {
+            return false;
+        }
+        group.waiting -= 1;
+        group.running += 1;
+        true
+    }
+}
+
+/// Counts a caller as waiting on a group until it either gets a slot
+/// or gives up.
+struct Waiting {
+    scheduler: WorkflowScheduler,
+    dna_hash: Option<Arc<DnaHash>>,
+}
+
+impl Waiting {
+    fn new(scheduler: WorkflowScheduler, dna_hash: Arc<DnaHash>) -> Self {
+        scheduler
+            .state
+            .lock()
+            .groups
+            .entry(dna_hash.clone())
+            .or_default()
+            .waiting += 1;
+        Self {
+            scheduler,
+            dna_hash: Some(dna_hash),
+        }
+    }
+
+    fn dna_hash(&self) -> &DnaHash {
+        self.dna_hash.as_ref().expect("Only taken when consumed")
+    }
+
+    fn into_permit(mut self) -> WorkflowPermit {
+        WorkflowPermit {
+            dna_hash: self.dna_hash.take().expect("Only taken when consumed"),
+            scheduler: self.scheduler.clone(),
+        }
+    }
+}
+
+impl Drop for Waiting {
+    fn drop(&mut self) {
+        // If the wait was cancelled, stop counting this group as active.
+        if let Some(dna_hash) = self.dna_hash.take() {
+            if let Some(group) = self.scheduler.state.lock().groups.get_mut(&dna_hash) {
+                group.waiting = group.waiting.saturating_sub(1);
+            }
+            let _ = self.scheduler.released.send(());
+        }
+    }
+}
+
+impl Drop for WorkflowPermit {
+    fn drop(&mut self) {
+        self.scheduler.release(&self.dna_hash);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::fixt::prelude::*;
+    use holo_hash::fixt::DnaHashFixturator;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn lone_dna_can_use_every_slot() {
+        let scheduler = WorkflowScheduler::new(4);
+        let dna = Arc::new(fixt!(DnaHash));
+        let mut permits = Vec::new();
+        for _ in 0..4 {
+            permits.push(scheduler.acquire(dna.clone()).await);
+        }
+        // No slots left.
+        let r = tokio::time::timeout(
+            std::time::Duration::from_millis(10),
+            scheduler.acquire(dna.clone()),
+        )
+        .await;
+        assert!(r.is_err());
+        drop(permits);
+        scheduler.acquire(dna).await;
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn busy_dna_cannot_starve_another() {
+        let scheduler = WorkflowScheduler::new(4);
+        let hot = Arc::new(fixt!(DnaHash));
+        let quiet = Arc::new(fixt!(DnaHash));
+
+        // The hot dna takes every slot while it's alone.
+        let mut hot_permits = Vec::new();
+        for _ in 0..4 {
+            hot_permits.push(scheduler.acquire(hot.clone()).await);
+        }
+
+        // Both dnas now want another slot.
+        let next_hot = tokio::spawn({
+            let scheduler = scheduler.clone();
+            let hot = hot.clone();
+            async move { scheduler.acquire(hot).await }
+        });
+        let next_quiet = tokio::spawn({
+            let scheduler = scheduler.clone();
+            async move { scheduler.acquire(quiet).await }
+        });
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+
+        // The hot dna is over its half share, so the freed slot goes to the
+        // quiet dna even though the hot dna is also waiting.
+        hot_permits.pop();
+        let _quiet_permit = tokio::time::timeout(std::time::Duration::from_secs(5), next_quiet)
+            .await
+            .expect("quiet dna was starved")
+            .unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        assert!(!next_hot.is_finished());
+
+        // Weighting the hot dna gives it a bigger share.
+        scheduler.set_weight(hot, 3);
+        hot_permits.pop();
+        tokio::time::timeout(std::time::Duration::from_secs(5), next_hot)
+            .await
+            .expect("hot dna didn't get its weighted share")
+            .unwrap();
+    }
+}
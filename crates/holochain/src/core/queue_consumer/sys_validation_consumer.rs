@@ -29,6 +29,7 @@ pub fn spawn_sys_validation_consumer(
     let workspace = Arc::new(workspace);
     let space = Arc::new(space);
     let handle = tokio::spawn(async move {
+        let mut failures = WorkflowFailures::new("sys_validation_workflow");
         loop {
             // Wait for next job
             if let Job::Shutdown = next_job_or_exit(&mut rx, &mut stop).await {
@@ -46,15 +47,18 @@ pub fn spawn_sys_validation_consumer(
                 trigger_self.clone(),
                 network.clone(),
                 conductor_handle.clone(),
+                WorkBudget::default(),
             )
+            .instrument(rx.run_span("sys_validation_workflow"))
             .await
             {
                 Ok(WorkComplete::Incomplete) => {
+                    failures.success();
                     tracing::debug!("Work incomplete, retriggering workflow");
                     trigger_self.trigger(&"retrigger")
                 }
-                Err(err) => handle_workflow_error(err)?,
-                _ => (),
+                Ok(WorkComplete::Complete) => failures.success(),
+                Err(err) => failures.handle_error(err)?,
             };
         }
         Ok(())
@@ -253,9 +253,15 @@ async fn publish_loop() {
         timer.elapsed() >= Duration::from_secs(60) && timer.elapsed() < Duration::from_secs(61)
     );
 
-    publish_dht_ops_workflow(db.clone(), &dna_network, &ts, author.clone())
-        .await
-        .unwrap();
+    publish_dht_ops_workflow(
+        db.clone(),
+        &dna_network,
+        &ts,
+        author.clone(),
+        WorkBudget::default(),
+    )
+    .await
+    .unwrap();
 
     // - Op was published.
     op_published.recv().await.unwrap();
@@ -267,9 +273,15 @@ async fn publish_loop() {
         timer.elapsed() >= Duration::from_secs(60 * 2)
             && timer.elapsed() < Duration::from_secs(60 * 2 + 1)
     );
-    publish_dht_ops_workflow(db.clone(), &dna_network, &ts, author.clone())
-        .await
-        .unwrap();
+    publish_dht_ops_workflow(
+        db.clone(),
+        &dna_network,
+        &ts,
+        author.clone(),
+        WorkBudget::default(),
+    )
+    .await
+    .unwrap();
 
     // - But the op isn't published because it was published in the last five minutes.
     assert_eq!(
@@ -283,9 +295,15 @@ async fn publish_loop() {
     let timer = tokio::time::Instant::now();
     trigger_recv.listen().await.unwrap();
     assert!(timer.elapsed() < Duration::from_secs(1));
-    publish_dht_ops_workflow(db.clone(), &dna_network, &ts, author.clone())
-        .await
-        .unwrap();
+    publish_dht_ops_workflow(
+        db.clone(),
+        &dna_network,
+        &ts,
+        author.clone(),
+        WorkBudget::default(),
+    )
+    .await
+    .unwrap();
 
     // - But still no op is published.
     assert_eq!(
@@ -314,9 +332,15 @@ async fn publish_loop() {
         timer.elapsed() >= Duration::from_secs(60) && timer.elapsed() < Duration::from_secs(61)
     );
 
-    publish_dht_ops_workflow(db.clone(), &dna_network, &ts, author.clone())
-        .await
-        .unwrap();
+    publish_dht_ops_workflow(
+        db.clone(),
+        &dna_network,
+        &ts,
+        author.clone(),
+        WorkBudget::default(),
+    )
+    .await
+    .unwrap();
 
     // - The data is published because of the last publish time being greater then the interval.
     op_published.recv().await.unwrap();
@@ -336,9 +360,15 @@ async fn publish_loop() {
         timer.elapsed() >= Duration::from_secs(60 * 2)
             && timer.elapsed() < Duration::from_secs(60 * 2 + 1)
     );
-    publish_dht_ops_workflow(db.clone(), &dna_network, &ts, author.clone())
-        .await
-        .unwrap();
+    publish_dht_ops_workflow(
+        db.clone(),
+        &dna_network,
+        &ts,
+        author.clone(),
+        WorkBudget::default(),
+    )
+    .await
+    .unwrap();
 
     // - But no op is published because receipts are complete.
     assert_eq!(
@@ -369,9 +399,15 @@ async fn publish_loop() {
     let timer = tokio::time::Instant::now();
     trigger_recv.listen().await.unwrap();
     assert!(timer.elapsed() < Duration::from_secs(1));
-    publish_dht_ops_workflow(db.clone(), &dna_network, &ts, author.clone())
-        .await
-        .unwrap();
+    publish_dht_ops_workflow(
+        db.clone(),
+        &dna_network,
+        &ts,
+        author.clone(),
+        WorkBudget::default(),
+    )
+    .await
+    .unwrap();
 
     // - Op was published.
     op_published.recv().await.unwrap();
@@ -383,12 +419,37 @@ async fn publish_loop() {
         timer.elapsed() >= Duration::from_secs(60) && timer.elapsed() < Duration::from_secs(61)
     );
 
-    publish_dht_ops_workflow(db.clone(), &dna_network, &ts, author.clone())
-        .await
-        .unwrap();
+    publish_dht_ops_workflow(
+        db.clone(),
+        &dna_network,
+        &ts,
+        author.clone(),
+        WorkBudget::default(),
+    )
+    .await
+    .unwrap();
     // - The op is not published because of the time interval.
     assert_eq!(
         op_published.try_recv(),
         Err(tokio::sync::mpsc::error::TryRecvError::Empty)
     );
 }
+
+#[test]
+fn repeated_workflow_failures_bail() {
+    let mut failures = WorkflowFailures::with_limit("test_workflow", 3);
+    let err = || WorkflowError::other("corrupted store");
+
+    // Failures which are interrupted by a success are only logged.
+    assert!(failures.handle_error(err()).is_ok());
+    assert!(failures.handle_error(err()).is_ok());
+    failures.success();
+    assert!(failures.handle_error(err()).is_ok());
+    assert!(failures.handle_error(err()).is_ok());
+
+    // Too many in a row stops the consumer with a recoverable error,
+    // so the cell's apps are paused rather than disabled.
+    let e = failures.handle_error(err()).unwrap_err();
+    assert!(e.is_recoverable());
+    assert!(e.is_repeated_workflow_failure());
+}
@@ -2,23 +2,26 @@
 
 use super::*;
 use crate::conductor::manager::ManagedTaskResult;
+use crate::core::workflow::fault_injection::FaultInjector;
 use crate::core::workflow::validation_receipt_workflow::validation_receipt_workflow;
 use tokio::task::JoinHandle;
 use tracing::*;
 
 /// Spawn the QueueConsumer for validation receipt workflow
-#[instrument(skip(env, conductor_handle, stop, network))]
+#[instrument(skip(env, conductor_handle, stop, network, faults))]
 pub fn spawn_validation_receipt_consumer(
     dna_hash: Arc<DnaHash>,
     env: DbWrite<DbKindDht>,
     conductor_handle: ConductorHandle,
     mut stop: sync::broadcast::Receiver<()>,
     network: HolochainP2pDna,
+    faults: FaultInjector,
 ) -> (TriggerSender, JoinHandle<ManagedTaskResult>) {
     let (tx, mut rx) = TriggerSender::new();
     let trigger_self = tx.clone();
     let keystore = conductor_handle.keystore().clone();
     let handle = tokio::spawn(async move {
+        let mut failures = WorkflowFailures::new("validation_receipt_workflow");
         loop {
             // Wait for next job
             if let Job::Shutdown = next_job_or_exit(&mut rx, &mut stop).await {
@@ -35,15 +38,18 @@ pub fn spawn_validation_receipt_consumer(
                 &network,
                 keystore.clone(),
                 conductor_handle.clone(),
+                &faults,
             )
+            .instrument(rx.run_span("validation_receipt_workflow"))
             .await
             {
                 Ok(WorkComplete::Incomplete) => {
+                    failures.success();
                     tracing::debug!("Work incomplete, retriggering workflow");
                     trigger_self.trigger(&"retrigger")
                 }
-                Err(err) => handle_workflow_error(err)?,
-                _ => (),
+                Ok(WorkComplete::Complete) => failures.success(),
+                Err(err) => failures.handle_error(err)?,
             };
         }
         Ok(())
@@ -11,7 +11,10 @@
 pub mod error;
 pub mod guest_callback;
 pub mod host_fn;
+pub mod instance_pool;
+pub mod module_cache;
 pub mod real_ribosome;
+pub mod zome_call_limits;
 
 use crate::conductor::api::CellConductorApi;
 use crate::conductor::api::CellConductorReadHandle;
@@ -37,6 +40,7 @@ use guest_callback::post_commit::PostCommitHostAccess;
 use guest_callback::validate::ValidateHostAccess;
 use guest_callback::validation_package::ValidationPackageHostAccess;
 use holo_hash::AgentPubKey;
+use holochain_conductor_api::config::conductor::ZomeCallLimitsConfig;
 use holochain_keystore::MetaLairClient;
 use holochain_p2p::HolochainP2pDna;
 use holochain_serialized_bytes::prelude::*;
@@ -47,6 +51,8 @@ use holochain_types::zome_types::GlobalZomeTypes;
 use mockall::automock;
 use std::iter::Iterator;
 use std::sync::Arc;
+use zome_call_limits::ZomeCallCancel;
+use zome_call_limits::ZomeCallDeadline;
 
 use self::guest_callback::{
     entry_defs::EntryDefsInvocation, genesis_self_check::GenesisSelfCheckResult,
@@ -62,6 +68,7 @@ pub struct CallContext {
     pub(crate) function_name: FunctionName,
     pub(crate) auth: InvocationAuth,
     pub(crate) host_context: HostContext,
+    pub(crate) deadline: ZomeCallDeadline,
 }
 
 impl CallContext {
@@ -76,6 +83,7 @@ impl CallContext {
             function_name,
             host_context,
             auth,
+            deadline: ZomeCallDeadline::default(),
         }
     }
 
@@ -94,6 +102,12 @@ impl CallContext {
     pub fn auth(&self) -> InvocationAuth {
         self.auth.clone()
     }
+
+    /// When the call gives up, which host functions pass on to the work
+    /// they wait on.
+    pub fn deadline(&self) -> ZomeCallDeadline {
+        self.deadline.clone()
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -190,6 +204,22 @@ impl HostContext {
         }
     }
 
+    /// Get the limits of a zome call, if this is one.
+    pub fn zome_call_limits(&self) -> Option<&ZomeCallLimitsConfig> {
+        match self {
+            Self::ZomeCall(ZomeCallHostAccess { limits, .. }) => Some(limits),
+            _ => None,
+        }
+    }
+
+    /// Get the token which cancels a zome call, if this is one.
+    pub fn zome_call_cancel(&self) -> Option<&ZomeCallCancel> {
+        match self {
+            Self::ZomeCall(ZomeCallHostAccess { cancel, .. }) => Some(cancel),
+            _ => None,
+        }
+    }
+
     /// Get the call zome handle, panics if none was provided
     pub fn call_zome_handle(&self) -> &CellConductorReadHandle {
         match self {
@@ -443,6 +473,10 @@ pub struct ZomeCallHostAccess {
     pub network: HolochainP2pDna,
     pub signal_tx: SignalBroadcaster,
     pub call_zome_handle: CellConductorReadHandle,
+    /// The limits the call is aborted at.
+    pub limits: ZomeCallLimitsConfig,
+    /// Aborts the call when cancelled.
+    pub cancel: ZomeCallCancel,
 }
 
 impl std::fmt::Debug for ZomeCallHostAccess {
@@ -507,6 +541,13 @@ pub trait RibosomeT: Sized + std::fmt::Debug + Send + Sync {
 
     fn get_integrity_zome(&self, zome_id: &ZomeId) -> Option<IntegrityZome>;
 
+    /// Get the entry def an integrity zome defined at this index, if there is one.
+    fn get_app_entry_def(
+        &self,
+        zome_id: ZomeId,
+        entry_def_index: EntryDefIndex,
+    ) -> RibosomeResult<Option<EntryDef>>;
+
     fn call_iterator<I: Invocation + 'static>(
         &self,
         host_context: HostContext,
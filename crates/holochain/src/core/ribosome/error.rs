@@ -2,6 +2,7 @@
 
 use crate::conductor::api::error::ConductorApiError;
 use crate::conductor::interface::error::InterfaceError;
+use crate::core::ribosome::zome_call_limits::ZomeCallLimit;
 use holo_hash::AnyDhtHash;
 use holochain_cascade::error::CascadeError;
 use holochain_serialized_bytes::prelude::SerializedBytesError;
@@ -35,10 +36,23 @@ pub enum RibosomeError {
     #[error("Attempted to call a zome function that doesn't exist: Zome: {0} Fn {1}")]
     ZomeFnNotExists(ZomeName, FunctionName),
 
+    /// A zome call was aborted for exceeding one of its configured limits
+    #[error("Zome call aborted for exceeding its {2} limit: Zome: {0} Fn {1}")]
+    ZomeCallLimitExceeded(ZomeName, FunctionName, ZomeCallLimit),
+
+    /// A zome call was cancelled before it finished
+    #[error("Zome call cancelled: Zome: {0} Fn {1}")]
+    ZomeCallCancelled(ZomeName, FunctionName),
+
     /// a problem with entry defs
     #[error("An error with entry defs in zome '{0}': {1}")]
     EntryDefs(ZomeName, String),
 
+    /// an app entry type which no integrity zome defines,
+    /// or with a visibility other than its entry def's
+    #[error("App entry type {0:?} doesn't match an entry def of the DNA")]
+    AppEntryTypeMismatch(AppEntryType),
+
     /// a mandatory dependency for a record doesn't exist
     /// for example a remove link ribosome call needs to find the add link in order to infer the
     /// correct base and this dependent relationship exists before even subconscious validation
@@ -72,6 +72,9 @@ host_fn_api_impls! {
     // @todo
     fn call_info (()) -> zt::info::CallInfo;
 
+    // The current runtime properties of the DNA, which may change between calls.
+    fn runtime_properties (()) -> SerializedBytes;
+
     fn call (Vec<zt::call::Call>) -> Vec<zt::ZomeCallResponse>;
 
     // @todo List all the local capability claims.
@@ -123,6 +126,9 @@ host_fn_api_impls! {
     // Delete an entry.
     fn delete (zt::entry::DeleteInput) -> holo_hash::ActionHash;
 
+    // Sys validate the records authored so far in this call, without committing them.
+    fn dry_run_sys_validation (()) -> Vec<zt::validate::SysValidationIssue>;
+
     // Delete a CreateLink record.
     fn delete_link (zt::link::DeleteLinkInput) -> holo_hash::ActionHash;
 
@@ -137,8 +143,14 @@ host_fn_api_impls! {
     // Attempt to get a live entry from the cascade.
     fn get (Vec<zt::entry::GetInput>) -> Vec<Option<zt::record::Record>>;
 
+    fn get_action (Vec<holo_hash::ActionHash>) -> Vec<Option<SignedActionHashed>>;
+
+    fn exists (Vec<holo_hash::AnyDhtHash>) -> Vec<bool>;
+
     fn get_agent_activity (zt::agent_activity::GetAgentActivityInput) -> zt::query::AgentActivity;
 
+    fn get_agent_activity_page (zt::agent_activity::GetAgentActivityPageInput) -> zt::query::AgentActivityPage;
+
     fn get_details (Vec<zt::entry::GetInput>) -> Vec<Option<zt::metadata::Details>>;
 
     // Get links by entry hash from the cascade.
@@ -146,6 +158,9 @@ host_fn_api_impls! {
 
     fn get_link_details (Vec<zt::link::GetLinksInput>) -> Vec<zt::link::LinkDetails>;
 
+    // Aggregate the links on a base without returning them.
+    fn aggregate_links (Vec<zt::link::AggregateLinksInput>) -> Vec<zt::link::LinkAggregate>;
+
     // Hash data on the host.
     fn hash (zt::hash::HashInput) -> zt::hash::HashOutput;
 
@@ -166,12 +181,21 @@ host_fn_api_impls! {
     // Query the source chain for data.
     fn query (zt::query::ChainQueryFilter) -> Vec<Record>;
 
+    // Query a page of the source chain.
+    fn query_page (zt::query::ChainQueryPageInput) -> zt::query::RecordsPage;
+
     // the length of random bytes to create
     fn random_bytes (u32) -> zt::bytes::Bytes;
 
+    // Read a chunked blob back from the cascade.
+    fn read_blob (zt::entry::BlobManifest) -> Option<zt::bytes::Bytes>;
+
     // Remotely signal many agents without waiting for responses
     fn remote_signal (zt::signal::RemoteSignal) -> ();
 
+    // Remotely signal many agents, waiting to hear whether each received it
+    fn remote_signal_acknowledged (zt::signal::RemoteSignal) -> Vec<zt::signal::RemoteSignalReceipt>;
+
     // // @todo
     // fn send (()) -> ();
 
@@ -199,6 +223,9 @@ host_fn_api_impls! {
 
     fn verify_signature (zt::signature::VerifySignature) -> bool;
 
+    // Commit a blob too large for one entry as chunks.
+    fn write_blob (zt::bytes::Bytes) -> zt::entry::BlobManifest;
+
     // The zome and agent info are constants specific to the current zome and chain.
     // All the information is provided by core so there is no input value.
     // These are constant for the lifetime of a zome call.
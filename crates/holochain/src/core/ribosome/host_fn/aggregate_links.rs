@@ -0,0 +1,77 @@
+use crate::core::ribosome::CallContext;
+use crate::core::ribosome::HostFnAccess;
+use crate::core::ribosome::RibosomeError;
+use crate::core::ribosome::RibosomeT;
+use futures::StreamExt;
+use holochain_cascade::Cascade;
+use holochain_p2p::actor::GetLinksOptions;
+use holochain_types::prelude::*;
+use holochain_wasmer_host::prelude::*;
+use std::sync::Arc;
+
+/// Aggregate the links on a base on the host, so the zome only receives
+/// the count, existence or tags it asked for.
+/// The links are still fetched from the authorities in full.
+#[allow(clippy::extra_unused_lifetimes)]
+#[tracing::instrument(skip(_ribosome, call_context), fields(?call_context.zome, function = ?call_context.function_name))]
+pub fn aggregate_links<'a>(
+    _ribosome: Arc<impl RibosomeT>,
+    call_context: Arc<CallContext>,
+    inputs: Vec<AggregateLinksInput>,
+) -> Result<Vec<LinkAggregate>, RuntimeError> {
+    match HostFnAccess::from(&call_context.host_context()) {
+        HostFnAccess {
+            read_workspace: Permission::Allow,
+            ..
+        } => {
+            let results: Vec<Result<LinkAggregate, RibosomeError>> =
+                tokio_helper::block_forever_on(async move {
+                    let call_context = &call_context;
+                    futures::stream::iter(inputs.into_iter().map(|input| async move {
+                        let AggregateLinksInput {
+                            base_address,
+                            link_type,
+                            tag_prefix,
+                            aggregation,
+                        } = input;
+
+                        let key = WireLinkKey {
+                            base: base_address,
+                            type_query: link_type,
+                            tag: tag_prefix,
+                        };
+                        Ok(Cascade::from_workspace_network(
+                            &call_context.host_context.workspace(),
+                            call_context.host_context.network().to_owned(),
+                        )
+                        .dht_get_link_aggregate(key, aggregation, GetLinksOptions::default())
+                        .await?)
+                    }))
+                    // Limit concurrent calls to 10 as each call
+                    // can spawn multiple connections.
+                    .buffered(10)
+                    .collect()
+                    .await
+                });
+            let results: Result<Vec<_>, RuntimeError> = results
+                .into_iter()
+                .map(|result| match result {
+                    Ok(aggregate) => Ok(aggregate),
+                    Err(cascade_error) => {
+                        Err(wasm_error!(WasmErrorInner::Host(cascade_error.to_string())).into())
+                    }
+                })
+                .collect();
+            results
+        }
+        _ => Err(wasm_error!(WasmErrorInner::Host(
+            RibosomeError::HostFnPermissions(
+                call_context.zome.zome_name().clone(),
+                call_context.function_name().clone(),
+                "aggregate_links".into(),
+            )
+            .to_string(),
+        ))
+        .into()),
+    }
+}
@@ -14,8 +14,12 @@ pub fn call(
     call_context: Arc<CallContext>,
     inputs: Vec<Call>,
 ) -> Result<Vec<ZomeCallResponse>, RuntimeError> {
+    let deadline = call_context.deadline();
+    // The bridge calls are cancelled if this call gives up on them.
+    let cancel = deadline.child_cancel();
+    let bridge_cancel = cancel.clone();
     let results: Vec<Result<ZomeCallResponse, RuntimeError>> =
-        tokio_helper::block_forever_on(async move {
+        tokio_helper::block_forever_on(deadline.run(async move {
             join_all(inputs.into_iter().map(|input| async {
                 // The line below was added when migrating to rust edition 2021, per
                 // https://doc.rust-lang.org/edition-guide/rust-2021/disjoint-capture-in-closures.html#migration
@@ -134,6 +138,7 @@ pub fn call(
                                                     .expect(
                                                         "Must have source chain to make zome call",
                                                     ),
+                                                bridge_cancel.clone(),
                                             )
                                             .await
                                         {
@@ -168,7 +173,11 @@ pub fn call(
                 }
             }))
             .await
-        });
+        }))
+        .map_err(|e| {
+            cancel.cancel();
+            e
+        })?;
     let results: Result<Vec<_>, _> = results.into_iter().collect();
     results
 }
@@ -11,7 +11,7 @@ use std::sync::Arc;
 /// create record
 #[allow(clippy::extra_unused_lifetimes)]
 pub fn create<'a>(
-    _ribosome: Arc<impl RibosomeT>,
+    ribosome: Arc<impl RibosomeT>,
     call_context: Arc<CallContext>,
     input: CreateInput,
 ) -> Result<ActionHash, RuntimeError> {
@@ -56,6 +56,24 @@ pub fn create<'a>(
                         }) => {
                             let app_entry_type =
                                 AppEntryType::new(entry_def_index, zome_id, entry_visibility);
+                            // The visibility must be the one the entry was defined
+                            // with, so private entries are never published.
+                            match ribosome.get_app_entry_def(zome_id, entry_def_index) {
+                                Ok(Some(entry_def))
+                                    if entry_def.visibility == *app_entry_type.visibility() => {}
+                                Ok(_) => {
+                                    return Err(wasm_error!(WasmErrorInner::Host(
+                                        RibosomeError::AppEntryTypeMismatch(app_entry_type)
+                                            .to_string()
+                                    ))
+                                    .into())
+                                }
+                                Err(e) => {
+                                    return Err(
+                                        wasm_error!(WasmErrorInner::Host(e.to_string())).into()
+                                    )
+                                }
+                            }
                             EntryType::App(app_entry_type)
                         }
                         EntryDefLocation::CapGrant => EntryType::CapGrant,
@@ -0,0 +1,69 @@
+use crate::core::ribosome::CallContext;
+use crate::core::ribosome::HostFnAccess;
+use crate::core::ribosome::RibosomeError;
+use crate::core::ribosome::RibosomeT;
+use crate::core::validation::OutcomeOrError;
+use holochain_types::prelude::*;
+use holochain_wasmer_host::prelude::*;
+use std::sync::Arc;
+
+/// Run the sys validation that committing the call's records will run on
+/// every record authored so far in the call, returning why any of them would
+/// be rejected instead of failing the call.
+#[allow(clippy::extra_unused_lifetimes)]
+pub fn dry_run_sys_validation<'a>(
+    _ribosome: Arc<impl RibosomeT>,
+    call_context: Arc<CallContext>,
+    _: (),
+) -> Result<Vec<SysValidationIssue>, RuntimeError> {
+    match HostFnAccess::from(&call_context.host_context()) {
+        HostFnAccess {
+            write_workspace: Permission::Allow,
+            ..
+        } => tokio_helper::block_forever_on(async move {
+            let workspace = call_context.host_context.workspace_write();
+            let records = workspace
+                .source_chain()
+                .as_ref()
+                .expect("Must have source chain if write_workspace access is given")
+                .scratch_records()
+                .map_err(|source_chain_error| -> RuntimeError {
+                    wasm_error!(WasmErrorInner::Host(source_chain_error.to_string())).into()
+                })?;
+            let mut issues = Vec::new();
+            // Records are validated in the order they were authored, so each
+            // one's previous action is found in the workspace.
+            for record in records {
+                match call_context
+                    .host_context
+                    .call_zome_handle()
+                    .sys_validate_record(
+                        &record,
+                        workspace,
+                        call_context.host_context.network().clone(),
+                    )
+                    .await
+                {
+                    Ok(()) => (),
+                    Err(OutcomeOrError::Outcome(outcome)) => issues.push(SysValidationIssue {
+                        action_hash: record.action_address().clone(),
+                        reason: outcome.into(),
+                    }),
+                    Err(OutcomeOrError::Err(e)) => {
+                        return Err(wasm_error!(WasmErrorInner::Host(e.to_string())).into())
+                    }
+                }
+            }
+            Ok(issues)
+        }),
+        _ => Err(wasm_error!(WasmErrorInner::Host(
+            RibosomeError::HostFnPermissions(
+                call_context.zome.zome_name().clone(),
+                call_context.function_name().clone(),
+                "dry_run_sys_validation".into(),
+            )
+            .to_string(),
+        ))
+        .into()),
+    }
+}
@@ -18,7 +18,11 @@ pub fn emit_signal(
                 ribosome.dna_def().as_hash().clone(),
                 call_context.host_context.workspace().source_chain().as_ref().expect("Must have a source chain to emit signals").agent_pubkey().clone(),
             );
-            let signal = Signal::App(cell_id, input);
+            let signal = Signal::App {
+                cell_id,
+                zome_name: call_context.zome.zome_name().clone(),
+                signal: input,
+            };
             call_context.host_context().signal_tx().send(signal).map_err(|interface_error| wasm_error!(WasmErrorInner::Host(interface_error.to_string())))?;
             Ok(())
         },
@@ -0,0 +1,58 @@
+use crate::core::ribosome::CallContext;
+use crate::core::ribosome::HostFnAccess;
+use crate::core::ribosome::RibosomeError;
+use crate::core::ribosome::RibosomeT;
+use futures::StreamExt;
+use holochain_cascade::Cascade;
+use holochain_types::prelude::*;
+use holochain_wasmer_host::prelude::*;
+use std::sync::Arc;
+
+#[allow(clippy::extra_unused_lifetimes)]
+#[tracing::instrument(skip(_ribosome, call_context), fields(?call_context.zome, function = ?call_context.function_name))]
+pub fn exists<'a>(
+    _ribosome: Arc<impl RibosomeT>,
+    call_context: Arc<CallContext>,
+    inputs: Vec<AnyDhtHash>,
+) -> Result<Vec<bool>, RuntimeError> {
+    match HostFnAccess::from(&call_context.host_context()) {
+        HostFnAccess {
+            read_workspace: Permission::Allow,
+            ..
+        } => {
+            let results: Vec<Result<bool, _>> =
+                tokio_helper::block_forever_on(call_context.deadline().run(async move {
+                    futures::stream::iter(inputs.into_iter().map(|hash| async {
+                        Cascade::from_workspace_network(
+                            &call_context.host_context.workspace(),
+                            call_context.host_context.network().clone(),
+                        )
+                        .exists(hash, Default::default())
+                        .await
+                    }))
+                    // Limit concurrent calls to 10 as each call
+                    // can spawn multiple connections.
+                    .buffered(10)
+                    .collect()
+                    .await
+                }))?;
+            results
+                .into_iter()
+                .map(|result| {
+                    result.map_err(|cascade_error| {
+                        wasm_error!(WasmErrorInner::Host(cascade_error.to_string())).into()
+                    })
+                })
+                .collect()
+        }
+        _ => Err(wasm_error!(WasmErrorInner::Host(
+            RibosomeError::HostFnPermissions(
+                call_context.zome.zome_name().clone(),
+                call_context.function_name().clone(),
+                "exists".into(),
+            )
+            .to_string(),
+        ))
+        .into()),
+    }
+}
@@ -23,7 +23,7 @@ pub fn get<'a>(
             ..
         } => {
             let results: Vec<Result<Option<Record>, _>> =
-                tokio_helper::block_forever_on(async move {
+                tokio_helper::block_forever_on(call_context.deadline().run(async move {
                     futures::stream::iter(inputs.into_iter().map(|input| async {
                         let GetInput {
                             any_dht_hash,
@@ -41,7 +41,7 @@ pub fn get<'a>(
                     .buffered(10)
                     .collect()
                     .await
-                });
+                }))?;
             let results: Result<Vec<_>, RuntimeError> = results
                 .into_iter()
                 .map(|result| match result {
@@ -37,7 +37,7 @@ pub fn get_agent_activity(
         let network = call_context.host_context.network().clone();
 
         // timeouts must be handled by the network
-        tokio_helper::block_forever_on(async move {
+        tokio_helper::block_forever_on(call_context.deadline().run(async move {
             let workspace = call_context.host_context.workspace();
             let mut cascade = Cascade::from_workspace_network(&workspace, network);
             let activity = cascade
@@ -46,7 +46,7 @@ pub fn get_agent_activity(
                 .map_err(|cascade_error| wasm_error!(WasmErrorInner::Host(cascade_error.to_string())))?;
 
                     Ok(activity.into())
-                })
+                }))?
         },
         _ => Err(wasm_error!(WasmErrorInner::Host(RibosomeError::HostFnPermissions(
             call_context.zome.zome_name().clone(),
@@ -0,0 +1,154 @@
+use crate::core::ribosome::CallContext;
+use crate::core::ribosome::HostFnAccess;
+use crate::core::ribosome::RibosomeError;
+use crate::core::ribosome::RibosomeT;
+use holochain_cascade::Cascade;
+use holochain_p2p::actor::GetActivityOptions;
+use holochain_types::prelude::*;
+use holochain_wasmer_host::prelude::*;
+use std::sync::Arc;
+
+pub fn get_agent_activity_page(
+    _ribosome: Arc<impl RibosomeT>,
+    call_context: Arc<CallContext>,
+    input: GetAgentActivityPageInput,
+) -> Result<AgentActivityPage, RuntimeError> {
+    match HostFnAccess::from(&call_context.host_context()) {
+        HostFnAccess {
+            read_workspace: Permission::Allow,
+            ..
+        } => {
+            let GetAgentActivityPageInput {
+                agent_pubkey,
+                chain_query_filter,
+                activity_request,
+                cursor,
+                limit,
+            } = input;
+            let options = match activity_request {
+                ActivityRequest::Status => GetActivityOptions {
+                    include_valid_activity: false,
+                    include_rejected_activity: false,
+                    ..Default::default()
+                },
+                ActivityRequest::Full => GetActivityOptions {
+                    include_valid_activity: true,
+                    include_rejected_activity: true,
+                    ..Default::default()
+                },
+            };
+            // Only the activity in the page is asked for, so the authorities
+            // neither send the earlier pages again nor the rest of the chain.
+            let range_end = match chain_query_filter.sequence_range {
+                ChainQueryFilterRange::Unbounded => Some(u32::MAX),
+                ChainQueryFilterRange::ActionSeqRange(_, end) => Some(end),
+                _ => None,
+            };
+            let chain_query_filter = chain_query_filter.page(cursor, limit);
+            let page_end = match chain_query_filter.sequence_range {
+                ChainQueryFilterRange::ActionSeqRange(_, end) => range_end.map(|r| (end, r)),
+                _ => None,
+            };
+
+            let network = call_context.host_context.network().clone();
+
+            // timeouts must be handled by the network
+            tokio_helper::block_forever_on(call_context.deadline().run(async move {
+                let workspace = call_context.host_context.workspace();
+                let mut cascade = Cascade::from_workspace_network(&workspace, network);
+                let mut activity: AgentActivity = cascade
+                    .get_agent_activity(agent_pubkey, chain_query_filter, options)
+                    .await
+                    .map_err(|cascade_error| {
+                        wasm_error!(WasmErrorInner::Host(cascade_error.to_string()))
+                    })?
+                    .into();
+
+                // Hash bounded ranges can't be narrowed, and walk the chain
+                // backwards, so they are cut at the cursor and put in chain
+                // order here.
+                let after = cursor.map(|c| c.action_seq());
+                for actions in [
+                    &mut activity.valid_activity,
+                    &mut activity.rejected_activity,
+                ] {
+                    actions.retain(|(seq, _)| after.map_or(true, |after| *seq > after));
+                    actions.sort_by_key(|(seq, _)| *seq);
+                }
+                Ok(match page_end {
+                    Some((page_end, range_end)) => activity.into_bounded_page(page_end, range_end),
+                    None => activity.into_page(limit),
+                })
+            }))?
+        }
+        _ => Err(wasm_error!(WasmErrorInner::Host(
+            RibosomeError::HostFnPermissions(
+                call_context.zome.zome_name().clone(),
+                call_context.function_name().clone(),
+                "get_agent_activity_page".into(),
+            )
+            .to_string(),
+        ))
+        .into()),
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "slow_tests")]
+pub mod slow_tests {
+    use crate::core::ribosome::wasm_test::RibosomeTestFixture;
+    use crate::test_utils::consistency_10s;
+    use hdk::prelude::*;
+    use holochain_wasm_test_utils::TestWasm;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn get_agent_activity_page_test() {
+        observability::test_run().ok();
+        let RibosomeTestFixture {
+            conductor,
+            alice,
+            bob,
+            alice_pubkey,
+            alice_cell,
+            bob_cell,
+            ..
+        } = RibosomeTestFixture::new(TestWasm::Query).await;
+
+        let _hash_a: EntryHash = conductor.call(&alice, "add_path", "a".to_string()).await;
+        let _hash_b: EntryHash = conductor.call(&alice, "add_path", "b".to_string()).await;
+        consistency_10s(&[&alice_cell, &bob_cell]).await;
+
+        let records: Vec<Record> = conductor
+            .call(&alice, "query", ChainQueryFilter::default())
+            .await;
+        let chain: Vec<_> = records
+            .iter()
+            .map(|r| (r.action().action_seq(), r.action_address().clone()))
+            .collect();
+
+        let mut paged = Vec::new();
+        let mut cursor = None;
+        loop {
+            let page: AgentActivityPage = conductor
+                .call(
+                    &bob,
+                    "get_agent_activity_page",
+                    GetAgentActivityPageInput {
+                        agent_pubkey: alice_pubkey.clone(),
+                        chain_query_filter: ChainQueryFilter::default(),
+                        activity_request: ActivityRequest::Full,
+                        cursor,
+                        limit: 4,
+                    },
+                )
+                .await;
+            assert!(page.activity.valid_activity.len() <= 4);
+            paged.extend(page.activity.valid_activity);
+            match page.next {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+        assert_eq!(paged, chain);
+    }
+}
@@ -20,7 +20,7 @@ pub fn get_details<'a>(
             ..
         } => {
             let results: Vec<Result<Option<Details>, _>> =
-                tokio_helper::block_forever_on(async move {
+                tokio_helper::block_forever_on(call_context.deadline().run(async move {
                     join_all(inputs.into_iter().map(|input| async {
                         let GetInput {
                             any_dht_hash,
@@ -34,7 +34,7 @@ pub fn get_details<'a>(
                         .await
                     }))
                     .await
-                });
+                }))?;
             let results: Result<Vec<_>, _> = results
                 .into_iter()
                 .map(|result| {
@@ -21,7 +21,7 @@ pub fn get_link_details<'a>(
             ..
         } => {
             let results: Vec<Result<Vec<_>, RibosomeError>> =
-                tokio_helper::block_forever_on(async move {
+                tokio_helper::block_forever_on(call_context.deadline().run(async move {
                     join_all(inputs.into_iter().map(|input| async {
                         let GetLinksInput {
                             base_address,
@@ -42,7 +42,7 @@ pub fn get_link_details<'a>(
                         .await?)
                     }))
                     .await
-                });
+                }))?;
             let results: Result<Vec<_>, RuntimeError> = results
                 .into_iter()
                 .map(|result| match result {
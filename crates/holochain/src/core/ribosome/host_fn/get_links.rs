@@ -24,7 +24,7 @@ pub fn get_links<'a>(
             ..
         } => {
             let results: Vec<Result<Vec<Link>, RibosomeError>> =
-                tokio_helper::block_forever_on(async move {
+                tokio_helper::block_forever_on(call_context.deadline().run(async move {
                     futures::stream::iter(inputs.into_iter().map(|input| async {
                         let GetLinksInput {
                             base_address,
@@ -49,7 +49,7 @@ pub fn get_links<'a>(
                     .buffered(10)
                     .collect()
                     .await
-                });
+                }))?;
             let results: Result<Vec<_>, RuntimeError> = results
                 .into_iter()
                 .map(|result| match result {
@@ -22,7 +22,7 @@ pub fn must_get_action<'a>(
             let action_hash = input.into_inner();
 
             // timeouts must be handled by the network
-            tokio_helper::block_forever_on(async move {
+            tokio_helper::block_forever_on(call_context.deadline().run(async move {
                 let workspace = call_context.host_context.workspace();
                 let mut cascade = match call_context.host_context {
                     HostContext::Validate(_) => Cascade::from_workspace(workspace.stores(), None),
@@ -93,7 +93,7 @@ pub fn must_get_action<'a>(
                         }
                     },
                 }
-            })
+            }))?
         }
         _ => Err(wasm_error!(WasmErrorInner::Host(
             RibosomeError::HostFnPermissions(
@@ -24,7 +24,7 @@ pub fn must_get_agent_activity(
             } = input;
 
             // timeouts must be handled by the network
-            tokio_helper::block_forever_on(async move {
+            tokio_helper::block_forever_on(call_context.deadline().run(async move {
                 let workspace = call_context.host_context.workspace();
                 let mut cascade = match call_context.host_context {
                     HostContext::Validate(_) => Cascade::from_workspace(workspace.stores(), None),
@@ -86,7 +86,7 @@ pub fn must_get_agent_activity(
                 };
 
                 result
-            })
+            }))?
         }
         _ => Err(wasm_error!(WasmErrorInner::Host(
             RibosomeError::HostFnPermissions(
@@ -0,0 +1,103 @@
+use crate::core::ribosome::CallContext;
+use crate::core::ribosome::HostFnAccess;
+use crate::core::ribosome::RibosomeError;
+use crate::core::ribosome::RibosomeT;
+use holochain_types::prelude::*;
+use holochain_wasmer_host::prelude::*;
+use std::sync::Arc;
+
+pub fn query_page(
+    _ribosome: Arc<impl RibosomeT>,
+    call_context: Arc<CallContext>,
+    input: ChainQueryPageInput,
+) -> Result<RecordsPage, RuntimeError> {
+    match HostFnAccess::from(&call_context.host_context()) {
+        HostFnAccess {
+            read_workspace: Permission::Allow,
+            ..
+        } => tokio_helper::block_forever_on(async move {
+            let ChainQueryPageInput {
+                filter,
+                cursor,
+                limit,
+            } = input;
+            let (records, more) = call_context
+                .host_context
+                .workspace()
+                .source_chain()
+                .as_ref()
+                .expect("Must have source chain to query the source chain")
+                .query_page(filter, cursor.map(|c| c.action_seq()), limit)
+                .await
+                .map_err(|source_chain_error| -> RuntimeError {
+                    wasm_error!(WasmErrorInner::Host(source_chain_error.to_string())).into()
+                })?;
+            let next = records
+                .last()
+                .filter(|_| more)
+                .map(|record| ChainCursor::after(record.action().action_seq()));
+            Ok(RecordsPage { records, next })
+        }),
+        _ => Err(wasm_error!(WasmErrorInner::Host(
+            RibosomeError::HostFnPermissions(
+                call_context.zome.zome_name().clone(),
+                call_context.function_name().clone(),
+                "query_page".into(),
+            )
+            .to_string(),
+        ))
+        .into()),
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "slow_tests")]
+pub mod slow_tests {
+    use crate::core::ribosome::wasm_test::RibosomeTestFixture;
+    use hdk::prelude::*;
+    use holochain_wasm_test_utils::TestWasm;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn query_page_test() {
+        observability::test_run().ok();
+        let RibosomeTestFixture {
+            conductor, alice, ..
+        } = RibosomeTestFixture::new(TestWasm::Query).await;
+
+        let _hash_a: EntryHash = conductor.call(&alice, "add_path", "a".to_string()).await;
+        let _hash_b: EntryHash = conductor.call(&alice, "add_path", "b".to_string()).await;
+
+        let records: Vec<Record> = conductor
+            .call(&alice, "query", ChainQueryFilter::default())
+            .await;
+
+        for filter in [
+            ChainQueryFilter::default(),
+            ChainQueryFilter::default()
+                .sequence_range(ChainQueryFilterRange::ActionSeqRange(0, u32::MAX)),
+        ] {
+            let mut paged = Vec::new();
+            let mut cursor = None;
+            loop {
+                let page: RecordsPage = conductor
+                    .call(
+                        &alice,
+                        "query_page",
+                        ChainQueryPageInput {
+                            filter: filter.clone(),
+                            cursor,
+                            limit: 4,
+                        },
+                    )
+                    .await;
+                assert!(page.records.len() <= 4);
+                paged.extend(page.records);
+                match page.next {
+                    Some(next) => cursor = Some(next),
+                    None => break,
+                }
+            }
+            assert_eq!(paged, records);
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use crate::core::ribosome::CallContext;
+use crate::core::ribosome::HostFnAccess;
+use crate::core::ribosome::RibosomeError;
+use crate::core::ribosome::RibosomeT;
+use holochain_cascade::Cascade;
+use holochain_p2p::actor::GetOptions as NetworkGetOptions;
+use holochain_types::prelude::*;
+use holochain_wasmer_host::prelude::*;
+use std::sync::Arc;
+
+/// Fetch the chunks of a blob and join them back together.
+#[allow(clippy::extra_unused_lifetimes)]
+pub fn read_blob<'a>(
+    _ribosome: Arc<impl RibosomeT>,
+    call_context: Arc<CallContext>,
+    input: BlobManifest,
+) -> Result<Option<holochain_types::prelude::Bytes>, RuntimeError> {
+    match HostFnAccess::from(&call_context.host_context()) {
+        HostFnAccess {
+            read_workspace: Permission::Allow,
+            ..
+        } => {
+            let blob = tokio_helper::block_forever_on(async move {
+                // Chunks are addressed by their content, so they are fetched
+                // like `must_get_entry` does rather than following updates.
+                Cascade::from_workspace_network(
+                    &call_context.host_context.workspace(),
+                    call_context.host_context.network().clone(),
+                )
+                .retrieve_blob(input, NetworkGetOptions::must_get_options())
+                .await
+            })
+            .map_err(|cascade_error| -> RuntimeError {
+                wasm_error!(WasmErrorInner::Host(cascade_error.to_string())).into()
+            })?;
+            Ok(blob.map(holochain_types::prelude::Bytes::from))
+        }
+        _ => Err(wasm_error!(WasmErrorInner::Host(
+            RibosomeError::HostFnPermissions(
+                call_context.zome.zome_name().clone(),
+                call_context.function_name().clone(),
+                "read_blob".into(),
+            )
+            .to_string(),
+        ))
+        .into()),
+    }
+}
@@ -4,8 +4,10 @@ use crate::core::ribosome::RibosomeError;
 use crate::core::ribosome::RibosomeT;
 use holochain_p2p::HolochainP2pDnaT;
 use holochain_types::access::Permission;
+use holochain_util::tokio_helper;
 use holochain_wasmer_host::prelude::*;
 use holochain_zome_types::signal::RemoteSignal;
+use holochain_zome_types::signal::RemoteSignalDelivery;
 use holochain_zome_types::zome::FunctionName;
 use std::sync::Arc;
 use tracing::Instrument;
@@ -25,23 +27,47 @@ pub fn remote_signal(
             const FN_NAME: &str = "recv_remote_signal";
             let from_agent = super::agent_info::agent_info(_ribosome, call_context.clone(), ())?
                 .agent_latest_pubkey;
-            // Timeouts and errors are ignored,
-            // this is a send and forget operation.
             let network = call_context.host_context().network().clone();
-            let RemoteSignal { agents, signal } = input;
+            let RemoteSignal {
+                agents,
+                signal,
+                delivery,
+            } = input;
             let zome_name = call_context.zome().zome_name().clone();
             let fn_name: FunctionName = FN_NAME.into();
-            tokio::task::spawn(
-                async move {
-                    if let Err(e) = network
-                        .remote_signal(from_agent, agents, zome_name, fn_name, None, signal)
-                        .await
-                    {
-                        tracing::info!("Failed to send remote signals because of {:?}", e);
-                    }
+            match delivery {
+                // Timeouts and errors are ignored,
+                // this is a send and forget operation.
+                RemoteSignalDelivery::BestEffort => {
+                    tokio::task::spawn(
+                        async move {
+                            if let Err(e) = network
+                                .remote_signal(
+                                    from_agent, agents, zome_name, fn_name, None, signal, true,
+                                )
+                                .await
+                            {
+                                tracing::info!("Failed to send remote signals because of {:?}", e);
+                            }
+                        }
+                        .in_current_span(),
+                    );
                 }
-                .in_current_span(),
-            );
+                // The network is waited on rather than dropping the signal,
+                // and any error is returned to the zome.
+                RemoteSignalDelivery::Batched => {
+                    tokio_helper::block_forever_on(call_context.deadline().run(async move {
+                        network
+                            .remote_signal(
+                                from_agent, agents, zome_name, fn_name, None, signal, false,
+                            )
+                            .await
+                    }))?
+                    .map_err(|e| -> RuntimeError {
+                        wasm_error!(WasmErrorInner::Host(e.to_string())).into()
+                    })?;
+                }
+            }
             Ok(())
         }
         _ => Err(wasm_error!(WasmErrorInner::Host(
@@ -51,7 +77,8 @@ pub fn remote_signal(
                 "remote_signal".into(),
             )
             .to_string(),
-        )).into()),
+        ))
+        .into()),
     }
 }
 
@@ -61,10 +88,37 @@ mod tests {
     use std::sync::atomic::Ordering;
 
     use super::*;
+    use crate::core::ribosome::HostContext;
+    use crate::fixt::CallContextFixturator;
+    use crate::fixt::RealRibosomeFixturator;
+    use crate::fixt::ZomeCallHostAccessFixturator;
     use crate::sweettest::*;
+    use ::fixt::prelude::*;
     use futures::future;
     use hdk::prelude::*;
     use holochain_types::inline_zome::InlineZomeSet;
+    use holochain_zome_types::zome::inline_zome::error::InlineZomeError;
+    use holochain_zome_types::zome::inline_zome::BoxApi;
+    use matches::assert_matches;
+
+    /// Grant everyone the capability to call `recv_remote_signal`.
+    fn grant_recv_remote_signal(api: &BoxApi) {
+        let mut functions: GrantedFunctions = BTreeSet::new();
+        functions.insert((api.zome_info(()).unwrap().name, "recv_remote_signal".into()));
+        let cap_grant_entry = CapGrantEntry {
+            tag: "".into(),
+            // empty access converts to unrestricted
+            access: ().into(),
+            functions,
+        };
+        api.create(CreateInput::new(
+            EntryDefLocation::CapGrant,
+            EntryVisibility::Private,
+            Entry::CapGrant(cap_grant_entry),
+            ChainTopOrdering::default(),
+        ))
+        .unwrap();
+    }
 
     fn zome(agents: Vec<AgentPubKey>, num_signals: Arc<AtomicUsize>) -> InlineZomeSet {
         let entry_def = EntryDef::default_with_id("entrydef");
@@ -75,6 +129,7 @@ mod tests {
                 let signal = RemoteSignal {
                     agents: agents.clone(),
                     signal,
+                    delivery: RemoteSignalDelivery::BestEffort,
                 };
                 tracing::debug!("sending signal to {:?}", agents);
                 api.remote_signal(signal)?;
@@ -86,22 +141,37 @@ mod tests {
                 api.emit_signal(AppSignal::new(signal)).map_err(Into::into)
             })
             .callback("init", move |api, ()| {
-                let mut functions: GrantedFunctions = BTreeSet::new();
-                functions.insert((api.zome_info(()).unwrap().name, "recv_remote_signal".into()));
-                let cap_grant_entry = CapGrantEntry {
-                    tag: "".into(),
-                    // empty access converts to unrestricted
-                    access: ().into(),
-                    functions,
-                };
-                api.create(CreateInput::new(
-                    EntryDefLocation::CapGrant,
-                    EntryVisibility::Private,
-                    Entry::CapGrant(cap_grant_entry),
-                    ChainTopOrdering::default(),
-                ))
-                .unwrap();
+                grant_recv_remote_signal(&api);
+                Ok(InitCallbackResult::Pass)
+            })
+            .into()
+    }
 
+    /// A zome whose `ungranted` agent doesn't grant the capability to call
+    /// `recv_remote_signal`, and whose `failing` agent fails to receive
+    /// signals.
+    fn acknowledged_zome(ungranted: AgentPubKey, failing: AgentPubKey) -> InlineZomeSet {
+        let entry_def = EntryDef::default_with_id("entrydef");
+
+        SweetEasyInline::new(vec![entry_def], 0)
+            .callback("signal_acknowledged", |api, agents: Vec<AgentPubKey>| {
+                let receipts = api.remote_signal_acknowledged(RemoteSignal {
+                    agents,
+                    signal: ExternIO::encode("Hey").unwrap(),
+                    delivery: RemoteSignalDelivery::BestEffort,
+                })?;
+                Ok(receipts)
+            })
+            .callback("recv_remote_signal", move |api, _signal: ExternIO| {
+                if api.agent_info(())?.agent_latest_pubkey == failing {
+                    return Err(InlineZomeError::TestError("refused".into()));
+                }
+                Ok(())
+            })
+            .callback("init", move |api, ()| {
+                if api.agent_info(())?.agent_latest_pubkey != ungranted {
+                    grant_recv_remote_signal(&api);
+                }
                 Ok(InitCallbackResult::Pass)
             })
             .into()
@@ -156,4 +226,87 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    #[cfg(feature = "test_utils")]
+    async fn remote_signal_acknowledged_test() {
+        observability::test_run().ok();
+        let mut conductors = SweetConductorBatch::from_standard_config(4).await;
+
+        let agents =
+            future::join_all(conductors.iter().map(|c| SweetAgents::one(c.keystore()))).await;
+        let (sender, delivered, unauthorized, failed) = (
+            agents[0].clone(),
+            agents[1].clone(),
+            agents[2].clone(),
+            agents[3].clone(),
+        );
+
+        let (dna_file, _, _) = SweetDnaFile::unique_from_inline_zomes(acknowledged_zome(
+            unauthorized.clone(),
+            failed.clone(),
+        ))
+        .await
+        .unwrap();
+
+        let apps = conductors
+            .setup_app_for_zipped_agents("app", &agents, &[dna_file.into()])
+            .await
+            .unwrap();
+
+        conductors.exchange_peer_info().await;
+
+        let cells: Vec<_> = apps.cells_flattened();
+        assert_eq!(cells[0].agent_pubkey(), &sender);
+
+        let receipts: Vec<RemoteSignalReceipt> = conductors[0]
+            .call(
+                &cells[0].zome(SweetEasyInline::COORDINATOR),
+                "signal_acknowledged",
+                vec![delivered.clone(), unauthorized.clone(), failed.clone()],
+            )
+            .await;
+
+        // A receipt for every agent, in the order they were given.
+        let agents: Vec<_> = receipts.iter().map(|r| r.agent.clone()).collect();
+        assert_eq!(agents, vec![delivered, unauthorized, failed]);
+        assert_eq!(receipts[0].status, RemoteSignalStatus::Delivered);
+        assert_eq!(receipts[1].status, RemoteSignalStatus::Unauthorized);
+        assert_matches!(receipts[2].status, RemoteSignalStatus::Failed(_));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn batched_remote_signal_returns_network_errors() {
+        let ribosome = Arc::new(
+            RealRibosomeFixturator::new(crate::fixt::curve::Zomes(vec![]))
+                .next()
+                .unwrap(),
+        );
+        let mut call_context = CallContextFixturator::new(::fixt::Unpredictable)
+            .next()
+            .unwrap();
+        // The fixture's network is a stub which fails every request.
+        call_context.host_context = HostContext::ZomeCall(fixt!(ZomeCallHostAccess, Predictable));
+        let call_context = Arc::new(call_context);
+        let signal = |delivery| RemoteSignal {
+            agents: vec![fixt!(AgentPubKey)],
+            signal: ExternIO::encode("Hey").unwrap(),
+            delivery,
+        };
+
+        // A best effort signal doesn't wait to hear that it failed.
+        super::remote_signal(
+            ribosome.clone(),
+            call_context.clone(),
+            signal(RemoteSignalDelivery::BestEffort),
+        )
+        .unwrap();
+        // A batched one returns the error to the zome.
+        assert!(super::remote_signal(
+            ribosome,
+            call_context,
+            signal(RemoteSignalDelivery::Batched)
+        )
+        .is_err());
+    }
 }
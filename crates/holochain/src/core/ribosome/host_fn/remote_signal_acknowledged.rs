@@ -0,0 +1,82 @@
+use crate::core::ribosome::CallContext;
+use crate::core::ribosome::HostFnAccess;
+use crate::core::ribosome::RibosomeError;
+use crate::core::ribosome::RibosomeT;
+use futures::future::join_all;
+use holochain_p2p::HolochainP2pDnaT;
+use holochain_types::prelude::*;
+use holochain_wasmer_host::prelude::*;
+use std::sync::Arc;
+
+/// Call every agent's `recv_remote_signal` with the signal, with a remote
+/// call to each agent made concurrently, and report what became of it for
+/// each of them.
+#[tracing::instrument(skip(_ribosome, call_context, input))]
+pub fn remote_signal_acknowledged(
+    _ribosome: Arc<impl RibosomeT>,
+    call_context: Arc<CallContext>,
+    input: RemoteSignal,
+) -> Result<Vec<RemoteSignalReceipt>, RuntimeError> {
+    match HostFnAccess::from(&call_context.host_context()) {
+        HostFnAccess {
+            write_network: Permission::Allow,
+            agent_info: Permission::Allow,
+            ..
+        } => {
+            const FN_NAME: &str = "recv_remote_signal";
+            let from_agent = super::agent_info::agent_info(_ribosome, call_context.clone(), ())?
+                .agent_latest_pubkey;
+            let network = call_context.host_context().network().clone();
+            let RemoteSignal { agents, signal, .. } = input;
+            let zome_name = call_context.zome().zome_name().clone();
+            let fn_name: FunctionName = FN_NAME.into();
+            tokio_helper::block_forever_on(call_context.deadline().run(async move {
+                join_all(agents.into_iter().map(|agent| {
+                    let network = network.clone();
+                    let from_agent = from_agent.clone();
+                    let zome_name = zome_name.clone();
+                    let fn_name = fn_name.clone();
+                    let signal = signal.clone();
+                    async move {
+                        let result = network
+                            .call_remote(
+                                from_agent,
+                                agent.clone(),
+                                zome_name,
+                                fn_name,
+                                None,
+                                signal,
+                            )
+                            .await;
+                        let status = match result.map(ZomeCallResponse::try_from) {
+                            Ok(Ok(ZomeCallResponse::Ok(_))) => RemoteSignalStatus::Delivered,
+                            Ok(Ok(ZomeCallResponse::Unauthorized(..))) => {
+                                RemoteSignalStatus::Unauthorized
+                            }
+                            Ok(Ok(ZomeCallResponse::NetworkError(e))) => {
+                                RemoteSignalStatus::Failed(e)
+                            }
+                            Ok(Ok(response)) => RemoteSignalStatus::Failed(format!(
+                                "unexpected response {:?}",
+                                response
+                            )),
+                            Ok(Err(e)) => RemoteSignalStatus::Failed(e.to_string()),
+                            Err(e) => RemoteSignalStatus::Failed(e.to_string()),
+                        };
+                        RemoteSignalReceipt { agent, status }
+                    }
+                }))
+                .await
+            }))
+        }
+        _ => Err(wasm_error!(WasmErrorInner::Host(
+            RibosomeError::HostFnPermissions(
+                call_context.zome.zome_name().clone(),
+                call_context.function_name().clone(),
+                "remote_signal_acknowledged".into(),
+            )
+            .to_string(),
+        ))
+        .into()),
+    }
+}
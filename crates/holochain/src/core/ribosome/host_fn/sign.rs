@@ -15,13 +15,13 @@ pub fn sign(
         HostFnAccess {
             keystore: Permission::Allow,
             ..
-        } => tokio_helper::block_forever_on(async move {
+        } => tokio_helper::block_forever_on(call_context.deadline().run(async move {
             call_context
                 .host_context
                 .keystore()
                 .sign(input.key, input.data.into_vec().into())
                 .await
-        })
+        }))?
         .map_err(|keystore_error| -> RuntimeError {
             wasm_error!(WasmErrorInner::Host(keystore_error.to_string())).into()
         }),
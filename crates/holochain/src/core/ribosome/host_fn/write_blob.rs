@@ -0,0 +1,111 @@
+use crate::core::ribosome::CallContext;
+use crate::core::ribosome::HostFnAccess;
+use crate::core::ribosome::RibosomeError;
+use crate::core::ribosome::RibosomeT;
+use holochain_types::prelude::*;
+use holochain_wasmer_host::prelude::*;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// Commit a blob as chunks, each created by its own action so the chunks
+/// are published as separate ops.
+#[allow(clippy::extra_unused_lifetimes)]
+pub fn write_blob<'a>(
+    _ribosome: Arc<impl RibosomeT>,
+    call_context: Arc<CallContext>,
+    input: holochain_types::prelude::Bytes,
+) -> Result<BlobManifest, RuntimeError> {
+    match HostFnAccess::from(&call_context.host_context()) {
+        HostFnAccess {
+            write_workspace: Permission::Allow,
+            ..
+        } => {
+            let blob = input.into_vec();
+            let chunks: Vec<EntryHashed> = Chunk::split(&blob)
+                .map(|chunk| EntryHashed::from_content_sync(Entry::Chunk(chunk)))
+                .collect();
+            let manifest = BlobManifest {
+                size: blob.len() as u64,
+                chunks: chunks.iter().map(|chunk| chunk.as_hash().clone()).collect(),
+            };
+
+            tokio_helper::block_forever_on(async move {
+                let workspace = call_context.host_context.workspace_write();
+                let source_chain = workspace
+                    .source_chain()
+                    .as_ref()
+                    .expect("Must have source chain if write_workspace access is given");
+                let mut committed = HashSet::new();
+                for chunk in chunks {
+                    let (entry, entry_hash) = chunk.into_inner();
+                    // A chunk which repeats within the blob is only committed once.
+                    if !committed.insert(entry_hash.clone()) {
+                        continue;
+                    }
+                    let action_builder = builder::Create {
+                        entry_type: EntryType::Chunk,
+                        entry_hash,
+                    };
+                    source_chain
+                        .put_weightless(action_builder, Some(entry), ChainTopOrdering::default())
+                        .await
+                        .map_err(|source_chain_error| -> RuntimeError {
+                            wasm_error!(WasmErrorInner::Host(source_chain_error.to_string())).into()
+                        })?;
+                }
+                Ok(manifest)
+            })
+        }
+        _ => Err(wasm_error!(WasmErrorInner::Host(
+            RibosomeError::HostFnPermissions(
+                call_context.zome.zome_name().clone(),
+                call_context.function_name().clone(),
+                "write_blob".into(),
+            )
+            .to_string(),
+        ))
+        .into()),
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "slow_tests")]
+pub mod wasm_test {
+    use crate::core::ribosome::wasm_test::RibosomeTestFixture;
+    use crate::test_utils::consistency_10s;
+    use hdk::prelude::*;
+    use holochain_wasm_test_utils::TestWasm;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn ribosome_write_and_read_blob() {
+        observability::test_run().ok();
+        let RibosomeTestFixture {
+            conductor,
+            alice,
+            bob,
+            alice_cell,
+            bob_cell,
+            ..
+        } = RibosomeTestFixture::new(TestWasm::Create).await;
+
+        // Two full chunks, which are identical, and a partial one.
+        let blob: Vec<u8> = std::iter::repeat(7)
+            .take(CHUNK_SIZE_LIMIT * 2)
+            .chain(std::iter::repeat(8).take(10))
+            .collect();
+        let manifest: BlobManifest = conductor
+            .call(&alice, "commit_blob", Bytes::from(blob.clone()))
+            .await;
+        assert_eq!(manifest.size, blob.len() as u64);
+        assert_eq!(manifest.chunks.len(), 3);
+        assert_eq!(manifest.chunks[0], manifest.chunks[1]);
+
+        let read: Option<Bytes> = conductor.call(&alice, "get_blob", manifest.clone()).await;
+        assert_eq!(read.unwrap().into_vec(), blob);
+
+        // The chunks pass validation and can be read from their authorities.
+        consistency_10s(&[&alice_cell, &bob_cell]).await;
+        let read: Option<Bytes> = conductor.call(&bob, "get_blob", manifest).await;
+        assert_eq!(read.unwrap().into_vec(), blob);
+    }
+}
@@ -0,0 +1,32 @@
+//! Pooling of ready wasm instances, configured with [`InstancePoolConfig`].
+//!
+//! Idle instances live in the instance cache of `holochain_wasmer_host`,
+//! keyed by their zome's wasm and DNA followed by the context key the
+//! instance was created with, so the idle instances of a zome sit in one
+//! range of keys. The [`RealRibosome`](super::real_ribosome::RealRibosome)
+//! takes instances from that range for calls, creates them ahead of time,
+//! and drops the range when a zome's wasm is replaced.
+
+use holochain_conductor_api::config::conductor::InstancePoolConfig;
+use once_cell::sync::OnceCell;
+
+static CONFIG: OnceCell<InstancePoolConfig> = OnceCell::new();
+
+/// Pool instances with this config.
+/// The pool is shared by the whole process, so only the first config set
+/// is used.
+pub fn configure(config: InstancePoolConfig) {
+    if let Err(config) = CONFIG.set(config) {
+        tracing::debug!(?config, "The instance pool was already configured");
+    }
+}
+
+/// How many instances of each zome to create ahead of the first call.
+pub(crate) fn prewarm() -> usize {
+    CONFIG.get().map_or(0, |config| config.prewarm)
+}
+
+/// The most idle instances to keep for each zome, if there is a limit.
+pub(crate) fn max_idle() -> Option<usize> {
+    CONFIG.get().map(InstancePoolConfig::max_idle)
+}
@@ -0,0 +1,158 @@
+//! A cache of compiled zome wasm on disk, so each wasm is compiled once
+//! rather than every time the conductor starts.
+//!
+//! Compiled modules are serialized to `<wasm hash>.module` when they are
+//! first compiled, and memory mapped and deserialized on later starts.
+//! The files live in a directory named after [`ARTIFACT_VERSION`], so
+//! artifacts compiled with different settings are never loaded.
+//! Without a directory, modules are only cached in memory.
+//!
+//! Every module is compiled into a store with the [`LimitingTunables`], so
+//! its instances are created with the configured memory ceiling.
+
+use super::error::RibosomeResult;
+use super::real_ribosome::RealRibosome;
+use super::zome_call_limits::LimitingTunables;
+use holochain_wasmer_host::module::PlruCache;
+use holochain_wasmer_host::module::MODULE_CACHE;
+use holochain_wasmer_host::prelude::*;
+use once_cell::sync::OnceCell;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Must change whenever the wasmer version or the
+/// [`cranelift`](RealRibosome::cranelift) settings do.
+const ARTIFACT_VERSION: &str = "wasmer-2.2.0-metered-1";
+
+static CACHE_DIR: OnceCell<PathBuf> = OnceCell::new();
+
+/// Persist compiled modules in this directory.
+/// The cache is shared by the whole process, so only the first directory
+/// set is used.
+pub fn set_cache_dir(dir: &Path) {
+    let dir = dir.join(ARTIFACT_VERSION);
+    if let Err(dir) = CACHE_DIR.set(dir) {
+        tracing::debug!(?dir, "The wasm module cache directory was already set");
+    }
+}
+
+/// The directory compiled modules are persisted in, if there is one.
+pub(crate) fn cache_dir() -> Option<&'static Path> {
+    CACHE_DIR.get().map(PathBuf::as_path)
+}
+
+/// Get the module from memory, else from disk, else compile it and persist
+/// it to disk if there is a directory to persist it in.
+pub(crate) fn get(dir: Option<&Path>, key: [u8; 32], wasm: &[u8]) -> RibosomeResult<Arc<Module>> {
+    // Hold the lock while loading, so a module is only compiled once.
+    let mut cache = MODULE_CACHE.write();
+    if let Some(module) = cache.get_item(&key) {
+        return Ok(module);
+    }
+
+    let store = Store::new_with_tunables(
+        &Universal::new(RealRibosome::cranelift()).engine(),
+        LimitingTunables::new(),
+    );
+    let module = match dir {
+        Some(dir) => {
+            let path = dir.join(format!("{}.module", file_stem(&key)));
+            match load(&store, &path) {
+                Ok(Some(module)) => module,
+                Ok(None) => compile_and_persist(&store, dir, &path, wasm)?,
+                Err(e) => {
+                    tracing::warn!(?path, ?e, "Recompiling unreadable compiled wasm");
+                    compile_and_persist(&store, dir, &path, wasm)?
+                }
+            }
+        }
+        None => compile(&store, wasm)?,
+    };
+    Ok(cache.put_item(key, Arc::new(module)))
+}
+
+fn file_stem(key: &[u8; 32]) -> String {
+    key.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Load a persisted module, or `None` if there isn't one.
+fn load(store: &Store, path: &Path) -> Result<Option<Module>, Box<dyn std::error::Error>> {
+    let file = match std::fs::File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    // Safe as the file is only ever created whole by `persist`, by renaming
+    // it into place, and is never modified after that.
+    let mmap = unsafe { memmap2::Mmap::map(&file)? };
+    // Safe as the artifact was serialized by `persist` with the same engine
+    // settings, which is what `ARTIFACT_VERSION` guards.
+    let module = unsafe { Module::deserialize(store, &mmap[..])? };
+    Ok(Some(module))
+}
+
+fn compile(store: &Store, wasm: &[u8]) -> RibosomeResult<Module> {
+    Ok(Module::from_binary(store, wasm)
+        .map_err(|e| RuntimeError::new(wasm_error!(WasmErrorInner::Compile(e.to_string()))))?)
+}
+
+fn compile_and_persist(
+    store: &Store,
+    dir: &Path,
+    path: &Path,
+    wasm: &[u8],
+) -> RibosomeResult<Module> {
+    let module = compile(store, wasm)?;
+    // The module can still be used if it couldn't be persisted.
+    if let Err(e) = persist(&module, dir, path) {
+        tracing::warn!(?path, ?e, "Failed to persist compiled wasm");
+    }
+    Ok(module)
+}
+
+fn persist(module: &Module, dir: &Path, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let bytes = module.serialize()?;
+    std::fs::create_dir_all(dir)?;
+    let mut file = tempfile::NamedTempFile::new_in(dir)?;
+    file.write_all(&bytes)?;
+    file.as_file().sync_all()?;
+    file.persist(path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A module exporting `answer`, which returns 42.
+    const ANSWER_WASM: &[u8] = &[
+        0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, // header
+        0x01, 0x05, 0x01, 0x60, 0x00, 0x01, 0x7f, // type: () -> i32
+        0x03, 0x02, 0x01, 0x00, // function
+        0x07, 0x0a, 0x01, 0x06, b'a', b'n', b's', b'w', b'e', b'r', 0x00, 0x00, // export
+        0x0a, 0x06, 0x01, 0x04, 0x00, 0x41, 0x2a, 0x0b, // code: i32.const 42
+    ];
+
+    #[test]
+    fn persisted_modules_are_reloaded() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("module");
+        let store = Store::new(&Universal::new(RealRibosome::cranelift()).engine());
+
+        assert!(load(&store, &path).unwrap().is_none());
+        compile_and_persist(&store, dir.path(), &path, ANSWER_WASM).unwrap();
+        let module = load(&store, &path).unwrap().unwrap();
+        let instance = Instance::new(&module, &imports! {}).unwrap();
+        let answer = instance
+            .exports
+            .get_native_function::<(), i32>("answer")
+            .unwrap();
+        assert_eq!(answer.call().unwrap(), 42);
+
+        // A corrupted artifact is an error rather than being loaded.
+        std::fs::write(&path, b"not a module").unwrap();
+        assert!(load(&store, &path).is_err());
+    }
+}
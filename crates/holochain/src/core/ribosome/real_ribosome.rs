@@ -5,6 +5,7 @@ use super::guest_callback::post_commit::PostCommitHostAccess;
 use super::guest_callback::validate::ValidateHostAccess;
 use super::guest_callback::validation_package::ValidationPackageHostAccess;
 use super::host_fn::get_agent_activity::get_agent_activity;
+use super::host_fn::get_agent_activity_page::get_agent_activity_page;
 use super::host_fn::HostFnApi;
 use super::HostContext;
 use super::ZomeCallHostAccess;
@@ -27,6 +28,7 @@ use crate::core::ribosome::guest_callback::validation_package::ValidationPackage
 use crate::core::ribosome::guest_callback::CallIterator;
 use crate::core::ribosome::host_fn::accept_countersigning_preflight_request::accept_countersigning_preflight_request;
 use crate::core::ribosome::host_fn::agent_info::agent_info;
+use crate::core::ribosome::host_fn::aggregate_links::aggregate_links;
 use crate::core::ribosome::host_fn::call::call;
 use crate::core::ribosome::host_fn::call_info::call_info;
 use crate::core::ribosome::host_fn::capability_claims::capability_claims;
@@ -38,8 +40,11 @@ use crate::core::ribosome::host_fn::create_x25519_keypair::create_x25519_keypair
 use crate::core::ribosome::host_fn::delete::delete;
 use crate::core::ribosome::host_fn::delete_link::delete_link;
 use crate::core::ribosome::host_fn::dna_info::dna_info;
+use crate::core::ribosome::host_fn::dry_run_sys_validation::dry_run_sys_validation;
 use crate::core::ribosome::host_fn::emit_signal::emit_signal;
+use crate::core::ribosome::host_fn::exists::exists;
 use crate::core::ribosome::host_fn::get::get;
+use crate::core::ribosome::host_fn::get_action::get_action;
 use crate::core::ribosome::host_fn::get_details::get_details;
 use crate::core::ribosome::host_fn::get_link_details::get_link_details;
 use crate::core::ribosome::host_fn::get_links::get_links;
@@ -49,8 +54,12 @@ use crate::core::ribosome::host_fn::must_get_agent_activity::must_get_agent_acti
 use crate::core::ribosome::host_fn::must_get_entry::must_get_entry;
 use crate::core::ribosome::host_fn::must_get_valid_record::must_get_valid_record;
 use crate::core::ribosome::host_fn::query::query;
+use crate::core::ribosome::host_fn::query_page::query_page;
 use crate::core::ribosome::host_fn::random_bytes::random_bytes;
+use crate::core::ribosome::host_fn::read_blob::read_blob;
 use crate::core::ribosome::host_fn::remote_signal::remote_signal;
+use crate::core::ribosome::host_fn::remote_signal_acknowledged::remote_signal_acknowledged;
+use crate::core::ribosome::host_fn::runtime_properties::runtime_properties;
 use crate::core::ribosome::host_fn::schedule::schedule;
 use crate::core::ribosome::host_fn::sign::sign;
 use crate::core::ribosome::host_fn::sign_ephemeral::sign_ephemeral;
@@ -60,6 +69,7 @@ use crate::core::ribosome::host_fn::trace::trace;
 use crate::core::ribosome::host_fn::update::update;
 use crate::core::ribosome::host_fn::verify_signature::verify_signature;
 use crate::core::ribosome::host_fn::version::version;
+use crate::core::ribosome::host_fn::write_blob::write_blob;
 use crate::core::ribosome::host_fn::x_25519_x_salsa20_poly1305_decrypt::x_25519_x_salsa20_poly1305_decrypt;
 use crate::core::ribosome::host_fn::x_25519_x_salsa20_poly1305_encrypt::x_25519_x_salsa20_poly1305_encrypt;
 use crate::core::ribosome::host_fn::x_salsa20_poly1305_decrypt::x_salsa20_poly1305_decrypt;
@@ -68,23 +78,28 @@ use crate::core::ribosome::host_fn::x_salsa20_poly1305_shared_secret_create_rand
 use crate::core::ribosome::host_fn::x_salsa20_poly1305_shared_secret_export::x_salsa20_poly1305_shared_secret_export;
 use crate::core::ribosome::host_fn::x_salsa20_poly1305_shared_secret_ingest::x_salsa20_poly1305_shared_secret_ingest;
 use crate::core::ribosome::host_fn::zome_info::zome_info;
+use crate::core::ribosome::instance_pool;
+use crate::core::ribosome::module_cache;
 use crate::core::ribosome::real_ribosome::wasmparser::Operator as WasmOperator;
+use crate::core::ribosome::zome_call_limits;
+use crate::core::ribosome::zome_call_limits::ZomeCallDeadline;
+use crate::core::ribosome::zome_call_limits::ZomeCallLimit;
 use crate::core::ribosome::CallContext;
 use crate::core::ribosome::Invocation;
 use crate::core::ribosome::RibosomeT;
 use crate::core::ribosome::ZomeCallInvocation;
 use fallible_iterator::FallibleIterator;
+use holochain_conductor_api::config::conductor::ZomeCallLimitsConfig;
 use holochain_types::prelude::*;
-use holochain_wasmer_host::module::SerializedModuleCache;
 use wasmer_middlewares::Metering;
 // This is here because there were errors about different crate versions
 // without it.
-use kitsune_p2p_types::dependencies::lair_keystore_api::dependencies::parking_lot::lock_api::RwLock;
 
 use holochain_types::zome_types::GlobalZomeTypes;
 use holochain_types::zome_types::ZomeTypesError;
 use holochain_wasmer_host::prelude::*;
 use once_cell::sync::Lazy;
+use once_cell::sync::OnceCell;
 use std::collections::HashMap;
 use std::sync::atomic::AtomicU64;
 use std::sync::Arc;
@@ -106,6 +121,10 @@ pub struct RealRibosome {
 
     /// Dependencies for every zome.
     pub zome_dependencies: Arc<HashMap<ZomeName, Vec<ZomeId>>>,
+
+    /// Entry defs of each integrity zome, in [`ZomeId`] order.
+    /// Filled by the `entry_defs` callbacks the first time they are needed.
+    pub entry_defs: Arc<OnceCell<Vec<EntryDefs>>>,
 }
 
 struct HostFnBuilder {
@@ -223,6 +242,14 @@ fn instance_cache_key(wasm_hash: &WasmHash, dna_hash: &DnaHash, context_key: u64
     bits
 }
 
+/// The range of instance cache keys for every context key.
+fn instance_key_range(
+    wasm_hash: &WasmHash,
+    dna_hash: &DnaHash,
+) -> std::ops::RangeInclusive<[u8; 32]> {
+    instance_cache_key(wasm_hash, dna_hash, 0)..=instance_cache_key(wasm_hash, dna_hash, u64::MAX)
+}
+
 /// Get the context key back from the end of the instance cache key.
 fn context_key_from_key(key: &[u8; 32]) -> u64 {
     let mut bits = [0u8; 8];
@@ -241,6 +268,7 @@ impl RealRibosome {
             dna_file,
             zome_types: Default::default(),
             zome_dependencies: Default::default(),
+            entry_defs: Default::default(),
         };
 
         // Collect the number of entry and link types
@@ -326,6 +354,7 @@ impl RealRibosome {
             dna_file: ribosome.dna_file,
             zome_types,
             zome_dependencies: Arc::new(zome_dependencies),
+            entry_defs: Default::default(),
         })
     }
 
@@ -335,30 +364,47 @@ impl RealRibosome {
             dna_file,
             zome_types: Default::default(),
             zome_dependencies: Default::default(),
+            entry_defs: Default::default(),
         }
     }
 
-    pub fn module(&self, zome_name: &ZomeName) -> RibosomeResult<Arc<Module>> {
-        if holochain_wasmer_host::module::SERIALIZED_MODULE_CACHE
-            .get()
-            .is_none()
-        {
-            holochain_wasmer_host::module::SERIALIZED_MODULE_CACHE
-                .set(RwLock::new(SerializedModuleCache::default_with_cranelift(
-                    Self::cranelift,
-                )))
-                // An error here means the cell is full when we tried to set it, so
-                // some other thread must have done something in between the get
-                // above and the set here. In this case we don't care as we don't
-                // have any competing code paths that could set it to something
-                // unexpected.
-                .ok();
-        }
+    /// Get the entry defs of each integrity zome, in [`ZomeId`] order.
+    /// The `entry_defs` callbacks are only run the first time.
+    pub fn entry_defs(&self) -> RibosomeResult<&[EntryDefs]> {
+        let entry_defs = self.entry_defs.get_or_try_init(|| {
+            let mut defs = match self.run_entry_defs(EntryDefsHostAccess, EntryDefsInvocation)? {
+                EntryDefsResult::Defs(defs) => defs,
+                EntryDefsResult::Err(zome, error_string) => {
+                    return Err(RibosomeError::EntryDefs(zome, error_string))
+                }
+            };
+            Ok(self
+                .dna_def()
+                .integrity_zomes
+                .iter()
+                .map(|(zome_name, _)| defs.remove(zome_name).unwrap_or(EntryDefs(vec![])))
+                .collect())
+        })?;
+        Ok(entry_defs)
+    }
 
-        Ok(holochain_wasmer_host::module::MODULE_CACHE.write().get(
+    pub fn module(&self, zome_name: &ZomeName) -> RibosomeResult<Arc<Module>> {
+        module_cache::get(
+            module_cache::cache_dir(),
             self.wasm_cache_key(zome_name)?,
             &*self.dna_file.get_wasm_for_zome(zome_name)?.code(),
-        )?)
+        )
+    }
+
+    /// Compile the wasm of every zome up front, so the first call to each
+    /// zome doesn't have to wait for it.
+    pub fn precompile(&self) -> RibosomeResult<()> {
+        for (zome_name, zome_def) in self.dna_def().all_zomes() {
+            if let ZomeDef::Wasm(_) = zome_def {
+                self.module(zome_name)?;
+            }
+        }
+        Ok(())
     }
 
     pub fn wasm_cache_key(&self, zome_name: &ZomeName) -> Result<[u8; 32], DnaError> {
@@ -392,67 +438,111 @@ impl RealRibosome {
         {
             CONTEXT_MAP.lock().remove(&context_key);
         }
-        let key = instance_cache_key(
-            &self
-                .dna_file
-                .dna()
-                .get_wasm_zome(zome_name)
-                .map_err(DnaError::from)?
-                .wasm_hash,
-            self.dna_file.dna_hash(),
-            context_key,
-        );
-        holochain_wasmer_host::module::INSTANCE_CACHE
-            .write()
-            .put_item(key, instance);
+        let wasm_hash = self.zome_wasm_hash(zome_name)?;
+        let key = instance_cache_key(&wasm_hash, self.dna_file.dna_hash(), context_key);
+        let mut cache = holochain_wasmer_host::module::INSTANCE_CACHE.write();
+        // Drop the instance rather than growing the pool past its limit.
+        if let Some(max_idle) = instance_pool::max_idle() {
+            let idle = cache
+                .cache()
+                .range(self.instance_key_range(&wasm_hash))
+                .count();
+            if idle >= max_idle {
+                return Ok(());
+            }
+        }
+        cache.put_item(key, instance);
 
         Ok(())
     }
 
+    /// Create instances of every wasm zome until each has the configured
+    /// number of idle instances ready for calls.
+    pub fn fill_instance_pool(&self) -> RibosomeResult<()> {
+        use holochain_wasmer_host::module::PlruCache;
+        let prewarm = instance_pool::prewarm();
+        if prewarm == 0 {
+            return Ok(());
+        }
+        for (zome_name, zome_def) in self.dna_def().all_zomes() {
+            if let ZomeDef::Wasm(_) = zome_def {
+                let wasm_hash = self.zome_wasm_hash(zome_name)?;
+                let idle = holochain_wasmer_host::module::INSTANCE_CACHE
+                    .read()
+                    .cache()
+                    .range(self.instance_key_range(&wasm_hash))
+                    .count();
+                for _ in idle..prewarm {
+                    let context_key = CONTEXT_KEY.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                    let instance = self.new_instance(zome_name, context_key)?;
+                    let key = instance_cache_key(&wasm_hash, self.dna_file.dna_hash(), context_key);
+                    holochain_wasmer_host::module::INSTANCE_CACHE
+                        .write()
+                        .put_item(key, instance);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Drop the idle instances of wasms which this DNA no longer uses,
+    /// e.g. the coordinators replaced by an update.
+    pub fn drop_instances(&self, wasm_hashes: &[WasmHash]) {
+        use holochain_wasmer_host::module::PlruCache;
+        let mut cache = holochain_wasmer_host::module::INSTANCE_CACHE.write();
+        for wasm_hash in wasm_hashes {
+            let keys: Vec<_> = cache
+                .cache()
+                .range(self.instance_key_range(wasm_hash))
+                .map(|(key, _)| *key)
+                .collect();
+            for key in keys {
+                cache.remove_item(&key);
+            }
+        }
+    }
+
+    fn zome_wasm_hash(&self, zome_name: &ZomeName) -> RibosomeResult<WasmHash> {
+        Ok(self
+            .dna_file
+            .dna()
+            .get_wasm_zome(zome_name)
+            .map_err(DnaError::from)?
+            .wasm_hash
+            .clone())
+    }
+
+    /// The instance cache keys of every instance of this wasm in this DNA.
+    fn instance_key_range(&self, wasm_hash: &WasmHash) -> std::ops::RangeInclusive<[u8; 32]> {
+        instance_key_range(wasm_hash, self.dna_file.dna_hash())
+    }
+
+    fn new_instance(
+        &self,
+        zome_name: &ZomeName,
+        context_key: u64,
+    ) -> RibosomeResult<Arc<Mutex<Instance>>> {
+        let module = self.module(zome_name)?;
+        let imports: ImportObject = Self::imports(self, context_key, module.store());
+        let instance = Arc::new(Mutex::new(Instance::new(&module, &imports).map_err(
+            |e| -> RuntimeError { wasm_error!(WasmErrorInner::Compile(e.to_string())).into() },
+        )?));
+        Ok(instance)
+    }
+
     pub fn instance(
         &self,
         call_context: CallContext,
     ) -> RibosomeResult<(Arc<Mutex<Instance>>, u64)> {
         use holochain_wasmer_host::module::PlruCache;
         let zome_name = call_context.zome.zome_name().clone();
+        let wasm_hash = self.zome_wasm_hash(&zome_name)?;
 
-        // Fallback to creating an instance if we don't have a cache hit.
-        let fallback = |context_key| {
-            let module = self.module(&zome_name)?;
-            let imports: ImportObject = Self::imports(self, context_key, module.store());
-            let instance = Arc::new(Mutex::new(Instance::new(&module, &imports).map_err(
-                |e| -> RuntimeError { wasm_error!(WasmErrorInner::Compile(e.to_string())).into() },
-            )?));
-            RibosomeResult::Ok(instance)
-        };
-
-        // Get the start of the possible keys.
-        let key_start = instance_cache_key(
-            &self
-                .dna_file
-                .dna()
-                .get_wasm_zome(&zome_name)
-                .map_err(DnaError::from)?
-                .wasm_hash,
-            self.dna_file.dna_hash(),
-            0,
-        );
-        // Get the end of the possible keys.
-        let key_end = instance_cache_key(
-            &self
-                .dna_file
-                .dna()
-                .get_wasm_zome(&zome_name)
-                .map_err(DnaError::from)?
-                .wasm_hash,
-            self.dna_file.dna_hash(),
-            CONTEXT_KEY.load(std::sync::atomic::Ordering::Relaxed),
-        );
         let mut lock = holochain_wasmer_host::module::INSTANCE_CACHE.write();
         // Get the first available key.
         let key = lock
             .cache()
-            .range(key_start..key_end)
+            .range(self.instance_key_range(&wasm_hash))
             .next()
             .map(|(k, _)| k)
             .cloned();
@@ -481,14 +571,45 @@ impl RealRibosome {
                 .insert(context_key, Arc::new(call_context));
         }
         // Fallback to creating the instance.
-        let instance = fallback(context_key)?;
+        drop(lock);
+        let instance = self.new_instance(&zome_name, context_key)?;
         Ok((instance, context_key))
     }
 
+    /// Which limit, if any, a zome call ran into.
+    /// Only a call which failed can have been aborted by a limit, and running
+    /// out of time also runs out of points, so that is checked first. A call
+    /// which failed with its memory grown to the ceiling ran out of memory.
+    fn exceeded_limit(
+        deadline: &ZomeCallDeadline,
+        instance: &Mutex<Instance>,
+        failed: bool,
+    ) -> Option<ZomeCallLimit> {
+        if !failed {
+            return None;
+        }
+        if deadline.timed_out() {
+            return Some(ZomeCallLimit::Timeout);
+        }
+        let instance = instance.lock();
+        if matches!(
+            wasmer_middlewares::metering::get_remaining_points(&instance),
+            wasmer_middlewares::metering::MeteringPoints::Exhausted
+        ) {
+            return Some(ZomeCallLimit::Fuel);
+        }
+        let out_of_memory = instance
+            .exports
+            .get_memory("memory")
+            .map_or(false, zome_call_limits::memory_limit_reached);
+        out_of_memory.then(|| ZomeCallLimit::Memory)
+    }
+
     pub fn cranelift() -> Cranelift {
-        let cost_function = |_operator: &WasmOperator| -> u64 { 0 };
-        // @todo 10 giga-ops is totally arbitrary cutoff so we probably
-        // want to make the limit configurable somehow.
+        // Every operator costs one point, so the points count instructions.
+        // The 10 giga-ops default is an arbitrary cutoff, which zome calls
+        // replace with their configured `max_fuel`.
+        let cost_function = |_operator: &WasmOperator| -> u64 { 1 };
         let metering = Arc::new(Metering::new(WASM_METERING_LIMIT, cost_function));
         let mut cranelift = Cranelift::default();
         cranelift.canonicalize_nans(true).push_middleware(metering);
@@ -556,6 +677,7 @@ impl RealRibosome {
             .with_host_function(&mut ns, "__zome_info", zome_info)
             .with_host_function(&mut ns, "__dna_info", dna_info)
             .with_host_function(&mut ns, "__call_info", call_info)
+            .with_host_function(&mut ns, "__runtime_properties", runtime_properties)
             .with_host_function(&mut ns, "__random_bytes", random_bytes)
             .with_host_function(&mut ns, "__sys_time", sys_time)
             .with_host_function(&mut ns, "__sleep", sleep)
@@ -564,13 +686,22 @@ impl RealRibosome {
             .with_host_function(&mut ns, "__capability_grants", capability_grants)
             .with_host_function(&mut ns, "__capability_info", capability_info)
             .with_host_function(&mut ns, "__get", get)
+            .with_host_function(&mut ns, "__get_action", get_action)
+            .with_host_function(&mut ns, "__exists", exists)
             .with_host_function(&mut ns, "__get_details", get_details)
             .with_host_function(&mut ns, "__get_links", get_links)
             .with_host_function(&mut ns, "__get_link_details", get_link_details)
+            .with_host_function(&mut ns, "__aggregate_links", aggregate_links)
             .with_host_function(&mut ns, "__get_agent_activity", get_agent_activity)
+            .with_host_function(
+                &mut ns,
+                "__get_agent_activity_page",
+                get_agent_activity_page,
+            )
             .with_host_function(&mut ns, "__must_get_entry", must_get_entry)
             .with_host_function(&mut ns, "__must_get_action", must_get_action)
             .with_host_function(&mut ns, "__must_get_valid_record", must_get_valid_record)
+            .with_host_function(&mut ns, "__read_blob", read_blob)
             .with_host_function(
                 &mut ns,
                 "__must_get_agent_activity",
@@ -582,14 +713,22 @@ impl RealRibosome {
                 accept_countersigning_preflight_request,
             )
             .with_host_function(&mut ns, "__query", query)
+            .with_host_function(&mut ns, "__query_page", query_page)
             .with_host_function(&mut ns, "__remote_signal", remote_signal)
+            .with_host_function(
+                &mut ns,
+                "__remote_signal_acknowledged",
+                remote_signal_acknowledged,
+            )
             .with_host_function(&mut ns, "__call", call)
             .with_host_function(&mut ns, "__create", create)
+            .with_host_function(&mut ns, "__write_blob", write_blob)
             .with_host_function(&mut ns, "__emit_signal", emit_signal)
             .with_host_function(&mut ns, "__create_link", create_link)
             .with_host_function(&mut ns, "__delete_link", delete_link)
             .with_host_function(&mut ns, "__update", update)
             .with_host_function(&mut ns, "__delete", delete)
+            .with_host_function(&mut ns, "__dry_run_sys_validation", dry_run_sys_validation)
             .with_host_function(&mut ns, "__schedule", schedule);
 
         imports.register("env", ns);
@@ -659,33 +798,13 @@ impl RibosomeT for RealRibosome {
                 .expect("Failed to get ID for current zome"),
             properties: SerializedBytes::default(),
             entry_defs: {
-                match self
-                    .run_entry_defs(EntryDefsHostAccess, EntryDefsInvocation)
-                    .map_err(|e| -> RuntimeError {
-                        wasm_error!(WasmErrorInner::Host(e.to_string())).into()
-                    })? {
-                    EntryDefsResult::Err(zome, error_string) => {
-                        return Err(RibosomeError::WasmRuntimeError(
-                            wasm_error!(WasmErrorInner::Host(format!(
-                                "{}: {}",
-                                zome, error_string
-                            )))
-                            .into(),
-                        ))
-                    }
-                    EntryDefsResult::Defs(defs) => {
-                        let vec = zome_dependencies
-                            .iter()
-                            .filter_map(|zome_id| {
-                                self.dna_def().integrity_zomes.get(zome_id.0 as usize)
-                            })
-                            .flat_map(|(zome_name, _)| {
-                                defs.get(zome_name).map(|e| e.0.clone()).unwrap_or_default()
-                            })
-                            .collect::<Vec<_>>();
-                        vec.into()
-                    }
-                }
+                let entry_defs = self.entry_defs()?;
+                zome_dependencies
+                    .iter()
+                    .filter_map(|zome_id| entry_defs.get(zome_id.0 as usize))
+                    .flat_map(|defs| defs.0.iter().cloned())
+                    .collect::<Vec<_>>()
+                    .into()
             },
             extern_fns: {
                 match zome.zome_def() {
@@ -721,11 +840,18 @@ impl RibosomeT for RealRibosome {
         zome: &Zome,
         to_call: &FunctionName,
     ) -> Result<Option<ExternIO>, RibosomeError> {
+        let limits = host_context.zome_call_limits().cloned();
+        let cancel = host_context.zome_call_cancel().cloned().unwrap_or_default();
+        let deadline = ZomeCallDeadline::new(
+            limits.as_ref().and_then(ZomeCallLimitsConfig::timeout),
+            cancel.clone(),
+        );
         let call_context = CallContext {
             zome: zome.clone(),
             function_name: to_call.clone(),
             host_context,
             auth: invocation.auth(),
+            deadline: deadline.clone(),
         };
 
         match zome.zome_def() {
@@ -738,6 +864,14 @@ impl RibosomeT for RealRibosome {
                     // because it builds guards against memory leaks and handles imports correctly
                     let (instance, context_key) = self.instance(call_context)?;
 
+                    let watchdog = limits.as_ref().and_then(|limits| {
+                        let instance = instance.lock();
+                        if let Some(max_fuel) = limits.max_fuel {
+                            wasmer_middlewares::metering::set_remaining_points(&instance, max_fuel);
+                        }
+                        zome_call_limits::Watchdog::start(&deadline, &instance)
+                    });
+
                     let result: Result<ExternIO, RuntimeError> = holochain_wasmer_host::guest::call(
                         instance.clone(),
                         to_call.as_ref(),
@@ -747,6 +881,32 @@ impl RibosomeT for RealRibosome {
                         invocation.to_owned().host_input()?,
                     );
 
+                    // Cancelling also runs out the points, so it's checked
+                    // before the limits.
+                    if result.is_err() && cancel.is_cancelled() {
+                        CONTEXT_MAP.lock().remove(&context_key);
+                        return Err(RibosomeError::ZomeCallCancelled(
+                            zome.zome_name().clone(),
+                            to_call.clone(),
+                        ));
+                    }
+                    drop(watchdog);
+                    let exceeded = limits
+                        .is_some()
+                        .then(|| Self::exceeded_limit(&deadline, &instance, result.is_err()))
+                        .flatten();
+                    if let Some(limit) = exceeded {
+                        zome_call_limits::record_exceeded(limit, zome.zome_name(), to_call);
+                        // Don't cache an instance which was aborted part way
+                        // through, just clear the context.
+                        CONTEXT_MAP.lock().remove(&context_key);
+                        return Err(RibosomeError::ZomeCallLimitExceeded(
+                            zome.zome_name().clone(),
+                            to_call.clone(),
+                            limit,
+                        ));
+                    }
+
                     // Cache this instance.
                     self.cache_instance(context_key, instance, zome.zome_name())?;
 
@@ -775,6 +935,7 @@ impl RibosomeT for RealRibosome {
             function_name: name.into(),
             host_context: HostContext::EntryDefs(EntryDefsHostAccess {}),
             auth: super::InvocationAuth::LocalCallback,
+            deadline: Default::default(),
         };
 
         match zome.zome_def() {
@@ -934,17 +1095,57 @@ impl RibosomeT for RealRibosome {
             .cloned()
             .map(|(name, def)| IntegrityZome::new(name, def))
     }
+
+    fn get_app_entry_def(
+        &self,
+        zome_id: ZomeId,
+        entry_def_index: EntryDefIndex,
+    ) -> RibosomeResult<Option<EntryDef>> {
+        Ok(self
+            .entry_defs()?
+            .get(zome_id.0 as usize)
+            .and_then(|defs| defs.0.get(entry_def_index.0 as usize))
+            .cloned())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::fixt::prelude::*;
+    use holo_hash::fixt::DnaHashFixturator;
+    use holo_hash::fixt::WasmHashFixturator;
+
+    #[test]
+    fn instance_keys_are_in_their_zomes_range() {
+        let wasm_hash = fixt!(WasmHash);
+        let dna_hash = fixt!(DnaHash);
+        let range = instance_key_range(&wasm_hash, &dna_hash);
+        for context_key in [0, 1, 255, 256, 70_000, u64::MAX] {
+            let key = instance_cache_key(&wasm_hash, &dna_hash, context_key);
+            assert!(range.contains(&key));
+            assert_eq!(context_key_from_key(&key), context_key);
+        }
+        let other = instance_cache_key(&fixt!(WasmHash), &dna_hash, 1);
+        assert!(!range.contains(&other));
+    }
 }
 
 #[cfg(test)]
 #[cfg(feature = "slow_tests")]
 pub mod wasm_test {
-    use crate::core::ribosome::wasm_test::RibosomeTestFixture;
+    use crate::core::ribosome::error::RibosomeError;
+    use crate::core::ribosome::error::RibosomeResult;
+    use crate::core::ribosome::zome_call_limits::exceeded_count;
+    use crate::core::ribosome::zome_call_limits::ZomeCallCancel;
+    use crate::core::ribosome::zome_call_limits::ZomeCallLimit;
     use crate::core::ribosome::ZomeCall;
+    use crate::sweettest::standard_config;
     use crate::sweettest::SweetConductor;
     use crate::sweettest::SweetDnaFile;
     use ::fixt::prelude::*;
     use hdk::prelude::*;
+    use holochain_conductor_api::config::conductor::ZomeCallLimitsConfig;
     use holochain_types::prelude::AgentPubKeyFixturator;
     use holochain_wasm_test_utils::TestWasm;
 
@@ -999,30 +1200,105 @@ pub mod wasm_test {
         }
     }
 
-    #[ignore = "turn this back on when we set the cost_function to return non-0"]
+    /// Call `smash`, which loops forever, on a conductor with these limits.
+    async fn smash_with_limits(limits: ZomeCallLimitsConfig) -> RibosomeResult<ZomeCallResponse> {
+        let mut config = standard_config();
+        config.zome_call_limits = Some(limits);
+        let mut conductor = SweetConductor::from_config(config).await;
+        let (dna_file, _, _) =
+            SweetDnaFile::unique_from_test_wasms(vec![TestWasm::TheIncredibleHalt])
+                .await
+                .unwrap();
+        let app = conductor.setup_app("app", &[dna_file]).await.unwrap();
+        let (alice,) = app.into_tuple();
+        conductor
+            .handle()
+            .call_zome(ZomeCall {
+                cell_id: alice.cell_id().clone(),
+                zome_name: alice.zome(TestWasm::TheIncredibleHalt).name().clone(),
+                fn_name: "smash".into(),
+                cap_secret: None,
+                provenance: alice.agent_pubkey().clone(),
+                payload: ExternIO::encode(()).unwrap(),
+            })
+            .await
+            .unwrap()
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn the_incredible_halt_test() {
         observability::test_run().ok();
-        let RibosomeTestFixture {
-            conductor, alice, ..
-        } = RibosomeTestFixture::new(TestWasm::TheIncredibleHalt).await;
 
         // This will run infinitely unless our metering kicks in and traps it.
-        // Also we stop it running after 10 seconds.
-        let result: Result<Result<(), _>, _> = tokio::time::timeout(
-            std::time::Duration::from_millis(10000),
-            conductor.call_fallible(&alice, "smash", ()),
-        )
+        let fuel_before = exceeded_count(ZomeCallLimit::Fuel);
+        let result = smash_with_limits(ZomeCallLimitsConfig {
+            max_fuel: Some(1_000_000),
+            ..Default::default()
+        })
         .await;
-        assert!(result.unwrap().is_err());
-
-        // The same thing will happen when we commit an entry due to a loop in
-        // the validation logic.
-        let create_result: Result<Result<(), _>, _> = tokio::time::timeout(
-            std::time::Duration::from_millis(10000),
-            conductor.call_fallible(&alice, "create_a_thing", ()),
-        )
+        assert!(matches!(
+            result,
+            Err(RibosomeError::ZomeCallLimitExceeded(
+                _,
+                _,
+                ZomeCallLimit::Fuel
+            ))
+        ));
+        assert!(exceeded_count(ZomeCallLimit::Fuel) > fuel_before);
+
+        // With plenty of fuel the timeout stops it instead.
+        let timeout_before = exceeded_count(ZomeCallLimit::Timeout);
+        let started = std::time::Instant::now();
+        let result = smash_with_limits(ZomeCallLimitsConfig {
+            timeout_ms: Some(500),
+            ..Default::default()
+        })
         .await;
-        assert!(create_result.unwrap().is_err());
+        assert!(matches!(
+            result,
+            Err(RibosomeError::ZomeCallLimitExceeded(
+                _,
+                _,
+                ZomeCallLimit::Timeout
+            ))
+        ));
+        assert!(started.elapsed() < std::time::Duration::from_secs(10));
+        assert!(exceeded_count(ZomeCallLimit::Timeout) > timeout_before);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn cancelled_calls_are_aborted() {
+        observability::test_run().ok();
+        let mut conductor = SweetConductor::from_standard_config().await;
+        let (dna_file, _, _) =
+            SweetDnaFile::unique_from_test_wasms(vec![TestWasm::TheIncredibleHalt])
+                .await
+                .unwrap();
+        let app = conductor.setup_app("app", &[dna_file]).await.unwrap();
+        let (alice,) = app.into_tuple();
+        let call = ZomeCall {
+            cell_id: alice.cell_id().clone(),
+            zome_name: alice.zome(TestWasm::TheIncredibleHalt).name().clone(),
+            fn_name: "smash".into(),
+            cap_secret: None,
+            provenance: alice.agent_pubkey().clone(),
+            payload: ExternIO::encode(()).unwrap(),
+        };
+        let cancel = ZomeCallCancel::cancellable();
+        let handle = conductor.handle();
+        let call = tokio::spawn({
+            let cancel = cancel.clone();
+            async move { handle.call_zome_cancellable(call, cancel).await.unwrap() }
+        });
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        cancel.cancel();
+        let result = tokio::time::timeout(std::time::Duration::from_secs(10), call)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(matches!(
+            result,
+            Err(RibosomeError::ZomeCallCancelled(_, _))
+        ));
     }
 }
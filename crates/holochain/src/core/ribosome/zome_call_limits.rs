@@ -0,0 +1,438 @@
+//! Enforcement of the [`ZomeCallLimitsConfig`](holochain_conductor_api::config::conductor::ZomeCallLimitsConfig)
+//! on wasm zome calls.
+//!
+//! Instruction counting is done by the metering middleware the wasm is
+//! compiled with, which traps once the remaining points run out. The same
+//! points are used for the timeout: a watchdog thread zeroes them once the
+//! deadline passes, so the guest traps at its next metered block. Calls made
+//! with a cancellable [`ZomeCallCancel`] are stopped the same way once they
+//! are cancelled.
+//!
+//! Both make up the call's [`ZomeCallDeadline`], which is also passed to the
+//! host functions it calls. The work they wait on, such as network gets,
+//! keystore signing and bridge calls, is dropped once the deadline passes,
+//! rather than left running after the call has given up.
+//!
+//! Memory is limited by the [`LimitingTunables`] every module is compiled
+//! with, which cap the maximum size of each wasm memory at the ceiling set
+//! with [`set_max_memory_bytes`], so growing past it fails in the guest.
+//!
+//! The number of calls aborted for each limit is counted, and can be read
+//! with [`exceeded_count`].
+
+use holochain_types::prelude::FunctionName;
+use holochain_types::prelude::ZomeName;
+use holochain_wasmer_host::prelude::*;
+use std::future::Future;
+use std::ptr::NonNull;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicU32;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+
+/// The name of the global the metering middleware counts down.
+const REMAINING_POINTS_GLOBAL: &str = "wasmer_metering_remaining_points";
+
+/// How often the watchdog zeroes the remaining points once the deadline has
+/// passed, in case the guest wrote back its own count in the meantime.
+/// Cancellation is checked this often too.
+const WATCHDOG_INTERVAL: Duration = Duration::from_millis(10);
+
+/// A limit which a zome call exceeded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ZomeCallLimit {
+    /// The call ran for longer than `timeout_ms`.
+    Timeout,
+    /// The call executed more than `max_fuel` instructions.
+    Fuel,
+    /// The call's memory grew beyond `max_memory_bytes`.
+    Memory,
+}
+
+impl ZomeCallLimit {
+    fn index(self) -> usize {
+        match self {
+            Self::Timeout => 0,
+            Self::Fuel => 1,
+            Self::Memory => 2,
+        }
+    }
+}
+
+impl std::fmt::Display for ZomeCallLimit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Timeout => write!(f, "timeout"),
+            Self::Fuel => write!(f, "fuel"),
+            Self::Memory => write!(f, "memory"),
+        }
+    }
+}
+
+static EXCEEDED: [AtomicU64; 3] = [AtomicU64::new(0), AtomicU64::new(0), AtomicU64::new(0)];
+
+/// The number of zome calls aborted for exceeding this limit since the
+/// conductor started.
+pub fn exceeded_count(limit: ZomeCallLimit) -> u64 {
+    EXCEEDED[limit.index()].load(Ordering::Relaxed)
+}
+
+pub(crate) fn record_exceeded(limit: ZomeCallLimit, zome_name: &ZomeName, fn_name: &FunctionName) {
+    let count = EXCEEDED[limit.index()].fetch_add(1, Ordering::Relaxed) + 1;
+    tracing::warn!(
+        %zome_name,
+        %fn_name,
+        %limit,
+        count,
+        "Zome call aborted for exceeding a limit"
+    );
+}
+
+/// The value of [`MAX_MEMORY_PAGES`] when there is no memory ceiling.
+const NO_MEMORY_LIMIT: u32 = u32::MAX;
+
+/// The ceiling on the size of each wasm memory, in wasm pages.
+static MAX_MEMORY_PAGES: AtomicU32 = AtomicU32::new(NO_MEMORY_LIMIT);
+
+/// Set the ceiling on the memory of the wasm instances created from now on
+/// to the configured `max_memory_bytes`, rounded down to whole wasm pages.
+/// Instances which already exist keep the ceiling they were created with.
+pub fn set_max_memory_bytes(max_memory_bytes: Option<u64>) {
+    let pages = max_memory_bytes.map_or(NO_MEMORY_LIMIT, |max| {
+        (max / WASM_PAGE_SIZE as u64).min(WASM_MAX_PAGES as u64) as u32
+    });
+    MAX_MEMORY_PAGES.store(pages, Ordering::Relaxed);
+}
+
+fn max_memory_pages(max_pages: &AtomicU32) -> Option<Pages> {
+    match max_pages.load(Ordering::Relaxed) {
+        NO_MEMORY_LIMIT => None,
+        pages => Some(Pages(pages)),
+    }
+}
+
+/// Has this memory grown as far as the ceiling lets it, so that a call
+/// which failed can be put down to running out of memory.
+pub(crate) fn memory_limit_reached(memory: &Memory) -> bool {
+    max_memory_pages(&MAX_MEMORY_PAGES).map_or(false, |max| memory.size() >= max)
+}
+
+/// Tunables which create every wasm memory with its maximum size capped at
+/// the memory ceiling, so the guest's `memory.grow` fails rather than
+/// growing past it. A module which needs more memory than the ceiling to
+/// start with can't be instantiated.
+#[derive(Clone)]
+pub(crate) struct LimitingTunables {
+    base: BaseTunables,
+    max_pages: &'static AtomicU32,
+}
+
+impl LimitingTunables {
+    /// Tunables for the ceiling set with [`set_max_memory_bytes`].
+    pub(crate) fn new() -> Self {
+        Self::with_max_pages(&MAX_MEMORY_PAGES)
+    }
+
+    fn with_max_pages(max_pages: &'static AtomicU32) -> Self {
+        Self {
+            base: BaseTunables::for_target(&Target::default()),
+            max_pages,
+        }
+    }
+
+    fn limit(&self, ty: &MemoryType) -> Result<MemoryType, MemoryError> {
+        let max = match max_memory_pages(self.max_pages) {
+            Some(max) => max,
+            None => return Ok(*ty),
+        };
+        if ty.minimum > max {
+            return Err(MemoryError::Generic(format!(
+                "The wasm needs {} bytes of memory, more than the limit of {} bytes",
+                ty.minimum.bytes().0,
+                max.bytes().0,
+            )));
+        }
+        let mut ty = *ty;
+        ty.maximum = Some(ty.maximum.map_or(max, |declared| declared.min(max)));
+        Ok(ty)
+    }
+}
+
+impl loupe::MemoryUsage for LimitingTunables {
+    fn size_of_val(&self, tracker: &mut dyn loupe::MemoryUsageTracker) -> usize {
+        std::mem::size_of_val(self) + self.base.size_of_val(tracker)
+            - std::mem::size_of_val(&self.base)
+    }
+}
+
+impl Tunables for LimitingTunables {
+    // The style is compiled into the module, so it follows the memory the
+    // wasm declares rather than a ceiling which can change on a reload.
+    fn memory_style(&self, memory: &MemoryType) -> vm::MemoryStyle {
+        self.base.memory_style(memory)
+    }
+
+    fn table_style(&self, table: &TableType) -> vm::TableStyle {
+        self.base.table_style(table)
+    }
+
+    fn create_host_memory(
+        &self,
+        ty: &MemoryType,
+        style: &vm::MemoryStyle,
+    ) -> Result<Arc<dyn vm::Memory>, MemoryError> {
+        self.base.create_host_memory(&self.limit(ty)?, style)
+    }
+
+    unsafe fn create_vm_memory(
+        &self,
+        ty: &MemoryType,
+        style: &vm::MemoryStyle,
+        vm_definition_location: NonNull<vm::VMMemoryDefinition>,
+    ) -> Result<Arc<dyn vm::Memory>, MemoryError> {
+        self.base
+            .create_vm_memory(&self.limit(ty)?, style, vm_definition_location)
+    }
+
+    fn create_host_table(
+        &self,
+        ty: &TableType,
+        style: &vm::TableStyle,
+    ) -> Result<Arc<dyn vm::Table>, String> {
+        self.base.create_host_table(ty, style)
+    }
+
+    unsafe fn create_vm_table(
+        &self,
+        ty: &TableType,
+        style: &vm::TableStyle,
+        vm_definition_location: NonNull<vm::VMTableDefinition>,
+    ) -> Result<Arc<dyn vm::Table>, String> {
+        self.base.create_vm_table(ty, style, vm_definition_location)
+    }
+}
+
+/// Cancels a running zome call, for the calls started with
+/// [`AppRequest::StartZomeCall`].
+///
+/// The default token can never be cancelled, so calls made with it aren't
+/// watched for cancellation.
+///
+/// [`AppRequest::StartZomeCall`]: holochain_conductor_api::AppRequest::StartZomeCall
+#[derive(Clone, Debug, Default)]
+pub struct ZomeCallCancel(Option<Arc<AtomicBool>>);
+
+impl ZomeCallCancel {
+    /// A token which can be cancelled.
+    pub fn cancellable() -> Self {
+        Self(Some(Arc::new(AtomicBool::new(false))))
+    }
+
+    /// Cancel the call, which is aborted at its next metered block, or
+    /// in the host function it's waiting on.
+    pub fn cancel(&self) {
+        if let Some(cancelled) = &self.0 {
+            cancelled.store(true, Ordering::Release);
+        }
+    }
+
+    /// Has the call been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.0
+            .as_ref()
+            .map_or(false, |cancelled| cancelled.load(Ordering::Acquire))
+    }
+
+    fn can_be_cancelled(&self) -> bool {
+        self.0.is_some()
+    }
+}
+
+/// When a zome call gives up: once its timeout runs out, or it's cancelled.
+///
+/// The default deadline never passes, as for the callbacks which aren't
+/// zome calls.
+#[derive(Clone, Debug, Default)]
+pub struct ZomeCallDeadline {
+    at: Option<Instant>,
+    cancel: ZomeCallCancel,
+}
+
+impl ZomeCallDeadline {
+    /// The deadline of a call starting now.
+    pub fn new(timeout: Option<Duration>, cancel: ZomeCallCancel) -> Self {
+        Self {
+            at: timeout.map(|timeout| Instant::now() + timeout),
+            cancel,
+        }
+    }
+
+    /// Has the call run out of time.
+    pub fn timed_out(&self) -> bool {
+        self.at.map_or(false, |at| Instant::now() >= at)
+    }
+
+    /// Has the call run out of time or been cancelled.
+    pub fn has_passed(&self) -> bool {
+        self.timed_out() || self.cancel.is_cancelled()
+    }
+
+    fn can_pass(&self) -> bool {
+        self.at.is_some() || self.cancel.can_be_cancelled()
+    }
+
+    /// The token for a call this one makes, such as a bridge call, to be
+    /// cancelled if [`Self::run`] gives up on it.
+    pub fn child_cancel(&self) -> ZomeCallCancel {
+        if self.can_pass() {
+            ZomeCallCancel::cancellable()
+        } else {
+            ZomeCallCancel::default()
+        }
+    }
+
+    /// Run the work of a host function, which is dropped if the deadline
+    /// passes first.
+    pub async fn run<F: Future>(self, work: F) -> Result<F::Output, RuntimeError> {
+        if !self.can_pass() {
+            return Ok(work.await);
+        }
+        tokio::select! {
+            output = work => Ok(output),
+            _ = self.passed() => {
+                let reason = if self.cancel.is_cancelled() {
+                    "The zome call was cancelled"
+                } else {
+                    "The zome call timed out"
+                };
+                Err(wasm_error!(WasmErrorInner::Host(reason.to_string())).into())
+            }
+        }
+    }
+
+    /// Wait for the deadline to pass.
+    async fn passed(&self) {
+        while !self.cancel.is_cancelled() {
+            let wait = match self.at {
+                Some(at) => match at.checked_duration_since(Instant::now()) {
+                    Some(left) if !left.is_zero() => left.min(WATCHDOG_INTERVAL),
+                    _ => return,
+                },
+                None => WATCHDOG_INTERVAL,
+            };
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// Watches a running call, and runs its metering points out once its
+/// deadline passes. The watchdog stops when this is dropped.
+pub(crate) struct Watchdog {
+    done: Arc<AtomicBool>,
+}
+
+impl Watchdog {
+    /// Start watching the instance, if its deadline can pass.
+    pub(crate) fn start(deadline: &ZomeCallDeadline, instance: &Instance) -> Option<Self> {
+        if !deadline.can_pass() {
+            return None;
+        }
+        let remaining_points = instance
+            .exports
+            .get_global(REMAINING_POINTS_GLOBAL)
+            .ok()?
+            .clone();
+        let done = Arc::new(AtomicBool::new(false));
+        {
+            let done = done.clone();
+            let deadline = deadline.clone();
+            std::thread::spawn(move || {
+                while !done.load(Ordering::Acquire) {
+                    if deadline.has_passed() && remaining_points.set(Value::I64(0)).is_err() {
+                        break;
+                    }
+                    let wait = deadline.at.map_or(WATCHDOG_INTERVAL, |at| {
+                        at.saturating_duration_since(Instant::now())
+                    });
+                    std::thread::sleep(wait.clamp(Duration::from_millis(1), WATCHDOG_INTERVAL));
+                }
+            });
+        }
+        Some(Self { done })
+    }
+}
+
+impl Drop for Watchdog {
+    fn drop(&mut self) {
+        self.done.store(true, Ordering::Release);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A module with one page of memory, exporting `grow`, which grows the
+    /// memory by the given number of pages and returns `memory.grow`'s result.
+    const GROW_WASM: &[u8] = &[
+        0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, // header
+        0x01, 0x06, 0x01, 0x60, 0x01, 0x7f, 0x01, 0x7f, // type: (i32) -> i32
+        0x03, 0x02, 0x01, 0x00, // function
+        0x05, 0x03, 0x01, 0x00, 0x01, // memory: 1 page, no maximum
+        0x07, 0x08, 0x01, 0x04, b'g', b'r', b'o', b'w', 0x00, 0x00, // export
+        0x0a, 0x08, 0x01, 0x06, 0x00, 0x20, 0x00, 0x40, 0x00, 0x0b, // code
+    ];
+
+    #[test]
+    fn memory_cannot_grow_past_the_limit() {
+        static MAX_PAGES: AtomicU32 = AtomicU32::new(3);
+        let store = Store::new_with_tunables(
+            &Universal::new(Cranelift::default()).engine(),
+            LimitingTunables::with_max_pages(&MAX_PAGES),
+        );
+        let module = Module::from_binary(&store, GROW_WASM).unwrap();
+
+        let instance = Instance::new(&module, &imports! {}).unwrap();
+        let grow = instance
+            .exports
+            .get_native_function::<i32, i32>("grow")
+            .unwrap();
+        // Growing to the limit succeeds, returning the previous size.
+        assert_eq!(grow.call(2).unwrap(), 1);
+        assert_eq!(grow.call(1).unwrap(), -1);
+
+        // A module that needs more than the limit to start can't be used.
+        MAX_PAGES.store(0, Ordering::Relaxed);
+        assert!(Instance::new(&module, &imports! {}).is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn host_fn_work_is_dropped_once_the_deadline_passes() {
+        let deadline = ZomeCallDeadline::new(Some(Duration::from_millis(10)), Default::default());
+        assert!(deadline
+            .clone()
+            .run(futures::future::pending::<()>())
+            .await
+            .is_err());
+        assert!(deadline.timed_out());
+
+        let cancel = ZomeCallCancel::cancellable();
+        let deadline = ZomeCallDeadline::new(None, cancel.clone());
+        let child = deadline.child_cancel();
+        cancel.cancel();
+        assert!(deadline.has_passed());
+        assert!(deadline
+            .run(futures::future::pending::<()>())
+            .await
+            .is_err());
+        assert!(!child.is_cancelled());
+
+        let deadline = ZomeCallDeadline::default();
+        assert_eq!(deadline.clone().run(async { 1 }).await.unwrap(), 1);
+        assert!(!deadline.child_cancel().can_be_cancelled());
+    }
+}
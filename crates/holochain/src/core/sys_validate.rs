@@ -8,6 +8,7 @@ use super::workflow::sys_validation_workflow::SysValidationWorkspace;
 use crate::conductor::entry_def_store::get_entry_def;
 use crate::conductor::handle::ConductorHandleT;
 use crate::conductor::space::Space;
+use holochain_cascade::CascadeT;
 use holochain_keystore::AgentPubKeyExt;
 use holochain_p2p::HolochainP2pDna;
 use holochain_types::prelude::*;
@@ -260,6 +261,7 @@ pub fn check_entry_type(entry_type: &EntryType, entry: &Entry) -> SysValidationR
         (EntryType::App(_), Entry::CounterSign(_, _)) => Ok(()),
         (EntryType::CapClaim, Entry::CapClaim(_)) => Ok(()),
         (EntryType::CapGrant, Entry::CapGrant(_)) => Ok(()),
+        (EntryType::Chunk, Entry::Chunk(_)) => Ok(()),
         _ => Err(ValidationOutcome::EntryType.into()),
     }
 }
@@ -303,7 +305,9 @@ pub async fn check_app_entry_type(
 pub fn check_not_private(entry_def: &EntryDef) -> SysValidationResult<()> {
     match entry_def.visibility {
         EntryVisibility::Public => Ok(()),
-        EntryVisibility::Private => Err(ValidationOutcome::PrivateEntry.into()),
+        EntryVisibility::Private => {
+            Err(ValidationOutcome::PrivateEntry(entry_def.id.clone()).into())
+        }
     }
 }
 
@@ -316,6 +320,33 @@ pub async fn check_entry_hash(hash: &EntryHash, entry: &Entry) -> SysValidationR
     }
 }
 
+/// Check the entries of a batch of ops against the entry hashes in their actions,
+/// returning the ops whose entries match, or which have no entry, and the ops whose
+/// entries don't match. Both keep the order of the batch.
+///
+/// Hashing is most of the cost of checking large entries, so rather than hashing each
+/// entry as its op is validated, the whole batch is hashed in parallel on rayon's
+/// thread pool, off the async runtime.
+pub async fn check_op_entry_hashes(
+    ops: Vec<(DhtOpHash, DhtOp)>,
+) -> SysValidationResult<(Vec<(DhtOpHash, DhtOp)>, Vec<(DhtOpHash, DhtOp)>)> {
+    use rayon::prelude::*;
+    tokio::task::spawn_blocking(move || {
+        ops.into_par_iter()
+            .partition(|(_, op)| op_entry_hash_matches(op))
+    })
+    .await
+    .map_err(|e| SysValidationError::WorkflowError(Box::new(e.into())))
+}
+
+fn op_entry_hash_matches(op: &DhtOp) -> bool {
+    let action = op.action();
+    match (action.entry_data(), op.entry()) {
+        (Some((entry_hash, _)), Some(entry)) => *entry_hash == EntryHash::with_data_sync(entry),
+        _ => true,
+    }
+}
+
 /// Check the action should have an entry.
 /// Is either a Create or Update
 pub fn check_new_entry_action(action: &Action) -> SysValidationResult<()> {
@@ -325,7 +356,8 @@ pub fn check_new_entry_action(action: &Action) -> SysValidationResult<()> {
     }
 }
 
-/// Check the entry size is under the MAX_ENTRY_SIZE
+/// Check the entry size is under the MAX_ENTRY_SIZE,
+/// or the CHUNK_SIZE_LIMIT for chunks
 pub fn check_entry_size(entry: &Entry) -> SysValidationResult<()> {
     match entry {
         Entry::App(bytes) => {
@@ -336,6 +368,14 @@ pub fn check_entry_size(entry: &Entry) -> SysValidationResult<()> {
                 Err(ValidationOutcome::EntryTooLarge(size, MAX_ENTRY_SIZE).into())
             }
         }
+        Entry::Chunk(chunk) => {
+            let size = chunk.bytes().len();
+            if size <= CHUNK_SIZE_LIMIT {
+                Ok(())
+            } else {
+                Err(ValidationOutcome::EntryTooLarge(size, CHUNK_SIZE_LIMIT).into())
+            }
+        }
         // Other entry types are small
         _ => Ok(()),
     }
@@ -621,6 +661,7 @@ impl IncomingDhtOpSender {
 }
 
 /// Where the record was found.
+#[derive(Debug)]
 enum Source {
     /// Locally because we are holding it or
     /// because we will be soon
@@ -649,18 +690,32 @@ async fn check_and_hold<I: Into<AnyDhtHash> + Clone>(
     workspace: &SysValidationWorkspace,
     network: HolochainP2pDna,
 ) -> SysValidationResult<Source> {
-    let hash: AnyDhtHash = hash.clone().into();
     // Create a workspace with just the local stores
     let mut local_cascade = workspace.local_cascade();
+    // Create a workspace with just the network
+    let mut network_only_cascade = workspace.full_cascade(network);
+    find_dependency(
+        hash.clone().into(),
+        &mut local_cascade,
+        &mut network_only_cascade,
+    )
+    .await
+}
+
+/// Find a dependency in the local cascade, or failing that
+/// in the network cascade.
+async fn find_dependency(
+    hash: AnyDhtHash,
+    local_cascade: &mut impl CascadeT,
+    network_cascade: &mut impl CascadeT,
+) -> SysValidationResult<Source> {
     if let Some(el) = local_cascade
         .retrieve(hash.clone(), Default::default())
         .await?
     {
         return Ok(Source::Local(el));
     }
-    // Create a workspace with just the network
-    let mut network_only_cascade = workspace.full_cascade(network);
-    match network_only_cascade
+    match network_cascade
         .retrieve(hash.clone(), Default::default())
         .await?
     {
@@ -669,6 +724,18 @@ async fn check_and_hold<I: Into<AnyDhtHash> + Clone>(
     }
 }
 
+/// Retrieve an action which another action depends on,
+/// failing validation if it can't be found.
+pub async fn retrieve_dependency_action(
+    hash: &ActionHash,
+    cascade: &mut impl CascadeT,
+) -> SysValidationResult<SignedActionHashed> {
+    cascade
+        .retrieve_action(hash.clone(), Default::default())
+        .await?
+        .ok_or_else(|| ValidationOutcome::DepMissingFromDht(hash.clone().into()).into())
+}
+
 /// Make a StoreRecord DhtOp from a Record.
 /// Note that this can fail if the op is missing an
 /// Entry when it was supposed to have one.
@@ -127,8 +127,8 @@ pub enum ValidationOutcome {
     PreflightResponseSignature(PreflightResponse),
     #[error(transparent)]
     PrevActionError(#[from] PrevActionError),
-    #[error("StoreEntry should not be gossiped for private entries")]
-    PrivateEntry,
+    #[error("The entry def {0:?} is private, so a StoreEntry op must never be published for it")]
+    PrivateEntry(EntryDefId),
     #[error("Update original EntryType: {0:?} doesn't match new EntryType {1:?}")]
     UpdateTypeMismatch(EntryType, EntryType),
     #[error("Signature {0:?} failed to verify for Action {1:?}")]
@@ -152,6 +152,51 @@ impl ValidationOutcome {
     }
 }
 
+impl From<ValidationOutcome> for SysValidationIssueReason {
+    fn from(outcome: ValidationOutcome) -> Self {
+        match outcome {
+            ValidationOutcome::Counterfeit(_, _) => Self::Counterfeit,
+            ValidationOutcome::VerifySignature(_, _)
+            | ValidationOutcome::PreflightResponseSignature(_) => Self::InvalidSignature,
+            ValidationOutcome::EntryTooLarge(size, max) => Self::EntryTooLarge {
+                size: size as u64,
+                max: max as u64,
+            },
+            ValidationOutcome::TagTooLarge(size, max) => Self::TagTooLarge {
+                size: size as u64,
+                max: max as u64,
+            },
+            ValidationOutcome::EntryType => Self::EntryType,
+            ValidationOutcome::EntryHash => Self::EntryHash,
+            ValidationOutcome::EntryDefId(_)
+            | ValidationOutcome::EntryVisibility(_)
+            | ValidationOutcome::ZomeId(_) => Self::AppEntryType(outcome.to_string()),
+            ValidationOutcome::UpdateTypeMismatch(_, _) => Self::UpdateTypeMismatch,
+            ValidationOutcome::PrevActionError(PrevActionError::Timestamp) => Self::Timestamp,
+            ValidationOutcome::PrevActionError(PrevActionError::InvalidSeq(seq, prev_seq)) => {
+                Self::Seq { seq, prev_seq }
+            }
+            ValidationOutcome::PrevActionError(PrevActionError::HashMismatch(seq)) => {
+                Self::PrevActionMismatch { seq }
+            }
+            ValidationOutcome::PrevActionError(PrevActionError::MissingMeta(hash)) => {
+                Self::MissingDependency(hash.into())
+            }
+            ValidationOutcome::PrevActionError(e) => Self::PrevAction(e.to_string()),
+            ValidationOutcome::DepMissingFromDht(hash) | ValidationOutcome::NotHoldingDep(hash) => {
+                Self::MissingDependency(hash)
+            }
+            ValidationOutcome::NotCreateLink(hash) => Self::NotCreateLink(hash),
+            ValidationOutcome::NotNewEntry(_) => Self::NotNewEntry,
+            ValidationOutcome::PrivateEntry(_) => Self::PrivateEntry,
+            ValidationOutcome::ActionNotInCounterSigningSession(_, _)
+            | ValidationOutcome::CounterSigningError(_) => {
+                Self::CounterSigning(outcome.to_string())
+            }
+        }
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum PrevActionError {
     #[error("The previous action hash specified in an action doesn't match the actual previous action. Seq: {0}")]
@@ -6,6 +6,7 @@ use crate::test_utils::fake_genesis;
 use ::fixt::prelude::*;
 use error::SysValidationError;
 
+use holochain_cascade::MockCascadeT;
 use holochain_keystore::AgentPubKeyExt;
 use holochain_serialized_bytes::SerializedBytes;
 use holochain_state::prelude::fresh_reader_test;
@@ -17,6 +18,7 @@ use holochain_types::test_utils::chain::{TestChainHash, TestChainItem};
 use holochain_wasm_test_utils::*;
 use holochain_zome_types::Action;
 use matches::assert_matches;
+use mockall::predicate;
 use observability;
 use std::convert::TryFrom;
 
@@ -217,6 +219,31 @@ async fn check_entry_type_test() {
     }
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn check_op_entry_hashes_test() {
+    let entry = fixt!(Entry);
+    let mut create = fixt!(Create);
+    create.entry_hash = EntryHash::with_data_sync(&entry);
+    let store_entry = |create: Create| {
+        let op = DhtOp::StoreEntry(
+            fixt!(Signature),
+            NewEntryAction::Create(create),
+            Box::new(entry.clone()),
+        );
+        (DhtOpHash::with_data_sync(&op), op)
+    };
+    let valid = store_entry(create.clone());
+    create.entry_hash = fixt!(EntryHash);
+    let tampered = store_entry(create);
+    let activity = DhtOp::RegisterAgentActivity(fixt!(Signature), fixt!(CreateLink).into());
+    let activity = (DhtOpHash::with_data_sync(&activity), activity);
+
+    let ops = vec![valid.clone(), tampered.clone(), activity.clone()];
+    let (matching, wrong) = check_op_entry_hashes(ops).await.unwrap();
+    assert_eq!(matching, vec![valid, activity]);
+    assert_eq!(wrong, vec![tampered]);
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn check_entry_hash_test() {
     let mut ec = fixt!(Create);
@@ -261,6 +288,20 @@ async fn check_entry_size_test() {
     // );
 }
 
+#[test]
+fn check_chunk_size_test() {
+    let chunk = Entry::Chunk(Chunk(vec![0; CHUNK_SIZE_LIMIT]));
+    assert_matches!(check_entry_size(&chunk), Ok(()));
+
+    let chunk = Entry::Chunk(Chunk(vec![0; CHUNK_SIZE_LIMIT + 1]));
+    assert_matches!(
+        check_entry_size(&chunk),
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::EntryTooLarge(_, _)
+        ))
+    );
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn check_update_reference_test() {
     let mut ec = fixt!(Create);
@@ -300,6 +341,58 @@ async fn check_update_reference_test() {
     );
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn dependencies_are_found_locally_before_the_network() {
+    let record = Record::new(fixt!(SignedActionHashed), None);
+    let hash: AnyDhtHash = record.action_address().clone().into();
+    let found = record.clone();
+
+    // Held locally, so the network isn't asked.
+    let mut local = MockCascadeT::new();
+    local
+        .expect_retrieve()
+        .with(predicate::eq(hash.clone()), predicate::always())
+        .returning(move |_, _| Ok(Some(found.clone())));
+    let mut network = MockCascadeT::new();
+    network.expect_retrieve().never();
+    assert_matches!(
+        find_dependency(hash.clone(), &mut local, &mut network).await,
+        Ok(Source::Local(_))
+    );
+
+    // Only on the network.
+    let mut local = MockCascadeT::new();
+    local.expect_retrieve().returning(|_, _| Ok(None));
+    let mut network = MockCascadeT::new();
+    network
+        .expect_retrieve()
+        .return_once(move |_, _| Ok(Some(record)));
+    assert_matches!(
+        find_dependency(hash.clone(), &mut local, &mut network).await,
+        Ok(Source::Network(_))
+    );
+
+    // Nowhere.
+    let mut network = MockCascadeT::new();
+    network.expect_retrieve().returning(|_, _| Ok(None));
+    assert_matches!(
+        find_dependency(hash, &mut local, &mut network).await,
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::NotHoldingDep(_)
+        ))
+    );
+
+    let action_hash = fixt!(ActionHash);
+    let mut cascade = MockCascadeT::new();
+    cascade.expect_retrieve_action().returning(|_, _| Ok(None));
+    assert_matches!(
+        retrieve_dependency_action(&action_hash, &mut cascade).await,
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::DepMissingFromDht(_)
+        ))
+    );
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn check_link_tag_size_test() {
     let tiny = LinkTag(vec![0; 1]);
@@ -336,6 +429,8 @@ async fn check_app_entry_type_test() {
             coordinator_zomes: vec![TestZomes::from(TestWasm::EntryDefs)
                 .coordinator
                 .into_inner()],
+            runtime_properties: Default::default(),
+            ephemeral_ttl_secs: None,
         },
         [integrity, coordinator],
     )
@@ -424,7 +519,7 @@ async fn check_entry_not_private_test() {
     assert_matches!(
         check_not_private(&ed),
         Err(SysValidationError::ValidationOutcome(
-            ValidationOutcome::PrivateEntry
+            ValidationOutcome::PrivateEntry(_)
         ))
     );
 }
@@ -583,3 +678,50 @@ fn valid_chain_test() {
         );
     });
 }
+
+#[test]
+fn validation_outcomes_are_reported_as_issues() {
+    assert_eq!(
+        SysValidationIssueReason::from(ValidationOutcome::EntryTooLarge(10, 5)),
+        SysValidationIssueReason::EntryTooLarge { size: 10, max: 5 }
+    );
+    assert_eq!(
+        SysValidationIssueReason::from(ValidationOutcome::PrevActionError(
+            PrevActionError::Timestamp
+        )),
+        SysValidationIssueReason::Timestamp
+    );
+    assert_eq!(
+        SysValidationIssueReason::from(ValidationOutcome::PrevActionError(
+            PrevActionError::InvalidSeq(3, 1)
+        )),
+        SysValidationIssueReason::Seq {
+            seq: 3,
+            prev_seq: 1
+        }
+    );
+    assert_eq!(
+        SysValidationIssueReason::from(ValidationOutcome::PrevActionError(
+            PrevActionError::HashMismatch(4)
+        )),
+        SysValidationIssueReason::PrevActionMismatch { seq: 4 }
+    );
+    assert_eq!(
+        SysValidationIssueReason::from(ValidationOutcome::Counterfeit(
+            fixt!(Signature),
+            fixt!(Action)
+        )),
+        SysValidationIssueReason::Counterfeit
+    );
+    assert_matches!(
+        SysValidationIssueReason::from(ValidationOutcome::PrevActionError(
+            PrevActionError::MissingPrev
+        )),
+        SysValidationIssueReason::PrevAction(_)
+    );
+    let hash: AnyDhtHash = fixt!(ActionHash).into();
+    assert_eq!(
+        SysValidationIssueReason::from(ValidationOutcome::NotHoldingDep(hash.clone())),
+        SysValidationIssueReason::MissingDependency(hash)
+    );
+}
@@ -1,8 +1,12 @@
 //! Types needed for all validation
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::collections::HashMap;
 use std::convert::TryFrom;
 
-use holo_hash::DhtOpHash;
-use holochain_types::dht_op::DhtOp;
+use holo_hash::ActionHash;
+use holochain_types::dht_op::DhtOpHashed;
+use holochain_zome_types::Action;
 
 use super::workflow::error::WorkflowResult;
 use super::SourceChainError;
@@ -28,68 +32,127 @@ macro_rules! from_sub_error {
     };
 }
 
-/// Type for deriving ordering of DhtOps
-/// Don't change the order of this enum unless
-/// you mean to change the order we process ops
-// TODO: We can probably remove this now?
-#[allow(missing_docs)]
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
-pub enum DhtOpOrder {
-    RegisterAgentActivity(holochain_zome_types::timestamp::Timestamp),
-    StoreEntry(holochain_zome_types::timestamp::Timestamp),
-    StoreRecord(holochain_zome_types::timestamp::Timestamp),
-    RegisterUpdatedContent(holochain_zome_types::timestamp::Timestamp),
-    RegisterUpdatedRecord(holochain_zome_types::timestamp::Timestamp),
-    RegisterDeletedBy(holochain_zome_types::timestamp::Timestamp),
-    RegisterDeletedEntryAction(holochain_zome_types::timestamp::Timestamp),
-    RegisterAddLink(holochain_zome_types::timestamp::Timestamp),
-    RegisterRemoveLink(holochain_zome_types::timestamp::Timestamp),
-}
+/// Split a batch of ops into levels, so that each op is in a later level
+/// than the ops in the batch its validation waits on: the action it updates
+/// or deletes, and the link it removes. Within a level an op comes after the
+/// previous action in its author's chain, and otherwise the ops keep their
+/// order, so a batch sorted by [`OpOrder`](holochain_types::dht_op::OpOrder)
+/// stays sorted by it where there are no dependencies between its ops.
+///
+/// Validating and committing one level before the next lets an op see the
+/// outcomes of the ops in the batch it depends on, rather than waiting for
+/// another pass. The previous action doesn't start a new level, as then a
+/// batch of one author's chain would be validated one action at a time.
+pub fn dependency_levels(ops: Vec<DhtOpHashed>) -> Vec<Vec<DhtOpHashed>> {
+    let actions: Vec<Action> = ops.iter().map(|op| op.as_content().action()).collect();
+    let mut ops_of_action: HashMap<ActionHash, Vec<usize>> = HashMap::new();
+    for (i, action) in actions.iter().enumerate() {
+        ops_of_action
+            .entry(ActionHash::with_data_sync(action))
+            .or_default()
+            .push(i);
+    }
 
-/// Op data that will be ordered by [DhtOpOrder]
-#[derive(Debug, Clone)]
-pub struct OrderedOp<V> {
-    pub order: DhtOpOrder,
-    pub hash: DhtOpHash,
-    pub op: DhtOp,
-    pub value: V,
-}
+    // The ops which depend on each op, and how many ops each op waits on.
+    let mut dependents = vec![Vec::new(); ops.len()];
+    let mut waiting_on = vec![0usize; ops.len()];
+    for (i, action) in actions.iter().enumerate() {
+        for dependency in referenced_action(action) {
+            for &j in ops_of_action.get(dependency).into_iter().flatten() {
+                if i != j {
+                    dependents[j].push(i);
+                    waiting_on[i] += 1;
+                }
+            }
+        }
+    }
 
-// Derivative is no longer working because we have a module called `core`
-// so I have to impl these traits manually.
+    // Each level is the ops which only wait on ops in earlier levels.
+    let mut level: Vec<usize> = (0..ops.len()).filter(|&i| waiting_on[i] == 0).collect();
+    let mut levels = Vec::new();
+    while !level.is_empty() {
+        let mut next = Vec::new();
+        for &i in &level {
+            for &dependent in &dependents[i] {
+                waiting_on[dependent] -= 1;
+                if waiting_on[dependent] == 0 {
+                    next.push(dependent);
+                }
+            }
+        }
+        next.sort_unstable();
+        let level = std::mem::replace(&mut level, next);
+        levels.push(chain_order(level, &actions, &ops_of_action));
+    }
 
-impl<V> PartialEq for OrderedOp<V> {
-    fn eq(&self, other: &Self) -> bool {
-        self.order.eq(&other.order)
+    let mut ops: Vec<_> = ops.into_iter().map(Some).collect();
+    let mut levels: Vec<Vec<_>> = levels
+        .into_iter()
+        .map(|level| level.into_iter().filter_map(|i| ops[i].take()).collect())
+        .collect();
+    // Hashes can't form a cycle, but keep any ops left over just in case.
+    let left_over: Vec<_> = ops.into_iter().flatten().collect();
+    if !left_over.is_empty() {
+        levels.push(left_over);
     }
+    levels
 }
-impl<V> PartialOrd for OrderedOp<V> {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.order.partial_cmp(&other.order)
+
+/// The action whose outcome validating an action waits on.
+fn referenced_action(action: &Action) -> Option<&ActionHash> {
+    match action {
+        Action::Update(update) => Some(&update.original_action_address),
+        Action::Delete(delete) => Some(&delete.deletes_address),
+        Action::DeleteLink(delete_link) => Some(&delete_link.link_add_address),
+        _ => None,
     }
 }
-impl<V> Eq for OrderedOp<V> {}
-impl<V> Ord for OrderedOp<V> {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.order.cmp(&other.order)
+
+/// Order the ops of a level so that each comes after the ops of the previous
+/// action in its author's chain, taking the earliest op in the batch
+/// whenever there is a choice.
+fn chain_order(
+    level: Vec<usize>,
+    actions: &[Action],
+    ops_of_action: &HashMap<ActionHash, Vec<usize>>,
+) -> Vec<usize> {
+    let position: HashMap<usize, usize> = level.iter().enumerate().map(|(p, &i)| (i, p)).collect();
+    let mut dependents = vec![Vec::new(); level.len()];
+    let mut waiting_on = vec![0usize; level.len()];
+    for (p, &i) in level.iter().enumerate() {
+        let prev_ops = actions[i]
+            .prev_action()
+            .and_then(|prev| ops_of_action.get(prev))
+            .into_iter()
+            .flatten();
+        for j in prev_ops {
+            if let Some(&q) = position.get(j) {
+                dependents[q].push(p);
+                waiting_on[p] += 1;
+            }
+        }
     }
-}
 
-impl From<&DhtOp> for DhtOpOrder {
-    fn from(op: &DhtOp) -> Self {
-        use DhtOpOrder::*;
-        match op {
-            DhtOp::StoreRecord(_, h, _) => StoreRecord(h.timestamp()),
-            DhtOp::StoreEntry(_, h, _) => StoreEntry(h.timestamp()),
-            DhtOp::RegisterAgentActivity(_, h) => RegisterAgentActivity(h.timestamp()),
-            DhtOp::RegisterUpdatedContent(_, h, _) => RegisterUpdatedContent(h.timestamp),
-            DhtOp::RegisterUpdatedRecord(_, h, _) => RegisterUpdatedRecord(h.timestamp),
-            DhtOp::RegisterDeletedBy(_, h) => RegisterDeletedBy(h.timestamp),
-            DhtOp::RegisterDeletedEntryAction(_, h) => RegisterDeletedEntryAction(h.timestamp),
-            DhtOp::RegisterAddLink(_, h) => RegisterAddLink(h.timestamp),
-            DhtOp::RegisterRemoveLink(_, h) => RegisterRemoveLink(h.timestamp),
+    let mut ready: BinaryHeap<_> = (0..level.len())
+        .filter(|&p| waiting_on[p] == 0)
+        .map(Reverse)
+        .collect();
+    let mut ordered = Vec::with_capacity(level.len());
+    while let Some(Reverse(p)) = ready.pop() {
+        ordered.push(level[p]);
+        for &dependent in &dependents[p] {
+            waiting_on[dependent] -= 1;
+            if waiting_on[dependent] == 0 {
+                ready.push(Reverse(dependent));
+            }
         }
     }
+    // Hashes can't form a cycle, but keep the batch order just in case.
+    if ordered.len() == level.len() {
+        ordered
+    } else {
+        level
+    }
 }
 
 impl OutcomeOrError<ValidationOutcome, SysValidationError> {
@@ -99,3 +162,73 @@ impl OutcomeOrError<ValidationOutcome, SysValidationError> {
         Err(SourceChainError::InvalidCommit(ValidationOutcome::try_from(self)?.to_string()).into())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::fixt::prelude::*;
+    use holo_hash::HasHash;
+    use holochain_types::dht_op::DhtOp;
+    use holochain_zome_types::fixt::*;
+
+    fn op(action: Action) -> DhtOpHashed {
+        DhtOpHashed::from_content_sync(DhtOp::RegisterAgentActivity(fixt!(Signature), action))
+    }
+
+    #[test]
+    fn ops_are_in_later_levels_than_their_dependencies() {
+        let create = Action::Create(fixt!(Create));
+        let mut update = fixt!(Update);
+        update.prev_action = ActionHash::with_data_sync(&create);
+        let update = Action::Update(update);
+        let mut delete = fixt!(Delete);
+        delete.deletes_address = ActionHash::with_data_sync(&update);
+        let delete = Action::Delete(delete);
+        let ops = vec![
+            op(Action::Create(fixt!(Create))),
+            op(delete),
+            op(update),
+            op(create),
+        ];
+
+        let levels = dependency_levels(ops.clone());
+        let hashes =
+            |ops: &[DhtOpHashed]| -> Vec<_> { ops.iter().map(|op| op.as_hash().clone()).collect() };
+        // The update only follows the create within the first level, while
+        // the delete waits for the update's outcome.
+        let expected = [
+            vec![ops[0].clone(), ops[3].clone(), ops[2].clone()],
+            vec![ops[1].clone()],
+        ];
+        assert_eq!(
+            levels.iter().map(|l| hashes(l)).collect::<Vec<_>>(),
+            expected.iter().map(|l| hashes(l)).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn one_authors_chain_is_not_split_into_a_level_per_action() {
+        let mut actions = Vec::new();
+        let mut prev_action = fixt!(ActionHash);
+        for action_seq in 1..=100 {
+            let mut create = fixt!(Create);
+            create.action_seq = action_seq;
+            create.prev_action = prev_action;
+            let create = Action::Create(create);
+            prev_action = ActionHash::with_data_sync(&create);
+            actions.push(create);
+        }
+        // The batch arrives out of chain order.
+        actions.reverse();
+        let ops: Vec<_> = actions.into_iter().map(op).collect();
+
+        let levels = dependency_levels(ops);
+
+        assert_eq!(levels.len(), 1);
+        let seqs: Vec<_> = levels[0]
+            .iter()
+            .map(|op| op.as_content().action().action_seq())
+            .collect();
+        assert_eq!(seqs, (1..=100).collect::<Vec<_>>());
+    }
+}
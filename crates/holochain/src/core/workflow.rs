@@ -26,10 +26,12 @@ pub mod error;
 pub mod app_validation_workflow;
 pub mod call_zome_workflow;
 pub mod countersigning_workflow;
+pub mod fault_injection;
 pub mod genesis_workflow;
 pub mod incoming_dht_ops_workflow;
 pub mod initialize_zomes_workflow;
 pub mod integrate_dht_ops_workflow;
+pub mod prune_dht_workflow;
 pub mod publish_dht_ops_workflow;
 pub mod sys_validation_workflow;
 pub mod validation_receipt_workflow;
@@ -7,12 +7,14 @@ use super::error::WorkflowResult;
 use super::sys_validation_workflow::validation_query;
 use crate::conductor::ConductorHandle;
 use crate::core::queue_consumer::TriggerSender;
+use crate::core::queue_consumer::WorkBudget;
 use crate::core::queue_consumer::WorkComplete;
 use crate::core::ribosome::guest_callback::validate::ValidateHostAccess;
 use crate::core::ribosome::guest_callback::validate::ValidateInvocation;
 use crate::core::ribosome::guest_callback::validate::ValidateResult;
 use crate::core::ribosome::RibosomeT;
 use crate::core::ribosome::ZomesToInvoke;
+use crate::core::validation::dependency_levels;
 use error::AppValidationResult;
 pub use error::*;
 use futures::stream::StreamExt;
@@ -62,6 +64,7 @@ pub async fn app_validation_workflow(
     conductor_handle: ConductorHandle,
     network: HolochainP2pDna,
     dht_query_cache: DhtDbQueryCache,
+    budget: WorkBudget,
 ) -> WorkflowResult<WorkComplete> {
     let complete = app_validation_workflow_inner(
         dna_hash,
@@ -69,6 +72,7 @@ pub async fn app_validation_workflow(
         conductor_handle,
         &network,
         dht_query_cache,
+        budget,
     )
     .await?;
     // --- END OF WORKFLOW, BEGIN FINISHER BOILERPLATE ---
@@ -85,180 +89,199 @@ async fn app_validation_workflow_inner(
     conductor_handle: ConductorHandle,
     network: &HolochainP2pDna,
     dht_query_cache: DhtDbQueryCache,
+    budget: WorkBudget,
 ) -> WorkflowResult<WorkComplete> {
     let db = workspace.dht_db.clone().into();
-    let sorted_ops = validation_query::get_ops_to_app_validate(&db).await?;
+    let (sorted_ops, over_budget) = validation_query::get_ops_to_app_validate(&db, budget).await?;
     let start_len = sorted_ops.len();
     tracing::debug!("validating {} ops", start_len);
     let start = (start_len >= NUM_CONCURRENT_OPS).then(std::time::Instant::now);
     let saturated = start.is_some();
+    let scheduler = conductor_handle.get_workflow_scheduler();
 
-    // Validate all the ops
-    let iter = sorted_ops.into_iter().map({
-        let network = network.clone();
-        let workspace = workspace.clone();
-        move |so| {
+    let mut total = 0;
+    let mut round_time = start.is_some().then(std::time::Instant::now);
+    // Each level's outcomes are committed before the next level is validated,
+    // so ops which `must_get_valid_record` an op from the same batch see its
+    // outcome instead of awaiting it until the next pass.
+    for level in dependency_levels(sorted_ops) {
+        // Validate all the ops
+        let iter = level.into_iter().map({
             let network = network.clone();
-            let conductor_handle = conductor_handle.clone();
             let workspace = workspace.clone();
+            let conductor_handle = conductor_handle.clone();
             let dna_hash = dna_hash.clone();
-            async move {
-                let (op, op_hash) = so.into_inner();
-                let op_type = op.get_type();
-                let action = op.action();
-                let dependency = get_dependency(op_type, &action);
-                let op_light = op.to_light();
-
-                // If this is agent activity, track it for the cache.
-                let activity = matches!(op_type, DhtOpType::RegisterAgentActivity).then(|| {
-                    (
-                        action.author().clone(),
-                        action.action_seq(),
-                        matches!(dependency, Dependency::Null),
-                    )
-                });
-
-                // Validate this op
-                let mut cascade = workspace.full_cascade(network.clone());
-                let r = match dhtop_to_op(op, &mut cascade).await {
-                    Ok(op) => {
-                        validate_op_outer(dna_hash, &op, &conductor_handle, &(*workspace), &network)
+            let scheduler = scheduler.clone();
+            move |so| {
+                let network = network.clone();
+                let conductor_handle = conductor_handle.clone();
+                let workspace = workspace.clone();
+                let dna_hash = dna_hash.clone();
+                let scheduler = scheduler.clone();
+                async move {
+                    // Wait for this space's fair share of validation slots.
+                    let _permit = scheduler.acquire(dna_hash.clone()).await;
+                    let (op, op_hash) = so.into_inner();
+                    let op_type = op.get_type();
+                    let action = op.action();
+                    let dependency = get_dependency(op_type, &action);
+                    let op_light = op.to_light();
+
+                    // If this is agent activity, track it for the cache.
+                    let activity = matches!(op_type, DhtOpType::RegisterAgentActivity).then(|| {
+                        (
+                            action.author().clone(),
+                            action.action_seq(),
+                            matches!(dependency, Dependency::Null),
+                        )
+                    });
+
+                    // Validate this op
+                    let mut cascade = workspace.full_cascade(network.clone());
+                    let r = match dhtop_to_op(op, &mut cascade).await {
+                        Ok(op) => {
+                            validate_op_outer(
+                                dna_hash,
+                                &op,
+                                &conductor_handle,
+                                &(*workspace),
+                                &network,
+                            )
                             .await
-                    }
-                    Err(e) => Err(e),
-                };
-                (op_hash, dependency, op_light, r, activity)
+                        }
+                        Err(e) => Err(e),
+                    };
+                    (op_hash, dependency, op_light, r, activity)
+                }
             }
-        }
-    });
-
-    // Create a stream of concurrent validation futures.
-    // This will run NUM_CONCURRENT_OPS validation futures concurrently and
-    // return up to NUM_CONCURRENT_OPS * 100 results.
-    let mut iter = futures::stream::iter(iter)
-        .buffer_unordered(NUM_CONCURRENT_OPS)
-        .ready_chunks(NUM_CONCURRENT_OPS * 100);
-
-    // Spawn a task to actually drive the stream.
-    // This allows the stream to make progress in the background while
-    // we are committing previous results to the database.
-    let (tx, rx) = tokio::sync::mpsc::channel(NUM_CONCURRENT_OPS * 100);
-    let jh = tokio::spawn(async move {
-        while let Some(op) = iter.next().await {
-            // Send the result to task that will commit to the database.
-            if tx.send(op).await.is_err() {
-                tracing::warn!("app validation task has failed to send ops. This is not a problem if the conductor is shutting down");
-                break;
+        });
+
+        // Create a stream of concurrent validation futures.
+        // This will run NUM_CONCURRENT_OPS validation futures concurrently and
+        // return up to NUM_CONCURRENT_OPS * 100 results.
+        let mut iter = futures::stream::iter(iter)
+            .buffer_unordered(NUM_CONCURRENT_OPS)
+            .ready_chunks(NUM_CONCURRENT_OPS * 100);
+
+        // Spawn a task to actually drive the stream.
+        // This allows the stream to make progress in the background while
+        // we are committing previous results to the database.
+        let (tx, rx) = tokio::sync::mpsc::channel(NUM_CONCURRENT_OPS * 100);
+        let jh = tokio::spawn(async move {
+            while let Some(op) = iter.next().await {
+                // Send the result to task that will commit to the database.
+                if tx.send(op).await.is_err() {
+                    tracing::warn!("app validation task has failed to send ops. This is not a problem if the conductor is shutting down");
+                    break;
+                }
             }
-        }
-    });
+        });
 
-    // Create a stream that will chunk up to NUM_CONCURRENT_OPS * 100 ready results.
-    let mut iter =
-        tokio_stream::wrappers::ReceiverStream::new(rx).ready_chunks(NUM_CONCURRENT_OPS * 100);
+        // Create a stream that will chunk up to NUM_CONCURRENT_OPS * 100 ready results.
+        let mut iter =
+            tokio_stream::wrappers::ReceiverStream::new(rx).ready_chunks(NUM_CONCURRENT_OPS * 100);
 
-    let mut total = 0;
-    let mut round_time = start.is_some().then(std::time::Instant::now);
-    // Pull in a chunk of results.
-    while let Some(chunk) = iter.next().await {
-        tracing::debug!(
-            "Committing {} ops",
-            chunk.iter().map(|c| c.len()).sum::<usize>()
-        );
-        let (t, a, r, activity) = workspace
-            .dht_db
-            .async_commit(move |txn| {
-                let mut total = 0;
-                let mut awaiting = 0;
-                let mut rejected = 0;
-                let mut agent_activity = Vec::new();
-                for outcome in chunk.into_iter().flatten() {
-                    let (op_hash, dependency, op_light, outcome, activity) = outcome;
-                    // Get the outcome or return the error
-                    let outcome = outcome.or_else(|outcome_or_err| outcome_or_err.try_into())?;
-
-                    // Collect all agent activity.
-                    if let Some(activity) = activity {
-                        // If the activity is accepted or rejected then it's ready to integrate.
-                        if matches!(&outcome, Outcome::Accepted | Outcome::Rejected(_)) {
-                            agent_activity.push(activity);
+        // Pull in a chunk of results.
+        while let Some(chunk) = iter.next().await {
+            tracing::debug!(
+                "Committing {} ops",
+                chunk.iter().map(|c| c.len()).sum::<usize>()
+            );
+            let (t, a, r, activity) = workspace
+                .dht_db
+                .async_commit(move |txn| {
+                    let mut total = 0;
+                    let mut awaiting = 0;
+                    let mut rejected = 0;
+                    let mut agent_activity = Vec::new();
+                    for outcome in chunk.into_iter().flatten() {
+                        let (op_hash, dependency, op_light, outcome, activity) = outcome;
+                        // Get the outcome or return the error
+                        let outcome = outcome.or_else(|outcome_or_err| outcome_or_err.try_into())?;
+
+                        // Collect all agent activity.
+                        if let Some(activity) = activity {
+                            // If the activity is accepted or rejected then it's ready to integrate.
+                            if matches!(&outcome, Outcome::Accepted | Outcome::Rejected(_)) {
+                                agent_activity.push(activity);
+                            }
                         }
-                    }
 
 
-                    if let Outcome::AwaitingDeps(_) | Outcome::Rejected(_) = &outcome {
-                        warn!(
-                            msg = "DhtOp has failed app validation",
-                            outcome = ?outcome,
-                        );
-                    }
-                    match outcome {
-                        Outcome::Accepted => {
-                            total += 1;
-                            if let Dependency::Null = dependency {
-                                put_integrated(txn, &op_hash, ValidationStatus::Valid)?;
-                            } else {
-                                put_integration_limbo(txn, &op_hash, ValidationStatus::Valid)?;
-                            }
-                        }
-                        Outcome::AwaitingDeps(deps) => {
-                            awaiting += 1;
-                            let status = ValidationLimboStatus::AwaitingAppDeps(deps);
-                            put_validation_limbo(txn, &op_hash, status)?;
+                        if let Outcome::AwaitingDeps(_) | Outcome::Rejected(_) = &outcome {
+                            warn!(
+                                msg = "DhtOp has failed app validation",
+                                outcome = ?outcome,
+                            );
                         }
-                        Outcome::Rejected(_) => {
-                            rejected += 1;
-                            tracing::warn!("Received invalid op! Warrants aren't implemented yet, so we can't do anything about this right now, but be warned that somebody on the network has maliciously hacked their node.\nOp: {:?}", op_light);
-                            if let Dependency::Null = dependency {
-                                put_integrated(txn, &op_hash, ValidationStatus::Rejected)?;
-                            } else {
-                                put_integration_limbo(txn, &op_hash, ValidationStatus::Rejected)?;
+                        match outcome {
+                            Outcome::Accepted => {
+                                total += 1;
+                                if let Dependency::Null = dependency {
+                                    put_integrated(txn, &op_hash, ValidationStatus::Valid)?;
+                                } else {
+                                    put_integration_limbo(txn, &op_hash, ValidationStatus::Valid)?;
+                                }
+                            }
+                            Outcome::AwaitingDeps(deps) => {
+                                awaiting += 1;
+                                let status = ValidationLimboStatus::AwaitingAppDeps(deps);
+                                put_validation_limbo(txn, &op_hash, status)?;
+                            }
+                            Outcome::Rejected(_) => {
+                                rejected += 1;
+                                tracing::warn!("Received invalid op! Warrants aren't implemented yet, so we can't do anything about this right now, but be warned that somebody on the network has maliciously hacked their node.\nOp: {:?}", op_light);
+                                if let Dependency::Null = dependency {
+                                    put_integrated(txn, &op_hash, ValidationStatus::Rejected)?;
+                                } else {
+                                    put_integration_limbo(txn, &op_hash, ValidationStatus::Rejected)?;
+                                }
                             }
                         }
                     }
+                    WorkflowResult::Ok((total, awaiting, rejected, agent_activity))
+                })
+                .await?;
+            // Once the database transaction is committed, add agent activity to the cache
+            // that is ready for integration.
+            for (author, seq, has_no_dependency) in activity {
+                // Any activity with no dependency is integrated in this workflow.
+                // TODO: This will no longer be true when [#1212](https://github.com/holochain/holochain/pull/1212) lands.
+                if has_no_dependency {
+                    dht_query_cache
+                        .set_activity_to_integrated(&author, seq)
+                        .await?;
+                } else {
+                    dht_query_cache
+                        .set_activity_ready_to_integrate(&author, seq)
+                        .await?;
                 }
-                WorkflowResult::Ok((total, awaiting, rejected, agent_activity))
-            })
-            .await?;
-        // Once the database transaction is committed, add agent activity to the cache
-        // that is ready for integration.
-        for (author, seq, has_no_dependency) in activity {
-            // Any activity with no dependency is integrated in this workflow.
-            // TODO: This will no longer be true when [#1212](https://github.com/holochain/holochain/pull/1212) lands.
-            if has_no_dependency {
-                dht_query_cache
-                    .set_activity_to_integrated(&author, seq)
-                    .await?;
-            } else {
-                dht_query_cache
-                    .set_activity_ready_to_integrate(&author, seq)
-                    .await?;
             }
-        }
-        total += t;
-        if let (Some(start), Some(round_time)) = (start, &mut round_time) {
-            let round_el = round_time.elapsed();
-            *round_time = std::time::Instant::now();
-            let avg_ops_ps = total as f64 / start.elapsed().as_micros() as f64 * 1_000_000.0;
-            let ops_ps = t as f64 / round_el.as_micros() as f64 * 1_000_000.0;
-            tracing::warn!(
-                "App validation is saturated. Util {:.2}%. OPS/s avg {:.2}, this round {:.2}",
-                (start_len - total) as f64 / NUM_CONCURRENT_OPS as f64 * 100.0,
-                avg_ops_ps,
-                ops_ps
+            total += t;
+            if let (Some(start), Some(round_time)) = (start, &mut round_time) {
+                let round_el = round_time.elapsed();
+                *round_time = std::time::Instant::now();
+                let avg_ops_ps = total as f64 / start.elapsed().as_micros() as f64 * 1_000_000.0;
+                let ops_ps = t as f64 / round_el.as_micros() as f64 * 1_000_000.0;
+                tracing::warn!(
+                    "App validation is saturated. Util {:.2}%. OPS/s avg {:.2}, this round {:.2}",
+                    (start_len - total) as f64 / NUM_CONCURRENT_OPS as f64 * 100.0,
+                    avg_ops_ps,
+                    ops_ps
+                );
+            }
+            tracing::debug!(
+                "{} committed, {} awaiting sys dep, {} rejected. {} committed this round",
+                t,
+                a,
+                r,
+                total
             );
         }
-        tracing::debug!(
-            "{} committed, {} awaiting sys dep, {} rejected. {} committed this round",
-            t,
-            a,
-            r,
-            total
-        );
+        jh.await?;
     }
-    jh.await?;
     tracing::debug!("accepted {} ops", total);
-    Ok(if saturated {
+    Ok(if saturated || over_budget {
         WorkComplete::Incomplete
     } else {
         WorkComplete::Complete
@@ -454,6 +477,12 @@ pub async fn validate_op<R>(
 where
     R: RibosomeT,
 {
+    // Chunks of blobs have no meaning to the app, so checking
+    // their type and size during sys validation is enough.
+    if is_chunk_op(op) {
+        return Ok(Outcome::Accepted);
+    }
+
     let zomes_to_invoke = match op {
         Op::RegisterAgentActivity(RegisterAgentActivity { .. }) => ZomesToInvoke::AllIntegrity,
         Op::StoreRecord(StoreRecord { record }) => {
@@ -508,6 +537,23 @@ where
     Ok(outcome)
 }
 
+/// Is this op for storing a chunk of a blob.
+/// Agent activity is still validated by the app, as it validates the
+/// author's chain, and so are deletes, as they may delete a chunk the app
+/// still needs.
+fn is_chunk_op(op: &Op) -> bool {
+    let entry_type = match op {
+        Op::StoreRecord(StoreRecord { record }) => record.action().entry_type(),
+        Op::StoreEntry(StoreEntry { action, .. }) => Some(action.hashed.entry_type()),
+        Op::RegisterUpdate(RegisterUpdate { update, .. }) => Some(&update.hashed.entry_type),
+        Op::RegisterAgentActivity(_)
+        | Op::RegisterDelete(_)
+        | Op::RegisterCreateLink(_)
+        | Op::RegisterDeleteLink(_) => None,
+    };
+    matches!(entry_type, Some(EntryType::Chunk))
+}
+
 pub fn entry_creation_zomes_to_invoke(
     action: &EntryCreationAction,
     ribosome: &impl RibosomeT,
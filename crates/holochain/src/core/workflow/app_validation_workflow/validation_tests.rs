@@ -1,13 +1,18 @@
 use std::{
     collections::{HashMap, HashSet},
     fmt::Write,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
 };
 
 use holo_hash::{ActionHash, AgentPubKey};
 use holochain_types::{dht_op::DhtOpType, inline_zome::InlineZomeSet};
 use holochain_zome_types::{
     op::*, Action, ActionType, AppEntryType, BoxApi, ChainTopOrdering, CreateInput, Entry,
-    EntryDef, EntryDefIndex, EntryVisibility, TryInto, ZomeId,
+    EntryDef, EntryDefIndex, EntryVisibility, MustGetValidRecordInput, TryInto, UpdateInput,
+    ZomeId,
 };
 
 use crate::{
@@ -446,3 +451,66 @@ async fn app_validation_ops() {
         );
     }
 }
+
+#[tokio::test(flavor = "multi_thread")]
+/// Test that an update validated in the same batch as the create it updates
+/// sees the create's outcome, rather than awaiting it until the next pass.
+async fn app_validation_sees_dependencies_from_the_same_batch() {
+    observability::test_run().ok();
+    let unresolved = Arc::new(AtomicUsize::new(0));
+
+    let zomes = InlineZomeSet::new_unique_single(
+        "integrity",
+        "coordinator",
+        vec![EntryDef::default_with_id("a")],
+        0,
+    )
+    .callback("coordinator", "create_and_update", |api: BoxApi, ()| {
+        let entry = Entry::app(().try_into().unwrap()).unwrap();
+        let create = api.create(CreateInput::new(
+            InlineZomeSet::get_entry_location(&api, EntryDefIndex(0)),
+            EntryVisibility::Public,
+            entry.clone(),
+            ChainTopOrdering::default(),
+        ))?;
+        let update = api.update(UpdateInput {
+            original_action_address: create,
+            entry,
+            chain_top_ordering: ChainTopOrdering::default(),
+        })?;
+        Ok(update)
+    })
+    .callback("integrity", "validate", {
+        let unresolved = unresolved.clone();
+        move |api: BoxApi, op: Op| {
+            if let Op::RegisterUpdate(RegisterUpdate { update, .. }) = op {
+                let original = update.hashed.original_action_address.clone();
+                api.must_get_valid_record(MustGetValidRecordInput::new(original))
+                    .map_err(|e| {
+                        unresolved.fetch_add(1, Ordering::SeqCst);
+                        e
+                    })?;
+            }
+            Ok(ValidateResult::Valid)
+        }
+    });
+    let (dna_file, _, _) = SweetDnaFile::unique_from_inline_zomes(zomes).await.unwrap();
+
+    let mut conductors = SweetConductorBatch::from_standard_config(2).await;
+    let apps = conductors
+        .setup_app(&"test_app", &[dna_file])
+        .await
+        .unwrap();
+    let ((alice,), (bob,)) = apps.into_tuples();
+    conductors.exchange_peer_info().await;
+
+    // The author validates its own ops locally, so it's bob who app
+    // validates the update and the create.
+    let _: ActionHash = conductors[0]
+        .call(&alice.zome("coordinator"), "create_and_update", ())
+        .await;
+
+    consistency_10s(&[&alice, &bob]).await;
+
+    assert_eq!(unresolved.load(Ordering::SeqCst), 0);
+}
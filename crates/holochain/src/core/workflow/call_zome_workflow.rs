@@ -8,8 +8,10 @@ use crate::conductor::api::CellConductorApiT;
 use crate::conductor::interface::SignalBroadcaster;
 use crate::conductor::ConductorHandle;
 use crate::core::queue_consumer::TriggerSender;
+use crate::core::ribosome::error::RibosomeError;
 use crate::core::ribosome::error::RibosomeResult;
 use crate::core::ribosome::guest_callback::post_commit::send_post_commit;
+use crate::core::ribosome::zome_call_limits::ZomeCallCancel;
 use crate::core::ribosome::RibosomeT;
 use crate::core::ribosome::ZomeCallHostAccess;
 use crate::core::ribosome::ZomeCallInvocation;
@@ -22,11 +24,15 @@ use holochain_state::source_chain::SourceChainError;
 use holochain_zome_types::record::Record;
 
 use holochain_types::prelude::*;
+use std::sync::Arc;
 use tracing::instrument;
 
+mod chain_head_coordination;
 #[cfg(test)]
 mod validation_test;
 
+pub use chain_head_coordination::ChainHeadCoordinator;
+
 /// Placeholder for the return value of a zome invocation
 pub type ZomeCallResult = RibosomeResult<ZomeCallResponse>;
 
@@ -37,6 +43,12 @@ pub struct CallZomeWorkflowArgs<RibosomeT> {
     pub conductor_handle: ConductorHandle,
     pub is_root_zome_call: bool,
     pub cell_id: CellId,
+    /// Coordinates the writes of the call with the other devices sharing
+    /// the agent key, if chain head coordination is enabled.
+    pub chain_head_coordinator: Arc<ChainHeadCoordinator>,
+    /// Aborts the call, dropping its writes, if cancelled before the call
+    /// is committed.
+    pub cancel: ZomeCallCancel,
 }
 
 #[instrument(skip(
@@ -65,9 +77,19 @@ where
         .ok();
     let should_write = args.is_root_zome_call;
     let conductor_handle = args.conductor_handle.clone();
+    let chain_head_coordinator = args.chain_head_coordinator.clone();
+    // Everything this call writes is dropped if it fails, so a failed
+    // call neither commits nor leaves writes for a calling zome to commit.
+    let savepoint = workspace.source_chain().savepoint()?;
     let result =
-        call_zome_workflow_inner(workspace.clone(), network.clone(), keystore.clone(), args)
-            .await?;
+        call_zome_workflow_inner(workspace.clone(), network.clone(), keystore.clone(), args).await;
+    if !matches!(result, Ok(Ok(_))) {
+        let rolled_back = workspace.source_chain().rollback_to(savepoint)?;
+        if rolled_back > 0 {
+            tracing::debug!(rolled_back, "Rolled back the writes of a failed zome call");
+        }
+    }
+    let result = result?;
 
     // --- END OF WORKFLOW, BEGIN FINISHER BOILERPLATE ---
 
@@ -75,9 +97,19 @@ where
     if should_write {
         let is_empty = workspace.source_chain().is_empty()?;
         let countersigning_op = workspace.source_chain().countersigning_op()?;
-        let flushed_actions: Vec<SignedActionHashed> = HostFnWorkspace::from(workspace.clone())
-            .flush(&network)
-            .await?;
+        let coordination = conductor_handle.get_config().chain_head_coordination;
+        let flushed_actions: Vec<SignedActionHashed> = match (is_empty, &coordination) {
+            (false, Some(config)) => {
+                chain_head_coordinator
+                    .flush(&workspace, &network, config)
+                    .await?
+            }
+            _ => {
+                HostFnWorkspace::from(workspace.clone())
+                    .flush(&network)
+                    .await?
+            }
+        };
         if !is_empty {
             match countersigning_op {
                 Some(op) => {
@@ -126,8 +158,11 @@ where
         signal_tx,
         conductor_handle,
         cell_id,
+        cancel,
         ..
     } = args;
+    let zome_name = invocation.zome.zome_name().clone();
+    let fn_name = invocation.fn_name.clone();
 
     let call_zome_handle =
         CellConductorApi::new(conductor_handle.clone(), cell_id).into_call_zome_handle();
@@ -139,10 +174,19 @@ where
         network.clone(),
         signal_tx,
         call_zome_handle,
+        conductor_handle
+            .get_config()
+            .zome_call_limits
+            .unwrap_or_default(),
+        cancel.clone(),
     );
     let (ribosome, result) =
         call_zome_function_authorized(ribosome, host_access, invocation).await?;
     tracing::trace!("After zome call");
+    // A call cancelled after the guest returned is rolled back all the same.
+    if cancel.is_cancelled() {
+        return Ok(Err(RibosomeError::ZomeCallCancelled(zome_name, fn_name)));
+    }
 
     let validation_result =
         inline_validation(workspace.clone(), network, conductor_handle, ribosome).await;
@@ -0,0 +1,124 @@
+use super::super::error::WorkflowResult;
+use holochain_conductor_api::config::conductor::ChainHeadCoordinationConfig;
+use holochain_p2p::actor::GetActivityOptions;
+use holochain_p2p::HolochainP2pDnaT;
+use holochain_state::host_fn_workspace::HostFnWorkspace;
+use holochain_state::host_fn_workspace::SourceChainWorkspace;
+use holochain_state::source_chain::chain_head_db;
+use holochain_state::source_chain::check_chain_head_agreement;
+use holochain_state::source_chain::SourceChainError;
+use holochain_types::prelude::*;
+use std::sync::Arc;
+
+#[cfg(test)]
+mod tests;
+
+/// Coordinates the chain head of a cell with the other devices sharing its
+/// agent key.
+#[derive(Default)]
+pub struct ChainHeadCoordinator {
+    /// Held from reading the chain head the authorities are asked about
+    /// until the write on top of it is committed, so that no other zome call
+    /// of the cell can move the head in between.
+    write_lock: tokio::sync::Mutex<()>,
+    agreed: parking_lot::Mutex<Option<ChainHead>>,
+}
+
+impl ChainHeadCoordinator {
+    /// The chain head of the last write committed after the agent activity
+    /// authorities agreed that no other device had written on top of the
+    /// head it built on.
+    pub fn agreed_chain_head(&self) -> Option<ChainHead> {
+        self.agreed.lock().clone()
+    }
+
+    /// Flush the workspace's writes, after asking the agent activity
+    /// authorities whether another device sharing the agent key has written
+    /// on top of the chain head they will be committed on. The flush fails
+    /// with a [`ChainHeadConflict`](holochain_state::source_chain::ChainHeadConflict)
+    /// if one has.
+    ///
+    /// If the authorities can't be reached they can't report a conflict, so
+    /// the writes are committed without agreement. That includes none of them
+    /// answering, and the network being disabled, when gets return nothing.
+    pub(super) async fn flush<N>(
+        &self,
+        workspace: &SourceChainWorkspace,
+        network: &N,
+        config: &ChainHeadCoordinationConfig,
+    ) -> WorkflowResult<Vec<SignedActionHashed>>
+    where
+        N: HolochainP2pDnaT + Clone + Send + Sync + 'static,
+    {
+        let _write = self.write_lock.lock().await;
+        let source_chain = workspace.source_chain();
+        // Writes are rebased onto the head in the database when it has
+        // moved since the workspace was created, so that is the head which
+        // needs agreeing on.
+        let author = Arc::new(source_chain.agent_pubkey().clone());
+        let (hash, action_seq, _) = source_chain
+            .author_db()
+            .async_reader(move |txn| chain_head_db(&txn, author))
+            .await?;
+        let local = ChainHead { action_seq, hash };
+        let agreed = check_chain_head(workspace, network, &local, config).await?;
+        let flushed_actions = HostFnWorkspace::from(workspace.clone())
+            .flush(network)
+            .await?;
+        if let (true, Some(head)) = (agreed, flushed_actions.last()) {
+            *self.agreed.lock() = Some(ChainHead {
+                action_seq: head.action().action_seq(),
+                hash: head.as_hash().clone(),
+            });
+        }
+        Ok(flushed_actions)
+    }
+}
+
+/// Ask the agent activity authorities whether another device has written on
+/// top of `local`, returning whether they could be asked.
+async fn check_chain_head<N>(
+    workspace: &SourceChainWorkspace,
+    network: &N,
+    local: &ChainHead,
+    config: &ChainHeadCoordinationConfig,
+) -> WorkflowResult<bool>
+where
+    N: HolochainP2pDnaT + Clone + Send + Sync + 'static,
+{
+    let options = GetActivityOptions {
+        timeout_ms: config.timeout_ms,
+        include_valid_activity: false,
+        include_rejected_activity: false,
+        ..Default::default()
+    };
+    // The network is asked even if this node is an authority itself, as its
+    // own view of the chain is not an agreement with anyone.
+    let agent = workspace.source_chain().agent_pubkey().clone();
+    let responses = match network
+        .get_agent_activity(agent.clone(), ChainQueryFilter::new(), options)
+        .await
+    {
+        Ok(responses) if !responses.is_empty() => responses,
+        Ok(_) => {
+            tracing::warn!(
+                "Committing without chain head agreement, as no agent activity authority answered"
+            );
+            return Ok(false);
+        }
+        Err(error) => {
+            tracing::warn!(
+                ?error,
+                "Committing without chain head agreement, as the agent activity authorities could not be reached"
+            );
+            return Ok(false);
+        }
+    };
+    let highest_observed = responses
+        .into_iter()
+        .filter(|response| response.agent == agent)
+        .filter_map(|response| response.highest_observed)
+        .max_by_key(|observed| observed.action_seq);
+    check_chain_head_agreement(local, highest_observed.as_ref()).map_err(SourceChainError::from)?;
+    Ok(true)
+}
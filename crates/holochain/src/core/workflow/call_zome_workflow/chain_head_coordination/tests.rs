@@ -0,0 +1,223 @@
+use super::*;
+use crate::core::workflow::error::WorkflowError;
+use crate::fixt::DnaDefFixturator;
+use crate::test_utils::fake_genesis;
+use ::fixt::prelude::*;
+use holochain_cascade::test_utils::MockNetwork;
+use holochain_keystore::MetaLairClient;
+use holochain_p2p::HolochainP2pError;
+use holochain_p2p::MockHolochainP2pDnaT;
+use holochain_state::prelude::test_authored_db;
+use holochain_state::prelude::test_cache_db;
+use holochain_state::prelude::test_dht_db;
+use holochain_state::source_chain::ChainHeadConflict;
+use holochain_state::test_utils::test_keystore;
+use holochain_types::db_cache::DhtDbQueryCache;
+use holochain_zome_types::fake_agent_pubkey_1;
+use matches::assert_matches;
+
+struct TestChain {
+    authored: DbWrite<DbKindAuthored>,
+    dht: DbWrite<DbKindDht>,
+    cache: DbWrite<DbKindCache>,
+    keystore: MetaLairClient,
+    dna_def: Arc<DnaDef>,
+}
+
+impl TestChain {
+    async fn new() -> Self {
+        let authored = test_authored_db().to_db();
+        let dht = test_dht_db().to_db();
+        let keystore = test_keystore();
+        fake_genesis(authored.clone(), dht.clone(), keystore.clone())
+            .await
+            .unwrap();
+        Self {
+            authored,
+            dht,
+            cache: test_cache_db().to_db(),
+            keystore,
+            dna_def: Arc::new(fixt!(DnaDef)),
+        }
+    }
+
+    /// A workspace with one create written to its scratch.
+    async fn workspace_with_write(&self) -> SourceChainWorkspace {
+        let workspace = SourceChainWorkspace::new(
+            self.authored.clone(),
+            self.dht.clone(),
+            DhtDbQueryCache::new(self.dht.clone().into()),
+            self.cache.clone(),
+            self.keystore.clone(),
+            fake_agent_pubkey_1(),
+            self.dna_def.clone(),
+        )
+        .await
+        .unwrap();
+        let entry = Entry::App(fixt!(AppEntryBytes));
+        let create = builder::Create {
+            entry_type: EntryType::App(fixt!(AppEntryType)),
+            entry_hash: EntryHash::with_data_sync(&entry),
+        };
+        workspace
+            .source_chain()
+            .put_weightless(create, Some(entry), ChainTopOrdering::Relaxed)
+            .await
+            .unwrap();
+        workspace
+    }
+
+    async fn head(&self) -> ChainHead {
+        let author = Arc::new(fake_agent_pubkey_1());
+        let (hash, action_seq, _) = self
+            .authored
+            .async_reader(move |txn| chain_head_db(&txn, author))
+            .await
+            .unwrap();
+        ChainHead { action_seq, hash }
+    }
+}
+
+/// A network whose agent activity authorities have observed the chain up to
+/// `highest_observed`, or can't be reached if it is `None`.
+fn network(highest_observed: Option<ChainHead>) -> MockNetwork {
+    let mut mock = MockHolochainP2pDnaT::new();
+    mock.expect_authority_for_hash().returning(|_| Ok(false));
+    mock.expect_get_agent_activity()
+        .returning(move |agent, _, _| match highest_observed.clone() {
+            Some(head) => Ok(vec![AgentActivityResponse {
+                agent,
+                valid_activity: ChainItems::NotRequested,
+                rejected_activity: ChainItems::NotRequested,
+                status: ChainStatus::Valid(head.clone()),
+                highest_observed: Some(HighestObserved {
+                    action_seq: head.action_seq,
+                    hash: vec![head.hash],
+                }),
+                warrants: Vec::new(),
+            }]),
+            None => Err(HolochainP2pError::other("unreachable")),
+        });
+    MockNetwork::new(mock)
+}
+
+/// A network where no agent activity authority answers, as when it is
+/// disabled.
+fn silent_network() -> MockNetwork {
+    let mut mock = MockHolochainP2pDnaT::new();
+    mock.expect_authority_for_hash().returning(|_| Ok(false));
+    mock.expect_get_agent_activity()
+        .returning(|_, _, _| Ok(vec![]));
+    MockNetwork::new(mock)
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn writes_on_the_observed_head_are_agreed() {
+    let chain = TestChain::new().await;
+    let head = chain.head().await;
+    let coordinator = ChainHeadCoordinator::default();
+
+    let workspace = chain.workspace_with_write().await;
+    let flushed = coordinator
+        .flush(&workspace, &network(Some(head)), &Default::default())
+        .await
+        .unwrap();
+
+    assert_eq!(flushed.len(), 1);
+    assert_eq!(coordinator.agreed_chain_head(), Some(chain.head().await));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn writes_behind_another_device_are_refused() {
+    let chain = TestChain::new().await;
+    let head = chain.head().await;
+    let coordinator = ChainHeadCoordinator::default();
+    let other_device = ChainHead {
+        action_seq: head.action_seq + 1,
+        hash: fixt!(ActionHash),
+    };
+
+    let workspace = chain.workspace_with_write().await;
+    let result = coordinator
+        .flush(
+            &workspace,
+            &network(Some(other_device)),
+            &Default::default(),
+        )
+        .await;
+
+    assert_matches!(
+        result,
+        Err(WorkflowError::SourceChainError(
+            SourceChainError::ChainHeadConflict(ChainHeadConflict::Behind { .. })
+        ))
+    );
+    assert_eq!(chain.head().await, head);
+    assert_eq!(coordinator.agreed_chain_head(), None);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn unreachable_authorities_do_not_fail_the_write() {
+    let chain = TestChain::new().await;
+    let head = chain.head().await;
+    let coordinator = ChainHeadCoordinator::default();
+
+    let workspace = chain.workspace_with_write().await;
+    let flushed = coordinator
+        .flush(&workspace, &network(None), &Default::default())
+        .await
+        .unwrap();
+
+    assert_eq!(flushed.len(), 1);
+    assert_eq!(chain.head().await.action_seq, head.action_seq + 1);
+    // Nothing was agreed on.
+    assert_eq!(coordinator.agreed_chain_head(), None);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn no_answer_from_the_authorities_is_not_agreement() {
+    let chain = TestChain::new().await;
+    let head = chain.head().await;
+    let coordinator = ChainHeadCoordinator::default();
+
+    let workspace = chain.workspace_with_write().await;
+    let flushed = coordinator
+        .flush(&workspace, &silent_network(), &Default::default())
+        .await
+        .unwrap();
+
+    assert_eq!(flushed.len(), 1);
+    assert_eq!(chain.head().await.action_seq, head.action_seq + 1);
+    assert_eq!(coordinator.agreed_chain_head(), None);
+}
+
+/// A write from a workspace created before another call of this device
+/// committed is rebased onto that call's write, so that is the head the
+/// authorities are asked about.
+#[tokio::test(flavor = "multi_thread")]
+async fn the_head_written_on_is_the_one_agreed_on() {
+    let chain = TestChain::new().await;
+    let coordinator = ChainHeadCoordinator::default();
+
+    let stale = chain.workspace_with_write().await;
+    let head = chain.head().await;
+    coordinator
+        .flush(
+            &chain.workspace_with_write().await,
+            &network(Some(head)),
+            &Default::default(),
+        )
+        .await
+        .unwrap();
+
+    // The authorities have seen the other call's write.
+    let moved = chain.head().await;
+    let flushed = coordinator
+        .flush(&stale, &network(Some(moved.clone())), &Default::default())
+        .await
+        .unwrap();
+
+    assert_eq!(flushed.len(), 1);
+    assert_eq!(flushed[0].action().prev_action(), Some(&moved.hash));
+    assert_eq!(coordinator.agreed_chain_head(), Some(chain.head().await));
+}
@@ -10,10 +10,13 @@ use holochain_state::mutations;
 use holochain_state::prelude::{
     current_countersigning_session, SourceChainResult, StateMutationResult, Store,
 };
+use holochain_types::chain::MustGetAgentActivityResponse;
 use holochain_types::dht_op::DhtOp;
 use holochain_types::signal::{Signal, SystemSignal};
 use holochain_zome_types::Timestamp;
-use holochain_zome_types::{Entry, SignedAction, ZomeCallResponse};
+use holochain_zome_types::{
+    ChainFilter, CounterSigningSessionData, Entry, SignedAction, ZomeCallResponse,
+};
 use kitsune_p2p_types::tx2::tx2_utils::Share;
 use rusqlite::{named_params, Transaction};
 
@@ -339,6 +342,47 @@ pub async fn countersigning_publish(
     Ok(())
 }
 
+/// Check with the agent activity authorities of every signer of a session
+/// that none of them hold any of the session's actions. A signer which sees
+/// the session succeed publishes the activity of every signer, so the
+/// session has only failed if none are found.
+///
+/// Returns false if any action is found, or if no authorities responded
+/// for one of the signers, as then the session can't be confirmed to have
+/// failed.
+pub(crate) async fn countersigning_session_failed<N>(
+    network: &N,
+    entry_hash: EntryHash,
+    session_data: &CounterSigningSessionData,
+) -> WorkflowResult<bool>
+where
+    N: HolochainP2pDnaT,
+{
+    let actions = session_data.build_action_set(entry_hash, weigh_placeholder())?;
+    for action in actions {
+        let action_hash = ActionHash::with_data_sync(&action);
+        let responses = network
+            .must_get_agent_activity(
+                action.author().clone(),
+                ChainFilter::new(action_hash.clone()).take(1),
+            )
+            .await?;
+        if responses.is_empty() {
+            return Ok(false);
+        }
+        let found = responses.iter().any(|response| match response {
+            MustGetAgentActivityResponse::Activity(activity) => activity
+                .iter()
+                .any(|activity| *activity.action.as_hash() == action_hash),
+            _ => false,
+        });
+        if found {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
 type AgentsToNotify = Vec<AgentPubKey>;
 type Ops = Vec<(DhtOpHash, DhtOp)>;
 type SignedActions = Vec<SignedAction>;
@@ -448,9 +492,133 @@ impl Default for CountersigningWorkspace {
 #[cfg(test)]
 mod tests {
     use arbitrary::Arbitrary;
+    use holo_hash::HasHash;
+    use holochain_p2p::HolochainP2pError;
+    use holochain_p2p::MockHolochainP2pDnaT;
+    use holochain_zome_types::*;
 
     use super::*;
 
+    /// A session between two agents, and the hash of its entry.
+    fn session(u: &mut arbitrary::Unstructured) -> (EntryHash, CounterSigningSessionData) {
+        let start = Timestamp::now();
+        let session_times = CounterSigningSessionTimes::try_new(
+            start,
+            (start + SESSION_ACTION_TIME_OFFSET).unwrap(),
+        )
+        .unwrap();
+        let preflight_request = PreflightRequest::try_new(
+            EntryHash::arbitrary(u).unwrap(),
+            vec![
+                (fake_agent_pubkey_1(), vec![]),
+                (fake_agent_pubkey_2(), vec![]),
+            ],
+            vec![],
+            0,
+            false,
+            session_times,
+            ActionBase::Create(CreateBase::new(EntryType::App(
+                AppEntryType::arbitrary(u).unwrap(),
+            ))),
+            PreflightBytes(vec![]),
+        )
+        .unwrap();
+        let responses = (0..2)
+            .map(|agent_index| {
+                (
+                    CounterSigningAgentState::new(
+                        agent_index,
+                        ActionHash::arbitrary(u).unwrap(),
+                        3,
+                    ),
+                    Signature::arbitrary(u).unwrap(),
+                )
+            })
+            .collect();
+        let session_data =
+            CounterSigningSessionData::try_new(preflight_request, responses, vec![]).unwrap();
+        (EntryHash::arbitrary(u).unwrap(), session_data)
+    }
+
+    /// A network whose agent activity authorities respond with the activity
+    /// `found` for the actions `found` is called with.
+    fn network<F>(found: F) -> MockHolochainP2pDnaT
+    where
+        F: Fn(ActionHash) -> Vec<MustGetAgentActivityResponse> + Send + 'static,
+    {
+        let mut network = MockHolochainP2pDnaT::new();
+        network
+            .expect_must_get_agent_activity()
+            .returning(move |_, filter| Ok(found(filter.chain_top)));
+        network
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn session_failed_if_no_signer_action_is_found() {
+        let mut u = arbitrary::Unstructured::new(&holochain_zome_types::NOISE);
+        let (entry_hash, session_data) = session(&mut u);
+        let network = network(|action_hash| {
+            vec![MustGetAgentActivityResponse::ChainTopNotFound(action_hash)]
+        });
+        assert!(
+            countersigning_session_failed(&network, entry_hash, &session_data)
+                .await
+                .unwrap()
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn session_not_failed_if_a_signer_action_is_found() {
+        let mut u = arbitrary::Unstructured::new(&holochain_zome_types::NOISE);
+        let (entry_hash, session_data) = session(&mut u);
+        let actions = session_data
+            .build_action_set(entry_hash.clone(), weigh_placeholder())
+            .unwrap();
+        // Only the second signer's action has been published.
+        let published = actions[1].clone();
+        let signature = Signature::arbitrary(&mut u).unwrap();
+        let network = network(move |action_hash| {
+            let published = ActionHashed::from_content_sync(published.clone());
+            if *published.as_hash() == action_hash {
+                vec![MustGetAgentActivityResponse::Activity(vec![
+                    RegisterAgentActivity {
+                        action: SignedActionHashed::with_presigned(published, signature.clone()),
+                        cached_entry: None,
+                    },
+                ])]
+            } else {
+                vec![MustGetAgentActivityResponse::ChainTopNotFound(action_hash)]
+            }
+        });
+        assert!(
+            !countersigning_session_failed(&network, entry_hash, &session_data)
+                .await
+                .unwrap()
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn session_not_failed_without_responses() {
+        let mut u = arbitrary::Unstructured::new(&holochain_zome_types::NOISE);
+        let (entry_hash, session_data) = session(&mut u);
+        let network = network(|_| vec![]);
+        assert!(
+            !countersigning_session_failed(&network, entry_hash.clone(), &session_data)
+                .await
+                .unwrap()
+        );
+
+        let mut network = MockHolochainP2pDnaT::new();
+        network
+            .expect_must_get_agent_activity()
+            .returning(|_, _| Err(HolochainP2pError::other("unreachable")));
+        assert!(
+            countersigning_session_failed(&network, entry_hash, &session_data)
+                .await
+                .is_err()
+        );
+    }
+
     #[test]
     /// Test that a session of 5 actions is complete when
     /// the expiry time is in the future and all required actions
@@ -0,0 +1,156 @@
+//! Faults which tests can inject into workflows, so that the paths which
+//! recover from failed workflow steps are exercised.
+//!
+//! Each [`FaultPoint`] is a step of a workflow which can fail in production,
+//! like a database commit or a network request. Tests can make a point fail,
+//! always or with some probability, and the workflow then fails there with
+//! the same kind of error as a real failure would cause. Faults can only be
+//! set with the `test_utils` feature, and checking a point without any set
+//! does nothing.
+
+use super::error::WorkflowError;
+use super::error::WorkflowResult;
+use holochain_keystore::KeystoreError;
+use holochain_p2p::HolochainP2pError;
+use holochain_sqlite::error::DatabaseError;
+use kitsune_p2p::KitsuneP2pError;
+use kitsune_p2p_types::KitsuneErrorKind;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// A step of a workflow where a fault can be injected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FaultPoint {
+    /// Committing the outcomes of sys validation. The fault fires after the
+    /// outcomes are written, so the transaction is rolled back.
+    SysValidationCommit,
+    /// Fetching an op's dependencies from the network during sys validation,
+    /// which fails with a timeout.
+    SysValidationNetworkGet,
+    /// Committing the ops the integration workflow has integrated. The fault
+    /// fires after they are written, so the transaction is rolled back.
+    IntegrationCommit,
+    /// Signing a validation receipt with the keystore.
+    ValidationReceiptSign,
+}
+
+impl FaultPoint {
+    fn error(&self) -> WorkflowError {
+        let reason = format!("Injected fault at {:?}", self);
+        match self {
+            FaultPoint::SysValidationCommit | FaultPoint::IntegrationCommit => {
+                DatabaseError::Other(anyhow::anyhow!(reason)).into()
+            }
+            FaultPoint::SysValidationNetworkGet => HolochainP2pError::OtherKitsuneP2pError(
+                KitsuneP2pError::KitsuneError(KitsuneErrorKind::TimedOut.into()),
+            )
+            .into(),
+            FaultPoint::ValidationReceiptSign => KeystoreError::Other(reason).into(),
+        }
+    }
+}
+
+/// How often an injected fault fires.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Fault {
+    probability: f64,
+    remaining: Option<usize>,
+}
+
+impl Fault {
+    /// Fire every time the point is reached.
+    pub fn always() -> Self {
+        Self::with_probability(1.0)
+    }
+
+    /// Fire with this probability each time the point is reached.
+    pub fn with_probability(probability: f64) -> Self {
+        Self {
+            probability,
+            remaining: None,
+        }
+    }
+
+    /// Stop firing after firing this many times.
+    pub fn times(mut self, times: usize) -> Self {
+        self.remaining = Some(times);
+        self
+    }
+}
+
+#[derive(Default)]
+struct Faults {
+    set: HashMap<FaultPoint, Fault>,
+    injected: HashMap<FaultPoint, usize>,
+}
+
+/// The faults injected into the workflows of a conductor.
+#[derive(Clone, Default)]
+pub struct FaultInjector(Arc<parking_lot::Mutex<Faults>>);
+
+impl FaultInjector {
+    /// Fail with the error of this point if a fault fires here.
+    pub fn check(&self, point: FaultPoint) -> WorkflowResult<()> {
+        let mut faults = self.0.lock();
+        let fault = match faults.set.get_mut(&point) {
+            Some(fault) => fault,
+            None => return Ok(()),
+        };
+        if fault.remaining == Some(0) || rand::random::<f64>() >= fault.probability {
+            return Ok(());
+        }
+        if let Some(remaining) = &mut fault.remaining {
+            *remaining -= 1;
+        }
+        *faults.injected.entry(point).or_default() += 1;
+        Err(point.error())
+    }
+
+    /// Inject a fault at this point, replacing any already set there.
+    #[cfg(any(test, feature = "test_utils"))]
+    pub fn set(&self, point: FaultPoint, fault: Fault) {
+        self.0.lock().set.insert(point, fault);
+    }
+
+    /// Stop injecting faults anywhere.
+    #[cfg(any(test, feature = "test_utils"))]
+    pub fn clear(&self) {
+        self.0.lock().set.clear();
+    }
+
+    /// How many times a fault has fired at this point.
+    #[cfg(any(test, feature = "test_utils"))]
+    pub fn injected(&self, point: FaultPoint) -> usize {
+        self.0.lock().injected.get(&point).copied().unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn faults_fire_at_their_point_until_used_up() {
+        let faults = FaultInjector::default();
+        faults.check(FaultPoint::IntegrationCommit).unwrap();
+
+        faults.set(FaultPoint::IntegrationCommit, Fault::always().times(2));
+        faults.set(
+            FaultPoint::SysValidationCommit,
+            Fault::with_probability(0.0),
+        );
+        assert!(matches!(
+            faults.check(FaultPoint::IntegrationCommit),
+            Err(WorkflowError::DatabaseError(_))
+        ));
+        assert!(faults.check(FaultPoint::IntegrationCommit).is_err());
+        faults.check(FaultPoint::IntegrationCommit).unwrap();
+        faults.check(FaultPoint::SysValidationCommit).unwrap();
+        assert_eq!(faults.injected(FaultPoint::IntegrationCommit), 2);
+        assert_eq!(faults.injected(FaultPoint::SysValidationCommit), 0);
+
+        faults.set(FaultPoint::ValidationReceiptSign, Fault::always());
+        faults.clear();
+        faults.check(FaultPoint::ValidationReceiptSign).unwrap();
+    }
+}
@@ -80,19 +80,22 @@ where
         hash: dna_hash,
         properties: properties.clone(),
     };
-    let result = ribosome.run_genesis_self_check(
-        GenesisSelfCheckHostAccess,
-        GenesisSelfCheckInvocation {
-            payload: Arc::new(GenesisSelfCheckData {
-                dna_info,
-                membrane_proof: membrane_proof.clone(),
-                agent_key: agent_pubkey.clone(),
-            }),
-        },
-    )?;
+    let invocation = GenesisSelfCheckInvocation {
+        payload: Arc::new(GenesisSelfCheckData {
+            dna_info,
+            membrane_proof: membrane_proof.clone(),
+            agent_key: agent_pubkey.clone(),
+        }),
+    };
+    // The self-check runs wasm, so keep it off the async executor.
+    let result = tokio::task::spawn_blocking(move || {
+        ribosome.run_genesis_self_check(GenesisSelfCheckHostAccess, invocation)
+    })
+    .await??;
 
     // If the self-check fails, fail genesis, and don't create the source chain.
     if let GenesisSelfCheckResult::Invalid(reason) = result {
+        warn!(agent = ?agent_pubkey, %reason, "Genesis self-check rejected the cell");
         return Err(WorkflowError::GenesisFailure(reason));
     }
 
@@ -100,7 +103,7 @@ where
     if api
         .dpki_request("is_agent_pubkey_valid".into(), agent_pubkey.to_string())
         .await
-        .expect("TODO: actually implement this")
+        .map_err(Box::new)?
         == "INVALID"
     {
         return Err(WorkflowError::AgentInvalid(agent_pubkey.clone()));
@@ -235,6 +238,38 @@ pub mod tests {
             );
         }
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn rejected_self_check_writes_nothing() {
+        observability::test_run().ok();
+        let test_db = test_authored_db();
+        let dht_db = test_dht_db();
+        let dht_db_cache = DhtDbQueryCache::new(dht_db.to_db().into());
+        let dna = fake_dna_file("a");
+        let author = fake_agent_pubkey_1();
+
+        let workspace = GenesisWorkspace::new(test_db.to_db(), dht_db.to_db()).unwrap();
+        let mut api = MockCellConductorApiT::new();
+        api.expect_dpki_request().never();
+        let mut ribosome = MockRibosomeT::new();
+        ribosome
+            .expect_run_genesis_self_check()
+            .returning(|_, _| Ok(GenesisSelfCheckResult::Invalid("bad proof".to_string())));
+        let dna_def = DnaDefHashed::from_content_sync(dna.dna_def().clone());
+        ribosome.expect_dna_def().return_const(dna_def);
+        let args = GenesisWorkflowArgs {
+            dna_file: dna.clone(),
+            agent_pubkey: author.clone(),
+            membrane_proof: None,
+            ribosome,
+            dht_db_cache,
+        };
+        let workspace_check = GenesisWorkspace::new(test_db.to_db(), dht_db.to_db()).unwrap();
+
+        let result = genesis_workflow(workspace, api, args).await;
+        assert_matches!(result, Err(WorkflowError::GenesisFailure(reason)) if reason == "bad proof");
+        assert!(!workspace_check.has_genesis(author).await.unwrap());
+    }
 }
 
 /* TODO: update and rewrite as proper rust docs
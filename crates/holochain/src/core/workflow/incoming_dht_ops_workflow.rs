@@ -1,10 +1,13 @@
 //! The workflow and queue consumer for DhtOp integration
 
+use super::error::WorkflowError;
 use super::error::WorkflowResult;
 use super::sys_validation_workflow::counterfeit_check;
 use crate::{
     conductor::{conductor::RwShare, space::Space},
     core::queue_consumer::TriggerSender,
+    core::sys_validate::check_op_entry_hashes,
+    core::{SysValidationError, ValidationOutcome},
 };
 use holo_hash::DhtOpHash;
 use holochain_sqlite::error::DatabaseResult;
@@ -18,6 +21,12 @@ use tracing::instrument;
 #[cfg(test)]
 mod test;
 
+/// The most ops written to the validation limbo in one transaction. A burst
+/// is committed in batches of about this size, and each sender is only
+/// acknowledged once its ops are committed, so nothing acknowledged is lost
+/// if the conductor crashes.
+const MAX_BATCH_OPS: usize = 10_000;
+
 type InOpBatchSnd = tokio::sync::oneshot::Sender<WorkflowResult<()>>;
 type InOpBatchRcv = tokio::sync::oneshot::Receiver<WorkflowResult<()>>;
 
@@ -26,6 +35,8 @@ struct InOpBatchEntry {
     snd: InOpBatchSnd,
     request_validation_receipt: bool,
     ops: Vec<(DhtOpHash, DhtOp)>,
+    /// Ops which are already stored and only need a validation receipt.
+    acknowledge: Vec<DhtOpHash>,
 }
 
 /// A batch of incoming ops memory.
@@ -50,12 +61,14 @@ fn batch_check_insert(
     batch: &IncomingOpsBatch,
     request_validation_receipt: bool,
     ops: Vec<(DhtOpHash, DhtOp)>,
+    acknowledge: Vec<DhtOpHash>,
 ) -> (Option<Vec<InOpBatchEntry>>, InOpBatchRcv) {
     let (snd, rcv) = tokio::sync::oneshot::channel();
     let entry = InOpBatchEntry {
         snd,
         request_validation_receipt,
         ops,
+        acknowledge,
     };
     batch.0.share_mut(|batch| {
         if batch.is_running {
@@ -76,7 +89,18 @@ fn batch_check_insert(
 fn batch_check_end(batch: &IncomingOpsBatch) -> Option<Vec<InOpBatchEntry>> {
     batch.0.share_mut(|batch| {
         assert!(batch.is_running);
-        let out: Vec<InOpBatchEntry> = batch.pending.drain(..).collect();
+        // take entries up to the batch size, but always at least one
+        let mut num_ops = 0;
+        let take = batch
+            .pending
+            .iter()
+            .take_while(|entry| {
+                let fits = num_ops == 0 || num_ops + entry.ops.len() <= MAX_BATCH_OPS;
+                num_ops += entry.ops.len();
+                fits
+            })
+            .count();
+        let out: Vec<InOpBatchEntry> = batch.pending.drain(..take).collect();
         if out.is_empty() {
             // pending was empty, we can end the loop for now
             batch.is_running = false;
@@ -88,35 +112,146 @@ fn batch_check_end(batch: &IncomingOpsBatch) -> Option<Vec<InOpBatchEntry>> {
     })
 }
 
-#[instrument(skip(txn, ops))]
-fn batch_process_entry(
+/// Write the ops of a batch of entries to the validation limbo, returning
+/// the result for each entry's sender. The ops of all entries are written in
+/// order of their basis, so ops arriving together for the same basis, from
+/// publish or gossip, are written together.
+#[instrument(skip(txn, entries))]
+fn batch_process_entries(
+    txn: &mut rusqlite::Transaction<'_>,
+    entries: Vec<InOpBatchEntry>,
+) -> Vec<(InOpBatchSnd, WorkflowResult<()>)> {
+    let mut results = Vec::with_capacity(entries.len());
+    let mut ops = Vec::new();
+    for (index, entry) in entries.into_iter().enumerate() {
+        let InOpBatchEntry {
+            snd,
+            request_validation_receipt,
+            ops: entry_ops,
+            acknowledge,
+        } = entry;
+        let res = acknowledge
+            .iter()
+            .try_for_each(|hash| set_send_receipt(txn, hash))
+            .map_err(Into::into);
+        results.push((snd, res));
+        ops.extend(
+            entry_ops
+                .into_iter()
+                .map(|(hash, op)| (index, request_validation_receipt, hash, op)),
+        );
+    }
+    ops.sort_by_cached_key(|(_, _, _, op)| op.dht_basis());
+
+    tracing::debug!("Inserting {} ops", ops.len());
+    for (index, request_validation_receipt, hash, op) in ops {
+        if results[index].1.is_err() {
+            continue;
+        }
+        if let Err(e) = batch_process_op(txn, request_validation_receipt, hash, op) {
+            results[index].1 = Err(e);
+        }
+    }
+    results
+}
+
+fn batch_process_op(
     txn: &mut rusqlite::Transaction<'_>,
     request_validation_receipt: bool,
-    ops: Vec<(DhtOpHash, DhtOp)>,
+    hash: DhtOpHash,
+    op: DhtOp,
 ) -> WorkflowResult<()> {
     // add incoming ops to the validation limbo
-    let mut to_pending = Vec::with_capacity(ops.len());
-    for (hash, op) in ops {
-        if !op_exists_inner(txn, &hash)? {
-            let op = DhtOpHashed::from_content_sync(op);
-            to_pending.push(op);
-        } else {
-            // Check if we should set receipt to send.
-            if request_validation_receipt {
-                set_send_receipt(txn, &hash)?;
-            }
-        }
+    if !op_exists_inner(txn, &hash)? {
+        let op = DhtOpHashed::from_content_sync(op);
+        add_to_pending(txn, &[op], request_validation_receipt)?;
+    } else if request_validation_receipt {
+        // Check if we should set receipt to send.
+        set_send_receipt(txn, &hash)?;
     }
-
-    tracing::debug!("Inserting {} ops", to_pending.len());
-    add_to_pending(txn, &to_pending, request_validation_receipt)?;
-
     Ok(())
 }
 
 #[derive(Default, Clone)]
 pub struct IncomingOpHashes(Arc<parking_lot::Mutex<HashSet<DhtOpHash>>>);
 
+/// The fewest ops a [`SeenOps`] filter is built to hold.
+const SEEN_OPS_MIN_CAPACITY: usize = 100_000;
+
+/// A bloom filter of the ops stored in the DHT database of a space, so
+/// incoming ops which have never been seen skip the database lookup.
+///
+/// A false positive only costs the lookup, and a false negative only costs
+/// the counterfeit check, because the batch still won't insert an op twice.
+#[derive(Default, Clone)]
+pub struct SeenOps(Arc<parking_lot::Mutex<Option<SeenOpsFilter>>>);
+
+struct SeenOpsFilter {
+    bloom: bloomfilter::Bloom<DhtOpHash>,
+    len: usize,
+    capacity: usize,
+}
+
+impl SeenOps {
+    /// Build the filter from the database if it hasn't been built yet or
+    /// has filled up.
+    async fn load(&self, dht_db: &DbWrite<DbKindDht>) -> DatabaseResult<()> {
+        if self.0.lock().is_some() {
+            return Ok(());
+        }
+        let hashes = dht_db
+            .async_reader(|txn| {
+                let mut stmt = txn.prepare("SELECT hash FROM DhtOp")?;
+                let hashes: Vec<DhtOpHash> = stmt
+                    .query_map([], |row| row.get(0))?
+                    .collect::<Result<_, _>>()?;
+                DatabaseResult::Ok(hashes)
+            })
+            .await?;
+        let capacity = (hashes.len() * 2).max(SEEN_OPS_MIN_CAPACITY);
+        let mut bloom = bloomfilter::Bloom::new_for_fp_rate(capacity, 0.01);
+        for hash in &hashes {
+            bloom.set(hash);
+        }
+        self.0.lock().get_or_insert(SeenOpsFilter {
+            bloom,
+            len: hashes.len(),
+            capacity,
+        });
+        Ok(())
+    }
+
+    /// Split ops into those which may have been seen and those which
+    /// definitely haven't.
+    fn partition(
+        &self,
+        ops: Vec<(DhtOpHash, DhtOp)>,
+    ) -> (Vec<(DhtOpHash, DhtOp)>, Vec<(DhtOpHash, DhtOp)>) {
+        let filter = self.0.lock();
+        ops.into_iter().partition(|(hash, _)| {
+            filter
+                .as_ref()
+                .map_or(true, |filter| filter.bloom.check(hash))
+        })
+    }
+
+    /// Record ops which have been stored.
+    fn insert<'a>(&self, hashes: impl IntoIterator<Item = &'a DhtOpHash>) {
+        let mut filter = self.0.lock();
+        if let Some(f) = filter.as_mut() {
+            for hash in hashes {
+                f.bloom.set(hash);
+                f.len += 1;
+            }
+            // A full filter has too many false positives, so rebuild it
+            // with more room next time.
+            if f.len > f.capacity {
+                *filter = None;
+            }
+        }
+    }
+}
+
 #[instrument(skip(space, sys_validation_trigger, ops))]
 pub async fn incoming_dht_ops_workflow(
     space: &Space,
@@ -127,6 +262,7 @@ pub async fn incoming_dht_ops_workflow(
     let Space {
         incoming_op_hashes,
         incoming_ops_batch,
+        seen_ops,
         dht_db,
         ..
     } = space;
@@ -151,11 +287,43 @@ pub async fn incoming_dht_ops_workflow(
         return Ok(());
     }
 
-    if !request_validation_receipt {
-        ops = filter_existing_ops(dht_db, ops).await?;
-    }
+    // Only ops which may have been seen before need looking up. Ops which
+    // are already stored don't go back into the validation limbo, they are
+    // only acknowledged if a receipt was requested.
+    seen_ops.load(dht_db).await?;
+    let (maybe_seen, mut unseen) = seen_ops.partition(ops);
+    let (existing, new) = partition_existing_ops(dht_db, maybe_seen).await?;
+    unseen.extend(new);
+    ops = unseen;
+    let acknowledge = if request_validation_receipt {
+        existing
+    } else {
+        Vec::new()
+    };
 
-    for (hash, op) in ops {
+    // Ops which have recently been through sys validation with the same
+    // signature don't need checking again. Rejected ops are dropped, and ops
+    // which passed skip the signature check.
+    for (hash, op, cached) in with_cached_outcomes(dht_db, ops).await? {
+        match cached {
+            Some(CachedValidationOutcome::Rejected(reason)) => {
+                tracing::debug!(
+                    msg = "Dropping op which was already rejected",
+                    ?hash,
+                    ?reason
+                );
+                continue;
+            }
+            // The outcome is for the op the hash names, so the hash must be
+            // the op's own.
+            Some(CachedValidationOutcome::SysValidated)
+                if DhtOpHash::with_data_sync(&op) == hash =>
+            {
+                filter_ops.push((hash, op));
+                continue;
+            }
+            _ => (),
+        }
         // It's cheaper to check if the op exists before trying
         // to check the signature or open a write transaction.
         match should_keep(&op).await {
@@ -165,13 +333,43 @@ pub async fn incoming_dht_ops_workflow(
                     msg = "Dropping op because it failed counterfeit checks",
                     ?op
                 );
+                if is_counterfeit(&e) {
+                    // Record the outcome under the op's own hash, so an op sent
+                    // with the hash of another op can't get that one dropped.
+                    let hash = DhtOpHash::with_data_sync(&op);
+                    let signature = op.signature().clone();
+                    let outcome =
+                        CachedValidationOutcome::Rejected(SysValidationIssueReason::Counterfeit);
+                    dht_db
+                        .async_commit(move |txn| {
+                            record_validation_outcome(txn, &hash, &signature, &outcome)?;
+                            prune_validation_outcomes(txn)?;
+                            StateMutationResult::Ok(())
+                        })
+                        .await?;
+                }
                 return Err(e);
             }
         }
     }
 
-    let (mut maybe_batch, rcv) =
-        batch_check_insert(incoming_ops_batch, request_validation_receipt, filter_ops);
+    // An op whose entry doesn't match its action's entry hash has been
+    // tampered with, so it is dropped. The entries are hashed in parallel.
+    let (filter_ops, wrong_entry_hash) = check_op_entry_hashes(filter_ops).await?;
+    for (_, op) in wrong_entry_hash {
+        tracing::warn!(
+            msg = "Dropping op because its entry doesn't match its action's entry hash",
+            ?op
+        );
+    }
+    let to_store: Vec<_> = filter_ops.iter().map(|(hash, _)| hash.clone()).collect();
+
+    let (mut maybe_batch, rcv) = batch_check_insert(
+        incoming_ops_batch,
+        request_validation_receipt,
+        filter_ops,
+        acknowledge,
+    );
 
     let incoming_ops_batch = incoming_ops_batch.clone();
     if maybe_batch.is_some() {
@@ -183,25 +381,21 @@ pub async fn incoming_dht_ops_workflow(
                     let senders = Arc::new(parking_lot::Mutex::new(Vec::new()));
                     let senders2 = senders.clone();
                     if let Err(err) = dht_db
-                        .async_commit(move |txn| {
-                            for entry in entries {
-                                let InOpBatchEntry {
-                                    snd,
-                                    request_validation_receipt,
-                                    ops,
-                                } = entry;
-                                let res = batch_process_entry(txn, request_validation_receipt, ops);
-
-                                // we can't send the results here...
-                                // we haven't comitted
-                                senders2.lock().push((snd, res));
-                            }
+                        .batched_commit(move |txn| {
+                            // we can't send the results here...
+                            // we haven't comitted
+                            senders2.lock().extend(batch_process_entries(txn, entries));
 
                             WorkflowResult::Ok(())
                         })
                         .await
                     {
                         tracing::error!(?err, "incoming_dht_ops_workflow error");
+                        // nothing was committed, so no sender can be acknowledged
+                        let reason = err.to_string();
+                        for (_, res) in senders.lock().iter_mut() {
+                            *res = Err(super::error::WorkflowError::other(reason.clone()));
+                        }
                     }
 
                     for (snd, res) in senders.lock().drain(..) {
@@ -220,6 +414,9 @@ pub async fn incoming_dht_ops_workflow(
     let r = rcv
         .await
         .map_err(|_| super::error::WorkflowError::RecvError)?;
+    if r.is_ok() {
+        seen_ops.insert(&to_store);
+    }
 
     {
         let mut set = incoming_op_hashes.0.lock();
@@ -230,6 +427,38 @@ pub async fn incoming_dht_ops_workflow(
     r
 }
 
+/// Pair each op with the outcome of sys validating it, if it was validated
+/// recently with the same signature.
+async fn with_cached_outcomes(
+    dht_db: &DbWrite<DbKindDht>,
+    ops: Vec<(DhtOpHash, DhtOp)>,
+) -> WorkflowResult<Vec<(DhtOpHash, DhtOp, Option<CachedValidationOutcome>)>> {
+    if ops.is_empty() {
+        return Ok(Vec::new());
+    }
+    Ok(dht_db
+        .async_reader(move |txn| {
+            ops.into_iter()
+                .map(|(hash, op)| {
+                    let cached = get_validation_outcome(&txn, &hash, op.signature())?;
+                    Ok((hash, op, cached))
+                })
+                .collect::<StateQueryResult<Vec<_>>>()
+        })
+        .await?)
+}
+
+/// Whether the counterfeit check failed because the op is counterfeit, rather
+/// than because the check couldn't be run.
+fn is_counterfeit(e: &WorkflowError) -> bool {
+    matches!(
+        e,
+        WorkflowError::SysValidationError(SysValidationError::ValidationOutcome(
+            ValidationOutcome::Counterfeit(_, _)
+        ))
+    )
+}
+
 #[instrument(skip(op))]
 /// If this op fails the counterfeit check it should be dropped
 async fn should_keep(op: &DhtOp) -> WorkflowResult<()> {
@@ -274,14 +503,30 @@ pub async fn op_exists(vault: &DbWrite<DbKindDht>, hash: DhtOpHash) -> DatabaseR
         .await
 }
 
-pub async fn filter_existing_ops(
+/// Split ops into the hashes of those which are already stored and the ops
+/// which aren't.
+pub async fn partition_existing_ops(
     vault: &DbWrite<DbKindDht>,
-    mut ops: Vec<(holo_hash::DhtOpHash, holochain_types::dht_op::DhtOp)>,
-) -> DatabaseResult<Vec<(holo_hash::DhtOpHash, holochain_types::dht_op::DhtOp)>> {
+    ops: Vec<(holo_hash::DhtOpHash, holochain_types::dht_op::DhtOp)>,
+) -> DatabaseResult<(
+    Vec<holo_hash::DhtOpHash>,
+    Vec<(holo_hash::DhtOpHash, holochain_types::dht_op::DhtOp)>,
+)> {
+    if ops.is_empty() {
+        return Ok((Vec::new(), ops));
+    }
     vault
         .async_reader(move |txn| {
-            ops.retain(|(hash, _)| !op_exists_inner(&txn, hash).unwrap_or(true));
-            Ok(ops)
+            let mut existing = Vec::new();
+            let mut new = Vec::new();
+            for (hash, op) in ops {
+                if op_exists_inner(&txn, &hash)? {
+                    existing.push(hash);
+                } else {
+                    new.push((hash, op));
+                }
+            }
+            Ok((existing, new))
         })
         .await
 }
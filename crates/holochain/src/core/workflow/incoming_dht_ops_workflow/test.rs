@@ -64,3 +64,89 @@ async fn incoming_ops_to_limbo() {
         }
     });
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn duplicate_ops_are_acknowledged_without_revalidation() {
+    observability::test_run().unwrap();
+    let space = TestSpace::new(fixt!(DnaHash));
+    let env = space.space.dht_db.clone();
+    let keystore = holochain_state::test_utils::test_keystore();
+
+    let author = fake_agent_pubkey_1();
+    let mut action = fixt!(CreateLink);
+    action.author = author.clone();
+    let action = Action::CreateLink(action);
+    let signature = author.sign(&keystore, &action).await.unwrap();
+    let op = DhtOp::RegisterAgentActivity(signature, action);
+    let hash = DhtOpHash::with_data_sync(&op);
+
+    let (sys_validation_trigger, _) = TriggerSender::new();
+    incoming_dht_ops_workflow(
+        &space.space,
+        sys_validation_trigger.clone(),
+        vec![(hash.clone(), op.clone())],
+        false,
+    )
+    .await
+    .unwrap();
+
+    let when_integrated = Timestamp::now();
+    env.async_commit({
+        let hash = hash.clone();
+        move |txn| {
+            set_validation_status(txn, &hash, ValidationStatus::Valid)?;
+            set_when_integrated(txn, &hash, when_integrated)
+        }
+    })
+    .await
+    .unwrap();
+
+    // The publisher resends the op and asks for a receipt.
+    incoming_dht_ops_workflow(
+        &space.space,
+        sys_validation_trigger,
+        vec![(hash.clone(), op)],
+        true,
+    )
+    .await
+    .unwrap();
+
+    fresh_reader_test(env, |txn| {
+        let (status, integrated, require_receipt): (ValidationStatus, Timestamp, bool) = txn
+            .query_row(
+                "
+                SELECT validation_status, when_integrated, require_receipt FROM DhtOp
+                WHERE hash = :hash
+                ",
+                named_params! {
+                    ":hash": hash,
+                },
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!(status, ValidationStatus::Valid);
+        assert_eq!(integrated, when_integrated);
+        assert!(require_receipt);
+    });
+}
+
+#[test]
+fn pending_ops_are_taken_in_bounded_batches() {
+    let op = DhtOp::RegisterAgentActivity(fixt!(Signature), Action::CreateLink(fixt!(CreateLink)));
+    let hash = DhtOpHash::with_data_sync(&op);
+    let batch = IncomingOpsBatch::default();
+
+    let (first, _rcv) = batch_check_insert(&batch, false, vec![(hash.clone(), op.clone())], vec![]);
+    assert_eq!(first.unwrap().len(), 1);
+    let mut receivers = Vec::new();
+    for _ in 0..3 {
+        let ops = vec![(hash.clone(), op.clone()); MAX_BATCH_OPS / 2];
+        let (running, rcv) = batch_check_insert(&batch, false, ops, vec![]);
+        assert!(running.is_none());
+        receivers.push(rcv);
+    }
+
+    assert_eq!(batch_check_end(&batch).unwrap().len(), 2);
+    assert_eq!(batch_check_end(&batch).unwrap().len(), 1);
+    assert!(batch_check_end(&batch).is_none());
+}
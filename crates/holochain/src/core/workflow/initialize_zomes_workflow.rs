@@ -84,6 +84,7 @@ where
     Ribosome: RibosomeT + 'static,
 {
     let dna_def = args.dna_def().clone();
+    let unrestricted_grant = unrestricted_functions_grant(&dna_def);
     let InitializeZomesWorkflowArgs {
         ribosome,
         conductor_handle,
@@ -105,11 +106,26 @@ where
         ribosome.run_init(host_access, invocation)?
     };
 
-    // Insert the init marker
+    // Grant access to the functions the DNA declares unrestricted
+    // and insert the init marker
     // FIXME: For some reason if we don't spawn here
     // this future never gets polled again.
     let ws = workspace.clone();
     tokio::task::spawn(async move {
+        if let Some(grant) = unrestricted_grant {
+            let entry = Entry::CapGrant(grant);
+            let entry_hash = EntryHash::with_data_sync(&entry);
+            ws.source_chain()
+                .put_weightless(
+                    builder::Create {
+                        entry_type: EntryType::CapGrant,
+                        entry_hash,
+                    },
+                    Some(entry),
+                    ChainTopOrdering::Strict,
+                )
+                .await?;
+        }
         ws.source_chain()
             .put(
                 builder::InitZomesComplete {},
@@ -126,6 +142,33 @@ where
     Ok(result)
 }
 
+/// The tag of the cap grant for the functions a DNA declares unrestricted.
+pub const UNRESTRICTED_FUNCTIONS_GRANT_TAG: &str = "unrestricted_functions";
+
+/// An unrestricted cap grant for the functions the coordinator zomes of this
+/// DNA declare unrestricted in the DNA manifest, if they declare any.
+fn unrestricted_functions_grant(dna_def: &DnaDef) -> Option<ZomeCallCapGrant> {
+    let functions: GrantedFunctions = dna_def
+        .coordinator_zomes
+        .iter()
+        .flat_map(|(zome_name, zome)| {
+            zome.as_any_zome_def()
+                .unrestricted_functions()
+                .iter()
+                .map(move |fn_name| (zome_name.clone(), fn_name.clone()))
+        })
+        .collect();
+    if functions.is_empty() {
+        None
+    } else {
+        Some(ZomeCallCapGrant::new(
+            UNRESTRICTED_FUNCTIONS_GRANT_TAG.into(),
+            CapAccess::Unrestricted,
+            functions,
+        ))
+    }
+}
+
 #[cfg(test)]
 pub mod tests {
     use std::sync::Arc;
@@ -139,7 +182,7 @@ pub mod tests {
     use crate::sweettest::*;
     use crate::test_utils::fake_genesis;
     use ::fixt::prelude::*;
-    use fixt::Unpredictable;
+    use holo_hash::fixt::WasmHashFixturator;
     use holochain_p2p::HolochainP2pDnaFixturator;
     use holochain_state::prelude::test_authored_db;
     use holochain_state::prelude::test_cache_db;
@@ -164,8 +207,9 @@ pub mod tests {
         .unwrap()
     }
 
-    #[tokio::test(flavor = "multi_thread")]
-    async fn adds_init_marker() {
+    /// Run the workflow with a mock ribosome for this DNA, returning the
+    /// workspace it wrote to.
+    async fn run_workflow(dna_def: DnaDef) -> SourceChainWorkspace {
         let test_db = test_authored_db();
         let test_cache = test_cache_db();
         let test_dht = test_dht_db();
@@ -178,7 +222,6 @@ pub mod tests {
             .await
             .unwrap();
 
-        let dna_def = DnaDefFixturator::new(Unpredictable).next().unwrap();
         let dna_def_hashed = DnaDefHashed::from_content_sync(dna_def.clone());
 
         let workspace = SourceChainWorkspace::new(
@@ -218,6 +261,13 @@ pub mod tests {
         initialize_zomes_workflow_inner(workspace.clone(), network, keystore, args)
             .await
             .unwrap();
+        workspace
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn adds_init_marker() {
+        let dna_def = DnaDefFixturator::new(Unpredictable).next().unwrap();
+        let workspace = run_workflow(dna_def).await;
 
         // Check init is added to the workspace
         let scratch = workspace.source_chain().snapshot().unwrap();
@@ -225,6 +275,37 @@ pub mod tests {
             scratch.actions().next().unwrap().action(),
             Action::InitZomesComplete(_)
         );
+        // and no grant is made when no functions are declared unrestricted.
+        assert_eq!(scratch.actions().count(), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn grants_access_to_unrestricted_functions() {
+        let mut dna_def = DnaDefFixturator::new(Unpredictable).next().unwrap();
+        let mut zome = WasmZome::new(fixt!(WasmHash));
+        zome.unrestricted_functions = vec!["recv_remote_signal".into()];
+        dna_def
+            .coordinator_zomes
+            .push(("signals".into(), ZomeDef::Wasm(zome).into()));
+        let workspace = run_workflow(dna_def).await;
+
+        let scratch = workspace.source_chain().snapshot().unwrap();
+        let grants: Vec<_> = scratch
+            .entries()
+            .filter_map(|(_, entry)| match &**entry {
+                Entry::CapGrant(grant) => Some(grant.clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            grants,
+            vec![ZomeCallCapGrant::new(
+                UNRESTRICTED_FUNCTIONS_GRANT_TAG.into(),
+                CapAccess::Unrestricted,
+                [("signals".into(), "recv_remote_signal".into())].into(),
+            )]
+        );
+        assert_eq!(scratch.actions().count(), 2);
     }
 
     #[tokio::test(flavor = "multi_thread")]
@@ -1,9 +1,10 @@
 //! The workflow and queue consumer for DhtOp integration
 
-use super::*;
+use super::error::WorkflowResult;
+use super::fault_injection::FaultInjector;
+use super::fault_injection::FaultPoint;
 use crate::core::queue_consumer::TriggerSender;
 use crate::core::queue_consumer::WorkComplete;
-use error::WorkflowResult;
 use holochain_p2p::HolochainP2pDna;
 use holochain_p2p::HolochainP2pDnaT;
 use holochain_state::prelude::*;
@@ -17,19 +18,21 @@ mod query_tests;
 #[cfg(feature = "test_utils")]
 mod tests;
 
-#[instrument(skip(vault, trigger_receipt, network, dht_query_cache))]
+#[instrument(skip(vault, trigger_receipt, network, dht_query_cache, faults))]
 pub async fn integrate_dht_ops_workflow(
     vault: DbWrite<DbKindDht>,
     dht_query_cache: &DhtDbQueryCache,
     trigger_receipt: TriggerSender,
     network: HolochainP2pDna,
+    faults: &FaultInjector,
 ) -> WorkflowResult<WorkComplete> {
     let start = std::time::Instant::now();
     let time = holochain_zome_types::Timestamp::now();
     // Get any activity from the cache that is ready to be integrated.
     let activity_to_integrate = dht_query_cache.get_activity_to_integrate().await?;
+    let faults = faults.clone();
     let (changed, activity_integrated) = vault
-        .async_commit(move |txn| {
+        .batched_commit(move |txn| {
             let mut total = 0;
             if !activity_to_integrate.is_empty() {
                 let mut stmt = txn.prepare_cached(
@@ -84,6 +87,7 @@ pub async fn integrate_dht_ops_workflow(
 
                 })?;
             total += changed;
+            faults.check(FaultPoint::IntegrationCommit)?;
             WorkflowResult::Ok((total, activity_to_integrate))
         })
         .await?;
@@ -107,6 +107,7 @@ async fn integrate_query() {
         &db.to_db().into(),
         qt,
         holochain_p2p_cell,
+        &Default::default(),
     )
     .await
     .unwrap();
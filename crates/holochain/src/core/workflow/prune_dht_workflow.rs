@@ -0,0 +1,71 @@
+//! The workflow for pruning deleted data from the DHT and cache databases.
+//!
+//! Once every create of an entry has a valid delete which has been integrated
+//! for longer than the retention period, the entry's content is no longer
+//! served to anyone, so it is removed to reclaim disk space. The actions and
+//! ops are kept so the deletes can still be gossiped and validated against.
+//!
+//! The ops of an ephemeral DNA are purged entirely once they are older than
+//! its TTL, and their authors stop publishing them, so nothing of them is
+//! left to gossip.
+
+use super::error::WorkflowResult;
+use crate::core::queue_consumer::WorkComplete;
+use holochain_state::mutations;
+use holochain_state::prelude::*;
+use holochain_types::prelude::*;
+use std::time::Duration;
+use tracing::*;
+
+#[cfg(test)]
+mod tests;
+
+#[instrument(skip(dht_db, cache_db))]
+pub async fn prune_dht_workflow(
+    dht_db: DbWrite<DbKindDht>,
+    cache_db: DbWrite<DbKindCache>,
+    retention_period: Duration,
+) -> WorkflowResult<WorkComplete> {
+    // A retention period reaching back before the epoch can't have
+    // anything to prune.
+    let cutoff = (Timestamp::now() - retention_period).unwrap_or(Timestamp::ZERO);
+    let pruned_dht = dht_db
+        .async_commit(move |txn| mutations::prune_deleted_entries(txn, cutoff))
+        .await?;
+    let pruned_cache = cache_db
+        .async_commit(move |txn| mutations::prune_deleted_entries(txn, cutoff))
+        .await?;
+    if pruned_dht + pruned_cache > 0 {
+        info!(
+            "Pruned {} deleted entries from the dht and {} from the cache",
+            pruned_dht, pruned_cache
+        );
+    }
+    Ok(WorkComplete::Complete)
+}
+
+#[instrument(skip(dht_db, cache_db, authored_db))]
+pub async fn expire_ephemeral_ops_workflow(
+    dht_db: DbWrite<DbKindDht>,
+    cache_db: DbWrite<DbKindCache>,
+    authored_db: DbWrite<DbKindAuthored>,
+    ttl: Duration,
+) -> WorkflowResult<WorkComplete> {
+    let cutoff = (Timestamp::now() - ttl).unwrap_or(Timestamp::MIN);
+    let purged_dht = dht_db
+        .async_commit(move |txn| mutations::purge_expired_ops(txn, cutoff))
+        .await?;
+    let purged_cache = cache_db
+        .async_commit(move |txn| mutations::purge_expired_ops(txn, cutoff))
+        .await?;
+    authored_db
+        .async_commit(move |txn| mutations::stop_publishing_expired_ops(txn, cutoff))
+        .await?;
+    if purged_dht + purged_cache > 0 {
+        info!(
+            "Purged {} expired ops from the dht and {} from the cache",
+            purged_dht, purged_cache
+        );
+    }
+    Ok(WorkComplete::Complete)
+}
@@ -0,0 +1,153 @@
+use super::*;
+use ::fixt::prelude::*;
+use holo_hash::HasHash;
+use holochain_state::test_utils::test_authored_db;
+use holochain_state::test_utils::test_cache_db;
+use holochain_state::test_utils::test_dht_db;
+use holochain_types::dht_op::DhtOp;
+use holochain_types::dht_op::DhtOpHashed;
+use holochain_types::dht_op::DhtOpType;
+
+/// Insert a created entry into the database along with a delete of it
+/// which was integrated `age` ago, returning the entry's hash.
+fn insert_deleted_entry(db: &DbWrite<DbKindDht>, age: Duration) -> EntryHash {
+    let entry = fixt!(Entry);
+    let entry_hash = EntryHash::with_data_sync(&entry);
+    let mut create = fixt!(Create);
+    create.entry_hash = entry_hash.clone();
+    create.entry_type = AppEntryTypeFixturator::new(EntryVisibility::Public)
+        .map(EntryType::App)
+        .next()
+        .unwrap();
+    let mut delete = fixt!(Delete);
+    delete.deletes_address = ActionHash::with_data_sync(&Action::Create(create.clone()));
+    delete.deletes_entry_address = entry_hash.clone();
+
+    let store_entry = DhtOpHashed::from_content_sync(DhtOp::StoreEntry(
+        fixt!(Signature),
+        NewEntryAction::Create(create),
+        Box::new(entry),
+    ));
+    let register_delete =
+        DhtOpHashed::from_content_sync(DhtOp::RegisterDeletedEntryAction(fixt!(Signature), delete));
+    let integrated = (Timestamp::now() - age).unwrap();
+    db.test_commit(|txn| {
+        for op in [&store_entry, &register_delete] {
+            mutations::insert_op(txn, op).unwrap();
+            mutations::set_validation_status(txn, op.as_hash(), ValidationStatus::Valid).unwrap();
+            mutations::set_when_integrated(txn, op.as_hash(), integrated).unwrap();
+        }
+    });
+    entry_hash
+}
+
+/// Insert the ops of a public create authored `age` ago, returning the
+/// hashes of the create and its entry.
+fn insert_create(db: &DbWrite<DbKindDht>, age: Duration) -> (ActionHash, EntryHash) {
+    let entry = fixt!(Entry);
+    let entry_hash = EntryHash::with_data_sync(&entry);
+    let mut create = fixt!(Create);
+    create.entry_hash = entry_hash.clone();
+    create.entry_type = AppEntryTypeFixturator::new(EntryVisibility::Public)
+        .map(EntryType::App)
+        .next()
+        .unwrap();
+    create.timestamp = (Timestamp::now() - age).unwrap();
+    let action_hash = ActionHash::with_data_sync(&Action::Create(create.clone()));
+    let signature = fixt!(Signature);
+    let ops = [
+        DhtOp::StoreRecord(
+            signature.clone(),
+            Action::Create(create.clone()),
+            Some(Box::new(entry.clone())),
+        ),
+        DhtOp::StoreEntry(
+            signature.clone(),
+            NewEntryAction::Create(create.clone()),
+            Box::new(entry),
+        ),
+        DhtOp::RegisterAgentActivity(signature, Action::Create(create)),
+    ];
+    db.test_commit(|txn| {
+        for op in ops {
+            mutations::insert_op(txn, &DhtOpHashed::from_content_sync(op)).unwrap();
+        }
+    });
+    (action_hash, entry_hash)
+}
+
+fn has_entry(db: &DbWrite<DbKindDht>, hash: &EntryHash) -> bool {
+    db.test_commit(|txn| {
+        txn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM Entry WHERE hash = ?)",
+            [hash],
+            |row| row.get(0),
+        )
+        .unwrap()
+    })
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn only_entries_deleted_before_the_retention_period_are_pruned() {
+    let dht_db = test_dht_db();
+    let cache_db = test_cache_db();
+    let old = insert_deleted_entry(&dht_db.to_db(), Duration::from_secs(60 * 60 * 2));
+    let recent = insert_deleted_entry(&dht_db.to_db(), Duration::from_secs(60));
+
+    prune_dht_workflow(
+        dht_db.to_db(),
+        cache_db.to_db(),
+        Duration::from_secs(60 * 60),
+    )
+    .await
+    .unwrap();
+
+    assert!(!has_entry(&dht_db.to_db(), &old));
+    assert!(has_entry(&dht_db.to_db(), &recent));
+
+    // The ops are kept, so the dht can still answer for the deleted entry.
+    let ops: usize = dht_db.to_db().test_commit(|txn| {
+        txn.query_row("SELECT COUNT(*) FROM DhtOp", [], |row| row.get(0))
+            .unwrap()
+    });
+    assert_eq!(ops, 4);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn expired_ops_are_purged_but_agent_activity_is_kept() {
+    let dht_db = test_dht_db();
+    let cache_db = test_cache_db();
+    let authored_db = test_authored_db();
+    let (old_action, old_entry) = insert_create(&dht_db.to_db(), Duration::from_secs(60 * 60 * 2));
+    let (_, recent_entry) = insert_create(&dht_db.to_db(), Duration::from_secs(60));
+
+    expire_ephemeral_ops_workflow(
+        dht_db.to_db(),
+        cache_db.to_db(),
+        authored_db.to_db(),
+        Duration::from_secs(60 * 60),
+    )
+    .await
+    .unwrap();
+
+    assert!(!has_entry(&dht_db.to_db(), &old_entry));
+    assert!(has_entry(&dht_db.to_db(), &recent_entry));
+
+    // Only the agent activity of the old create is left, so its author's
+    // chain can still be checked.
+    let old_ops: Vec<DhtOpType> = dht_db.to_db().test_commit(|txn| {
+        let mut stmt = txn
+            .prepare("SELECT type FROM DhtOp WHERE action_hash = ?")
+            .unwrap();
+        stmt.query_map([&old_action], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap()
+    });
+    assert_eq!(old_ops, vec![DhtOpType::RegisterAgentActivity]);
+    let ops: usize = dht_db.to_db().test_commit(|txn| {
+        txn.query_row("SELECT COUNT(*) FROM DhtOp", [], |row| row.get(0))
+            .unwrap()
+    });
+    assert_eq!(ops, 4);
+}
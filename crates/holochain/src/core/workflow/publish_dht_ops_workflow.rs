@@ -12,6 +12,7 @@
 
 use super::error::WorkflowResult;
 use crate::core::queue_consumer::TriggerSender;
+use crate::core::queue_consumer::WorkBudget;
 use crate::core::queue_consumer::WorkComplete;
 use holo_hash::*;
 use holochain_p2p::HolochainP2pDnaT;
@@ -37,9 +38,15 @@ pub async fn publish_dht_ops_workflow(
     network: &(dyn HolochainP2pDnaT + Send + Sync),
     trigger_self: &TriggerSender,
     agent: AgentPubKey,
+    budget: WorkBudget,
 ) -> WorkflowResult<WorkComplete> {
     let mut complete = WorkComplete::Complete;
-    let to_publish = publish_dht_ops_workflow_inner(db.clone().into(), agent).await?;
+    let (to_publish, over_budget) =
+        publish_dht_ops_workflow_inner(db.clone().into(), agent, budget).await?;
+    if over_budget {
+        // Publish the rest of the ops on the next pass.
+        complete = WorkComplete::Incomplete;
+    }
 
     // Commit to the network
     tracing::info!("publishing to {} nodes", to_publish.len());
@@ -90,15 +97,19 @@ pub async fn publish_dht_ops_workflow(
     Ok(complete)
 }
 
-/// Read the authored for ops with receipt count < R
+/// Read the authored for ops with receipt count < R,
+/// taking no more than fit in the budget.
+/// The bool is true if there were more ops than fit.
 pub async fn publish_dht_ops_workflow_inner(
     db: DbRead<DbKindAuthored>,
     agent: AgentPubKey,
-) -> WorkflowResult<HashMap<AnyDhtHash, Vec<(DhtOpHash, DhtOp)>>> {
+    budget: WorkBudget,
+) -> WorkflowResult<(HashMap<AnyDhtHash, Vec<(DhtOpHash, DhtOp)>>, bool)> {
     // Ops to publish by basis
     let mut to_publish = HashMap::new();
 
-    for op_hashed in publish_query::get_ops_to_publish(agent, &db).await? {
+    let (ops, over_budget) = publish_query::get_ops_to_publish(agent, &db, budget).await?;
+    for op_hashed in ops {
         let (op, op_hash) = op_hashed.into_inner();
         // For every op publish a request
         // Collect and sort ops by basis
@@ -108,7 +119,7 @@ pub async fn publish_dht_ops_workflow_inner(
             .push((op_hash, op));
     }
 
-    Ok(to_publish)
+    Ok((to_publish, over_budget))
 }
 
 #[cfg(test)]
@@ -240,9 +251,15 @@ mod tests {
         author: AgentPubKey,
     ) {
         let (trigger_sender, _) = TriggerSender::new();
-        publish_dht_ops_workflow(db.clone().into(), &dna_network, &trigger_sender, author)
-            .await
-            .unwrap();
+        publish_dht_ops_workflow(
+            db.clone().into(),
+            &dna_network,
+            &trigger_sender,
+            author,
+            WorkBudget::default(),
+        )
+        .await
+        .unwrap();
     }
 
     /// There is a test that shows that network messages would be sent to all agents via broadcast.
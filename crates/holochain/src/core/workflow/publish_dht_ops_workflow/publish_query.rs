@@ -15,6 +15,7 @@ use holochain_zome_types::SignedAction;
 use rusqlite::named_params;
 use rusqlite::Transaction;
 
+use crate::core::queue_consumer::WorkBudget;
 use crate::core::workflow::error::WorkflowResult;
 
 use super::MIN_PUBLISH_INTERVAL;
@@ -23,10 +24,12 @@ use super::MIN_PUBLISH_INTERVAL;
 /// - Don't publish private entries.
 /// - Only get ops that haven't been published within the minimum publish interval
 /// - Only get ops that have less then the RECEIPT_BUNDLE_SIZE
+/// - Only get as many ops as fit in the budget. The bool is true if any were left over.
 pub async fn get_ops_to_publish(
     agent: AgentPubKey,
     db: &DbRead<DbKindAuthored>,
-) -> WorkflowResult<Vec<DhtOpHashed>> {
+    budget: WorkBudget,
+) -> WorkflowResult<(Vec<DhtOpHashed>, bool)> {
     let recency_threshold = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .ok()
@@ -36,6 +39,7 @@ pub async fn get_ops_to_publish(
 
     let results = db
         .async_reader(move |txn| {
+            let mut spend = budget.start();
             let mut stmt = txn.prepare(
                 "
             SELECT
@@ -58,39 +62,43 @@ pub async fn get_ops_to_publish(
             (DhtOp.last_publish_time IS NULL OR DhtOp.last_publish_time <= :recency_threshold)
             AND
             DhtOp.receipts_complete IS NULL
+            LIMIT :limit
             ",
             )?;
-            let r = stmt.query_and_then(
-                named_params! {
-                    ":author": agent,
-                    ":recency_threshold": recency_threshold,
-                    ":store_entry": DhtOpType::StoreEntry,
-                },
-                |row| {
-                    let action = from_blob::<SignedAction>(row.get("action_blob")?)?;
-                    let op_type: DhtOpType = row.get("dht_type")?;
-                    let hash: DhtOpHash = row.get("dht_hash")?;
-                    let entry = match action.0.entry_type().map(|et| et.visibility()) {
-                        Some(EntryVisibility::Public) => {
-                            let entry: Option<Vec<u8>> = row.get("entry_blob")?;
-                            match entry {
-                                Some(entry) => Some(from_blob::<Entry>(entry)?),
-                                None => None,
-                            }
-                        }
-                        _ => None,
-                    };
-                    WorkflowResult::Ok(DhtOpHashed::with_pre_hashed(
-                        DhtOp::from_type(op_type, action, entry)?,
-                        hash,
-                    ))
-                },
-            )?;
-            WorkflowResult::Ok(r.collect())
+            let mut rows = stmt.query(named_params! {
+                ":author": agent,
+                ":recency_threshold": recency_threshold,
+                ":store_entry": DhtOpType::StoreEntry,
+                ":limit": spend.query_limit(),
+            })?;
+            let mut ops = Vec::new();
+            while let Some(row) = rows.next()? {
+                let action_blob: Vec<u8> = row.get("action_blob")?;
+                let action = from_blob::<SignedAction>(action_blob.clone())?;
+                let entry_blob = match action.0.entry_type().map(|et| et.visibility()) {
+                    Some(EntryVisibility::Public) => row.get::<_, Option<Vec<u8>>>("entry_blob")?,
+                    _ => None,
+                };
+                let size = action_blob.len() + entry_blob.as_ref().map_or(0, |e| e.len());
+                if !spend.try_spend(size) {
+                    break;
+                }
+                let op_type: DhtOpType = row.get("dht_type")?;
+                let hash: DhtOpHash = row.get("dht_hash")?;
+                let entry = match entry_blob {
+                    Some(entry) => Some(from_blob::<Entry>(entry)?),
+                    None => None,
+                };
+                ops.push(DhtOpHashed::with_pre_hashed(
+                    DhtOp::from_type(op_type, action, entry)?,
+                    hash,
+                ));
+            }
+            WorkflowResult::Ok((ops, spend.is_exhausted()))
         })
         .await?;
     tracing::debug!(?results);
-    results
+    Ok(results)
 }
 
 /// Get the number of ops that might need to publish again in the future.
@@ -159,10 +167,43 @@ mod tests {
         observability::test_run().ok();
         let db = test_authored_db();
         let expected = test_data(&db.to_db().into());
-        let r = get_ops_to_publish(expected.agent.clone(), &db.to_db().into())
-            .await
-            .unwrap();
+        let (r, more) = get_ops_to_publish(
+            expected.agent.clone(),
+            &db.to_db().into(),
+            WorkBudget::default(),
+        )
+        .await
+        .unwrap();
         assert_eq!(r, expected.results);
+        assert!(!more);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn publish_query_respects_budget() {
+        observability::test_run().ok();
+        let db = test_authored_db();
+        let cd = Consistent {
+            this_agent: fixt!(AgentPubKey),
+        };
+        let facts = Facts {
+            private: false,
+            within_min_period: false,
+            has_required_receipts: false,
+            is_this_agent: true,
+            store_entry: false,
+        };
+        for _ in 0..5 {
+            create_and_insert_op(&db.to_db(), facts, &cd);
+        }
+        let (r, more) = get_ops_to_publish(
+            cd.this_agent.clone(),
+            &db.to_db().into(),
+            WorkBudget::new(3, usize::MAX),
+        )
+        .await
+        .unwrap();
+        assert_eq!(r.len(), 3);
+        assert!(more);
     }
 
     fn create_and_insert_op(
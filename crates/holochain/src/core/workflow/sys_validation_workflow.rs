@@ -1,16 +1,17 @@
 //! The workflow and queue consumer for sys validation
 #![allow(deprecated)]
 
-use super::*;
+use super::error::WorkflowResult;
+use super::fault_injection::FaultPoint;
 use crate::conductor::handle::ConductorHandleT;
 use crate::conductor::space::Space;
 use crate::conductor::ConductorHandle;
 use crate::core::queue_consumer::TriggerSender;
+use crate::core::queue_consumer::WorkBudget;
 use crate::core::queue_consumer::WorkComplete;
 use crate::core::sys_validate::check_and_hold_store_record;
 use crate::core::sys_validate::*;
 use crate::core::validation::*;
-use error::WorkflowResult;
 use holo_hash::DhtOpHash;
 use holochain_cascade::Cascade;
 use holochain_p2p::HolochainP2pDna;
@@ -58,6 +59,7 @@ pub async fn sys_validation_workflow(
     sys_validation_trigger: TriggerSender,
     network: HolochainP2pDna,
     conductor_handle: ConductorHandle,
+    budget: WorkBudget,
 ) -> WorkflowResult<WorkComplete> {
     let complete = sys_validation_workflow_inner(
         workspace,
@@ -65,6 +67,7 @@ pub async fn sys_validation_workflow(
         network,
         conductor_handle,
         sys_validation_trigger,
+        budget,
     )
     .await?;
 
@@ -82,18 +85,56 @@ async fn sys_validation_workflow_inner(
     network: HolochainP2pDna,
     conductor_handle: ConductorHandle,
     sys_validation_trigger: TriggerSender,
+    budget: WorkBudget,
 ) -> WorkflowResult<WorkComplete> {
     let db = workspace.dht_db.clone();
-    let sorted_ops = validation_query::get_ops_to_sys_validate(&db).await?;
+    let (sorted_ops, over_budget) = validation_query::get_ops_to_sys_validate(&db, budget).await?;
+
+    // Ops of an ephemeral DNA which have already expired are purged rather
+    // than validated, so they aren't held again when peers which haven't
+    // purged them yet gossip them back.
+    let expired_before = workspace.dna_def.expired_before(Timestamp::now());
+    let (sorted_ops, expired): (Vec<_>, Vec<_>) = sorted_ops.into_iter().partition(|op| {
+        !expired_before.map_or(false, |cutoff| has_expired(op.as_content(), cutoff))
+    });
+    if let (Some(cutoff), false) = (expired_before, expired.is_empty()) {
+        space
+            .dht_db
+            .async_commit(move |txn| purge_expired_ops(txn, cutoff))
+            .await?;
+    }
+
     let start_len = sorted_ops.len();
     tracing::debug!("Validating {} ops", start_len);
     let start = (start_len >= NUM_CONCURRENT_OPS).then(std::time::Instant::now);
     let saturated = start.is_some();
+    let scheduler = conductor_handle.get_workflow_scheduler();
+
+    // Check the entry hashes of the whole batch up front, as hashing large
+    // entries is much faster in parallel than one op at a time.
+    let (sorted_ops, wrong_entry_hash) = check_op_entry_hashes(
+        sorted_ops
+            .into_iter()
+            .map(|op| {
+                let (op, op_hash) = op.into_inner();
+                (op_hash, op)
+            })
+            .collect(),
+    )
+    .await?;
+    let ops = sorted_ops
+        .into_iter()
+        .map(|(op_hash, op)| (op_hash, op, true))
+        .chain(
+            wrong_entry_hash
+                .into_iter()
+                .map(|(op_hash, op)| (op_hash, op, false)),
+        );
 
     // Process each op
-    let iter = sorted_ops.into_iter().map({
+    let iter = ops.map({
         let space = space.clone();
-        move |so| {
+        move |(op_hash, op, entry_hash_matches)| {
             // Create an incoming ops sender for any dependencies we find
             // that we are meant to be holding but aren't.
             // If we are not holding them they will be added to our incoming ops.
@@ -102,13 +143,34 @@ async fn sys_validation_workflow_inner(
             let network = network.clone();
             let workspace = workspace.clone();
             let conductor_handle = conductor_handle.clone();
+            let scheduler = scheduler.clone();
+            let dna_hash = space.dna_hash.clone();
+            let faults = space.faults.clone();
             async move {
-                let (op, op_hash) = so.into_inner();
                 let op_type = op.get_type();
                 let action = op.action();
 
                 let dependency = get_dependency(op_type, &action);
 
+                if !entry_hash_matches {
+                    let reason = SysValidationIssueReason::EntryHash;
+                    warn!(
+                        dna = %dna_hash,
+                        msg = "DhtOp was rejected during system validation.",
+                        ?op,
+                        ?reason,
+                    );
+                    // The outcome isn't cached, because the same action and
+                    // signature may yet come with the right entry.
+                    return Ok((op_hash, None, Outcome::Rejected(reason), dependency));
+                }
+                // Once the entry matches its action, the outcome only depends
+                // on the action and its signature, so it can be cached.
+                let signature = Some(op.signature().clone());
+
+                // Wait for this space's fair share of validation slots.
+                let _permit = scheduler.acquire(dna_hash).await;
+                faults.check(FaultPoint::SysValidationNetworkGet)?;
                 let r = validate_op(
                     &op,
                     &(*workspace),
@@ -117,7 +179,7 @@ async fn sys_validation_workflow_inner(
                     Some(incoming_dht_ops_sender),
                 )
                 .await;
-                r.map(|o| (op_hash, o, dependency))
+                r.map(|o| (op_hash, signature, o, dependency))
             }
         }
     });
@@ -154,15 +216,26 @@ async fn sys_validation_workflow_inner(
     while let Some(chunk) = iter.next().await {
         let num_ops: usize = chunk.iter().map(|c| c.len()).sum();
         tracing::debug!("Committing {} ops", num_ops);
+        let faults = space.faults.clone();
         let (t, a, m, r) = space
             .dht_db
-            .async_commit(move |txn| {
+            .batched_commit(move |txn| {
                 let mut total = 0;
                 let mut awaiting = 0;
                 let mut missing = 0;
                 let mut rejected = 0;
                 for outcome in chunk.into_iter().flatten() {
-                    let (op_hash, outcome, dependency) = outcome?;
+                    let (op_hash, signature, outcome, dependency) = outcome?;
+                    let cached = match &outcome {
+                        Outcome::Accepted => Some(CachedValidationOutcome::SysValidated),
+                        Outcome::Rejected(reason) => {
+                            Some(CachedValidationOutcome::Rejected(reason.clone()))
+                        }
+                        Outcome::AwaitingOpDep(_) | Outcome::MissingDhtDep => None,
+                    };
+                    if let (Some(signature), Some(cached)) = (&signature, &cached) {
+                        record_validation_outcome(txn, &op_hash, signature, cached)?;
+                    }
                     match outcome {
                         Outcome::Accepted => {
                             total += 1;
@@ -191,8 +264,9 @@ async fn sys_validation_workflow_inner(
                             // TODO: Not sure what missing dht dep is. Check if we need this.
                             put_validation_limbo(txn, &op_hash, ValidationLimboStatus::Pending)?;
                         }
-                        Outcome::Rejected => {
+                        Outcome::Rejected(reason) => {
                             rejected += 1;
+                            set_rejected_reason(txn, &op_hash, &reason)?;
                             if let Dependency::Null = dependency {
                                 put_integrated(txn, &op_hash, ValidationStatus::Rejected)?;
                             } else {
@@ -201,6 +275,8 @@ async fn sys_validation_workflow_inner(
                         }
                     }
                 }
+                prune_validation_outcomes(txn)?;
+                faults.check(FaultPoint::SysValidationCommit)?;
                 WorkflowResult::Ok((total, awaiting, missing, rejected))
             })
             .await?;
@@ -222,13 +298,19 @@ async fn sys_validation_workflow_inner(
     }
     jh.await?;
     tracing::debug!("Accepted {} ops", total);
-    Ok(if saturated {
+    Ok(if saturated || over_budget {
         WorkComplete::Incomplete
     } else {
         WorkComplete::Complete
     })
 }
 
+/// Whether an op of an ephemeral DNA was authored before `cutoff`.
+/// Agent activity ops never expire.
+fn has_expired(op: &DhtOp, cutoff: Timestamp) -> bool {
+    op.get_type() != DhtOpType::RegisterAgentActivity && op.timestamp() < cutoff
+}
+
 async fn validate_op(
     op: &DhtOp,
     workspace: &SysValidationWorkspace,
@@ -256,11 +338,12 @@ async fn validate_op(
                 error_msg = %e
             );
             let outcome = handle_failed(e);
-            if let Outcome::Rejected = outcome {
+            if let Outcome::Rejected(reason) = &outcome {
                 warn!(
                     dna = %workspace.dna_hash(),
                     msg = "DhtOp was rejected during system validation.",
                     ?op,
+                    ?reason,
                 )
             }
             Ok(outcome)
@@ -269,37 +352,36 @@ async fn validate_op(
     }
 }
 
-/// For now errors result in an outcome but in the future
-/// we might find it useful to include the reason something
-/// was rejected etc.
-/// This is why the errors contain data but is currently unread.
+/// Turn a failed validation into an outcome. Rejected ops keep the reason
+/// they were rejected for, which is stored with the op and sent back to its
+/// author in the validation receipt.
 fn handle_failed(error: ValidationOutcome) -> Outcome {
     use Outcome::*;
     match error {
         ValidationOutcome::Counterfeit(_, _) => {
             unreachable!("Counterfeit ops are dropped before sys validation")
         }
-        ValidationOutcome::ActionNotInCounterSigningSession(_, _) => Rejected,
+        e @ ValidationOutcome::ActionNotInCounterSigningSession(_, _) => Rejected(e.into()),
         ValidationOutcome::DepMissingFromDht(_) => MissingDhtDep,
-        ValidationOutcome::EntryDefId(_) => Rejected,
-        ValidationOutcome::EntryHash => Rejected,
-        ValidationOutcome::EntryTooLarge(_, _) => Rejected,
-        ValidationOutcome::EntryType => Rejected,
-        ValidationOutcome::EntryVisibility(_) => Rejected,
-        ValidationOutcome::TagTooLarge(_, _) => Rejected,
-        ValidationOutcome::NotCreateLink(_) => Rejected,
-        ValidationOutcome::NotNewEntry(_) => Rejected,
+        e @ ValidationOutcome::EntryDefId(_) => Rejected(e.into()),
+        e @ ValidationOutcome::EntryHash => Rejected(e.into()),
+        e @ ValidationOutcome::EntryTooLarge(_, _) => Rejected(e.into()),
+        e @ ValidationOutcome::EntryType => Rejected(e.into()),
+        e @ ValidationOutcome::EntryVisibility(_) => Rejected(e.into()),
+        e @ ValidationOutcome::TagTooLarge(_, _) => Rejected(e.into()),
+        e @ ValidationOutcome::NotCreateLink(_) => Rejected(e.into()),
+        e @ ValidationOutcome::NotNewEntry(_) => Rejected(e.into()),
         ValidationOutcome::NotHoldingDep(dep) => AwaitingOpDep(dep),
         ValidationOutcome::PrevActionError(PrevActionError::MissingMeta(dep)) => {
             AwaitingOpDep(dep.into())
         }
-        ValidationOutcome::PrevActionError(_) => Rejected,
-        ValidationOutcome::PrivateEntry => Rejected,
-        ValidationOutcome::PreflightResponseSignature(_) => Rejected,
-        ValidationOutcome::UpdateTypeMismatch(_, _) => Rejected,
-        ValidationOutcome::VerifySignature(_, _) => Rejected,
-        ValidationOutcome::ZomeId(_) => Rejected,
-        ValidationOutcome::CounterSigningError(_) => Rejected,
+        e @ ValidationOutcome::PrevActionError(_) => Rejected(e.into()),
+        e @ ValidationOutcome::PrivateEntry(_) => Rejected(e.into()),
+        e @ ValidationOutcome::PreflightResponseSignature(_) => Rejected(e.into()),
+        e @ ValidationOutcome::UpdateTypeMismatch(_, _) => Rejected(e.into()),
+        e @ ValidationOutcome::VerifySignature(_, _) => Rejected(e.into()),
+        e @ ValidationOutcome::ZomeId(_) => Rejected(e.into()),
+        e @ ValidationOutcome::CounterSigningError(_) => Rejected(e.into()),
     }
 }
 
@@ -322,16 +404,8 @@ async fn validate_op_inner(
                         .ok_or_else(|| SysValidationError::NonEntryAction(action.clone()))?;
                     for action in session_data.build_action_set(entry_hash, weight)? {
                         let hh = ActionHash::with_data_sync(&action);
-                        if workspace
-                            .full_cascade(network.clone())
-                            .retrieve_action(hh.clone(), Default::default())
-                            .await?
-                            .is_none()
-                        {
-                            return Err(SysValidationError::ValidationOutcome(
-                                ValidationOutcome::DepMissingFromDht(hh.into()),
-                            ));
-                        }
+                        let mut cascade = workspace.full_cascade(network.clone());
+                        retrieve_dependency_action(&hh, &mut cascade).await?;
                     }
                 }
                 store_entry(
@@ -493,10 +567,12 @@ async fn sys_validate_record_inner(
         if let Some((maybe_entry, EntryVisibility::Public)) =
             &maybe_entry.and_then(|e| action.entry_type().map(|et| (e, et.visibility())))
         {
+            let new_entry_action: NewEntryActionRef = (action)
+                .try_into()
+                .map_err(|_| ValidationOutcome::NotNewEntry(action.clone()))?;
+            check_entry_hash(new_entry_action.entry_hash(), maybe_entry).await?;
             store_entry(
-                (action)
-                    .try_into()
-                    .map_err(|_| ValidationOutcome::NotNewEntry(action.clone()))?,
+                new_entry_action,
                 maybe_entry,
                 conductor_handle,
                 workspace,
@@ -594,16 +670,47 @@ async fn store_record(
     check_prev_action(action)?;
     if let Some(prev_action_hash) = prev_action_hash {
         let mut cascade = workspace.full_cascade(network);
-        let prev_action = cascade
-            .retrieve_action(prev_action_hash.clone(), Default::default())
-            .await?
-            .ok_or_else(|| ValidationOutcome::DepMissingFromDht(prev_action_hash.clone().into()))?;
+        let prev_action = match retrieve_dependency_action(prev_action_hash, &mut cascade).await {
+            // The previous action of an ephemeral DNA may have expired and
+            // been purged by its record authorities, but its agent activity
+            // authorities keep every action of the chain, so the checks
+            // are still made with the action they hold.
+            Err(SysValidationError::ValidationOutcome(ValidationOutcome::DepMissingFromDht(_)))
+                if workspace.dna_def.ephemeral_ttl().is_some() =>
+            {
+                retrieve_activity_action(action.author(), prev_action_hash, &mut cascade).await?
+            }
+            r => r?,
+        };
         check_prev_timestamp(action, prev_action.action())?;
         check_prev_seq(action, prev_action.action())?;
     }
     Ok(())
 }
 
+/// Get an action of an agent's chain from the agent activity authorities,
+/// which hold the actions of ephemeral DNAs after they have expired.
+async fn retrieve_activity_action(
+    author: &AgentPubKey,
+    hash: &ActionHash,
+    cascade: &mut Cascade<HolochainP2pDna>,
+) -> SysValidationResult<SignedActionHashed> {
+    let filter = ChainFilter::new(hash.clone()).take(1);
+    let action = match cascade
+        .must_get_agent_activity(author.clone(), filter)
+        .await?
+    {
+        MustGetAgentActivityResponse::Activity(activity) => activity
+            .into_iter()
+            .map(|activity| activity.action)
+            .find(|action| action.as_hash() == hash),
+        _ => None,
+    };
+    action.ok_or_else(|| ValidationOutcome::DepMissingFromDht(hash.clone().into()).into())
+}
+
+/// The entry hash must already have been checked, either for the whole batch
+/// with [`check_op_entry_hashes`] or with [`check_entry_hash`].
 async fn store_entry(
     action: NewEntryActionRef<'_>,
     entry: &Entry,
@@ -613,7 +720,6 @@ async fn store_entry(
 ) -> SysValidationResult<()> {
     // Get data ready to validate
     let entry_type = action.entry_type();
-    let entry_hash = action.entry_hash();
 
     // Checks
     check_entry_type(entry_type, entry)?;
@@ -623,19 +729,14 @@ async fn store_entry(
         check_not_private(&entry_def)?;
     }
 
-    check_entry_hash(entry_hash, entry).await?;
     check_entry_size(entry)?;
 
     // Additional checks if this is an Update
     if let NewEntryActionRef::Update(entry_update) = action {
         let original_action_address = &entry_update.original_action_address;
         let mut cascade = workspace.full_cascade(network);
-        let original_action = cascade
-            .retrieve_action(original_action_address.clone(), Default::default())
-            .await?
-            .ok_or_else(|| {
-                ValidationOutcome::DepMissingFromDht(original_action_address.clone().into())
-            })?;
+        let original_action =
+            retrieve_dependency_action(original_action_address, &mut cascade).await?;
         update_check(entry_update, original_action.action())?;
     }
 
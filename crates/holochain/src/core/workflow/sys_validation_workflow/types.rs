@@ -13,5 +13,5 @@ pub(super) enum Outcome {
     /// Note this is not proof it doesn't exist.
     MissingDhtDep,
     /// Moves to integration with status rejected
-    Rejected,
+    Rejected(SysValidationIssueReason),
 }
@@ -8,27 +8,36 @@ use holochain_types::dht_op::DhtOpType;
 use holochain_zome_types::Entry;
 use holochain_zome_types::SignedAction;
 
-pub use crate::core::validation::DhtOpOrder;
+use crate::core::queue_consumer::WorkBudget;
 use crate::core::workflow::error::WorkflowResult;
 
 /// Get all ops that need to sys or app validated in order.
 /// - Sys validated or awaiting app dependencies.
-/// - Ordered by type then timestamp (See [`DhtOpOrder`])
-pub async fn get_ops_to_app_validate(db: &DbRead<DbKindDht>) -> WorkflowResult<Vec<DhtOpHashed>> {
-    get_ops_to_validate(db, false).await
+/// - Ordered by type then timestamp (See [`OpOrder`](holochain_types::dht_op::OpOrder))
+/// - No more than fit in the budget. The bool is true if ops were left over.
+pub async fn get_ops_to_app_validate(
+    db: &DbRead<DbKindDht>,
+    budget: WorkBudget,
+) -> WorkflowResult<(Vec<DhtOpHashed>, bool)> {
+    get_ops_to_validate(db, false, budget).await
 }
 
 /// Get all ops that need to sys or app validated in order.
 /// - Pending or awaiting sys dependencies.
-/// - Ordered by type then timestamp (See [`DhtOpOrder`])
-pub async fn get_ops_to_sys_validate(db: &DbRead<DbKindDht>) -> WorkflowResult<Vec<DhtOpHashed>> {
-    get_ops_to_validate(db, true).await
+/// - Ordered by type then timestamp (See [`OpOrder`](holochain_types::dht_op::OpOrder))
+/// - No more than fit in the budget. The bool is true if ops were left over.
+pub async fn get_ops_to_sys_validate(
+    db: &DbRead<DbKindDht>,
+    budget: WorkBudget,
+) -> WorkflowResult<(Vec<DhtOpHashed>, bool)> {
+    get_ops_to_validate(db, true, budget).await
 }
 
 async fn get_ops_to_validate(
     db: &DbRead<DbKindDht>,
     system: bool,
-) -> WorkflowResult<Vec<DhtOpHashed>> {
+    budget: WorkBudget,
+) -> WorkflowResult<(Vec<DhtOpHashed>, bool)> {
     let mut sql = "
         SELECT
         Action.blob as action_blob,
@@ -67,7 +76,7 @@ async fn get_ops_to_validate(
             ",
         );
     }
-    // TODO: There is a very unlikely chance that 10000 ops
+    // TODO: There is a very unlikely chance that a whole budget of ops
     // could all fail to validate and prevent validation from
     // moving on but this is not easy to overcome.
     // Once we impl abandoned this won't happen anyway.
@@ -76,29 +85,36 @@ async fn get_ops_to_validate(
         ORDER BY
         DhtOp.num_validation_attempts ASC,
         DhtOp.op_order ASC
-        LIMIT 10000
+        LIMIT :limit
         ",
     );
     db.async_reader(move |txn| {
+        let mut spend = budget.start();
         let mut stmt = txn.prepare(&sql)?;
-        let r = stmt.query_and_then([], |row| {
-            let action = from_blob::<SignedAction>(row.get("action_blob")?)?;
+        let mut rows = stmt.query(named_params! { ":limit": spend.query_limit() })?;
+        let mut ops = Vec::new();
+        while let Some(row) = rows.next()? {
+            let action_blob: Vec<u8> = row.get("action_blob")?;
+            let entry_blob: Option<Vec<u8>> = row.get("entry_blob")?;
+            let size = action_blob.len() + entry_blob.as_ref().map_or(0, |e| e.len());
+            if !spend.try_spend(size) {
+                break;
+            }
+            let action = from_blob::<SignedAction>(action_blob)?;
             let op_type: DhtOpType = row.get("dht_type")?;
             let hash: DhtOpHash = row.get("dht_hash")?;
-            let entry: Option<Vec<u8>> = row.get("entry_blob")?;
-            let entry = match entry {
+            let entry = match entry_blob {
                 Some(entry) => Some(from_blob::<Entry>(entry)?),
                 None => None,
             };
-            WorkflowResult::Ok(DhtOpHashed::with_pre_hashed(
+            ops.push(DhtOpHashed::with_pre_hashed(
                 DhtOp::from_type(op_type, action, entry)?,
                 hash,
-            ))
-        })?;
-        let r = r.collect();
-        WorkflowResult::Ok(r)
+            ));
+        }
+        WorkflowResult::Ok((ops, spend.is_exhausted()))
     })
-    .await?
+    .await
 }
 
 #[cfg(test)]
@@ -138,7 +154,10 @@ mod tests {
         observability::test_run().ok();
         let db = test_dht_db();
         let expected = test_data(&db.to_db().into());
-        let r = get_ops_to_validate(&db.to_db().into(), true).await.unwrap();
+        let (r, more) = get_ops_to_validate(&db.to_db().into(), true, WorkBudget::default())
+            .await
+            .unwrap();
+        assert!(!more);
         let mut r_sorted = r.clone();
         // Sorted by OpOrder
         r_sorted.sort_by_key(|d| {
@@ -152,6 +171,34 @@ mod tests {
         }
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn sys_validation_query_respects_budget() {
+        observability::test_run().ok();
+        let db = test_dht_db();
+        let expected = test_data(&db.to_db().into());
+        let read: DbRead<_> = db.to_db().into();
+
+        let (r, more) = get_ops_to_sys_validate(&read, WorkBudget::new(10, usize::MAX))
+            .await
+            .unwrap();
+        assert_eq!(r.len(), 10);
+        assert!(more);
+
+        let (r, more) =
+            get_ops_to_sys_validate(&read, WorkBudget::new(expected.results.len(), usize::MAX))
+                .await
+                .unwrap();
+        assert_eq!(r.len(), expected.results.len());
+        assert!(!more);
+
+        // An op bigger than the byte budget is still taken on its own.
+        let (r, more) = get_ops_to_sys_validate(&read, WorkBudget::new(100, 1))
+            .await
+            .unwrap();
+        assert_eq!(r.len(), 1);
+        assert!(more);
+    }
+
     fn create_and_insert_op(db: &DbWrite<DbKindDht>, facts: Facts) -> DhtOpHashed {
         let state = DhtOpHashed::from_content_sync(DhtOp::RegisterAgentActivity(
             fixt!(Signature),
@@ -241,18 +288,20 @@ mod tests {
 
         let read: DbRead<_> = db.clone().into();
         let mut read_ops = std::collections::HashSet::new();
-        let hashes: Vec<_> = get_ops_to_app_validate(&read)
+        let hashes: Vec<_> = get_ops_to_app_validate(&read, WorkBudget::default())
             .await
             .unwrap()
+            .0
             .into_iter()
             .map(|op| op.to_hash())
             .collect();
         for h in &hashes {
             read_ops.insert(h.clone());
         }
-        let hashes: Vec<_> = get_ops_to_sys_validate(&read)
+        let hashes: Vec<_> = get_ops_to_sys_validate(&read, WorkBudget::default())
             .await
             .unwrap()
+            .0
             .into_iter()
             .map(|op| op.to_hash())
             .collect();
@@ -12,11 +12,13 @@ use crate::conductor::ConductorHandle;
 use crate::core::queue_consumer::WorkComplete;
 
 use super::error::WorkflowResult;
+use super::fault_injection::FaultInjector;
+use super::fault_injection::FaultPoint;
 
 #[cfg(test)]
 mod tests;
 
-#[instrument(skip(vault, network, keystore, conductor))]
+#[instrument(skip(vault, network, keystore, conductor, faults))]
 /// Send validation receipts to their authors in serial and without waiting for
 /// responses.
 /// TODO: Currently still waiting for responses because we don't have a network call
@@ -27,6 +29,7 @@ pub async fn validation_receipt_workflow(
     network: &HolochainP2pDna,
     keystore: MetaLairClient,
     conductor: ConductorHandle,
+    faults: &FaultInjector,
 ) -> WorkflowResult<WorkComplete> {
     // Who we are.
     // TODO: I think this is right but maybe we need to make sure these cells are in
@@ -57,8 +60,8 @@ pub async fn validation_receipt_workflow(
             move |txn| {
                 let mut stmt = txn.prepare(
                     "
-            SELECT Action.author, DhtOp.hash, DhtOp.validation_status,
-            DhtOp.when_integrated
+            SELECT Action.author, DhtOp.hash, DhtOp.basis_hash, DhtOp.validation_status,
+            DhtOp.when_integrated, DhtOp.rejected_reason
             From DhtOp
             JOIN Action ON DhtOp.action_hash = Action.hash
             WHERE
@@ -73,17 +76,22 @@ pub async fn validation_receipt_workflow(
                     .query_and_then([], |r| {
                         let author: AgentPubKey = r.get("author")?;
                         let dht_op_hash = r.get("hash")?;
+                        let basis: AnyDhtHash = r.get("basis_hash")?;
                         let validation_status = r.get("validation_status")?;
                         // NB: timestamp will never be null, so this is OK
                         let when_integrated = r.get("when_integrated")?;
+                        let rejected_reason: Option<Vec<u8>> = r.get("rejected_reason")?;
+                        let rejected_reason = rejected_reason.map(from_blob).transpose()?;
                         StateQueryResult::Ok((
                             ValidationReceipt {
                                 dht_op_hash,
                                 validation_status,
                                 validators: validators.clone(),
                                 when_integrated,
+                                rejected_reason,
                             },
                             author,
+                            basis,
                         ))
                     })?
                     .collect::<StateQueryResult<Vec<_>>>()?;
@@ -93,16 +101,24 @@ pub async fn validation_receipt_workflow(
         .await?;
 
     // Send the validation receipts
-    for (receipt, author) in receipts {
+    for (mut receipt, author, basis) in receipts {
         // Don't send receipt to self.
         if validators.iter().any(|validator| *validator == author) {
             continue;
         }
 
+        receipt.validators = receipt_validators(network, &validators, basis).await;
+
         let op_hash = receipt.dht_op_hash.clone();
 
         // Sign on the dotted line.
-        let receipt = match ValidationReceipt::sign(receipt, &keystore).await {
+        let signed = match faults.check(FaultPoint::ValidationReceiptSign) {
+            Ok(()) => ValidationReceipt::sign(receipt, &keystore)
+                .await
+                .map_err(Into::into),
+            Err(e) => Err(e),
+        };
+        let receipt = match signed {
             Ok(Some(r)) => r,
             Ok(None) => {
                 return Ok(WorkComplete::Incomplete);
@@ -135,3 +151,26 @@ pub async fn validation_receipt_workflow(
 
     Ok(WorkComplete::Complete)
 }
+
+/// The local agents which vouch for an op, which are those the network
+/// routes the op's basis to. If none of them are known to be authorities
+/// for it, such as before our own agent infos are in the peer store, all
+/// of them vouch for it as we are holding it.
+async fn receipt_validators(
+    network: &HolochainP2pDna,
+    validators: &[AgentPubKey],
+    basis: AnyDhtHash,
+) -> Vec<AgentPubKey> {
+    let authorities =
+        holochain_p2p::HolochainP2pDnaT::authorities_for_basis(network, basis, u32::MAX).await;
+    let local: Vec<_> = authorities
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|agent| validators.contains(agent))
+        .collect();
+    if local.is_empty() {
+        validators.to_vec()
+    } else {
+        local
+    }
+}
@@ -16,6 +16,7 @@ use crate::core::ribosome::guest_callback::validate::ValidateHostAccess;
 use crate::core::ribosome::guest_callback::validation_package::ValidationPackageHostAccess;
 use crate::core::ribosome::guest_callback::validation_package::ValidationPackageInvocation;
 use crate::core::ribosome::real_ribosome::RealRibosome;
+use crate::core::ribosome::zome_call_limits::ZomeCallCancel;
 use crate::core::ribosome::CallContext;
 use crate::core::ribosome::FnComponents;
 use crate::core::ribosome::HostContext;
@@ -28,6 +29,7 @@ use crate::test_utils::fake_genesis;
 use ::fixt::prelude::*;
 pub use holo_hash::fixt::*;
 use holo_hash::WasmHash;
+use holochain_conductor_api::config::conductor::ZomeCallLimitsConfig;
 use holochain_keystore::MetaLairClient;
 use holochain_p2p::HolochainP2pDnaFixturator;
 use holochain_state::host_fn_workspace::HostFnWorkspace;
@@ -356,9 +358,19 @@ fixturator!(
     vanilla fn make_call_zome_handle(CellId);
 );
 
+fixturator!(
+    ZomeCallLimitsConfig;
+    constructor fn default();;
+);
+
+fixturator!(
+    ZomeCallCancel;
+    constructor fn default();;
+);
+
 fixturator!(
     ZomeCallHostAccess;
-    constructor fn new(HostFnWorkspace, MetaLairClient, HolochainP2pDna, SignalBroadcaster, CellConductorReadHandle);
+    constructor fn new(HostFnWorkspace, MetaLairClient, HolochainP2pDna, SignalBroadcaster, CellConductorReadHandle, ZomeCallLimitsConfig, ZomeCallCancel);
 );
 
 fixturator!(
@@ -471,6 +471,8 @@ async fn setup(
                 .map(TestZomes::from)
                 .map(|z| z.coordinator.into_inner())
                 .collect(),
+            runtime_properties: Default::default(),
+            ephemeral_ttl_secs: None,
         },
         zomes.into_iter().map(Into::into),
     )
@@ -11,6 +11,11 @@
 //! A handy collection of cells installed under the same app.
 //! Makes it easy to destructure the result of a SweetConductor::setup_app call
 //! into a collection of SweetCells which can be used for zome calls.
+//!
+//! ### SweetScenario
+//! Several conductors in one process, networked in memory and all running
+//! the same app, installed from DnaFiles or an app bundle. Lets app
+//! developers write end-to-end tests in Rust without running any binaries.
 
 mod sweet_agents;
 mod sweet_app;
@@ -20,6 +25,7 @@ mod sweet_conductor_batch;
 mod sweet_conductor_handle;
 mod sweet_dna;
 mod sweet_network;
+mod sweet_scenario;
 mod sweet_zome;
 
 pub use sweet_agents::*;
@@ -30,4 +36,5 @@ pub use sweet_conductor_batch::*;
 pub use sweet_conductor_handle::*;
 pub use sweet_dna::*;
 pub use sweet_network::*;
+pub use sweet_scenario::*;
 pub use sweet_zome::*;
@@ -53,7 +53,7 @@ pub fn standard_config() -> ConductorConfig {
         override_port: None,
     }];
     let admin_interface = AdminInterfaceConfig {
-        driver: InterfaceDriver::Websocket { port: 0 },
+        driver: InterfaceDriver::websocket(0),
     };
     ConductorConfig {
         network: Some(network),
@@ -376,6 +376,52 @@ impl SweetConductor {
         Ok(SweetAppBatch(apps))
     }
 
+    /// Opinionated app setup from an app bundle.
+    /// Installs and enables the app in the bundle at the given path for the given agent,
+    /// using no membrane proofs. The cells of the returned SweetApp are sorted by role id.
+    pub async fn setup_app_from_bundle(
+        &mut self,
+        installed_app_id: &str,
+        agent: AgentPubKey,
+        bundle_path: &Path,
+    ) -> ConductorApiResult<SweetApp> {
+        let app = self
+            .handle()
+            .0
+            .clone()
+            .install_app_bundle(InstallAppBundlePayload {
+                source: AppBundleSource::Path(bundle_path.to_owned()),
+                agent_key: agent.clone(),
+                installed_app_id: Some(installed_app_id.to_string()),
+                membrane_proofs: Default::default(),
+                network_seed: None,
+            })
+            .await?;
+        self.handle()
+            .0
+            .clone()
+            .enable_app(installed_app_id.to_string())
+            .await?;
+
+        self.handle()
+            .0
+            .clone()
+            .reconcile_cell_status_with_app_status()
+            .await?;
+
+        let mut roles: Vec<_> = app
+            .provisioned_cells()
+            .map(|(role, cell_id)| (role.clone(), cell_id.dna_hash().clone()))
+            .collect();
+        roles.sort();
+        self.setup_app_3_create_sweet_app(
+            installed_app_id,
+            agent,
+            roles.into_iter().map(|(_, dna_hash)| dna_hash),
+        )
+        .await
+    }
+
     /// Get a stream of all Signals emitted on the "sweet-interface" AppInterface.
     ///
     /// This is designed to crash if called more than once, because as currently
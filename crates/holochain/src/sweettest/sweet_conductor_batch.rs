@@ -3,6 +3,7 @@ use crate::conductor::{api::error::ConductorApiResult, config::ConductorConfig};
 use futures::future;
 use hdk::prelude::*;
 use holochain_types::prelude::*;
+use std::path::Path;
 /// A collection of SweetConductors, with methods for operating on the entire collection
 #[derive(derive_more::From, derive_more::Into, derive_more::IntoIterator)]
 pub struct SweetConductorBatch(Vec<SweetConductor>);
@@ -110,6 +111,32 @@ impl SweetConductorBatch {
             .into())
     }
 
+    /// Opinionated app setup from an app bundle.
+    /// Installs the app in the bundle at the given path on each Conductor in this batch,
+    /// creating a new AgentPubKey for each.
+    pub async fn setup_app_from_bundle(
+        &mut self,
+        installed_app_id: &str,
+        bundle_path: &Path,
+    ) -> ConductorApiResult<SweetAppBatch> {
+        let apps = self
+            .0
+            .iter_mut()
+            .map(|conductor| async move {
+                let agent = SweetAgents::one(conductor.keystore()).await;
+                conductor
+                    .setup_app_from_bundle(installed_app_id, agent, bundle_path)
+                    .await
+            })
+            .collect::<Vec<_>>();
+
+        Ok(future::join_all(apps)
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?
+            .into())
+    }
+
     /// Let each conductor know about each others' agents so they can do networking
     pub async fn exchange_peer_info(&self) {
         let mut all = Vec::new();
@@ -151,6 +178,14 @@ impl SweetConductorBatch {
             c.force_all_publish_dht_ops().await;
         }
     }
+
+    /// Wait until every conductor has integrated all the ops published by
+    /// the cells on all of the conductors, panicking after the timeout.
+    /// See [`crate::test_utils::await_consistency`].
+    pub async fn await_consistency(&self, timeout: std::time::Duration) {
+        let handles: Vec<_> = self.0.iter().map(|c| c.inner_handle()).collect();
+        crate::test_utils::await_consistency(&handles, timeout).await
+    }
 }
 
 impl std::ops::Index<usize> for SweetConductorBatch {
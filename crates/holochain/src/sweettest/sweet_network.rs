@@ -25,6 +25,13 @@ impl SweetNetwork {
         })
     }
 
+    /// In-memory network, which only reaches conductors in the same process
+    pub fn mem() -> KitsuneP2pConfig {
+        let mut network = KitsuneP2pConfig::default();
+        network.transport_pool = vec![kitsune_p2p::TransportConfig::Mem {}];
+        network
+    }
+
     /// Local quic proxy network
     pub fn local_quic() -> KitsuneP2pConfig {
         let mut network = KitsuneP2pConfig::default();
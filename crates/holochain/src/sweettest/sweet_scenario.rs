@@ -0,0 +1,134 @@
+use super::{standard_config, SweetAppBatch, SweetCell, SweetConductorBatch, SweetNetwork};
+use crate::conductor::api::error::ConductorApiResult;
+use holochain_types::prelude::*;
+use kitsune_p2p::KitsuneP2pConfig;
+use std::path::PathBuf;
+
+/// The app a SweetScenario installs on each conductor
+enum ScenarioApp {
+    Dnas(Vec<DnaFile>),
+    Bundle(PathBuf),
+}
+
+/// Builder for a [`SweetScenario`]
+pub struct SweetScenarioBuilder {
+    num_conductors: usize,
+    network: KitsuneP2pConfig,
+    installed_app_id: InstalledAppId,
+    app: ScenarioApp,
+}
+
+impl SweetScenarioBuilder {
+    /// Use a different network than the default in-memory network,
+    /// e.g. [`SweetNetwork::local_quic`].
+    pub fn network(mut self, network: KitsuneP2pConfig) -> Self {
+        self.network = network;
+        self
+    }
+
+    /// Install the app under this id instead of "app".
+    pub fn installed_app_id(mut self, installed_app_id: impl Into<InstalledAppId>) -> Self {
+        self.installed_app_id = installed_app_id.into();
+        self
+    }
+
+    /// Install an app made of these DnaFiles on each conductor.
+    pub fn dnas(mut self, dnas: impl IntoIterator<Item = DnaFile>) -> Self {
+        self.app = ScenarioApp::Dnas(dnas.into_iter().collect());
+        self
+    }
+
+    /// Install the app in the bundle at this path on each conductor.
+    pub fn app_bundle(mut self, path: impl Into<PathBuf>) -> Self {
+        self.app = ScenarioApp::Bundle(path.into());
+        self
+    }
+
+    /// Start the conductors, install the app on each with a new agent,
+    /// and let the conductors know about each others' agents.
+    pub async fn build(self) -> ConductorApiResult<SweetScenario> {
+        let mut config = standard_config();
+        config.network = Some(self.network);
+        let mut conductors = SweetConductorBatch::from_config(self.num_conductors, config).await;
+        let apps = match &self.app {
+            ScenarioApp::Dnas(dnas) => conductors.setup_app(&self.installed_app_id, dnas).await?,
+            ScenarioApp::Bundle(path) => {
+                conductors
+                    .setup_app_from_bundle(&self.installed_app_id, path)
+                    .await?
+            }
+        };
+        conductors.exchange_peer_info().await;
+        Ok(SweetScenario { conductors, apps })
+    }
+}
+
+/// A number of conductors running the same app and networked together,
+/// for end-to-end tests of an app.
+///
+/// ```ignore
+/// let scenario = SweetScenario::builder(2)
+///     .app_bundle("workdir/my_app.happ")
+///     .build()
+///     .await
+///     .unwrap();
+/// let cells = scenario.cells();
+/// let hash: ActionHash = scenario.conductors()[0]
+///     .call(&cells[0].zome("posts"), "create_post", "hello")
+///     .await;
+/// scenario.await_consistency().await;
+/// let post: Option<Record> = scenario.conductors()[1]
+///     .call(&cells[1].zome("posts"), "get_post", hash)
+///     .await;
+/// ```
+pub struct SweetScenario {
+    conductors: SweetConductorBatch,
+    apps: SweetAppBatch,
+}
+
+impl SweetScenario {
+    /// Start building a scenario with this many conductors.
+    /// By default they are networked in memory and the app has no DNAs.
+    pub fn builder(num_conductors: usize) -> SweetScenarioBuilder {
+        SweetScenarioBuilder {
+            num_conductors,
+            network: SweetNetwork::mem(),
+            installed_app_id: "app".into(),
+            app: ScenarioApp::Dnas(Vec::new()),
+        }
+    }
+
+    /// Accessor
+    pub fn conductors(&self) -> &SweetConductorBatch {
+        &self.conductors
+    }
+
+    /// Accessor
+    pub fn conductors_mut(&mut self) -> &mut SweetConductorBatch {
+        &mut self.conductors
+    }
+
+    /// The app installed on each conductor, in the same order as the conductors
+    pub fn apps(&self) -> &SweetAppBatch {
+        &self.apps
+    }
+
+    /// All cells of the app on all conductors
+    pub fn cells(&self) -> Vec<&SweetCell> {
+        self.apps.cells_flattened()
+    }
+
+    /// Wait up to 10 seconds for every conductor to have integrated
+    /// everything the cells of the app have published, panicking if they
+    /// haven't by then.
+    pub async fn await_consistency(&self) {
+        self.conductors
+            .await_consistency(std::time::Duration::from_secs(10))
+            .await
+    }
+
+    /// Convert into the conductors and apps
+    pub fn into_inner(self) -> (SweetConductorBatch, SweetAppBatch) {
+        (self.conductors, self.apps)
+    }
+}
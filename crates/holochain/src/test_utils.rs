@@ -15,6 +15,7 @@ use ::fixt::prelude::*;
 use hdk::prelude::ZomeName;
 use holo_hash::fixt::*;
 use holo_hash::*;
+use holochain_conductor_api::CellOpCounts;
 use holochain_conductor_api::IntegrationStateDump;
 use holochain_conductor_api::IntegrationStateDumps;
 use holochain_keystore::MetaLairClient;
@@ -40,6 +41,7 @@ use holochain_types::prelude::*;
 use holochain_wasm_test_utils::TestWasm;
 use kitsune_p2p::KitsuneP2pConfig;
 use rusqlite::named_params;
+use std::collections::HashMap;
 use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
@@ -237,6 +239,9 @@ where
                 QueryAgentInfoSigned { respond, .. } => {
                     respond.r(Ok(async move { Ok(vec![]) }.boxed().into()));
                 }
+                TakeQueuedRemoteSignals { respond, .. } => {
+                    respond.r(Ok(async move { Ok(vec![]) }.boxed().into()));
+                }
                 QueryPeerDensity { respond, .. } => {
                     respond.r(Ok(async move {
                         Ok(PeerViewQ::new(
@@ -372,7 +377,7 @@ pub async fn setup_app_inner(
     let conductor_handle = ConductorBuilder::new()
         .config(ConductorConfig {
             admin_interfaces: Some(vec![AdminInterfaceConfig {
-                driver: InterfaceDriver::Websocket { port: 0 },
+                driver: InterfaceDriver::websocket(0),
             }]),
             network,
             ..Default::default()
@@ -481,6 +486,46 @@ pub async fn consistency_dbs<AuthorDb, DhtDb>(
     }
 }
 
+/// Wait until the conductors have converged, which is when each of them has
+/// integrated every op published by the cells of each of its DNAs on all
+/// of the conductors. This assumes no other conductors are running the DNAs.
+///
+/// Panics with the last op counts of each conductor if they haven't
+/// converged before the timeout.
+pub async fn await_consistency(conductors: &[ConductorHandle], timeout: Duration) {
+    const DELAY_PER_ATTEMPT: Duration = Duration::from_millis(100);
+    let deadline = tokio::time::Instant::now() + timeout;
+    loop {
+        let mut counts = Vec::with_capacity(conductors.len());
+        for conductor in conductors {
+            counts.push(conductor.op_counts().await.unwrap());
+        }
+        if ops_converged(&counts) {
+            return;
+        }
+        if tokio::time::Instant::now() >= deadline {
+            panic!(
+                "Conductors did not converge within {:?}. Op counts: {:#?}",
+                timeout, counts
+            );
+        }
+        tokio::time::sleep(DELAY_PER_ATTEMPT).await;
+    }
+}
+
+/// Whether every conductor has integrated all the ops published for
+/// each of its DNAs, given the op counts of each conductor.
+fn ops_converged(counts: &[Vec<CellOpCounts>]) -> bool {
+    let mut published: HashMap<&DnaHash, usize> = HashMap::new();
+    for cell in counts.iter().flatten() {
+        *published.entry(cell.cell_id.dna_hash()).or_default() += cell.published;
+    }
+    counts
+        .iter()
+        .flatten()
+        .all(|cell| cell.integrated == published[cell.cell_id.dna_hash()])
+}
+
 /// Same as wait_for_integration but with a default wait time of 60 seconds
 /// Wait for all cells to reach consistency for 10 seconds
 pub async fn consistency_10s_others(all_cells: &[&SweetCell]) {
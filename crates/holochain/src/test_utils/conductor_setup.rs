@@ -191,6 +191,8 @@ impl ConductorTestData {
                     .map(TestZomes::from)
                     .map(|z| z.coordinator.into_inner())
                     .collect(),
+                runtime_properties: Default::default(),
+                ephemeral_ttl_secs: None,
             },
             zomes.into_iter().flat_map(Vec::<DnaWasm>::from),
         )
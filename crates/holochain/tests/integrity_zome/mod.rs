@@ -105,6 +105,7 @@ async fn test_coordinator_zome_update_multi_integrity() {
         ZomeDef::Wasm(WasmZome {
             wasm_hash,
             mut dependencies,
+            ..
         }) => {
             dependencies.clear();
             dependencies.push("2".into());
@@ -114,6 +115,7 @@ async fn test_coordinator_zome_update_multi_integrity() {
                 ZomeDef::Wasm(WasmZome {
                     wasm_hash,
                     dependencies,
+                    unrestricted_functions: Default::default(),
                 })
                 .into(),
             )
@@ -196,6 +198,7 @@ async fn test_coordinator_zome_update_multi_integrity() {
     let new_coordinator: CoordinatorZomeDef = ZomeDef::Wasm(WasmZome {
         wasm_hash,
         dependencies: vec!["2".into()],
+        unrestricted_functions: Default::default(),
     })
     .into();
 
@@ -252,6 +255,7 @@ async fn test_update_admin_interface() {
             dependencies: Some(vec![ZomeDependency {
                 name: TestIntegrityWasm::IntegrityZome.into(),
             }]),
+            unrestricted_functions: None,
         }],
     };
 
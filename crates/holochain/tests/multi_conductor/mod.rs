@@ -20,7 +20,7 @@ struct AppString(String);
 async fn test_publish() -> anyhow::Result<()> {
     use std::sync::Arc;
 
-    use holochain::test_utils::{consistency_10s, inline_zomes::simple_create_read_zome};
+    use holochain::test_utils::inline_zomes::simple_create_read_zome;
     use kitsune_p2p::KitsuneP2pConfig;
 
     let _g = observability::test_run().ok();
@@ -43,15 +43,25 @@ async fn test_publish() -> anyhow::Result<()> {
     let apps = conductors.setup_app("app", &[dna_file]).await.unwrap();
     conductors.exchange_peer_info().await;
 
-    let ((alice,), (bobbo,), (carol,)) = apps.into_tuples();
+    let ((alice,), (bobbo,), (_carol,)) = apps.into_tuples();
 
     // Call the "create" zome fn on Alice's app
     let hash: ActionHash = conductors[0]
         .call(&alice.zome("simple"), "create", ())
         .await;
 
-    // Wait long enough for Bob to receive gossip
-    consistency_10s(&[&alice, &bobbo, &carol]).await;
+    // Wait until every conductor has integrated everything that was published
+    conductors
+        .await_consistency(std::time::Duration::from_secs(10))
+        .await;
+    let mut counts = Vec::new();
+    for conductor in conductors.iter() {
+        counts.extend(conductor.inner_handle().op_counts().await.unwrap());
+    }
+    let published: usize = counts.iter().map(|c| c.published).sum();
+    assert_eq!(counts.len(), NUM_CONDUCTORS);
+    assert!(published > 0);
+    assert!(counts.iter().all(|c| c.integrated == published));
 
     // Verify that bobbo can run "read" on his cell and get alice's Action
     let record: Option<Record> = conductors[1]
@@ -69,6 +79,94 @@ async fn test_publish() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Test that a scenario networks its conductors in memory so that they
+/// can get each other's records.
+#[cfg(feature = "test_utils")]
+#[tokio::test(flavor = "multi_thread")]
+async fn scenario_conductors_reach_consistency() -> anyhow::Result<()> {
+    use holochain::sweettest::SweetScenario;
+    use holochain::test_utils::inline_zomes::simple_create_read_zome;
+
+    let _g = observability::test_run().ok();
+
+    let (dna_file, _, _) = SweetDnaFile::unique_from_inline_zomes(simple_create_read_zome())
+        .await
+        .unwrap();
+    let scenario = SweetScenario::builder(2)
+        .dnas([dna_file])
+        .build()
+        .await
+        .unwrap();
+    let cells = scenario.cells();
+
+    let hash: ActionHash = scenario.conductors()[0]
+        .call(&cells[0].zome("simple"), "create", ())
+        .await;
+    scenario.await_consistency().await;
+
+    let record: Option<Record> = scenario.conductors()[1]
+        .call(&cells[1].zome("simple"), "read", hash)
+        .await;
+    let record = record.expect("Record was None: the second conductor couldn't `get` it");
+    assert_eq!(record.action().author(), cells[0].agent_pubkey());
+
+    Ok(())
+}
+
+/// Test that ops published to a conductor are still validated and integrated
+/// after its sys validation and integration commits have failed.
+#[cfg(feature = "test_utils")]
+#[tokio::test(flavor = "multi_thread")]
+async fn ops_are_integrated_after_injected_faults() -> anyhow::Result<()> {
+    use holochain::core::workflow::fault_injection::{Fault, FaultPoint};
+    use holochain::sweettest::SweetScenario;
+    use holochain::test_utils::inline_zomes::simple_create_read_zome;
+    use std::time::Duration;
+
+    let _g = observability::test_run().ok();
+
+    let (dna_file, _, _) = SweetDnaFile::unique_from_inline_zomes(simple_create_read_zome())
+        .await
+        .unwrap();
+    let scenario = SweetScenario::builder(2)
+        .dnas([dna_file])
+        .build()
+        .await
+        .unwrap();
+    let cells = scenario.cells();
+
+    let faults = scenario.conductors()[1].inner_handle().fault_injector();
+    faults.set(FaultPoint::SysValidationCommit, Fault::always().times(1));
+    faults.set(FaultPoint::IntegrationCommit, Fault::always().times(1));
+
+    // Keep publishing until both faults have fired, so the failed
+    // workflows are triggered again by new ops.
+    tokio::time::timeout(Duration::from_secs(30), async {
+        while faults.injected(FaultPoint::SysValidationCommit) == 0
+            || faults.injected(FaultPoint::IntegrationCommit) == 0
+        {
+            let _: ActionHash = scenario.conductors()[0]
+                .call(&cells[0].zome("simple"), "create", ())
+                .await;
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    })
+    .await
+    .expect("The injected faults never fired");
+
+    let hash: ActionHash = scenario.conductors()[0]
+        .call(&cells[0].zome("simple"), "create", ())
+        .await;
+    scenario.await_consistency().await;
+
+    let record: Option<Record> = scenario.conductors()[1]
+        .call(&cells[1].zome("simple"), "read", hash)
+        .await;
+    assert!(record.is_some());
+
+    Ok(())
+}
+
 #[cfg(feature = "test_utils")]
 #[tokio::test(flavor = "multi_thread")]
 async fn multi_conductor() -> anyhow::Result<()> {
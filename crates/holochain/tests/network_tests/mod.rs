@@ -516,6 +516,7 @@ async fn run_fixt_network(
                             updates: btreeset! {},
                             invalid_actions: btreeset! {},
                             entry_dht_status: None,
+                            crud_summary: None,
                         };
                         respond.respond(Ok(async move { Ok(metadata.try_into().unwrap()) }
                             .boxed()
@@ -54,6 +54,8 @@ async fn ser_regression_test() {
             coordinator_zomes: vec![TestZomes::from(TestWasm::SerRegression)
                 .coordinator
                 .into_inner()],
+            runtime_properties: Default::default(),
+            ephemeral_ttl_secs: None,
         },
         <Vec<DnaWasm>>::from(TestWasm::SerRegression),
     )
@@ -373,6 +373,7 @@ async fn mock_network_sharded_gossip() {
             // Simulated agents will send messages from within 50 to 100 ms.
             outbound_delay_range: std::time::Duration::from_millis(50)
                 ..std::time::Duration::from_millis(150),
+            ..Default::default()
         },
     );
 
@@ -430,7 +431,9 @@ async fn mock_network_sharded_gossip() {
                         holochain_p2p::WireMessage::ValidationReceipt { receipt: _ } => {
                             debug!("Validation Receipt")
                         }
-                        holochain_p2p::WireMessage::Get { dht_hash, options } => {
+                        holochain_p2p::WireMessage::Get {
+                            dht_hash, options, ..
+                        } => {
                             let txn = conn
                                 .transaction_with_behavior(rusqlite::TransactionBehavior::Exclusive)
                                 .unwrap();
@@ -462,6 +465,7 @@ async fn mock_network_sharded_gossip() {
                         holochain_p2p::WireMessage::GetValidationPackage { .. } => {
                             debug!("get_validation_package")
                         }
+                        holochain_p2p::WireMessage::FetchDna { .. } => debug!("fetch_dna"),
                         holochain_p2p::WireMessage::CountersigningSessionNegotiation { .. } => {
                             debug!("countersigning_session_negotiation")
                         }
@@ -676,6 +680,12 @@ async fn mock_network_sharded_gossip() {
                                         );
                                     }
                                     ShardedGossipWire::OpRegions(_) => todo!("must implement"),
+                                    // A region bloom only answers the `OpRegions` of a
+                                    // historical round, and these simulated agents only
+                                    // ever gossip with op blooms.
+                                    ShardedGossipWire::OpRegionBloom(_) => {
+                                        unreachable!("region bloom without sending op regions")
+                                    }
 
                                     ShardedGossipWire::Agents(_) => {}
                                     ShardedGossipWire::MissingAgents(_) => {}
@@ -905,6 +915,7 @@ async fn mock_network_sharding() {
             // Simulated agents will send messages from within 50 to 100 ms.
             outbound_delay_range: std::time::Duration::from_millis(50)
                 ..std::time::Duration::from_millis(150),
+            ..Default::default()
         },
     );
 
@@ -935,7 +946,9 @@ async fn mock_network_sharding() {
                         holochain_p2p::WireMessage::ValidationReceipt { receipt: _ } => {
                             debug!("Validation Receipt")
                         }
-                        holochain_p2p::WireMessage::Get { dht_hash, options } => {
+                        holochain_p2p::WireMessage::Get {
+                            dht_hash, options, ..
+                        } => {
                             num_gets.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
                             let ops = if data.agent_to_arc[&agent].contains(dht_hash.get_loc()) {
                                 let txn = conn
@@ -980,6 +993,7 @@ async fn mock_network_sharding() {
                         holochain_p2p::WireMessage::GetValidationPackage { .. } => {
                             debug!("get_validation_package")
                         }
+                        holochain_p2p::WireMessage::FetchDna { .. } => debug!("fetch_dna"),
                         holochain_p2p::WireMessage::CountersigningSessionNegotiation { .. } => {
                             debug!("countersigning_session_negotiation")
                         }
@@ -1181,6 +1195,12 @@ async fn mock_network_sharding() {
                                         channel.send(msg.addressed((*agent).clone())).await;
                                     }
                                     ShardedGossipWire::OpRegions(_) => todo!("must implement"),
+                                    // A region bloom only answers the `OpRegions` of a
+                                    // historical round, and these simulated agents only
+                                    // ever gossip with op blooms.
+                                    ShardedGossipWire::OpRegionBloom(_) => {
+                                        unreachable!("region bloom without sending op regions")
+                                    }
 
                                     ShardedGossipWire::MissingAgents(_) => {}
                                     ShardedGossipWire::Accept(_) => (),
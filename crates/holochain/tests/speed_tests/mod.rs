@@ -130,6 +130,8 @@ async fn speed_test(n: Option<usize>) -> Arc<TempDir> {
             origin_time: Timestamp::HOLOCHAIN_EPOCH,
             integrity_zomes: vec![TestZomes::from(TestWasm::Anchor).integrity.into_inner()],
             coordinator_zomes: vec![TestZomes::from(TestWasm::Anchor).coordinator.into_inner()],
+            runtime_properties: Default::default(),
+            ephemeral_ttl_secs: None,
         },
         vec![TestWasm::Anchor.into()],
     )
@@ -176,7 +178,12 @@ async fn speed_test(n: Option<usize>) -> Arc<TempDir> {
 
     // Setup websocket handle and app interface
     let (mut client, _) = websocket_client(&handle).await.unwrap();
-    let request = AdminRequest::AttachAppInterface { port: None };
+    let request = AdminRequest::AttachAppInterface {
+        port: None,
+        bind_address: None,
+        allowed_origins: None,
+        require_signed_zome_calls: false,
+    };
     let response = client.request(request);
     let response = response.await.unwrap();
     let app_port = match response {
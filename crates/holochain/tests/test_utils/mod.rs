@@ -116,7 +116,12 @@ pub async fn call_zome_fn<S>(
 }
 
 pub async fn attach_app_interface(client: &mut WebsocketSender, port: Option<u16>) -> u16 {
-    let request = AdminRequest::AttachAppInterface { port };
+    let request = AdminRequest::AttachAppInterface {
+        port,
+        bind_address: None,
+        allowed_origins: None,
+        require_signed_zome_calls: false,
+    };
     let response = client.request(request);
     let response = check_timeout(response, 3000).await;
     match response {
@@ -271,13 +276,28 @@ pub async fn check_started(holochain: &mut Child) {
 pub fn create_config(port: u16, environment_path: PathBuf) -> ConductorConfig {
     ConductorConfig {
         admin_interfaces: Some(vec![AdminInterfaceConfig {
-            driver: InterfaceDriver::Websocket { port },
+            driver: InterfaceDriver::websocket(port),
         }]),
         environment_path: environment_path.into(),
         network: None,
+        network_backend: None,
+        dna_network_backends: vec![],
         dpki: None,
         keystore: KeystoreConfig::DangerTestKeystoreLegacyDeprecated,
         db_sync_strategy: DbSyncStrategy::default(),
+        db_encryption: None,
+        dht_pruning: None,
+        dna_tuning_params: vec![],
+        http_gateway: None,
+        health_endpoint: None,
+        open_telemetry: None,
+        audit_log: None,
+        zome_call_limits: None,
+        instance_pool: None,
+        entry_compression: None,
+        chain_head_coordination: None,
+        relay: None,
+        log_filter: None,
     }
 }
 
@@ -14,6 +14,10 @@ use holochain::{
     },
     fixt::*,
 };
+use holochain_conductor_api::signal_subscription::SignalFilterSet;
+use holochain_conductor_api::signal_subscription::SignalSubscription;
+use holochain_conductor_api::AppRequest;
+use holochain_conductor_api::AppResponse;
 use holochain_types::{
     prelude::*,
     test_utils::{fake_agent_pubkey_1, fake_dna_zomes, write_fake_dna_file},
@@ -246,6 +250,7 @@ async fn remote_signals() -> anyhow::Result<()> {
             RemoteSignal {
                 signal: signal.clone(),
                 agents: all_agents,
+                delivery: Default::default(),
             },
         )
         .await;
@@ -256,7 +261,7 @@ async fn remote_signals() -> anyhow::Result<()> {
     for mut rx in rxs {
         let r = rx.try_recv();
         // Each handle should recv a signal
-        assert_matches!(r, Ok(Signal::App(_, a)) if a == signal);
+        assert_matches!(r, Ok(Signal::App { signal: a, .. }) if a == signal);
     }
 
     Ok(())
@@ -320,6 +325,8 @@ async fn emit_signals() {
 
     let (mut app_tx_1, app_rx_1) = websocket_client_by_port(app_port).await.unwrap();
     let (_, app_rx_2) = websocket_client_by_port(app_port).await.unwrap();
+    let mut app_rx_1 = Box::pin(app_rx_1.timeout(Duration::from_secs(1)));
+    let mut app_rx_2 = Box::pin(app_rx_2.timeout(Duration::from_secs(1)));
 
     call_zome_fn(
         &mut app_tx_1,
@@ -330,26 +337,43 @@ async fn emit_signals() {
     )
     .await;
 
-    let (sig1, msg1) = Box::pin(app_rx_1.timeout(Duration::from_secs(1)))
-        .next()
-        .await
-        .unwrap()
-        .unwrap();
+    let (sig1, msg1) = app_rx_1.next().await.unwrap().unwrap();
     assert!(!msg1.is_request());
 
-    let (sig2, msg2) = Box::pin(app_rx_2.timeout(Duration::from_secs(1)))
-        .next()
-        .await
-        .unwrap()
-        .unwrap();
+    let (sig2, msg2) = app_rx_2.next().await.unwrap().unwrap();
     assert!(!msg2.is_request());
 
     assert_eq!(
-        Signal::App(cell_id, AppSignal::new(ExternIO::encode(()).unwrap())),
+        Signal::App {
+            cell_id: cell_id.clone(),
+            zome_name: TestWasm::EmitSignal.into(),
+            signal: AppSignal::new(ExternIO::encode(()).unwrap()),
+        },
         Signal::try_from(sig1.clone()).unwrap(),
     );
     assert_eq!(sig1, sig2);
 
+    // Unsubscribe the first connection from the app's signals.
+    let request = AppRequest::SignalSubscription(SignalSubscription {
+        installed_app_id: "test".to_string(),
+        filters: SignalFilterSet::block_all(),
+    });
+    let response = check_timeout(app_tx_1.request(request), 3000).await;
+    assert_matches!(response, AppResponse::SignalSubscriptionUpdated);
+
+    call_zome_fn(
+        &mut app_tx_1,
+        cell_id.clone(),
+        TestWasm::EmitSignal,
+        "emit".into(),
+        (),
+    )
+    .await;
+
+    let (sig2, _) = app_rx_2.next().await.unwrap().unwrap();
+    assert_eq!(sig1, sig2);
+    assert!(app_rx_1.next().await.unwrap().is_err());
+
     ///////////////////////////////////////////////////////
 }
 
@@ -3,15 +3,21 @@ use std::collections::HashSet;
 use super::*;
 use holochain_p2p::actor::GetActivityOptions;
 
-pub(crate) fn merge_activities(
+#[cfg(test)]
+mod test;
+
+pub(crate) async fn merge_activities(
     agent: AgentPubKey,
     options: &GetActivityOptions,
     results: Vec<AgentActivityResponse<ActionHash>>,
 ) -> CascadeResult<AgentActivityResponse<ActionHash>> {
-    if !options.include_rejected_activity && !options.include_valid_activity {
-        return Ok(merge_status_only(agent, results));
-    }
-    Ok(merge_hashes(agent, options, results))
+    let mut merged = if !options.include_rejected_activity && !options.include_valid_activity {
+        merge_status_only(agent, results)
+    } else {
+        merge_hashes(agent, options, results)
+    };
+    merged.warrants = merge_warrants(merged.warrants).await;
+    Ok(merged)
 }
 
 fn merge_hashes(
@@ -22,17 +28,20 @@ fn merge_hashes(
     let mut valid = HashSet::new();
     let mut rejected = HashSet::new();
     let mut merged_highest_observed = None;
+    let mut merged_warrants = Vec::new();
     for result in results {
         let AgentActivityResponse {
             agent: the_agent,
             highest_observed,
             valid_activity,
             rejected_activity,
+            warrants,
             ..
         } = result;
         if the_agent != agent {
             continue;
         }
+        merged_warrants.extend(warrants);
 
         match (merged_highest_observed.take(), highest_observed) {
             (None, None) => {}
@@ -84,7 +93,26 @@ fn merge_hashes(
         valid_activity,
         rejected_activity,
         highest_observed: merged_highest_observed,
+        warrants: merged_warrants,
+    }
+}
+
+/// Keep one of each of the warrants from the authorities, dropping any which
+/// don't verify, as an authority could send a forged warrant against an
+/// agent it wants to harm.
+async fn merge_warrants(warrants: Vec<Warrant>) -> Vec<Warrant> {
+    let mut merged = Vec::with_capacity(warrants.len());
+    for warrant in warrants {
+        if merged.contains(&warrant) {
+            continue;
+        }
+        if warrant.verify().await {
+            merged.push(warrant);
+        } else {
+            warn!(author = ?warrant.author, "Dropping a warrant which failed verification");
+        }
     }
+    merged
 }
 
 type ValidHashes = Vec<(u32, ActionHash)>;
@@ -147,16 +175,19 @@ fn merge_status_only(
 ) -> AgentActivityResponse<ActionHash> {
     let mut merged_status = None;
     let mut merged_highest_observed = None;
+    let mut merged_warrants = Vec::new();
     for result in results {
         let AgentActivityResponse {
             status,
             agent: the_agent,
             highest_observed,
+            warrants,
             ..
         } = result;
         if the_agent != agent {
             continue;
         }
+        merged_warrants.extend(warrants);
         match (merged_highest_observed.take(), highest_observed) {
             (None, None) => {}
             (Some(h), None) | (None, Some(h)) => {
@@ -234,5 +265,6 @@ fn merge_status_only(
         valid_activity: ChainItems::NotRequested,
         rejected_activity: ChainItems::NotRequested,
         highest_observed: merged_highest_observed,
+        warrants: merged_warrants,
     }
 }
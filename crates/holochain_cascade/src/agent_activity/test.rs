@@ -0,0 +1,61 @@
+use super::*;
+use ::fixt::prelude::*;
+use ghost_actor::dependencies::observability;
+
+/// Two warrants for a new agent forking their chain at its second action.
+async fn fork_warrants() -> (AgentPubKey, Warrant, Warrant) {
+    let keystore = holochain_state::test_utils::test_keystore();
+    let agent = keystore.new_sign_keypair_random().await.unwrap();
+    let mut forks = Vec::new();
+    for mut create in CreateFixturator::new(Unpredictable).take(4) {
+        create.author = agent.clone();
+        create.action_seq = 1;
+        let action = Action::Create(create);
+        let signature = agent.sign(&keystore, &action).await.unwrap();
+        forks.push(SignedAction(action, signature));
+    }
+    let mut forks = forks.into_iter();
+    let mut warrant = || Warrant::chain_fork(forks.next().unwrap(), forks.next().unwrap()).unwrap();
+    (agent, warrant(), warrant())
+}
+
+fn response(agent: &AgentPubKey, warrants: Vec<Warrant>) -> AgentActivityResponse<ActionHash> {
+    AgentActivityResponse {
+        agent: agent.clone(),
+        valid_activity: ChainItems::NotRequested,
+        rejected_activity: ChainItems::NotRequested,
+        status: ChainStatus::Empty,
+        highest_observed: None,
+        warrants,
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn forged_warrants_are_dropped() {
+    observability::test_run().ok();
+    let (agent, warrant, mut forged) = fork_warrants().await;
+    // A warrant with a signature which doesn't match its action.
+    match &mut forged.proof {
+        WarrantProof::ChainFork { first, second } => std::mem::swap(&mut first.1, &mut second.1),
+    }
+
+    for options in [
+        GetActivityOptions::default(),
+        GetActivityOptions {
+            include_valid_activity: false,
+            ..Default::default()
+        },
+    ] {
+        let merged = merge_activities(
+            agent.clone(),
+            &options,
+            vec![
+                response(&agent, vec![warrant.clone(), forged.clone()]),
+                response(&agent, vec![warrant.clone()]),
+            ],
+        )
+        .await
+        .unwrap();
+        assert_eq!(merged.warrants, vec![warrant.clone()]);
+    }
+}
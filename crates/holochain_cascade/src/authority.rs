@@ -10,6 +10,11 @@ use self::{
 use super::error::CascadeResult;
 use holo_hash::ActionHash;
 use holo_hash::AgentPubKey;
+use holo_hash::AnyDhtHash;
+use holo_hash::AnyDhtHashPrimitive;
+use holochain_sqlite::error::DatabaseError;
+use holochain_sqlite::rusqlite::named_params;
+use holochain_sqlite::sql::sql_cell::ENTRY_CRUD_SUMMARY;
 use holochain_state::query::Query;
 use holochain_state::query::Txn;
 use holochain_types::prelude::*;
@@ -28,9 +33,9 @@ pub(crate) mod get_record_query;
 pub async fn handle_get_entry(
     db: DbRead<DbKindDht>,
     hash: EntryHash,
-    _options: holochain_p2p::event::GetOptions,
+    options: holochain_p2p::event::GetOptions,
 ) -> CascadeResult<WireEntryOps> {
-    let query = GetEntryOpsQuery::new(hash);
+    let query = GetEntryOpsQuery::new(hash, options);
     let results = db
         .async_reader(move |txn| query.run(Txn::from(&txn)))
         .await?;
@@ -50,6 +55,50 @@ pub async fn handle_get_record(
     Ok(results)
 }
 
+/// Only the `crud_summary` of an entry is answered, every other part of the
+/// metadata is left empty.
+#[instrument(skip(env))]
+pub async fn handle_get_meta(
+    env: DbRead<DbKindDht>,
+    hash: AnyDhtHash,
+    options: holochain_p2p::event::GetMetaOptions,
+) -> CascadeResult<MetadataSet> {
+    let crud_summary = match hash.into_primitive() {
+        AnyDhtHashPrimitive::Entry(entry_hash) if options.metadata_request.crud_summary => Some(
+            env.async_reader(move |txn| {
+                txn.query_row(
+                    ENTRY_CRUD_SUMMARY,
+                    named_params! {
+                        ":store_entry": DhtOpType::StoreEntry,
+                        ":delete": DhtOpType::RegisterDeletedEntryAction,
+                        ":update": DhtOpType::RegisterUpdatedContent,
+                        ":entry_hash": entry_hash,
+                        ":status": ValidationStatus::Valid,
+                    },
+                    |row| {
+                        Ok(EntryCrudSummary {
+                            live_actions: row.get("live_actions")?,
+                            updates: row.get("updates")?,
+                            deletes: row.get("deletes")?,
+                        })
+                    },
+                )
+                .map_err(DatabaseError::from)
+            })
+            .await?,
+        ),
+        _ => None,
+    };
+    Ok(MetadataSet {
+        actions: Default::default(),
+        invalid_actions: Default::default(),
+        deletes: Default::default(),
+        updates: Default::default(),
+        entry_dht_status: None,
+        crud_summary,
+    })
+}
+
 #[instrument(skip(env))]
 pub async fn handle_get_agent_activity(
     env: DbRead<DbKindDht>,
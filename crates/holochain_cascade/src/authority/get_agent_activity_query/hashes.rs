@@ -32,11 +32,14 @@ pub struct State {
     rejected: Vec<ActionHashed>,
     pending: Vec<ActionHashed>,
     status: Option<ChainStatus>,
+    /// The signature of the last valid action.
+    last_valid_signature: Option<Signature>,
+    warrants: Vec<Warrant>,
 }
 
 #[derive(Debug)]
 pub enum Item {
-    Integrated(ActionHashed),
+    Integrated(ActionHashed, Signature),
     Pending(ActionHashed),
 }
 
@@ -80,9 +83,10 @@ impl Query for GetAgentActivityQuery {
             let hash: ActionHash = row.get("hash")?;
             from_blob::<SignedAction>(row.get("action_blob")?).and_then(|action| {
                 let integrated: Option<Timestamp> = row.get("when_integrated")?;
-                let action = ActionHashed::with_pre_hashed(action.0, hash);
+                let SignedAction(action, signature) = action;
+                let action = ActionHashed::with_pre_hashed(action, hash);
                 let item = if integrated.is_some() {
-                    Item::Integrated(action)
+                    Item::Integrated(action, signature)
                 } else {
                     Item::Pending(action)
                 };
@@ -94,28 +98,40 @@ impl Query for GetAgentActivityQuery {
     fn fold(&self, mut state: Self::State, item: Self::Item) -> StateQueryResult<Self::State> {
         let status = item.validation_status();
         match (status, item.data) {
-            (Some(ValidationStatus::Valid), Item::Integrated(action)) => {
+            (Some(ValidationStatus::Valid), Item::Integrated(action, signature)) => {
                 let seq = action.action_seq();
-                if state.status.is_none() {
-                    let fork = state.valid.last().and_then(|v| {
-                        if seq == v.action_seq() {
-                            Some(v)
-                        } else {
-                            None
-                        }
-                    });
-                    if let Some(fork) = fork {
+                let fork =
+                    state.valid.last().and_then(
+                        |v| {
+                            if seq == v.action_seq() {
+                                Some(v)
+                            } else {
+                                None
+                            }
+                        },
+                    );
+                if let Some(fork) = fork {
+                    if state.status.is_none() {
                         state.status = Some(ChainStatus::Forked(ChainFork {
                             fork_seq: seq,
                             first_action: action.as_hash().clone(),
                             second_action: fork.as_hash().clone(),
                         }));
                     }
+                    // Every fork is warranted, not just the first, so that the
+                    // warrant can't be dodged by forking the chain again.
+                    if let Some(fork_signature) = state.last_valid_signature.clone() {
+                        state.warrants.extend(Warrant::chain_fork(
+                            SignedAction(action.as_content().clone(), signature.clone()),
+                            SignedAction(fork.as_content().clone(), fork_signature),
+                        ));
+                    }
                 }
 
+                state.last_valid_signature = Some(signature);
                 state.valid.push(action);
             }
-            (Some(ValidationStatus::Rejected), Item::Integrated(action)) => {
+            (Some(ValidationStatus::Rejected), Item::Integrated(action, _)) => {
                 if state.status.is_none() {
                     state.status = Some(ChainStatus::Invalid(ChainHead {
                         action_seq: action.action_seq(),
@@ -139,6 +155,7 @@ impl Query for GetAgentActivityQuery {
 
         let valid = state.valid;
         let rejected = state.rejected;
+        let warrants = state.warrants;
         let valid_activity = if self.options.include_valid_activity {
             let valid = self
                 .filter
@@ -168,6 +185,7 @@ impl Query for GetAgentActivityQuery {
             rejected_activity,
             status,
             highest_observed,
+            warrants,
         })
     }
 }
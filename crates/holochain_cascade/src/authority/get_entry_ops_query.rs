@@ -1,6 +1,8 @@
 use std::sync::Arc;
 
 use holo_hash::EntryHash;
+use holochain_p2p::event::GetOptions;
+use holochain_p2p::event::GetRequest;
 use holochain_sqlite::rusqlite::named_params;
 use holochain_sqlite::rusqlite::Row;
 use holochain_state::query::prelude::*;
@@ -18,11 +20,11 @@ use holochain_zome_types::TryFrom;
 use holochain_zome_types::TryInto;
 
 #[derive(Debug, Clone)]
-pub struct GetEntryOpsQuery(EntryHash);
+pub struct GetEntryOpsQuery(EntryHash, GetOptions);
 
 impl GetEntryOpsQuery {
-    pub fn new(hash: EntryHash) -> Self {
-        Self(hash)
+    pub fn new(hash: EntryHash, request: GetOptions) -> Self {
+        Self(hash, request)
     }
 }
 
@@ -132,6 +134,9 @@ impl Query for GetEntryOpsQuery {
     where
         S: Store,
     {
+        if let GetRequest::Action = self.1.request_type {
+            return Ok(state.ops);
+        }
         if let Some((entry_hash, entry_type)) = state.entry_data {
             let entry = stores.get_entry(&entry_hash)?;
             state.ops.entry = entry.map(|entry| EntryData { entry, entry_type });
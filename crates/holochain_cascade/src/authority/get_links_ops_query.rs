@@ -3,6 +3,7 @@ use std::sync::Arc;
 use holo_hash::AnyLinkableHash;
 use holochain_sqlite::rusqlite::named_params;
 use holochain_sqlite::rusqlite::Row;
+use holochain_state::query::link::LinksQuery;
 use holochain_state::query::prelude::*;
 use holochain_state::query::StateQueryError;
 use holochain_types::dht_op::DhtOpType;
@@ -74,14 +75,14 @@ impl Query for GetLinksOpsQuery {
         .to_string();
 
         if let Some(tag) = &self.tag {
-            let tag = Self::tag_to_hex(tag.as_ref());
             common_query = format!(
                 "
                     {}
                     AND
-                    HEX(Action.tag) LIKE '{}%'
+                    {}
                 ",
-                common_query, tag
+                common_query,
+                LinksQuery::tag_prefix_condition(tag.as_ref())
             );
         }
         common_query = format!(
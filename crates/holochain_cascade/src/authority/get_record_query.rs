@@ -52,7 +52,8 @@ impl Query for GetRecordOpsQuery {
         match request_type {
             holochain_p2p::event::GetRequest::All
             | holochain_p2p::event::GetRequest::Content
-            | holochain_p2p::event::GetRequest::Metadata => {
+            | holochain_p2p::event::GetRequest::Metadata
+            | holochain_p2p::event::GetRequest::Action => {
                 format!("{}{}", query, is_integrated)
             }
             holochain_p2p::event::GetRequest::Pending => query.into(),
@@ -114,6 +115,9 @@ impl Query for GetRecordOpsQuery {
     where
         S: Store,
     {
+        if let holochain_p2p::event::GetRequest::Action = self.1.request_type {
+            return Ok(state);
+        }
         let entry_hash = state.action.as_ref().and_then(|wire_op| {
             wire_op
                 .data
@@ -63,6 +63,63 @@ async fn get_entry() {
     assert_eq!(result, expected);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn get_entry_crud_summary() {
+    observability::test_run().ok();
+    let db = test_dht_db();
+
+    let td = EntryTestData::create();
+    let mut options = holochain_p2p::event::GetMetaOptions::default();
+    options.metadata_request.crud_summary = true;
+    let summary = |db: DbRead<DbKindDht>| {
+        let options = options.clone();
+        let hash = td.hash.clone().into();
+        async move {
+            handle_get_meta(db, hash, options)
+                .await
+                .unwrap()
+                .crud_summary
+                .unwrap()
+        }
+    };
+
+    fill_db(&db.to_db(), td.store_entry_op.clone());
+    fill_db(&db.to_db(), td.update_content_op.clone());
+    let result = summary(db.to_db().into()).await;
+    assert_eq!(
+        result,
+        EntryCrudSummary {
+            live_actions: 1,
+            updates: 1,
+            deletes: 0,
+        }
+    );
+    assert!(result.is_updated());
+    assert!(!result.is_deleted());
+
+    fill_db(&db.to_db(), td.delete_entry_action_op.clone());
+    let result = summary(db.to_db().into()).await;
+    assert_eq!(
+        result,
+        EntryCrudSummary {
+            live_actions: 0,
+            updates: 1,
+            deletes: 1,
+        }
+    );
+    assert!(result.is_deleted());
+
+    // Only a summary which was asked for is answered.
+    let result = handle_get_meta(
+        db.to_db().into(),
+        td.hash.clone().into(),
+        Default::default(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(result.crud_summary, None);
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn get_record() {
     observability::test_run().ok();
@@ -153,6 +210,47 @@ async fn retrieve_record() {
     assert_eq!(result, expected);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn action_requests_leave_out_the_entry() {
+    observability::test_run().ok();
+    let db = test_dht_db();
+
+    let record = RecordTestData::create();
+    let entry = EntryTestData::create();
+
+    fill_db(&db.to_db(), record.store_record_op.clone());
+    fill_db(&db.to_db(), entry.store_entry_op.clone());
+
+    let mut options = options();
+    options.request_type = GetRequest::Action;
+
+    let result = handle_get_record(
+        db.to_db().into(),
+        record.create_hash.clone(),
+        options.clone(),
+    )
+    .await
+    .unwrap();
+    let expected = WireRecordOps {
+        action: Some(record.wire_create.clone()),
+        deletes: vec![],
+        updates: vec![],
+        entry: None,
+    };
+    assert_eq!(result, expected);
+
+    let result = handle_get_entry(db.to_db().into(), entry.hash.clone(), options)
+        .await
+        .unwrap();
+    let expected = WireEntryOps {
+        creates: vec![entry.wire_create.clone()],
+        deletes: vec![],
+        updates: vec![],
+        entry: None,
+    };
+    assert_eq!(result, expected);
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn get_links() {
     observability::test_run().ok();
@@ -224,6 +322,7 @@ async fn get_agent_activity() {
         rejected_activity: ChainItems::NotRequested,
         status: ChainStatus::Valid(td.chain_head.clone()),
         highest_observed: Some(td.highest_observed.clone()),
+        warrants: Vec::new(),
     };
     assert_eq!(result, expected);
 
@@ -246,3 +345,54 @@ async fn get_agent_activity() {
 
     assert_eq!(result, expected);
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_agent_activity_warrants_forks() {
+    use ::fixt::prelude::*;
+
+    observability::test_run().ok();
+    let db = test_dht_db();
+    let keystore = holochain_state::test_utils::test_keystore();
+    let agent = fixt!(AgentPubKey, Predictable, 0);
+
+    let mut dna = fixt!(Dna);
+    dna.author = agent.clone();
+    let dna = Action::Dna(dna);
+    let prev_action = ActionHash::with_data_sync(&dna);
+    // Two different actions at the same position of the chain.
+    let forks = CreateFixturator::new(Unpredictable)
+        .take(2)
+        .map(|mut create| {
+            create.author = agent.clone();
+            create.action_seq = 1;
+            create.prev_action = prev_action.clone();
+            Action::Create(create)
+        });
+    for action in std::iter::once(dna).chain(forks) {
+        let signature = agent.sign(&keystore, &action).await.unwrap();
+        let op = DhtOpHashed::from_content_sync(DhtOp::RegisterAgentActivity(signature, action));
+        fill_db(&db.to_db(), op);
+    }
+
+    let options = actor::GetActivityOptions::default();
+    let result = handle_get_agent_activity(
+        db.to_db().into(),
+        agent.clone(),
+        ChainQueryFilter::new(),
+        (&options).into(),
+    )
+    .await
+    .unwrap();
+    assert!(matches!(result.status, ChainStatus::Forked(_)));
+    assert_eq!(result.warrants.len(), 1);
+    let warrant = result.warrants[0].clone();
+    assert_eq!(warrant.author, agent);
+    assert!(warrant.verify().await);
+
+    // A warrant with a signature which doesn't match its action is refused.
+    let mut forged = warrant;
+    match &mut forged.proof {
+        WarrantProof::ChainFork { first, second } => std::mem::swap(&mut first.1, &mut second.1),
+    }
+    assert!(!forged.verify().await);
+}
@@ -30,6 +30,20 @@ pub enum CascadeError {
     #[error("Got an invalid response from an authority for the request hash: {0:?}")]
     InvalidResponse(AnyDhtHash),
 
+    #[error("The blob chunk {0:?} is not a chunk entry")]
+    NotAChunk(EntryHash),
+
+    #[error("The blob's chunks add up to {actual} bytes but its manifest says {expected}")]
+    BlobSizeMismatch { expected: u64, actual: u64 },
+
+    #[error("Only {agreeing} of {responses} authorities agreed on {hash:?}, but a quorum of {quorum} is required")]
+    QuorumNotReached {
+        hash: AnyDhtHash,
+        quorum: u8,
+        responses: usize,
+        agreeing: usize,
+    },
+
     #[error(transparent)]
     JoinError(#[from] JoinError),
 
@@ -6,6 +6,7 @@
 
 use std::sync::Arc;
 
+use error::CascadeError;
 use error::CascadeResult;
 use holo_hash::hash_type::AnyDht;
 use holo_hash::ActionHash;
@@ -14,6 +15,7 @@ use holo_hash::AnyDhtHash;
 use holo_hash::EntryHash;
 use holochain_p2p::actor::GetActivityOptions;
 use holochain_p2p::actor::GetLinksOptions;
+use holochain_p2p::actor::GetMetaOptions;
 use holochain_p2p::actor::GetOptions as NetworkGetOptions;
 use holochain_p2p::HolochainP2pDna;
 use holochain_p2p::HolochainP2pDnaT;
@@ -24,6 +26,7 @@ use holochain_state::mutations::set_validation_status;
 use holochain_state::prelude::*;
 use holochain_state::query::entry_details::GetEntryDetailsQuery;
 use holochain_state::query::link::GetLinksQuery;
+use holochain_state::query::link_aggregate::GetLinkAggregateQuery;
 use holochain_state::query::link_details::GetLinkDetailsQuery;
 use holochain_state::query::live_entry::GetLiveEntryQuery;
 use holochain_state::query::live_record::GetLiveRecordQuery;
@@ -67,6 +70,51 @@ macro_rules! ok_or_return {
     };
 }
 
+/// The retrievals validation makes through a [`Cascade`].
+///
+/// Validation which is generic over this trait can be unit tested with a
+/// `MockCascadeT` programmed with the data each retrieval finds, instead of
+/// databases and a network.
+#[cfg_attr(feature = "test_utils", mockall::automock)]
+#[async_trait::async_trait]
+pub trait CascadeT: Send {
+    /// See [`Cascade::retrieve`].
+    async fn retrieve(
+        &mut self,
+        hash: AnyDhtHash,
+        options: NetworkGetOptions,
+    ) -> CascadeResult<Option<Record>>;
+
+    /// See [`Cascade::retrieve_action`].
+    async fn retrieve_action(
+        &mut self,
+        hash: ActionHash,
+        options: NetworkGetOptions,
+    ) -> CascadeResult<Option<SignedActionHashed>>;
+}
+
+#[async_trait::async_trait]
+impl<Network> CascadeT for Cascade<Network>
+where
+    Network: HolochainP2pDnaT + Clone + 'static + Send + Sync,
+{
+    async fn retrieve(
+        &mut self,
+        hash: AnyDhtHash,
+        options: NetworkGetOptions,
+    ) -> CascadeResult<Option<Record>> {
+        Cascade::retrieve(self, hash, options).await
+    }
+
+    async fn retrieve_action(
+        &mut self,
+        hash: ActionHash,
+        options: NetworkGetOptions,
+    ) -> CascadeResult<Option<SignedActionHashed>> {
+        Cascade::retrieve_action(self, hash, options).await
+    }
+}
+
 #[derive(Clone)]
 pub struct Cascade<Network = HolochainP2pDna> {
     authored: Option<DbRead<DbKindAuthored>>,
@@ -343,14 +391,45 @@ where
     ) -> CascadeResult<()> {
         let network = ok_or_return!(self.network.as_mut());
         let results = network
-            .get(hash, options.clone())
+            .get(hash.clone(), options.clone())
             .instrument(debug_span!("fetch_record::network_get"))
             .await?;
 
+        let results = match options.quorum {
+            Some(quorum) => vec![Self::quorum_response(hash, quorum, results)?],
+            None => results,
+        };
+
         self.merge_ops_into_cache(results).await?;
         Ok(())
     }
 
+    /// Find the response the most authorities agree on,
+    /// failing if fewer than the quorum sent it.
+    fn quorum_response(
+        hash: AnyDhtHash,
+        quorum: u8,
+        results: Vec<WireOps>,
+    ) -> CascadeResult<WireOps> {
+        let responses = results.len();
+        let mut counts: Vec<(WireOps, usize)> = Vec::new();
+        for response in results {
+            match counts.iter_mut().find(|(r, _)| *r == response) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((response, 1)),
+            }
+        }
+        match counts.into_iter().max_by_key(|(_, count)| *count) {
+            Some((response, agreeing)) if agreeing >= quorum as usize => Ok(response),
+            best => Err(CascadeError::QuorumNotReached {
+                hash,
+                quorum,
+                responses,
+                agreeing: best.map(|(_, count)| count).unwrap_or(0),
+            }),
+        }
+    }
+
     #[instrument(skip(self, options))]
     async fn fetch_links(
         &mut self,
@@ -527,6 +606,39 @@ where
         Ok(result.map(EntryHashed::from_content_sync))
     }
 
+    /// Retrieve the chunks of a blob from either locally or from authorities,
+    /// and join them back together.
+    /// Returns `None` if any chunk can't be found.
+    pub async fn retrieve_blob(
+        &mut self,
+        manifest: BlobManifest,
+        options: NetworkGetOptions,
+    ) -> CascadeResult<Option<Vec<u8>>> {
+        let BlobManifest { size, chunks } = manifest;
+        let mut blob = Vec::new();
+        for hash in chunks {
+            let chunk = match self.retrieve_entry(hash.clone(), options.clone()).await? {
+                Some(entry) => match entry.into_content() {
+                    Entry::Chunk(chunk) => chunk,
+                    _ => return Err(CascadeError::NotAChunk(hash)),
+                },
+                None => return Ok(None),
+            };
+            blob.extend(chunk.into_inner());
+            // Stop early rather than fetching the rest of an oversized blob.
+            if blob.len() as u64 > size {
+                break;
+            }
+        }
+        if blob.len() as u64 != size {
+            return Err(CascadeError::BlobSizeMismatch {
+                expected: size,
+                actual: blob.len() as u64,
+            });
+        }
+        Ok(Some(blob))
+    }
+
     /// Retrieve [`SignedActionHashed`] from either locally or from an authority.
     /// Data might not have been validated yet by the authority.
     pub async fn retrieve_action(
@@ -591,6 +703,127 @@ where
         Ok(result)
     }
 
+    /// Ask authorities for the actions at a hash without their entries.
+    /// The responses aren't added to the cache, where a record without its
+    /// entry would look like one whose entry isn't stored.
+    async fn fetch_actions(
+        &mut self,
+        hash: AnyDhtHash,
+        mut options: NetworkGetOptions,
+    ) -> CascadeResult<Vec<WireOps>> {
+        let network = ok_or_return!(self.network.as_mut(), Vec::new());
+        options.request_type = holochain_p2p::event::GetRequest::Action;
+        Ok(network.get(hash, options).await?)
+    }
+
+    /// Get the [`SignedActionHashed`] for this [`ActionHash`] without its
+    /// entry, from either locally or from an authority, which only sends
+    /// the action.
+    /// Actions the authorities have rejected are ignored.
+    pub async fn get_action_only(
+        &mut self,
+        hash: ActionHash,
+        options: NetworkGetOptions,
+    ) -> CascadeResult<Option<SignedActionHashed>> {
+        let result = self
+            .find_map({
+                let hash = hash.clone();
+                move |store| Ok(store.get_action(&hash)?)
+            })
+            .await?;
+        if result.is_some() {
+            return Ok(result);
+        }
+        let authoring = self.am_i_authoring(&hash.clone().into())?;
+        if authoring || self.am_i_an_authority(hash.clone().into()).await? {
+            return Ok(None);
+        }
+        let responses = self.fetch_actions(hash.clone().into(), options).await?;
+        Ok(responses.into_iter().find_map(|response| match response {
+            WireOps::Record(WireRecordOps {
+                action: Some(action),
+                ..
+            }) if action.validation_status() != Some(ValidationStatus::Rejected) => {
+                let action = SignedActionHashed::from_content_sync(action.data);
+                // Don't trust an authority which sent some other action.
+                (*action.as_hash() == hash).then(|| action)
+            }
+            _ => None,
+        }))
+    }
+
+    /// Whether the data at this hash exists, as far as either this agent's
+    /// stores or the authorities know, which only send its actions.
+    /// Data the authorities have rejected doesn't count.
+    pub async fn exists(
+        &mut self,
+        hash: AnyDhtHash,
+        options: NetworkGetOptions,
+    ) -> CascadeResult<bool> {
+        let found = self
+            .find_map({
+                let hash = hash.clone();
+                move |store| Ok(store.contains_hash(&hash)?.then(|| ()))
+            })
+            .await?;
+        if found.is_some() {
+            return Ok(true);
+        }
+        let authoring = self.am_i_authoring(&hash)?;
+        if authoring || self.am_i_an_authority(hash.clone()).await? {
+            return Ok(false);
+        }
+        let not_rejected =
+            |status: Option<ValidationStatus>| status != Some(ValidationStatus::Rejected);
+        let responses = self.fetch_actions(hash, options).await?;
+        Ok(responses.iter().any(|response| match response {
+            WireOps::Record(ops) => ops
+                .action
+                .as_ref()
+                .map_or(false, |action| not_rejected(action.validation_status())),
+            WireOps::Entry(ops) => ops
+                .creates
+                .iter()
+                .any(|create| not_rejected(create.validation_status())),
+        }))
+    }
+
+    /// Get how many live actions, updates and deletes an entry has, e.g. to
+    /// show whether it has been edited or deleted, without getting all of
+    /// its actions.
+    ///
+    /// Authorities may have seen different actions, so the summary of the
+    /// authority which has seen the most deletes and updates is returned.
+    /// If this agent is an authority for the entry it answers from its own
+    /// DHT database.
+    pub async fn get_entry_crud_summary(
+        &mut self,
+        hash: EntryHash,
+        mut options: GetMetaOptions,
+    ) -> CascadeResult<Option<EntryCrudSummary>> {
+        options.metadata_request = MetadataRequest {
+            all_valid_actions: false,
+            all_invalid_actions: false,
+            all_deletes: false,
+            all_updates: false,
+            follow_redirects: false,
+            entry_dht_status: false,
+            crud_summary: true,
+        };
+        if let Some(dht) = self.dht.clone() {
+            if self.am_i_an_authority(hash.clone().into()).await? {
+                let meta = authority::handle_get_meta(dht, hash.into(), (&options).into()).await?;
+                return Ok(meta.crud_summary);
+            }
+        }
+        let network = ok_or_return!(self.network.as_mut(), None);
+        let responses = network.get_meta(hash.into(), options).await?;
+        Ok(responses
+            .into_iter()
+            .filter_map(|meta| meta.crud_summary)
+            .max_by_key(|summary| (summary.deletes, summary.updates, summary.live_actions)))
+    }
+
     #[instrument(skip(self, options))]
     pub async fn get_entry_details(
         &mut self,
@@ -819,6 +1052,28 @@ where
         Ok(results)
     }
 
+    #[instrument(skip(self, options))]
+    /// Aggregates the links which [`Self::dht_get_links`] would return,
+    /// without handing every link back to the caller.
+    ///
+    /// Authorities don't aggregate links themselves, so the links are
+    /// fetched in full like [`Self::dht_get_links`] does and aggregated
+    /// from the local databases.
+    pub async fn dht_get_link_aggregate(
+        &mut self,
+        key: WireLinkKey,
+        aggregation: LinkAggregation,
+        options: GetLinksOptions,
+    ) -> CascadeResult<LinkAggregate> {
+        let authority = self.am_i_an_authority(key.base.clone().into()).await?;
+        if !authority {
+            self.fetch_links(key.clone(), options).await?;
+        }
+        let query = GetLinkAggregateQuery::new(key.base, key.type_query, key.tag, aggregation);
+        let results = self.cascading(query).await?;
+        Ok(results)
+    }
+
     #[instrument(skip(self, key, options))]
     /// Return all CreateLink actions
     /// and DeleteLink actions ordered by time.
@@ -919,7 +1174,7 @@ where
                 .fetch_agent_activity(agent.clone(), query.clone(), options.clone())
                 .await?;
             let merged_response: AgentActivityResponse<ActionHash> =
-                agent_activity::merge_activities(agent.clone(), &options, results)?;
+                agent_activity::merge_activities(agent.clone(), &options, results).await?;
             merged_response
         } else {
             match self.dht.clone() {
@@ -932,11 +1187,10 @@ where
                     )
                     .await?
                 }
-                None => agent_activity::merge_activities(
-                    agent.clone(),
-                    &options,
-                    Vec::with_capacity(0),
-                )?,
+                None => {
+                    agent_activity::merge_activities(agent.clone(), &options, Vec::with_capacity(0))
+                        .await?
+                }
             }
         };
 
@@ -962,6 +1216,7 @@ where
             rejected_activity,
             status,
             highest_observed,
+            warrants,
         } = merged_response;
         let valid_activity = match valid_activity {
             ChainItems::Hashes(hashes) => {
@@ -1016,6 +1271,7 @@ where
             rejected_activity,
             status,
             highest_observed,
+            warrants,
         };
         Ok(r)
     }
@@ -139,10 +139,17 @@ impl HolochainP2pDnaT for PassThroughNetwork {
     }
     async fn get_meta(
         &self,
-        _dht_hash: holo_hash::AnyDhtHash,
-        _options: actor::GetMetaOptions,
+        dht_hash: holo_hash::AnyDhtHash,
+        options: actor::GetMetaOptions,
     ) -> actor::HolochainP2pResult<Vec<MetadataSet>> {
-        todo!()
+        let mut out = Vec::new();
+        for env in &self.envs {
+            let r = authority::handle_get_meta(env.clone(), dht_hash.clone(), (&options).into())
+                .await
+                .map_err(|e| HolochainP2pError::Other(e.into()))?;
+            out.push(r);
+        }
+        Ok(out)
     }
     async fn get_links(
         &self,
@@ -205,6 +212,14 @@ impl HolochainP2pDnaT for PassThroughNetwork {
         Ok(self.authority)
     }
 
+    async fn authorities_for_basis(
+        &self,
+        _basis: holo_hash::AnyDhtHash,
+        _limit: u32,
+    ) -> actor::HolochainP2pResult<Vec<AgentPubKey>> {
+        Ok(Vec::new())
+    }
+
     fn dna_hash(&self) -> holo_hash::DnaHash {
         todo!()
     }
@@ -217,6 +232,7 @@ impl HolochainP2pDnaT for PassThroughNetwork {
         _fn_name: holochain_zome_types::FunctionName,
         _cap: Option<holochain_zome_types::CapSecret>,
         _payload: holochain_zome_types::ExternIO,
+        _drop_at_limit: bool,
     ) -> actor::HolochainP2pResult<()> {
         todo!()
     }
@@ -395,6 +411,18 @@ impl HolochainP2pDnaT for MockNetwork {
         self.0.lock().await.authority_for_hash(dht_hash).await
     }
 
+    async fn authorities_for_basis(
+        &self,
+        basis: holo_hash::AnyDhtHash,
+        limit: u32,
+    ) -> actor::HolochainP2pResult<Vec<AgentPubKey>> {
+        self.0
+            .lock()
+            .await
+            .authorities_for_basis(basis, limit)
+            .await
+    }
+
     fn dna_hash(&self) -> holo_hash::DnaHash {
         todo!()
     }
@@ -407,6 +435,7 @@ impl HolochainP2pDnaT for MockNetwork {
         _fn_name: holochain_zome_types::FunctionName,
         _cap: Option<holochain_zome_types::CapSecret>,
         _payload: holochain_zome_types::ExternIO,
+        _drop_at_limit: bool,
     ) -> actor::HolochainP2pResult<()> {
         todo!()
     }
@@ -481,9 +510,9 @@ pub fn wire_to_shh<T: TryInto<SignedAction> + Clone>(op: &T) -> SignedActionHash
 pub fn handle_get_entry_txn(
     txn: &Transaction<'_>,
     hash: EntryHash,
-    _options: holochain_p2p::event::GetOptions,
+    options: holochain_p2p::event::GetOptions,
 ) -> WireEntryOps {
-    let query = GetEntryOpsQuery::new(hash);
+    let query = GetEntryOpsQuery::new(hash, options);
     query.run(Txn::from(txn)).unwrap()
 }
 
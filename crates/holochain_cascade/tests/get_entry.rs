@@ -1,5 +1,6 @@
 use ghost_actor::dependencies::observability;
 use holo_hash::HasHash;
+use holochain_cascade::error::CascadeError;
 use holochain_cascade::test_utils::*;
 use holochain_cascade::Cascade;
 use holochain_p2p::HolochainP2pDnaT;
@@ -9,6 +10,11 @@ use holochain_state::prelude::test_authored_db;
 use holochain_state::prelude::test_cache_db;
 use holochain_state::prelude::test_dht_db;
 use holochain_state::scratch::Scratch;
+use holochain_zome_types::entry::BlobManifest;
+use holochain_zome_types::entry::Chunk;
+use holochain_zome_types::entry::Entry;
+use holochain_zome_types::entry::EntryHashed;
+use holochain_zome_types::entry::CHUNK_SIZE_LIMIT;
 use holochain_zome_types::ChainTopOrdering;
 use holochain_zome_types::Details;
 use holochain_zome_types::EntryDetails;
@@ -284,6 +290,53 @@ async fn entry_authoring() {
     assert_can_get(&td_entry, &td_record, &mut cascade, GetOptions::latest()).await;
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn blob_in_scratch() {
+    observability::test_run().ok();
+
+    // Environments
+    let cache = test_cache_db();
+    let mut scratch = Scratch::new();
+
+    // Data
+    let blob: Vec<u8> = (0..CHUNK_SIZE_LIMIT + 10).map(|i| i as u8).collect();
+    let chunks: Vec<_> = Chunk::split(&blob)
+        .map(|chunk| EntryHashed::from_content_sync(Entry::Chunk(chunk)))
+        .collect();
+    let manifest = BlobManifest {
+        size: blob.len() as u64,
+        chunks: chunks.iter().map(|chunk| chunk.as_hash().clone()).collect(),
+    };
+    for chunk in chunks {
+        scratch.add_entry(chunk, ChainTopOrdering::default());
+    }
+
+    // Network
+    // - Not expecting any calls to the network.
+    let mut mock = MockHolochainP2pDnaT::new();
+    mock.expect_authority_for_hash().returning(|_| Ok(false));
+    let mock = MockNetwork::new(mock);
+
+    // Cascade
+    let mut cascade = Cascade::empty()
+        .with_scratch(scratch.into_sync())
+        .with_network(mock, cache.to_db());
+
+    let r = cascade
+        .retrieve_blob(manifest.clone(), Default::default())
+        .await
+        .unwrap();
+    assert_eq!(r, Some(blob));
+
+    // - A manifest whose size doesn't match its chunks is an error.
+    let manifest = BlobManifest {
+        size: manifest.size - 1,
+        ..manifest
+    };
+    let r = cascade.retrieve_blob(manifest, Default::default()).await;
+    assert!(matches!(r, Err(CascadeError::BlobSizeMismatch { .. })));
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn entry_authority() {
     observability::test_run().ok();
@@ -508,3 +561,57 @@ async fn test_pending_data_isnt_returned() {
 
     assert_can_retrieve(&td_entry, &mut cascade, GetOptions::latest()).await;
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn entry_quorum() {
+    observability::test_run().ok();
+
+    // Environments
+    let cache = test_cache_db();
+    let authority_a = test_dht_db();
+    let authority_b = test_dht_db();
+
+    // Data
+    let td_entry = EntryTestData::create();
+    let td_record = RecordTestData::create();
+    fill_db(&authority_a.to_db(), td_entry.store_entry_op.clone());
+    fill_db(&authority_a.to_db(), td_record.any_store_record_op.clone());
+
+    // Network
+    let network = PassThroughNetwork::authority_for_nothing(vec![
+        authority_a.to_db().clone().into(),
+        authority_b.to_db().clone().into(),
+    ]);
+
+    // Cascade
+    let mut cascade = Cascade::empty().with_network(network.clone(), cache.to_db());
+
+    // - The authorities diverge so the quorum isn't reached.
+    let r = cascade
+        .dht_get(
+            td_entry.hash.clone().into(),
+            GetOptions::latest().with_quorum(2),
+        )
+        .await;
+    assert!(matches!(
+        r,
+        Err(CascadeError::QuorumNotReached {
+            quorum: 2,
+            responses: 2,
+            agreeing: 1,
+            ..
+        })
+    ));
+
+    // - Once both authorities hold the data they agree.
+    fill_db(&authority_b.to_db(), td_entry.store_entry_op.clone());
+    fill_db(&authority_b.to_db(), td_record.any_store_record_op.clone());
+
+    assert_can_get(
+        &td_entry,
+        &td_record,
+        &mut cascade,
+        GetOptions::latest().with_quorum(2),
+    )
+    .await;
+}
@@ -1,9 +1,14 @@
 use holo_hash::*;
+use holochain_state::integrity::DbIntegrityReport;
 use holochain_types::prelude::*;
 use holochain_zome_types::cell::CellId;
 use kitsune_p2p::agent_store::AgentInfoSigned;
+use kitsune_p2p::dependencies::kitsune_p2p_types::bootstrap::BootstrapServerHealth;
 
-use crate::{FullStateDump, InstalledAppInfo};
+use crate::audit_log::AuditEntry;
+use crate::{
+    AppInterfaceInfo, CellOpCounts, FullStateDump, InstalledAppInfo, InterfaceStats, TaskInfo,
+};
 
 /// Represents the available conductor functions to call over an admin interface.
 ///
@@ -16,8 +21,11 @@ use crate::{FullStateDump, InstalledAppInfo};
 // Expects a serialized object with any contents of the enum on a key `data`
 // and the enum variant on a key `type`, e.g.
 // `{ type: 'enable_app', data: { installed_app_id: 'test_app' } }`
-#[derive(Debug, serde::Serialize, serde::Deserialize, SerializedBytes)]
+#[derive(
+    Debug, serde::Serialize, serde::Deserialize, SerializedBytes, strum_macros::IntoStaticStr,
+)]
 #[serde(rename_all = "snake_case", tag = "type", content = "data")]
+#[strum(serialize_all = "snake_case")]
 pub enum AdminRequest {
     /// Set up and register one or more new admin interfaces
     /// as specified by a list of configurations.
@@ -36,6 +44,25 @@ pub enum AdminRequest {
     /// [`AdminResponse::DnaRegistered`]
     RegisterDna(Box<RegisterDnaPayload>),
 
+    /// Fetch a DNA which this conductor doesn't have from its peers, by the
+    /// hash of the DNA, e.g. to install an app that an invite names it in.
+    ///
+    /// Peers only serve a DNA on its own network, so the agent joins the
+    /// network of the DNA, without holding any of its data, to ask them. The
+    /// first DNA received which matches the hash is registered, as with
+    /// [`AdminRequest::RegisterDna`]. A DNA which is already registered isn't
+    /// fetched again.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::DnaRegistered`]
+    FetchDna {
+        /// The hash of the DNA to fetch.
+        dna_hash: DnaHash,
+        /// The agent which joins the DNA's network to fetch it.
+        agent_key: AgentPubKey,
+    },
+
     /// Update coordinator zomes for an already installed DNA.
     ///
     /// Replaces any installed coordinator zomes with the same zome name.
@@ -47,12 +74,28 @@ pub enum AdminRequest {
     /// [`AdminResponse::CoordinatorsUpdated`]
     UpdateCoordinators(Box<UpdateCoordinatorsPayload>),
 
+    /// Update the runtime properties of an already installed DNA.
+    ///
+    /// These properties don't affect the DNA hash, so the DNA's cells stay
+    /// on the same network. Zome calls which start after the update read
+    /// the new properties.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::RuntimePropertiesUpdated`]
+    UpdateRuntimeProperties(Box<UpdateRuntimePropertiesPayload>),
+
     /// Clone a DNA (in the biological sense), thus creating a new `Cell`.
     ///
     /// Using the provided, already-registered DNA, create a new DNA with a unique
     /// ID and the specified properties, create a new cell from this cloned DNA,
     /// and add the cell to the specified app.
     ///
+    /// If the DNA is instead a clone which another agent created, e.g. named
+    /// in an invite, the new cell joins that clone. A clone which the
+    /// conductor doesn't have is fetched from its peers, as with
+    /// [`AdminRequest::FetchDna`].
+    ///
     /// # Returns
     ///
     /// [`AdminResponse::CloneCellCreated`]
@@ -71,7 +114,10 @@ pub enum AdminRequest {
     ///
     /// # Returns
     ///
-    /// [`AdminResponse::AppInstalled`]
+    /// [`AdminResponse::AppInstalled`], or if genesis fails for any cell,
+    /// e.g. because its DNA's `genesis_self_check` rejects the membrane proof,
+    /// an [`AdminResponse::Error`] of [`ExternalApiWireError::GenesisFailed`]
+    /// and the app is not installed.
     ///
     /// [`EnableApp`]: AdminRequest::EnableApp
     InstallApp(Box<InstallAppPayload>),
@@ -89,24 +135,56 @@ pub enum AdminRequest {
     ///
     /// # Returns
     ///
-    /// [`AdminResponse::AppInstalled`]
+    /// [`AdminResponse::AppInstalled`], or if genesis fails for any cell,
+    /// e.g. because its DNA's `genesis_self_check` rejects the membrane proof,
+    /// an [`AdminResponse::Error`] of [`ExternalApiWireError::GenesisFailed`]
+    /// and the app is not installed.
     ///
     /// [`EnableApp`]: AdminRequest::EnableApp
     InstallAppBundle(Box<InstallAppBundlePayload>),
 
+    /// Install an app bundle once for each of several agents, as when one
+    /// conductor hosts the same app for many users.
+    ///
+    /// Each agent gets their own installed app, with an ID made from the
+    /// `installed_app_id` and the agent key by [`agent_app_id`], and their own
+    /// cells. An HTTP gateway token with an `agent_key` calls the app of that
+    /// agent when it names the `installed_app_id`.
+    /// See [`InstallAppBundleForAgentsPayload`] for full details on the configuration.
+    ///
+    /// If the app can't be installed for one of the agents, it is
+    /// uninstalled again for the agents it was already installed for.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::AppBundleInstalledForAgents`]
+    InstallAppBundleForAgents(Box<InstallAppBundleForAgentsPayload>),
+
     /// Uninstalls the app specified by argument `installed_app_id` from the conductor.
     ///
     /// The app will be removed from the list of installed apps, and any cells
-    /// which were referenced only by this app will be disabled and removed, clearing up
-    /// any persisted data.
+    /// which were referenced only by this app will be disabled and removed.
     /// Cells which are still referenced by other installed apps will not be removed.
     ///
+    /// By default the source chains of the removed cells are kept on disk,
+    /// orphaned, and are picked up again if an app with the same DNAs is
+    /// installed with the same agent key. They are only deleted if asked to.
+    ///
     /// # Returns
     ///
     /// [`AdminResponse::AppUninstalled`]
     UninstallApp {
         /// The app ID to uninstall
         installed_app_id: InstalledAppId,
+        /// What to do with the data of the cells which are removed.
+        #[serde(default)]
+        data: UninstalledAppData,
+        /// A file on the conductor's machine to export the source chains
+        /// of all the app's cells to, as JSON, before anything is removed.
+        /// Must not already exist. If the export fails, the app is not
+        /// uninstalled.
+        #[serde(default)]
+        export_path: Option<std::path::PathBuf>,
     },
 
     /// List the hashes of all installed DNAs.
@@ -143,6 +221,7 @@ pub enum AdminRequest {
     /// List the apps and their information that are installed in the conductor.
     ///
     /// If `status_filter` is `Some(_)`, it will return only the apps with the specified status.
+    /// If `agent_pub_key` is `Some(_)`, it will return only the apps installed for that agent.
     ///
     /// # Returns
     ///
@@ -150,6 +229,9 @@ pub enum AdminRequest {
     ListApps {
         /// An optional status to filter the list of apps by
         status_filter: Option<AppStatusFilter>,
+        /// An optional agent to list only the apps installed for
+        #[serde(default)]
+        agent_pub_key: Option<AgentPubKey>,
     },
 
     /// Changes the specified app from a disabled to an enabled state in the conductor.
@@ -190,6 +272,23 @@ pub enum AdminRequest {
         installed_app_id: InstalledAppId,
     },
 
+    /// Replace the quota of an installed app, which limits the disk space,
+    /// clone cells and zome call rate it may use.
+    ///
+    /// Requests which would exceed the quota fail with an error of
+    /// [`ExternalApiWireError::QuotaExceeded`], and the first of them is
+    /// also signalled as a [`SystemSignal::QuotaExceeded`].
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::AppQuotaSet`]
+    SetAppQuota {
+        /// The app ID to set the quota of
+        installed_app_id: InstalledAppId,
+        /// The new quota, which replaces the old one entirely
+        quota: AppQuota,
+    },
+
     /// Open up a new websocket for processing [`AppRequest`]s.
     ///
     /// Any active app will be callable via the attached app interface.
@@ -204,10 +303,48 @@ pub enum AdminRequest {
     /// a free port is chosen by the conductor.
     /// The response will contain the port chosen by the conductor if `None` was passed.
     ///
+    /// The interface listens on localhost unless a `bind_address` is passed,
+    /// and accepts connections from any origin unless `allowed_origins` is passed.
+    /// It accepts unsigned zome calls unless `require_signed_zome_calls` is set.
+    /// See [`InterfaceDriver::Websocket`] for details.
+    ///
     /// [`AppRequest`]: super::AppRequest
+    /// [`InterfaceDriver::Websocket`]: crate::config::InterfaceDriver::Websocket
     AttachAppInterface {
         /// Optional port number
         port: Option<u16>,
+        /// Optional address of the network interface to listen on
+        #[serde(default)]
+        bind_address: Option<std::net::IpAddr>,
+        /// Optional origins to accept websocket connections from
+        #[serde(default)]
+        allowed_origins: Option<Vec<String>>,
+        /// Whether to refuse zome calls which aren't signed
+        #[serde(default)]
+        require_signed_zome_calls: bool,
+    },
+
+    /// Open up a new websocket for processing [`AppRequest`]s on a local
+    /// socket rather than a port, so only clients on this machine can connect.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::AppLocalSocketAttached`]
+    ///
+    /// # Arguments
+    ///
+    /// The `path` of the Unix domain socket, or the name of the named pipe on
+    /// Windows, and optionally the Unix file permissions `mode` of the socket.
+    /// See [`InterfaceDriver::LocalSocket`] for details.
+    ///
+    /// [`AppRequest`]: super::AppRequest
+    /// [`InterfaceDriver::LocalSocket`]: crate::config::InterfaceDriver::LocalSocket
+    AttachAppLocalSocket {
+        /// The path of the socket
+        path: std::path::PathBuf,
+        /// Optional file permissions of the socket
+        #[serde(default)]
+        mode: Option<u32>,
     },
 
     /// List all the app interfaces currently attached with [`AttachAppInterface`].
@@ -215,12 +352,36 @@ pub enum AdminRequest {
     /// # Returns
     ///
     /// [`AdminResponse::AppInterfacesListed`], a list of websocket ports that can
-    /// process [`AppRequest`]s.
+    /// process [`AppRequest`]s. App interfaces on local sockets aren't listed.
     ///
     /// [`AttachAppInterface`]: AdminRequest::AttachAppInterface
     /// [`AppRequest`]: super::AppRequest
     ListAppInterfaces,
 
+    /// List the settings of all the app interfaces, including those on local
+    /// sockets: where each listens, which origins it accepts connections
+    /// from, and its signal subscriptions.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::AppInterfaceSettingsListed`]
+    ListAppInterfaceSettings,
+
+    /// Close the app interface on a port, along with its connections.
+    /// It isn't opened again when the conductor restarts.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::AppInterfaceDetached`]
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no app interface is listening on the port.
+    DetachAppInterface {
+        /// The port of the interface
+        port: u16,
+    },
+
     /// Dump the state of the cell specified by argument `cell_id`,
     /// including its chain, as a string containing JSON.
     ///
@@ -265,6 +426,51 @@ pub enum AdminRequest {
         dna_hash: Option<DnaHash>,
     },
 
+    /// Dump a summary of this conductor's network stats: open connections,
+    /// and for each DNA the gossip round outcomes, bytes sent and received,
+    /// and the current arcs of local agents.
+    ///
+    /// Use [`AdminRequest::DumpNetworkMetrics`] for per peer details.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::NetworkStatsDumped`]
+    DumpNetworkStats,
+
+    /// Dump the stats of the relay this conductor runs for other peers:
+    /// its address, the peers connected to it, and the messages and bytes
+    /// it has forwarded for each of them and refused to forward.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::RelayStatsDumped`]
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the conductor has no `relay` configured.
+    DumpRelayStats,
+
+    /// Dump the number of clients connected to each of the conductor's
+    /// admin and app interfaces.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::InterfaceStatsDumped`]
+    DumpInterfaceStats,
+
+    /// Dump the long running tasks of the conductor, such as the queue
+    /// consumers which run each cell's workflows, and whether they have
+    /// stalled.
+    ///
+    /// A task which has been on one run of its work for ten minutes is
+    /// stalled. It's aborted, and the apps using its cell are paused until
+    /// they are started again.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::TasksDumped`]
+    DumpTasks,
+
     /// Add a list of agents to this conductor's peer store.
     ///
     /// This is a way of shortcutting peer discovery and is useful for testing.
@@ -344,6 +550,276 @@ pub enum AdminRequest {
         /// The records to be inserted into the source chain.
         records: Vec<Record>,
     },
+
+    /// Re-read the conductor config file and apply any changed settings
+    /// which are safe to change while the conductor is running.
+    ///
+    /// If `config_path` is `None`, the file the conductor was started with is used.
+    /// Settings which can't be changed at runtime are left untouched and
+    /// reported back, so the operator knows a restart is needed.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::ConfigReloaded`]
+    ReloadConfig {
+        /// Optionally load the config from a different path.
+        config_path: Option<std::path::PathBuf>,
+    },
+
+    /// Gracefully shut down the conductor.
+    ///
+    /// No new zome calls or interface requests are accepted once this has
+    /// been received. In-flight zome calls are allowed to finish, and
+    /// workflows commit their current batch before stopping. Finally all
+    /// databases are flushed to disk and network connections are closed.
+    ///
+    /// The response is sent as soon as the shutdown has started,
+    /// so this interface will close shortly afterwards.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::ShuttingDown`]
+    Shutdown,
+
+    /// Set the scheduling weight of a DNA.
+    ///
+    /// All DNAs on the conductor share a fixed amount of validation
+    /// concurrency. While several DNAs have validation work queued, each
+    /// gets a share in proportion to its weight, so a DNA with a large
+    /// backlog can't hold up validation for every other app.
+    /// DNAs have a weight of 1 unless set otherwise.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::DnaSchedulingWeightSet`]
+    SetDnaSchedulingWeight {
+        /// The DNA to set the weight of.
+        dna_hash: DnaHash,
+        /// The new weight. Zero is treated as 1.
+        weight: u32,
+    },
+
+    /// Back up the source chains and integrated ops of a cell's DNA while
+    /// the conductor is running.
+    ///
+    /// Databases are shared by all the cells of a DNA on a conductor,
+    /// so the backup covers every one of them. Each database in the backup
+    /// is a consistent snapshot.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::CellBackedUp`]
+    BackupCell {
+        /// A cell of the DNA to back up.
+        cell_id: CellId,
+        /// A directory on the conductor's machine to write the backup to.
+        /// Must not already contain a backup of this DNA.
+        path: std::path::PathBuf,
+    },
+
+    /// Restore a backup taken with [`AdminRequest::BackupCell`].
+    ///
+    /// Data in the backup is merged with any data already held for the DNA.
+    /// None of the DNA's cells may be running, so that a source chain can't
+    /// be forked by merging in an older copy of it. Restore before installing
+    /// the app into a fresh conductor, with the same agent key, and genesis
+    /// will find the restored chain and be skipped.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::CellRestored`]
+    RestoreCell {
+        /// A cell of the DNA to restore.
+        cell_id: CellId,
+        /// The directory the backup was written to.
+        path: std::path::PathBuf,
+    },
+
+    /// Back up the whole conductor while it is running: its state, wasms, and
+    /// the source chains and integrated ops of every DNA.
+    ///
+    /// The backup is laid out like an environment directory. To restore it,
+    /// start a fresh conductor with the backup (or a copy of it) as its
+    /// `environment_path`.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::ConductorBackedUp`]
+    BackupConductor {
+        /// A new directory on the conductor's machine to write the backup to.
+        path: std::path::PathBuf,
+    },
+
+    /// Export every integrated op this conductor holds for a cell's DNA to a
+    /// file, which a new node can import with [`AdminRequest::ImportDhtOps`]
+    /// to seed its DHT data rather than gossiping it all in.
+    ///
+    /// Private entries are never exported. This covers every cell of the DNA.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::DhtOpsExported`]
+    ExportDhtOps {
+        /// A cell of the DNA to export.
+        cell_id: CellId,
+        /// A file on the conductor's machine to write to, which must not exist.
+        path: std::path::PathBuf,
+    },
+
+    /// Import a file written by [`AdminRequest::ExportDhtOps`] into a cell's
+    /// DNA. The cell must be running.
+    ///
+    /// The ops are not trusted: they are validated like ops received from
+    /// any other peer, and only integrated if they are valid. This call
+    /// returns once every op has been queued for validation.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::DhtOpsImported`]
+    ImportDhtOps {
+        /// The running cell to import into.
+        cell_id: CellId,
+        /// The file the ops were exported to.
+        path: std::path::PathBuf,
+    },
+
+    /// Check the databases of a cell's DNA for corruption, such as may be
+    /// left behind by a crash or a disk error.
+    ///
+    /// Every action and entry is checked against its hash, every op against
+    /// its action, and every source chain for continuity from genesis.
+    /// This covers every cell of the DNA.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::DbIntegrityChecked`]
+    CheckDbIntegrity {
+        /// A cell of the DNA to check.
+        cell_id: CellId,
+        /// Remove corrupt data from the DHT and cache databases, so it will
+        /// be fetched again from the network. Problems with source chains
+        /// are only ever reported, as they can't be fetched again.
+        repair: bool,
+    },
+
+    /// Recover a cell whose chain was left half-written by a crash during
+    /// a countersigning session.
+    ///
+    /// A countersigned action is written to the chain before the session
+    /// completes, and is only published once it does. If the session failed,
+    /// the action is removed from the head of the chain and the chain is
+    /// unlocked. The session has failed once its lock has expired and the
+    /// agent activity authorities of its signers hold none of its actions.
+    /// This fails while the session could still complete, or if it can't be
+    /// confirmed to have failed.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::UncommittedRolledBack`]
+    RollbackUncommitted {
+        /// The cell whose chain to roll back.
+        cell_id: CellId,
+    },
+
+    /// Report how each of the conductor's configured bootstrap servers
+    /// has been responding, e.g. to diagnose peer discovery problems.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::BootstrapHealthRequested`]
+    RequestBootstrapHealth,
+
+    /// Take the conductor's network offline, or bring it back online.
+    ///
+    /// While offline, gossip is paused, and publishes and remote calls are
+    /// held until the network is enabled again, when they are sent. Gets
+    /// only return locally held data. Ops waiting to be published and
+    /// remote signals are stored in the database, so they are still sent
+    /// after a restart. Remote calls are not, as they wait for a response.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::NetworkEnabledSet`]
+    SetNetworkEnabled {
+        /// Whether the network should be enabled.
+        enabled: bool,
+    },
+
+    /// Read entries from the conductor's audit log, oldest first.
+    ///
+    /// The audit log must be enabled in the conductor config.
+    /// Large logs can be read in pages, passing the timestamp of the last
+    /// entry of each page as `since` for the next.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::AuditLogQueried`]
+    QueryAuditLog {
+        /// Only return entries recorded after this time.
+        #[serde(default)]
+        since: Option<Timestamp>,
+        /// Only return entries recorded before this time.
+        #[serde(default)]
+        until: Option<Timestamp>,
+        /// Return at most this many entries.
+        #[serde(default)]
+        limit: Option<usize>,
+    },
+
+    /// Get the chain head of a cell's last write which was committed after
+    /// the agent activity authorities agreed that no other device sharing the
+    /// agent key had written on top of the head it built on.
+    ///
+    /// Chain head coordination must be enabled in the conductor config.
+    /// The agreed head is kept in memory, so there is none until the cell
+    /// has committed a write since the conductor started.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::AgreedChainHeadRequested`]
+    GetAgreedChainHead {
+        /// The cell whose agreed chain head to get.
+        cell_id: CellId,
+    },
+
+    /// Count the ops each running cell has published and the ops its
+    /// conductor has integrated for the cell's DNA.
+    ///
+    /// Comparing the counts of several conductors shows whether they have
+    /// converged, without waiting a fixed amount of time.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::OpCountsRequested`]
+    RequestOpCounts,
+
+    /// Unlock a conductor which was started locked, with the passphrase of
+    /// its keystore.
+    ///
+    /// While a conductor is locked its admin interfaces only accept this
+    /// request, and its cells are not started. Once unlocked, the admin
+    /// interfaces are briefly closed while the conductor starts up, then
+    /// reopened with the full API. Unlocking a running conductor does nothing.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::ConductorUnlocked`]
+    UnlockConductor {
+        /// The passphrase of the conductor's keystore.
+        passphrase: Passphrase,
+    },
+}
+
+/// A keystore passphrase sent over an admin interface, which is left out of
+/// debug output so it isn't logged.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[serde(transparent)]
+pub struct Passphrase(pub String);
+
+impl std::fmt::Debug for Passphrase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Passphrase(<redacted>)")
+    }
 }
 
 /// Represents the possible responses to an [`AdminRequest`]
@@ -362,12 +838,16 @@ pub enum AdminResponse {
     /// There has been an error during the handling of the request.
     Error(ExternalApiWireError),
 
-    /// The successful response to an [`AdminRequest::RegisterDna`]
+    /// The successful response to an [`AdminRequest::RegisterDna`] or
+    /// [`AdminRequest::FetchDna`]
     DnaRegistered(DnaHash),
 
     /// The successful response to an [`AdminRequest::UpdateCoordinators`]
     CoordinatorsUpdated,
 
+    /// The successful response to an [`AdminRequest::UpdateRuntimeProperties`]
+    RuntimePropertiesUpdated,
+
     /// The successful response to an [`AdminRequest::InstallApp`].
     ///
     /// The resulting [`InstalledAppInfo`] contains the app ID,
@@ -382,6 +862,12 @@ pub enum AdminResponse {
     /// of the newly installed DNAs.
     AppBundleInstalled(InstalledAppInfo),
 
+    /// The successful response to an [`AdminRequest::InstallAppBundleForAgents`].
+    ///
+    /// Contains the [`InstalledAppInfo`] of the app installed for each agent,
+    /// in the order of the agents in the request.
+    AppBundleInstalledForAgents(Vec<InstalledAppInfo>),
+
     /// The successful response to an [`AdminRequest::UninstallApp`].
     ///
     /// It means the app was uninstalled successfully.
@@ -435,9 +921,20 @@ pub enum AdminResponse {
         port: u16,
     },
 
+    /// The successful response to an [`AdminRequest::AttachAppLocalSocket`].
+    ///
+    /// `AppInterfaceApi` successfully attached to the local socket.
+    AppLocalSocketAttached,
+
     /// The list of attached app interfaces.
     AppInterfacesListed(Vec<u16>),
 
+    /// The successful response to an [`AdminRequest::ListAppInterfaceSettings`].
+    AppInterfaceSettingsListed(Vec<AppInterfaceInfo>),
+
+    /// The successful response to an [`AdminRequest::DetachAppInterface`].
+    AppInterfaceDetached,
+
     /// The successful response to an [`AdminRequest::EnableApp`].
     ///
     /// It means the app was enabled successfully. If it was possible to
@@ -466,6 +963,11 @@ pub enum AdminResponse {
     /// failed to start.
     /// TODO: add reason why app couldn't start
     AppStarted(bool),
+
+    /// The successful response to an [`AdminRequest::SetAppQuota`].
+    ///
+    /// It means the new quota applies to the app from now on.
+    AppQuotaSet,
     #[deprecated = "alias for AppDisabled"]
     AppDeactivated,
 
@@ -488,6 +990,26 @@ pub enum AdminResponse {
     /// The string is a JSON blob of the metrics results.
     NetworkMetricsDumped(String),
 
+    /// The successful result of a call to [`AdminRequest::DumpNetworkStats`].
+    ///
+    /// The string is a JSON blob of the network stats.
+    NetworkStatsDumped(String),
+
+    /// The successful result of a call to [`AdminRequest::DumpRelayStats`].
+    ///
+    /// The string is a JSON blob of the relay stats.
+    RelayStatsDumped(String),
+
+    /// The successful result of a call to [`AdminRequest::DumpInterfaceStats`].
+    ///
+    /// The stats of each open interface.
+    InterfaceStatsDumped(Vec<InterfaceStats>),
+
+    /// The successful result of a call to [`AdminRequest::DumpTasks`].
+    ///
+    /// The tasks the conductor is running, oldest first.
+    TasksDumped(Vec<TaskInfo>),
+
     /// The successful response to an [`AdminRequest::AddAgentInfo`].
     ///
     /// This means the agent info was successfully added to the peer store.
@@ -500,6 +1022,78 @@ pub enum AdminResponse {
 
     /// The successful response to an [`AdminRequest::AddRecords`].
     RecordsAdded,
+
+    /// The successful response to an [`AdminRequest::ReloadConfig`].
+    ///
+    /// Lists which changed settings were applied and which require a restart.
+    ConfigReloaded(crate::config::conductor::ConfigReloadReport),
+
+    /// The successful response to an [`AdminRequest::Shutdown`].
+    ///
+    /// The conductor has started shutting down.
+    ShuttingDown,
+
+    /// The successful response to an [`AdminRequest::SetDnaSchedulingWeight`].
+    DnaSchedulingWeightSet,
+
+    /// The successful response to an [`AdminRequest::BackupCell`].
+    CellBackedUp,
+
+    /// The successful response to an [`AdminRequest::RestoreCell`].
+    CellRestored,
+
+    /// The successful response to an [`AdminRequest::BackupConductor`].
+    ConductorBackedUp,
+
+    /// The successful response to an [`AdminRequest::ExportDhtOps`].
+    ///
+    /// Contains the number of ops exported.
+    DhtOpsExported(usize),
+
+    /// The successful response to an [`AdminRequest::ImportDhtOps`].
+    ///
+    /// Contains the number of ops read from the file, including any
+    /// already held which were skipped.
+    DhtOpsImported(usize),
+
+    /// The successful response to an [`AdminRequest::CheckDbIntegrity`].
+    ///
+    /// Lists the problems found in each of the DNA's databases, which are
+    /// empty if all is well, and how many rows were removed by a repair.
+    DbIntegrityChecked(DbIntegrityReport),
+
+    /// The successful response to an [`AdminRequest::RollbackUncommitted`].
+    ///
+    /// Contains the hash of the action which was removed, if there was one.
+    UncommittedRolledBack(Option<ActionHash>),
+
+    /// The successful response to an [`AdminRequest::RequestBootstrapHealth`].
+    ///
+    /// The health of each configured bootstrap server, which is empty if
+    /// the network doesn't use bootstrapping.
+    BootstrapHealthRequested(Vec<BootstrapServerHealth>),
+
+    /// The successful response to an [`AdminRequest::SetNetworkEnabled`].
+    NetworkEnabledSet,
+
+    /// The successful response to an [`AdminRequest::QueryAuditLog`].
+    AuditLogQueried(Vec<AuditEntry>),
+
+    /// The successful response to an [`AdminRequest::GetAgreedChainHead`].
+    ///
+    /// Contains the last agreed chain head, if there has been one.
+    AgreedChainHeadRequested(Option<ChainHead>),
+
+    /// The successful response to an [`AdminRequest::RequestOpCounts`].
+    ///
+    /// The op counts of each running cell.
+    OpCountsRequested(Vec<CellOpCounts>),
+
+    /// The successful response to an [`AdminRequest::UnlockConductor`].
+    ///
+    /// The conductor is starting up, and its admin interfaces will reopen
+    /// once it has.
+    ConductorUnlocked,
 }
 
 /// Error type that goes over the websocket wire.
@@ -523,8 +1117,40 @@ pub enum ExternalApiWireError {
     ActivateApp(String),
     /// The zome call is unauthorized.
     ZomeCallUnauthorized(String),
+    /// The zome call was cancelled with [`AppRequest::CancelZomeCall`].
+    ///
+    /// [`AppRequest::CancelZomeCall`]: super::AppRequest::CancelZomeCall
+    ZomeCallCancelled(String),
     /// A countersigning session has failed.
     CountersigningSessionError(String),
+    /// Genesis failed for some of the cells of an app, so the app was not
+    /// installed.
+    GenesisFailed(Vec<CellGenesisFailure>),
+    /// The request would have exceeded the quota of an app.
+    QuotaExceeded(AppQuotaExceeded),
+    /// The conductor is locked, and must be unlocked with
+    /// [`AdminRequest::UnlockConductor`] before it handles other requests.
+    ConductorLocked,
+}
+
+/// Why genesis failed for one cell of an app being installed.
+#[derive(Debug, serde::Serialize, serde::Deserialize, SerializedBytes, Clone, PartialEq)]
+pub struct CellGenesisFailure {
+    /// The cell genesis failed for.
+    pub cell_id: CellId,
+    /// Why it failed.
+    pub reason: GenesisFailureReason,
+}
+
+/// Why genesis failed for a cell.
+#[derive(Debug, serde::Serialize, serde::Deserialize, SerializedBytes, Clone, PartialEq)]
+#[serde(rename_all = "snake_case", tag = "type", content = "data")]
+pub enum GenesisFailureReason {
+    /// The DNA's `genesis_self_check` callback rejected the agent key or
+    /// membrane proof, with this reason.
+    SelfCheckRejected(String),
+    /// Genesis could not be run.
+    Error(String),
 }
 
 impl ExternalApiWireError {
@@ -545,3 +1171,22 @@ pub enum AppStatusFilter {
     Stopped,
     Paused,
 }
+
+/// What happens to the data of the cells which are removed when an app is
+/// uninstalled with [`AdminRequest::UninstallApp`].
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum UninstalledAppData {
+    /// Keep the source chains of the cells on disk, orphaned.
+    Retain,
+    /// Delete the source chains of the cells, including their private entries.
+    /// Data held for the rest of the DHT is kept, as it is shared with
+    /// any other cells of the same DNA.
+    Delete,
+}
+
+impl Default for UninstalledAppData {
+    fn default() -> Self {
+        Self::Retain
+    }
+}
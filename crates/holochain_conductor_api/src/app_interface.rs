@@ -25,6 +25,17 @@ pub enum AppRequest {
         /// The app ID for which to get information
         installed_app_id: InstalledAppId,
     },
+    /// Get everything a UI needs to show the state of an app: its status,
+    /// agent, and each of its roles with their cells, clone cells, whether
+    /// each cell is running, and the modifiers of each cell's DNA.
+    ///
+    /// # Returns
+    ///
+    /// [`AppResponse::FullAppInfo`]
+    FullAppInfo {
+        /// The app ID for which to get information
+        installed_app_id: InstalledAppId,
+    },
     /// Is currently unimplemented and will return
     /// an [`AppResponse::Unimplemented`].
     Crypto(Box<CryptoRequest>),
@@ -39,9 +50,104 @@ pub enum AppRequest {
     #[deprecated = "use ZomeCall"]
     ZomeCallInvocation(Box<ZomeCall>),
 
-    /// Is currently unimplemented and will return
-    /// an [`AppResponse::Unimplemented`].
+    /// Call a zome function as the agent of the cell, with a call signed
+    /// by that agent. See [`SignedZomeCall`].
+    ///
+    /// This lets a client which holds its own keys, e.g. in a browser, call
+    /// a cell hosted by this conductor, which checks the signature with the
+    /// agent's public key. Calls which commit to the source chain still need
+    /// the conductor's keystore to sign the new actions as the agent.
+    ///
+    /// A signature only protects the agent if the interface refuses
+    /// unsigned zome calls, which may be made as any agent, so interfaces
+    /// such clients connect to must be attached with
+    /// `require_signed_zome_calls`.
+    ///
+    /// # Returns
+    ///
+    /// [`AppResponse::ZomeCall`]
+    SignedZomeCall(Box<SignedZomeCall>),
+
+    /// Start a zome call without waiting for it to finish, so a long
+    /// running call can be cancelled with [`AppRequest::CancelZomeCall`].
+    /// Its result is read with [`AppRequest::AwaitZomeCall`].
+    ///
+    /// Started calls belong to the connection which started them, and are
+    /// cancelled if it closes.
+    ///
+    /// # Returns
+    ///
+    /// [`AppResponse::ZomeCallStarted`]
+    StartZomeCall(Box<ZomeCall>),
+
+    /// Wait for a call started with [`AppRequest::StartZomeCall`] to finish.
+    /// The result of a call can only be read once.
+    ///
+    /// # Returns
+    ///
+    /// [`AppResponse::ZomeCall`], or [`ExternalApiWireError::ZomeCallCancelled`]
+    /// if the call was cancelled.
+    AwaitZomeCall {
+        /// The ID the call was started with
+        call_id: ZomeCallId,
+    },
+
+    /// Cancel a call started with [`AppRequest::StartZomeCall`]. The call
+    /// is aborted and its writes are dropped, unless it has already been
+    /// committed. Awaiting the call tells when it has stopped.
+    ///
+    /// # Returns
+    ///
+    /// [`AppResponse::ZomeCallCancelled`]
+    CancelZomeCall {
+        /// The ID the call was started with
+        call_id: ZomeCallId,
+    },
+
+    /// Create the cell of a role which is deferred in the app manifest,
+    /// running genesis, and start it if the app is running.
+    ///
+    /// Deferred cells are also created by the first zome call to them,
+    /// so this is only needed to create one ahead of time.
+    ///
+    /// # Returns
+    ///
+    /// [`AppResponse::DeferredCellProvisioned`]
+    ProvisionDeferredCell {
+        /// The app ID the role belongs to
+        installed_app_id: InstalledAppId,
+        /// The role whose cell to create
+        role_id: AppRoleId,
+    },
+
+    /// Choose which of an app's signals are sent on this connection.
+    /// See [`SignalSubscription`].
+    ///
+    /// # Returns
+    ///
+    /// [`AppResponse::SignalSubscriptionUpdated`]
     SignalSubscription(SignalSubscription),
+
+    /// List the records integrated into the DHT database of a cell's DNA
+    /// at or after a time, oldest first, so a client can fetch what's new
+    /// since it last looked instead of re-running its queries.
+    ///
+    /// To page through them, pass the `when_integrated` of the last record
+    /// as the next `since`. Records integrated at that same time are
+    /// listed again.
+    ///
+    /// # Returns
+    ///
+    /// [`AppResponse::RecordsIntegratedSince`]
+    RecordsIntegratedSince {
+        /// The cell whose DNA's DHT database to list
+        cell_id: CellId,
+        /// The earliest integration time to list
+        since: Timestamp,
+        /// The most records to list, up to 1000. Defaults to 100.
+        #[serde(default)]
+        limit: Option<u32>,
+    },
 }
 
 /// Represents the possible responses to an [`AppRequest`].
@@ -62,6 +168,11 @@ pub enum AppResponse {
     /// Check out [`InstalledApp`] for details on when the option is `Some<InstalledAppInfo>`
     AppInfo(Option<InstalledAppInfo>),
 
+    /// The succesful response to an [`AppRequest::FullAppInfo`].
+    ///
+    /// Option will be `None` if there is no installed app with the given `installed_app_id`.
+    FullAppInfo(Option<FullAppInfo>),
+
     /// The successful response to an [`AppRequest::ZomeCall`].
     ///
     /// Note that [`ExternIO`] is simply a structure of [`struct@SerializedBytes`], so the client will have
@@ -72,8 +183,41 @@ pub enum AppResponse {
 
     #[deprecated = "use ZomeCall"]
     ZomeCallInvocation(Box<ExternIO>),
+
+    /// The successful response to an [`AppRequest::StartZomeCall`].
+    ///
+    /// Contains the ID to await or cancel the call with.
+    ZomeCallStarted(ZomeCallId),
+
+    /// The successful response to an [`AppRequest::CancelZomeCall`].
+    ZomeCallCancelled,
+
+    /// The successful response to an [`AppRequest::ProvisionDeferredCell`].
+    ///
+    /// Contains the ID of the cell, which may have already been created.
+    DeferredCellProvisioned(CellId),
+
+    /// The successful response to an [`AppRequest::SignalSubscription`].
+    SignalSubscriptionUpdated,
+
+    /// The successful response to an [`AppRequest::RecordsIntegratedSince`].
+    RecordsIntegratedSince(Vec<IntegratedRecord>),
 }
 
+/// A record listed by [`AppRequest::RecordsIntegratedSince`].
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct IntegratedRecord {
+    /// When the first of the record's ops in the listed range was integrated
+    pub when_integrated: Timestamp,
+    /// The record. Private entries are never held in the DHT, so records
+    /// of private entries are listed without them.
+    pub record: Record,
+}
+
+/// Identifies a zome call started with [`AppRequest::StartZomeCall`] on an
+/// app interface connection.
+pub type ZomeCallId = u64;
+
 /// The data provided over an app interface in order to make a zome call
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct ZomeCall {
@@ -99,6 +243,35 @@ pub struct ZomeCall {
     pub provenance: AgentPubKey,
 }
 
+/// A [`ZomeCall`] signed by its provenance, which must be the agent of the
+/// cell being called.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct SignedZomeCall {
+    /// The call to make
+    pub call: ZomeCall,
+    /// Random bytes which make each signed call unique. The conductor
+    /// refuses a nonce it has already seen from the agent until the call
+    /// it was used in expires.
+    pub nonce: [u8; 32],
+    /// When the call may no longer be made. This may be at most
+    /// five minutes from when the conductor receives the call.
+    pub expires_at: Timestamp,
+    /// The provenance's signature of [`SignedZomeCall::data_to_sign`]
+    pub signature: Signature,
+}
+
+impl SignedZomeCall {
+    /// The bytes which are signed for a call: the msgpack encoding of the
+    /// call, nonce and expiry, as an array.
+    pub fn data_to_sign(
+        call: &ZomeCall,
+        nonce: &[u8; 32],
+        expires_at: &Timestamp,
+    ) -> Result<Vec<u8>, SerializedBytesError> {
+        holochain_serialized_bytes::encode(&(call, nonce, expires_at))
+    }
+}
+
 #[allow(missing_docs)]
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "snake_case", tag = "type", content = "data")]
@@ -113,10 +286,22 @@ pub enum CryptoRequest {
 pub struct InstalledAppInfo {
     /// The unique identifier for an installed app in this conductor
     pub installed_app_id: InstalledAppId,
+    /// The agent the app was installed for
+    pub agent_pub_key: AgentPubKey,
     /// Info about the cells installed in this app
     pub cell_data: Vec<InstalledCell>,
+    /// The cells of deferred roles, which will be created when they
+    /// are first called
+    #[serde(default)]
+    pub deferred_cell_data: Vec<InstalledCell>,
+    /// The cells cloned from the app's roles at runtime
+    #[serde(default)]
+    pub clone_cell_data: Vec<InstalledCell>,
     /// The app's current status, in an API-friendly format
     pub status: InstalledAppInfoStatus,
+    /// The resources the app may use
+    #[serde(default)]
+    pub quota: AppQuota,
 }
 
 impl InstalledAppInfo {
@@ -127,10 +312,139 @@ impl InstalledAppInfo {
             .provisioned_cells()
             .map(|(role_id, id)| InstalledCell::new(id.clone(), role_id.clone()))
             .collect();
+        let deferred_cell_data = app
+            .deferred_cells()
+            .map(|(role_id, id)| InstalledCell::new(id.clone(), role_id.clone()))
+            .collect();
+        let clone_cell_data = app
+            .roles()
+            .iter()
+            .flat_map(|(role_id, role)| {
+                role.clones()
+                    .iter()
+                    .map(|id| InstalledCell::new(id.clone(), role_id.clone()))
+            })
+            .collect();
         Self {
             installed_app_id,
+            agent_pub_key: app._agent_key().clone(),
             cell_data,
+            deferred_cell_data,
+            clone_cell_data,
             status,
+            quota: app.quota().clone(),
+        }
+    }
+}
+
+/// Everything about an installed app, returned as part of
+/// [`AppResponse::FullAppInfo`]
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, SerializedBytes)]
+pub struct FullAppInfo {
+    /// The unique identifier for an installed app in this conductor
+    pub installed_app_id: InstalledAppId,
+    /// The agent the app was installed for
+    pub agent_pub_key: AgentPubKey,
+    /// The app's current status, in an API-friendly format
+    pub status: InstalledAppInfoStatus,
+    /// The resources the app may use
+    pub quota: AppQuota,
+    /// The app's roles, ordered by their IDs
+    pub roles: Vec<AppRoleInfo>,
+}
+
+/// A role of an app, as part of a [`FullAppInfo`]
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct AppRoleInfo {
+    /// The ID of the role
+    pub role_id: AppRoleId,
+    /// The role's base cell. It is only created when it's first called if
+    /// it is deferred.
+    pub base_cell: AppCellInfo,
+    /// Whether the base cell has been created
+    pub is_provisioned: bool,
+    /// How many cells may be cloned from the role
+    pub clone_limit: u32,
+    /// The cells cloned from the role at runtime
+    pub clone_cells: Vec<AppCellInfo>,
+}
+
+/// A cell of an app, as part of a [`FullAppInfo`]
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct AppCellInfo {
+    /// The ID of the cell
+    pub cell_id: CellId,
+    /// Whether the cell is running in the conductor
+    pub enabled: bool,
+    /// The modifiers of the cell's DNA, or `None` if the DNA isn't
+    /// registered, as for a deferred cell whose DNA has been removed.
+    pub dna_modifiers: Option<DnaModifiers>,
+}
+
+/// The parts of a DNA which are set when it's installed or cloned, rather
+/// than by its code, as part of an [`AppCellInfo`]
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct DnaModifiers {
+    /// The network seed of the DNA
+    pub network_seed: String,
+    /// The properties of the DNA
+    pub properties: SerializedBytes,
+    /// The properties of the DNA which can be updated while it runs
+    pub runtime_properties: SerializedBytes,
+    /// The time the DNA's network started
+    pub origin_time: Timestamp,
+    /// How many seconds data is kept for, if the DNA is ephemeral
+    #[serde(default)]
+    pub ephemeral_ttl_secs: Option<u64>,
+}
+
+impl From<&DnaDef> for DnaModifiers {
+    fn from(dna_def: &DnaDef) -> Self {
+        Self {
+            network_seed: dna_def.network_seed.clone(),
+            properties: dna_def.properties.clone(),
+            runtime_properties: dna_def.runtime_properties.clone(),
+            origin_time: dna_def.origin_time,
+            ephemeral_ttl_secs: dna_def.ephemeral_ttl_secs,
+        }
+    }
+}
+
+impl FullAppInfo {
+    /// Describe an app, given whether each cell is running and the
+    /// definition of each DNA.
+    pub fn from_installed_app(
+        app: &InstalledApp,
+        is_running: impl Fn(&CellId) -> bool,
+        dna_def: impl Fn(&DnaHash) -> Option<DnaDef>,
+    ) -> Self {
+        let cell_info = |cell_id: &CellId| AppCellInfo {
+            cell_id: cell_id.clone(),
+            enabled: is_running(cell_id),
+            dna_modifiers: dna_def(cell_id.dna_hash()).as_ref().map(DnaModifiers::from),
+        };
+        let mut roles: Vec<_> = app
+            .roles()
+            .iter()
+            .map(|(role_id, role)| {
+                let mut clone_cells: Vec<_> = role.clones().iter().map(&cell_info).collect();
+                clone_cells.sort_by(|a, b| a.cell_id.dna_hash().cmp(b.cell_id.dna_hash()));
+                AppRoleInfo {
+                    role_id: role_id.clone(),
+                    base_cell: cell_info(role.cell_id()),
+                    is_provisioned: role.is_provisioned(),
+                    clone_limit: role.clone_limit(),
+                    clone_cells,
+                }
+            })
+            .collect();
+        roles.sort_by(|a, b| a.role_id.cmp(&b.role_id));
+        Self {
+            installed_app_id: app.id().clone(),
+            agent_pub_key: app._agent_key().clone(),
+            status: app.status().clone().into(),
+            quota: app.quota().clone(),
+            roles,
         }
     }
 }
@@ -0,0 +1,53 @@
+//! Types for the entries of the conductor's audit log.
+//! See [`AuditLogConfig`](crate::config::conductor::AuditLogConfig).
+
+use holo_hash::AgentPubKey;
+use holochain_types::prelude::*;
+use holochain_zome_types::cell::CellId;
+
+/// A record of one admin call or zome call made to the conductor.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct AuditEntry {
+    /// When the call finished.
+    pub timestamp: Timestamp,
+    /// The call which was made.
+    pub event: AuditEvent,
+    /// Whether the call succeeded.
+    pub outcome: AuditOutcome,
+}
+
+/// A call recorded in the audit log.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum AuditEvent {
+    /// A call to an admin interface.
+    AdminCall {
+        /// The `type` of the [`AdminRequest`](crate::AdminRequest),
+        /// e.g. `install_app`.
+        request: String,
+    },
+    /// A zome call made through an app interface or the HTTP gateway.
+    ZomeCall {
+        /// The cell which was called.
+        cell_id: CellId,
+        /// The zome which was called.
+        zome_name: ZomeName,
+        /// The function which was called.
+        fn_name: FunctionName,
+        /// The agent the call was made as.
+        provenance: AgentPubKey,
+    },
+}
+
+/// The outcome of a call recorded in the audit log.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum AuditOutcome {
+    /// The call succeeded.
+    Success,
+    /// The call failed or was refused.
+    Error {
+        /// Why the call failed.
+        message: String,
+    },
+}
@@ -7,20 +7,53 @@ use serde::Deserialize;
 use serde::Serialize;
 
 mod admin_interface_config;
+mod audit_log_config;
+mod chain_head_coordination_config;
+mod db_encryption_config;
+mod dht_pruning_config;
+mod dna_tuning_params_config;
 mod dpki_config;
+mod entry_compression_config;
 #[allow(missing_docs)]
 mod error;
+mod health_endpoint_config;
+mod http_gateway_config;
+mod instance_pool_config;
 mod keystore_config;
+mod network_backend_config;
+mod open_telemetry_config;
 pub mod paths;
+mod relay_config;
+mod reload;
+mod zome_call_limits_config;
 //mod logger_config;
 //mod signal_config;
 pub use paths::DatabaseRootPath;
 
 pub use super::*;
+pub use audit_log_config::{AuditLogConfig, DEFAULT_AUDIT_LOG_MAX_FILE_SIZE_BYTES};
+pub use chain_head_coordination_config::ChainHeadCoordinationConfig;
+pub use db_encryption_config::DbEncryptionConfig;
+pub use dht_pruning_config::DhtPruningConfig;
+pub use dna_tuning_params_config::DnaTuningParamsConfig;
 pub use dpki_config::DpkiConfig;
+pub use entry_compression_config::{
+    EntryCompressionConfig, DEFAULT_ENTRY_COMPRESSION_THRESHOLD_BYTES,
+};
+pub use health_endpoint_config::HealthEndpointConfig;
+pub use http_gateway_config::{
+    HttpGatewayConfig, HttpGatewayToken, DEFAULT_HTTP_GATEWAY_MAX_REQUEST_BYTES,
+};
+pub use instance_pool_config::{InstancePoolConfig, DEFAULT_INSTANCE_POOL_MAX_IDLE};
 //pub use logger_config::LoggerConfig;
 pub use error::*;
 pub use keystore_config::KeystoreConfig;
+pub use network_backend_config::DnaNetworkBackendConfig;
+pub use network_backend_config::NetworkBackendConfig;
+pub use open_telemetry_config::OpenTelemetryConfig;
+pub use relay_config::RelayConfig;
+pub use reload::ConfigReloadReport;
+pub use zome_call_limits_config::ZomeCallLimitsConfig;
 //pub use signal_config::SignalConfig;
 use std::path::Path;
 
@@ -46,6 +79,15 @@ pub struct ConductorConfig {
     /// Optional config for the network module.
     pub network: Option<holochain_p2p::kitsune_p2p::KitsuneP2pConfig>,
 
+    /// Optional choice of network backend, which is kitsune if omitted.
+    /// See [`NetworkBackendConfig`] for details.
+    pub network_backend: Option<NetworkBackendConfig>,
+
+    /// Network backends for some DNAs which override the `network_backend`.
+    /// See [`DnaNetworkBackendConfig`] for details.
+    #[serde(default)]
+    pub dna_network_backends: Vec<DnaNetworkBackendConfig>,
+
     #[serde(default)]
     /// Override the default database synchronous strategy.
     ///
@@ -56,6 +98,63 @@ pub struct ConductorConfig {
     ///
     /// [sqlite documentation]: https://www.sqlite.org/pragma.html#pragma_synchronous
     pub db_sync_strategy: DbSyncStrategy,
+
+    /// Optional encryption of the databases at rest.
+    /// See [`DbEncryptionConfig`] for details.
+    pub db_encryption: Option<DbEncryptionConfig>,
+
+    /// Optional pruning of deleted data from the DHT.
+    /// See [`DhtPruningConfig`] for details.
+    pub dht_pruning: Option<DhtPruningConfig>,
+
+    /// Network tuning params for particular DNAs, overriding
+    /// `network.tuning_params`.
+    /// See [`DnaTuningParamsConfig`] for details.
+    #[serde(default)]
+    pub dna_tuning_params: Vec<DnaTuningParamsConfig>,
+
+    /// Optional HTTP gateway for making zome calls with JSON requests.
+    /// See [`HttpGatewayConfig`] for details.
+    pub http_gateway: Option<HttpGatewayConfig>,
+
+    /// Optional HTTP endpoint for liveness and readiness checks.
+    /// See [`HealthEndpointConfig`] for details.
+    pub health_endpoint: Option<HealthEndpointConfig>,
+
+    /// Optional export of tracing spans to an OpenTelemetry collector.
+    /// See [`OpenTelemetryConfig`] for details.
+    pub open_telemetry: Option<OpenTelemetryConfig>,
+
+    /// Optional audit log of admin calls and zome calls.
+    /// See [`AuditLogConfig`] for details.
+    pub audit_log: Option<AuditLogConfig>,
+
+    /// Optional limits on the time, instructions and memory a zome call
+    /// may use.
+    /// See [`ZomeCallLimitsConfig`] for details.
+    pub zome_call_limits: Option<ZomeCallLimitsConfig>,
+
+    /// Optional pool of ready wasm instances for each zome.
+    /// See [`InstancePoolConfig`] for details.
+    pub instance_pool: Option<InstancePoolConfig>,
+
+    /// Optional compression of large entries in the databases.
+    /// See [`EntryCompressionConfig`] for details.
+    pub entry_compression: Option<EntryCompressionConfig>,
+
+    /// Optional coordination of the chain head between devices which share
+    /// an agent key.
+    /// See [`ChainHeadCoordinationConfig`] for details.
+    pub chain_head_coordination: Option<ChainHeadCoordinationConfig>,
+
+    /// Optional relay for other peers, run alongside the conductor.
+    /// See [`RelayConfig`] for details.
+    pub relay: Option<RelayConfig>,
+
+    /// Optional filter on which logs are written, in the same format as
+    /// `RUST_LOG`, e.g. `info,kitsune_p2p=warn`. Takes the place of
+    /// `RUST_LOG` when set, and can be changed with a config reload.
+    pub log_filter: Option<String>,
     //
     //
     // Which signals to emit
@@ -123,10 +222,25 @@ pub mod tests {
             ConductorConfig {
                 environment_path: PathBuf::from("/path/to/env").into(),
                 network: None,
+                network_backend: None,
+                dna_network_backends: vec![],
                 dpki: None,
                 keystore: KeystoreConfig::DangerTestKeystoreLegacyDeprecated,
                 admin_interfaces: None,
                 db_sync_strategy: DbSyncStrategy::default(),
+                db_encryption: None,
+                dht_pruning: None,
+                dna_tuning_params: vec![],
+                http_gateway: None,
+                health_endpoint: None,
+                open_telemetry: None,
+                audit_log: None,
+                zome_call_limits: None,
+                instance_pool: None,
+                entry_compression: None,
+                chain_head_coordination: None,
+                relay: None,
+                log_filter: None,
             }
         );
     }
@@ -177,6 +291,18 @@ pub mod tests {
       network_type: quic_bootstrap
 
     db_sync_strategy: Fast
+
+    db_encryption:
+      rekey_on_startup: true
+
+    dht_pruning:
+      retention_period_secs: 3600
+
+    dna_tuning_params:
+      - role_id: archive
+        tuning_params:
+          gossip_loop_iteration_delay_ms: 60000
+          gossip_dynamic_arcs: false
     "#;
         let result: ConductorConfigResult<ConductorConfig> = config_from_yaml(yaml);
         use holochain_p2p::kitsune_p2p::*;
@@ -217,10 +343,39 @@ pub mod tests {
                     danger_passphrase_insecure_from_config: "test-passphrase".to_string(),
                 },
                 admin_interfaces: Some(vec![AdminInterfaceConfig {
-                    driver: InterfaceDriver::Websocket { port: 1234 }
+                    driver: InterfaceDriver::websocket(1234)
                 }]),
                 network: Some(network_config),
+                network_backend: None,
+                dna_network_backends: vec![],
                 db_sync_strategy: DbSyncStrategy::Fast,
+                db_encryption: Some(DbEncryptionConfig {
+                    rekey_on_startup: true,
+                }),
+                dht_pruning: Some(DhtPruningConfig {
+                    retention_period_secs: 3600,
+                }),
+                dna_tuning_params: vec![DnaTuningParamsConfig {
+                    dna_hash: None,
+                    role_id: Some("archive".to_string()),
+                    tuning_params: [
+                        ("gossip_loop_iteration_delay_ms", "60000"),
+                        ("gossip_dynamic_arcs", "false"),
+                    ]
+                    .into_iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect(),
+                }],
+                http_gateway: None,
+                health_endpoint: None,
+                open_telemetry: None,
+                audit_log: None,
+                zome_call_limits: None,
+                instance_pool: None,
+                entry_compression: None,
+                chain_head_coordination: None,
+                relay: None,
+                log_filter: None,
             }
         );
     }
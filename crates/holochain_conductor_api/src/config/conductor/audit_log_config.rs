@@ -0,0 +1,43 @@
+use serde::Deserialize;
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// Default size at which the audit log starts a new file.
+pub const DEFAULT_AUDIT_LOG_MAX_FILE_SIZE_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Record every admin call, and optionally every zome call, in an
+/// append-only audit log for deployments which must account for how the
+/// conductor was used.
+///
+/// Each call is written as a line of JSON with a timestamp, the call made,
+/// and whether it succeeded. When the current file reaches
+/// `max_file_size_bytes` it is rotated out to a file named after the time
+/// of the rotation, and a new file is started.
+/// The log can be read with the `QueryAuditLog` admin call.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq)]
+pub struct AuditLogConfig {
+    /// The directory to write the log to.
+    /// Defaults to `audit` in the environment path.
+    #[serde(default)]
+    pub path: Option<PathBuf>,
+    /// Also record every zome call made through an app interface or the
+    /// HTTP gateway, with the cell, function and provenance of the call.
+    #[serde(default)]
+    pub include_zome_calls: bool,
+    /// Rotate the current file once it is this large.
+    /// Defaults to [`DEFAULT_AUDIT_LOG_MAX_FILE_SIZE_BYTES`].
+    #[serde(default)]
+    pub max_file_size_bytes: Option<u64>,
+    /// How many rotated files to keep, deleting the oldest.
+    /// If omitted every rotated file is kept.
+    #[serde(default)]
+    pub max_rotated_files: Option<usize>,
+}
+
+impl AuditLogConfig {
+    /// Get the size at which the current file is rotated.
+    pub fn max_file_size_bytes(&self) -> u64 {
+        self.max_file_size_bytes
+            .unwrap_or(DEFAULT_AUDIT_LOG_MAX_FILE_SIZE_BYTES)
+    }
+}
@@ -0,0 +1,37 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+/// Coordinate chain head advancement between devices which share one agent
+/// key, so that they don't fork the agent's source chain.
+///
+/// Before a zome call's writes are committed, the conductor asks the agent
+/// activity authorities of the agent for the highest actions they have
+/// observed on its chain. These authorities receive the actions of every
+/// device, so if another device has already written on top of the local
+/// chain head, the write is refused with a chain head conflict instead of
+/// forking the chain. The device then has to catch up with the other
+/// device's actions before it can write again.
+///
+/// Devices sharing a key can't be told apart by the network, which is why
+/// the agreement is reached through the authorities rather than between
+/// the devices directly.
+///
+/// Authorities which can't be reached, for example while the device is
+/// offline, can't report a conflict, so the write goes ahead. An offline
+/// device stays usable, at the risk of forking its chain.
+///
+/// The zome calls of one device are committed one at a time while
+/// coordination is enabled, so a call can't build on a head the authorities
+/// weren't asked about. Two devices which write at the same moment can
+/// still both be told there is no conflict, as neither write has reached
+/// the authorities yet.
+///
+/// The last chain head which was agreed on for a cell can be requested
+/// with the `GetAgreedChainHead` admin call.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Default)]
+pub struct ChainHeadCoordinationConfig {
+    /// How long to wait for the authorities to respond before committing.
+    /// Defaults to the network's own timeout.
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+}
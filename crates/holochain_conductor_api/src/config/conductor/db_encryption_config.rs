@@ -0,0 +1,19 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+/// Configure encryption of the conductor's databases at rest.
+///
+/// The databases are encrypted with a random key. That key is stored in the
+/// environment directory, itself encrypted with a secret held in the
+/// keystore, so the databases can only be read while the keystore is
+/// unlocked. Requires Holochain to be built with the `db-encryption` feature.
+///
+/// Encryption must be enabled on a new environment: existing unencrypted
+/// databases are not converted.
+#[derive(Clone, Deserialize, Serialize, Default, Debug, PartialEq)]
+pub struct DbEncryptionConfig {
+    /// Re-encrypt every database with a newly generated key at startup.
+    /// The old key is discarded once all the databases have been re-keyed.
+    #[serde(default)]
+    pub rekey_on_startup: bool,
+}
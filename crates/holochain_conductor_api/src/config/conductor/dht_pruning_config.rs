@@ -0,0 +1,36 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+/// Configure pruning of deleted data from the DHT and cache databases,
+/// to reclaim disk space on long-running nodes.
+///
+/// Once every create of an entry has been deleted, and the deletes have been
+/// integrated for longer than the retention period, the entry's content is
+/// purged. The actions and ops which created and deleted it are kept, so the
+/// DHT's metadata stays intact. The authored source chains are never pruned.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq)]
+pub struct DhtPruningConfig {
+    /// How long, in seconds, a deleted entry is kept before its content is
+    /// purged. Defaults to 30 days.
+    #[serde(default = "default_retention_period_secs")]
+    pub retention_period_secs: u64,
+}
+
+fn default_retention_period_secs() -> u64 {
+    60 * 60 * 24 * 30
+}
+
+impl Default for DhtPruningConfig {
+    fn default() -> Self {
+        Self {
+            retention_period_secs: default_retention_period_secs(),
+        }
+    }
+}
+
+impl DhtPruningConfig {
+    /// The retention period as a [`std::time::Duration`].
+    pub fn retention_period(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.retention_period_secs)
+    }
+}
@@ -0,0 +1,42 @@
+use holo_hash::DnaHash;
+use holochain_types::prelude::AppRoleId;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Network tuning params for some DNAs which override the global
+/// `network.tuning_params`, so DNAs with very different traffic can coexist
+/// on one conductor, e.g. a chatty chat DNA gossiping often alongside a slow
+/// archival DNA which gossips rarely and holds a wide arc.
+///
+/// The DNAs are picked by hash, or by the role they are installed under
+/// in any app. An entry naming both applies to either.
+/// Entries are applied in order, so later entries win where they overlap.
+/// The params are read when the DNA's network space is first joined.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq)]
+pub struct DnaTuningParamsConfig {
+    /// The hash of the DNA to tune.
+    #[serde(default)]
+    pub dna_hash: Option<DnaHash>,
+    /// The role id of the DNAs to tune.
+    #[serde(default)]
+    pub role_id: Option<AppRoleId>,
+    /// The tuning params to override, named and written as in
+    /// `network.tuning_params`.
+    pub tuning_params: HashMap<String, String>,
+}
+
+impl DnaTuningParamsConfig {
+    /// Does this entry apply to the DNA, which is installed under these roles.
+    pub fn applies_to<'a>(
+        &self,
+        dna_hash: &DnaHash,
+        mut role_ids: impl Iterator<Item = &'a AppRoleId>,
+    ) -> bool {
+        self.dna_hash.as_ref() == Some(dna_hash)
+            || self
+                .role_id
+                .as_ref()
+                .map_or(false, |role_id| role_ids.any(|r| r == role_id))
+    }
+}
@@ -0,0 +1,35 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+/// Default size, in bytes, above which entries are compressed.
+pub const DEFAULT_ENTRY_COMPRESSION_THRESHOLD_BYTES: usize = 16 * 1024;
+
+/// Compress large entries with zstd when they are stored, to save disk
+/// space for apps which commit large documents.
+///
+/// Compression is transparent: entries are decompressed when they are read,
+/// and entries stored without compression can still be read, so this can
+/// be turned on or off at any time. Only newly stored entries are affected.
+///
+/// Entries are also compressed on the wire by the network, which is
+/// configured separately with the `wire_compression_threshold_bytes`
+/// tuning param.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq)]
+pub struct EntryCompressionConfig {
+    /// Compress entries which are at least this many bytes once serialized.
+    /// Defaults to [`DEFAULT_ENTRY_COMPRESSION_THRESHOLD_BYTES`].
+    #[serde(default = "default_threshold_bytes")]
+    pub threshold_bytes: usize,
+}
+
+fn default_threshold_bytes() -> usize {
+    DEFAULT_ENTRY_COMPRESSION_THRESHOLD_BYTES
+}
+
+impl Default for EntryCompressionConfig {
+    fn default() -> Self {
+        Self {
+            threshold_bytes: default_threshold_bytes(),
+        }
+    }
+}
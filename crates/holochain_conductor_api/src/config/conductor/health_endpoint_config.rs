@@ -0,0 +1,29 @@
+use serde::Deserialize;
+use serde::Serialize;
+use std::net::IpAddr;
+use std::net::Ipv4Addr;
+
+/// Serve health checks over HTTP, for service managers and container
+/// orchestrators to probe.
+///
+/// `GET /livez` answers `200 OK` for as long as the conductor is running,
+/// and `GET /readyz` answers `200 OK` once the conductor has started its
+/// interfaces and apps, and `503 Service Unavailable` before then and while
+/// it is shutting down. Neither needs authorization, so the endpoint should
+/// only be reachable by whatever probes it.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq)]
+pub struct HealthEndpointConfig {
+    /// The port to listen on, or 0 to have the OS choose one.
+    pub port: u16,
+    /// The address of the network interface to listen on.
+    /// Defaults to `127.0.0.1`.
+    #[serde(default)]
+    pub bind_address: Option<IpAddr>,
+}
+
+impl HealthEndpointConfig {
+    /// Get the address of the network interface to listen on.
+    pub fn bind_address(&self) -> IpAddr {
+        self.bind_address.unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST))
+    }
+}
@@ -0,0 +1,146 @@
+use holochain_types::prelude::agent_app_id;
+use holochain_types::prelude::AgentPubKey;
+use holochain_types::prelude::InstalledAppId;
+use serde::Deserialize;
+use serde::Serialize;
+use std::net::IpAddr;
+use std::net::Ipv4Addr;
+
+/// Default size, in bytes, of the largest request body the HTTP gateway
+/// accepts.
+pub const DEFAULT_HTTP_GATEWAY_MAX_REQUEST_BYTES: usize = 16 * 1024 * 1024;
+
+/// Configure an HTTP gateway, which makes zome calls for POSTed JSON requests,
+/// so services and scripts can call apps without speaking the websocket
+/// app interface's wire protocol.
+///
+/// Requests are made to `/zome_call` with a bearer token in the
+/// `Authorization` header, and name the app and role of the cell to call.
+/// Calls are made as the agent of the app, so tokens should be kept as
+/// secret as the agent's keys.
+///
+/// On a conductor hosting an app for many agents, each agent's token can be
+/// given their `agent_key`, so their calls go to their own cells.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq)]
+pub struct HttpGatewayConfig {
+    /// The port to listen on, or 0 to have the OS choose one.
+    pub port: u16,
+    /// The address of the network interface to listen on.
+    /// Defaults to `127.0.0.1`.
+    #[serde(default)]
+    pub bind_address: Option<IpAddr>,
+    /// The tokens which may make zome calls.
+    pub tokens: Vec<HttpGatewayToken>,
+    /// The largest request body accepted, in bytes. Larger requests are
+    /// refused with `413 Payload Too Large` before they are read.
+    /// Defaults to [`DEFAULT_HTTP_GATEWAY_MAX_REQUEST_BYTES`].
+    #[serde(default = "default_max_request_bytes")]
+    pub max_request_bytes: usize,
+}
+
+fn default_max_request_bytes() -> usize {
+    DEFAULT_HTTP_GATEWAY_MAX_REQUEST_BYTES
+}
+
+/// A token allowed to make zome calls through the [`HttpGatewayConfig`].
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq)]
+pub struct HttpGatewayToken {
+    /// The secret sent as `Authorization: Bearer <token>`.
+    pub token: String,
+    /// The apps this token may call, or any app if omitted.
+    #[serde(default)]
+    pub installed_app_ids: Option<Vec<InstalledAppId>>,
+    /// The agent this token calls apps for. If set, a call naming an app
+    /// goes to the app installed for this agent with
+    /// `InstallAppBundleForAgents` instead.
+    #[serde(default)]
+    pub agent_key: Option<AgentPubKey>,
+}
+
+impl HttpGatewayConfig {
+    /// Get the address of the network interface to listen on.
+    pub fn bind_address(&self) -> IpAddr {
+        self.bind_address.unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST))
+    }
+
+    /// Find the token matching the `Authorization` header's bearer token.
+    pub fn authorize(&self, bearer_token: &str) -> Option<&HttpGatewayToken> {
+        self.tokens
+            .iter()
+            .find(|t| constant_time_eq(t.token.as_bytes(), bearer_token.as_bytes()))
+    }
+}
+
+impl HttpGatewayToken {
+    /// Can this token call the app.
+    pub fn can_call(&self, installed_app_id: &InstalledAppId) -> bool {
+        self.installed_app_ids
+            .as_ref()
+            .map_or(true, |ids| ids.contains(installed_app_id))
+    }
+
+    /// The installed app a call naming this app goes to.
+    pub fn route(&self, installed_app_id: &InstalledAppId) -> InstalledAppId {
+        match &self.agent_key {
+            Some(agent_key) => agent_app_id(installed_app_id, agent_key),
+            None => installed_app_id.clone(),
+        }
+    }
+}
+
+/// Compare secrets without leaking how much of them matched through timing.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokens_are_scoped_to_apps() {
+        let config = HttpGatewayConfig {
+            port: 0,
+            bind_address: None,
+            tokens: vec![
+                HttpGatewayToken {
+                    token: "admin-secret".to_string(),
+                    installed_app_ids: None,
+                    agent_key: None,
+                },
+                HttpGatewayToken {
+                    token: "chat-secret".to_string(),
+                    installed_app_ids: Some(vec!["chat".to_string()]),
+                    agent_key: None,
+                },
+            ],
+            max_request_bytes: DEFAULT_HTTP_GATEWAY_MAX_REQUEST_BYTES,
+        };
+        assert!(config.authorize("wrong").is_none());
+        assert!(config.authorize("admin-secre").is_none());
+
+        let admin = config.authorize("admin-secret").unwrap();
+        assert!(admin.can_call(&"chat".to_string()));
+        assert!(admin.can_call(&"files".to_string()));
+
+        let chat = config.authorize("chat-secret").unwrap();
+        assert!(chat.can_call(&"chat".to_string()));
+        assert!(!chat.can_call(&"files".to_string()));
+        assert_eq!(chat.route(&"chat".to_string()), "chat");
+    }
+
+    #[test]
+    fn agent_tokens_route_to_the_app_of_their_agent() {
+        let agent_key = AgentPubKey::from_raw_36(vec![1; 36]);
+        let token = HttpGatewayToken {
+            token: "alice-secret".to_string(),
+            installed_app_ids: Some(vec!["chat".to_string()]),
+            agent_key: Some(agent_key.clone()),
+        };
+        assert!(token.can_call(&"chat".to_string()));
+        assert_eq!(
+            token.route(&"chat".to_string()),
+            agent_app_id("chat", &agent_key)
+        );
+    }
+}
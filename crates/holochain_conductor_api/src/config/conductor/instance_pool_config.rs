@@ -0,0 +1,33 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+/// Default number of idle instances kept for each zome.
+pub const DEFAULT_INSTANCE_POOL_MAX_IDLE: usize = 8;
+
+/// Keep a pool of ready wasm instances for each zome, so zome calls don't
+/// have to wait for an instance to be created.
+///
+/// Instances are created for every wasm zome of a DNA when it is
+/// registered, loaded at startup, or has its coordinators updated.
+/// An instance goes back to its zome's pool when a call returns, unless the
+/// pool already holds `max_idle` instances.
+/// Instances of coordinator zomes which have been updated are dropped.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq)]
+pub struct InstancePoolConfig {
+    /// How many instances of each zome to create ahead of the first call.
+    #[serde(default)]
+    pub prewarm: usize,
+    /// The most idle instances to keep for each zome.
+    /// Defaults to [`DEFAULT_INSTANCE_POOL_MAX_IDLE`].
+    #[serde(default)]
+    pub max_idle: Option<usize>,
+}
+
+impl InstancePoolConfig {
+    /// Get the most idle instances to keep for each zome.
+    pub fn max_idle(&self) -> usize {
+        self.max_idle
+            .unwrap_or(DEFAULT_INSTANCE_POOL_MAX_IDLE)
+            .max(self.prewarm)
+    }
+}
@@ -0,0 +1,53 @@
+use holo_hash::DnaHash;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// Choose the implementation of the network which the conductor's cells
+/// talk to each other over.
+///
+/// The conductor only uses the network through the `HolochainP2p` actor,
+/// so any backend which implements it can be used, and a backend which
+/// isn't listed here can be given to the `ConductorBuilder` directly.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NetworkBackendConfig {
+    /// The kitsune network, configured by the `network` section.
+    /// This is the default.
+    Kitsune,
+    /// A network which only reaches the other conductors in the same process
+    /// which use an in-memory network with the same name.
+    /// There is no gossip or transport, which makes it useful for tests.
+    InMemory {
+        /// The name of the network to join.
+        name: String,
+    },
+    /// An experimental network built on libp2p, using kademlia to find
+    /// peers, gossipsub to broadcast published ops and request/response
+    /// for gets. Every node holds all the data of the DNAs it has joined.
+    /// The conductor must be built with the `libp2p` feature.
+    Libp2p {
+        /// The addresses to listen on, e.g. `/ip4/0.0.0.0/tcp/0`.
+        #[serde(default)]
+        listen_addresses: Vec<String>,
+        /// The addresses of the peers to find other peers through,
+        /// ending with the peer's id, e.g. `/ip4/1.2.3.4/tcp/5678/p2p/12D3KooW...`.
+        #[serde(default)]
+        bootstrap_peers: Vec<String>,
+    },
+}
+
+impl Default for NetworkBackendConfig {
+    fn default() -> Self {
+        Self::Kitsune
+    }
+}
+
+/// A network backend for one DNA, which overrides the `network_backend`,
+/// e.g. to try the libp2p backend for a single DNA.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq)]
+pub struct DnaNetworkBackendConfig {
+    /// The hash of the DNA.
+    pub dna_hash: DnaHash,
+    /// The backend the DNA's cells use.
+    pub backend: NetworkBackendConfig,
+}
@@ -0,0 +1,41 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+/// Export the conductor's tracing spans to an [OpenTelemetry] collector,
+/// so a single user action can be followed through zome calls, workflows
+/// and requests to other nodes.
+///
+/// Spans are exported over OTLP/gRPC. The trace context is sent along
+/// with network requests, so the spans of other conductors exporting to
+/// the same collector join the same trace.
+///
+/// Which spans are recorded is still controlled by `RUST_LOG`.
+///
+/// [OpenTelemetry]: https://opentelemetry.io/
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq)]
+pub struct OpenTelemetryConfig {
+    /// The OTLP endpoint of the collector,
+    /// e.g. `http://localhost:4317`.
+    pub endpoint: String,
+    /// The service name the spans are reported under.
+    /// Defaults to `holochain`.
+    #[serde(default)]
+    pub service_name: Option<String>,
+    /// The fraction of traces to record, between 0 and 1.
+    /// Traces started by other nodes are recorded if they were recorded
+    /// there. Defaults to recording every trace.
+    #[serde(default)]
+    pub sample_ratio: Option<f64>,
+}
+
+impl OpenTelemetryConfig {
+    /// Get the service name the spans are reported under.
+    pub fn service_name(&self) -> &str {
+        self.service_name.as_deref().unwrap_or("holochain")
+    }
+
+    /// Get the fraction of traces to record.
+    pub fn sample_ratio(&self) -> f64 {
+        self.sample_ratio.unwrap_or(1.0).clamp(0.0, 1.0)
+    }
+}
@@ -0,0 +1,38 @@
+use serde::Deserialize;
+use serde::Serialize;
+use std::path::PathBuf;
+use url2::Url2;
+
+/// Run a relay in the conductor, which forwards traffic between peers that
+/// can't reach each other directly, e.g. because they are behind NATs, so a
+/// community can host its own relay without running a separate binary.
+///
+/// The relay has its own address, logged when the conductor starts and
+/// included in the relay stats. Peers use it by setting that address as the
+/// `relay_url` of a `direct_with_relay_fallback` proxy config.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq)]
+pub struct RelayConfig {
+    /// The address to bind the relay to. Defaults to
+    /// `kitsune-quic://0.0.0.0:0`, a random port, so set this to keep the
+    /// same address across restarts.
+    #[serde(default)]
+    pub bind_to: Option<Url2>,
+
+    /// The file to keep the relay's TLS certificate in, which is created if
+    /// it doesn't exist. The certificate's digest is part of the relay's
+    /// address, so without this the address changes on every restart.
+    /// The private key is stored unencrypted.
+    #[serde(default)]
+    pub tls_cert_path: Option<PathBuf>,
+
+    /// The peers the relay forwards for, by the certificate digest in their
+    /// network address, i.e. the host part of `kitsune-proxy://<digest>/...`.
+    /// Omit this to forward for any peer.
+    #[serde(default)]
+    pub allowed_clients: Option<Vec<String>>,
+
+    /// The most bytes the relay forwards for each peer per second.
+    /// Messages over the limit are refused. Omit this for no limit.
+    #[serde(default)]
+    pub max_bytes_per_second_per_client: Option<u64>,
+}
@@ -0,0 +1,257 @@
+use super::ConductorConfig;
+use holochain_p2p::kitsune_p2p::dependencies::kitsune_p2p_types::config::LIVE_TUNING_PARAMS;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// The outcome of comparing a freshly loaded [`ConductorConfig`] against the
+/// one a conductor is currently running with.
+///
+/// Settings are named by their path in the YAML config file,
+/// e.g. `network.tuning_params`.
+#[derive(Clone, Deserialize, Serialize, Default, Debug, PartialEq)]
+pub struct ConfigReloadReport {
+    /// Settings which changed and were applied to the running conductor.
+    pub applied: Vec<String>,
+    /// Settings which changed but will only take effect after a restart.
+    pub requires_restart: Vec<String>,
+}
+
+impl ConfigReloadReport {
+    /// True if nothing changed between the two configs.
+    pub fn is_empty(&self) -> bool {
+        self.applied.is_empty() && self.requires_restart.is_empty()
+    }
+}
+
+impl ConductorConfig {
+    /// Compare this (running) config against a newly loaded one, and sort
+    /// each changed setting into those which are safe to apply at runtime
+    /// and those which require a conductor restart.
+    ///
+    /// Admin interfaces can be added at runtime but not removed, so only a
+    /// strict addition of interfaces is considered safe.
+    /// Bootstrap services and the tuning params in [`LIVE_TUNING_PARAMS`]
+    /// are pushed into the running network, whereas every other tuning param
+    /// is baked into the transport or the gossip modules when they are
+    /// spawned, and is reported by name, e.g.
+    /// `network.tuning_params.tls_pin_peer_certs`.
+    pub fn reload_report(&self, new: &ConductorConfig) -> ConfigReloadReport {
+        let mut report = ConfigReloadReport::default();
+
+        let mut restart_if = |changed: bool, name: &str| {
+            if changed {
+                report.requires_restart.push(name.to_string());
+            }
+        };
+        restart_if(
+            self.environment_path != new.environment_path,
+            "environment_path",
+        );
+        restart_if(self.keystore != new.keystore, "keystore");
+        restart_if(self.dpki != new.dpki, "dpki");
+        restart_if(
+            self.network_backend != new.network_backend,
+            "network_backend",
+        );
+        restart_if(
+            self.dna_network_backends != new.dna_network_backends,
+            "dna_network_backends",
+        );
+        restart_if(
+            self.db_sync_strategy != new.db_sync_strategy,
+            "db_sync_strategy",
+        );
+        restart_if(self.db_encryption != new.db_encryption, "db_encryption");
+        restart_if(
+            self.dna_tuning_params != new.dna_tuning_params,
+            "dna_tuning_params",
+        );
+        restart_if(self.http_gateway != new.http_gateway, "http_gateway");
+        restart_if(
+            self.health_endpoint != new.health_endpoint,
+            "health_endpoint",
+        );
+        restart_if(self.open_telemetry != new.open_telemetry, "open_telemetry");
+        restart_if(self.audit_log != new.audit_log, "audit_log");
+        restart_if(self.instance_pool != new.instance_pool, "instance_pool");
+        restart_if(
+            self.entry_compression != new.entry_compression,
+            "entry_compression",
+        );
+        restart_if(self.relay != new.relay, "relay");
+
+        let old_network = self.network.clone().unwrap_or_default();
+        let new_network = new.network.clone().unwrap_or_default();
+        restart_if(
+            old_network.transport_pool != new_network.transport_pool,
+            "network.transport_pool",
+        );
+        restart_if(
+            old_network.network_type != new_network.network_type,
+            "network.network_type",
+        );
+        restart_if(
+            old_network.tls_cert_path != new_network.tls_cert_path,
+            "network.tls_cert_path",
+        );
+        let changed_params = old_network
+            .tuning_params
+            .changed_params(&new_network.tuning_params);
+        for param in &changed_params {
+            restart_if(
+                !LIVE_TUNING_PARAMS.contains(param),
+                &format!("network.tuning_params.{}", param),
+            );
+        }
+
+        if changed_params
+            .iter()
+            .any(|p| LIVE_TUNING_PARAMS.contains(p))
+        {
+            report.applied.push("network.tuning_params".to_string());
+        }
+        if old_network.bootstrap_service != new_network.bootstrap_service {
+            report.applied.push("network.bootstrap_service".to_string());
+        }
+        if old_network.bootstrap_services != new_network.bootstrap_services {
+            report
+                .applied
+                .push("network.bootstrap_services".to_string());
+        }
+        if self.log_filter != new.log_filter {
+            report.applied.push("log_filter".to_string());
+        }
+        if self.dht_pruning != new.dht_pruning {
+            report.applied.push("dht_pruning".to_string());
+        }
+        if self.zome_call_limits != new.zome_call_limits {
+            report.applied.push("zome_call_limits".to_string());
+        }
+        if self.chain_head_coordination != new.chain_head_coordination {
+            report.applied.push("chain_head_coordination".to_string());
+        }
+
+        let old_admin = self.admin_interfaces.clone().unwrap_or_default();
+        let new_admin = new.admin_interfaces.clone().unwrap_or_default();
+        if old_admin != new_admin {
+            if old_admin.iter().all(|c| new_admin.contains(c)) {
+                report.applied.push("admin_interfaces".to_string());
+            } else {
+                report.requires_restart.push("admin_interfaces".to_string());
+            }
+        }
+
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AdminInterfaceConfig;
+    use crate::config::InterfaceDriver;
+    use holochain_types::db::DbSyncStrategy;
+
+    fn admin(port: u16) -> AdminInterfaceConfig {
+        AdminInterfaceConfig {
+            driver: InterfaceDriver::websocket(port),
+        }
+    }
+
+    #[test]
+    fn identical_configs_report_nothing() {
+        let config = ConductorConfig::default();
+        assert!(config.reload_report(&config.clone()).is_empty());
+    }
+
+    #[test]
+    fn added_admin_interface_is_applied() {
+        let old = ConductorConfig {
+            admin_interfaces: Some(vec![admin(1234)]),
+            ..Default::default()
+        };
+        let new = ConductorConfig {
+            admin_interfaces: Some(vec![admin(1234), admin(5678)]),
+            ..Default::default()
+        };
+        let report = old.reload_report(&new);
+        assert_eq!(report.applied, vec!["admin_interfaces".to_string()]);
+        assert!(report.requires_restart.is_empty());
+
+        // Removing an interface can't be done live.
+        let report = new.reload_report(&old);
+        assert!(report.applied.is_empty());
+        assert_eq!(
+            report.requires_restart,
+            vec!["admin_interfaces".to_string()]
+        );
+    }
+
+    #[test]
+    fn dht_pruning_is_applied() {
+        let old = ConductorConfig::default();
+        let new = ConductorConfig {
+            dht_pruning: Some(Default::default()),
+            ..Default::default()
+        };
+        let report = old.reload_report(&new);
+        assert_eq!(report.applied, vec!["dht_pruning".to_string()]);
+        assert!(report.requires_restart.is_empty());
+    }
+
+    #[test]
+    fn network_settings_are_applied() {
+        let old = ConductorConfig::default();
+        let mut network = holochain_p2p::kitsune_p2p::KitsuneP2pConfig::default();
+        network.bootstrap_service = Some(url2::url2!("https://bootstrap.holo.host"));
+        let mut tuning_params = (*network.tuning_params).clone();
+        tuning_params.disable_publish = true;
+        tuning_params.gossip_max_batch_size = 1_000;
+        network.tuning_params = std::sync::Arc::new(tuning_params);
+        let new = ConductorConfig {
+            network: Some(network),
+            log_filter: Some("debug".to_string()),
+            ..Default::default()
+        };
+        let report = old.reload_report(&new);
+        assert_eq!(
+            report.applied,
+            vec![
+                "network.tuning_params".to_string(),
+                "network.bootstrap_service".to_string(),
+                "log_filter".to_string(),
+            ]
+        );
+        assert!(report.requires_restart.is_empty());
+    }
+
+    #[test]
+    fn restart_required_settings() {
+        let old = ConductorConfig::default();
+        let mut network = holochain_p2p::kitsune_p2p::KitsuneP2pConfig::default();
+        let mut tuning_params = (*network.tuning_params).clone();
+        tuning_params.disable_publish = true;
+        tuning_params.gossip_strategy = "none".to_string();
+        // Pinning is baked into the proxy endpoint when it is spawned.
+        tuning_params.tls_pin_peer_certs = !tuning_params.tls_pin_peer_certs;
+        network.tuning_params = std::sync::Arc::new(tuning_params);
+        network.tls_cert_path = Some("/tls/cert.pem".into());
+        let new = ConductorConfig {
+            db_sync_strategy: DbSyncStrategy::Resilient,
+            network: Some(network),
+            ..Default::default()
+        };
+        let report = old.reload_report(&new);
+        // The live params which changed are still applied.
+        assert_eq!(report.applied, vec!["network.tuning_params".to_string()]);
+        assert_eq!(
+            report.requires_restart,
+            vec![
+                "db_sync_strategy".to_string(),
+                "network.tls_cert_path".to_string(),
+                "network.tuning_params.gossip_strategy".to_string(),
+                "network.tuning_params.tls_pin_peer_certs".to_string(),
+            ]
+        );
+    }
+}
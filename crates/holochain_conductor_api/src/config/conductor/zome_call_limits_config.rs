@@ -0,0 +1,42 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+/// Limits on the resources a single zome call may use, so a buggy or
+/// hostile zome can't tie up the conductor.
+///
+/// A call which exceeds any limit is aborted, fails with a distinct
+/// `ZomeCallLimitExceeded` error, and is counted by the limit it hit.
+/// Each limit is only enforced if it is set, and applies to the zome
+/// function itself, not to the validation of what it commits.
+///
+/// Changes to these limits are applied to a running conductor when its
+/// config is reloaded.
+#[derive(Clone, Deserialize, Serialize, Default, Debug, PartialEq)]
+pub struct ZomeCallLimitsConfig {
+    /// Abort a call which runs for longer than this many milliseconds.
+    ///
+    /// The deadline is checked as the wasm runs, so a call blocked inside
+    /// a host function, e.g. waiting on the network, is only aborted once
+    /// that host function returns.
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+    /// Abort a call which executes more than this many wasm instructions.
+    #[serde(default)]
+    pub max_fuel: Option<u64>,
+    /// Don't let a zome's wasm memory grow beyond this many bytes, rounded
+    /// down to whole 64KiB wasm pages.
+    ///
+    /// The ceiling is set on each wasm instance when it is created, so growing
+    /// past it fails in the guest and the call which failed doing so is
+    /// reported as having exceeded its memory limit. A changed ceiling only
+    /// applies to instances created after the config is reloaded.
+    #[serde(default)]
+    pub max_memory_bytes: Option<u64>,
+}
+
+impl ZomeCallLimitsConfig {
+    /// Get the wall-clock timeout for a call, if there is one.
+    pub fn timeout(&self) -> Option<std::time::Duration> {
+        self.timeout_ms.map(std::time::Duration::from_millis)
+    }
+}
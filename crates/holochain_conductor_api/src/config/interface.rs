@@ -1,11 +1,14 @@
 use serde::Deserialize;
 use serde::Serialize;
+use std::net::IpAddr;
+use std::net::Ipv4Addr;
+use std::path::PathBuf;
 
 /// Information neeeded to spawn an admin interface
 #[derive(Clone, Deserialize, Serialize, Debug, PartialEq)]
 pub struct AdminInterfaceConfig {
-    /// By what means the interface will be exposed.
-    /// Currently the only option is a local websocket running on a configurable port.
+    /// By what means the interface will be exposed,
+    /// either a websocket on a configurable port or a local socket.
     pub driver: InterfaceDriver,
     // How long will this interface be accessible between authentications?
     // TODO: implement once we have authentication
@@ -28,14 +31,161 @@ pub enum InterfaceDriver {
     Websocket {
         /// The port on which to establish the WebsocketListener
         port: u16,
+        /// The address of the network interface to listen on,
+        /// e.g. `0.0.0.0` to listen on all of them.
+        /// Defaults to `127.0.0.1`, so only local clients can connect.
+        #[serde(default)]
+        bind_address: Option<IpAddr>,
+        /// The `Origin` headers to accept websocket connections from, where
+        /// `*` matches any origin. Browsers send the origin of the webpage
+        /// making the connection, so this stops any webpage from calling the
+        /// interface. Clients which send no `Origin` header are always accepted.
+        /// Defaults to accepting any origin.
+        #[serde(default)]
+        allowed_origins: Option<Vec<String>>,
+        /// How the interface checks that its clients are still connected.
+        /// Defaults to pinging each client every 30 seconds, and closing
+        /// connections which haven't been heard from in 90.
+        #[serde(default)]
+        heartbeat: Option<InterfaceHeartbeat>,
+        /// Whether an app interface only accepts zome calls signed by the
+        /// agent of the cell they call, i.e. `AppRequest::SignedZomeCall`.
+        /// Unsigned zome calls may be made as any agent, so this must be set
+        /// on interfaces which clients holding their own keys connect to.
+        /// Defaults to `false`. Admin interfaces ignore it.
+        #[serde(default)]
+        require_signed_zome_calls: bool,
+    },
+    /// An interface implemented via websockets over a local socket, which is
+    /// a Unix domain socket, or a named pipe on Windows, so no port is opened
+    /// and only clients on this machine can connect.
+    LocalSocket {
+        /// The path of the socket file, or the name of the pipe on Windows,
+        /// e.g. `\\.\pipe\holochain-admin`.
+        /// An existing socket at this path is replaced.
+        path: PathBuf,
+        /// The Unix file permissions of the socket, which control who can
+        /// connect to the interface.
+        /// Defaults to `0o600`, so only the user running the conductor can.
+        /// On Windows the pipe only lets the user running the conductor,
+        /// administrators and the system connect.
+        #[serde(default)]
+        mode: Option<u32>,
     },
 }
 
 impl InterfaceDriver {
-    /// Get the port for this driver.
+    /// A websocket interface on localhost accepting any origin.
+    pub fn websocket(port: u16) -> Self {
+        InterfaceDriver::Websocket {
+            port,
+            bind_address: None,
+            allowed_origins: None,
+            heartbeat: None,
+            require_signed_zome_calls: false,
+        }
+    }
+
+    /// A local socket interface which only the user running the conductor
+    /// can connect to.
+    pub fn local_socket(path: PathBuf) -> Self {
+        InterfaceDriver::LocalSocket { path, mode: None }
+    }
+
+    /// Get the port for this driver, which is 0 for local sockets.
     pub fn port(&self) -> u16 {
         match self {
-            InterfaceDriver::Websocket { port } => *port,
+            InterfaceDriver::Websocket { port, .. } => *port,
+            InterfaceDriver::LocalSocket { .. } => 0,
+        }
+    }
+
+    /// Get the address of the network interface this driver listens on.
+    pub fn bind_address(&self) -> IpAddr {
+        match self {
+            InterfaceDriver::Websocket { bind_address, .. } => {
+                bind_address.unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST))
+            }
+            InterfaceDriver::LocalSocket { .. } => IpAddr::V4(Ipv4Addr::LOCALHOST),
+        }
+    }
+
+    /// Get the origins this driver accepts connections from,
+    /// or `None` if it accepts any origin.
+    pub fn allowed_origins(&self) -> Option<&Vec<String>> {
+        match self {
+            InterfaceDriver::Websocket {
+                allowed_origins, ..
+            } => allowed_origins.as_ref(),
+            InterfaceDriver::LocalSocket { .. } => None,
+        }
+    }
+
+    /// Get how this driver checks that its clients are still connected.
+    /// Local sockets always use the default.
+    pub fn heartbeat(&self) -> InterfaceHeartbeat {
+        match self {
+            InterfaceDriver::Websocket { heartbeat, .. } => heartbeat.unwrap_or_default(),
+            InterfaceDriver::LocalSocket { .. } => InterfaceHeartbeat::default(),
+        }
+    }
+
+    /// Whether this driver only accepts signed zome calls.
+    /// Local sockets accept unsigned ones, as only local clients can connect.
+    pub fn require_signed_zome_calls(&self) -> bool {
+        match self {
+            InterfaceDriver::Websocket {
+                require_signed_zome_calls,
+                ..
+            } => *require_signed_zome_calls,
+            InterfaceDriver::LocalSocket { .. } => false,
+        }
+    }
+
+    /// Copy this driver with a different port,
+    /// e.g. the one the OS assigned in place of port 0.
+    /// Local sockets have no port, so are copied as they are.
+    pub fn with_port(&self, port: u16) -> Self {
+        match self.clone() {
+            InterfaceDriver::Websocket {
+                bind_address,
+                allowed_origins,
+                heartbeat,
+                require_signed_zome_calls,
+                ..
+            } => InterfaceDriver::Websocket {
+                port,
+                bind_address,
+                allowed_origins,
+                heartbeat,
+                require_signed_zome_calls,
+            },
+            driver @ InterfaceDriver::LocalSocket { .. } => driver,
+        }
+    }
+}
+
+/// How an interface checks that its clients are still connected, so the
+/// connections of clients which vanished without closing them, such as
+/// those on flaky networks, are cleaned up.
+#[derive(Clone, Copy, Deserialize, Serialize, Debug, PartialEq, Eq)]
+pub struct InterfaceHeartbeat {
+    /// Seconds between the pings sent to each client, or 0 to send none.
+    pub interval_s: u64,
+    /// Seconds without hearing from a client, not even a pong, after which
+    /// its connection is closed.
+    pub timeout_s: u64,
+}
+
+impl Default for InterfaceHeartbeat {
+    fn default() -> Self {
+        Self {
+            interval_s: 30,
+            timeout_s: 90,
         }
     }
 }
+
+/// The permissions a local socket is created with by default,
+/// which only let the user running the conductor connect.
+pub const DEFAULT_LOCAL_SOCKET_MODE: u32 = 0o600;
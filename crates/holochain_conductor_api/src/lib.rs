@@ -2,6 +2,7 @@
 
 mod admin_interface;
 mod app_interface;
+pub mod audit_log;
 pub mod config;
 pub mod signal_subscription;
 pub mod state_dump;
@@ -1,16 +1,22 @@
 use holochain_serialized_bytes::prelude::*;
 use holochain_types::app::InstalledAppId;
 use holochain_zome_types::cell::CellId;
+use holochain_zome_types::signal::AppSignal;
+use holochain_zome_types::zome::ZomeName;
 use std::collections::HashMap;
 
 /// Declares updated Signal subscription settings for an App.
 /// This message is part of the AppInterfaceApi
+///
+/// The settings apply to the connection the message is sent on, and replace
+/// any previous settings for the app on that connection. Connections start
+/// out subscribed to all signals.
 #[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, SerializedBytes)]
 pub struct SignalSubscription {
     /// The app for which to manage subscription
-    installed_app_id: InstalledAppId,
+    pub installed_app_id: InstalledAppId,
     /// Fine-grained per-cell filters
-    filters: SignalFilterSet,
+    pub filters: SignalFilterSet,
 }
 
 /// Associate a SignalFilter with each Cell in an App.
@@ -46,11 +52,38 @@ impl SignalFilterSet {
     pub fn block_all() -> Self {
         SignalFilterSet::Include(HashMap::new())
     }
+
+    /// Whether a signal emitted by a zome in a cell comes through
+    pub fn allows(&self, cell_id: &CellId, zome_name: &ZomeName, signal: &AppSignal) -> bool {
+        let matches = |filters: &HashMap<CellId, SignalFilter>| {
+            filters
+                .get(cell_id)
+                .map_or(false, |filter| filter.matches(zome_name, signal))
+        };
+        match self {
+            SignalFilterSet::Include(filters) => matches(filters),
+            SignalFilterSet::Exclude(filters) => !matches(filters),
+        }
+    }
 }
 
 /// Specifies fine-grained filter controls for the signals
+///
+/// The name of a signal is the `type` field of a signal which is a map, as
+/// serde writes enums with `#[serde(tag = "type")]`, or the variant of an
+/// enum written with serde's default tagging.
 #[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, SerializedBytes)]
-pub struct SignalFilter;
+pub struct SignalFilter {
+    /// Only match signals emitted by these zomes.
+    /// `None` matches every zome.
+    #[serde(default)]
+    pub zome_names: Option<Vec<ZomeName>>,
+    /// Only match signals whose name matches one of these patterns,
+    /// in which `*` matches any run of characters.
+    /// `None` matches every signal, including signals without a name.
+    #[serde(default)]
+    pub signal_names: Option<Vec<String>>,
+}
 
 impl Default for SignalFilter {
     fn default() -> Self {
@@ -62,6 +95,153 @@ impl Default for SignalFilter {
 impl SignalFilter {
     /// A passthrough filter which filters nothing
     pub fn empty() -> Self {
-        SignalFilter
+        SignalFilter {
+            zome_names: None,
+            signal_names: None,
+        }
+    }
+
+    /// Whether a signal emitted by a zome matches this filter
+    pub fn matches(&self, zome_name: &ZomeName, signal: &AppSignal) -> bool {
+        if let Some(zome_names) = &self.zome_names {
+            if !zome_names.contains(zome_name) {
+                return false;
+            }
+        }
+        match &self.signal_names {
+            None => true,
+            Some(patterns) => signal_name(signal).map_or(false, |name| {
+                patterns
+                    .iter()
+                    .any(|pattern| pattern_matches(pattern, &name))
+            }),
+        }
+    }
+}
+
+/// The shapes of signal which have a name.
+#[derive(Debug, serde::Deserialize)]
+#[serde(untagged)]
+enum NamedSignal {
+    Tagged {
+        #[serde(rename = "type")]
+        name: String,
+    },
+    UnitVariant(String),
+    Variant(HashMap<String, serde::de::IgnoredAny>),
+}
+
+fn signal_name(signal: &AppSignal) -> Option<String> {
+    match signal.clone().into_inner().decode().ok()? {
+        NamedSignal::Tagged { name } | NamedSignal::UnitVariant(name) => Some(name),
+        NamedSignal::Variant(variant) if variant.len() == 1 => variant.into_keys().next(),
+        NamedSignal::Variant(_) => None,
+    }
+}
+
+/// Match a name against a pattern in which `*` matches any run of characters.
+fn pattern_matches(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    // There is always a first part, which the name must start with.
+    let first = parts.next().unwrap_or_default();
+    let mut rest = match name.strip_prefix(first) {
+        Some(rest) => rest,
+        None => return false,
+    };
+    let mut parts: Vec<_> = parts.collect();
+    // Without a `*` the whole name must match.
+    let last = match parts.pop() {
+        Some(last) => last,
+        None => return rest.is_empty(),
+    };
+    for part in parts {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use holochain_zome_types::ExternIO;
+
+    #[derive(serde::Serialize, Debug)]
+    #[serde(tag = "type")]
+    enum Tagged {
+        PostCreated { id: u32 },
+    }
+
+    #[derive(serde::Serialize, Debug)]
+    enum External {
+        PostDeleted,
+        PostUpdated(u32),
+    }
+
+    fn signal<T: serde::Serialize + std::fmt::Debug>(t: T) -> AppSignal {
+        AppSignal::new(ExternIO::encode(t).unwrap())
+    }
+
+    #[test]
+    fn signals_are_named_by_their_tag_or_variant() {
+        assert_eq!(
+            signal_name(&signal(Tagged::PostCreated { id: 1 })).as_deref(),
+            Some("PostCreated")
+        );
+        assert_eq!(
+            signal_name(&signal(External::PostDeleted)).as_deref(),
+            Some("PostDeleted")
+        );
+        assert_eq!(
+            signal_name(&signal(External::PostUpdated(1))).as_deref(),
+            Some("PostUpdated")
+        );
+        assert_eq!(signal_name(&signal(42)), None);
+    }
+
+    #[test]
+    fn patterns_match_with_wildcards() {
+        assert!(pattern_matches("Post*", "PostCreated"));
+        assert!(pattern_matches("*Created", "PostCreated"));
+        assert!(pattern_matches("P*t*d", "PostCreated"));
+        assert!(pattern_matches("*", ""));
+        assert!(pattern_matches("PostCreated", "PostCreated"));
+        assert!(!pattern_matches("Post", "PostCreated"));
+        assert!(!pattern_matches("*Deleted", "PostCreated"));
+        assert!(!pattern_matches("Po*ost", "Post"));
+    }
+
+    #[test]
+    fn filter_sets_include_or_exclude_matching_signals() {
+        let cell_id = CellId::new(
+            holo_hash::DnaHash::from_raw_32(vec![1; 32]),
+            holo_hash::AgentPubKey::from_raw_32(vec![2; 32]),
+        );
+        let other_cell_id = CellId::new(
+            holo_hash::DnaHash::from_raw_32(vec![3; 32]),
+            holo_hash::AgentPubKey::from_raw_32(vec![2; 32]),
+        );
+        let posts: ZomeName = "posts".into();
+        let created = signal(Tagged::PostCreated { id: 1 });
+        let filter = SignalFilter {
+            zome_names: Some(vec![posts.clone()]),
+            signal_names: Some(vec!["*Created".into()]),
+        };
+
+        let include = SignalFilterSet::Include([(cell_id.clone(), filter.clone())].into());
+        assert!(include.allows(&cell_id, &posts, &created));
+        assert!(!include.allows(&cell_id, &"comments".into(), &created));
+        assert!(!include.allows(&cell_id, &posts, &signal(External::PostDeleted)));
+        assert!(!include.allows(&other_cell_id, &posts, &created));
+
+        let exclude = SignalFilterSet::Exclude([(cell_id.clone(), filter)].into());
+        assert!(!exclude.allows(&cell_id, &posts, &created));
+        assert!(exclude.allows(&cell_id, &posts, &signal(External::PostDeleted)));
+        assert!(exclude.allows(&other_cell_id, &posts, &created));
+
+        assert!(SignalFilterSet::allow_all().allows(&cell_id, &posts, &created));
+        assert!(!SignalFilterSet::block_all().allows(&cell_id, &posts, &created));
     }
 }
@@ -2,15 +2,23 @@ use holo_hash::AgentPubKey;
 use holo_hash::DnaHash;
 use holochain_state::source_chain::SourceChainJsonDump;
 use holochain_types::dht_op::DhtOp;
+use holochain_zome_types::cell::CellId;
+use holochain_zome_types::zome::ZomeName;
+use holochain_zome_types::Timestamp;
 use serde::Deserialize;
 use serde::Serialize;
 use std::sync::Arc;
 
+use crate::config::InterfaceDriver;
+use crate::signal_subscription::SignalSubscription;
+
 #[derive(Serialize, Deserialize)]
 pub struct JsonDump {
     pub peer_dump: P2pAgentsDump,
     pub source_chain_dump: SourceChainJsonDump,
     pub integration_dump: IntegrationStateDump,
+    #[serde(default)]
+    pub init_status: CellInitStatus,
 }
 
 #[derive(Serialize, Clone, Debug, Deserialize)]
@@ -18,6 +26,43 @@ pub struct FullStateDump {
     pub peer_dump: P2pAgentsDump,
     pub source_chain_dump: SourceChainJsonDump,
     pub integration_dump: FullIntegrationStateDump,
+    #[serde(default)]
+    pub init_status: CellInitStatus,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "status", rename_all = "snake_case")]
+/// Whether the init callbacks of a cell's zomes have run.
+/// Init runs on the first zome call to the cell, and other calls
+/// wait for it to finish.
+pub enum CellInitStatus {
+    /// No zome call has needed init to run yet.
+    NotRun,
+    /// Init is running, or waiting to be retried.
+    Running,
+    /// Every zome's init passed.
+    Initialized,
+    /// A zome's init failed, and will run again on the next zome call.
+    Failed {
+        /// The zome whose init failed.
+        zome_name: ZomeName,
+        /// Why it failed.
+        reason: String,
+    },
+    /// A zome's init still had unresolved dependencies after being retried,
+    /// and will run again on the next zome call.
+    UnresolvedDependencies {
+        /// The zome whose init had unresolved dependencies.
+        zome_name: ZomeName,
+        /// How many times init was run.
+        attempts: u32,
+    },
+}
+
+impl Default for CellInitStatus {
+    fn default() -> Self {
+        Self::NotRun
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -41,6 +86,63 @@ pub struct IntegrationStateDump {
     pub integrated: usize,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+/// How many ops a cell has published and how many its conductor has
+/// integrated for the cell's DNA.
+/// Once every conductor running a DNA has integrated all the ops published
+/// by every cell of that DNA, the conductors are consistent.
+pub struct CellOpCounts {
+    /// The cell these counts are for.
+    pub cell_id: CellId,
+    /// Ops authored by the cell which are published to the DHT.
+    /// Ops for private entries are not counted.
+    pub published: usize,
+    /// Ops that are integrated in the DHT database of the cell's DNA.
+    /// This includes rejected.
+    pub integrated: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+/// The clients connected to one of the conductor's interfaces.
+pub struct InterfaceStats {
+    /// The interface, with the port the OS assigned in place of port 0.
+    pub driver: InterfaceDriver,
+    /// Whether this is an admin interface rather than an app interface.
+    pub admin: bool,
+    /// How many clients are connected to the interface.
+    /// Connections which stop answering heartbeat pings are closed, and
+    /// no longer counted.
+    pub active_connections: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+/// The settings of one of the conductor's app interfaces.
+pub struct AppInterfaceInfo {
+    /// The interface, with the port the OS assigned in place of port 0.
+    pub driver: InterfaceDriver,
+    /// The signal subscription settings for each app.
+    pub signal_subscriptions:
+        std::collections::HashMap<holochain_types::app::InstalledAppId, SignalSubscription>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+/// A long running task of the conductor.
+pub struct TaskInfo {
+    /// What the task does, e.g. the workflow a queue consumer runs.
+    pub name: String,
+    /// The cell the task is critical to, if any.
+    pub cell_id: Option<CellId>,
+    /// When the task was started.
+    pub started_at: Timestamp,
+    /// When the task last finished a run of its work, if it reports its
+    /// progress and has finished one.
+    pub last_progress: Option<Timestamp>,
+    /// When the task started the run of its work it's on, if it's on one.
+    pub working_since: Option<Timestamp>,
+    /// Whether the task has stalled, and is being aborted.
+    pub stalled: bool,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 /// A full view of the DHT shard of the Cell.
 /// Ops start in the validation limbo then proceed
@@ -101,7 +203,29 @@ impl std::fmt::Display for JsonDump {
             "Records authored: {}, Ops published: {}",
             s.records.len(),
             s.published_ops_count
-        )
+        )?;
+        writeln!(f, "Init status: {}", self.init_status)
+    }
+}
+
+impl std::fmt::Display for CellInitStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotRun => write!(f, "not run"),
+            Self::Running => write!(f, "running"),
+            Self::Initialized => write!(f, "initialized"),
+            Self::Failed { zome_name, reason } => {
+                write!(f, "failed in zome {}: {}", zome_name, reason)
+            }
+            Self::UnresolvedDependencies {
+                zome_name,
+                attempts,
+            } => write!(
+                f,
+                "unresolved dependencies in zome {} after {} attempts",
+                zome_name, attempts
+            ),
+        }
     }
 }
 
@@ -602,6 +602,8 @@ pub enum EntryType {
     CapClaim,
     /// A Capability grant.
     CapGrant,
+    /// A chunk of a blob.
+    Chunk,
 }
 
 impl EntryType {
@@ -611,6 +613,7 @@ impl EntryType {
             EntryType::App(t) => t.visibility(),
             EntryType::CapClaim => &EntryVisibility::Private,
             EntryType::CapGrant => &EntryVisibility::Private,
+            EntryType::Chunk => &EntryVisibility::Public,
         }
     }
 }
@@ -622,6 +625,7 @@ impl std::fmt::Display for EntryType {
             EntryType::App(aet) => writeln!(f, "App({:?}, {:?})", aet.id(), aet.visibility()),
             EntryType::CapClaim => writeln!(f, "CapClaim"),
             EntryType::CapGrant => writeln!(f, "CapGrant"),
+            EntryType::Chunk => writeln!(f, "Chunk"),
         }
     }
 }
@@ -20,8 +20,10 @@ use holo_hash::HashableContentBytes;
 use holochain_serialized_bytes::prelude::*;
 
 mod app_entry_bytes;
+mod chunk;
 mod error;
 pub use app_entry_bytes::*;
+pub use chunk::*;
 pub use error::*;
 
 /// Entries larger than this number of bytes cannot be created
@@ -99,6 +101,8 @@ pub enum Entry {
     /// The capability grant system entry which allows granting of application defined
     /// capabilities
     CapGrant(CapGrantEntry),
+    /// A chunk of a blob which is too large for a single entry
+    Chunk(Chunk),
 }
 
 impl Entry {
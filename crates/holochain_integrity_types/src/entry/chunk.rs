@@ -0,0 +1,63 @@
+use holo_hash::EntryHash;
+use holochain_serialized_bytes::prelude::*;
+
+/// Chunks larger than this number of bytes cannot be created.
+/// This is well under the [`ENTRY_SIZE_LIMIT`](super::ENTRY_SIZE_LIMIT), so
+/// a blob is spread over many authorities rather than a few huge entries.
+pub const CHUNK_SIZE_LIMIT: usize = 1000 * 1000; // 1MB
+
+/// A piece of a blob which is too large for a single entry.
+///
+/// Chunks are committed as [`Entry::Chunk`](super::Entry::Chunk), so they
+/// are addressed by their content and identical chunks share an entry hash.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct Chunk(#[serde(with = "serde_bytes")] pub Vec<u8>);
+
+impl Chunk {
+    /// Split a blob into chunks of at most [`CHUNK_SIZE_LIMIT`] bytes.
+    pub fn split(blob: &[u8]) -> impl Iterator<Item = Self> + '_ {
+        blob.chunks(CHUNK_SIZE_LIMIT)
+            .map(|chunk| Self(chunk.to_vec()))
+    }
+
+    /// The bytes of this chunk.
+    pub fn bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Consumes self for the bytes of this chunk.
+    pub fn into_inner(self) -> Vec<u8> {
+        self.0
+    }
+}
+
+/// Everything needed to read a chunked blob back.
+///
+/// This is returned when a blob is written, and is small enough for the app
+/// to store in its own entries.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, SerializedBytes)]
+pub struct BlobManifest {
+    /// The size of the whole blob in bytes.
+    pub size: u64,
+    /// The hashes of the blob's chunks, in order.
+    pub chunks: Vec<EntryHash>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_chunks_join_back_into_the_blob() {
+        let blob: Vec<u8> = (0..CHUNK_SIZE_LIMIT * 2 + 3).map(|i| i as u8).collect();
+        let chunks: Vec<_> = Chunk::split(&blob).collect();
+        assert_eq!(chunks.len(), 3);
+        assert!(chunks.iter().all(|c| c.bytes().len() <= CHUNK_SIZE_LIMIT));
+        assert_eq!(chunks[2].bytes().len(), 3);
+
+        let joined: Vec<u8> = chunks.into_iter().flat_map(Chunk::into_inner).collect();
+        assert_eq!(joined, blob);
+        assert_eq!(Chunk::split(&[]).count(), 0);
+    }
+}
@@ -394,6 +394,11 @@ impl EntryCreationAction {
         matches!(self.entry_type(), EntryType::CapGrant)
     }
 
+    /// Returns `true` if this action creates an [`EntryType::Chunk`] [`Entry`].
+    pub fn is_chunk_entry_type(&self) -> bool {
+        matches!(self.entry_type(), EntryType::Chunk)
+    }
+
     /// Get the [`ActionType`] for this.
     pub fn action_type(&self) -> ActionType {
         match self {
@@ -659,6 +664,9 @@ pub enum OpActivity<UnitType, LT> {
     /// This operation registers the [`Action`] for a
     /// Capability Grant to the author's chain.
     CreateCapGrant(EntryHash),
+    /// This operation registers the [`Action`] for a
+    /// chunk of a blob to the author's chain.
+    CreateChunk(EntryHash),
     /// This operation registers the [`Action`] for an
     /// updated app defined entry type to the author's chain.
     UpdateEntry {
@@ -717,6 +725,16 @@ pub enum OpActivity<UnitType, LT> {
         /// The hash of the original Capability Grant.
         original_entry_hash: EntryHash,
     },
+    /// This operation registers the [`Action`] for an
+    /// updated chunk of a blob to the author's chain.
+    UpdateChunk {
+        /// The hash of the newly created chunk.
+        entry_hash: EntryHash,
+        /// The hash of the original chunk's [`Action`].
+        original_action_hash: ActionHash,
+        /// The hash of the original chunk.
+        original_entry_hash: EntryHash,
+    },
     /// This operation registers the [`Action`] for a
     /// deleted app defined entry type to the author's chain.
     DeleteEntry {
@@ -928,6 +946,14 @@ where
         /// The hash of the deleted Capability Grant.
         original_entry_hash: EntryHash,
     },
+    /// This operation registers a deletion to a
+    /// chunk of a blob.
+    Chunk {
+        /// The hash of the deleted chunk's [`Action`].
+        original_action_hash: ActionHash,
+        /// The hash of the deleted chunk.
+        original_entry_hash: EntryHash,
+    },
 }
 
 /// Allows a [`EntryCreationAction`] to hash the same bytes as
@@ -53,7 +53,9 @@ pub trait HolochainP2pDnaT {
     /// Invoke a zome function on a remote node (if you have been granted the capability).
     /// This is a fire-and-forget operation, a best effort will be made
     /// to forward the signal, but if the conductor network is overworked
-    /// it may decide not to deliver some of the signals.
+    /// and `drop_at_limit` is set it may decide not to deliver some of
+    /// the signals. Otherwise this waits for the network to take them.
+    #[allow(clippy::too_many_arguments)]
     async fn remote_signal(
         &self,
         from_agent: AgentPubKey,
@@ -62,6 +64,7 @@ pub trait HolochainP2pDnaT {
         fn_name: FunctionName,
         cap: Option<CapSecret>,
         payload: ExternIO,
+        drop_at_limit: bool,
     ) -> actor::HolochainP2pResult<()>;
 
     /// Publish data to the correct neighborhood.
@@ -131,6 +134,13 @@ pub trait HolochainP2pDnaT {
         dht_hash: holo_hash::AnyDhtHash,
     ) -> actor::HolochainP2pResult<bool>;
 
+    /// The known agents whose storage arcs cover a basis, nearest first.
+    async fn authorities_for_basis(
+        &self,
+        basis: holo_hash::AnyDhtHash,
+        limit: u32,
+    ) -> actor::HolochainP2pResult<Vec<AgentPubKey>>;
+
     /// Messages between agents driving a countersigning session.
     async fn countersigning_session_negotiation(
         &self,
@@ -199,7 +209,8 @@ impl HolochainP2pDnaT for HolochainP2pDna {
     /// Invoke a zome function on a remote node (if you have been granted the capability).
     /// This is a fire-and-forget operation, a best effort will be made
     /// to forward the signal, but if the conductor network is overworked
-    /// it may decide not to deliver some of the signals.
+    /// and `drop_at_limit` is set it may decide not to deliver some of
+    /// the signals. Otherwise this waits for the network to take them.
     async fn remote_signal(
         &self,
         from_agent: AgentPubKey,
@@ -208,6 +219,7 @@ impl HolochainP2pDnaT for HolochainP2pDna {
         fn_name: FunctionName,
         cap: Option<CapSecret>,
         payload: ExternIO,
+        drop_at_limit: bool,
     ) -> actor::HolochainP2pResult<()> {
         self.sender
             .remote_signal(
@@ -218,6 +230,7 @@ impl HolochainP2pDnaT for HolochainP2pDna {
                 fn_name,
                 cap,
                 payload,
+                drop_at_limit,
             )
             .await
     }
@@ -335,6 +348,16 @@ impl HolochainP2pDnaT for HolochainP2pDna {
             .await
     }
 
+    async fn authorities_for_basis(
+        &self,
+        basis: holo_hash::AnyDhtHash,
+        limit: u32,
+    ) -> actor::HolochainP2pResult<Vec<AgentPubKey>> {
+        self.sender
+            .authorities_for_basis((*self.dna_hash).clone(), basis, limit)
+            .await
+    }
+
     async fn countersigning_session_negotiation(
         &self,
         agents: Vec<AgentPubKey>,
@@ -4,6 +4,19 @@ use crate::event::*;
 mod actor;
 use actor::*;
 
+mod bandwidth;
+mod in_memory;
+pub use in_memory::InMemoryNetwork;
+#[cfg(feature = "libp2p")]
+mod libp2p;
+#[cfg(feature = "libp2p")]
+pub use self::libp2p::Libp2pConfig;
+mod network_switch;
+mod per_dna;
+mod publish_fanout;
+mod rate_limit;
+mod routing;
+
 /// Spawn a new HolochainP2p actor.
 /// Conductor will call this on initialization.
 pub async fn spawn_holochain_p2p(
@@ -30,3 +43,79 @@ pub async fn spawn_holochain_p2p(
 
     Ok((sender, evt_recv))
 }
+
+/// Spawn a new HolochainP2p actor on an in-memory network, which only
+/// reaches the other conductors in this process on the same network.
+pub async fn spawn_in_memory_holochain_p2p(
+    network: InMemoryNetwork,
+) -> HolochainP2pResult<(
+    ghost_actor::GhostSender<HolochainP2p>,
+    HolochainP2pEventReceiver,
+)> {
+    let (evt_send, evt_recv) = futures::channel::mpsc::channel(10);
+
+    let builder = ghost_actor::actor_builder::GhostActorBuilder::new();
+
+    let sender = builder
+        .channel_factory()
+        .create_channel::<HolochainP2p>()
+        .await?;
+
+    tokio::task::spawn(builder.spawn(in_memory::InMemoryActor::new(network, evt_send)));
+
+    Ok((sender, evt_recv))
+}
+
+/// Spawn a new HolochainP2p actor on the experimental libp2p backend.
+#[cfg(feature = "libp2p")]
+pub async fn spawn_libp2p_holochain_p2p(
+    config: Libp2pConfig,
+) -> HolochainP2pResult<(
+    ghost_actor::GhostSender<HolochainP2p>,
+    HolochainP2pEventReceiver,
+)> {
+    let (evt_send, evt_recv) = futures::channel::mpsc::channel(10);
+
+    let builder = ghost_actor::actor_builder::GhostActorBuilder::new();
+
+    let sender = builder
+        .channel_factory()
+        .create_channel::<HolochainP2p>()
+        .await?;
+
+    tokio::task::spawn(builder.spawn(self::libp2p::Libp2pActor::new(config, evt_send)?));
+
+    Ok((sender, evt_recv))
+}
+
+/// Spawn a HolochainP2p actor which uses the other backends for the dnas
+/// listed with them, and the default backend for every other dna.
+pub async fn spawn_per_dna_holochain_p2p(
+    default: (
+        ghost_actor::GhostSender<HolochainP2p>,
+        HolochainP2pEventReceiver,
+    ),
+    others: Vec<(
+        Vec<holo_hash::DnaHash>,
+        (
+            ghost_actor::GhostSender<HolochainP2p>,
+            HolochainP2pEventReceiver,
+        ),
+    )>,
+) -> HolochainP2pResult<(
+    ghost_actor::GhostSender<HolochainP2p>,
+    HolochainP2pEventReceiver,
+)> {
+    let (evt_send, evt_recv) = futures::channel::mpsc::channel(10);
+
+    let builder = ghost_actor::actor_builder::GhostActorBuilder::new();
+
+    let sender = builder
+        .channel_factory()
+        .create_channel::<HolochainP2p>()
+        .await?;
+
+    tokio::task::spawn(builder.spawn(per_dna::PerDnaActor::new(default, others, evt_send)));
+
+    Ok((sender, evt_recv))
+}
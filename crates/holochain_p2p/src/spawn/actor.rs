@@ -9,6 +9,12 @@ use kitsune_p2p::event::*;
 use kitsune_p2p::KOp;
 use kitsune_p2p::KitsuneOpData;
 
+use super::bandwidth::BandwidthBudgets;
+use super::network_switch::NetworkSwitch;
+use super::publish_fanout::PublishFanout;
+use super::rate_limit::PeerQuotas;
+use super::rate_limit::RequestKind;
+use super::routing;
 use crate::types::AgentPubKeyExt;
 
 use ghost_actor::dependencies::tracing;
@@ -242,6 +248,18 @@ impl WrapEvtSender {
         )
     }
 
+    fn fetch_dna(
+        &self,
+        dna_hash: DnaHash,
+        to_agent: AgentPubKey,
+        requested: DnaHash,
+    ) -> impl Future<Output = HolochainP2pResult<FetchDnaResponse>> + 'static + Send {
+        timing_trace!(
+            { self.0.fetch_dna(dna_hash, to_agent, requested) },
+            "(hp2p:handle) fetch_dna",
+        )
+    }
+
     fn validation_receipt_received(
         &self,
         dna_hash: DnaHash,
@@ -321,16 +339,116 @@ impl WrapEvtSender {
             "(hp2p:handle) countersigning_session_negotiation"
         )
     }
+
+    fn queue_remote_signal(
+        &self,
+        dna_hash: DnaHash,
+        to_agent_list: Vec<AgentPubKey>,
+        payload: Vec<u8>,
+    ) -> impl Future<Output = HolochainP2pResult<()>> + 'static + Send {
+        timing_trace!(
+            { self.0.queue_remote_signal(dna_hash, to_agent_list, payload) },
+            "(hp2p:handle) queue_remote_signal"
+        )
+    }
+
+    fn take_queued_remote_signals(
+        &self,
+        dna_hash: DnaHash,
+    ) -> impl Future<Output = HolochainP2pResult<Vec<QueuedRemoteSignal>>> + 'static + Send {
+        timing_trace!(
+            { self.0.take_queued_remote_signals(dna_hash) },
+            "(hp2p:handle) take_queued_remote_signals"
+        )
+    }
+}
+
+/// Send the remote signals which were queued for a DNA while the network
+/// was disabled. Like any other remote signal, a signal that can't be
+/// delivered is dropped.
+async fn send_queued_remote_signals(
+    evt_sender: WrapEvtSender,
+    kitsune_p2p: ghost_actor::GhostSender<kitsune_p2p::actor::KitsuneP2p>,
+    tuning_params: kitsune_p2p_types::config::KitsuneP2pTuningParams,
+    dna_hash: DnaHash,
+) {
+    let space = dna_hash.to_kitsune();
+    let queued = match evt_sender.take_queued_remote_signals(dna_hash).await {
+        Ok(queued) => queued,
+        Err(err) => {
+            tracing::warn!(?err, "failed to take the queued remote signals");
+            return;
+        }
+    };
+    for QueuedRemoteSignal {
+        to_agent_list,
+        payload,
+    } in queued
+    {
+        let to_agent_list = to_agent_list
+            .into_iter()
+            .map(|a| a.into_kitsune())
+            .collect();
+        let timeout = tuning_params.implicit_timeout();
+        if let Err(err) = kitsune_p2p
+            .targeted_broadcast(space.clone(), to_agent_list, timeout, payload, false)
+            .await
+        {
+            tracing::warn!(?err, "failed to send a queued remote signal");
+        }
+    }
+}
+
+/// Decode the responses to an rpc_multi call. A response that fails to decode
+/// can only come from a faulty or malicious peer, so rather than failing the
+/// whole call it is dropped, and the peer is reported to kitsune so it's less
+/// likely to be chosen again.
+async fn decode_rpc_multi_responses<T>(
+    kitsune_p2p: &ghost_actor::GhostSender<kitsune_p2p::actor::KitsuneP2p>,
+    space: Arc<kitsune_p2p::KitsuneSpace>,
+    result: Vec<kitsune_p2p::actor::RpcMultiResponse>,
+) -> Vec<T>
+where
+    T: TryFrom<SerializedBytes, Error = holochain_serialized_bytes::SerializedBytesError>,
+{
+    let mut out = Vec::new();
+    for kitsune_p2p::actor::RpcMultiResponse { agent, response } in result {
+        match SerializedBytes::from(UnsafeBytes::from(response)).try_into() {
+            Ok(r) => out.push(r),
+            Err(err) => {
+                tracing::warn!(?err, ?agent, "dropping invalid response from peer");
+                if let Err(err) = kitsune_p2p
+                    .record_validation_failure(space.clone(), agent)
+                    .await
+                {
+                    tracing::warn!(?err, "failed to record validation failure");
+                }
+            }
+        }
+    }
+    out
 }
 
 pub(crate) struct HolochainP2pActor {
     tuning_params: kitsune_p2p_types::config::KitsuneP2pTuningParams,
     evt_sender: WrapEvtSender,
     kitsune_p2p: ghost_actor::GhostSender<kitsune_p2p::actor::KitsuneP2p>,
+    bandwidth: Arc<BandwidthBudgets>,
+    quotas: PeerQuotas,
+    publish_fanout: PublishFanout,
+    network: Arc<NetworkSwitch>,
+    /// The DNAs which have had remote signals queued since the network
+    /// was disabled.
+    queued_signal_dnas: HashSet<DnaHash>,
 }
 
 impl ghost_actor::GhostControlHandler for HolochainP2pActor {}
 
+/// While the network is disabled gets return nothing, so only local data is used.
+fn offline_get<T: 'static + Send>() -> HolochainP2pHandlerResult<Vec<T>> {
+    Ok(async move { Ok(Vec::new()) }.boxed().into())
+}
+
 impl HolochainP2pActor {
     /// constructor
     pub async fn new(
@@ -347,6 +465,11 @@ impl HolochainP2pActor {
         channel_factory.attach_receiver(kitsune_p2p_events).await?;
 
         Ok(Self {
+            bandwidth: Arc::new(BandwidthBudgets::new(tuning_params.clone())),
+            quotas: PeerQuotas::new(tuning_params.clone()),
+            publish_fanout: PublishFanout::new(tuning_params.clone()),
+            network: Arc::new(NetworkSwitch::new()),
+            queued_signal_dnas: HashSet::new(),
             tuning_params,
             evt_sender: WrapEvtSender(evt_sender),
             kitsune_p2p,
@@ -491,6 +614,24 @@ impl HolochainP2pActor {
         .into())
     }
 
+    /// receiving an incoming fetch_dna request from a remote node
+    fn handle_incoming_fetch_dna(
+        &mut self,
+        dna_hash: DnaHash,
+        to_agent: AgentPubKey,
+        requested: DnaHash,
+    ) -> kitsune_p2p::actor::KitsuneP2pHandlerResult<Vec<u8>> {
+        let evt_sender = self.evt_sender.clone();
+        Ok(async move {
+            let res = evt_sender.fetch_dna(dna_hash, to_agent, requested).await;
+            res.and_then(|r| Ok(SerializedBytes::try_from(r)?))
+                .map_err(kitsune_p2p::KitsuneP2pError::from)
+                .map(|res| UnsafeBytes::from(res).into())
+        }
+        .boxed()
+        .into())
+    }
+
     /// receiving an incoming publish from a remote node
     fn handle_incoming_publish(
         &mut self,
@@ -621,9 +762,11 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
             let agents = match (agents, window, arc_set, near_basis, limit) {
                 // If only basis and limit are set, this is a "near basis" query
                 (None, None, None, Some(basis), Some(limit)) => {
-                    evt_sender
+                    let mut agents = evt_sender
                         .query_agent_info_signed_near_basis(h_space, space, basis.as_u32(), limit)
-                        .await?
+                        .await?;
+                    routing::rank_authorities(basis, &mut agents);
+                    agents
                 }
 
                 // If arc_set is set, this is a "gossip agents" query
@@ -680,6 +823,7 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
         &mut self,
         space: Arc<kitsune_p2p::KitsuneSpace>,
         to_agent: Arc<kitsune_p2p::KitsuneAgent>,
+        from_peer: Option<kitsune_p2p_types::Tx2Cert>,
         payload: Vec<u8>,
     ) -> kitsune_p2p::event::KitsuneP2pEventHandlerResult<Vec<u8>> {
         let space = DnaHash::from_kitsune(&space);
@@ -687,34 +831,44 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
 
         let request =
             crate::wire::WireMessage::decode(payload.as_ref()).map_err(HolochainP2pError::from)?;
+        if let Some(kind) = RequestKind::of(&request) {
+            self.quotas.check(from_peer.as_ref(), kind)?;
+        }
+        let span = tracing::info_span!("incoming_request");
+        trace_context::set_parent(&span, request.trace_context());
 
-        match request {
+        let fut = match request {
             crate::wire::WireMessage::CallRemote {
                 zome_name,
                 fn_name,
                 cap_secret,
                 data,
                 from_agent,
+                ..
             } => self.handle_incoming_call_remote(
                 space, to_agent, from_agent, zome_name, fn_name, cap_secret, data,
             ),
-            crate::wire::WireMessage::Get { dht_hash, options } => {
-                self.handle_incoming_get(space, to_agent, dht_hash, options)
-            }
-            crate::wire::WireMessage::GetMeta { dht_hash, options } => {
-                self.handle_incoming_get_meta(space, to_agent, dht_hash, options)
-            }
-            crate::wire::WireMessage::GetLinks { link_key, options } => {
-                self.handle_incoming_get_links(space, to_agent, link_key, options)
-            }
+            crate::wire::WireMessage::Get {
+                dht_hash, options, ..
+            } => self.handle_incoming_get(space, to_agent, dht_hash, options),
+            crate::wire::WireMessage::GetMeta {
+                dht_hash, options, ..
+            } => self.handle_incoming_get_meta(space, to_agent, dht_hash, options),
+            crate::wire::WireMessage::GetLinks {
+                link_key, options, ..
+            } => self.handle_incoming_get_links(space, to_agent, link_key, options),
             crate::wire::WireMessage::GetAgentActivity {
                 agent,
                 query,
                 options,
+                ..
             } => self.handle_incoming_get_agent_activity(space, to_agent, agent, query, options),
-            crate::wire::WireMessage::MustGetAgentActivity { agent, filter } => {
+            crate::wire::WireMessage::MustGetAgentActivity { agent, filter, .. } => {
                 self.handle_incoming_must_get_agent_activity(space, to_agent, agent, filter)
             }
+            crate::wire::WireMessage::FetchDna { dna_hash, .. } => {
+                self.handle_incoming_fetch_dna(space, to_agent, dna_hash)
+            }
             // holochain_p2p never publishes via request
             // these only occur on broadcasts
             crate::wire::WireMessage::Publish { .. } => {
@@ -726,7 +880,7 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
             crate::wire::WireMessage::ValidationReceipt { receipt } => {
                 self.handle_incoming_validation_receipt(space, to_agent, receipt)
             }
-            crate::wire::WireMessage::GetValidationPackage { action_hash } => {
+            crate::wire::WireMessage::GetValidationPackage { action_hash, .. } => {
                 self.handle_incoming_get_validation_package(space, to_agent, action_hash)
             }
             // holochain_p2p only broadcasts this message.
@@ -737,7 +891,8 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
                 )
                 .into())
             }
-        }
+        }?;
+        Ok(fut.instrument(span).boxed().into())
     }
 
     #[tracing::instrument(skip(self), level = "trace")]
@@ -745,15 +900,23 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
         &mut self,
         space: Arc<kitsune_p2p::KitsuneSpace>,
         to_agent: Arc<kitsune_p2p::KitsuneAgent>,
+        from_peer: Option<kitsune_p2p_types::Tx2Cert>,
         payload: Vec<u8>,
     ) -> kitsune_p2p::event::KitsuneP2pEventHandlerResult<()> {
         let space = DnaHash::from_kitsune(&space);
         let to_agent = AgentPubKey::from_kitsune(&to_agent);
+        let payload_size = payload.len();
 
         let request =
             crate::wire::WireMessage::decode(payload.as_ref()).map_err(HolochainP2pError::from)?;
+        // Notifies over the quota are dropped, kitsune logs the error.
+        if let Some(kind) = RequestKind::of(&request) {
+            self.quotas.check(from_peer.as_ref(), kind)?;
+        }
+        let span = tracing::info_span!("incoming_notify");
+        trace_context::set_parent(&span, request.trace_context());
 
-        match request {
+        let fut = match request {
             // error on these call type messages
             crate::wire::WireMessage::Get { .. }
             | crate::wire::WireMessage::GetMeta { .. }
@@ -761,6 +924,7 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
             | crate::wire::WireMessage::GetAgentActivity { .. }
             | crate::wire::WireMessage::MustGetAgentActivity { .. }
             | crate::wire::WireMessage::GetValidationPackage { .. }
+            | crate::wire::WireMessage::FetchDna { .. }
             | crate::wire::WireMessage::ValidationReceipt { .. } => {
                 Err(HolochainP2pError::invalid_p2p_message(
                     "invalid call type message in a notify".to_string(),
@@ -773,6 +937,7 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
                 from_agent,
                 cap_secret,
                 data,
+                ..
             } => {
                 let fut = self.handle_incoming_call_remote(
                     space, to_agent, from_agent, zome_name, fn_name, cap_secret, data,
@@ -789,16 +954,28 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
                 countersigning_session,
                 dht_hash: _,
                 ops,
-            } => self.handle_incoming_publish(
-                space,
-                request_validation_receipt,
-                countersigning_session,
-                ops,
-            ),
+                ..
+            } => {
+                let bandwidth = self.bandwidth.clone();
+                let dna_hash = space.clone();
+                let fut = self.handle_incoming_publish(
+                    space,
+                    request_validation_receipt,
+                    countersigning_session,
+                    ops,
+                )?;
+                Ok(async move {
+                    bandwidth.incoming_publish(&dna_hash, payload_size).await;
+                    fut.await
+                }
+                .boxed()
+                .into())
+            }
             crate::wire::WireMessage::CountersigningSessionNegotiation { message } => {
                 self.handle_incoming_countersigning_session_negotiation(space, to_agent, message)
             }
-        }
+        }?;
+        Ok(fut.instrument(span).boxed().into())
     }
 
     #[tracing::instrument(skip(self), level = "trace")]
@@ -808,6 +985,7 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
         ops: Vec<KOp>,
     ) -> kitsune_p2p::event::KitsuneP2pEventHandlerResult<()> {
         let space = DnaHash::from_kitsune(&space);
+        let bytes: usize = ops.iter().map(|op| op.size()).sum();
         let ops = ops
             .into_iter()
             .map(|op_data| {
@@ -817,7 +995,15 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
                 Ok(op)
             })
             .collect::<Result<_, HolochainP2pError>>()?;
-        self.handle_incoming_publish(space, false, false, ops)
+        let bandwidth = self.bandwidth.clone();
+        let dna_hash = space.clone();
+        let fut = self.handle_incoming_publish(space, false, false, ops)?;
+        Ok(async move {
+            bandwidth.incoming_op_data(&dna_hash, bytes).await;
+            fut.await
+        }
+        .boxed()
+        .into())
     }
 
     #[tracing::instrument(skip(self), level = "trace")]
@@ -859,6 +1045,7 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
         let query = FetchOpDataQuery::from_kitsune(query);
 
         let evt_sender = self.evt_sender.clone();
+        let bandwidth = self.bandwidth.clone();
         Ok(async move {
             let mut out = vec![];
             for (op_hash, dht_op) in evt_sender.fetch_op_data(space.clone(), query).await? {
@@ -871,6 +1058,9 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
                     ),
                 ));
             }
+            // Op data is only fetched to be gossiped.
+            let bytes: usize = out.iter().map(|(_, op)| op.size()).sum();
+            bandwidth.outgoing_op_data(&space, bytes).await;
             Ok(out)
         }
         .boxed()
@@ -906,15 +1096,29 @@ impl HolochainP2pHandler for HolochainP2pActor {
         agent_pub_key: AgentPubKey,
         initial_arc: Option<crate::dht_arc::DhtArc>,
     ) -> HolochainP2pHandlerResult<()> {
-        let space = dna_hash.into_kitsune();
+        let space = dna_hash.to_kitsune();
         let agent = agent_pub_key.into_kitsune();
 
+        let evt_sender = self.evt_sender.clone();
         let kitsune_p2p = self.kitsune_p2p.clone();
-        Ok(
-            async move { Ok(kitsune_p2p.join(space, agent, initial_arc).await?) }
-                .boxed()
-                .into(),
-        )
+        let tuning_params = self.tuning_params.clone();
+        let network = self.network.clone();
+        Ok(async move {
+            kitsune_p2p.join(space, agent, initial_arc).await?;
+            // Send any signals left queued by a conductor that was
+            // restarted while its network was disabled.
+            if network.is_enabled() {
+                tokio::task::spawn(send_queued_remote_signals(
+                    evt_sender,
+                    kitsune_p2p,
+                    tuning_params,
+                    dna_hash,
+                ));
+            }
+            Ok(())
+        }
+        .boxed()
+        .into())
     }
 
     #[tracing::instrument(skip(self), level = "trace")]
@@ -952,7 +1156,9 @@ impl HolochainP2pHandler for HolochainP2pActor {
         .encode()?;
 
         let kitsune_p2p = self.kitsune_p2p.clone();
+        let network = self.network.clone();
         Ok(async move {
+            network.enabled().await;
             let result: Vec<u8> = kitsune_p2p.rpc_single(space, to_agent, req, None).await?;
             Ok(UnsafeBytes::from(result).into())
         }
@@ -970,23 +1176,46 @@ impl HolochainP2pHandler for HolochainP2pActor {
         fn_name: FunctionName,
         cap: Option<CapSecret>,
         payload: ExternIO,
+        drop_at_limit: bool,
     ) -> HolochainP2pHandlerResult<()> {
-        let space = dna_hash.into_kitsune();
-        let to_agent_list = to_agent_list
-            .into_iter()
-            .map(|a| a.into_kitsune())
-            .collect();
-
         let req =
             crate::wire::WireMessage::call_remote(zome_name, fn_name, from_agent, cap, payload)
                 .encode()?;
 
-        let timeout = self.tuning_params.implicit_timeout();
-
+        let evt_sender = self.evt_sender.clone();
+        let tuning_params = self.tuning_params.clone();
         let kitsune_p2p = self.kitsune_p2p.clone();
+        let network = self.network.clone();
+
+        if !network.is_enabled() {
+            // Signals don't have a response to wait for, so they're stored
+            // until the network is enabled, surviving a restart.
+            self.queued_signal_dnas.insert(dna_hash.clone());
+            return Ok(async move {
+                evt_sender
+                    .queue_remote_signal(dna_hash.clone(), to_agent_list, req)
+                    .await?;
+                // The network may have been enabled while the signal was
+                // being stored, after the queue was last sent.
+                if network.is_enabled() {
+                    send_queued_remote_signals(evt_sender, kitsune_p2p, tuning_params, dna_hash)
+                        .await;
+                }
+                Ok(())
+            }
+            .boxed()
+            .into());
+        }
+
+        let space = dna_hash.into_kitsune();
+        let to_agent_list = to_agent_list
+            .into_iter()
+            .map(|a| a.into_kitsune())
+            .collect();
         Ok(async move {
+            let timeout = tuning_params.implicit_timeout();
             kitsune_p2p
-                .targeted_broadcast(space, to_agent_list, timeout, req, true)
+                .targeted_broadcast(space, to_agent_list, timeout, req, drop_at_limit)
                 .await?;
             Ok(())
         }
@@ -1006,13 +1235,17 @@ impl HolochainP2pHandler for HolochainP2pActor {
     ) -> HolochainP2pHandlerResult<usize> {
         use kitsune_p2p_types::KitsuneTimeout;
 
-        let space = dna_hash.into_kitsune();
+        let space = dna_hash.clone().into_kitsune();
         let basis = dht_hash.to_kitsune();
-        let timeout = match timeout_ms {
-            Some(ms) => KitsuneTimeout::from_millis(ms),
-            None => self.tuning_params.implicit_timeout(),
+        let basis_loc = dht_hash.get_loc();
+        // Without validation receipts we can't tell whether the authorities
+        // got the ops, so those are still sent to every agent near the basis.
+        let fanout = if request_validation_receipt {
+            self.publish_fanout
+                .next(&dna_hash, ops.iter().map(DhtOpHash::with_data_sync))
+        } else {
+            None
         };
-
         let payload = crate::wire::WireMessage::publish(
             request_validation_receipt,
             countersigning_session,
@@ -1022,11 +1255,49 @@ impl HolochainP2pHandler for HolochainP2pActor {
         .encode()?;
         let payload_size = payload.len();
 
+        let tuning_params = self.tuning_params.clone();
+        let evt_sender = self.evt_sender.clone();
         let kitsune_p2p = self.kitsune_p2p.clone();
+        let bandwidth = self.bandwidth.clone();
+        let network = self.network.clone();
         Ok(async move {
-            kitsune_p2p
-                .broadcast(space, basis, timeout, BroadcastTo::Notify, payload)
-                .await?;
+            // start the timeout once the network is enabled
+            network.enabled().await;
+            let timeout = match timeout_ms {
+                Some(ms) => KitsuneTimeout::from_millis(ms),
+                None => tuning_params.implicit_timeout(),
+            };
+            let authorities: Vec<_> = match fanout {
+                Some(limit) => match evt_sender
+                    .query_agent_info_signed_near_basis(
+                        dna_hash.clone(),
+                        space.clone(),
+                        basis_loc.as_u32(),
+                        limit,
+                    )
+                    .await
+                {
+                    Ok(agents) => routing::authorities(basis_loc, agents)
+                        .map(|info| info.agent.clone())
+                        .collect(),
+                    Err(err) => {
+                        tracing::debug!(?err, "failed to find authorities to publish to");
+                        Vec::new()
+                    }
+                },
+                None => Vec::new(),
+            };
+            bandwidth.outgoing_publish(&dna_hash, payload_size).await;
+            // Until we know of any authorities, let kitsune search for them.
+            if authorities.is_empty() {
+                kitsune_p2p
+                    .broadcast(space, basis, timeout, BroadcastTo::Notify, payload)
+                    .await?;
+            } else {
+                kitsune_p2p
+                    .targeted_broadcast(space, authorities, timeout, payload, false)
+                    .await?;
+            }
             Ok(payload_size)
         }
         .boxed()
@@ -1043,6 +1314,12 @@ impl HolochainP2pHandler for HolochainP2pActor {
 
         let req = crate::wire::WireMessage::get_validation_package(input.action_hash).encode()?;
 
+        if !self.network.is_enabled() {
+            return Ok(async move { Ok(ValidationPackageResponse(None)) }
+                .boxed()
+                .into());
+        }
+
         let kitsune_p2p = self.kitsune_p2p.clone();
         Ok(async move {
             let response = kitsune_p2p.rpc_single(space, to_agent, req, None).await?;
@@ -1060,6 +1337,10 @@ impl HolochainP2pHandler for HolochainP2pActor {
         dht_hash: holo_hash::AnyDhtHash,
         options: actor::GetOptions,
     ) -> HolochainP2pHandlerResult<Vec<WireOps>> {
+        if !self.network.is_enabled() {
+            return offline_get();
+        }
+
         let space = dna_hash.into_kitsune();
         let basis = dht_hash.to_kitsune();
         let r_options: event::GetOptions = (&options).into();
@@ -1069,19 +1350,42 @@ impl HolochainP2pHandler for HolochainP2pActor {
         let kitsune_p2p = self.kitsune_p2p.clone();
         let tuning_params = self.tuning_params.clone();
         Ok(async move {
-            let input = kitsune_p2p::actor::RpcMulti::new(&tuning_params, space, basis, payload);
-            let result = kitsune_p2p
-                .rpc_multi(input)
-                .instrument(tracing::debug_span!("rpc_multi"))
-                .await?;
-
-            let mut out = Vec::new();
-            for item in result {
-                let kitsune_p2p::actor::RpcMultiResponse { response, .. } = item;
-                out.push(SerializedBytes::from(UnsafeBytes::from(response)).try_into()?);
+            let mut input =
+                kitsune_p2p::actor::RpcMulti::new(&tuning_params, space.clone(), basis, payload);
+            if let Some(remote_agent_count) = options.remote_agent_count {
+                input.max_remote_agent_count = remote_agent_count;
+            }
+            if let Some(timeout_ms) = options.timeout_ms {
+                input.max_timeout = kitsune_p2p_types::KitsuneTimeout::from_millis(timeout_ms);
+            }
+            if let Some(hedge_delay_ms) = options.hedge_delay_ms {
+                input.hedge_delay_ms = hedge_delay_ms;
+            }
+            if let Some(quorum) = options.quorum {
+                // a quorum needs to hear from every authority
+                input.hedge_delay_ms = 0;
+                input.max_remote_agent_count = input.max_remote_agent_count.max(quorum);
+            }
+            let retry_count = options
+                .retry_count
+                .unwrap_or(tuning_params.default_rpc_multi_retry_count);
+
+            let mut attempt = 0;
+            loop {
+                let result = kitsune_p2p
+                    .rpc_multi(input.clone())
+                    .instrument(tracing::debug_span!("rpc_multi"))
+                    .await;
+                match result {
+                    Ok(result) if !result.is_empty() || attempt >= retry_count => {
+                        return Ok(decode_rpc_multi_responses(&kitsune_p2p, space, result).await);
+                    }
+                    Err(err) if attempt >= retry_count => return Err(err.into()),
+                    Ok(_) => tracing::debug!(attempt, "get had no responses, retrying"),
+                    Err(err) => tracing::debug!(attempt, ?err, "get failed, retrying"),
+                }
+                attempt += 1;
             }
-
-            Ok(out)
         }
         .boxed()
         .into())
@@ -1094,6 +1398,10 @@ impl HolochainP2pHandler for HolochainP2pActor {
         dht_hash: holo_hash::AnyDhtHash,
         options: actor::GetMetaOptions,
     ) -> HolochainP2pHandlerResult<Vec<MetadataSet>> {
+        if !self.network.is_enabled() {
+            return offline_get();
+        }
+
         let space = dna_hash.into_kitsune();
         let basis = dht_hash.to_kitsune();
         let r_options: event::GetMetaOptions = (&options).into();
@@ -1103,16 +1411,11 @@ impl HolochainP2pHandler for HolochainP2pActor {
         let kitsune_p2p = self.kitsune_p2p.clone();
         let tuning_params = self.tuning_params.clone();
         Ok(async move {
-            let input = kitsune_p2p::actor::RpcMulti::new(&tuning_params, space, basis, payload);
+            let input =
+                kitsune_p2p::actor::RpcMulti::new(&tuning_params, space.clone(), basis, payload);
             let result = kitsune_p2p.rpc_multi(input).await?;
 
-            let mut out = Vec::new();
-            for item in result {
-                let kitsune_p2p::actor::RpcMultiResponse { response, .. } = item;
-                out.push(SerializedBytes::from(UnsafeBytes::from(response)).try_into()?);
-            }
-
-            Ok(out)
+            Ok(decode_rpc_multi_responses(&kitsune_p2p, space, result).await)
         }
         .boxed()
         .into())
@@ -1125,6 +1428,10 @@ impl HolochainP2pHandler for HolochainP2pActor {
         link_key: WireLinkKey,
         options: actor::GetLinksOptions,
     ) -> HolochainP2pHandlerResult<Vec<WireLinkOps>> {
+        if !self.network.is_enabled() {
+            return offline_get();
+        }
+
         let space = dna_hash.into_kitsune();
         let basis = AnyDhtHash::from(link_key.base.clone()).to_kitsune();
         let r_options: event::GetLinksOptions = (&options).into();
@@ -1135,20 +1442,14 @@ impl HolochainP2pHandler for HolochainP2pActor {
         let tuning_params = self.tuning_params.clone();
         Ok(async move {
             let mut input =
-                kitsune_p2p::actor::RpcMulti::new(&tuning_params, space, basis, payload);
+                kitsune_p2p::actor::RpcMulti::new(&tuning_params, space.clone(), basis, payload);
             // NOTE - We're just targeting a single remote node for now
             //        without doing any pagination / etc...
             //        Setting up RpcMulti to act like RpcSingle
             input.max_remote_agent_count = 1;
             let result = kitsune_p2p.rpc_multi(input).await?;
 
-            let mut out = Vec::new();
-            for item in result {
-                let kitsune_p2p::actor::RpcMultiResponse { response, .. } = item;
-                out.push(SerializedBytes::from(UnsafeBytes::from(response)).try_into()?);
-            }
-
-            Ok(out)
+            Ok(decode_rpc_multi_responses(&kitsune_p2p, space, result).await)
         }
         .boxed()
         .into())
@@ -1162,6 +1463,10 @@ impl HolochainP2pHandler for HolochainP2pActor {
         query: ChainQueryFilter,
         options: actor::GetActivityOptions,
     ) -> HolochainP2pHandlerResult<Vec<AgentActivityResponse<ActionHash>>> {
+        if !self.network.is_enabled() {
+            return offline_get();
+        }
+
         let space = dna_hash.into_kitsune();
         // Convert the agent key to an any dht hash so it can be used
         // as the basis for sending this request
@@ -1176,20 +1481,14 @@ impl HolochainP2pHandler for HolochainP2pActor {
         let tuning_params = self.tuning_params.clone();
         Ok(async move {
             let mut input =
-                kitsune_p2p::actor::RpcMulti::new(&tuning_params, space, basis, payload);
+                kitsune_p2p::actor::RpcMulti::new(&tuning_params, space.clone(), basis, payload);
             // TODO - We're just targeting a single remote node for now
             //        without doing any pagination / etc...
             //        Setting up RpcMulti to act like RpcSingle
             input.max_remote_agent_count = 1;
             let result = kitsune_p2p.rpc_multi(input).await?;
 
-            let mut out = Vec::new();
-            for item in result {
-                let kitsune_p2p::actor::RpcMultiResponse { response, .. } = item;
-                out.push(SerializedBytes::from(UnsafeBytes::from(response)).try_into()?);
-            }
-
-            Ok(out)
+            Ok(decode_rpc_multi_responses(&kitsune_p2p, space, result).await)
         }
         .boxed()
         .into())
@@ -1202,6 +1501,10 @@ impl HolochainP2pHandler for HolochainP2pActor {
         agent: AgentPubKey,
         filter: holochain_zome_types::chain::ChainFilter,
     ) -> HolochainP2pHandlerResult<Vec<MustGetAgentActivityResponse>> {
+        if !self.network.is_enabled() {
+            return offline_get();
+        }
+
         let space = dna_hash.into_kitsune();
         // Convert the agent key to an any dht hash so it can be used
         // as the basis for sending this request
@@ -1214,20 +1517,45 @@ impl HolochainP2pHandler for HolochainP2pActor {
         let tuning_params = self.tuning_params.clone();
         Ok(async move {
             let mut input =
-                kitsune_p2p::actor::RpcMulti::new(&tuning_params, space, basis, payload);
+                kitsune_p2p::actor::RpcMulti::new(&tuning_params, space.clone(), basis, payload);
             // TODO - We're just targeting a single remote node for now
             //        without doing any pagination / etc...
             //        Setting up RpcMulti to act like RpcSingle
             input.max_remote_agent_count = 1;
             let result = kitsune_p2p.rpc_multi(input).await?;
 
-            let mut out = Vec::new();
-            for item in result {
-                let kitsune_p2p::actor::RpcMultiResponse { response, .. } = item;
-                out.push(SerializedBytes::from(UnsafeBytes::from(response)).try_into()?);
-            }
+            Ok(decode_rpc_multi_responses(&kitsune_p2p, space, result).await)
+        }
+        .boxed()
+        .into())
+    }
 
-            Ok(out)
+    #[tracing::instrument(skip(self), level = "trace")]
+    fn handle_fetch_dna(
+        &mut self,
+        dna_hash: DnaHash,
+        requested: DnaHash,
+    ) -> HolochainP2pHandlerResult<Vec<FetchDnaResponse>> {
+        if !self.network.is_enabled() {
+            return offline_get();
+        }
+
+        let space = dna_hash.into_kitsune();
+        // Any peer may have the DNA, so the requested hash is only used
+        // to pick a neighborhood to ask
+        let basis_hash: AnyDhtHash = requested.clone().retype(holo_hash::hash_type::Entry).into();
+        let basis = basis_hash.to_kitsune();
+
+        let payload = crate::wire::WireMessage::fetch_dna(requested).encode()?;
+
+        let kitsune_p2p = self.kitsune_p2p.clone();
+        let tuning_params = self.tuning_params.clone();
+        Ok(async move {
+            let input =
+                kitsune_p2p::actor::RpcMulti::new(&tuning_params, space.clone(), basis, payload);
+            let result = kitsune_p2p.rpc_multi(input).await?;
+
+            Ok(decode_rpc_multi_responses(&kitsune_p2p, space, result).await)
         }
         .boxed()
         .into())
@@ -1246,7 +1574,9 @@ impl HolochainP2pHandler for HolochainP2pActor {
         let req = crate::wire::WireMessage::validation_receipt(receipt).encode()?;
 
         let kitsune_p2p = self.kitsune_p2p.clone();
+        let network = self.network.clone();
         Ok(async move {
+            network.enabled().await;
             kitsune_p2p.rpc_single(space, to_agent, req, None).await?;
             Ok(())
         }
@@ -1283,6 +1613,29 @@ impl HolochainP2pHandler for HolochainP2pActor {
         )
     }
 
+    #[tracing::instrument(skip(self), level = "trace")]
+    fn handle_authorities_for_basis(
+        &mut self,
+        dna_hash: DnaHash,
+        basis: AnyDhtHash,
+        limit: u32,
+    ) -> HolochainP2pHandlerResult<Vec<AgentPubKey>> {
+        let space = dna_hash.to_kitsune();
+        let basis_loc = basis.get_loc();
+
+        let evt_sender = self.evt_sender.clone();
+        Ok(async move {
+            let agents = evt_sender
+                .query_agent_info_signed_near_basis(dna_hash, space, basis_loc.as_u32(), limit)
+                .await?;
+            Ok(routing::authorities(basis_loc, agents)
+                .map(|info| AgentPubKey::from_kitsune(&info.agent))
+                .collect())
+        }
+        .boxed()
+        .into())
+    }
+
     #[tracing::instrument(skip(self), level = "trace")]
     fn handle_countersigning_session_negotiation(
         &mut self,
@@ -1293,13 +1646,16 @@ impl HolochainP2pHandler for HolochainP2pActor {
         let space = dna_hash.into_kitsune();
         let agents = agents.into_iter().map(|a| a.into_kitsune()).collect();
 
-        let timeout = self.tuning_params.implicit_timeout();
-
         let payload =
             crate::wire::WireMessage::countersigning_session_negotiation(message).encode()?;
 
+        let tuning_params = self.tuning_params.clone();
         let kitsune_p2p = self.kitsune_p2p.clone();
+        let network = self.network.clone();
         Ok(async move {
+            // start the timeout once the network is enabled
+            network.enabled().await;
+            let timeout = tuning_params.implicit_timeout();
             kitsune_p2p
                 .targeted_broadcast(space, agents, timeout, payload, false)
                 .await?;
@@ -1322,4 +1678,68 @@ impl HolochainP2pHandler for HolochainP2pActor {
         .boxed()
         .into())
     }
+
+    fn handle_dump_network_stats(&mut self) -> HolochainP2pHandlerResult<String> {
+        let kitsune_p2p = self.kitsune_p2p.clone();
+        Ok(async move {
+            serde_json::to_string_pretty(&kitsune_p2p.dump_network_stats().await?)
+                .map_err(HolochainP2pError::other)
+        }
+        .boxed()
+        .into())
+    }
+
+    fn handle_bootstrap_health(
+        &mut self,
+    ) -> HolochainP2pHandlerResult<Vec<kitsune_p2p_types::bootstrap::BootstrapServerHealth>> {
+        let kitsune_p2p = self.kitsune_p2p.clone();
+        Ok(async move { Ok(kitsune_p2p.bootstrap_health().await?) }
+            .boxed()
+            .into())
+    }
+
+    fn handle_set_network_enabled(&mut self, enabled: bool) -> HolochainP2pHandlerResult<()> {
+        self.network.set_enabled(enabled);
+        let queued_signal_dnas: Vec<_> = if enabled {
+            self.queued_signal_dnas.drain().collect()
+        } else {
+            Vec::new()
+        };
+
+        let evt_sender = self.evt_sender.clone();
+        let kitsune_p2p = self.kitsune_p2p.clone();
+        let tuning_params = self.tuning_params.clone();
+        Ok(async move {
+            kitsune_p2p.set_gossip_paused(!enabled).await?;
+            for dna_hash in queued_signal_dnas {
+                tokio::task::spawn(send_queued_remote_signals(
+                    evt_sender.clone(),
+                    kitsune_p2p.clone(),
+                    tuning_params.clone(),
+                    dna_hash,
+                ));
+            }
+            Ok(())
+        }
+        .boxed()
+        .into())
+    }
+
+    fn handle_update_network_config(
+        &mut self,
+        tuning_params: kitsune_p2p_types::config::KitsuneP2pTuningParams,
+        bootstrap_urls: Vec<kitsune_p2p::dependencies::url2::Url2>,
+    ) -> HolochainP2pHandlerResult<()> {
+        self.quotas.set_tuning_params(tuning_params.clone());
+        self.publish_fanout.set_tuning_params(tuning_params.clone());
+        self.tuning_params = tuning_params.clone();
+        let kitsune_p2p = self.kitsune_p2p.clone();
+        Ok(async move {
+            kitsune_p2p.update_tuning_params(tuning_params).await?;
+            kitsune_p2p.set_bootstrap_urls(bootstrap_urls).await?;
+            Ok(())
+        }
+        .boxed()
+        .into())
+    }
 }
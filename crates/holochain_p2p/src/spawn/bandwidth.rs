@@ -0,0 +1,75 @@
+//! Bandwidth budgets for the op data holochain_p2p sends and receives.
+//!
+//! Kitsune already limits all gossip for the conductor. On top of that,
+//! publishes are limited for the whole conductor, and optionally all op data,
+//! gossiped or published, is limited separately for each DNA.
+
+use holo_hash::DnaHash;
+use holochain_types::share::RwShare;
+use kitsune_p2p::gossip::sharded_gossip::BandwidthThrottle;
+use kitsune_p2p_types::config::KitsuneP2pTuningParams;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Token buckets for publish and per DNA traffic.
+pub(crate) struct BandwidthBudgets {
+    tuning_params: KitsuneP2pTuningParams,
+    publish: BandwidthThrottle,
+    dnas: RwShare<HashMap<DnaHash, Arc<BandwidthThrottle>>>,
+}
+
+impl BandwidthBudgets {
+    pub(crate) fn new(tuning_params: KitsuneP2pTuningParams) -> Self {
+        let publish = BandwidthThrottle::new(
+            tuning_params.publish_inbound_target_mbps,
+            tuning_params.publish_outbound_target_mbps,
+            tuning_params.gossip_burst_ratio,
+        );
+        Self {
+            tuning_params,
+            publish,
+            dnas: RwShare::new(HashMap::new()),
+        }
+    }
+
+    /// Wait until there's enough bandwidth to publish this many bytes.
+    pub(crate) async fn outgoing_publish(&self, dna_hash: &DnaHash, bytes: usize) {
+        self.publish.outgoing_bytes(bytes).await;
+        self.outgoing_op_data(dna_hash, bytes).await;
+    }
+
+    /// Wait until there's enough bandwidth to receive a publish of this many bytes.
+    pub(crate) async fn incoming_publish(&self, dna_hash: &DnaHash, bytes: usize) {
+        self.publish.incoming_bytes(bytes).await;
+        self.incoming_op_data(dna_hash, bytes).await;
+    }
+
+    /// Wait until the DNA has enough bandwidth to send this much op data.
+    pub(crate) async fn outgoing_op_data(&self, dna_hash: &DnaHash, bytes: usize) {
+        if let Some(throttle) = self.dna(dna_hash) {
+            throttle.outgoing_bytes(bytes).await;
+        }
+    }
+
+    /// Wait until the DNA has enough bandwidth to receive this much op data.
+    pub(crate) async fn incoming_op_data(&self, dna_hash: &DnaHash, bytes: usize) {
+        if let Some(throttle) = self.dna(dna_hash) {
+            throttle.incoming_bytes(bytes).await;
+        }
+    }
+
+    /// Get the throttle for a DNA, or None if DNAs aren't limited.
+    fn dna(&self, dna_hash: &DnaHash) -> Option<Arc<BandwidthThrottle>> {
+        let inbound = self.tuning_params.dna_inbound_target_mbps;
+        let outbound = self.tuning_params.dna_outbound_target_mbps;
+        if inbound <= 0.0 && outbound <= 0.0 {
+            return None;
+        }
+        let burst_ratio = self.tuning_params.gossip_burst_ratio;
+        Some(self.dnas.share_mut(|dnas| {
+            dnas.entry(dna_hash.clone())
+                .or_insert_with(|| Arc::new(BandwidthThrottle::new(inbound, outbound, burst_ratio)))
+                .clone()
+        }))
+    }
+}
@@ -0,0 +1,1277 @@
+#![allow(clippy::too_many_arguments)]
+//! An experimental network backend built on libp2p, to compare against
+//! kitsune and to ease interop with other libp2p based networks.
+//!
+//! - Kademlia is used for peer discovery. Each node provides a record for
+//!   every agent which joins a dna, so the node an agent is on can be found.
+//! - Gossipsub broadcasts published ops, with a topic for each dna.
+//! - Request/response carries the gets, remote calls, validation receipts
+//!   and countersigning messages, encoded as the same wire messages kitsune
+//!   sends.
+//!
+//! There are no storage arcs: every node which has joined a dna receives all
+//! of its ops, and gets ask every peer subscribed to the dna's topic. This is
+//! fine for comparing the stacks on small networks, but it won't scale.
+//!
+//! The swarm is owned by a driver task, which the actor sends [`Command`]s to.
+
+use crate::actor::*;
+use crate::event::*;
+use crate::*;
+
+use futures::channel::mpsc::Sender;
+use futures::future::FutureExt;
+use futures::AsyncRead;
+use futures::AsyncWrite;
+use futures::AsyncWriteExt;
+use futures::StreamExt;
+use libp2p::core::upgrade::read_length_prefixed;
+use libp2p::core::upgrade::write_length_prefixed;
+use libp2p::core::upgrade::ProtocolName;
+use libp2p::gossipsub::Gossipsub;
+use libp2p::gossipsub::GossipsubConfigBuilder;
+use libp2p::gossipsub::GossipsubEvent;
+use libp2p::gossipsub::IdentTopic;
+use libp2p::gossipsub::MessageAuthenticity;
+use libp2p::gossipsub::ValidationMode;
+use libp2p::identity;
+use libp2p::kad::record::Key;
+use libp2p::kad::store::MemoryStore;
+use libp2p::kad::GetProvidersOk;
+use libp2p::kad::Kademlia;
+use libp2p::kad::KademliaEvent;
+use libp2p::kad::QueryId;
+use libp2p::kad::QueryResult;
+use libp2p::multiaddr::Protocol;
+use libp2p::request_response::ProtocolSupport;
+use libp2p::request_response::RequestId;
+use libp2p::request_response::RequestResponse;
+use libp2p::request_response::RequestResponseCodec;
+use libp2p::request_response::RequestResponseConfig;
+use libp2p::request_response::RequestResponseEvent;
+use libp2p::request_response::RequestResponseMessage;
+use libp2p::request_response::ResponseChannel;
+use libp2p::swarm::SwarmBuilder;
+use libp2p::swarm::SwarmEvent;
+use libp2p::Multiaddr;
+use libp2p::NetworkBehaviour;
+use libp2p::PeerId;
+use libp2p::Swarm;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::future::Future;
+use std::time::Duration;
+use tokio::sync::mpsc::UnboundedReceiver;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::oneshot;
+
+use super::network_switch::NetworkSwitch;
+
+/// The largest request, response or broadcast which is sent or accepted.
+const MAX_MESSAGE_BYTES: usize = 16 * 1024 * 1024;
+
+/// How long to wait for the response to a request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The name of the request/response protocol.
+const PROTOCOL_NAME: &[u8] = b"/holochain/p2p/0.1.0";
+
+/// Configuration of the libp2p backend.
+#[derive(Clone, Debug, Default)]
+pub struct Libp2pConfig {
+    /// The addresses to listen on, e.g. `/ip4/0.0.0.0/tcp/0`.
+    pub listen_addresses: Vec<String>,
+    /// The addresses of the peers to bootstrap the kademlia routing table
+    /// from, which must end with the peer's id, e.g.
+    /// `/ip4/1.2.3.4/tcp/5678/p2p/12D3KooW...`.
+    pub bootstrap_peers: Vec<String>,
+}
+
+/// A request to a peer, or an op broadcast to a dna's topic.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+struct Request {
+    dna_hash: DnaHash,
+    /// The agent to handle the request. If there is none, any agent of the
+    /// peer which has joined the dna handles it.
+    to_agent: Option<AgentPubKey>,
+    /// The encoded [`WireMessage`](crate::wire::WireMessage).
+    #[serde(with = "serde_bytes")]
+    message: Vec<u8>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+enum Response {
+    Ok(#[serde(with = "serde_bytes")] Vec<u8>),
+    Err(String),
+}
+
+#[derive(Clone)]
+struct WireProtocol;
+
+impl ProtocolName for WireProtocol {
+    fn protocol_name(&self) -> &[u8] {
+        PROTOCOL_NAME
+    }
+}
+
+#[derive(Clone)]
+struct WireCodec;
+
+fn invalid_data(e: impl std::fmt::Display) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string())
+}
+
+#[async_trait::async_trait]
+impl RequestResponseCodec for WireCodec {
+    type Protocol = WireProtocol;
+    type Request = Request;
+    type Response = Response;
+
+    async fn read_request<T>(&mut self, _: &WireProtocol, io: &mut T) -> std::io::Result<Request>
+    where
+        T: AsyncRead + Unpin + Send,
+    {
+        let data = read_length_prefixed(io, MAX_MESSAGE_BYTES).await?;
+        holochain_serialized_bytes::decode(&data).map_err(invalid_data)
+    }
+
+    async fn read_response<T>(&mut self, _: &WireProtocol, io: &mut T) -> std::io::Result<Response>
+    where
+        T: AsyncRead + Unpin + Send,
+    {
+        let data = read_length_prefixed(io, MAX_MESSAGE_BYTES).await?;
+        holochain_serialized_bytes::decode(&data).map_err(invalid_data)
+    }
+
+    async fn write_request<T>(
+        &mut self,
+        _: &WireProtocol,
+        io: &mut T,
+        request: Request,
+    ) -> std::io::Result<()>
+    where
+        T: AsyncWrite + Unpin + Send,
+    {
+        let data = holochain_serialized_bytes::encode(&request).map_err(invalid_data)?;
+        write_length_prefixed(io, data).await?;
+        io.close().await
+    }
+
+    async fn write_response<T>(
+        &mut self,
+        _: &WireProtocol,
+        io: &mut T,
+        response: Response,
+    ) -> std::io::Result<()>
+    where
+        T: AsyncWrite + Unpin + Send,
+    {
+        let data = holochain_serialized_bytes::encode(&response).map_err(invalid_data)?;
+        write_length_prefixed(io, data).await?;
+        io.close().await
+    }
+}
+
+#[derive(NetworkBehaviour)]
+#[behaviour(out_event = "BehaviourEvent")]
+struct Behaviour {
+    kademlia: Kademlia<MemoryStore>,
+    gossipsub: Gossipsub,
+    request_response: RequestResponse<WireCodec>,
+}
+
+enum BehaviourEvent {
+    Kademlia(KademliaEvent),
+    Gossipsub(GossipsubEvent),
+    RequestResponse(RequestResponseEvent<Request, Response>),
+}
+
+impl From<KademliaEvent> for BehaviourEvent {
+    fn from(event: KademliaEvent) -> Self {
+        Self::Kademlia(event)
+    }
+}
+
+impl From<GossipsubEvent> for BehaviourEvent {
+    fn from(event: GossipsubEvent) -> Self {
+        Self::Gossipsub(event)
+    }
+}
+
+impl From<RequestResponseEvent<Request, Response>> for BehaviourEvent {
+    fn from(event: RequestResponseEvent<Request, Response>) -> Self {
+        Self::RequestResponse(event)
+    }
+}
+
+/// The agents on this node and the dnas they have joined.
+type LocalAgents = Arc<parking_lot::Mutex<HashSet<(DnaHash, AgentPubKey)>>>;
+
+/// An agent being looked up, and who is waiting for the peer it's on.
+type Lookup = ((DnaHash, AgentPubKey), Vec<oneshot::Sender<Option<PeerId>>>);
+
+/// What the actor asks of the driver task.
+enum Command {
+    /// Provide the agent's record, and subscribe to the dna's topic
+    /// if this is the first local agent in the dna.
+    Join {
+        dna_hash: DnaHash,
+        agent: AgentPubKey,
+        first: bool,
+    },
+    /// Stop providing the agent's record, and unsubscribe from the dna's
+    /// topic if this was the last local agent in the dna.
+    Leave {
+        dna_hash: DnaHash,
+        agent: AgentPubKey,
+        last: bool,
+    },
+    /// Find the peer the agent is on.
+    FindPeer {
+        dna_hash: DnaHash,
+        agent: AgentPubKey,
+        respond: oneshot::Sender<Option<PeerId>>,
+    },
+    /// The peers subscribed to the dna's topic.
+    DnaPeers {
+        dna_hash: DnaHash,
+        respond: oneshot::Sender<Vec<PeerId>>,
+    },
+    /// Send a request to a peer.
+    Request {
+        peer: PeerId,
+        request: Request,
+        respond: oneshot::Sender<HolochainP2pResult<Vec<u8>>>,
+    },
+    /// Broadcast to the peers subscribed to the dna's topic.
+    Broadcast {
+        request: Request,
+        respond: oneshot::Sender<HolochainP2pResult<()>>,
+    },
+    /// A summary of the swarm, as JSON.
+    Stats {
+        respond: oneshot::Sender<serde_json::Value>,
+    },
+}
+
+fn topic(dna_hash: &DnaHash) -> IdentTopic {
+    IdentTopic::new(format!("holochain/{}", dna_hash))
+}
+
+fn agent_key(dna_hash: &DnaHash, agent: &AgentPubKey) -> Key {
+    Key::new(&[dna_hash.get_raw_39(), agent.get_raw_39()].concat())
+}
+
+fn driver_gone<T>(_: T) -> HolochainP2pError {
+    HolochainP2pError::other("the libp2p driver task has stopped")
+}
+
+/// The peer id at the end of a bootstrap address.
+fn bootstrap_peer(address: &str) -> HolochainP2pResult<(PeerId, Multiaddr)> {
+    let address: Multiaddr = address.parse().map_err(HolochainP2pError::other)?;
+    match address.iter().last() {
+        Some(Protocol::P2p(hash)) => {
+            let peer = PeerId::from_multihash(hash)
+                .map_err(|_| HolochainP2pError::other(format!("invalid peer id in {}", address)))?;
+            Ok((peer, address))
+        }
+        _ => Err(HolochainP2pError::other(format!(
+            "bootstrap address {} doesn't end with a peer id",
+            address
+        ))),
+    }
+}
+
+/// Start the swarm and the task which drives it.
+fn spawn_driver(
+    config: Libp2pConfig,
+    evt_sender: Sender<HolochainP2pEvent>,
+    local: LocalAgents,
+) -> HolochainP2pResult<UnboundedSender<Command>> {
+    let keypair = identity::Keypair::generate_ed25519();
+    let local_peer = PeerId::from(keypair.public());
+    let transport =
+        libp2p::tokio_development_transport(keypair.clone()).map_err(HolochainP2pError::other)?;
+
+    let mut kademlia = Kademlia::new(local_peer, MemoryStore::new(local_peer));
+    let gossipsub_config = GossipsubConfigBuilder::default()
+        .validation_mode(ValidationMode::Strict)
+        .max_transmit_size(MAX_MESSAGE_BYTES)
+        .build()
+        .map_err(HolochainP2pError::other)?;
+    let gossipsub = Gossipsub::new(MessageAuthenticity::Signed(keypair), gossipsub_config)
+        .map_err(HolochainP2pError::other)?;
+    let mut request_response_config = RequestResponseConfig::default();
+    request_response_config.set_request_timeout(REQUEST_TIMEOUT);
+    let request_response = RequestResponse::new(
+        WireCodec,
+        std::iter::once((WireProtocol, ProtocolSupport::Full)),
+        request_response_config,
+    );
+
+    let mut bootstrap = Vec::new();
+    for address in &config.bootstrap_peers {
+        let (peer, address) = bootstrap_peer(address)?;
+        kademlia.add_address(&peer, address.clone());
+        bootstrap.push(address);
+    }
+
+    let behaviour = Behaviour {
+        kademlia,
+        gossipsub,
+        request_response,
+    };
+    let mut swarm = SwarmBuilder::new(transport, behaviour, local_peer)
+        .executor(Box::new(|fut| {
+            tokio::task::spawn(fut);
+        }))
+        .build();
+
+    for address in &config.listen_addresses {
+        let address: Multiaddr = address.parse().map_err(HolochainP2pError::other)?;
+        swarm.listen_on(address).map_err(HolochainP2pError::other)?;
+    }
+    for address in bootstrap {
+        if let Err(err) = swarm.dial(address.clone()) {
+            tracing::warn!(?err, %address, "failed to dial bootstrap peer");
+        }
+    }
+    if !config.bootstrap_peers.is_empty() {
+        if let Err(err) = swarm.behaviour_mut().kademlia.bootstrap() {
+            tracing::warn!(?err, "failed to bootstrap kademlia");
+        }
+    }
+    tracing::info!(%local_peer, "libp2p network started");
+
+    let (commands, command_receiver) = tokio::sync::mpsc::unbounded_channel();
+    let (responses, response_receiver) = tokio::sync::mpsc::unbounded_channel();
+    let driver = Driver {
+        swarm,
+        evt_sender,
+        local,
+        responses,
+        peers: HashMap::new(),
+        lookups: HashMap::new(),
+        requests: HashMap::new(),
+    };
+    tokio::task::spawn(driver.run(command_receiver, response_receiver));
+    Ok(commands)
+}
+
+struct Driver {
+    swarm: Swarm<Behaviour>,
+    evt_sender: Sender<HolochainP2pEvent>,
+    local: LocalAgents,
+    /// Where the tasks handling incoming requests send their responses.
+    responses: UnboundedSender<(ResponseChannel<Response>, Response)>,
+    /// The peers remote agents were last found on.
+    peers: HashMap<(DnaHash, AgentPubKey), PeerId>,
+    /// The agents being looked up, and who is waiting for them.
+    lookups: HashMap<QueryId, Lookup>,
+    /// The requests waiting for a response.
+    requests: HashMap<RequestId, oneshot::Sender<HolochainP2pResult<Vec<u8>>>>,
+}
+
+impl Driver {
+    async fn run(
+        mut self,
+        mut commands: UnboundedReceiver<Command>,
+        mut responses: UnboundedReceiver<(ResponseChannel<Response>, Response)>,
+    ) {
+        loop {
+            tokio::select! {
+                command = commands.recv() => match command {
+                    Some(command) => self.handle_command(command),
+                    // The actor has shut down.
+                    None => return,
+                },
+                Some((channel, response)) = responses.recv() => {
+                    // The peer may have gone away while the request was handled.
+                    let _ = self
+                        .swarm
+                        .behaviour_mut()
+                        .request_response
+                        .send_response(channel, response);
+                }
+                event = self.swarm.select_next_some() => self.handle_swarm_event(event),
+            }
+        }
+    }
+
+    fn handle_command(&mut self, command: Command) {
+        let behaviour = self.swarm.behaviour_mut();
+        match command {
+            Command::Join {
+                dna_hash,
+                agent,
+                first,
+            } => {
+                if let Err(err) = behaviour
+                    .kademlia
+                    .start_providing(agent_key(&dna_hash, &agent))
+                {
+                    tracing::warn!(?err, "failed to provide agent record");
+                }
+                if first {
+                    if let Err(err) = behaviour.gossipsub.subscribe(&topic(&dna_hash)) {
+                        tracing::warn!(?err, "failed to subscribe to dna topic");
+                    }
+                }
+            }
+            Command::Leave {
+                dna_hash,
+                agent,
+                last,
+            } => {
+                behaviour
+                    .kademlia
+                    .stop_providing(&agent_key(&dna_hash, &agent));
+                if last {
+                    if let Err(err) = behaviour.gossipsub.unsubscribe(&topic(&dna_hash)) {
+                        tracing::warn!(?err, "failed to unsubscribe from dna topic");
+                    }
+                }
+            }
+            Command::FindPeer {
+                dna_hash,
+                agent,
+                respond,
+            } => {
+                let key = (dna_hash, agent);
+                if let Some(peer) = self.peers.get(&key) {
+                    let _ = respond.send(Some(*peer));
+                    return;
+                }
+                let id = behaviour.kademlia.get_providers(agent_key(&key.0, &key.1));
+                self.lookups
+                    .entry(id)
+                    .or_insert_with(|| (key, Vec::new()))
+                    .1
+                    .push(respond);
+            }
+            Command::DnaPeers { dna_hash, respond } => {
+                let topic = topic(&dna_hash).hash();
+                let peers = behaviour
+                    .gossipsub
+                    .all_peers()
+                    .filter(|(_, topics)| topics.contains(&&topic))
+                    .map(|(peer, _)| *peer)
+                    .collect();
+                let _ = respond.send(peers);
+            }
+            Command::Request {
+                peer,
+                request,
+                respond,
+            } => {
+                let id = behaviour.request_response.send_request(&peer, request);
+                self.requests.insert(id, respond);
+            }
+            Command::Broadcast { request, respond } => {
+                let topic = topic(&request.dna_hash);
+                let result = holochain_serialized_bytes::encode(&request)
+                    .map_err(HolochainP2pError::from)
+                    .and_then(|data| match behaviour.gossipsub.publish(topic, data) {
+                        Ok(_) => Ok(()),
+                        // There is nobody else in the dna to send it to.
+                        Err(libp2p::gossipsub::error::PublishError::InsufficientPeers) => Ok(()),
+                        Err(err) => Err(HolochainP2pError::other(format!("{:?}", err))),
+                    });
+                let _ = respond.send(result);
+            }
+            Command::Stats { respond } => {
+                let info = self.swarm.network_info();
+                let stats = serde_json::json!({
+                    "peer_id": self.swarm.local_peer_id().to_string(),
+                    "listen_addresses": self
+                        .swarm
+                        .listeners()
+                        .map(|a| a.to_string())
+                        .collect::<Vec<_>>(),
+                    "connected_peers": info.num_peers(),
+                    "known_agents": self.peers.len(),
+                });
+                let _ = respond.send(stats);
+            }
+        }
+    }
+
+    fn handle_swarm_event<E: std::fmt::Debug>(&mut self, event: SwarmEvent<BehaviourEvent, E>) {
+        match event {
+            SwarmEvent::Behaviour(BehaviourEvent::Kademlia(event)) => {
+                self.handle_kademlia_event(event)
+            }
+            SwarmEvent::Behaviour(BehaviourEvent::Gossipsub(event)) => {
+                self.handle_gossipsub_event(event)
+            }
+            SwarmEvent::Behaviour(BehaviourEvent::RequestResponse(event)) => {
+                self.handle_request_response_event(event)
+            }
+            SwarmEvent::NewListenAddr { address, .. } => {
+                tracing::info!(%address, "libp2p listening");
+            }
+            event => tracing::trace!(?event, "libp2p swarm event"),
+        }
+    }
+
+    fn handle_kademlia_event(&mut self, event: KademliaEvent) {
+        match event {
+            KademliaEvent::OutboundQueryCompleted {
+                id,
+                result: QueryResult::GetProviders(result),
+                ..
+            } => {
+                let (key, waiting) = match self.lookups.remove(&id) {
+                    Some(lookup) => lookup,
+                    None => return,
+                };
+                let local_peer = *self.swarm.local_peer_id();
+                let peer = match result {
+                    Ok(GetProvidersOk { providers, .. }) => {
+                        providers.into_iter().find(|p| *p != local_peer)
+                    }
+                    Err(err) => {
+                        tracing::debug!(?err, "agent lookup failed");
+                        None
+                    }
+                };
+                if let Some(peer) = peer {
+                    self.peers.insert(key, peer);
+                }
+                for respond in waiting {
+                    let _ = respond.send(peer);
+                }
+            }
+            // Connect to the peers kademlia finds, so gossipsub can reach them.
+            KademliaEvent::RoutingUpdated {
+                peer, is_new_peer, ..
+            } if is_new_peer => {
+                if let Err(err) = self.swarm.dial(peer) {
+                    tracing::debug!(?err, %peer, "failed to dial peer");
+                }
+            }
+            event => tracing::trace!(?event, "kademlia event"),
+        }
+    }
+
+    fn handle_gossipsub_event(&mut self, event: GossipsubEvent) {
+        let message = match event {
+            GossipsubEvent::Message { message, .. } => message,
+            event => {
+                tracing::trace!(?event, "gossipsub event");
+                return;
+            }
+        };
+        let Request {
+            dna_hash, message, ..
+        } = match holochain_serialized_bytes::decode(&message.data) {
+            Ok(request) => request,
+            Err(err) => {
+                tracing::warn!(?err, "dropping invalid broadcast");
+                return;
+            }
+        };
+        if !self.local.lock().iter().any(|(dna, _)| *dna == dna_hash) {
+            return;
+        }
+        match crate::wire::WireMessage::decode(&message) {
+            Ok(crate::wire::WireMessage::Publish {
+                request_validation_receipt,
+                countersigning_session,
+                ops,
+                ..
+            }) => {
+                let publish = self.evt_sender.publish(
+                    dna_hash,
+                    request_validation_receipt,
+                    countersigning_session,
+                    ops,
+                );
+                tokio::task::spawn(async move {
+                    if let Err(err) = publish.await {
+                        tracing::warn!(?err, "failed to handle broadcast publish");
+                    }
+                });
+            }
+            Ok(_) => tracing::warn!("dropping broadcast which isn't a publish"),
+            Err(err) => tracing::warn!(?err, "dropping invalid broadcast"),
+        }
+    }
+
+    fn handle_request_response_event(&mut self, event: RequestResponseEvent<Request, Response>) {
+        match event {
+            RequestResponseEvent::Message {
+                message:
+                    RequestResponseMessage::Request {
+                        request, channel, ..
+                    },
+                ..
+            } => {
+                let to_agent = request.to_agent.clone().or_else(|| {
+                    self.local
+                        .lock()
+                        .iter()
+                        .find(|(dna, _)| *dna == request.dna_hash)
+                        .map(|(_, agent)| agent.clone())
+                });
+                let evt_sender = self.evt_sender.clone();
+                let responses = self.responses.clone();
+                tokio::task::spawn(async move {
+                    let result = match to_agent {
+                        Some(to_agent) => {
+                            match crate::wire::WireMessage::decode(&request.message) {
+                                Ok(message) => {
+                                    handle_request(evt_sender, request.dna_hash, to_agent, message)
+                                        .await
+                                }
+                                Err(err) => Err(err.into()),
+                            }
+                        }
+                        None => Err(HolochainP2pError::RoutingDnaError(request.dna_hash)),
+                    };
+                    let response = match result {
+                        Ok(data) => Response::Ok(data),
+                        Err(err) => Response::Err(err.to_string()),
+                    };
+                    let _ = responses.send((channel, response));
+                });
+            }
+            RequestResponseEvent::Message {
+                message:
+                    RequestResponseMessage::Response {
+                        request_id,
+                        response,
+                    },
+                ..
+            } => {
+                if let Some(respond) = self.requests.remove(&request_id) {
+                    let _ = respond.send(match response {
+                        Response::Ok(data) => Ok(data),
+                        Response::Err(err) => Err(HolochainP2pError::other(err)),
+                    });
+                }
+            }
+            RequestResponseEvent::OutboundFailure {
+                request_id, error, ..
+            } => {
+                if let Some(respond) = self.requests.remove(&request_id) {
+                    let _ = respond.send(Err(HolochainP2pError::other(format!("{:?}", error))));
+                }
+            }
+            RequestResponseEvent::InboundFailure { peer, error, .. } => {
+                tracing::debug!(%peer, ?error, "failed to respond to request");
+            }
+            RequestResponseEvent::ResponseSent { .. } => {}
+        }
+    }
+}
+
+/// Hand a request from a peer to the conductor, and encode its response.
+async fn handle_request(
+    evt_sender: Sender<HolochainP2pEvent>,
+    dna_hash: DnaHash,
+    to_agent: AgentPubKey,
+    message: crate::wire::WireMessage,
+) -> HolochainP2pResult<Vec<u8>> {
+    use crate::wire::WireMessage;
+
+    fn encode<T>(response: T) -> HolochainP2pResult<Vec<u8>>
+    where
+        T: TryInto<SerializedBytes, Error = SerializedBytesError>,
+    {
+        Ok(UnsafeBytes::from(response.try_into()?).into())
+    }
+
+    match message {
+        WireMessage::CallRemote {
+            zome_name,
+            fn_name,
+            from_agent,
+            cap_secret,
+            data,
+            ..
+        } => {
+            let response = evt_sender
+                .call_remote(
+                    dna_hash,
+                    to_agent,
+                    from_agent,
+                    zome_name,
+                    fn_name,
+                    cap_secret,
+                    ExternIO::from(data),
+                )
+                .await?;
+            Ok(UnsafeBytes::from(response).into())
+        }
+        WireMessage::Get {
+            dht_hash, options, ..
+        } => encode(
+            evt_sender
+                .get(dna_hash, to_agent, dht_hash, options)
+                .await?,
+        ),
+        WireMessage::GetMeta {
+            dht_hash, options, ..
+        } => encode(
+            evt_sender
+                .get_meta(dna_hash, to_agent, dht_hash, options)
+                .await?,
+        ),
+        WireMessage::GetLinks {
+            link_key, options, ..
+        } => encode(
+            evt_sender
+                .get_links(dna_hash, to_agent, link_key, options)
+                .await?,
+        ),
+        WireMessage::GetAgentActivity {
+            agent,
+            query,
+            options,
+            ..
+        } => encode(
+            evt_sender
+                .get_agent_activity(dna_hash, to_agent, agent, query, options)
+                .await?,
+        ),
+        WireMessage::MustGetAgentActivity { agent, filter, .. } => encode(
+            evt_sender
+                .must_get_agent_activity(dna_hash, to_agent, agent, filter)
+                .await?,
+        ),
+        WireMessage::FetchDna {
+            dna_hash: requested,
+            ..
+        } => encode(evt_sender.fetch_dna(dna_hash, to_agent, requested).await?),
+        WireMessage::GetValidationPackage { action_hash, .. } => encode(
+            evt_sender
+                .get_validation_package(dna_hash, to_agent, action_hash)
+                .await?,
+        ),
+        WireMessage::ValidationReceipt { receipt } => {
+            evt_sender
+                .validation_receipt_received(dna_hash, to_agent, UnsafeBytes::from(receipt).into())
+                .await?;
+            Ok(Vec::new())
+        }
+        WireMessage::CountersigningSessionNegotiation { message } => {
+            evt_sender
+                .countersigning_session_negotiation(dna_hash, to_agent, message)
+                .await?;
+            Ok(Vec::new())
+        }
+        WireMessage::Publish { .. } => Err(HolochainP2pError::invalid_p2p_message(
+            "invalid: publish is a broadcast type, not a request".to_string(),
+        )),
+    }
+}
+
+pub(crate) struct Libp2pActor {
+    evt_sender: Sender<HolochainP2pEvent>,
+    commands: UnboundedSender<Command>,
+    local: LocalAgents,
+    switch: Arc<NetworkSwitch>,
+}
+
+impl Libp2pActor {
+    /// Start the swarm.
+    pub fn new(
+        config: Libp2pConfig,
+        evt_sender: Sender<HolochainP2pEvent>,
+    ) -> HolochainP2pResult<Self> {
+        let local = LocalAgents::default();
+        let commands = spawn_driver(config, evt_sender.clone(), local.clone())?;
+        Ok(Self {
+            evt_sender,
+            commands,
+            local,
+            switch: Arc::new(NetworkSwitch::new()),
+        })
+    }
+
+    /// A local agent which has joined the dna, if any.
+    fn local_agent(&self, dna_hash: &DnaHash) -> Option<AgentPubKey> {
+        self.local
+            .lock()
+            .iter()
+            .find(|(dna, _)| dna == dna_hash)
+            .map(|(_, agent)| agent.clone())
+    }
+
+    /// Send a message to an agent, handling it here if the agent is local.
+    fn request(
+        &self,
+        dna_hash: DnaHash,
+        to_agent: AgentPubKey,
+        message: Vec<u8>,
+    ) -> impl Future<Output = HolochainP2pResult<Vec<u8>>> + 'static + Send {
+        let is_local = self
+            .local
+            .lock()
+            .contains(&(dna_hash.clone(), to_agent.clone()));
+        let evt_sender = self.evt_sender.clone();
+        let commands = self.commands.clone();
+        let switch = self.switch.clone();
+        async move {
+            switch.enabled().await;
+            if is_local {
+                let message = crate::wire::WireMessage::decode(&message)?;
+                return handle_request(evt_sender, dna_hash, to_agent, message).await;
+            }
+            let (respond, peer) = oneshot::channel();
+            commands
+                .send(Command::FindPeer {
+                    dna_hash: dna_hash.clone(),
+                    agent: to_agent.clone(),
+                    respond,
+                })
+                .map_err(driver_gone)?;
+            let peer = peer
+                .await
+                .map_err(driver_gone)?
+                .ok_or_else(|| HolochainP2pError::RoutingAgentError(to_agent.clone()))?;
+            send_request(
+                &commands,
+                peer,
+                Request {
+                    dna_hash,
+                    to_agent: Some(to_agent),
+                    message,
+                },
+            )
+            .await
+        }
+    }
+
+    /// Ask every peer in the dna, and this node if it has joined,
+    /// dropping the failed and invalid responses.
+    fn get_from_all<T>(
+        &self,
+        dna_hash: DnaHash,
+        message: Vec<u8>,
+    ) -> HolochainP2pHandlerResult<Vec<T>>
+    where
+        T: 'static + Send + TryFrom<SerializedBytes, Error = SerializedBytesError>,
+    {
+        if !self.switch.is_enabled() {
+            return Ok(async move { Ok(Vec::new()) }.boxed().into());
+        }
+        let local_agent = self.local_agent(&dna_hash);
+        let evt_sender = self.evt_sender.clone();
+        let commands = self.commands.clone();
+        Ok(async move {
+            let (respond, peers) = oneshot::channel();
+            commands
+                .send(Command::DnaPeers {
+                    dna_hash: dna_hash.clone(),
+                    respond,
+                })
+                .map_err(driver_gone)?;
+            let mut requests = peers
+                .await
+                .map_err(driver_gone)?
+                .into_iter()
+                .map(|peer| {
+                    let request = Request {
+                        dna_hash: dna_hash.clone(),
+                        to_agent: None,
+                        message: message.clone(),
+                    };
+                    let commands = commands.clone();
+                    async move { send_request(&commands, peer, request).await }.boxed()
+                })
+                .collect::<Vec<_>>();
+            if let Some(agent) = local_agent {
+                let message = crate::wire::WireMessage::decode(&message)?;
+                requests.push(handle_request(evt_sender, dna_hash, agent, message).boxed());
+            }
+            Ok(futures::future::join_all(requests)
+                .await
+                .into_iter()
+                .filter_map(|response| {
+                    let response = response.and_then(|data| {
+                        Ok(SerializedBytes::from(UnsafeBytes::from(data)).try_into()?)
+                    });
+                    match response {
+                        Ok(response) => Some(response),
+                        Err(err) => {
+                            tracing::debug!(?err, "dropping failed get response");
+                            None
+                        }
+                    }
+                })
+                .collect())
+        }
+        .boxed()
+        .into())
+    }
+
+    /// Send the same message to each agent without waiting for the
+    /// responses, once the network is enabled.
+    fn send_to_each(
+        &self,
+        dna_hash: DnaHash,
+        agents: Vec<AgentPubKey>,
+        message: Vec<u8>,
+    ) -> HolochainP2pHandlerResult<()> {
+        let requests: Vec<_> = agents
+            .into_iter()
+            .map(|agent| self.request(dna_hash.clone(), agent, message.clone()))
+            .collect();
+        let switch = self.switch.clone();
+        Ok(async move {
+            switch.enabled().await;
+            for request in requests {
+                tokio::task::spawn(async move {
+                    if let Err(err) = request.await {
+                        tracing::debug!(?err, "failed to send message");
+                    }
+                });
+            }
+            Ok(())
+        }
+        .boxed()
+        .into())
+    }
+
+    fn stats(&self) -> HolochainP2pHandlerResult<String> {
+        let commands = self.commands.clone();
+        Ok(async move {
+            let (respond, stats) = oneshot::channel();
+            commands
+                .send(Command::Stats { respond })
+                .map_err(driver_gone)?;
+            serde_json::to_string_pretty(&stats.await.map_err(driver_gone)?)
+                .map_err(HolochainP2pError::other)
+        }
+        .boxed()
+        .into())
+    }
+}
+
+async fn send_request(
+    commands: &UnboundedSender<Command>,
+    peer: PeerId,
+    request: Request,
+) -> HolochainP2pResult<Vec<u8>> {
+    let (respond, response) = oneshot::channel();
+    commands
+        .send(Command::Request {
+            peer,
+            request,
+            respond,
+        })
+        .map_err(driver_gone)?;
+    response.await.map_err(driver_gone)?
+}
+
+impl ghost_actor::GhostControlHandler for Libp2pActor {}
+
+impl ghost_actor::GhostHandler<HolochainP2p> for Libp2pActor {}
+
+impl HolochainP2pHandler for Libp2pActor {
+    fn handle_join(
+        &mut self,
+        dna_hash: DnaHash,
+        agent_pub_key: AgentPubKey,
+        _initial_arc: Option<crate::dht_arc::DhtArc>,
+    ) -> HolochainP2pHandlerResult<()> {
+        let first = self.local_agent(&dna_hash).is_none();
+        self.local
+            .lock()
+            .insert((dna_hash.clone(), agent_pub_key.clone()));
+        self.commands
+            .send(Command::Join {
+                dna_hash,
+                agent: agent_pub_key,
+                first,
+            })
+            .map_err(driver_gone)?;
+        Ok(async move { Ok(()) }.boxed().into())
+    }
+
+    fn handle_leave(
+        &mut self,
+        dna_hash: DnaHash,
+        agent_pub_key: AgentPubKey,
+    ) -> HolochainP2pHandlerResult<()> {
+        self.local
+            .lock()
+            .remove(&(dna_hash.clone(), agent_pub_key.clone()));
+        let last = self.local_agent(&dna_hash).is_none();
+        self.commands
+            .send(Command::Leave {
+                dna_hash,
+                agent: agent_pub_key,
+                last,
+            })
+            .map_err(driver_gone)?;
+        Ok(async move { Ok(()) }.boxed().into())
+    }
+
+    fn handle_call_remote(
+        &mut self,
+        dna_hash: DnaHash,
+        from_agent: AgentPubKey,
+        to_agent: AgentPubKey,
+        zome_name: ZomeName,
+        fn_name: FunctionName,
+        cap_secret: Option<CapSecret>,
+        payload: ExternIO,
+    ) -> HolochainP2pHandlerResult<SerializedBytes> {
+        let message = crate::wire::WireMessage::call_remote(
+            zome_name, fn_name, from_agent, cap_secret, payload,
+        )
+        .encode()?;
+        let request = self.request(dna_hash, to_agent, message);
+        Ok(async move { Ok(UnsafeBytes::from(request.await?).into()) }
+            .boxed()
+            .into())
+    }
+
+    fn handle_remote_signal(
+        &mut self,
+        dna_hash: DnaHash,
+        from_agent: AgentPubKey,
+        to_agent_list: Vec<AgentPubKey>,
+        zome_name: ZomeName,
+        fn_name: FunctionName,
+        cap: Option<CapSecret>,
+        payload: ExternIO,
+        // Requests are queued by libp2p rather than dropped at a limit.
+        _drop_at_limit: bool,
+    ) -> HolochainP2pHandlerResult<()> {
+        let message =
+            crate::wire::WireMessage::call_remote(zome_name, fn_name, from_agent, cap, payload)
+                .encode()?;
+        self.send_to_each(dna_hash, to_agent_list, message)
+    }
+
+    fn handle_publish(
+        &mut self,
+        dna_hash: DnaHash,
+        request_validation_receipt: bool,
+        countersigning_session: bool,
+        dht_hash: holo_hash::AnyDhtHash,
+        ops: Vec<holochain_types::dht_op::DhtOp>,
+        _timeout_ms: Option<u64>,
+    ) -> HolochainP2pHandlerResult<usize> {
+        let message = crate::wire::WireMessage::publish(
+            request_validation_receipt,
+            countersigning_session,
+            dht_hash,
+            ops.clone(),
+        )
+        .encode()?;
+        let payload_size = message.len();
+        // Gossipsub doesn't deliver a node's own broadcasts back to it.
+        let local_publish = self.local_agent(&dna_hash).map(|_| {
+            self.evt_sender.publish(
+                dna_hash.clone(),
+                request_validation_receipt,
+                countersigning_session,
+                ops,
+            )
+        });
+        let commands = self.commands.clone();
+        let switch = self.switch.clone();
+        Ok(async move {
+            switch.enabled().await;
+            let (respond, sent) = oneshot::channel();
+            commands
+                .send(Command::Broadcast {
+                    request: Request {
+                        dna_hash,
+                        to_agent: None,
+                        message,
+                    },
+                    respond,
+                })
+                .map_err(driver_gone)?;
+            sent.await.map_err(driver_gone)??;
+            if let Some(local_publish) = local_publish {
+                local_publish.await?;
+            }
+            Ok(payload_size)
+        }
+        .boxed()
+        .into())
+    }
+
+    fn handle_get_validation_package(
+        &mut self,
+        input: actor::GetValidationPackage,
+    ) -> HolochainP2pHandlerResult<ValidationPackageResponse> {
+        if !self.switch.is_enabled() {
+            return Ok(async move { Ok(ValidationPackageResponse(None)) }
+                .boxed()
+                .into());
+        }
+        let message =
+            crate::wire::WireMessage::get_validation_package(input.action_hash).encode()?;
+        let request = self.request(input.dna_hash, input.request_from, message);
+        Ok(async move {
+            let response = SerializedBytes::from(UnsafeBytes::from(request.await?));
+            Ok(response.try_into()?)
+        }
+        .boxed()
+        .into())
+    }
+
+    fn handle_get(
+        &mut self,
+        dna_hash: DnaHash,
+        dht_hash: holo_hash::AnyDhtHash,
+        options: actor::GetOptions,
+    ) -> HolochainP2pHandlerResult<Vec<WireOps>> {
+        let message = crate::wire::WireMessage::get(dht_hash, (&options).into()).encode()?;
+        self.get_from_all(dna_hash, message)
+    }
+
+    fn handle_get_meta(
+        &mut self,
+        dna_hash: DnaHash,
+        dht_hash: holo_hash::AnyDhtHash,
+        options: actor::GetMetaOptions,
+    ) -> HolochainP2pHandlerResult<Vec<MetadataSet>> {
+        let message = crate::wire::WireMessage::get_meta(dht_hash, (&options).into()).encode()?;
+        self.get_from_all(dna_hash, message)
+    }
+
+    fn handle_get_links(
+        &mut self,
+        dna_hash: DnaHash,
+        link_key: WireLinkKey,
+        options: actor::GetLinksOptions,
+    ) -> HolochainP2pHandlerResult<Vec<WireLinkOps>> {
+        let message = crate::wire::WireMessage::get_links(link_key, (&options).into()).encode()?;
+        self.get_from_all(dna_hash, message)
+    }
+
+    fn handle_get_agent_activity(
+        &mut self,
+        dna_hash: DnaHash,
+        agent: AgentPubKey,
+        query: ChainQueryFilter,
+        options: actor::GetActivityOptions,
+    ) -> HolochainP2pHandlerResult<Vec<AgentActivityResponse<ActionHash>>> {
+        let message = crate::wire::WireMessage::get_agent_activity(agent, query, (&options).into())
+            .encode()?;
+        self.get_from_all(dna_hash, message)
+    }
+
+    fn handle_must_get_agent_activity(
+        &mut self,
+        dna_hash: DnaHash,
+        agent: AgentPubKey,
+        filter: holochain_zome_types::chain::ChainFilter,
+    ) -> HolochainP2pHandlerResult<Vec<MustGetAgentActivityResponse>> {
+        let message = crate::wire::WireMessage::must_get_agent_activity(agent, filter).encode()?;
+        self.get_from_all(dna_hash, message)
+    }
+
+    fn handle_fetch_dna(
+        &mut self,
+        dna_hash: DnaHash,
+        requested: DnaHash,
+    ) -> HolochainP2pHandlerResult<Vec<FetchDnaResponse>> {
+        let message = crate::wire::WireMessage::fetch_dna(requested).encode()?;
+        self.get_from_all(dna_hash, message)
+    }
+
+    fn handle_send_validation_receipt(
+        &mut self,
+        dna_hash: DnaHash,
+        to_agent: AgentPubKey,
+        receipt: SerializedBytes,
+    ) -> HolochainP2pHandlerResult<()> {
+        let message = crate::wire::WireMessage::validation_receipt(receipt).encode()?;
+        let request = self.request(dna_hash, to_agent, message);
+        Ok(async move {
+            request.await?;
+            Ok(())
+        }
+        .boxed()
+        .into())
+    }
+
+    fn handle_new_integrated_data(&mut self, _dna_hash: DnaHash) -> HolochainP2pHandlerResult<()> {
+        // Ops are broadcast to the whole dna when they are published,
+        // so there is nothing to gossip.
+        Ok(async move { Ok(()) }.boxed().into())
+    }
+
+    fn handle_authority_for_hash(
+        &mut self,
+        dna_hash: DnaHash,
+        _dht_hash: AnyDhtHash,
+    ) -> HolochainP2pHandlerResult<bool> {
+        // Every node holds all the ops of the dnas it has joined.
+        let authority = self.local_agent(&dna_hash).is_some();
+        Ok(async move { Ok(authority) }.boxed().into())
+    }
+
+    fn handle_authorities_for_basis(
+        &mut self,
+        dna_hash: DnaHash,
+        _basis: AnyDhtHash,
+        limit: u32,
+    ) -> HolochainP2pHandlerResult<Vec<AgentPubKey>> {
+        // Remote agents are only looked up by key, so only the local
+        // agents are known to be authorities.
+        let agents = self
+            .local
+            .lock()
+            .iter()
+            .filter(|(dna, _)| *dna == dna_hash)
+            .map(|(_, agent)| agent.clone())
+            .take(limit as usize)
+            .collect();
+        Ok(async move { Ok(agents) }.boxed().into())
+    }
+
+    fn handle_countersigning_session_negotiation(
+        &mut self,
+        dna_hash: DnaHash,
+        agents: Vec<AgentPubKey>,
+        message: CountersigningSessionNegotiationMessage,
+    ) -> HolochainP2pHandlerResult<()> {
+        let message =
+            crate::wire::WireMessage::countersigning_session_negotiation(message).encode()?;
+        self.send_to_each(dna_hash, agents, message)
+    }
+
+    fn handle_dump_network_metrics(
+        &mut self,
+        _dna_hash: Option<DnaHash>,
+    ) -> HolochainP2pHandlerResult<String> {
+        self.stats()
+    }
+
+    fn handle_dump_network_stats(&mut self) -> HolochainP2pHandlerResult<String> {
+        self.stats()
+    }
+
+    fn handle_bootstrap_health(
+        &mut self,
+    ) -> HolochainP2pHandlerResult<Vec<kitsune_p2p_types::bootstrap::BootstrapServerHealth>> {
+        // There are no bootstrap servers, only bootstrap peers.
+        Ok(async move { Ok(Vec::new()) }.boxed().into())
+    }
+
+    fn handle_set_network_enabled(&mut self, enabled: bool) -> HolochainP2pHandlerResult<()> {
+        self.switch.set_enabled(enabled);
+        Ok(async move { Ok(()) }.boxed().into())
+    }
+
+    fn handle_update_network_config(
+        &mut self,
+        _tuning_params: kitsune_p2p_types::config::KitsuneP2pTuningParams,
+        _bootstrap_urls: Vec<kitsune_p2p::dependencies::url2::Url2>,
+    ) -> HolochainP2pHandlerResult<()> {
+        // The kitsune tuning params don't apply to libp2p, and its
+        // bootstrap peers are only dialled at startup.
+        Ok(async move { Ok(()) }.boxed().into())
+    }
+}
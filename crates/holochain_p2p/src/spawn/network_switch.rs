@@ -0,0 +1,70 @@
+//! Lets the conductor take the network offline, e.g. for an airplane mode.
+//!
+//! While offline, publishes, remote calls and signals wait for the network
+//! to be enabled again, and are sent as soon as it is. Gets return nothing
+//! rather than erroring, so the cascade falls back to local data. Ops still
+//! needing publishing are tracked in the authored database, so they are
+//! published even if the conductor restarts while offline.
+
+use ghost_actor::dependencies::tracing;
+
+/// Whether the network is enabled, which can be waited on.
+pub(crate) struct NetworkSwitch(tokio::sync::watch::Sender<bool>);
+
+impl NetworkSwitch {
+    /// The network starts enabled.
+    pub(crate) fn new() -> Self {
+        Self(tokio::sync::watch::channel(true).0)
+    }
+
+    /// Enable or disable the network.
+    pub(crate) fn set_enabled(&self, enabled: bool) {
+        if self.0.send_replace(enabled) != enabled {
+            tracing::info!(enabled, "network enabled changed");
+        }
+    }
+
+    /// Is the network enabled.
+    pub(crate) fn is_enabled(&self) -> bool {
+        *self.0.borrow()
+    }
+
+    /// Wait until the network is enabled.
+    pub(crate) async fn enabled(&self) {
+        let mut enabled = self.0.subscribe();
+        while !*enabled.borrow_and_update() {
+            // The sender lives as long as self, so this can't fail.
+            if enabled.changed().await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn waits_for_network_to_be_enabled() {
+        let switch = Arc::new(NetworkSwitch::new());
+        assert!(switch.is_enabled());
+        switch.enabled().await;
+
+        switch.set_enabled(false);
+        assert!(!switch.is_enabled());
+        let waiting = tokio::spawn({
+            let switch = switch.clone();
+            async move { switch.enabled().await }
+        });
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        assert!(!waiting.is_finished());
+
+        switch.set_enabled(true);
+        tokio::time::timeout(std::time::Duration::from_secs(1), waiting)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+}
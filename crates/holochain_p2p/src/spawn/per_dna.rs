@@ -0,0 +1,382 @@
+#![allow(clippy::too_many_arguments)]
+//! Use a different network backend for some dnas.
+//!
+//! Each call for a dna is forwarded to the backend chosen for that dna,
+//! and the events from all the backends are merged into one receiver.
+
+use crate::actor::*;
+use crate::event::*;
+use crate::*;
+
+use futures::future::FutureExt;
+use futures::StreamExt;
+use std::collections::HashMap;
+
+pub(crate) struct PerDnaActor {
+    default: HolochainP2pRef,
+    others: Vec<HolochainP2pRef>,
+    backend_for_dna: HashMap<DnaHash, HolochainP2pRef>,
+}
+
+impl PerDnaActor {
+    /// Forward the events of every backend to the sender.
+    pub fn new(
+        default: (HolochainP2pRef, HolochainP2pEventReceiver),
+        others: Vec<(Vec<DnaHash>, (HolochainP2pRef, HolochainP2pEventReceiver))>,
+        evt_sender: futures::channel::mpsc::Sender<HolochainP2pEvent>,
+    ) -> Self {
+        let (default, default_events) = default;
+        tokio::task::spawn(default_events.map(Ok).forward(evt_sender.clone()));
+        let mut backend_for_dna = HashMap::new();
+        let others = others
+            .into_iter()
+            .map(|(dna_hashes, (backend, events))| {
+                tokio::task::spawn(events.map(Ok).forward(evt_sender.clone()));
+                for dna_hash in dna_hashes {
+                    backend_for_dna.insert(dna_hash, backend.clone());
+                }
+                backend
+            })
+            .collect();
+        Self {
+            default,
+            others,
+            backend_for_dna,
+        }
+    }
+
+    /// The backend for this dna.
+    fn backend(&self, dna_hash: &DnaHash) -> &HolochainP2pRef {
+        self.backend_for_dna.get(dna_hash).unwrap_or(&self.default)
+    }
+
+    /// Every backend.
+    fn backends(&self) -> impl Iterator<Item = HolochainP2pRef> + '_ {
+        std::iter::once(&self.default)
+            .chain(self.others.iter())
+            .cloned()
+    }
+}
+
+impl ghost_actor::GhostControlHandler for PerDnaActor {}
+
+impl ghost_actor::GhostHandler<HolochainP2p> for PerDnaActor {}
+
+impl HolochainP2pHandler for PerDnaActor {
+    fn handle_join(
+        &mut self,
+        dna_hash: DnaHash,
+        agent_pub_key: AgentPubKey,
+        initial_arc: Option<crate::dht_arc::DhtArc>,
+    ) -> HolochainP2pHandlerResult<()> {
+        Ok(self
+            .backend(&dna_hash)
+            .join(dna_hash, agent_pub_key, initial_arc)
+            .boxed()
+            .into())
+    }
+
+    fn handle_leave(
+        &mut self,
+        dna_hash: DnaHash,
+        agent_pub_key: AgentPubKey,
+    ) -> HolochainP2pHandlerResult<()> {
+        Ok(self
+            .backend(&dna_hash)
+            .leave(dna_hash, agent_pub_key)
+            .boxed()
+            .into())
+    }
+
+    fn handle_call_remote(
+        &mut self,
+        dna_hash: DnaHash,
+        from_agent: AgentPubKey,
+        to_agent: AgentPubKey,
+        zome_name: ZomeName,
+        fn_name: FunctionName,
+        cap_secret: Option<CapSecret>,
+        payload: ExternIO,
+    ) -> HolochainP2pHandlerResult<SerializedBytes> {
+        Ok(self
+            .backend(&dna_hash)
+            .call_remote(
+                dna_hash, from_agent, to_agent, zome_name, fn_name, cap_secret, payload,
+            )
+            .boxed()
+            .into())
+    }
+
+    fn handle_remote_signal(
+        &mut self,
+        dna_hash: DnaHash,
+        from_agent: AgentPubKey,
+        to_agent_list: Vec<AgentPubKey>,
+        zome_name: ZomeName,
+        fn_name: FunctionName,
+        cap: Option<CapSecret>,
+        payload: ExternIO,
+        drop_at_limit: bool,
+    ) -> HolochainP2pHandlerResult<()> {
+        Ok(self
+            .backend(&dna_hash)
+            .remote_signal(
+                dna_hash,
+                from_agent,
+                to_agent_list,
+                zome_name,
+                fn_name,
+                cap,
+                payload,
+                drop_at_limit,
+            )
+            .boxed()
+            .into())
+    }
+
+    fn handle_publish(
+        &mut self,
+        dna_hash: DnaHash,
+        request_validation_receipt: bool,
+        countersigning_session: bool,
+        dht_hash: holo_hash::AnyDhtHash,
+        ops: Vec<holochain_types::dht_op::DhtOp>,
+        timeout_ms: Option<u64>,
+    ) -> HolochainP2pHandlerResult<usize> {
+        Ok(self
+            .backend(&dna_hash)
+            .publish(
+                dna_hash,
+                request_validation_receipt,
+                countersigning_session,
+                dht_hash,
+                ops,
+                timeout_ms,
+            )
+            .boxed()
+            .into())
+    }
+
+    fn handle_get_validation_package(
+        &mut self,
+        input: actor::GetValidationPackage,
+    ) -> HolochainP2pHandlerResult<ValidationPackageResponse> {
+        Ok(self
+            .backend(&input.dna_hash)
+            .get_validation_package(input)
+            .boxed()
+            .into())
+    }
+
+    fn handle_get(
+        &mut self,
+        dna_hash: DnaHash,
+        dht_hash: holo_hash::AnyDhtHash,
+        options: actor::GetOptions,
+    ) -> HolochainP2pHandlerResult<Vec<WireOps>> {
+        Ok(self
+            .backend(&dna_hash)
+            .get(dna_hash, dht_hash, options)
+            .boxed()
+            .into())
+    }
+
+    fn handle_get_meta(
+        &mut self,
+        dna_hash: DnaHash,
+        dht_hash: holo_hash::AnyDhtHash,
+        options: actor::GetMetaOptions,
+    ) -> HolochainP2pHandlerResult<Vec<MetadataSet>> {
+        Ok(self
+            .backend(&dna_hash)
+            .get_meta(dna_hash, dht_hash, options)
+            .boxed()
+            .into())
+    }
+
+    fn handle_get_links(
+        &mut self,
+        dna_hash: DnaHash,
+        link_key: WireLinkKey,
+        options: actor::GetLinksOptions,
+    ) -> HolochainP2pHandlerResult<Vec<WireLinkOps>> {
+        Ok(self
+            .backend(&dna_hash)
+            .get_links(dna_hash, link_key, options)
+            .boxed()
+            .into())
+    }
+
+    fn handle_get_agent_activity(
+        &mut self,
+        dna_hash: DnaHash,
+        agent: AgentPubKey,
+        query: ChainQueryFilter,
+        options: actor::GetActivityOptions,
+    ) -> HolochainP2pHandlerResult<Vec<AgentActivityResponse<ActionHash>>> {
+        Ok(self
+            .backend(&dna_hash)
+            .get_agent_activity(dna_hash, agent, query, options)
+            .boxed()
+            .into())
+    }
+
+    fn handle_must_get_agent_activity(
+        &mut self,
+        dna_hash: DnaHash,
+        agent: AgentPubKey,
+        filter: holochain_zome_types::chain::ChainFilter,
+    ) -> HolochainP2pHandlerResult<Vec<MustGetAgentActivityResponse>> {
+        Ok(self
+            .backend(&dna_hash)
+            .must_get_agent_activity(dna_hash, agent, filter)
+            .boxed()
+            .into())
+    }
+
+    fn handle_fetch_dna(
+        &mut self,
+        dna_hash: DnaHash,
+        requested: DnaHash,
+    ) -> HolochainP2pHandlerResult<Vec<FetchDnaResponse>> {
+        Ok(self
+            .backend(&dna_hash)
+            .fetch_dna(dna_hash, requested)
+            .boxed()
+            .into())
+    }
+
+    fn handle_send_validation_receipt(
+        &mut self,
+        dna_hash: DnaHash,
+        to_agent: AgentPubKey,
+        receipt: SerializedBytes,
+    ) -> HolochainP2pHandlerResult<()> {
+        Ok(self
+            .backend(&dna_hash)
+            .send_validation_receipt(dna_hash, to_agent, receipt)
+            .boxed()
+            .into())
+    }
+
+    fn handle_new_integrated_data(&mut self, dna_hash: DnaHash) -> HolochainP2pHandlerResult<()> {
+        Ok(self
+            .backend(&dna_hash)
+            .new_integrated_data(dna_hash)
+            .boxed()
+            .into())
+    }
+
+    fn handle_authority_for_hash(
+        &mut self,
+        dna_hash: DnaHash,
+        dht_hash: AnyDhtHash,
+    ) -> HolochainP2pHandlerResult<bool> {
+        Ok(self
+            .backend(&dna_hash)
+            .authority_for_hash(dna_hash, dht_hash)
+            .boxed()
+            .into())
+    }
+
+    fn handle_authorities_for_basis(
+        &mut self,
+        dna_hash: DnaHash,
+        basis: AnyDhtHash,
+        limit: u32,
+    ) -> HolochainP2pHandlerResult<Vec<AgentPubKey>> {
+        Ok(self
+            .backend(&dna_hash)
+            .authorities_for_basis(dna_hash, basis, limit)
+            .boxed()
+            .into())
+    }
+
+    fn handle_countersigning_session_negotiation(
+        &mut self,
+        dna_hash: DnaHash,
+        agents: Vec<AgentPubKey>,
+        message: CountersigningSessionNegotiationMessage,
+    ) -> HolochainP2pHandlerResult<()> {
+        Ok(self
+            .backend(&dna_hash)
+            .countersigning_session_negotiation(dna_hash, agents, message)
+            .boxed()
+            .into())
+    }
+
+    fn handle_dump_network_metrics(
+        &mut self,
+        dna_hash: Option<DnaHash>,
+    ) -> HolochainP2pHandlerResult<String> {
+        let backend = match &dna_hash {
+            Some(dna_hash) => self.backend(dna_hash),
+            None => &self.default,
+        };
+        Ok(backend.dump_network_metrics(dna_hash).boxed().into())
+    }
+
+    fn handle_dump_network_stats(&mut self) -> HolochainP2pHandlerResult<String> {
+        Ok(self.default.dump_network_stats().boxed().into())
+    }
+
+    fn handle_bootstrap_health(
+        &mut self,
+    ) -> HolochainP2pHandlerResult<Vec<kitsune_p2p_types::bootstrap::BootstrapServerHealth>> {
+        let checks: Vec<_> = self
+            .backends()
+            .map(|backend| async move { backend.bootstrap_health().await })
+            .collect();
+        Ok(async move {
+            let mut health = Vec::new();
+            for result in futures::future::join_all(checks).await {
+                health.extend(result?);
+            }
+            Ok(health)
+        }
+        .boxed()
+        .into())
+    }
+
+    fn handle_set_network_enabled(&mut self, enabled: bool) -> HolochainP2pHandlerResult<()> {
+        let calls: Vec<_> = self
+            .backends()
+            .map(|backend| async move { backend.set_network_enabled(enabled).await })
+            .collect();
+        Ok(async move {
+            for result in futures::future::join_all(calls).await {
+                result?;
+            }
+            Ok(())
+        }
+        .boxed()
+        .into())
+    }
+
+    fn handle_update_network_config(
+        &mut self,
+        tuning_params: kitsune_p2p_types::config::KitsuneP2pTuningParams,
+        bootstrap_urls: Vec<kitsune_p2p::dependencies::url2::Url2>,
+    ) -> HolochainP2pHandlerResult<()> {
+        let calls: Vec<_> = self
+            .backends()
+            .map(|backend| {
+                let tuning_params = tuning_params.clone();
+                let bootstrap_urls = bootstrap_urls.clone();
+                async move {
+                    backend
+                        .update_network_config(tuning_params, bootstrap_urls)
+                        .await
+                }
+            })
+            .collect();
+        Ok(async move {
+            for result in futures::future::join_all(calls).await {
+                result?;
+            }
+            Ok(())
+        }
+        .boxed()
+        .into())
+    }
+}
@@ -0,0 +1,128 @@
+//! How many authorities a publish is sent to.
+//!
+//! Rather than sending ops to every agent found near their basis, a publish
+//! goes to the `publish_authority_count` authorities nearest the basis. The
+//! publish workflow only publishes an op again while it lacks validation
+//! receipts, so each further publish of the same op doubles the fanout,
+//! until it reaches every authority we know of. New ops start again from
+//! the smallest fanout, however often their basis has been published to.
+
+use holo_hash::DhtOpHash;
+use holo_hash::DnaHash;
+use kitsune_p2p_types::config::KitsuneP2pTuningParams;
+use std::collections::HashMap;
+use tokio::time::Duration;
+use tokio::time::Instant;
+
+/// How long since an op was last published before we start again
+/// from the smallest fanout.
+const FORGET_AFTER: Duration = Duration::from_secs(60 * 60);
+
+/// How often an op has been published.
+struct Publishes {
+    count: u32,
+    last: Instant,
+}
+
+/// The publishes of every op still waiting for receipts, by dna.
+pub(crate) struct PublishFanout {
+    tuning_params: KitsuneP2pTuningParams,
+    publishes: HashMap<(DnaHash, DhtOpHash), Publishes>,
+    last_pruned: Instant,
+}
+
+impl PublishFanout {
+    pub(crate) fn new(tuning_params: KitsuneP2pTuningParams) -> Self {
+        Self {
+            tuning_params,
+            publishes: HashMap::new(),
+            last_pruned: Instant::now(),
+        }
+    }
+
+    /// Switch to a new authority count, keeping the publish counts.
+    pub(crate) fn set_tuning_params(&mut self, tuning_params: KitsuneP2pTuningParams) {
+        self.tuning_params = tuning_params;
+    }
+
+    /// Count a publish of these ops, returning how many authorities they
+    /// should be sent to, or `None` to send them to every agent near the
+    /// basis. The ops are sent as wide as the most published of them needs.
+    pub(crate) fn next(
+        &mut self,
+        dna_hash: &DnaHash,
+        op_hashes: impl IntoIterator<Item = DhtOpHash>,
+    ) -> Option<u32> {
+        let first = self.tuning_params.publish_authority_count;
+        if first == 0 {
+            return None;
+        }
+        let now = Instant::now();
+        if now.duration_since(self.last_pruned) >= FORGET_AFTER {
+            self.publishes
+                .retain(|_, publishes| now.duration_since(publishes.last) < FORGET_AFTER);
+            self.last_pruned = now;
+        }
+        let mut doublings = 0;
+        for op_hash in op_hashes {
+            let publishes =
+                self.publishes
+                    .entry((dna_hash.clone(), op_hash))
+                    .or_insert(Publishes {
+                        count: 0,
+                        last: now,
+                    });
+            if now.duration_since(publishes.last) >= FORGET_AFTER {
+                publishes.count = 0;
+            }
+            doublings = doublings.max(publishes.count);
+            publishes.count = publishes.count.saturating_add(1);
+            publishes.last = now;
+        }
+        Some(first.saturating_mul(2u32.saturating_pow(doublings)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::fixt::prelude::*;
+    use holo_hash::fixt::DhtOpHashFixturator;
+    use holo_hash::fixt::DnaHashFixturator;
+    use kitsune_p2p_types::config::tuning_params_struct;
+    use std::sync::Arc;
+
+    #[tokio::test(start_paused = true)]
+    async fn fanout_doubles_for_each_publish_of_an_op() {
+        let mut params = tuning_params_struct::KitsuneP2pTuningParams::default();
+        params.publish_authority_count = 3;
+        let mut fanout = PublishFanout::new(Arc::new(params));
+        let dna_hash = fixt!(DnaHash);
+        let op = fixt!(DhtOpHash);
+
+        assert_eq!(fanout.next(&dna_hash, vec![op.clone()]), Some(3));
+        assert_eq!(fanout.next(&dna_hash, vec![op.clone()]), Some(6));
+        assert_eq!(fanout.next(&dna_hash, vec![op.clone()]), Some(12));
+
+        // New ops start from the smallest fanout.
+        assert_eq!(fanout.next(&dna_hash, vec![fixt!(DhtOpHash)]), Some(3));
+
+        // Unless they are published with an op which is still missing receipts.
+        assert_eq!(
+            fanout.next(&dna_hash, vec![fixt!(DhtOpHash), op.clone()]),
+            Some(24)
+        );
+
+        // An op starts again once it hasn't been published for a while.
+        tokio::time::advance(FORGET_AFTER).await;
+        assert_eq!(fanout.next(&dna_hash, vec![op]), Some(3));
+    }
+
+    #[test]
+    fn zero_publishes_to_every_agent() {
+        let mut params = tuning_params_struct::KitsuneP2pTuningParams::default();
+        params.publish_authority_count = 0;
+        let mut fanout = PublishFanout::new(Arc::new(params));
+        assert_eq!(fanout.next(&fixt!(DnaHash), vec![fixt!(DhtOpHash)]), None);
+    }
+}
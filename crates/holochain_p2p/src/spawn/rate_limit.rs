@@ -0,0 +1,159 @@
+//! Per peer quotas for the requests remote peers send us.
+//!
+//! Each remote peer, identified by its TLS certificate, may send a number of
+//! gets, remote calls and publishes each minute. Requests reflected from our
+//! own agents have no peer and are never limited.
+
+use crate::actor::HolochainP2pResult;
+use crate::wire::WireMessage;
+use crate::HolochainP2pError;
+use holochain_types::share::RwShare;
+use kitsune_p2p_types::config::KitsuneP2pTuningParams;
+use kitsune_p2p_types::Tx2Cert;
+use std::collections::HashMap;
+use tokio::time::Duration;
+use tokio::time::Instant;
+
+/// How long a quota lasts before the counts are reset.
+const QUOTA_WINDOW: Duration = Duration::from_secs(60);
+
+/// The kinds of request which have separate quotas.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum RequestKind {
+    /// Gets of records, links, metadata, agent activity or validation packages.
+    Get,
+    /// Remote zome calls and remote signals.
+    Call,
+    /// Publishes of ops.
+    Publish,
+}
+
+impl RequestKind {
+    /// The quota a message counts towards, if any.
+    pub(crate) fn of(message: &WireMessage) -> Option<Self> {
+        match message {
+            WireMessage::Get { .. }
+            | WireMessage::GetMeta { .. }
+            | WireMessage::GetLinks { .. }
+            | WireMessage::GetAgentActivity { .. }
+            | WireMessage::MustGetAgentActivity { .. }
+            | WireMessage::GetValidationPackage { .. }
+            | WireMessage::FetchDna { .. } => Some(RequestKind::Get),
+            WireMessage::CallRemote { .. } => Some(RequestKind::Call),
+            WireMessage::Publish { .. } => Some(RequestKind::Publish),
+            WireMessage::ValidationReceipt { .. }
+            | WireMessage::CountersigningSessionNegotiation { .. } => None,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            RequestKind::Get => "gets",
+            RequestKind::Call => "calls",
+            RequestKind::Publish => "publishes",
+        }
+    }
+}
+
+/// Counts of the requests each peer has sent in the current window.
+struct Window {
+    started: Instant,
+    counts: HashMap<(Tx2Cert, RequestKind), u32>,
+}
+
+/// The quotas for every remote peer.
+pub(crate) struct PeerQuotas {
+    tuning_params: KitsuneP2pTuningParams,
+    window: RwShare<Window>,
+}
+
+impl PeerQuotas {
+    pub(crate) fn new(tuning_params: KitsuneP2pTuningParams) -> Self {
+        Self {
+            tuning_params,
+            window: RwShare::new(Window {
+                started: Instant::now(),
+                counts: HashMap::new(),
+            }),
+        }
+    }
+
+    /// Switch to new quotas, keeping the counts of the current window.
+    pub(crate) fn set_tuning_params(&mut self, tuning_params: KitsuneP2pTuningParams) {
+        self.tuning_params = tuning_params;
+    }
+
+    /// Count a request from a peer, or return a throttle error if the peer
+    /// has already used up its quota for this kind of request.
+    pub(crate) fn check(
+        &self,
+        from_peer: Option<&Tx2Cert>,
+        kind: RequestKind,
+    ) -> HolochainP2pResult<()> {
+        let limit = self.limit(kind);
+        let peer = match from_peer {
+            Some(peer) if limit > 0 => peer,
+            _ => return Ok(()),
+        };
+        self.window.share_mut(|window| {
+            let now = Instant::now();
+            if now.duration_since(window.started) >= QUOTA_WINDOW {
+                window.started = now;
+                window.counts.clear();
+            }
+            let count = window.counts.entry((peer.clone(), kind)).or_insert(0);
+            if *count >= limit {
+                return Err(HolochainP2pError::Throttled {
+                    peer: peer.as_nick().to_string(),
+                    kind: kind.name(),
+                    limit,
+                });
+            }
+            *count += 1;
+            Ok(())
+        })
+    }
+
+    fn limit(&self, kind: RequestKind) -> u32 {
+        match kind {
+            RequestKind::Get => self.tuning_params.inbound_gets_per_peer_per_minute,
+            RequestKind::Call => self.tuning_params.inbound_calls_per_peer_per_minute,
+            RequestKind::Publish => self.tuning_params.inbound_publishes_per_peer_per_minute,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kitsune_p2p_types::config::tuning_params_struct;
+    use std::sync::Arc;
+
+    #[tokio::test(start_paused = true)]
+    async fn peers_are_throttled_per_kind_until_the_window_ends() {
+        let mut params = tuning_params_struct::KitsuneP2pTuningParams::default();
+        params.inbound_gets_per_peer_per_minute = 2;
+        let quotas = PeerQuotas::new(Arc::new(params));
+        let alice = Tx2Cert::from(vec![1; 32]);
+        let bob = Tx2Cert::from(vec![2; 32]);
+
+        quotas.check(Some(&alice), RequestKind::Get).unwrap();
+        quotas.check(Some(&alice), RequestKind::Get).unwrap();
+        assert!(matches!(
+            quotas.check(Some(&alice), RequestKind::Get),
+            Err(HolochainP2pError::Throttled {
+                kind: "gets",
+                limit: 2,
+                ..
+            })
+        ));
+
+        // Other peers, unlimited kinds and local requests are unaffected.
+        quotas.check(Some(&bob), RequestKind::Get).unwrap();
+        quotas.check(Some(&alice), RequestKind::Call).unwrap();
+        quotas.check(None, RequestKind::Get).unwrap();
+
+        tokio::time::advance(QUOTA_WINDOW).await;
+        quotas.check(Some(&alice), RequestKind::Get).unwrap();
+    }
+}
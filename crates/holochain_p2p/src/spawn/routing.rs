@@ -0,0 +1,89 @@
+//! Choosing the authorities for a basis.
+//!
+//! Kitsune publishes ops and makes gets to the agents it finds near the
+//! basis of the data, which it asks holochain for, and validation receipts
+//! are signed by the local agents which are authorities for the basis of
+//! the op. All of them use the ranking here: the agents whose storage arcs
+//! cover the basis come first, nearest to it by their own location, followed
+//! by the agents whose arcs come nearest to covering it.
+
+use kitsune_p2p::agent_store::AgentInfoSigned;
+use kitsune_p2p::KitsuneBinType;
+use kitsune_p2p_types::dht_arc::DhtArc;
+use kitsune_p2p_types::dht_arc::DhtLocation;
+
+/// Sort agents by how well placed they are to be authorities for this basis,
+/// best first.
+pub(crate) fn rank_authorities(basis: DhtLocation, agents: &mut [AgentInfoSigned]) {
+    agents
+        .sort_by_cached_key(|info| authority_rank(basis, info.agent.get_loc(), &info.storage_arc));
+}
+
+/// The agents whose storage arcs cover this basis, best placed first.
+pub(crate) fn authorities(
+    basis: DhtLocation,
+    mut agents: Vec<AgentInfoSigned>,
+) -> impl Iterator<Item = AgentInfoSigned> {
+    rank_authorities(basis, &mut agents);
+    agents
+        .into_iter()
+        .take_while(move |info| info.storage_arc.contains(basis))
+}
+
+/// Lower is better: how far the arc is from covering the basis,
+/// then how far the agent is from the basis.
+fn authority_rank(basis: DhtLocation, agent: DhtLocation, arc: &DhtArc) -> (u32, u32) {
+    let arc_distance = match arc.to_bounds_grouped() {
+        None => u32::MAX,
+        Some(_) if arc.contains(basis) => 0,
+        Some((start, end)) => distance(start, basis).min(distance(end, basis)),
+    };
+    (arc_distance, distance(agent, basis))
+}
+
+/// The distance between two locations the shortest way around the DHT.
+fn distance(a: DhtLocation, b: DhtLocation) -> u32 {
+    let d = a.as_u32().wrapping_sub(b.as_u32());
+    d.min(d.wrapping_neg())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn covering_agents_rank_first_by_distance() {
+        let basis = DhtLocation::from(100u32);
+        let wide = DhtArc::from_bounds(0u32, 1000u32);
+        let near_arc = DhtArc::from_bounds(200u32, 300u32);
+        let far_arc = DhtArc::from_bounds(5000u32, 6000u32);
+        let empty = DhtArc::empty(DhtLocation::from(100u32));
+
+        let near_agent = authority_rank(basis, DhtLocation::from(90u32), &wide);
+        let far_agent = authority_rank(basis, DhtLocation::from(900u32), &wide);
+        assert_eq!(near_agent, (0, 10));
+        assert!(near_agent < far_agent);
+        assert!(far_agent < authority_rank(basis, DhtLocation::from(100u32), &near_arc));
+        assert!(
+            authority_rank(basis, DhtLocation::from(250u32), &near_arc)
+                < authority_rank(basis, DhtLocation::from(5500u32), &far_arc)
+        );
+        assert_eq!(authority_rank(basis, basis, &empty).0, u32::MAX);
+    }
+
+    #[test]
+    fn distance_wraps_around_the_dht() {
+        assert_eq!(
+            distance(DhtLocation::from(u32::MAX), DhtLocation::from(1u32)),
+            2
+        );
+        assert_eq!(
+            distance(DhtLocation::from(1u32), DhtLocation::from(u32::MAX)),
+            2
+        );
+        assert_eq!(
+            distance(DhtLocation::from(10u32), DhtLocation::from(10u32)),
+            0
+        );
+    }
+}
@@ -49,6 +49,7 @@ impl HolochainP2pHandler for StubNetwork {
         fn_name: FunctionName,
         cap: Option<CapSecret>,
         payload: ExternIO,
+        drop_at_limit: bool,
     ) -> HolochainP2pHandlerResult<()> {
         Err("stub".into())
     }
@@ -110,6 +111,13 @@ impl HolochainP2pHandler for StubNetwork {
     ) -> HolochainP2pHandlerResult<Vec<MustGetAgentActivityResponse>> {
         Err("stub".into())
     }
+    fn handle_fetch_dna(
+        &mut self,
+        dna_hash: DnaHash,
+        requested: DnaHash,
+    ) -> HolochainP2pHandlerResult<Vec<event::FetchDnaResponse>> {
+        Err("stub".into())
+    }
     fn handle_send_validation_receipt(
         &mut self,
         dna_hash: DnaHash,
@@ -128,6 +136,14 @@ impl HolochainP2pHandler for StubNetwork {
     ) -> HolochainP2pHandlerResult<bool> {
         Err("stub".into())
     }
+    fn handle_authorities_for_basis(
+        &mut self,
+        dna_hash: DnaHash,
+        basis: AnyDhtHash,
+        limit: u32,
+    ) -> HolochainP2pHandlerResult<Vec<AgentPubKey>> {
+        Err("stub".into())
+    }
     fn handle_countersigning_session_negotiation(
         &mut self,
         dna_hash: DnaHash,
@@ -142,6 +158,24 @@ impl HolochainP2pHandler for StubNetwork {
     ) -> HolochainP2pHandlerResult<String> {
         Err("stub".into())
     }
+    fn handle_dump_network_stats(&mut self) -> HolochainP2pHandlerResult<String> {
+        Err("stub".into())
+    }
+    fn handle_bootstrap_health(
+        &mut self,
+    ) -> HolochainP2pHandlerResult<Vec<kitsune_p2p_types::bootstrap::BootstrapServerHealth>> {
+        Err("stub".into())
+    }
+    fn handle_set_network_enabled(&mut self, enabled: bool) -> HolochainP2pHandlerResult<()> {
+        Err("stub".into())
+    }
+    fn handle_update_network_config(
+        &mut self,
+        tuning_params: kitsune_p2p_types::config::KitsuneP2pTuningParams,
+        bootstrap_urls: Vec<kitsune_p2p::dependencies::url2::Url2>,
+    ) -> HolochainP2pHandlerResult<()> {
+        Err("stub".into())
+    }
 }
 
 /// Spawn a stub network that doesn't respond to any messages.
@@ -276,6 +310,191 @@ mod tests {
         r_task.await.unwrap();
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_in_memory_network() {
+        let (dna, a1, a2, a3) = test_setup();
+
+        let network = InMemoryNetwork::new();
+        let (p2p1, mut evt1) = spawn_in_memory_holochain_p2p(network.clone())
+            .await
+            .unwrap();
+        let (p2p2, mut evt2) = spawn_in_memory_holochain_p2p(network).await.unwrap();
+
+        let r_task1 = tokio::task::spawn(async move {
+            use tokio_stream::StreamExt;
+            while let Some(evt) = evt1.next().await {
+                if let crate::types::event::HolochainP2pEvent::Publish { respond, .. } = evt {
+                    respond.r(Ok(async move { Ok(()) }.boxed().into()));
+                }
+            }
+        });
+
+        let publish_count = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let r_task2 = tokio::task::spawn({
+            let publish_count = publish_count.clone();
+            async move {
+                use tokio_stream::StreamExt;
+                while let Some(evt) = evt2.next().await {
+                    use crate::types::event::HolochainP2pEvent::*;
+                    match evt {
+                        CallRemote { respond, .. } => {
+                            respond.r(Ok(
+                                async move { Ok(UnsafeBytes::from(b"yada".to_vec()).into()) }
+                                    .boxed()
+                                    .into(),
+                            ));
+                        }
+                        Publish { respond, .. } => {
+                            publish_count.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                            respond.r(Ok(async move { Ok(()) }.boxed().into()));
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        });
+
+        p2p1.join(dna.clone(), a1.clone(), None).await.unwrap();
+        p2p2.join(dna.clone(), a2.clone(), None).await.unwrap();
+
+        let res = p2p1
+            .call_remote(
+                dna.clone(),
+                a1.clone(),
+                a2.clone(),
+                "".into(),
+                "".into(),
+                None,
+                ExternIO::encode(b"yippo").unwrap(),
+            )
+            .await
+            .unwrap();
+        let res: Vec<u8> = UnsafeBytes::from(res).into();
+        assert_eq!(b"yada".to_vec(), res);
+
+        // Agents which haven't joined can't be reached.
+        assert!(p2p1
+            .call_remote(
+                dna.clone(),
+                a1.clone(),
+                a3,
+                "".into(),
+                "".into(),
+                None,
+                ExternIO::encode(()).unwrap(),
+            )
+            .await
+            .is_err());
+
+        let mut authorities = p2p1
+            .authorities_for_basis(dna.clone(), a1.clone().into(), u32::MAX)
+            .await
+            .unwrap();
+        authorities.sort();
+        let mut expected = vec![a1.clone(), a2.clone()];
+        expected.sort();
+        assert_eq!(authorities, expected);
+
+        let basis: AnyDhtHash = a1.clone().into();
+        p2p1.publish(dna.clone(), false, false, basis.clone(), vec![], None)
+            .await
+            .unwrap();
+        assert_eq!(publish_count.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        // Once the agent leaves, its node no longer receives publishes.
+        p2p2.leave(dna.clone(), a2).await.unwrap();
+        p2p1.publish(dna, false, false, basis, vec![], None)
+            .await
+            .unwrap();
+        assert_eq!(publish_count.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        p2p1.ghost_actor_shutdown().await.unwrap();
+        p2p2.ghost_actor_shutdown().await.unwrap();
+        r_task1.await.unwrap();
+        r_task2.await.unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_per_dna_network() {
+        let (dna1, a1, a2, a3) = test_setup();
+        let dna2 = newhash!(DnaHash, 't');
+
+        /// Answer every remote call with these bytes.
+        fn respond_with(
+            mut evt: crate::event::HolochainP2pEventReceiver,
+            reply: &'static [u8],
+        ) -> tokio::task::JoinHandle<()> {
+            tokio::task::spawn(async move {
+                use tokio_stream::StreamExt;
+                while let Some(evt) = evt.next().await {
+                    if let crate::types::event::HolochainP2pEvent::CallRemote { respond, .. } = evt
+                    {
+                        respond.r(Ok(
+                            async move { Ok(UnsafeBytes::from(reply.to_vec()).into()) }
+                                .boxed()
+                                .into(),
+                        ));
+                    }
+                }
+            })
+        }
+
+        let network_a = InMemoryNetwork::new();
+        let network_b = InMemoryNetwork::new();
+        let (p2p1, evt1) = spawn_per_dna_holochain_p2p(
+            spawn_in_memory_holochain_p2p(network_a.clone())
+                .await
+                .unwrap(),
+            vec![(
+                vec![dna2.clone()],
+                spawn_in_memory_holochain_p2p(network_b.clone())
+                    .await
+                    .unwrap(),
+            )],
+        )
+        .await
+        .unwrap();
+        let (p2p2, evt2) = spawn_in_memory_holochain_p2p(network_a).await.unwrap();
+        let (p2p3, evt3) = spawn_in_memory_holochain_p2p(network_b).await.unwrap();
+        let r_tasks = vec![
+            respond_with(evt1, b"one"),
+            respond_with(evt2, b"two"),
+            respond_with(evt3, b"three"),
+        ];
+
+        p2p1.join(dna1.clone(), a1.clone(), None).await.unwrap();
+        p2p1.join(dna2.clone(), a1.clone(), None).await.unwrap();
+        p2p2.join(dna1.clone(), a2.clone(), None).await.unwrap();
+        p2p3.join(dna2.clone(), a3.clone(), None).await.unwrap();
+
+        let call = |p2p: &HolochainP2pRef, dna: &DnaHash, from: &AgentPubKey, to: &AgentPubKey| {
+            p2p.call_remote(
+                dna.clone(),
+                from.clone(),
+                to.clone(),
+                "".into(),
+                "".into(),
+                None,
+                ExternIO::encode(()).unwrap(),
+            )
+            .map(|res| res.map(|res| Vec::<u8>::from(UnsafeBytes::from(res))))
+        };
+
+        // Each dna's calls go over its own network, in both directions.
+        assert_eq!(call(&p2p1, &dna1, &a1, &a2).await.unwrap(), b"two");
+        assert_eq!(call(&p2p1, &dna2, &a1, &a3).await.unwrap(), b"three");
+        assert_eq!(call(&p2p2, &dna1, &a2, &a1).await.unwrap(), b"one");
+        assert_eq!(call(&p2p3, &dna2, &a3, &a1).await.unwrap(), b"one");
+        assert!(call(&p2p1, &dna1, &a1, &a3).await.is_err());
+
+        p2p1.ghost_actor_shutdown().await.unwrap();
+        p2p2.ghost_actor_shutdown().await.unwrap();
+        p2p3.ghost_actor_shutdown().await.unwrap();
+        for r_task in r_tasks {
+            r_task.abort();
+        }
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_send_validation_receipt_workflow() {
         let (dna, a1, a2, _) = test_setup();
@@ -389,6 +608,189 @@ mod tests {
         r_task.await.unwrap();
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_publish_waits_for_network() {
+        let (dna, a1, a2, _) = test_setup();
+
+        let (p2p, mut evt) = spawn_holochain_p2p(
+            KitsuneP2pConfig::default(),
+            TlsConfig::new_ephemeral().await.unwrap(),
+            kitsune_p2p::HostStub::new(),
+        )
+        .await
+        .unwrap();
+
+        let recv_count = Arc::new(std::sync::atomic::AtomicU8::new(0));
+
+        let recv_count_clone = recv_count.clone();
+        let r_task = tokio::task::spawn(async move {
+            use tokio_stream::StreamExt;
+            while let Some(evt) = evt.next().await {
+                use crate::types::event::HolochainP2pEvent::*;
+                match evt {
+                    Publish { respond, .. } => {
+                        respond.r(Ok(async move { Ok(()) }.boxed().into()));
+                        recv_count_clone.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                    }
+                    SignNetworkData { respond, .. } => {
+                        respond.r(Ok(async move { Ok([0; 64].into()) }.boxed().into()));
+                    }
+                    PutAgentInfoSigned { respond, .. } => {
+                        respond.r(Ok(async move { Ok(()) }.boxed().into()));
+                    }
+                    QueryAgentInfoSigned { respond, .. } => {
+                        respond.r(Ok(async move { Ok(vec![]) }.boxed().into()));
+                    }
+                    TakeQueuedRemoteSignals { respond, .. } => {
+                        respond.r(Ok(async move { Ok(vec![]) }.boxed().into()));
+                    }
+                    QueryPeerDensity { respond, .. } => {
+                        let view = test_peer_view();
+                        respond.r(Ok(async move { Ok(view) }.boxed().into()));
+                    }
+                    _ => {}
+                }
+            }
+        });
+
+        p2p.join(dna.clone(), a1.clone(), None).await.unwrap();
+        p2p.join(dna.clone(), a2.clone(), None).await.unwrap();
+        p2p.set_network_enabled(false).await.unwrap();
+
+        let action_hash = holo_hash::AnyDhtHash::from_raw_36_and_type(
+            b"eeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee".to_vec(),
+            holo_hash::hash_type::AnyDht::Action,
+        );
+
+        // Gets don't wait for the network, they only return local data.
+        let res = p2p
+            .get(
+                dna.clone(),
+                action_hash.clone(),
+                actor::GetOptions::default(),
+            )
+            .await
+            .unwrap();
+        assert!(res.is_empty());
+
+        let publish = tokio::task::spawn({
+            let p2p = p2p.clone();
+            async move {
+                p2p.publish(dna, true, false, action_hash, vec![], Some(200))
+                    .await
+            }
+        });
+
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+        assert!(!publish.is_finished());
+        assert_eq!(0, recv_count.load(std::sync::atomic::Ordering::SeqCst));
+
+        p2p.set_network_enabled(true).await.unwrap();
+        let _ = publish.await.unwrap();
+        assert_eq!(2, recv_count.load(std::sync::atomic::Ordering::SeqCst));
+
+        p2p.ghost_actor_shutdown().await.unwrap();
+        r_task.await.unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_remote_signals_are_queued_while_offline() {
+        let (dna, a1, a2, _) = test_setup();
+
+        let (p2p, mut evt) = spawn_holochain_p2p(
+            KitsuneP2pConfig::default(),
+            TlsConfig::new_ephemeral().await.unwrap(),
+            kitsune_p2p::HostStub::new(),
+        )
+        .await
+        .unwrap();
+
+        let queue = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recv_count = Arc::new(std::sync::atomic::AtomicU8::new(0));
+
+        let queue_clone = queue.clone();
+        let recv_count_clone = recv_count.clone();
+        let r_task = tokio::task::spawn(async move {
+            use tokio_stream::StreamExt;
+            while let Some(evt) = evt.next().await {
+                use crate::types::event::HolochainP2pEvent::*;
+                match evt {
+                    CallRemote { respond, .. } => {
+                        recv_count_clone.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                        respond.r(Ok(async move { Ok(UnsafeBytes::from(vec![]).into()) }
+                            .boxed()
+                            .into()));
+                    }
+                    QueueRemoteSignal {
+                        to_agent_list,
+                        payload,
+                        respond,
+                        ..
+                    } => {
+                        queue_clone.lock().unwrap().push(event::QueuedRemoteSignal {
+                            to_agent_list,
+                            payload,
+                        });
+                        respond.r(Ok(async move { Ok(()) }.boxed().into()));
+                    }
+                    TakeQueuedRemoteSignals { respond, .. } => {
+                        let queued = std::mem::take(&mut *queue_clone.lock().unwrap());
+                        respond.r(Ok(async move { Ok(queued) }.boxed().into()));
+                    }
+                    SignNetworkData { respond, .. } => {
+                        respond.r(Ok(async move { Ok([0; 64].into()) }.boxed().into()));
+                    }
+                    PutAgentInfoSigned { respond, .. } => {
+                        respond.r(Ok(async move { Ok(()) }.boxed().into()));
+                    }
+                    QueryPeerDensity { respond, .. } => {
+                        let view = test_peer_view();
+                        respond.r(Ok(async move { Ok(view) }.boxed().into()));
+                    }
+                    _ => {}
+                }
+            }
+        });
+
+        p2p.join(dna.clone(), a1.clone(), None).await.unwrap();
+        p2p.join(dna.clone(), a2.clone(), None).await.unwrap();
+        p2p.set_network_enabled(false).await.unwrap();
+
+        // The signal is stored rather than waiting for the network.
+        p2p.remote_signal(
+            dna,
+            a1,
+            vec![a2.clone()],
+            "".into(),
+            "".into(),
+            None,
+            ExternIO::encode(b"yippo").unwrap(),
+            false,
+        )
+        .await
+        .unwrap();
+        {
+            let queue = queue.lock().unwrap();
+            assert_eq!(1, queue.len());
+            assert_eq!(vec![a2], queue[0].to_agent_list);
+        }
+        assert_eq!(0, recv_count.load(std::sync::atomic::Ordering::SeqCst));
+
+        // It's sent once the network is enabled again.
+        p2p.set_network_enabled(true).await.unwrap();
+        tokio::time::timeout(std::time::Duration::from_secs(10), async {
+            while recv_count.load(std::sync::atomic::Ordering::SeqCst) == 0 {
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+        assert!(queue.lock().unwrap().is_empty());
+
+        p2p.ghost_actor_shutdown().await.unwrap();
+        r_task.await.unwrap();
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_get_workflow() {
         observability::test_run().ok();
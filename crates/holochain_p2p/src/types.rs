@@ -26,6 +26,17 @@ pub enum HolochainP2pError {
     #[error("InvalidP2pMessage: {0}")]
     InvalidP2pMessage(String),
 
+    /// A remote peer has sent more requests of a kind than its quota allows
+    #[error("Throttled: {peer} has exceeded its quota of {limit} {kind} per minute")]
+    Throttled {
+        /// The nickname of the peer's certificate
+        peer: String,
+        /// The kind of request which was throttled
+        kind: &'static str,
+        /// How many requests of this kind the peer may send per minute
+        limit: u32,
+    },
+
     /// Other
     #[error("Other: {0}")]
     Other(Box<dyn std::error::Error + Send + Sync>),
@@ -107,6 +118,7 @@ pub mod event;
 #[cfg(feature = "mock_network")]
 pub mod mock_network;
 
+pub mod trace_context;
 pub(crate) mod wire;
 
 pub use wire::WireDhtOpData;
@@ -46,6 +46,25 @@ pub struct GetOptions {
     /// Set to `None` for a default "best-effort" race.
     pub race_timeout_ms: Option<u64>,
 
+    /// ```[Network]```
+    /// If set, ask the best authority first and only ask the next one
+    /// once this long has passed without a response, or the previous
+    /// request has failed. The first response received is returned.
+    /// Set to `None` for the default from the tuning params.
+    pub hedge_delay_ms: Option<u64>,
+
+    /// ```[Network]```
+    /// How many times to retry the get if no authority responds.
+    /// Set to `None` for the default from the tuning params.
+    pub retry_count: Option<u8>,
+
+    /// ```[Network]```
+    /// If set, the get is only successful when at least this many
+    /// authorities send the same response. Authorities are asked
+    /// all at once, ignoring `hedge_delay_ms`.
+    /// This is checked by the cascade.
+    pub quorum: Option<u8>,
+
     /// ```[Remote]```
     /// Whether the remote-end should follow redirects or just return the
     /// requested entry.
@@ -68,6 +87,9 @@ impl Default for GetOptions {
             timeout_ms: None,
             as_race: true,
             race_timeout_ms: None,
+            hedge_delay_ms: None,
+            retry_count: None,
+            quorum: None,
             follow_redirects: true,
             all_live_actions_with_metadata: false,
             request_type: Default::default(),
@@ -84,6 +106,9 @@ impl GetOptions {
             timeout_ms: None,
             as_race: true,
             race_timeout_ms: None,
+            hedge_delay_ms: None,
+            retry_count: None,
+            quorum: None,
             // Never redirect as the returned value must always match the hash.
             follow_redirects: false,
             all_live_actions_with_metadata: false,
@@ -94,8 +119,15 @@ impl GetOptions {
 }
 
 impl From<holochain_zome_types::entry::GetOptions> for GetOptions {
-    fn from(_: holochain_zome_types::entry::GetOptions) -> Self {
-        Self::default()
+    fn from(options: holochain_zome_types::entry::GetOptions) -> Self {
+        match options.quorum {
+            Some(quorum) => Self {
+                remote_agent_count: Some(quorum),
+                quorum: Some(quorum),
+                ..Self::default()
+            },
+            None => Self::default(),
+        }
     }
 }
 
@@ -227,7 +259,8 @@ ghost_actor::ghost_chan! {
         /// Invoke a zome function on a remote node (if you have been granted the capability).
         /// This is a fire-and-forget operation, a best effort will be made
         /// to forward the signal, but if the conductor network is overworked
-        /// it may decide not to deliver some of the signals.
+        /// and `drop_at_limit` is set it may decide not to deliver some of
+        /// the signals. Otherwise this waits for the network to take them.
         fn remote_signal(
             dna_hash: DnaHash,
             from_agent: AgentPubKey,
@@ -236,6 +269,7 @@ ghost_actor::ghost_chan! {
             fn_name: FunctionName,
             cap: Option<CapSecret>,
             payload: ExternIO,
+            drop_at_limit: bool,
         ) -> ();
 
         /// Publish data to the correct neighborhood.
@@ -287,6 +321,11 @@ ghost_actor::ghost_chan! {
             filter: holochain_zome_types::chain::ChainFilter,
         ) -> Vec<MustGetAgentActivityResponse>;
 
+        /// Request a DNA which this node doesn't have from the peers of a
+        /// DNA it has joined. Peers only serve a DNA on its own network, so
+        /// this is the DNA's network, joined to ask them.
+        fn fetch_dna(dna_hash: DnaHash, requested: DnaHash) -> Vec<event::FetchDnaResponse>;
+
         /// Send a validation receipt to a remote node.
         fn send_validation_receipt(dna_hash: DnaHash, to_agent: AgentPubKey, receipt: SerializedBytes) -> ();
 
@@ -296,6 +335,10 @@ ghost_actor::ghost_chan! {
         /// Check if any local agent in this space is an authority for a hash.
         fn authority_for_hash(dna_hash: DnaHash, dht_hash: AnyDhtHash) -> bool;
 
+        /// The known agents whose storage arcs cover a basis, nearest first,
+        /// which are the agents that publishes and gets for it are sent to.
+        fn authorities_for_basis(dna_hash: DnaHash, basis: AnyDhtHash, limit: u32) -> Vec<AgentPubKey>;
+
         /// Messages between agents negotiation a countersigning session.
         fn countersigning_session_negotiation(
             dna_hash: DnaHash,
@@ -307,6 +350,26 @@ ghost_actor::ghost_chan! {
         fn dump_network_metrics(
             dna_hash: Option<DnaHash>,
         ) -> String;
+
+        /// Dump a summary of network stats for the whole conductor.
+        fn dump_network_stats() -> String;
+
+        /// How each configured bootstrap server has been responding.
+        fn bootstrap_health() -> Vec<kitsune_p2p_types::bootstrap::BootstrapServerHealth>;
+
+        /// Enable or disable the network. While disabled, gossip is paused,
+        /// publishes and remote calls wait for the network to be enabled
+        /// again, remote signals are queued with the conductor, and gets
+        /// return nothing so only local data is used.
+        fn set_network_enabled(enabled: bool) -> ();
+
+        /// Switch the running network to new tuning params and bootstrap
+        /// services. Only the tuning params in `LIVE_TUNING_PARAMS` take
+        /// effect, the rest stay as they were when the network was spawned.
+        fn update_network_config(
+            tuning_params: kitsune_p2p_types::config::KitsuneP2pTuningParams,
+            bootstrap_urls: Vec<kitsune_p2p::dependencies::url2::Url2>,
+        ) -> ();
     }
 }
 
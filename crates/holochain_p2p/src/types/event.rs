@@ -17,6 +17,10 @@ pub enum GetRequest {
     Metadata,
     /// Get the content even if it's still pending.
     Pending,
+    /// Get only the integrated actions, without the entry.
+    /// This is all that's needed to check the data exists, or to read its
+    /// action, and saves transferring the entry.
+    Action,
 }
 
 /// Get options help control how the get is processed at various levels.
@@ -49,17 +53,23 @@ impl Default for GetRequest {
 }
 
 /// GetMeta options help control how the get is processed at various levels.
-#[derive(Debug, serde::Serialize, serde::Deserialize)]
-pub struct GetMetaOptions {}
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct GetMetaOptions {
+    /// Which metadata to return.
+    #[serde(default)]
+    pub metadata_request: MetadataRequest,
+}
 
 impl From<&actor::GetMetaOptions> for GetMetaOptions {
-    fn from(_a: &actor::GetMetaOptions) -> Self {
-        Self {}
+    fn from(a: &actor::GetMetaOptions) -> Self {
+        Self {
+            metadata_request: a.metadata_request.clone(),
+        }
     }
 }
 
 /// GetLinks options help control how the get is processed at various levels.
-#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct GetLinksOptions {}
 
 impl From<&actor::GetLinksOptions> for GetLinksOptions {
@@ -111,6 +121,20 @@ pub enum CountersigningSessionNegotiationMessage {
     EnzymePush(Box<DhtOp>),
 }
 
+/// A remote node's answer to a request for a DNA, which has the DNA if the
+/// remote node has it.
+#[derive(Debug, serde::Serialize, serde::Deserialize, SerializedBytes)]
+pub struct FetchDnaResponse(pub Option<DnaFile>);
+
+/// A remote signal waiting for the network to be enabled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueuedRemoteSignal {
+    /// The agents to send the signal to.
+    pub to_agent_list: Vec<AgentPubKey>,
+    /// The encoded wire message.
+    pub payload: Vec<u8>,
+}
+
 /// Multiple ways to fetch op data
 #[derive(Debug, derive_more::From)]
 pub enum FetchOpDataQuery {
@@ -231,6 +255,16 @@ ghost_actor::ghost_chan! {
             filter: holochain_zome_types::chain::ChainFilter,
         ) -> MustGetAgentActivityResponse;
 
+        /// A remote node is requesting a DNA which it doesn't have from us.
+        /// It's only served if it's the DNA of the network it's requested on.
+        fn fetch_dna(
+            // The dna_hash / space_hash context.
+            dna_hash: DnaHash,
+            to_agent: AgentPubKey,
+            // The DNA being requested.
+            requested: DnaHash,
+        ) -> FetchDnaResponse;
+
         /// A remote node has sent us a validation receipt.
         fn validation_receipt_received(
             dna_hash: DnaHash,
@@ -272,6 +306,19 @@ ghost_actor::ghost_chan! {
             to_agent: AgentPubKey,
             message: CountersigningSessionNegotiationMessage,
         ) -> ();
+
+        /// Store a remote signal which was sent while the network is
+        /// disabled, so it's still sent if the conductor is restarted
+        /// before the network is enabled again.
+        fn queue_remote_signal(
+            dna_hash: DnaHash,
+            to_agent_list: Vec<AgentPubKey>,
+            // The encoded wire message.
+            payload: Vec<u8>,
+        ) -> ();
+
+        /// Remove and return every remote signal queued for this DNA.
+        fn take_queued_remote_signals(dna_hash: DnaHash) -> Vec<QueuedRemoteSignal>;
     }
 }
 
@@ -286,6 +333,7 @@ macro_rules! match_p2p_evt {
             HolochainP2pEvent::GetLinks { $i, .. } => { $($t)* }
             HolochainP2pEvent::GetAgentActivity { $i, .. } => { $($t)* }
             HolochainP2pEvent::MustGetAgentActivity { $i, .. } => { $($t)* }
+            HolochainP2pEvent::FetchDna { $i, .. } => { $($t)* }
             HolochainP2pEvent::ValidationReceiptReceived { $i, .. } => { $($t)* }
             HolochainP2pEvent::SignNetworkData { $i, .. } => { $($t)* }
             HolochainP2pEvent::CountersigningSessionNegotiation { $i, .. } => { $($t)* }
@@ -306,6 +354,8 @@ impl HolochainP2pEvent {
             HolochainP2pEvent::QueryGossipAgents { dna_hash, .. } => { dna_hash }
             HolochainP2pEvent::PutAgentInfoSigned { dna_hash, .. } => { dna_hash }
             HolochainP2pEvent::QueryPeerDensity { dna_hash, .. } => { dna_hash }
+            HolochainP2pEvent::QueueRemoteSignal { dna_hash, .. } => { dna_hash }
+            HolochainP2pEvent::TakeQueuedRemoteSignals { dna_hash, .. } => { dna_hash }
         })
     }
 
@@ -320,6 +370,8 @@ impl HolochainP2pEvent {
             HolochainP2pEvent::QueryGossipAgents { .. } => { unimplemented!("There is no single agent target for QueryGossipAgents") },
             HolochainP2pEvent::PutAgentInfoSigned { .. } => { unimplemented!("There is no single agent target for PutAgentInfoSigned") },
             HolochainP2pEvent::QueryPeerDensity { .. } => { unimplemented!() },
+            HolochainP2pEvent::QueueRemoteSignal { .. } => { unimplemented!("There is no single agent target for QueueRemoteSignal") },
+            HolochainP2pEvent::TakeQueuedRemoteSignals { .. } => { unimplemented!("There is no single agent target for TakeQueuedRemoteSignals") },
         })
     }
 }
@@ -1,7 +1,6 @@
 //! Tools for simulating a network around real holochain nodes.
 //! This is a very early prototype and subject to change.
 
-use fixt::prelude::Distribution;
 use futures::stream::Stream;
 use kitsune_p2p::actor::BroadcastTo;
 use std::collections::HashMap;
@@ -11,6 +10,7 @@ use std::pin::Pin;
 use std::sync::Arc;
 use std::time::Duration;
 
+use self::scheduler::{delivery_queue, Direction, Scheduler};
 use crate::types::AgentPubKeyExt;
 use crate::types::DnaHashExt;
 use futures::StreamExt;
@@ -20,7 +20,6 @@ use kitsune_p2p::dependencies::kitsune_p2p_proxy::ProxyUrl;
 use kitsune_p2p::test_util::mock_network::to_kitsune_channel;
 use kitsune_p2p::test_util::mock_network::KitsuneMock;
 use kitsune_p2p::test_util::mock_network::ToKitsuneMockChannelRx;
-use kitsune_p2p::test_util::mock_network::ToKitsuneMockChannelTx;
 use kitsune_p2p::test_util::mock_network::{FromKitsuneMockChannelTx, KitsuneMockRespond};
 use kitsune_p2p::wire as kwire;
 use kitsune_p2p::GossipModuleType;
@@ -28,6 +27,8 @@ use kitsune_p2p_types::tx2::tx2_utils::TxUrl;
 use kitsune_p2p_types::tx2::MsgId;
 use kitsune_p2p_types::Tx2Cert;
 
+mod scheduler;
+
 static MSG_ID: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
 
 fn next_msg_id() -> MsgId {
@@ -39,7 +40,8 @@ fn next_msg_id() -> MsgId {
 pub struct HolochainP2pMockChannel {
     address_map: HashMap<AgentPubKey, (Tx2Cert, TxUrl)>,
     from_kitsune: Pin<Box<dyn Stream<Item = KitsuneMock> + Send + Sync + 'static>>,
-    to_kitsune: ToKitsuneMockChannelTx,
+    to_kitsune: tokio::sync::mpsc::Sender<(tokio::time::Instant, KitsuneMock)>,
+    scheduler: Arc<Scheduler>,
 }
 
 #[derive(Clone)]
@@ -53,6 +55,23 @@ pub struct MockScenario {
     pub inbound_delay_range: Range<Duration>,
     /// The range of time outbound messages will be delayed.
     pub outbound_delay_range: Range<Duration>,
+    /// The seed for every random decision the network makes.
+    /// With the same seed, on paused tokio time, messages sent in the same
+    /// order are dropped, delayed and delivered the same way.
+    /// If None a random seed is used and logged.
+    pub seed: Option<u64>,
+    /// Times when some simulated agents are cut off from the real nodes.
+    pub partitions: Vec<MockPartition>,
+}
+
+#[derive(Clone, Debug)]
+/// A set of simulated agents which can neither send to nor receive from
+/// the real nodes for a period of time, after which the partition heals.
+pub struct MockPartition {
+    /// The simulated agents which are cut off.
+    pub agents: HashSet<AgentPubKey>,
+    /// When the agents are cut off, relative to the creation of the channel.
+    pub during: Range<Duration>,
 }
 
 impl Default for MockScenario {
@@ -62,6 +81,8 @@ impl Default for MockScenario {
             percent_offline: 0.0,
             inbound_delay_range: Duration::from_millis(0)..Duration::from_millis(0),
             outbound_delay_range: Duration::from_millis(0)..Duration::from_millis(0),
+            seed: None,
+            partitions: Vec::new(),
         }
     }
 }
@@ -177,80 +198,37 @@ impl HolochainP2pMockChannel {
                 (agent, (cert, url))
             })
             .collect();
-        let offline_nodes: HashSet<_> = {
-            let mut rng = rand::thread_rng();
-            address_map
-                .values()
-                .filter(|_| {
-                    let offline = rand::distributions::Uniform::from(0.0..1.0);
-                    offline.sample(&mut rng) <= scenario.percent_offline
-                })
-                .map(|(cert, _)| cert.clone())
-                .collect()
-        };
-        let offline_nodes = Arc::new(offline_nodes);
-        let (from_kitsune_tx, from_kitsune_rx) = tokio::sync::mpsc::channel(buffer);
+        let certs = address_map
+            .iter()
+            .map(|(agent, (cert, _))| (agent.clone(), cert.clone()))
+            .collect();
+        let scheduler = Arc::new(Scheduler::new(scenario, &certs));
+        let (from_kitsune_tx, mut from_kitsune_rx) =
+            tokio::sync::mpsc::channel::<KitsuneMock>(buffer);
         let (to_kitsune_tx, to_kitsune_rx) = to_kitsune_channel(buffer);
-        let (tx, rx) = tokio::sync::mpsc::channel(buffer);
 
-        let stream = tokio_stream::wrappers::ReceiverStream::new(from_kitsune_rx)
-            .map({
-                let scenario = scenario.clone();
-                move |t: KitsuneMock| {
-                    let scenario = scenario.clone();
-                    let offline_nodes = offline_nodes.clone();
-                    async move {
-                        let (delay, keep) = {
-                            let mut rng = rand::thread_rng();
-                            let delay = if scenario.inbound_delay_range.is_empty() {
-                                Duration::from_millis(0)
-                            } else {
-                                let delay = rand::distributions::Uniform::from(
-                                    scenario.inbound_delay_range,
-                                );
-                                delay.sample(&mut rng)
-                            };
-                            let drop = rand::distributions::Uniform::from(0.0..1.0);
-                            let keep = drop.sample(&mut rng) > scenario.percent_drop_msg;
-                            (delay, keep)
-                        };
-                        tokio::time::sleep(delay).await;
-                        keep.then(|| t)
-                            .filter(|m| !offline_nodes.contains(m.cert()))
-                    }
-                }
-            })
-            .buffer_unordered(10)
-            .filter_map(|t| async move { t });
-        let from_kitsune = Box::pin(stream);
-        let stream = tokio_stream::wrappers::ReceiverStream::new(rx).for_each_concurrent(10, {
-            move |msg| {
-                let scenario = scenario.clone();
-                let to_kitsune_tx = to_kitsune_tx.clone();
-                async move {
-                    let scenario = scenario.clone();
-                    let (delay, keep) = {
-                        let mut rng = rand::thread_rng();
-                        let delay = if scenario.outbound_delay_range.is_empty() {
-                            Duration::from_millis(0)
-                        } else {
-                            let delay =
-                                rand::distributions::Uniform::from(scenario.outbound_delay_range);
-                            delay.sample(&mut rng)
-                        };
-                        let drop = rand::distributions::Uniform::from(0.0..1.0);
-                        let keep = drop.sample(&mut rng) > scenario.percent_drop_msg;
-                        (delay, keep)
-                    };
-                    tokio::time::sleep(delay).await;
-                    if keep {
-                        to_kitsune_tx.send(msg).await.unwrap();
+        let (inbound_tx, inbound_rx) = delivery_queue(buffer);
+        tokio::spawn({
+            let scheduler = scheduler.clone();
+            async move {
+                while let Some(msg) = from_kitsune_rx.recv().await {
+                    if let Some(at) = scheduler.schedule(msg.cert(), Direction::Inbound) {
+                        if inbound_tx.send((at, msg)).await.is_err() {
+                            break;
+                        }
                     }
                 }
             }
         });
+        let from_kitsune = Box::pin(tokio_stream::wrappers::ReceiverStream::new(inbound_rx));
+
+        let (outbound_tx, mut outbound_rx) = delivery_queue(buffer);
         tokio::spawn(async move {
-            stream.await;
+            while let Some(msg) = outbound_rx.recv().await {
+                if to_kitsune_tx.send(msg).await.is_err() {
+                    break;
+                }
+            }
         });
 
         (
@@ -259,7 +237,8 @@ impl HolochainP2pMockChannel {
             Self {
                 address_map,
                 from_kitsune,
-                to_kitsune: tx,
+                to_kitsune: outbound_tx,
+                scheduler,
             },
         )
     }
@@ -318,7 +297,9 @@ impl HolochainP2pMockChannel {
                 Some(rx),
             )
         };
-        let _ = self.to_kitsune.send(msg).await;
+        if let Some(at) = self.scheduler.schedule(msg.cert(), Direction::Outbound) {
+            let _ = self.to_kitsune.send((at, msg)).await;
+        }
         match rx {
             Some(rx) => rx
                 .await
@@ -348,7 +329,8 @@ impl HolochainP2pMockMsg {
                 | crate::wire::WireMessage::GetLinks { .. }
                 | crate::wire::WireMessage::GetAgentActivity { .. }
                 | crate::wire::WireMessage::MustGetAgentActivity { .. }
-                | crate::wire::WireMessage::GetValidationPackage { .. } => next_msg_id().as_req(),
+                | crate::wire::WireMessage::GetValidationPackage { .. }
+                | crate::wire::WireMessage::FetchDna { .. } => next_msg_id().as_req(),
                 crate::wire::WireMessage::Publish { .. }
                 | crate::wire::WireMessage::CountersigningSessionNegotiation { .. } => {
                     MsgId::new_notify()
@@ -376,7 +358,8 @@ impl HolochainP2pMockMsg {
                     | crate::wire::WireMessage::GetLinks { .. }
                     | crate::wire::WireMessage::GetAgentActivity { .. }
                     | crate::wire::WireMessage::MustGetAgentActivity { .. }
-                    | crate::wire::WireMessage::GetValidationPackage { .. } => true,
+                    | crate::wire::WireMessage::GetValidationPackage { .. }
+                    | crate::wire::WireMessage::FetchDna { .. } => true,
                     crate::wire::WireMessage::Publish { .. }
                     | crate::wire::WireMessage::CountersigningSessionNegotiation { .. } => false,
                 };
@@ -505,6 +488,9 @@ impl HolochainP2pMockMsg {
                     gossip,
                 }
             }
+            compressed @ kwire::Wire::Compressed(_) => {
+                Self::from_wire_msg(compressed.decompress().unwrap())
+            }
             kwire::Wire::MetricExchange(msg) => HolochainP2pMockMsg::MetricExchange(msg),
             kwire::Wire::PeerGet(msg) => HolochainP2pMockMsg::PeerGet(msg),
             kwire::Wire::PeerGetResp(msg) => HolochainP2pMockMsg::PeerGetResp(msg),
@@ -0,0 +1,238 @@
+//! A seeded scheduler deciding when, and whether, simulated messages arrive.
+//!
+//! Every decision about a message (dropping it, how long it is delayed) is
+//! drawn from a single seeded rng in the order messages are sent, and
+//! messages are released in order of their arrival time, breaking ties by the
+//! order they were sent. Run on paused tokio time, the same seed and the same
+//! sends replay the same network.
+
+use super::MockScenario;
+use ghost_actor::dependencies::tracing;
+use holo_hash::AgentPubKey;
+use kitsune_p2p_types::Tx2Cert;
+use rand::rngs::StdRng;
+use rand::Rng;
+use rand::SeedableRng;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::ops::Range;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+
+/// Which way a message is travelling through the simulated network.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Direction {
+    /// From the real nodes to a simulated agent.
+    Inbound,
+    /// From a simulated agent to the real nodes.
+    Outbound,
+}
+
+/// Decides the fate of each message sent through the simulated network.
+pub(super) struct Scheduler {
+    scenario: MockScenario,
+    rng: parking_lot::Mutex<StdRng>,
+    started: Instant,
+    offline: HashSet<Tx2Cert>,
+    /// The certs of the simulated agents in each of the scenario's partitions.
+    partitions: Vec<(HashSet<Tx2Cert>, Range<Duration>)>,
+}
+
+impl Scheduler {
+    /// Create a scheduler for the simulated agents with these certs.
+    pub(super) fn new(scenario: MockScenario, certs: &HashMap<AgentPubKey, Tx2Cert>) -> Self {
+        let seed = scenario.seed.unwrap_or_else(rand::random);
+        tracing::info!(seed, "Simulated network scheduler seed");
+        let mut rng = StdRng::seed_from_u64(seed);
+        // Sort so the same seed takes the same agents offline regardless
+        // of the iteration order of the map.
+        let mut all: Vec<_> = certs.values().cloned().collect();
+        all.sort();
+        let offline = all
+            .into_iter()
+            .filter(|_| rng.gen::<f32>() <= scenario.percent_offline)
+            .collect();
+        let partitions = scenario
+            .partitions
+            .iter()
+            .map(|partition| {
+                let certs = partition
+                    .agents
+                    .iter()
+                    .filter_map(|agent| certs.get(agent).cloned())
+                    .collect();
+                (certs, partition.during.clone())
+            })
+            .collect();
+        Self {
+            scenario,
+            rng: parking_lot::Mutex::new(rng),
+            started: Instant::now(),
+            offline,
+            partitions,
+        }
+    }
+
+    /// When a message to or from the simulated agent with this cert
+    /// should arrive, or None if it is lost.
+    pub(super) fn schedule(&self, cert: &Tx2Cert, direction: Direction) -> Option<Instant> {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.started);
+        let delay_range = match direction {
+            Direction::Inbound => &self.scenario.inbound_delay_range,
+            Direction::Outbound => &self.scenario.outbound_delay_range,
+        };
+        // Always draw from the rng so a message which is lost for another
+        // reason doesn't change the fate of the messages after it.
+        let (delay, keep) = {
+            let mut rng = self.rng.lock();
+            let delay = if delay_range.is_empty() {
+                Duration::from_millis(0)
+            } else {
+                rng.gen_range(delay_range.clone())
+            };
+            (delay, rng.gen::<f32>() > self.scenario.percent_drop_msg)
+        };
+        let offline = direction == Direction::Inbound && self.offline.contains(cert);
+        let partitioned = self
+            .partitions
+            .iter()
+            .any(|(certs, during)| during.contains(&elapsed) && certs.contains(cert));
+        (keep && !offline && !partitioned).then(|| now + delay)
+    }
+}
+
+/// A message waiting in the delivery queue.
+struct Scheduled<T> {
+    at: Instant,
+    seq: u64,
+    msg: T,
+}
+
+impl<T> PartialEq for Scheduled<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T> Eq for Scheduled<T> {}
+
+impl<T> PartialOrd for Scheduled<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for Scheduled<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed so the heap pops the earliest message first.
+        (other.at, other.seq).cmp(&(self.at, self.seq))
+    }
+}
+
+/// Spawn a queue which releases each message at the time it is scheduled
+/// for, in order of that time and then the order they were queued.
+pub(super) fn delivery_queue<T: Send + 'static>(
+    buffer: usize,
+) -> (mpsc::Sender<(Instant, T)>, mpsc::Receiver<T>) {
+    let (in_tx, mut in_rx) = mpsc::channel::<(Instant, T)>(buffer);
+    let (out_tx, out_rx) = mpsc::channel(buffer);
+    tokio::spawn(async move {
+        let mut queue = BinaryHeap::new();
+        let mut seq = 0;
+        let mut closed = false;
+        loop {
+            let next = queue.peek().map(|scheduled: &Scheduled<T>| scheduled.at);
+            let wake = tokio::time::sleep_until(next.unwrap_or_else(Instant::now));
+            tokio::select! {
+                biased;
+                item = in_rx.recv(), if !closed => match item {
+                    Some((at, msg)) => {
+                        queue.push(Scheduled { at, seq, msg });
+                        seq += 1;
+                    }
+                    None => closed = true,
+                },
+                _ = wake, if next.is_some() => {
+                    if let Some(scheduled) = queue.pop() {
+                        if out_tx.send(scheduled.msg).await.is_err() {
+                            break;
+                        }
+                    }
+                }
+                else => break,
+            }
+        }
+    });
+    (in_tx, out_rx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::MockPartition;
+    use super::*;
+
+    fn agent(n: u8) -> AgentPubKey {
+        AgentPubKey::from_raw_32(vec![n; 32])
+    }
+
+    fn scenario(seed: u64) -> MockScenario {
+        MockScenario {
+            seed: Some(seed),
+            percent_drop_msg: 0.3,
+            inbound_delay_range: Duration::from_millis(10)..Duration::from_millis(100),
+            partitions: vec![MockPartition {
+                agents: [agent(1)].into_iter().collect(),
+                during: Duration::from_secs(1)..Duration::from_secs(2),
+            }],
+            ..Default::default()
+        }
+    }
+
+    /// Send 60 messages to each of two agents, one of which is partitioned
+    /// for the second of the three seconds, and collect what arrives.
+    async fn run(seed: u64) -> Vec<(u8, u32)> {
+        let certs: HashMap<AgentPubKey, Tx2Cert> = [1, 2]
+            .into_iter()
+            .map(|n| (agent(n), Tx2Cert::from(vec![n; 32])))
+            .collect();
+        let scheduler = Scheduler::new(scenario(seed), &certs);
+        let (tx, mut rx) = delivery_queue(100);
+        for i in 0..60 {
+            for n in [1, 2] {
+                if let Some(at) = scheduler.schedule(&certs[&agent(n)], Direction::Inbound) {
+                    tx.send((at, (n, i))).await.unwrap();
+                }
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        drop(tx);
+        let mut delivered = Vec::new();
+        while let Some(msg) = rx.recv().await {
+            delivered.push(msg);
+        }
+        delivered
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn the_same_seed_replays_the_same_network() {
+        let delivered = run(42).await;
+        assert_eq!(delivered, run(42).await);
+        assert_ne!(delivered, run(43).await);
+
+        // Some messages were dropped and some were overtaken.
+        assert!(delivered.len() < 120);
+        assert!(delivered.windows(2).any(|w| w[0].1 > w[1].1));
+
+        // Agent 1 heard nothing while partitioned and recovered afterwards.
+        let to_agent_1: Vec<_> = delivered.iter().filter(|(a, _)| *a == 1).collect();
+        assert!(to_agent_1.iter().all(|(_, i)| !(20..40).contains(i)));
+        assert!(to_agent_1.iter().any(|(_, i)| *i >= 40));
+        assert!(delivered
+            .iter()
+            .any(|(a, i)| *a == 2 && (20..40).contains(i)));
+    }
+}
@@ -0,0 +1,63 @@
+//! Carry the OpenTelemetry trace context of a span along with network
+//! requests, so the spans handling a request on a remote node join the
+//! trace of the span which sent it.
+//!
+//! The context is sent as a [W3C `traceparent`] string. If the conductor
+//! isn't exporting spans there is no context, and nothing is sent.
+//!
+//! [W3C `traceparent`]: https://www.w3.org/TR/trace-context/#traceparent-header
+
+use ghost_actor::dependencies::tracing;
+use opentelemetry::propagation::TextMapPropagator;
+use opentelemetry::sdk::propagation::TraceContextPropagator;
+use std::collections::HashMap;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+const TRACEPARENT: &str = "traceparent";
+
+/// The `traceparent` of the current span, if it is being exported.
+pub fn current() -> Option<String> {
+    let mut carrier = HashMap::new();
+    TraceContextPropagator::new().inject_context(&tracing::Span::current().context(), &mut carrier);
+    carrier.remove(TRACEPARENT)
+}
+
+/// Make the span a child of the remote span with this `traceparent`.
+pub fn set_parent(span: &tracing::Span, traceparent: Option<&str>) {
+    if let Some(traceparent) = traceparent {
+        let carrier: HashMap<String, String> =
+            std::iter::once((TRACEPARENT.to_string(), traceparent.to_string())).collect();
+        span.set_parent(TraceContextPropagator::new().extract(&carrier));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry::trace::TraceContextExt;
+    use opentelemetry::trace::TracerProvider as _;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn no_context_without_exporter() {
+        let span = tracing::info_span!("untraced");
+        let _entered = span.enter();
+        assert_eq!(current(), None);
+    }
+
+    #[test]
+    fn traceparent_of_current_span() {
+        let tracer = opentelemetry::sdk::trace::TracerProvider::builder()
+            .build()
+            .tracer("test", None);
+        let subscriber =
+            tracing_subscriber::registry().with(tracing_opentelemetry::layer().with_tracer(tracer));
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!("sender");
+            let _entered = span.enter();
+            let traceparent = current().unwrap();
+            let trace_id = span.context().span().span_context().trace_id();
+            assert_eq!(traceparent.split('-').nth(1).unwrap(), trace_id.to_hex());
+        });
+    }
+}
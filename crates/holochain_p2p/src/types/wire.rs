@@ -21,6 +21,10 @@ impl WireDhtOpData {
     }
 }
 
+/// Messages sent between holochain nodes.
+///
+/// Requests carry the `traceparent` of the span which sent them,
+/// see [`trace_context`](crate::trace_context).
 #[derive(Debug, serde::Serialize, serde::Deserialize, SerializedBytes)]
 #[serde(tag = "type", content = "content")]
 #[allow(missing_docs)]
@@ -32,12 +36,16 @@ pub enum WireMessage {
         cap_secret: Option<CapSecret>,
         #[serde(with = "serde_bytes")]
         data: Vec<u8>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        trace_context: Option<String>,
     },
     Publish {
         request_validation_receipt: bool,
         countersigning_session: bool,
         dht_hash: holo_hash::AnyDhtHash,
         ops: Vec<holochain_types::dht_op::DhtOp>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        trace_context: Option<String>,
     },
     ValidationReceipt {
         #[serde(with = "serde_bytes")]
@@ -46,30 +54,47 @@ pub enum WireMessage {
     Get {
         dht_hash: holo_hash::AnyDhtHash,
         options: event::GetOptions,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        trace_context: Option<String>,
     },
     GetMeta {
         dht_hash: holo_hash::AnyDhtHash,
         options: event::GetMetaOptions,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        trace_context: Option<String>,
     },
     GetLinks {
         link_key: WireLinkKey,
         options: event::GetLinksOptions,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        trace_context: Option<String>,
     },
     GetAgentActivity {
         agent: AgentPubKey,
         query: ChainQueryFilter,
         options: event::GetActivityOptions,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        trace_context: Option<String>,
     },
     MustGetAgentActivity {
         agent: AgentPubKey,
         filter: holochain_zome_types::chain::ChainFilter,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        trace_context: Option<String>,
     },
     GetValidationPackage {
         action_hash: ActionHash,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        trace_context: Option<String>,
     },
     CountersigningSessionNegotiation {
         message: event::CountersigningSessionNegotiationMessage,
     },
+    FetchDna {
+        dna_hash: DnaHash,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        trace_context: Option<String>,
+    },
 }
 
 #[allow(missing_docs)]
@@ -82,6 +107,22 @@ impl WireMessage {
         holochain_serialized_bytes::decode(&data)
     }
 
+    /// The `traceparent` of the span which sent this request, if any.
+    pub fn trace_context(&self) -> Option<&str> {
+        match self {
+            Self::CallRemote { trace_context, .. }
+            | Self::Publish { trace_context, .. }
+            | Self::Get { trace_context, .. }
+            | Self::GetMeta { trace_context, .. }
+            | Self::GetLinks { trace_context, .. }
+            | Self::GetAgentActivity { trace_context, .. }
+            | Self::MustGetAgentActivity { trace_context, .. }
+            | Self::GetValidationPackage { trace_context, .. }
+            | Self::FetchDna { trace_context, .. } => trace_context.as_deref(),
+            Self::ValidationReceipt { .. } | Self::CountersigningSessionNegotiation { .. } => None,
+        }
+    }
+
     pub fn call_remote(
         zome_name: ZomeName,
         fn_name: FunctionName,
@@ -95,6 +136,7 @@ impl WireMessage {
             from_agent,
             cap_secret,
             data: payload.into_vec(),
+            trace_context: trace_context::current(),
         }
     }
 
@@ -109,6 +151,7 @@ impl WireMessage {
             countersigning_session,
             dht_hash,
             ops,
+            trace_context: trace_context::current(),
         }
     }
 
@@ -119,18 +162,30 @@ impl WireMessage {
     }
 
     pub fn get(dht_hash: holo_hash::AnyDhtHash, options: event::GetOptions) -> WireMessage {
-        Self::Get { dht_hash, options }
+        Self::Get {
+            dht_hash,
+            options,
+            trace_context: trace_context::current(),
+        }
     }
 
     pub fn get_meta(
         dht_hash: holo_hash::AnyDhtHash,
         options: event::GetMetaOptions,
     ) -> WireMessage {
-        Self::GetMeta { dht_hash, options }
+        Self::GetMeta {
+            dht_hash,
+            options,
+            trace_context: trace_context::current(),
+        }
     }
 
     pub fn get_links(link_key: WireLinkKey, options: event::GetLinksOptions) -> WireMessage {
-        Self::GetLinks { link_key, options }
+        Self::GetLinks {
+            link_key,
+            options,
+            trace_context: trace_context::current(),
+        }
     }
 
     pub fn get_agent_activity(
@@ -142,6 +197,7 @@ impl WireMessage {
             agent,
             query,
             options,
+            trace_context: trace_context::current(),
         }
     }
 
@@ -149,11 +205,25 @@ impl WireMessage {
         agent: AgentPubKey,
         filter: holochain_zome_types::chain::ChainFilter,
     ) -> WireMessage {
-        Self::MustGetAgentActivity { agent, filter }
+        Self::MustGetAgentActivity {
+            agent,
+            filter,
+            trace_context: trace_context::current(),
+        }
     }
 
     pub fn get_validation_package(action_hash: ActionHash) -> WireMessage {
-        Self::GetValidationPackage { action_hash }
+        Self::GetValidationPackage {
+            action_hash,
+            trace_context: trace_context::current(),
+        }
+    }
+
+    pub fn fetch_dna(dna_hash: DnaHash) -> WireMessage {
+        Self::FetchDna {
+            dna_hash,
+            trace_context: trace_context::current(),
+        }
     }
 
     pub fn countersigning_session_negotiation(
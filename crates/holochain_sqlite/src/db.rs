@@ -25,6 +25,13 @@ pub use p2p_agent_store::*;
 mod p2p_metrics;
 pub use p2p_metrics::*;
 
+mod write_batch;
+use write_batch::WriteBatch;
+pub use write_batch::WRITE_BATCH_WINDOW;
+
+#[cfg(test)]
+mod tests;
+
 #[async_trait::async_trait]
 /// A trait for being generic over [`DbWrite`] and [`DbRead`] that
 /// both implement read access.
@@ -106,6 +113,7 @@ pub struct DbRead<Kind: DbKindT> {
     read_semaphore: Arc<Semaphore>,
     max_readers: usize,
     num_readers: Arc<AtomicUsize>,
+    write_batch: Arc<WriteBatch>,
 }
 
 #[derive(Shrinkwrap)]
@@ -197,6 +205,33 @@ impl<Kind: DbKindT> DbRead<Kind> {
             .await
             .expect("We don't ever close these semaphores")
     }
+
+    /// Write a consistent snapshot of this database to a new file at `path`.
+    /// Readers and writers carry on as normal while the snapshot is taken.
+    pub async fn backup_to(&self, path: &Path) -> DatabaseResult<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let path = path_to_sql(path)?;
+        let _g = self.acquire_reader_permit().await;
+        let conn = self.conn()?;
+        task::spawn_blocking(move || conn.execute("VACUUM INTO ?1", [path])).await??;
+        Ok(())
+    }
+
+    /// The bytes this database takes up, counting every page it has
+    /// allocated whether or not it is in use.
+    pub async fn size_bytes(&self) -> DatabaseResult<u64> {
+        self.async_reader(|txn| {
+            let bytes: i64 = txn.query_row(
+                "SELECT page_count * page_size FROM pragma_page_count(), pragma_page_size()",
+                [],
+                |row| row.get(0),
+            )?;
+            DatabaseResult::Ok(bytes.max(0) as u64)
+        })
+        .await
+    }
 }
 
 /// The canonical representation of a (singleton) database.
@@ -294,6 +329,7 @@ impl<Kind: DbKindT + Send + Sync + 'static> DbWrite<Kind> {
             read_semaphore: Self::get_read_semaphore(kind.kind()),
             max_readers: num_read_threads(),
             num_readers: Arc::new(AtomicUsize::new(0)),
+            write_batch: Arc::new(WriteBatch::default()),
             kind,
             path: path.unwrap_or_default(),
             connection_pool: pool,
@@ -353,6 +389,49 @@ impl<Kind: DbKindT + Send + Sync + 'static> DbWrite<Kind> {
         r
     }
 
+    /// Commit a write along with the other batched writes to this database
+    /// which arrive within [`WRITE_BATCH_WINDOW`] of the first, in one
+    /// transaction, so workflows which write often don't each pay for a
+    /// commit.
+    ///
+    /// The write runs in its own savepoint, so if it fails only its own
+    /// changes are rolled back. The result is returned once the batch is
+    /// committed.
+    pub async fn batched_commit<E, R, F>(&self, f: F) -> Result<R, E>
+    where
+        E: From<DatabaseError> + Send + 'static,
+        F: FnOnce(&mut Transaction) -> Result<R, E> + Send + 'static,
+        R: Send + 'static,
+    {
+        let (result_tx, result_rx) = tokio::sync::oneshot::channel();
+        if self.write_batch.push(write_batch::batched(f, result_tx)) {
+            let db = self.clone();
+            tokio::task::spawn(async move {
+                tokio::time::sleep(WRITE_BATCH_WINDOW).await;
+                let writes = db.write_batch.take();
+                let delivers = Arc::new(Mutex::new(Vec::with_capacity(writes.len())));
+                let result = db
+                    .async_commit({
+                        let delivers = delivers.clone();
+                        move |txn| {
+                            *delivers.lock() = write_batch::commit_batch(txn, writes);
+                            DatabaseResult::Ok(())
+                        }
+                    })
+                    .await;
+                if let Err(e) = &result {
+                    tracing::error!(?e, "Failed to commit a batch of writes");
+                }
+                for deliver in std::mem::take(&mut *delivers.lock()) {
+                    deliver(result.as_ref().err());
+                }
+            });
+        }
+        result_rx
+            .await
+            .unwrap_or_else(|_| Err(write_batch::batch_dropped().into()))
+    }
+
     #[cfg(any(test, feature = "test_utils"))]
     pub fn test_commit<R, F>(&self, f: F) -> R
     where
@@ -375,6 +454,37 @@ impl<Kind: DbKindT + Send + Sync + 'static> DbWrite<Kind> {
         task::block_in_place(move || conn.with_commit_sync(f))
     }
 
+    /// Move everything in the write-ahead log into the main database file
+    /// and truncate the log, so nothing committed is left only in the WAL.
+    /// Waits for any in-progress write to finish first.
+    pub async fn checkpoint(&self) -> DatabaseResult<()> {
+        let _g = self.acquire_writer_permit().await;
+        let conn = self.conn()?;
+        task::spawn_blocking(move || {
+            conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))
+        })
+        .await??;
+        Ok(())
+    }
+
+    /// Merge a backup taken with [`DbRead::backup_to`] into this database.
+    /// Rows which are already present are kept as they are.
+    pub async fn restore_from(&self, path: &Path) -> DatabaseResult<()> {
+        if !path.is_file() {
+            return Err(DatabaseError::DatabaseMissing(path.to_owned()));
+        }
+        let path = path_to_sql(path)?;
+        let _g = self.acquire_writer_permit().await;
+        let mut conn = self.conn()?;
+        task::spawn_blocking(move || {
+            conn.execute("ATTACH DATABASE ?1 AS backup", [path])?;
+            let r = merge_backup(&mut conn);
+            conn.execute("DETACH DATABASE backup", [])?;
+            r
+        })
+        .await?
+    }
+
     async fn acquire_writer_permit(&self) -> OwnedSemaphorePermit {
         self.0
             .write_semaphore
@@ -385,6 +495,42 @@ impl<Kind: DbKindT + Send + Sync + 'static> DbWrite<Kind> {
     }
 }
 
+/// Copy every row of the attached `backup` database into the main database,
+/// skipping rows which already exist.
+fn merge_backup(conn: &mut Connection) -> DatabaseResult<()> {
+    let txn = conn.transaction()?;
+    // Tables are listed in the order they were created, which respects
+    // the foreign keys between them.
+    let tables = txn
+        .prepare(
+            "SELECT name FROM backup.sqlite_master
+            WHERE type = 'table' AND name NOT LIKE 'sqlite_%'
+            ORDER BY rowid",
+        )?
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    for table in tables {
+        txn.execute(
+            &format!(
+                "INSERT OR IGNORE INTO main.\"{0}\" SELECT * FROM backup.\"{0}\"",
+                table
+            ),
+            [],
+        )?;
+    }
+    txn.commit()?;
+    Ok(())
+}
+
+fn path_to_sql(path: &Path) -> DatabaseResult<String> {
+    path.to_str().map(ToString::to_string).ok_or_else(|| {
+        DatabaseError::Other(anyhow::anyhow!(
+            "Database path is not valid UTF-8: {}",
+            path.display()
+        ))
+    })
+}
+
 pub fn num_read_threads() -> usize {
     let num_cpus = num_cpus::get();
     let num_threads = num_cpus.checked_div(2).unwrap_or(0);
@@ -125,6 +125,28 @@ pub fn p2p_put_single(txn: &mut Transaction<'_>, signed: &AgentInfoSigned) -> Da
     tx_p2p_put(txn, record)
 }
 
+/// Check a new agent info advertises the same tls cert digests as the
+/// unexpired info already held for its agent, so that peers' certs can
+/// be pinned. Infos without any certs, e.g. of agents going offline,
+/// always match.
+pub fn p2p_cert_pin_matches(
+    txn: &Transaction<'_>,
+    signed: &AgentInfoSigned,
+    now_ms: u64,
+) -> DatabaseResult<bool> {
+    let digests = signed.cert_digests();
+    if digests.is_empty() {
+        return Ok(true);
+    }
+    Ok(match txn.p2p_get_agent(&signed.agent)? {
+        Some(pinned) if pinned.expires_at_ms > now_ms => {
+            let pinned = pinned.cert_digests();
+            pinned.is_empty() || pinned == digests
+        }
+        _ => true,
+    })
+}
+
 fn tx_p2p_put(txn: &mut Transaction, record: P2pRecord) -> DatabaseResult<()> {
     txn.execute(
         sql_p2p_agent_store::INSERT,
@@ -252,3 +252,49 @@ async fn test_p2p_agent_store_gossip_query_sanity() {
     // clean up temp dir
     tmp_dir.close().unwrap();
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_p2p_agent_store_cert_pin() {
+    let tmp_dir = tempfile::Builder::new()
+        .prefix("p2p_agent_store_cert_pin")
+        .tempdir()
+        .unwrap();
+
+    let space = rand_space();
+    let agent = rand_agent();
+
+    let db = DbWrite::test(tmp_dir.path(), DbKindP2pAgents(space.clone())).unwrap();
+
+    let sign = |digest: &str, signed_at_ms: u64| {
+        let url = format!(
+            "kitsune-proxy://{}/kitsune-quic/h/127.0.0.1/p/5778/--",
+            digest
+        );
+        AgentInfoSigned::sign(
+            space.clone(),
+            agent.clone(),
+            u32::MAX,
+            vec![url.into()],
+            signed_at_ms,
+            signed_at_ms + 1000,
+            |_| async { Ok(Arc::new(KitsuneSignature(vec![0; 64]))) },
+        )
+    };
+
+    let pinned = sign("pinned", 1000).await.unwrap();
+    let same = sign("pinned", 1500).await.unwrap();
+    let changed = sign("changed", 1500).await.unwrap();
+
+    p2p_put(&db, &pinned).await.unwrap();
+
+    db.async_commit(move |txn| {
+        // A new info with the same cert matches the pin.
+        assert!(p2p_cert_pin_matches(txn, &same, 1500)?);
+        // A different cert doesn't, until the pinned info expires.
+        assert!(!p2p_cert_pin_matches(txn, &changed, 1500)?);
+        assert!(p2p_cert_pin_matches(txn, &changed, 2000)?);
+        DatabaseResult::Ok(())
+    })
+    .await
+    .unwrap();
+}
@@ -1,10 +1,12 @@
 use crate::prelude::{DatabaseError, DatabaseResult};
 use crate::sql::*;
 use holochain_zome_types::prelude::*;
-use kitsune_p2p::event::MetricRecord;
+use kitsune_p2p::event::{MetricRecord, MetricRecordKind};
+use kitsune_p2p::KitsuneAgent;
 use rusqlite::*;
 use std::{
     num::TryFromIntError,
+    sync::Arc,
     time::{Duration, SystemTime},
 };
 
@@ -33,11 +35,13 @@ pub fn time_from_micros(micros: i64) -> DatabaseResult<SystemTime> {
 pub trait AsP2pMetricStoreConExt {
     fn p2p_log_metrics(&mut self, metrics: Vec<MetricRecord>) -> DatabaseResult<()>;
     fn p2p_prune_metrics(&mut self) -> DatabaseResult<()>;
+    fn p2p_latest_metrics(&mut self) -> DatabaseResult<Vec<MetricRecord>>;
 }
 
 pub trait AsP2pMetricStoreTxExt {
     fn p2p_log_metrics(&self, metrics: Vec<MetricRecord>) -> DatabaseResult<()>;
     fn p2p_prune_metrics(&self) -> DatabaseResult<()>;
+    fn p2p_latest_metrics(&self) -> DatabaseResult<Vec<MetricRecord>>;
 }
 
 impl AsP2pMetricStoreConExt for crate::db::PConnGuard {
@@ -50,6 +54,10 @@ impl AsP2pMetricStoreConExt for crate::db::PConnGuard {
         use crate::db::WriteManager;
         self.with_commit_sync(move |writer| writer.p2p_prune_metrics())
     }
+
+    fn p2p_latest_metrics(&mut self) -> DatabaseResult<Vec<MetricRecord>> {
+        self.with_reader(move |reader| reader.p2p_latest_metrics())
+    }
 }
 
 impl AsP2pMetricStoreTxExt for Transaction<'_> {
@@ -84,4 +92,26 @@ impl AsP2pMetricStoreTxExt for Transaction<'_> {
         )?;
         Ok(())
     }
+
+    fn p2p_latest_metrics(&self) -> DatabaseResult<Vec<MetricRecord>> {
+        let mut stmt = self.prepare(sql_p2p_metrics::SELECT_LATEST)?;
+        let mut out = Vec::new();
+        for r in stmt.query_map([], |r| {
+            let kind: String = r.get("kind")?;
+            let agent: Option<Vec<u8>> = r.get("agent")?;
+            let data: Option<String> = r.get("data")?;
+            Ok(MetricRecord {
+                kind: MetricRecordKind::from_db(&kind),
+                agent: agent.map(|a| Arc::new(KitsuneAgent(a))),
+                recorded_at_utc: Timestamp::from_micros(r.get("recorded_at_utc_micros")?),
+                expires_at_utc: Timestamp::from_micros(r.get("expires_at_utc_micros")?),
+                data: data
+                    .and_then(|d| serde_json::from_str(&d).ok())
+                    .unwrap_or(serde_json::Value::Null),
+            })
+        })? {
+            out.push(r?);
+        }
+        Ok(out)
+    }
 }
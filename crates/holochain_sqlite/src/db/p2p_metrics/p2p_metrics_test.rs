@@ -66,3 +66,53 @@ async fn test_p2p_metric_store_sanity() {
     // clean up temp dir
     tmp_dir.close().unwrap();
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_p2p_metric_store_latest() {
+    let tmp_dir = tempfile::Builder::new()
+        .prefix("p2p_metric_store_latest")
+        .tempdir()
+        .unwrap();
+
+    let space = rand_space();
+    let agent = rand_agent();
+
+    let db = DbWrite::test(tmp_dir.path(), DbKindP2pMetrics(space.clone())).unwrap();
+
+    let permit = db.conn_permit().await;
+    let mut con = db.with_permit(permit).unwrap();
+
+    let record = |micros, data| MetricRecord {
+        kind: MetricRecordKind::ValidationFailures,
+        agent: Some(agent.clone()),
+        recorded_at_utc: Timestamp::from_micros(micros),
+        expires_at_utc: Timestamp::MAX,
+        data: serde_json::json!(data),
+    };
+    con.p2p_log_metrics(vec![
+        record(1, 1),
+        record(3, 3),
+        record(2, 2),
+        MetricRecord {
+            kind: MetricRecordKind::AggExtrapCov,
+            agent: None,
+            recorded_at_utc: Timestamp::from_micros(4),
+            expires_at_utc: Timestamp::MAX,
+            data: serde_json::json!(42.42),
+        },
+    ])
+    .unwrap();
+
+    // only the latest record for the agent is returned
+    let latest = con.p2p_latest_metrics().unwrap();
+    assert_eq!(1, latest.len());
+    assert!(matches!(
+        latest[0].kind,
+        MetricRecordKind::ValidationFailures
+    ));
+    assert_eq!(Some(agent), latest[0].agent);
+    assert_eq!(serde_json::json!(3), latest[0].data);
+
+    // clean up temp dir
+    tmp_dir.close().unwrap();
+}
@@ -1,8 +1,10 @@
-use tempfile::TempDir;
+use crate::prelude::*;
 
-use crate::prelude::DatabaseResult;
+use super::{num_read_threads, DbKindWasm, DbWrite, ReadAccess};
 
-use super::{num_read_threads, DbKind, DbWrite};
+fn tempdir(prefix: &str) -> tempfile::TempDir {
+    tempfile::Builder::new().prefix(prefix).tempdir().unwrap()
+}
 
 /// This test does prove that making all transactions
 /// synchronous fixes the db timeout issue but it's slow
@@ -10,8 +12,8 @@ use super::{num_read_threads, DbKind, DbWrite};
 #[tokio::test(flavor = "multi_thread")]
 #[ignore = "This is too slow for CI as it has to wait for the timeouts"]
 async fn db_connection_doesnt_timeout() {
-    let td = TempDir::new("lots_of_dbs").unwrap();
-    let db = DbWrite::test(&td, DbKind::Wasm).unwrap();
+    let td = tempdir("lots_of_dbs");
+    let db = DbWrite::test(td.path(), DbKindWasm).unwrap();
     let num_readers = num_read_threads() * 2;
     let mut jhs = Vec::new();
 
@@ -88,7 +90,7 @@ async fn db_connection_doesnt_timeout() {
         let jh = tokio::spawn(async move {
             db.conn()
                 .unwrap()
-                .with_reader_test(|txn| {
+                .with_reader(|txn| {
                     let _c: usize = txn
                         .query_row("SELECT COUNT(rowid) FROM Wasm", [], |row| row.get(0))
                         .unwrap();
@@ -132,7 +134,69 @@ async fn db_connection_doesnt_timeout() {
     }
 
     let result = results.into_iter().collect::<Result<Vec<_>, _>>();
-    // Here we expect an error because the `with_reader_test` uses up the connections
+    // Here we expect an error because the `with_reader` uses up the connections
     // without taking permits.
     assert!(result.is_err());
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn backup_and_restore_merges_rows() {
+    let td = tempdir("backup");
+    let insert = |db: &DbWrite<DbKindWasm>, hash: u8| {
+        let db = db.clone();
+        async move {
+            db.async_commit(move |txn| {
+                txn.execute(
+                    "INSERT INTO Wasm (hash, blob) VALUES(?, ?)",
+                    [vec![hash], vec![hash]],
+                )?;
+                DatabaseResult::Ok(())
+            })
+            .await
+            .unwrap()
+        }
+    };
+    let count = |db: &DbWrite<DbKindWasm>| {
+        db.sync_reader(|txn| {
+            let c: usize = txn.query_row("SELECT COUNT(rowid) FROM Wasm", [], |row| row.get(0))?;
+            DatabaseResult::Ok(c)
+        })
+        .unwrap()
+    };
+
+    let source = DbWrite::test(&td.path().join("source"), DbKindWasm).unwrap();
+    insert(&source, 1).await;
+    insert(&source, 2).await;
+    let backup = td.path().join("backup.sqlite3");
+    source.backup_to(&backup).await.unwrap();
+
+    // Rows already in the target are kept and the rest are added.
+    let target = DbWrite::test(&td.path().join("target"), DbKindWasm).unwrap();
+    insert(&target, 2).await;
+    insert(&target, 3).await;
+    target.restore_from(&backup).await.unwrap();
+    assert_eq!(count(&target), 3);
+
+    // Restoring twice changes nothing.
+    target.restore_from(&backup).await.unwrap();
+    assert_eq!(count(&target), 3);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn size_grows_with_the_data() {
+    let td = tempdir("size");
+    let db = DbWrite::test(td.path(), DbKindWasm).unwrap();
+    let empty = db.size_bytes().await.unwrap();
+    assert!(empty > 0);
+
+    db.async_commit(|txn| {
+        txn.execute(
+            "INSERT INTO Wasm (hash, blob) VALUES(?, ?)",
+            [vec![0], vec![0; 100_000]],
+        )?;
+        DatabaseResult::Ok(())
+    })
+    .await
+    .unwrap();
+    assert!(db.size_bytes().await.unwrap() > empty);
+}
@@ -0,0 +1,152 @@
+//! Merging the writes of several workflows to the same database into one
+//! transaction, for [`DbWrite::batched_commit`].
+//!
+//! The first write to arrive opens a short window, and every write which
+//! arrives during it is committed along with it. Each write runs in its own
+//! savepoint, so a write which fails is rolled back without the rest, and
+//! its caller gets its error. A failed commit fails every write in it.
+
+use crate::prelude::DatabaseError;
+use rusqlite::Transaction;
+use std::time::Duration;
+
+/// How long the first write of a batch waits for others to join it.
+pub const WRITE_BATCH_WINDOW: Duration = Duration::from_millis(5);
+
+/// Tells the caller of a batched write how it went, once the batch has
+/// been committed, or has failed to commit with this error.
+pub(super) type Deliver = Box<dyn FnOnce(Option<&DatabaseError>) + Send>;
+
+/// A write waiting for its batch to be committed.
+pub(super) type BatchedWrite = Box<dyn FnOnce(&mut Transaction) -> Deliver + Send>;
+
+/// The writes waiting for the next commit of a database.
+#[derive(Default)]
+pub(super) struct WriteBatch(parking_lot::Mutex<Vec<BatchedWrite>>);
+
+impl WriteBatch {
+    /// Add a write to the batch, returning whether it is the first, and so
+    /// has to commit the batch.
+    pub(super) fn push(&self, write: BatchedWrite) -> bool {
+        let mut writes = self.0.lock();
+        writes.push(write);
+        writes.len() == 1
+    }
+
+    /// Take the writes of the batch, so new writes start another.
+    pub(super) fn take(&self) -> Vec<BatchedWrite> {
+        std::mem::take(&mut *self.0.lock())
+    }
+}
+
+/// Wrap a write for a batch, sending its result once the batch is
+/// committed.
+pub(super) fn batched<E, R, F>(
+    f: F,
+    result_tx: tokio::sync::oneshot::Sender<Result<R, E>>,
+) -> BatchedWrite
+where
+    E: From<DatabaseError> + Send + 'static,
+    F: FnOnce(&mut Transaction) -> Result<R, E> + Send + 'static,
+    R: Send + 'static,
+{
+    Box::new(move |txn| {
+        let result = in_savepoint(txn, f);
+        Box::new(move |commit_error| {
+            let result = match commit_error {
+                Some(e) => result.and_then(|_| Err(failed_commit(e).into())),
+                None => result,
+            };
+            let _ = result_tx.send(result);
+        })
+    })
+}
+
+/// Run a write in a savepoint, which is rolled back if the write fails.
+fn in_savepoint<E, R, F>(txn: &mut Transaction, f: F) -> Result<R, E>
+where
+    E: From<DatabaseError>,
+    F: FnOnce(&mut Transaction) -> Result<R, E>,
+{
+    txn.execute_batch("SAVEPOINT batched_write")
+        .map_err(DatabaseError::from)?;
+    let result = f(txn);
+    let end = if result.is_ok() {
+        "RELEASE batched_write"
+    } else {
+        "ROLLBACK TO batched_write; RELEASE batched_write"
+    };
+    txn.execute_batch(end).map_err(DatabaseError::from)?;
+    result
+}
+
+/// The error of each write in a batch which failed to commit.
+fn failed_commit(e: &DatabaseError) -> DatabaseError {
+    DatabaseError::Other(anyhow::anyhow!("Batched commit failed: {}", e))
+}
+
+/// The error of a write whose batch was dropped before it was committed.
+pub(super) fn batch_dropped() -> DatabaseError {
+    DatabaseError::Other(anyhow::anyhow!(
+        "The batch of this write was dropped before it was committed"
+    ))
+}
+
+/// Commit a batch of writes in one transaction, then tell their callers how
+/// they went.
+pub(super) fn commit_batch(txn: &mut Transaction, writes: Vec<BatchedWrite>) -> Vec<Deliver> {
+    writes.into_iter().map(|write| write(txn)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn failed_writes_are_rolled_back_without_the_rest() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = DbWrite::test(dir.path(), DbKindWasm).unwrap();
+        let write = |hash: &'static str| {
+            let db = db.clone();
+            async move {
+                db.batched_commit(move |txn| {
+                    txn.execute(
+                        "INSERT INTO Wasm (hash, blob) VALUES (?, ?)",
+                        rusqlite::params![hash, vec![0u8]],
+                    )?;
+                    DatabaseResult::Ok(())
+                })
+                .await
+            }
+        };
+        let failing = {
+            let db = db.clone();
+            async move {
+                db.batched_commit(|txn| {
+                    txn.execute(
+                        "INSERT INTO Wasm (hash, blob) VALUES (?, ?)",
+                        rusqlite::params!["c", vec![0u8]],
+                    )?;
+                    DatabaseResult::<()>::Err(DatabaseError::EmptyKey)
+                })
+                .await
+            }
+        };
+        let (a, b, c) = futures::join!(write("a"), write("b"), failing);
+        assert!(a.is_ok());
+        assert!(b.is_ok());
+        assert!(c.is_err());
+
+        let hashes: Vec<String> = db
+            .async_reader(|txn| {
+                let mut stmt = txn.prepare("SELECT hash FROM Wasm ORDER BY hash")?;
+                let hashes = stmt
+                    .query_map([], |row| row.get(0))?
+                    .collect::<Result<_, _>>()?;
+                DatabaseResult::Ok(hashes)
+            })
+            .await
+            .unwrap();
+        assert_eq!(hashes, vec!["a".to_string(), "b".to_string()]);
+    }
+}
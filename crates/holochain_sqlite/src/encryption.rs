@@ -0,0 +1,167 @@
+//! At-rest encryption of databases.
+//!
+//! Databases are only encrypted when this crate is built with the
+//! `db-encryption` feature, which swaps SQLite for SQLCipher, and a key has
+//! been set with [`set_db_key`]. Every connection opened after the key is set
+//! is keyed with it, so it must be set before any database is opened.
+
+use crate::prelude::*;
+use once_cell::sync::Lazy;
+use rusqlite::Connection;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// The key every new connection is opened with.
+static DB_KEY: Lazy<parking_lot::RwLock<Option<DbKey>>> =
+    Lazy::new(|| parking_lot::RwLock::new(None));
+
+/// A 256 bit key used to encrypt databases at rest.
+#[derive(Clone, PartialEq, Eq)]
+pub struct DbKey([u8; 32]);
+
+impl DbKey {
+    /// Generate a new random key.
+    pub fn generate() -> Self {
+        use rand::RngCore;
+        let mut bytes = [0; 32];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        Self(bytes)
+    }
+
+    /// Construct a key from raw bytes.
+    pub fn from_bytes(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+
+    /// The raw bytes of the key.
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    /// The key as a SQLCipher raw key literal, so it is used as is
+    /// rather than being put through key derivation.
+    fn pragma_value(&self) -> String {
+        use std::fmt::Write;
+        let mut value = String::with_capacity(67);
+        value.push_str("x'");
+        for b in &self.0 {
+            write!(value, "{:02X}", b).expect("Writing to a String can't fail");
+        }
+        value.push('\'');
+        value
+    }
+}
+
+impl std::fmt::Debug for DbKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("DbKey(<redacted>)")
+    }
+}
+
+/// Whether this build is able to encrypt databases.
+pub const fn encryption_supported() -> bool {
+    cfg!(feature = "db-encryption")
+}
+
+/// Set the key which all databases are opened with from now on.
+pub fn set_db_key(key: DbKey) -> DatabaseResult<()> {
+    if !encryption_supported() {
+        return Err(DatabaseError::EncryptionUnsupported);
+    }
+    *DB_KEY.write() = Some(key);
+    Ok(())
+}
+
+/// Key a newly opened connection, if a key has been set.
+pub(crate) fn apply_db_key(conn: &mut Connection) -> rusqlite::Result<()> {
+    #[cfg(feature = "db-encryption")]
+    if let Some(key) = DB_KEY.read().as_ref() {
+        conn.pragma_update(None, "key", &key.pragma_value())?;
+    }
+    #[cfg(not(feature = "db-encryption"))]
+    let _ = conn;
+    Ok(())
+}
+
+/// Re-encrypt every database under `root` with `new_key`, and make it the key
+/// which databases are opened with from now on. Returns the number of
+/// databases that were re-keyed.
+///
+/// This must be run before any of these databases are opened, as any open
+/// connections would be left using the old key. If a database fails to be
+/// re-keyed, the ones which already were are put back to the old key.
+pub fn rekey_databases(root: &Path, new_key: DbKey) -> DatabaseResult<usize> {
+    if !encryption_supported() {
+        return Err(DatabaseError::EncryptionUnsupported);
+    }
+    let old_key = DB_KEY
+        .read()
+        .clone()
+        .ok_or(DatabaseError::EncryptionKeyMissing)?;
+    let mut paths = Vec::new();
+    find_databases(root, &mut paths)?;
+
+    let mut done = Vec::with_capacity(paths.len());
+    for path in paths {
+        match rekey_database(&path, &old_key, &new_key) {
+            Ok(()) => done.push(path),
+            Err(e) => {
+                for path in done {
+                    if let Err(e) = rekey_database(&path, &new_key, &old_key) {
+                        tracing::error!(?path, ?e, "Failed to restore the old key of a database");
+                    }
+                }
+                return Err(e);
+            }
+        }
+    }
+    *DB_KEY.write() = Some(new_key);
+    Ok(done.len())
+}
+
+fn rekey_database(path: &Path, old_key: &DbKey, new_key: &DbKey) -> DatabaseResult<()> {
+    let conn = Connection::open(path)?;
+    conn.pragma_update(None, "key", &old_key.pragma_value())?;
+    // SQLCipher can't re-key a database in WAL mode, so fold the log back
+    // into the database first. WAL mode is turned back on when it's opened.
+    conn.pragma_update(None, "journal_mode", &"DELETE".to_string())?;
+    conn.pragma_update(None, "rekey", &new_key.pragma_value())?;
+    Ok(())
+}
+
+/// Collect the paths of all the database files under `dir`.
+fn find_databases(dir: &Path, paths: &mut Vec<PathBuf>) -> DatabaseResult<()> {
+    if !dir.is_dir() {
+        return Ok(());
+    }
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            find_databases(&path, paths)?;
+        } else if path.extension().map_or(false, |ext| ext == "sqlite3") {
+            paths.push(path);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn key_is_hex_encoded_and_never_printed() {
+        let key = DbKey::from_bytes([0xAB; 32]);
+        assert_eq!(key.pragma_value(), format!("x'{}'", "AB".repeat(32)));
+        assert_eq!(format!("{:?}", key), "DbKey(<redacted>)");
+    }
+
+    #[cfg(not(feature = "db-encryption"))]
+    #[test]
+    fn keys_are_refused_without_encryption_support() {
+        assert!(matches!(
+            set_db_key(DbKey::generate()),
+            Err(DatabaseError::EncryptionUnsupported)
+        ));
+    }
+}
@@ -50,6 +50,12 @@ pub enum DatabaseError {
     #[error("Unable to construct a value key")]
     KeyConstruction,
 
+    #[error("Database encryption requires holochain to be built with the `db-encryption` feature")]
+    EncryptionUnsupported,
+
+    #[error("Databases can only be re-keyed once the current encryption key has been set")]
+    EncryptionKeyMissing,
+
     #[error("transparent")]
     FailedToJoinBlocking(#[from] tokio::task::JoinError),
 }
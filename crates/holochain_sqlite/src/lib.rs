@@ -6,6 +6,7 @@
 
 pub mod conn;
 pub mod db;
+pub mod encryption;
 pub mod error;
 pub mod exports;
 pub mod fatal;
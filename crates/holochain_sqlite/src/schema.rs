@@ -6,19 +6,30 @@ use crate::sql::*;
 
 pub static SCHEMA_CELL: Lazy<Schema> = Lazy::new(|| {
     let migration_0 = Migration::initial(sql_cell::SCHEMA);
+    let migration_1 = Migration::forward(sql_cell::migrations::REJECTED_REASON);
+    let migration_2 = Migration::forward(sql_cell::migrations::VALIDATION_OUTCOME_CACHE);
+    let migration_3 = Migration::forward(sql_cell::migrations::LINK_INDEXES);
+    let migration_4 = Migration::forward(sql_cell::migrations::WHEN_INTEGRATED_INDEX);
 
     Schema {
-        current_index: 0,
-        migrations: vec![migration_0],
+        current_index: 4,
+        migrations: vec![
+            migration_0,
+            migration_1,
+            migration_2,
+            migration_3,
+            migration_4,
+        ],
     }
 });
 
 pub static SCHEMA_CONDUCTOR: Lazy<Schema> = Lazy::new(|| {
     let migration_0 = Migration::initial(sql_conductor::SCHEMA);
+    let migration_1 = Migration::forward(sql_conductor::migrations::QUEUED_REMOTE_SIGNALS);
 
     Schema {
-        current_index: 0,
-        migrations: vec![migration_0],
+        current_index: 1,
+        migrations: vec![migration_0, migration_1],
     }
 });
 
@@ -59,6 +70,12 @@ impl Schema {
     /// The decision is based on the difference between this Schema's
     /// current_index and the user_version pragma value in the database itself.
     /// NB: The current_index is 0-based, and the user_version is 1-based.
+    ///
+    /// A user_version of 0 means the database was either just created or
+    /// created before its version was recorded, so its schema is the one
+    /// created by the initial migration, if any.
+    /// The initial schema is created if it doesn't exist yet and every
+    /// later migration is run on top of it.
     pub fn initialize(
         &self,
         conn: &mut Connection,
@@ -72,21 +89,29 @@ impl Schema {
 
         if user_version == 0 {
             // database just needs to be created / initialized
-            self.migrations[self.current_index].initialize(conn)?;
+            let txn = conn.transaction()?;
+            self.migrations[0].initialize(&txn)?;
+            for migration in &self.migrations[1..=self.current_index] {
+                migration.run(&txn)?;
+            }
+            txn.pragma_update(None, "user_version", &self.user_version())?;
+            txn.commit()?;
             tracing::info!("database initialized: {}", db_kind);
             return Ok(());
         } else {
             let current_index = user_version as usize - 1;
             match current_index.cmp(&self.current_index) {
                 std::cmp::Ordering::Less => {
-                    // run forward migrations
-                    for v in current_index..self.current_index + 1 {
-                        self.migrations[v].run(conn)?;
+                    // run forward migrations, from the one after the
+                    // database's current migration
+                    let txn = conn.transaction()?;
+                    for migration in &self.migrations[current_index + 1..=self.current_index] {
+                        migration.run(&txn)?;
                     }
                     // set the DB user_version so that next time we don't run
                     // the same migration
-                    let new_user_version = (self.current_index + 1) as u16;
-                    conn.pragma_update(None, "user_version", &new_user_version)?;
+                    txn.pragma_update(None, "user_version", &self.user_version())?;
+                    txn.commit()?;
                     tracing::info!(
                         "database forward migrated: {} from {} to {}",
                         db_kind,
@@ -108,11 +133,16 @@ impl Schema {
 
         Ok(())
     }
+
+    /// The user_version of a database which has had every migration run.
+    fn user_version(&self) -> u16 {
+        (self.current_index + 1) as u16
+    }
 }
 
 pub struct Migration {
     schema: Sql,
-    _forward: Sql,
+    forward: Sql,
     _backward: Option<Sql>,
 }
 
@@ -120,19 +150,32 @@ impl Migration {
     pub fn initial(schema: &str) -> Self {
         Self {
             schema: schema.into(),
-            _forward: "".into(),
+            forward: "".into(),
             _backward: None,
         }
     }
 
-    pub fn initialize(&self, conn: &mut Connection) -> rusqlite::Result<()> {
+    /// A migration of the schema left by the previous migration.
+    pub fn forward(forward: &str) -> Self {
+        Self {
+            schema: "".into(),
+            forward: forward.into(),
+            _backward: None,
+        }
+    }
+
+    pub fn initialize(&self, conn: &Connection) -> rusqlite::Result<()> {
         conn.execute_batch(&self.schema)?;
         Ok(())
     }
 
-    pub fn run(&self, _conn: &mut Connection) -> rusqlite::Result<()> {
-        unimplemented!("actual migrations not yet implemented")
+    pub fn run(&self, conn: &Connection) -> rusqlite::Result<()> {
+        conn.execute_batch(&self.forward)?;
+        Ok(())
     }
 }
 
+#[cfg(test)]
+mod tests;
+
 type Sql = String;
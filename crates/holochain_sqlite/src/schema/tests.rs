@@ -0,0 +1,117 @@
+use super::*;
+
+fn user_version(conn: &Connection) -> u16 {
+    conn.pragma_query_value(None, "user_version", |row| row.get(0))
+        .unwrap()
+}
+
+fn columns(conn: &Connection, table: &str) -> Vec<String> {
+    let mut stmt = conn
+        .prepare(&format!("PRAGMA table_info({})", table))
+        .unwrap();
+    let columns = stmt
+        .query_map([], |row| row.get(1))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    columns
+}
+
+fn indexes(conn: &Connection) -> Vec<String> {
+    let mut stmt = conn
+        .prepare("SELECT name FROM sqlite_master WHERE type = 'index'")
+        .unwrap();
+    let indexes = stmt
+        .query_map([], |row| row.get(0))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    indexes
+}
+
+/// A cell database left by the initial migration, holding one op.
+fn initial_cell_db(user_version: u16) -> Connection {
+    let conn = Connection::open_in_memory().unwrap();
+    conn.execute_batch(sql_cell::SCHEMA).unwrap();
+    // The op's action doesn't need to exist for these tests.
+    conn.pragma_update(None, "foreign_keys", &false).unwrap();
+    conn.execute(
+        "INSERT INTO DhtOp (hash, type, basis_hash, action_hash, require_receipt,
+            storage_center_loc, authored_timestamp, op_order)
+        VALUES (X'01', 'StoreRecord', X'02', X'03', 0, 0, 0, '0')",
+        [],
+    )
+    .unwrap();
+    conn.pragma_update(None, "user_version", &user_version)
+        .unwrap();
+    conn
+}
+
+fn assert_migrated(conn: &Connection) {
+    assert_eq!(user_version(conn), SCHEMA_CELL.user_version());
+    assert!(columns(conn, "DhtOp").contains(&"rejected_reason".to_string()));
+    assert!(!columns(conn, "ValidationOutcomeCache").is_empty());
+    let indexes = indexes(conn);
+    for index in [
+        "Action_base_tag_idx",
+        "Action_create_link_idx",
+        "Action_base_author_idx",
+        "DhtOp_basis_timestamp_idx",
+        "DhtOp_when_int_idx",
+    ] {
+        assert!(indexes.contains(&index.to_string()), "missing {}", index);
+    }
+}
+
+#[test]
+fn new_cell_db_is_migrated() {
+    let mut conn = Connection::open_in_memory().unwrap();
+    SCHEMA_CELL.initialize(&mut conn, None).unwrap();
+    assert_migrated(&conn);
+
+    // Opening it again doesn't run any migrations.
+    SCHEMA_CELL.initialize(&mut conn, None).unwrap();
+    assert_migrated(&conn);
+}
+
+#[test]
+fn v1_cell_db_is_migrated() {
+    let mut conn = initial_cell_db(1);
+    SCHEMA_CELL.initialize(&mut conn, None).unwrap();
+    assert_migrated(&conn);
+
+    let rejected_reason: Option<Vec<u8>> = conn
+        .query_row(
+            "SELECT rejected_reason FROM DhtOp WHERE hash = X'01'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(rejected_reason, None);
+}
+
+/// Databases created before their version was recorded have a user_version
+/// of 0 and the initial schema.
+#[test]
+fn unversioned_cell_db_is_migrated() {
+    let mut conn = initial_cell_db(0);
+    SCHEMA_CELL.initialize(&mut conn, None).unwrap();
+    assert_migrated(&conn);
+
+    let ops: usize = conn
+        .query_row("SELECT COUNT(*) FROM DhtOp", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(ops, 1);
+}
+
+#[test]
+fn v1_conductor_db_is_migrated() {
+    let mut conn = Connection::open_in_memory().unwrap();
+    conn.execute_batch(sql_conductor::SCHEMA).unwrap();
+    conn.pragma_update(None, "user_version", &1).unwrap();
+
+    SCHEMA_CONDUCTOR.initialize(&mut conn, None).unwrap();
+    assert_eq!(user_version(&conn), SCHEMA_CONDUCTOR.user_version());
+    assert!(columns(&conn, "QueuedRemoteSignal").contains(&"payload".to_string()));
+    assert!(indexes(&conn).contains(&"QueuedRemoteSignal_dna_hash_idx".to_string()));
+}
@@ -1,5 +1,15 @@
 pub mod sql_cell {
     pub(crate) const SCHEMA: &str = include_str!("sql/cell/schema.sql");
+    pub(crate) mod migrations {
+        pub(crate) const REJECTED_REASON: &str =
+            include_str!("sql/cell/migrations/01_rejected_reason.sql");
+        pub(crate) const VALIDATION_OUTCOME_CACHE: &str =
+            include_str!("sql/cell/migrations/02_validation_outcome_cache.sql");
+        pub(crate) const LINK_INDEXES: &str =
+            include_str!("sql/cell/migrations/03_link_indexes.sql");
+        pub(crate) const WHEN_INTEGRATED_INDEX: &str =
+            include_str!("sql/cell/migrations/04_when_integrated_index.sql");
+    }
     pub const UPDATE_INTEGRATE_DEP_ACTIVITY: &str =
         include_str!("sql/cell/update_dep_activity.sql");
     pub const ACTIVITY_INTEGRATED_UPPER_BOUND: &str =
@@ -25,6 +35,15 @@ pub mod sql_cell {
 
     pub const FETCH_OP: &str = include_str!("sql/cell/fetch_op.sql");
 
+    pub const FETCH_INTEGRATED_OPS: &str = include_str!("sql/cell/fetch_integrated_ops.sql");
+
+    pub const RECORDS_INTEGRATED_SINCE: &str =
+        include_str!("sql/cell/records_integrated_since.sql");
+
+    pub const ENTRY_CRUD_SUMMARY: &str = include_str!("sql/cell/entry_crud_summary.sql");
+
+    pub const PRUNE_DELETED_ENTRIES: &str = include_str!("sql/cell/prune_deleted_entries.sql");
+
     pub mod must_get_agent_activity {
         pub const MUST_GET_AGENT_ACTIVITY: &str =
             include_str!("sql/cell/agent_activity/must_get_agent_activity.sql");
@@ -54,6 +73,10 @@ pub mod sql_cell {
 
 pub(crate) mod sql_conductor {
     pub(crate) const SCHEMA: &str = include_str!("sql/conductor/schema.sql");
+    pub(crate) mod migrations {
+        pub(crate) const QUEUED_REMOTE_SIGNALS: &str =
+            include_str!("sql/conductor/migrations/01_queued_remote_signals.sql");
+    }
 }
 
 pub(crate) mod sql_wasm {
@@ -77,4 +100,5 @@ pub(crate) mod sql_p2p_metrics {
     pub(crate) const SCHEMA: &str = include_str!("sql/p2p_metrics/schema.sql");
     pub(crate) const INSERT: &str = include_str!("sql/p2p_metrics/insert.sql");
     pub(crate) const PRUNE: &str = include_str!("sql/p2p_metrics/prune.sql");
+    pub(crate) const SELECT_LATEST: &str = include_str!("sql/p2p_metrics/select_latest.sql");
 }
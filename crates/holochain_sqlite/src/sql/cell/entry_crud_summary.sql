@@ -0,0 +1,49 @@
+-- How many of the valid actions which create an entry haven't been
+-- deleted, and how many valid updates and deletes there are of the entry.
+SELECT
+  (
+    SELECT
+      COUNT(*)
+    FROM
+      DhtOp
+    WHERE
+      DhtOp.type = :store_entry
+      AND DhtOp.basis_hash = :entry_hash
+      AND DhtOp.when_integrated IS NOT NULL
+      AND DhtOp.validation_status = :status
+      AND NOT EXISTS (
+        SELECT
+          1
+        FROM
+          DhtOp AS DeleteOp
+          JOIN Action AS Delete ON DeleteOp.action_hash = Delete.hash
+        WHERE
+          DeleteOp.type = :delete
+          AND DeleteOp.basis_hash = :entry_hash
+          AND DeleteOp.when_integrated IS NOT NULL
+          AND DeleteOp.validation_status = :status
+          AND Delete.deletes_action_hash = DhtOp.action_hash
+      )
+  ) AS live_actions,
+  (
+    SELECT
+      COUNT(*)
+    FROM
+      DhtOp
+    WHERE
+      DhtOp.type = :update
+      AND DhtOp.basis_hash = :entry_hash
+      AND DhtOp.when_integrated IS NOT NULL
+      AND DhtOp.validation_status = :status
+  ) AS updates,
+  (
+    SELECT
+      COUNT(*)
+    FROM
+      DhtOp
+    WHERE
+      DhtOp.type = :delete
+      AND DhtOp.basis_hash = :entry_hash
+      AND DhtOp.when_integrated IS NOT NULL
+      AND DhtOp.validation_status = :status
+  ) AS deletes
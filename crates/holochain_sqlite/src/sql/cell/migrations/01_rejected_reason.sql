@@ -0,0 +1,6 @@
+-- Why sys validation rejected the op, if it did.
+-- See SysValidationIssueReason.
+ALTER TABLE
+  DhtOp
+ADD
+  COLUMN rejected_reason BLOB NULL;
@@ -0,0 +1,11 @@
+-- The outcomes of recently sys validating ops, so ops which are received
+-- again can skip the checks. Only the most recent outcomes are kept.
+CREATE TABLE ValidationOutcomeCache (
+  op_hash BLOB NOT NULL,
+  signature BLOB NOT NULL,
+  -- NULL if the op was sys validated, otherwise why it was rejected.
+  rejected_reason BLOB NULL,
+  when_recorded INTEGER NOT NULL,
+  PRIMARY KEY (op_hash, signature) ON CONFLICT REPLACE
+);
+CREATE INDEX ValidationOutcomeCache_when_idx ON ValidationOutcomeCache (when_recorded);
@@ -0,0 +1,9 @@
+-- Links are looked up by their base and tag prefix, and removes of links
+-- by the link they remove.
+CREATE INDEX Action_base_tag_idx ON Action (base_hash, tag);
+CREATE INDEX Action_create_link_idx ON Action (create_link_hash);
+-- Links by their base and author.
+CREATE INDEX Action_base_author_idx ON Action (base_hash, author);
+-- The ops of links are held at their base, so this indexes links by their
+-- base and when they were created.
+CREATE INDEX DhtOp_basis_timestamp_idx ON DhtOp (basis_hash, authored_timestamp);
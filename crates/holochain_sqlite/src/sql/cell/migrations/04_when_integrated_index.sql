@@ -0,0 +1,2 @@
+-- Ops are looked up by when they were integrated, whatever their type.
+CREATE INDEX DhtOp_when_int_idx ON DhtOp (when_integrated);
@@ -0,0 +1,35 @@
+DELETE FROM
+  Entry
+WHERE
+  Entry.hash IN (
+    SELECT
+      DhtOp.basis_hash
+    FROM
+      DhtOp
+    WHERE
+      DhtOp.type = :deleted_entry_action
+      AND DhtOp.validation_status = :valid
+      AND DhtOp.when_integrated < :cutoff
+  )
+  AND NOT EXISTS(
+    SELECT
+      1
+    FROM
+      DhtOp AS StoreEntry
+    WHERE
+      StoreEntry.basis_hash = Entry.hash
+      AND StoreEntry.type = :store_entry
+      AND NOT EXISTS(
+        SELECT
+          1
+        FROM
+          DhtOp AS DeleteOp
+          JOIN Action ON DeleteOp.action_hash = Action.hash
+        WHERE
+          DeleteOp.basis_hash = Entry.hash
+          AND DeleteOp.type = :deleted_entry_action
+          AND DeleteOp.validation_status = :valid
+          AND DeleteOp.when_integrated < :cutoff
+          AND Action.deletes_action_hash = StoreEntry.action_hash
+      )
+  )
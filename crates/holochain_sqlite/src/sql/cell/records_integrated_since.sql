@@ -0,0 +1,21 @@
+-- The records with valid ops integrated at or after a time, in the order
+-- they were integrated, with the time of their first op in that range.
+SELECT
+  Action.hash AS action_hash,
+  Action.blob AS action_blob,
+  Entry.blob AS entry_blob,
+  MIN(DhtOp.when_integrated) AS when_integrated
+FROM
+  DhtOp
+  JOIN Action ON DhtOp.action_hash = Action.hash
+  LEFT JOIN Entry ON Action.entry_hash = Entry.hash
+WHERE
+  DhtOp.when_integrated >= :since
+  AND DhtOp.validation_status = :status
+GROUP BY
+  Action.hash
+ORDER BY
+  when_integrated,
+  Action.hash
+LIMIT
+  :limit
@@ -0,0 +1,10 @@
+-- Remote signals sent while the network was disabled, waiting to be sent.
+CREATE TABLE QueuedRemoteSignal (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  dna_hash BLOB NOT NULL,
+  -- The msgpack encoded list of agents to send the signal to.
+  to_agents BLOB NOT NULL,
+  -- The encoded wire message.
+  payload BLOB NOT NULL
+);
+CREATE INDEX QueuedRemoteSignal_dna_hash_idx ON QueuedRemoteSignal (dna_hash);
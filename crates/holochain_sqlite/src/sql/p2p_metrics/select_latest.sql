@@ -0,0 +1,14 @@
+-- sqlite returns the other columns from the row with the max
+SELECT
+  kind,
+  agent,
+  MAX(recorded_at_utc_micros) AS recorded_at_utc_micros,
+  expires_at_utc_micros,
+  data
+FROM
+  p2p_metrics
+WHERE
+  agent IS NOT NULL
+GROUP BY
+  kind,
+  agent;
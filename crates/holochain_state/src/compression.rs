@@ -0,0 +1,82 @@
+//! Compression of large entries in the databases.
+//!
+//! Entries whose serialized blob is at least the configured threshold are
+//! compressed with zstd when they are inserted. Any blob which starts with
+//! the zstd magic number is decompressed by [`from_blob`](crate::query::from_blob),
+//! which can't be mistaken for a serialized value as those are always
+//! msgpack maps, arrays or strings. Compressed and uncompressed blobs can
+//! therefore live side by side, and compression can be turned on and off.
+
+use crate::query::StateQueryError;
+use crate::query::StateQueryResult;
+use once_cell::sync::OnceCell;
+
+/// The bytes every zstd frame starts with.
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// The zstd compression level, which favours speed as entries are
+/// compressed inside write transactions.
+const COMPRESSION_LEVEL: i32 = 3;
+
+static THRESHOLD_BYTES: OnceCell<usize> = OnceCell::new();
+
+/// Compress entries which are at least this many bytes once serialized.
+/// The threshold is shared by the whole process, so only the first one
+/// set is used.
+pub fn configure(threshold_bytes: usize) {
+    if let Err(threshold_bytes) = THRESHOLD_BYTES.set(threshold_bytes) {
+        tracing::debug!(threshold_bytes, "Entry compression was already configured");
+    }
+}
+
+/// Compress a blob if compression is configured and the blob is large
+/// enough, and if compressing actually makes it smaller.
+pub(crate) fn compress_blob(blob: Vec<u8>) -> Vec<u8> {
+    match THRESHOLD_BYTES.get() {
+        Some(threshold) if blob.len() >= *threshold => compress(blob),
+        _ => blob,
+    }
+}
+
+fn compress(blob: Vec<u8>) -> Vec<u8> {
+    match zstd::bulk::compress(&blob, COMPRESSION_LEVEL) {
+        Ok(compressed) if compressed.len() < blob.len() => compressed,
+        Ok(_) => blob,
+        Err(e) => {
+            tracing::warn!(?e, "Failed to compress an entry, storing it as is");
+            blob
+        }
+    }
+}
+
+/// Decompress a blob if it was compressed, else return it as is.
+pub(crate) fn decompress_blob(blob: Vec<u8>) -> StateQueryResult<Vec<u8>> {
+    if !blob.starts_with(&ZSTD_MAGIC) {
+        return Ok(blob);
+    }
+    zstd::stream::decode_all(&blob[..]).map_err(StateQueryError::Decompression)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query::from_blob;
+    use crate::query::to_blob;
+    use holochain_zome_types::Entry;
+
+    #[test]
+    fn compressed_entries_round_trip() {
+        let entry = Entry::app(holochain_serialized_bytes::SerializedBytes::from(
+            holochain_serialized_bytes::UnsafeBytes::from(vec![7; 64 * 1024]),
+        ))
+        .unwrap();
+        let blob = to_blob(&entry).unwrap();
+        let compressed = compress(blob.clone());
+        assert!(compressed.starts_with(&ZSTD_MAGIC));
+        assert!(compressed.len() < blob.len());
+
+        // Compressed and uncompressed blobs both decode to the entry.
+        assert_eq!(from_blob::<Entry>(compressed).unwrap(), entry);
+        assert_eq!(from_blob::<Entry>(blob).unwrap(), entry);
+    }
+}
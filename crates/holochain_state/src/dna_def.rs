@@ -59,3 +59,7 @@ pub fn contains(txn: &Transaction<'_>, hash: &DnaHash) -> StateQueryResult<bool>
 pub fn put(txn: &mut Transaction, dna_def: DnaDef) -> StateMutationResult<()> {
     mutations::insert_dna_def(txn, &DnaDefHashed::from_content_sync(dna_def))
 }
+
+pub fn update(txn: &mut Transaction, dna_def: &DnaDefHashed) -> StateMutationResult<()> {
+    mutations::update_dna_def(txn, dna_def)
+}
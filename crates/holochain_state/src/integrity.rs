@@ -0,0 +1,360 @@
+//! Checks for inconsistencies in a cell database, such as those left behind
+//! by a crash or a disk error, and repair of the ones that can be fixed.
+//!
+//! Data in the DHT and cache databases can always be fetched from the network
+//! again, so rows found to be corrupt there can simply be removed. The
+//! authored database holds the only copy of an agent's source chain, so
+//! problems found there are reported but never repaired.
+
+use crate::mutations::StateMutationResult;
+use crate::query::from_blob;
+use crate::query::StateQueryResult;
+use holo_hash::*;
+use holochain_sqlite::rusqlite::named_params;
+use holochain_sqlite::rusqlite::Transaction;
+use holochain_zome_types::Entry;
+use holochain_zome_types::SignedAction;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// A problem found in a database by [`check_db`] or [`check_source_chains`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DbIntegrityIssue {
+    /// SQLite found the database file itself to be malformed.
+    Malformed(String),
+    /// An action can't be decoded or doesn't hash to its key.
+    ActionHashMismatch(ActionHash),
+    /// An entry can't be decoded or doesn't hash to its key.
+    EntryHashMismatch(EntryHash),
+    /// An op refers to an action which isn't in the database.
+    OpMissingAction {
+        /// The op.
+        op_hash: DhtOpHash,
+        /// The action it refers to.
+        action_hash: ActionHash,
+    },
+    /// A source chain action's entry isn't in the database.
+    ChainMissingEntry {
+        /// The action.
+        action_hash: ActionHash,
+        /// The entry it refers to.
+        entry_hash: EntryHash,
+    },
+    /// A source chain has no action at this sequence number,
+    /// although it has actions after it.
+    ChainGap {
+        /// The author of the chain.
+        author: AgentPubKey,
+        /// The first missing sequence number.
+        seq: u32,
+    },
+    /// A source chain action doesn't follow on from the action before it,
+    /// either because its previous action hash doesn't match, or because
+    /// there is more than one action at its sequence number.
+    ChainBroken {
+        /// The author of the chain.
+        author: AgentPubKey,
+        /// The sequence number of the action.
+        seq: u32,
+        /// The action.
+        action_hash: ActionHash,
+    },
+}
+
+impl DbIntegrityIssue {
+    /// Can this issue be repaired by removing the corrupt rows,
+    /// in a database whose data can be fetched again from the network.
+    pub fn is_repairable(&self) -> bool {
+        matches!(
+            self,
+            Self::ActionHashMismatch(_) | Self::EntryHashMismatch(_) | Self::OpMissingAction { .. }
+        )
+    }
+}
+
+/// The result of checking the databases of a DNA.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DbIntegrityReport {
+    /// Problems found in the authored database.
+    pub authored: Vec<DbIntegrityIssue>,
+    /// Problems found in the DHT database.
+    pub dht: Vec<DbIntegrityIssue>,
+    /// Problems found in the cache database.
+    pub cache: Vec<DbIntegrityIssue>,
+    /// How many rows were removed from the DHT and cache databases
+    /// to repair them, if a repair was asked for.
+    pub rows_removed: usize,
+}
+
+impl DbIntegrityReport {
+    /// No problems were found in any of the databases.
+    pub fn is_healthy(&self) -> bool {
+        self.authored.is_empty() && self.dht.is_empty() && self.cache.is_empty()
+    }
+}
+
+/// Check that the database file is well formed, that every action and entry
+/// hashes to its key, and that every op's action is present.
+pub fn check_db(txn: &Transaction) -> StateQueryResult<Vec<DbIntegrityIssue>> {
+    let mut issues = Vec::new();
+
+    let mut stmt = txn.prepare("PRAGMA integrity_check")?;
+    let results = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    issues.extend(
+        results
+            .into_iter()
+            .filter(|r| r != "ok")
+            .map(DbIntegrityIssue::Malformed),
+    );
+
+    let mut stmt = txn.prepare("SELECT hash, blob FROM Action")?;
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        let hash: ActionHash = row.get("hash")?;
+        let matches = from_blob::<SignedAction>(row.get("blob")?)
+            .map(|signed| ActionHash::with_data_sync(signed.action()) == hash)
+            .unwrap_or(false);
+        if !matches {
+            issues.push(DbIntegrityIssue::ActionHashMismatch(hash));
+        }
+    }
+
+    let mut stmt = txn.prepare("SELECT hash, blob FROM Entry")?;
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        let hash: EntryHash = row.get("hash")?;
+        let matches = from_blob::<Entry>(row.get("blob")?)
+            .map(|entry| EntryHash::with_data_sync(&entry) == hash)
+            .unwrap_or(false);
+        if !matches {
+            issues.push(DbIntegrityIssue::EntryHashMismatch(hash));
+        }
+    }
+
+    let mut stmt = txn.prepare(
+        "
+        SELECT DhtOp.hash, DhtOp.action_hash FROM DhtOp
+        LEFT JOIN Action ON DhtOp.action_hash = Action.hash
+        WHERE Action.hash IS NULL
+        ",
+    )?;
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        issues.push(DbIntegrityIssue::OpMissingAction {
+            op_hash: row.get(0)?,
+            action_hash: row.get(1)?,
+        });
+    }
+
+    Ok(issues)
+}
+
+/// Check that every source chain in an authored database runs unbroken from
+/// genesis to its head, and that the entries of its actions are present.
+pub fn check_source_chains(txn: &Transaction) -> StateQueryResult<Vec<DbIntegrityIssue>> {
+    let mut issues = Vec::new();
+    let authors = txn
+        .prepare("SELECT DISTINCT author FROM Action")?
+        .query_map([], |row| row.get::<_, AgentPubKey>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut stmt = txn.prepare(
+        "
+        SELECT Action.hash, Action.seq, Action.prev_hash, Action.entry_hash,
+        Entry.hash IS NOT NULL AS has_entry
+        FROM Action
+        LEFT JOIN Entry ON Action.entry_hash = Entry.hash
+        WHERE Action.author = :author
+        ORDER BY Action.seq
+        ",
+    )?;
+    for author in authors {
+        let mut rows = stmt.query(named_params! { ":author": author })?;
+        let mut expected_seq = 0;
+        let mut prev: Option<ActionHash> = None;
+        while let Some(row) = rows.next()? {
+            let hash: ActionHash = row.get("hash")?;
+            let seq: u32 = row.get("seq")?;
+            let prev_hash: Option<ActionHash> = row.get("prev_hash")?;
+            if seq > expected_seq {
+                issues.push(DbIntegrityIssue::ChainGap {
+                    author: author.clone(),
+                    seq: expected_seq,
+                });
+            }
+            if seq < expected_seq || (seq > 0 && prev.is_some() && prev_hash != prev) {
+                issues.push(DbIntegrityIssue::ChainBroken {
+                    author: author.clone(),
+                    seq,
+                    action_hash: hash.clone(),
+                });
+            }
+            if let Some(entry_hash) = row.get::<_, Option<EntryHash>>("entry_hash")? {
+                if !row.get::<_, bool>("has_entry")? {
+                    issues.push(DbIntegrityIssue::ChainMissingEntry {
+                        action_hash: hash.clone(),
+                        entry_hash,
+                    });
+                }
+            }
+            expected_seq = seq + 1;
+            prev = Some(hash);
+        }
+    }
+    Ok(issues)
+}
+
+/// Remove the rows behind the repairable issues, along with any ops and
+/// receipts which depend on them. Returns the number of rows removed.
+///
+/// This must only be used on databases whose data can be fetched again
+/// from the network, i.e. never on an authored database.
+pub fn remove_corrupt_rows(
+    txn: &mut Transaction,
+    issues: &[DbIntegrityIssue],
+) -> StateMutationResult<usize> {
+    let mut removed = 0;
+    for issue in issues {
+        match issue {
+            DbIntegrityIssue::ActionHashMismatch(hash) => {
+                removed += txn.execute(
+                    "
+                    DELETE FROM ValidationReceipt WHERE op_hash IN
+                    (SELECT hash FROM DhtOp WHERE action_hash = :hash)
+                    ",
+                    named_params! { ":hash": hash },
+                )?;
+                removed += txn.execute(
+                    "DELETE FROM DhtOp WHERE action_hash = :hash",
+                    named_params! { ":hash": hash },
+                )?;
+                removed += txn.execute(
+                    "DELETE FROM Action WHERE hash = :hash",
+                    named_params! { ":hash": hash },
+                )?;
+            }
+            DbIntegrityIssue::EntryHashMismatch(hash) => {
+                removed += txn.execute(
+                    "DELETE FROM Entry WHERE hash = :hash",
+                    named_params! { ":hash": hash },
+                )?;
+            }
+            DbIntegrityIssue::OpMissingAction { op_hash, .. } => {
+                removed += txn.execute(
+                    "DELETE FROM ValidationReceipt WHERE op_hash = :hash",
+                    named_params! { ":hash": op_hash },
+                )?;
+                removed += txn.execute(
+                    "DELETE FROM DhtOp WHERE hash = :hash",
+                    named_params! { ":hash": op_hash },
+                )?;
+            }
+            _ => (),
+        }
+    }
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mutations::insert_op;
+    use crate::test_utils::test_authored_db;
+    use crate::test_utils::test_dht_db;
+    use ::fixt::prelude::*;
+    use holochain_sqlite::rusqlite::OptionalExtension;
+    use holochain_types::dht_op::DhtOp;
+    use holochain_types::dht_op::DhtOpHashed;
+    use holochain_zome_types::fixt::*;
+    use holochain_zome_types::Action;
+
+    fn store_record_op(action: Action) -> DhtOpHashed {
+        DhtOpHashed::from_content_sync(DhtOp::StoreRecord(fixt!(Signature), action, None))
+    }
+
+    fn has_action(txn: &Transaction, hash: &ActionHash) -> bool {
+        txn.query_row(
+            "SELECT 1 FROM Action WHERE hash = :hash",
+            named_params! { ":hash": hash },
+            |_| Ok(()),
+        )
+        .optional()
+        .unwrap()
+        .is_some()
+    }
+
+    #[test]
+    fn corrupt_action_is_found_and_removed() {
+        let db = test_dht_db();
+        let op = store_record_op(Action::Dna(fixt!(Dna)));
+        let action_hash = ActionHash::with_data_sync(&op.as_content().action());
+        db.test_commit(|txn| {
+            insert_op(txn, &op).unwrap();
+            assert!(check_db(txn).unwrap().is_empty());
+
+            // Corrupt the action so it no longer hashes to its key.
+            txn.execute(
+                "UPDATE Action SET blob = randomblob(8) WHERE hash = ?",
+                [&action_hash],
+            )
+            .unwrap();
+            let issues = check_db(txn).unwrap();
+            assert_eq!(
+                issues,
+                vec![DbIntegrityIssue::ActionHashMismatch(action_hash.clone())]
+            );
+
+            // The action and its op are removed.
+            assert_eq!(remove_corrupt_rows(txn, &issues).unwrap(), 2);
+            assert!(!has_action(txn, &action_hash));
+            assert!(check_db(txn).unwrap().is_empty());
+        });
+    }
+
+    #[test]
+    fn broken_source_chain_is_found() {
+        let db = test_authored_db();
+        let author = fixt!(AgentPubKey);
+        let mut dna = fixt!(Dna);
+        dna.author = author.clone();
+        let mut avp = fixt!(AgentValidationPkg);
+        avp.author = author.clone();
+        avp.action_seq = 1;
+        avp.prev_action = ActionHash::with_data_sync(&Action::Dna(dna.clone()));
+        // The third action doesn't point back to the second,
+        // and the chain is missing the fourth.
+        let mut izc = fixt!(InitZomesComplete);
+        izc.author = author.clone();
+        izc.action_seq = 2;
+        let mut create_link = fixt!(CreateLink);
+        create_link.author = author.clone();
+        create_link.action_seq = 4;
+        let izc_hash = ActionHash::with_data_sync(&Action::InitZomesComplete(izc.clone()));
+        create_link.prev_action = izc_hash.clone();
+
+        db.test_commit(|txn| {
+            for action in [
+                Action::Dna(dna),
+                Action::AgentValidationPkg(avp),
+                Action::InitZomesComplete(izc),
+                Action::CreateLink(create_link),
+            ] {
+                insert_op(txn, &store_record_op(action)).unwrap();
+            }
+            assert_eq!(
+                check_source_chains(txn).unwrap(),
+                vec![
+                    DbIntegrityIssue::ChainBroken {
+                        author: author.clone(),
+                        seq: 2,
+                        action_hash: izc_hash,
+                    },
+                    DbIntegrityIssue::ChainGap { author, seq: 3 },
+                ]
+            );
+        });
+    }
+}
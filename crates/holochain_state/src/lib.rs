@@ -10,6 +10,9 @@
 //!
 //! The [`host_fn_workspace`] module provides abstractions for reading data during workflows.
 //!
+//! The [`snapshot`] module provides [`DbSnapshot`](snapshot::DbSnapshot) for long-running
+//! reads which need a consistent view of a database without blocking writers.
+//!
 //! ## Writes
 //! The [`mutations`] module is the complete set of functions
 //! for writing data to sqlite in holochain.
@@ -27,20 +30,24 @@
 #![allow(deprecated)]
 
 pub mod chain_lock;
+pub mod compression;
 #[allow(missing_docs)]
 pub mod dna_def;
 pub mod entry_def;
 pub mod host_fn_workspace;
 pub mod integrate;
+pub mod integrity;
 pub mod mutations;
 #[allow(missing_docs)]
 pub mod prelude;
 pub mod query;
 pub mod schedule;
 pub mod scratch;
+pub mod snapshot;
 #[allow(missing_docs)]
 pub mod source_chain;
 pub mod validation_db;
+pub mod validation_outcome_cache;
 pub mod validation_receipts;
 #[allow(missing_docs)]
 pub mod wasm;
@@ -1,3 +1,4 @@
+use crate::compression::compress_blob;
 use crate::entry_def::EntryDefStoreKey;
 use crate::prelude::SignedValidationReceipt;
 use crate::query::from_blob;
@@ -230,6 +231,19 @@ pub fn insert_dna_def(txn: &mut Transaction, dna_def: &DnaDefHashed) -> StateMut
     Ok(())
 }
 
+/// Replace a stored [`DnaDef`], keeping its hash, for the parts of it which
+/// don't affect the hash.
+pub fn update_dna_def(txn: &mut Transaction, dna_def: &DnaDefHashed) -> StateMutationResult<()> {
+    txn.execute(
+        "UPDATE DnaDef SET blob = :blob WHERE hash = :hash",
+        named_params! {
+            ":blob": to_blob(dna_def.as_content())?,
+            ":hash": dna_def.as_hash(),
+        },
+    )?;
+    Ok(())
+}
+
 /// Insert a [`EntryDef`] into the database.
 pub fn insert_entry_def(
     txn: &mut Transaction,
@@ -268,6 +282,19 @@ pub fn set_validation_status(
     })?;
     Ok(())
 }
+
+/// Set why sys validation rejected a [`DhtOp`](holochain_types::dht_op::DhtOp) in the database.
+pub fn set_rejected_reason(
+    txn: &mut Transaction,
+    hash: &DhtOpHash,
+    reason: &SysValidationIssueReason,
+) -> StateMutationResult<()> {
+    dht_op_update!(txn, hash, {
+        "rejected_reason": to_blob(reason)?,
+    })?;
+    Ok(())
+}
+
 /// Set the integration dependency of a [`DhtOp`](holochain_types::dht_op::DhtOp) in the database.
 pub fn set_dependency(
     txn: &mut Transaction,
@@ -544,7 +571,7 @@ pub fn insert_entry(
     };
     sql_insert!(txn, Entry, {
         "hash": hash,
-        "blob": to_blob(entry)?,
+        "blob": compress_blob(to_blob(entry)?),
         "tag": cap_tag,
         "access_type": cap_access,
         "grantor": cap_grantor,
@@ -586,6 +613,159 @@ pub fn unlock_chain(txn: &mut Transaction, author: &AgentPubKey) -> StateMutatio
     Ok(())
 }
 
+/// Delete an author's whole source chain from the authored database: its
+/// actions, the ops produced from them and their validation receipts, the
+/// entries no other author's actions refer to, and any chain lock or
+/// scheduled functions of the author.
+/// Returns the number of actions deleted.
+pub fn delete_source_chain(
+    txn: &mut Transaction,
+    author: &AgentPubKey,
+) -> StateMutationResult<usize> {
+    txn.execute(
+        "
+        DELETE FROM ValidationReceipt WHERE op_hash IN (
+            SELECT DhtOp.hash FROM DhtOp
+            JOIN Action ON DhtOp.action_hash = Action.hash
+            WHERE Action.author = :author
+        )
+        ",
+        named_params! { ":author": author },
+    )?;
+    txn.execute(
+        "
+        DELETE FROM DhtOp WHERE action_hash IN (
+            SELECT hash FROM Action WHERE author = :author
+        )
+        ",
+        named_params! { ":author": author },
+    )?;
+    txn.execute(
+        "
+        DELETE FROM Entry WHERE hash IN (
+            SELECT entry_hash FROM Action WHERE author = :author
+        )
+        AND NOT EXISTS (
+            SELECT 1 FROM Action
+            WHERE Action.entry_hash = Entry.hash AND Action.author != :author
+        )
+        ",
+        named_params! { ":author": author },
+    )?;
+    let deleted = txn.execute(
+        "DELETE FROM Action WHERE author = :author",
+        named_params! { ":author": author },
+    )?;
+    txn.execute("DELETE FROM ChainLock WHERE author = ?", [author])?;
+    txn.execute("DELETE FROM ScheduledFunctions WHERE author = ?", [author])?;
+    Ok(deleted)
+}
+
+/// Delete the payloads of entries for which every create held in this
+/// database has a valid delete that was integrated before `cutoff`.
+/// The actions and ops are kept, so only the entry data is lost.
+/// Returns the number of entries removed.
+pub fn prune_deleted_entries(
+    txn: &mut Transaction,
+    cutoff: Timestamp,
+) -> StateMutationResult<usize> {
+    let pruned = txn.execute(
+        holochain_sqlite::sql::sql_cell::PRUNE_DELETED_ENTRIES,
+        named_params! {
+            ":deleted_entry_action": DhtOpType::RegisterDeletedEntryAction,
+            ":store_entry": DhtOpType::StoreEntry,
+            ":valid": ValidationStatus::Valid,
+            ":cutoff": cutoff,
+        },
+    )?;
+    Ok(pruned)
+}
+
+/// Purge the ops of an ephemeral DNA which were authored before `cutoff`,
+/// along with their validation receipts and the actions and entries no
+/// other op still needs.
+/// Agent activity ops are kept, so authorities can still check the chains
+/// of the agents they hold.
+/// Returns the number of ops purged.
+pub fn purge_expired_ops(txn: &mut Transaction, cutoff: Timestamp) -> StateMutationResult<usize> {
+    let params = named_params! {
+        ":cutoff": cutoff,
+        ":activity": DhtOpType::RegisterAgentActivity,
+    };
+    let expired: usize = txn.query_row(
+        "
+        SELECT COUNT(*) FROM DhtOp
+        WHERE authored_timestamp < :cutoff AND type != :activity
+        ",
+        params,
+        |row| row.get(0),
+    )?;
+    if expired == 0 {
+        return Ok(0);
+    }
+    txn.execute(
+        "
+        DELETE FROM ValidationReceipt WHERE op_hash IN (
+            SELECT hash FROM DhtOp
+            WHERE authored_timestamp < :cutoff AND type != :activity
+        )
+        ",
+        params,
+    )?;
+    txn.execute(
+        "
+        DELETE FROM Entry WHERE hash IN (
+            SELECT Action.entry_hash FROM DhtOp
+            JOIN Action ON DhtOp.action_hash = Action.hash
+            WHERE DhtOp.authored_timestamp < :cutoff AND DhtOp.type != :activity
+        )
+        AND NOT EXISTS (
+            SELECT 1 FROM DhtOp
+            JOIN Action ON DhtOp.action_hash = Action.hash
+            WHERE Action.entry_hash = Entry.hash
+            AND DhtOp.authored_timestamp >= :cutoff AND DhtOp.type != :activity
+        )
+        ",
+        params,
+    )?;
+    // Deleting an action deletes its ops along with it.
+    txn.execute(
+        "
+        DELETE FROM Action WHERE hash IN (
+            SELECT action_hash FROM DhtOp
+            WHERE authored_timestamp < :cutoff AND type != :activity
+        )
+        AND NOT EXISTS (
+            SELECT 1 FROM DhtOp
+            WHERE DhtOp.action_hash = Action.hash AND DhtOp.type = :activity
+        )
+        ",
+        params,
+    )?;
+    txn.execute(
+        "DELETE FROM DhtOp WHERE authored_timestamp < :cutoff AND type != :activity",
+        params,
+    )?;
+    Ok(expired)
+}
+
+/// Mark the ops of an ephemeral DNA which were authored before `cutoff` as
+/// needing no more validation receipts, so their author stops publishing
+/// them. Returns the number of ops marked.
+pub fn stop_publishing_expired_ops(
+    txn: &mut Transaction,
+    cutoff: Timestamp,
+) -> StateMutationResult<usize> {
+    let stopped = txn.execute(
+        "
+        UPDATE DhtOp SET receipts_complete = 1
+        WHERE authored_timestamp < :cutoff AND receipts_complete IS NULL
+        ",
+        named_params! { ":cutoff": cutoff },
+    )?;
+    Ok(stopped)
+}
+
 pub fn delete_all_ephemeral_scheduled_fns(
     txn: &mut Transaction,
     author: &AgentPubKey,
@@ -2,6 +2,7 @@ pub use crate::mutations::*;
 pub use crate::query::prelude::*;
 pub use crate::source_chain::*;
 pub use crate::validation_db::*;
+pub use crate::validation_outcome_cache::*;
 pub use crate::validation_receipts::*;
 pub use crate::wasm::*;
 pub use crate::workspace::*;
@@ -39,7 +39,9 @@ mod tests;
 pub mod chain_head;
 pub mod entry_details;
 pub mod error;
+pub mod integrated_since;
 pub mod link;
+pub mod link_aggregate;
 pub mod link_details;
 pub mod live_entry;
 pub mod live_record;
@@ -372,11 +374,11 @@ impl<'stmt> Store for Txn<'stmt, '_> {
                 },
             },
             AnyDht::Action => Ok(self.get_exact_record(&hash.clone().into())?.map(|el| {
-                // Filter out the entry if it's private.
+                // Filter out the entry if it's private and not authored by this agent.
                 let is_private_entry = el.action().entry_type().map_or(false, |et| {
                     matches!(et.visibility(), EntryVisibility::Private)
                 });
-                if is_private_entry {
+                if is_private_entry && author != Some(el.action().author()) {
                     Record::new(el.into_inner().0, None)
                 } else {
                     el
@@ -872,8 +874,10 @@ pub fn to_blob<T: Serialize + std::fmt::Debug>(t: &T) -> StateQueryResult<Vec<u8
     Ok(holochain_serialized_bytes::encode(t)?)
 }
 
-/// Deserialize a BLOB from a database into a value
+/// Deserialize a BLOB from a database into a value,
+/// decompressing it first if it was compressed.
 pub fn from_blob<T: DeserializeOwned + std::fmt::Debug>(blob: Vec<u8>) -> StateQueryResult<T> {
+    let blob = crate::compression::decompress_blob(blob)?;
     Ok(holochain_serialized_bytes::decode(&blob)?)
 }
 
@@ -987,6 +991,10 @@ pub fn map_sql_dht_op_common(row: &Row) -> StateQueryResult<Option<DhtOp>> {
             Some(entry) => Some(from_blob::<Entry>(entry)?),
             None => None,
         };
+        // The entry has been pruned so there is nothing left to store.
+        if entry.is_none() && op_type == DhtOpType::StoreEntry {
+            return Ok(None);
+        }
     }
     Ok(Some(DhtOp::from_type(op_type, action, entry)?))
 }
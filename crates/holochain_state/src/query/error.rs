@@ -25,6 +25,10 @@ pub enum StateQueryError {
     ActionError(#[from] holochain_zome_types::action::ActionError),
     #[error(transparent)]
     SyncScratchError(#[from] SyncScratchError),
+    #[error("The database snapshot has expired")]
+    SnapshotExpired,
+    #[error("Failed to decompress a stored value: {0}")]
+    Decompression(std::io::Error),
 }
 
 pub type StateQueryResult<T> = Result<T, StateQueryError>;
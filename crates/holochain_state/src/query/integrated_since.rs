@@ -0,0 +1,50 @@
+//! The records integrated into a DHT database since a point in time, so
+//! clients can fetch what's new without walking chains or polling every
+//! query they show.
+
+use super::from_blob;
+use super::row_blob_and_hash_to_action;
+use super::StateQueryResult;
+use holochain_sqlite::rusqlite::named_params;
+use holochain_sqlite::rusqlite::Transaction;
+use holochain_sqlite::sql::sql_cell::RECORDS_INTEGRATED_SINCE;
+use holochain_zome_types::Entry;
+use holochain_zome_types::EntryVisibility;
+use holochain_zome_types::Record;
+use holochain_zome_types::Timestamp;
+use holochain_zome_types::ValidationStatus;
+
+/// Up to `limit` records with valid ops integrated at or after `since`,
+/// oldest first, each with when its first op in that range was integrated.
+///
+/// The time of the last record can be passed as the next `since` to page
+/// through them; records integrated at that same time are returned again.
+/// Private entries are never held in the DHT database, so their records are
+/// returned without them.
+pub fn records_integrated_since(
+    txn: &Transaction,
+    since: Timestamp,
+    limit: usize,
+) -> StateQueryResult<Vec<(Timestamp, Record)>> {
+    let to_action = row_blob_and_hash_to_action("action_blob", "action_hash");
+    let mut stmt = txn.prepare_cached(RECORDS_INTEGRATED_SINCE)?;
+    let mut rows = stmt.query(named_params! {
+        ":since": since,
+        ":status": ValidationStatus::Valid,
+        ":limit": limit as i64,
+    })?;
+    let mut records = Vec::new();
+    while let Some(row) = rows.next()? {
+        let action = to_action(row)?;
+        let public = action
+            .action()
+            .entry_type()
+            .map_or(false, |et| *et.visibility() == EntryVisibility::Public);
+        let entry = match row.get::<_, Option<Vec<u8>>>("entry_blob")? {
+            Some(entry) if public => Some(from_blob::<Entry>(entry)?),
+            _ => None,
+        };
+        records.push((row.get("when_integrated")?, Record::new(action, entry)));
+    }
+    Ok(records)
+}
@@ -22,13 +22,13 @@ pub struct LinksQuery {
 
 impl LinksQuery {
     pub fn new(base: AnyLinkableHash, type_query: LinkTypeFilter, tag: Option<LinkTag>) -> Self {
-        let tag = tag.map(|tag| Self::tag_to_hex(&tag));
-        let create_string = Self::create_query_string(&type_query, tag.clone());
-        let delete_string = Self::delete_query_string(&type_query, tag.clone());
+        let tag_condition = tag.as_ref().map(Self::tag_prefix_condition);
+        let create_string = Self::create_query_string(&type_query, tag_condition.as_deref());
+        let delete_string = Self::delete_query_string(&type_query, tag_condition.as_deref());
         Self {
             base: Arc::new(base),
             type_query,
-            tag,
+            tag: tag.map(|tag| Self::tag_to_hex(&tag)),
             query: Self::create_query(create_string, delete_string),
         }
     }
@@ -42,6 +42,29 @@ impl LinksQuery {
         s
     }
 
+    /// The condition on `Action.tag` for tags starting with this prefix.
+    /// It's a range rather than a pattern, so it can be looked up in the
+    /// index of links by base and tag.
+    pub fn tag_prefix_condition(tag: &LinkTag) -> String {
+        let start = Self::tag_to_hex(tag);
+        // The first byte string after every string with the prefix.
+        let mut end = tag.0.clone();
+        while end.last() == Some(&u8::MAX) {
+            end.pop();
+        }
+        match end.last_mut() {
+            Some(last) => {
+                *last += 1;
+                format!(
+                    "Action.tag >= X'{}' AND Action.tag < X'{}'",
+                    start,
+                    Self::tag_to_hex(&LinkTag::new(end))
+                )
+            }
+            None => format!("Action.tag >= X'{}'", start),
+        }
+    }
+
     pub fn base(base: AnyLinkableHash, dependencies: Vec<ZomeId>) -> Self {
         Self::new(base, LinkTypeFilter::Dependencies(dependencies), None)
     }
@@ -65,7 +88,7 @@ impl LinksQuery {
             AND DhtOp.when_integrated IS NOT NULL
         "
     }
-    fn create_query_string(type_query: &LinkTypeFilter, tag: Option<String>) -> String {
+    fn create_query_string(type_query: &LinkTypeFilter, tag: Option<&str>) -> String {
         let mut s = format!(
             "
             SELECT Action.blob AS action_blob FROM DhtOp
@@ -76,14 +99,14 @@ impl LinksQuery {
         s = Self::add_type_query(s, type_query);
         Self::add_tag(s, tag)
     }
-    fn add_tag(q: String, tag: Option<String>) -> String {
-        match tag {
-            Some(tag) => {
+    fn add_tag(q: String, tag_condition: Option<&str>) -> String {
+        match tag_condition {
+            Some(tag_condition) => {
                 format!(
                     "{}
                     AND
-                    HEX(Action.tag) like '{}%'",
-                    q, tag
+                    {}",
+                    q, tag_condition
                 )
             }
             None => q,
@@ -92,7 +115,7 @@ impl LinksQuery {
     fn add_type_query(q: String, type_query: &LinkTypeFilter) -> String {
         format!("{} {} ", q, type_query.to_sql_statement())
     }
-    fn delete_query_string(type_query: &LinkTypeFilter, tag: Option<String>) -> String {
+    fn delete_query_string(type_query: &LinkTypeFilter, tag: Option<&str>) -> String {
         let mut sub_create_query = format!(
             "
             SELECT Action.hash FROM DhtOp
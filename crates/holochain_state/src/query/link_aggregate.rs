@@ -0,0 +1,73 @@
+use holo_hash::*;
+use holochain_zome_types::*;
+use std::collections::BTreeSet;
+use std::fmt::Debug;
+
+use super::link::GetLinksQuery;
+use super::*;
+
+/// Folds the same links as [`GetLinksQuery`], but renders an aggregate of
+/// them instead of the links.
+#[derive(Debug, Clone)]
+pub struct GetLinkAggregateQuery {
+    links: GetLinksQuery,
+    aggregation: LinkAggregation,
+}
+
+impl GetLinkAggregateQuery {
+    pub fn new(
+        base: AnyLinkableHash,
+        type_query: LinkTypeFilter,
+        tag: Option<LinkTag>,
+        aggregation: LinkAggregation,
+    ) -> Self {
+        Self {
+            links: GetLinksQuery::new(base, type_query, tag),
+            aggregation,
+        }
+    }
+}
+
+impl Query for GetLinkAggregateQuery {
+    type Item = Judged<SignedActionHashed>;
+    type State = Maps<Link>;
+    type Output = LinkAggregate;
+    fn query(&self) -> String {
+        self.links.query()
+    }
+
+    fn params(&self) -> Vec<Params> {
+        self.links.params()
+    }
+
+    fn init_fold(&self) -> StateQueryResult<Self::State> {
+        self.links.init_fold()
+    }
+
+    fn as_map(&self) -> Arc<dyn Fn(&Row) -> StateQueryResult<Self::Item>> {
+        self.links.as_map()
+    }
+
+    fn as_filter(&self) -> Box<dyn Fn(&QueryData<Self>) -> bool> {
+        self.links.as_filter()
+    }
+
+    fn fold(&self, state: Self::State, data: Self::Item) -> StateQueryResult<Self::State> {
+        self.links.fold(state, data)
+    }
+
+    fn render<S>(&self, state: Self::State, _stores: S) -> StateQueryResult<Self::Output>
+    where
+        S: Store,
+    {
+        let links = state.creates;
+        Ok(match self.aggregation {
+            LinkAggregation::Count => LinkAggregate::Count(links.len()),
+            LinkAggregation::Exists => LinkAggregate::Exists(!links.is_empty()),
+            LinkAggregation::DistinctTags => {
+                let tags: BTreeSet<_> = links.into_values().map(|link| link.tag).collect();
+                LinkAggregate::DistinctTags(tags.into_iter().collect())
+            }
+        })
+    }
+}
@@ -99,13 +99,17 @@ impl Query for GetLiveRecordQuery {
         match state.0 {
             Some(action) => {
                 let mut entry = None;
-                if let Some(entry_hash) = action.action().entry_hash() {
+                if let Some((entry_hash, entry_type)) = action.action().entry_data() {
                     let author = self
                         .1
                         .as_ref()
                         .map(|a| a.as_ref())
                         .filter(|a| *a == action.action().author());
-                    entry = stores.get_public_or_authored_entry(entry_hash, author)?;
+                    // A private entry is only shown to its author, even if the
+                    // same entry has also been committed publicly.
+                    if *entry_type.visibility() == EntryVisibility::Public || author.is_some() {
+                        entry = stores.get_public_or_authored_entry(entry_hash, author)?;
+                    }
                 }
                 Ok(Some(Record::new(action, entry)))
             }
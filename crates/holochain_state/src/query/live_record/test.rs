@@ -1,6 +1,9 @@
+use ::fixt::prelude::*;
 use holochain_sqlite::rusqlite::Connection;
 use holochain_sqlite::rusqlite::TransactionBehavior;
 use holochain_sqlite::schema::SCHEMA_CELL;
+use holochain_types::dht_op::DhtOp;
+use holochain_types::dht_op::DhtOpHashed;
 use test_data::RecordTestData;
 
 use crate::mutations::insert_op_scratch;
@@ -48,3 +51,52 @@ async fn can_handle_update_in_scratch() {
     assert_eq!(*r.entry().as_option().unwrap(), td.entry);
     assert_eq!(*r.action(), *td.update_action.action());
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn private_entries_are_only_shown_to_their_author() {
+    observability::test_run().ok();
+    let mut conn = Connection::open_in_memory().unwrap();
+    SCHEMA_CELL.initialize(&mut conn, None).unwrap();
+
+    let mut txn = conn
+        .transaction_with_behavior(TransactionBehavior::Exclusive)
+        .unwrap();
+
+    // The same entry is committed privately by one agent and publicly by another.
+    let entry = Entry::App(fixt!(AppEntryBytes));
+    let entry_hash = EntryHash::with_data_sync(&entry);
+    let create_op = |visibility| {
+        let mut create = fixt!(Create);
+        create.entry_hash = entry_hash.clone();
+        create.entry_type = EntryType::App(AppEntryType::new(0.into(), 0.into(), visibility));
+        let action = Action::Create(create);
+        let op = DhtOpHashed::from_content_sync(DhtOp::StoreRecord(
+            fixt!(Signature),
+            action.clone(),
+            Some(Box::new(entry.clone())),
+        ));
+        (
+            ActionHash::with_data_sync(&action),
+            action.author().clone(),
+            op,
+        )
+    };
+    let (private_hash, author, private_op) = create_op(EntryVisibility::Private);
+    let (_, _, public_op) = create_op(EntryVisibility::Public);
+    insert_valid_integrated_op(&mut txn, &private_op).unwrap();
+    insert_valid_integrated_op(&mut txn, &public_op).unwrap();
+
+    // - Other agents don't get the entry of the private action.
+    let r = GetLiveRecordQuery::new(private_hash.clone())
+        .run(Txn::from(&txn))
+        .unwrap()
+        .expect("Record not found");
+    assert_eq!(*r.entry(), RecordEntry::Hidden);
+
+    // - The author does.
+    let r = GetLiveRecordQuery::with_private_data_access(private_hash, Arc::new(author))
+        .run(Txn::from(&txn))
+        .unwrap()
+        .expect("Record not found");
+    assert_eq!(r.entry().as_option(), Some(&entry));
+}
@@ -22,6 +22,7 @@ mod chain_sequence;
 #[cfg(todo_redo_old_tests)]
 mod chain_test;
 mod details;
+mod integrated_since;
 mod links;
 mod links_test;
 mod store;
@@ -0,0 +1,50 @@
+use super::*;
+use crate::query::integrated_since::records_integrated_since;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn records_are_listed_from_when_they_were_integrated() {
+    let mut conn = Connection::open_in_memory().unwrap();
+    SCHEMA_CELL.initialize(&mut conn, None).unwrap();
+    let mut txn = conn
+        .transaction_with_behavior(TransactionBehavior::Exclusive)
+        .unwrap();
+
+    let td = LinkTestData::new();
+    let ops = [
+        (&td.create_link_op, Timestamp(1_000)),
+        (&td.later_create_link_op, Timestamp(2_000)),
+    ];
+    for (op, when_integrated) in ops.iter() {
+        insert_op(&mut txn, op).unwrap();
+        set_validation_status(&mut txn, op.as_hash(), ValidationStatus::Valid).unwrap();
+        set_when_integrated(&mut txn, op.as_hash(), *when_integrated).unwrap();
+    }
+
+    let records = records_integrated_since(&txn, Timestamp(0), 10).unwrap();
+    assert_eq!(
+        records
+            .iter()
+            .map(|(when, record)| (*when, record.action_address().clone()))
+            .collect::<Vec<_>>(),
+        vec![
+            (Timestamp(1_000), td.create_link_action.as_hash().clone()),
+            (
+                Timestamp(2_000),
+                td.later_create_link_action.as_hash().clone()
+            ),
+        ]
+    );
+
+    let records = records_integrated_since(&txn, Timestamp(1_500), 10).unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(
+        records[0].1.action_address(),
+        td.later_create_link_action.as_hash()
+    );
+    assert_eq!(
+        records_integrated_since(&txn, Timestamp(0), 1)
+            .unwrap()
+            .len(),
+        1
+    );
+}
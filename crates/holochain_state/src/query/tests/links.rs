@@ -25,3 +25,78 @@ async fn link_queries_are_ordered_by_timestamp() {
         ]
     );
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn link_aggregate_queries_fold_like_link_queries() {
+    use crate::query::link_aggregate::GetLinkAggregateQuery;
+
+    let mut conn = Connection::open_in_memory().unwrap();
+    SCHEMA_CELL.initialize(&mut conn, None).unwrap();
+
+    let mut txn = conn
+        .transaction_with_behavior(TransactionBehavior::Exclusive)
+        .unwrap();
+
+    let td = LinkTestData::new();
+    let base = match td.create_link_action.action() {
+        Action::CreateLink(create_link) => create_link.base_address.clone(),
+        _ => unreachable!(),
+    };
+    let aggregate = |txn: &Transaction, aggregation| {
+        GetLinkAggregateQuery::new(
+            base.clone(),
+            LinkTypeFilter::single_dep(0.into()),
+            Some(td.link.tag.clone()),
+            aggregation,
+        )
+        .run(Txn::from(txn))
+        .unwrap()
+    };
+
+    assert_eq!(
+        aggregate(&txn, LinkAggregation::Count),
+        LinkAggregate::Count(0)
+    );
+    assert_eq!(
+        aggregate(&txn, LinkAggregation::Exists),
+        LinkAggregate::Exists(false)
+    );
+
+    insert_valid_integrated_op(&mut txn, &td.create_link_op).unwrap();
+    insert_valid_integrated_op(&mut txn, &td.later_create_link_op).unwrap();
+    assert_eq!(
+        aggregate(&txn, LinkAggregation::Count),
+        LinkAggregate::Count(2)
+    );
+    assert_eq!(
+        aggregate(&txn, LinkAggregation::Exists),
+        LinkAggregate::Exists(true)
+    );
+    // Both links have the same tag.
+    assert_eq!(
+        aggregate(&txn, LinkAggregation::DistinctTags),
+        LinkAggregate::DistinctTags(vec![td.link.tag.clone()])
+    );
+
+    insert_valid_integrated_op(&mut txn, &td.delete_link_op).unwrap();
+    assert_eq!(
+        aggregate(&txn, LinkAggregation::Count),
+        LinkAggregate::Count(1)
+    );
+}
+
+#[test]
+fn tag_prefixes_are_byte_ranges() {
+    assert_eq!(
+        LinksQuery::tag_prefix_condition(&LinkTag::new(vec![0x01, 0xFF])),
+        "Action.tag >= X'01FF' AND Action.tag < X'02'"
+    );
+    assert_eq!(
+        LinksQuery::tag_prefix_condition(&LinkTag::new(vec![0xFF])),
+        "Action.tag >= X'FF'"
+    );
+    assert_eq!(
+        LinksQuery::tag_prefix_condition(&LinkTag::new(vec![])),
+        "Action.tag >= X''"
+    );
+}
@@ -44,6 +44,15 @@ pub struct Scratch {
 #[derive(Debug, Clone)]
 pub struct SyncScratch(Arc<Mutex<Scratch>>);
 
+/// A point in a [`Scratch`] which it can be rolled back to,
+/// so the writes of a failed call can be dropped without
+/// dropping the writes staged before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScratchSavepoint {
+    actions: usize,
+    scheduled_fns: usize,
+}
+
 // MD: hmm, why does this need to be a separate type? Why collect into this?
 pub struct FilteredScratch {
     actions: Vec<SignedActionHashed>,
@@ -180,6 +189,39 @@ impl Scratch {
         Ok(r)
     }
 
+    /// Mark the current state of the scratch, to roll back to with
+    /// [`Scratch::rollback_to`].
+    pub fn savepoint(&self) -> ScratchSavepoint {
+        ScratchSavepoint {
+            actions: self.actions.len(),
+            scheduled_fns: self.scheduled_fns.len(),
+        }
+    }
+
+    /// Drop everything staged since the savepoint was taken.
+    /// Entries are dropped unless an action staged before the
+    /// savepoint still refers to them.
+    /// Returns the number of actions dropped.
+    pub fn rollback_to(&mut self, savepoint: ScratchSavepoint) -> usize {
+        let dropped = self.actions.len().saturating_sub(savepoint.actions);
+        self.actions.truncate(savepoint.actions);
+        self.scheduled_fns.truncate(savepoint.scheduled_fns);
+        self.chain_head = self
+            .actions
+            .iter()
+            .enumerate()
+            .rev()
+            .max_by_key(|(_, shh)| shh.action().action_seq())
+            .map(|(i, shh)| (shh.action().action_seq(), i));
+        let actions = &self.actions;
+        self.entries.retain(|hash, _| {
+            actions
+                .iter()
+                .any(|shh| shh.action().entry_hash() == Some(hash))
+        });
+        dropped
+    }
+
     pub fn drain_scheduled_fns(&mut self) -> impl Iterator<Item = ScheduledFn> + '_ {
         self.scheduled_fns.drain(..)
     }
@@ -0,0 +1,166 @@
+//! Read-only snapshots of a database for long-running reads.
+//!
+//! A [`DbSnapshot`] holds a single read transaction open so that any number of
+//! reads made through it see the database exactly as it was when the snapshot
+//! was opened. The databases run in WAL mode, so writers carry on while a
+//! snapshot is open, but SQLite can't checkpoint past the oldest open read
+//! transaction. Every snapshot therefore has a timeout, after which its
+//! transaction is closed even if it has not been dropped.
+
+use crate::query::StateQueryError;
+use crate::query::StateQueryResult;
+use holochain_sqlite::db::PermittedConn;
+use holochain_sqlite::prelude::*;
+use holochain_sqlite::rusqlite::Transaction;
+use std::sync::mpsc;
+use std::time::Duration;
+use std::time::Instant;
+use tokio::sync::oneshot;
+
+type SnapshotJob = Box<dyn FnOnce(&Transaction) + Send + 'static>;
+
+/// How long the snapshot a state dump is read from stays open.
+pub const STATE_DUMP_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// A consistent, read-only view of a database which doesn't block writers.
+///
+/// The snapshot's transaction is held on a blocking thread until the snapshot
+/// is dropped or its timeout passes, whichever comes first. Reads made after
+/// the timeout fail with [`StateQueryError::SnapshotExpired`].
+pub struct DbSnapshot {
+    jobs: mpsc::Sender<SnapshotJob>,
+    expires_at: Instant,
+}
+
+impl DbSnapshot {
+    /// Open a snapshot of the database as it is now, which will
+    /// be closed after `timeout`.
+    pub async fn open<Kind: DbKindT>(
+        db: &DbRead<Kind>,
+        timeout: Duration,
+    ) -> StateQueryResult<Self> {
+        let permit = db.conn_permit().await;
+        let mut conn = db.with_permit(permit)?;
+        let expires_at = Instant::now() + timeout;
+        let (jobs, rx) = mpsc::channel::<SnapshotJob>();
+        let (opened_tx, opened_rx) = oneshot::channel();
+        tokio::task::spawn_blocking(move || {
+            let txn = match begin_snapshot(&mut conn) {
+                Ok(txn) => txn,
+                Err(e) => {
+                    let _ = opened_tx.send(Err(e));
+                    return;
+                }
+            };
+            let _ = opened_tx.send(Ok(()));
+            loop {
+                let remaining = expires_at.saturating_duration_since(Instant::now());
+                match rx.recv_timeout(remaining) {
+                    Ok(job) => job(&txn),
+                    Err(mpsc::RecvTimeoutError::Timeout) => {
+                        tracing::warn!(
+                            "A database snapshot was still open after {:?} and has been closed",
+                            timeout
+                        );
+                        break;
+                    }
+                    Err(mpsc::RecvTimeoutError::Disconnected) => break,
+                }
+            }
+            // Dropping the transaction rolls it back, which releases the
+            // snapshot. The connection and permit are given back after.
+        });
+        opened_rx
+            .await
+            .map_err(|_| StateQueryError::SnapshotExpired)??;
+        Ok(Self { jobs, expires_at })
+    }
+
+    /// Run a read against the snapshot.
+    pub async fn read<R, E, F>(&self, f: F) -> Result<R, E>
+    where
+        E: From<StateQueryError> + Send + 'static,
+        F: FnOnce(&Transaction) -> Result<R, E> + Send + 'static,
+        R: Send + 'static,
+    {
+        if self.is_expired() {
+            return Err(StateQueryError::SnapshotExpired.into());
+        }
+        let (tx, rx) = oneshot::channel();
+        self.jobs
+            .send(Box::new(move |txn| {
+                let _ = tx.send(f(txn));
+            }))
+            .map_err(|_| StateQueryError::SnapshotExpired)?;
+        rx.await.map_err(|_| StateQueryError::SnapshotExpired)?
+    }
+
+    /// When this snapshot will be closed.
+    pub fn expires_at(&self) -> Instant {
+        self.expires_at
+    }
+
+    /// Has this snapshot's timeout passed.
+    pub fn is_expired(&self) -> bool {
+        Instant::now() >= self.expires_at
+    }
+}
+
+/// Start a read transaction and make a read so that SQLite
+/// takes the snapshot now rather than on the first query.
+fn begin_snapshot(conn: &mut PConnGuard) -> StateQueryResult<Transaction<'_>> {
+    let txn = conn.transaction()?;
+    txn.query_row("SELECT COUNT(*) FROM sqlite_master", [], |row| {
+        row.get::<_, i64>(0)
+    })?;
+    Ok(txn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::test_dht_db;
+
+    fn count_entries(txn: &Transaction) -> StateQueryResult<i64> {
+        Ok(txn.query_row("SELECT COUNT(*) FROM Entry", [], |row| row.get(0))?)
+    }
+
+    fn insert_entry(txn: &mut Transaction) {
+        txn.execute(
+            "INSERT INTO Entry (hash, blob) VALUES (randomblob(36), randomblob(8))",
+            [],
+        )
+        .unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn snapshot_is_consistent_while_writers_continue() {
+        let test_db = test_dht_db();
+        let db = test_db.to_db();
+        let snapshot = DbSnapshot::open(&db, Duration::from_secs(10))
+            .await
+            .unwrap();
+
+        // Writes aren't blocked by the open snapshot.
+        db.test_commit(insert_entry);
+
+        assert_eq!(snapshot.read(count_entries).await.unwrap(), 0);
+        let count = db.async_reader(|txn| count_entries(&txn)).await.unwrap();
+        assert_eq!(count, 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn snapshot_is_closed_after_timeout() {
+        let db = test_dht_db();
+        let snapshot = DbSnapshot::open(&db.to_db(), Duration::from_millis(50))
+            .await
+            .unwrap();
+        assert_eq!(snapshot.read(count_entries).await.unwrap(), 0);
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(matches!(
+            snapshot.read(count_entries).await,
+            Err(StateQueryError::SnapshotExpired)
+        ));
+    }
+}
@@ -25,6 +25,7 @@ use holochain_types::dht_op::UniqueForm;
 use holochain_types::record::SignedActionHashedExt;
 use holochain_types::sql::AsSql;
 use holochain_zome_types::action;
+use holochain_zome_types::query::ChainCursor;
 use holochain_zome_types::query::ChainQueryFilterRange;
 use holochain_zome_types::Action;
 use holochain_zome_types::ActionBuilder;
@@ -57,12 +58,17 @@ use crate::chain_lock::is_lock_expired;
 use crate::prelude::*;
 use crate::query::chain_head::ChainHeadQuery;
 use crate::scratch::Scratch;
+use crate::scratch::ScratchSavepoint;
 use crate::scratch::SyncScratch;
+use crate::snapshot::DbSnapshot;
+use crate::snapshot::STATE_DUMP_TIMEOUT;
 use holo_hash::EntryHash;
 use holochain_serialized_bytes::prelude::*;
 
+pub use chain_head_agreement::*;
 pub use error::*;
 
+mod chain_head_agreement;
 mod error;
 
 #[derive(Clone)]
@@ -510,6 +516,20 @@ where
         self.scratch.clone()
     }
 
+    /// Mark the current state of the scratch, so the writes made after
+    /// this point can be dropped with [`SourceChain::rollback_to`].
+    pub fn savepoint(&self) -> SourceChainResult<ScratchSavepoint> {
+        Ok(self.scratch.apply(|scratch| scratch.savepoint())?)
+    }
+
+    /// Drop the writes made to the scratch since the savepoint,
+    /// returning how many actions were dropped.
+    pub fn rollback_to(&self, savepoint: ScratchSavepoint) -> SourceChainResult<usize> {
+        Ok(self
+            .scratch
+            .apply(|scratch| scratch.rollback_to(savepoint))?)
+    }
+
     pub fn agent_pubkey(&self) -> &AgentPubKey {
         self.author.as_ref()
     }
@@ -694,6 +714,41 @@ where
     /// used by the `query` host function, which crosses the wasm boundary
     // FIXME: This query needs to be tested.
     pub async fn query(&self, query: QueryFilter) -> SourceChainResult<Vec<Record>> {
+        self.query_after(query, None, None).await
+    }
+
+    /// Query a page of up to `limit` records, after the action at `after_seq`
+    /// if given, so the records of earlier pages aren't read again.
+    /// The bool is true if there are more records after the page.
+    pub async fn query_page(
+        &self,
+        query: QueryFilter,
+        after_seq: Option<u32>,
+        limit: u32,
+    ) -> SourceChainResult<(Vec<Record>, bool)> {
+        let limit = limit.max(1);
+        // A sequence range is narrowed to the page, plus one position to tell
+        // whether there is a next page. Other queries stop reading rows at the
+        // limit instead, as their records can be spread out along the chain.
+        let query = match query.sequence_range {
+            ChainQueryFilterRange::ActionSeqRange(_, _) => {
+                query.page(after_seq.map(ChainCursor::after), limit.saturating_add(1))
+            }
+            _ => query,
+        };
+        let limit = limit as usize;
+        let mut records = self.query_after(query, after_seq, Some(limit + 1)).await?;
+        let more = records.len() > limit;
+        records.truncate(limit);
+        Ok((records, more))
+    }
+
+    async fn query_after(
+        &self,
+        query: QueryFilter,
+        after_seq: Option<u32>,
+        limit: Option<usize>,
+    ) -> SourceChainResult<Vec<Record>> {
         if query.sequence_range != ChainQueryFilterRange::Unbounded
             && (query.action_type.is_some()
                 || query.entry_type.is_some()
@@ -704,6 +759,16 @@ where
         }
         let author = self.author.clone();
         let public_only = self.public_only;
+        // The limit can only be applied to the rows when no later filtering
+        // drops any, and hash bounded ranges are walked back from their end.
+        let sql_limit = match (&query.sequence_range, &query.entry_hashes, limit) {
+            (
+                ChainQueryFilterRange::Unbounded | ChainQueryFilterRange::ActionSeqRange(_, _),
+                None,
+                Some(limit),
+            ) => limit as i64,
+            _ => -1,
+        };
         let mut records = self
             .vault
             .async_reader({
@@ -769,7 +834,10 @@ where
                 (:entry_type IS NULL OR Action.entry_type = :entry_type)
                 AND
                 (:action_type IS NULL OR Action.type = :action_type)
+                AND
+                (:after_seq IS NULL OR Action.seq > :after_seq)
                 ORDER BY Action.seq ASC
+                LIMIT :limit
                 ",
                     );
                     let mut stmt = txn.prepare(&sql)?;
@@ -779,6 +847,8 @@ where
                                 ":author": author.as_ref(),
                                 ":entry_type": query.entry_type.as_sql(),
                                 ":action_type": query.action_type.as_sql(),
+                                ":after_seq": after_seq,
+                                ":limit": sql_limit,
                                 ":range_start": match query.sequence_range {
                                     ChainQueryFilterRange::ActionSeqRange(start, _) => Some(start),
                                     _ => None,
@@ -831,6 +901,7 @@ where
         self.scratch.apply(|scratch| {
             let mut scratch_records: Vec<_> = scratch
                 .actions()
+                .filter(|shh| after_seq.map_or(true, |seq| shh.action().action_seq() > seq))
                 .filter_map(|shh| {
                     let entry = match shh.action().entry_hash() {
                         Some(eh) if query.include_entries => scratch.get_entry(eh).ok()?,
@@ -843,7 +914,11 @@ where
 
             records.extend(scratch_records);
         })?;
-        Ok(query.filter_records(records))
+        let mut records = query.filter_records(records);
+        if let Some(limit) = limit {
+            records.truncate(limit);
+        }
+        Ok(records)
     }
 
     pub async fn is_chain_locked(&self, lock: Vec<u8>) -> SourceChainResult<bool> {
@@ -1122,6 +1197,105 @@ pub fn current_countersigning_session(
     }
 }
 
+/// Get the session of a countersigned action left at the head of an
+/// author's chain by a session which hasn't succeeded, e.g. because the
+/// conductor stopped before the session did. Such an action's ops are
+/// withheld from publishing until the session succeeds, so if the session
+/// failed it would stay at the head of the chain without ever being
+/// published.
+///
+/// Returns the hash of the action along with the session.
+/// Fails with [`SourceChainError::ChainLocked`] while the session's lock
+/// hasn't expired, as the session may still complete.
+pub fn uncommitted_countersigning_session(
+    txn: &Transaction,
+    author: Arc<AgentPubKey>,
+) -> SourceChainResult<Option<(ActionHash, EntryHash, CounterSigningSessionData)>> {
+    if is_chain_locked(txn, &[], author.as_ref())? {
+        return Err(SourceChainError::ChainLocked);
+    }
+    let head = match chain_head_db(txn, author) {
+        Ok((head, _, _)) => head,
+        Err(SourceChainError::ChainEmpty) => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    let withheld = txn
+        .query_row(
+            "
+            SELECT EXISTS(
+                SELECT 1 FROM DhtOp
+                WHERE action_hash = :hash AND withhold_publish = 1
+            )
+            ",
+            named_params! { ":hash": head },
+            |row| row.get::<_, bool>(0),
+        )
+        .map_err(StateMutationError::from)?;
+    if !withheld {
+        return Ok(None);
+    }
+    let record = match Txn::from(txn).get_record(&head.clone().into())? {
+        Some(record) => record,
+        None => return Ok(None),
+    };
+    let (shh, ee) = record.into_inner();
+    Ok(match (shh.action().entry_hash(), ee.into_option()) {
+        (Some(entry_hash), Some(Entry::CounterSign(cs, _))) => {
+            Some((head, entry_hash.to_owned(), *cs))
+        }
+        _ => None,
+    })
+}
+
+/// Remove the countersigned action found by
+/// [`uncommitted_countersigning_session`] once its session is known to have
+/// failed. It must not be removed while any of the signers may have seen
+/// the session succeed, as they will publish it.
+///
+/// The chain lock is removed along with the action, its ops and its entry.
+/// Returns false, without removing anything, if the action is no longer an
+/// uncommitted countersigned action at the head of the chain.
+pub fn rollback_uncommitted(
+    txn: &mut Transaction,
+    author: Arc<AgentPubKey>,
+    action_hash: &ActionHash,
+) -> SourceChainResult<bool> {
+    let entry_hash = match uncommitted_countersigning_session(txn, author.clone())? {
+        Some((head, entry_hash, _)) if head == *action_hash => entry_hash,
+        _ => return Ok(false),
+    };
+    let head = action_hash;
+    txn.execute(
+        "
+        DELETE FROM ValidationReceipt WHERE op_hash IN
+        (SELECT hash FROM DhtOp WHERE action_hash = :hash)
+        ",
+        named_params! { ":hash": head },
+    )
+    .map_err(StateMutationError::from)?;
+    txn.execute(
+        "DELETE FROM DhtOp WHERE action_hash = :hash",
+        named_params! { ":hash": head },
+    )
+    .map_err(StateMutationError::from)?;
+    txn.execute(
+        "DELETE FROM Action WHERE hash = :hash",
+        named_params! { ":hash": head },
+    )
+    .map_err(StateMutationError::from)?;
+    // Another action may have created the same entry.
+    txn.execute(
+        "
+        DELETE FROM Entry WHERE hash = :hash
+        AND NOT EXISTS(SELECT 1 FROM Action WHERE entry_hash = :hash)
+        ",
+        named_params! { ":hash": entry_hash },
+    )
+    .map_err(StateMutationError::from)?;
+    unlock_chain(txn, &author)?;
+    Ok(true)
+}
+
 #[cfg(test)]
 async fn _put_db<H: holochain_zome_types::ActionUnweighed, B: ActionBuilder<H>>(
     vault: holochain_types::db::DbWrite<DbKindAuthored>,
@@ -1173,14 +1347,20 @@ async fn _put_db<H: holochain_zome_types::ActionUnweighed, B: ActionBuilder<H>>(
 }
 
 /// dump the entire source chain as a pretty-printed json string
+///
+/// The chain is read from a [`DbSnapshot`], so the records and the count of
+/// published ops agree, and a dump of a long chain neither holds up writers
+/// nor keeps its read open for longer than [`STATE_DUMP_TIMEOUT`].
 pub async fn dump_state(
     vault: DbRead<DbKindAuthored>,
     author: AgentPubKey,
 ) -> Result<SourceChainJsonDump, SourceChainError> {
-    Ok(vault
-        .async_reader(move |txn| {
-            let records = txn
-                .prepare(
+    let snapshot = DbSnapshot::open(&vault, STATE_DUMP_TIMEOUT).await?;
+    let records = snapshot
+        .read({
+            let author = author.clone();
+            move |txn| {
+                txn.prepare(
                     "
                 SELECT DISTINCT
                 Action.blob AS action_blob, Entry.blob AS entry_blob,
@@ -1213,8 +1393,13 @@ pub async fn dump_state(
                         })
                     },
                 )?
-                .collect::<StateQueryResult<Vec<_>>>()?;
-            let published_ops_count = txn.query_row(
+                .collect::<StateQueryResult<Vec<_>>>()
+            }
+        })
+        .await?;
+    let published_ops_count = snapshot
+        .read(move |txn| {
+            StateQueryResult::Ok(txn.query_row(
                 "
                 SELECT COUNT(DhtOp.hash) FROM DhtOp
                 JOIN Action ON DhtOp.action_hash = Action.hash
@@ -1227,13 +1412,13 @@ pub async fn dump_state(
                 ":author": author,
                 },
                 |row| row.get(0),
-            )?;
-            StateQueryResult::Ok(SourceChainJsonDump {
-                records,
-                published_ops_count,
-            })
+            )?)
         })
-        .await?)
+        .await?;
+    Ok(SourceChainJsonDump {
+        records,
+        published_ops_count,
+    })
 }
 
 impl From<SourceChain> for SourceChainRead {
@@ -1353,6 +1538,186 @@ pub mod tests {
 
         Ok(())
     }
+    #[tokio::test(flavor = "multi_thread")]
+    async fn rollback_drops_writes_after_savepoint() -> SourceChainResult<()> {
+        let test_db = test_authored_db();
+        let dht_db = test_dht_db();
+        let keystore = test_keystore();
+        let db = test_db.to_db();
+        let alice = fixt!(AgentPubKey, Predictable, 0);
+        let dht_db_cache = DhtDbQueryCache::new(dht_db.to_db().into());
+
+        source_chain::genesis(
+            db.clone(),
+            dht_db.to_db(),
+            &dht_db_cache,
+            keystore.clone(),
+            fake_dna_hash(1),
+            alice.clone(),
+            None,
+        )
+        .await
+        .unwrap();
+        let chain = SourceChain::new(
+            db.clone().into(),
+            dht_db.to_db(),
+            dht_db_cache.clone(),
+            keystore.clone(),
+            alice.clone(),
+        )
+        .await?;
+
+        let entry_1 = Entry::App(fixt!(AppEntryBytes));
+        let eh1 = EntryHash::with_data_sync(&entry_1);
+        let create = builder::Create {
+            entry_type: EntryType::App(fixt!(AppEntryType)),
+            entry_hash: eh1.clone(),
+        };
+        let h1 = chain
+            .put_weightless(create, Some(entry_1), ChainTopOrdering::Relaxed)
+            .await?;
+        let head = chain.chain_head()?;
+
+        let savepoint = chain.savepoint()?;
+        let entry_2 = Entry::App(fixt!(AppEntryBytes));
+        let eh2 = EntryHash::with_data_sync(&entry_2);
+        let create = builder::Create {
+            entry_type: EntryType::App(fixt!(AppEntryType)),
+            entry_hash: eh2.clone(),
+        };
+        chain
+            .put_weightless(create, Some(entry_2), ChainTopOrdering::Relaxed)
+            .await?;
+        assert_eq!(chain.scratch_records()?.len(), 2);
+
+        assert_eq!(chain.rollback_to(savepoint)?, 1);
+        let records = chain.scratch_records()?;
+        assert_eq!(records.len(), 1);
+        assert_eq!(*records[0].action_address(), h1);
+        assert_eq!(chain.chain_head()?, head);
+        chain.scratch().apply(|scratch| {
+            assert!(scratch.contains_entry(&eh1).unwrap());
+            assert!(!scratch.contains_entry(&eh2).unwrap());
+        })?;
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn rollback_uncommitted_countersigned_action() -> SourceChainResult<()> {
+        let test_db = test_authored_db();
+        let dht_db = test_dht_db();
+        let keystore = test_keystore();
+        let db = test_db.to_db();
+        let alice = fixt!(AgentPubKey, Predictable, 0);
+        let bob = fixt!(AgentPubKey, Predictable, 1);
+
+        let mut mock = MockHolochainP2pDnaT::new();
+        mock.expect_authority_for_hash().returning(|_| Ok(false));
+        let dht_db_cache = DhtDbQueryCache::new(dht_db.to_db().into());
+
+        source_chain::genesis(
+            db.clone(),
+            dht_db.to_db(),
+            &dht_db_cache,
+            keystore.clone(),
+            fake_dna_hash(1),
+            alice.clone(),
+            None,
+        )
+        .await
+        .unwrap();
+        let chain = SourceChain::new(
+            db.clone().into(),
+            dht_db.to_db(),
+            dht_db_cache.clone(),
+            keystore.clone(),
+            alice.clone(),
+        )
+        .await?;
+        let (head, _, _) = chain.chain_head()?;
+
+        // A session which alice commits to but which never succeeds.
+        let start = Timestamp::now();
+        let session_times = CounterSigningSessionTimes::try_new(
+            start,
+            (start + SESSION_ACTION_TIME_OFFSET).unwrap(),
+        )
+        .unwrap();
+        let preflight_request = PreflightRequest::try_new(
+            fixt!(EntryHash),
+            vec![(alice.clone(), vec![]), (bob, vec![])],
+            vec![],
+            0,
+            false,
+            session_times,
+            ActionBase::Create(CreateBase::new(EntryType::App(fixt!(AppEntryType)))),
+            PreflightBytes(vec![]),
+        )
+        .unwrap();
+        let alice_state = chain
+            .accept_countersigning_preflight_request(preflight_request.clone(), 0)
+            .await?;
+        let bob_state = CounterSigningAgentState::new(1, fixt!(ActionHash), 3);
+        let session_data = CounterSigningSessionData::try_new(
+            preflight_request,
+            vec![
+                (alice_state, fixt!(Signature)),
+                (bob_state, fixt!(Signature)),
+            ],
+            vec![],
+        )
+        .unwrap();
+        let entry = Entry::CounterSign(Box::new(session_data), fixt!(AppEntryBytes));
+        let action_hash = chain
+            .put_countersigned(entry, ChainTopOrdering::Strict, EntryRateWeight::default())
+            .await?;
+        chain.flush(&mock).await?;
+
+        let author = Arc::new(alice);
+        let session = |db: DbWrite<DbKindAuthored>, author: Arc<AgentPubKey>| async move {
+            db.async_reader(move |txn| uncommitted_countersigning_session(&txn, author))
+                .await
+        };
+
+        // The session may still complete while the chain is locked.
+        assert_matches!(
+            session(db.clone(), author.clone()).await,
+            Err(SourceChainError::ChainLocked)
+        );
+
+        tokio::time::sleep(SESSION_ACTION_TIME_OFFSET).await;
+        let (found, _, _) = session(db.clone(), author.clone()).await?.unwrap();
+        assert_eq!(found, action_hash);
+
+        // Nothing is removed if the action isn't the one found.
+        let other = fixt!(ActionHash);
+        let author_1 = author.clone();
+        assert!(
+            !db.async_commit(move |txn| rollback_uncommitted(txn, author_1, &other))
+                .await?
+        );
+
+        let author_2 = author.clone();
+        assert!(
+            db.async_commit(move |txn| rollback_uncommitted(txn, author_2, &action_hash))
+                .await?
+        );
+        let author_3 = author.clone();
+        let (new_head, _, _) = db
+            .async_reader(move |txn| chain_head_db(&txn, author_3))
+            .await?;
+        assert_eq!(new_head, head);
+        assert_matches!(session(db.clone(), author.clone()).await, Ok(None));
+        let locks: usize = db
+            .async_reader(|txn| {
+                txn.query_row("SELECT COUNT(*) FROM ChainLock", [], |row| row.get(0))
+                    .map_err(StateMutationError::from)
+            })
+            .await?;
+        assert_eq!(locks, 0);
+        Ok(())
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_relaxed_ordering_with_entry() -> SourceChainResult<()> {
         let test_db = test_authored_db();
@@ -2024,4 +2389,62 @@ pub mod tests {
             }
         }
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn source_chain_query_page() {
+        let test_db = test_authored_db();
+        let dht_db = test_dht_db();
+        let dht_db_cache = DhtDbQueryCache::new(dht_db.to_db().into());
+        let keystore = test_keystore();
+        let vault = test_db.to_db();
+        let alice = keystore.new_sign_keypair_random().await.unwrap();
+
+        genesis(
+            vault.clone().into(),
+            dht_db.to_db(),
+            &dht_db_cache,
+            keystore.clone(),
+            fixt!(DnaHash),
+            alice.clone(),
+            None,
+        )
+        .await
+        .unwrap();
+
+        let chain = SourceChain::new(vault, dht_db.to_db(), dht_db_cache, keystore, alice)
+            .await
+            .unwrap();
+        let seqs = |records: Vec<Record>| {
+            records
+                .iter()
+                .map(|r| r.action().action_seq())
+                .collect::<Vec<_>>()
+        };
+
+        for sequence_range in [
+            ChainQueryFilterRange::Unbounded,
+            ChainQueryFilterRange::ActionSeqRange(0, 2),
+        ] {
+            let query = ChainQueryFilter::new().sequence_range(sequence_range);
+
+            let (records, more) = chain.query_page(query.clone(), None, 2).await.unwrap();
+            assert_eq!(seqs(records), vec![0, 1]);
+            assert!(more);
+
+            let (records, more) = chain.query_page(query.clone(), Some(1), 2).await.unwrap();
+            assert_eq!(seqs(records), vec![2]);
+            assert!(!more);
+
+            let (records, more) = chain.query_page(query, Some(0), 2).await.unwrap();
+            assert_eq!(seqs(records), vec![1, 2]);
+            assert!(!more);
+        }
+
+        // The page ends with the range.
+        let query =
+            ChainQueryFilter::new().sequence_range(ChainQueryFilterRange::ActionSeqRange(0, 1));
+        let (records, more) = chain.query_page(query, Some(0), 2).await.unwrap();
+        assert_eq!(seqs(records), vec![1]);
+        assert!(!more);
+    }
 }
@@ -0,0 +1,114 @@
+//! Agreement on the chain head between devices which share an agent key.
+//!
+//! Each device publishes its actions to the agent activity authorities of
+//! the agent, so those authorities see the chain heads of every device.
+//! Before a device writes, it can ask them what they have observed and only
+//! write if no other device has already written on top of its chain head.
+
+use holochain_zome_types::query::ChainHead;
+use holochain_zome_types::query::HighestObserved;
+use thiserror::Error;
+
+/// Why a write on the local chain head would fork the chain.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ChainHeadConflict {
+    /// Another device has written past the local chain head.
+    #[error(
+        "Another device with this agent key has written up to action {} on top of the local chain head at action {}",
+        .observed.action_seq,
+        .local.action_seq
+    )]
+    Behind {
+        /// The local chain head.
+        local: ChainHead,
+        /// The highest actions the authorities have observed.
+        observed: HighestObserved,
+    },
+    /// Another device has written a different action at the sequence of the
+    /// local chain head, so the chain has already forked there.
+    #[error(
+        "Another device with this agent key has written a different action at action {} than the local chain head {}",
+        .local.action_seq,
+        .local.hash
+    )]
+    Diverged {
+        /// The local chain head.
+        local: ChainHead,
+        /// The highest actions the authorities have observed.
+        observed: HighestObserved,
+    },
+}
+
+/// Check that the agent activity authorities have not observed any action
+/// which a write on top of `local` would fork from.
+///
+/// Authorities which haven't observed the local head yet, because it hasn't
+/// been published, agree with it.
+pub fn check_chain_head_agreement(
+    local: &ChainHead,
+    highest_observed: Option<&HighestObserved>,
+) -> Result<(), ChainHeadConflict> {
+    let observed = match highest_observed {
+        Some(observed) => observed,
+        None => return Ok(()),
+    };
+    if observed.action_seq > local.action_seq {
+        Err(ChainHeadConflict::Behind {
+            local: local.clone(),
+            observed: observed.clone(),
+        })
+    } else if observed.action_seq == local.action_seq
+        && observed.hash.iter().any(|hash| *hash != local.hash)
+    {
+        Err(ChainHeadConflict::Diverged {
+            local: local.clone(),
+            observed: observed.clone(),
+        })
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::fixt::prelude::*;
+    use holo_hash::fixt::ActionHashFixturator;
+
+    #[test]
+    fn authorities_agree_unless_another_device_wrote_on_the_head() {
+        let local = ChainHead {
+            action_seq: 5,
+            hash: fixt!(ActionHash),
+        };
+        let observed = |action_seq, hash: Vec<_>| HighestObserved { action_seq, hash };
+
+        assert_eq!(check_chain_head_agreement(&local, None), Ok(()));
+        // Our head hasn't been published yet.
+        assert_eq!(
+            check_chain_head_agreement(&local, Some(&observed(4, vec![fixt!(ActionHash)]))),
+            Ok(())
+        );
+        assert_eq!(
+            check_chain_head_agreement(&local, Some(&observed(5, vec![local.hash.clone()]))),
+            Ok(())
+        );
+
+        let ahead = observed(6, vec![fixt!(ActionHash)]);
+        assert_eq!(
+            check_chain_head_agreement(&local, Some(&ahead)),
+            Err(ChainHeadConflict::Behind {
+                local: local.clone(),
+                observed: ahead,
+            })
+        );
+        let diverged = observed(5, vec![local.hash.clone(), fixt!(ActionHash)]);
+        assert_eq!(
+            check_chain_head_agreement(&local, Some(&diverged)),
+            Err(ChainHeadConflict::Diverged {
+                local: local.clone(),
+                observed: diverged,
+            })
+        );
+    }
+}
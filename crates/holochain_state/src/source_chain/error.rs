@@ -27,6 +27,9 @@ pub enum SourceChainError {
         Option<(ActionHash, u32, Timestamp)>,
     ),
 
+    #[error(transparent)]
+    ChainHeadConflict(#[from] super::ChainHeadConflict),
+
     #[error(transparent)]
     TimestampError(#[from] holochain_zome_types::TimestampError),
 
@@ -0,0 +1,160 @@
+//! A small cache of the recent sys validation outcomes of ops, so ops which
+//! are received again, from other publishers or in later gossip rounds, can
+//! skip the checks they have already been through.
+//!
+//! Outcomes are keyed by the op hash along with the op's signature, as the
+//! hash only covers the op's action and an outcome only holds for the same
+//! action signed the same way. Neither covers the op's entry, so an outcome
+//! never says anything about whether an entry matches its action.
+
+use crate::prelude::from_blob;
+use crate::prelude::to_blob;
+use crate::prelude::StateMutationResult;
+use crate::prelude::StateQueryResult;
+use holo_hash::DhtOpHash;
+use holochain_sqlite::rusqlite::named_params;
+use holochain_sqlite::rusqlite::OptionalExtension;
+use holochain_sqlite::rusqlite::Transaction;
+use holochain_zome_types::signature::Signature;
+use holochain_zome_types::validate::SysValidationIssueReason;
+use holochain_zome_types::Timestamp;
+
+/// The most outcomes kept. The oldest are dropped by [`prune_validation_outcomes`].
+pub const VALIDATION_OUTCOME_CACHE_SIZE: usize = 10_000;
+
+/// The outcome of sys validating an op.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CachedValidationOutcome {
+    /// The op passed sys validation.
+    SysValidated,
+    /// The op was rejected for this reason.
+    Rejected(SysValidationIssueReason),
+}
+
+/// Record the outcome of sys validating an op, replacing any earlier outcome.
+pub fn record_validation_outcome(
+    txn: &mut Transaction,
+    op_hash: &DhtOpHash,
+    signature: &Signature,
+    outcome: &CachedValidationOutcome,
+) -> StateMutationResult<()> {
+    let reason = match outcome {
+        CachedValidationOutcome::SysValidated => None,
+        CachedValidationOutcome::Rejected(reason) => Some(to_blob(reason)?),
+    };
+    txn.execute(
+        "
+        INSERT INTO ValidationOutcomeCache (op_hash, signature, rejected_reason, when_recorded)
+        VALUES (:op_hash, :signature, :rejected_reason, :when_recorded)
+        ",
+        named_params! {
+            ":op_hash": op_hash,
+            ":signature": &signature.0[..],
+            ":rejected_reason": reason,
+            ":when_recorded": Timestamp::now(),
+        },
+    )?;
+    Ok(())
+}
+
+/// Drop all but the [`VALIDATION_OUTCOME_CACHE_SIZE`] most recent outcomes.
+pub fn prune_validation_outcomes(txn: &mut Transaction) -> StateMutationResult<usize> {
+    Ok(txn.execute(
+        "
+        DELETE FROM ValidationOutcomeCache
+        WHERE rowid IN (
+            SELECT rowid FROM ValidationOutcomeCache
+            ORDER BY when_recorded DESC
+            LIMIT -1 OFFSET :size
+        )
+        ",
+        named_params! {
+            ":size": VALIDATION_OUTCOME_CACHE_SIZE as i64,
+        },
+    )?)
+}
+
+/// Get the recorded outcome of sys validating an op with this signature.
+pub fn get_validation_outcome(
+    txn: &Transaction,
+    op_hash: &DhtOpHash,
+    signature: &Signature,
+) -> StateQueryResult<Option<CachedValidationOutcome>> {
+    let reason: Option<Option<Vec<u8>>> = txn
+        .query_row(
+            "
+            SELECT rejected_reason FROM ValidationOutcomeCache
+            WHERE op_hash = :op_hash AND signature = :signature
+            ",
+            named_params! {
+                ":op_hash": op_hash,
+                ":signature": &signature.0[..],
+            },
+            |row| row.get(0),
+        )
+        .optional()?;
+    reason
+        .map(|reason| match reason {
+            None => Ok(CachedValidationOutcome::SysValidated),
+            Some(reason) => Ok(CachedValidationOutcome::Rejected(from_blob(reason)?)),
+        })
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::test_dht_db;
+    use ::fixt::prelude::*;
+    use holo_hash::fixt::DhtOpHashFixturator;
+
+    #[test]
+    fn outcomes_are_kept_per_signature() {
+        let db = test_dht_db();
+        let op_hash = fixt!(DhtOpHash);
+        let signature = Signature([1; 64]);
+        let rejected = CachedValidationOutcome::Rejected(SysValidationIssueReason::Counterfeit);
+        db.test_commit(|txn| {
+            assert_eq!(
+                get_validation_outcome(txn, &op_hash, &signature).unwrap(),
+                None
+            );
+
+            record_validation_outcome(txn, &op_hash, &signature, &rejected).unwrap();
+            assert_eq!(
+                get_validation_outcome(txn, &op_hash, &signature).unwrap(),
+                Some(rejected.clone())
+            );
+            // The outcome doesn't hold for another signature.
+            assert_eq!(
+                get_validation_outcome(txn, &op_hash, &Signature([2; 64])).unwrap(),
+                None
+            );
+
+            let validated = CachedValidationOutcome::SysValidated;
+            record_validation_outcome(txn, &op_hash, &signature, &validated).unwrap();
+            assert_eq!(
+                get_validation_outcome(txn, &op_hash, &signature).unwrap(),
+                Some(validated)
+            );
+        });
+    }
+
+    #[test]
+    fn only_recent_outcomes_are_kept() {
+        let db = test_dht_db();
+        let signature = Signature([1; 64]);
+        db.test_commit(|txn| {
+            for _ in 0..VALIDATION_OUTCOME_CACHE_SIZE + 10 {
+                record_validation_outcome(
+                    txn,
+                    &fixt!(DhtOpHash),
+                    &signature,
+                    &CachedValidationOutcome::SysValidated,
+                )
+                .unwrap();
+            }
+            assert_eq!(prune_validation_outcomes(txn).unwrap(), 10);
+        });
+    }
+}
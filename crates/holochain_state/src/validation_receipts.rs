@@ -13,6 +13,7 @@ use holochain_sqlite::rusqlite::named_params;
 use holochain_sqlite::rusqlite::OptionalExtension;
 use holochain_sqlite::rusqlite::Transaction;
 use holochain_zome_types::signature::Signature;
+use holochain_zome_types::validate::SysValidationIssueReason;
 use holochain_zome_types::Timestamp;
 use holochain_zome_types::ValidationStatus;
 use mutations::StateMutationResult;
@@ -46,6 +47,10 @@ pub struct ValidationReceipt {
 
     /// Time when the op was integrated
     pub when_integrated: Timestamp,
+
+    /// why sys validation rejected the op, if it did.
+    #[serde(default)]
+    pub rejected_reason: Option<SysValidationIssueReason>,
 }
 
 impl ValidationReceipt {
@@ -171,6 +176,7 @@ mod tests {
             validation_status: ValidationStatus::Valid,
             validators: vec![agent],
             when_integrated: Timestamp::now(),
+            rejected_reason: None,
         };
         receipt.sign(keystore).await.unwrap().unwrap()
     }
@@ -2,6 +2,7 @@
 
 use holo_hash::ActionHash;
 use holo_hash::AgentPubKey;
+use holochain_keystore::AgentPubKeyExt;
 use holochain_serialized_bytes::prelude::*;
 use holochain_zome_types::prelude::*;
 
@@ -20,6 +21,10 @@ pub struct AgentActivityResponse<T = SignedActionHashed> {
     /// The highest chain action that has
     /// been observed by this authority.
     pub highest_observed: Option<HighestObserved>,
+    /// Warrants against the agent for what this authority has seen of
+    /// their chain, such as a fork.
+    #[serde(default)]
+    pub warrants: Vec<Warrant>,
 }
 
 holochain_serial!(AgentActivityResponse<ActionHash>);
@@ -38,6 +43,7 @@ impl<A> AgentActivityResponse<A> {
             rejected_activity: convert_activity(&other.rejected_activity),
             status: ChainStatus::Empty,
             highest_observed: other.highest_observed,
+            warrants: other.warrants,
         }
     }
 
@@ -49,6 +55,7 @@ impl<A> AgentActivityResponse<A> {
             rejected_activity: ChainItems::NotRequested,
             status: ChainStatus::Empty,
             highest_observed: other.highest_observed,
+            warrants: other.warrants,
         }
     }
 
@@ -65,6 +72,7 @@ impl<A> AgentActivityResponse<A> {
             rejected_activity: convert_activity(other.rejected_activity),
             status: other.status,
             highest_observed: other.highest_observed,
+            warrants: other.warrants,
         }
     }
 }
@@ -103,7 +111,35 @@ impl From<AgentActivityResponse<Record>> for holochain_zome_types::query::AgentA
             rejected_activity,
             status: a.status,
             highest_observed: a.highest_observed,
-            warrants: Vec::with_capacity(0),
+            warrants: a.warrants,
+        }
+    }
+}
+
+/// Extension trait to keep zome types minimal
+#[async_trait::async_trait]
+pub trait WarrantExt {
+    /// Check that the proof of the warrant shows what it claims,
+    /// and was signed by the agent the warrant is against.
+    async fn verify(&self) -> bool;
+}
+
+#[async_trait::async_trait]
+impl WarrantExt for Warrant {
+    async fn verify(&self) -> bool {
+        match &self.proof {
+            WarrantProof::ChainFork { first, second } => {
+                let forked = Warrant::chain_fork(first.clone(), second.clone());
+                if forked.map_or(true, |w| w.author != self.author) {
+                    return false;
+                }
+                for SignedAction(action, signature) in [first, second] {
+                    if !self.author.verify_signature(signature, action).await {
+                        return false;
+                    }
+                }
+                true
+            }
         }
     }
 }
@@ -11,6 +11,8 @@ mod app_bundle;
 mod app_manifest;
 mod dna_gamut;
 pub mod error;
+mod quota;
+mod zome_fn_access;
 use crate::{dna::DnaBundle, prelude::CoordinatorBundle};
 pub use app_bundle::*;
 pub use app_manifest::app_manifest_validated::*;
@@ -22,10 +24,12 @@ use holochain_serialized_bytes::prelude::*;
 use holochain_util::ffs;
 use holochain_zome_types::prelude::*;
 use itertools::Itertools;
+pub use quota::*;
 use std::{
     collections::{HashMap, HashSet},
     path::PathBuf,
 };
+pub use zome_fn_access::*;
 
 use self::error::{AppError, AppResult};
 
@@ -76,12 +80,23 @@ pub struct UpdateCoordinatorsPayload {
     pub source: CoordinatorSource,
 }
 
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+/// The instructions on how to update the runtime properties of a dna.
+pub struct UpdateRuntimePropertiesPayload {
+    /// The hash of the dna to update.
+    pub dna_hash: DnaHash,
+    /// The properties to replace the current ones with.
+    pub properties: YamlProperties,
+}
+
 /// The instructions on how to get the DNA to be registered
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct CreateCloneCellPayload {
     /// Properties to override when installing this DNA
     pub properties: Option<YamlProperties>,
-    /// The DNA to clone
+    /// The DNA to clone, or a clone of the role's DNA which another agent
+    /// created, to join it. A clone which the conductor doesn't have is
+    /// fetched from its peers.
     pub dna_hash: DnaHash,
     /// The Agent key with which to create this Cell
     /// (TODO: should this be derived from the App?)
@@ -139,6 +154,42 @@ pub struct InstallAppBundlePayload {
     pub network_seed: Option<NetworkSeed>,
 }
 
+/// An [AppBundle] to install once for each of several agents, as when one
+/// conductor hosts the same app for many users.
+///
+/// Each agent gets their own installed app, with the ID given by
+/// [`agent_app_id`], and their own cells in the app's DNAs.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct InstallAppBundleForAgentsPayload {
+    /// The location of the bundle.
+    #[serde(flatten)]
+    pub source: AppBundleSource,
+
+    /// The agents to install the app for.
+    pub agent_keys: Vec<AgentPubKey>,
+
+    /// The ID which the installed app of each agent is named after.
+    /// If not specified, it will be derived from the app name in the bundle manifest.
+    pub installed_app_id: Option<InstalledAppId>,
+
+    /// Include proof-of-membrane-membership data for the cells of each agent
+    /// that require it, keyed by agent and then by the AppRoleId specified in
+    /// the app bundle manifest.
+    #[serde(default)]
+    pub membrane_proofs: HashMap<AgentPubKey, HashMap<AppRoleId, MembraneProof>>,
+
+    /// Optional: overwrites all network seeds for all DNAs of Cells created by this app.
+    /// The cells of all the agents share the same network seed.
+    pub network_seed: Option<NetworkSeed>,
+}
+
+/// The ID of the app installed for an agent with an
+/// [`InstallAppBundleForAgentsPayload`], which keeps the apps of
+/// different agents apart.
+pub fn agent_app_id(installed_app_id: &str, agent_key: &AgentPubKey) -> InstalledAppId {
+    format!("{}::{}", installed_app_id, agent_key)
+}
+
 /// The possible locations of an AppBundle
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -395,12 +446,14 @@ impl From<StoppedApp> for InstalledApp {
 pub struct InstalledAppCommon {
     /// The unique identifier for an installed app in this conductor
     installed_app_id: InstalledAppId,
-    /// The agent key used to install this app. Currently this is meaningless,
-    /// but I'm leaving it here as a placeholder in case we ever want it to
-    /// have formal significance.
+    /// The agent key used to install this app, which is the agent of all
+    /// of its cells unless it uses existing cells of other agents.
     _agent_key: AgentPubKey,
     /// The assignments of the roles as specified in the AppManifest
     role_assignments: HashMap<AppRoleId, AppRoleAssignment>,
+    /// The resources the app may use
+    #[serde(default)]
+    quota: AppQuota,
 }
 
 impl InstalledAppCommon {
@@ -414,6 +467,7 @@ impl InstalledAppCommon {
             installed_app_id: installed_app_id.to_string(),
             _agent_key,
             role_assignments: role_assignments.into_iter().collect(),
+            quota: AppQuota::default(),
         }
     }
 
@@ -436,6 +490,14 @@ impl InstalledAppCommon {
             .filter_map(|(role_id, role)| role.into_provisioned_cell().map(|c| (role_id, c)))
     }
 
+    /// Accessor for the cells of deferred roles which are not provisioned yet
+    pub fn deferred_cells(&self) -> impl Iterator<Item = (&AppRoleId, &CellId)> {
+        self.role_assignments
+            .iter()
+            .filter(|(_, role)| !role.is_provisioned)
+            .map(|(role_id, role)| (role_id, role.cell_id()))
+    }
+
     /// Accessor
     pub fn cloned_cells(&self) -> impl Iterator<Item = &CellId> {
         self.role_assignments
@@ -490,12 +552,31 @@ impl InstalledAppCommon {
         Ok(())
     }
 
+    /// Record that the deferred base cell of a role has been provisioned,
+    /// returning its ID. Its membrane proof is no longer needed.
+    pub fn provision_deferred_cell(&mut self, role_id: &AppRoleId) -> AppResult<CellId> {
+        let role = self.role_mut(role_id)?;
+        role.is_provisioned = true;
+        role.membrane_proof = None;
+        Ok(role.base_cell_id.clone())
+    }
+
     /// Remove a cloned cell
     pub fn remove_clone(&mut self, role_id: &AppRoleId, cell_id: &CellId) -> AppResult<bool> {
         let role = self.role_mut(role_id)?;
         Ok(role.clones.remove(cell_id))
     }
 
+    /// Accessor
+    pub fn quota(&self) -> &AppQuota {
+        &self.quota
+    }
+
+    /// Replace the quota of the app
+    pub fn set_quota(&mut self, quota: AppQuota) {
+        self.quota = quota;
+    }
+
     /// Accessor
     pub fn _agent_key(&self) -> &AgentPubKey {
         &self._agent_key
@@ -550,6 +631,8 @@ impl InstalledAppCommon {
                     is_provisioned: true,
                     clones: HashSet::new(),
                     clone_limit: 0,
+                    membrane_proof: None,
+                    zome_fn_access: ZomeFnAccessRules::default(),
                 };
                 (role_id, role)
             })
@@ -558,6 +641,7 @@ impl InstalledAppCommon {
             installed_app_id,
             _agent_key,
             role_assignments: roles,
+            quota: AppQuota::default(),
         })
     }
 }
@@ -779,6 +863,13 @@ pub struct AppRoleAssignment {
     /// Cells which were cloned at runtime. The length cannot grow beyond
     /// `clone_limit`
     clones: HashSet<CellId>,
+    /// The membrane proof for a deferred base cell, kept until the cell is
+    /// provisioned and genesis is run.
+    #[serde(default)]
+    membrane_proof: Option<MembraneProof>,
+    /// Where the zome functions of the role's cells may be called from.
+    #[serde(default)]
+    zome_fn_access: ZomeFnAccessRules,
 }
 
 impl AppRoleAssignment {
@@ -789,6 +880,21 @@ impl AppRoleAssignment {
             is_provisioned,
             clone_limit,
             clones: HashSet::new(),
+            membrane_proof: None,
+            zome_fn_access: ZomeFnAccessRules::default(),
+        }
+    }
+
+    /// Constructor for a role whose base cell is deferred, so it is only
+    /// provisioned when it is first used.
+    pub fn new_deferred(
+        base_cell_id: CellId,
+        clone_limit: u32,
+        membrane_proof: Option<MembraneProof>,
+    ) -> Self {
+        Self {
+            membrane_proof,
+            ..Self::new(base_cell_id, false, clone_limit)
         }
     }
 
@@ -816,6 +922,44 @@ impl AppRoleAssignment {
         }
     }
 
+    /// Accessor
+    pub fn is_provisioned(&self) -> bool {
+        self.is_provisioned
+    }
+
+    /// Accessor
+    pub fn clone_limit(&self) -> u32 {
+        self.clone_limit
+    }
+
+    /// Accessor
+    pub fn clones(&self) -> &HashSet<CellId> {
+        &self.clones
+    }
+
+    /// Accessor
+    pub fn membrane_proof(&self) -> Option<&MembraneProof> {
+        self.membrane_proof.as_ref()
+    }
+
+    /// Accessor
+    pub fn zome_fn_access(&self) -> &ZomeFnAccessRules {
+        &self.zome_fn_access
+    }
+
+    /// Set where the zome functions of the role's cells may be called from.
+    pub fn with_zome_fn_access(self, zome_fn_access: ZomeFnAccessRules) -> Self {
+        Self {
+            zome_fn_access,
+            ..self
+        }
+    }
+
+    /// The base cell, whether it is provisioned or not, and the clones.
+    pub fn cells(&self) -> impl Iterator<Item = &CellId> {
+        std::iter::once(&self.base_cell_id).chain(&self.clones)
+    }
+
     /// Transformer
     pub fn into_provisioned_cell(self) -> Option<CellId> {
         if self.is_provisioned {
@@ -45,39 +45,87 @@ impl AppBundle {
         self.0
     }
 
+    /// The DNA version specs of the roles which use a DNA the conductor
+    /// already has, each with whether the role needs it rather than falling
+    /// back to creating its cell from the bundle.
+    pub fn existing_dna_versions(&self) -> AppBundleResult<Vec<(DnaVersionSpec, bool)>> {
+        Ok(self
+            .manifest()
+            .clone()
+            .validate()?
+            .roles
+            .into_values()
+            .filter_map(|role| match role {
+                AppRoleManifestValidated::UseExisting { version, .. } => Some((version, true)),
+                AppRoleManifestValidated::CreateIfNotExists { version, .. } => {
+                    Some((version, false))
+                }
+                _ => None,
+            })
+            .collect())
+    }
+
     /// Given a DnaGamut, decide which of the available DNAs or Cells should be
-    /// used for each cell in this app.
+    /// used for each cell in this app. Roles which use an existing DNA
+    /// can use the cells in the gamut, or the DNAs in `available_dnas`.
     pub async fn resolve_cells(
         self,
         agent: AgentPubKey,
-        _gamut: DnaGamut,
+        gamut: DnaGamut,
+        available_dnas: HashMap<DnaHash, DnaFile>,
         membrane_proofs: HashMap<AppRoleId, MembraneProof>,
     ) -> AppBundleResult<AppRoleResolution> {
-        let AppManifestValidated { name: _, roles } = self.manifest().clone().validate()?;
+        let AppManifestValidated {
+            name: _,
+            roles,
+            mut zome_fn_access,
+        } = self.manifest().clone().validate()?;
         let bundle = Arc::new(self);
-        let tasks = roles.into_iter().map(|(role_id, role)| async {
+        let existing = Arc::new((gamut, available_dnas));
+        let tasks = roles.into_iter().map(|(role_id, role)| {
             let bundle = bundle.clone();
-            Ok((role_id, bundle.resolve_cell(role).await?))
+            let existing = existing.clone();
+            let agent = agent.clone();
+            async move {
+                let deferred = role.is_deferred();
+                let (gamut, available_dnas) = &*existing;
+                let op = bundle
+                    .resolve_cell(role, gamut, available_dnas, &agent)
+                    .await?;
+                Ok((role_id, deferred, op))
+            }
         });
-        let resolution = futures::future::join_all(tasks)
+        let mut resolution = futures::future::join_all(tasks)
             .await
             .into_iter()
             .collect::<AppBundleResult<Vec<_>>>()?
             .into_iter()
             .fold(
                 Ok(AppRoleResolution::new(agent.clone())),
-                |acc: AppBundleResult<AppRoleResolution>, (role_id, op)| {
+                |acc: AppBundleResult<AppRoleResolution>, (role_id, deferred, op)| {
                     if let Ok(mut resolution) = acc {
                         match op {
                             CellProvisioningOp::Create(dna, clone_limit) => {
                                 let agent = resolution.agent.clone();
                                 let dna_hash = dna.dna_hash().clone();
                                 let cell_id = CellId::new(dna_hash, agent);
-                                let role = AppRoleAssignment::new(cell_id, true, clone_limit);
                                 // TODO: could sequentialize this to remove the clone
                                 let proof = membrane_proofs.get(&role_id).cloned();
-                                resolution.dnas_to_register.push((dna, proof));
-                                resolution.role_assignments.push((role_id, role));
+                                if deferred {
+                                    // The DNA is registered now, but the cell is only
+                                    // created when it is first used.
+                                    let role = AppRoleAssignment::new_deferred(
+                                        cell_id,
+                                        clone_limit,
+                                        proof,
+                                    );
+                                    resolution.dnas_to_register.push((dna, None));
+                                    resolution.role_assignments.push((role_id, role));
+                                } else {
+                                    let role = AppRoleAssignment::new(cell_id, true, clone_limit);
+                                    resolution.dnas_to_register.push((dna, proof));
+                                    resolution.role_assignments.push((role_id, role));
+                                }
                             }
                             CellProvisioningOp::Existing(cell_id, clone_limit) => {
                                 let role = AppRoleAssignment::new(cell_id, true, clone_limit);
@@ -89,6 +137,9 @@ impl AppBundle {
                                     AppRoleAssignment::new(cell_id, false, clone_limit),
                                 ));
                             }
+                            CellProvisioningOp::NoMatch => {
+                                return Err(AppBundleError::CellResolutionFailure(role_id));
+                            }
                             other => {
                                 tracing::error!(
                                     "Encountered unexpected CellProvisioningOp: {:?}",
@@ -104,13 +155,25 @@ impl AppBundle {
                 },
             )?;
 
-        // let resolution = cells.into_iter();
+        // The access rules are kept with the roles they were set for.
+        resolution.role_assignments = resolution
+            .role_assignments
+            .into_iter()
+            .map(|(role_id, role)| match zome_fn_access.remove(&role_id) {
+                Some(rules) => (role_id, role.with_zome_fn_access(rules)),
+                None => (role_id, role),
+            })
+            .collect();
+
         Ok(resolution)
     }
 
     async fn resolve_cell(
         &self,
         role: AppRoleManifestValidated,
+        gamut: &DnaGamut,
+        available_dnas: &HashMap<DnaHash, DnaFile>,
+        agent: &AgentPubKey,
     ) -> AppBundleResult<CellProvisioningOp> {
         Ok(match role {
             AppRoleManifestValidated::Create {
@@ -138,7 +201,7 @@ impl AppBundle {
                 version,
                 clone_limit,
                 deferred: _,
-            } => self.resolve_cell_existing(&version, clone_limit),
+            } => self.resolve_cell_existing(&version, clone_limit, gamut, available_dnas, agent),
             AppRoleManifestValidated::CreateIfNotExists {
                 location,
                 version,
@@ -146,7 +209,13 @@ impl AppBundle {
                 properties,
                 network_seed,
                 deferred: _,
-            } => match self.resolve_cell_existing(&version, clone_limit) {
+            } => match self.resolve_cell_existing(
+                &version,
+                clone_limit,
+                gamut,
+                available_dnas,
+                agent,
+            ) {
                 op @ CellProvisioningOp::Existing(_, _) => op,
                 CellProvisioningOp::NoMatch => {
                     self.resolve_cell_create(
@@ -161,9 +230,7 @@ impl AppBundle {
                 CellProvisioningOp::Conflict(_) => {
                     unimplemented!("conflicts are not handled, or even possible yet")
                 }
-                CellProvisioningOp::Create(_, _) => {
-                    unreachable!("resolve_cell_existing will never return a Create op")
-                }
+                op @ CellProvisioningOp::Create(_, _) => op,
                 CellProvisioningOp::Noop(_, _) => {
                     unreachable!("resolve_cell_existing will never return a Noop")
                 }
@@ -198,12 +265,25 @@ impl AppBundle {
         Ok(CellProvisioningOp::Create(dna_file, clone_limit))
     }
 
+    /// Use a cell in the gamut which matches the version spec, or else
+    /// create one from a matching DNA which the conductor already has.
     fn resolve_cell_existing(
         &self,
-        _version: &DnaVersionSpec,
-        _clone_limit: u32,
+        version: &DnaVersionSpec,
+        clone_limit: u32,
+        gamut: &DnaGamut,
+        available_dnas: &HashMap<DnaHash, DnaFile>,
+        agent: &AgentPubKey,
     ) -> CellProvisioningOp {
-        unimplemented!("Reusing existing cells is not yet implemented")
+        if let CellResolution::Match(cell_id, _) = gamut.resolve_cell(version.clone(), agent) {
+            return CellProvisioningOp::Existing(cell_id, clone_limit);
+        }
+        version
+            .dna_hashes()
+            .into_iter()
+            .find_map(|hash| available_dnas.get(hash.as_ref()))
+            .map(|dna| CellProvisioningOp::Create(dna.clone(), clone_limit))
+            .unwrap_or(CellProvisioningOp::NoMatch)
     }
 }
 
@@ -237,7 +317,8 @@ impl AppRoleResolution {
     }
 
     /// Return the IDs of new cells to be created as part of the resolution.
-    /// Does not return existing cells to be reused.
+    /// Does not return existing cells to be reused, or deferred cells which
+    /// are created when they are first used.
     pub fn cells_to_create(&self) -> Vec<(CellId, Option<MembraneProof>)> {
         self.dnas_to_register
             .iter()
@@ -247,6 +328,11 @@ impl AppRoleResolution {
                     proof.clone(),
                 )
             })
+            .filter(|(cell_id, _)| {
+                self.role_assignments
+                    .iter()
+                    .any(|(_, role)| role.provisioned_cell() == Some(cell_id))
+            })
             .collect()
     }
 }
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::path::PathBuf;
 
 use crate::prelude::*;
@@ -54,7 +55,12 @@ async fn provisioning_1_create() {
     let cell_id = CellId::new(dna.dna_hash().to_owned(), agent.clone());
 
     let resolution = bundle
-        .resolve_cells(agent.clone(), DnaGamut::placeholder(), Default::default())
+        .resolve_cells(
+            agent.clone(),
+            DnaGamut::placeholder(),
+            Default::default(),
+            Default::default(),
+        )
         .await
         .unwrap();
 
@@ -69,3 +75,101 @@ async fn provisioning_1_create() {
     };
     assert_eq!(resolution, expected);
 }
+
+/// Test that a deferred cell's DNA is registered but the cell isn't created
+#[tokio::test]
+async fn provisioning_1_create_deferred() {
+    let agent = fixt!(AgentPubKey);
+    let (bundle, _) = app_bundle_fixture().await;
+    let mut manifest = bundle.manifest().clone();
+    let AppManifest::V1(m) = &mut manifest;
+    m.roles[0].provisioning = Some(CellProvisioning::Create { deferred: true });
+    let bundle = AppBundle::from(bundle.into_inner().update_manifest(manifest).unwrap());
+    let proof = MembraneProof::new(SerializedBytes::from(UnsafeBytes::from(vec![1])));
+    let proofs = vec![("role_id".into(), proof.clone())]
+        .into_iter()
+        .collect();
+
+    let resolution = bundle
+        .resolve_cells(agent, DnaGamut::placeholder(), Default::default(), proofs)
+        .await
+        .unwrap();
+
+    assert_eq!(resolution.dnas_to_register.len(), 1);
+    assert!(resolution.cells_to_create().is_empty());
+    let (_, role) = &resolution.role_assignments[0];
+    assert_eq!(role.provisioned_cell(), None);
+    assert_eq!(role.membrane_proof(), Some(&proof));
+}
+
+fn use_existing(bundle: AppBundle) -> AppBundle {
+    let mut manifest = bundle.manifest().clone();
+    let AppManifest::V1(m) = &mut manifest;
+    m.roles[0].provisioning = Some(CellProvisioning::UseExisting { deferred: false });
+    AppBundle::from(bundle.into_inner().update_manifest(manifest).unwrap())
+}
+
+/// Test that a role using an existing DNA creates a cell of it if there's
+/// no cell of it yet, and uses the cell if there is
+#[tokio::test]
+async fn provisioning_use_existing() {
+    let agent = fixt!(AgentPubKey);
+    let (bundle, dna) = app_bundle_fixture().await;
+    let bundle = use_existing(bundle);
+    let cell_id = CellId::new(dna.dna_hash().to_owned(), agent.clone());
+    let available_dnas: HashMap<_, _> = vec![(dna.dna_hash().clone(), dna.clone())]
+        .into_iter()
+        .collect();
+
+    let resolution = AppBundle::decode(&bundle.encode().unwrap())
+        .unwrap()
+        .resolve_cells(
+            agent.clone(),
+            DnaGamut::placeholder(),
+            available_dnas,
+            Default::default(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(resolution.dnas_to_register, vec![(dna, None)]);
+    assert_eq!(
+        resolution.role_assignments,
+        vec![(
+            "role_id".into(),
+            AppRoleAssignment::new(cell_id.clone(), true, 50)
+        )]
+    );
+
+    let resolution = bundle
+        .resolve_cells(
+            agent,
+            DnaGamut::new(vec![cell_id.clone()]),
+            Default::default(),
+            Default::default(),
+        )
+        .await
+        .unwrap();
+    assert!(resolution.dnas_to_register.is_empty());
+    assert_eq!(
+        resolution.role_assignments,
+        vec![("role_id".into(), AppRoleAssignment::new(cell_id, true, 50))]
+    );
+}
+
+/// Test that a role using an existing DNA fails to resolve without it
+#[tokio::test]
+async fn provisioning_use_existing_missing() {
+    let (bundle, _) = app_bundle_fixture().await;
+    let result = use_existing(bundle)
+        .resolve_cells(
+            fixt!(AgentPubKey),
+            DnaGamut::placeholder(),
+            Default::default(),
+            Default::default(),
+        )
+        .await;
+    assert!(matches!(
+        result,
+        Err(AppBundleError::CellResolutionFailure(role_id)) if role_id == "role_id"
+    ));
+}
@@ -7,7 +7,7 @@ use super::{
     app_manifest_validated::{AppManifestValidated, AppRoleManifestValidated},
     error::{AppManifestError, AppManifestResult},
 };
-use crate::prelude::{AppRoleId, YamlProperties};
+use crate::prelude::{AppRoleId, YamlProperties, ZomeFnAccessRules};
 use holo_hash::{DnaHash, DnaHashB64};
 use holochain_zome_types::NetworkSeed;
 use std::collections::HashMap;
@@ -48,6 +48,12 @@ pub struct AppRoleManifest {
     /// Declares where to find the DNA, and options to modify it before
     /// inclusion in a Cell
     pub dna: AppRoleDnaManifest,
+
+    /// Where the zome functions of the role's cells may be called from,
+    /// by zome name and then function name. Functions which aren't listed
+    /// may be called from anywhere.
+    #[serde(default, skip_serializing_if = "ZomeFnAccessRules::is_empty")]
+    pub zome_fn_access: ZomeFnAccessRules,
 }
 
 impl AppRoleManifest {
@@ -57,6 +63,7 @@ impl AppRoleManifest {
             id,
             provisioning: Some(CellProvisioning::default()),
             dna: AppRoleDnaManifest::sample(),
+            zome_fn_access: ZomeFnAccessRules::default(),
         }
     }
 }
@@ -173,7 +180,9 @@ pub enum CellProvisioning {
     CreateClone { deferred: bool },
     /// Require that a Cell is already installed which matches the DNA version
     /// spec, and which has an Agent that's associated with this App's agent
-    /// via DPKI. If no such Cell exists, *app installation fails*.
+    /// via DPKI. If no such Cell exists, a Cell is created from a DNA which
+    /// matches the spec, fetched from the peers of that DNA if the conductor
+    /// doesn't have it. If there's no such DNA, *app installation fails*.
     UseExisting { deferred: bool },
     /// Try `UseExisting`, and if that fails, fallback to `Create`
     CreateIfNotExists { deferred: bool },
@@ -211,6 +220,7 @@ impl AppManifestV1 {
             roles,
             description: _,
         } = self;
+        let mut zome_fn_access = HashMap::new();
         let roles = roles
             .into_iter()
             .map(
@@ -218,7 +228,11 @@ impl AppManifestV1 {
                      id,
                      provisioning,
                      dna,
+                     zome_fn_access: role_zome_fn_access,
                  }| {
+                    if !role_zome_fn_access.is_empty() {
+                        zome_fn_access.insert(id.clone(), role_zome_fn_access);
+                    }
                     let AppRoleDnaManifest {
                         location,
                         properties,
@@ -232,7 +246,7 @@ impl AppManifestV1 {
                         CellProvisioning::Create { deferred } => AppRoleManifestValidated::Create {
                             deferred,
                             clone_limit,
-                            location: Self::require(location, "roles.dna.(path|url)")?,
+                            location: Self::require(location, &id, "roles.dna.(path|url)")?,
                             properties,
                             network_seed,
                             version,
@@ -241,7 +255,7 @@ impl AppManifestV1 {
                             AppRoleManifestValidated::CreateClone {
                                 deferred,
                                 clone_limit,
-                                location: Self::require(location, "roles.dna.(path|url)")?,
+                                location: Self::require(location, &id, "roles.dna.(path|url)")?,
                                 properties,
                                 version,
                             }
@@ -250,33 +264,33 @@ impl AppManifestV1 {
                             AppRoleManifestValidated::UseExisting {
                                 deferred,
                                 clone_limit,
-                                version: Self::require(version, "roles.dna.version")?,
+                                version: Self::require(version, &id, "roles.dna.version")?,
                             }
                         }
                         CellProvisioning::CreateIfNotExists { deferred } => {
                             AppRoleManifestValidated::CreateIfNotExists {
                                 deferred,
                                 clone_limit,
-                                location: Self::require(location, "roles.dna.(path|url)")?,
-                                version: Self::require(version, "roles.dna.version")?,
+                                location: Self::require(location, &id, "roles.dna.(path|url)")?,
+                                version: Self::require(version, &id, "roles.dna.version")?,
                                 properties,
                                 network_seed,
                             }
                         }
                         CellProvisioning::Disabled => AppRoleManifestValidated::Disabled {
                             clone_limit,
-                            version: Self::require(version, "roles.dna.version")?,
+                            version: Self::require(version, &id, "roles.dna.version")?,
                         },
                     };
                     Ok((id, validated))
                 },
             )
             .collect::<Result<HashMap<_, _>, _>>()?;
-        AppManifestValidated::new(name, roles)
+        AppManifestValidated::new(name, roles, zome_fn_access)
     }
 
-    fn require<T>(maybe: Option<T>, context: &str) -> AppManifestResult<T> {
-        maybe.ok_or_else(|| AppManifestError::MissingField(context.to_owned()))
+    fn require<T>(maybe: Option<T>, role_id: &AppRoleId, context: &str) -> AppManifestResult<T> {
+        maybe.ok_or_else(|| AppManifestError::MissingField(role_id.clone(), context.to_owned()))
     }
 }
 
@@ -329,6 +343,7 @@ pub mod tests {
                 clone_limit: 50,
             },
             provisioning: Some(CellProvisioning::Create { deferred: false }),
+            zome_fn_access: ZomeFnAccessRules::default(),
         }];
         let manifest = AppManifest::V1(AppManifestV1 {
             name: "Test app".to_string(),
@@ -7,7 +7,7 @@
 
 use super::error::{AppManifestError, AppManifestResult};
 use crate::app::app_manifest::current::{DnaLocation, DnaVersionSpec};
-use crate::prelude::{AppRoleId, YamlProperties};
+use crate::prelude::{AppRoleId, YamlProperties, ZomeFnAccessRules};
 use std::collections::HashMap;
 
 /// Normalized, validated representation of the App Manifest.
@@ -18,6 +18,9 @@ pub struct AppManifestValidated {
 
     /// The role descriptions that make up this app.
     pub(in crate::app) roles: HashMap<AppRoleId, AppRoleManifestValidated>,
+
+    /// The zome function access rules of the roles which have any.
+    pub(in crate::app) zome_fn_access: HashMap<AppRoleId, ZomeFnAccessRules>,
 }
 
 impl AppManifestValidated {
@@ -28,6 +31,7 @@ impl AppManifestValidated {
     pub(in crate::app) fn new(
         name: String,
         roles: HashMap<AppRoleId, AppRoleManifestValidated>,
+        zome_fn_access: HashMap<AppRoleId, ZomeFnAccessRules>,
     ) -> AppManifestResult<Self> {
         for (role_id, role) in roles.iter() {
             if let AppRoleManifestValidated::Disabled { clone_limit, .. } = role {
@@ -38,7 +42,11 @@ impl AppManifestValidated {
                 }
             }
         }
-        Ok(AppManifestValidated { name, roles })
+        Ok(AppManifestValidated {
+            name,
+            roles,
+            zome_fn_access,
+        })
     }
 }
 
@@ -66,7 +74,9 @@ pub enum AppRoleManifestValidated {
     },
     /// Require that a Cell is already installed which matches the DNA version
     /// spec, and which has an Agent that's associated with this App's agent
-    /// via DPKI. If no such Cell exists, *app installation fails*.
+    /// via DPKI. If no such Cell exists, a Cell is created from a DNA which
+    /// matches the spec, fetched from the peers of that DNA if the conductor
+    /// doesn't have it. If there's no such DNA, *app installation fails*.
     UseExisting {
         clone_limit: u32,
         deferred: bool,
@@ -88,3 +98,17 @@ pub enum AppRoleManifestValidated {
         clone_limit: u32,
     },
 }
+
+impl AppRoleManifestValidated {
+    /// Whether the cell for this role is only created when it is first used,
+    /// rather than when the app is installed.
+    pub fn is_deferred(&self) -> bool {
+        match self {
+            Self::Create { deferred, .. }
+            | Self::CreateClone { deferred, .. }
+            | Self::UseExisting { deferred, .. }
+            | Self::CreateIfNotExists { deferred, .. } => *deferred,
+            Self::Disabled { .. } => false,
+        }
+    }
+}
@@ -5,11 +5,21 @@ use crate::prelude::AppRoleId;
 #[allow(missing_docs)]
 #[derive(Debug, Error)]
 pub enum AppManifestError {
-    #[error("Missing required field in app manifest: {0}")]
-    MissingField(String),
+    #[error("Missing required field in app manifest for app role '{0}': {1}")]
+    MissingField(AppRoleId, String),
 
     #[error("Invalid manifest for app role '{0}': Using strategy 'disabled' with clone_limit == 0 is pointless")]
     InvalidStrategyDisabled(AppRoleId),
 }
 
+impl AppManifestError {
+    /// The app role whose part of the manifest is invalid.
+    pub fn role_id(&self) -> &AppRoleId {
+        match self {
+            AppManifestError::MissingField(role_id, _)
+            | AppManifestError::InvalidStrategyDisabled(role_id) => role_id,
+        }
+    }
+}
+
 pub type AppManifestResult<T> = Result<T, AppManifestError>;
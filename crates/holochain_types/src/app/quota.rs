@@ -0,0 +1,89 @@
+//! Limits on the resources an installed app may use, so that one conductor
+//! can host the apps of many users without one of them starving the others.
+
+use super::InstalledAppId;
+use crate::prelude::*;
+
+/// The resources an installed app may use. Each limit is optional, and an
+/// app with the default quota is not limited at all.
+#[derive(
+    Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, SerializedBytes,
+)]
+pub struct AppQuota {
+    /// The most bytes the databases of the app's cells may take up on disk:
+    /// the source chains, the data held for the DHT and the cache of each
+    /// of its DNAs. These are shared by every cell of a DNA, so a DNA's
+    /// databases count in full against each app with a cell of it.
+    /// Zome calls are refused once the app uses this much.
+    #[serde(default)]
+    pub max_disk_bytes: Option<u64>,
+    /// The most clone cells the app may have across all of its roles.
+    #[serde(default)]
+    pub max_clone_cells: Option<u32>,
+    /// The most zome calls per second which may be made to the app's cells.
+    #[serde(default)]
+    pub max_zome_calls_per_second: Option<u32>,
+}
+
+impl AppQuota {
+    /// Is nothing limited by this quota.
+    pub fn is_unlimited(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// Which quota of an app a request would have exceeded.
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    SerializedBytes,
+    thiserror::Error,
+)]
+#[serde(rename_all = "snake_case", tag = "type", content = "data")]
+pub enum QuotaExceeded {
+    /// The databases of the app's cells take up `used` bytes,
+    /// and may take up at most `max`.
+    #[error("The app has used {used} bytes of its {max} byte disk quota")]
+    DiskBytes {
+        /// The bytes the app's databases take up.
+        used: u64,
+        /// The quota.
+        max: u64,
+    },
+    /// The app already has as many clone cells as it may have.
+    #[error("The app already has its maximum of {max} clone cells")]
+    CloneCells {
+        /// The quota.
+        max: u32,
+    },
+    /// The app has already been called as often as it may be this second.
+    #[error("The app may be called at most {max} times per second")]
+    ZomeCallRate {
+        /// The quota.
+        max: u32,
+    },
+}
+
+/// An app which a request was refused for, because of the quota it would
+/// have exceeded.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, SerializedBytes)]
+pub struct AppQuotaExceeded {
+    /// The app whose quota would have been exceeded.
+    pub installed_app_id: InstalledAppId,
+    /// The quota which would have been exceeded.
+    pub exceeded: QuotaExceeded,
+}
+
+impl std::fmt::Display for AppQuotaExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Quota of app {} exceeded: {}",
+            self.installed_app_id, self.exceeded
+        )
+    }
+}
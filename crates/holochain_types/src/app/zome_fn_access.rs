@@ -0,0 +1,148 @@
+//! Rules, set in the app manifest, for where the zome functions of an app
+//! may be called from, so an app can keep administrative functions off the
+//! public interface.
+
+use crate::prelude::*;
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+
+/// Where calls to a zome function may come from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum ZomeFnAccess {
+    /// From app interfaces, remote agents and other cells.
+    /// This is the access of functions without a rule.
+    Public,
+    /// From remote agents and other cells, but not from app interfaces.
+    Remote,
+    /// Only from the other cells of the app.
+    Internal,
+}
+
+impl Default for ZomeFnAccess {
+    fn default() -> Self {
+        Self::Public
+    }
+}
+
+impl ZomeFnAccess {
+    /// Whether a call from this origin is allowed, given the cells of the app
+    /// which set the rule.
+    pub fn allows(&self, origin: &ZomeCallOrigin, app_cells: &HashSet<CellId>) -> bool {
+        match (self, origin) {
+            (Self::Public, _) => true,
+            (Self::Remote, ZomeCallOrigin::AppInterface) => false,
+            (Self::Remote, _) => true,
+            (Self::Internal, ZomeCallOrigin::Cell(cell_id)) => app_cells.contains(cell_id),
+            (Self::Internal, _) => false,
+        }
+    }
+}
+
+/// Where a zome call came from. Calls a cell makes to itself are always
+/// allowed, so they have no origin.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ZomeCallOrigin {
+    /// A client of an app interface.
+    AppInterface,
+    /// Another agent, with `call_remote`.
+    Remote,
+    /// Another cell in this conductor, with `call`.
+    Cell(CellId),
+}
+
+impl std::fmt::Display for ZomeCallOrigin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::AppInterface => write!(f, "an app interface"),
+            Self::Remote => write!(f, "a remote agent"),
+            Self::Cell(cell_id) => write!(f, "cell {}", cell_id),
+        }
+    }
+}
+
+/// The access of the zome functions of an app role, by zome name and then
+/// function name. Functions without a rule are [`ZomeFnAccess::Public`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(transparent)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct ZomeFnAccessRules(BTreeMap<String, BTreeMap<String, ZomeFnAccess>>);
+
+impl ZomeFnAccessRules {
+    /// Are there no rules.
+    pub fn is_empty(&self) -> bool {
+        self.0.values().all(BTreeMap::is_empty)
+    }
+
+    /// Set the access of a zome function.
+    pub fn set(&mut self, zome_name: &ZomeName, fn_name: &FunctionName, access: ZomeFnAccess) {
+        self.0
+            .entry(zome_name.to_string())
+            .or_default()
+            .insert(fn_name.0.clone(), access);
+    }
+
+    /// The access of a zome function.
+    pub fn access(&self, zome_name: &ZomeName, fn_name: &FunctionName) -> ZomeFnAccess {
+        self.0
+            .get(zome_name.0.as_ref())
+            .and_then(|fns| fns.get(&fn_name.0))
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::fixt::prelude::*;
+    use holo_hash::fixt::AgentPubKeyFixturator;
+    use holo_hash::fixt::DnaHashFixturator;
+
+    #[test]
+    fn access_is_checked_against_the_origin() {
+        let app_cell = CellId::new(fixt!(DnaHash), fixt!(AgentPubKey));
+        let other_cell = CellId::new(fixt!(DnaHash), fixt!(AgentPubKey));
+        let app_cells: HashSet<_> = [app_cell.clone()].into();
+        let origins = [
+            ZomeCallOrigin::AppInterface,
+            ZomeCallOrigin::Remote,
+            ZomeCallOrigin::Cell(other_cell),
+            ZomeCallOrigin::Cell(app_cell),
+        ];
+        let allowed = |access: ZomeFnAccess| {
+            origins
+                .iter()
+                .map(|origin| access.allows(origin, &app_cells))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(allowed(ZomeFnAccess::Public), [true, true, true, true]);
+        assert_eq!(allowed(ZomeFnAccess::Remote), [false, true, true, true]);
+        assert_eq!(allowed(ZomeFnAccess::Internal), [false, false, false, true]);
+    }
+
+    #[test]
+    fn rules_are_read_from_yaml() {
+        let rules: ZomeFnAccessRules = serde_yaml::from_str(
+            "
+admin:
+  reset: internal
+  ban: remote
+",
+        )
+        .unwrap();
+        let admin: ZomeName = "admin".into();
+        assert_eq!(
+            rules.access(&admin, &"reset".into()),
+            ZomeFnAccess::Internal
+        );
+        assert_eq!(rules.access(&admin, &"ban".into()), ZomeFnAccess::Remote);
+        assert_eq!(rules.access(&admin, &"get".into()), ZomeFnAccess::Public);
+        assert_eq!(
+            rules.access(&"posts".into(), &"reset".into()),
+            ZomeFnAccess::Public
+        );
+        assert!(ZomeFnAccessRules::default().is_empty());
+    }
+}
@@ -233,6 +233,44 @@ impl FromSql for DhtOpType {
     }
 }
 
+impl DhtOpType {
+    /// The basis of the op of this type for an action. This is the hash the
+    /// op is stored at, so the authorities for the op are the agents whose
+    /// storage arcs cover the location of this hash.
+    pub fn basis(&self, action: &Action) -> DhtOpResult<AnyDhtHash> {
+        let mismatch = || DhtOpError::OpActionMismatch(*self, action.action_type());
+        let basis = match (self, action) {
+            (DhtOpType::StoreRecord, _) => ActionHash::with_data_sync(action).into(),
+            (DhtOpType::StoreEntry, _) => action
+                .entry_hash()
+                .cloned()
+                .ok_or_else(|| DhtOpError::ActionWithoutEntry(action.clone()))?
+                .into(),
+            (DhtOpType::RegisterAgentActivity, _) => action.author().clone().into(),
+            (DhtOpType::RegisterUpdatedContent, Action::Update(update)) => {
+                update.original_entry_address.clone().into()
+            }
+            (DhtOpType::RegisterUpdatedRecord, Action::Update(update)) => {
+                update.original_action_address.clone().into()
+            }
+            (DhtOpType::RegisterDeletedBy, Action::Delete(delete)) => {
+                delete.deletes_address.clone().into()
+            }
+            (DhtOpType::RegisterDeletedEntryAction, Action::Delete(delete)) => {
+                delete.deletes_entry_address.clone().into()
+            }
+            (DhtOpType::RegisterAddLink, Action::CreateLink(create_link)) => {
+                create_link.base_address.clone().into()
+            }
+            (DhtOpType::RegisterRemoveLink, Action::DeleteLink(delete_link)) => {
+                delete_link.base_address.clone().into()
+            }
+            _ => return Err(mismatch()),
+        };
+        Ok(basis)
+    }
+}
+
 impl DhtOp {
     fn as_unique_form(&self) -> UniqueForm<'_> {
         match self {
@@ -510,71 +548,35 @@ impl DhtOpLight {
         action_hash: ActionHash,
         action: &Action,
     ) -> DhtOpResult<Self> {
+        let basis = match op_type {
+            // The basis is the action hash, which we already have.
+            DhtOpType::StoreRecord => action_hash.clone().into(),
+            _ => op_type.basis(action)?,
+        };
+        let entry_hash = || {
+            action
+                .entry_hash()
+                .cloned()
+                .ok_or_else(|| DhtOpError::ActionWithoutEntry(action.clone()))
+        };
         let op = match op_type {
             DhtOpType::StoreRecord => {
-                let entry_hash = action.entry_hash().cloned();
-                Self::StoreRecord(action_hash.clone(), entry_hash, action_hash.into())
-            }
-            DhtOpType::StoreEntry => {
-                let entry_hash = action
-                    .entry_hash()
-                    .cloned()
-                    .ok_or_else(|| DhtOpError::ActionWithoutEntry(action.clone()))?;
-                Self::StoreEntry(action_hash, entry_hash.clone(), entry_hash.into())
-            }
-            DhtOpType::RegisterAgentActivity => {
-                Self::RegisterAgentActivity(action_hash, action.author().clone().into())
+                Self::StoreRecord(action_hash, action.entry_hash().cloned(), basis)
             }
+            DhtOpType::StoreEntry => Self::StoreEntry(action_hash, entry_hash()?, basis),
+            DhtOpType::RegisterAgentActivity => Self::RegisterAgentActivity(action_hash, basis),
             DhtOpType::RegisterUpdatedContent => {
-                let entry_hash = action
-                    .entry_hash()
-                    .cloned()
-                    .ok_or_else(|| DhtOpError::ActionWithoutEntry(action.clone()))?;
-                let basis = match action {
-                    Action::Update(update) => update.original_entry_address.clone(),
-                    _ => return Err(DhtOpError::OpActionMismatch(op_type, action.action_type())),
-                };
-                Self::RegisterUpdatedContent(action_hash, entry_hash, basis.into())
+                Self::RegisterUpdatedContent(action_hash, entry_hash()?, basis)
             }
             DhtOpType::RegisterUpdatedRecord => {
-                let entry_hash = action
-                    .entry_hash()
-                    .cloned()
-                    .ok_or_else(|| DhtOpError::ActionWithoutEntry(action.clone()))?;
-                let basis = match action {
-                    Action::Update(update) => update.original_entry_address.clone(),
-                    _ => return Err(DhtOpError::OpActionMismatch(op_type, action.action_type())),
-                };
-                Self::RegisterUpdatedRecord(action_hash, entry_hash, basis.into())
-            }
-            DhtOpType::RegisterDeletedBy => {
-                let basis = match action {
-                    Action::Delete(delete) => delete.deletes_address.clone(),
-                    _ => return Err(DhtOpError::OpActionMismatch(op_type, action.action_type())),
-                };
-                Self::RegisterDeletedBy(action_hash, basis.into())
+                Self::RegisterUpdatedRecord(action_hash, entry_hash()?, basis)
             }
+            DhtOpType::RegisterDeletedBy => Self::RegisterDeletedBy(action_hash, basis),
             DhtOpType::RegisterDeletedEntryAction => {
-                let basis = match action {
-                    Action::Delete(delete) => delete.deletes_entry_address.clone(),
-                    _ => return Err(DhtOpError::OpActionMismatch(op_type, action.action_type())),
-                };
-                Self::RegisterDeletedEntryAction(action_hash, basis.into())
-            }
-            DhtOpType::RegisterAddLink => {
-                let basis = match action {
-                    Action::CreateLink(create_link) => create_link.base_address.clone(),
-                    _ => return Err(DhtOpError::OpActionMismatch(op_type, action.action_type())),
-                };
-                Self::RegisterAddLink(action_hash, basis.into())
-            }
-            DhtOpType::RegisterRemoveLink => {
-                let basis = match action {
-                    Action::DeleteLink(delete_link) => delete_link.base_address.clone(),
-                    _ => return Err(DhtOpError::OpActionMismatch(op_type, action.action_type())),
-                };
-                Self::RegisterRemoveLink(action_hash, basis.into())
+                Self::RegisterDeletedEntryAction(action_hash, basis)
             }
+            DhtOpType::RegisterAddLink => Self::RegisterAddLink(action_hash, basis),
+            DhtOpType::RegisterRemoveLink => Self::RegisterRemoveLink(action_hash, basis),
         };
         Ok(op)
     }
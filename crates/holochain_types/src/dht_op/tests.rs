@@ -361,6 +361,21 @@ fn from_type_op_light() {
     }
 }
 
+#[test]
+fn op_type_basis_matches_op_basis() {
+    for record in all_records() {
+        for op in produce_ops_from_record(&record).unwrap() {
+            let action = op.action();
+            let basis = op.get_type().basis(&action).unwrap();
+            assert_eq!(basis, op.dht_basis());
+            assert_eq!(&basis, op.to_light().dht_basis());
+            let action_hash = ActionHash::with_data_sync(&action);
+            let light = DhtOpLight::from_type(op.get_type(), action_hash, &action).unwrap();
+            assert_eq!(&basis, light.dht_basis());
+        }
+    }
+}
+
 #[test]
 fn test_all_ops_basis() {
     let check_all_ops = |record| {
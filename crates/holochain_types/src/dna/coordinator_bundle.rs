@@ -1,6 +1,5 @@
 use holochain_serialized_bytes::prelude::*;
 use holochain_zome_types::CoordinatorZomes;
-use holochain_zome_types::WasmZome;
 use holochain_zome_types::ZomeDef;
 use mr_bundle::Manifest;
 
@@ -47,21 +46,9 @@ impl CoordinatorBundle {
         let coordinator = hash_bytes(self.manifest().zomes.iter().cloned(), &mut resources).await?;
         let coordinator_zomes = coordinator
             .iter()
-            .map(|(zome_name, hash, _, dependencies)| {
-                (
-                    zome_name.clone(),
-                    ZomeDef::Wasm(WasmZome {
-                        wasm_hash: hash.clone(),
-                        dependencies: dependencies.clone(),
-                    })
-                    .into(),
-                )
-            })
-            .collect();
-        let wasms = coordinator
-            .into_iter()
-            .map(|(_, _, wasm, _)| wasm)
+            .map(|(zome_name, zome, _)| (zome_name.clone(), ZomeDef::Wasm(zome.clone()).into()))
             .collect();
+        let wasms = coordinator.into_iter().map(|(_, _, wasm)| wasm).collect();
 
         Ok((coordinator_zomes, wasms))
     }
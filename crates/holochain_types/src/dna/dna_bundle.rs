@@ -77,34 +77,16 @@ impl DnaBundle {
 
         let integrity_zomes = data[0]
             .iter()
-            .map(|(zome_name, hash, _, dependencies)| {
-                (
-                    zome_name.clone(),
-                    ZomeDef::Wasm(WasmZome {
-                        wasm_hash: hash.clone(),
-                        dependencies: dependencies.clone(),
-                    })
-                    .into(),
-                )
-            })
+            .map(|(zome_name, zome, _)| (zome_name.clone(), ZomeDef::Wasm(zome.clone()).into()))
             .collect();
         let coordinator_zomes = data[1]
             .iter()
-            .map(|(zome_name, hash, _, dependencies)| {
-                (
-                    zome_name.clone(),
-                    ZomeDef::Wasm(WasmZome {
-                        wasm_hash: hash.clone(),
-                        dependencies: dependencies.clone(),
-                    })
-                    .into(),
-                )
-            })
+            .map(|(zome_name, zome, _)| (zome_name.clone(), ZomeDef::Wasm(zome.clone()).into()))
             .collect();
         let code: BTreeMap<_, _> = data
             .into_iter()
             .flatten()
-            .map(|(_, hash, wasm, _)| (hash, wasm))
+            .map(|(_, zome, wasm)| (zome.wasm_hash, wasm))
             .collect();
 
         let wasms = WasmMap::from(code);
@@ -131,6 +113,13 @@ impl DnaBundle {
                     origin_time: manifest.integrity.origin_time.into(),
                     integrity_zomes,
                     coordinator_zomes,
+                    runtime_properties: manifest
+                        .runtime_properties
+                        .clone()
+                        .map(SerializedBytes::try_from)
+                        .transpose()?
+                        .unwrap_or_default(),
+                    ephemeral_ttl_secs: manifest.integrity.ephemeral_ttl_secs,
                 };
 
                 if network_seed.is_none() && properties.is_none() {
@@ -193,6 +182,7 @@ impl DnaBundle {
                         hash: Some(hash),
                         location: Location::Bundled(PathBuf::from(filename)),
                         dependencies: Some(dependencies),
+                        unrestricted_functions: None,
                     }
                 })
             })
@@ -208,6 +198,9 @@ impl DnaBundle {
                     .cloned()
                     .map(|name| ZomeDependency { name })
                     .collect();
+                let unrestricted_functions = Some(zome.as_any_zome_def().unrestricted_functions())
+                    .filter(|functions| !functions.is_empty())
+                    .map(<[_]>::to_vec);
                 zome.wasm_hash(&name).ok().map(|hash| {
                     let hash = WasmHashB64::from(hash);
                     let filename = format!("{}", hash);
@@ -216,10 +209,21 @@ impl DnaBundle {
                         hash: Some(hash),
                         location: Location::Bundled(PathBuf::from(filename)),
                         dependencies: Some(dependencies),
+                        unrestricted_functions,
                     }
                 })
             })
             .collect();
+        let runtime_properties = if dna_def.runtime_properties == SerializedBytes::default() {
+            None
+        } else {
+            Some(dna_def.runtime_properties.try_into().map_err(|e| {
+                DnaError::DnaFileToBundleConversionError(format!(
+                    "DnaDef runtime properties were not YAML-deserializable: {}",
+                    e
+                ))
+            })?)
+        };
         Ok(DnaManifestCurrent {
             name: dna_def.name,
             integrity: IntegrityManifest {
@@ -232,8 +236,10 @@ impl DnaBundle {
                 })?),
                 origin_time: dna_def.origin_time.into(),
                 zomes: integrity,
+                ephemeral_ttl_secs: dna_def.ephemeral_ttl_secs,
             },
             coordinator: CoordinatorManifest { zomes: coordinator },
+            runtime_properties,
         }
         .into())
     }
@@ -242,7 +248,7 @@ impl DnaBundle {
 pub(super) async fn hash_bytes(
     zomes: impl Iterator<Item = ZomeManifest>,
     resources: &mut HashMap<Location, ResourceBytes>,
-) -> DnaResult<Vec<(ZomeName, WasmHash, DnaWasm, Vec<ZomeName>)>> {
+) -> DnaResult<Vec<(ZomeName, WasmZome, DnaWasm)>> {
     let iter = zomes.map(|z| {
         let bytes = resources
             .remove(&z.location)
@@ -253,6 +259,7 @@ pub(super) async fn hash_bytes(
         let dependencies = z.dependencies.map_or(Vec::with_capacity(0), |deps| {
             deps.into_iter().map(|d| d.name).collect()
         });
+        let unrestricted_functions = z.unrestricted_functions.unwrap_or_default();
         async move {
             let hash = wasm.to_hash().await;
             if let Some(expected) = expected_hash {
@@ -260,7 +267,12 @@ pub(super) async fn hash_bytes(
                     return Err(DnaError::WasmHashMismatch(expected, hash));
                 }
             }
-            DnaResult::Ok((zome_name, hash, wasm, dependencies))
+            let zome = WasmZome {
+                wasm_hash: hash,
+                dependencies,
+                unrestricted_functions,
+            };
+            DnaResult::Ok((zome_name, zome, wasm))
         }
     });
     futures::stream::iter(iter)
@@ -297,6 +309,7 @@ mod tests {
                         hash: None,
                         location: mr_bundle::Location::Bundled(path1.clone()),
                         dependencies: Default::default(),
+                        unrestricted_functions: None,
                     },
                     ZomeManifest {
                         name: "zome2".into(),
@@ -304,10 +317,13 @@ mod tests {
                         hash: Some(hash1.clone().into()),
                         location: mr_bundle::Location::Bundled(path2.clone()),
                         dependencies: Default::default(),
+                        unrestricted_functions: None,
                     },
                 ],
+                ephemeral_ttl_secs: None,
             },
             coordinator: CoordinatorManifest { zomes: vec![] },
+            runtime_properties: None,
         };
         let resources = vec![(path1, wasm1), (path2, wasm2)];
 
@@ -353,4 +369,52 @@ mod tests {
             SerializedBytes::try_from(properties).unwrap()
         );
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn runtime_properties_do_not_affect_the_dna_hash() {
+        let path = PathBuf::from("1");
+        let manifest = |runtime_properties: Option<YamlProperties>| DnaManifestCurrent {
+            name: "name".into(),
+            integrity: IntegrityManifest {
+                network_seed: None,
+                properties: None,
+                origin_time: Timestamp::HOLOCHAIN_EPOCH.into(),
+                zomes: vec![ZomeManifest {
+                    name: "zome".into(),
+                    hash: None,
+                    location: mr_bundle::Location::Bundled(path.clone()),
+                    dependencies: Default::default(),
+                    unrestricted_functions: None,
+                }],
+                ephemeral_ttl_secs: None,
+            },
+            coordinator: CoordinatorManifest { zomes: vec![] },
+            runtime_properties,
+        };
+        let dna_file = |manifest: DnaManifestCurrent| {
+            let bundle: DnaBundle = mr_bundle::Bundle::new_unchecked(
+                manifest.try_into().unwrap(),
+                vec![(path.clone(), vec![1, 2, 3])],
+            )
+            .unwrap()
+            .into();
+            bundle.into_dna_file(None, None)
+        };
+
+        let runtime_properties: YamlProperties =
+            serde_yaml::from_str("service_url: https://example.com").unwrap();
+        let (without, _) = dna_file(manifest(None)).await.unwrap();
+        let (with, _) = dna_file(manifest(Some(runtime_properties.clone())))
+            .await
+            .unwrap();
+        assert_eq!(without.dna_hash(), with.dna_hash());
+        assert_eq!(
+            without.dna_def().runtime_properties,
+            SerializedBytes::default()
+        );
+        assert_eq!(
+            with.dna_def().runtime_properties,
+            SerializedBytes::try_from(runtime_properties).unwrap()
+        );
+    }
 }
@@ -154,6 +154,12 @@ impl DnaFile {
         Ok(old_wasm_hashes)
     }
 
+    /// Replace the runtime properties of this dna, which leaves its hash
+    /// as it was.
+    pub fn update_runtime_properties(&mut self, runtime_properties: SerializedBytes) {
+        self.dna.content.runtime_properties = runtime_properties;
+    }
+
     /// Construct a DnaFile from its constituent parts
     #[cfg(feature = "fixturators")]
     pub fn from_parts(dna: DnaDefHashed, code: WasmMap) -> Self {
@@ -182,6 +188,34 @@ impl DnaFile {
             .map_err(|hash| DnaError::DnaHashMismatch(self.dna.as_hash().clone(), hash))
     }
 
+    /// Verify that this is the DNA with the given hash, along with the code
+    /// of all its wasm zomes, e.g. when it was received from a peer.
+    pub async fn verify(&self, dna_hash: &DnaHash) -> Result<(), DnaError> {
+        self.verify_hash()?;
+        if self.dna_hash() != dna_hash {
+            return Err(DnaError::DnaHashMismatch(
+                dna_hash.clone(),
+                self.dna_hash().clone(),
+            ));
+        }
+        for (wasm_hash, wasm) in self.code() {
+            if WasmHash::with_data(wasm).await != *wasm_hash {
+                return Err(DnaError::InvalidWasmHash);
+            }
+        }
+        let missing_code = self.dna.all_zomes().any(|(_, zome)| {
+            if let ZomeDef::Wasm(zome) = zome {
+                !self.code().contains_key(&zome.wasm_hash)
+            } else {
+                false
+            }
+        });
+        if missing_code {
+            return Err(DnaError::InvalidWasmHash);
+        }
+        Ok(())
+    }
+
     /// Load dna_file bytecode into this rust struct.
     #[deprecated = "remove after app bundles become standard; use DnaBundle instead"]
     pub async fn from_file_content(data: &[u8]) -> Result<Self, DnaError> {
@@ -34,6 +34,7 @@ async fn test_update_coordinators() {
             CoordinatorZomeDef::from(ZomeDef::Wasm(WasmZome {
                 wasm_hash: WasmHash::with_data(&dna_wasms[2]).await,
                 dependencies: vec!["b".into()],
+                unrestricted_functions: Default::default(),
             })),
         ),
         (
@@ -41,6 +42,7 @@ async fn test_update_coordinators() {
             CoordinatorZomeDef::from(ZomeDef::Wasm(WasmZome {
                 wasm_hash: WasmHash::with_data(&dna_wasms[3]).await,
                 dependencies: vec!["b".into(), "a".into()],
+                unrestricted_functions: Default::default(),
             })),
         ),
     ];
@@ -65,6 +67,7 @@ async fn test_update_coordinators() {
         CoordinatorZomeDef::from(ZomeDef::Wasm(WasmZome {
             wasm_hash: WasmHash::with_data(&new_dna_wasms[0]).await,
             dependencies: vec!["b".into()],
+            unrestricted_functions: Default::default(),
         })),
     )];
     let old_wasm = dna
@@ -98,6 +101,7 @@ async fn test_update_coordinators() {
         CoordinatorZomeDef::from(ZomeDef::Wasm(WasmZome {
             wasm_hash: WasmHash::with_data(&new_dna_wasms[0]).await,
             dependencies: vec!["a".into()],
+            unrestricted_functions: Default::default(),
         })),
     )];
     let old_wasm = dna
@@ -139,6 +143,7 @@ async fn test_update_coordinators() {
             CoordinatorZomeDef::from(ZomeDef::Wasm(WasmZome {
                 wasm_hash: WasmHash::with_data(&new_dna_wasms[0]).await,
                 dependencies: vec!["a".into()],
+                unrestricted_functions: Default::default(),
             })),
         ),
         (
@@ -146,6 +151,7 @@ async fn test_update_coordinators() {
             CoordinatorZomeDef::from(ZomeDef::Wasm(WasmZome {
                 wasm_hash: WasmHash::with_data(&new_dna_wasms[1]).await,
                 dependencies: vec!["a".into()],
+                unrestricted_functions: Default::default(),
             })),
         ),
         (
@@ -153,6 +159,7 @@ async fn test_update_coordinators() {
             CoordinatorZomeDef::from(ZomeDef::Wasm(WasmZome {
                 wasm_hash: WasmHash::with_data(&new_dna_wasms[2]).await,
                 dependencies: vec!["a".into()],
+                unrestricted_functions: Default::default(),
             })),
         ),
         (
@@ -160,6 +167,7 @@ async fn test_update_coordinators() {
             CoordinatorZomeDef::from(ZomeDef::Wasm(WasmZome {
                 wasm_hash: WasmHash::with_data(&new_dna_wasms[3]).await,
                 dependencies: vec!["a".into()],
+                unrestricted_functions: Default::default(),
             })),
         ),
     ];
@@ -231,6 +239,7 @@ async fn test_update_coordinators_checks_deps() {
             CoordinatorZomeDef::from(ZomeDef::Wasm(WasmZome {
                 wasm_hash: WasmHash::with_data(&dna_wasms[2]).await,
                 dependencies: vec!["b".into()],
+                unrestricted_functions: Default::default(),
             })),
         ),
         (
@@ -238,6 +247,7 @@ async fn test_update_coordinators_checks_deps() {
             CoordinatorZomeDef::from(ZomeDef::Wasm(WasmZome {
                 wasm_hash: WasmHash::with_data(&dna_wasms[3]).await,
                 dependencies: vec!["b".into(), "a".into()],
+                unrestricted_functions: Default::default(),
             })),
         ),
     ];
@@ -260,6 +270,7 @@ async fn test_update_coordinators_checks_deps() {
         CoordinatorZomeDef::from(ZomeDef::Wasm(WasmZome {
             wasm_hash: WasmHash::with_data(&new_dna_wasms[0]).await,
             dependencies: vec!["z".into()],
+            unrestricted_functions: Default::default(),
         })),
     )];
     let err = dna
@@ -278,6 +289,7 @@ async fn test_update_coordinators_checks_deps() {
         CoordinatorZomeDef::from(ZomeDef::Wasm(WasmZome {
             wasm_hash: WasmHash::with_data(&new_dna_wasms[0]).await,
             dependencies: vec!["z".into()],
+            unrestricted_functions: Default::default(),
         })),
     )];
     let err = dna
@@ -287,3 +299,45 @@ async fn test_update_coordinators_checks_deps() {
 
     assert!(matches!(err, DnaError::DanglingZomeDependency(_, _)));
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn dna_files_are_verified_against_their_hash_and_code() {
+    let wasm = DnaWasm {
+        code: Arc::new(Box::new([0])),
+    };
+    let mut dna_def = DnaDefBuilder::default();
+    dna_def
+        .integrity_zomes(vec![(
+            "a".into(),
+            IntegrityZomeDef::from_hash(WasmHash::with_data(&wasm).await),
+        )])
+        .network_seed("00000000-0000-0000-0000-000000000000".into());
+    let dna = DnaFile::new(dna_def.build().unwrap(), vec![wasm])
+        .await
+        .unwrap();
+    dna.verify(dna.dna_hash()).await.unwrap();
+
+    let other = dna.clone().with_network_seed("other".into()).await.unwrap();
+    assert!(matches!(
+        dna.verify(other.dna_hash()).await,
+        Err(DnaError::DnaHashMismatch(..))
+    ));
+
+    let mut tampered = dna.clone();
+    for wasm in tampered.code.0.values_mut() {
+        *wasm = DnaWasm {
+            code: Arc::new(Box::new([1])),
+        };
+    }
+    assert!(matches!(
+        tampered.verify(dna.dna_hash()).await,
+        Err(DnaError::InvalidWasmHash)
+    ));
+
+    let mut missing = dna.clone();
+    missing.code.0.clear();
+    assert!(matches!(
+        missing.verify(dna.dna_hash()).await,
+        Err(DnaError::InvalidWasmHash)
+    ));
+}
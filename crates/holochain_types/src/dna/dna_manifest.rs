@@ -56,10 +56,11 @@ impl DnaManifest {
     ) -> Self {
         DnaManifestCurrent::new(
             name,
-            IntegrityManifest::new(network_seed, properties, origin_time, integrity_zomes),
+            IntegrityManifest::new(network_seed, properties, origin_time, integrity_zomes, None),
             CoordinatorManifest {
                 zomes: coordinator_zomes,
             },
+            None,
         )
         .into()
     }
@@ -71,6 +72,13 @@ impl DnaManifest {
         }
     }
 
+    /// Getter for runtime_properties
+    pub fn runtime_properties(&self) -> Option<YamlProperties> {
+        match self {
+            DnaManifest::V1(manifest) => manifest.runtime_properties.clone(),
+        }
+    }
+
     /// Getter for network_seed
     pub fn network_seed(&self) -> Option<String> {
         match self {
@@ -94,6 +102,15 @@ impl TryFrom<DnaManifest> for ValidatedDnaManifest {
             DnaManifest::V1(m) => {
                 let integrity_zome_names: HashSet<_> =
                     m.integrity.zomes.iter().map(|z| z.name.clone()).collect();
+                // Check only coordinator zomes declare unrestricted functions.
+                if let Some(z) = m
+                    .integrity
+                    .zomes
+                    .iter()
+                    .find(|z| z.unrestricted_functions.is_some())
+                {
+                    return Err(DnaError::UnrestrictedIntegrityFunctions(z.name.to_string()));
+                }
                 // Check there are no duplicate zome names.
                 let mut names = HashSet::new();
                 for z in m.all_zomes() {
@@ -18,6 +18,7 @@ use serde_with::serde_as;
 ///   network_seed: 00000000-0000-0000-0000-000000000000
 ///   properties: ~
 ///   origin_time: 2022-02-11T23:05:19.470323Z
+///   ephemeral_ttl_secs: 86400
 ///   zomes:
 ///     - name: zome1
 ///       bundled: ../dna1/zomes/zome1.wasm
@@ -34,6 +35,10 @@ use serde_with::serde_as;
 ///       dependencies:
 ///         - name: zome1
 ///         - name: zome2
+///       unrestricted_functions:
+///         - recv_remote_signal
+/// runtime_properties:
+///   service_url: https://example.com
 /// ```
 ///
 /// When there's only one integrity zome, it will automatically be a dependency
@@ -83,6 +88,14 @@ pub struct DnaManifestV1 {
     ///
     /// Does not affect the [`DnaHash`].
     pub coordinator: CoordinatorManifest,
+
+    /// Application properties which can be updated over the admin API
+    /// while the DNA runs, and which zomes read with `runtime_properties`.
+    ///
+    /// Does not affect the [`DnaHash`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[builder(default)]
+    pub runtime_properties: Option<YamlProperties>,
 }
 
 impl DnaManifestV1 {
@@ -126,6 +139,12 @@ pub struct IntegrityManifest {
     /// The order is significant: it determines initialization order.
     /// The integrity zome manifests.
     pub zomes: Vec<ZomeManifest>,
+
+    /// Makes this an ephemeral DNA, whose ops are purged by their authorities
+    /// and no longer published once they're older than this many seconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[builder(default)]
+    pub ephemeral_ttl_secs: Option<u64>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Default)]
@@ -159,6 +178,12 @@ pub struct ZomeManifest {
     /// The order of these must match the order the types
     /// are used in the zome.
     pub dependencies: Option<Vec<ZomeDependency>>,
+
+    /// Functions of this coordinator zome which anyone may call.
+    /// Each cell of the DNA grants unrestricted access to them at genesis,
+    /// so zomes don't need to create the grant in `init`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unrestricted_functions: Option<Vec<FunctionName>>,
 }
 
 /// Manifest for integrity zomes that another zome
@@ -72,3 +72,55 @@ coordinator:
     serde_yaml::from_str::<DnaManifest>(&manifest_yaml)
         .expect_err("This should fail because integrity zomes are required");
 }
+
+#[test]
+fn unrestricted_functions_are_only_declared_by_coordinators() {
+    let manifest_yaml = r#"
+---
+manifest_version: "1"
+name: test_dna
+integrity:
+  network_seed: blablabla
+  origin_time: 2022-02-11T23:29:00.789576Z
+  properties: ~
+  zomes:
+    - name: zome1
+      bundled: zome-1.wasm
+coordinator:
+  zomes:
+    - name: zome2
+      bundled: zome-2.wasm
+      unrestricted_functions:
+        - recv_remote_signal
+        "#;
+
+    let manifest: DnaManifest = serde_yaml::from_str(manifest_yaml).unwrap();
+    let DnaManifest::V1(m) = &manifest;
+    assert_eq!(m.integrity.zomes[0].unrestricted_functions, None);
+    assert_eq!(
+        m.coordinator.zomes[0].unrestricted_functions,
+        Some(vec!["recv_remote_signal".into()])
+    );
+    ValidatedDnaManifest::try_from(manifest).unwrap();
+
+    let manifest_yaml = r#"
+---
+manifest_version: "1"
+name: test_dna
+integrity:
+  network_seed: blablabla
+  origin_time: 2022-02-11T23:29:00.789576Z
+  properties: ~
+  zomes:
+    - name: zome1
+      bundled: zome-1.wasm
+      unrestricted_functions:
+        - recv_remote_signal
+        "#;
+
+    let manifest: DnaManifest = serde_yaml::from_str(manifest_yaml).unwrap();
+    assert!(matches!(
+        ValidatedDnaManifest::try_from(manifest),
+        Err(DnaError::UnrestrictedIntegrityFunctions(_))
+    ));
+}
@@ -69,6 +69,9 @@ pub enum DnaError {
 
     #[error("Zome dependency {0} for {1} is not pointing at an existing integrity zome that is not itself")]
     DanglingZomeDependency(String, String),
+
+    #[error("Integrity zome {0} declares unrestricted functions, but only coordinator zomes have functions to call")]
+    UnrestrictedIntegrityFunctions(String),
 }
 
 impl From<std::io::Error> for DnaError {
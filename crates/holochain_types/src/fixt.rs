@@ -64,6 +64,7 @@ fn new_entry_record(entry: Entry, action_type: ActionType, index: usize) -> Reco
         Entry::Agent(_) => EntryType::AgentPubKey,
         Entry::CapClaim(_) => EntryType::CapClaim,
         Entry::CapGrant(_) => EntryType::CapGrant,
+        Entry::Chunk(_) => EntryType::Chunk,
     };
     match action_type {
         ActionType::Create => {
@@ -102,6 +103,7 @@ fixturator!(
             Entry::Agent(_) => EntryType::AgentPubKey,
             Entry::CapClaim(_) => EntryType::CapClaim,
             Entry::CapGrant(_) => EntryType::CapGrant,
+            Entry::Chunk(_) => EntryType::Chunk,
         };
         let new = NewEntryActionFixturator::new_indexed(et, get_fixt_index!()).next().unwrap();
         let (shh, _) = RecordFixturator::new_indexed(new, get_fixt_index!()).next().unwrap().into_inner();
@@ -33,6 +33,34 @@ pub struct MetadataSet {
     /// This is simply a faster way of determining if
     /// there are any live actions on an entry.
     pub entry_dht_status: Option<EntryDhtStatus>,
+    /// How many live actions, updates and deletes an entry has.
+    #[serde(default)]
+    pub crud_summary: Option<EntryCrudSummary>,
+}
+
+/// A summary of the actions on an entry, enough to tell whether it has been
+/// edited or deleted without getting all of them.
+/// Only valid actions are counted.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct EntryCrudSummary {
+    /// The actions which created the entry and haven't been deleted.
+    pub live_actions: u32,
+    /// The updates of the entry.
+    pub updates: u32,
+    /// The deletes of actions which created the entry.
+    pub deletes: u32,
+}
+
+impl EntryCrudSummary {
+    /// Whether the entry has been updated.
+    pub fn is_updated(&self) -> bool {
+        self.updates > 0
+    }
+
+    /// Whether every action which created the entry has been deleted.
+    pub fn is_deleted(&self) -> bool {
+        self.deletes > 0 && self.live_actions == 0
+    }
 }
 
 impl From<ActionHashed> for TimedActionHash {
@@ -12,7 +12,14 @@ use holochain_zome_types::prelude::*;
 #[derive(Clone, Debug, Serialize, Deserialize, SerializedBytes, PartialEq, Eq)]
 pub enum Signal {
     /// Signal from a Cell, generated by `emit_signal`
-    App(CellId, AppSignal),
+    App {
+        /// The cell the signal was emitted in
+        cell_id: CellId,
+        /// The zome which emitted the signal
+        zome_name: ZomeName,
+        /// The signal
+        signal: AppSignal,
+    },
     /// System-defined signals
     System(SystemSignal),
 }
@@ -29,6 +36,27 @@ pub enum SystemSignal {
     Test(String),
     /// A countersigning session has successfully completed.
     SuccessfulCountersigning(holo_hash::EntryHash),
+    /// A cell was paused because one of its workflows kept failing,
+    /// e.g. due to a corrupted store. The apps which use the cell have been
+    /// paused with the error as the reason, and can be resumed with the
+    /// `EnableApp` admin call once the cause has been fixed.
+    CellPaused(CellId, String),
+    /// A request was refused because it would have exceeded the quota of
+    /// an app. Only the first refusal is signalled until the app is back
+    /// within its quota.
+    QuotaExceeded(crate::app::AppQuotaExceeded),
+    /// A validator sent a receipt rejecting an op authored in a cell.
+    /// The reason is given if the op was rejected by sys validation.
+    OpRejected {
+        /// The cell the op was authored in
+        cell_id: CellId,
+        /// The rejected op
+        op_hash: holo_hash::DhtOpHash,
+        /// The validators which signed the receipt
+        validators: Vec<holo_hash::AgentPubKey>,
+        /// Why sys validation rejected the op
+        reason: Option<SysValidationIssueReason>,
+    },
 }
 
 /// Create a test signal
@@ -11,6 +11,7 @@ pub use holochain_zome_types::test_utils::*;
 
 #[warn(missing_docs)]
 pub mod chain;
+pub mod generators;
 
 #[derive(Serialize, Deserialize, SerializedBytes, Debug)]
 struct FakeProperties {
@@ -47,6 +48,8 @@ pub fn fake_dna_zomes_named(
         origin_time: Timestamp::HOLOCHAIN_EPOCH,
         integrity_zomes: Vec::new(),
         coordinator_zomes: Vec::new(),
+        runtime_properties: Default::default(),
+        ephemeral_ttl_secs: None,
     };
     tokio_helper::block_forever_on(async move {
         let mut wasm_code = Vec::new();
@@ -58,6 +61,7 @@ pub fn fake_dna_zomes_named(
                 ZomeDef::Wasm(WasmZome {
                     wasm_hash,
                     dependencies: Default::default(),
+                    unrestricted_functions: Default::default(),
                 })
                 .into(),
             ));
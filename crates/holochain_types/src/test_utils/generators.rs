@@ -0,0 +1,287 @@
+//! Generators of realistic structured data for fuzzing validation.
+//!
+//! Everything here is drawn from an [`Unstructured`], so a fuzz target can
+//! feed in its input and a test can use [`holochain_zome_types::NOISE`].
+//! Unlike plain `Arbitrary` data, the chains generated here are valid:
+//! they start with the genesis actions, each action links to the one before
+//! it with the next seq and a later timestamp, every action is signed by
+//! its author with a real keystore, and actions only refer to entries and
+//! actions earlier on the same chain.
+
+use crate::dht_op::produce_ops_from_record;
+use crate::prelude::*;
+use crate::record::SignedActionHashedExt;
+use arbitrary::Arbitrary;
+use arbitrary::Unstructured;
+use holochain_keystore::MetaLairClient;
+use std::time::Duration;
+
+/// An arbitrary app entry.
+pub fn app_entry(u: &mut Unstructured) -> Entry {
+    Entry::App(AppEntryBytes::arbitrary(u).unwrap())
+}
+
+/// An arbitrary app entry type, which may be public or private.
+pub fn app_entry_type(u: &mut Unstructured) -> EntryType {
+    let visibility = if bool::arbitrary(u).unwrap() {
+        EntryVisibility::Public
+    } else {
+        EntryVisibility::Private
+    };
+    EntryType::App(AppEntryType::new(
+        u8::arbitrary(u).unwrap().into(),
+        u8::arbitrary(u).unwrap().into(),
+        visibility,
+    ))
+}
+
+/// The entries created on a chain so far, which later actions can refer to.
+#[derive(Default)]
+struct Created {
+    entries: Vec<(ActionHash, EntryHash, EntryType)>,
+    links: Vec<(ActionHash, AnyLinkableHash)>,
+}
+
+/// The position on a chain at which the next action is written.
+struct Head {
+    author: AgentPubKey,
+    timestamp: Timestamp,
+    action_seq: u32,
+    prev_action: ActionHash,
+}
+
+/// The action to write after `head`, along with its entry if it has one.
+/// After the genesis actions this is arbitrary, but updates, deletes and
+/// links only refer to what was created on the chain before.
+fn next_action(u: &mut Unstructured, head: Head, created: &Created) -> (Action, Option<Entry>) {
+    let Head {
+        author,
+        timestamp,
+        action_seq,
+        prev_action,
+    } = head;
+    match action_seq {
+        1 => {
+            let action = Action::AgentValidationPkg(AgentValidationPkg {
+                author,
+                timestamp,
+                action_seq,
+                prev_action,
+                membrane_proof: None,
+            });
+            return (action, None);
+        }
+        2 => {
+            let action = Action::Create(Create {
+                author: author.clone(),
+                timestamp,
+                action_seq,
+                prev_action,
+                entry_type: EntryType::AgentPubKey,
+                entry_hash: author.clone().into(),
+                weight: Default::default(),
+            });
+            return (action, Some(Entry::Agent(author)));
+        }
+        _ => (),
+    }
+    let choice = if created.entries.is_empty() {
+        0
+    } else {
+        u.int_in_range(0..=4).unwrap()
+    };
+    match choice {
+        0 | 1 => {
+            let entry = app_entry(u);
+            let action = Action::Create(Create {
+                author,
+                timestamp,
+                action_seq,
+                prev_action,
+                entry_type: app_entry_type(u),
+                entry_hash: EntryHash::with_data_sync(&entry),
+                weight: Default::default(),
+            });
+            (action, Some(entry))
+        }
+        2 => {
+            let (original_action_address, original_entry_address, entry_type) =
+                u.choose(&created.entries).unwrap().clone();
+            let entry = app_entry(u);
+            let action = Action::Update(Update {
+                author,
+                timestamp,
+                action_seq,
+                prev_action,
+                original_action_address,
+                original_entry_address,
+                entry_type,
+                entry_hash: EntryHash::with_data_sync(&entry),
+                weight: Default::default(),
+            });
+            (action, Some(entry))
+        }
+        3 => {
+            let (deletes_address, deletes_entry_address, _) =
+                u.choose(&created.entries).unwrap().clone();
+            let action = Action::Delete(Delete {
+                author,
+                timestamp,
+                action_seq,
+                prev_action,
+                deletes_address,
+                deletes_entry_address,
+                weight: Default::default(),
+            });
+            (action, None)
+        }
+        _ if created.links.is_empty() || bool::arbitrary(u).unwrap() => {
+            let (_, base, _) = u.choose(&created.entries).unwrap();
+            let (_, target, _) = u.choose(&created.entries).unwrap();
+            let action = Action::CreateLink(CreateLink {
+                author,
+                timestamp,
+                action_seq,
+                prev_action,
+                base_address: base.clone().into(),
+                target_address: target.clone().into(),
+                zome_id: u8::arbitrary(u).unwrap().into(),
+                link_type: u8::arbitrary(u).unwrap().into(),
+                tag: LinkTag::arbitrary(u).unwrap(),
+                weight: Default::default(),
+            });
+            (action, None)
+        }
+        _ => {
+            let (link_add_address, base_address) = u.choose(&created.links).unwrap().clone();
+            let action = Action::DeleteLink(DeleteLink {
+                author,
+                timestamp,
+                action_seq,
+                prev_action,
+                base_address,
+                link_add_address,
+            });
+            (action, None)
+        }
+    }
+}
+
+/// A valid source chain for this agent in this dna: the three genesis
+/// actions followed by `len` arbitrary creates, updates, deletes and links,
+/// all signed with the keystore.
+///
+/// The chain ends before now, so it isn't rejected for being in the future.
+pub async fn valid_chain(
+    u: &mut Unstructured<'_>,
+    keystore: &MetaLairClient,
+    author: AgentPubKey,
+    dna_hash: DnaHash,
+    len: usize,
+) -> Vec<Record> {
+    // Leave room for a gap of up to a second between each action.
+    let start = Timestamp::now().as_micros() - (len as i64 + 3) * 1_000_000;
+    let mut timestamp = Timestamp::from_micros(start);
+    let mut records = Vec::with_capacity(len + 3);
+    let mut created = Created::default();
+    let mut prev: Option<ActionHash> = None;
+    for action_seq in 0..(len + 3) as u32 {
+        let (action, entry) = match prev.clone() {
+            None => {
+                let dna = Dna {
+                    author: author.clone(),
+                    timestamp,
+                    hash: dna_hash.clone(),
+                };
+                (Action::Dna(dna), None)
+            }
+            Some(prev_action) => {
+                let micros = u.int_in_range(1..=1_000_000).unwrap();
+                timestamp = (timestamp + Duration::from_micros(micros)).unwrap();
+                let head = Head {
+                    author: author.clone(),
+                    timestamp,
+                    action_seq,
+                    prev_action,
+                };
+                next_action(u, head, &created)
+            }
+        };
+        let action = ActionHashed::from_content_sync(action);
+        let hash = action.as_hash().clone();
+        match action.as_content() {
+            Action::Create(Create {
+                entry_type: entry_type @ EntryType::App(_),
+                entry_hash,
+                ..
+            })
+            | Action::Update(Update {
+                entry_type,
+                entry_hash,
+                ..
+            }) => created
+                .entries
+                .push((hash.clone(), entry_hash.clone(), entry_type.clone())),
+            Action::CreateLink(link) => created
+                .links
+                .push((hash.clone(), link.base_address.clone())),
+            _ => (),
+        }
+        let action = SignedActionHashed::sign(keystore, action)
+            .await
+            .expect("Can sign the action");
+        records.push(Record::new(action, entry));
+        prev = Some(hash);
+    }
+    records
+}
+
+/// All the ops the author of these records would publish for them.
+pub fn chain_ops(records: &[Record]) -> Vec<DhtOp> {
+    records
+        .iter()
+        .flat_map(|record| produce_ops_from_record(record).expect("Records produce ops"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::fixt::prelude::*;
+    use contrafact::check_seq;
+    use holo_hash::fixt::DnaHashFixturator;
+    use holochain_keystore::test_keystore::spawn_test_keystore;
+    use holochain_zome_types::action::facts::valid_chain as valid_chain_fact;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn generated_chains_are_valid() {
+        let mut u = Unstructured::new(&holochain_zome_types::NOISE);
+        let keystore = spawn_test_keystore().await.unwrap();
+        let author = AgentPubKey::new_random(&keystore).await.unwrap();
+        let records = valid_chain(&mut u, &keystore, author.clone(), fixt!(DnaHash), 20).await;
+        assert_eq!(records.len(), 23);
+
+        let actions: Vec<Action> = records.iter().map(|r| r.action().clone()).collect();
+        check_seq(actions.as_slice(), valid_chain_fact()).unwrap();
+        assert!(matches!(actions[1], Action::AgentValidationPkg(_)));
+        assert!(actions
+            .windows(2)
+            .all(|w| w[0].timestamp() < w[1].timestamp()));
+        assert!(actions[actions.len() - 1].timestamp() < Timestamp::now());
+        for record in &records {
+            assert_eq!(record.action().author(), &author);
+            record.signed_action().validate().await.unwrap();
+            if let Some((entry_hash, _)) = record.action().entry_data() {
+                let entry = record.entry().as_option().unwrap();
+                assert_eq!(&EntryHash::with_data_sync(entry), entry_hash);
+            }
+        }
+
+        let ops = chain_ops(&records);
+        let activity = ops
+            .iter()
+            .filter(|op| matches!(op, DhtOp::RegisterAgentActivity(_, _)))
+            .count();
+        assert_eq!(activity, records.len());
+    }
+}
@@ -3,35 +3,78 @@
 use once_cell::sync::Lazy;
 use std::io::Result;
 
-static PIPED: Lazy<std::sync::Mutex<bool>> = Lazy::new(|| std::sync::Mutex::new(false));
+/// Where to read the passphrase from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PassphraseSource {
+    /// Prompt for it on the tty.
+    Interactive,
+    /// Read the first line of stdin.
+    Piped,
+    /// Read it from this environment variable, which is then removed so it
+    /// isn't passed on to child processes.
+    Env(String),
+    /// Read the first line of this file descriptor, such as one a service
+    /// manager passes the process.
+    Fd(i32),
+    /// Run this shell command and read the first line of its output, for
+    /// tools like `systemd-ask-password` or a secrets manager.
+    Command(String),
+}
+
+impl Default for PassphraseSource {
+    fn default() -> Self {
+        Self::Interactive
+    }
+}
+
+static SOURCE: Lazy<std::sync::Mutex<PassphraseSource>> =
+    Lazy::new(|| std::sync::Mutex::new(PassphraseSource::default()));
 
 /// Set the "piped" flag. If the user would prefer to send the passphrase
 /// over stdin (rather than tty capture). This must be set before the first
 /// call to [pw_get] or the passphrase will already be captured.
 pub fn pw_set_piped(piped: bool) {
-    *PIPED.lock().unwrap() = piped;
+    pw_set_source(if piped {
+        PassphraseSource::Piped
+    } else {
+        PassphraseSource::Interactive
+    });
 }
 
-fn get_piped() -> bool {
-    *PIPED.lock().unwrap()
+/// Set where the passphrase is read from. This must be set before the first
+/// call to [pw_get] or the passphrase will already be captured.
+pub fn pw_set_source(source: PassphraseSource) {
+    *SOURCE.lock().unwrap() = source;
+}
+
+fn get_source() -> PassphraseSource {
+    SOURCE.lock().unwrap().clone()
 }
 
 static PASSPHRASE: Lazy<std::result::Result<sodoken::BufRead, String>> = Lazy::new(|| {
-    if get_piped() {
-        read_piped_passphrase().map_err(|e| e.to_string())
-    } else {
-        read_interactive_passphrase("# passphrase> ").map_err(|e| e.to_string())
+    match get_source() {
+        PassphraseSource::Interactive => read_interactive_passphrase("# passphrase> "),
+        PassphraseSource::Piped => read_piped_passphrase(),
+        PassphraseSource::Env(var) => read_env_passphrase(&var),
+        PassphraseSource::Fd(fd) => read_fd_passphrase(fd),
+        PassphraseSource::Command(command) => read_command_passphrase(&command),
     }
+    .map_err(|e| e.to_string())
 });
 
 /// Capture a passphrase from the user. Either captures from tty, or
-/// reads stdin if [pw_set_piped] was called with `true`.
+/// from the source set with [pw_set_source].
 pub fn pw_get() -> Result<sodoken::BufRead> {
     PASSPHRASE
         .clone()
         .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))
 }
 
+/// Copy a passphrase into locked memory.
+pub fn pw_from_bytes(pass_tmp: Vec<u8>) -> Result<sodoken::BufRead> {
+    vec_to_locked(pass_tmp)
+}
+
 fn vec_to_locked(mut pass_tmp: Vec<u8>) -> Result<sodoken::BufRead> {
     match sodoken::BufWrite::new_mem_locked(pass_tmp.len()) {
         Err(e) => {
@@ -56,15 +99,73 @@ fn read_interactive_passphrase(prompt: &str) -> Result<sodoken::BufRead> {
 }
 
 fn read_piped_passphrase() -> Result<sodoken::BufRead> {
-    use std::io::Read;
-
     let stdin = std::io::stdin();
-    let mut stdin = stdin.lock();
+    let stdin = stdin.lock();
+    read_first_line(stdin)
+}
+
+fn read_env_passphrase(var: &str) -> Result<sodoken::BufRead> {
+    let passphrase = std::env::var_os(var).ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("passphrase environment variable {} is not set", var),
+        )
+    })?;
+    std::env::remove_var(var);
+    vec_to_locked(passphrase.to_string_lossy().into_owned().into_bytes())
+}
+
+#[cfg(unix)]
+fn read_fd_passphrase(fd: i32) -> Result<sodoken::BufRead> {
+    use std::os::unix::io::FromRawFd;
+    // The file descriptor is given to us to read the passphrase from,
+    // so we take ownership of it and close it once it's read.
+    let file = unsafe { std::fs::File::from_raw_fd(fd) };
+    read_first_line(file)
+}
+
+#[cfg(not(unix))]
+fn read_fd_passphrase(_fd: i32) -> Result<sodoken::BufRead> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "reading the passphrase from a file descriptor is only supported on unix",
+    ))
+}
+
+fn read_command_passphrase(command: &str) -> Result<sodoken::BufRead> {
+    #[cfg(unix)]
+    let mut cmd = std::process::Command::new("sh");
+    #[cfg(unix)]
+    cmd.arg("-c");
+    #[cfg(not(unix))]
+    let mut cmd = std::process::Command::new("cmd");
+    #[cfg(not(unix))]
+    cmd.arg("/C");
+    let output = cmd
+        .arg(command)
+        .stdin(std::process::Stdio::inherit())
+        .stderr(std::process::Stdio::inherit())
+        .output()?;
+    let mut stdout = output.stdout;
+    let passphrase = if output.status.success() {
+        read_first_line(&stdout[..])
+    } else {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Other,
+            format!("passphrase command failed with {}", output.status),
+        ))
+    };
+    stdout.fill(0);
+    passphrase
+}
+
+/// Read up to the first newline, dropping it and any carriage return.
+fn read_first_line(mut reader: impl std::io::Read) -> Result<sodoken::BufRead> {
     let passphrase = <sodoken::BufWriteSized<512>>::new_mem_locked()?;
     let mut next_char = 0;
     loop {
         let mut lock = passphrase.write_lock();
-        let done = match stdin.read_exact(&mut lock[next_char..next_char + 1]) {
+        let done = match reader.read_exact(&mut lock[next_char..next_char + 1]) {
             Ok(_) => {
                 if lock[next_char] == 10 {
                     true
@@ -17,6 +17,10 @@
 //! You can use [`ListenerHandle::close`] to close immediately or
 //! [`ListenerHandle::close_on`] to close on a future completing.
 //!
+//! To serve only clients on the same machine without opening a port, use
+//! [`WebsocketListener::bind_local`], which listens on a Unix domain socket,
+//! or a named pipe on Windows, and connect to it with [`connect_local`].
+//!
 //! # Example
 //!
 //! ```
@@ -78,18 +82,25 @@
 
 use std::io::Error;
 use std::io::ErrorKind;
+use std::path::Path;
 use std::sync::Arc;
 
 use holochain_serialized_bytes::prelude::*;
 use stream_cancel::Valve;
 use tracing::instrument;
 use url2::Url2;
+use util::addr_to_url;
+use util::path_to_url;
 use util::url_to_addr;
+use util::RawSocket;
 use websocket::Websocket;
 
 mod websocket_config;
 pub use websocket_config::*;
 
+mod wire_format;
+pub use wire_format::WireFormat;
+
 #[allow(missing_docs)]
 mod error;
 pub use error::*;
@@ -119,20 +130,71 @@ pub async fn connect(
     // socket.set_keepalive(Some(std::time::Duration::from_secs(
     //     config.tcp_keepalive_s as u64,
     // )))?;
-    let (socket, _) = tokio_tungstenite::client_async_with_config(
-        url.as_str(),
-        socket,
-        Some(config.to_tungstenite()),
+    let remote_addr = addr_to_url(socket.peer_addr()?, config.scheme);
+    connect_socket(url, Box::new(socket), remote_addr, config).await
+}
+
+#[instrument(skip(config, path))]
+/// Create a new websocket connection over a local socket,
+/// which is a Unix domain socket, or a named pipe on Windows.
+/// See [`WebsocketListener::bind_local`].
+pub async fn connect_local(
+    path: impl AsRef<Path>,
+    config: Arc<WebsocketConfig>,
+) -> WebsocketResult<(WebsocketSender, WebsocketReceiver)> {
+    let path = path.as_ref();
+    #[cfg(unix)]
+    let socket = tokio::net::UnixStream::connect(path).await?;
+    #[cfg(windows)]
+    let socket = tokio::net::windows::named_pipe::ClientOptions::new().open(path)?;
+    connect_socket(
+        url2::url2!("ws://localhost/"),
+        Box::new(socket),
+        path_to_url(path),
+        config,
     )
     .await
-    .map_err(|e| Error::new(ErrorKind::Other, e))?;
-    tracing::debug!("Client connected");
+}
+
+async fn connect_socket(
+    url: Url2,
+    socket: Box<dyn RawSocket>,
+    remote_addr: Url2,
+    config: Arc<WebsocketConfig>,
+) -> WebsocketResult<(WebsocketSender, WebsocketReceiver)> {
+    use tungstenite::client::IntoClientRequest;
+    use tungstenite::http::header::HeaderValue;
+    use tungstenite::http::header::SEC_WEBSOCKET_PROTOCOL;
+
+    let mut request = url
+        .as_str()
+        .into_client_request()
+        .map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
+    // Msgpack is used when no subprotocol is requested,
+    // which works with listeners that don't negotiate formats.
+    if config.wire_format != WireFormat::Msgpack {
+        request.headers_mut().insert(
+            SEC_WEBSOCKET_PROTOCOL,
+            HeaderValue::from_static(config.wire_format.subprotocol()),
+        );
+    }
+    let (socket, response) =
+        tokio_tungstenite::client_async_with_config(request, socket, Some(config.to_tungstenite()))
+            .await
+            .map_err(|e| Error::new(ErrorKind::Other, e))?;
+    let wire_format = response
+        .headers()
+        .get(SEC_WEBSOCKET_PROTOCOL)
+        .and_then(|protocol| protocol.to_str().ok())
+        .and_then(WireFormat::negotiate)
+        .unwrap_or_default();
+    tracing::debug!(?wire_format, "Client connected");
 
     // Noop valve because we don't have a listener to shutdown the
     // ends when creating a client
     let (exit, valve) = Valve::new();
     exit.disable();
-    Websocket::create_ends(config, socket, valve)
+    Websocket::create_ends(config, socket, remote_addr, wire_format, valve)
 }
 
 #[derive(Debug, serde::Serialize, serde::Deserialize, SerializedBytes)]
@@ -140,6 +202,8 @@ pub async fn connect(
 /// The messages actually sent over the wire by this library.
 /// If you want to impliment your own server or client you
 /// will need this type or be able to serialize / deserialize it.
+/// Over [`WireFormat::Json`] connections these are sent as JSON,
+/// with the data as JSON values rather than bytes.
 pub enum WireMessage {
     /// A message without a response.
     Signal {
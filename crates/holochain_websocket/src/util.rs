@@ -1,21 +1,82 @@
 //! internal websocket utility types and code
 
 use std::net::SocketAddr;
+use std::path::Path;
 
 use url2::{url2, Url2};
 
+use crate::WebsocketConfig;
+
 use std::io::{Error, ErrorKind, Result};
 
-pub(crate) type ToFromSocket = tokio_tungstenite::WebSocketStream<tokio::net::TcpStream>;
+pub(crate) type ToFromSocket = tokio_tungstenite::WebSocketStream<Box<dyn RawSocket>>;
+
+/// A stream a websocket can run over,
+/// e.g. a tcp socket or a unix domain socket.
+pub trait RawSocket: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin + Send + 'static {}
+
+impl<T> RawSocket for T where
+    T: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin + Send + 'static
+{
+}
 
 /// Amount of time to spend waiting for channels to empty before forcing them to close.
 pub(crate) const CLOSE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
 
+/// Pings the other side of a connection at an interval, and notices when
+/// nothing has been received from it for longer than the timeout, as happens
+/// when the other side vanishes and leaves the connection half-open.
+pub(crate) struct Heartbeat {
+    /// When to send the next ping, or `None` if pings are off.
+    interval: Option<tokio::time::Interval>,
+    timeout: std::time::Duration,
+    last_received: tokio::time::Instant,
+}
+
+impl Heartbeat {
+    pub(crate) fn new(config: &WebsocketConfig) -> Self {
+        let interval = (config.heartbeat_interval_s > 0).then(|| {
+            let period = std::time::Duration::from_secs(config.heartbeat_interval_s as u64);
+            tokio::time::interval_at(tokio::time::Instant::now() + period, period)
+        });
+        Self {
+            interval,
+            timeout: std::time::Duration::from_secs(config.heartbeat_timeout_s as u64),
+            last_received: tokio::time::Instant::now(),
+        }
+    }
+
+    /// Note that something was received from the other side.
+    pub(crate) fn received(&mut self) {
+        self.last_received = tokio::time::Instant::now();
+    }
+
+    /// Wait until the next ping is due, which is never if pings are off.
+    pub(crate) async fn tick(&mut self) {
+        match &mut self.interval {
+            Some(interval) => {
+                interval.tick().await;
+            }
+            None => futures::future::pending().await,
+        }
+    }
+
+    /// Has nothing been received for longer than the timeout.
+    pub(crate) fn is_stale(&self) -> bool {
+        self.interval.is_some() && self.last_received.elapsed() > self.timeout
+    }
+}
+
 /// internal helper to convert addrs to urls
 pub(crate) fn addr_to_url(a: SocketAddr, scheme: &str) -> Url2 {
     url2!("{}://{}", scheme, a)
 }
 
+/// internal helper to convert local socket paths to urls
+pub(crate) fn path_to_url(path: &Path) -> Url2 {
+    url2!("local:{}", path.display())
+}
+
 /// internal helper convert urls to socket addrs for binding / connection
 pub(crate) async fn url_to_addr(url: &Url2, scheme: &str) -> Result<SocketAddr> {
     if url.scheme() != scheme || url.host_str().is_none() || url.port().is_none() {
@@ -16,9 +16,10 @@ use ghost_actor::*;
 use tungstenite::protocol::frame::coding::CloseCode;
 use tungstenite::protocol::CloseFrame;
 
-use crate::util::addr_to_url;
+use crate::util::Heartbeat;
 use crate::util::ToFromSocket;
 use crate::util::CLOSE_TIMEOUT;
+use crate::wire_format::JsonWireMessage;
 use crate::CancelResponse;
 use crate::IncomingMessage;
 use crate::OutgoingMessage;
@@ -31,13 +32,15 @@ use crate::WebsocketError;
 use crate::WebsocketReceiver;
 use crate::WebsocketResult;
 use crate::WebsocketSender;
+use crate::WireFormat;
 use crate::WireMessage;
 
 type GhostResult<T> = std::result::Result<T, GhostError>;
 
 #[derive(Debug, Clone)]
-/// Actor that tracks responses.
-pub struct Websocket(GhostActor<WebsocketInner>);
+/// Actor that tracks responses,
+/// and the format of the messages on the wire.
+pub struct Websocket(GhostActor<WebsocketInner>, WireFormat);
 
 #[derive(Debug)]
 struct ResponseTracker {
@@ -125,13 +128,11 @@ impl Websocket {
     pub fn create_ends(
         config: Arc<WebsocketConfig>,
         socket: ToFromSocket,
+        remote_addr: url2::Url2,
+        wire_format: WireFormat,
         listener_shutdown: Valve,
     ) -> WebsocketResult<(WebsocketSender, WebsocketReceiver)> {
-        let remote_addr = url2::url2!(
-            "{}#{}",
-            addr_to_url(socket.get_ref().peer_addr()?, config.scheme),
-            nanoid::nanoid!(),
-        );
+        let remote_addr = url2::url2!("{}#{}", remote_addr, nanoid::nanoid!());
 
         // Channel to the websocket from the application
         let (tx_to_websocket, rx_to_websocket) = tokio::sync::mpsc::channel(config.max_send_queue);
@@ -161,6 +162,8 @@ impl Websocket {
         // Run the to and from external socket tasks.
         Websocket::run(
             socket,
+            wire_format,
+            Heartbeat::new(&config),
             tx_to_websocket.clone(),
             rx_to_websocket_stream,
             tx_from_websocket,
@@ -170,16 +173,23 @@ impl Websocket {
         // Create the sender end.
         let sender = WebsocketSender::new(
             tx_to_websocket,
+            wire_format,
             listener_shutdown,
             pair_shutdown_handle.clone(),
         );
         // Create the receiver end.
-        let receiver = WebsocketReceiver::new(rx_from_websocket, remote_addr, pair_shutdown_handle);
+        let receiver = WebsocketReceiver::new(
+            rx_from_websocket,
+            remote_addr,
+            wire_format,
+            pair_shutdown_handle,
+        );
         Ok((sender, receiver))
     }
 
     #[instrument(skip(
         socket,
+        heartbeat,
         tx_to_websocket,
         rx_to_websocket,
         tx_from_websocket,
@@ -187,6 +197,8 @@ impl Websocket {
     ))]
     fn run(
         socket: ToFromSocket,
+        wire_format: WireFormat,
+        heartbeat: Heartbeat,
         tx_to_websocket: TxToWebsocket,
         rx_to_websocket: RxToWebsocket,
         tx_from_websocket: TxFromWebsocket,
@@ -197,9 +209,10 @@ impl Websocket {
             responses: ResponseTracker::new(),
         });
         tokio::task::spawn(driver);
-        let actor = Self(actor);
+        let actor = Self(actor, wire_format);
         actor.run_socket(
             socket,
+            heartbeat,
             tx_to_websocket,
             rx_to_websocket,
             tx_from_websocket,
@@ -210,6 +223,7 @@ impl Websocket {
     fn run_socket(
         self,
         socket: ToFromSocket,
+        heartbeat: Heartbeat,
         send_response: TxToWebsocket,
         to_websocket: RxToWebsocket,
         from_websocket: TxFromWebsocket,
@@ -239,6 +253,7 @@ impl Websocket {
         tokio::task::spawn(
             self.run_from_socket(
                 from_socket,
+                heartbeat,
                 from_websocket,
                 send_response,
                 shutdown_to_socket,
@@ -275,12 +290,14 @@ impl Websocket {
         }
         // Send close frame so the connection is
         // gracefully shutdown if we can.
+        // The timeout stops a half-open connection from holding up the
+        // shutdown.
         if let Task::Exit = task {
-            to_socket
-                .send(tungstenite::Message::Close(Some(CloseFrame {
-                    code: CloseCode::Normal,
-                    reason: "Shutting down sender".into(),
-                })))
+            let close = to_socket.send(tungstenite::Message::Close(Some(CloseFrame {
+                code: CloseCode::Normal,
+                reason: "Shutting down sender".into(),
+            })));
+            tokio::time::timeout(CLOSE_TIMEOUT, close)
                 .await
                 // If we fail to send there's not much we can do.
                 // Logging this will just create noise on shutdown.
@@ -328,11 +345,18 @@ impl Websocket {
                         to_socket.send(tungstenite::Message::Pong(data)).await.ok();
                         return Task::cont();
                     }
+                    OutgoingMessage::Ping => {
+                        to_socket
+                            .send(tungstenite::Message::Ping(Vec::new()))
+                            .await
+                            .ok();
+                        return Task::cont();
+                    }
                     OutgoingMessage::Debug(tx_requests_debug) => {
                         return self.handle_requests_debug(tx_requests_debug).await;
                     }
                 };
-                let msg = Self::serialize_msg(msg)?;
+                let msg = self.serialize_msg(msg)?;
 
                 // Write to_socket
                 match to_socket.send(msg).await {
@@ -355,6 +379,7 @@ impl Websocket {
     #[instrument(skip(
         self,
         from_socket,
+        heartbeat,
         from_websocket,
         send_response,
         shutdown_to_socket_immediately
@@ -365,6 +390,7 @@ impl Websocket {
         from_socket: impl futures::stream::Stream<
             Item = std::result::Result<tungstenite::Message, tungstenite::Error>,
         >,
+        mut heartbeat: Heartbeat,
         mut from_websocket: TxFromWebsocket,
         mut send_response: TxToWebsocket,
         shutdown_to_socket_immediately: Trigger,
@@ -376,7 +402,25 @@ impl Websocket {
         // Note that this task awaits on the incoming external socket stream
         // and will close when that connection closes.
         loop {
-            let msg = from_socket.next().await;
+            let msg = tokio::select! {
+                msg = from_socket.next() => {
+                    heartbeat.received();
+                    msg
+                }
+                _ = heartbeat.tick() => {
+                    if heartbeat.is_stale() {
+                        // The other side has gone quiet, so the connection
+                        // is most likely half-open and a close frame would
+                        // never be answered.
+                        tracing::warn!("Websocket: Nothing received within the heartbeat timeout");
+                        task = Task::ExitNow;
+                        break;
+                    }
+                    // If the queue is full there's already something to send.
+                    send_response.try_send(OutgoingMessage::Ping).ok();
+                    continue;
+                }
+            };
             if let Err(t) = self
                 .process_from_websocket(msg, &mut from_websocket, &mut send_response)
                 .await
@@ -422,8 +466,8 @@ impl Websocket {
 
                 // Deserialize the incoming wire message.
                 match msg {
-                    tungstenite::Message::Binary(bytes) => {
-                        let msg = Self::deserialize_message(bytes)?;
+                    msg @ (tungstenite::Message::Binary(_) | tungstenite::Message::Text(_)) => {
+                        let msg = self.deserialize_message(msg)?;
                         let (msg, resp) = match msg {
                             WireMessage::Signal { data } => {
                                 (Self::deserialize_bytes(data)?, Respond::Signal)
@@ -491,9 +535,9 @@ impl Websocket {
                         send_response.send(OutgoingMessage::Pong(data)).await.ok();
                         Task::cont()
                     }
-                    m => {
-                        // Received a text message which we don't support.
-                        tracing::error!("Websocket: Bad message type {:?}", m);
+                    tungstenite::Message::Pong(_) => {
+                        // An answer to a heartbeat ping, which has already
+                        // been noted as received.
                         Task::cont()
                     }
                 }
@@ -678,29 +722,48 @@ impl Websocket {
 
     /// Try to serialize the wire message and continue to next
     /// message if failure.
-    fn serialize_msg(msg: WireMessage) -> Loop<tungstenite::Message> {
-        let msg: SerializedBytes = match msg.try_into() {
-            Ok(msg) => msg,
+    fn serialize_msg(&self, msg: WireMessage) -> Loop<tungstenite::Message> {
+        let msg = match self.1 {
+            WireFormat::Msgpack => SerializedBytes::try_from(msg)
+                .map(|msg| tungstenite::Message::Binary(UnsafeBytes::from(msg).into())),
+            WireFormat::Json => JsonWireMessage::from_wire(msg).and_then(|msg| {
+                serde_json::to_string(&msg)
+                    .map(tungstenite::Message::Text)
+                    .map_err(|e| SerializedBytesError::Serialize(e.to_string()))
+            }),
+        };
+        match msg {
+            Ok(msg) => Ok(msg),
             Err(e) => {
                 tracing::error!("Websocket: Message failed to serialize {:?}", e);
                 // Should not kill the websocket just because a single message
                 // failed serialization.
-                return Task::cont();
+                Task::cont()
             }
-        };
-        let bytes: Vec<u8> = UnsafeBytes::from(msg).into();
-
-        let msg = tungstenite::Message::Binary(bytes);
-        Ok(msg)
+        }
     }
 
     /// Try to deserialize the wire message and continue to next
     /// message if failure.
-    fn deserialize_message(bytes: Vec<u8>) -> Loop<WireMessage> {
-        match SerializedBytes::try_from(UnsafeBytes::from(bytes))
-            .map_err(WebsocketError::from)
-            .and_then(|sb| Ok(WireMessage::try_from(sb)?))
-        {
+    fn deserialize_message(&self, msg: tungstenite::Message) -> Loop<WireMessage> {
+        let msg = match (self.1, msg) {
+            (WireFormat::Msgpack, tungstenite::Message::Binary(bytes)) => {
+                SerializedBytes::try_from(UnsafeBytes::from(bytes))
+                    .map_err(WebsocketError::from)
+                    .and_then(|sb| Ok(WireMessage::try_from(sb)?))
+            }
+            (WireFormat::Json, tungstenite::Message::Text(text)) => {
+                serde_json::from_str::<JsonWireMessage>(&text)
+                    .map_err(|e| SerializedBytesError::Deserialize(e.to_string()))
+                    .and_then(JsonWireMessage::into_wire)
+                    .map_err(WebsocketError::from)
+            }
+            (wire_format, m) => {
+                tracing::error!(?wire_format, "Websocket: Bad message type {:?}", m);
+                return Task::cont();
+            }
+        };
+        match msg {
             Ok(msg) => Ok(msg),
             Err(e) => {
                 tracing::error!("Websocket failed to deserialize {:?}", e,);
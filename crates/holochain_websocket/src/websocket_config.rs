@@ -1,5 +1,7 @@
 //! defines a builder-style config struct for setting up websockets
 
+use crate::WireFormat;
+
 /// A builder-style config struct for setting up websockets.
 #[derive(Debug)]
 pub struct WebsocketConfig {
@@ -15,6 +17,16 @@ pub struct WebsocketConfig {
     /// [default = 10]
     pub tcp_keepalive_s: usize,
 
+    /// Seconds between the pings sent to the other side, to keep the
+    /// connection alive and find out when it has silently gone away,
+    /// or 0 to send none. [default = 30]
+    pub heartbeat_interval_s: usize,
+
+    /// Seconds without receiving anything, not even a pong, after which the
+    /// connection is treated as dead and closed. Only applies if pings are
+    /// sent. [default = 90]
+    pub heartbeat_timeout_s: usize,
+
     /// How many items are allowed in the outgoing queue. [default = 10]
     pub max_send_queue: usize,
 
@@ -26,6 +38,21 @@ pub struct WebsocketConfig {
 
     /// Maximum number of pending new incoming connections. [default = 255]
     pub max_pending_connections: usize,
+
+    /// The `Origin` headers a listener accepts connections from, or `None`
+    /// to accept any origin. Connections without an `Origin` header, which
+    /// browsers always send, are accepted either way, and ones with an
+    /// `Origin` header which isn't valid UTF-8 are always rejected.
+    /// [default = None]
+    pub allowed_origins: Option<Vec<String>>,
+
+    /// The [`WireFormat`] a client requests when connecting. The listener may
+    /// not support it, so check [`WebsocketSender::wire_format`] to see what
+    /// was agreed. Listeners use whichever format each client requests.
+    /// [default = Msgpack]
+    ///
+    /// [`WebsocketSender::wire_format`]: crate::WebsocketSender::wire_format
+    pub wire_format: WireFormat,
 }
 
 impl Default for WebsocketConfig {
@@ -34,10 +61,14 @@ impl Default for WebsocketConfig {
             scheme: "ws",
             default_request_timeout_s: 30,
             tcp_keepalive_s: 30,
+            heartbeat_interval_s: 30,
+            heartbeat_timeout_s: 90,
             max_send_queue: 10,
             max_message_size: 64 << 20,
             max_frame_size: 16 << 20,
             max_pending_connections: 255,
+            allowed_origins: None,
+            wire_format: WireFormat::Msgpack,
         }
     }
 }
@@ -61,6 +92,18 @@ impl WebsocketConfig {
         self
     }
 
+    /// Builder-style setter.
+    pub fn heartbeat_interval_s(mut self, s: usize) -> Self {
+        self.heartbeat_interval_s = s;
+        self
+    }
+
+    /// Builder-style setter.
+    pub fn heartbeat_timeout_s(mut self, s: usize) -> Self {
+        self.heartbeat_timeout_s = s;
+        self
+    }
+
     /// Builder-style setter.
     pub fn max_send_queue(mut self, max: usize) -> Self {
         self.max_send_queue = max;
@@ -78,6 +121,26 @@ impl WebsocketConfig {
         self.max_frame_size = max;
         self
     }
+
+    /// Builder-style setter.
+    pub fn allowed_origins(mut self, origins: Option<Vec<String>>) -> Self {
+        self.allowed_origins = origins;
+        self
+    }
+
+    /// Builder-style setter.
+    pub fn wire_format(mut self, wire_format: WireFormat) -> Self {
+        self.wire_format = wire_format;
+        self
+    }
+
+    /// Does this config accept a connection from this origin.
+    pub(crate) fn is_origin_allowed(&self, origin: Option<&str>) -> bool {
+        match (&self.allowed_origins, origin) {
+            (Some(allowed), Some(origin)) => allowed.iter().any(|a| a == "*" || a == origin),
+            _ => true,
+        }
+    }
 }
 
 /// internal helper to convert our configs into tungstenite configs
@@ -4,6 +4,7 @@ use futures::TryStreamExt;
 use std::io::Error;
 use std::io::ErrorKind;
 use std::net::SocketAddr;
+use std::path::Path;
 use std::sync::Arc;
 use stream_cancel::Trigger;
 use stream_cancel::Valve;
@@ -12,13 +13,16 @@ use tracing::instrument;
 use url2::Url2;
 
 use crate::util::addr_to_url;
+use crate::util::path_to_url;
 use crate::util::url_to_addr;
+use crate::util::RawSocket;
 use crate::websocket::Websocket;
 use crate::WebsocketConfig;
 use crate::WebsocketError;
 use crate::WebsocketReceiver;
 use crate::WebsocketResult;
 use crate::WebsocketSender;
+use crate::WireFormat;
 
 /// Listens for connecting clients.
 ///
@@ -106,6 +110,79 @@ impl WebsocketListener {
     )> {
         websocket_bind(addr, config).await
     }
+
+    /// Bind to a local socket to accept incoming connections from this
+    /// machine only, without opening a port.
+    ///
+    /// On Unix this is a Unix domain socket at `path`, which is created with
+    /// the file permissions `mode`, e.g. `0o600` so only this user can
+    /// connect. An existing socket at `path` is replaced.
+    ///
+    /// On Windows this is a named pipe such as `\\.\pipe\my-pipe`, which
+    /// rejects remote clients. `mode` is ignored, and the pipe has the
+    /// default access control list, which only lets the creating user,
+    /// administrators and the system connect.
+    pub async fn bind_local(
+        path: impl AsRef<Path>,
+        mode: u32,
+        config: Arc<WebsocketConfig>,
+    ) -> WebsocketResult<Self> {
+        let (handle, stream) = Self::bind_local_with_handle(path.as_ref(), mode, config).await?;
+        Ok(Self {
+            handle,
+            stream: stream.boxed(),
+        })
+    }
+
+    #[instrument(skip(config, path))]
+    /// Same as [`WebsocketListener::bind_local`] but gives you a [`ListenerHandle`]
+    /// to shutdown the listener and any open connections.
+    pub async fn bind_local_with_handle(
+        path: &Path,
+        mode: u32,
+        config: Arc<WebsocketConfig>,
+    ) -> WebsocketResult<(
+        ListenerHandle,
+        impl futures::stream::Stream<Item = ListenerItem>,
+    )> {
+        local_bind(path, mode, config)
+    }
+
+    /// Accept connections on an already bound TCP listener, such as one
+    /// inherited from a service manager, giving you a [`ListenerHandle`]
+    /// to shutdown the listener and any open connections.
+    pub fn from_std_with_handle(
+        listener: std::net::TcpListener,
+        config: Arc<WebsocketConfig>,
+    ) -> WebsocketResult<(
+        ListenerHandle,
+        impl futures::stream::Stream<Item = ListenerItem>,
+    )> {
+        tcp_listen(listener, config)
+    }
+
+    /// Accept connections on an already bound Unix domain socket, such as
+    /// one inherited from a service manager, giving you a [`ListenerHandle`]
+    /// to shutdown the listener and any open connections.
+    #[cfg(unix)]
+    pub fn from_std_local_with_handle(
+        listener: std::os::unix::net::UnixListener,
+        config: Arc<WebsocketConfig>,
+    ) -> WebsocketResult<(
+        ListenerHandle,
+        impl futures::stream::Stream<Item = ListenerItem>,
+    )> {
+        let local_addr = match listener.local_addr()?.as_pathname() {
+            Some(path) => path_to_url(path),
+            None => url2::url2!("local:"),
+        };
+        let remote_addr = local_addr.clone();
+        listener.set_nonblocking(true)?;
+        let listener = tokio::net::UnixListener::from_std(listener)?;
+        let listener_stream = tokio_stream::wrappers::UnixListenerStream::new(listener)
+            .map_ok(move |socket| (Box::new(socket) as Box<dyn RawSocket>, remote_addr.clone()));
+        Ok(listen(listener_stream, local_addr, config))
+    }
     /// Shutdown the listener stream.
     pub fn close(self) {
         self.handle.close()
@@ -199,11 +276,125 @@ async fn websocket_bind(
     .reuse_address(true)?
     .bind(addr)?
     .listen(config.max_pending_connections as i32)?;
+    tcp_listen(socket, config)
+}
+
+fn tcp_listen(
+    socket: std::net::TcpListener,
+    config: Arc<WebsocketConfig>,
+) -> WebsocketResult<(
+    ListenerHandle,
+    impl futures::stream::Stream<Item = ListenerItem>,
+)> {
     socket.set_nonblocking(true)?;
     let local_addr = addr_to_url(socket.local_addr()?, config.scheme);
     let listener = tokio::net::TcpListener::from_std(socket)?;
-    let listener_stream = tokio_stream::wrappers::TcpListenerStream::new(listener);
+    let scheme = config.scheme;
+    let listener_stream =
+        tokio_stream::wrappers::TcpListenerStream::new(listener).map(move |socket| {
+            let socket = socket?;
+            let remote_addr = addr_to_url(socket.peer_addr()?, scheme);
+            std::io::Result::Ok((Box::new(socket) as Box<dyn RawSocket>, remote_addr))
+        });
+    Ok(listen(listener_stream, local_addr, config))
+}
+
+fn local_bind(
+    path: &Path,
+    mode: u32,
+    config: Arc<WebsocketConfig>,
+) -> WebsocketResult<(
+    ListenerHandle,
+    impl futures::stream::Stream<Item = ListenerItem>,
+)> {
+    let local_addr = path_to_url(path);
+    let remote_addr = local_addr.clone();
+    let listener_stream =
+        local_socket_stream(path, mode)?.map_ok(move |socket| (socket, remote_addr.clone()));
+    Ok(listen(listener_stream, local_addr, config))
+}
+
+/// Bind a unix domain socket, setting its permissions before any client
+/// can connect by binding it in a private directory, then moving it to `path`.
+#[cfg(unix)]
+fn local_socket_stream(
+    path: &Path,
+    mode: u32,
+) -> WebsocketResult<BoxStream<'static, std::io::Result<Box<dyn RawSocket>>>> {
+    use std::os::unix::fs::DirBuilderExt;
+    use std::os::unix::fs::FileTypeExt;
+    use std::os::unix::fs::PermissionsExt;
+
+    if let Ok(metadata) = std::fs::symlink_metadata(path) {
+        if !metadata.file_type().is_socket() {
+            return Err(Error::new(
+                ErrorKind::AlreadyExists,
+                format!("{} exists and is not a socket", path.display()),
+            )
+            .into());
+        }
+    }
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "socket path has no file name"))?;
+    let private_dir = path.with_file_name(format!(".{}", nanoid::nanoid!()));
+    std::fs::DirBuilder::new()
+        .mode(0o700)
+        .create(&private_dir)?;
+    let bind = || {
+        let private_path = private_dir.join(file_name);
+        let listener = std::os::unix::net::UnixListener::bind(&private_path)?;
+        std::fs::set_permissions(&private_path, std::fs::Permissions::from_mode(mode))?;
+        std::fs::rename(&private_path, path)?;
+        std::io::Result::Ok(listener)
+    };
+    let listener = bind();
+    let _ = std::fs::remove_dir_all(&private_dir);
+    let listener = listener?;
+    listener.set_nonblocking(true)?;
+    let listener = tokio::net::UnixListener::from_std(listener)?;
+    Ok(tokio_stream::wrappers::UnixListenerStream::new(listener)
+        .map_ok(|socket| Box::new(socket) as Box<dyn RawSocket>)
+        .boxed())
+}
+
+/// Create a named pipe, creating the next instance of it each time a client connects.
+#[cfg(windows)]
+fn local_socket_stream(
+    path: &Path,
+    _mode: u32,
+) -> WebsocketResult<BoxStream<'static, std::io::Result<Box<dyn RawSocket>>>> {
+    use tokio::net::windows::named_pipe::ServerOptions;
+
+    let server = ServerOptions::new()
+        .first_pipe_instance(true)
+        .reject_remote_clients(true)
+        .create(path)?;
+    let path = path.to_owned();
+    Ok(
+        futures::stream::try_unfold((server, path), |(server, path)| async move {
+            server.connect().await?;
+            let next = ServerOptions::new()
+                .reject_remote_clients(true)
+                .create(&path)?;
+            Ok(Some((Box::new(server) as Box<dyn RawSocket>, (next, path))))
+        })
+        .boxed(),
+    )
+}
 
+/// A socket accepted by a listener, and the address of its client.
+type Accepted = (Box<dyn RawSocket>, Url2);
+
+/// Accept websocket connections on the sockets of a listener.
+fn listen(
+    listener_stream: impl futures::stream::Stream<Item = std::io::Result<Accepted>>,
+    local_addr: Url2,
+    config: Arc<WebsocketConfig>,
+) -> (
+    ListenerHandle,
+    impl futures::stream::Stream<Item = ListenerItem>,
+) {
     // Setup proper shutdown
     let (shutdown, valve) = Valve::new();
 
@@ -212,7 +403,7 @@ async fn websocket_bind(
         .map_ok({
             let config = config.clone();
             let valve = valve.clone();
-            move |socket_result| connect(config.clone(), socket_result, valve.clone())
+            move |(socket, remote_addr)| connect(config.clone(), socket, remote_addr, valve.clone())
         })
         .try_buffer_unordered(config.max_pending_connections);
     tracing::debug!(sever_listening_on = ?local_addr);
@@ -224,13 +415,14 @@ async fn websocket_bind(
         config,
         local_addr,
     };
-    Ok((listener_handle, stream))
+    (listener_handle, stream)
 }
 
 #[instrument(skip(config, socket, valve))]
 async fn connect(
     config: Arc<WebsocketConfig>,
-    socket: tokio::net::TcpStream,
+    socket: Box<dyn RawSocket>,
+    remote_addr: Url2,
     valve: Valve,
 ) -> WebsocketResult<Pair> {
     // TODO: find alternative to set the keepalive
@@ -239,10 +431,49 @@ async fn connect(
     // )))?;
     tracing::debug!(
         message = "accepted incoming raw socket",
-        remote_addr = %socket.peer_addr()?,
+        remote_addr = %remote_addr,
     );
-    let socket = tokio_tungstenite::accept_async_with_config(
+    // The client chooses the wire format with the subprotocol it requests.
+    let wire_format = std::sync::Mutex::new(WireFormat::Msgpack);
+    let handshake = {
+        let config = config.clone();
+        let wire_format = &wire_format;
+        move |request: &tungstenite::handshake::server::Request,
+              mut response: tungstenite::handshake::server::Response| {
+            let origin = request.headers().get(tungstenite::http::header::ORIGIN);
+            // An origin which isn't valid UTF-8 can't be one of the allowed
+            // origins, so it is rejected rather than treated as missing.
+            let allowed = match origin.map(|origin| origin.to_str()) {
+                Some(Ok(origin)) => config.is_origin_allowed(Some(origin)),
+                Some(Err(_)) => false,
+                None => config.is_origin_allowed(None),
+            };
+            if !allowed {
+                tracing::warn!(?origin, "rejected websocket connection from origin");
+                let mut response = tungstenite::handshake::server::ErrorResponse::new(Some(
+                    "origin not allowed".to_string(),
+                ));
+                *response.status_mut() = tungstenite::http::StatusCode::FORBIDDEN;
+                return Err(response);
+            }
+            if let Some(format) = request
+                .headers()
+                .get(tungstenite::http::header::SEC_WEBSOCKET_PROTOCOL)
+                .and_then(|protocols| protocols.to_str().ok())
+                .and_then(WireFormat::negotiate)
+            {
+                response.headers_mut().insert(
+                    tungstenite::http::header::SEC_WEBSOCKET_PROTOCOL,
+                    tungstenite::http::HeaderValue::from_static(format.subprotocol()),
+                );
+                *wire_format.lock().expect("not poisoned") = format;
+            }
+            Ok(response)
+        }
+    };
+    let socket = tokio_tungstenite::accept_hdr_async_with_config(
         socket,
+        handshake,
         Some(tungstenite::protocol::WebSocketConfig {
             max_send_queue: Some(config.max_send_queue),
             max_message_size: Some(config.max_message_size),
@@ -252,6 +483,7 @@ async fn connect(
     )
     .await
     .map_err(|e| Error::new(ErrorKind::Other, e))?;
+    let wire_format = wire_format.into_inner().expect("not poisoned");
 
-    Websocket::create_ends(config, socket, valve)
+    Websocket::create_ends(config, socket, remote_addr, wire_format, valve)
 }
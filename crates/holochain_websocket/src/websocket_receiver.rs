@@ -11,6 +11,7 @@ use crate::websocket::RxFromWebsocket;
 use crate::websocket::TxToWebsocket;
 use crate::OutgoingMessage;
 use crate::WebsocketResult;
+use crate::WireFormat;
 
 /// Receive signals and requests from a connection.
 ///
@@ -38,6 +39,7 @@ use crate::WebsocketResult;
 pub struct WebsocketReceiver {
     rx_from_websocket: Valved<Valved<RxFromWebsocket>>,
     remote_addr: Url2,
+    wire_format: WireFormat,
     handle: Option<ReceiverHandle>,
     __pair_shutdown: Arc<PairShutdown>,
 }
@@ -85,6 +87,7 @@ impl WebsocketReceiver {
     pub(crate) fn new(
         rx_from_websocket: Valved<RxFromWebsocket>,
         remote_addr: Url2,
+        wire_format: WireFormat,
         pair_shutdown: Arc<PairShutdown>,
     ) -> Self {
         let (shutdown, rx_from_websocket_valved) = Valved::new(rx_from_websocket);
@@ -92,6 +95,7 @@ impl WebsocketReceiver {
         Self {
             rx_from_websocket: rx_from_websocket_valved,
             remote_addr,
+            wire_format,
             handle,
             __pair_shutdown: pair_shutdown,
         }
@@ -105,6 +109,11 @@ impl WebsocketReceiver {
     pub fn remote_addr(&self) -> &Url2 {
         &self.remote_addr
     }
+    /// The format of the messages on this connection. Messages received over
+    /// [`WireFormat::Json`] connections are JSON, and should be responded to with JSON.
+    pub fn wire_format(&self) -> WireFormat {
+        self.wire_format
+    }
 }
 
 impl futures::stream::Stream for WebsocketReceiver {
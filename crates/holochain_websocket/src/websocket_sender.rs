@@ -9,6 +9,7 @@ use websocket::TxToWebsocket;
 use crate::websocket;
 use crate::WebsocketError;
 use crate::WebsocketResult;
+use crate::WireFormat;
 use std::convert::TryFrom;
 use std::convert::TryInto;
 use std::sync::Arc;
@@ -54,6 +55,7 @@ use std::sync::Arc;
 /// ```
 pub struct WebsocketSender {
     tx_to_websocket: TxToWebsocket,
+    wire_format: WireFormat,
     listener_shutdown: Valve,
     __pair_shutdown: Arc<PairShutdown>,
 }
@@ -97,6 +99,7 @@ pub(crate) enum OutgoingMessage {
     Response(Option<SerializedBytes>, u64),
     StaleRequest(u64),
     Pong(Vec<u8>),
+    Ping,
     #[allow(dead_code)]
     Debug(TxRequestsDebug),
 }
@@ -104,16 +107,25 @@ pub(crate) enum OutgoingMessage {
 impl WebsocketSender {
     pub(crate) fn new(
         tx_to_websocket: TxToWebsocket,
+        wire_format: WireFormat,
         listener_shutdown: Valve,
         pair_shutdown: Arc<PairShutdown>,
     ) -> Self {
         Self {
             tx_to_websocket,
+            wire_format,
             listener_shutdown,
             __pair_shutdown: pair_shutdown,
         }
     }
 
+    /// The format of the messages on this connection.
+    /// Over [`WireFormat::Json`] connections, [`WebsocketSender::request`]
+    /// sends and receives JSON, and signals should be JSON.
+    pub fn wire_format(&self) -> WireFormat {
+        self.wire_format
+    }
+
     #[tracing::instrument(skip(self))]
     /// Make a request to for the other side to respond to.
     pub async fn request_timeout<I, O>(
@@ -155,11 +167,13 @@ impl WebsocketSender {
         let (tx_stale_resp, rx_stale_resp) = tokio::sync::oneshot::channel();
         let mut rx_resp = self.listener_shutdown.wrap(rx_resp.into_stream());
         let resp = RegisterResponse::new(tx_resp);
-        let msg = OutgoingMessage::Request(
-            hsb::UnsafeBytes::from(hsb::encode(&msg)?).try_into()?,
-            resp,
-            tx_stale_resp,
-        );
+        let msg = match self.wire_format {
+            WireFormat::Msgpack => hsb::encode(&msg)?,
+            WireFormat::Json => serde_json::to_vec(&msg)
+                .map_err(|e| SerializedBytesError::Serialize(e.to_string()))?,
+        };
+        let msg =
+            OutgoingMessage::Request(hsb::UnsafeBytes::from(msg).try_into()?, resp, tx_stale_resp);
 
         self.tx_to_websocket
             .send(msg)
@@ -176,7 +190,12 @@ impl WebsocketSender {
             .ok_or(WebsocketError::Shutdown)?
             .map_err(|_| WebsocketError::FailedToRecvResp)?
             .ok_or(WebsocketError::FailedToRecvResp)?;
-        let resp: O = hsb::decode(&Vec::from(hsb::UnsafeBytes::from(sb)))?;
+        let bytes = Vec::from(hsb::UnsafeBytes::from(sb));
+        let resp: O = match self.wire_format {
+            WireFormat::Msgpack => hsb::decode(&bytes)?,
+            WireFormat::Json => serde_json::from_slice(&bytes)
+                .map_err(|e| SerializedBytesError::Deserialize(e.to_string()))?,
+        };
         stale_request_guard.response_received();
         Ok(resp)
     }
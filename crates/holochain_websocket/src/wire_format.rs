@@ -0,0 +1,121 @@
+//! Negotiation of the encoding of messages on the wire.
+
+use holochain_serialized_bytes::SerializedBytesError;
+
+use crate::WireMessage;
+
+/// The encoding of the messages sent over a connection, which the client
+/// chooses by requesting the matching websocket subprotocol in the
+/// `Sec-WebSocket-Protocol` header of the handshake.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WireFormat {
+    /// [Message pack](https://msgpack.org/) in binary frames.
+    /// This is used when the client requests no known subprotocol.
+    Msgpack,
+    /// JSON in text frames, where the data of each [`WireMessage`] is a
+    /// JSON value rather than bytes. The data of messages sent and received
+    /// over these connections is JSON rather than msgpack.
+    Json,
+}
+
+impl Default for WireFormat {
+    fn default() -> Self {
+        WireFormat::Msgpack
+    }
+}
+
+impl WireFormat {
+    /// The websocket subprotocol a client requests to use this format.
+    pub fn subprotocol(&self) -> &'static str {
+        match self {
+            WireFormat::Msgpack => "holochain-msgpack",
+            WireFormat::Json => "holochain-json",
+        }
+    }
+
+    /// The first format in a `Sec-WebSocket-Protocol` header we support.
+    pub(crate) fn negotiate(protocols: &str) -> Option<Self> {
+        protocols
+            .split(',')
+            .map(str::trim)
+            .find_map(|protocol| match protocol {
+                "holochain-msgpack" => Some(WireFormat::Msgpack),
+                "holochain-json" => Some(WireFormat::Json),
+                _ => None,
+            })
+    }
+}
+
+/// A [`WireMessage`] as sent over a [`WireFormat::Json`] connection.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type")]
+pub(crate) enum JsonWireMessage {
+    Signal {
+        data: serde_json::Value,
+    },
+    Request {
+        id: u64,
+        data: serde_json::Value,
+    },
+    Response {
+        id: u64,
+        data: Option<serde_json::Value>,
+    },
+}
+
+impl JsonWireMessage {
+    /// Embed the JSON data of a wire message.
+    pub(crate) fn from_wire(msg: WireMessage) -> Result<Self, SerializedBytesError> {
+        let value = |data: Vec<u8>| {
+            serde_json::from_slice(&data)
+                .map_err(|e| SerializedBytesError::Serialize(e.to_string()))
+        };
+        Ok(match msg {
+            WireMessage::Signal { data } => JsonWireMessage::Signal { data: value(data)? },
+            WireMessage::Request { id, data } => JsonWireMessage::Request {
+                id,
+                data: value(data)?,
+            },
+            WireMessage::Response { id, data } => JsonWireMessage::Response {
+                id,
+                data: data.map(value).transpose()?,
+            },
+        })
+    }
+
+    /// Extract the JSON data of a wire message.
+    pub(crate) fn into_wire(self) -> Result<WireMessage, SerializedBytesError> {
+        let bytes = |data: serde_json::Value| {
+            serde_json::to_vec(&data).map_err(|e| SerializedBytesError::Deserialize(e.to_string()))
+        };
+        Ok(match self {
+            JsonWireMessage::Signal { data } => WireMessage::Signal { data: bytes(data)? },
+            JsonWireMessage::Request { id, data } => WireMessage::Request {
+                id,
+                data: bytes(data)?,
+            },
+            JsonWireMessage::Response { id, data } => WireMessage::Response {
+                id,
+                data: data.map(bytes).transpose()?,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn negotiates_first_supported_format() {
+        assert_eq!(
+            WireFormat::negotiate("holochain-json"),
+            Some(WireFormat::Json)
+        );
+        assert_eq!(
+            WireFormat::negotiate("chat, holochain-msgpack, holochain-json"),
+            Some(WireFormat::Msgpack)
+        );
+        assert_eq!(WireFormat::negotiate("chat"), None);
+    }
+}
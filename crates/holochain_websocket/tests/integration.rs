@@ -3,13 +3,16 @@ use std::sync::Arc;
 use futures::StreamExt;
 use holochain_serialized_bytes::prelude::*;
 use holochain_websocket::connect;
+use holochain_websocket::connect_local;
 use holochain_websocket::ListenerHandle;
 use holochain_websocket::ListenerItem;
 use holochain_websocket::WebsocketConfig;
 use holochain_websocket::WebsocketError;
 use holochain_websocket::WebsocketListener;
+use holochain_websocket::WireFormat;
 use stream_cancel::Tripwire;
 use tracing::Instrument;
+use tungstenite::http::HeaderValue;
 use url2::url2;
 
 #[derive(Debug, serde::Serialize, serde::Deserialize, SerializedBytes)]
@@ -121,6 +124,158 @@ async fn can_connect() {
         .expect("Failed to connect to server");
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn rejects_disallowed_origins() {
+    observability::test_run().ok();
+    let config =
+        WebsocketConfig::default().allowed_origins(Some(vec!["http://localhost:8888".to_string()]));
+    let (handle, mut listener) =
+        WebsocketListener::bind_with_handle(url2!("ws://127.0.0.1:0"), Arc::new(config))
+            .await
+            .unwrap();
+    let binding = handle.local_addr().clone();
+    let connect_from = |origin: tungstenite::http::HeaderValue| {
+        let request = tungstenite::http::Request::builder()
+            .uri(binding.as_str())
+            .header("Origin", origin)
+            .body(())
+            .unwrap();
+        tokio_tungstenite::connect_async(request)
+    };
+
+    let (client, server) = tokio::join!(
+        connect_from(HeaderValue::from_static("http://evil.example")),
+        listener.next()
+    );
+    assert!(client.is_err());
+    assert!(server.unwrap().is_err());
+
+    // An origin which isn't UTF-8 isn't treated as a missing one.
+    let (client, server) = tokio::join!(
+        connect_from(HeaderValue::from_bytes(b"http://localhost:8888\xff").unwrap()),
+        listener.next()
+    );
+    assert!(client.is_err());
+    assert!(server.unwrap().is_err());
+
+    let (client, server) = tokio::join!(
+        connect_from(HeaderValue::from_static("http://localhost:8888")),
+        listener.next()
+    );
+    assert!(client.is_ok());
+    assert!(server.unwrap().is_ok());
+
+    // Clients other than browsers don't send an origin.
+    let (client, server) = tokio::join!(
+        connect(binding.clone(), Arc::new(WebsocketConfig::default())),
+        listener.next()
+    );
+    assert!(client.is_ok());
+    assert!(server.unwrap().is_ok());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn closes_connections_which_stop_answering_pings() {
+    observability::test_run().ok();
+    let config = WebsocketConfig::default()
+        .heartbeat_interval_s(1)
+        .heartbeat_timeout_s(2);
+    let (handle, mut listener) =
+        WebsocketListener::bind_with_handle(url2!("ws://127.0.0.1:0"), Arc::new(config))
+            .await
+            .unwrap();
+    let binding = handle.local_addr().clone();
+
+    // A client which answers pings stays connected.
+    let (client, server) = tokio::join!(
+        connect(binding.clone(), Arc::new(WebsocketConfig::default())),
+        listener.next()
+    );
+    let _client = client.unwrap();
+    let (_sender, mut receiver) = server.unwrap().unwrap();
+    let next = tokio::time::timeout(std::time::Duration::from_secs(4), receiver.next()).await;
+    assert!(next.is_err());
+
+    // A client which is never read from doesn't answer pings, just like
+    // one whose connection is half-open.
+    let (client, server) = tokio::join!(
+        tokio_tungstenite::connect_async(binding.as_str()),
+        listener.next()
+    );
+    let _client = client.unwrap();
+    let (_sender, mut receiver) = server.unwrap().unwrap();
+    let next = tokio::time::timeout(std::time::Duration::from_secs(10), receiver.next()).await;
+    assert!(matches!(next, Ok(None)));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn negotiates_json_wire_format() {
+    observability::test_run().ok();
+    let (handle, mut listener) = server().await;
+    let binding = handle.local_addr().clone();
+    let jh = tokio::task::spawn(async move {
+        let (_sender, mut receiver) = listener.next().await.unwrap().unwrap();
+        assert_eq!(receiver.wire_format(), WireFormat::Json);
+        let (msg, resp) = receiver.next().await.unwrap();
+        assert_eq!(msg.bytes(), br#""Hey from client""#);
+        let response = SerializedBytes::from(UnsafeBytes::from(br#""Bye from server""#.to_vec()));
+        resp.respond(response).await.unwrap();
+    });
+
+    let config = WebsocketConfig::default().wire_format(WireFormat::Json);
+    let (mut sender, _receiver) = connect(binding, Arc::new(config)).await.unwrap();
+    assert_eq!(sender.wire_format(), WireFormat::Json);
+    let msg: TestString = sender
+        .request(TestString("Hey from client".into()))
+        .await
+        .unwrap();
+    assert_eq!(msg.0, "Bye from server");
+
+    jh.await.unwrap();
+}
+
+#[cfg(unix)]
+#[tokio::test(flavor = "multi_thread")]
+async fn can_connect_over_unix_socket() {
+    use std::os::unix::fs::PermissionsExt;
+
+    observability::test_run().ok();
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("websocket.sock");
+    let (_handle, mut listener) = WebsocketListener::bind_local_with_handle(
+        &path,
+        0o600,
+        Arc::new(WebsocketConfig::default()),
+    )
+    .await
+    .unwrap();
+    let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+    // Only the socket is left in the directory.
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+
+    let jh = tokio::task::spawn(async move {
+        let (_sender, mut receiver) = listener.next().await.unwrap().unwrap();
+        let (msg, resp) = receiver.next().await.unwrap();
+        let msg: TestString = msg.try_into().unwrap();
+        assert_eq!(msg.0, "Hey from client");
+        resp.respond(TestString("Bye from server".into()).try_into().unwrap())
+            .await
+            .unwrap();
+    });
+
+    let (mut sender, _receiver) = connect_local(&path, Arc::new(WebsocketConfig::default()))
+        .await
+        .unwrap();
+    let msg: TestString = sender
+        .request(TestString("Hey from client".into()))
+        .await
+        .unwrap();
+    assert_eq!(msg.0, "Bye from server");
+
+    jh.await.unwrap();
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn can_send_signal() {
     observability::test_run().ok();
@@ -25,6 +25,20 @@ impl GetAgentActivityInput {
     }
 }
 
+/// Arguments for a page of an agent's activity.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct GetAgentActivityPageInput {
+    pub agent_pubkey: holo_hash::AgentPubKey,
+    pub chain_query_filter: crate::query::ChainQueryFilter,
+    pub activity_request: crate::query::ActivityRequest,
+    /// Where the page starts, or `None` for the first page.
+    pub cursor: Option<crate::query::ChainCursor>,
+    /// The most chain positions in the page, at least one. Pages of hash
+    /// bounded ranges hold up to this many valid actions instead, along with
+    /// the rejected actions among them.
+    pub limit: u32,
+}
+
 /// Query arguments for the deterministic version of GetAgentActivity
 #[derive(serde::Serialize, serde::Deserialize, SerializedBytes, PartialEq, Clone, Debug)]
 pub struct DeterministicGetAgentActivityFilter {
@@ -60,6 +60,21 @@ pub struct DnaDef {
     /// A vector of zomes that do not affect
     /// the [`DnaHash`].
     pub coordinator_zomes: CoordinatorZomes,
+
+    /// Application properties which do not affect the [`DnaHash`], so they
+    /// can be updated while the DNA runs without creating a new network,
+    /// e.g. display names or service URLs.
+    #[serde(default)]
+    #[cfg_attr(feature = "full-dna-def", builder(default))]
+    pub runtime_properties: SerializedBytes,
+
+    /// How many seconds the data of an ephemeral DNA is kept for, e.g. for
+    /// chat which doesn't want a permanent history. Authorities purge ops
+    /// authored longer ago than this, and authors stop publishing them.
+    /// This affects the [`DnaHash`] when set, so the whole network agrees on it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "full-dna-def", builder(default))]
+    pub ephemeral_ttl_secs: Option<u64>,
 }
 
 #[derive(Serialize, Debug, PartialEq, Eq)]
@@ -69,6 +84,8 @@ struct DnaDefHash<'a> {
     network_seed: &'a String,
     properties: &'a SerializedBytes,
     integrity_zomes: &'a IntegrityZomes,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ephemeral_ttl_secs: Option<u64>,
 }
 
 #[cfg(feature = "test_utils")]
@@ -101,6 +118,18 @@ impl DnaDef {
                     .map(|(n, def)| (n, def.as_any_zome_def())),
             )
     }
+
+    /// How long data is kept for, if this is an ephemeral DNA.
+    pub fn ephemeral_ttl(&self) -> Option<std::time::Duration> {
+        self.ephemeral_ttl_secs.map(std::time::Duration::from_secs)
+    }
+
+    /// The time before which ops authored at `now` have expired, if this is
+    /// an ephemeral DNA.
+    pub fn expired_before(&self, now: Timestamp) -> Option<Timestamp> {
+        self.ephemeral_ttl()
+            .map(|ttl| (now - ttl).unwrap_or(Timestamp::MIN))
+    }
 }
 
 #[cfg(feature = "full-dna-def")]
@@ -227,6 +256,7 @@ impl HashableContent for DnaDef {
             network_seed: &self.network_seed,
             properties: &self.properties,
             integrity_zomes: &self.integrity_zomes,
+            ephemeral_ttl_secs: self.ephemeral_ttl_secs,
         };
         HashableContentBytes::Content(
             holochain_serialized_bytes::UnsafeBytes::from(
@@ -55,6 +55,11 @@ pub struct GetOptions {
     /// If it is false you will get whatever is locally
     /// available on this conductor.
     pub strategy: GetStrategy,
+    /// If set, data fetched from the network is only returned when
+    /// at least this many authorities sent the same response.
+    /// If they diverge the get fails rather than picking one.
+    #[serde(default)]
+    pub quorum: Option<u8>,
 }
 
 impl GetOptions {
@@ -69,6 +74,7 @@ impl GetOptions {
     pub fn latest() -> Self {
         Self {
             strategy: GetStrategy::Latest,
+            quorum: None,
         }
     }
     /// Gets the content but does not
@@ -81,8 +87,16 @@ impl GetOptions {
     pub fn content() -> Self {
         Self {
             strategy: GetStrategy::Content,
+            quorum: None,
         }
     }
+
+    /// Require at least this many authorities to agree
+    /// on data fetched from the network.
+    pub fn with_quorum(mut self, quorum: u8) -> Self {
+        self.quorum = Some(quorum);
+        self
+    }
 }
 
 impl Default for GetOptions {
@@ -588,6 +588,7 @@ fixturator!(
             Entry::Agent(_) => EntryType::AgentPubKey,
             Entry::CapClaim(_) => EntryType::CapClaim,
             Entry::CapGrant(_) => EntryType::CapGrant,
+            Entry::Chunk(_) => EntryType::Chunk,
         };
         CreateFixturator::new_indexed(et, get_fixt_index!()).next().unwrap()
     };
@@ -624,6 +625,7 @@ fixturator!(
             Entry::Agent(_) => EntryType::AgentPubKey,
             Entry::CapClaim(_) => EntryType::CapClaim,
             Entry::CapGrant(_) => EntryType::CapGrant,
+            Entry::Chunk(_) => EntryType::Chunk,
         };
         let eh = EntryHash::with_data_sync(&get_fixt_curve!());
         UpdateFixturator::new_indexed((et, eh), get_fixt_index!()).next().unwrap()
@@ -745,6 +747,8 @@ fixturator!(
         coordinator_zomes: CoordinatorZomesFixturator::new_indexed(Empty, get_fixt_index!())
             .next()
             .unwrap(),
+        runtime_properties: SerializedBytes::default(),
+        ephemeral_ttl_secs: None,
     };
 
     curve Unpredictable DnaDef {
@@ -764,6 +768,8 @@ fixturator!(
         coordinator_zomes: CoordinatorZomesFixturator::new_indexed(Empty, get_fixt_index!())
             .next()
             .unwrap(),
+        runtime_properties: SerializedBytes::default(),
+        ephemeral_ttl_secs: None,
     };
 
     curve Predictable DnaDef {
@@ -783,6 +789,8 @@ fixturator!(
         coordinator_zomes: CoordinatorZomesFixturator::new_indexed(Empty, get_fixt_index!())
             .next()
             .unwrap(),
+        runtime_properties: SerializedBytes::default(),
+        ephemeral_ttl_secs: None,
     };
 );
 
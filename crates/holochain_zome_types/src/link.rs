@@ -103,6 +103,55 @@ impl GetLinksInput {
     }
 }
 
+/// An aggregate of the live links on a base.
+/// The host computes it from the links, so the links themselves don't have
+/// to be returned to the zome.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LinkAggregation {
+    /// The number of links.
+    Count,
+    /// Whether there are any links.
+    Exists,
+    /// The distinct tags of the links.
+    DistinctTags,
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct AggregateLinksInput {
+    pub base_address: holo_hash::AnyLinkableHash,
+    /// The link types to include in the aggregate.
+    pub link_type: LinkTypeFilter,
+    pub tag_prefix: Option<crate::link::LinkTag>,
+    pub aggregation: LinkAggregation,
+}
+
+impl AggregateLinksInput {
+    pub fn new(
+        base_address: holo_hash::AnyLinkableHash,
+        link_type: LinkTypeFilter,
+        tag_prefix: Option<crate::link::LinkTag>,
+        aggregation: LinkAggregation,
+    ) -> Self {
+        Self {
+            base_address,
+            link_type,
+            tag_prefix,
+            aggregation,
+        }
+    }
+}
+
+/// The result of a [`LinkAggregation`].
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize, SerializedBytes)]
+pub enum LinkAggregate {
+    /// The number of links.
+    Count(usize),
+    /// Whether there are any links.
+    Exists(bool),
+    /// The distinct tags of the links, in ascending order.
+    DistinctTags(Vec<LinkTag>),
+}
+
 type CreateLinkWithDeleteLinks = Vec<(SignedActionHashed, Vec<SignedActionHashed>)>;
 #[derive(Clone, PartialEq, Debug, serde::Serialize, serde::Deserialize, SerializedBytes)]
 /// CreateLinks with and DeleteLinks on them
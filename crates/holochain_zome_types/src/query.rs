@@ -92,11 +92,97 @@ pub struct AgentActivity {
     /// The highest chain action that has
     /// been observed by this authority.
     pub highest_observed: Option<HighestObserved>,
-    /// Warrants about this AgentActivity.
-    /// Placeholder for future.
+    /// Warrants against the agent of this chain,
+    /// such as for forking it.
     pub warrants: Vec<Warrant>,
 }
 
+/// An opaque position in a source chain, returned with a page of results so
+/// the next page can carry on from it without the earlier pages being read
+/// again.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct ChainCursor(u32);
+
+impl ChainCursor {
+    /// The cursor after the action at this sequence number.
+    pub fn after(action_seq: u32) -> Self {
+        Self(action_seq)
+    }
+
+    /// The sequence number of the last action before the cursor.
+    pub fn action_seq(&self) -> u32 {
+        self.0
+    }
+}
+
+/// Query arguments for a page of a source chain query.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, SerializedBytes)]
+pub struct ChainQueryPageInput {
+    /// The query to page through.
+    pub filter: ChainQueryFilter,
+    /// Where the page starts, or `None` for the first page.
+    pub cursor: Option<ChainCursor>,
+    /// The most records in the page, at least one.
+    pub limit: u32,
+}
+
+/// A page of the records of a source chain query.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, SerializedBytes)]
+pub struct RecordsPage {
+    /// The records of the page, in chain order.
+    pub records: Vec<Record>,
+    /// Where the next page starts, or `None` if this is the last page.
+    pub next: Option<ChainCursor>,
+}
+
+/// A page of an agent's activity.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, SerializedBytes)]
+pub struct AgentActivityPage {
+    /// The actions of the page. The status, highest observed action and
+    /// warrants are still those of the whole chain.
+    pub activity: AgentActivity,
+    /// Where the next page starts, or `None` if this is the last page.
+    pub next: Option<ChainCursor>,
+}
+
+impl AgentActivity {
+    /// Make a page of activity which was fetched with its sequence range
+    /// narrowed by [`ChainQueryFilter::page`] to end at `page_end`. There is
+    /// a next page if both the chain and the range being paged through, which
+    /// ends at `range_end`, carry on past the page.
+    pub fn into_bounded_page(self, page_end: u32, range_end: u32) -> AgentActivityPage {
+        let chain_end = self.highest_observed.as_ref().map(|h| h.action_seq);
+        let next = (page_end < range_end && chain_end.map_or(false, |end| page_end < end))
+            .then(|| ChainCursor::after(page_end));
+        AgentActivityPage {
+            activity: self,
+            next,
+        }
+    }
+
+    /// Make a page of the first `limit` valid actions, at least one, along
+    /// with the rejected actions among them.
+    pub fn into_page(mut self, limit: u32) -> AgentActivityPage {
+        let limit = limit.max(1) as usize;
+        let next = if self.valid_activity.len() > limit {
+            self.valid_activity.truncate(limit);
+            self.valid_activity
+                .last()
+                .map(|(seq, _)| ChainCursor::after(*seq))
+        } else {
+            None
+        };
+        if let Some(next) = next {
+            self.rejected_activity
+                .retain(|(seq, _)| *seq <= next.action_seq());
+        }
+        AgentActivityPage {
+            activity: self,
+            next,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize, SerializedBytes)]
 /// Get either the full activity or just the status of the chain
 pub enum ActivityRequest {
@@ -207,6 +293,22 @@ impl ChainQueryFilter {
         self
     }
 
+    /// Narrow the sequence range to a page of the `limit` chain positions,
+    /// at least one, after a cursor or from the start of the range.
+    /// Hash bounded ranges are left as they are, so their results still have
+    /// to be cut to the page.
+    pub fn page(mut self, cursor: Option<ChainCursor>, limit: u32) -> Self {
+        let after = cursor.map_or(0, |cursor| cursor.action_seq().saturating_add(1));
+        let (start, end) = match self.sequence_range {
+            ChainQueryFilterRange::Unbounded => (after, u32::MAX),
+            ChainQueryFilterRange::ActionSeqRange(start, end) => (start.max(after), end),
+            _ => return self,
+        };
+        let page_end = start.saturating_add(limit.max(1) - 1).min(end);
+        self.sequence_range = ChainQueryFilterRange::ActionSeqRange(start, page_end);
+        self
+    }
+
     /// If the sequence range supports fork disambiguation, apply it to remove
     /// actions that are not in the correct branch.
     /// Numerical range bounds do NOT support fork disambiguation, and neither
@@ -312,7 +414,11 @@ impl ChainQueryFilter {
 #[cfg(test)]
 #[cfg(feature = "fixturators")]
 mod tests {
+    use super::AgentActivity;
+    use super::ChainCursor;
     use super::ChainQueryFilter;
+    use super::ChainStatus;
+    use super::HighestObserved;
     use crate::action::EntryType;
     use crate::fixt::AppEntryTypeFixturator;
     use crate::fixt::*;
@@ -561,4 +667,80 @@ mod tests {
             [true, false, false, false, true, true, false].to_vec()
         );
     }
+
+    #[test]
+    fn filter_page() {
+        let actions = fixtures();
+
+        assert_eq!(
+            map_query(&ChainQueryFilter::new().page(None, 2), &actions),
+            [true, true, false, false, false, false, false].to_vec()
+        );
+        assert_eq!(
+            map_query(
+                &ChainQueryFilter::new().page(Some(ChainCursor::after(2)), 3),
+                &actions
+            ),
+            [false, false, false, true, true, true, true].to_vec()
+        );
+        // The page ends with the range.
+        assert_eq!(
+            map_query(
+                &ChainQueryFilter::new()
+                    .sequence_range(ChainQueryFilterRange::ActionSeqRange(1, 4))
+                    .page(Some(ChainCursor::after(2)), 3),
+                &actions
+            ),
+            [false, false, false, true, true, true, false].to_vec()
+        );
+    }
+
+    #[test]
+    fn activity_pages_end_at_their_last_valid_action() {
+        let actions = fixtures();
+        let activity = AgentActivity {
+            valid_activity: actions
+                .iter()
+                .map(|a| (a.action_seq(), a.as_hash().clone()))
+                .collect(),
+            rejected_activity: vec![(2, actions[2].as_hash().clone())],
+            status: ChainStatus::Empty,
+            highest_observed: None,
+            warrants: vec![],
+        };
+
+        let page = activity.clone().into_page(2);
+        assert_eq!(page.activity.valid_activity.len(), 2);
+        assert!(page.activity.rejected_activity.is_empty());
+        assert_eq!(page.next, Some(ChainCursor::after(1)));
+
+        let page = activity.clone().into_page(3);
+        assert_eq!(page.activity.rejected_activity.len(), 1);
+        assert_eq!(page.next, Some(ChainCursor::after(2)));
+
+        let page = activity.into_page(7);
+        assert_eq!(page.activity.valid_activity.len(), 7);
+        assert_eq!(page.next, None);
+    }
+
+    #[test]
+    fn bounded_activity_pages_end_with_the_chain_or_range() {
+        let activity = AgentActivity {
+            valid_activity: vec![],
+            rejected_activity: vec![],
+            status: ChainStatus::Empty,
+            highest_observed: Some(HighestObserved {
+                action_seq: 6,
+                hash: vec![],
+            }),
+            warrants: vec![],
+        };
+
+        assert_eq!(
+            activity.clone().into_bounded_page(2, u32::MAX).next,
+            Some(ChainCursor::after(2))
+        );
+        assert_eq!(activity.clone().into_bounded_page(2, 2).next, None);
+        assert_eq!(activity.into_bounded_page(6, u32::MAX).next, None);
+    }
 }
@@ -21,6 +21,10 @@ pub struct MetadataRequest {
     /// This is faster then getting all the actions
     /// and checking for live actions.
     pub entry_dht_status: bool,
+    /// Request how many live actions, updates and deletes an entry has.
+    /// This is much smaller than all the actions, updates and deletes.
+    #[serde(default)]
+    pub crud_summary: bool,
 }
 
 impl Default for MetadataRequest {
@@ -32,6 +36,7 @@ impl Default for MetadataRequest {
             all_updates: true,
             follow_redirects: false,
             entry_dht_status: false,
+            crud_summary: false,
         }
     }
 }
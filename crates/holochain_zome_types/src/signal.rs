@@ -28,4 +28,49 @@ pub struct RemoteSignal {
     pub agents: Vec<AgentPubKey>,
     /// The signal to send.
     pub signal: crate::ExternIO,
+    /// How the signal is sent to the agents.
+    /// Signals sent with `remote_signal_acknowledged` ignore this, as they
+    /// are always sent with a separate remote call to each agent.
+    #[serde(default)]
+    pub delivery: RemoteSignalDelivery,
+}
+
+/// How a [`RemoteSignal`] is sent to its agents.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum RemoteSignalDelivery {
+    /// Hand the signal to the network and return straight away.
+    /// The signal may not be sent to some agents if the network is busy.
+    BestEffort,
+    /// Hand the signal for all the agents to the network at once, returning
+    /// once the network has taken it for each of them.
+    /// The signal is never dropped for a busy network, which is waited on.
+    Batched,
+}
+
+impl Default for RemoteSignalDelivery {
+    fn default() -> Self {
+        Self::BestEffort
+    }
+}
+
+/// Whether a signal sent with `remote_signal_acknowledged` reached an agent.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct RemoteSignalReceipt {
+    /// The agent the signal was sent to.
+    pub agent: AgentPubKey,
+    /// What became of the signal.
+    pub status: RemoteSignalStatus,
+}
+
+/// What became of a signal sent to one agent.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum RemoteSignalStatus {
+    /// The agent's conductor received the signal and handed it to the
+    /// agent's `recv_remote_signal` callback.
+    Delivered,
+    /// The agent hasn't granted the capability to call its
+    /// `recv_remote_signal` callback.
+    Unauthorized,
+    /// The signal didn't reach the agent, for this reason.
+    Failed(String),
 }
@@ -1,5 +1,6 @@
 use crate::record::Record;
 use crate::CallbackResult;
+use holo_hash::ActionHash;
 use holo_hash::AnyDhtHash;
 use holochain_serialized_bytes::prelude::*;
 use holochain_wasmer_common::*;
@@ -90,6 +91,64 @@ impl ValidationPackage {
     }
 }
 
+/// A record authored in the current zome call which would fail sys validation
+/// when the call's records are committed.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, SerializedBytes)]
+pub struct SysValidationIssue {
+    /// The action of the record.
+    pub action_hash: ActionHash,
+    /// Why the record would be rejected.
+    pub reason: SysValidationIssueReason,
+}
+
+/// Why sys validation would reject a record.
+///
+/// Ops rejected by sys validation are stored with their reason, which is sent
+/// back to the op's author in the validation receipt.
+#[derive(
+    Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, SerializedBytes,
+)]
+pub enum SysValidationIssueReason {
+    /// The action's signature isn't the author's.
+    Counterfeit,
+    /// A signature on the action's data, such as a preflight response,
+    /// doesn't verify.
+    InvalidSignature,
+    /// The entry is bigger than the maximum entry size.
+    EntryTooLarge { size: u64, max: u64 },
+    /// The link tag is bigger than the maximum tag size.
+    TagTooLarge { size: u64, max: u64 },
+    /// The entry has a different type to the action's entry type.
+    EntryType,
+    /// The entry has a different hash to the action's entry hash.
+    EntryHash,
+    /// The app entry type isn't defined by the zome, or has a different visibility.
+    AppEntryType(String),
+    /// An update changes the entry type of the entry it updates.
+    UpdateTypeMismatch,
+    /// The action's timestamp isn't after the previous action's.
+    Timestamp,
+    /// The action's sequence number doesn't follow the previous action's.
+    Seq { seq: u32, prev_seq: u32 },
+    /// The action's previous action hash isn't the hash of the action
+    /// before it on the author's chain.
+    PrevActionMismatch { seq: u32 },
+    /// The action doesn't fit onto the previous action in some other way.
+    PrevAction(String),
+    /// The action depends on data which couldn't be found.
+    MissingDependency(AnyDhtHash),
+    /// The action refers to an action which isn't a link creation.
+    NotCreateLink(ActionHash),
+    /// The action refers to an action which doesn't create or update an entry.
+    NotNewEntry,
+    /// A private entry was published.
+    PrivateEntry,
+    /// The action isn't valid for its countersigning session.
+    CounterSigning(String),
+    /// Any other reason, as described by the host.
+    Other(String),
+}
+
 #[cfg(feature = "full")]
 impl rusqlite::ToSql for ValidationStatus {
     fn to_sql(&self) -> rusqlite::Result<rusqlite::types::ToSqlOutput> {
@@ -1,6 +1,47 @@
 //! Types for warrants
 pub use holochain_serialized_bytes::prelude::*;
 
-#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, SerializedBytes)]
-/// Placeholder for warrant type
-pub struct Warrant;
+use crate::record::SignedAction;
+use holo_hash::AgentPubKey;
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, SerializedBytes)]
+/// Evidence that an agent has broken the rules of the network.
+///
+/// The proof is made of data the agent signed themselves, so anyone holding a
+/// warrant can check it without having to trust whoever issued it.
+pub struct Warrant {
+    /// The agent the warrant is against.
+    pub author: AgentPubKey,
+    /// What the agent did.
+    pub proof: WarrantProof,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+/// The signed data which shows what the agent of a [`Warrant`] did.
+pub enum WarrantProof {
+    /// The agent signed two different actions at the same
+    /// sequence position of their chain.
+    ChainFork {
+        /// One of the actions.
+        first: SignedAction,
+        /// The other action.
+        second: SignedAction,
+    },
+}
+
+impl Warrant {
+    /// A warrant for a chain fork, if these are two different actions
+    /// by the same author at the same sequence position.
+    ///
+    /// The signatures are not checked here.
+    pub fn chain_fork(first: SignedAction, second: SignedAction) -> Option<Self> {
+        let (a, b) = (&first.0, &second.0);
+        if a.author() != b.author() || a.action_seq() != b.action_seq() || a == b {
+            return None;
+        }
+        Some(Self {
+            author: a.author().clone(),
+            proof: WarrantProof::ChainFork { first, second },
+        })
+    }
+}
@@ -271,6 +271,18 @@ impl ZomeDef {
             ZomeDef::Inline { dependencies, .. } => &dependencies[..],
         }
     }
+
+    /// Get the functions of this zome which anyone may call.
+    pub fn unrestricted_functions(&self) -> &[FunctionName] {
+        match self {
+            ZomeDef::Wasm(WasmZome {
+                unrestricted_functions,
+                ..
+            }) => &unrestricted_functions[..],
+            #[cfg(feature = "full-dna-def")]
+            ZomeDef::Inline { .. } => &[],
+        }
+    }
 }
 
 impl IntegrityZomeDef {
@@ -328,6 +340,10 @@ pub struct WasmZome {
     pub wasm_hash: holo_hash::WasmHash,
     /// Integrity zomes this zome depends on.
     pub dependencies: Vec<ZomeName>,
+    /// Functions of this coordinator zome which anyone may call.
+    /// The conductor grants unrestricted access to them at genesis.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unrestricted_functions: Vec<FunctionName>,
 }
 
 impl WasmZome {
@@ -336,6 +352,7 @@ impl WasmZome {
         Self {
             wasm_hash,
             dependencies: Default::default(),
+            unrestricted_functions: Default::default(),
         }
     }
 }
@@ -346,6 +363,7 @@ impl ZomeDef {
         Self::Wasm(WasmZome {
             wasm_hash,
             dependencies: Default::default(),
+            unrestricted_functions: Default::default(),
         })
     }
 }
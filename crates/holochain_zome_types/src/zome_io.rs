@@ -49,6 +49,9 @@ wasm_io_types! {
     // @todo
     fn call_info (()) -> zt::info::CallInfo;
 
+    // The current runtime properties of the DNA, which may change between calls.
+    fn runtime_properties (()) -> SerializedBytes;
+
     fn call (Vec<zt::call::Call>) -> Vec<zt::ZomeCallResponse>;
 
     // @todo List all the local capability claims.
@@ -78,22 +81,37 @@ wasm_io_types! {
     // Delete a record.
     fn delete (zt::entry::DeleteInput) -> holo_hash::ActionHash;
 
+    // Sys validate the records authored so far in this call, without committing them.
+    fn dry_run_sys_validation (()) -> Vec<zt::validate::SysValidationIssue>;
+
     // Action hash of the newly committed record.
     // Emit a Signal::App to subscribers on the interface
     fn emit_signal (zt::signal::AppSignal) -> ();
 
     fn get_agent_activity (zt::agent_activity::GetAgentActivityInput) -> zt::query::AgentActivity;
 
+    // Get a page of an agent's activity, after a cursor from the last page.
+    fn get_agent_activity_page (zt::agent_activity::GetAgentActivityPageInput) -> zt::query::AgentActivityPage;
+
     fn get_details (Vec<zt::entry::GetInput>) -> Vec<Option<zt::metadata::Details>>;
 
     fn get_link_details (Vec<zt::link::GetLinksInput>) -> Vec<zt::link::LinkDetails>;
 
+    // Aggregate the links on a base without returning them.
+    fn aggregate_links (Vec<zt::link::AggregateLinksInput>) -> Vec<zt::link::LinkAggregate>;
+
     // Get links by entry hash from the cascade.
     fn get_links (Vec<zt::link::GetLinksInput>) -> Vec<Vec<zt::link::Link>>;
 
     // Attempt to get a live entry from the cascade.
     fn get (Vec<zt::entry::GetInput>) -> Vec<Option<zt::record::Record>>;
 
+    // Get only the actions at these hashes, without their entries.
+    fn get_action (Vec<holo_hash::ActionHash>) -> Vec<Option<zt::SignedActionHashed>>;
+
+    // Check whether there is any data at these hashes, without fetching it.
+    fn exists (Vec<holo_hash::AnyDhtHash>) -> Vec<bool>;
+
     // Hash data on the host.
     fn hash (zt::hash::HashInput) -> zt::hash::HashOutput;
 
@@ -111,12 +129,21 @@ wasm_io_types! {
     // Query the source chain for data.
     fn query (zt::query::ChainQueryFilter) -> Vec<crate::Record>;
 
+    // Query a page of the source chain, after a cursor from the last page.
+    fn query_page (zt::query::ChainQueryPageInput) -> zt::query::RecordsPage;
+
     // the length of random bytes to create
     fn random_bytes (u32) -> zt::bytes::Bytes;
 
+    // Read a chunked blob back from the cascade.
+    fn read_blob (zt::entry::BlobManifest) -> Option<zt::bytes::Bytes>;
+
     // Remotely signal many agents without waiting for responses
     fn remote_signal (zt::signal::RemoteSignal) -> ();
 
+    // Remotely signal many agents, waiting to hear whether each received it
+    fn remote_signal_acknowledged (zt::signal::RemoteSignal) -> Vec<zt::signal::RemoteSignalReceipt>;
+
     // // @todo
     // fn send (()) -> ();
 
@@ -143,6 +170,9 @@ wasm_io_types! {
 
     fn verify_signature (zt::signature::VerifySignature) -> bool;
 
+    // Commit a blob too large for one entry as chunks.
+    fn write_blob (zt::bytes::Bytes) -> zt::entry::BlobManifest;
+
     fn x_salsa20_poly1305_shared_secret_create_random(
         Option<zt::x_salsa20_poly1305::key_ref::XSalsa20Poly1305KeyRef>
     ) -> zt::x_salsa20_poly1305::key_ref::XSalsa20Poly1305KeyRef;
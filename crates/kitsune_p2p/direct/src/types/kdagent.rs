@@ -41,6 +41,8 @@ impl KdAgentInfoExt for KdAgentInfo {
             url_list,
             signed_at_ms,
             expires_at_ms,
+            // Not kept by kdirect, so never send this agent compressed messages.
+            accepts_compression: false,
             signature,
             encoded_bytes,
         }))
@@ -1,9 +1,12 @@
+use kitsune_p2p_transport_webrtc::bind_url_from_signal_url;
 use kitsune_p2p_types::config::KitsuneP2pTuningParams;
 use kitsune_p2p_types::tx2::tx2_adapter::AdapterFactory;
 use kitsune_p2p_types::tx2::tx2_utils::*;
 use kitsune_p2p_types::*;
 use url2::Url2;
 
+pub use kitsune_p2p_transport_webrtc::tx2::IceServerConfig;
+
 // TODO - FIXME - holochain bootstrap should not be encoded in kitsune
 /// The default production bootstrap service url.
 pub const BOOTSTRAP_SERVICE_DEFAULT: &str = "https://bootstrap-staging.holo.host";
@@ -14,8 +17,16 @@ pub const BOOTSTRAP_SERVICE_DEV: &str = "https://bootstrap-dev.holohost.workers.
 
 pub(crate) enum KitsuneP2pTx2Backend {
     Mem,
-    Quic { bind_to: TxUrl },
-    Mock { mock_network: AdapterFactory },
+    Quic {
+        bind_to: TxUrl,
+    },
+    WebRtc {
+        bind_to: TxUrl,
+        ice_servers: Vec<IceServerConfig>,
+    },
+    Mock {
+        mock_network: AdapterFactory,
+    },
 }
 
 pub(crate) enum KitsuneP2pTx2ProxyConfig {
@@ -26,6 +37,7 @@ pub(crate) enum KitsuneP2pTx2ProxyConfig {
         bootstrap_url: TxUrl,
         fallback_proxy_url: Option<TxUrl>,
     },
+    Relay(TxUrl),
 }
 
 pub(crate) struct KitsuneP2pTx2Config {
@@ -41,6 +53,11 @@ pub struct KitsuneP2pConfig {
     pub transport_pool: Vec<TransportConfig>,
     /// The service used for peers to discover each before they are peers.
     pub bootstrap_service: Option<Url2>,
+    /// Additional bootstrap services, used alongside `bootstrap_service`.
+    /// Agent info is published to all of them, and peers are fetched from
+    /// whichever responds, so discovery keeps working if one is down.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bootstrap_services: Vec<Url2>,
     /// Network tuning parameters. These are managed loosely,
     /// as they are subject to change. If you specify a tuning parameter
     /// that no longer exists, or a value that does not parse,
@@ -49,6 +66,12 @@ pub struct KitsuneP2pConfig {
     pub tuning_params: KitsuneP2pTuningParams,
     /// The network used for connecting to other peers
     pub network_type: NetworkType,
+    /// A tls certificate file to use instead of a generated certificate,
+    /// in the format written by `kitsune-p2p-tx2-proxy --danger-gen-unenc-cert`.
+    /// Holochain conductors otherwise use a certificate kept in their
+    /// keystore, which stays the same across restarts so peers can pin it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls_cert_path: Option<std::path::PathBuf>,
 }
 
 impl Default for KitsuneP2pConfig {
@@ -56,8 +79,10 @@ impl Default for KitsuneP2pConfig {
         Self {
             transport_pool: Vec::new(),
             bootstrap_service: None,
+            bootstrap_services: Vec::new(),
             tuning_params: KitsuneP2pTuningParams::default(),
             network_type: NetworkType::QuicBootstrap,
+            tls_cert_path: None,
         }
     }
 }
@@ -70,6 +95,21 @@ fn cnv_bind_to(bind_to: &Option<url2::Url2>) -> TxUrl {
 }
 
 impl KitsuneP2pConfig {
+    /// Every configured bootstrap service, without duplicates.
+    pub fn bootstrap_urls(&self) -> Vec<Url2> {
+        let mut urls: Vec<Url2> = Vec::new();
+        for url in self
+            .bootstrap_service
+            .iter()
+            .chain(self.bootstrap_services.iter())
+        {
+            if !urls.contains(url) {
+                urls.push(url.clone());
+            }
+        }
+        urls
+    }
+
     /// `tx2` is currently designed to use exactly one proxy wrapped transport,
     /// so convert a bunch of the options from the previous transport
     /// paradigm into that pattern.
@@ -86,7 +126,14 @@ impl KitsuneP2pConfig {
                         let bind_to = cnv_bind_to(bind_to);
                         KitsuneP2pTx2Backend::Quic { bind_to }
                     }
-                    _ => return Err("kitsune tx2 backend must be mem or quic".into()),
+                    TransportConfig::WebRtc {
+                        signal_url,
+                        ice_servers,
+                    } => KitsuneP2pTx2Backend::WebRtc {
+                        bind_to: bind_url_from_signal_url(signal_url)?,
+                        ice_servers: ice_servers.clone(),
+                    },
+                    _ => return Err("kitsune tx2 backend must be mem, quic or webrtc".into()),
                 };
                 let use_proxy = match proxy_config {
                     ProxyConfig::RemoteProxyClient { proxy_url } => {
@@ -99,6 +146,9 @@ impl KitsuneP2pConfig {
                         bootstrap_url: bootstrap_url.clone().into(),
                         fallback_proxy_url: fallback_proxy_url.clone().map(Into::into),
                     },
+                    ProxyConfig::DirectWithRelayFallback { relay_url } => {
+                        Relay(relay_url.clone().into())
+                    }
                     ProxyConfig::LocalProxyServer { .. } => NoProxy,
                 };
                 Ok(KitsuneP2pTx2Config { backend, use_proxy })
@@ -110,6 +160,16 @@ impl KitsuneP2pConfig {
                     use_proxy: NoProxy,
                 })
             }
+            Some(TransportConfig::WebRtc {
+                signal_url,
+                ice_servers,
+            }) => Ok(KitsuneP2pTx2Config {
+                backend: KitsuneP2pTx2Backend::WebRtc {
+                    bind_to: bind_url_from_signal_url(signal_url)?,
+                    ice_servers: ice_servers.clone(),
+                },
+                use_proxy: NoProxy,
+            }),
             Some(TransportConfig::Mock { mock_network }) => Ok(KitsuneP2pTx2Config {
                 backend: KitsuneP2pTx2Backend::Mock {
                     mock_network: mock_network.0.clone(),
@@ -160,6 +220,18 @@ pub enum TransportConfig {
         /// - be directly addressable, but not proxy for others
         proxy_config: ProxyConfig,
     },
+    /// A transport that connects peers directly over WebRTC,
+    /// after they have exchanged session descriptions through a signal server
+    WebRtc {
+        /// The `wss://` (or `ws://`) url of the signal server to register with
+        signal_url: Url2,
+
+        /// STUN / TURN servers used to traverse NATs.
+        /// Peers behind symmetric NATs will need a TURN server.
+        /// Default: empty = only connect to peers on the same network
+        #[serde(default)]
+        ice_servers: Vec<IceServerConfig>,
+    },
     #[serde(skip)]
     /// A mock network for testing
     Mock {
@@ -212,6 +284,16 @@ pub enum ProxyConfig {
         fallback_proxy_url: Option<Url2>,
     },
 
+    /// We want peers to connect to us directly, but to keep a connection
+    /// open to a relay they can route through if that fails, e.g. because
+    /// we are behind a NAT. While relayed, we'll try to hole punch a
+    /// direct connection (see the `proxy_hole_punch` tuning param).
+    DirectWithRelayFallback {
+        /// The relay to fall back to. This is a proxy url, so the relay
+        /// must be a proxy server that accepts proxy requests.
+        relay_url: Url2,
+    },
+
     /// We want to be a proxy server for others.
     /// (We can also deny all proxy requests for something in-between.)
     LocalProxyServer {
@@ -238,6 +320,11 @@ pub enum ProxyAcceptConfig {
 pub enum NetworkType {
     /// Via bootstrap server to the WAN
     QuicBootstrap,
-    /// Via MDNS to the LAN
+    /// Via MDNS to the LAN.
+    ///
+    /// Each local agent's info is broadcast under its space, and peers in the
+    /// same space are discovered from the broadcasts of others on the LAN.
+    /// This needs no bootstrap service or internet connection, so it suits
+    /// workshops, demos and offline deployments.
     QuicMdns,
 }
@@ -29,6 +29,7 @@ use tokio::time::Instant;
 
 pub use self::bandwidth::BandwidthThrottle;
 use self::ops::OpsBatchQueue;
+use self::recent_delay::RecentGossipDelay;
 use self::state_map::RoundStateMap;
 use crate::metrics::MetricsSync;
 
@@ -45,6 +46,7 @@ mod agents;
 mod bloom;
 mod initiate;
 mod ops;
+mod recent_delay;
 mod state_map;
 mod store;
 
@@ -112,6 +114,8 @@ pub struct ShardedGossip {
     pub(crate) state: Share<ShardedGossipState>,
     /// Bandwidth for incoming and outgoing gossip.
     bandwidth: Arc<BandwidthThrottle>,
+    /// While paused no gossip is sent or received.
+    paused: AtomicBool,
 }
 
 impl std::fmt::Debug for ShardedGossip {
@@ -175,19 +179,30 @@ impl ShardedGossip {
         #[cfg(not(feature = "test"))]
         let state = Default::default();
 
+        let mut local_state = ShardedGossipLocalState::new(metrics);
+        if gossip_type == GossipType::Recent {
+            local_state.recent_delay = Some(RecentGossipDelay::new(
+                Duration::from_millis(tuning_params.gossip_recent_min_peer_delay_ms as u64),
+                Duration::from_millis(
+                    tuning_params.gossip_peer_on_success_next_gossip_delay_ms as u64,
+                ),
+            ));
+        }
+
         let this = Arc::new(Self {
             ep_hnd,
             state: Share::new(state),
             gossip: ShardedGossipLocal {
-                tuning_params,
+                tuning_params: parking_lot::RwLock::new(tuning_params),
                 space,
                 evt_sender,
                 host_api,
-                inner: Share::new(ShardedGossipLocalState::new(metrics)),
+                inner: Share::new(local_state),
                 gossip_type,
                 closing: AtomicBool::new(false),
             },
             bandwidth,
+            paused: AtomicBool::new(false),
         });
         metric_task({
             let this = this.clone();
@@ -200,6 +215,9 @@ impl ShardedGossip {
                     .load(std::sync::atomic::Ordering::Relaxed)
                 {
                     tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+                    if this.paused.load(std::sync::atomic::Ordering::Relaxed) {
+                        continue;
+                    }
                     this.run_one_iteration().await;
                     this.stats(&mut stats);
                 }
@@ -236,13 +254,24 @@ impl ShardedGossip {
         };
         let gossip = gossip.encode_vec().map_err(KitsuneError::other)?;
         let bytes = gossip.len();
+        // Only compress for a partner whose agents are known from a round.
+        let remote_agents = self
+            .gossip
+            .get_state(&cert)?
+            .map(|state| state.remote_agent_list)
+            .unwrap_or_default();
+        let tuning_params = self.gossip.tuning_params();
         let gossip = wire::Wire::gossip(
             self.gossip.space.clone(),
             gossip.into(),
             self.gossip.gossip_type.into(),
+        )
+        .compress_for(
+            &remote_agents,
+            tuning_params.wire_compression_threshold_bytes,
         );
 
-        let timeout = self.gossip.tuning_params.implicit_timeout();
+        let timeout = tuning_params.implicit_timeout();
 
         self.bandwidth.outgoing_bytes(bytes).await;
 
@@ -259,6 +288,10 @@ impl ShardedGossip {
         // Wait for enough available outgoing bandwidth here before
         // actually sending the gossip.
         con.notify(&gossip, timeout).await?;
+        self.gossip.inner.share_mut(|i, _| {
+            i.metrics.write().record_bytes_sent(bytes);
+            Ok(())
+        })?;
         Ok(())
     }
 
@@ -266,6 +299,10 @@ impl ShardedGossip {
         let (incoming, outgoing) = self.pop_queues()?;
         if let Some((con, remote_url, msg, bytes)) = incoming {
             self.bandwidth.incoming_bytes(bytes).await;
+            self.gossip.inner.share_mut(|i, _| {
+                i.metrics.write().record_bytes_received(bytes);
+                Ok(())
+            })?;
             let outgoing = match self.gossip.process_incoming(con.peer_cert(), msg).await {
                 Ok(r) => r,
                 Err(e) => {
@@ -369,7 +406,8 @@ impl ShardedGossip {
 ///     get sent by the enclosing `ShardedGossip`
 pub struct ShardedGossipLocal {
     gossip_type: GossipType,
-    tuning_params: KitsuneP2pTuningParams,
+    /// Swapped out when the tuning params are updated while running.
+    tuning_params: parking_lot::RwLock<KitsuneP2pTuningParams>,
     space: Arc<KitsuneSpace>,
     evt_sender: EventSender,
     host_api: HostApi,
@@ -406,6 +444,9 @@ pub struct ShardedGossipLocalState {
     /// Metrics that track remote node states and help guide
     /// the next node to gossip with.
     metrics: MetricsSync,
+    /// For recent gossip, how long to wait before gossiping again
+    /// with a peer we've successfully gossiped with.
+    recent_delay: Option<RecentGossipDelay>,
 }
 
 impl ShardedGossipLocalState {
@@ -435,6 +476,9 @@ impl ShardedGossipLocalState {
                 self.metrics.write().record_error(&r.remote_agent_list);
             } else {
                 self.metrics.write().record_success(&r.remote_agent_list);
+                if let Some(recent_delay) = &mut self.recent_delay {
+                    recent_delay.round_finished();
+                }
             }
         } else if init_tgt && error {
             self.metrics.write().record_error(&remote_agent_list);
@@ -534,6 +578,12 @@ impl ShardedGossipState {
         self.queues.outgoing.extend(outgoing.into_iter());
     }
 
+    /// Drop every queued message, without touching the history.
+    pub fn clear(&mut self) {
+        self.queues.incoming.clear();
+        self.queues.outgoing.clear();
+    }
+
     pub fn pop(&mut self) -> (Option<Incoming>, Option<Outgoing>) {
         (
             self.queues.incoming.pop_front(),
@@ -583,10 +633,16 @@ impl ShardedGossipLocal {
     /// The number of bloom filters we want to send in a single gossip iteration.
     const UPPER_BLOOM_BOUND: usize = 10;
 
+    /// The current tuning params.
+    fn tuning_params(&self) -> KitsuneP2pTuningParams {
+        self.tuning_params.read().clone()
+    }
+
     /// Calculate the time range for a gossip round.
     fn calculate_time_range(&self) -> TimeWindow {
         const NOW: Duration = Duration::from_secs(0);
-        let threshold = Duration::from_secs(self.tuning_params.danger_gossip_recent_threshold_secs);
+        let threshold =
+            Duration::from_secs(self.tuning_params().danger_gossip_recent_threshold_secs);
         match self.gossip_type {
             GossipType::Recent => time_range(threshold, NOW),
             GossipType::Historical => {
@@ -804,6 +860,15 @@ impl ShardedGossipLocal {
                     vec![]
                 }
             }
+            ShardedGossipWire::OpRegionBloom(OpRegionBloom { filter }) => {
+                match self.get_state(&cert)? {
+                    Some(state) => {
+                        let filter = filter.map(|filter| decode_bloom_filter(&filter));
+                        self.incoming_region_bloom(state, filter).await?
+                    }
+                    None => Vec::with_capacity(0),
+                }
+            }
             ShardedGossipWire::MissingOps(MissingOps { ops, finished }) => {
                 let mut gossip = Vec::with_capacity(0);
                 let finished = MissingOpsStatus::try_from(finished)?;
@@ -1058,6 +1123,14 @@ kitsune_p2p_types::write_codec_enum! {
             region_set.0: RegionSetLtcs,
         },
 
+        /// Send a bloom filter of the ops held within the regions
+        /// which differ between the two region sets, so that only the
+        /// ops missing from it are sent back.
+        OpRegionBloom(0x52) {
+            /// The bloom filter, or None if no ops are held in those regions.
+            filter.0: Option<PoolBuf>,
+        },
+
         /// Any ops that were missing from the remote bloom.
         MissingOps(0x60) {
             /// The missing ops
@@ -1121,6 +1194,9 @@ impl AsGossipModule for ShardedGossip {
         remote_url: TxUrl,
         gossip_data: Box<[u8]>,
     ) -> KitsuneResult<()> {
+        if self.paused.load(std::sync::atomic::Ordering::Relaxed) {
+            return Ok(());
+        }
         use kitsune_p2p_types::codec::*;
         let (bytes, gossip) =
             ShardedGossipWire::decode_ref(&gossip_data).map_err(KitsuneError::other)?;
@@ -1178,6 +1254,23 @@ impl AsGossipModule for ShardedGossip {
             Ok(())
         });
     }
+
+    fn update_tuning_params(&self, tuning_params: KitsuneP2pTuningParams) {
+        *self.gossip.tuning_params.write() = tuning_params;
+    }
+
+    fn set_paused(&self, paused: bool) {
+        self.paused
+            .store(paused, std::sync::atomic::Ordering::Relaxed);
+        if paused {
+            // Messages queued before pausing would be stale by the time
+            // gossip resumes, and any round they belong to will time out.
+            let _ = self.state.share_mut(|i, _| {
+                i.clear();
+                Ok(())
+            });
+        }
+    }
 }
 
 struct ShardedRecentGossipFactory {
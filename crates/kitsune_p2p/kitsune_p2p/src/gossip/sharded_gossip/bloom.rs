@@ -1,4 +1,5 @@
 use crate::gossip::sharded_gossip::store::TimeChunk;
+use kitsune_p2p_types::dht::region::RegionBounds;
 
 use super::*;
 
@@ -38,6 +39,48 @@ impl ShardedGossipLocal {
         Ok(Some(bloom))
     }
 
+    /// Generate a bloom filter of the ops held within a set of regions.
+    /// - At most [`Self::UPPER_HASHES_BOUND`] hashes are included, so for a very
+    /// large diff the remote node may send back some ops we already hold.
+    /// - Returns None if no ops are held in the regions.
+    pub(super) async fn generate_region_bloom(
+        &self,
+        regions: &[RegionBounds],
+    ) -> KitsuneResult<Option<BloomFilter>> {
+        let mut hashes = Vec::new();
+        for bounds in regions {
+            let max_ops = Self::UPPER_HASHES_BOUND.saturating_sub(hashes.len());
+            if max_ops == 0 {
+                break;
+            }
+            let arc_set = DhtArcSet::from_bounds(bounds.x.0, bounds.x.1);
+            // Region time bounds are inclusive but query windows are not.
+            let window =
+                bounds.t.0..Timestamp::from_micros(bounds.t.1.as_micros().saturating_add(1));
+            if let Some((found, _)) = store::all_op_hashes_within_arcset(
+                &self.evt_sender,
+                &self.space,
+                arc_set,
+                window,
+                max_ops,
+                false,
+            )
+            .await?
+            {
+                hashes.extend(found);
+            }
+        }
+
+        if hashes.is_empty() {
+            return Ok(None);
+        }
+        let mut bloom = bloomfilter::Bloom::new_for_fp_rate(hashes.len(), Self::TGT_FP);
+        for hash in hashes {
+            bloom.set(&MetaOpKey::Op(hash));
+        }
+        Ok(Some(bloom))
+    }
+
     /// Generate a bloom filter of all ops.
     /// - Ops are only included if they are within the common arc set.
     /// - The bloom is `KitsuneOpHah`.
@@ -85,22 +85,49 @@ impl ShardedGossipLocal {
         }
 
         let remote_nodes = remote_nodes.into_iter().map(|(_, v)| v).collect();
-        let tuning_params = self.tuning_params.clone();
+        let tuning_params = self.tuning_params();
         // We could clone the metrics store out of the lock here but I don't think
         // the next_remote_node will be that slow so we can just choose the next node inline.
         self.inner.share_mut(|i, _| {
-            let node = next_remote_node(remote_nodes, &i.metrics, tuning_params);
+            let success_delay_ms = match &i.recent_delay {
+                Some(recent_delay) => recent_delay.delay().as_millis() as u32,
+                None => tuning_params.gossip_peer_on_success_next_gossip_delay_ms,
+            };
+            let node = next_remote_node_with_delay(
+                remote_nodes,
+                &i.metrics,
+                &tuning_params,
+                success_delay_ms,
+            );
             Ok(node)
         })
     }
 }
 
 /// Find the next remote node to sync with.
+#[cfg(test)]
 fn next_remote_node(
-    mut remote_nodes: Vec<Node>,
+    remote_nodes: Vec<Node>,
     metrics: &MetricsSync,
     tuning_params: KitsuneP2pTuningParams,
 ) -> Option<Node> {
+    next_remote_node_with_delay(
+        remote_nodes,
+        metrics,
+        &tuning_params,
+        tuning_params.gossip_peer_on_success_next_gossip_delay_ms,
+    )
+}
+
+/// Find the next remote node to sync with, waiting the given delay
+/// before gossiping again with a node after a success.
+fn next_remote_node_with_delay(
+    mut remote_nodes: Vec<Node>,
+    metrics: &MetricsSync,
+    tuning_params: &KitsuneP2pTuningParams,
+    success_delay_ms: u32,
+) -> Option<Node> {
+    let error_delay_ms = tuning_params.gossip_peer_on_error_next_gossip_delay_ms;
     use rand::prelude::*;
     let mut rng = thread_rng();
 
@@ -131,8 +158,18 @@ fn next_remote_node(
         }
     });
 
+    // Drop nodes scoring below the evict threshold, and move those
+    // below the deprioritize threshold behind all the others.
+    let (mut remote_nodes, low_score): (Vec<_>, Vec<_>) = remote_nodes
+        .into_iter()
+        .map(|n| (metrics.read().peer_score(&n.agent_info_list) as f64, n))
+        .filter(|(score, _)| *score >= tuning_params.peer_score_evict_threshold)
+        .partition(|(score, _)| *score >= tuning_params.peer_score_deprioritize_threshold);
+    remote_nodes.extend(low_score);
+
     remote_nodes
         .into_iter()
+        .map(|(_, n)| n)
         // Don't initiate with nodes we are currently gossiping with.
         .filter(|n| !metrics.read().is_current_round(&n.agent_info_list))
         .find(|n| {
@@ -140,12 +177,10 @@ fn next_remote_node(
                 Some(RoundOutcome::Success(when)) => {
                     // If we should force initiate then we don't need to wait for the delay.
                     metrics.read().forced_initiate()
-                        || when.elapsed().as_millis() as u32
-                            >= tuning_params.gossip_peer_on_success_next_gossip_delay_ms
+                        || when.elapsed().as_millis() as u32 >= success_delay_ms
                 }
                 Some(RoundOutcome::Error(when)) => {
-                    when.elapsed().as_millis() as u32
-                        >= tuning_params.gossip_peer_on_error_next_gossip_delay_ms
+                    when.elapsed().as_millis() as u32 >= error_delay_ms
                 }
                 _ => true,
             }
@@ -675,4 +710,38 @@ mod tests {
         // - Now the reset has run out we get no nodes.
         assert!(r.is_none());
     }
+
+    #[test]
+    /// Test that low scoring nodes are only chosen once
+    /// better nodes have been gossiped with, and that
+    /// nodes below the evict threshold are never chosen.
+    fn low_scoring_nodes_deprioritized_and_evicted() {
+        let remote_nodes = create_remote_nodes(2);
+        let metrics = MetricsSync::default();
+
+        // - Give the first node a low score.
+        for _ in 0..10 {
+            metrics
+                .write()
+                .record_validation_failure(&remote_nodes[0].agent_info_list);
+        }
+
+        // - The second node is chosen, although both are equally due.
+        let r = next_remote_node(remote_nodes.clone(), &metrics, tuning_params_no_delay());
+        assert_eq!(r, remote_nodes.get(1).cloned());
+
+        // - Once the second node is in a round, the first is chosen.
+        metrics
+            .write()
+            .record_initiate(&remote_nodes[1].agent_info_list);
+        let r = next_remote_node(remote_nodes.clone(), &metrics, tuning_params_no_delay());
+        assert_eq!(r, remote_nodes.get(0).cloned());
+
+        // - Unless it's below the evict threshold.
+        let mut t = tuning_params_struct::KitsuneP2pTuningParams::default();
+        t.gossip_peer_on_success_next_gossip_delay_ms = 0;
+        t.peer_score_evict_threshold = 0.5;
+        let r = next_remote_node(remote_nodes.clone(), &metrics, Arc::new(t));
+        assert!(r.is_none());
+    }
 }
@@ -15,6 +15,9 @@ pub struct OpsBatchQueueInner {
     next_id: usize,
     queues: HashMap<usize, VecDeque<QueuedOps>>,
     region_queue: VecDeque<Region>,
+    /// The remote node's bloom of the ops it holds in the regions
+    /// queued above. Ops found in it are not sent.
+    region_bloom: Option<BloomFilter>,
 }
 
 /// Identify the next items to process from the region queue.
@@ -121,12 +124,22 @@ impl ShardedGossipLocal {
                 .host_api
                 .query_size_limited_regions(
                     self.space.clone(),
-                    self.tuning_params.gossip_max_batch_size,
+                    self.tuning_params().gossip_max_batch_size,
                     diff_regions,
                 )
                 .await
                 .map_err(KitsuneError::other)?;
 
+            let topo = self
+                .host_api
+                .get_topology(self.space.clone())
+                .await
+                .map_err(KitsuneError::other)?;
+            let bounds: Vec<_> = limited_regions
+                .iter()
+                .map(|r| r.coords.to_bounds(&topo))
+                .collect();
+
             state.ops_batch_queue.0.share_mut(|queue, _| {
                 for region in limited_regions {
                     queue.region_queue.push_back(region)
@@ -134,12 +147,31 @@ impl ShardedGossipLocal {
                 Ok(())
             })?;
 
-            self.process_next_region_batch(state).await
+            // Rather than sending every op in the regions that differ, first
+            // let our partner know which ops we already hold in them.
+            let bloom = self.generate_region_bloom(&bounds).await?;
+            Ok(vec![ShardedGossipWire::op_region_bloom(
+                bloom.map(|bloom| encode_bloom_filter(&bloom)),
+            )])
         } else {
             Err(KitsuneError::other("We received OpRegions gossip without sending any ourselves. This can only happen if Recent gossip somehow sends an OpRegions message."))
         }
     }
 
+    /// Incoming bloom of the ops our partner holds in the regions that differ.
+    /// Start sending back the ops in those regions which are missing from it.
+    pub(super) async fn incoming_region_bloom(
+        &self,
+        state: RoundState,
+        remote_bloom: Option<BloomFilter>,
+    ) -> KitsuneResult<Vec<ShardedGossipWire>> {
+        state.ops_batch_queue.0.share_mut(|queue, _| {
+            queue.region_bloom = remote_bloom;
+            Ok(())
+        })?;
+        self.process_next_region_batch(state).await
+    }
+
     pub(super) async fn process_next_region_batch(
         &self,
         state: RoundState,
@@ -153,7 +185,7 @@ impl ShardedGossipLocal {
         let (to_fetch, finished) = state.ops_batch_queue.share_mut(|queues, _| {
             let items = get_region_queue_batch(
                 &mut queues.region_queue,
-                self.tuning_params.gossip_max_batch_size,
+                self.tuning_params().gossip_max_batch_size,
             );
             Ok((items, queues.region_queue.is_empty()))
         })?;
@@ -171,10 +203,21 @@ impl ShardedGossipLocal {
                 query: FetchOpDataEvtQuery::Regions(bounds),
             })
             .await
-            .map_err(KitsuneError::other)?
-            .into_iter()
-            .map(second)
-            .collect();
+            .map_err(KitsuneError::other)?;
+
+        // Skip any ops our partner told us they already hold.
+        let ops = state.ops_batch_queue.0.share_ref(|queues| {
+            Ok(ops
+                .into_iter()
+                .filter(|(hash, _)| {
+                    !queues
+                        .region_bloom
+                        .as_ref()
+                        .map_or(false, |bloom| bloom.check(&MetaOpKey::Op(hash.clone())))
+                })
+                .map(second)
+                .collect())
+        })?;
 
         let finished_val = if finished { 2 } else { 1 };
         Ok(vec![ShardedGossipWire::missing_ops(ops, finished_val)])
@@ -286,9 +329,18 @@ impl ShardedGossipLocal {
 
     /// Incoming ops that were missing from this nodes bloom filter.
     pub(super) async fn incoming_missing_ops(&self, ops: Vec<KOp>) -> KitsuneResult<()> {
+        let count = ops.len();
+
         // Put the ops in the agents that contain the ops within their arcs.
         store::put_ops(&self.evt_sender, &self.space, ops).await?;
 
+        self.inner.share_mut(|i, _| {
+            if let Some(recent_delay) = &mut i.recent_delay {
+                recent_delay.record_new_ops(count);
+            }
+            Ok(())
+        })?;
+
         Ok(())
     }
 }
@@ -359,6 +411,7 @@ impl OpsBatchQueueInner {
             next_id: 0,
             queues: HashMap::new(),
             region_queue: VecDeque::new(),
+            region_bloom: None,
         }
     }
 
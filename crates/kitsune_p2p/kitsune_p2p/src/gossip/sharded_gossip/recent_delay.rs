@@ -0,0 +1,81 @@
+use std::time::Duration;
+
+/// How long recent gossip waits before another round with a peer it has
+/// successfully gossiped with, adapted to how much new data rounds are finding.
+/// While rounds keep bringing in new ops the delay halves down to `min`,
+/// so fresh data propagates quickly. When they stop, it doubles back up to `max`.
+#[derive(Debug, Clone)]
+pub(crate) struct RecentGossipDelay {
+    min: Duration,
+    max: Duration,
+    delay: Duration,
+    /// New ops received since the last round finished.
+    new_ops: usize,
+}
+
+impl RecentGossipDelay {
+    /// Start at the shortest delay, so a node that has just come online
+    /// catches up quickly.
+    pub(crate) fn new(min: Duration, max: Duration) -> Self {
+        let min = min.min(max);
+        Self {
+            min,
+            max,
+            delay: min,
+            new_ops: 0,
+        }
+    }
+
+    /// Record ops received that we didn't have.
+    pub(crate) fn record_new_ops(&mut self, count: usize) {
+        self.new_ops += count;
+    }
+
+    /// A round has finished successfully, so adapt the delay
+    /// to whether any new ops were received during it.
+    pub(crate) fn round_finished(&mut self) {
+        let delay = if self.new_ops > 0 {
+            self.delay / 2
+        } else {
+            self.delay * 2
+        };
+        self.delay = delay.max(self.min).min(self.max);
+        self.new_ops = 0;
+    }
+
+    /// The current delay.
+    pub(crate) fn delay(&self) -> Duration {
+        self.delay
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delay_adapts_to_new_ops() {
+        let min = Duration::from_secs(5);
+        let max = Duration::from_secs(60);
+        let mut delay = RecentGossipDelay::new(min, max);
+        assert_eq!(delay.delay(), min);
+
+        // Quiet rounds back off up to the max.
+        for _ in 0..10 {
+            delay.round_finished();
+        }
+        assert_eq!(delay.delay(), max);
+
+        // A round finding new ops halves the delay.
+        delay.record_new_ops(3);
+        delay.round_finished();
+        assert_eq!(delay.delay(), max / 2);
+
+        // But never below the min.
+        for _ in 0..10 {
+            delay.record_new_ops(1);
+            delay.round_finished();
+        }
+        assert_eq!(delay.delay(), min);
+    }
+}
@@ -25,7 +25,7 @@ impl ShardedGossipLocal {
         let space = Arc::new(space);
         Self {
             gossip_type,
-            tuning_params: Default::default(),
+            tuning_params: parking_lot::RwLock::new(Default::default()),
             space,
             evt_sender,
             host_api: host,
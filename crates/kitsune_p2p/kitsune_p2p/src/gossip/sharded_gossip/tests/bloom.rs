@@ -5,6 +5,7 @@ use super::common::*;
 use super::*;
 use crate::gossip::sharded_gossip::bloom::Batch;
 use crate::HostStub;
+use kitsune_p2p_types::dht::region::RegionBounds;
 
 #[tokio::test(flavor = "multi_thread")]
 async fn bloom_windows() {
@@ -184,6 +185,28 @@ async fn bloom_windows() {
     }
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn region_bloom_contains_held_ops() {
+    let window = time_range(Duration::from_secs(20), Duration::from_secs(2));
+    let region = || RegionBounds::new((0u32, u32::MAX), (window.start, window.end));
+
+    let bloom = make_node(1, window.clone())
+        .await
+        .generate_region_bloom(&[region()])
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(bloom.check(&MetaOpKey::Op(Arc::new(KitsuneOpHash(vec![0; 36])))));
+
+    // No bloom is created if no ops are held in the regions.
+    let bloom = make_empty_node()
+        .await
+        .generate_region_bloom(&[region()])
+        .await
+        .unwrap();
+    assert!(bloom.is_none());
+}
+
 async fn make_node(num: usize, window: TimeWindow) -> ShardedGossipLocal {
     make_node_inner(Some((num, window))).await
 }
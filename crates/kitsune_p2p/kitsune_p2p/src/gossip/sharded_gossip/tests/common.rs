@@ -88,6 +88,17 @@ impl KitsuneHost for StandardResponsesHostApi {
         box_fut(Ok(()))
     }
 
+    fn query_latest_metrics(
+        &self,
+        _space: Arc<KitsuneSpace>,
+    ) -> crate::KitsuneHostResult<Vec<MetricRecord>> {
+        box_fut(Ok(vec![]))
+    }
+
+    fn dht_storage_bytes(&self, _space: Arc<KitsuneSpace>) -> crate::KitsuneHostResult<u64> {
+        box_fut(Ok(0))
+    }
+
     fn get_topology(
         &self,
         _space: Arc<KitsuneSpace>,
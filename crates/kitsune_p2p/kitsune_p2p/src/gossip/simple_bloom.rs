@@ -248,6 +248,7 @@ enum CheckResult {
 
 pub(crate) struct SimpleBloomMod {
     cont: Arc<atomic::AtomicBool>,
+    paused: atomic::AtomicBool,
     tuning_params: KitsuneP2pTuningParams,
     send_interval_ms: u64,
     space: Arc<KitsuneSpace>,
@@ -280,6 +281,7 @@ impl SimpleBloomMod {
 
         let this = Arc::new(Self {
             cont: cont.clone(),
+            paused: atomic::AtomicBool::new(false),
             tuning_params,
             space,
             ep_hnd,
@@ -303,6 +305,10 @@ impl SimpleBloomMod {
                 ))
                 .await;
 
+                if gossip.paused.load(atomic::Ordering::Relaxed) {
+                    continue;
+                }
+
                 if let GossipIterationResult::Close = gossip.run_one_iteration().await {
                     break;
                 }
@@ -470,6 +476,9 @@ impl AsGossipModule for SimpleBloomMod {
         remote_url: TxUrl,
         gossip_data: Box<[u8]>,
     ) -> KitsuneResult<()> {
+        if self.paused.load(atomic::Ordering::Relaxed) {
+            return Ok(());
+        }
         use kitsune_p2p_types::codec::*;
         let (_, gossip) = GossipWire::decode_ref(&gossip_data).map_err(KitsuneError::other)?;
         self.inner.share_mut(move |i, _| {
@@ -497,6 +506,17 @@ impl AsGossipModule for SimpleBloomMod {
             Ok(())
         });
     }
+
+    fn set_paused(&self, paused: bool) {
+        self.paused.store(paused, atomic::Ordering::Relaxed);
+        if paused {
+            let _ = self.inner.share_mut(|i, _| {
+                i.incoming.clear();
+                i.outgoing.clear();
+                Ok(())
+            });
+        }
+    }
 }
 
 struct SimpleBloomModFactory;
@@ -1,4 +1,6 @@
+use kitsune_p2p_types::box_fut;
 use must_future::MustBoxFuture;
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use kitsune_p2p_types::{
@@ -53,8 +55,28 @@ pub trait KitsuneHost: 'static + Send + Sync {
         records: Vec<MetricRecord>,
     ) -> KitsuneHostResult<()>;
 
+    /// Get the most recently recorded metric of each kind
+    /// for each remote agent
+    fn query_latest_metrics(
+        &self,
+        space: Arc<KitsuneSpace>,
+    ) -> KitsuneHostResult<Vec<MetricRecord>>;
+
+    /// Get the number of bytes the host is using to store DHT data for this space
+    fn dht_storage_bytes(&self, space: Arc<KitsuneSpace>) -> KitsuneHostResult<u64>;
+
     /// Get the quantum Topology associated with this Space
     fn get_topology(&self, space: Arc<KitsuneSpace>) -> KitsuneHostResult<Topology>;
+
+    /// Get the tuning params this space should use instead of the global ones,
+    /// keyed by param name, as in the config. Called once when the space is
+    /// created. By default no params are overridden.
+    fn space_tuning_param_overrides(
+        &self,
+        _space: Arc<KitsuneSpace>,
+    ) -> KitsuneHostResult<HashMap<String, String>> {
+        box_fut(Ok(HashMap::new()))
+    }
 }
 
 /// Trait object for the host interface
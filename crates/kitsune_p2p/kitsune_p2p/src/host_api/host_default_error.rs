@@ -49,6 +49,18 @@ pub trait KitsuneHostDefaultError: KitsuneHost {
         .into()))
     }
 
+    fn query_latest_metrics(
+        &self,
+        _space: Arc<KitsuneSpace>,
+    ) -> KitsuneHostResult<Vec<MetricRecord>> {
+        box_fut(Err(format!(
+            "error for unimplemented KitsuneHost test behavior: method {} of {}",
+            "query_latest_metrics",
+            Self::NAME
+        )
+        .into()))
+    }
+
     fn query_region_set(
         &self,
         _space: Arc<KitsuneSpace>,
@@ -79,6 +91,15 @@ pub trait KitsuneHostDefaultError: KitsuneHost {
         .into()))
     }
 
+    fn dht_storage_bytes(&self, _space: Arc<KitsuneSpace>) -> KitsuneHostResult<u64> {
+        box_fut(Err(format!(
+            "error for unimplemented KitsuneHost test behavior: method {} of {}",
+            "dht_storage_bytes",
+            Self::NAME
+        )
+        .into()))
+    }
+
     fn get_topology(&self, _space: Arc<KitsuneSpace>) -> KitsuneHostResult<Topology> {
         box_fut(Err(format!(
             "error for unimplemented KitsuneHost test behavior: method {} of {}",
@@ -113,6 +134,13 @@ impl<T: KitsuneHostDefaultError> KitsuneHost for T {
         KitsuneHostDefaultError::record_metrics(self, space, records)
     }
 
+    fn query_latest_metrics(
+        &self,
+        space: Arc<KitsuneSpace>,
+    ) -> KitsuneHostResult<Vec<MetricRecord>> {
+        KitsuneHostDefaultError::query_latest_metrics(self, space)
+    }
+
     fn query_size_limited_regions(
         &self,
         space: Arc<KitsuneSpace>,
@@ -130,6 +158,10 @@ impl<T: KitsuneHostDefaultError> KitsuneHost for T {
         KitsuneHostDefaultError::query_region_set(self, space, dht_arc_set)
     }
 
+    fn dht_storage_bytes(&self, space: Arc<KitsuneSpace>) -> KitsuneHostResult<u64> {
+        KitsuneHostDefaultError::dht_storage_bytes(self, space)
+    }
+
     fn get_topology(&self, space: Arc<KitsuneSpace>) -> KitsuneHostResult<Topology> {
         KitsuneHostDefaultError::get_topology(self, space)
     }
@@ -101,6 +101,9 @@ const MAX_TRIGGERS: u8 = 2;
 /// per remote node.
 const MAX_HISTORY: usize = 10;
 
+/// The latency at which a remote node's score is halved.
+const HALF_SCORE_LATENCY_MICROS: f32 = 1_000_000.0;
+
 #[derive(Debug, Clone, Default)]
 /// Information about a remote node.
 struct NodeInfo {
@@ -122,6 +125,8 @@ struct NodeInfo {
     complete_rounds: VecDeque<Instant>,
     /// Is this node currently in an active round?
     current_round: bool,
+    /// Times data sent by this node failed validation.
+    validation_failures: u32,
 }
 
 #[derive(Debug, Default)]
@@ -137,6 +142,12 @@ pub struct Metrics {
     // Number of times we need to force initiate
     // the next round.
     force_initiates: u8,
+
+    /// Total bytes of gossip and publish messages sent for this space.
+    bytes_sent: u64,
+
+    /// Total bytes of gossip and publish messages received for this space.
+    bytes_received: u64,
 }
 
 /// Outcome of a gossip round.
@@ -204,6 +215,14 @@ impl Metrics {
                 expires_at_utc: expires_at,
                 data: serde_json::json!(*node.latency_micros),
             });
+
+            out.push(MetricRecord {
+                kind: MetricRecordKind::ValidationFailures,
+                agent: Some(agent.clone()),
+                recorded_at_utc: now,
+                expires_at_utc: expires_at,
+                data: serde_json::json!(node.validation_failures),
+            });
         }
 
         out.push(MetricRecord {
@@ -217,6 +236,30 @@ impl Metrics {
         out
     }
 
+    /// Restore the remote node metrics from previously recorded
+    /// historical metrics, so they survive a restart.
+    pub fn restore_historical(&mut self, records: Vec<MetricRecord>) {
+        for record in records {
+            let (agent, value) = match (record.agent, record.data.as_f64()) {
+                (Some(agent), Some(value)) => (agent, value),
+                _ => continue,
+            };
+            let info = self.map.entry(agent).or_default();
+            match record.kind {
+                MetricRecordKind::ReachabilityQuotient => {
+                    info.reachability_quotient = value.into();
+                }
+                MetricRecordKind::LatencyMicros => {
+                    info.latency_micros = value.into();
+                }
+                MetricRecordKind::ValidationFailures => {
+                    info.validation_failures = value as u32;
+                }
+                _ => (),
+            }
+        }
+    }
+
     /// Dump json encoded metrics
     pub fn dump(&self) -> serde_json::Value {
         let agents: serde_json::Value = self
@@ -228,6 +271,12 @@ impl Metrics {
                     serde_json::json!({
                         "reachability_quotient": *i.reachability_quotient,
                         "latency_micros": *i.latency_micros,
+                        "complete_rounds": i.complete_rounds.len(),
+                        "errors": i.errors.len(),
+                        "current_round": i.current_round,
+                        "last_outcome": last_outcome_str(i),
+                        "validation_failures": i.validation_failures,
+                        "score": i.score(),
                     }),
                 )
             })
@@ -236,10 +285,51 @@ impl Metrics {
 
         serde_json::json!({
             "aggExtrapCov": *self.agg_extrap_cov,
+            "bytesSent": self.bytes_sent,
+            "bytesReceived": self.bytes_received,
             "agents": agents,
         })
     }
 
+    /// Dump a json encoded summary of these metrics,
+    /// aggregated over all remote nodes.
+    pub fn summary(&self) -> serde_json::Value {
+        let mut complete_rounds = 0;
+        let mut errors = 0;
+        let mut current_rounds = 0;
+        let mut latency_micros = RunAvg::default();
+        for info in self.map.values() {
+            complete_rounds += info.complete_rounds.len();
+            errors += info.errors.len();
+            if info.current_round {
+                current_rounds += 1;
+            }
+            if info.latency_micros.1 > 0 {
+                latency_micros.push(*info.latency_micros);
+            }
+        }
+        serde_json::json!({
+            "aggExtrapCov": *self.agg_extrap_cov,
+            "bytesSent": self.bytes_sent,
+            "bytesReceived": self.bytes_received,
+            "remoteNodes": self.map.len(),
+            "completeRounds": complete_rounds,
+            "errors": errors,
+            "currentRounds": current_rounds,
+            "avgLatencyMicros": *latency_micros,
+        })
+    }
+
+    /// Record bytes sent to remote nodes in this space.
+    pub fn record_bytes_sent(&mut self, bytes: usize) {
+        self.bytes_sent = self.bytes_sent.saturating_add(bytes as u64);
+    }
+
+    /// Record bytes received from remote nodes in this space.
+    pub fn record_bytes_received(&mut self, bytes: usize) {
+        self.bytes_received = self.bytes_received.saturating_add(bytes as u64);
+    }
+
     /// Record an individual extrapolated coverage event
     /// (either from us or a remote)
     /// and add it to our running aggregate extrapolated coverage metric.
@@ -442,6 +532,41 @@ impl Metrics {
         }
     }
 
+    /// Record that data sent by these remote agents failed validation.
+    pub fn record_validation_failure<'a, T, I>(&mut self, remote_agent_list: I)
+    where
+        T: Into<AgentLike<'a>>,
+        I: IntoIterator<Item = T>,
+    {
+        for agent_info in remote_agent_list {
+            let info = self
+                .map
+                .entry(agent_info.into().agent().clone())
+                .or_default();
+            info.validation_failures = info.validation_failures.saturating_add(1);
+        }
+    }
+
+    /// Return the average (mean) score between 0 and 1 for the supplied
+    /// remote agents, combining their reachability, latency and
+    /// validation failures. Agents we know nothing about score 1.
+    pub fn peer_score<'a, T, I>(&self, remote_agent_list: I) -> f32
+    where
+        T: Into<AgentLike<'a>>,
+        I: IntoIterator<Item = T>,
+    {
+        let (sum, cnt) = remote_agent_list
+            .into_iter()
+            .filter_map(|agent_info| self.map.get(agent_info.into().agent()))
+            .map(|info| info.score())
+            .fold((0.0, 0.0), |acc, x| (acc.0 + x, acc.1 + 1.0));
+        if cnt <= 0.0 {
+            1.0
+        } else {
+            sum / cnt
+        }
+    }
+
     /// Return the average (mean) latency microseconds for the
     /// supplied remote agents.
     pub fn latency_micros<'a, T, I>(&self, remote_agent_list: I) -> f32
@@ -463,6 +588,19 @@ impl Metrics {
 }
 
 impl NodeInfo {
+    /// This node's score between 0 and 1.
+    fn score(&self) -> f32 {
+        let reachability = if self.reachability_quotient.1 > 0 {
+            *self.reachability_quotient / 100.0
+        } else {
+            1.0
+        };
+        let latency =
+            HALF_SCORE_LATENCY_MICROS / (HALF_SCORE_LATENCY_MICROS + *self.latency_micros);
+        let validity = 1.0 / (1.0 + self.validation_failures as f32);
+        reachability * latency * validity
+    }
+
     /// Was the last round for this node initiated by us?
     fn is_initiate_round(&self) -> bool {
         match (self.remote_rounds.back(), self.initiates.back()) {
@@ -473,6 +611,15 @@ impl NodeInfo {
     }
 }
 
+fn last_outcome_str(info: &NodeInfo) -> Option<&'static str> {
+    match (info.errors.back(), info.complete_rounds.back()) {
+        (Some(error), Some(success)) if error >= success => Some("error"),
+        (Some(_), None) => Some("error"),
+        (_, Some(_)) => Some("success"),
+        (None, None) => None,
+    }
+}
+
 fn record_instant(buffer: &mut VecDeque<Instant>) {
     if buffer.len() > MAX_HISTORY {
         buffer.pop_front();
@@ -658,4 +805,52 @@ mod tests {
         a5.push_n(1, 255);
         assert_eq!(1.0, *a5);
     }
+
+    #[test]
+    fn test_dump_rounds_and_bytes() {
+        let agent = Arc::new(KitsuneAgent::new(vec![0; 36]));
+        let mut metrics = Metrics::default();
+        metrics.record_initiate([&agent]);
+        metrics.record_success([&agent]);
+        metrics.record_bytes_sent(100);
+        metrics.record_bytes_sent(20);
+        metrics.record_bytes_received(7);
+
+        let dump = metrics.dump();
+        assert_eq!(dump["bytesSent"], 120);
+        assert_eq!(dump["bytesReceived"], 7);
+        let info = &dump["agents"][agent.to_string()];
+        assert_eq!(info["complete_rounds"], 1);
+        assert_eq!(info["errors"], 0);
+        assert_eq!(info["last_outcome"], "success");
+
+        metrics.record_error([&agent]);
+        let summary = metrics.summary();
+        assert_eq!(summary["remoteNodes"], 1);
+        assert_eq!(summary["completeRounds"], 1);
+        assert_eq!(summary["errors"], 1);
+        assert_eq!(
+            metrics.dump()["agents"][agent.to_string()]["last_outcome"],
+            "error"
+        );
+    }
+
+    #[test]
+    fn test_peer_score_survives_restore() {
+        let agent = Arc::new(KitsuneAgent::new(vec![0; 36]));
+        let mut metrics = Metrics::default();
+
+        // - Unknown agents get the benefit of the doubt.
+        assert_eq!(1.0, metrics.peer_score([&agent]));
+
+        metrics.record_reachability_event(true, [&agent]);
+        metrics.record_validation_failure([&agent]);
+        let score = metrics.peer_score([&agent]);
+        assert_eq!(0.5, score);
+
+        // - The score is the same once restored from the historical records.
+        let mut restored = Metrics::default();
+        restored.restore_historical(metrics.dump_historical());
+        assert_eq!(score, restored.peer_score([&agent]));
+    }
 }
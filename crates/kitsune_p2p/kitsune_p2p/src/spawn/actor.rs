@@ -12,6 +12,7 @@ use futures::future::FutureExt;
 use futures::stream::StreamExt;
 use kitsune_p2p_proxy::tx2::*;
 use kitsune_p2p_transport_quic::tx2::*;
+use kitsune_p2p_transport_webrtc::tx2::*;
 use kitsune_p2p_types::agent_info::AgentInfoSigned;
 use kitsune_p2p_types::async_lazy::AsyncLazy;
 use kitsune_p2p_types::tx2::tx2_api::*;
@@ -55,6 +56,7 @@ ghost_actor::ghost_chan! {
             space: KSpace,
             basis: KBasis,
             to_agent: KAgent,
+            from_peer: Tx2Cert,
             mod_idx: u32,
             mod_cnt: u32,
             destination: BroadcastTo,
@@ -92,6 +94,8 @@ pub(crate) struct KitsuneP2pActor {
     config: Arc<KitsuneP2pConfig>,
     bandwidth_throttles: BandwidthThrottles,
     parallel_notify_permit: Arc<tokio::sync::Semaphore>,
+    bootstrap: bootstrap::BootstrapClient,
+    gossip_paused: bool,
 }
 
 impl KitsuneP2pActor {
@@ -133,6 +137,21 @@ impl KitsuneP2pActor {
                     bind_to,
                 )
             }
+            KitsuneP2pTx2Backend::WebRtc {
+                bind_to,
+                ice_servers,
+            } => {
+                let mut conf = WebRtcConfig::default();
+                conf.tls = Some(tls_config.clone());
+                conf.tuning_params = Some(config.tuning_params.clone());
+                conf.ice_servers = ice_servers;
+                (
+                    tx2_webrtc_adapter(conf)
+                        .await
+                        .map_err(KitsuneP2pError::other)?,
+                    bind_to,
+                )
+            }
             KitsuneP2pTx2Backend::Mock { mock_network } => {
                 is_mock = true;
                 (mock_network, "none:".into())
@@ -154,6 +173,9 @@ impl KitsuneP2pActor {
                 KitsuneP2pTx2ProxyConfig::Specific(proxy_url) => {
                     conf.client_of_remote_proxy = ProxyRemoteType::Specific(proxy_url);
                 }
+                KitsuneP2pTx2ProxyConfig::Relay(relay_url) => {
+                    conf.fallback_relay = Some(relay_url);
+                }
                 KitsuneP2pTx2ProxyConfig::Bootstrap {
                     bootstrap_url,
                     fallback_proxy_url,
@@ -261,7 +283,7 @@ impl KitsuneP2pActor {
                             }) => {
                                 let _ = i_s.del_con(url).await;
                             }
-                            IncomingRequest(Tx2EpIncomingRequest { data, respond, .. }) => {
+                            IncomingRequest(Tx2EpIncomingRequest { con, data, respond, .. }) => {
                                 match data {
                                     wire::Wire::Call(wire::Call {
                                         space,
@@ -270,7 +292,12 @@ impl KitsuneP2pActor {
                                         ..
                                     }) => {
                                         let res = match evt_sender
-                                            .call(space, to_agent, data.into())
+                                            .call(
+                                                space,
+                                                to_agent,
+                                                Some(con.peer_cert()),
+                                                data.into(),
+                                            )
                                             .await
                                         {
                                             Err(err) => {
@@ -324,6 +351,13 @@ impl KitsuneP2pActor {
                                 }
                             }
                             IncomingNotify(Tx2EpIncomingNotify { con, data, url, .. }) => {
+                                let data = match data.decompress() {
+                                    Ok(data) => data,
+                                    Err(err) => {
+                                        tracing::warn!(?err, "failed to decompress incoming notify");
+                                        return;
+                                    }
+                                };
                                 match data {
                                     wire::Wire::DelegateBroadcast(wire::DelegateBroadcast {
                                         space,
@@ -344,6 +378,7 @@ impl KitsuneP2pActor {
                                                 space,
                                                 basis,
                                                 to_agent,
+                                                con.peer_cert(),
                                                 mod_idx,
                                                 mod_cnt,
                                                 destination,
@@ -371,6 +406,7 @@ impl KitsuneP2pActor {
                                                 .notify(
                                                     space,
                                                     to_agent,
+                                                    Some(con.peer_cert()),
                                                     data.into(),
                                                 )
                                                 .await
@@ -448,6 +484,8 @@ impl KitsuneP2pActor {
         let parallel_notify_permit = Arc::new(tokio::sync::Semaphore::new(
             config.tuning_params.concurrent_limit_per_thread,
         ));
+        let bootstrap =
+            bootstrap::BootstrapClient::new(config.bootstrap_urls(), &config.tuning_params);
 
         Ok(Self {
             channel_factory,
@@ -459,6 +497,8 @@ impl KitsuneP2pActor {
             config: Arc::new(config),
             bandwidth_throttles,
             parallel_notify_permit,
+            bootstrap,
+            gossip_paused: false,
         })
     }
 }
@@ -506,6 +546,7 @@ impl InternalHandler for KitsuneP2pActor {
         space: Arc<KitsuneSpace>,
         basis: Arc<KitsuneBasis>,
         to_agent: Arc<KitsuneAgent>,
+        from_peer: Tx2Cert,
         mod_idx: u32,
         mod_cnt: u32,
         destination: BroadcastTo,
@@ -528,6 +569,7 @@ impl InternalHandler for KitsuneP2pActor {
                     space,
                     basis,
                     to_agent,
+                    from_peer,
                     mod_idx,
                     mod_cnt,
                     destination,
@@ -645,18 +687,20 @@ impl KitsuneP2pEventHandler for KitsuneP2pActor {
         &mut self,
         space: Arc<KitsuneSpace>,
         to_agent: Arc<KitsuneAgent>,
+        from_peer: Option<Tx2Cert>,
         payload: Vec<u8>,
     ) -> KitsuneP2pEventHandlerResult<Vec<u8>> {
-        Ok(self.evt_sender.call(space, to_agent, payload))
+        Ok(self.evt_sender.call(space, to_agent, from_peer, payload))
     }
 
     fn handle_notify(
         &mut self,
         space: Arc<KitsuneSpace>,
         to_agent: Arc<KitsuneAgent>,
+        from_peer: Option<Tx2Cert>,
         payload: Vec<u8>,
     ) -> KitsuneP2pEventHandlerResult<()> {
-        Ok(self.evt_sender.notify(space, to_agent, payload))
+        Ok(self.evt_sender.notify(space, to_agent, from_peer, payload))
     }
 
     fn handle_gossip(
@@ -710,9 +754,27 @@ impl KitsuneP2pHandler for KitsuneP2pActor {
         let config = Arc::clone(&self.config);
         let bandwidth_throttles = self.bandwidth_throttles.clone();
         let parallel_notify_permit = self.parallel_notify_permit.clone();
+        let bootstrap = self.bootstrap.clone();
+        let gossip_paused = self.gossip_paused;
         let space_sender = match self.spaces.entry(space.clone()) {
             Entry::Occupied(entry) => entry.into_mut(),
             Entry::Vacant(entry) => entry.insert(AsyncLazy::new(async move {
+                let config = match host.space_tuning_param_overrides(space2.clone()).await {
+                    Ok(overrides) if !overrides.is_empty() => {
+                        let mut space_config = (*config).clone();
+                        space_config.tuning_params =
+                            Arc::new(config.tuning_params.with_overrides(&overrides));
+                        Arc::new(space_config)
+                    }
+                    Ok(_) => config,
+                    Err(err) => {
+                        tracing::warn!(
+                            ?err,
+                            "failed to get space tuning params, using the global ones"
+                        );
+                        config
+                    }
+                };
                 let (send, send_inner, evt_recv) = spawn_space(
                     space2,
                     ep_hnd,
@@ -720,9 +782,15 @@ impl KitsuneP2pHandler for KitsuneP2pActor {
                     config,
                     bandwidth_throttles,
                     parallel_notify_permit,
+                    bootstrap,
                 )
                 .await
                 .expect("cannot fail to create space");
+                if gossip_paused {
+                    send.set_gossip_paused(true)
+                        .await
+                        .expect("cannot fail to pause gossip");
+                }
                 internal_sender
                     .register_space_event_handler(evt_recv)
                     .await
@@ -873,6 +941,23 @@ impl KitsuneP2pHandler for KitsuneP2pActor {
         .into())
     }
 
+    fn handle_record_validation_failure(
+        &mut self,
+        space: Arc<KitsuneSpace>,
+        agent: Arc<KitsuneAgent>,
+    ) -> KitsuneP2pHandlerResult<()> {
+        let space_sender = match self.spaces.get_mut(&space) {
+            None => return Err(KitsuneP2pError::RoutingSpaceError(space)),
+            Some(space) => space.get(),
+        };
+        Ok(async move {
+            let (space_sender, _) = space_sender.await;
+            space_sender.record_validation_failure(space, agent).await
+        }
+        .boxed()
+        .into())
+    }
+
     fn handle_dump_network_metrics(
         &mut self,
         space: Option<Arc<KitsuneSpace>>,
@@ -901,6 +986,93 @@ impl KitsuneP2pHandler for KitsuneP2pActor {
         .boxed()
         .into())
     }
+
+    fn handle_dump_network_stats(&mut self) -> KitsuneP2pHandlerResult<serde_json::Value> {
+        let spaces = self.spaces.values().map(|s| s.get()).collect::<Vec<_>>();
+        let transport = self.ep_hnd.debug();
+        Ok(async move {
+            let mut all = Vec::new();
+            for (space, _) in futures::future::join_all(spaces).await {
+                all.push(space.dump_network_stats());
+            }
+            let spaces = futures::future::try_join_all(all).await?;
+            Ok(serde_json::json!({
+                "transport": transport,
+                "spaces": spaces,
+            }))
+        }
+        .boxed()
+        .into())
+    }
+
+    fn handle_bootstrap_health(
+        &mut self,
+    ) -> KitsuneP2pHandlerResult<Vec<kitsune_p2p_types::bootstrap::BootstrapServerHealth>> {
+        let health = self.bootstrap.health();
+        Ok(async move { Ok(health) }.boxed().into())
+    }
+
+    fn handle_set_gossip_paused(&mut self, paused: bool) -> KitsuneP2pHandlerResult<()> {
+        self.gossip_paused = paused;
+        let spaces = self.spaces.values().map(|s| s.get()).collect::<Vec<_>>();
+        Ok(async move {
+            for (space, _) in futures::future::join_all(spaces).await {
+                space.set_gossip_paused(paused).await?;
+            }
+            Ok(())
+        }
+        .boxed()
+        .into())
+    }
+
+    fn handle_update_tuning_params(
+        &mut self,
+        tuning_params: kitsune_p2p_types::config::KitsuneP2pTuningParams,
+    ) -> KitsuneP2pHandlerResult<()> {
+        let mut config = (*self.config).clone();
+        config.tuning_params = tuning_params.clone();
+        self.config = Arc::new(config);
+        let host = self.host.clone();
+        let spaces = self
+            .spaces
+            .iter()
+            .map(|(space, s)| {
+                let space = space.clone();
+                let s = s.get();
+                async move { (space, s.await) }
+            })
+            .collect::<Vec<_>>();
+        Ok(async move {
+            for (space, (sender, _)) in futures::future::join_all(spaces).await {
+                let space_params = match host.space_tuning_param_overrides(space).await {
+                    Ok(overrides) if !overrides.is_empty() => {
+                        Arc::new(tuning_params.with_overrides(&overrides))
+                    }
+                    Ok(_) => tuning_params.clone(),
+                    Err(err) => {
+                        tracing::warn!(
+                            ?err,
+                            "failed to get space tuning params, using the global ones"
+                        );
+                        tuning_params.clone()
+                    }
+                };
+                sender.update_tuning_params(space_params).await?;
+            }
+            Ok(())
+        }
+        .boxed()
+        .into())
+    }
+
+    fn handle_set_bootstrap_urls(&mut self, urls: Vec<url2::Url2>) -> KitsuneP2pHandlerResult<()> {
+        let mut config = (*self.config).clone();
+        config.bootstrap_service = urls.first().cloned();
+        config.bootstrap_services = urls.iter().skip(1).cloned().collect();
+        self.config = Arc::new(config);
+        self.bootstrap.set_urls(urls);
+        unit_ok_fut()
+    }
 }
 
 #[cfg(any(test, feature = "test_utils"))]
@@ -930,6 +1102,7 @@ mockall::mock! {
             &mut self,
             space: Arc<KitsuneSpace>,
             to_agent: Arc<KitsuneAgent>,
+            from_peer: Option<Tx2Cert>,
             payload: Vec<u8>,
         ) -> KitsuneP2pEventHandlerResult<Vec<u8>>;
 
@@ -937,6 +1110,7 @@ mockall::mock! {
             &mut self,
             space: Arc<KitsuneSpace>,
             to_agent: Arc<KitsuneAgent>,
+            from_peer: Option<Tx2Cert>,
             payload: Vec<u8>,
         ) -> KitsuneP2pEventHandlerResult<()> ;
 
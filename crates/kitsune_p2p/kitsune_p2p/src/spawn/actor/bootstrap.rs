@@ -1,10 +1,14 @@
 use crate::types::actor::KitsuneP2pResult;
 use crate::types::agent_store::AgentInfoSigned;
+use ghost_actor::dependencies::tracing;
+use kitsune_p2p_types::bootstrap::BootstrapServerHealth;
 use kitsune_p2p_types::bootstrap::RandomQuery;
+use kitsune_p2p_types::config::KitsuneP2pTuningParams;
 use once_cell::sync::Lazy;
 use once_cell::sync::OnceCell;
 use std::convert::TryFrom;
 use std::convert::TryInto;
+use std::sync::Arc;
 use url2::Url2;
 
 /// Reuse a single reqwest Client for efficiency as we likely need several connections.
@@ -177,6 +181,164 @@ pub async fn proxy_list(url: Url2) -> KitsuneP2pResult<Vec<Url2>> {
         .collect())
 }
 
+/// Client for all the bootstrap servers a node is configured with.
+///
+/// Agent infos are put to every server, so each server can hand them out,
+/// while peers are fetched from the first server that responds. Failed
+/// requests are retried against the same server with a jittered backoff
+/// before moving on, and servers that have been failing are tried last.
+#[derive(Clone)]
+pub(crate) struct BootstrapClient(Arc<BootstrapClientInner>);
+
+struct BootstrapClientInner {
+    health: parking_lot::Mutex<Vec<BootstrapServerHealth>>,
+    retry_count: u32,
+    retry_delay_ms: u64,
+}
+
+impl BootstrapClient {
+    pub fn new(urls: Vec<Url2>, tuning_params: &KitsuneP2pTuningParams) -> Self {
+        Self(Arc::new(BootstrapClientInner {
+            health: parking_lot::Mutex::new(
+                urls.into_iter().map(BootstrapServerHealth::new).collect(),
+            ),
+            retry_count: std::cmp::max(1, tuning_params.bootstrap_retry_count),
+            retry_delay_ms: tuning_params.bootstrap_retry_delay_ms as u64,
+        }))
+    }
+
+    /// The current health of each server, in configured order.
+    pub fn health(&self) -> Vec<BootstrapServerHealth> {
+        self.0.health.lock().clone()
+    }
+
+    /// Switch to a new list of servers, keeping the health of the ones
+    /// which were already configured.
+    pub fn set_urls(&self, urls: Vec<Url2>) {
+        let mut health = self.0.health.lock();
+        let old = std::mem::take(&mut *health);
+        *health = urls
+            .into_iter()
+            .map(|url| match old.iter().find(|h| h.url == url) {
+                Some(h) => h.clone(),
+                None => BootstrapServerHealth::new(url),
+            })
+            .collect();
+    }
+
+    /// Put an agent info to every server.
+    /// Succeeds if any server accepted it, or if there are no servers.
+    pub async fn put(&self, agent_info_signed: AgentInfoSigned) -> KitsuneP2pResult<()> {
+        let urls = self.urls();
+        if urls.is_empty() {
+            return Ok(());
+        }
+        let results = futures::future::join_all(urls.into_iter().map(|url| {
+            let agent_info_signed = agent_info_signed.clone();
+            self.with_retry(url.clone(), move || {
+                put(Some(url.clone()), agent_info_signed.clone())
+            })
+        }))
+        .await;
+        let mut last_err = None;
+        for r in results {
+            match r {
+                Ok(()) => return Ok(()),
+                Err(e) => last_err = Some(e),
+            }
+        }
+        Err(last_err.expect("at least one server"))
+    }
+
+    /// Fetch random agent infos from the healthiest server that responds.
+    /// Returns an empty list if there are no servers.
+    pub async fn random(&self, query: RandomQuery) -> KitsuneP2pResult<Vec<AgentInfoSigned>> {
+        let mut urls = self.urls();
+        {
+            use rand::seq::SliceRandom;
+            urls.shuffle(&mut rand::thread_rng());
+        }
+        {
+            // stable sort, so servers that are equally healthy stay shuffled
+            let health = self.0.health.lock();
+            urls.sort_by_key(|url| {
+                health
+                    .iter()
+                    .find(|h| &h.url == url)
+                    .map(|h| h.consecutive_failures)
+            });
+        }
+        let mut last_err = None;
+        for url in urls {
+            let query = query.clone();
+            match self
+                .with_retry(url.clone(), move || {
+                    random(Some(url.clone()), query.clone())
+                })
+                .await
+            {
+                Ok(list) => return Ok(list),
+                Err(e) => last_err = Some(e),
+            }
+        }
+        match last_err {
+            Some(e) => Err(e),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    fn urls(&self) -> Vec<Url2> {
+        self.0.health.lock().iter().map(|h| h.url.clone()).collect()
+    }
+
+    /// Make a request to a single server, retrying on failure,
+    /// and record how it went in the server's health.
+    async fn with_retry<T, F, Fut>(&self, url: Url2, f: F) -> KitsuneP2pResult<T>
+    where
+        F: Fn() -> Fut,
+        Fut: std::future::Future<Output = KitsuneP2pResult<T>>,
+    {
+        let mut delay_ms = self.0.retry_delay_ms;
+        let mut attempt = 1;
+        loop {
+            let res = f().await;
+            self.record(&url, res.as_ref().err());
+            match res {
+                Ok(r) => return Ok(r),
+                Err(e) if attempt >= self.0.retry_count => return Err(e),
+                Err(e) => {
+                    tracing::debug!(%url, ?e, attempt, "bootstrap request failed, retrying");
+                    let jittered = {
+                        use rand::Rng;
+                        rand::thread_rng().gen_range(delay_ms / 2..=delay_ms)
+                    };
+                    tokio::time::sleep(std::time::Duration::from_millis(jittered)).await;
+                    delay_ms *= 2;
+                    attempt += 1;
+                }
+            }
+        }
+    }
+
+    fn record(&self, url: &Url2, err: Option<&crate::KitsuneP2pError>) {
+        let now = local_now().ok();
+        let mut health = self.0.health.lock();
+        if let Some(h) = health.iter_mut().find(|h| &h.url == url) {
+            match err {
+                None => {
+                    h.last_success_ms = now;
+                    h.consecutive_failures = 0;
+                }
+                Some(err) => {
+                    h.last_failure_ms = now;
+                    h.last_error = Some(err.to_string());
+                    h.consecutive_failures += 1;
+                }
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -376,4 +538,55 @@ mod tests {
         assert!(random_single.len() == 1);
         assert!(expected[0] == random_single[0] || expected[1] == random_single[0]);
     }
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_client_falls_back_to_healthy_server() {
+        let (driver, addr, shutdown) = kitsune_p2p_bootstrap::run(([127, 0, 0, 1], 0), vec![])
+            .await
+            .unwrap();
+        tokio::task::spawn(driver);
+        let live = url2::url2!("http://{}", addr);
+        // nothing listens on port 1
+        let dead = url2::url2!("http://127.0.0.1:1");
+
+        let mut tuning_params =
+            kitsune_p2p_types::config::tuning_params_struct::KitsuneP2pTuningParams::default();
+        tuning_params.bootstrap_retry_count = 2;
+        tuning_params.bootstrap_retry_delay_ms = 10;
+        let tuning_params = Arc::new(tuning_params);
+        let query = RandomQuery {
+            space: Arc::new(fixt!(KitsuneSpace, Unpredictable)),
+            ..Default::default()
+        };
+
+        // a dead server is retried, then the error is returned
+        let client = BootstrapClient::new(vec![dead.clone()], &tuning_params);
+        assert!(client.random(query.clone()).await.is_err());
+        let health = client.health();
+        assert_eq!(2, health[0].consecutive_failures);
+        assert!(health[0].last_error.is_some());
+        assert!(health[0].last_success_ms.is_none());
+
+        // with a live server as well, we get an answer
+        // (the live server has no agents in this space, but it responds)
+        let client = BootstrapClient::new(vec![dead.clone(), live.clone()], &tuning_params);
+        for _ in 0..3 {
+            let list = client.random(query.clone()).await.unwrap();
+            assert!(list.is_empty());
+        }
+        let health = client.health();
+        assert_eq!(live, health[1].url);
+        assert!(health[1].last_success_ms.is_some());
+        assert_eq!(0, health[1].consecutive_failures);
+        // once the dead server has failed, the live server is tried first
+        assert!(health[0].consecutive_failures <= 2);
+
+        // switching servers keeps the health of the ones still configured
+        client.set_urls(vec![live.clone()]);
+        let health = client.health();
+        assert_eq!(1, health.len());
+        assert_eq!(live, health[0].url);
+        assert!(health[0].last_success_ms.is_some());
+
+        shutdown();
+    }
 }
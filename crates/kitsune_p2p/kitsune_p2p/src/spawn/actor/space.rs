@@ -1,3 +1,4 @@
+use super::bootstrap::BootstrapClient;
 use super::*;
 use crate::metrics::*;
 use crate::types::gossip::GossipModule;
@@ -9,7 +10,6 @@ use kitsune_p2p_types::dht_arc::{DhtArc, DhtArcRange, DhtArcSet};
 use kitsune_p2p_types::tx2::tx2_utils::TxUrl;
 use std::collections::{HashMap, HashSet};
 use std::sync::atomic::AtomicBool;
-use url2::Url2;
 
 /// How often to record historical metrics
 /// (currently once per hour)
@@ -57,6 +57,7 @@ ghost_actor::ghost_chan! {
             space: KSpace,
             basis: KBasis,
             to_agent: KAgent,
+            from_peer: Tx2Cert,
             mod_idx: u32,
             mod_cnt: u32,
             destination: BroadcastTo,
@@ -84,6 +85,7 @@ pub(crate) async fn spawn_space(
     config: Arc<KitsuneP2pConfig>,
     bandwidth_throttles: BandwidthThrottles,
     parallel_notify_permit: Arc<tokio::sync::Semaphore>,
+    bootstrap: BootstrapClient,
 ) -> KitsuneP2pResult<(
     ghost_actor::GhostSender<KitsuneP2p>,
     ghost_actor::GhostSender<SpaceInternal>,
@@ -112,6 +114,7 @@ pub(crate) async fn spawn_space(
         config,
         bandwidth_throttles,
         parallel_notify_permit,
+        bootstrap,
     )));
 
     Ok((sender, i_s, evt_recv))
@@ -145,7 +148,7 @@ impl SpaceInternalHandler for Space {
 
     fn handle_update_agent_info(&mut self) -> SpaceInternalHandlerResult<()> {
         let space = self.space.clone();
-        let mut mdns_handles = self.mdns_handles.clone();
+        let mdns_handles = self.mdns_handles.clone();
         let network_type = self.config.network_type.clone();
         let mut agent_list = Vec::with_capacity(self.local_joined_agents.len());
         for agent in self.local_joined_agents.iter().cloned() {
@@ -154,13 +157,15 @@ impl SpaceInternalHandler for Space {
         }
         let ep_hnd = self.ro_inner.ep_hnd.clone();
         let evt_sender = self.evt_sender.clone();
-        let bootstrap_service = self.config.bootstrap_service.clone();
+        let bootstrap = self.ro_inner.bootstrap.clone();
         let expires_after = self.config.tuning_params.agent_info_expires_after_ms as u64;
         let dynamic_arcs = self.config.tuning_params.gossip_dynamic_arcs;
         let single_storage_arc_per_space = self
             .config
             .tuning_params
             .gossip_single_storage_arc_per_space;
+        let arc_limits = ArcLimits::new(&self.config.tuning_params);
+        let host_api = self.ro_inner.host_api.clone();
         let internal_sender = self.i_s.clone();
         Ok(async move {
             let urls = vec![ep_hnd.local_addr()?];
@@ -175,10 +180,12 @@ impl SpaceInternalHandler for Space {
                     evt_sender: &evt_sender,
                     internal_sender: &internal_sender,
                     network_type: network_type.clone(),
-                    mdns_handles: &mut mdns_handles,
-                    bootstrap_service: &bootstrap_service,
+                    mdns_handles: &mdns_handles,
+                    bootstrap: &bootstrap,
                     dynamic_arcs,
                     single_storage_arc_per_space,
+                    arc_limits,
+                    host_api: &host_api,
                 };
                 peer_data.push(update_single_agent_info(input).await?);
             }
@@ -199,18 +206,20 @@ impl SpaceInternalHandler for Space {
         agent: Arc<KitsuneAgent>,
     ) -> SpaceInternalHandlerResult<()> {
         let space = self.space.clone();
-        let mut mdns_handles = self.mdns_handles.clone();
+        let mdns_handles = self.mdns_handles.clone();
         let network_type = self.config.network_type.clone();
         let ep_hnd = self.ro_inner.ep_hnd.clone();
         let evt_sender = self.evt_sender.clone();
         let internal_sender = self.i_s.clone();
-        let bootstrap_service = self.config.bootstrap_service.clone();
+        let bootstrap = self.ro_inner.bootstrap.clone();
         let expires_after = self.config.tuning_params.agent_info_expires_after_ms as u64;
         let dynamic_arcs = self.config.tuning_params.gossip_dynamic_arcs;
         let single_storage_arc_per_space = self
             .config
             .tuning_params
             .gossip_single_storage_arc_per_space;
+        let arc_limits = ArcLimits::new(&self.config.tuning_params);
+        let host_api = self.ro_inner.host_api.clone();
         let arc = self.get_agent_arc(&agent);
 
         Ok(async move {
@@ -224,10 +233,12 @@ impl SpaceInternalHandler for Space {
                 evt_sender: &evt_sender,
                 internal_sender: &internal_sender,
                 network_type: network_type.clone(),
-                mdns_handles: &mut mdns_handles,
-                bootstrap_service: &bootstrap_service,
+                mdns_handles: &mdns_handles,
+                bootstrap: &bootstrap,
                 dynamic_arcs,
                 single_storage_arc_per_space,
+                arc_limits,
+                host_api: &host_api,
             };
             let peer_data = vec![update_single_agent_info(input).await?];
             internal_sender
@@ -294,11 +305,17 @@ impl SpaceInternalHandler for Space {
         space: Arc<KitsuneSpace>,
         basis: Arc<KitsuneBasis>,
         _to_agent: Arc<KitsuneAgent>,
+        from_peer: Tx2Cert,
         mod_idx: u32,
         mod_cnt: u32,
         destination: BroadcastTo,
         data: crate::wire::WireData,
     ) -> InternalHandlerResult<()> {
+        self.ro_inner
+            .metrics
+            .write()
+            .record_bytes_received(data.len());
+
         // first, forward this incoming broadcast to all connected
         // local agents.
         let mut local_notify_events = Vec::new();
@@ -311,6 +328,7 @@ impl SpaceInternalHandler for Space {
                             let fut = self.evt_sender.notify(
                                 space.clone(),
                                 agent.clone(),
+                                Some(from_peer.clone()),
                                 data.clone().into(),
                             );
                             local_notify_events.push(async move {
@@ -356,6 +374,10 @@ impl SpaceInternalHandler for Space {
             futures::future::join_all(local_agent_info_events).await;
 
             let info_list = fut.await?;
+            let compression_threshold = ro_inner
+                .config
+                .tuning_params
+                .wire_compression_threshold_bytes;
 
             // for all agents in the gathered list, check the modulo params
             // i.e. if `agent.get_loc() % mod_cnt == mod_idx` we know we are
@@ -370,6 +392,7 @@ impl SpaceInternalHandler for Space {
                 let data = data.clone();
                 all.push(async move {
                     use discover::PeerDiscoverResult;
+                    let metrics = ro_inner.metrics.clone();
 
                     // attempt to establish a connection
                     let con_hnd = match discover::peer_connect(ro_inner, &info, timeout).await {
@@ -381,13 +404,14 @@ impl SpaceInternalHandler for Space {
                         }
                     };
 
-                    // generate our broadcast payload
+                    // generate and forward our broadcast payload
+                    let bytes = data.len();
                     let payload =
-                        wire::Wire::broadcast(space, info.agent.clone(), destination, data);
-
-                    // forward the data
-                    if let Err(err) = con_hnd.notify(&payload, timeout).await {
-                        tracing::warn!(?err, "broadcast error");
+                        wire::Wire::broadcast(space, info.agent.clone(), destination, data)
+                            .compress_for([&info], compression_threshold);
+                    match con_hnd.notify(&payload, timeout).await {
+                        Ok(_) => metrics.write().record_bytes_sent(bytes),
+                        Err(err) => tracing::warn!(?err, "broadcast error"),
                     }
                 })
             }
@@ -451,14 +475,53 @@ struct UpdateAgentInfoInput<'borrow> {
     evt_sender: &'borrow futures::channel::mpsc::Sender<KitsuneP2pEvent>,
     internal_sender: &'borrow ghost_actor::GhostSender<SpaceInternal>,
     network_type: NetworkType,
-    mdns_handles: &'borrow mut HashMap<Vec<u8>, Arc<AtomicBool>>,
-    bootstrap_service: &'borrow Option<Url2>,
+    mdns_handles: &'borrow MdnsHandles,
+    bootstrap: &'borrow BootstrapClient,
     dynamic_arcs: bool,
     single_storage_arc_per_space: bool,
+    arc_limits: ArcLimits,
+    host_api: &'borrow HostApi,
+}
+
+/// Bounds on how far a storage arc may be resized.
+#[derive(Debug, Clone, Copy)]
+struct ArcLimits {
+    /// The longest an arc may grow to.
+    max_length: u64,
+    /// Above this many bytes of stored DHT data, arcs shrink rather than grow.
+    /// Zero if unlimited.
+    storage_limit_bytes: u64,
+}
+
+impl ArcLimits {
+    fn new(tuning_params: &kitsune_p2p_types::config::KitsuneP2pTuningParams) -> Self {
+        let max_coverage = tuning_params.gossip_arc_max_coverage.clamp(0.0, 1.0);
+        Self {
+            max_length: (max_coverage * kitsune_p2p_types::dht_arc::FULL_LEN_F) as u64,
+            storage_limit_bytes: tuning_params.gossip_arc_storage_limit_mb as u64 * 1024 * 1024,
+        }
+    }
+
+    /// Constrain an arc that has just been resized from `length_before`
+    /// for peer density, given how much DHT data is stored.
+    fn constrain(&self, arc: &mut DhtArc, length_before: u64, stored_bytes: Option<u64>) {
+        let mut length = arc.length();
+        if matches!(stored_bytes, Some(b) if b > self.storage_limit_bytes) {
+            // Under storage pressure, shed half the arc rather than
+            // taking on any more of the keyspace.
+            length = length.min(length_before) / 2;
+        }
+        length = length.min(self.max_length);
+        if length != arc.length() {
+            arc.update_length(length);
+        }
+    }
 }
 
 async fn update_arc_length(
     evt_sender: &futures::channel::mpsc::Sender<KitsuneP2pEvent>,
+    host_api: &HostApi,
+    arc_limits: ArcLimits,
     space: Arc<KitsuneSpace>,
     arc: &mut DhtArc,
 ) -> KitsuneP2pResult<()> {
@@ -468,8 +531,22 @@ async fn update_arc_length(
     tracing::trace!("Updating arc for space {:?}:", space);
     tracing::trace!("Before: {:2.1}% |{}|", cov_before, arc.to_ascii(64));
 
+    let length_before = arc.length();
     view.update_arc(arc);
 
+    let stored_bytes = if arc_limits.storage_limit_bytes > 0 {
+        match host_api.dht_storage_bytes(space.clone()).await {
+            Ok(bytes) => Some(bytes),
+            Err(err) => {
+                tracing::warn!(?err, "failed to get dht storage size, not limiting arc");
+                None
+            }
+        }
+    } else {
+        None
+    };
+    arc_limits.constrain(arc, length_before, stored_bytes);
+
     let cov_after = arc.coverage() * 100.0;
     tracing::trace!("After:  {:2.1}% |{}|", cov_after, arc.to_ascii(64));
     tracing::trace!("Diff: {:-2.2}%", cov_after - cov_before);
@@ -490,16 +567,18 @@ async fn update_single_agent_info(
         internal_sender,
         network_type,
         mdns_handles,
-        bootstrap_service,
+        bootstrap,
         dynamic_arcs,
         single_storage_arc_per_space,
+        arc_limits,
+        host_api,
     } = input;
 
     // If there is only a single agent per space don't update the empty arcs.
     let should_not_update_arc_length = single_storage_arc_per_space && arc.is_empty();
 
     if dynamic_arcs && !should_not_update_arc_length {
-        update_arc_length(evt_sender, space.clone(), &mut arc).await?;
+        update_arc_length(evt_sender, host_api, arc_limits, space.clone(), &mut arc).await?;
     }
 
     // Update the agents arc through the internal sender.
@@ -542,6 +621,7 @@ async fn update_single_agent_info(
             if !urls.is_empty() {
                 // Kill previous broadcast for this space + agent
                 let key = [space.get_bytes(), agent.get_bytes()].concat();
+                let mut mdns_handles = mdns_handles.lock();
                 if let Some(current_handle) = mdns_handles.get(&key) {
                     mdns_kill_thread(current_handle.to_owned());
                 }
@@ -554,17 +634,19 @@ async fn update_single_agent_info(
                 let mut buffer = Vec::new();
                 rmp_encode(&mut buffer, &agent_info_signed)?;
                 tracing::trace!(?space_b64, ?agent_b64);
-                let handle = mdns_create_broadcast_thread(space_b64, agent_b64, &buffer);
-                // store handle in self
-                mdns_handles.insert(key, handle);
+                match mdns_create_broadcast_thread(space_b64, agent_b64, &buffer) {
+                    // store handle in self
+                    Ok(handle) => {
+                        mdns_handles.insert(key, handle);
+                    }
+                    Err(e) => {
+                        tracing::error!(msg = "Failed to broadcast agent info over MDNS", ?e);
+                    }
+                }
             }
         }
         NetworkType::QuicBootstrap => {
-            crate::spawn::actor::bootstrap::put(
-                bootstrap_service.clone(),
-                agent_info_signed.clone(),
-            )
-            .await?;
+            bootstrap.put(agent_info_signed.clone()).await?;
         }
     }
     Ok(agent_info_signed)
@@ -600,6 +682,20 @@ impl KitsuneP2pHandler for Space {
         )
     }
 
+    fn handle_bootstrap_health(
+        &mut self,
+    ) -> KitsuneP2pHandlerResult<Vec<kitsune_p2p_types::bootstrap::BootstrapServerHealth>> {
+        unreachable!(
+            "These requests are handled at the to actor level and are never propagated down to the space."
+        )
+    }
+
+    fn handle_set_bootstrap_urls(&mut self, _urls: Vec<url2::Url2>) -> KitsuneP2pHandlerResult<()> {
+        unreachable!(
+            "These requests are handled at the to actor level and are never propagated down to the space."
+        )
+    }
+
     fn handle_join(
         &mut self,
         space: Arc<KitsuneSpace>,
@@ -622,7 +718,13 @@ impl KitsuneP2pHandler for Space {
                 if !self.mdns_listened_spaces.contains(&space_b64) {
                     self.mdns_listened_spaces.insert(space_b64.clone());
                     tokio::task::spawn(async move {
-                        let stream = mdns_listen(space_b64);
+                        let stream = match mdns_listen(space_b64) {
+                            Ok(stream) => stream,
+                            Err(e) => {
+                                tracing::error!(msg = "Failed to listen for MDNS peers", ?e);
+                                return;
+                            }
+                        };
                         tokio::pin!(stream);
                         while let Some(maybe_response) = stream.next().await {
                             match maybe_response {
@@ -636,6 +738,17 @@ impl KitsuneP2pHandler for Space {
                                         continue;
                                     }
                                     if let Ok(remote_agent_info_signed) = maybe_agent_info_signed {
+                                        let remote_agent_info_signed: AgentInfoSigned =
+                                            remote_agent_info_signed;
+                                        // The service type should only match peers
+                                        // in this space, but anyone can broadcast it.
+                                        if remote_agent_info_signed.space != space {
+                                            tracing::warn!(
+                                                msg = "Ignoring MDNS peer from another space",
+                                                ?remote_agent_info_signed
+                                            );
+                                            continue;
+                                        }
                                         // Add to local storage
                                         if let Err(e) = evt_sender
                                             .put_agent_info_signed(PutAgentInfoSignedEvt {
@@ -672,6 +785,12 @@ impl KitsuneP2pHandler for Space {
     ) -> KitsuneP2pHandlerResult<()> {
         self.local_joined_agents.remove(&agent);
         self.agent_arcs.remove(&agent);
+        // Stop advertising the agent on the LAN.
+        // Peers that already found it will let its agent info expire.
+        let key = [self.space.get_bytes(), agent.get_bytes()].concat();
+        if let Some(handle) = self.mdns_handles.lock().remove(&key) {
+            mdns_kill_thread(handle);
+        }
         self.update_metric_exchange_arcset();
         for module in self.gossip_mod.values() {
             module.local_agent_leave(agent.clone());
@@ -708,7 +827,7 @@ impl KitsuneP2pHandler for Space {
             match discover_fut.await {
                 discover::PeerDiscoverResult::OkShortcut => {
                     // reflect this request locally
-                    evt_sender.call(space, to_agent, payload).await
+                    evt_sender.call(space, to_agent, None, payload).await
                 }
                 discover::PeerDiscoverResult::OkRemote { con_hnd, .. } => {
                     let payload = wire::Wire::call(space.clone(), to_agent.clone(), payload.into());
@@ -790,6 +909,7 @@ impl KitsuneP2pHandler for Space {
                             let fut = self.evt_sender.notify(
                                 space.clone(),
                                 agent.clone(),
+                                None,
                                 payload.clone(),
                             );
                             local_notify_events.push(async move {
@@ -875,7 +995,7 @@ impl KitsuneP2pHandler for Space {
                                 PeerDiscoverResult::OkRemote { con_hnd, .. } => con_hnd,
                                 PeerDiscoverResult::Err(_) => return None,
                             };
-                        Some((info.agent.clone(), con_hnd))
+                        Some((info, con_hnd))
                     });
                 }
 
@@ -897,22 +1017,30 @@ impl KitsuneP2pHandler for Space {
                 // `agent.get_loc() % mod_cnt == mod_idx` -- if true,
                 // they'll be responsible for forwarding the data to that node.
                 let mod_cnt = con_list.len();
-                for (mod_idx, (agent, con_hnd)) in con_list.into_iter().enumerate() {
+                let bytes = payload.len();
+                let compression_threshold = ro_inner
+                    .config
+                    .tuning_params
+                    .wire_compression_threshold_bytes;
+                for (mod_idx, (info, con_hnd)) in con_list.into_iter().enumerate() {
                     // build our delegate message
                     let payload = wire::Wire::delegate_broadcast(
                         space.clone(),
                         basis.clone(),
-                        agent,
+                        info.agent.clone(),
                         mod_idx as u32,
                         mod_cnt as u32,
                         destination,
                         payload.clone().into(),
-                    );
+                    )
+                    .compress_for([&info], compression_threshold);
 
                     // notify the remote node
+                    let metrics = ro_inner.metrics.clone();
                     all.push(async move {
-                        if let Err(err) = con_hnd.notify(&payload, timeout).await {
-                            tracing::warn!(?err, "delegate broadcast error");
+                        match con_hnd.notify(&payload, timeout).await {
+                            Ok(_) => metrics.write().record_bytes_sent(bytes),
+                            Err(err) => tracing::warn!(?err, "delegate broadcast error"),
                         }
                     });
                 }
@@ -976,7 +1104,7 @@ impl KitsuneP2pHandler for Space {
                         discover::PeerDiscoverResult::OkShortcut => {
                             // reflect this request locally
                             evt_sender
-                                .notify(space, agent, payload)
+                                .notify(space, agent, None, payload)
                                 .map(|r| {
                                     if let Err(e) = r {
                                         tracing::error!(
@@ -1027,6 +1155,42 @@ impl KitsuneP2pHandler for Space {
         .into())
     }
 
+    fn handle_set_gossip_paused(&mut self, paused: bool) -> KitsuneP2pHandlerResult<()> {
+        for module in self.gossip_mod.values() {
+            module.set_paused(paused);
+        }
+        unit_ok_fut()
+    }
+
+    fn handle_update_tuning_params(
+        &mut self,
+        tuning_params: kitsune_p2p_types::config::KitsuneP2pTuningParams,
+    ) -> KitsuneP2pHandlerResult<()> {
+        let mut config = (*self.config).clone();
+        config.tuning_params = tuning_params.clone();
+        let config = Arc::new(config);
+        // Tasks already running with the old read only inner finish with
+        // the old params, everything started from now on gets the new ones.
+        let ro_inner = &self.ro_inner;
+        self.ro_inner = Arc::new(SpaceReadOnlyInner {
+            space: ro_inner.space.clone(),
+            i_s: ro_inner.i_s.clone(),
+            evt_sender: ro_inner.evt_sender.clone(),
+            host_api: ro_inner.host_api.clone(),
+            ep_hnd: ro_inner.ep_hnd.clone(),
+            config: config.clone(),
+            parallel_notify_permit: ro_inner.parallel_notify_permit.clone(),
+            metrics: ro_inner.metrics.clone(),
+            metric_exchange: ro_inner.metric_exchange.clone(),
+            bootstrap: ro_inner.bootstrap.clone(),
+        });
+        self.config = config;
+        for module in self.gossip_mod.values() {
+            module.update_tuning_params(tuning_params.clone());
+        }
+        unit_ok_fut()
+    }
+
     fn handle_new_integrated_data(&mut self, _: KSpace) -> InternalHandlerResult<()> {
         for module in self.gossip_mod.values() {
             module.new_integrated_data();
@@ -1047,16 +1211,49 @@ impl KitsuneP2pHandler for Space {
         Ok(async move { Ok(r) }.boxed().into())
     }
 
+    fn handle_record_validation_failure(
+        &mut self,
+        _space: Arc<KitsuneSpace>,
+        agent: Arc<KitsuneAgent>,
+    ) -> KitsuneP2pHandlerResult<()> {
+        self.ro_inner
+            .metrics
+            .write()
+            .record_validation_failure([&agent]);
+        Ok(async move { Ok(()) }.boxed().into())
+    }
+
     fn handle_dump_network_metrics(
         &mut self,
         _space: Option<Arc<KitsuneSpace>>,
     ) -> KitsuneP2pHandlerResult<serde_json::Value> {
         let space = self.ro_inner.space.clone();
         let metrics = self.ro_inner.metrics.read().dump();
+        let arcs = self.dump_local_arcs();
+        let transport = self.ro_inner.ep_hnd.debug();
         Ok(async move {
             Ok(serde_json::json!({
                 "space": space.to_string(),
+                "arcs": arcs,
                 "metrics": metrics,
+                "transport": transport,
+            }))
+        }
+        .boxed()
+        .into())
+    }
+
+    fn handle_dump_network_stats(&mut self) -> KitsuneP2pHandlerResult<serde_json::Value> {
+        let space = self.ro_inner.space.clone();
+        let stats = self.ro_inner.metrics.read().summary();
+        let arcs = self.dump_local_arcs();
+        let tuning_params = self.config.tuning_params.clone();
+        Ok(async move {
+            Ok(serde_json::json!({
+                "space": space.to_string(),
+                "arcs": arcs,
+                "stats": stats,
+                "tuning_params": tuning_params,
             }))
         }
         .boxed()
@@ -1064,6 +1261,9 @@ impl KitsuneP2pHandler for Space {
     }
 }
 
+/// The running MDNS broadcasts of our local agents, by space + agent.
+type MdnsHandles = Arc<parking_lot::Mutex<HashMap<Vec<u8>, Arc<AtomicBool>>>>;
+
 pub(crate) struct SpaceReadOnlyInner {
     pub(crate) space: Arc<KitsuneSpace>,
     #[allow(dead_code)]
@@ -1071,11 +1271,11 @@ pub(crate) struct SpaceReadOnlyInner {
     pub(crate) evt_sender: futures::channel::mpsc::Sender<KitsuneP2pEvent>,
     pub(crate) host_api: HostApi,
     pub(crate) ep_hnd: Tx2EpHnd<wire::Wire>,
-    #[allow(dead_code)]
     pub(crate) config: Arc<KitsuneP2pConfig>,
     pub(crate) parallel_notify_permit: Arc<tokio::sync::Semaphore>,
     pub(crate) metrics: MetricsSync,
     pub(crate) metric_exchange: MetricExchangeSync,
+    pub(crate) bootstrap: BootstrapClient,
 }
 
 /// A Kitsune P2p Node can track multiple "spaces" -- Non-interacting namespaced
@@ -1089,7 +1289,7 @@ pub(crate) struct Space {
     pub(crate) local_joined_agents: HashSet<Arc<KitsuneAgent>>,
     pub(crate) agent_arcs: HashMap<Arc<KitsuneAgent>, DhtArc>,
     pub(crate) config: Arc<KitsuneP2pConfig>,
-    mdns_handles: HashMap<Vec<u8>, Arc<AtomicBool>>,
+    mdns_handles: MdnsHandles,
     mdns_listened_spaces: HashSet<String>,
     gossip_mod: HashMap<GossipModuleType, GossipModule>,
 }
@@ -1106,6 +1306,7 @@ impl Space {
         config: Arc<KitsuneP2pConfig>,
         bandwidth_throttles: BandwidthThrottles,
         parallel_notify_permit: Arc<tokio::sync::Semaphore>,
+        bootstrap: BootstrapClient,
     ) -> Self {
         let metrics = MetricsSync::default();
 
@@ -1114,6 +1315,12 @@ impl Space {
             let metrics = metrics.clone();
             let host = host_api.clone();
             tokio::task::spawn(async move {
+                // pick up where we left off with the remote nodes we already know
+                match host.query_latest_metrics(space.clone()).await {
+                    Ok(records) => metrics.write().restore_historical(records),
+                    Err(err) => tracing::debug!(?err, "failed to load historical metrics"),
+                }
+
                 loop {
                     tokio::time::sleep(std::time::Duration::from_millis(
                         HISTORICAL_METRIC_RECORD_FREQ_MS,
@@ -1196,7 +1403,7 @@ impl Space {
             // spawn the periodic bootstrap pull
             let i_s_c = i_s.clone();
             let evt_s_c = evt_sender.clone();
-            let bootstrap_service = config.bootstrap_service.clone();
+            let bootstrap = bootstrap.clone();
             let space_c = space.clone();
             tokio::task::spawn(async move {
                 const START_DELAY: std::time::Duration = std::time::Duration::from_secs(1);
@@ -1210,19 +1417,23 @@ impl Space {
                         break;
                     }
 
-                    tokio::time::sleep(delay_len).await;
+                    // jitter so that nodes started together
+                    // don't all hit the bootstrap servers together
+                    let jitter = {
+                        use rand::Rng;
+                        rand::thread_rng().gen_range(0.75..1.25)
+                    };
+                    tokio::time::sleep(delay_len.mul_f64(jitter)).await;
                     if delay_len <= MAX_DELAY {
                         delay_len *= 2;
                     }
 
-                    match super::bootstrap::random(
-                        bootstrap_service.clone(),
-                        kitsune_p2p_types::bootstrap::RandomQuery {
+                    match bootstrap
+                        .random(kitsune_p2p_types::bootstrap::RandomQuery {
                             space: space_c.clone(),
                             limit: 8.into(),
-                        },
-                    )
-                    .await
+                        })
+                        .await
                     {
                         Err(e) => {
                             tracing::error!(msg = "Failed to get peers from bootstrap", ?e);
@@ -1270,6 +1481,7 @@ impl Space {
             parallel_notify_permit,
             metrics,
             metric_exchange,
+            bootstrap,
         });
 
         Self {
@@ -1281,7 +1493,7 @@ impl Space {
             local_joined_agents: HashSet::new(),
             agent_arcs: HashMap::new(),
             config,
-            mdns_handles: HashMap::new(),
+            mdns_handles: MdnsHandles::default(),
             mdns_listened_spaces: HashSet::new(),
             gossip_mod,
         }
@@ -1296,6 +1508,24 @@ impl Space {
         self.ro_inner.metric_exchange.write().update_arcset(arc_set);
     }
 
+    /// The current arc of each local agent, for network dumps.
+    fn dump_local_arcs(&self) -> serde_json::Value {
+        self.agent_arcs
+            .iter()
+            .map(|(agent, arc)| {
+                (
+                    agent.to_string(),
+                    serde_json::json!({
+                        "start_loc": arc.start_loc().as_u32(),
+                        "length": arc.length(),
+                        "coverage": arc.coverage(),
+                    }),
+                )
+            })
+            .collect::<serde_json::map::Map<String, serde_json::Value>>()
+            .into()
+    }
+
     fn publish_leave_agent_info(
         &mut self,
         agent: Arc<KitsuneAgent>,
@@ -1303,7 +1533,7 @@ impl Space {
         let space = self.space.clone();
         let network_type = self.config.network_type.clone();
         let evt_sender = self.evt_sender.clone();
-        let bootstrap_service = self.config.bootstrap_service.clone();
+        let bootstrap = self.ro_inner.bootstrap.clone();
         let expires_after = self.config.tuning_params.agent_info_expires_after_ms as u64;
         Ok(async move {
             let signed_at_ms = crate::spawn::actor::bootstrap::now_once(None).await?;
@@ -1346,11 +1576,7 @@ impl Space {
             match network_type {
                 NetworkType::QuicMdns => tracing::warn!("NOT publishing leaves to mdns"),
                 NetworkType::QuicBootstrap => {
-                    crate::spawn::actor::bootstrap::put(
-                        bootstrap_service.clone(),
-                        agent_info_signed,
-                    )
-                    .await?;
+                    bootstrap.put(agent_info_signed).await?;
                 }
             }
 
@@ -1391,3 +1617,32 @@ impl Space {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kitsune_p2p_types::dht_arc::{DhtLocation, FULL_LEN};
+
+    #[test]
+    fn arc_limits_cap_coverage_and_shrink_under_storage_pressure() {
+        let limits = ArcLimits {
+            max_length: FULL_LEN / 4,
+            storage_limit_bytes: 100,
+        };
+
+        // Growth past the max coverage is capped.
+        let mut arc = DhtArc::full(DhtLocation::new(0));
+        limits.constrain(&mut arc, FULL_LEN / 8, Some(0));
+        assert_eq!(arc.length(), FULL_LEN / 4);
+
+        // Over the storage limit the arc halves rather than growing.
+        let mut arc = DhtArc::from_start_and_len(0u32, FULL_LEN / 4);
+        limits.constrain(&mut arc, FULL_LEN / 8, Some(101));
+        assert_eq!(arc.length(), FULL_LEN / 16);
+
+        // An unknown storage size doesn't shrink the arc.
+        let mut arc = DhtArc::from_start_and_len(0u32, FULL_LEN / 8);
+        limits.constrain(&mut arc, FULL_LEN / 8, None);
+        assert_eq!(arc.length(), FULL_LEN / 8);
+    }
+}
@@ -61,8 +61,10 @@ struct Outer {
     agg: TaskAgg,
     kill: Arc<Kill>,
     got_data: Arc<Notify>,
+    call_failed: Arc<Notify>,
     grace_rs: ReverseSemaphore,
     remote_request_grace_ms: u64,
+    hedge_delay_ms: u64,
     max_timeout: KitsuneTimeout,
     space: Arc<KitsuneSpace>,
     basis: Arc<KitsuneBasis>,
@@ -108,6 +110,7 @@ impl Outer {
             max_remote_agent_count,
             max_timeout,
             remote_request_grace_ms,
+            hedge_delay_ms,
         } = input;
 
         let grace_rs = ReverseSemaphore::new();
@@ -124,8 +127,10 @@ impl Outer {
             agg,
             kill: Kill::new(),
             got_data: Arc::new(Notify::new()),
+            call_failed: Arc::new(Notify::new()),
             grace_rs,
             remote_request_grace_ms,
+            hedge_delay_ms,
             max_timeout,
             space,
             basis,
@@ -214,6 +219,7 @@ impl Outer {
         let kill = self.kill.clone();
         let got_data = self.got_data.clone();
         let grace_rs = self.grace_rs.clone();
+        let hedging = self.hedge_delay_ms > 0;
 
         self.add_task(async move {
             tracing::trace!("(rpc_multi_logic) grace time check start");
@@ -222,9 +228,12 @@ impl Outer {
             got_data.notified().await;
             tracing::trace!("(rpc_multi_logic) grace time got data");
 
-            // wait for any pending grace permits
-            grace_rs.wait_on_zero_permits().await;
-            tracing::trace!("(rpc_multi_logic) grace time zero permits");
+            // when hedging we return the first response,
+            // otherwise wait for any pending grace permits
+            if !hedging {
+                grace_rs.wait_on_zero_permits().await;
+                tracing::trace!("(rpc_multi_logic) grace time zero permits");
+            }
 
             // end all processing
             kill.kill_all();
@@ -297,7 +306,7 @@ impl Outer {
 
         Arc::new(move |to_agent, permit| {
             let report_results = report_results.clone();
-            let fut = evt_sender.call(space.clone(), to_agent.clone(), payload.clone());
+            let fut = evt_sender.call(space.clone(), to_agent.clone(), None, payload.clone());
 
             // see add_tokio_task vs add_task
             add_tokio_task(
@@ -327,6 +336,7 @@ impl Outer {
     ) -> Arc<dyn Fn(AgentInfoSigned, Share<ReverseSemaphorePermit>) + 'static + Send + Sync> {
         let add_tokio_task = self.gen_add_tokio_task_fn();
         let report_results = self.gen_report_results_fn();
+        let call_failed = self.call_failed.clone();
 
         let ro_inner = self.ro_inner.clone();
         let space = self.space.clone();
@@ -335,6 +345,7 @@ impl Outer {
 
         Arc::new(move |info, permit| {
             let report_results = report_results.clone();
+            let call_failed = call_failed.clone();
             let ro_inner = ro_inner.clone();
             let space = space.clone();
             let payload = payload.clone();
@@ -347,11 +358,13 @@ impl Outer {
                         match discover::peer_connect(ro_inner.clone(), &info, max_timeout).await {
                             PeerDiscoverResult::OkShortcut => {
                                 tracing::trace!("remote peer is local");
+                                call_failed.notify_one();
                                 permit.close();
                                 return;
                             }
                             PeerDiscoverResult::Err(err) => {
                                 tracing::warn!(?err, "remote call error");
+                                call_failed.notify_one();
                                 permit.close();
                                 return;
                             }
@@ -389,6 +402,7 @@ impl Outer {
                                 .write()
                                 .record_latency_micros(start.elapsed().as_micros(), [&info.agent]);
                             tracing::warn!(?oth, "unexpected remote call result");
+                            call_failed.notify_one();
                         }
                     }
 
@@ -439,6 +453,8 @@ impl Outer {
         let grace_permit = self.gen_grace_permit_fn();
         let add_tokio_task = self.gen_add_tokio_task_fn();
         let remote_call = self.gen_remote_call_fn();
+        let call_failed = self.call_failed.clone();
+        let hedge_delay_ms = self.hedge_delay_ms;
 
         // see add_tokio_task vs add_task
         add_tokio_task(
@@ -462,13 +478,40 @@ impl Outer {
                         "(rpc_multi_logic) remote get searched",
                     );
 
-                    for info in infos {
+                    // skip peers scoring below the evict threshold, and
+                    // only call those below the deprioritize threshold once
+                    // the better peers have been tried.
+                    let tuning_params = &ro_inner.config.tuning_params;
+                    let (mut infos, low_score): (Vec<_>, Vec<_>) = infos
+                        .into_iter()
+                        .map(|info| (ro_inner.metrics.read().peer_score([&info]) as f64, info))
+                        .filter(|(score, _)| *score >= tuning_params.peer_score_evict_threshold)
+                        .partition(|(score, _)| {
+                            *score >= tuning_params.peer_score_deprioritize_threshold
+                        });
+                    infos.extend(low_score);
+
+                    for (_, info) in infos {
                         if check_remote_agent(&inner, &info.agent) {
                             continue;
                         }
 
                         let permit = grace_permit();
                         remote_call(info, permit);
+
+                        // when hedging, only ask the next peer once this one
+                        // has failed or taken too long. We stop altogether
+                        // as soon as any response arrives.
+                        if hedge_delay_ms > 0 {
+                            let delay = tokio::time::sleep(std::time::Duration::from_millis(
+                                hedge_delay_ms,
+                            ));
+                            futures::future::select(
+                                Box::pin(delay),
+                                Box::pin(call_failed.notified()),
+                            )
+                            .await;
+                        }
                     }
                 }
 
@@ -77,6 +77,25 @@ async fn build_ep_hnd(config: Arc<KitsuneP2pConfig>, m: MockBindAdapt) -> Tx2EpH
 
 #[tokio::test]
 async fn test_rpc_multi_logic_mocked() {
+    // without hedging we wait to hear from all remotes
+    let res = rpc_multi_mocked(0).await;
+    assert_eq!(3, res.len());
+    for r in res {
+        let RpcMultiResponse { response, .. } = r;
+        assert_eq!(b"test", response.as_slice());
+    }
+}
+
+#[tokio::test]
+async fn test_rpc_multi_logic_mocked_hedged() {
+    // with hedging the first response is returned
+    let res = rpc_multi_mocked(1000).await;
+    assert_eq!(1, res.len());
+    let RpcMultiResponse { response, .. } = res.into_iter().next().unwrap();
+    assert_eq!(b"test", response.as_slice());
+}
+
+async fn rpc_multi_mocked(hedge_delay_ms: u64) -> Vec<RpcMultiResponse> {
     observability::test_run().ok();
 
     // allow fake timing during test
@@ -248,6 +267,7 @@ async fn test_rpc_multi_logic_mocked() {
         parallel_notify_permit: Arc::new(tokio::sync::Semaphore::new(
             config.tuning_params.concurrent_limit_per_thread,
         )),
+        bootstrap: BootstrapClient::new(Vec::new(), &config.tuning_params),
         config,
         metrics,
         metric_exchange,
@@ -264,6 +284,7 @@ async fn test_rpc_multi_logic_mocked() {
             max_remote_agent_count: 3,
             max_timeout: KitsuneTimeout::from_millis(30000),
             remote_request_grace_ms: 3000,
+            hedge_delay_ms,
         },
         ro_inner,
         HashSet::new(),
@@ -273,9 +294,5 @@ async fn test_rpc_multi_logic_mocked() {
 
     // await responses
     println!("{:#?}", res);
-    assert_eq!(3, res.len());
-    for r in res {
-        let RpcMultiResponse { response, .. } = r;
-        assert_eq!(b"test", response.as_slice());
-    }
+    res
 }
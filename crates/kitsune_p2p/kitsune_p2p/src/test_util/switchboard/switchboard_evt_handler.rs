@@ -76,6 +76,17 @@ impl KitsuneHost for SwitchboardEventHandler {
         box_fut(Ok(()))
     }
 
+    fn query_latest_metrics(
+        &self,
+        _space: Arc<KitsuneSpace>,
+    ) -> crate::KitsuneHostResult<Vec<MetricRecord>> {
+        box_fut(Ok(vec![]))
+    }
+
+    fn dht_storage_bytes(&self, _space: Arc<KitsuneSpace>) -> crate::KitsuneHostResult<u64> {
+        box_fut(Ok(0))
+    }
+
     fn query_size_limited_regions(
         &self,
         _space: Arc<KitsuneSpace>,
@@ -225,6 +236,7 @@ impl KitsuneP2pEventHandler for SwitchboardEventHandler {
         &mut self,
         space: Arc<KitsuneSpace>,
         to_agent: Arc<KitsuneAgent>,
+        from_peer: Option<Tx2Cert>,
         payload: Vec<u8>,
     ) -> KitsuneP2pEventHandlerResult<Vec<u8>> {
         todo!()
@@ -234,6 +246,7 @@ impl KitsuneP2pEventHandler for SwitchboardEventHandler {
         &mut self,
         space: Arc<KitsuneSpace>,
         to_agent: Arc<KitsuneAgent>,
+        from_peer: Option<Tx2Cert>,
         payload: Vec<u8>,
     ) -> KitsuneP2pEventHandlerResult<()> {
         todo!()
@@ -701,6 +701,7 @@ fn fake_agent_info(
     let url_list = vec![node.local_addr().unwrap()];
     let meta_info = AgentMetaInfoEncode {
         dht_storage_arc_half_length: 0,
+        accepts_compression: true,
     };
     let mut buf = Vec::new();
     kitsune_p2p_types::codec::rmp_encode(&mut buf, meta_info).unwrap();
@@ -724,6 +725,7 @@ fn fake_agent_info(
         url_list,
         signed_at_ms: 0,
         expires_at_ms: u64::MAX,
+        accepts_compression: true,
         signature: Arc::new(fixt::prelude::fixt!(KitsuneSignature)),
         encoded_bytes,
     };
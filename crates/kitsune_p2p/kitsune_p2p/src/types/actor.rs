@@ -29,6 +29,13 @@ pub struct RpcMulti {
     /// but made any additional outgoing remote requests,
     /// we'll wait at least this long for additional responses.
     pub remote_request_grace_ms: u64,
+
+    /// Remote request hedge delay.
+    /// If non-zero, remote requests are made one at a time, asking the
+    /// next peer only once this long has passed without a response or
+    /// the previous request has failed. The first response is returned.
+    /// If zero, all remote requests are made at once.
+    pub hedge_delay_ms: u64,
 }
 
 impl RpcMulti {
@@ -47,6 +54,7 @@ impl RpcMulti {
             max_remote_agent_count: tuning_params.default_rpc_multi_remote_agent_count,
             max_timeout: tuning_params.implicit_timeout(),
             remote_request_grace_ms: tuning_params.default_rpc_multi_remote_request_grace_ms,
+            hedge_delay_ms: tuning_params.default_rpc_multi_hedge_delay_ms,
         }
     }
 }
@@ -77,6 +85,7 @@ type KBasis = Arc<super::KitsuneBasis>;
 type Payload = Vec<u8>;
 type OptU64 = Option<u64>;
 type OptArc = Option<crate::dht_arc::DhtArc>;
+type Urls = Vec<Url2>;
 
 ghost_actor::ghost_chan! {
     /// The KitsuneP2pSender allows async remote-control of the KitsuneP2p actor.
@@ -137,9 +146,36 @@ ghost_actor::ghost_chan! {
             basis: KBasis,
         ) -> bool;
 
+        /// Report that data received from a remote agent failed validation,
+        /// lowering its score when choosing gossip partners and remote
+        /// call targets.
+        fn record_validation_failure(
+            space: KSpace,
+            agent: KAgent,
+        ) -> ();
+
         /// dump network metrics
         fn dump_network_metrics(
             space: KSpaceOpt,
         ) -> serde_json::Value;
+
+        /// dump a summary of network stats for the whole node:
+        /// open connections, and per space gossip, throughput and arcs
+        fn dump_network_stats() -> serde_json::Value;
+
+        /// How each configured bootstrap server has been responding.
+        fn bootstrap_health() -> Vec<kitsune_p2p_types::bootstrap::BootstrapServerHealth>;
+
+        /// Stop gossiping in every space, or start again. Spaces joined
+        /// while gossip is paused start out paused.
+        fn set_gossip_paused(paused: bool) -> ();
+
+        /// Switch every space to new tuning params, re-applying any
+        /// per-space overrides. Only the params in `LIVE_TUNING_PARAMS`
+        /// take effect, the rest stay as they were when the space was spawned.
+        fn update_tuning_params(tuning_params: KitsuneP2pTuningParams) -> ();
+
+        /// Switch to a new list of bootstrap services.
+        fn set_bootstrap_urls(urls: Urls) -> ();
     }
 }
@@ -159,6 +159,7 @@ const METRIC_KIND_UNKNOWN: &str = "Unknown";
 const METRIC_KIND_REACHABILITY_QUOTIENT: &str = "ReachabilityQuotient";
 const METRIC_KIND_LATENCY_MICROS: &str = "LatencyMicros";
 const METRIC_KIND_AGG_EXTRAP_COV: &str = "AggExtrapCov";
+const METRIC_KIND_VALIDATION_FAILURES: &str = "ValidationFailures";
 
 /// The type of metric recorded
 pub enum MetricRecordKind {
@@ -173,6 +174,9 @@ pub enum MetricRecordKind {
 
     /// AggExtrapCov metric kind
     AggExtrapCov,
+
+    /// ValidationFailures metric kind
+    ValidationFailures,
 }
 
 impl MetricRecordKind {
@@ -184,6 +188,7 @@ impl MetricRecordKind {
             ReachabilityQuotient => METRIC_KIND_REACHABILITY_QUOTIENT,
             LatencyMicros => METRIC_KIND_LATENCY_MICROS,
             AggExtrapCov => METRIC_KIND_AGG_EXTRAP_COV,
+            ValidationFailures => METRIC_KIND_VALIDATION_FAILURES,
         }
     }
 
@@ -196,6 +201,8 @@ impl MetricRecordKind {
             LatencyMicros
         } else if input == METRIC_KIND_AGG_EXTRAP_COV {
             AggExtrapCov
+        } else if input == METRIC_KIND_VALIDATION_FAILURES {
+            ValidationFailures
         } else {
             Unknown
         }
@@ -224,6 +231,7 @@ type KSpace = Arc<super::KitsuneSpace>;
 type KAgent = Arc<super::KitsuneAgent>;
 type KOpHash = Arc<super::KitsuneOpHash>;
 type Payload = Vec<u8>;
+type FromPeer = Option<kitsune_p2p_types::Tx2Cert>;
 type Ops = Vec<KOp>;
 
 ghost_actor::ghost_chan! {
@@ -241,10 +249,14 @@ ghost_actor::ghost_chan! {
         fn query_peer_density(space: KSpace, dht_arc: kitsune_p2p_types::dht_arc::DhtArc) -> kitsune_p2p_types::dht::PeerView;
 
         /// We are receiving a request from a remote node.
-        fn call(space: KSpace, to_agent: KAgent, payload: Payload) -> Vec<u8>;
+        /// `from_peer` is the certificate of the node the request arrived
+        /// from, or `None` if it was reflected from a local agent.
+        fn call(space: KSpace, to_agent: KAgent, from_peer: FromPeer, payload: Payload) -> Vec<u8>;
 
         /// We are receiving a notification from a remote node.
-        fn notify(space: KSpace, to_agent: KAgent, payload: Payload) -> ();
+        /// `from_peer` is the certificate of the node the notification
+        /// arrived from, or `None` if it was reflected from a local agent.
+        fn notify(space: KSpace, to_agent: KAgent, from_peer: FromPeer, payload: Payload) -> ();
 
         /// We are receiving a dht op we may need to hold distributed via gossip.
         fn gossip(space: KSpace, ops: Ops) -> ();
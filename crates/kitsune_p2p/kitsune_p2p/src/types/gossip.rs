@@ -30,6 +30,8 @@ pub trait AsGossipModule: 'static + Send + Sync {
     fn local_agent_join(&self, a: Arc<KitsuneAgent>);
     fn local_agent_leave(&self, a: Arc<KitsuneAgent>);
     fn new_integrated_data(&self) {}
+    fn set_paused(&self, paused: bool);
+    fn update_tuning_params(&self, _tuning_params: KitsuneP2pTuningParams) {}
 }
 
 #[derive(Clone)]
@@ -61,6 +63,17 @@ impl GossipModule {
     pub fn new_integrated_data(&self) {
         self.0.new_integrated_data();
     }
+
+    /// Stop gossiping, or start again. While paused no rounds are
+    /// initiated and incoming gossip is dropped.
+    pub fn set_paused(&self, paused: bool) {
+        self.0.set_paused(paused);
+    }
+
+    /// Switch to new tuning params, from the next gossip round on.
+    pub fn update_tuning_params(&self, tuning_params: KitsuneP2pTuningParams) {
+        self.0.update_tuning_params(tuning_params);
+    }
 }
 
 impl std::fmt::Debug for GossipModule {
@@ -4,7 +4,10 @@ use crate::actor::BroadcastTo;
 use crate::agent_store::AgentInfoSigned;
 use crate::types::*;
 use derive_more::*;
+use kitsune_p2p_types::codec::Codec;
 use kitsune_p2p_types::dht_arc::DhtLocation;
+use kitsune_p2p_types::KitsuneError;
+use kitsune_p2p_types::KitsuneResult;
 use std::sync::Arc;
 
 /// Type used for content data of wire messages.
@@ -114,6 +117,13 @@ kitsune_p2p_types::write_codec_enum! {
             peer_list.0: Vec<AgentInfoSigned>,
         },
 
+        /// Another message, encoded then compressed with zstd.
+        /// Only sent to peers whose agent info says they accept
+        /// compression, see [`Wire::compress_for`].
+        Compressed(0x60) {
+            data.0: WireData,
+        },
+
         /// MetricsExchangeMessage
         MetricExchange(0xa0) {
             space.0: Arc<KitsuneSpace>,
@@ -121,3 +131,81 @@ kitsune_p2p_types::write_codec_enum! {
         },
     }
 }
+
+/// Compressed messages which decompress to more than this are rejected.
+const MAX_DECOMPRESSED_BYTES: usize = 64 * 1024 * 1024;
+
+impl Wire {
+    /// Compress this message for sending to the given agents, if they all
+    /// accept compression and the message is at least `threshold` bytes
+    /// once encoded. A threshold of zero disables compression.
+    pub fn compress_for<'a>(
+        self,
+        agents: impl IntoIterator<Item = &'a AgentInfoSigned>,
+        threshold: u32,
+    ) -> Self {
+        let mut agents = agents.into_iter().peekable();
+        if threshold == 0 || agents.peek().is_none() || !agents.all(|info| info.accepts_compression)
+        {
+            return self;
+        }
+        let encoded = match self.encode_vec() {
+            Ok(encoded) if encoded.len() >= threshold as usize => encoded,
+            _ => return self,
+        };
+        match zstd::bulk::compress(&encoded, 0) {
+            Ok(data) if data.len() < encoded.len() => Self::compressed(data.into()),
+            _ => self,
+        }
+    }
+
+    /// Decompress a [`Wire::Compressed`] message,
+    /// or return any other message as is.
+    pub fn decompress(self) -> KitsuneResult<Self> {
+        match self {
+            Self::Compressed(Compressed { data }) => {
+                let encoded = zstd::bulk::decompress(&data, MAX_DECOMPRESSED_BYTES)
+                    .map_err(KitsuneError::other)?;
+                match Self::decode_ref(&encoded).map_err(KitsuneError::other)?.1 {
+                    Self::Compressed(_) => Err("Nested compressed wire message".into()),
+                    wire => Ok(wire),
+                }
+            }
+            wire => Ok(wire),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn large_messages_are_compressed_for_agents_which_accept_it() {
+        let space = Arc::new(KitsuneSpace(vec![0x01; 36]));
+        let agent = Arc::new(KitsuneAgent(vec![0x02; 36]));
+        let info = AgentInfoSigned::sign(
+            space.clone(),
+            agent.clone(),
+            42,
+            vec![],
+            42,
+            69,
+            |_| async move { Ok(Arc::new(vec![0x03; 64].into())) },
+        )
+        .await
+        .unwrap();
+        let call = Wire::call(space, agent, WireData(vec![0x04; 64 * 1024]));
+
+        let compressed = call.clone().compress_for([&info], 1024);
+        assert!(matches!(compressed, Wire::Compressed(_)));
+        let encoded = compressed.encode_vec().unwrap();
+        let (_, decoded) = Wire::decode_ref(&encoded).unwrap();
+        assert_eq!(decoded.decompress().unwrap(), call);
+
+        // Small messages, no agents and a zero threshold are left alone.
+        assert_eq!(call.clone().compress_for([&info], 1024 * 1024), call);
+        assert_eq!(call.clone().compress_for([], 1024), call);
+        assert_eq!(call.clone().compress_for([&info], 0), call);
+    }
+}
@@ -13,7 +13,7 @@ async fn main() {
     // Launch thread
     let service_type = "bobby".to_owned();
     let service_name = (0..62).map(|_| "X").collect::<String>();
-    let tx = mdns_create_broadcast_thread(service_type, service_name, &buffer);
+    let tx = mdns_create_broadcast_thread(service_type, service_name, &buffer).unwrap();
     // Kill thread after a minute
     tokio::time::sleep(::std::time::Duration::from_secs(60)).await;
     mdns_kill_thread(tx);
@@ -6,7 +6,7 @@ async fn main() {
     println!("Starting discovery");
     let service_name = "bobby".to_owned();
     // Start Stream
-    let stream = mdns_listen(service_name);
+    let stream = mdns_listen(service_name).unwrap();
     pin_mut!(stream);
     while let Some(maybe_response) = stream.next().await {
         match maybe_response {
@@ -22,6 +22,9 @@ pub enum MdnsError {
     Mdns(#[error(source)] mdns::Error),
     #[error(display = "Base64 decoding error {}", _0)]
     Base64(#[error(source)] base64::DecodeError),
+    /// e.g. there is no network interface to broadcast on
+    #[error(display = "IO error {}", _0)]
+    Io(#[error(source)] std::io::Error),
 }
 
 /// Stop thread created by `mdns_create_broadcast_thread()`
@@ -35,7 +38,7 @@ pub fn mdns_create_broadcast_thread(
     service_type: String,
     service_name: String,
     buffer: &[u8],
-) -> ::std::sync::Arc<AtomicBool> {
+) -> Result<::std::sync::Arc<AtomicBool>, MdnsError> {
     let svc_type = format!("_{}{}", service_type, HC_SERVICE_PROTOCOL);
     // Constraints in libmdns
     assert!(
@@ -46,6 +49,8 @@ pub fn mdns_create_broadcast_thread(
         service_type
     );
     assert!(service_name.len() < 63);
+    // Create mdns responder
+    let responder = libmdns::Responder::new()?;
     // Create Termination command variable
     let can_run = ::std::sync::Arc::new(AtomicBool::new(true));
     let can_run_clone = can_run.clone();
@@ -71,9 +76,6 @@ pub fn mdns_create_broadcast_thread(
         substrs.push(b64);
         let txts: Vec<_> = substrs.iter().map(AsRef::as_ref).collect();
         //println!("Entering mdns broadcasting thread...");
-        // Create mdns responder
-
-        let responder = libmdns::Responder::new().unwrap();
         let _svc = responder.register(svc_type, service_name, 0, &txts);
         // Loop forever unless termination command received
         loop {
@@ -85,7 +87,7 @@ pub fn mdns_create_broadcast_thread(
         }
     });
     // Done
-    can_run
+    Ok(can_run)
 }
 
 ///
@@ -104,12 +106,13 @@ pub struct MdnsResponse {
 /// Queries the network for the holochain service.
 /// Returns an iterator over all responses received.
 #[allow(clippy::let_and_return)]
-pub fn mdns_listen(service_type: String) -> impl Stream<Item = Result<MdnsResponse, MdnsError>> {
+pub fn mdns_listen(
+    service_type: String,
+) -> Result<impl Stream<Item = Result<MdnsResponse, MdnsError>>, MdnsError> {
     //let service_name = format!("{}.local", HC_SERVICE_TYPE);
     let svc_type = format!("_{}{}.local", service_type, HC_SERVICE_PROTOCOL);
     //println!("MDNS query for service type '{}'", svc_type);
-    let query = mdns::discover::all(svc_type, Duration::from_secs(QUERY_INTERVAL_SEC))
-        .expect("mdns Discover failed");
+    let query = mdns::discover::all(svc_type, Duration::from_secs(QUERY_INTERVAL_SEC))?;
     // Get Mdns Response stream
     let response_stream = query.listen();
     // Change it into a MdnsResponse stream
@@ -171,5 +174,5 @@ pub fn mdns_listen(service_type: String) -> impl Stream<Item = Result<MdnsRespon
             })
         });
     // Done
-    mdns_stream
+    Ok(mdns_stream)
 }
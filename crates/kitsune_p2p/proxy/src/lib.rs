@@ -8,6 +8,7 @@ use kitsune_p2p_types::dependencies::url2;
 use kitsune_p2p_types::*;
 use std::sync::Arc;
 
+pub mod relay;
 pub mod tx2;
 
 mod proxy_url;
@@ -0,0 +1,121 @@
+//! Run a relay, which forwards traffic for peers that can't reach each
+//! other directly, e.g. because they are behind NATs.
+
+use crate::tx2::*;
+use crate::*;
+use futures::stream::StreamExt;
+use ghost_actor::dependencies::tracing;
+use kitsune_p2p_transport_quic::tx2::*;
+use kitsune_p2p_types::config::KitsuneP2pTuningParams;
+use kitsune_p2p_types::dependencies::serde_json;
+use kitsune_p2p_types::tls::TlsConfig;
+use kitsune_p2p_types::tx2::tx2_pool::*;
+use kitsune_p2p_types::tx2::tx2_pool_promote::*;
+use kitsune_p2p_types::tx2::tx2_utils::*;
+
+/// Configuration for [`spawn_relay`].
+pub struct RelayConfig {
+    /// The address to bind to, e.g. `kitsune-quic://0.0.0.0:5778`.
+    pub bind_to: TxUrl,
+
+    /// The tls cert of the relay. Its digest is part of the relay's url,
+    /// so it must be kept to keep the same url across restarts.
+    pub tls: TlsConfig,
+
+    /// Tuning Params
+    pub tuning_params: KitsuneP2pTuningParams,
+
+    /// Which peers the relay forwards for, and how much.
+    pub limits: RelayLimits,
+}
+
+/// A running relay. Dropping this doesn't stop it, call [`Relay::close`].
+pub struct Relay {
+    ep_hnd: EpHnd,
+}
+
+impl Relay {
+    /// The url peers use to reach the relay.
+    pub fn local_addr(&self) -> KitsuneResult<TxUrl> {
+        self.ep_hnd.local_addr()
+    }
+
+    /// What the relay has forwarded and refused to forward, for each peer,
+    /// and its connections.
+    pub fn stats(&self) -> serde_json::Value {
+        self.ep_hnd.debug()
+    }
+
+    /// Stop the relay.
+    pub async fn close(&self) {
+        self.ep_hnd.close(0, "relay closed").await
+    }
+}
+
+/// Parse a peer's cert digest, as it appears in its proxy url.
+pub fn parse_cert_digest(digest: &str) -> KitsuneResult<Tx2Cert> {
+    let digest = base64::decode_config(digest, base64::URL_SAFE_NO_PAD)
+        .map_err(|e| KitsuneError::from(format!("invalid cert digest {}: {}", digest, e)))?;
+    if digest.len() != 32 {
+        return Err(format!("invalid cert digest length: {}", digest.len()).into());
+    }
+    Ok(digest.into())
+}
+
+/// Bind a relay over quic, which forwards for the peers the limits allow.
+/// Any data sent to the relay itself is answered with its stats.
+pub async fn spawn_relay(config: RelayConfig) -> KitsuneResult<Relay> {
+    let RelayConfig {
+        bind_to,
+        tls,
+        tuning_params,
+        limits,
+    } = config;
+
+    let mut conf = QuicConfig::default();
+    conf.tls = Some(tls);
+    conf.tuning_params = Some(tuning_params.clone());
+    let f = QuicBackendAdapt::new(conf).await?;
+    let f = tx2_pool_promote(f, tuning_params.clone());
+    let mut conf = ProxyConfig::default();
+    conf.tuning_params = Some(tuning_params.clone());
+    conf.allow_proxy_fwd = true;
+    conf.relay_limits = limits;
+    let f = tx2_proxy(f, conf)?;
+
+    let ep = f.bind(bind_to, tuning_params.implicit_timeout()).await?;
+    let ep_hnd = ep.handle().clone();
+    tracing::info!(addr = ?ep_hnd.local_addr(), "relay bound");
+
+    let stats_hnd = ep_hnd.clone();
+    tokio::task::spawn(ep.for_each_concurrent(
+        tuning_params.concurrent_limit_per_thread,
+        move |evt| {
+            let stats_hnd = stats_hnd.clone();
+            let t = tuning_params.implicit_timeout();
+            async move {
+                if let EpEvent::IncomingData(EpIncomingData {
+                    con,
+                    msg_id,
+                    mut data,
+                    ..
+                }) = evt
+                {
+                    let stats = serde_json::to_string_pretty(&stats_hnd.debug()).unwrap();
+                    data.clear();
+                    data.extend_from_slice(stats.as_bytes());
+                    let msg_id = if msg_id.is_notify() {
+                        0.into()
+                    } else {
+                        msg_id.as_res()
+                    };
+                    if let Err(e) = con.write(msg_id, data, t).await {
+                        tracing::warn!(?e, "failed to write relay stats");
+                    }
+                }
+            }
+        },
+    ));
+
+    Ok(Relay { ep_hnd })
+}
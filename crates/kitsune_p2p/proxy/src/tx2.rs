@@ -13,6 +13,7 @@ use kitsune_p2p_types::tx2::tx2_pool::*;
 use kitsune_p2p_types::tx2::tx2_utils::*;
 use kitsune_p2p_types::tx2::*;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use tokio::sync::Notify;
 
@@ -78,6 +79,29 @@ fn stub_proxy_from_bootstrap_cb(_: TxUrl) -> BoxFuture<'static, Option<TxUrl>> {
     Box::pin(async move { None })
 }
 
+/// Limits on the forwarding a proxy does for other peers,
+/// when `allow_proxy_fwd` is enabled.
+#[derive(Debug, Clone, Default)]
+pub struct RelayLimits {
+    /// If Some, only forward messages between these peers,
+    /// and only introduce these peers to each other for hole punching.
+    /// Default: None = any peer.
+    pub allowed_clients: Option<HashSet<Tx2Cert>>,
+
+    /// If Some, the most bytes forwarded for each peer per second.
+    /// Messages over the limit are refused with a route error.
+    /// Default: None = no limit.
+    pub max_bytes_per_second: Option<u64>,
+}
+
+impl RelayLimits {
+    fn is_allowed(&self, cert: &Tx2Cert) -> bool {
+        self.allowed_clients
+            .as_ref()
+            .map_or(true, |allowed| allowed.contains(cert))
+    }
+}
+
 /// Configuration for tx2 proxy wrapper
 #[non_exhaustive]
 pub struct ProxyConfig {
@@ -99,6 +123,17 @@ pub struct ProxyConfig {
 
     /// Logic for dynamically fetching a proxy url from the bootstrap service
     pub proxy_from_bootstrap_cb: ProxyFromBootstrapCb,
+
+    /// If Some(addr), we will try to keep an open connection to the relay at
+    /// addr, while still using our own address as our external address.
+    /// Peers that fail to connect to us directly can route through the relay,
+    /// and we will likewise route through it if we fail to connect to a peer.
+    /// Default: None.
+    pub fallback_relay: Option<TxUrl>,
+
+    /// Limits on forwarding, if `allow_proxy_fwd` is enabled.
+    /// Default: no limits.
+    pub relay_limits: RelayLimits,
 }
 
 impl Default for ProxyConfig {
@@ -108,6 +143,8 @@ impl Default for ProxyConfig {
             allow_proxy_fwd: false,
             client_of_remote_proxy: ProxyRemoteType::default(),
             proxy_from_bootstrap_cb: Arc::new(stub_proxy_from_bootstrap_cb),
+            fallback_relay: None,
+            relay_limits: RelayLimits::default(),
         }
     }
 }
@@ -121,12 +158,16 @@ impl ProxyConfig {
         bool,
         ProxyRemoteType,
         ProxyFromBootstrapCb,
+        Option<TxUrl>,
+        RelayLimits,
     )> {
         let ProxyConfig {
             tuning_params,
             allow_proxy_fwd,
             client_of_remote_proxy,
             proxy_from_bootstrap_cb,
+            fallback_relay,
+            relay_limits,
         } = self;
 
         let tuning_params = tuning_params.unwrap_or_default();
@@ -136,6 +177,8 @@ impl ProxyConfig {
             allow_proxy_fwd,
             client_of_remote_proxy,
             proxy_from_bootstrap_cb,
+            fallback_relay,
+            relay_limits,
         ))
     }
 }
@@ -151,6 +194,8 @@ const PROXY_TYPE_BYTES: usize = 1;
 const DIGEST_BYTES: usize = 32;
 
 const PROXY_FWD_MSG: u8 = 0x30;
+const PROXY_PUNCH_REQ: u8 = 0x31;
+const PROXY_PUNCH: u8 = 0x32;
 const PROXY_ROUTE_ERR: u8 = 0xc0;
 
 struct ProxyConHnd {
@@ -299,9 +344,127 @@ struct ProxyEpInner {
     // these are both INCOMING and OUTGOING
     direct_to_final_peer_con_map: HashMap<Uniq, HashMap<Tx2Cert, ConHnd>>,
 
+    // how each of the !OUT CONS! above reaches its final peer,
+    // reported in debug output
+    routes: HashMap<Uniq, ConRoute>,
+
+    // direct !SUB CONS! to final peers we otherwise reach through
+    // their proxy, established by hole punching
+    punched_sub_con_map: HashMap<Tx2Cert, ConHnd>,
+
+    // when we last asked a proxy to introduce us to a final peer
+    punch_requests: HashMap<Tx2Cert, std::time::Instant>,
+
+    // the cert each address we've connected to presented,
+    // if pinning peer certs
+    addr_pins: HashMap<TxUrl, Tx2Cert>,
+
+    // what we've forwarded for other peers, if we are a proxy
+    relay_stats: RelayStats,
+
     backoff: Backoff,
 }
 
+/// What a proxy has forwarded, and refused to forward, for other peers.
+#[derive(Default)]
+struct RelayStats {
+    clients: HashMap<Tx2Cert, RelayClientStats>,
+    refused_not_allowed: u64,
+    refused_over_limit: u64,
+}
+
+#[derive(Default)]
+struct RelayClientStats {
+    forwarded_messages: u64,
+    forwarded_bytes: u64,
+    // the bytes forwarded in the current one second window
+    window_start: Option<std::time::Instant>,
+    window_bytes: u64,
+}
+
+impl RelayStats {
+    /// Count a message the peer with the src cert asked us to forward to
+    /// the dest cert, or refuse it if that's over the limits.
+    fn forward(
+        &mut self,
+        limits: &RelayLimits,
+        src_cert: &Tx2Cert,
+        dest_cert: &Tx2Cert,
+        len: usize,
+    ) -> KitsuneResult<()> {
+        if !limits.is_allowed(src_cert) || !limits.is_allowed(dest_cert) {
+            self.refused_not_allowed += 1;
+            return Err("proxy fwd not allowed for this peer".into());
+        }
+        let len = len as u64;
+        let now = std::time::Instant::now();
+        let client = self.clients.entry(src_cert.clone()).or_default();
+        match client.window_start {
+            Some(start) if now.duration_since(start) < std::time::Duration::from_secs(1) => (),
+            _ => {
+                client.window_start = Some(now);
+                client.window_bytes = 0;
+            }
+        }
+        if let Some(max) = limits.max_bytes_per_second {
+            if client.window_bytes + len > max {
+                self.refused_over_limit += 1;
+                return Err("proxy fwd bandwidth limit exceeded".into());
+            }
+        }
+        client.window_bytes += len;
+        client.forwarded_messages += 1;
+        client.forwarded_bytes += len;
+        Ok(())
+    }
+
+    fn debug(&self, limits: &RelayLimits) -> serde_json::Value {
+        let clients = self
+            .clients
+            .iter()
+            .map(|(cert, c)| {
+                serde_json::json!({
+                    "client": cert.as_str(),
+                    "forwarded_messages": c.forwarded_messages,
+                    "forwarded_bytes": c.forwarded_bytes,
+                })
+            })
+            .collect::<Vec<_>>();
+        serde_json::json!({
+            "allowed_client_count": limits.allowed_clients.as_ref().map(|a| a.len()),
+            "max_bytes_per_second": limits.max_bytes_per_second,
+            "forwarded_messages": self.clients.values().map(|c| c.forwarded_messages).sum::<u64>(),
+            "forwarded_bytes": self.clients.values().map(|c| c.forwarded_bytes).sum::<u64>(),
+            "refused_not_allowed": self.refused_not_allowed,
+            "refused_over_limit": self.refused_over_limit,
+            "clients": clients,
+        })
+    }
+}
+
+/// How an out con reaches its final peer.
+struct ConRoute {
+    peer_cert: Tx2Cert,
+    // the proxy or relay we route through, if not direct
+    via: Option<Tx2Cert>,
+    hole_punched: bool,
+}
+
+impl ConRoute {
+    fn debug(&self) -> serde_json::Value {
+        let route = match (&self.via, self.hole_punched) {
+            (Some(_), _) => "relayed",
+            (None, true) => "hole_punched",
+            (None, false) => "direct",
+        };
+        serde_json::json!({
+            "peer": self.peer_cert.as_nick(),
+            "route": route,
+            "via": self.via.as_ref().map(|v| v.as_nick()),
+        })
+    }
+}
+
 impl ProxyEpInner {
     pub fn get_con_hnd(
         &mut self,
@@ -310,6 +473,19 @@ impl ProxyEpInner {
         final_peer_cert: Tx2Cert,
     ) -> KitsuneResult<(bool, ConHnd)> {
         let direct_peer = sub_con.uniq();
+        let route = ConRoute {
+            peer_cert: final_peer_cert.clone(),
+            via: if sub_con.peer_cert() == final_peer_cert {
+                None
+            } else {
+                Some(sub_con.peer_cert())
+            },
+            hole_punched: self
+                .punched_sub_con_map
+                .get(&final_peer_cert)
+                .map(|c| c.uniq())
+                == Some(direct_peer),
+        };
         let inner_map = self
             .direct_to_final_peer_con_map
             .entry(direct_peer)
@@ -325,6 +501,9 @@ impl ProxyEpInner {
                 })
                 .clone()
         };
+        if did_insert {
+            self.routes.insert(con.uniq(), route);
+        }
         Ok((did_insert, con))
     }
 }
@@ -335,6 +514,10 @@ struct ProxyEpHnd {
     logic_hnd: LogicChanHandle<EpEvent>,
     inner: Share<ProxyEpInner>,
     cur_proxy_url: Share<Option<ProxyUrl>>,
+    fallback_relay: Option<ProxyUrl>,
+    tuning_params: KitsuneP2pTuningParams,
+    // the limits on forwarding, if we forward for other peers
+    relay_limits: Option<RelayLimits>,
 }
 
 async fn get_con_hnd(
@@ -366,6 +549,9 @@ impl ProxyEpHnd {
         logic_hnd: LogicChanHandle<EpEvent>,
         backoff: Backoff,
         cur_proxy_url: Share<Option<ProxyUrl>>,
+        fallback_relay: Option<ProxyUrl>,
+        tuning_params: KitsuneP2pTuningParams,
+        relay_limits: Option<RelayLimits>,
     ) -> KitsuneResult<Arc<ProxyEpHnd>> {
         let local_cert = sub_ep_hnd.local_cert();
         Ok(Arc::new(ProxyEpHnd {
@@ -375,9 +561,17 @@ impl ProxyEpHnd {
             inner: Share::new(ProxyEpInner {
                 digest_to_sub_con_map: HashMap::new(),
                 direct_to_final_peer_con_map: HashMap::new(),
+                routes: HashMap::new(),
+                punched_sub_con_map: HashMap::new(),
+                punch_requests: HashMap::new(),
+                addr_pins: HashMap::new(),
+                relay_stats: RelayStats::default(),
                 backoff,
             }),
             cur_proxy_url,
+            fallback_relay,
+            tuning_params,
+            relay_limits,
         }))
     }
 }
@@ -391,12 +585,20 @@ impl AsEpHnd for ProxyEpHnd {
                 .keys()
                 .map(|k| format!("{:?}", k))
                 .collect::<Vec<_>>();
+            let routes = i.routes.values().map(|r| r.debug()).collect::<Vec<_>>();
+            let relay = self
+                .relay_limits
+                .as_ref()
+                .map(|limits| i.relay_stats.debug(limits));
             Ok(serde_json::json!({
                 "type": "tx2_proxy",
                 "state": "open",
                 "addr": addr?,
                 "proxy_count": i.digest_to_sub_con_map.len(),
                 "proxy_list": proxy_list,
+                "fallback_relay": self.fallback_relay.as_ref().map(|r| r.as_str()),
+                "routes": routes,
+                "relay": relay,
                 "sub": self.sub_ep_hnd.debug(),
             }))
         }) {
@@ -476,16 +678,170 @@ impl AsEpHnd for ProxyEpHnd {
 
         let local_cert = self.local_cert.clone();
         let logic_hnd = self.logic_hnd.clone();
-        let con_fut = self.sub_ep_hnd.get_connection(base_url, timeout);
+        let sub_ep_hnd = self.sub_ep_hnd.clone();
         let inner = self.inner.clone();
+        let tuning_params = self.tuning_params.clone();
+        let fallback_relay = self
+            .fallback_relay
+            .as_ref()
+            .filter(|r| Tx2Cert::from(r.digest()) != peer_cert)
+            .map(|r| TxUrl::from(r.as_base().as_str()));
+        let punched = self.inner.share_ref(|i| {
+            Ok(i.punched_sub_con_map
+                .get(&peer_cert)
+                .filter(|c| !c.is_closed())
+                .cloned())
+        });
         async move {
-            let sub_con = con_fut.await?;
+            // prefer a direct connection we've already hole punched
+            if let Ok(Some(sub_con)) = punched {
+                return get_con_hnd(&inner, logic_hnd, sub_con, local_cert, peer_cert, true).await;
+            }
+
+            let (sub_con, sub_url) = match fallback_relay {
+                None => (
+                    sub_ep_hnd.get_connection(base_url.clone(), timeout).await?,
+                    base_url,
+                ),
+                Some(relay_url) => {
+                    // leave time to fall back to the relay
+                    let direct_timeout = KitsuneTimeout::new(std::cmp::min(
+                        timeout.time_remaining(),
+                        std::time::Duration::from_millis(
+                            tuning_params.proxy_direct_connect_timeout_ms as u64,
+                        ),
+                    ));
+                    match sub_ep_hnd
+                        .get_connection(base_url.clone(), direct_timeout)
+                        .await
+                    {
+                        Ok(sub_con) => (sub_con, base_url),
+                        Err(err) => {
+                            tracing::info!(
+                                ?err,
+                                ?peer_cert,
+                                "direct connection failed, routing through relay"
+                            );
+                            (
+                                sub_ep_hnd
+                                    .get_connection(relay_url.clone(), timeout)
+                                    .await?,
+                                relay_url,
+                            )
+                        }
+                    }
+                }
+            };
+
+            if tuning_params.tls_pin_peer_certs {
+                check_addr_pin(&inner, &sub_url, &sub_con)?;
+            }
+
+            if sub_con.peer_cert() != peer_cert && tuning_params.proxy_hole_punch {
+                request_hole_punch(&inner, &tuning_params, &sub_con, &peer_cert).await;
+            }
+
             get_con_hnd(&inner, logic_hnd, sub_con, local_cert, peer_cert, true).await
         }
         .boxed()
     }
 }
 
+/// Remember the cert an address presents the first time we connect to it,
+/// and reject connections to it that present a different cert.
+fn check_addr_pin(inner: &Share<ProxyEpInner>, url: &TxUrl, sub_con: &ConHnd) -> KitsuneResult<()> {
+    let cert = sub_con.peer_cert();
+    inner.share_mut(|i, _| match i.addr_pins.get(url) {
+        Some(pinned) if *pinned != cert => {
+            tracing::warn!(%url, ?pinned, ?cert, "rejecting connection, address cert changed");
+            Err(format!("rejecting connection to {}, its cert has changed", url).into())
+        }
+        Some(_) => Ok(()),
+        None => {
+            i.addr_pins.insert(url.clone(), cert);
+            Ok(())
+        }
+    })
+}
+
+/// Ask the proxy we reach a peer through to introduce us,
+/// so that we can both try to connect to each other directly.
+/// Does nothing if we asked recently.
+async fn request_hole_punch(
+    inner: &Share<ProxyEpInner>,
+    tuning_params: &KitsuneP2pTuningParams,
+    sub_con: &ConHnd,
+    peer_cert: &Tx2Cert,
+) {
+    let retry = std::time::Duration::from_millis(tuning_params.proxy_hole_punch_retry_ms as u64);
+    let now = std::time::Instant::now();
+    let should_request = inner
+        .share_mut(|i, _| {
+            // forget requests old enough to be asked again
+            i.punch_requests
+                .retain(|_, last| now.duration_since(*last) < retry);
+            Ok(match i.punch_requests.get(peer_cert) {
+                Some(last) if now.duration_since(*last) < retry => false,
+                _ => {
+                    i.punch_requests.insert(peer_cert.clone(), now);
+                    true
+                }
+            })
+        })
+        .unwrap_or(false);
+    if !should_request {
+        return;
+    }
+
+    // the proxy knows who is asking from the connection itself
+    let mut data = PoolBuf::new();
+    data.reserve_front(PROXY_TYPE_BYTES + DIGEST_BYTES);
+    data.prepend_from_slice(AsRef::<[u8]>::as_ref(peer_cert));
+    data.prepend_from_slice(&[PROXY_PUNCH_REQ]);
+    if let Err(err) = sub_con
+        .write(0.into(), data, tuning_params.implicit_timeout())
+        .await
+    {
+        tracing::debug!(?err, ?peer_cert, "failed to request hole punch");
+    }
+}
+
+/// Connect directly to a peer we've been introduced to. If our introducer
+/// did the same for the peer, the packets we each send open up our NATs.
+async fn hole_punch(hnd: Arc<ProxyEpHnd>, peer_cert: Tx2Cert, url: TxUrl) {
+    let timeout =
+        KitsuneTimeout::from_millis(hnd.tuning_params.proxy_direct_connect_timeout_ms as u64);
+    match hnd.sub_ep_hnd.get_connection(url.clone(), timeout).await {
+        Ok(sub_con) if sub_con.peer_cert() == peer_cert => {
+            tracing::info!(?peer_cert, %url, "hole punched direct connection");
+            let _ = hnd.inner.share_mut(move |i, _| {
+                i.punched_sub_con_map.insert(peer_cert, sub_con);
+                Ok(())
+            });
+        }
+        Ok(sub_con) => {
+            tracing::warn!(
+                ?peer_cert,
+                actual = ?sub_con.peer_cert(),
+                %url,
+                "hole punch reached the wrong peer"
+            );
+        }
+        Err(err) => {
+            tracing::debug!(?err, ?peer_cert, %url, "hole punch failed, staying relayed");
+        }
+    }
+}
+
+fn punch_msg(peer_cert: &Tx2Cert, url: &TxUrl) -> PoolBuf {
+    let mut data = PoolBuf::new();
+    data.extend_from_slice(url.as_str().as_bytes());
+    data.reserve_front(PROXY_TYPE_BYTES + DIGEST_BYTES);
+    data.prepend_from_slice(AsRef::<[u8]>::as_ref(peer_cert));
+    data.prepend_from_slice(&[PROXY_PUNCH]);
+    data
+}
+
 async fn close_ep(hnd: &Arc<ProxyEpHnd>, logic_hnd: &LogicChanHandle<EpEvent>) {
     let _ = hnd.inner.share_mut(|_, c| {
         *c = true;
@@ -654,13 +1010,24 @@ async fn incoming_evt_handle(
                         });
                         let _ = logic_hnd.emit(evt).await;
                     } else {
-                        let dest = if !allow_proxy_fwd {
-                            tracing::error!("received fwd request on, but proxy fwd is disallowed");
-                            Err("proxy fwd disallowed".into())
-                        } else {
-                            hnd.inner.share_mut(|i, _| {
+                        let dest = match &hnd.relay_limits {
+                            Some(limits) if allow_proxy_fwd => hnd.inner.share_mut(|i, _| {
+                                // account to the peer we're connected to,
+                                // not the src cert it claims
+                                i.relay_stats.forward(
+                                    limits,
+                                    &sub_con.peer_cert(),
+                                    &dest_cert,
+                                    data.len(),
+                                )?;
                                 Ok(i.digest_to_sub_con_map.get(&dest_cert).cloned())
-                            })
+                            }),
+                            _ => {
+                                tracing::error!(
+                                    "received fwd request on, but proxy fwd is disallowed"
+                                );
+                                Err("proxy fwd disallowed".into())
+                            }
                         };
                         if let Err(e) = match dest {
                             Ok(Some(d_sub_con)) => {
@@ -703,6 +1070,94 @@ async fn incoming_evt_handle(
                         }
                     }
                 }
+                PROXY_PUNCH_REQ => {
+                    // introducing peers is a service we only offer as a proxy
+                    if !allow_proxy_fwd {
+                        return;
+                    }
+                    const DEST_START: usize = PROXY_TYPE_BYTES;
+                    const DEST_END: usize = DEST_START + DIGEST_BYTES;
+                    if data.len() < DEST_END {
+                        return;
+                    }
+                    // only ever introduce the peer on the other end of
+                    // this connection, never a cert it claims to be
+                    let src_cert = sub_con.peer_cert();
+                    let dest_cert: Tx2Cert = data[DEST_START..DEST_END].to_vec().into();
+                    if let Some(limits) = &hnd.relay_limits {
+                        if !limits.is_allowed(&src_cert) || !limits.is_allowed(&dest_cert) {
+                            return;
+                        }
+                    }
+                    let d_sub_con = match hnd
+                        .inner
+                        .share_mut(|i, _| Ok(i.digest_to_sub_con_map.get(&dest_cert).cloned()))
+                    {
+                        Ok(Some(d_sub_con)) => d_sub_con,
+                        _ => return,
+                    };
+                    // these are the addresses we see the peers connecting
+                    // from, i.e. the outside of any NATs they are behind
+                    let (src_addr, dest_addr) = match (sub_con.peer_addr(), d_sub_con.peer_addr()) {
+                        (Ok(src_addr), Ok(dest_addr)) => (src_addr, dest_addr),
+                        _ => return,
+                    };
+                    tracing::debug!(?src_cert, ?dest_cert, "introducing peers to hole punch");
+                    let _ = write_to_sub_con(
+                        tuning_params,
+                        &hnd.inner,
+                        logic_hnd,
+                        d_sub_con,
+                        0.into(),
+                        punch_msg(&src_cert, &src_addr),
+                        cur_proxy_url,
+                    )
+                    .await;
+                    let _ = write_to_sub_con(
+                        tuning_params,
+                        &hnd.inner,
+                        logic_hnd,
+                        sub_con,
+                        0.into(),
+                        punch_msg(&dest_cert, &dest_addr),
+                        cur_proxy_url,
+                    )
+                    .await;
+                }
+                PROXY_PUNCH => {
+                    const CERT_START: usize = PROXY_TYPE_BYTES;
+                    const CERT_END: usize = CERT_START + DIGEST_BYTES;
+                    if data.len() <= CERT_END {
+                        return;
+                    }
+                    let peer_cert: Tx2Cert = data[CERT_START..CERT_END].to_vec().into();
+                    // only dial addresses given to us by our own proxy,
+                    // or by a proxy we asked for an introduction
+                    let from_our_proxy = matches!(
+                        cur_proxy_url.share_ref(|r| Ok(r.clone())),
+                        Ok(Some(proxy_url)) if Tx2Cert::from(proxy_url.digest()) == sub_con.peer_cert()
+                    );
+                    let retry = std::time::Duration::from_millis(
+                        hnd.tuning_params.proxy_hole_punch_retry_ms as u64,
+                    );
+                    let requested = hnd
+                        .inner
+                        .share_ref(|i| {
+                            Ok(matches!(
+                                i.punch_requests.get(&peer_cert),
+                                Some(last) if last.elapsed() < retry
+                            ))
+                        })
+                        .unwrap_or(false);
+                    if !from_our_proxy && !requested {
+                        tracing::warn!(?peer_cert, "ignoring unrequested hole punch");
+                        return;
+                    }
+                    let url: TxUrl = String::from_utf8_lossy(&data[CERT_END..])
+                        .to_string()
+                        .into();
+                    tokio::task::spawn(hole_punch(hnd.clone(), peer_cert, url));
+                }
                 PROXY_ROUTE_ERR => {
                     const SRC_START: usize = PROXY_TYPE_BYTES;
                     const SRC_END: usize = SRC_START + DIGEST_BYTES;
@@ -804,11 +1259,16 @@ async fn close_connection_inner(
             i.digest_to_sub_con_map.remove(&peer_cert);
         }
 
+        i.punched_sub_con_map.retain(|_, c| c.uniq() != direct_peer);
+
         // remove all out cons associated with this exact connection
-        Ok((
-            i.backoff.clone(),
-            i.direct_to_final_peer_con_map.remove(&direct_peer),
-        ))
+        let kill_cons = i.direct_to_final_peer_con_map.remove(&direct_peer);
+        if let Some(kill_cons) = &kill_cons {
+            for c in kill_cons.values() {
+                i.routes.remove(&c.uniq());
+            }
+        }
+        Ok((i.backoff.clone(), kill_cons))
     });
 
     let kill_cons = match inner_res {
@@ -857,6 +1317,8 @@ impl ProxyEp {
         allow_proxy_fwd: bool,
         client_of_remote_proxy: ProxyRemoteType,
         proxy_from_bootstrap_cb: ProxyFromBootstrapCb,
+        fallback_relay: Option<TxUrl>,
+        relay_limits: RelayLimits,
     ) -> KitsuneResult<Ep> {
         // this isn't something that needs to be configurable,
         // because it's entirely dependent on the code written here
@@ -876,6 +1338,16 @@ impl ProxyEp {
             logic_hnd.clone(),
             backoff.clone(),
             cur_proxy_url.clone(),
+            fallback_relay
+                .as_ref()
+                .map(|r| ProxyUrl::from_full(r.as_str()))
+                .transpose()?,
+            tuning_params.clone(),
+            if allow_proxy_fwd {
+                Some(relay_limits)
+            } else {
+                None
+            },
         )?;
 
         let logic = incoming_evt_logic(
@@ -907,6 +1379,15 @@ impl ProxyEp {
                 });
             }
 
+            // stay connected to our relay, if any, so peers can reach us through it
+            if let Some(relay_url) = fallback_relay.clone() {
+                let timeout = tuning_params.implicit_timeout();
+                let hnd = hnd.clone();
+                tokio::task::spawn(async move {
+                    let _ = hnd.get_connection(relay_url, timeout).await;
+                });
+            }
+
             // set up the logic loop that keeps us connected to a proxy
             let hnd = hnd.clone();
             l_hnd
@@ -927,6 +1408,11 @@ impl ProxyEp {
                             let timeout = tuning_params.implicit_timeout();
                             let _ = hnd.get_connection(proxy_url, timeout).await;
                         }
+
+                        if let Some(relay_url) = fallback_relay.clone() {
+                            let timeout = tuning_params.implicit_timeout();
+                            let _ = hnd.get_connection(relay_url, timeout).await;
+                        }
                     }
                 })
                 .await?;
@@ -961,18 +1447,28 @@ struct ProxyEpFactory {
     allow_proxy_fwd: bool,
     client_of_remote_proxy: ProxyRemoteType,
     proxy_from_bootstrap_cb: ProxyFromBootstrapCb,
+    fallback_relay: Option<TxUrl>,
+    relay_limits: RelayLimits,
     sub_fact: EpFactory,
 }
 
 impl ProxyEpFactory {
     pub fn new(sub_fact: EpFactory, config: ProxyConfig) -> KitsuneResult<EpFactory> {
-        let (tuning_params, allow_proxy_fwd, client_of_remote_proxy, proxy_from_bootstrap_cb) =
-            config.split()?;
+        let (
+            tuning_params,
+            allow_proxy_fwd,
+            client_of_remote_proxy,
+            proxy_from_bootstrap_cb,
+            fallback_relay,
+            relay_limits,
+        ) = config.split()?;
         let fact: EpFactory = Arc::new(ProxyEpFactory {
             tuning_params,
             allow_proxy_fwd,
             client_of_remote_proxy,
             proxy_from_bootstrap_cb,
+            fallback_relay,
+            relay_limits,
             sub_fact,
         });
         Ok(fact)
@@ -990,6 +1486,8 @@ impl AsEpFactory for ProxyEpFactory {
         let allow_proxy_fwd = self.allow_proxy_fwd;
         let client_of_remote_proxy = self.client_of_remote_proxy.clone();
         let proxy_from_bootstrap_cb = self.proxy_from_bootstrap_cb.clone();
+        let fallback_relay = self.fallback_relay.clone();
+        let relay_limits = self.relay_limits.clone();
         async move {
             let sub_ep = fut.await?;
             ProxyEp::new(
@@ -998,6 +1496,8 @@ impl AsEpFactory for ProxyEpFactory {
                 allow_proxy_fwd,
                 client_of_remote_proxy,
                 proxy_from_bootstrap_cb,
+                fallback_relay,
+                relay_limits,
             )
             .await
         }
@@ -1078,6 +1578,32 @@ mod tests {
             .into()
     }
 
+    #[test]
+    fn test_relay_limits() {
+        let a: Tx2Cert = vec![0xaa; 32].into();
+        let b: Tx2Cert = vec![0xbb; 32].into();
+        let c: Tx2Cert = vec![0xcc; 32].into();
+        let limits = RelayLimits {
+            allowed_clients: Some([a.clone(), b.clone()].into_iter().collect()),
+            max_bytes_per_second: Some(100),
+        };
+        let mut stats = RelayStats::default();
+
+        assert!(stats.forward(&limits, &a, &b, 60).is_ok());
+        assert!(stats.forward(&limits, &b, &a, 60).is_ok());
+        // a has used up its bandwidth for this second
+        assert!(stats.forward(&limits, &a, &b, 60).is_err());
+        // c is neither allowed to send nor receive
+        assert!(stats.forward(&limits, &c, &a, 1).is_err());
+        assert!(stats.forward(&limits, &a, &c, 1).is_err());
+
+        let debug = stats.debug(&limits);
+        assert_eq!(debug["forwarded_messages"], 2);
+        assert_eq!(debug["forwarded_bytes"], 120);
+        assert_eq!(debug["refused_over_limit"], 1);
+        assert_eq!(debug["refused_not_allowed"], 2);
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_tx2_route_err() {
         observability::test_run().ok();
@@ -1165,4 +1691,64 @@ mod tests {
 
         futures::future::try_join_all(all_tasks).await.unwrap();
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_tx2_proxy_hole_punch() {
+        observability::test_run().ok();
+
+        let t = KitsuneTimeout::from_millis(5000);
+
+        let mut all_tasks = Vec::new();
+
+        let (p_join, p_addr, p_ep) = build_node(None, false).await;
+        all_tasks.push(p_join);
+
+        let (t_join, t_addr, t_ep) = build_node(None, false).await;
+        all_tasks.push(t_join);
+
+        // establish proxy connection
+        let _ = t_ep.get_connection(p_addr.clone(), t).await.unwrap();
+
+        let t_addr_proxy = proxify_addr(&p_addr, &t_addr);
+
+        let (s_done, r_done) = tokio::sync::oneshot::channel();
+        let (n_join, _n_addr, n_ep) = build_node(Some(s_done), false).await;
+
+        // the first connection is relayed, and asks the proxy to introduce us
+        let _ = n_ep.get_connection(t_addr_proxy.clone(), t).await.unwrap();
+
+        let route_of = |debug: serde_json::Value| {
+            debug["routes"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|r| r["route"].as_str().unwrap().to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(vec!["relayed".to_string()], route_of(n_ep.debug()));
+
+        // once the hole is punched, new connections go direct
+        let mut con = None;
+        for _ in 0..50 {
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            let c = n_ep.get_connection(t_addr_proxy.clone(), t).await.unwrap();
+            if route_of(n_ep.debug()).contains(&"hole_punched".to_string()) {
+                con = Some(c);
+                break;
+            }
+        }
+        let con = con.expect("hole punch never completed");
+
+        let mut data = PoolBuf::new();
+        data.extend_from_slice(b"hello");
+        con.write(0.into(), data, t).await.unwrap();
+        r_done.await.unwrap();
+        n_ep.close(0, "").await;
+        n_join.await.unwrap().unwrap();
+
+        p_ep.close(0, "").await;
+        t_ep.close(0, "").await;
+
+        futures::future::try_join_all(all_tasks).await.unwrap();
+    }
 }
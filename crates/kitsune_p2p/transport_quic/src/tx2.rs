@@ -10,6 +10,8 @@ use kitsune_p2p_types::tx2::tx2_adapter::*;
 use kitsune_p2p_types::tx2::tx2_utils::*;
 use kitsune_p2p_types::tx2::*;
 use kitsune_p2p_types::*;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 
 /// Configuration for QuicBackendAdapt
@@ -51,6 +53,29 @@ pub async fn tx2_quic_adapter(config: QuicConfig) -> KitsuneResult<AdapterFactor
 /// Tls ALPN identifier for kitsune quic handshaking
 const ALPN_KITSUNE_QUIC_0: &[u8] = b"kitsune-quic/0";
 
+/// Resume sessions from the server's session storage rather than from
+/// encrypted tickets, as rustls only accepts 0-RTT data with stateful
+/// resumption.
+struct StatefulResumption;
+
+impl rustls::server::ProducesTickets for StatefulResumption {
+    fn enabled(&self) -> bool {
+        false
+    }
+
+    fn lifetime(&self) -> u32 {
+        0
+    }
+
+    fn encrypt(&self, _plain: &[u8]) -> Option<Vec<u8>> {
+        None
+    }
+
+    fn decrypt(&self, _cipher: &[u8]) -> Option<Vec<u8>> {
+        None
+    }
+}
+
 struct QuicInChanRecvAdapt(BoxStream<'static, InChanFut>);
 
 impl QuicInChanRecvAdapt {
@@ -185,13 +210,38 @@ impl ConAdapt for QuicConAdapt {
     }
 }
 
-fn connecting(con_fut: quinn::Connecting, local_cert: Tx2Cert, dir: Tx2ConDir) -> ConFut {
+/// Establish a connection. `zero_rtt_accepted` counts the connections which
+/// resumed a session with 0-RTT, if it is enabled.
+fn connecting(
+    con_fut: quinn::Connecting,
+    local_cert: Tx2Cert,
+    dir: Tx2ConDir,
+    zero_rtt_accepted: Option<Arc<AtomicU64>>,
+) -> ConFut {
     async move {
         let quinn::NewConnection {
             connection,
             uni_streams,
             ..
-        } = con_fut.await.map_err(KitsuneError::other)?;
+        } = if let Some(zero_rtt_accepted) = zero_rtt_accepted {
+            // Only possible if we have a session to resume with this peer,
+            // otherwise fall back to a full handshake.
+            match con_fut.into_0rtt() {
+                Ok((new_con, accepted)) => {
+                    // Until the handshake completes, the peer's identity is
+                    // the one cached with the session, which may be another
+                    // peer's as sessions are cached by server name, so the
+                    // connection is only used once the peer is confirmed.
+                    if accepted.await {
+                        zero_rtt_accepted.fetch_add(1, Ordering::Relaxed);
+                    }
+                    new_con
+                }
+                Err(con_fut) => con_fut.await.map_err(KitsuneError::other)?,
+            }
+        } else {
+            con_fut.await.map_err(KitsuneError::other)?
+        };
 
         let con: Arc<dyn ConAdapt> = Arc::new(QuicConAdapt::new(connection, dir)?);
         let chan_recv: Box<dyn InChanRecvAdapt> = Box::new(QuicInChanRecvAdapt::new(uni_streams));
@@ -235,7 +285,7 @@ impl QuicConRecvAdapt {
                 move |(mut recv, local_cert, on_drop)| async move {
                     recv.next().await.map(|con| {
                         (
-                            connecting(con, local_cert.clone(), Tx2ConDir::Incoming),
+                            connecting(con, local_cert.clone(), Tx2ConDir::Incoming, None),
                             (recv, local_cert, on_drop),
                         )
                     })
@@ -264,16 +314,20 @@ impl ConRecvAdapt for QuicConRecvAdapt {}
 struct QuicEndpointAdaptInner {
     ep: quinn::Endpoint,
     local_cert: Tx2Cert,
+    /// The count of outgoing connections which resumed with 0-RTT,
+    /// if it is enabled.
+    zero_rtt_accepted: Option<Arc<AtomicU64>>,
 }
 
 struct QuicEndpointAdapt(Share<QuicEndpointAdaptInner>, Uniq, Tx2Cert);
 
 impl QuicEndpointAdapt {
-    pub fn new(ep: quinn::Endpoint, local_cert: Tx2Cert) -> Self {
+    pub fn new(ep: quinn::Endpoint, local_cert: Tx2Cert, zero_rtt: bool) -> Self {
         Self(
             Share::new(QuicEndpointAdaptInner {
                 ep,
                 local_cert: local_cert.clone(),
+                zero_rtt_accepted: zero_rtt.then(Default::default),
             }),
             Uniq::default(),
             local_cert,
@@ -283,11 +337,18 @@ impl QuicEndpointAdapt {
 
 impl EndpointAdapt for QuicEndpointAdapt {
     fn debug(&self) -> serde_json::Value {
+        let zero_rtt_accepted = self
+            .0
+            .share_mut(|i, _| Ok(i.zero_rtt_accepted.clone()))
+            .ok()
+            .flatten()
+            .map(|count| count.load(Ordering::Relaxed));
         match self.local_addr() {
             Ok(addr) => serde_json::json!({
                 "type": "tx2_quic",
                 "state": "open",
                 "addr": addr,
+                "zero_rtt_accepted": zero_rtt_accepted,
             }),
             Err(_) => serde_json::json!({
                 "type": "tx2_quic",
@@ -334,17 +395,21 @@ impl EndpointAdapt for QuicEndpointAdapt {
     }
 
     fn connect(&self, url: TxUrl, timeout: KitsuneTimeout) -> ConFut {
-        let maybe_ep = self
-            .0
-            .share_mut(|i, _| Ok((i.ep.clone(), i.local_cert.clone())));
+        let maybe_ep = self.0.share_mut(|i, _| {
+            Ok((
+                i.ep.clone(),
+                i.local_cert.clone(),
+                i.zero_rtt_accepted.clone(),
+            ))
+        });
         timeout
             .mix(async move {
-                let (ep, local_cert) = maybe_ep?;
+                let (ep, local_cert, zero_rtt_accepted) = maybe_ep?;
                 let addr = crate::url_to_addr(url.as_url2(), crate::SCHEME)
                     .await
                     .map_err(KitsuneError::other)?;
                 let con = ep.connect(addr, "stub.stub").map_err(KitsuneError::other);
-                match connecting(con?, local_cert, Tx2ConDir::Outgoing).await {
+                match connecting(con?, local_cert, Tx2ConDir::Outgoing, zero_rtt_accepted).await {
                     Ok(con) => Ok(con),
                     Err(err) => {
                         tracing::warn!(?err, "failed to establish outgoing connection (quic)");
@@ -378,16 +443,32 @@ pub struct QuicBackendAdapt {
     local_cert: Tx2Cert,
     quic_srv: quinn::ServerConfig,
     quic_cli: quinn::ClientConfig,
+    zero_rtt: bool,
 }
 
 impl QuicBackendAdapt {
     /// Construct a new quic tx2 backend bind adapter
     pub async fn new(config: QuicConfig) -> KitsuneResult<AdapterFactory> {
+        let out: AdapterFactory = Arc::new(Self::build(config).await?);
+        Ok(out)
+    }
+
+    async fn build(config: QuicConfig) -> KitsuneResult<Self> {
         let (tls, tuning_params) = config.split().await?;
 
         let local_cert = tls.cert_digest.clone().into();
 
-        let (tls_srv, tls_cli) = gen_tls_configs(ALPN_KITSUNE_QUIC_0, &tls, tuning_params.clone())?;
+        let (mut tls_srv, mut tls_cli) =
+            gen_tls_configs(ALPN_KITSUNE_QUIC_0, &tls, tuning_params.clone())?;
+
+        let zero_rtt = tuning_params.tx2_quic_enable_0rtt;
+        if zero_rtt {
+            // quic requires the early data limit to be exactly u32::MAX,
+            // flow control limits the actual amount.
+            Arc::make_mut(&mut tls_srv).max_early_data_size = u32::MAX;
+            Arc::make_mut(&mut tls_srv).ticketer = Arc::new(StatefulResumption);
+            Arc::make_mut(&mut tls_cli).enable_early_data = true;
+        }
 
         let mut transport = quinn::TransportConfig::default();
 
@@ -414,19 +495,19 @@ impl QuicBackendAdapt {
 
         let mut quic_srv = quinn::ServerConfig::with_crypto(tls_srv);
         quic_srv.transport = transport.clone();
+        quic_srv.migration(tuning_params.tx2_quic_allow_migration);
 
         let mut quic_cli = quinn::ClientConfig::new(tls_cli);
         quic_cli.transport = transport;
 
         tracing::debug!(?quic_srv, ?quic_cli, "build quinn configs");
 
-        let out: AdapterFactory = Arc::new(Self {
+        Ok(Self {
             local_cert,
             quic_srv,
             quic_cli,
-        });
-
-        Ok(out)
+            zero_rtt,
+        })
     }
 }
 
@@ -435,6 +516,7 @@ impl BindAdapt for QuicBackendAdapt {
         let local_cert = self.local_cert.clone();
         let quic_srv = self.quic_srv.clone();
         let quic_cli = self.quic_cli.clone();
+        let zero_rtt = self.zero_rtt;
         timeout
             .mix(async move {
                 let addr = crate::url_to_addr(url.as_url2(), crate::SCHEME)
@@ -447,7 +529,7 @@ impl BindAdapt for QuicBackendAdapt {
                 ep.set_default_client_config(quic_cli);
 
                 let ep: Arc<dyn EndpointAdapt> =
-                    Arc::new(QuicEndpointAdapt::new(ep, local_cert.clone()));
+                    Arc::new(QuicEndpointAdapt::new(ep, local_cert.clone(), zero_rtt));
                 let con_recv: Box<dyn ConRecvAdapt> =
                     Box::new(QuicConRecvAdapt::new(inc, local_cert.clone(), ep.clone()));
 
@@ -525,4 +607,154 @@ mod tests {
 
         rt.await.unwrap().unwrap();
     }
+
+    fn tuned(
+        f: impl FnOnce(&mut kitsune_p2p_types::config::tuning_params_struct::KitsuneP2pTuningParams),
+    ) -> QuicConfig {
+        let mut tuning_params = (*KitsuneP2pTuningParams::default()).clone();
+        f(&mut tuning_params);
+        QuicConfig {
+            tls: None,
+            tuning_params: Some(Arc::new(tuning_params)),
+        }
+    }
+
+    /// Bind an endpoint which accepts connections and keeps them open.
+    async fn bind_accepting(config: QuicConfig) -> Arc<dyn EndpointAdapt> {
+        let t = KitsuneTimeout::from_millis(5000);
+        let factory = QuicBackendAdapt::new(config).await.unwrap();
+        let (ep, mut con_recv) = factory
+            .bind("kitsune-quic://127.0.0.1:0".into(), t)
+            .await
+            .unwrap();
+        tokio::task::spawn(async move {
+            let mut cons = Vec::new();
+            while let Some(mc) = con_recv.next().await {
+                if let Ok(con) = mc.await {
+                    cons.push(con);
+                }
+            }
+        });
+        ep
+    }
+
+    fn zero_rtt_accepted(ep: &Arc<dyn EndpointAdapt>) -> u64 {
+        ep.debug()["zero_rtt_accepted"].as_u64().unwrap()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_quic_tx2_0rtt() {
+        kitsune_p2p_types::dependencies::observability::test_run().ok();
+
+        let t = KitsuneTimeout::from_millis(5000);
+        let zero_rtt = || tuned(|tp| tp.tx2_quic_enable_0rtt = true);
+
+        let client = bind_accepting(zero_rtt()).await;
+        let server1 = bind_accepting(zero_rtt()).await;
+        let server2 = bind_accepting(zero_rtt()).await;
+
+        // The first connection is a full handshake which leaves a session
+        // to resume, though it may take a moment to arrive.
+        let mut resumed = false;
+        for _ in 0..20 {
+            let (con, _recv) = client
+                .connect(server1.local_addr().unwrap(), t)
+                .await
+                .unwrap();
+            assert_eq!(server1.local_cert(), con.peer_cert());
+            if zero_rtt_accepted(&client) > 0 {
+                resumed = true;
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+        assert!(resumed);
+
+        // Sessions are cached by server name, which is the same for every
+        // peer, so the session with server1 is offered to server2, which
+        // must reject it, and the connection reports server2's cert.
+        let accepted = zero_rtt_accepted(&client);
+        let (con, _recv) = client
+            .connect(server2.local_addr().unwrap(), t)
+            .await
+            .unwrap();
+        assert_eq!(server2.local_cert(), con.peer_cert());
+        assert_eq!(accepted, zero_rtt_accepted(&client));
+
+        client.close(0, "").await;
+        server1.close(0, "").await;
+        server2.close(0, "").await;
+    }
+
+    /// Send a message, move the client to a new socket, and see whether a
+    /// second message still arrives.
+    async fn message_arrives_after_migrating(allow_migration: bool) -> bool {
+        let t = KitsuneTimeout::from_millis(5000);
+
+        let factory = QuicBackendAdapt::build(tuned(|tp| {
+            tp.tx2_quic_allow_migration = allow_migration;
+        }))
+        .await
+        .unwrap();
+        let (server, mut con_recv) = factory
+            .bind("kitsune-quic://127.0.0.1:0".into(), t)
+            .await
+            .unwrap();
+        let addr = crate::url_to_addr(server.local_addr().unwrap().as_url2(), crate::SCHEME)
+            .await
+            .unwrap();
+
+        // A bare quinn endpoint, so the client's socket can be swapped.
+        let mut client = quinn::Endpoint::client("127.0.0.1:0".parse().unwrap()).unwrap();
+        client.set_default_client_config(factory.quic_cli.clone());
+        let quinn::NewConnection { connection, .. } =
+            client.connect(addr, "stub.stub").unwrap().await.unwrap();
+        let con = QuicConAdapt::new(connection, Tx2ConDir::Outgoing).unwrap();
+
+        let send = |msg: &'static [u8]| {
+            let con = &con;
+            async move {
+                let mut c = con.out_chan(t).await.unwrap();
+                let mut data = PoolBuf::new();
+                data.extend_from_slice(msg);
+                c.write(0.into(), data, t).await.unwrap();
+            }
+        };
+
+        send(b"before").await;
+        let (_con, mut recv) = con_recv.next().await.unwrap().await.unwrap();
+        let mut c = recv.next().await.unwrap().await.unwrap();
+        let (_, data) = c.read(t).await.unwrap();
+        assert_eq!(b"before", data.as_ref());
+
+        client
+            .rebind(std::net::UdpSocket::bind("127.0.0.1:0").unwrap())
+            .unwrap();
+        send(b"after").await;
+
+        let arrived = tokio::time::timeout(std::time::Duration::from_secs(2), async {
+            let mut c = recv.next().await?.await.ok()?;
+            let (_, data) = c.read(t).await.ok()?;
+            Some(data)
+        })
+        .await;
+
+        server.close(0, "").await;
+
+        match arrived {
+            Ok(Some(data)) => {
+                assert_eq!(b"after", data.as_ref());
+                true
+            }
+            _ => false,
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_quic_tx2_migration() {
+        kitsune_p2p_types::dependencies::observability::test_run().ok();
+
+        assert!(message_arrives_after_migrating(true).await);
+        assert!(!message_arrives_after_migrating(false).await);
+    }
 }
@@ -0,0 +1,31 @@
+use kitsune_p2p_transport_webrtc::signal::*;
+use kitsune_p2p_types::*;
+use structopt::StructOpt;
+
+/// Option Parsing
+#[derive(structopt::StructOpt, Debug)]
+#[structopt(name = "kitsune-p2p-webrtc-signal")]
+pub struct Opt {
+    /// To which network interface / port should we bind?
+    #[structopt(short = "b", long, default_value = "0.0.0.0:0")]
+    pub bind_to: std::net::SocketAddr,
+}
+
+#[tokio::main(flavor = "multi_thread")]
+async fn main() {
+    observability::test_run().ok();
+
+    if let Err(e) = inner().await {
+        eprintln!("{:?}", e);
+    }
+}
+
+async fn inner() -> KitsuneResult<()> {
+    let opt = Opt::from_args();
+
+    let (addr, task) = signal_server(opt.bind_to).await?;
+
+    println!("ws://{}", addr);
+
+    task.await.map_err(KitsuneError::other)
+}
@@ -0,0 +1,125 @@
+#![deny(missing_docs)]
+//! WebRTC transport module for kitsune-p2p
+//!
+//! Peers find each other through a signal server, which each of them holds a
+//! websocket open to. Once they have exchanged session descriptions through
+//! it, the peers connect directly, traversing NATs with the configured
+//! STUN / TURN servers. The signal server never sees the data sent over
+//! the peer connections.
+//!
+//! Urls name the signal server a peer is registered with, and the peer's id
+//! on it, e.g. `kitsune-webrtc://signal.example.com:443/<id>`. The signal
+//! server is reached over `wss://`, or over `ws://` if the url has an
+//! `insecure` query parameter.
+
+/// Re-exported dependencies.
+pub mod dependencies {
+    pub use ::kitsune_p2p_types;
+    pub use ::webrtc;
+}
+
+use kitsune_p2p_types::dependencies::url2::*;
+use kitsune_p2p_types::tx2::tx2_utils::TxUrl;
+use kitsune_p2p_types::*;
+
+const SCHEME: &str = "kitsune-webrtc";
+
+/// Query parameter marking a signal server reached over plain `ws://`.
+const INSECURE: &str = "insecure";
+
+/// Convert the `ws://` or `wss://` url of a signal server
+/// into the url to bind a webrtc endpoint to.
+pub fn bind_url_from_signal_url(signal_url: &Url2) -> KitsuneResult<TxUrl> {
+    let insecure = match signal_url.scheme() {
+        "ws" => true,
+        "wss" => false,
+        _ => {
+            return Err(format!(
+                "invalid signal url. got: '{}', expected: 'wss://host:port'",
+                signal_url
+            )
+            .into())
+        }
+    };
+    let host = signal_url
+        .host_str()
+        .ok_or_else(|| KitsuneError::from(format!("signal url has no host: {}", signal_url)))?;
+    let mut url = url2!("{}://{}", SCHEME, host);
+    url.set_port(signal_url.port_or_known_default())
+        .map_err(|_| KitsuneError::from(format!("invalid signal url port: {}", signal_url)))?;
+    if insecure {
+        url.set_query(Some(INSECURE));
+    }
+    Ok(url.into())
+}
+
+/// internal helper get the websocket url of the signal server named by a url
+pub(crate) fn signal_url(url: &Url2) -> KitsuneResult<Url2> {
+    if url.scheme() != SCHEME || url.host_str().is_none() || url.port().is_none() {
+        return Err(format!(
+            "invalid input. got: '{}', expected: '{}://host:port'",
+            url, SCHEME
+        )
+        .into());
+    }
+    let insecure = url.query_pairs().any(|(k, _)| k == INSECURE);
+    Ok(url2!(
+        "{}://{}:{}",
+        if insecure { "ws" } else { "wss" },
+        url.host_str().unwrap(),
+        url.port().unwrap(),
+    ))
+}
+
+/// internal helper get the url of a peer registered with the same
+/// signal server as `url`
+pub(crate) fn peer_url(url: &Url2, id: &str) -> TxUrl {
+    let mut url = url.clone();
+    url.set_path(id);
+    url.into()
+}
+
+/// internal helper get the peer id from a peer url
+pub(crate) fn peer_id(url: &Url2) -> KitsuneResult<String> {
+    match url
+        .path_segments()
+        .and_then(|mut s| s.find(|s| !s.is_empty()))
+    {
+        Some(id) => Ok(id.to_string()),
+        None => Err(format!("invalid input. got: '{}', expected a peer id path", url).into()),
+    }
+}
+
+/// internal helper peer ids are the base64 of the peer's certificate digest
+pub(crate) fn peer_cert(id: &str) -> KitsuneResult<Tx2Cert> {
+    let digest = base64::decode_config(id, base64::URL_SAFE_NO_PAD)
+        .map_err(|_| KitsuneError::from(format!("invalid peer id: {}", id)))?;
+    Ok(digest.into())
+}
+
+pub mod signal;
+pub mod tx2;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signal_urls_round_trip() {
+        let bind = bind_url_from_signal_url(&url2!("ws://127.0.0.1:4000")).unwrap();
+        assert_eq!("kitsune-webrtc://127.0.0.1:4000?insecure", bind.as_str());
+        assert_eq!(
+            "ws://127.0.0.1:4000/",
+            signal_url(bind.as_url2()).unwrap().as_str()
+        );
+
+        let bind = bind_url_from_signal_url(&url2!("wss://signal.example.com")).unwrap();
+        let peer = peer_url(bind.as_url2(), "abc");
+        assert_eq!("kitsune-webrtc://signal.example.com:443/abc", peer.as_str());
+        assert_eq!(
+            "wss://signal.example.com/",
+            signal_url(peer.as_url2()).unwrap().as_str()
+        );
+        assert_eq!("abc", peer_id(peer.as_url2()).unwrap());
+    }
+}
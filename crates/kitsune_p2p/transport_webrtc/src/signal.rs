@@ -0,0 +1,198 @@
+//! The signal server webrtc peers exchange session descriptions through.
+//!
+//! Peers register with the server under an id, then ask it to forward offers
+//! and answers to other registered peers. Registration isn't authenticated,
+//! so every offer and answer carries a [`DtlsProof`] binding the DTLS
+//! certificate of the peer connection to the tls cert the id is a digest of.
+
+use futures::sink::SinkExt;
+use futures::stream::StreamExt;
+use kitsune_p2p_types::dependencies::{ghost_actor::dependencies::tracing, serde_json};
+use kitsune_p2p_types::*;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio_tungstenite::tungstenite::Message;
+use webrtc::peer_connection::sdp::session_description::RTCSessionDescription;
+
+/// Proves that the DTLS certificate fingerprinted in a session description
+/// belongs to the peer with a given tls cert: the peer signs the fingerprint
+/// with its tls cert key.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct DtlsProof {
+    /// The base64 of the signing peer's der encoded tls cert.
+    pub cert: String,
+    /// The base64 of the signature over the DTLS fingerprint.
+    pub sig: String,
+}
+
+/// A message between two peers, relayed by the signal server.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SignalMsg {
+    /// Open a new connection.
+    Offer {
+        /// Identifies the connection the answer is for.
+        con_id: String,
+        /// The offering peer's session description.
+        sdp: RTCSessionDescription,
+        /// Proves the offer's DTLS certificate is the offering peer's.
+        proof: DtlsProof,
+    },
+    /// Accept a connection.
+    Answer {
+        /// The id from the offer.
+        con_id: String,
+        /// The answering peer's session description.
+        sdp: RTCSessionDescription,
+        /// Proves the answer's DTLS certificate is the answering peer's.
+        proof: DtlsProof,
+    },
+}
+
+/// Messages sent over the websocket to and from the signal server.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SignalWire {
+    /// Client to server, forward messages for this id to me.
+    Register {
+        /// The id to register.
+        id: String,
+    },
+    /// Client to server, forward a message to another peer.
+    Forward {
+        /// The id of the peer to forward to.
+        to: String,
+        /// The message.
+        msg: SignalMsg,
+    },
+    /// Server to client, a message forwarded from another peer.
+    Forwarded {
+        /// The id of the peer that sent the message.
+        from: String,
+        /// The message.
+        msg: SignalMsg,
+    },
+    /// Server to client, a message couldn't be forwarded
+    /// as no peer is registered with that id.
+    Unreachable {
+        /// The id the message was for.
+        to: String,
+        /// The message.
+        msg: SignalMsg,
+    },
+}
+
+impl SignalWire {
+    pub(crate) fn encode(&self) -> KitsuneResult<Message> {
+        Ok(Message::Text(
+            serde_json::to_string(self).map_err(KitsuneError::other)?,
+        ))
+    }
+
+    pub(crate) fn decode(msg: &Message) -> KitsuneResult<Option<Self>> {
+        match msg {
+            Message::Text(text) => Ok(Some(
+                serde_json::from_str(text).map_err(KitsuneError::other)?,
+            )),
+            _ => Ok(None),
+        }
+    }
+}
+
+type Peers =
+    Arc<parking_lot::Mutex<HashMap<String, tokio::sync::mpsc::UnboundedSender<SignalWire>>>>;
+
+/// Run a signal server listening on `addr`.
+/// Returns the bound address, and the task serving connections.
+pub async fn signal_server(
+    addr: SocketAddr,
+) -> KitsuneResult<(SocketAddr, tokio::task::JoinHandle<()>)> {
+    let listener = tokio::net::TcpListener::bind(addr)
+        .await
+        .map_err(KitsuneError::other)?;
+    let addr = listener.local_addr().map_err(KitsuneError::other)?;
+    tracing::info!(%addr, "bound signal server (webrtc)");
+
+    let peers = Peers::default();
+    let task = tokio::task::spawn(async move {
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    tokio::task::spawn(serve_peer(stream, peers.clone()));
+                }
+                Err(err) => {
+                    tracing::warn!(?err, "signal server failed to accept (webrtc)");
+                }
+            }
+        }
+    });
+    Ok((addr, task))
+}
+
+async fn serve_peer(stream: tokio::net::TcpStream, peers: Peers) {
+    let ws = match tokio_tungstenite::accept_async(stream).await {
+        Ok(ws) => ws,
+        Err(err) => {
+            tracing::debug!(?err, "signal server websocket handshake failed (webrtc)");
+            return;
+        }
+    };
+    let (mut sink, mut stream) = ws.split();
+
+    let (send, mut recv) = tokio::sync::mpsc::unbounded_channel::<SignalWire>();
+    let writer = tokio::task::spawn(async move {
+        while let Some(wire) = recv.recv().await {
+            let msg = match wire.encode() {
+                Ok(msg) => msg,
+                Err(_) => continue,
+            };
+            if sink.send(msg).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut registered = None;
+    while let Some(Ok(msg)) = stream.next().await {
+        let wire = match SignalWire::decode(&msg) {
+            Ok(Some(wire)) => wire,
+            Ok(None) => continue,
+            Err(err) => {
+                tracing::debug!(?err, "signal server got an invalid message (webrtc)");
+                continue;
+            }
+        };
+        match wire {
+            SignalWire::Register { id } => {
+                peers.lock().insert(id.clone(), send.clone());
+                registered = Some(id);
+            }
+            SignalWire::Forward { to, msg } => {
+                let from = match &registered {
+                    Some(from) => from.clone(),
+                    None => continue,
+                };
+                let peer = peers.lock().get(&to).cloned();
+                let forwarded = SignalWire::Forwarded { from, msg };
+                let undelivered = match peer {
+                    Some(peer) => peer.send(forwarded).err().map(|e| e.0),
+                    None => Some(forwarded),
+                };
+                if let Some(SignalWire::Forwarded { msg, .. }) = undelivered {
+                    let _ = send.send(SignalWire::Unreachable { to, msg });
+                }
+            }
+            _ => (),
+        }
+    }
+
+    // Only unregister if another connection hasn't since registered the id.
+    if let Some(id) = registered {
+        let mut peers = peers.lock();
+        if peers.get(&id).map(|p| p.same_channel(&send)) == Some(true) {
+            peers.remove(&id);
+        }
+    }
+    writer.abort();
+}
@@ -0,0 +1,989 @@
+#![allow(clippy::new_ret_no_self)]
+//! kitsune tx2 webrtc transport backend
+
+use crate::signal::*;
+use futures::future::{BoxFuture, FutureExt};
+use futures::sink::SinkExt;
+use futures::stream::{BoxStream, StreamExt};
+use kitsune_p2p_types::config::*;
+use kitsune_p2p_types::dependencies::lair_keystore_api::dependencies::sodoken;
+use kitsune_p2p_types::dependencies::{ghost_actor::dependencies::tracing, serde_json};
+use kitsune_p2p_types::tls::*;
+use kitsune_p2p_types::tx2::tx2_adapter::*;
+use kitsune_p2p_types::tx2::tx2_utils::*;
+use kitsune_p2p_types::tx2::*;
+use kitsune_p2p_types::*;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::watch;
+use tokio_util::compat::{TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt};
+use webrtc::api::setting_engine::SettingEngine;
+use webrtc::api::{APIBuilder, API};
+use webrtc::data::data_channel::{DataChannel, PollDataChannel};
+use webrtc::data_channel::RTCDataChannel;
+use webrtc::ice_transport::ice_server::RTCIceServer;
+use webrtc::peer_connection::configuration::RTCConfiguration;
+use webrtc::peer_connection::peer_connection_state::RTCPeerConnectionState;
+use webrtc::peer_connection::sdp::session_description::RTCSessionDescription;
+use webrtc::peer_connection::RTCPeerConnection;
+
+/// A STUN or TURN server used to traverse NATs.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, PartialEq)]
+pub struct IceServerConfig {
+    /// The server's urls, e.g. `stun:stun.example.com:3478`
+    pub urls: Vec<String>,
+
+    /// The username, for TURN servers
+    #[serde(default)]
+    pub username: Option<String>,
+
+    /// The credential, for TURN servers
+    #[serde(default)]
+    pub credential: Option<String>,
+}
+
+/// Configuration for WebRtcBackendAdapt
+#[non_exhaustive]
+#[derive(Default)]
+pub struct WebRtcConfig {
+    /// Tls config
+    /// Default: None = ephemeral.
+    pub tls: Option<TlsConfig>,
+
+    /// Tuning Params
+    /// Default: None = default.
+    pub tuning_params: Option<KitsuneP2pTuningParams>,
+
+    /// STUN / TURN servers used to traverse NATs.
+    /// Default: empty = only host candidates, direct connections
+    /// are only possible on the same network.
+    pub ice_servers: Vec<IceServerConfig>,
+}
+
+impl WebRtcConfig {
+    /// into inner contents with default application
+    pub async fn split(
+        self,
+    ) -> KitsuneResult<(TlsConfig, KitsuneP2pTuningParams, Vec<IceServerConfig>)> {
+        let WebRtcConfig {
+            tls,
+            tuning_params,
+            ice_servers,
+        } = self;
+
+        let tls = match tls {
+            None => TlsConfig::new_ephemeral().await?,
+            Some(tls) => tls,
+        };
+
+        let tuning_params = tuning_params.unwrap_or_default();
+
+        Ok((tls, tuning_params, ice_servers))
+    }
+}
+
+/// WebRTC endpoint bind adapter for kitsune tx2
+pub async fn tx2_webrtc_adapter(config: WebRtcConfig) -> KitsuneResult<AdapterFactory> {
+    WebRtcBackendAdapt::new(config).await
+}
+
+// -- private -- //
+
+/// Label of the data channel created with every offer, so that the
+/// offer negotiates a data transport. It is never used.
+const INIT_LABEL: &str = "kitsune-init";
+
+/// Label of the data channels kitsune channels are sent over.
+const CHAN_LABEL: &str = "kitsune";
+
+/// Data channels are message based, and messages much over 16KiB aren't
+/// reliably delivered by every implementation, so writes are split at that.
+const MAX_MESSAGE_SIZE: usize = 16 * 1024;
+
+struct ChunkedWrite<W>(W);
+
+impl<W: futures::io::AsyncWrite + Unpin> futures::io::AsyncWrite for ChunkedWrite<W> {
+    fn poll_write(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &[u8],
+    ) -> std::task::Poll<std::io::Result<usize>> {
+        let len = buf.len().min(MAX_MESSAGE_SIZE);
+        std::pin::Pin::new(&mut self.0).poll_write(cx, &buf[..len])
+    }
+
+    fn poll_flush(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        std::pin::Pin::new(&mut self.0).poll_flush(cx)
+    }
+
+    fn poll_close(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        std::pin::Pin::new(&mut self.0).poll_close(cx)
+    }
+}
+
+fn poll_data_channel(raw: Arc<DataChannel>) -> PollDataChannel {
+    let mut chan = PollDataChannel::new(raw);
+    chan.set_read_buf_capacity(MAX_MESSAGE_SIZE);
+    chan
+}
+
+/// Wait for a data channel to open, then detach it so it can be
+/// read and written as a stream.
+async fn detach_on_open(dc: Arc<RTCDataChannel>) -> KitsuneResult<Arc<DataChannel>> {
+    let (s_open, r_open) = tokio::sync::oneshot::channel();
+    let s_open = parking_lot::Mutex::new(Some(s_open));
+    dc.on_open(Box::new(move || {
+        if let Some(s_open) = s_open.lock().take() {
+            let _ = s_open.send(());
+        }
+        Box::pin(async move {})
+    }))
+    .await;
+    r_open
+        .await
+        .map_err(|_| KitsuneError::from("data channel closed before opening"))?;
+    dc.detach().await.map_err(KitsuneError::other)
+}
+
+fn is_down(state: RTCPeerConnectionState) -> bool {
+    matches!(
+        state,
+        RTCPeerConnectionState::Failed | RTCPeerConnectionState::Closed
+    )
+}
+
+/// Prefixed to DTLS fingerprints before signing them, so that the
+/// signatures can't be passed off as anything else signed by a tls cert key.
+const PROOF_CONTEXT: &[u8] = b"kitsune-webrtc-dtls-fingerprint:";
+
+/// The message signed to prove the DTLS certificate
+/// fingerprinted in a session description.
+fn proof_msg(sdp: &str) -> KitsuneResult<Vec<u8>> {
+    let mut fingerprints = sdp
+        .lines()
+        .filter_map(|l| l.trim().strip_prefix("a=fingerprint:"));
+    let fingerprint = fingerprints
+        .next()
+        .ok_or_else(|| KitsuneError::from("session description has no DTLS fingerprint"))?;
+    if fingerprints.any(|f| f != fingerprint) {
+        return Err("session description has more than one DTLS fingerprint".into());
+    }
+    let mut msg = PROOF_CONTEXT.to_vec();
+    msg.extend_from_slice(fingerprint.as_bytes());
+    Ok(msg)
+}
+
+/// Signs the DTLS fingerprints of our session descriptions with our tls cert
+/// key, so that peers don't have to trust the id the signal server relays.
+struct DtlsSigner {
+    cert: String,
+    key: ring::signature::EcdsaKeyPair,
+    rng: ring::rand::SystemRandom,
+}
+
+impl DtlsSigner {
+    fn new(tls: &TlsConfig) -> KitsuneResult<Self> {
+        let key = ring::signature::EcdsaKeyPair::from_pkcs8(
+            &ring::signature::ECDSA_P256_SHA256_ASN1_SIGNING,
+            &tls.cert_priv_key.read_lock(),
+        )
+        .map_err(|_| KitsuneError::from("tls cert key is not an ECDSA P-256 key"))?;
+        Ok(Self {
+            cert: base64::encode_config(&tls.cert, base64::URL_SAFE_NO_PAD),
+            key,
+            rng: ring::rand::SystemRandom::new(),
+        })
+    }
+
+    fn sign(&self, desc: &RTCSessionDescription) -> KitsuneResult<DtlsProof> {
+        let sig = self
+            .key
+            .sign(&self.rng, &proof_msg(&desc.sdp)?)
+            .map_err(|_| KitsuneError::from("failed to sign DTLS fingerprint"))?;
+        Ok(DtlsProof {
+            cert: self.cert.clone(),
+            sig: base64::encode_config(sig.as_ref(), base64::URL_SAFE_NO_PAD),
+        })
+    }
+}
+
+/// Check that the DTLS fingerprint in a session description was signed with
+/// the key of the tls cert `peer_cert` is the digest of. The DTLS handshake
+/// then checks that the peer holds the fingerprinted DTLS certificate.
+async fn verify_dtls_proof(
+    peer_cert: &Tx2Cert,
+    desc: &RTCSessionDescription,
+    proof: &DtlsProof,
+) -> KitsuneResult<()> {
+    let decode = |s: &str| {
+        base64::decode_config(s, base64::URL_SAFE_NO_PAD)
+            .map_err(|_| KitsuneError::from("invalid DTLS proof encoding"))
+    };
+    let cert = decode(&proof.cert)?;
+    let sig = decode(&proof.sig)?;
+
+    let digest = sodoken::BufWriteSized::<32>::new_no_lock();
+    sodoken::hash::blake2b::hash(digest.clone(), cert.clone())
+        .await
+        .map_err(KitsuneError::other)?;
+    if &*digest.read_lock() != AsRef::<[u8]>::as_ref(peer_cert) {
+        return Err(format!("DTLS proof is for a tls cert other than {:?}", peer_cert).into());
+    }
+
+    let msg = proof_msg(&desc.sdp)?;
+    webpki::EndEntityCert::try_from(cert.as_slice())
+        .and_then(|cert| cert.verify_signature(&webpki::ECDSA_P256_SHA256, &msg, &sig))
+        .map_err(|err| format!("invalid DTLS proof from {:?}: {:?}", peer_cert, err).into())
+}
+
+struct WebRtcInChanRecvAdapt(BoxStream<'static, InChanFut>);
+
+impl WebRtcInChanRecvAdapt {
+    pub fn new(
+        recv: tokio::sync::mpsc::UnboundedReceiver<InChanFut>,
+        state: watch::Receiver<RTCPeerConnectionState>,
+    ) -> Self {
+        Self(
+            futures::stream::unfold((recv, state), move |(mut recv, mut state)| async move {
+                loop {
+                    if is_down(*state.borrow()) {
+                        return None;
+                    }
+                    tokio::select! {
+                        in_ = recv.recv() => return in_.map(|in_| (in_, (recv, state))),
+                        r = state.changed() => if r.is_err() {
+                            return None;
+                        },
+                    }
+                }
+            })
+            .boxed(),
+        )
+    }
+}
+
+impl futures::stream::Stream for WebRtcInChanRecvAdapt {
+    type Item = InChanFut;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        let inner = &mut self.0;
+        tokio::pin!(inner);
+        futures::stream::Stream::poll_next(inner, cx)
+    }
+}
+
+impl InChanRecvAdapt for WebRtcInChanRecvAdapt {}
+
+/// Set the local description and wait for ice gathering to finish,
+/// so that the description sent to the peer has all our candidates.
+async fn describe(
+    pc: &RTCPeerConnection,
+    desc: RTCSessionDescription,
+) -> KitsuneResult<RTCSessionDescription> {
+    let mut gathered = pc.gathering_complete_promise().await;
+    pc.set_local_description(desc)
+        .await
+        .map_err(KitsuneError::other)?;
+    let _ = gathered.recv().await;
+    pc.local_description()
+        .await
+        .ok_or_else(|| "no local session description".into())
+}
+
+/// A peer connection, with handlers set up for incoming channels
+/// and state changes, which has not yet been negotiated.
+struct PeerCon {
+    pc: Arc<RTCPeerConnection>,
+    state: watch::Receiver<RTCPeerConnectionState>,
+    chan_recv: WebRtcInChanRecvAdapt,
+}
+
+impl PeerCon {
+    async fn new(api: &API, ice_servers: &[RTCIceServer]) -> KitsuneResult<Self> {
+        let config = RTCConfiguration {
+            ice_servers: ice_servers.to_vec(),
+            ..Default::default()
+        };
+        let pc = Arc::new(
+            api.new_peer_connection(config)
+                .await
+                .map_err(KitsuneError::other)?,
+        );
+
+        let (s_state, state) = watch::channel(RTCPeerConnectionState::New);
+        pc.on_peer_connection_state_change(Box::new(move |s| {
+            let _ = s_state.send(s);
+            Box::pin(async move {})
+        }))
+        .await;
+
+        let (s_chan, r_chan) = tokio::sync::mpsc::unbounded_channel();
+        pc.on_data_channel(Box::new(move |dc: Arc<RTCDataChannel>| {
+            if dc.label() == CHAN_LABEL {
+                let in_: InChanFut = async move {
+                    let raw = detach_on_open(dc).await?;
+                    let in_: InChan =
+                        Box::new(FramedReader::new(Box::new(poll_data_channel(raw).compat())));
+                    Ok(in_)
+                }
+                .boxed();
+                let _ = s_chan.send(in_);
+            }
+            Box::pin(async move {})
+        }))
+        .await;
+
+        let chan_recv = WebRtcInChanRecvAdapt::new(r_chan, state.clone());
+        Ok(Self {
+            pc,
+            state,
+            chan_recv,
+        })
+    }
+
+    /// Wait for the connection to be established
+    /// and convert it into a kitsune connection.
+    async fn connected(
+        mut self,
+        peer_cert: Tx2Cert,
+        peer_url: TxUrl,
+        dir: Tx2ConDir,
+        local_cert: Tx2Cert,
+    ) -> KitsuneResult<Con> {
+        loop {
+            let state = *self.state.borrow();
+            if state == RTCPeerConnectionState::Connected {
+                break;
+            }
+            if is_down(state) {
+                return Err(format!("peer connection {} (webrtc)", state).into());
+            }
+            if self.state.changed().await.is_err() {
+                return Err(KitsuneErrorKind::Closed.into());
+            }
+        }
+
+        match dir {
+            Tx2ConDir::Outgoing => {
+                tracing::info!(?local_cert, ?peer_cert, %peer_url, "established outgoing connection (webrtc)");
+            }
+            Tx2ConDir::Incoming => {
+                tracing::info!(?local_cert, ?peer_cert, %peer_url, "established incoming connection (webrtc)");
+            }
+        }
+
+        let con: Arc<dyn ConAdapt> = Arc::new(WebRtcConAdapt::new(
+            self.pc, self.state, peer_cert, peer_url, dir,
+        ));
+        let chan_recv: Box<dyn InChanRecvAdapt> = Box::new(self.chan_recv);
+        Ok((con, chan_recv))
+    }
+
+    async fn close(self) {
+        let _ = self.pc.close().await;
+    }
+}
+
+struct WebRtcConAdaptInner {
+    peer_cert: Tx2Cert,
+    pc: Arc<RTCPeerConnection>,
+    state: watch::Receiver<RTCPeerConnectionState>,
+}
+
+struct WebRtcConAdapt(Share<WebRtcConAdaptInner>, Uniq, Tx2Cert, Tx2ConDir, TxUrl);
+
+impl WebRtcConAdapt {
+    pub fn new(
+        pc: Arc<RTCPeerConnection>,
+        state: watch::Receiver<RTCPeerConnectionState>,
+        peer_cert: Tx2Cert,
+        peer_url: TxUrl,
+        dir: Tx2ConDir,
+    ) -> Self {
+        Self(
+            Share::new(WebRtcConAdaptInner {
+                peer_cert: peer_cert.clone(),
+                pc,
+                state,
+            }),
+            Uniq::default(),
+            peer_cert,
+            dir,
+            peer_url,
+        )
+    }
+}
+
+impl ConAdapt for WebRtcConAdapt {
+    fn uniq(&self) -> Uniq {
+        self.1
+    }
+
+    fn dir(&self) -> Tx2ConDir {
+        self.3
+    }
+
+    fn peer_addr(&self) -> KitsuneResult<TxUrl> {
+        Ok(self.4.clone())
+    }
+
+    fn peer_cert(&self) -> Tx2Cert {
+        self.2.clone()
+    }
+
+    fn out_chan(&self, timeout: KitsuneTimeout) -> OutChanFut {
+        let maybe_pc = self.0.share_ref(|i| Ok(i.pc.clone()));
+        timeout
+            .mix(async move {
+                let dc = maybe_pc?
+                    .create_data_channel(CHAN_LABEL, None)
+                    .await
+                    .map_err(KitsuneError::other)?;
+                let raw = detach_on_open(dc).await?;
+                let out: OutChan = Box::new(FramedWriter::new(Box::new(ChunkedWrite(
+                    poll_data_channel(raw).compat_write(),
+                ))));
+                Ok(out)
+            })
+            .boxed()
+    }
+
+    fn is_closed(&self) -> bool {
+        self.0
+            .share_ref(|i| Ok(is_down(*i.state.borrow())))
+            .unwrap_or(true)
+    }
+
+    fn close(&self, code: u32, reason: &str) -> BoxFuture<'static, ()> {
+        let maybe_pc = self.0.share_mut(|i, c| {
+            tracing::info!(
+                peer_cert=?i.peer_cert,
+                %code,
+                %reason,
+                "close connection (webrtc)",
+            );
+            *c = true;
+            Ok(i.pc.clone())
+        });
+        async move {
+            if let Ok(pc) = maybe_pc {
+                let _ = pc.close().await;
+            }
+        }
+        .boxed()
+    }
+}
+
+/// State shared by an endpoint and the task reading from its signal server.
+#[derive(Clone)]
+struct SignalCtx {
+    api: Arc<API>,
+    ice_servers: Vec<RTCIceServer>,
+    local_cert: Tx2Cert,
+    local_url: TxUrl,
+    signer: Arc<DtlsSigner>,
+    signal: tokio::sync::mpsc::UnboundedSender<SignalWire>,
+    /// Outgoing connections waiting on an answer, by connection id.
+    pending: Arc<parking_lot::Mutex<HashMap<String, PendingAnswer>>>,
+    /// Closes the signal server connection.
+    close_signal: Arc<tokio::sync::Notify>,
+}
+
+type PendingAnswer =
+    tokio::sync::oneshot::Sender<KitsuneResult<(RTCSessionDescription, DtlsProof)>>;
+
+/// Removes an outgoing connection from the pending map if
+/// it times out or fails before being answered.
+struct PendingGuard(
+    Arc<parking_lot::Mutex<HashMap<String, PendingAnswer>>>,
+    String,
+);
+
+impl Drop for PendingGuard {
+    fn drop(&mut self) {
+        self.0.lock().remove(&self.1);
+    }
+}
+
+fn accepting(
+    ctx: SignalCtx,
+    from: String,
+    con_id: String,
+    offer: RTCSessionDescription,
+    proof: DtlsProof,
+) -> ConFut {
+    async move {
+        let peer_cert = crate::peer_cert(&from)?;
+        verify_dtls_proof(&peer_cert, &offer, &proof).await?;
+        let peer_url = crate::peer_url(ctx.local_url.as_url2(), &from);
+        let con = PeerCon::new(&ctx.api, &ctx.ice_servers).await?;
+        let pc = con.pc.clone();
+        let answer = async {
+            pc.set_remote_description(offer)
+                .await
+                .map_err(KitsuneError::other)?;
+            let answer = pc.create_answer(None).await.map_err(KitsuneError::other)?;
+            let answer = describe(&pc, answer).await?;
+            let proof = ctx.signer.sign(&answer)?;
+            KitsuneResult::Ok((answer, proof))
+        }
+        .await;
+        let (answer, proof) = match answer {
+            Ok(answer) => answer,
+            Err(err) => {
+                con.close().await;
+                return Err(err);
+            }
+        };
+        ctx.signal
+            .send(SignalWire::Forward {
+                to: from,
+                msg: SignalMsg::Answer {
+                    con_id,
+                    sdp: answer,
+                    proof,
+                },
+            })
+            .map_err(|_| KitsuneError::from(KitsuneErrorKind::Closed))?;
+        con.connected(peer_cert, peer_url, Tx2ConDir::Incoming, ctx.local_cert)
+            .await
+    }
+    .boxed()
+}
+
+struct WebRtcConRecvAdapt(BoxStream<'static, ConFut>);
+
+impl WebRtcConRecvAdapt {
+    pub fn new(
+        recv: tokio::sync::mpsc::UnboundedReceiver<ConFut>,
+        ep: Arc<dyn EndpointAdapt>,
+    ) -> Self {
+        struct OnDrop(Arc<dyn EndpointAdapt>);
+
+        impl Drop for OnDrop {
+            fn drop(&mut self) {
+                let f = self.0.close(500, "listener closed");
+                tokio::task::spawn(async move {
+                    f.await;
+                });
+            }
+        }
+
+        let on_drop = OnDrop(ep);
+
+        Self(
+            futures::stream::unfold((recv, on_drop), move |(mut recv, on_drop)| async move {
+                recv.recv().await.map(|con| (con, (recv, on_drop)))
+            })
+            .boxed(),
+        )
+    }
+}
+
+impl futures::stream::Stream for WebRtcConRecvAdapt {
+    type Item = ConFut;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        let inner = &mut self.0;
+        tokio::pin!(inner);
+        futures::stream::Stream::poll_next(inner, cx)
+    }
+}
+
+impl ConRecvAdapt for WebRtcConRecvAdapt {}
+
+struct WebRtcEndpointAdapt(Share<SignalCtx>, Uniq, Tx2Cert);
+
+impl WebRtcEndpointAdapt {
+    pub fn new(ctx: SignalCtx) -> Self {
+        let local_cert = ctx.local_cert.clone();
+        Self(Share::new(ctx), Uniq::default(), local_cert)
+    }
+}
+
+impl EndpointAdapt for WebRtcEndpointAdapt {
+    fn debug(&self) -> serde_json::Value {
+        match self.local_addr() {
+            Ok(addr) => serde_json::json!({
+                "type": "tx2_webrtc",
+                "state": "open",
+                "addr": addr,
+            }),
+            Err(_) => serde_json::json!({
+                "type": "tx2_webrtc",
+                "state": "closed",
+            }),
+        }
+    }
+
+    fn uniq(&self) -> Uniq {
+        self.1
+    }
+
+    fn local_addr(&self) -> KitsuneResult<TxUrl> {
+        self.0.share_ref(|i| Ok(i.local_url.clone()))
+    }
+
+    fn local_cert(&self) -> Tx2Cert {
+        self.2.clone()
+    }
+
+    fn connect(&self, url: TxUrl, timeout: KitsuneTimeout) -> ConFut {
+        let maybe_ctx = self.0.share_ref(|i| Ok(i.clone()));
+        timeout
+            .mix(async move {
+                let ctx = maybe_ctx?;
+                let peer_id = crate::peer_id(url.as_url2())?;
+                let peer_cert = crate::peer_cert(&peer_id)?;
+                let con = PeerCon::new(&ctx.api, &ctx.ice_servers).await?;
+                let pc = con.pc.clone();
+
+                let con_id = nanoid::nanoid!();
+                let (s_answer, r_answer) = tokio::sync::oneshot::channel();
+                ctx.pending.lock().insert(con_id.clone(), s_answer);
+                let _guard = PendingGuard(ctx.pending.clone(), con_id.clone());
+
+                let answer = async {
+                    pc.create_data_channel(INIT_LABEL, None)
+                        .await
+                        .map_err(KitsuneError::other)?;
+                    let offer = pc.create_offer(None).await.map_err(KitsuneError::other)?;
+                    let offer = describe(&pc, offer).await?;
+                    let proof = ctx.signer.sign(&offer)?;
+                    ctx.signal
+                        .send(SignalWire::Forward {
+                            to: peer_id,
+                            msg: SignalMsg::Offer {
+                                con_id,
+                                sdp: offer,
+                                proof,
+                            },
+                        })
+                        .map_err(|_| KitsuneError::from(KitsuneErrorKind::Closed))?;
+                    let (answer, proof) = r_answer
+                        .await
+                        .map_err(|_| KitsuneError::from(KitsuneErrorKind::Closed))??;
+                    verify_dtls_proof(&peer_cert, &answer, &proof).await?;
+                    pc.set_remote_description(answer)
+                        .await
+                        .map_err(KitsuneError::other)
+                }
+                .await;
+                let res = match answer {
+                    Ok(()) => {
+                        con.connected(peer_cert, url, Tx2ConDir::Outgoing, ctx.local_cert)
+                            .await
+                    }
+                    Err(err) => {
+                        con.close().await;
+                        Err(err)
+                    }
+                };
+                if let Err(err) = &res {
+                    tracing::warn!(?err, "failed to establish outgoing connection (webrtc)");
+                }
+                res
+            })
+            .boxed()
+    }
+
+    fn is_closed(&self) -> bool {
+        self.0.is_closed()
+    }
+
+    fn close(&self, code: u32, reason: &str) -> BoxFuture<'static, ()> {
+        let _ = self.0.share_mut(|i, c| {
+            tracing::warn!(
+                local_cert=?i.local_cert,
+                %code,
+                %reason,
+                "CLOSING ENDPOINT"
+            );
+            i.close_signal.notify_one();
+            *c = true;
+            Ok(())
+        });
+        async move {}.boxed()
+    }
+}
+
+/// WebRTC endpoint backend bind adapter for kitsune tx2
+pub struct WebRtcBackendAdapt {
+    local_cert: Tx2Cert,
+    signer: Arc<DtlsSigner>,
+    api: Arc<API>,
+    ice_servers: Vec<RTCIceServer>,
+}
+
+impl WebRtcBackendAdapt {
+    /// Construct a new webrtc tx2 backend bind adapter
+    pub async fn new(config: WebRtcConfig) -> KitsuneResult<AdapterFactory> {
+        let (tls, _tuning_params, ice_servers) = config.split().await?;
+
+        let local_cert = tls.cert_digest.clone().into();
+        let signer = Arc::new(DtlsSigner::new(&tls)?);
+
+        // Detached data channels can be read and written as streams,
+        // which is what kitsune channels are framed over.
+        let mut settings = SettingEngine::default();
+        settings.detach_data_channels();
+        let api = Arc::new(APIBuilder::new().with_setting_engine(settings).build());
+
+        let ice_servers = ice_servers
+            .into_iter()
+            .map(|s| RTCIceServer {
+                urls: s.urls,
+                username: s.username.unwrap_or_default(),
+                credential: s.credential.unwrap_or_default(),
+                ..Default::default()
+            })
+            .collect();
+
+        let out: AdapterFactory = Arc::new(Self {
+            local_cert,
+            signer,
+            api,
+            ice_servers,
+        });
+
+        Ok(out)
+    }
+}
+
+impl BindAdapt for WebRtcBackendAdapt {
+    fn bind(&self, url: TxUrl, timeout: KitsuneTimeout) -> EndpointFut {
+        let local_cert = self.local_cert.clone();
+        let signer = self.signer.clone();
+        let api = self.api.clone();
+        let ice_servers = self.ice_servers.clone();
+        timeout
+            .mix(async move {
+                let signal_url = crate::signal_url(url.as_url2())?;
+                let (ws, _) = tokio_tungstenite::connect_async(signal_url.as_str())
+                    .await
+                    .map_err(KitsuneError::other)?;
+                let (mut sink, mut stream) = ws.split();
+
+                let id = local_cert.as_str().to_string();
+                sink.send(SignalWire::Register { id: id.clone() }.encode()?)
+                    .await
+                    .map_err(KitsuneError::other)?;
+
+                let (signal, mut r_signal) = tokio::sync::mpsc::unbounded_channel::<SignalWire>();
+                let close_signal = Arc::new(tokio::sync::Notify::new());
+                let closed = close_signal.clone();
+                tokio::task::spawn(async move {
+                    loop {
+                        let wire = tokio::select! {
+                            wire = r_signal.recv() => wire,
+                            _ = closed.notified() => None,
+                        };
+                        let wire = match wire {
+                            Some(wire) => wire,
+                            None => break,
+                        };
+                        let msg = match wire.encode() {
+                            Ok(msg) => msg,
+                            Err(_) => continue,
+                        };
+                        if sink.send(msg).await.is_err() {
+                            break;
+                        }
+                    }
+                    let _ = sink.close().await;
+                });
+
+                let ctx = SignalCtx {
+                    api,
+                    ice_servers,
+                    local_cert: local_cert.clone(),
+                    local_url: crate::peer_url(url.as_url2(), &id),
+                    signer,
+                    signal,
+                    pending: Default::default(),
+                    close_signal,
+                };
+
+                let ep = WebRtcEndpointAdapt::new(ctx.clone());
+                let ep_share = ep.0.clone();
+                let ep: Arc<dyn EndpointAdapt> = Arc::new(ep);
+
+                let (s_con, r_con) = tokio::sync::mpsc::unbounded_channel();
+                tokio::task::spawn(async move {
+                    while let Some(Ok(msg)) = stream.next().await {
+                        let wire = match SignalWire::decode(&msg) {
+                            Ok(Some(wire)) => wire,
+                            Ok(None) => continue,
+                            Err(err) => {
+                                tracing::warn!(?err, "invalid signal message (webrtc)");
+                                continue;
+                            }
+                        };
+                        match wire {
+                            SignalWire::Forwarded {
+                                from,
+                                msg: SignalMsg::Offer { con_id, sdp, proof },
+                            } => {
+                                if s_con
+                                    .send(accepting(ctx.clone(), from, con_id, sdp, proof))
+                                    .is_err()
+                                {
+                                    break;
+                                }
+                            }
+                            SignalWire::Forwarded {
+                                msg: SignalMsg::Answer { con_id, sdp, proof },
+                                ..
+                            } => {
+                                if let Some(s_answer) = ctx.pending.lock().remove(&con_id) {
+                                    let _ = s_answer.send(Ok((sdp, proof)));
+                                }
+                            }
+                            SignalWire::Unreachable {
+                                to,
+                                msg: SignalMsg::Offer { con_id, .. },
+                            } => {
+                                if let Some(s_answer) = ctx.pending.lock().remove(&con_id) {
+                                    let _ = s_answer.send(Err(format!(
+                                        "peer {} is not registered with the signal server",
+                                        to
+                                    )
+                                    .into()));
+                                }
+                            }
+                            _ => (),
+                        }
+                    }
+                    tracing::warn!("signal server connection closed (webrtc)");
+                    ep_share.close();
+                });
+
+                let con_recv: Box<dyn ConRecvAdapt> =
+                    Box::new(WebRtcConRecvAdapt::new(r_con, ep.clone()));
+
+                let url = ep.local_addr()?;
+
+                tracing::info!(?local_cert, %url, "bound local endpoint (webrtc)");
+
+                Ok((ep, con_recv))
+            })
+            .boxed()
+    }
+
+    fn local_cert(&self) -> Tx2Cert {
+        self.local_cert.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kitsune_p2p_types::dependencies::url2::url2;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_webrtc_tx2() {
+        kitsune_p2p_types::dependencies::observability::test_run().ok();
+
+        let t = KitsuneTimeout::from_millis(5000);
+
+        let (addr, _signal) = crate::signal::signal_server(([127, 0, 0, 1], 0).into())
+            .await
+            .unwrap();
+        let bind_to =
+            crate::bind_url_from_signal_url(&url2!("ws://127.0.0.1:{}", addr.port())).unwrap();
+
+        let (s_done, r_done) = tokio::sync::oneshot::channel();
+
+        let factory = WebRtcBackendAdapt::new(WebRtcConfig::default())
+            .await
+            .unwrap();
+        let (ep1, _con_recv1) = factory.bind(bind_to.clone(), t).await.unwrap();
+
+        let factory = WebRtcBackendAdapt::new(WebRtcConfig::default())
+            .await
+            .unwrap();
+        let (ep2, mut con_recv2) = factory.bind(bind_to, t).await.unwrap();
+
+        let addr2 = ep2.local_addr().unwrap();
+
+        let rt = kitsune_p2p_types::metrics::metric_task(async move {
+            if let Some(mc) = con_recv2.next().await {
+                let (_con, mut recv) = mc.await.unwrap();
+                if let Some(mc) = recv.next().await {
+                    let mut c = mc.await.unwrap();
+                    let t = KitsuneTimeout::from_millis(5000);
+                    let (_, data) = c.read(t).await.unwrap();
+                    s_done.send(data.len()).unwrap();
+                }
+            }
+            KitsuneResult::Ok(())
+        });
+
+        let (c, _recv) = ep1.connect(addr2, t).await.unwrap();
+        assert_eq!(ep2.local_cert(), c.peer_cert());
+        let mut c = c.out_chan(t).await.unwrap();
+
+        // Larger than a single data channel message.
+        let mut data = PoolBuf::new();
+        data.extend_from_slice(&[0xdb; MAX_MESSAGE_SIZE * 3]);
+        c.write(0.into(), data, t).await.unwrap();
+
+        assert_eq!("open", ep1.debug()["state"]);
+        assert_eq!(MAX_MESSAGE_SIZE * 3, r_done.await.unwrap());
+
+        ep1.close(0, "").await;
+        ep2.close(0, "").await;
+
+        rt.await.unwrap().unwrap();
+    }
+
+    fn test_desc(fingerprint: &str) -> RTCSessionDescription {
+        serde_json::from_value(serde_json::json!({
+            "type": "offer",
+            "sdp": format!("v=0\r\na=fingerprint:sha-256 {}\r\n", fingerprint),
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_webrtc_dtls_proof() {
+        let tls = TlsConfig::new_ephemeral().await.unwrap();
+        let cert: Tx2Cert = tls.cert_digest.clone().into();
+        let signer = DtlsSigner::new(&tls).unwrap();
+        let desc = test_desc("AB:CD");
+        let proof = signer.sign(&desc).unwrap();
+
+        verify_dtls_proof(&cert, &desc, &proof).await.unwrap();
+
+        // the proof doesn't cover another DTLS certificate
+        verify_dtls_proof(&cert, &test_desc("EF:01"), &proof)
+            .await
+            .unwrap_err();
+
+        // nor can another peer pass its own proof off as ours
+        let other_tls = TlsConfig::new_ephemeral().await.unwrap();
+        let other_proof = DtlsSigner::new(&other_tls).unwrap().sign(&desc).unwrap();
+        verify_dtls_proof(&cert, &desc, &other_proof)
+            .await
+            .unwrap_err();
+
+        // or sign with its own key under our cert
+        let forged = DtlsProof {
+            cert: proof.cert.clone(),
+            sig: other_proof.sig,
+        };
+        verify_dtls_proof(&cert, &desc, &forged).await.unwrap_err();
+    }
+}
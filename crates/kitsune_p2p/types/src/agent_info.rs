@@ -20,6 +20,11 @@ pub mod agent_info_helper {
     #[derive(Debug, serde::Serialize, serde::Deserialize)]
     pub struct AgentMetaInfoEncode {
         pub dht_storage_arc_half_length: u32,
+
+        /// Missing from the meta info of older nodes, which can't
+        /// decompress messages.
+        #[serde(default)]
+        pub accepts_compression: bool,
     }
 
     #[allow(missing_docs)]
@@ -81,6 +86,9 @@ pub struct AgentInfoInner {
     /// intuitive absolute value.
     pub expires_at_ms: u64,
 
+    /// Whether the agent's node can decompress compressed wire messages.
+    pub accepts_compression: bool,
+
     /// Raw bytes of agent info signature as kitsune signature.
     pub signature: Arc<KitsuneSignature>,
 
@@ -97,6 +105,7 @@ impl std::fmt::Debug for AgentInfoInner {
             .field("url_list", &self.url_list)
             .field("signed_at_ms", &self.signed_at_ms)
             .field("expires_at_ms", &self.expires_at_ms)
+            .field("accepts_compression", &self.accepts_compression)
             .finish()
     }
 }
@@ -183,6 +192,7 @@ impl<'de> serde::Deserialize<'de> for AgentInfoSigned {
             url_list: urls,
             signed_at_ms,
             expires_at_ms: signed_at_ms + expires_after_ms,
+            accepts_compression: meta.accepts_compression,
             signature,
             encoded_bytes: agent_info,
         };
@@ -208,6 +218,7 @@ impl AgentInfoSigned {
     {
         let meta = AgentMetaInfoEncode {
             dht_storage_arc_half_length,
+            accepts_compression: true,
         };
         let mut buf = Vec::new();
         crate::codec::rmp_encode(&mut buf, meta).map_err(KitsuneError::other)?;
@@ -235,6 +246,7 @@ impl AgentInfoSigned {
             url_list,
             signed_at_ms,
             expires_at_ms,
+            accepts_compression: true,
             signature,
             encoded_bytes,
         };
@@ -259,6 +271,21 @@ impl AgentInfoSigned {
     pub fn to_agent_arc(&self) -> AgentArc {
         (self.agent.clone(), self.storage_arc)
     }
+
+    /// The tls cert digests this agent can be reached with,
+    /// which are the hosts of the `kitsune-proxy` urls in its url list.
+    pub fn cert_digests(&self) -> Vec<String> {
+        let mut digests: Vec<String> = self
+            .url_list
+            .iter()
+            .map(|url| url.as_url2())
+            .filter(|url| url.scheme() == "kitsune-proxy")
+            .filter_map(|url| url.host_str().map(str::to_string))
+            .collect();
+        digests.sort();
+        digests.dedup();
+        digests
+    }
 }
 
 #[cfg(test)]
@@ -290,5 +317,24 @@ mod tests {
         let mut bytes: &[u8] = &enc;
         let info2: AgentInfoSigned = crate::codec::rmp_decode(&mut bytes).unwrap();
         assert_eq!(info, info2);
+        assert!(info2.accepts_compression);
+    }
+
+    #[test]
+    fn older_meta_info_does_not_accept_compression() {
+        #[derive(serde::Serialize)]
+        struct OlderAgentMetaInfoEncode {
+            dht_storage_arc_half_length: u32,
+        }
+
+        let mut enc = Vec::new();
+        let meta = OlderAgentMetaInfoEncode {
+            dht_storage_arc_half_length: 42,
+        };
+        crate::codec::rmp_encode(&mut enc, meta).unwrap();
+        let mut bytes: &[u8] = &enc;
+        let meta: AgentMetaInfoEncode = crate::codec::rmp_decode(&mut bytes).unwrap();
+        assert_eq!(meta.dht_storage_arc_half_length, 42);
+        assert!(!meta.accepts_compression);
     }
 }
@@ -36,3 +36,32 @@ impl Default for RandomLimit {
         Self(RANDOM_LIMIT_DEFAULT)
     }
 }
+
+/// How a bootstrap server has been responding to this node.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct BootstrapServerHealth {
+    /// The url of the bootstrap server.
+    pub url: url2::Url2,
+    /// When a request to this server last succeeded, in unix epoch milliseconds.
+    pub last_success_ms: Option<u64>,
+    /// When a request to this server last failed, in unix epoch milliseconds.
+    pub last_failure_ms: Option<u64>,
+    /// The error from the last failed request.
+    pub last_error: Option<String>,
+    /// The number of requests that have failed in a row,
+    /// including retries. Zero once a request succeeds.
+    pub consecutive_failures: u32,
+}
+
+impl BootstrapServerHealth {
+    /// Health of a server we haven't made any requests to yet.
+    pub fn new(url: url2::Url2) -> Self {
+        Self {
+            url,
+            last_success_ms: None,
+            last_failure_ms: None,
+            last_error: None,
+            consecutive_failures: 0,
+        }
+    }
+}
@@ -61,8 +61,19 @@ pub mod tuning_params_struct {
                     D: serde::Deserializer<'de>,
                 {
                     let result = <HashMap<String, String>>::deserialize(deserializer)?;
-                    let mut out = KitsuneP2pTuningParams::default();
-                    for (k, v) in result.into_iter() {
+                    Ok(KitsuneP2pTuningParams::default().with_overrides(&result))
+                }
+            }
+
+            impl KitsuneP2pTuningParams {
+                /// Copy these params, replacing the ones named in `overrides`
+                /// with the parsed string values, e.g. to tune a single space
+                /// differently from the rest of the conductor.
+                /// Params that fail to parse or don't exist are skipped
+                /// with a warning, as when deserializing.
+                pub fn with_overrides(&self, overrides: &HashMap<String, String>) -> Self {
+                    let mut out = self.clone();
+                    for (k, v) in overrides.iter() {
                         match k.as_str() {
                             $(
                                 stringify!($i) => match v.parse::<$t>() {
@@ -73,7 +84,31 @@ pub mod tuning_params_struct {
                             _ => tracing::warn!("INVALID TUNING PARAM: '{}'", k),
                         }
                     }
-                    Ok(out)
+                    out
+                }
+
+                /// The names of the params which differ between these
+                /// and `other`.
+                pub fn changed_params(&self, other: &Self) -> Vec<&'static str> {
+                    let mut changed = Vec::new();
+                    $(
+                        if self.$i != other.$i {
+                            changed.push(stringify!($i));
+                        }
+                    )*
+                    changed
+                }
+
+                /// Copy these params, taking the ones named in `names`
+                /// from `other`.
+                pub fn with_params_from(&self, other: &Self, names: &[&str]) -> Self {
+                    let mut out = self.clone();
+                    $(
+                        if names.contains(&stringify!($i)) {
+                            out.$i = other.$i.clone();
+                        }
+                    )*
+                    out
                 }
             }
         };
@@ -113,20 +148,96 @@ pub mod tuning_params_struct {
         /// seconds to "refill"). [Default: 100]
         gossip_burst_ratio: f64 = 100.0,
 
+        /// The target outbound bandwidth for publishes from all DNAs.
+        /// Publishes wait for bandwidth to become available, with
+        /// bursts allowed according to `gossip_burst_ratio`.
+        /// Zero means unlimited. [Default: 0]
+        publish_outbound_target_mbps: f64 = 0.0,
+
+        /// The target inbound bandwidth for publishes to all DNAs.
+        /// Zero means unlimited. [Default: 0]
+        publish_inbound_target_mbps: f64 = 0.0,
+
+        /// The target outbound bandwidth for the op data of each DNA,
+        /// whether gossiped or published. This applies on top of the
+        /// conductor wide gossip and publish targets.
+        /// Zero means unlimited. [Default: 0]
+        dna_outbound_target_mbps: f64 = 0.0,
+
+        /// The target inbound bandwidth for the op data of each DNA,
+        /// whether gossiped or published.
+        /// Zero means unlimited. [Default: 0]
+        dna_inbound_target_mbps: f64 = 0.0,
+
+        /// How many gets, of records, links, metadata, agent activity or
+        /// validation packages, each remote peer may send us per minute.
+        /// Requests over the quota are answered with a throttle error.
+        /// Zero means unlimited. [Default: 0]
+        inbound_gets_per_peer_per_minute: u32 = 0,
+
+        /// How many remote zome calls and remote signals each remote peer
+        /// may send us per minute. Calls over the quota are answered with
+        /// a throttle error and signals over it are dropped.
+        /// Zero means unlimited. [Default: 0]
+        inbound_calls_per_peer_per_minute: u32 = 0,
+
+        /// How many publishes each remote peer may send us per minute.
+        /// Publishes over the quota are dropped, and the ops will still
+        /// reach us through gossip.
+        /// Zero means unlimited. [Default: 0]
+        inbound_publishes_per_peer_per_minute: u32 = 0,
+
+        /// How many of the authorities nearest their basis ops are first
+        /// published to. Each time an op is published again, because it
+        /// still lacks validation receipts, the count doubles. Zero publishes to every agent found near the basis.
+        /// [Default: 5]
+        publish_authority_count: u32 = 5,
+
         /// How long should we hold off talking to a peer
         /// we've previously spoken successfully to.
         /// [Default: 1 minute]
         gossip_peer_on_success_next_gossip_delay_ms: u32 = 1000 * 60,
 
+        /// The shortest time recent gossip will wait before gossiping again
+        /// with a peer it has successfully spoken to. While recent rounds
+        /// keep finding new ops the wait shrinks towards this, and while
+        /// they don't it grows back to
+        /// `gossip_peer_on_success_next_gossip_delay_ms`.
+        /// [Default: 5 seconds]
+        gossip_recent_min_peer_delay_ms: u32 = 1000 * 5,
+
         /// How long should we hold off talking to a peer
         /// we've previously gotten errors speaking to.
         /// [Default: 5 minute]
         gossip_peer_on_error_next_gossip_delay_ms: u32 = 1000 * 60 * 5,
 
+        /// Peers scoring below this are only chosen for gossip or remote
+        /// calls once better scoring peers have been tried. A peer's score,
+        /// between 0 and 1, combines how reachable it is, its latency and
+        /// how often data it sent us failed validation.
+        /// [Default: 0.2]
+        peer_score_deprioritize_threshold: f64 = 0.2,
+
+        /// Peers scoring below this are never chosen for gossip or
+        /// remote calls.
+        /// [Default: 0.0 (never evict)]
+        peer_score_evict_threshold: f64 = 0.0,
+
         /// How often should we update and publish our agent info?
         /// [Default: 5 minutes]
         gossip_agent_info_update_interval_ms: u32 = 1000 * 60 * 5,
 
+        /// How many times to try a request to a single bootstrap server
+        /// before moving on to the next one.
+        /// [Default: 3]
+        bootstrap_retry_count: u32 = 3,
+
+        /// The delay before the first retry of a failed bootstrap request.
+        /// This doubles with each retry, with up to 50% random jitter
+        /// so that nodes don't retry in lockstep.
+        /// [Default: 500 milliseconds]
+        bootstrap_retry_delay_ms: u32 = 500,
+
         /// How frequently we should locally sync when there is
         /// no new data. Agents arc can change so this shouldn't
         /// be too long. [Default: 1 minutes]
@@ -143,6 +254,15 @@ pub mod tuning_params_struct {
         /// Should gossip dynamically resize storage arcs?
         gossip_dynamic_arcs: bool = true,
 
+        /// The largest fraction of the DHT keyspace a storage arc may
+        /// grow to when dynamically resized. [Default: 1.0]
+        gossip_arc_max_coverage: f64 = 1.0,
+
+        /// Once the DHT data stored for a space exceeds this many
+        /// megabytes, dynamic arcs stop growing and instead halve each
+        /// time they are updated. Zero means unlimited. [Default: 0]
+        gossip_arc_storage_limit_mb: u32 = 0,
+
         /// Allow only the first agent to join the space to
         /// have a sized storage arc. [Default: false]
         /// This is an experimental feature that sets the first
@@ -164,12 +284,41 @@ pub mod tuning_params_struct {
         /// we'll wait at least this long for additional responses.
         default_rpc_multi_remote_request_grace_ms: u64 = 1000 * 3,
 
+        /// Default hedge delay for rpc multi. [Default: 0 (no hedging)]
+        /// If set, remote requests are made one at a time, best scoring
+        /// peer first. The next peer is only asked once this long has
+        /// passed without a response, or as soon as the previous request
+        /// fails, and the first response received is returned.
+        default_rpc_multi_hedge_delay_ms: u64 = 0,
+
+        /// Default number of times a network get is retried when
+        /// no peer responds. [Default: 0]
+        default_rpc_multi_retry_count: u8 = 0,
+
         /// Default agent expires after milliseconds. [Default: 20 minutes]
         agent_info_expires_after_ms: u32 = 1000 * 60 * 20,
 
         /// Tls in-memory session storage capacity. [Default: 512]
         tls_in_mem_session_storage: u32 = 512,
 
+        /// Pin the tls certs of known peers. A peer's new agent info is
+        /// rejected while it advertises different certs to the unexpired
+        /// info we already hold for it, and a connection to an address
+        /// that presents a different cert to the one it presented before
+        /// is rejected as a possible man in the middle.
+        /// Only enable this if peers keep their certs across restarts,
+        /// see `tls_cert_path` in the kitsune config.
+        /// [Default: false]
+        tls_pin_peer_certs: bool = false,
+
+        /// Compress wire messages which are at least this many bytes,
+        /// such as publishes and gossiped op data, with zstd. Messages are
+        /// only compressed for peers whose agent info says they can
+        /// decompress them, so older peers are still sent plain messages.
+        /// Zero disables compression.
+        /// [Default: 0]
+        wire_compression_threshold_bytes: u32 = 0,
+
         /// How often should NAT nodes refresh their proxy contract?
         /// [Default: 2 minutes]
         proxy_keepalive_ms: u32 = 1000 * 60 * 2,
@@ -179,6 +328,22 @@ pub mod tuning_params_struct {
         /// [Default: 5 minutes]
         proxy_to_expire_ms: u32 = 1000 * 60 * 5,
 
+        /// When we reach a peer through its proxy, ask the proxy to
+        /// introduce us so we can both try to connect directly
+        /// by hole punching through our NATs.
+        /// [Default: true]
+        proxy_hole_punch: bool = true,
+
+        /// How long to wait before asking a proxy to introduce us
+        /// to the same peer again after a failed hole punch.
+        /// [Default: 5 minutes]
+        proxy_hole_punch_retry_ms: u32 = 1000 * 60 * 5,
+
+        /// If a fallback relay is configured, how long a direct connection
+        /// attempt may take before falling back to routing through the relay.
+        /// [Default: 5 seconds]
+        proxy_direct_connect_timeout_ms: u32 = 1000 * 5,
+
         /// Mainly used as the for_each_concurrent limit,
         /// this restricts the number of active polled futures
         /// on a single thread.
@@ -189,6 +354,18 @@ pub mod tuning_params_struct {
         /// [Default: 30 seconds]
         tx2_quic_max_idle_timeout_ms: u32 = 1000 * 30,
 
+        /// tx2 quic: resume the TLS session with 0-RTT on reconnects to a
+        /// peer we have a resumable session with. The connection is only
+        /// used once the resumed handshake is confirmed, so no data is sent
+        /// as (replayable) early data.
+        /// [Default: false]
+        tx2_quic_enable_0rtt: bool = false,
+
+        /// tx2 quic: let peers keep a connection open when their address
+        /// changes, e.g. when a laptop moves between networks.
+        /// [Default: true]
+        tx2_quic_allow_migration: bool = true,
+
         /// tx2 pool max connection count
         /// [Default: 4096]
         tx2_pool_max_connection_count: usize = 4096,
@@ -248,6 +425,32 @@ pub mod tuning_params_struct {
     }
 }
 
+/// The tuning params which a running node picks up when they are changed
+/// with `update_tuning_params`. All the others are read once, when the
+/// network or a space is spawned, and only change on a restart.
+pub const LIVE_TUNING_PARAMS: &[&str] = &[
+    "inbound_gets_per_peer_per_minute",
+    "inbound_calls_per_peer_per_minute",
+    "inbound_publishes_per_peer_per_minute",
+    "publish_authority_count",
+    "gossip_peer_on_error_next_gossip_delay_ms",
+    "peer_score_deprioritize_threshold",
+    "peer_score_evict_threshold",
+    "gossip_max_batch_size",
+    "gossip_dynamic_arcs",
+    "gossip_arc_max_coverage",
+    "gossip_arc_storage_limit_mb",
+    "gossip_single_storage_arc_per_space",
+    "default_rpc_single_timeout_ms",
+    "default_rpc_multi_remote_agent_count",
+    "default_rpc_multi_remote_request_grace_ms",
+    "default_rpc_multi_hedge_delay_ms",
+    "default_rpc_multi_retry_count",
+    "agent_info_expires_after_ms",
+    "wire_compression_threshold_bytes",
+    "disable_publish",
+];
+
 /// We don't want to clone these tuning params over-and-over.
 /// They should normally be passed around as an Arc.
 pub type KitsuneP2pTuningParams = std::sync::Arc<tuning_params_struct::KitsuneP2pTuningParams>;
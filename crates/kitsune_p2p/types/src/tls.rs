@@ -30,6 +30,48 @@ impl TlsConfig {
             cert_digest: cert.digest.into(),
         })
     }
+
+    /// Load a tls certificate saved with [`TlsConfig::save_file`].
+    /// Operators can provide their own certificate in this format.
+    pub async fn load_file(path: impl AsRef<std::path::Path>) -> KitsuneResult<Self> {
+        let file = tokio::fs::read(path).await.map_err(KitsuneError::other)?;
+        let file: TlsFileCert = crate::codec::rmp_decode(&mut std::io::Cursor::new(&file))
+            .map_err(KitsuneError::other)?;
+        if file.digest.len() != 32 {
+            return Err("invalid tls cert file digest".into());
+        }
+        Ok(Self {
+            cert: file.cert.into(),
+            cert_priv_key: file.priv_key.into(),
+            cert_digest: CertDigest::from_slice(&file.digest),
+        })
+    }
+
+    /// Save this tls certificate to a file.
+    /// Danger - the private key is written unencrypted.
+    pub async fn save_file(&self, path: impl AsRef<std::path::Path>) -> KitsuneResult<()> {
+        let file = TlsFileCert {
+            cert: self.cert.to_vec(),
+            priv_key: self.cert_priv_key.read_lock().to_vec(),
+            digest: self.cert_digest.to_vec(),
+        };
+        let mut out = Vec::new();
+        crate::codec::rmp_encode(&mut out, &file).map_err(KitsuneError::other)?;
+        tokio::fs::write(path, &out)
+            .await
+            .map_err(KitsuneError::other)
+    }
+}
+
+/// The file format tls certificates are saved in.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct TlsFileCert {
+    #[serde(with = "serde_bytes")]
+    cert: Vec<u8>,
+    #[serde(with = "serde_bytes")]
+    priv_key: Vec<u8>,
+    #[serde(with = "serde_bytes")]
+    digest: Vec<u8>,
 }
 
 /// Allow only these cipher suites for kitsune Tls.
@@ -448,5 +448,6 @@ async fn path_to_def(path: PathBuf, dependencies: Vec<ZomeName>) -> ZomeDef {
     ZomeDef::Wasm(WasmZome {
         wasm_hash,
         dependencies,
+        unrestricted_functions: Default::default(),
     })
 }
@@ -199,3 +199,13 @@ fn call_create_entry_remotely_no_rec(agent: AgentPubKey) -> ExternResult<ActionH
         ))),
     }
 }
+
+#[hdk_extern]
+fn commit_blob(blob: Bytes) -> ExternResult<BlobManifest> {
+    write_blob(blob.into_vec())
+}
+
+#[hdk_extern]
+fn get_blob(manifest: BlobManifest) -> ExternResult<Option<Bytes>> {
+    read_blob(manifest)
+}
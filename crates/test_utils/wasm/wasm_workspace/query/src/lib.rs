@@ -18,3 +18,19 @@ fn query(args: QueryFilter) -> ExternResult<Vec<Record>> {
 fn add_path(s: String) -> ExternResult<EntryHash> {
     path(&s)
 }
+
+#[hdk_extern]
+fn query_page(input: ChainQueryPageInput) -> ExternResult<RecordsPage> {
+    hdk::prelude::query_page(input.filter, input.cursor, input.limit)
+}
+
+#[hdk_extern]
+fn get_agent_activity_page(input: GetAgentActivityPageInput) -> ExternResult<AgentActivityPage> {
+    hdk::prelude::get_agent_activity_page(
+        input.agent_pubkey,
+        input.chain_query_filter,
+        input.activity_request,
+        input.cursor,
+        input.limit,
+    )
+}